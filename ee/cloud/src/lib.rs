@@ -10,10 +10,13 @@
 //! Features:
 //! - Multi-node coordination (100+ cells)
 //! - Global state synchronization
-//! - Autonomic mitosis (auto-scaling)
+//! - Autonomic mitosis (auto-scaling), placing new cells on the greenest grid
 //! - Cross-region failover
+//! - Readiness probes: degraded components mark their cell Degraded
 
+use agentkern_arbiter::CarbonForecastSource;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Enterprise license error.
@@ -247,6 +250,100 @@ impl MeshCoordinator {
     pub fn healthy_cell_count(&self) -> usize {
         self.cells.iter().filter(|c| c.status == CellStatus::Healthy).count()
     }
+
+    /// Run a cell's readiness probe and record the resulting status.
+    ///
+    /// Returns `None` for an unknown cell.
+    pub fn probe_cell(&mut self, cell_id: &str, probe: &ReadinessProbe) -> Option<ReadinessReport> {
+        let cell = self.cells.iter_mut().find(|c| c.cell_id == cell_id)?;
+        let report = probe.evaluate();
+        if cell.status != report.status {
+            tracing::warn!(
+                cell_id = %cell.cell_id,
+                from = ?cell.status,
+                to = ?report.status,
+                "Cell status changed by readiness probe"
+            );
+            cell.status = report.status;
+        }
+        Some(report)
+    }
+}
+
+// ============================================
+// Readiness Probe
+// ============================================
+
+/// Readiness of one component of a cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", content = "reason", rename_all = "snake_case")]
+pub enum Readiness {
+    /// Fully serving
+    Ready,
+    /// Serving with reduced capability
+    Degraded(String),
+    /// Not serving
+    NotReady(String),
+}
+
+/// Component that contributes to its cell's readiness (connectors,
+/// stores, upstream services).
+pub trait ReadinessCheck: Send + Sync {
+    /// Component name in the report
+    fn name(&self) -> &str;
+
+    /// Current readiness; called on every probe, so it should be cheap.
+    fn readiness(&self) -> Readiness;
+}
+
+/// Result of one probe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// Healthy, Degraded if any component is degraded, Offline if any
+    /// component is not ready
+    pub status: CellStatus,
+    /// Readiness per component, in registration order
+    pub components: Vec<(String, Readiness)>,
+}
+
+impl ReadinessReport {
+    /// Whether the cell should receive traffic (Healthy or Degraded).
+    pub fn is_ready(&self) -> bool {
+        self.status != CellStatus::Offline
+    }
+}
+
+/// Readiness probe of a cell, aggregating registered checks.
+#[derive(Default, Clone)]
+pub struct ReadinessProbe {
+    checks: Vec<Arc<dyn ReadinessCheck>>,
+}
+
+impl ReadinessProbe {
+    /// Create an empty probe (always Healthy).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component check.
+    pub fn register(&mut self, check: Arc<dyn ReadinessCheck>) {
+        self.checks.push(check);
+    }
+
+    /// Evaluate all checks.
+    pub fn evaluate(&self) -> ReadinessReport {
+        let components: Vec<_> = self.checks.iter()
+            .map(|check| (check.name().to_string(), check.readiness()))
+            .collect();
+        let status = if components.iter().any(|(_, r)| matches!(r, Readiness::NotReady(_))) {
+            CellStatus::Offline
+        } else if components.iter().any(|(_, r)| matches!(r, Readiness::Degraded(_))) {
+            CellStatus::Degraded
+        } else {
+            CellStatus::Healthy
+        };
+        ReadinessReport { status, components }
+    }
 }
 
 // ============================================
@@ -341,6 +438,8 @@ pub struct MitosisController {
     policy: ScalingPolicy,
     last_scale_time: u64,
     events: Vec<MitosisEvent>,
+    /// Grid intensity used to choose regions
+    carbon: Option<Arc<dyn CarbonForecastSource>>,
 }
 
 impl MitosisController {
@@ -352,9 +451,44 @@ impl MitosisController {
             policy,
             last_scale_time: 0,
             events: vec![],
+            carbon: None,
         })
     }
 
+    /// Prefer low-carbon regions when spawning and retiring cells.
+    pub fn with_carbon_forecast(mut self, source: Arc<dyn CarbonForecastSource>) -> Self {
+        self.carbon = Some(source);
+        self
+    }
+
+    /// Candidates with known grid intensity.
+    fn intensities<'a>(&self, candidates: &[&'a str]) -> Vec<(&'a str, f64)> {
+        let Some(carbon) = &self.carbon else {
+            return vec![];
+        };
+        candidates.iter()
+            .filter_map(|&region| carbon.current_intensity(region).map(|grams| (region, grams)))
+            .collect()
+    }
+
+    /// Region to spawn new cells in on `ScaleUp`: the greenest, else the first.
+    pub fn spawn_region(&self, candidates: &[&str]) -> Option<String> {
+        self.intensities(candidates).into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(region, _)| region)
+            .or(candidates.first().copied())
+            .map(str::to_string)
+    }
+
+    /// Region to retire cells from on `ScaleDown`: the most carbon-intensive, else the last.
+    pub fn retire_region(&self, candidates: &[&str]) -> Option<String> {
+        self.intensities(candidates).into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(region, _)| region)
+            .or(candidates.last().copied())
+            .map(str::to_string)
+    }
+
     /// Evaluate current metrics and decide on scaling.
     pub fn evaluate(&mut self, metrics: &MeshMetrics) -> ScalingDecision {
         let now = std::time::SystemTime::now()
@@ -509,6 +643,67 @@ mod tests {
         std::env::remove_var("AGENTKERN_LICENSE_KEY");
     }
 
+    struct Fixed(&'static str, Readiness);
+
+    impl ReadinessCheck for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn readiness(&self) -> Readiness {
+            self.1.clone()
+        }
+    }
+
+    #[test]
+    fn test_readiness_probe() {
+        let mut probe = ReadinessProbe::new();
+        assert_eq!(probe.evaluate().status, CellStatus::Healthy);
+
+        probe.register(Arc::new(Fixed("gate", Readiness::Ready)));
+        probe.register(Arc::new(Fixed("connectors", Readiness::Degraded("sap: circuit open".into()))));
+        let report = probe.evaluate();
+        assert_eq!(report.status, CellStatus::Degraded);
+        assert!(report.is_ready());
+        assert_eq!(report.components[1].0, "connectors");
+
+        probe.register(Arc::new(Fixed("store", Readiness::NotReady("disk full".into()))));
+        assert!(!probe.evaluate().is_ready());
+    }
+
+    #[derive(Debug)]
+    struct Grid;
+
+    impl CarbonForecastSource for Grid {
+        fn current_intensity(&self, region: &str) -> Option<f64> {
+            match region {
+                "eu-north-1" => Some(20.0),
+                "us-east-1" => Some(380.0),
+                _ => None,
+            }
+        }
+
+        fn green_window(&self, _region: &str, _duration: std::time::Duration, _finish_by: u64) -> Option<agentkern_arbiter::GreenWindow> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_mitosis_prefers_green_regions() {
+        let controller = MitosisController {
+            policy: ScalingPolicy::default(),
+            last_scale_time: 0,
+            events: vec![],
+            carbon: None,
+        };
+        let candidates = ["ap-south-1", "us-east-1", "eu-north-1"];
+        assert_eq!(controller.spawn_region(&candidates).as_deref(), Some("ap-south-1"));
+
+        let controller = controller.with_carbon_forecast(Arc::new(Grid));
+        assert_eq!(controller.spawn_region(&candidates).as_deref(), Some("eu-north-1"));
+        assert_eq!(controller.retire_region(&candidates).as_deref(), Some("us-east-1"));
+    }
+
     #[test]
     fn test_scaling_policy_defaults() {
         let policy = ScalingPolicy::default();
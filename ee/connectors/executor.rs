@@ -0,0 +1,206 @@
+//! Async Execution
+//!
+//! RFC, CICS, IMS, MQ and the blocking HTTP clients hold the calling
+//! thread for the whole round trip. The async connector APIs run that work
+//! on Tokio's blocking pool so gateway worker threads stay free, bounded by
+//! a timeout and an optional cancellation token.
+//!
+//! A call that times out or is cancelled returns at once. The blocking work
+//! cannot be interrupted: it finishes in the background, returns its pooled
+//! connection and its result is dropped.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let cancel = CancelToken::new();
+//! let options = CallOptions::new().with_timeout(Duration::from_secs(5)).with_cancel(cancel.clone());
+//! let reply = bounded("mainframe", &options, connector.link_program("ACCTINQ", &commarea)).await?;
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use super::resilience::{ConnectorError, ResilienceConfig, Unavailable};
+
+/// Cancels in-flight calls that were given a clone of this token.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            let notified = self.inner.notify.notified();
+            // Re-check after registering so a concurrent cancel is not missed
+            if self.is_cancelled() {
+                break;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Timeout and cancellation for async connector calls.
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancelToken>,
+}
+
+impl CallOptions {
+    /// No timeout, no cancellation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Timeout from `call_timeout_ms` (0 disables it).
+    pub fn from_config(config: &ResilienceConfig) -> Self {
+        Self {
+            timeout: (config.call_timeout_ms > 0).then(|| Duration::from_millis(config.call_timeout_ms)),
+            cancel: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+/// Run blocking connector work on the blocking pool.
+///
+/// Panics in `work` are propagated to the caller.
+pub async fn run_blocking<T, E>(
+    service: &str,
+    options: &CallOptions,
+    work: impl FnOnce() -> Result<T, E> + Send + 'static,
+) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: ConnectorError + Send + 'static,
+{
+    bounded(service, options, async {
+        match tokio::task::spawn_blocking(work).await {
+            Ok(result) => result,
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    })
+    .await
+}
+
+/// Bound any connector call by the timeout and cancellation in `options`.
+pub async fn bounded<T, E>(
+    service: &str,
+    options: &CallOptions,
+    call: impl Future<Output = Result<T, E>>,
+) -> Result<T, E>
+where
+    E: ConnectorError,
+{
+    let cancelled = async {
+        match &options.cancel {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    let deadline = async {
+        match options.timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    if options.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+        return Err(E::unavailable(service, Unavailable::Cancelled));
+    }
+    tokio::select! {
+        result = call => result,
+        _ = deadline => Err(E::unavailable(service, Unavailable::TimedOut)),
+        _ = cancelled => Err(E::unavailable(service, Unavailable::Cancelled)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    enum TestError {
+        #[error("{0} unavailable: {1}")]
+        Unavailable(String, Unavailable),
+    }
+
+    impl ConnectorError for TestError {
+        fn is_transient(&self) -> bool {
+            false
+        }
+
+        fn unavailable(service: &str, reason: Unavailable) -> Self {
+            TestError::Unavailable(service.to_string(), reason)
+        }
+    }
+
+    fn slow(millis: u64) -> impl FnOnce() -> Result<u64, TestError> + Send + 'static {
+        move || {
+            std::thread::sleep(Duration::from_millis(millis));
+            Ok(millis)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_cancel() {
+        let options = CallOptions::new().with_timeout(Duration::from_millis(500));
+        assert_eq!(run_blocking("svc", &options, slow(10)).await.unwrap(), 10);
+
+        let options = CallOptions::new().with_timeout(Duration::from_millis(20));
+        let err = run_blocking("svc", &options, slow(300)).await.unwrap_err();
+        assert!(matches!(err, TestError::Unavailable(_, Unavailable::TimedOut)));
+
+        let cancel = CancelToken::new();
+        let options = CallOptions::new().with_cancel(cancel.clone());
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let err = run_blocking("svc", &options, slow(300)).await.unwrap_err();
+        assert!(matches!(err, TestError::Unavailable(_, Unavailable::Cancelled)));
+        // Already-cancelled tokens fail fast
+        let err = run_blocking("svc", &options, slow(10)).await.unwrap_err();
+        assert!(matches!(err, TestError::Unavailable(_, Unavailable::Cancelled)));
+    }
+}
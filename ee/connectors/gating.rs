@@ -0,0 +1,387 @@
+//! Connector Action Gating
+//!
+//! Connector calls are checked against Gate policies before they reach the
+//! backend. Each call is described as a [`ConnectorAction`] and verified as
+//! `"{connector}.{operation}"`, with the target, amount, currency and
+//! counterparties in the policy context:
+//!
+//! - Blocked by a policy or the risk score: the call fails with
+//!   [`GateRejection::Denied`]
+//! - Risk at or above the escalation threshold (e.g. a `Review` rule): the
+//!   call fails with [`GateRejection::Escalated`] until a reviewer approves
+//!   that exact action, once
+//! - Otherwise the call runs
+//!
+//! Every decision leaves an audit record.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Policy rule: action == 'swift.create_payment' && context.amount > 1000000 => Review
+//! let gate = Arc::new(ConnectorGate::new(engine, "treasury-agent"));
+//! let swift = SwiftConnector::new(config)?.with_gate(gate.clone());
+//!
+//! if let Err(SwiftError::Gated(GateRejection::Escalated { request_id, .. })) = swift.create_payment(payment.clone()).await {
+//!     // After sign-off
+//!     gate.approve(request_id, "j.doe");
+//!     swift.create_payment(payment).await?;
+//! }
+//! ```
+
+use agentkern_gate::engine::VerificationRequestBuilder;
+use agentkern_gate::{GateEngine, VerificationRequest, VerificationResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// A connector call as seen by Gate policies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectorAction {
+    /// `sap`, `swift` or `mainframe`
+    pub connector: String,
+    /// Connector method, e.g. `call_bapi`
+    pub operation: String,
+    /// BAPI, entity set, transaction, program, queue or UETR
+    pub target: Option<String>,
+    pub amount: Option<f64>,
+    pub currency: Option<String>,
+    /// Parties money or data moves between (names, accounts, BICs)
+    pub counterparties: Vec<String>,
+}
+
+impl ConnectorAction {
+    pub fn new(connector: &str, operation: &str) -> Self {
+        Self {
+            connector: connector.to_string(),
+            operation: operation.to_string(),
+            target: None,
+            amount: None,
+            currency: None,
+            counterparties: Vec::new(),
+        }
+    }
+
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    pub fn with_amount(mut self, amount: f64, currency: &str) -> Self {
+        self.amount = Some(amount);
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    pub fn with_counterparty(mut self, counterparty: &str) -> Self {
+        self.counterparties.push(counterparty.to_string());
+        self
+    }
+
+    /// Action name policies match on, e.g. `sap.call_bapi`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.connector, self.operation)
+    }
+
+    /// Verification request; fields are available as `context.<field>`.
+    pub fn to_request(&self, agent_id: &str) -> VerificationRequest {
+        let mut builder = VerificationRequestBuilder::new(agent_id, self.name())
+            .context("connector", self.connector.as_str())
+            .context("operation", self.operation.as_str())
+            .context("counterparties", self.counterparties.clone());
+        if let Some(target) = &self.target {
+            builder = builder.context("target", target.as_str());
+        }
+        if let Some(amount) = self.amount {
+            builder = builder.context("amount", amount);
+        }
+        if let Some(currency) = &self.currency {
+            builder = builder.context("currency", currency.as_str());
+        }
+        builder.build()
+    }
+}
+
+/// Why a gated call did not run.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum GateRejection {
+    #[error("{action} denied: {reasoning}")]
+    Denied {
+        action: String,
+        request_id: Uuid,
+        blocking_policies: Vec<String>,
+        reasoning: String,
+    },
+
+    #[error("{action} escalated for review ({request_id}, risk {risk_score})")]
+    Escalated {
+        action: String,
+        request_id: Uuid,
+        risk_score: u8,
+    },
+}
+
+/// A call held for review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Escalation {
+    pub request_id: Uuid,
+    pub agent_id: String,
+    pub action: ConnectorAction,
+    pub risk_score: u8,
+    pub reasoning: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+/// Outcome of a gate check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GateDecision {
+    Allowed,
+    /// Ran on a reviewer's approval
+    Approved,
+    Escalated,
+    Denied,
+}
+
+/// Audit record of one gate check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateAuditRecord {
+    /// Verification request ID; for approved calls, the escalation's
+    pub request_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub agent_id: String,
+    pub action: ConnectorAction,
+    pub decision: GateDecision,
+    pub risk_score: u8,
+    pub evaluated_policies: Vec<String>,
+    pub blocking_policies: Vec<String>,
+    pub reasoning: String,
+    /// Who approved the escalation
+    pub reviewer: Option<String>,
+}
+
+type EscalationHook = Box<dyn Fn(&Escalation) + Send + Sync>;
+
+/// Checks connector calls of one agent against a Gate engine.
+pub struct ConnectorGate {
+    engine: Arc<GateEngine>,
+    agent_id: String,
+    escalation_threshold: u8,
+    on_escalation: Option<EscalationHook>,
+    pending: Mutex<HashMap<Uuid, Escalation>>,
+    approved: Mutex<Vec<(Escalation, String)>>,
+    audit: Mutex<VecDeque<GateAuditRecord>>,
+    audit_capacity: usize,
+}
+
+impl ConnectorGate {
+    pub fn new(engine: Arc<GateEngine>, agent_id: &str) -> Self {
+        Self {
+            engine,
+            agent_id: agent_id.to_string(),
+            escalation_threshold: 60,
+            on_escalation: None,
+            pending: Mutex::new(HashMap::new()),
+            approved: Mutex::new(Vec::new()),
+            audit: Mutex::new(VecDeque::new()),
+            audit_capacity: 10_000,
+        }
+    }
+
+    /// Risk score from which allowed calls are escalated (default 60, what
+    /// a `Review` rule raises it to). Gate blocks from 80 on, so 80 or more
+    /// disables escalation.
+    pub fn with_escalation_threshold(mut self, threshold: u8) -> Self {
+        self.escalation_threshold = threshold;
+        self
+    }
+
+    /// Called for every new escalation, e.g. to page a reviewer.
+    pub fn with_escalation_hook(mut self, hook: impl Fn(&Escalation) + Send + Sync + 'static) -> Self {
+        self.on_escalation = Some(Box::new(hook));
+        self
+    }
+
+    /// Set how many audit records are kept in memory.
+    pub fn with_audit_capacity(mut self, capacity: usize) -> Self {
+        self.audit_capacity = capacity;
+        self
+    }
+
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    /// Verify an action; `Ok` means the call may run.
+    pub async fn authorize(&self, action: ConnectorAction) -> Result<(), GateRejection> {
+        let result = self.engine.verify(action.to_request(&self.agent_id)).await;
+        self.decide(action, result)
+    }
+
+    /// Blocking [`authorize`](Self::authorize).
+    ///
+    /// Runs the check on a private runtime, so it must not be called from
+    /// within a Tokio runtime.
+    pub fn authorize_blocking(&self, action: ConnectorAction) -> Result<(), GateRejection> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("building gate runtime")
+            .block_on(self.authorize(action))
+    }
+
+    fn decide(&self, action: ConnectorAction, result: VerificationResult) -> Result<(), GateRejection> {
+        if !result.allowed {
+            self.record(&action, &result, GateDecision::Denied, None);
+            return Err(GateRejection::Denied {
+                action: action.name(),
+                request_id: result.request_id,
+                blocking_policies: result.blocking_policies,
+                reasoning: result.reasoning,
+            });
+        }
+        if result.final_risk_score < self.escalation_threshold {
+            self.record(&action, &result, GateDecision::Allowed, None);
+            return Ok(());
+        }
+
+        // A policy that now denies the call overrides an earlier approval,
+        // hence approvals are only consulted once the engine allows it
+        if let Some((escalation, reviewer)) = self.take_approval(&action) {
+            let result = VerificationResult { request_id: escalation.request_id, ..result };
+            self.record(&action, &result, GateDecision::Approved, Some(reviewer));
+            return Ok(());
+        }
+
+        let escalation = Escalation {
+            request_id: result.request_id,
+            agent_id: self.agent_id.clone(),
+            action: action.clone(),
+            risk_score: result.final_risk_score,
+            reasoning: result.reasoning.clone(),
+            raised_at: Utc::now(),
+        };
+        if let Some(hook) = &self.on_escalation {
+            hook(&escalation);
+        }
+        self.pending.lock().unwrap().insert(escalation.request_id, escalation);
+        self.record(&action, &result, GateDecision::Escalated, None);
+        Err(GateRejection::Escalated {
+            action: action.name(),
+            request_id: result.request_id,
+            risk_score: result.final_risk_score,
+        })
+    }
+
+    fn take_approval(&self, action: &ConnectorAction) -> Option<(Escalation, String)> {
+        let mut approved = self.approved.lock().unwrap();
+        let position = approved.iter().position(|(escalation, _)| escalation.action == *action)?;
+        Some(approved.remove(position))
+    }
+
+    /// Approve an escalated call; the next identical call runs once.
+    /// Returns false if the escalation is unknown or already decided.
+    pub fn approve(&self, request_id: Uuid, reviewer: &str) -> bool {
+        let Some(escalation) = self.pending.lock().unwrap().remove(&request_id) else {
+            return false;
+        };
+        self.approved.lock().unwrap().push((escalation, reviewer.to_string()));
+        true
+    }
+
+    /// Reject an escalated call. Returns false if it was not pending.
+    pub fn reject(&self, request_id: Uuid) -> bool {
+        self.pending.lock().unwrap().remove(&request_id).is_some()
+    }
+
+    /// Escalations awaiting review, oldest first.
+    pub fn pending_escalations(&self) -> Vec<Escalation> {
+        let mut pending: Vec<_> = self.pending.lock().unwrap().values().cloned().collect();
+        pending.sort_by_key(|e| e.raised_at);
+        pending
+    }
+
+    fn record(&self, action: &ConnectorAction, result: &VerificationResult, decision: GateDecision, reviewer: Option<String>) {
+        let record = GateAuditRecord {
+            request_id: result.request_id,
+            timestamp: Utc::now(),
+            agent_id: self.agent_id.clone(),
+            action: action.clone(),
+            decision,
+            risk_score: result.final_risk_score,
+            evaluated_policies: result.evaluated_policies.clone(),
+            blocking_policies: result.blocking_policies.clone(),
+            reasoning: result.reasoning.clone(),
+            reviewer,
+        };
+        let mut audit = self.audit.lock().unwrap();
+        if audit.len() >= self.audit_capacity {
+            audit.pop_front();
+        }
+        audit.push_back(record);
+    }
+
+    /// Recent gate decisions, oldest first.
+    pub fn audit_records(&self) -> Vec<GateAuditRecord> {
+        self.audit.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_gate::{Policy, PolicyAction, PolicyRule};
+
+    fn policy(id: &str, condition: &str, action: PolicyAction) -> Policy {
+        Policy {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            priority: 100,
+            enabled: true,
+            jurisdictions: vec![],
+            rules: vec![PolicyRule {
+                id: format!("{id}-rule"),
+                condition: condition.to_string(),
+                action,
+                message: None,
+                risk_score: None,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deny_escalate_and_approve() {
+        let engine = Arc::new(GateEngine::new().with_neural_threshold(100));
+        engine.register_policy(policy("no-vendor-changes", "context.target == 'BAPI_VENDOR_CHANGE'", PolicyAction::Deny)).await;
+        engine.register_policy(policy("large-payments", "action == 'swift.create_payment' && context.amount > 1000000", PolicyAction::Review)).await;
+        let gate = ConnectorGate::new(engine, "treasury-agent");
+
+        let denied = ConnectorAction::new("sap", "call_bapi").with_target("BAPI_VENDOR_CHANGE");
+        let err = gate.authorize(denied).await.unwrap_err();
+        assert!(matches!(err, GateRejection::Denied { ref blocking_policies, .. } if blocking_policies == &["no-vendor-changes"]));
+
+        let small = ConnectorAction::new("swift", "create_payment").with_amount(5_000.0, "EUR").with_counterparty("Acme GmbH");
+        assert!(gate.authorize(small).await.is_ok());
+
+        let large = ConnectorAction::new("swift", "create_payment").with_amount(2_500_000.0, "EUR").with_counterparty("Acme GmbH");
+        let Err(GateRejection::Escalated { request_id, .. }) = gate.authorize(large.clone()).await else {
+            panic!("large payment should be escalated");
+        };
+        assert_eq!(gate.pending_escalations()[0].action, large);
+
+        // Approval lets the same call through once
+        assert!(gate.approve(request_id, "j.doe"));
+        assert!(gate.authorize(large.clone()).await.is_ok());
+        assert!(matches!(gate.authorize(large).await, Err(GateRejection::Escalated { .. })));
+
+        let decisions: Vec<_> = gate.audit_records().iter().map(|r| r.decision).collect();
+        assert_eq!(decisions, [
+            GateDecision::Denied, GateDecision::Allowed, GateDecision::Escalated,
+            GateDecision::Approved, GateDecision::Escalated,
+        ]);
+        let approved = &gate.audit_records()[3];
+        assert_eq!((approved.request_id, approved.reviewer.as_deref()), (request_id, Some("j.doe")));
+    }
+}
@@ -0,0 +1,496 @@
+//! Connector Health and Metrics
+//!
+//! What the mesh sees of the enterprise connectors:
+//! - Latency and outcome of every backend call made through
+//!   [`Resilience`](super::resilience::Resilience), by error class
+//! - Availability of each connector from its pools and circuit breakers,
+//!   with a history of changes
+//! - Depths of the queues a connector consumes (MQ, Event Mesh)
+//! - Prometheus text exposition of all of the above
+//!
+//! [`HealthMonitor`] is a readiness check of the cell: a degraded or
+//! unavailable connector marks the cell Degraded.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let monitor = Arc::new(HealthMonitor::new()
+//!     .with_connector(sap.clone())
+//!     .with_connector(mainframe.clone()));
+//! probe.register(monitor.clone());
+//! tokio::spawn(monitor.clone().run(Duration::from_secs(15), cancel.clone()));
+//!
+//! // GET /metrics
+//! let body = format!("{}{}", observability.prometheus_metrics(), connector_metrics().prometheus());
+//! ```
+
+use agentkern_arbiter::CircuitState;
+use agentkern_cloud::{Readiness, ReadinessCheck};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::executor::CancelToken;
+use super::resilience::PoolStats;
+
+/// Call latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+/// Class of a failed connector call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Lost connection, timeout, 5xx; retrying may succeed
+    Transient,
+    /// Not attempted: circuit open, pool exhausted, timed out or cancelled
+    Unavailable,
+    /// Answered with an error about the request itself
+    Permanent,
+}
+
+impl ErrorClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Transient => "transient",
+            Self::Unavailable => "unavailable",
+            Self::Permanent => "permanent",
+        }
+    }
+}
+
+/// Availability of a connector or one of its components.
+///
+/// Ordered from best to worst, so the worst of several is their `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    Up,
+    /// Serving, but with a half-open circuit, saturated pool or missing data
+    Degraded,
+    /// Not connected or circuit open
+    Down,
+}
+
+impl Availability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Degraded => "degraded",
+            Self::Down => "down",
+        }
+    }
+}
+
+/// Health of one component (RFC, OData, CICS, MQ, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub availability: Availability,
+    /// Why it is not up
+    pub detail: Option<String>,
+}
+
+impl ComponentHealth {
+    pub fn up(name: &str) -> Self {
+        Self { name: name.to_string(), availability: Availability::Up, detail: None }
+    }
+
+    pub fn degraded(name: &str, detail: &str) -> Self {
+        Self { name: name.to_string(), availability: Availability::Degraded, detail: Some(detail.to_string()) }
+    }
+
+    pub fn down(name: &str, detail: &str) -> Self {
+        Self { name: name.to_string(), availability: Availability::Down, detail: Some(detail.to_string()) }
+    }
+
+    /// From a circuit breaker: open is down, half-open degraded.
+    pub fn from_circuit(name: &str, circuit: CircuitState) -> Self {
+        match circuit {
+            CircuitState::Closed => Self::up(name),
+            CircuitState::HalfOpen => Self::degraded(name, "circuit half-open"),
+            CircuitState::Open => Self::down(name, "circuit open"),
+        }
+    }
+
+    /// From a connection pool: its circuit, and degraded while every
+    /// connection is checked out.
+    pub fn from_pool(name: &str, stats: &PoolStats) -> Self {
+        match Self::from_circuit(name, stats.circuit) {
+            health if health.availability != Availability::Up => health,
+            _ if stats.open >= stats.size && stats.idle == 0 => Self::degraded(name, "connection pool saturated"),
+            health => health,
+        }
+    }
+}
+
+/// Health of a connector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectorHealth {
+    pub connector: String,
+    /// Configured components; none means nothing is connected
+    pub components: Vec<ComponentHealth>,
+    /// Sampled depth per consumed queue
+    pub queue_depths: BTreeMap<String, u64>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ConnectorHealth {
+    pub fn new(connector: &str) -> Self {
+        Self {
+            connector: connector.to_string(),
+            components: Vec::new(),
+            queue_depths: BTreeMap::new(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    pub fn with_component(mut self, component: ComponentHealth) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    pub fn with_queue_depth(mut self, queue: &str, depth: u64) -> Self {
+        self.queue_depths.insert(queue.to_string(), depth);
+        self
+    }
+
+    /// Worst availability of the components; down if there are none.
+    pub fn availability(&self) -> Availability {
+        self.components.iter().map(|c| c.availability).max().unwrap_or(Availability::Down)
+    }
+
+    /// Components that are not up, as `name: detail`.
+    pub fn problems(&self) -> Vec<String> {
+        if self.components.is_empty() {
+            return vec!["not connected".to_string()];
+        }
+        self.components.iter()
+            .filter(|c| c.availability != Availability::Up)
+            .map(|c| format!("{}: {}", c.name, c.detail.as_deref().unwrap_or(c.availability.as_str())))
+            .collect()
+    }
+}
+
+/// Connector that can report its health.
+///
+/// Called from [`HealthMonitor::check`] on a blocking thread; sampling
+/// queue depths may call the backend.
+pub trait HealthSource: Send + Sync {
+    fn health(&self) -> ConnectorHealth;
+}
+
+/// Availability change of a connector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthChange {
+    pub at: DateTime<Utc>,
+    pub availability: Availability,
+    pub problems: Vec<String>,
+}
+
+#[derive(Default)]
+struct Tracked {
+    latest: Option<ConnectorHealth>,
+    changes: VecDeque<HealthChange>,
+}
+
+/// Polls connectors, keeps their availability history and feeds the
+/// cell's readiness probe.
+pub struct HealthMonitor {
+    sources: Vec<Arc<dyn HealthSource>>,
+    history_capacity: usize,
+    tracked: Mutex<HashMap<String, Tracked>>,
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            history_capacity: 100,
+            tracked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_connector(mut self, source: Arc<dyn HealthSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Availability changes kept per connector (default 100).
+    pub fn with_history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity.max(1);
+        self
+    }
+
+    /// Poll every connector, record changes and update the metrics.
+    pub fn check(&self) -> Vec<ConnectorHealth> {
+        let results: Vec<_> = self.sources.iter().map(|source| source.health()).collect();
+        let metrics = connector_metrics();
+        let mut tracked = self.tracked.lock().unwrap();
+        for health in &results {
+            let availability = health.availability();
+            metrics.set_availability(&health.connector, availability);
+            for (queue, depth) in &health.queue_depths {
+                metrics.set_queue_depth(&health.connector, queue, *depth);
+            }
+
+            let entry = tracked.entry(health.connector.clone()).or_default();
+            let previous = entry.latest.as_ref().map(|h| h.availability());
+            if previous != Some(availability) {
+                if entry.changes.len() >= self.history_capacity {
+                    entry.changes.pop_front();
+                }
+                entry.changes.push_back(HealthChange {
+                    at: health.checked_at,
+                    availability,
+                    problems: health.problems(),
+                });
+            }
+            entry.latest = Some(health.clone());
+        }
+        results
+    }
+
+    /// Check every `interval` until `cancel` fires.
+    pub async fn run(self: Arc<Self>, interval: Duration, cancel: CancelToken) {
+        while !cancel.is_cancelled() {
+            let monitor = self.clone();
+            // Health sources may call the backend; a panicking one is
+            // retried on the next round
+            let _ = tokio::task::spawn_blocking(move || monitor.check()).await;
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+
+    /// Result of the last check per connector.
+    pub fn latest(&self) -> Vec<ConnectorHealth> {
+        let mut latest: Vec<_> = self.tracked.lock().unwrap()
+            .values()
+            .filter_map(|t| t.latest.clone())
+            .collect();
+        latest.sort_by(|a, b| a.connector.cmp(&b.connector));
+        latest
+    }
+
+    /// Availability changes of a connector, oldest first.
+    pub fn history(&self, connector: &str) -> Vec<HealthChange> {
+        self.tracked.lock().unwrap()
+            .get(connector)
+            .map(|t| t.changes.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReadinessCheck for HealthMonitor {
+    fn name(&self) -> &str {
+        "connectors"
+    }
+
+    /// From the last check; connectors are never polled by the probe
+    /// itself. The cell keeps serving other traffic while a connector is
+    /// down, so the worst this reports is Degraded.
+    fn readiness(&self) -> Readiness {
+        let problems: Vec<_> = self.latest().iter()
+            .filter(|h| h.availability() != Availability::Up)
+            .map(|h| format!("{} ({})", h.connector, h.problems().join(", ")))
+            .collect();
+        if problems.is_empty() {
+            Readiness::Ready
+        } else {
+            Readiness::Degraded(problems.join("; "))
+        }
+    }
+}
+
+#[derive(Default)]
+struct CallStats {
+    ok: u64,
+    errors: BTreeMap<ErrorClass, u64>,
+    /// Cumulative, per `LATENCY_BUCKETS` entry
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+}
+
+/// Process-wide connector metrics.
+pub struct ConnectorMetrics {
+    calls: Mutex<BTreeMap<String, CallStats>>,
+    availability: Mutex<BTreeMap<String, Availability>>,
+    queue_depths: Mutex<BTreeMap<(String, String), u64>>,
+}
+
+static METRICS: ConnectorMetrics = ConnectorMetrics {
+    calls: Mutex::new(BTreeMap::new()),
+    availability: Mutex::new(BTreeMap::new()),
+    queue_depths: Mutex::new(BTreeMap::new()),
+};
+
+/// Metrics shared by all connectors.
+pub fn connector_metrics() -> &'static ConnectorMetrics {
+    &METRICS
+}
+
+impl ConnectorMetrics {
+    /// Record a backend call, retries included.
+    pub fn record_call(&self, service: &str, elapsed: Duration, outcome: Result<(), ErrorClass>) {
+        let mut calls = self.calls.lock().unwrap();
+        let stats = calls.entry(service.to_string()).or_default();
+        match outcome {
+            Ok(()) => stats.ok += 1,
+            Err(class) => *stats.errors.entry(class).or_default() += 1,
+        }
+        let secs = elapsed.as_secs_f64();
+        for (bucket, le) in stats.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= le {
+                *bucket += 1;
+            }
+        }
+        stats.count += 1;
+        stats.sum_secs += secs;
+    }
+
+    pub fn set_availability(&self, connector: &str, availability: Availability) {
+        self.availability.lock().unwrap().insert(connector.to_string(), availability);
+    }
+
+    pub fn set_queue_depth(&self, connector: &str, queue: &str, depth: u64) {
+        self.queue_depths.lock().unwrap().insert((connector.to_string(), queue.to_string()), depth);
+    }
+
+    /// Prometheus text exposition.
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP agentkern_connector_availability Connector availability (1 for the current state)\n");
+        out.push_str("# TYPE agentkern_connector_availability gauge\n");
+        for (connector, availability) in self.availability.lock().unwrap().iter() {
+            for state in [Availability::Up, Availability::Degraded, Availability::Down] {
+                let _ = writeln!(
+                    out,
+                    "agentkern_connector_availability{{connector=\"{}\",state=\"{}\"}} {}",
+                    escape(connector),
+                    state.as_str(),
+                    u8::from(*availability == state),
+                );
+            }
+        }
+
+        let calls = self.calls.lock().unwrap();
+        out.push_str("\n# HELP agentkern_connector_calls_total Backend calls by outcome\n");
+        out.push_str("# TYPE agentkern_connector_calls_total counter\n");
+        for (service, stats) in calls.iter() {
+            let service = escape(service);
+            let _ = writeln!(out, "agentkern_connector_calls_total{{service=\"{}\",outcome=\"ok\"}} {}", service, stats.ok);
+            for (class, count) in &stats.errors {
+                let _ = writeln!(
+                    out,
+                    "agentkern_connector_calls_total{{service=\"{}\",outcome=\"{}\"}} {}",
+                    service,
+                    class.as_str(),
+                    count,
+                );
+            }
+        }
+
+        out.push_str("\n# HELP agentkern_connector_call_duration_seconds Backend call latency, retries included\n");
+        out.push_str("# TYPE agentkern_connector_call_duration_seconds histogram\n");
+        for (service, stats) in calls.iter() {
+            let service = escape(service);
+            for (le, count) in LATENCY_BUCKETS.iter().zip(stats.buckets) {
+                let _ = writeln!(
+                    out,
+                    "agentkern_connector_call_duration_seconds_bucket{{service=\"{}\",le=\"{}\"}} {}",
+                    service, le, count,
+                );
+            }
+            let _ = writeln!(
+                out,
+                "agentkern_connector_call_duration_seconds_bucket{{service=\"{}\",le=\"+Inf\"}} {}",
+                service, stats.count,
+            );
+            let _ = writeln!(out, "agentkern_connector_call_duration_seconds_sum{{service=\"{}\"}} {}", service, stats.sum_secs);
+            let _ = writeln!(out, "agentkern_connector_call_duration_seconds_count{{service=\"{}\"}} {}", service, stats.count);
+        }
+        drop(calls);
+
+        out.push_str("\n# HELP agentkern_connector_queue_depth Messages waiting on a consumed queue\n");
+        out.push_str("# TYPE agentkern_connector_queue_depth gauge\n");
+        for ((connector, queue), depth) in self.queue_depths.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "agentkern_connector_queue_depth{{connector=\"{}\",queue=\"{}\"}} {}",
+                escape(connector),
+                escape(queue),
+                depth,
+            );
+        }
+        out
+    }
+}
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    /// Connector whose CICS circuit is set by the test.
+    struct Mainframe(AtomicU8);
+
+    impl HealthSource for Mainframe {
+        fn health(&self) -> ConnectorHealth {
+            let circuit = match self.0.load(Ordering::SeqCst) {
+                0 => CircuitState::Closed,
+                1 => CircuitState::HalfOpen,
+                _ => CircuitState::Open,
+            };
+            ConnectorHealth::new("test-mainframe")
+                .with_component(ComponentHealth::from_circuit("cics", circuit))
+                .with_queue_depth("PAY.IN", 7)
+        }
+    }
+
+    #[test]
+    fn test_health_history_readiness_and_metrics() {
+        let source = Arc::new(Mainframe(AtomicU8::new(0)));
+        let monitor = HealthMonitor::new().with_connector(source.clone());
+
+        monitor.check();
+        monitor.check();
+        assert_eq!(monitor.readiness(), Readiness::Ready);
+
+        source.0.store(2, Ordering::SeqCst);
+        monitor.check();
+        assert_eq!(monitor.readiness(), Readiness::Degraded("test-mainframe (cics: circuit open)".into()));
+        source.0.store(0, Ordering::SeqCst);
+        monitor.check();
+
+        let history: Vec<_> = monitor.history("test-mainframe").iter().map(|c| c.availability).collect();
+        assert_eq!(history, [Availability::Up, Availability::Down, Availability::Up]);
+
+        let metrics = connector_metrics();
+        metrics.record_call("test-cics", Duration::from_millis(20), Ok(()));
+        metrics.record_call("test-cics", Duration::from_millis(300), Err(ErrorClass::Transient));
+        let text = metrics.prometheus();
+        assert!(text.contains("agentkern_connector_availability{connector=\"test-mainframe\",state=\"up\"} 1"));
+        assert!(text.contains("agentkern_connector_calls_total{service=\"test-cics\",outcome=\"transient\"} 1"));
+        assert!(text.contains("agentkern_connector_call_duration_seconds_bucket{service=\"test-cics\",le=\"0.025\"} 1"));
+        assert!(text.contains("agentkern_connector_call_duration_seconds_count{service=\"test-cics\"} 2"));
+        assert!(text.contains("agentkern_connector_queue_depth{connector=\"test-mainframe\",queue=\"PAY.IN\"} 7"));
+    }
+}
@@ -3,6 +3,7 @@
 //! Execute CICS transactions and programs
 
 use super::{MainframeConfig, MainframeError};
+use super::super::resilience::PooledConnection;
 
 /// CICS client.
 pub struct CicsClient {
@@ -52,6 +53,13 @@ impl CicsClient {
     }
 }
 
+impl PooledConnection for CicsClient {
+    fn is_healthy(&self) -> bool {
+        // Production would check the CTG connection
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
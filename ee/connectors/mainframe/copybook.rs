@@ -0,0 +1,659 @@
+//! COBOL copybooks
+//!
+//! Parses copybook record layouts and maps COMMAREA bytes to JSON values,
+//! and so to serde structs, and back. Names become snake_case
+//! (`CUST-NAME` → `cust_name`), groups become objects and OCCURS arrays.
+//!
+//! Supported: `PIC X/A/9` with `S`, `V` and repeat counts; DISPLAY (zoned),
+//! COMP-3 / PACKED-DECIMAL and COMP / COMP-4 / COMP-5 / BINARY usage;
+//! OCCURS (the maximum for OCCURS DEPENDING ON); REDEFINES (the first
+//! definition is mapped); FILLER. Numeric-edited pictures are read as text.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let layout = Copybook::parse(r#"
+//!        01  ACCOUNT-INQ.
+//!            05  ACCT-NO      PIC X(10).
+//!            05  BALANCE      PIC S9(13)V99 COMP-3.
+//! "#)?;
+//! let reply: AccountInq = connector.link_program_mapped("ACCTINQ", &layout, &request).await?;
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::ebcdic::{CodePage, EBCDIC_SPACE};
+use super::MainframeError;
+
+/// Storage format of a numeric item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Usage {
+    /// Zoned decimal, one digit per byte
+    Display,
+    /// COMP-3, two digits per byte plus sign nibble
+    Packed,
+    /// COMP / COMP-4 / COMP-5 / BINARY, big-endian
+    Binary,
+}
+
+/// Type of a copybook item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Group(Vec<Field>),
+    /// PIC X / A
+    Alphanumeric,
+    Numeric { digits: u32, scale: u32, signed: bool, usage: Usage },
+    /// Numeric-edited picture (`ZZ,ZZ9.99-`), read as text
+    Edited,
+}
+
+/// Item with its position in the record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// COBOL name, `FILLER` for unnamed items
+    pub name: String,
+    pub level: u8,
+    /// Offset within the enclosing group occurrence
+    pub offset: usize,
+    /// Size of one occurrence in bytes
+    pub size: usize,
+    pub occurs: Option<usize>,
+    pub redefines: Option<String>,
+    pub field_type: FieldType,
+}
+
+impl Field {
+    /// Key in mapped values (`CUST-NAME` → `cust_name`).
+    pub fn key(&self) -> String {
+        self.name.to_ascii_lowercase().replace('-', "_")
+    }
+
+    /// FILLER and redefining items are not mapped.
+    fn is_mapped(&self) -> bool {
+        self.name != "FILLER" && self.redefines.is_none()
+    }
+}
+
+/// Record layout parsed from a copybook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Copybook {
+    pub name: Option<String>,
+    pub fields: Vec<Field>,
+    size: usize,
+}
+
+impl Copybook {
+    /// Parse a copybook holding one record (fixed or free format).
+    pub fn parse(source: &str) -> Result<Self, MainframeError> {
+        let entries = statements(source)
+            .iter()
+            .filter_map(|s| parse_entry(s).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut top = build_tree(&mut entries.into_iter().peekable(), 0)?;
+
+        if top.is_empty() {
+            return Err(copybook_error("no data items"));
+        }
+        let (name, mut fields) = match top.iter().filter(|f| f.level == 1).count() {
+            // Fragment without an 01 level, e.g. 05 items
+            0 => (None, top),
+            1 if top.len() == 1 => {
+                let record = top.remove(0);
+                match record.field_type {
+                    FieldType::Group(children) => (Some(record.name), children),
+                    _ => (Some(record.name.clone()), vec![record]),
+                }
+            }
+            _ => return Err(copybook_error("more than one record")),
+        };
+        let size = layout(&mut fields, 0)?;
+        Ok(Self { name, fields, size })
+    }
+
+    /// Record length in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Map record bytes to a JSON object.
+    pub fn decode(&self, data: &[u8], code_page: &CodePage) -> Result<Value, MainframeError> {
+        if data.len() < self.size {
+            return Err(MainframeError::EncodingError(format!(
+                "Record is {} bytes, layout needs {}", data.len(), self.size)));
+        }
+        decode_fields(&self.fields, data, code_page, "")
+    }
+
+    /// Build record bytes from a JSON object; missing items are spaces or zero.
+    pub fn encode(&self, value: &Value, code_page: &CodePage) -> Result<Vec<u8>, MainframeError> {
+        let mut out = vec![EBCDIC_SPACE; self.size];
+        encode_fields(&self.fields, value, &mut out, code_page, "")?;
+        Ok(out)
+    }
+
+    /// Map record bytes into a typed struct.
+    pub fn decode_as<T: DeserializeOwned>(&self, data: &[u8], code_page: &CodePage) -> Result<T, MainframeError> {
+        serde_json::from_value(self.decode(data, code_page)?)
+            .map_err(|e| MainframeError::EncodingError(format!("Cannot map record: {}", e)))
+    }
+
+    /// Build record bytes from a typed struct.
+    pub fn encode_from<T: Serialize>(&self, value: &T, code_page: &CodePage) -> Result<Vec<u8>, MainframeError> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| MainframeError::EncodingError(format!("Cannot map record: {}", e)))?;
+        self.encode(&value, code_page)
+    }
+}
+
+fn copybook_error(msg: &str) -> MainframeError {
+    MainframeError::EncodingError(format!("Copybook: {}", msg))
+}
+
+// ----------------------------------------------------------------------------
+// Parsing
+// ----------------------------------------------------------------------------
+
+/// Source text split into period-terminated entries, comments removed.
+fn statements(source: &str) -> Vec<String> {
+    let mut text = String::new();
+    for line in source.lines() {
+        let fixed = line.len() >= 7 && line[..6].bytes().all(|b| b.is_ascii_digit() || b == b' ');
+        let content = if fixed {
+            // Columns 1-6 sequence, 7 indicator, 8-72 code
+            if matches!(line.as_bytes()[6], b'*' | b'/') {
+                continue;
+            }
+            line.get(7..line.len().min(72)).unwrap_or_default()
+        } else {
+            line.split("*>").next().unwrap_or_default()
+        };
+        text.push_str(content);
+        text.push(' ');
+    }
+
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (q, Some(open)) if q == open => quote = None,
+            // A period ends an entry only before whitespace; PIC 9.99 keeps it
+            ('.', None) if chars.peek().is_none_or(|n| n.is_whitespace()) => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+    statements
+}
+
+/// Data item from one entry; `None` for 66/88 levels.
+fn parse_entry(statement: &str) -> Result<Option<Field>, MainframeError> {
+    let tokens: Vec<String> = statement.split_whitespace().map(|t| t.to_ascii_uppercase()).collect();
+    let level: u8 = tokens.first()
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(|| copybook_error(&format!("expected level number: {}", statement)))?;
+    if matches!(level, 66 | 88) {
+        return Ok(None);
+    }
+
+    let mut rest = tokens[1..].iter().map(String::as_str).peekable();
+    let name = match rest.peek() {
+        Some(t) if !is_keyword(t) => rest.next().unwrap_or_default().to_string(),
+        _ => "FILLER".to_string(),
+    };
+
+    let mut picture = None;
+    let mut usage = Usage::Display;
+    let mut occurs = None;
+    let mut redefines = None;
+    while let Some(token) = rest.next() {
+        match token {
+            "PIC" | "PICTURE" => {
+                let pic = rest.next().filter(|t| *t != "IS").or_else(|| rest.next());
+                picture = Some(pic.ok_or_else(|| copybook_error(&format!("{}: PIC without picture", name)))?.to_string());
+            }
+            "USAGE" | "IS" => {}
+            "COMP-3" | "COMPUTATIONAL-3" | "PACKED-DECIMAL" => usage = Usage::Packed,
+            "COMP" | "COMP-4" | "COMP-5" | "COMPUTATIONAL" | "COMPUTATIONAL-4" | "COMPUTATIONAL-5" | "BINARY" => {
+                usage = Usage::Binary
+            }
+            "DISPLAY" => usage = Usage::Display,
+            "COMP-1" | "COMP-2" | "COMPUTATIONAL-1" | "COMPUTATIONAL-2" => {
+                return Err(copybook_error(&format!("{}: floating point usage not supported", name)));
+            }
+            "OCCURS" => {
+                let count = rest.next().and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| copybook_error(&format!("{}: OCCURS without count", name)))?;
+                // OCCURS n TO m DEPENDING ON: lay out the maximum
+                let max = if rest.peek() == Some(&"TO") {
+                    rest.next();
+                    rest.next().and_then(|n| n.parse().ok())
+                        .ok_or_else(|| copybook_error(&format!("{}: OCCURS TO without maximum", name)))?
+                } else {
+                    count
+                };
+                occurs = Some(max);
+            }
+            "REDEFINES" => redefines = rest.next().map(String::from),
+            "SEPARATE" => return Err(copybook_error(&format!("{}: SIGN SEPARATE not supported", name))),
+            // VALUE literals and the remaining clauses do not affect layout
+            "VALUE" | "VALUES" => break,
+            _ => {}
+        }
+    }
+
+    let (field_type, size) = match picture {
+        Some(pic) => picture_type(&name, &pic, usage)?,
+        None => (FieldType::Group(Vec::new()), 0),
+    };
+    Ok(Some(Field { name, level, offset: 0, size, occurs, redefines, field_type }))
+}
+
+fn is_keyword(token: &str) -> bool {
+    matches!(token, "PIC" | "PICTURE" | "USAGE" | "OCCURS" | "REDEFINES" | "VALUE" | "COMP" | "COMP-3"
+        | "COMP-4" | "COMP-5" | "BINARY" | "PACKED-DECIMAL" | "DISPLAY")
+}
+
+/// Item type and storage size of a picture.
+fn picture_type(name: &str, pic: &str, usage: Usage) -> Result<(FieldType, usize), MainframeError> {
+    let symbols = expand_picture(pic).ok_or_else(|| copybook_error(&format!("{}: invalid PIC {}", name, pic)))?;
+
+    if symbols.iter().all(|c| matches!(c, 'X' | 'A' | '9')) && symbols.iter().any(|c| *c != '9') {
+        return Ok((FieldType::Alphanumeric, symbols.len()));
+    }
+    if symbols.iter().all(|c| matches!(c, '9' | 'S' | 'V')) {
+        let signed = symbols.first() == Some(&'S');
+        let point = symbols.iter().position(|c| *c == 'V');
+        let digits = symbols.iter().filter(|c| **c == '9').count() as u32;
+        let scale = point.map_or(0, |p| symbols[p..].iter().filter(|c| **c == '9').count() as u32);
+        if digits == 0 || digits > 18 {
+            return Err(copybook_error(&format!("{}: {} digits not supported", name, digits)));
+        }
+        let size = match usage {
+            Usage::Display => digits as usize,
+            Usage::Packed => digits as usize / 2 + 1,
+            Usage::Binary => match digits {
+                1..=4 => 2,
+                5..=9 => 4,
+                _ => 8,
+            },
+        };
+        return Ok((FieldType::Numeric { digits, scale, signed, usage }, size));
+    }
+    if usage != Usage::Display {
+        return Err(copybook_error(&format!("{}: edited PIC {} must be DISPLAY", name, pic)));
+    }
+    // Every edit symbol takes one position except the implied point
+    Ok((FieldType::Edited, symbols.iter().filter(|c| **c != 'V').count()))
+}
+
+/// `S9(3)V99` → `S999V99`.
+fn expand_picture(pic: &str) -> Option<Vec<char>> {
+    let mut out: Vec<char> = Vec::new();
+    let mut chars = pic.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '(' {
+            let count: String = chars.by_ref().take_while(|c| *c != ')').collect();
+            let repeat = *out.last()?;
+            let count: usize = count.trim().parse().ok()?;
+            out.extend(std::iter::repeat_n(repeat, count.checked_sub(1)?));
+        } else {
+            out.push(c.to_ascii_uppercase());
+        }
+    }
+    Some(out)
+}
+
+/// Nest entries by level number.
+fn build_tree(entries: &mut std::iter::Peekable<impl Iterator<Item = Field>>, parent: u8) -> Result<Vec<Field>, MainframeError> {
+    let mut fields = Vec::new();
+    while let Some(mut field) = entries.next_if(|e| e.level > parent) {
+        if let FieldType::Group(children) = &mut field.field_type {
+            *children = build_tree(entries, field.level)?;
+            if children.is_empty() {
+                return Err(copybook_error(&format!("{}: group without items or PIC", field.name)));
+            }
+        }
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+/// Assign offsets and group sizes; returns the extent of `fields`.
+fn layout(fields: &mut [Field], start: usize) -> Result<usize, MainframeError> {
+    let mut offset = start;
+    let mut end = start;
+    for i in 0..fields.len() {
+        let at = match &fields[i].redefines {
+            Some(target) => fields[..i].iter().find(|f| &f.name == target).map(|f| f.offset)
+                .ok_or_else(|| copybook_error(&format!("{} redefines unknown {}", fields[i].name, target)))?,
+            None => offset,
+        };
+        let field = &mut fields[i];
+        field.offset = at;
+        if let FieldType::Group(children) = &mut field.field_type {
+            // Children are laid out relative to each occurrence
+            field.size = layout(children, 0)?;
+        }
+        let extent = at + field.size * field.occurs.unwrap_or(1);
+        if field.redefines.is_none() {
+            offset = extent;
+        }
+        end = end.max(extent);
+    }
+    Ok(end - start)
+}
+
+// ----------------------------------------------------------------------------
+// Mapping
+// ----------------------------------------------------------------------------
+
+fn decode_fields(fields: &[Field], data: &[u8], cp: &CodePage, path: &str) -> Result<Value, MainframeError> {
+    let mut map = Map::new();
+    for field in fields.iter().filter(|f| f.is_mapped()) {
+        let path = format!("{}{}", path, field.name);
+        let value = match field.occurs {
+            Some(n) => Value::Array((0..n)
+                .map(|i| {
+                    let start = field.offset + i * field.size;
+                    decode_item(field, &data[start..start + field.size], cp, &format!("{}({})", path, i + 1))
+                })
+                .collect::<Result<_, _>>()?),
+            None => decode_item(field, &data[field.offset..field.offset + field.size], cp, &path)?,
+        };
+        map.insert(field.key(), value);
+    }
+    Ok(Value::Object(map))
+}
+
+fn decode_item(field: &Field, bytes: &[u8], cp: &CodePage, path: &str) -> Result<Value, MainframeError> {
+    match &field.field_type {
+        FieldType::Group(children) => decode_fields(children, bytes, cp, &format!("{}.", path)),
+        FieldType::Alphanumeric | FieldType::Edited => {
+            Ok(Value::String(cp.decode(bytes).trim_end_matches([' ', '\0']).to_string()))
+        }
+        FieldType::Numeric { scale, signed, usage, .. } => {
+            let unscaled = decode_number(bytes, *usage, *signed)
+                .map_err(|msg| MainframeError::EncodingError(format!("{}: {}", path, msg)))?;
+            Ok(match scale {
+                0 => Value::from(unscaled),
+                _ => Value::from(unscaled as f64 / 10f64.powi(*scale as i32)),
+            })
+        }
+    }
+}
+
+fn decode_number(bytes: &[u8], usage: Usage, signed: bool) -> Result<i64, String> {
+    // Never-initialised storage reads as zero
+    if bytes.iter().all(|&b| b == EBCDIC_SPACE) || bytes.iter().all(|&b| b == 0) && usage != Usage::Binary {
+        return Ok(0);
+    }
+    match usage {
+        Usage::Display => {
+            let mut value: i64 = 0;
+            for &b in bytes {
+                let digit = b & 0x0F;
+                if digit > 9 || b >> 4 < 0x0A {
+                    return Err(format!("invalid zoned decimal byte {:02X}", b));
+                }
+                value = value * 10 + i64::from(digit);
+            }
+            let zone = bytes.last().map_or(0x0F, |b| b >> 4);
+            Ok(if matches!(zone, 0x0B | 0x0D) { -value } else { value })
+        }
+        Usage::Packed => {
+            let mut value: i64 = 0;
+            let nibbles = bytes.iter().flat_map(|b| [b >> 4, b & 0x0F]);
+            let count = bytes.len() * 2;
+            let mut sign = 0x0C;
+            for (i, nibble) in nibbles.enumerate() {
+                if i == count - 1 {
+                    sign = nibble;
+                } else if nibble > 9 {
+                    return Err(format!("invalid packed decimal digit {:X}", nibble));
+                } else {
+                    value = value * 10 + i64::from(nibble);
+                }
+            }
+            match sign {
+                0x0B | 0x0D => Ok(-value),
+                0x0A | 0x0C | 0x0E | 0x0F => Ok(value),
+                _ => Err(format!("invalid packed decimal sign {:X}", sign)),
+            }
+        }
+        Usage::Binary => {
+            let fill = if signed && bytes.first().is_some_and(|b| b & 0x80 != 0) { 0xFF } else { 0x00 };
+            let mut buf = [fill; 8];
+            buf[8 - bytes.len()..].copy_from_slice(bytes);
+            Ok(i64::from_be_bytes(buf))
+        }
+    }
+}
+
+fn encode_fields(fields: &[Field], value: &Value, out: &mut [u8], cp: &CodePage, path: &str) -> Result<(), MainframeError> {
+    if !value.is_object() && !value.is_null() {
+        return Err(MainframeError::EncodingError(format!("{}: expected an object", path.trim_end_matches('.'))));
+    }
+    for field in fields {
+        let path = format!("{}{}", path, field.name);
+        let item = if field.is_mapped() { value.get(field.key()) } else { None };
+        // Redefining items share storage with the item they redefine
+        if field.redefines.is_some() {
+            continue;
+        }
+        match field.occurs {
+            Some(n) => {
+                let items = match item {
+                    Some(Value::Array(items)) => items.as_slice(),
+                    None | Some(Value::Null) => &[],
+                    Some(_) => return Err(MainframeError::EncodingError(format!("{}: expected an array", path))),
+                };
+                if items.len() > n {
+                    return Err(MainframeError::EncodingError(format!("{}: {} items, OCCURS {}", path, items.len(), n)));
+                }
+                for i in 0..n {
+                    let start = field.offset + i * field.size;
+                    encode_item(field, items.get(i), &mut out[start..start + field.size], cp, &format!("{}({})", path, i + 1))?;
+                }
+            }
+            None => encode_item(field, item, &mut out[field.offset..field.offset + field.size], cp, &path)?,
+        }
+    }
+    Ok(())
+}
+
+fn encode_item(field: &Field, value: Option<&Value>, out: &mut [u8], cp: &CodePage, path: &str) -> Result<(), MainframeError> {
+    let error = |msg: String| MainframeError::EncodingError(format!("{}: {}", path, msg));
+    match &field.field_type {
+        FieldType::Group(children) => {
+            encode_fields(children, value.unwrap_or(&Value::Null), out, cp, &format!("{}.", path))
+        }
+        FieldType::Alphanumeric | FieldType::Edited => {
+            let text = match value {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                Some(Value::Bool(b)) => b.to_string(),
+                Some(_) => return Err(error("expected text".into())),
+            };
+            let bytes = cp.encode(&text).map_err(|e| error(e.to_string()))?;
+            if bytes.len() > out.len() {
+                return Err(error(format!("{} characters, PIC allows {}", bytes.len(), out.len())));
+            }
+            out[..bytes.len()].copy_from_slice(&bytes);
+            out[bytes.len()..].fill(EBCDIC_SPACE);
+            Ok(())
+        }
+        FieldType::Numeric { digits, scale, signed, usage } => {
+            let unscaled = match value {
+                None | Some(Value::Null) => 0,
+                Some(Value::Number(n)) => scale_decimal(&n.to_string(), *scale).map_err(error)?,
+                Some(Value::String(s)) => scale_decimal(s.trim(), *scale).map_err(error)?,
+                Some(_) => return Err(error("expected a number".into())),
+            };
+            if unscaled < 0 && !signed {
+                return Err(error("negative value for unsigned item".into()));
+            }
+            if unscaled.unsigned_abs() >= 10u64.pow(*digits) {
+                return Err(error(format!("{} does not fit {} digits", unscaled, digits)));
+            }
+            encode_number(unscaled, *usage, *signed, out);
+            Ok(())
+        }
+    }
+}
+
+/// `"12.345"` at scale 2 → `1235` (half away from zero).
+fn scale_decimal(text: &str, scale: u32) -> Result<i64, String> {
+    let invalid = || format!("invalid number {}", text);
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    // serde_json prints large floats in exponent form
+    if unsigned.contains(['e', 'E']) {
+        let value: f64 = text.parse().map_err(|_| invalid())?;
+        return Ok((value * 10f64.powi(scale as i32)).round() as i64);
+    }
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if int.is_empty() && frac.is_empty() || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let mut digits: String = int.to_string();
+    let kept: String = frac.chars().chain(std::iter::repeat('0')).take(scale as usize).collect();
+    digits.push_str(&kept);
+    let mut value: i64 = digits.trim_start_matches('0').parse().or_else(|_| {
+        if digits.bytes().all(|b| b == b'0') { Ok(0) } else { Err(invalid()) }
+    })?;
+    if frac.as_bytes().get(scale as usize).is_some_and(|d| *d >= b'5') {
+        value += 1;
+    }
+    Ok(if negative { -value } else { value })
+}
+
+fn encode_number(value: i64, usage: Usage, signed: bool, out: &mut [u8]) {
+    let negative = value < 0;
+    let mut magnitude = value.unsigned_abs();
+    match usage {
+        Usage::Display => {
+            for byte in out.iter_mut().rev() {
+                *byte = 0xF0 | (magnitude % 10) as u8;
+                magnitude /= 10;
+            }
+            if signed {
+                let last = out.len() - 1;
+                out[last] = (out[last] & 0x0F) | if negative { 0xD0 } else { 0xC0 };
+            }
+        }
+        Usage::Packed => {
+            let sign = match (signed, negative) {
+                (false, _) => 0x0F,
+                (true, false) => 0x0C,
+                (true, true) => 0x0D,
+            };
+            let mut nibbles = vec![sign];
+            while nibbles.len() < out.len() * 2 {
+                nibbles.push((magnitude % 10) as u8);
+                magnitude /= 10;
+            }
+            for (i, byte) in out.iter_mut().rev().enumerate() {
+                *byte = (nibbles[2 * i + 1] << 4) | nibbles[2 * i];
+            }
+        }
+        Usage::Binary => {
+            let bytes = value.to_be_bytes();
+            out.copy_from_slice(&bytes[8 - out.len()..]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    const ACCOUNT: &str = "\
+000100* ACCOUNT INQUIRY COMMAREA
+000200 01  ACCOUNT-INQ.
+000300     05  ACCT-NO            PIC X(8).
+000400     05  ACCT-STATUS        PIC X.
+000500         88  ACCT-OPEN      VALUE 'O'.
+000600     05  BALANCE            PIC S9(7)V99 COMP-3.
+000700     05  TXN-COUNT          PIC 9(4) COMP.
+000800     05  FILLER             PIC X(2).
+000900     05  LAST-TXN OCCURS 2 TIMES.
+001000         10  TXN-AMOUNT     PIC S9(5)V99.
+001100         10  TXN-DATE       PIC X(8).
+001200     05  LAST-TXN-R REDEFINES LAST-TXN PIC X(30).
+";
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct AccountInq {
+        acct_no: String,
+        acct_status: String,
+        balance: f64,
+        txn_count: u16,
+        last_txn: Vec<Txn>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Txn {
+        txn_amount: f64,
+        txn_date: String,
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        let book = Copybook::parse(ACCOUNT).unwrap();
+        assert_eq!(book.name.as_deref(), Some("ACCOUNT-INQ"));
+        assert_eq!(book.size(), 8 + 1 + 5 + 2 + 2 + 2 * 15);
+
+        let offsets: Vec<_> = book.fields.iter().map(|f| (f.name.as_str(), f.offset, f.size)).collect();
+        assert_eq!(offsets, [
+            ("ACCT-NO", 0, 8), ("ACCT-STATUS", 8, 1), ("BALANCE", 9, 5), ("TXN-COUNT", 14, 2),
+            ("FILLER", 16, 2), ("LAST-TXN", 18, 15), ("LAST-TXN-R", 18, 30),
+        ]);
+        assert!(Copybook::parse("01 A. 05 B COMP-1.").is_err());
+    }
+
+    #[test]
+    fn test_struct_roundtrip() {
+        let cp = CodePage::from_name("IBM037").unwrap();
+        let book = Copybook::parse(ACCOUNT).unwrap();
+        let account = AccountInq {
+            acct_no: "AC123".into(),
+            acct_status: "O".into(),
+            balance: -1234.5,
+            txn_count: 2,
+            last_txn: vec![Txn { txn_amount: 99.99, txn_date: "20251226".into() }],
+        };
+
+        let bytes = book.encode_from(&account, &cp).unwrap();
+        assert_eq!(&bytes[9..14], [0x00, 0x01, 0x23, 0x45, 0x0D]);
+        assert_eq!(&bytes[14..16], [0x00, 0x02]);
+        // Zoned 0009999 with positive sign in the last zone
+        assert_eq!(&bytes[18..25], [0xF0, 0xF0, 0xF0, 0xF9, 0xF9, 0xF9, 0xC9]);
+
+        let decoded: AccountInq = book.decode_as(&bytes, &cp).unwrap();
+        assert_eq!(decoded.acct_no, "AC123");
+        assert_eq!(decoded.balance, -1234.5);
+        assert_eq!(decoded.last_txn[0], account.last_txn[0]);
+        assert_eq!(decoded.last_txn[1], Txn { txn_amount: 0.0, txn_date: String::new() });
+
+        let too_long = AccountInq { acct_no: "TOO-LONG-ID".into(), ..account };
+        assert!(book.encode_from(&too_long, &cp).is_err());
+    }
+}
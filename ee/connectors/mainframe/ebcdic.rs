@@ -0,0 +1,207 @@
+//! EBCDIC code pages
+//!
+//! Conversion between EBCDIC payloads and UTF-8 for the code page in
+//! `MainframeConfig.code_page`. Supported: IBM037 (US/Canada), IBM273
+//! (Germany/Austria), IBM500 (International), IBM1047 (z/OS Unix) and the
+//! euro variants IBM1140, IBM1141 and IBM1148.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::MainframeError;
+
+/// EBCDIC space, used to pad alphanumeric fields.
+pub const EBCDIC_SPACE: u8 = 0x40;
+
+/// Single-byte EBCDIC code page.
+#[derive(Clone, Copy)]
+pub struct CodePage {
+    table: &'static CodeTable,
+}
+
+struct CodeTable {
+    name: &'static str,
+    ccsid: u16,
+    decode: [u16; 256],
+    encode: OnceLock<HashMap<char, u8>>,
+}
+
+impl CodeTable {
+    const fn new(name: &'static str, ccsid: u16, decode: [u16; 256]) -> Self {
+        Self { name, ccsid, decode, encode: OnceLock::new() }
+    }
+}
+
+static CODE_PAGES: [CodeTable; 7] = [
+    CodeTable::new("IBM037", 37, CP037),
+    CodeTable::new("IBM273", 273, CP273),
+    CodeTable::new("IBM500", 500, CP500),
+    CodeTable::new("IBM1047", 1047, cp1047()),
+    CodeTable::new("IBM1140", 1140, with_euro(CP037)),
+    CodeTable::new("IBM1141", 1141, with_euro(CP273)),
+    CodeTable::new("IBM1148", 1148, with_euro(CP500)),
+];
+
+impl CodePage {
+    /// Look up a code page by name or CCSID (`IBM037`, `CP037`, `IBM-1047`, `1140`).
+    pub fn from_name(name: &str) -> Result<Self, MainframeError> {
+        let upper = name.trim().to_ascii_uppercase();
+        let digits = upper.trim_start_matches("IBM").trim_start_matches("CP").trim_start_matches('-');
+        digits.parse::<u16>().ok()
+            .and_then(|ccsid| CODE_PAGES.iter().find(|t| t.ccsid == ccsid))
+            .map(|table| Self { table })
+            .ok_or_else(|| MainframeError::EncodingError(format!("Unsupported code page: {}", name)))
+    }
+
+    /// Canonical name, e.g. `IBM037`.
+    pub fn name(&self) -> &'static str {
+        self.table.name
+    }
+
+    pub fn ccsid(&self) -> u16 {
+        self.table.ccsid
+    }
+
+    /// Decode EBCDIC bytes; every byte maps to a character.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|&b| self.decode_byte(b)).collect()
+    }
+
+    pub fn decode_byte(&self, byte: u8) -> char {
+        // Tables only hold BMP code points
+        char::from_u32(u32::from(self.table.decode[byte as usize])).unwrap_or('\u{FFFD}')
+    }
+
+    /// Encode text; characters outside the code page are an error.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, MainframeError> {
+        text.chars()
+            .map(|c| self.encode_char(c).ok_or_else(|| {
+                MainframeError::EncodingError(format!("'{}' (U+{:04X}) not in {}", c, c as u32, self.name()))
+            }))
+            .collect()
+    }
+
+    pub fn encode_char(&self, c: char) -> Option<u8> {
+        self.table.encode
+            .get_or_init(|| (0..=255u8).map(|b| (self.decode_byte(b), b)).collect())
+            .get(&c)
+            .copied()
+    }
+}
+
+impl std::fmt::Debug for CodePage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CodePage").field(&self.table.name).finish()
+    }
+}
+
+impl PartialEq for CodePage {
+    fn eq(&self, other: &Self) -> bool {
+        self.table.ccsid == other.table.ccsid
+    }
+}
+
+/// Euro variants replace the currency sign at 0x9F.
+const fn with_euro(mut table: [u16; 256]) -> [u16; 256] {
+    table[0x9F] = 0x20AC;
+    table
+}
+
+/// IBM1047 is IBM037 with brackets, caret and not-sign moved.
+const fn cp1047() -> [u16; 256] {
+    let mut table = CP037;
+    let swaps = [(0x5F, 0xB0), (0xAD, 0xBA), (0xBB, 0xBD)];
+    let mut i = 0;
+    while i < swaps.len() {
+        let (a, b) = swaps[i];
+        let tmp = table[a];
+        table[a] = table[b];
+        table[b] = tmp;
+        i += 1;
+    }
+    table
+}
+
+/// IBM037 to Unicode.
+const CP037: [u16; 256] = [
+    0x0000, 0x0001, 0x0002, 0x0003, 0x009C, 0x0009, 0x0086, 0x007F, 0x0097, 0x008D, 0x008E, 0x000B, 0x000C, 0x000D, 0x000E, 0x000F,
+    0x0010, 0x0011, 0x0012, 0x0013, 0x009D, 0x0085, 0x0008, 0x0087, 0x0018, 0x0019, 0x0092, 0x008F, 0x001C, 0x001D, 0x001E, 0x001F,
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x000A, 0x0017, 0x001B, 0x0088, 0x0089, 0x008A, 0x008B, 0x008C, 0x0005, 0x0006, 0x0007,
+    0x0090, 0x0091, 0x0016, 0x0093, 0x0094, 0x0095, 0x0096, 0x0004, 0x0098, 0x0099, 0x009A, 0x009B, 0x0014, 0x0015, 0x009E, 0x001A,
+    0x0020, 0x00A0, 0x00E2, 0x00E4, 0x00E0, 0x00E1, 0x00E3, 0x00E5, 0x00E7, 0x00F1, 0x00A2, 0x002E, 0x003C, 0x0028, 0x002B, 0x007C,
+    0x0026, 0x00E9, 0x00EA, 0x00EB, 0x00E8, 0x00ED, 0x00EE, 0x00EF, 0x00EC, 0x00DF, 0x0021, 0x0024, 0x002A, 0x0029, 0x003B, 0x00AC,
+    0x002D, 0x002F, 0x00C2, 0x00C4, 0x00C0, 0x00C1, 0x00C3, 0x00C5, 0x00C7, 0x00D1, 0x00A6, 0x002C, 0x0025, 0x005F, 0x003E, 0x003F,
+    0x00F8, 0x00C9, 0x00CA, 0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x0060, 0x003A, 0x0023, 0x0040, 0x0027, 0x003D, 0x0022,
+    0x00D8, 0x0061, 0x0062, 0x0063, 0x0064, 0x0065, 0x0066, 0x0067, 0x0068, 0x0069, 0x00AB, 0x00BB, 0x00F0, 0x00FD, 0x00FE, 0x00B1,
+    0x00B0, 0x006A, 0x006B, 0x006C, 0x006D, 0x006E, 0x006F, 0x0070, 0x0071, 0x0072, 0x00AA, 0x00BA, 0x00E6, 0x00B8, 0x00C6, 0x00A4,
+    0x00B5, 0x007E, 0x0073, 0x0074, 0x0075, 0x0076, 0x0077, 0x0078, 0x0079, 0x007A, 0x00A1, 0x00BF, 0x00D0, 0x00DD, 0x00DE, 0x00AE,
+    0x005E, 0x00A3, 0x00A5, 0x00B7, 0x00A9, 0x00A7, 0x00B6, 0x00BC, 0x00BD, 0x00BE, 0x005B, 0x005D, 0x00AF, 0x00A8, 0x00B4, 0x00D7,
+    0x007B, 0x0041, 0x0042, 0x0043, 0x0044, 0x0045, 0x0046, 0x0047, 0x0048, 0x0049, 0x00AD, 0x00F4, 0x00F6, 0x00F2, 0x00F3, 0x00F5,
+    0x007D, 0x004A, 0x004B, 0x004C, 0x004D, 0x004E, 0x004F, 0x0050, 0x0051, 0x0052, 0x00B9, 0x00FB, 0x00FC, 0x00F9, 0x00FA, 0x00FF,
+    0x005C, 0x00F7, 0x0053, 0x0054, 0x0055, 0x0056, 0x0057, 0x0058, 0x0059, 0x005A, 0x00B2, 0x00D4, 0x00D6, 0x00D2, 0x00D3, 0x00D5,
+    0x0030, 0x0031, 0x0032, 0x0033, 0x0034, 0x0035, 0x0036, 0x0037, 0x0038, 0x0039, 0x00B3, 0x00DB, 0x00DC, 0x00D9, 0x00DA, 0x009F,
+];
+
+/// IBM273 to Unicode.
+const CP273: [u16; 256] = [
+    0x0000, 0x0001, 0x0002, 0x0003, 0x009C, 0x0009, 0x0086, 0x007F, 0x0097, 0x008D, 0x008E, 0x000B, 0x000C, 0x000D, 0x000E, 0x000F,
+    0x0010, 0x0011, 0x0012, 0x0013, 0x009D, 0x0085, 0x0008, 0x0087, 0x0018, 0x0019, 0x0092, 0x008F, 0x001C, 0x001D, 0x001E, 0x001F,
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x000A, 0x0017, 0x001B, 0x0088, 0x0089, 0x008A, 0x008B, 0x008C, 0x0005, 0x0006, 0x0007,
+    0x0090, 0x0091, 0x0016, 0x0093, 0x0094, 0x0095, 0x0096, 0x0004, 0x0098, 0x0099, 0x009A, 0x009B, 0x0014, 0x0015, 0x009E, 0x001A,
+    0x0020, 0x00A0, 0x00E2, 0x007B, 0x00E0, 0x00E1, 0x00E3, 0x00E5, 0x00E7, 0x00F1, 0x00C4, 0x002E, 0x003C, 0x0028, 0x002B, 0x0021,
+    0x0026, 0x00E9, 0x00EA, 0x00EB, 0x00E8, 0x00ED, 0x00EE, 0x00EF, 0x00EC, 0x007E, 0x00DC, 0x0024, 0x002A, 0x0029, 0x003B, 0x005E,
+    0x002D, 0x002F, 0x00C2, 0x005B, 0x00C0, 0x00C1, 0x00C3, 0x00C5, 0x00C7, 0x00D1, 0x00F6, 0x002C, 0x0025, 0x005F, 0x003E, 0x003F,
+    0x00F8, 0x00C9, 0x00CA, 0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x0060, 0x003A, 0x0023, 0x00A7, 0x0027, 0x003D, 0x0022,
+    0x00D8, 0x0061, 0x0062, 0x0063, 0x0064, 0x0065, 0x0066, 0x0067, 0x0068, 0x0069, 0x00AB, 0x00BB, 0x00F0, 0x00FD, 0x00FE, 0x00B1,
+    0x00B0, 0x006A, 0x006B, 0x006C, 0x006D, 0x006E, 0x006F, 0x0070, 0x0071, 0x0072, 0x00AA, 0x00BA, 0x00E6, 0x00B8, 0x00C6, 0x00A4,
+    0x00B5, 0x00DF, 0x0073, 0x0074, 0x0075, 0x0076, 0x0077, 0x0078, 0x0079, 0x007A, 0x00A1, 0x00BF, 0x00D0, 0x00DD, 0x00DE, 0x00AE,
+    0x00A2, 0x00A3, 0x00A5, 0x00B7, 0x00A9, 0x0040, 0x00B6, 0x00BC, 0x00BD, 0x00BE, 0x00AC, 0x007C, 0x203E, 0x00A8, 0x00B4, 0x00D7,
+    0x00E4, 0x0041, 0x0042, 0x0043, 0x0044, 0x0045, 0x0046, 0x0047, 0x0048, 0x0049, 0x00AD, 0x00F4, 0x00A6, 0x00F2, 0x00F3, 0x00F5,
+    0x00FC, 0x004A, 0x004B, 0x004C, 0x004D, 0x004E, 0x004F, 0x0050, 0x0051, 0x0052, 0x00B9, 0x00FB, 0x007D, 0x00F9, 0x00FA, 0x00FF,
+    0x00D6, 0x00F7, 0x0053, 0x0054, 0x0055, 0x0056, 0x0057, 0x0058, 0x0059, 0x005A, 0x00B2, 0x00D4, 0x005C, 0x00D2, 0x00D3, 0x00D5,
+    0x0030, 0x0031, 0x0032, 0x0033, 0x0034, 0x0035, 0x0036, 0x0037, 0x0038, 0x0039, 0x00B3, 0x00DB, 0x005D, 0x00D9, 0x00DA, 0x009F,
+];
+
+/// IBM500 to Unicode.
+const CP500: [u16; 256] = [
+    0x0000, 0x0001, 0x0002, 0x0003, 0x009C, 0x0009, 0x0086, 0x007F, 0x0097, 0x008D, 0x008E, 0x000B, 0x000C, 0x000D, 0x000E, 0x000F,
+    0x0010, 0x0011, 0x0012, 0x0013, 0x009D, 0x0085, 0x0008, 0x0087, 0x0018, 0x0019, 0x0092, 0x008F, 0x001C, 0x001D, 0x001E, 0x001F,
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x000A, 0x0017, 0x001B, 0x0088, 0x0089, 0x008A, 0x008B, 0x008C, 0x0005, 0x0006, 0x0007,
+    0x0090, 0x0091, 0x0016, 0x0093, 0x0094, 0x0095, 0x0096, 0x0004, 0x0098, 0x0099, 0x009A, 0x009B, 0x0014, 0x0015, 0x009E, 0x001A,
+    0x0020, 0x00A0, 0x00E2, 0x00E4, 0x00E0, 0x00E1, 0x00E3, 0x00E5, 0x00E7, 0x00F1, 0x005B, 0x002E, 0x003C, 0x0028, 0x002B, 0x0021,
+    0x0026, 0x00E9, 0x00EA, 0x00EB, 0x00E8, 0x00ED, 0x00EE, 0x00EF, 0x00EC, 0x00DF, 0x005D, 0x0024, 0x002A, 0x0029, 0x003B, 0x005E,
+    0x002D, 0x002F, 0x00C2, 0x00C4, 0x00C0, 0x00C1, 0x00C3, 0x00C5, 0x00C7, 0x00D1, 0x00A6, 0x002C, 0x0025, 0x005F, 0x003E, 0x003F,
+    0x00F8, 0x00C9, 0x00CA, 0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x0060, 0x003A, 0x0023, 0x0040, 0x0027, 0x003D, 0x0022,
+    0x00D8, 0x0061, 0x0062, 0x0063, 0x0064, 0x0065, 0x0066, 0x0067, 0x0068, 0x0069, 0x00AB, 0x00BB, 0x00F0, 0x00FD, 0x00FE, 0x00B1,
+    0x00B0, 0x006A, 0x006B, 0x006C, 0x006D, 0x006E, 0x006F, 0x0070, 0x0071, 0x0072, 0x00AA, 0x00BA, 0x00E6, 0x00B8, 0x00C6, 0x00A4,
+    0x00B5, 0x007E, 0x0073, 0x0074, 0x0075, 0x0076, 0x0077, 0x0078, 0x0079, 0x007A, 0x00A1, 0x00BF, 0x00D0, 0x00DD, 0x00DE, 0x00AE,
+    0x00A2, 0x00A3, 0x00A5, 0x00B7, 0x00A9, 0x00A7, 0x00B6, 0x00BC, 0x00BD, 0x00BE, 0x00AC, 0x007C, 0x00AF, 0x00A8, 0x00B4, 0x00D7,
+    0x007B, 0x0041, 0x0042, 0x0043, 0x0044, 0x0045, 0x0046, 0x0047, 0x0048, 0x0049, 0x00AD, 0x00F4, 0x00F6, 0x00F2, 0x00F3, 0x00F5,
+    0x007D, 0x004A, 0x004B, 0x004C, 0x004D, 0x004E, 0x004F, 0x0050, 0x0051, 0x0052, 0x00B9, 0x00FB, 0x00FC, 0x00F9, 0x00FA, 0x00FF,
+    0x005C, 0x00F7, 0x0053, 0x0054, 0x0055, 0x0056, 0x0057, 0x0058, 0x0059, 0x005A, 0x00B2, 0x00D4, 0x00D6, 0x00D2, 0x00D3, 0x00D5,
+    0x0030, 0x0031, 0x0032, 0x0033, 0x0034, 0x0035, 0x0036, 0x0037, 0x0038, 0x0039, 0x00B3, 0x00DB, 0x00DC, 0x00D9, 0x00DA, 0x009F,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_and_variants() {
+        let cp037 = CodePage::from_name("IBM037").unwrap();
+        assert_eq!(cp037.encode("HELLO 123").unwrap(), [0xC8, 0xC5, 0xD3, 0xD3, 0xD6, 0x40, 0xF1, 0xF2, 0xF3]);
+        assert_eq!(cp037.decode(&[0xC1, 0x81, 0x4B]), "Aa.");
+        assert!(cp037.encode("€").is_err());
+
+        let cp1140 = CodePage::from_name("cp1140").unwrap();
+        assert_eq!(cp1140.encode("€").unwrap(), [0x9F]);
+
+        let cp1047 = CodePage::from_name("IBM-1047").unwrap();
+        assert_eq!(cp1047.encode("[^]").unwrap(), [0xAD, 0x5F, 0xBD]);
+        assert_eq!(cp037.encode("[^]").unwrap(), [0xBA, 0xB0, 0xBB]);
+
+        let cp273 = CodePage::from_name("273").unwrap();
+        assert_eq!(cp273.decode(&cp273.encode("Straße Ä").unwrap()), "Straße Ä");
+        assert!(CodePage::from_name("IBM930").is_err());
+    }
+}
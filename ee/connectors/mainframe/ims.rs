@@ -3,6 +3,7 @@
 //! Access IMS databases and transactions
 
 use super::{MainframeConfig, MainframeError};
+use super::super::resilience::PooledConnection;
 
 /// IMS client via IMS Connect.
 pub struct ImsClient {
@@ -34,3 +35,10 @@ impl ImsClient {
         &self.datastores
     }
 }
+
+impl PooledConnection for ImsClient {
+    fn is_healthy(&self) -> bool {
+        // Production would ping IMS Connect
+        true
+    }
+}
@@ -4,15 +4,30 @@
 //! Per LICENSING.md: Enterprise tier (F500 mainframe deals)
 
 mod cics;
+mod copybook;
+mod ebcdic;
 mod ims;
 mod mq;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use super::executor::{run_blocking, CallOptions};
+use super::gating::{ConnectorAction, ConnectorGate, GateRejection};
+use super::health::{ComponentHealth, ConnectorHealth, ErrorClass, HealthSource};
 use super::license::{check_feature_license, LicenseError};
+use super::secrets::{SecretError, SecretRef};
+use super::resilience::{ConnectionPool, ConnectorError, PoolStats, PooledConnection, ResilienceConfig, Unavailable};
 
 pub use cics::CicsClient;
+pub use copybook::{Copybook, Field, FieldType, Usage};
+pub use ebcdic::{CodePage, EBCDIC_SPACE};
 pub use ims::ImsClient;
-pub use mq::MqClient;
+pub use mq::{
+    MqClient, MqSession, MqMessage, MessageDescriptor, MessageId, JmsHeaders, JmsBodyType, DeadLetterHeader,
+    GetOptions, QueueManager, MqConnection, MemoryQueueManager, QueueAttributes,
+    FORMAT_STRING, FORMAT_RFH2, FORMAT_DEAD_LETTER, MQRC_BACKOUT_THRESHOLD_REACHED,
+};
 
 /// Mainframe connector configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,14 +36,43 @@ pub struct MainframeConfig {
     pub host: String,
     /// CICS port
     pub cics_port: u16,
+    /// RACF user for CICS
+    #[serde(default)]
+    pub user: Option<String>,
+    /// RACF password, resolved per connection
+    #[serde(default)]
+    pub password: Option<SecretRef>,
     /// IMS Connect port
     pub ims_port: Option<u16>,
     /// MQ Queue Manager
     pub queue_manager: Option<String>,
     /// MQ Channel
     pub mq_channel: Option<String>,
-    /// Code page (EBCDIC)
+    /// Backout threshold for queues without BOTHRESH (0 disables)
+    #[serde(default = "default_backout_threshold")]
+    pub mq_backout_threshold: u32,
+    /// Dead-letter queue for poison messages, overriding the queue manager's DEADQ
+    #[serde(default)]
+    pub mq_dead_letter_queue: Option<String>,
+    /// Queues whose depth is reported with the connector health
+    #[serde(default)]
+    pub mq_monitored_queues: Vec<String>,
+    /// Code page (EBCDIC), e.g. `IBM037` or `IBM1047`
     pub code_page: String,
+    /// Connections per subsystem (CICS, IMS, MQ)
+    #[serde(default = "default_pool_size")]
+    pub pool_size: usize,
+    /// Retry and circuit breaker settings
+    #[serde(default)]
+    pub resilience: ResilienceConfig,
+}
+
+fn default_pool_size() -> usize {
+    5
+}
+
+fn default_backout_threshold() -> u32 {
+    5
 }
 
 impl Default for MainframeConfig {
@@ -36,20 +80,33 @@ impl Default for MainframeConfig {
         Self {
             host: String::new(),
             cics_port: 1490,
+            user: None,
+            password: None,
             ims_port: Some(9999),
             queue_manager: None,
             mq_channel: None,
+            mq_backout_threshold: default_backout_threshold(),
+            mq_dead_letter_queue: None,
+            mq_monitored_queues: Vec::new(),
             code_page: "IBM037".to_string(),
+            pool_size: default_pool_size(),
+            resilience: ResilienceConfig::default(),
         }
     }
 }
 
 /// Mainframe connector.
+///
+/// Calls are async and run on the blocking pool; the `*_blocking` variants
+/// call the backend on the current thread, for CLI tools.
 pub struct MainframeConnector {
     config: MainframeConfig,
-    cics: Option<CicsClient>,
-    ims: Option<ImsClient>,
-    mq: Option<MqClient>,
+    cics: Option<Arc<ConnectionPool<CicsClient, MainframeError>>>,
+    ims: Option<Arc<ConnectionPool<ImsClient, MainframeError>>>,
+    mq: Option<Arc<ConnectionPool<MqClient, MainframeError>>>,
+    queue_manager: Option<Arc<dyn QueueManager>>,
+    call_options: CallOptions,
+    gate: Option<Arc<ConnectorGate>>,
 }
 
 impl MainframeConnector {
@@ -58,64 +115,394 @@ impl MainframeConnector {
         check_feature_license("mainframe")?;
         
         Ok(Self {
+            call_options: CallOptions::from_config(&config.resilience),
             config,
             cics: None,
             ims: None,
             mq: None,
+            queue_manager: None,
+            gate: None,
         })
     }
     
-    /// Connect to CICS.
-    pub fn connect_cics(&mut self, user: &str, password: &str) -> Result<(), MainframeError> {
-        self.cics = Some(CicsClient::connect(&self.config, user, password)?);
+    /// Use a specific MQ queue manager backend.
+    pub fn with_queue_manager(mut self, queue_manager: Arc<dyn QueueManager>) -> Self {
+        self.queue_manager = Some(queue_manager);
+        self
+    }
+    
+    /// Override timeout and cancellation of async calls.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.call_options = options;
+        self
+    }
+    
+    /// Check transactions, programs and queue operations against Gate
+    /// policies before they run.
+    pub fn with_gate(mut self, gate: Arc<ConnectorGate>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+    
+    /// Connect to CICS with the configured RACF credentials.
+    pub async fn connect_cics(&mut self) -> Result<(), MainframeError> {
+        let pool = Arc::new(self.cics_pool());
+        let warming = pool.clone();
+        self.run(move || warming.warm_up()).await?;
+        self.cics = Some(pool);
+        Ok(())
+    }
+    
+    /// Blocking [`connect_cics`](Self::connect_cics).
+    pub fn connect_cics_blocking(&mut self) -> Result<(), MainframeError> {
+        let pool = self.cics_pool();
+        pool.warm_up()?;
+        self.cics = Some(Arc::new(pool));
         Ok(())
     }
     
     /// Connect to IMS.
-    pub fn connect_ims(&mut self, datastores: Vec<String>) -> Result<(), MainframeError> {
-        if self.config.ims_port.is_none() {
-            return Err(MainframeError::ImsNotConfigured);
-        }
-        self.ims = Some(ImsClient::connect(&self.config, datastores)?);
+    pub async fn connect_ims(&mut self, datastores: Vec<String>) -> Result<(), MainframeError> {
+        let pool = Arc::new(self.ims_pool(datastores)?);
+        let warming = pool.clone();
+        self.run(move || warming.warm_up()).await?;
+        self.ims = Some(pool);
+        Ok(())
+    }
+    
+    /// Blocking [`connect_ims`](Self::connect_ims).
+    pub fn connect_ims_blocking(&mut self, datastores: Vec<String>) -> Result<(), MainframeError> {
+        let pool = self.ims_pool(datastores)?;
+        pool.warm_up()?;
+        self.ims = Some(Arc::new(pool));
         Ok(())
     }
     
     /// Connect to MQ.
-    pub fn connect_mq(&mut self) -> Result<(), MainframeError> {
-        let qm = self.config.queue_manager.as_ref()
-            .ok_or(MainframeError::MqNotConfigured)?;
-        self.mq = Some(MqClient::connect(&self.config, qm)?);
+    pub async fn connect_mq(&mut self) -> Result<(), MainframeError> {
+        let pool = Arc::new(self.mq_pool()?);
+        let warming = pool.clone();
+        self.run(move || warming.warm_up()).await?;
+        self.mq = Some(pool);
+        Ok(())
+    }
+    
+    /// Blocking [`connect_mq`](Self::connect_mq).
+    pub fn connect_mq_blocking(&mut self) -> Result<(), MainframeError> {
+        let pool = self.mq_pool()?;
+        pool.warm_up()?;
+        self.mq = Some(Arc::new(pool));
         Ok(())
     }
     
+    fn cics_pool(&self) -> ConnectionPool<CicsClient, MainframeError> {
+        let config = self.config.clone();
+        self.pool("cics", move || {
+            let (Some(user), Some(password)) = (&config.user, &config.password) else {
+                return Err(MainframeError::CicsError("No RACF credentials configured".into()));
+            };
+            // Resolved per connection so rotated passwords are picked up
+            let password = password.resolve()?;
+            CicsClient::connect(&config, user, password.expose_str()?)
+        })
+    }
+    
+    fn ims_pool(&self, datastores: Vec<String>) -> Result<ConnectionPool<ImsClient, MainframeError>, MainframeError> {
+        if self.config.ims_port.is_none() {
+            return Err(MainframeError::ImsNotConfigured);
+        }
+        let config = self.config.clone();
+        Ok(self.pool("ims", move || ImsClient::connect(&config, datastores.clone())))
+    }
+    
+    fn mq_pool(&self) -> Result<ConnectionPool<MqClient, MainframeError>, MainframeError> {
+        let queue_manager = match &self.queue_manager {
+            Some(queue_manager) => queue_manager.clone(),
+            None => {
+                let name = self.config.queue_manager.as_deref()
+                    .ok_or(MainframeError::MqNotConfigured)?;
+                // Production would MQCONNX to `name` over `mq_channel`
+                Arc::new(MemoryQueueManager::new(name)) as Arc<dyn QueueManager>
+            }
+        };
+        let config = self.config.clone();
+        Ok(self.pool("mq", move || MqClient::connect(&config, &queue_manager)))
+    }
+    
+    fn pool<C: PooledConnection>(
+        &self,
+        subsystem: &str,
+        connect: impl Fn() -> Result<C, MainframeError> + Send + Sync + 'static,
+    ) -> ConnectionPool<C, MainframeError> {
+        let service = format!("mainframe-{}-{}", subsystem, self.config.host);
+        ConnectionPool::new(&service, self.config.pool_size, &self.config.resilience, connect)
+    }
+    
+    async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> Result<T, MainframeError> + Send + 'static,
+    ) -> Result<T, MainframeError> {
+        run_blocking(&format!("mainframe-{}", self.config.host), &self.call_options, work).await
+    }
+    
+    async fn authorize(&self, operation: &str, target: Option<&str>) -> Result<(), MainframeError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize(Self::action(operation, target)).await?),
+            None => Ok(()),
+        }
+    }
+    
+    fn authorize_blocking(&self, operation: &str, target: Option<&str>) -> Result<(), MainframeError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize_blocking(Self::action(operation, target))?),
+            None => Ok(()),
+        }
+    }
+    
+    fn action(operation: &str, target: Option<&str>) -> ConnectorAction {
+        ConnectorAction { target: target.map(String::from), ..ConnectorAction::new("mainframe", operation) }
+    }
+    
+    fn cics(&self) -> Result<Arc<ConnectionPool<CicsClient, MainframeError>>, MainframeError> {
+        self.cics.clone().ok_or(MainframeError::NotConnected)
+    }
+    
+    fn ims(&self) -> Result<Arc<ConnectionPool<ImsClient, MainframeError>>, MainframeError> {
+        self.ims.clone().ok_or(MainframeError::ImsNotConfigured)
+    }
+    
+    fn mq(&self) -> Result<Arc<ConnectionPool<MqClient, MainframeError>>, MainframeError> {
+        self.mq.clone().ok_or(MainframeError::MqNotConfigured)
+    }
+    
     /// Execute CICS transaction.
-    pub fn exec_transaction(&self, tranid: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
-        let cics = self.cics.as_ref().ok_or(MainframeError::NotConnected)?;
-        cics.exec_transaction(tranid, commarea)
+    pub async fn exec_transaction(&self, tranid: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
+        self.authorize("exec_transaction", Some(tranid)).await?;
+        let cics = self.cics()?;
+        let (tranid, commarea) = (tranid.to_string(), commarea.to_vec());
+        self.run(move || cics.call(|c| c.exec_transaction(&tranid, &commarea))).await
+    }
+    
+    /// Blocking [`exec_transaction`](Self::exec_transaction).
+    pub fn exec_transaction_blocking(&self, tranid: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
+        self.authorize_blocking("exec_transaction", Some(tranid))?;
+        self.cics()?.call(|c| c.exec_transaction(tranid, commarea))
     }
     
     /// Execute CICS program.
-    pub fn link_program(&self, program: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
-        let cics = self.cics.as_ref().ok_or(MainframeError::NotConnected)?;
-        cics.link_program(program, commarea)
+    pub async fn link_program(&self, program: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
+        self.authorize("link_program", Some(program)).await?;
+        let cics = self.cics()?;
+        let (program, commarea) = (program.to_string(), commarea.to_vec());
+        self.run(move || cics.call(|c| c.link_program(&program, &commarea))).await
+    }
+    
+    /// Blocking [`link_program`](Self::link_program).
+    pub fn link_program_blocking(&self, program: &str, commarea: &[u8]) -> Result<Vec<u8>, MainframeError> {
+        self.authorize_blocking("link_program", Some(program))?;
+        self.cics()?.call(|c| c.link_program(program, commarea))
     }
     
     /// Run IMS transaction.
-    pub fn ims_transaction(&self, trancode: &str, segments: Vec<&[u8]>) -> Result<Vec<Vec<u8>>, MainframeError> {
-        let ims = self.ims.as_ref().ok_or(MainframeError::ImsNotConfigured)?;
-        ims.exec_transaction(trancode, segments)
+    pub async fn ims_transaction(&self, trancode: &str, segments: Vec<&[u8]>) -> Result<Vec<Vec<u8>>, MainframeError> {
+        self.authorize("ims_transaction", Some(trancode)).await?;
+        let ims = self.ims()?;
+        let trancode = trancode.to_string();
+        let segments: Vec<Vec<u8>> = segments.into_iter().map(<[u8]>::to_vec).collect();
+        self.run(move || {
+            let segments: Vec<&[u8]> = segments.iter().map(Vec::as_slice).collect();
+            ims.call(|c| c.exec_transaction(&trancode, segments.clone()))
+        }).await
+    }
+    
+    /// Blocking [`ims_transaction`](Self::ims_transaction).
+    pub fn ims_transaction_blocking(&self, trancode: &str, segments: Vec<&[u8]>) -> Result<Vec<Vec<u8>>, MainframeError> {
+        self.authorize_blocking("ims_transaction", Some(trancode))?;
+        self.ims()?.call(|c| c.exec_transaction(trancode, segments.clone()))
+    }
+    
+    /// Run a CICS transaction with a COMMAREA mapped through `layout`.
+    ///
+    /// The reply is decoded with the same layout, as CICS returns the
+    /// updated COMMAREA.
+    pub async fn exec_transaction_mapped<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        tranid: &str,
+        layout: &Copybook,
+        request: &Req,
+    ) -> Result<Resp, MainframeError> {
+        let code_page = self.code_page()?;
+        let reply = self.exec_transaction(tranid, &layout.encode_from(request, &code_page)?).await?;
+        layout.decode_as(&reply, &code_page)
+    }
+    
+    /// Blocking [`exec_transaction_mapped`](Self::exec_transaction_mapped).
+    pub fn exec_transaction_mapped_blocking<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        tranid: &str,
+        layout: &Copybook,
+        request: &Req,
+    ) -> Result<Resp, MainframeError> {
+        let code_page = self.code_page()?;
+        let reply = self.exec_transaction_blocking(tranid, &layout.encode_from(request, &code_page)?)?;
+        layout.decode_as(&reply, &code_page)
+    }
+    
+    /// Link to a CICS program with a COMMAREA mapped through `layout`.
+    pub async fn link_program_mapped<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        program: &str,
+        layout: &Copybook,
+        request: &Req,
+    ) -> Result<Resp, MainframeError> {
+        let code_page = self.code_page()?;
+        let reply = self.link_program(program, &layout.encode_from(request, &code_page)?).await?;
+        layout.decode_as(&reply, &code_page)
+    }
+    
+    /// Blocking [`link_program_mapped`](Self::link_program_mapped).
+    pub fn link_program_mapped_blocking<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        program: &str,
+        layout: &Copybook,
+        request: &Req,
+    ) -> Result<Resp, MainframeError> {
+        let code_page = self.code_page()?;
+        let reply = self.link_program_blocking(program, &layout.encode_from(request, &code_page)?)?;
+        layout.decode_as(&reply, &code_page)
     }
     
     /// Put message to MQ queue.
-    pub fn mq_put(&self, queue: &str, message: &[u8]) -> Result<String, MainframeError> {
-        let mq = self.mq.as_ref().ok_or(MainframeError::MqNotConfigured)?;
-        mq.put(queue, message)
+    pub async fn mq_put(&self, queue: &str, message: &[u8]) -> Result<String, MainframeError> {
+        self.authorize("mq_put", Some(queue)).await?;
+        let mq = self.mq()?;
+        let (queue, message) = (queue.to_string(), message.to_vec());
+        // Not retried: a lost reply may hide a successful put
+        self.run(move || mq.call_once(|c| c.put(&queue, &message))).await
+    }
+    
+    /// Blocking [`mq_put`](Self::mq_put).
+    pub fn mq_put_blocking(&self, queue: &str, message: &[u8]) -> Result<String, MainframeError> {
+        self.authorize_blocking("mq_put", Some(queue))?;
+        self.mq()?.call_once(|c| c.put(queue, message))
     }
     
     /// Get message from MQ queue.
-    pub fn mq_get(&self, queue: &str) -> Result<Option<Vec<u8>>, MainframeError> {
-        let mq = self.mq.as_ref().ok_or(MainframeError::MqNotConfigured)?;
-        mq.get(queue)
+    pub async fn mq_get(&self, queue: &str) -> Result<Option<Vec<u8>>, MainframeError> {
+        self.authorize("mq_get", Some(queue)).await?;
+        let mq = self.mq()?;
+        let queue = queue.to_string();
+        self.run(move || mq.call(|c| c.get(&queue))).await
+    }
+    
+    /// Blocking [`mq_get`](Self::mq_get).
+    pub fn mq_get_blocking(&self, queue: &str) -> Result<Option<Vec<u8>>, MainframeError> {
+        self.authorize_blocking("mq_get", Some(queue))?;
+        self.mq()?.call(|c| c.get(queue))
+    }
+    
+    /// Put a message (descriptor, JMS headers) outside syncpoint.
+    pub async fn mq_put_message(&self, queue: &str, message: MqMessage) -> Result<MessageId, MainframeError> {
+        self.authorize("mq_put_message", Some(queue)).await?;
+        let mq = self.mq()?;
+        let queue = queue.to_string();
+        self.run(move || mq.call_once(|c| c.put_message(&queue, &message))).await
+    }
+    
+    /// Blocking [`mq_put_message`](Self::mq_put_message).
+    pub fn mq_put_message_blocking(&self, queue: &str, message: MqMessage) -> Result<MessageId, MainframeError> {
+        self.authorize_blocking("mq_put_message", Some(queue))?;
+        self.mq()?.call_once(|c| c.put_message(queue, &message))
+    }
+    
+    /// Get the first message matching `options`.
+    pub async fn mq_get_message(&self, queue: &str, options: GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        self.authorize("mq_get_message", Some(queue)).await?;
+        let mq = self.mq()?;
+        let queue = queue.to_string();
+        self.run(move || mq.call(|c| Self::get_committed(c, &queue, &options))).await
+    }
+    
+    /// Blocking [`mq_get_message`](Self::mq_get_message).
+    pub fn mq_get_message_blocking(&self, queue: &str, options: GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        self.authorize_blocking("mq_get_message", Some(queue))?;
+        self.mq()?.call(|c| Self::get_committed(c, queue, &options))
+    }
+    
+    /// A syncpoint get outside a transaction is committed right away so the
+    /// pooled connection is returned clean.
+    fn get_committed(mq: &MqClient, queue: &str, options: &GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        if options.syncpoint {
+            mq.transaction(|session| session.get(queue, options.clone()))
+        } else {
+            mq.get_message(queue, options)
+        }
+    }
+    
+    /// Messages matching `options`, left on the queue.
+    pub async fn mq_browse(&self, queue: &str, options: GetOptions) -> Result<Vec<MqMessage>, MainframeError> {
+        self.authorize("mq_browse", Some(queue)).await?;
+        let mq = self.mq()?;
+        let queue = queue.to_string();
+        self.run(move || mq.call(|c| c.browse_messages(&queue, &options))).await
+    }
+    
+    /// Blocking [`mq_browse`](Self::mq_browse).
+    pub fn mq_browse_blocking(&self, queue: &str, options: GetOptions) -> Result<Vec<MqMessage>, MainframeError> {
+        self.authorize_blocking("mq_browse", Some(queue))?;
+        self.mq()?.call(|c| c.browse_messages(queue, &options))
+    }
+    
+    /// Run `work` as one MQ unit of work on a single connection.
+    ///
+    /// Committed if `work` succeeds, backed out otherwise; never retried.
+    /// Gated once, as `mq_transaction`, not per put or get.
+    pub async fn mq_transaction<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&MqSession<'_>) -> Result<T, MainframeError> + Send + 'static,
+    ) -> Result<T, MainframeError> {
+        self.authorize("mq_transaction", None).await?;
+        let mq = self.mq()?;
+        self.run(move || mq.call_once(|c| c.transaction(work))).await
+    }
+    
+    /// Blocking [`mq_transaction`](Self::mq_transaction).
+    pub fn mq_transaction_blocking<T>(
+        &self,
+        work: impl FnOnce(&MqSession<'_>) -> Result<T, MainframeError>,
+    ) -> Result<T, MainframeError> {
+        self.authorize_blocking("mq_transaction", None)?;
+        self.mq()?.call_once(|c| c.transaction(work))
+    }
+    
+    /// Configured EBCDIC code page.
+    pub fn code_page(&self) -> Result<CodePage, MainframeError> {
+        CodePage::from_name(&self.config.code_page)
+    }
+    
+    /// Convert UTF-8 text to the configured code page.
+    pub fn encode_text(&self, text: &str) -> Result<Vec<u8>, MainframeError> {
+        self.code_page()?.encode(text)
+    }
+    
+    /// Convert EBCDIC bytes in the configured code page to UTF-8.
+    pub fn decode_text(&self, bytes: &[u8]) -> Result<String, MainframeError> {
+        Ok(self.code_page()?.decode(bytes))
+    }
+    
+    /// Pool usage per connected subsystem.
+    pub fn pool_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        let mut stats = Vec::new();
+        if let Some(pool) = &self.cics {
+            stats.push(("cics", pool.stats()));
+        }
+        if let Some(pool) = &self.ims {
+            stats.push(("ims", pool.stats()));
+        }
+        if let Some(pool) = &self.mq {
+            stats.push(("mq", pool.stats()));
+        }
+        stats
     }
     
     /// Health check.
@@ -128,6 +515,24 @@ impl MainframeConnector {
     }
 }
 
+impl HealthSource for MainframeConnector {
+    fn health(&self) -> ConnectorHealth {
+        let mut health = ConnectorHealth::new(&format!("mainframe-{}", self.config.host));
+        for (subsystem, stats) in self.pool_stats() {
+            health = health.with_component(ComponentHealth::from_pool(subsystem, &stats));
+        }
+        if let Some(pool) = &self.mq {
+            for queue in &self.config.mq_monitored_queues {
+                // A failed inquiry shows in the MQ circuit instead
+                if let Ok(depth) = pool.call(|mq| mq.depth(queue)) {
+                    health = health.with_queue_depth(queue, depth);
+                }
+            }
+        }
+        health
+    }
+}
+
 /// Mainframe health status.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MainframeHealth {
@@ -160,10 +565,41 @@ pub enum MainframeError {
     #[error("Encoding error: {0}")]
     EncodingError(String),
     
+    /// Connection to the gateway or queue manager failed or was lost
+    #[error("Connection error: {0}")]
+    ConnectionError(String),
+    
+    #[error("{0} unavailable: {1}")]
+    Unavailable(String, Unavailable),
+    
+    #[error("Gate: {0}")]
+    Gated(#[from] GateRejection),
+    
+    #[error("Secret error: {0}")]
+    Secret(#[from] SecretError),
+    
     #[error("License error: {0}")]
     LicenseError(#[from] LicenseError),
 }
 
+impl ConnectorError for MainframeError {
+    fn is_transient(&self) -> bool {
+        matches!(self, MainframeError::ConnectionError(_))
+    }
+
+    fn unavailable(service: &str, reason: Unavailable) -> Self {
+        MainframeError::Unavailable(service.to_string(), reason)
+    }
+
+    fn class(&self) -> ErrorClass {
+        match self {
+            MainframeError::Unavailable(..) => ErrorClass::Unavailable,
+            e if e.is_transient() => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
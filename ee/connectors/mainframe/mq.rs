@@ -1,51 +0,0 @@
-//! MQ Client - IBM MQ
-//!
-//! Message queue integration
-
-use super::{MainframeConfig, MainframeError};
-
-/// IBM MQ client.
-pub struct MqClient {
-    queue_manager: String,
-    config: MainframeConfig,
-}
-
-impl MqClient {
-    /// Connect to MQ Queue Manager.
-    pub fn connect(config: &MainframeConfig, queue_manager: &str) -> Result<Self, MainframeError> {
-        Ok(Self {
-            queue_manager: queue_manager.to_string(),
-            config: config.clone(),
-        })
-    }
-    
-    /// Put message to queue.
-    pub fn put(&self, queue: &str, message: &[u8]) -> Result<String, MainframeError> {
-        // Production would use MQ client (pymqi or similar)
-        let msg_id = uuid::Uuid::new_v4().to_string();
-        Ok(msg_id)
-    }
-    
-    /// Get message from queue.
-    pub fn get(&self, queue: &str) -> Result<Option<Vec<u8>>, MainframeError> {
-        // Production would MQGET
-        Ok(None)
-    }
-    
-    /// Browse queue (peek without removing).
-    pub fn browse(&self, queue: &str) -> Result<Vec<Vec<u8>>, MainframeError> {
-        // Production would MQGET with MQGMO_BROWSE_FIRST/NEXT
-        Ok(vec![])
-    }
-    
-    /// Get queue depth.
-    pub fn depth(&self, queue: &str) -> Result<u64, MainframeError> {
-        // Production would inquire CURDEPTH
-        Ok(0)
-    }
-    
-    /// Get queue manager name.
-    pub fn queue_manager(&self) -> &str {
-        &self.queue_manager
-    }
-}
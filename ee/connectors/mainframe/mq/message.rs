@@ -0,0 +1,473 @@
+//! MQ messages: descriptor, JMS (MQRFH2) headers and dead-letter header
+//!
+//! Messages from JMS applications carry an MQRFH2 header in front of the
+//! payload with `<mcd>`, `<jms>` and `<usr>` folders. Messages on a
+//! dead-letter queue are prefixed with an MQDLH naming the original
+//! destination and why delivery failed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::super::MainframeError;
+
+/// Payload is character data.
+pub const FORMAT_STRING: &str = "MQSTR";
+/// Payload starts with an MQRFH2 header.
+pub const FORMAT_RFH2: &str = "MQHRF2";
+/// Payload starts with an MQDLH header.
+pub const FORMAT_DEAD_LETTER: &str = "MQDEAD";
+
+/// Dead-letter reason for messages over the backout threshold.
+pub const MQRC_BACKOUT_THRESHOLD_REACHED: i32 = 2362;
+
+/// UTF-8, for RFH2 folders and text payloads.
+const CCSID_UTF8: i32 = 1208;
+/// Big-endian integers, as on z/OS.
+const ENCODING_BIG_ENDIAN: i32 = 273;
+const RFH2_FIXED_LEN: usize = 36;
+const DLH_LEN: usize = 172;
+
+/// 24-byte MQ message or correlation identifier.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MessageId(#[serde(with = "hex_id")] [u8; 24]);
+
+impl MessageId {
+    /// New unique identifier.
+    pub fn new() -> Self {
+        let mut id = [0u8; 24];
+        id[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+        id[16..].copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..8]);
+        Self(id)
+    }
+
+    pub fn from_bytes(bytes: [u8; 24]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse `ID:<48 hex digits>` (JMS form) or bare hex; any other text is
+    /// used as bytes, zero-padded, as JMS does for application correlation IDs.
+    pub fn parse(text: &str) -> Self {
+        let hex = text.strip_prefix("ID:").unwrap_or(text);
+        if let Some(bytes) = decode_hex(hex) {
+            return Self(bytes);
+        }
+        let mut id = [0u8; 24];
+        let len = text.len().min(24);
+        id[..len].copy_from_slice(&text.as_bytes()[..len]);
+        Self(id)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 24] {
+        &self.0
+    }
+}
+
+impl Default for MessageId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+impl fmt::Debug for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ID:{}", self)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; 24]> {
+    if hex.len() != 48 || !hex.is_ascii() {
+        return None;
+    }
+    let mut id = [0u8; 24];
+    for (i, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(id)
+}
+
+mod hex_id {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(id: &[u8; 24], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&super::MessageId(*id))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 24], D::Error> {
+        let text = String::deserialize(deserializer)?;
+        super::decode_hex(text.strip_prefix("ID:").unwrap_or(&text))
+            .ok_or_else(|| D::Error::custom(format!("invalid message id {}", text)))
+    }
+}
+
+/// MQMD fields the connector reads and sets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageDescriptor {
+    /// Assigned on put when empty
+    pub message_id: Option<MessageId>,
+    pub correlation_id: Option<MessageId>,
+    /// Format of the payload, e.g. `MQSTR`
+    pub format: String,
+    pub persistent: bool,
+    /// 0 (lowest) to 9
+    pub priority: u8,
+    pub reply_to_queue: Option<String>,
+    /// Times the message was backed out under syncpoint
+    pub backout_count: u32,
+    pub put_time: Option<DateTime<Utc>>,
+}
+
+impl Default for MessageDescriptor {
+    fn default() -> Self {
+        Self {
+            message_id: None,
+            correlation_id: None,
+            format: FORMAT_STRING.to_string(),
+            persistent: true,
+            priority: 4,
+            reply_to_queue: None,
+            backout_count: 0,
+            put_time: None,
+        }
+    }
+}
+
+/// Body type announced in the `<mcd>` folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JmsBodyType {
+    #[default]
+    Text,
+    Bytes,
+}
+
+/// JMS headers and user properties carried in MQRFH2.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JmsHeaders {
+    pub body_type: JmsBodyType,
+    /// JMSDestination, e.g. `queue:///PAYMENTS.IN`
+    pub destination: Option<String>,
+    pub reply_to: Option<String>,
+    pub correlation_id: Option<String>,
+    /// JMSTimestamp, ms since the epoch
+    pub timestamp: Option<i64>,
+    /// JMSExpiration, ms since the epoch
+    pub expiration: Option<i64>,
+    pub priority: Option<u8>,
+    /// 1 non-persistent, 2 persistent
+    pub delivery_mode: Option<u8>,
+    /// `<usr>` folder; names must be valid XML element names
+    pub properties: BTreeMap<String, String>,
+}
+
+impl JmsHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_property(mut self, name: &str, value: &str) -> Self {
+        self.properties.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    fn folders(&self) -> Vec<String> {
+        let mut folders = vec![format!("<mcd><Msd>{}</Msd></mcd>", match self.body_type {
+            JmsBodyType::Text => "jms_text",
+            JmsBodyType::Bytes => "jms_bytes",
+        })];
+
+        let mut jms = String::new();
+        let mut element = |tag: &str, value: Option<String>| {
+            if let Some(value) = value {
+                jms.push_str(&format!("<{0}>{1}</{0}>", tag, escape(&value)));
+            }
+        };
+        element("Dst", self.destination.clone());
+        element("Rto", self.reply_to.clone());
+        element("Cid", self.correlation_id.clone());
+        element("Tms", self.timestamp.map(|t| t.to_string()));
+        element("Exp", self.expiration.map(|t| t.to_string()));
+        element("Pri", self.priority.map(|p| p.to_string()));
+        element("Dlv", self.delivery_mode.map(|d| d.to_string()));
+        if !jms.is_empty() {
+            folders.push(format!("<jms>{}</jms>", jms));
+        }
+
+        if !self.properties.is_empty() {
+            let usr: String = self.properties.iter()
+                .map(|(name, value)| format!("<{0}>{1}</{0}>", name, escape(value)))
+                .collect();
+            folders.push(format!("<usr>{}</usr>", usr));
+        }
+        folders
+    }
+
+    fn read_folder(&mut self, xml: &str) -> Result<(), MainframeError> {
+        let doc = roxmltree::Document::parse(xml)
+            .map_err(|e| MainframeError::MqError(format!("Invalid RFH2 folder: {}", e)))?;
+        let root = doc.root_element();
+        for child in root.children().filter(|n| n.is_element()) {
+            let name = child.tag_name().name();
+            let value = child.text().unwrap_or_default().to_string();
+            match (root.tag_name().name(), name) {
+                ("mcd", "Msd") if value == "jms_bytes" => self.body_type = JmsBodyType::Bytes,
+                ("jms", "Dst") => self.destination = Some(value),
+                ("jms", "Rto") => self.reply_to = Some(value),
+                ("jms", "Cid") => self.correlation_id = Some(value),
+                ("jms", "Tms") => self.timestamp = value.parse().ok(),
+                ("jms", "Exp") => self.expiration = value.parse().ok(),
+                ("jms", "Pri") => self.priority = value.parse().ok(),
+                ("jms", "Dlv") => self.delivery_mode = value.parse().ok(),
+                ("usr", _) => {
+                    self.properties.insert(name.to_string(), value);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Message with descriptor and, for JMS peers, RFH2 headers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MqMessage {
+    pub descriptor: MessageDescriptor,
+    pub jms: Option<JmsHeaders>,
+    pub payload: Vec<u8>,
+}
+
+impl MqMessage {
+    pub fn new(payload: impl Into<Vec<u8>>) -> Self {
+        Self { payload: payload.into(), ..Self::default() }
+    }
+
+    pub fn with_correlation_id(mut self, id: MessageId) -> Self {
+        self.descriptor.correlation_id = Some(id);
+        self
+    }
+
+    pub fn with_reply_to(mut self, queue: &str) -> Self {
+        self.descriptor.reply_to_queue = Some(queue.to_string());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.descriptor.priority = priority.min(9);
+        self
+    }
+
+    pub fn non_persistent(mut self) -> Self {
+        self.descriptor.persistent = false;
+        self
+    }
+
+    /// Send with an MQRFH2 header for JMS consumers.
+    pub fn with_jms(mut self, headers: JmsHeaders) -> Self {
+        self.jms = Some(headers);
+        self
+    }
+
+    pub fn message_id(&self) -> Option<MessageId> {
+        self.descriptor.message_id
+    }
+
+    /// Payload as text, if it is UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
+    }
+
+    /// Wire form: JMS headers folded into an MQRFH2 prefix.
+    pub(super) fn to_wire(&self) -> MqMessage {
+        let Some(jms) = &self.jms else { return self.clone() };
+        let mut folders = Vec::new();
+        for folder in jms.folders() {
+            let mut data = folder.into_bytes();
+            // Folder lengths are multiples of 4, padded with blanks
+            data.resize(data.len().div_ceil(4) * 4, b' ');
+            folders.extend_from_slice(&(data.len() as i32).to_be_bytes());
+            folders.extend_from_slice(&data);
+        }
+
+        let mut payload = Vec::with_capacity(RFH2_FIXED_LEN + folders.len() + self.payload.len());
+        payload.extend_from_slice(b"RFH ");
+        payload.extend_from_slice(&2i32.to_be_bytes());
+        payload.extend_from_slice(&((RFH2_FIXED_LEN + folders.len()) as i32).to_be_bytes());
+        payload.extend_from_slice(&ENCODING_BIG_ENDIAN.to_be_bytes());
+        payload.extend_from_slice(&CCSID_UTF8.to_be_bytes());
+        payload.extend_from_slice(&fixed(&self.descriptor.format, 8));
+        payload.extend_from_slice(&0i32.to_be_bytes());
+        payload.extend_from_slice(&CCSID_UTF8.to_be_bytes());
+        payload.extend_from_slice(&folders);
+        payload.extend_from_slice(&self.payload);
+
+        MqMessage {
+            descriptor: MessageDescriptor { format: FORMAT_RFH2.to_string(), ..self.descriptor.clone() },
+            jms: None,
+            payload,
+        }
+    }
+
+    /// Message as received: an MQRFH2 prefix is parsed into `jms`.
+    pub(super) fn from_wire(mut message: MqMessage) -> Result<MqMessage, MainframeError> {
+        if message.descriptor.format.trim_end() != FORMAT_RFH2 {
+            return Ok(message);
+        }
+        let invalid = |msg: &str| MainframeError::MqError(format!("Invalid RFH2 header: {}", msg));
+        let data = &message.payload;
+        if data.len() < RFH2_FIXED_LEN || &data[..4] != b"RFH " {
+            return Err(invalid("missing RFH2 structure"));
+        }
+        // Integer byte order follows the sender; the version (2) tells which
+        let big_endian = data[4..8] == 2i32.to_be_bytes();
+        let int = |at: usize| {
+            let bytes = [data[at], data[at + 1], data[at + 2], data[at + 3]];
+            if big_endian { i32::from_be_bytes(bytes) } else { i32::from_le_bytes(bytes) }
+        };
+        let length = usize::try_from(int(8)).map_err(|_| invalid("negative length"))?;
+        if length < RFH2_FIXED_LEN || length > data.len() {
+            return Err(invalid("length out of range"));
+        }
+        let format = String::from_utf8_lossy(&data[20..28]).trim_end().to_string();
+
+        let mut jms = JmsHeaders::default();
+        let mut at = RFH2_FIXED_LEN;
+        while at + 4 <= length {
+            let size = usize::try_from(int(at)).map_err(|_| invalid("negative folder length"))?;
+            let end = at + 4 + size;
+            if end > length {
+                return Err(invalid("folder overruns header"));
+            }
+            let folder = std::str::from_utf8(&data[at + 4..end]).map_err(|_| invalid("folder is not UTF-8"))?;
+            jms.read_folder(folder.trim_end_matches([' ', '\0']))?;
+            at = end;
+        }
+
+        message.payload.drain(..length);
+        message.descriptor.format = format;
+        message.jms = Some(jms);
+        Ok(message)
+    }
+}
+
+/// MQDLH prefixed to messages on a dead-letter queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetterHeader {
+    /// MQRC or MQFB code
+    pub reason: i32,
+    pub destination_queue: String,
+    pub destination_queue_manager: String,
+    /// Format of the original payload
+    pub format: String,
+    pub put_application: String,
+    pub put_time: DateTime<Utc>,
+}
+
+impl DeadLetterHeader {
+    /// Dead-letter message wrapping `message` (already in wire form).
+    pub fn wrap(&self, message: &MqMessage) -> MqMessage {
+        let mut payload = Vec::with_capacity(DLH_LEN + message.payload.len());
+        payload.extend_from_slice(b"DLH ");
+        payload.extend_from_slice(&1i32.to_be_bytes());
+        payload.extend_from_slice(&self.reason.to_be_bytes());
+        payload.extend_from_slice(&fixed(&self.destination_queue, 48));
+        payload.extend_from_slice(&fixed(&self.destination_queue_manager, 48));
+        payload.extend_from_slice(&ENCODING_BIG_ENDIAN.to_be_bytes());
+        payload.extend_from_slice(&CCSID_UTF8.to_be_bytes());
+        payload.extend_from_slice(&fixed(&self.format, 8));
+        // PutApplType 28: user application
+        payload.extend_from_slice(&28i32.to_be_bytes());
+        payload.extend_from_slice(&fixed(&self.put_application, 28));
+        payload.extend_from_slice(self.put_time.format("%Y%m%d").to_string().as_bytes());
+        let hundredths = self.put_time.timestamp_subsec_millis() / 10;
+        payload.extend_from_slice(format!("{}{:02}", self.put_time.format("%H%M%S"), hundredths).as_bytes());
+        payload.extend_from_slice(&message.payload);
+
+        MqMessage {
+            descriptor: MessageDescriptor { format: FORMAT_DEAD_LETTER.to_string(), ..message.descriptor.clone() },
+            jms: None,
+            payload,
+        }
+    }
+
+    /// Split a dead-letter message into header and original message.
+    pub fn unwrap(message: &MqMessage) -> Result<(DeadLetterHeader, MqMessage), MainframeError> {
+        let data = &message.payload;
+        if data.len() < DLH_LEN || &data[..4] != b"DLH " {
+            return Err(MainframeError::MqError("Invalid dead-letter header".into()));
+        }
+        let int = |at: usize| i32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let text = |from: usize, len: usize| String::from_utf8_lossy(&data[from..from + len]).trim_end().to_string();
+        // PutDate YYYYMMDD, PutTime HHMMSSTH (hundredths)
+        let put_time = chrono::NaiveDateTime::parse_from_str(&format!("{}{}0", text(156, 8), text(164, 8)), "%Y%m%d%H%M%S%3f")
+            .map(|t| t.and_utc())
+            .unwrap_or_default();
+
+        let header = DeadLetterHeader {
+            reason: int(8),
+            destination_queue: text(12, 48),
+            destination_queue_manager: text(60, 48),
+            format: text(116, 8),
+            put_application: text(128, 28),
+            put_time,
+        };
+        let original = MqMessage {
+            descriptor: MessageDescriptor { format: header.format.clone(), ..message.descriptor.clone() },
+            jms: None,
+            payload: data[DLH_LEN..].to_vec(),
+        };
+        Ok((header, original))
+    }
+}
+
+/// Blank-padded fixed-width character field.
+fn fixed(text: &str, len: usize) -> Vec<u8> {
+    let mut field: Vec<u8> = text.bytes().take(len).collect();
+    field.resize(len, b' ');
+    field
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfh2_and_dead_letter_roundtrip() {
+        let message = MqMessage::new("{\"amount\":100}")
+            .with_correlation_id(MessageId::parse("PAY-001"))
+            .with_jms(JmsHeaders {
+                destination: Some("queue:///PAYMENTS.IN".into()),
+                priority: Some(7),
+                ..JmsHeaders::new().with_property("tenant", "acme & co")
+            });
+
+        let wire = message.to_wire();
+        assert_eq!(wire.descriptor.format, FORMAT_RFH2);
+        assert_eq!(&wire.payload[..4], b"RFH ");
+        assert_eq!(MqMessage::from_wire(wire.clone()).unwrap(), message);
+
+        let header = DeadLetterHeader {
+            reason: MQRC_BACKOUT_THRESHOLD_REACHED,
+            destination_queue: "PAYMENTS.IN".into(),
+            destination_queue_manager: "QM1".into(),
+            format: wire.descriptor.format.clone(),
+            put_application: "agentkern".into(),
+            put_time: Utc::now(),
+        };
+        let (unwrapped, original) = DeadLetterHeader::unwrap(&header.wrap(&wire)).unwrap();
+        assert_eq!((unwrapped.reason, unwrapped.destination_queue.as_str()), (2362, "PAYMENTS.IN"));
+        assert_eq!(original, wire);
+
+        let id = MessageId::new();
+        assert_eq!(MessageId::parse(&format!("ID:{}", id)), id);
+    }
+}
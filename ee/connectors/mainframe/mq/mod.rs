@@ -0,0 +1,271 @@
+//! MQ Client - IBM MQ
+//!
+//! Message queue integration:
+//! - Transacted sessions: gets and puts under syncpoint, committed or
+//!   backed out together
+//! - Poison messages: once a message's backout count reaches the queue's
+//!   BOTHRESH (or `mq_backout_threshold`) it moves to BOQNAME, else to the
+//!   dead-letter queue with an MQDLH, instead of being redelivered
+//! - Gets and browses filtered by message or correlation ID
+//! - JMS headers and user properties via MQRFH2
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! mq.transaction(|session| {
+//!     let Some(request) = session.get("PAYMENTS.IN", GetOptions::new())? else { return Ok(()) };
+//!     let reply = MqMessage::new(process(&request.payload)?)
+//!         .with_correlation_id(request.message_id().unwrap());
+//!     session.put("PAYMENTS.OUT", &reply)?;
+//!     Ok(())
+//! })?;
+//! ```
+
+mod message;
+mod queue_manager;
+
+pub use message::{
+    MqMessage, MessageDescriptor, MessageId, JmsHeaders, JmsBodyType, DeadLetterHeader,
+    FORMAT_STRING, FORMAT_RFH2, FORMAT_DEAD_LETTER, MQRC_BACKOUT_THRESHOLD_REACHED,
+};
+pub use queue_manager::{QueueManager, MqConnection, MemoryQueueManager, GetOptions, QueueAttributes};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::{MainframeConfig, MainframeError};
+use super::super::resilience::PooledConnection;
+
+/// Put application name recorded in dead-letter headers.
+const PUT_APPLICATION: &str = "agentkern";
+
+/// IBM MQ client.
+pub struct MqClient {
+    queue_manager: String,
+    config: MainframeConfig,
+    conn: Box<dyn MqConnection>,
+    /// Syncpoint work since the last commit or backout
+    in_transaction: AtomicBool,
+}
+
+impl MqClient {
+    /// Connect to MQ Queue Manager.
+    pub fn connect(config: &MainframeConfig, queue_manager: &Arc<dyn QueueManager>) -> Result<Self, MainframeError> {
+        Ok(Self {
+            queue_manager: queue_manager.name().to_string(),
+            config: config.clone(),
+            conn: queue_manager.connect()?,
+            in_transaction: AtomicBool::new(false),
+        })
+    }
+
+    /// Put message to queue.
+    pub fn put(&self, queue: &str, message: &[u8]) -> Result<String, MainframeError> {
+        Ok(self.put_message(queue, &MqMessage::new(message))?.to_string())
+    }
+
+    /// Get message from queue.
+    pub fn get(&self, queue: &str) -> Result<Option<Vec<u8>>, MainframeError> {
+        Ok(self.get_message(queue, &GetOptions::new())?.map(|m| m.payload))
+    }
+
+    /// Browse queue (peek without removing).
+    pub fn browse(&self, queue: &str) -> Result<Vec<Vec<u8>>, MainframeError> {
+        Ok(self.browse_messages(queue, &GetOptions::new())?.into_iter().map(|m| m.payload).collect())
+    }
+
+    /// Get queue depth.
+    pub fn depth(&self, queue: &str) -> Result<u64, MainframeError> {
+        Ok(self.conn.attributes(queue)?.depth)
+    }
+
+    /// Get queue manager name.
+    pub fn queue_manager(&self) -> &str {
+        &self.queue_manager
+    }
+
+    /// Put outside syncpoint; returns the message ID.
+    pub fn put_message(&self, queue: &str, message: &MqMessage) -> Result<MessageId, MainframeError> {
+        self.put_wire(queue, message, false)
+    }
+
+    fn put_wire(&self, queue: &str, message: &MqMessage, syncpoint: bool) -> Result<MessageId, MainframeError> {
+        let mut wire = message.to_wire();
+        let id = *wire.descriptor.message_id.get_or_insert_with(MessageId::new);
+        wire.descriptor.put_time = Some(chrono::Utc::now());
+        self.conn.put(queue, &wire, syncpoint)?;
+        if syncpoint {
+            self.in_transaction.store(true, Ordering::SeqCst);
+        }
+        Ok(id)
+    }
+
+    /// Get the first matching message.
+    ///
+    /// Under syncpoint, poison messages are requeued and skipped. When no
+    /// other work is pending the move is committed at once; otherwise it
+    /// is part of the current unit of work.
+    pub fn get_message(&self, queue: &str, options: &GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        let had_work = self.in_transaction.load(Ordering::SeqCst);
+        loop {
+            let Some(message) = self.conn.get(queue, options)? else {
+                return Ok(None);
+            };
+            if !options.syncpoint {
+                return MqMessage::from_wire(message).map(Some);
+            }
+            self.in_transaction.store(true, Ordering::SeqCst);
+
+            let attributes = self.conn.attributes(queue)?;
+            let threshold = match attributes.backout_threshold {
+                0 => self.config.mq_backout_threshold,
+                queue_threshold => queue_threshold,
+            };
+            if threshold == 0 || message.descriptor.backout_count < threshold {
+                return MqMessage::from_wire(message).map(Some);
+            }
+            self.requeue_poison(queue, &message, attributes.backout_requeue_queue.as_deref())?;
+            if !had_work {
+                self.commit()?;
+            }
+        }
+    }
+
+    /// Backout queue gets the message unchanged, the dead-letter queue
+    /// with an MQDLH in front.
+    fn requeue_poison(&self, queue: &str, message: &MqMessage, backout_queue: Option<&str>) -> Result<(), MainframeError> {
+        if let Some(target) = backout_queue {
+            return self.conn.put(target, message, true);
+        }
+        let dead_letter_queue = self.config.mq_dead_letter_queue.clone()
+            .or_else(|| self.conn.dead_letter_queue())
+            .ok_or_else(|| MainframeError::MqError(format!(
+                "Poison message {:?} on {}: no backout or dead-letter queue",
+                message.descriptor.message_id, queue)))?;
+        let header = DeadLetterHeader {
+            reason: MQRC_BACKOUT_THRESHOLD_REACHED,
+            destination_queue: queue.to_string(),
+            destination_queue_manager: self.queue_manager.clone(),
+            format: message.descriptor.format.clone(),
+            put_application: PUT_APPLICATION.to_string(),
+            put_time: chrono::Utc::now(),
+        };
+        self.conn.put(&dead_letter_queue, &header.wrap(message), true)
+    }
+
+    /// Matching messages without removing them.
+    pub fn browse_messages(&self, queue: &str, options: &GetOptions) -> Result<Vec<MqMessage>, MainframeError> {
+        self.conn.browse(queue, options)?
+            .into_iter()
+            .map(MqMessage::from_wire)
+            .collect()
+    }
+
+    /// Commit syncpoint work.
+    pub fn commit(&self) -> Result<(), MainframeError> {
+        self.conn.commit()?;
+        self.in_transaction.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Back out syncpoint work.
+    pub fn backout(&self) -> Result<(), MainframeError> {
+        self.conn.backout()?;
+        self.in_transaction.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Run `work` as one unit of work: committed if it returns `Ok`,
+    /// backed out if it fails or panics.
+    pub fn transaction<T>(&self, work: impl FnOnce(&MqSession<'_>) -> Result<T, MainframeError>) -> Result<T, MainframeError> {
+        struct Rollback<'a>(&'a MqClient);
+        impl Drop for Rollback<'_> {
+            fn drop(&mut self) {
+                let _ = self.0.backout();
+            }
+        }
+
+        let rollback = Rollback(self);
+        let value = work(&MqSession { client: self })?;
+        self.commit()?;
+        std::mem::forget(rollback);
+        Ok(value)
+    }
+}
+
+impl PooledConnection for MqClient {
+    fn is_healthy(&self) -> bool {
+        !self.in_transaction.load(Ordering::SeqCst) && self.conn.is_healthy()
+    }
+}
+
+/// Unit of work on one connection; see [`MqClient::transaction`].
+pub struct MqSession<'a> {
+    client: &'a MqClient,
+}
+
+impl MqSession<'_> {
+    /// Put under syncpoint; visible to others after commit.
+    pub fn put(&self, queue: &str, message: &MqMessage) -> Result<MessageId, MainframeError> {
+        self.client.put_wire(queue, message, true)
+    }
+
+    /// Get under syncpoint; restored to the queue on backout.
+    pub fn get(&self, queue: &str, options: GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        self.client.get_message(queue, &options.with_syncpoint())
+    }
+
+    pub fn browse(&self, queue: &str, options: &GetOptions) -> Result<Vec<MqMessage>, MainframeError> {
+        self.client.browse_messages(queue, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(qm: &Arc<dyn QueueManager>) -> MqClient {
+        let config = MainframeConfig { mq_backout_threshold: 2, ..MainframeConfig::default() };
+        MqClient::connect(&config, qm).unwrap()
+    }
+
+    #[test]
+    fn test_transactions_and_poison_messages() {
+        let memory = Arc::new(MemoryQueueManager::new("QM1"));
+        let qm: Arc<dyn QueueManager> = memory.clone();
+        let (producer, consumer) = (client(&qm), client(&qm));
+
+        let reply_to = MessageId::parse("REQ-42");
+        producer.put_message("PAY.IN", &MqMessage::new("first")).unwrap();
+        producer.put_message("PAY.IN", &MqMessage::new("second").with_correlation_id(reply_to)).unwrap();
+
+        // Correlation filter and browse leave other messages alone
+        let by_correlation = GetOptions::new().matching_correlation_id(reply_to);
+        assert_eq!(consumer.browse_messages("PAY.IN", &by_correlation).unwrap()[0].text(), Some("second"));
+        assert_eq!(consumer.depth("PAY.IN").unwrap(), 2);
+
+        // Failed processing puts nothing and returns the message
+        let failed: Result<(), _> = consumer.transaction(|session| {
+            session.get("PAY.IN", GetOptions::new())?;
+            session.put("PAY.OUT", &MqMessage::new("reply"))?;
+            Err(MainframeError::MqError("processing failed".into()))
+        });
+        assert!(failed.is_err());
+        assert!(memory.messages("PAY.OUT").is_empty());
+        assert_eq!(memory.messages("PAY.IN")[0].descriptor.backout_count, 1);
+
+        let _ = consumer.transaction(|session| {
+            session.get("PAY.IN", GetOptions::new())?;
+            Err::<(), _>(MainframeError::MqError("processing failed".into()))
+        });
+
+        // Over the threshold the message goes to the dead-letter queue
+        let next = consumer.transaction(|session| session.get("PAY.IN", GetOptions::new())).unwrap().unwrap();
+        assert_eq!(next.text(), Some("second"));
+        let dead = memory.messages("SYSTEM.DEAD.LETTER.QUEUE");
+        let (header, original) = DeadLetterHeader::unwrap(&dead[0]).unwrap();
+        assert_eq!((header.reason, header.destination_queue.as_str()), (MQRC_BACKOUT_THRESHOLD_REACHED, "PAY.IN"));
+        assert_eq!(original.payload, b"first");
+        assert_eq!(consumer.depth("PAY.IN").unwrap(), 0);
+    }
+}
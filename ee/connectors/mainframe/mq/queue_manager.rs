@@ -0,0 +1,270 @@
+//! MQI backend
+//!
+//! `QueueManager` opens connections (MQCONNX); `MqConnection` is the MQI
+//! surface the client builds on: MQPUT/MQGET under or outside syncpoint,
+//! browse, MQCMIT/MQBACK and MQINQ of queue attributes.
+//! `MemoryQueueManager` implements the same semantics in-process.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::message::{MessageId, MqMessage};
+use super::super::MainframeError;
+
+/// Options of a get or browse.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GetOptions {
+    /// Get under syncpoint (MQGMO_SYNCPOINT); ignored by browse
+    pub syncpoint: bool,
+    /// Wait for a matching message (MQGMO_WAIT); ignored by browse
+    pub wait: Option<Duration>,
+    /// Only the message with this ID (MQMO_MATCH_MSG_ID)
+    pub message_id: Option<MessageId>,
+    /// Only messages with this correlation ID (MQMO_MATCH_CORREL_ID)
+    pub correlation_id: Option<MessageId>,
+}
+
+impl GetOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_syncpoint(mut self) -> Self {
+        self.syncpoint = true;
+        self
+    }
+
+    pub fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    pub fn matching_message_id(mut self, id: MessageId) -> Self {
+        self.message_id = Some(id);
+        self
+    }
+
+    pub fn matching_correlation_id(mut self, id: MessageId) -> Self {
+        self.correlation_id = Some(id);
+        self
+    }
+
+    /// Whether a message satisfies the match options.
+    pub fn matches(&self, message: &MqMessage) -> bool {
+        self.message_id.is_none_or(|id| message.descriptor.message_id == Some(id))
+            && self.correlation_id.is_none_or(|id| message.descriptor.correlation_id == Some(id))
+    }
+}
+
+/// Queue attributes relevant to poison message handling.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueueAttributes {
+    /// BOTHRESH; 0 when not set
+    pub backout_threshold: u32,
+    /// BOQNAME
+    pub backout_requeue_queue: Option<String>,
+    /// CURDEPTH
+    pub depth: u64,
+}
+
+/// Queue manager the client connects to.
+pub trait QueueManager: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Open a connection with its own unit of work.
+    fn connect(&self) -> Result<Box<dyn MqConnection>, MainframeError>;
+}
+
+/// One MQI connection.
+///
+/// Messages are in wire form; JMS headers travel inside the payload.
+pub trait MqConnection: Send {
+    /// Put a message; the descriptor's message ID is already set.
+    fn put(&self, queue: &str, message: &MqMessage, syncpoint: bool) -> Result<(), MainframeError>;
+
+    /// Remove the first matching message.
+    fn get(&self, queue: &str, options: &GetOptions) -> Result<Option<MqMessage>, MainframeError>;
+
+    /// Matching messages in queue order, without removing them.
+    fn browse(&self, queue: &str, options: &GetOptions) -> Result<Vec<MqMessage>, MainframeError>;
+
+    /// Commit the unit of work.
+    fn commit(&self) -> Result<(), MainframeError>;
+
+    /// Back out the unit of work; got messages return with their backout
+    /// count incremented.
+    fn backout(&self) -> Result<(), MainframeError>;
+
+    fn attributes(&self, queue: &str) -> Result<QueueAttributes, MainframeError>;
+
+    /// Queue manager's dead-letter queue (DEADQ), if defined.
+    fn dead_letter_queue(&self) -> Option<String>;
+
+    fn is_healthy(&self) -> bool {
+        true
+    }
+}
+
+/// In-process queue manager.
+///
+/// Queues are created on first use. Used when no MQ client library is
+/// linked, and in tests.
+pub struct MemoryQueueManager {
+    name: String,
+    dead_letter_queue: Option<String>,
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    queues: Mutex<HashMap<String, MemoryQueue>>,
+    /// Signalled on every commit and non-syncpoint put
+    arrived: Condvar,
+}
+
+#[derive(Default)]
+struct MemoryQueue {
+    messages: VecDeque<MqMessage>,
+    backout_threshold: u32,
+    backout_requeue_queue: Option<String>,
+}
+
+impl MemoryQueueManager {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            dead_letter_queue: Some("SYSTEM.DEAD.LETTER.QUEUE".to_string()),
+            shared: Arc::new(Shared::default()),
+        }
+    }
+
+    /// Set or clear DEADQ.
+    pub fn with_dead_letter_queue(mut self, queue: Option<&str>) -> Self {
+        self.dead_letter_queue = queue.map(String::from);
+        self
+    }
+
+    /// Define BOTHRESH and BOQNAME of a queue.
+    pub fn define_queue(&self, queue: &str, backout_threshold: u32, backout_requeue_queue: Option<&str>) {
+        let mut queues = self.shared.queues.lock().unwrap();
+        let entry = queues.entry(queue.to_string()).or_default();
+        entry.backout_threshold = backout_threshold;
+        entry.backout_requeue_queue = backout_requeue_queue.map(String::from);
+    }
+
+    /// Committed messages on a queue.
+    pub fn messages(&self, queue: &str) -> Vec<MqMessage> {
+        self.shared.queues.lock().unwrap()
+            .get(queue)
+            .map(|q| q.messages.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl QueueManager for MemoryQueueManager {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> Result<Box<dyn MqConnection>, MainframeError> {
+        Ok(Box::new(MemoryConnection {
+            dead_letter_queue: self.dead_letter_queue.clone(),
+            shared: self.shared.clone(),
+            unit_of_work: Mutex::new(UnitOfWork::default()),
+        }))
+    }
+}
+
+struct MemoryConnection {
+    dead_letter_queue: Option<String>,
+    shared: Arc<Shared>,
+    unit_of_work: Mutex<UnitOfWork>,
+}
+
+#[derive(Default)]
+struct UnitOfWork {
+    got: Vec<(String, MqMessage)>,
+    put: Vec<(String, MqMessage)>,
+}
+
+impl MqConnection for MemoryConnection {
+    fn put(&self, queue: &str, message: &MqMessage, syncpoint: bool) -> Result<(), MainframeError> {
+        if syncpoint {
+            self.unit_of_work.lock().unwrap().put.push((queue.to_string(), message.clone()));
+        } else {
+            self.shared.queues.lock().unwrap().entry(queue.to_string()).or_default().messages.push_back(message.clone());
+            self.shared.arrived.notify_all();
+        }
+        Ok(())
+    }
+
+    fn get(&self, queue: &str, options: &GetOptions) -> Result<Option<MqMessage>, MainframeError> {
+        let deadline = Instant::now() + options.wait.unwrap_or_default();
+        let mut queues = self.shared.queues.lock().unwrap();
+        loop {
+            let messages = &mut queues.entry(queue.to_string()).or_default().messages;
+            if let Some(position) = messages.iter().position(|m| options.matches(m)) {
+                let message = messages.remove(position).expect("position in range");
+                if options.syncpoint {
+                    self.unit_of_work.lock().unwrap().got.push((queue.to_string(), message.clone()));
+                }
+                return Ok(Some(message));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            queues = self.shared.arrived.wait_timeout(queues, deadline - now).unwrap().0;
+        }
+    }
+
+    fn browse(&self, queue: &str, options: &GetOptions) -> Result<Vec<MqMessage>, MainframeError> {
+        Ok(self.shared.queues.lock().unwrap()
+            .get(queue)
+            .map(|q| q.messages.iter().filter(|m| options.matches(m)).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn commit(&self) -> Result<(), MainframeError> {
+        let work = std::mem::take(&mut *self.unit_of_work.lock().unwrap());
+        let mut queues = self.shared.queues.lock().unwrap();
+        for (queue, message) in work.put {
+            queues.entry(queue).or_default().messages.push_back(message);
+        }
+        self.shared.arrived.notify_all();
+        Ok(())
+    }
+
+    fn backout(&self) -> Result<(), MainframeError> {
+        let work = std::mem::take(&mut *self.unit_of_work.lock().unwrap());
+        let mut queues = self.shared.queues.lock().unwrap();
+        // Restore in reverse so the original order is kept at the front
+        for (queue, mut message) in work.got.into_iter().rev() {
+            message.descriptor.backout_count += 1;
+            queues.entry(queue).or_default().messages.push_front(message);
+        }
+        self.shared.arrived.notify_all();
+        Ok(())
+    }
+
+    fn attributes(&self, queue: &str) -> Result<QueueAttributes, MainframeError> {
+        let queues = self.shared.queues.lock().unwrap();
+        Ok(queues.get(queue).map_or_else(QueueAttributes::default, |q| QueueAttributes {
+            backout_threshold: q.backout_threshold,
+            backout_requeue_queue: q.backout_requeue_queue.clone(),
+            depth: q.messages.len() as u64,
+        }))
+    }
+
+    fn dead_letter_queue(&self) -> Option<String> {
+        self.dead_letter_queue.clone()
+    }
+}
+
+impl Drop for MemoryConnection {
+    fn drop(&mut self) {
+        // A connection lost mid-transaction backs out
+        let _ = self.backout();
+    }
+}
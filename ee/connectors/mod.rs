@@ -5,16 +5,37 @@
 //!
 //! Features:
 //! - SAP RFC/BAPI/OData/Event Mesh
-//! - SWIFT MX (ISO 20022), GPI, Sanctions
-//! - Mainframe CICS, IMS, MQ
+//! - SWIFT MX (ISO 20022), GPI, Sanctions, payment pre-validation
+//! - Mainframe CICS, IMS, MQ (transacted, JMS, poison messages)
+//! - Pooling, retry and circuit breaking shared by all connectors
+//! - Async APIs on Tokio's blocking pool, with timeouts and cancellation
+//! - Gate policy checks before BAPI, payment, transaction and queue calls,
+//!   with escalation and audit records
+//! - Credentials as secret references (env, file, Vault, KMS), resolved
+//!   at connect time and zeroized after use
+//! - Availability, latency, error and queue depth metrics (Prometheus),
+//!   feeding the cell's readiness probe
 
 pub mod sap;
 pub mod swift;
 pub mod mainframe;
 pub mod license;
+pub mod resilience;
+pub mod executor;
+pub mod gating;
+pub mod secrets;
+pub mod health;
 
 // Re-exports
 pub use sap::{SapConnector, SapConfig, RfcConnection, BapiCaller};
-pub use swift::{SwiftConnector, SwiftConfig, MxParser, GpiTracker};
-pub use mainframe::{MainframeConnector, CicsClient, ImsClient, MqClient};
+pub use swift::{SwiftConnector, SwiftConfig, MxParser, GpiTracker, ValidationMode, ValidationReport};
+pub use mainframe::{MainframeConnector, CicsClient, ImsClient, MqClient, MqMessage, CodePage, Copybook};
 pub use license::{check_license, LicenseError};
+pub use resilience::{ConnectionPool, Resilience, ResilienceConfig, RetryPolicy, PoolStats, Unavailable};
+pub use executor::{bounded, run_blocking, CallOptions, CancelToken};
+pub use gating::{ConnectorGate, ConnectorAction, GateRejection, GateDecision, GateAuditRecord, Escalation};
+pub use secrets::{SecretRef, Secret, SecretResolver, SecretError, KmsDecrypt, VaultConfig, install_resolver};
+pub use health::{
+    HealthMonitor, HealthSource, ConnectorHealth, ComponentHealth, HealthChange, Availability, ErrorClass,
+    ConnectorMetrics, connector_metrics,
+};
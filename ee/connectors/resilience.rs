@@ -0,0 +1,480 @@
+//! Connector Resilience
+//!
+//! Shared by the SAP, SWIFT and Mainframe connectors:
+//! - Connection pools sized from the connector config
+//! - Health-checked checkout, replacing broken connections
+//! - Retry with exponential backoff for transient failures
+//! - Circuit breaking via arbiter's `CircuitBreaker`
+//! - Latency and error class of every call recorded in the connector metrics
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let pool = ConnectionPool::new("cics", 5, &ResilienceConfig::default(), move || {
+//!     CicsClient::connect(&config, &user, &password)
+//! });
+//! let reply = pool.call(|cics| cics.link_program("ACCTINQ", &commarea))?;
+//! ```
+
+use agentkern_arbiter::{CircuitBreaker, CircuitState};
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::health::{connector_metrics, ErrorClass};
+
+/// Why a backend is not accepting calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unavailable {
+    CircuitOpen,
+    PoolExhausted,
+    /// Async call exceeded its timeout
+    TimedOut,
+    /// Async call was cancelled by the caller
+    Cancelled,
+}
+
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CircuitOpen => write!(f, "circuit open"),
+            Self::PoolExhausted => write!(f, "connection pool exhausted"),
+            Self::TimedOut => write!(f, "call timed out"),
+            Self::Cancelled => write!(f, "call cancelled"),
+        }
+    }
+}
+
+/// Connector errors the resilience layer can classify and produce.
+pub trait ConnectorError: std::error::Error + Sized {
+    /// Whether retrying may succeed (lost connections, timeouts, 5xx).
+    fn is_transient(&self) -> bool;
+
+    /// Error returned without calling the backend.
+    fn unavailable(service: &str, reason: Unavailable) -> Self;
+
+    /// Class reported in the connector metrics.
+    fn class(&self) -> ErrorClass {
+        if self.is_transient() {
+            ErrorClass::Transient
+        } else {
+            ErrorClass::Permanent
+        }
+    }
+}
+
+/// Connection that can report whether it is still usable.
+pub trait PooledConnection: Send {
+    fn is_healthy(&self) -> bool;
+}
+
+/// Retry with exponential backoff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts including the first (1 disables retry)
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5_000,
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Single attempt.
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// Delay after failed attempt `attempt` (1-based), minus up to 25% jitter.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.multiplier.max(1.0).powi(attempt.saturating_sub(1).min(32) as i32);
+        let delay = (self.initial_backoff_ms as f64 * exp).min(self.max_backoff_ms as f64);
+        // Jitter keeps callers from retrying in lockstep after a shared outage
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+        let jitter = delay * 0.25 * f64::from(nanos % 1_000) / 1_000.0;
+        Duration::from_millis((delay - jitter) as u64)
+    }
+}
+
+/// Retry, circuit breaker and pool settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResilienceConfig {
+    pub retry: RetryPolicy,
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// Successful probes that close it again
+    pub success_threshold: u32,
+    /// Seconds the circuit stays open before probing
+    pub reset_timeout_secs: u64,
+    /// Wait for a free pooled connection
+    pub checkout_timeout_ms: u64,
+    /// Idle connections unused for longer are checked before reuse
+    pub health_check_interval_secs: u64,
+    /// Timeout of async connector calls, retries included (0 disables it)
+    pub call_timeout_ms: u64,
+}
+
+impl Default for ResilienceConfig {
+    fn default() -> Self {
+        Self {
+            retry: RetryPolicy::default(),
+            failure_threshold: 5,
+            success_threshold: 2,
+            reset_timeout_secs: 30,
+            checkout_timeout_ms: 5_000,
+            health_check_interval_secs: 30,
+            call_timeout_ms: 30_000,
+        }
+    }
+}
+
+/// Retry and circuit breaking for one backend service.
+pub struct Resilience {
+    service: String,
+    retry: RetryPolicy,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl Resilience {
+    pub fn new(service: &str, config: &ResilienceConfig) -> Self {
+        let breaker = CircuitBreaker::new(service)
+            .with_thresholds(config.failure_threshold, config.success_threshold)
+            .with_reset_timeout(chrono::Duration::seconds(config.reset_timeout_secs as i64));
+        Self {
+            service: service.to_string(),
+            retry: config.retry.clone(),
+            breaker: Mutex::new(breaker),
+        }
+    }
+
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// Current circuit state.
+    pub fn state(&self) -> CircuitState {
+        self.breaker.lock().unwrap().state()
+    }
+
+    /// Run an operation that is safe to repeat, retrying transient failures.
+    pub fn call<T, E: ConnectorError>(&self, op: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        self.run(self.retry.max_attempts, op)
+    }
+
+    /// Run an operation at most once (circuit breaking only).
+    pub fn call_once<T, E: ConnectorError>(&self, op: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let mut op = Some(op);
+        self.run(1, || (op.take().expect("called once"))())
+    }
+
+    fn run<T, E: ConnectorError>(&self, attempts: u32, op: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let started = Instant::now();
+        let result = self.attempt(attempts, op);
+        let outcome = result.as_ref().map(|_| ()).map_err(ConnectorError::class);
+        connector_metrics().record_call(&self.service, started.elapsed(), outcome);
+        result
+    }
+
+    fn attempt<T, E: ConnectorError>(&self, attempts: u32, mut op: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+        let mut attempt = 1;
+        loop {
+            if !self.breaker.lock().unwrap().is_allowed() {
+                return Err(E::unavailable(&self.service, Unavailable::CircuitOpen));
+            }
+            match op() {
+                Ok(value) => {
+                    self.breaker.lock().unwrap().record_success();
+                    return Ok(value);
+                }
+                Err(e) if e.is_transient() => {
+                    self.breaker.lock().unwrap().record_failure();
+                    if attempt >= attempts.max(1) {
+                        return Err(e);
+                    }
+                    std::thread::sleep(self.retry.backoff(attempt));
+                    attempt += 1;
+                }
+                Err(e) => {
+                    // The backend answered; business errors say nothing about its health
+                    self.breaker.lock().unwrap().record_success();
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+struct Idle<C> {
+    conn: C,
+    since: Instant,
+}
+
+struct PoolState<C> {
+    idle: Vec<Idle<C>>,
+    open: usize,
+}
+
+/// Pool usage snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolStats {
+    pub size: usize,
+    pub open: usize,
+    pub idle: usize,
+    pub circuit: CircuitState,
+}
+
+/// Bounded pool of connections to one backend.
+pub struct ConnectionPool<C, E> {
+    size: usize,
+    checkout_timeout: Duration,
+    health_check_interval: Duration,
+    connect: Box<dyn Fn() -> Result<C, E> + Send + Sync>,
+    state: Mutex<PoolState<C>>,
+    returned: Condvar,
+    resilience: Resilience,
+}
+
+impl<C: PooledConnection, E: ConnectorError> ConnectionPool<C, E> {
+    /// Create pool; connections are opened on demand.
+    pub fn new(
+        service: &str,
+        size: usize,
+        config: &ResilienceConfig,
+        connect: impl Fn() -> Result<C, E> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            size: size.max(1),
+            checkout_timeout: Duration::from_millis(config.checkout_timeout_ms),
+            health_check_interval: Duration::from_secs(config.health_check_interval_secs),
+            connect: Box::new(connect),
+            state: Mutex::new(PoolState { idle: Vec::new(), open: 0 }),
+            returned: Condvar::new(),
+            resilience: Resilience::new(service, config),
+        }
+    }
+
+    /// Open one connection now so bad settings surface at connect time.
+    pub fn warm_up(&self) -> Result<(), E> {
+        self.resilience.call(|| self.checkout().map(drop))
+    }
+
+    /// Run an operation that is safe to repeat on a pooled connection.
+    ///
+    /// A connection that failed transiently is dropped; the retry gets a
+    /// fresh one.
+    pub fn call<T>(&self, mut op: impl FnMut(&C) -> Result<T, E>) -> Result<T, E> {
+        self.resilience.call(|| self.attempt(&mut op))
+    }
+
+    /// Run an operation at most once on a pooled connection.
+    pub fn call_once<T>(&self, op: impl FnOnce(&C) -> Result<T, E>) -> Result<T, E> {
+        self.resilience.call_once(|| self.attempt(op))
+    }
+
+    fn attempt<T>(&self, op: impl FnOnce(&C) -> Result<T, E>) -> Result<T, E> {
+        let mut conn = self.checkout()?;
+        let result = op(&conn);
+        if matches!(&result, Err(e) if e.is_transient()) {
+            conn.discard();
+        }
+        result
+    }
+
+    /// Check out a healthy connection, waiting up to the checkout timeout.
+    pub fn checkout(&self) -> Result<Pooled<'_, C, E>, E> {
+        let deadline = Instant::now() + self.checkout_timeout;
+        let mut state = self.state.lock().unwrap();
+        loop {
+            while let Some(idle) = state.idle.pop() {
+                if idle.since.elapsed() < self.health_check_interval || idle.conn.is_healthy() {
+                    return Ok(Pooled { pool: self, conn: Some(idle.conn) });
+                }
+                state.open -= 1;
+            }
+
+            if state.open < self.size {
+                state.open += 1;
+                drop(state);
+                // Connect outside the lock; others can check in meanwhile
+                return match (self.connect)() {
+                    Ok(conn) => Ok(Pooled { pool: self, conn: Some(conn) }),
+                    Err(e) => {
+                        self.release_slot();
+                        Err(e)
+                    }
+                };
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(E::unavailable(self.resilience.service(), Unavailable::PoolExhausted));
+            }
+            state = self.returned.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    /// Usage snapshot.
+    pub fn stats(&self) -> PoolStats {
+        let state = self.state.lock().unwrap();
+        PoolStats {
+            size: self.size,
+            open: state.open,
+            idle: state.idle.len(),
+            circuit: self.resilience.state(),
+        }
+    }
+
+    fn release_slot(&self) {
+        self.state.lock().unwrap().open -= 1;
+        self.returned.notify_one();
+    }
+
+    fn check_in(&self, conn: C) {
+        self.state.lock().unwrap().idle.push(Idle { conn, since: Instant::now() });
+        self.returned.notify_one();
+    }
+}
+
+/// Checked-out connection, returned to the pool on drop.
+pub struct Pooled<'a, C: PooledConnection, E: ConnectorError> {
+    pool: &'a ConnectionPool<C, E>,
+    conn: Option<C>,
+}
+
+impl<C: PooledConnection, E: ConnectorError> Pooled<'_, C, E> {
+    /// Close instead of returning to the pool.
+    pub fn discard(&mut self) {
+        if self.conn.take().is_some() {
+            self.pool.release_slot();
+        }
+    }
+}
+
+impl<C: PooledConnection, E: ConnectorError> Deref for Pooled<'_, C, E> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.conn.as_ref().expect("connection discarded")
+    }
+}
+
+impl<C: PooledConnection, E: ConnectorError> Drop for Pooled<'_, C, E> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.check_in(conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, thiserror::Error)]
+    enum TestError {
+        #[error("connection lost")]
+        Lost,
+        #[error("bad request")]
+        Invalid,
+        #[error("{0}: {1}")]
+        Unavailable(String, Unavailable),
+    }
+
+    impl ConnectorError for TestError {
+        fn is_transient(&self) -> bool {
+            *self == TestError::Lost
+        }
+
+        fn unavailable(service: &str, reason: Unavailable) -> Self {
+            TestError::Unavailable(service.to_string(), reason)
+        }
+    }
+
+    struct Conn {
+        id: u32,
+        healthy: Arc<AtomicU32>,
+    }
+
+    impl PooledConnection for Conn {
+        fn is_healthy(&self) -> bool {
+            self.healthy.load(Ordering::SeqCst) == 1
+        }
+    }
+
+    fn config() -> ResilienceConfig {
+        ResilienceConfig {
+            retry: RetryPolicy { initial_backoff_ms: 0, ..RetryPolicy::default() },
+            failure_threshold: 3,
+            checkout_timeout_ms: 10,
+            health_check_interval_secs: 0,
+            ..ResilienceConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_and_circuit_breaker() {
+        let resilience = Resilience::new("backend", &config());
+        let calls = AtomicU32::new(0);
+
+        let result = resilience.call(|| match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(TestError::Lost),
+            n => Ok(n),
+        });
+        assert_eq!(result, Ok(1));
+
+        // Business errors are not retried and do not trip the breaker
+        calls.store(0, Ordering::SeqCst);
+        assert_eq!(resilience.call(|| { calls.fetch_add(1, Ordering::SeqCst); Err::<(), _>(TestError::Invalid) }), Err(TestError::Invalid));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(resilience.call(|| Err::<(), _>(TestError::Lost)), Err(TestError::Lost));
+        assert_eq!(resilience.state(), CircuitState::Open);
+        assert_eq!(
+            resilience.call_once(|| Ok::<_, TestError>(())),
+            Err(TestError::Unavailable("backend".into(), Unavailable::CircuitOpen))
+        );
+    }
+
+    #[test]
+    fn test_pool_replaces_broken_connections() {
+        let healthy = Arc::new(AtomicU32::new(1));
+        let opened = Arc::new(AtomicU32::new(0));
+        let (h, o) = (healthy.clone(), opened.clone());
+        let pool = ConnectionPool::new("backend", 1, &config(), move || {
+            Ok::<_, TestError>(Conn { id: o.fetch_add(1, Ordering::SeqCst), healthy: h.clone() })
+        });
+
+        pool.warm_up().unwrap();
+        assert_eq!(pool.call(|c| Ok(c.id)), Ok(0));
+
+        // Unhealthy idle connection is replaced on checkout
+        healthy.store(0, Ordering::SeqCst);
+        assert_eq!(pool.call(|c| Ok(c.id)), Ok(1));
+        healthy.store(1, Ordering::SeqCst);
+
+        // Transient failure drops the connection and retries on a new one
+        let first = AtomicU32::new(1);
+        assert_eq!(pool.call(|c| if first.swap(0, Ordering::SeqCst) == 1 { Err(TestError::Lost) } else { Ok(c.id) }), Ok(2));
+
+        let held = pool.checkout().unwrap();
+        assert_eq!(pool.checkout().err(), Some(TestError::Unavailable("backend".into(), Unavailable::PoolExhausted)));
+        drop(held);
+        assert_eq!(pool.stats().idle, 1);
+    }
+}
@@ -1,58 +0,0 @@
-//! SAP Event Mesh Client
-//!
-//! Event-driven integration with SAP Event Mesh
-
-use super::{SapConfig, SapError};
-
-/// SAP Event Mesh client.
-pub struct EventMeshClient {
-    config: SapConfig,
-    subscriptions: Vec<String>,
-}
-
-impl EventMeshClient {
-    /// Create new Event Mesh client.
-    pub fn new(config: &SapConfig) -> Result<Self, SapError> {
-        Ok(Self {
-            config: config.clone(),
-            subscriptions: vec![],
-        })
-    }
-    
-    /// Subscribe to queue.
-    pub fn subscribe(&mut self, queue: &str) -> Result<(), SapError> {
-        self.subscriptions.push(queue.to_string());
-        Ok(())
-    }
-    
-    /// Unsubscribe from queue.
-    pub fn unsubscribe(&mut self, queue: &str) -> Result<(), SapError> {
-        self.subscriptions.retain(|q| q != queue);
-        Ok(())
-    }
-    
-    /// Publish event.
-    pub fn publish(&self, topic: &str, event: EventPayload) -> Result<(), SapError> {
-        // Production would use Event Mesh REST API
-        Ok(())
-    }
-    
-    /// Acknowledge message.
-    pub fn ack(&self, message_id: &str) -> Result<(), SapError> {
-        // Production would ACK via Event Mesh
-        Ok(())
-    }
-    
-    /// Get active subscriptions.
-    pub fn subscriptions(&self) -> &[String] {
-        &self.subscriptions
-    }
-}
-
-/// Event payload for Event Mesh.
-#[derive(Debug, Clone)]
-pub struct EventPayload {
-    pub event_type: String,
-    pub source: String,
-    pub data: serde_json::Value,
-}
@@ -0,0 +1,173 @@
+//! Event Mesh backend
+//!
+//! `EventBroker` is the messaging surface the client builds on: publish to
+//! a topic, consume from a queue with QoS 1, acknowledge or return a
+//! message. Consumed messages stay in flight until acknowledged; a message
+//! that is never acknowledged is redelivered.
+//! `MemoryBroker` implements the same semantics in-process.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{EventMessage, EventPayload};
+use super::super::SapError;
+
+/// Broker the Event Mesh client talks to.
+pub trait EventBroker: Send + Sync {
+    /// Publish an event to every queue subscribed to `topic`.
+    fn publish(&self, topic: &str, event: &EventPayload) -> Result<(), SapError>;
+
+    /// Next message of `queue`, waiting up to `wait`. The message stays in
+    /// flight until it is acknowledged or returned.
+    fn receive(&self, queue: &str, wait: Duration) -> Result<Option<EventMessage>, SapError>;
+
+    /// Acknowledge (remove) an in-flight message.
+    fn ack(&self, queue: &str, message_id: &str) -> Result<(), SapError>;
+
+    /// Return an in-flight message, deliverable again after `delay`.
+    fn nack(&self, queue: &str, message_id: &str, delay: Duration) -> Result<(), SapError>;
+
+    /// Messages waiting on `queue`, if the broker reports it.
+    fn depth(&self, _queue: &str) -> Option<u64> {
+        None
+    }
+}
+
+/// Whether `topic` matches a subscription: `*` stands for one level, a
+/// trailing `>` for one or more.
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let mut levels = topic.split('/');
+    for part in pattern.split('/') {
+        match (part, levels.next()) {
+            (">", Some(_)) => return true,
+            ("*", Some(_)) => {}
+            (part, Some(level)) if part == level => {}
+            _ => return false,
+        }
+    }
+    levels.next().is_none()
+}
+
+/// In-process broker.
+///
+/// Queues are created on first use and receive events of the topics bound
+/// with [`bind`](Self::bind). Used when no Event Mesh service is
+/// configured, and in tests.
+#[derive(Default)]
+pub struct MemoryBroker {
+    queues: Mutex<HashMap<String, MemoryQueue>>,
+    /// Signalled on every publish and nack
+    arrived: Condvar,
+}
+
+#[derive(Default)]
+struct MemoryQueue {
+    subscriptions: Vec<String>,
+    ready: VecDeque<Stored>,
+    in_flight: HashMap<String, Stored>,
+}
+
+#[derive(Clone)]
+struct Stored {
+    message_id: String,
+    topic: String,
+    payload: EventPayload,
+    deliveries: u32,
+    available_at: Instant,
+}
+
+impl MemoryBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe a queue to a topic pattern.
+    pub fn bind(&self, queue: &str, topic: &str) {
+        self.queues.lock().unwrap().entry(queue.to_string()).or_default().subscriptions.push(topic.to_string());
+    }
+
+    /// Messages waiting on a queue, including delayed ones.
+    pub fn messages(&self, queue: &str) -> Vec<EventPayload> {
+        self.queues.lock().unwrap()
+            .get(queue)
+            .map(|q| q.ready.iter().map(|m| m.payload.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of unacknowledged deliveries on a queue.
+    pub fn in_flight(&self, queue: &str) -> usize {
+        self.queues.lock().unwrap().get(queue).map_or(0, |q| q.in_flight.len())
+    }
+}
+
+impl EventBroker for MemoryBroker {
+    fn publish(&self, topic: &str, event: &EventPayload) -> Result<(), SapError> {
+        let mut queues = self.queues.lock().unwrap();
+        for queue in queues.values_mut() {
+            if queue.subscriptions.iter().any(|s| topic_matches(s, topic)) {
+                queue.ready.push_back(Stored {
+                    message_id: uuid::Uuid::new_v4().to_string(),
+                    topic: topic.to_string(),
+                    payload: event.clone(),
+                    deliveries: 0,
+                    available_at: Instant::now(),
+                });
+            }
+        }
+        self.arrived.notify_all();
+        Ok(())
+    }
+
+    fn receive(&self, queue: &str, wait: Duration) -> Result<Option<EventMessage>, SapError> {
+        let deadline = Instant::now() + wait;
+        let mut queues = self.queues.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let entry = queues.entry(queue.to_string()).or_default();
+            if let Some(position) = entry.ready.iter().position(|m| m.available_at <= now) {
+                let mut stored = entry.ready.remove(position).expect("position in range");
+                stored.deliveries += 1;
+                let message = EventMessage {
+                    message_id: stored.message_id.clone(),
+                    queue: queue.to_string(),
+                    topic: stored.topic.clone(),
+                    payload: stored.payload.clone(),
+                    delivery_count: stored.deliveries,
+                };
+                entry.in_flight.insert(stored.message_id.clone(), stored);
+                return Ok(Some(message));
+            }
+            if now >= deadline {
+                return Ok(None);
+            }
+            // Wake for the next delayed message if it is due before the deadline
+            let wake = entry.ready.iter().map(|m| m.available_at).min().map_or(deadline, |at| at.min(deadline));
+            queues = self.arrived.wait_timeout(queues, wake.saturating_duration_since(now)).unwrap().0;
+        }
+    }
+
+    fn ack(&self, queue: &str, message_id: &str) -> Result<(), SapError> {
+        self.queues.lock().unwrap()
+            .get_mut(queue)
+            .and_then(|q| q.in_flight.remove(message_id))
+            .map(|_| ())
+            .ok_or_else(|| SapError::EventMeshError(format!("No in-flight message {} on {}", message_id, queue)))
+    }
+
+    fn nack(&self, queue: &str, message_id: &str, delay: Duration) -> Result<(), SapError> {
+        let mut queues = self.queues.lock().unwrap();
+        let entry = queues.get_mut(queue)
+            .ok_or_else(|| SapError::EventMeshError(format!("Unknown queue {}", queue)))?;
+        let mut stored = entry.in_flight.remove(message_id)
+            .ok_or_else(|| SapError::EventMeshError(format!("No in-flight message {} on {}", message_id, queue)))?;
+        stored.available_at = Instant::now() + delay;
+        entry.ready.push_back(stored);
+        self.arrived.notify_all();
+        Ok(())
+    }
+
+    fn depth(&self, queue: &str) -> Option<u64> {
+        Some(self.queues.lock().unwrap().get(queue).map_or(0, |q| q.ready.len() as u64))
+    }
+}
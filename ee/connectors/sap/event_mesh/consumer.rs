@@ -0,0 +1,183 @@
+//! Event Mesh consumer
+//!
+//! At-least-once processing of one queue: every message is handed to the
+//! handler registered for its event type and acknowledged only after the
+//! handler accepts it. Retried messages come back after the redelivery
+//! backoff; once `redelivery.max_attempts` deliveries are used up, or the
+//! handler rejects the event, it is published to the dead-letter topic and
+//! acknowledged. A consumer that dies mid-message leaves it unacknowledged,
+//! so the broker delivers it again.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::broker::EventBroker;
+use super::{EventMeshConfig, EventMessage, EventPayload};
+use super::super::SapError;
+use super::super::super::executor::{run_blocking, CallOptions, CancelToken};
+use super::super::super::resilience::ConnectorError;
+
+/// What to do with a handled message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Disposition {
+    /// Processed; acknowledge it
+    Ack,
+    /// Failed for now; redeliver after backoff
+    Retry(String),
+    /// Cannot be processed; dead-letter it right away
+    Reject(String),
+}
+
+/// Handles events of the types it is registered for.
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn handle(&self, message: &EventMessage) -> Disposition;
+}
+
+/// Outcome of processing one message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Processed {
+    Acked { message_id: String },
+    Redelivering { message_id: String, delay: Duration },
+    DeadLettered { message_id: String, reason: String },
+}
+
+/// Consumer loop for one queue.
+pub struct EventConsumer {
+    broker: Arc<dyn EventBroker>,
+    queue: String,
+    config: EventMeshConfig,
+    handlers: Vec<(String, Arc<dyn EventHandler>)>,
+}
+
+impl EventConsumer {
+    pub fn new(broker: Arc<dyn EventBroker>, queue: &str, config: &EventMeshConfig) -> Self {
+        Self {
+            broker,
+            queue: queue.to_string(),
+            config: config.clone(),
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Register a handler for an event type; a trailing `*` matches any
+    /// suffix (`sap.s4.beh.businesspartner.*`). The first match wins;
+    /// events without a handler are dead-lettered.
+    pub fn with_handler(mut self, event_type: &str, handler: impl EventHandler + 'static) -> Self {
+        self.handlers.push((event_type.to_string(), Arc::new(handler)));
+        self
+    }
+
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+
+    fn handler(&self, event_type: &str) -> Option<&Arc<dyn EventHandler>> {
+        self.handlers.iter()
+            .find(|(pattern, _)| match pattern.strip_suffix('*') {
+                Some(prefix) => event_type.starts_with(prefix),
+                None => pattern == event_type,
+            })
+            .map(|(_, handler)| handler)
+    }
+
+    /// Receive and process at most one message, waiting up to
+    /// `receive_wait_ms`.
+    pub async fn poll(&self) -> Result<Option<Processed>, SapError> {
+        let (broker, queue) = (self.broker.clone(), self.queue.clone());
+        let wait = Duration::from_millis(self.config.receive_wait_ms);
+        let received = run_blocking(&self.service(), &CallOptions::new(), move || broker.receive(&queue, wait)).await?;
+        match received {
+            Some(message) => self.process(message).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn process(&self, message: EventMessage) -> Result<Processed, SapError> {
+        let disposition = match self.handler(&message.payload.event_type) {
+            Some(handler) => handler.handle(&message).await,
+            None => Disposition::Reject(format!("No handler for {}", message.payload.event_type)),
+        };
+        let message_id = message.message_id.clone();
+        let processed = match disposition {
+            Disposition::Ack => Processed::Acked { message_id },
+            Disposition::Retry(reason) if message.delivery_count >= self.config.redelivery.max_attempts => {
+                Processed::DeadLettered {
+                    message_id,
+                    reason: format!("{} (after {} deliveries)", reason, message.delivery_count),
+                }
+            }
+            Disposition::Retry(_) => Processed::Redelivering {
+                message_id,
+                delay: self.config.redelivery.backoff(message.delivery_count),
+            },
+            Disposition::Reject(reason) => Processed::DeadLettered { message_id, reason },
+        };
+
+        let (broker, settled) = (self.broker.clone(), processed.clone());
+        let dead_letter_topic = self.dead_letter_topic();
+        run_blocking(&self.service(), &CallOptions::new(), move || match settled {
+            Processed::Acked { .. } => broker.ack(&message.queue, &message.message_id),
+            Processed::Redelivering { delay, .. } => broker.nack(&message.queue, &message.message_id, delay),
+            Processed::DeadLettered { reason, .. } => {
+                // Published before the ack: a crash in between duplicates
+                // the dead letter rather than losing the event
+                broker.publish(&dead_letter_topic, &dead_letter(&message, &reason))?;
+                broker.ack(&message.queue, &message.message_id)
+            }
+        })
+        .await?;
+        Ok(processed)
+    }
+
+    /// Process messages until `cancel` fires.
+    ///
+    /// Cancellation is checked between messages, so it takes effect within
+    /// `receive_wait_ms` and never abandons a message mid-handling.
+    /// Transient broker errors are retried with the redelivery backoff;
+    /// other errors end the loop.
+    pub async fn run(&self, cancel: CancelToken) -> Result<(), SapError> {
+        let mut failures = 0;
+        while !cancel.is_cancelled() {
+            match self.poll().await {
+                Ok(_) => failures = 0,
+                Err(e) if e.is_transient() => {
+                    failures += 1;
+                    tokio::select! {
+                        _ = tokio::time::sleep(self.config.redelivery.backoff(failures)) => {}
+                        _ = cancel.cancelled() => break,
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn dead_letter_topic(&self) -> String {
+        self.config.dead_letter_topic.clone().unwrap_or_else(|| format!("{}/deadletter", self.queue))
+    }
+
+    fn service(&self) -> String {
+        format!("sap-event-mesh-{}", self.queue)
+    }
+}
+
+/// Dead letter: the original type and source, with the event wrapped
+/// together with why and where it failed.
+fn dead_letter(message: &EventMessage, reason: &str) -> EventPayload {
+    EventPayload {
+        event_type: message.payload.event_type.clone(),
+        source: message.payload.source.clone(),
+        data: serde_json::json!({
+            "reason": reason,
+            "queue": message.queue,
+            "topic": message.topic,
+            "message_id": message.message_id,
+            "deliveries": message.delivery_count,
+            "data": message.payload.data,
+        }),
+    }
+}
@@ -0,0 +1,236 @@
+//! SAP Event Mesh Client
+//!
+//! Event-driven integration with SAP Event Mesh:
+//! - Publish to topics, consume from queues with QoS 1
+//! - Manual ack/nack for at-least-once delivery
+//! - Consumer loop with handlers per event type, redelivery backoff and a
+//!   dead-letter topic for unprocessable events
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! struct PartnerSync;
+//!
+//! #[async_trait]
+//! impl EventHandler for PartnerSync {
+//!     async fn handle(&self, message: &EventMessage) -> Disposition {
+//!         match sync_partner(&message.payload.data).await {
+//!             Ok(()) => Disposition::Ack,
+//!             Err(e) if e.is_permanent() => Disposition::Reject(e.to_string()),
+//!             Err(e) => Disposition::Retry(e.to_string()),
+//!         }
+//!     }
+//! }
+//!
+//! let consumer = sap.event_consumer("agentkern/partners")?
+//!     .with_handler("sap.s4.beh.businesspartner.v1.*", PartnerSync);
+//! tokio::spawn(async move { consumer.run(cancel).await });
+//! ```
+
+mod broker;
+mod consumer;
+
+pub use broker::{EventBroker, MemoryBroker, topic_matches};
+pub use consumer::{EventConsumer, EventHandler, Disposition, Processed};
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::{SapConfig, SapError};
+use super::super::resilience::RetryPolicy;
+
+/// Event Mesh consumer settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventMeshConfig {
+    /// Deliveries per message (`max_attempts`) and backoff between them
+    pub redelivery: RetryPolicy,
+    /// Topic for dead letters; `{queue}/deadletter` if not set
+    pub dead_letter_topic: Option<String>,
+    /// Long-poll wait of one receive
+    pub receive_wait_ms: u64,
+}
+
+impl Default for EventMeshConfig {
+    fn default() -> Self {
+        Self {
+            redelivery: RetryPolicy {
+                max_attempts: 5,
+                initial_backoff_ms: 1_000,
+                max_backoff_ms: 60_000,
+                multiplier: 2.0,
+            },
+            dead_letter_topic: None,
+            receive_wait_ms: 1_000,
+        }
+    }
+}
+
+/// SAP Event Mesh client.
+pub struct EventMeshClient {
+    config: SapConfig,
+    broker: Arc<dyn EventBroker>,
+    subscriptions: RwLock<Vec<String>>,
+}
+
+impl EventMeshClient {
+    /// Create new Event Mesh client.
+    pub fn new(config: &SapConfig, broker: Arc<dyn EventBroker>) -> Result<Self, SapError> {
+        Ok(Self {
+            config: config.clone(),
+            broker,
+            subscriptions: RwLock::new(vec![]),
+        })
+    }
+
+    /// Subscribe to queue.
+    pub fn subscribe(&self, queue: &str) -> Result<(), SapError> {
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        if !subscriptions.iter().any(|q| q == queue) {
+            subscriptions.push(queue.to_string());
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from queue.
+    pub fn unsubscribe(&self, queue: &str) -> Result<(), SapError> {
+        self.subscriptions.write().unwrap().retain(|q| q != queue);
+        Ok(())
+    }
+
+    /// Publish event.
+    pub fn publish(&self, topic: &str, event: EventPayload) -> Result<(), SapError> {
+        self.broker.publish(topic, &event)
+    }
+
+    /// Receive the next message of a subscribed queue; it must be acked
+    /// or nacked.
+    pub fn receive(&self, queue: &str, wait: Duration) -> Result<Option<EventMessage>, SapError> {
+        self.subscribed(queue)?;
+        self.broker.receive(queue, wait)
+    }
+
+    /// Acknowledge message.
+    pub fn ack(&self, message: &EventMessage) -> Result<(), SapError> {
+        self.broker.ack(&message.queue, &message.message_id)
+    }
+
+    /// Return message for redelivery after `delay`.
+    pub fn nack(&self, message: &EventMessage, delay: Duration) -> Result<(), SapError> {
+        self.broker.nack(&message.queue, &message.message_id, delay)
+    }
+
+    /// Consumer for a subscribed queue.
+    pub fn consumer(&self, queue: &str) -> Result<EventConsumer, SapError> {
+        self.subscribed(queue)?;
+        Ok(EventConsumer::new(self.broker.clone(), queue, &self.config.event_mesh))
+    }
+
+    fn subscribed(&self, queue: &str) -> Result<(), SapError> {
+        if self.subscriptions.read().unwrap().iter().any(|q| q == queue) {
+            Ok(())
+        } else {
+            Err(SapError::EventMeshError(format!("Not subscribed to {}", queue)))
+        }
+    }
+
+    /// Messages waiting on a queue, if the broker reports it.
+    pub fn depth(&self, queue: &str) -> Option<u64> {
+        self.broker.depth(queue)
+    }
+
+    /// Get active subscriptions.
+    pub fn subscriptions(&self) -> Vec<String> {
+        self.subscriptions.read().unwrap().clone()
+    }
+}
+
+/// Event payload for Event Mesh.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventPayload {
+    /// CloudEvents `type`
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub source: String,
+    pub data: serde_json::Value,
+}
+
+/// A delivered message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventMessage {
+    pub message_id: String,
+    pub queue: String,
+    /// Topic it was published to
+    pub topic: String,
+    pub payload: EventPayload,
+    /// 1 on first delivery
+    pub delivery_count: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct FlakyHandler;
+
+    #[async_trait]
+    impl EventHandler for FlakyHandler {
+        async fn handle(&self, message: &EventMessage) -> Disposition {
+            match message.payload.data["partner"].as_str() {
+                Some("BROKEN") => Disposition::Retry("partner service down".into()),
+                Some(_) if message.delivery_count < 2 => Disposition::Retry("timeout".into()),
+                Some(_) => Disposition::Ack,
+                None => Disposition::Reject("missing partner".into()),
+            }
+        }
+    }
+
+    fn event(event_type: &str, data: serde_json::Value) -> EventPayload {
+        EventPayload { event_type: event_type.into(), source: "/default/sap.s4/S4H".into(), data }
+    }
+
+    #[tokio::test]
+    async fn test_consumer_redelivery_and_dead_letters() {
+        let broker = Arc::new(MemoryBroker::new());
+        broker.bind("partners", "sap/s4/beh/businesspartner/*/Changed");
+        broker.bind("dead", "partners/deadletter");
+        assert!(topic_matches("sap/>", "sap/s4/beh") && !topic_matches("sap/*", "sap/s4/beh"));
+
+        let mut config = SapConfig::default();
+        config.event_mesh.redelivery = RetryPolicy { max_attempts: 3, initial_backoff_ms: 1, max_backoff_ms: 5, multiplier: 2.0 };
+        config.event_mesh.receive_wait_ms = 50;
+        let client = EventMeshClient::new(&config, broker.clone()).unwrap();
+        assert!(client.consumer("partners").is_err());
+        client.subscribe("partners").unwrap();
+
+        let changed = "sap.s4.beh.businesspartner.v1.BusinessPartner.Changed.v1";
+        let topic = "sap/s4/beh/businesspartner/v1/Changed";
+        client.publish(topic, event(changed, serde_json::json!({"partner": "1000"}))).unwrap();
+        client.publish(topic, event(changed, serde_json::json!({"partner": "BROKEN"}))).unwrap();
+        client.publish(topic, event(changed, serde_json::json!({}))).unwrap();
+        client.publish(topic, event("sap.s4.beh.salesorder.v1.SalesOrder.Created.v1", serde_json::json!({}))).unwrap();
+
+        let consumer = client.consumer("partners").unwrap()
+            .with_handler("sap.s4.beh.businesspartner.*", FlakyHandler);
+        let mut outcomes = Vec::new();
+        while let Some(processed) = consumer.poll().await.unwrap() {
+            outcomes.push(processed);
+        }
+
+        // First partner acked on its second delivery, the broken one retried
+        // until max_attempts, the others dead-lettered at once
+        let acked = outcomes.iter().filter(|p| matches!(p, Processed::Acked { .. })).count();
+        let redelivered = outcomes.iter().filter(|p| matches!(p, Processed::Redelivering { .. })).count();
+        assert_eq!((acked, redelivered), (1, 3));
+        assert_eq!(broker.in_flight("partners"), 0);
+
+        let dead = broker.messages("dead");
+        let reasons: Vec<_> = dead.iter().map(|d| d.data["reason"].as_str().unwrap()).collect();
+        assert_eq!(reasons.len(), 3);
+        assert!(reasons.contains(&"missing partner"));
+        assert!(reasons.contains(&"partner service down (after 3 deliveries)"));
+        assert!(reasons.iter().any(|r| r.starts_with("No handler for sap.s4.beh.salesorder")));
+    }
+}
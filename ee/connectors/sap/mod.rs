@@ -10,12 +10,24 @@ mod event_mesh;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use super::executor::{run_blocking, CallOptions};
+use super::gating::{ConnectorAction, ConnectorGate, GateRejection};
+use super::health::{ComponentHealth, ConnectorHealth, ErrorClass, HealthSource};
 use super::license::{check_feature_license, LicenseError};
+use super::secrets::{SecretError, SecretRef};
+use super::resilience::{ConnectionPool, ConnectorError, PoolStats, Resilience, ResilienceConfig, Unavailable};
 
 pub use rfc::RfcConnection;
 pub use bapi::BapiCaller;
-pub use odata::ODataClient;
-pub use event_mesh::EventMeshClient;
+pub use odata::{
+    ODataClient, ODataQuery, ODataResponse, DeltaResult, BatchRequest, BatchResponse, BatchOperation, ChangeSet,
+    ServiceMetadata, EntityType, Property, NavigationProperty, ODataTransport, ReqwestTransport, HttpRequest, HttpResponse,
+};
+pub use event_mesh::{
+    EventMeshClient, EventMeshConfig, EventPayload, EventMessage, EventBroker, MemoryBroker, EventConsumer,
+    EventHandler, Disposition, Processed, topic_matches,
+};
 
 /// SAP connector configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,10 +42,19 @@ pub struct SapConfig {
     pub sysnr: String,
     /// SAP user
     pub user: String,
+    /// RFC logon password, resolved per connection
+    #[serde(default)]
+    pub password: Option<SecretRef>,
     /// Language
     pub language: String,
     /// Connection pool size
     pub pool_size: usize,
+    /// Retry and circuit breaker settings
+    #[serde(default)]
+    pub resilience: ResilienceConfig,
+    /// Event Mesh consumer settings
+    #[serde(default)]
+    pub event_mesh: EventMeshConfig,
 }
 
 impl Default for SapConfig {
@@ -44,18 +65,28 @@ impl Default for SapConfig {
             ashost: "localhost".to_string(),
             sysnr: "00".to_string(),
             user: String::new(),
+            password: None,
             language: "EN".to_string(),
             pool_size: 5,
+            resilience: ResilienceConfig::default(),
+            event_mesh: EventMeshConfig::default(),
         }
     }
 }
 
 /// SAP connector with all integration modes.
+///
+/// Calls are async and run on the blocking pool; the `*_blocking` variants
+/// call SAP on the current thread, for CLI tools.
 pub struct SapConnector {
     config: SapConfig,
-    rfc: Option<RfcConnection>,
-    odata: Option<ODataClient>,
-    event_mesh: Option<EventMeshClient>,
+    rfc: Option<Arc<ConnectionPool<RfcConnection, SapError>>>,
+    odata: Option<Arc<ODataClient>>,
+    odata_resilience: Arc<Resilience>,
+    event_mesh: Option<Arc<EventMeshClient>>,
+    event_broker: Option<Arc<dyn EventBroker>>,
+    call_options: CallOptions,
+    gate: Option<Arc<ConnectorGate>>,
 }
 
 impl SapConnector {
@@ -64,52 +95,235 @@ impl SapConnector {
         check_feature_license("sap")?;
         
         Ok(Self {
+            odata_resilience: Arc::new(Resilience::new("sap-odata", &config.resilience)),
+            call_options: CallOptions::from_config(&config.resilience),
             config,
             rfc: None,
             odata: None,
             event_mesh: None,
+            event_broker: None,
+            gate: None,
         })
     }
     
-    /// Connect via RFC.
-    pub fn connect_rfc(&mut self, password: &str) -> Result<(), SapError> {
-        self.rfc = Some(RfcConnection::new(&self.config, password)?);
+    /// Override timeout and cancellation of async calls.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.call_options = options;
+        self
+    }
+    
+    /// Use a specific Event Mesh broker.
+    pub fn with_event_broker(mut self, broker: Arc<dyn EventBroker>) -> Self {
+        self.event_broker = Some(broker);
+        self
+    }
+    
+    /// Check BAPI, OData and publish calls against Gate policies before they run.
+    pub fn with_gate(mut self, gate: Arc<ConnectorGate>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+    
+    /// Connect via RFC (pool of `pool_size` connections) with the
+    /// configured password.
+    pub async fn connect_rfc(&mut self) -> Result<(), SapError> {
+        let pool = Arc::new(self.rfc_pool());
+        let warming = pool.clone();
+        self.run(move || warming.warm_up()).await?;
+        self.rfc = Some(pool);
         Ok(())
     }
     
+    /// Blocking [`connect_rfc`](Self::connect_rfc).
+    pub fn connect_rfc_blocking(&mut self) -> Result<(), SapError> {
+        let pool = self.rfc_pool();
+        pool.warm_up()?;
+        self.rfc = Some(Arc::new(pool));
+        Ok(())
+    }
+    
+    fn rfc_pool(&self) -> ConnectionPool<RfcConnection, SapError> {
+        let config = self.config.clone();
+        ConnectionPool::new("sap-rfc", self.config.pool_size, &self.config.resilience, move || {
+            // Resolved per connection so rotated passwords are picked up
+            let password = config.password.as_ref()
+                .ok_or_else(|| SapError::RfcError("No RFC password configured".into()))?
+                .resolve()?;
+            RfcConnection::new(&config, password.expose_str()?)
+        })
+    }
+    
     /// Connect via OData (S/4HANA).
+    ///
+    /// Does not contact the service; the first call fetches the CSRF token.
     pub fn connect_odata(&mut self, base_url: &str, auth: ODataAuth) -> Result<(), SapError> {
-        self.odata = Some(ODataClient::new(base_url, auth)?);
+        self.odata = Some(Arc::new(ODataClient::new(base_url, auth)?.with_sap_client(&self.config.client)));
         Ok(())
     }
     
+    async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> Result<T, SapError> + Send + 'static,
+    ) -> Result<T, SapError> {
+        run_blocking(&format!("sap-{}", self.config.system_id), &self.call_options, work).await
+    }
+    
+    async fn authorize(&self, operation: &str, target: Option<&str>) -> Result<(), SapError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize(Self::action(operation, target)).await?),
+            None => Ok(()),
+        }
+    }
+    
+    fn authorize_blocking(&self, operation: &str, target: Option<&str>) -> Result<(), SapError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize_blocking(Self::action(operation, target))?),
+            None => Ok(()),
+        }
+    }
+    
+    fn action(operation: &str, target: Option<&str>) -> ConnectorAction {
+        ConnectorAction { target: target.map(String::from), ..ConnectorAction::new("sap", operation) }
+    }
+    
+    fn rfc(&self) -> Result<Arc<ConnectionPool<RfcConnection, SapError>>, SapError> {
+        self.rfc.clone().ok_or(SapError::NotConnected)
+    }
+    
+    fn odata_client(&self) -> Result<Arc<ODataClient>, SapError> {
+        self.odata.clone().ok_or(SapError::ODataNotConfigured)
+    }
+    
     /// Call a BAPI function.
-    pub fn call_bapi(&self, bapi_name: &str, params: HashMap<String, serde_json::Value>) -> Result<BapiResult, SapError> {
-        let rfc = self.rfc.as_ref().ok_or(SapError::NotConnected)?;
-        let caller = BapiCaller::new(rfc);
-        caller.call(bapi_name, params)
+    pub async fn call_bapi(&self, bapi_name: &str, params: HashMap<String, serde_json::Value>) -> Result<BapiResult, SapError> {
+        self.authorize("call_bapi", Some(bapi_name)).await?;
+        let pool = self.rfc()?;
+        let bapi_name = bapi_name.to_string();
+        self.run(move || Self::bapi(&pool, &bapi_name, params)).await
+    }
+    
+    /// Blocking [`call_bapi`](Self::call_bapi).
+    pub fn call_bapi_blocking(&self, bapi_name: &str, params: HashMap<String, serde_json::Value>) -> Result<BapiResult, SapError> {
+        self.authorize_blocking("call_bapi", Some(bapi_name))?;
+        Self::bapi(&*self.rfc()?, bapi_name, params)
+    }
+    
+    fn bapi(
+        pool: &ConnectionPool<RfcConnection, SapError>,
+        bapi_name: &str,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<BapiResult, SapError> {
+        // Uncommitted BAPI work is rolled back when the connection drops,
+        // so retrying on a fresh connection cannot apply it twice
+        pool.call(|rfc| BapiCaller::new(rfc).call(bapi_name, params.clone()))
     }
     
     /// Read OData entity.
-    pub fn read_entity(&self, entity_set: &str, key: &str) -> Result<serde_json::Value, SapError> {
-        let odata = self.odata.as_ref().ok_or(SapError::ODataNotConfigured)?;
-        odata.get(entity_set, key)
+    pub async fn read_entity(&self, entity_set: &str, key: &str) -> Result<serde_json::Value, SapError> {
+        self.authorize("read_entity", Some(entity_set)).await?;
+        let (odata, resilience) = (self.odata_client()?, self.odata_resilience.clone());
+        let (entity_set, key) = (entity_set.to_string(), key.to_string());
+        self.run(move || resilience.call(|| odata.get(&entity_set, &key))).await
+    }
+    
+    /// Blocking [`read_entity`](Self::read_entity).
+    pub fn read_entity_blocking(&self, entity_set: &str, key: &str) -> Result<serde_json::Value, SapError> {
+        self.authorize_blocking("read_entity", Some(entity_set))?;
+        let odata = self.odata_client()?;
+        self.odata_resilience.call(|| odata.get(entity_set, key))
     }
     
     /// Create OData entity.
-    pub fn create_entity(&self, entity_set: &str, data: serde_json::Value) -> Result<serde_json::Value, SapError> {
-        let odata = self.odata.as_ref().ok_or(SapError::ODataNotConfigured)?;
-        odata.post(entity_set, data)
+    pub async fn create_entity(&self, entity_set: &str, data: serde_json::Value) -> Result<serde_json::Value, SapError> {
+        self.authorize("create_entity", Some(entity_set)).await?;
+        let (odata, resilience) = (self.odata_client()?, self.odata_resilience.clone());
+        let entity_set = entity_set.to_string();
+        // Not retried: a lost response may still have created the entity
+        self.run(move || resilience.call_once(|| odata.post(&entity_set, data))).await
+    }
+    
+    /// Blocking [`create_entity`](Self::create_entity).
+    pub fn create_entity_blocking(&self, entity_set: &str, data: serde_json::Value) -> Result<serde_json::Value, SapError> {
+        self.authorize_blocking("create_entity", Some(entity_set))?;
+        let odata = self.odata_client()?;
+        self.odata_resilience.call_once(|| odata.post(entity_set, data))
+    }
+    
+    /// Run work against the OData client (queries, batch, change tracking)
+    /// on the blocking pool, behind the OData circuit breaker.
+    ///
+    /// Gated once per call, as `odata_call`. Not retried, as `work` may write.
+    pub async fn odata_call<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&ODataClient) -> Result<T, SapError> + Send + 'static,
+    ) -> Result<T, SapError> {
+        self.authorize("odata_call", None).await?;
+        let (odata, resilience) = (self.odata_client()?, self.odata_resilience.clone());
+        self.run(move || resilience.call_once(|| work(&odata))).await
+    }
+    
+    /// OData client, once connected, for blocking use.
+    pub fn odata(&self) -> Option<&ODataClient> {
+        self.odata.as_deref()
     }
     
     /// Subscribe to Event Mesh.
-    pub fn subscribe_events(&mut self, queue: &str) -> Result<(), SapError> {
-        let mesh = EventMeshClient::new(&self.config)?;
+    pub async fn subscribe_events(&mut self, queue: &str) -> Result<(), SapError> {
+        let mesh = self.event_mesh_client()?;
+        let (subscribing, queue) = (mesh.clone(), queue.to_string());
+        self.run(move || subscribing.subscribe(&queue)).await?;
+        self.event_mesh = Some(mesh);
+        Ok(())
+    }
+    
+    /// Blocking [`subscribe_events`](Self::subscribe_events).
+    pub fn subscribe_events_blocking(&mut self, queue: &str) -> Result<(), SapError> {
+        let mesh = self.event_mesh_client()?;
         mesh.subscribe(queue)?;
         self.event_mesh = Some(mesh);
         Ok(())
     }
     
+    /// Existing client, or a new one on the configured broker.
+    fn event_mesh_client(&self) -> Result<Arc<EventMeshClient>, SapError> {
+        if let Some(mesh) = &self.event_mesh {
+            return Ok(mesh.clone());
+        }
+        let broker = match &self.event_broker {
+            Some(broker) => broker.clone(),
+            // Production would use the Event Mesh messaging REST API
+            None => Arc::new(MemoryBroker::new()) as Arc<dyn EventBroker>,
+        };
+        Ok(Arc::new(EventMeshClient::new(&self.config, broker)?))
+    }
+    
+    fn event_mesh(&self) -> Result<Arc<EventMeshClient>, SapError> {
+        self.event_mesh.clone().ok_or(SapError::NotConnected)
+    }
+    
+    /// Publish an event to a topic.
+    pub async fn publish_event(&self, topic: &str, event: EventPayload) -> Result<(), SapError> {
+        self.authorize("publish_event", Some(topic)).await?;
+        let (mesh, topic) = (self.event_mesh()?, topic.to_string());
+        self.run(move || mesh.publish(&topic, event)).await
+    }
+    
+    /// Blocking [`publish_event`](Self::publish_event).
+    pub fn publish_event_blocking(&self, topic: &str, event: EventPayload) -> Result<(), SapError> {
+        self.authorize_blocking("publish_event", Some(topic))?;
+        self.event_mesh()?.publish(topic, event)
+    }
+    
+    /// Consumer for a subscribed queue; register handlers, then `run` it.
+    pub fn event_consumer(&self, queue: &str) -> Result<EventConsumer, SapError> {
+        self.event_mesh()?.consumer(queue)
+    }
+    
+    /// RFC pool usage, once connected.
+    pub fn rfc_pool_stats(&self) -> Option<PoolStats> {
+        self.rfc.as_ref().map(|pool| pool.stats())
+    }
+    
     /// Health check.
     pub fn health_check(&self) -> SapHealth {
         SapHealth {
@@ -120,12 +334,34 @@ impl SapConnector {
     }
 }
 
+impl HealthSource for SapConnector {
+    fn health(&self) -> ConnectorHealth {
+        let mut health = ConnectorHealth::new(&format!("sap-{}", self.config.system_id));
+        if let Some(pool) = &self.rfc {
+            health = health.with_component(ComponentHealth::from_pool("rfc", &pool.stats()));
+        }
+        if self.odata.is_some() {
+            health = health.with_component(ComponentHealth::from_circuit("odata", self.odata_resilience.state()));
+        }
+        if let Some(mesh) = &self.event_mesh {
+            health = health.with_component(ComponentHealth::up("event_mesh"));
+            for queue in mesh.subscriptions() {
+                if let Some(depth) = mesh.depth(&queue) {
+                    health = health.with_queue_depth(&queue, depth);
+                }
+            }
+        }
+        health
+    }
+}
+
 /// OData authentication.
 #[derive(Debug, Clone)]
 pub enum ODataAuth {
-    Basic { username: String, password: String },
-    OAuth2 { client_id: String, client_secret: String, token_url: String },
-    Certificate { cert_path: String, key_path: String },
+    Basic { username: String, password: SecretRef },
+    OAuth2 { client_id: String, client_secret: SecretRef, token_url: String },
+    /// Client certificate and private key (PEM)
+    Certificate { cert: SecretRef, key: SecretRef },
 }
 
 /// BAPI call result.
@@ -175,10 +411,42 @@ pub enum SapError {
     #[error("Event Mesh error: {0}")]
     EventMeshError(String),
     
+    #[error("{0} unavailable: {1}")]
+    Unavailable(String, Unavailable),
+    
+    #[error("Gate: {0}")]
+    Gated(#[from] GateRejection),
+    
+    #[error("Secret error: {0}")]
+    Secret(#[from] SecretError),
+    
     #[error("License error: {0}")]
     LicenseError(#[from] LicenseError),
 }
 
+impl ConnectorError for SapError {
+    fn is_transient(&self) -> bool {
+        match self {
+            SapError::RfcError(_) | SapError::EventMeshError(_) => true,
+            // 4xx answers are about the request, not the system
+            SapError::ODataError(msg) => !msg.starts_with("HTTP 4"),
+            _ => false,
+        }
+    }
+
+    fn unavailable(service: &str, reason: Unavailable) -> Self {
+        SapError::Unavailable(service.to_string(), reason)
+    }
+
+    fn class(&self) -> ErrorClass {
+        match self {
+            SapError::Unavailable(..) => ErrorClass::Unavailable,
+            e if e.is_transient() => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -1,90 +0,0 @@
-//! SAP OData Client
-//!
-//! OData v4 for S/4HANA Cloud and On-Premise
-
-use super::{ODataAuth, SapError};
-
-/// OData client for SAP S/4HANA.
-pub struct ODataClient {
-    base_url: String,
-    auth: ODataAuth,
-}
-
-impl ODataClient {
-    /// Create new OData client.
-    pub fn new(base_url: &str, auth: ODataAuth) -> Result<Self, SapError> {
-        Ok(Self {
-            base_url: base_url.to_string(),
-            auth,
-        })
-    }
-    
-    /// GET entity by key.
-    pub fn get(&self, entity_set: &str, key: &str) -> Result<serde_json::Value, SapError> {
-        let _url = format!("{}/{}('{}')", self.base_url, entity_set, key);
-        // Production would use reqwest with auth
-        Ok(serde_json::json!({
-            "@odata.context": format!("$metadata#{}", entity_set),
-            "value": key
-        }))
-    }
-    
-    /// POST new entity.
-    pub fn post(&self, entity_set: &str, data: serde_json::Value) -> Result<serde_json::Value, SapError> {
-        let _url = format!("{}/{}", self.base_url, entity_set);
-        // Production would POST with auth
-        Ok(data)
-    }
-    
-    /// PATCH entity.
-    pub fn patch(&self, entity_set: &str, key: &str, data: serde_json::Value) -> Result<(), SapError> {
-        let _url = format!("{}/{}('{}')", self.base_url, entity_set, key);
-        // Production would PATCH with auth
-        Ok(())
-    }
-    
-    /// DELETE entity.
-    pub fn delete(&self, entity_set: &str, key: &str) -> Result<(), SapError> {
-        let _url = format!("{}/{}('{}')", self.base_url, entity_set, key);
-        // Production would DELETE with auth
-        Ok(())
-    }
-    
-    /// Query with OData filters.
-    pub fn query(&self, entity_set: &str, filter: Option<&str>, top: Option<u32>) -> Result<ODataResponse, SapError> {
-        let mut url = format!("{}/{}", self.base_url, entity_set);
-        let mut params = vec![];
-        
-        if let Some(f) = filter {
-            params.push(format!("$filter={}", f));
-        }
-        if let Some(t) = top {
-            params.push(format!("$top={}", t));
-        }
-        
-        if !params.is_empty() {
-            url = format!("{}?{}", url, params.join("&"));
-        }
-        
-        // Production would execute query
-        Ok(ODataResponse {
-            context: format!("$metadata#{}", entity_set),
-            count: None,
-            value: vec![],
-        })
-    }
-    
-    /// Get service metadata.
-    pub fn metadata(&self) -> Result<String, SapError> {
-        // Would fetch $metadata
-        Ok("<?xml version=\"1.0\"?>".to_string())
-    }
-}
-
-/// OData query response.
-#[derive(Debug, Clone)]
-pub struct ODataResponse {
-    pub context: String,
-    pub count: Option<u64>,
-    pub value: Vec<serde_json::Value>,
-}
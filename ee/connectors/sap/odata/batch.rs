@@ -0,0 +1,275 @@
+//! OData $batch (multipart/mixed)
+//!
+//! Reads go into the batch as individual parts; changes are grouped into
+//! change sets, which the server applies atomically. A failing change set
+//! is answered with a single error response instead of one per operation.
+
+use serde_json::Value;
+
+use super::super::SapError;
+
+/// Single operation in a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOperation {
+    pub method: String,
+    /// Path relative to the service root, e.g. `SalesOrder('1')`
+    pub path: String,
+    pub body: Option<Value>,
+    pub content_id: Option<String>,
+}
+
+/// Atomic group of changes.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeSet {
+    operations: Vec<BatchOperation>,
+}
+
+impl ChangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn post(self, path: &str, body: Value) -> Self {
+        self.push("POST", path, Some(body))
+    }
+
+    pub fn patch(self, path: &str, body: Value) -> Self {
+        self.push("PATCH", path, Some(body))
+    }
+
+    pub fn delete(self, path: &str) -> Self {
+        self.push("DELETE", path, None)
+    }
+
+    /// Operations get Content-IDs 1..n so later ones can reference `$1`.
+    fn push(mut self, method: &str, path: &str, body: Option<Value>) -> Self {
+        let content_id = (self.operations.len() + 1).to_string();
+        self.operations.push(BatchOperation {
+            method: method.to_string(),
+            path: path.to_string(),
+            body,
+            content_id: Some(content_id),
+        });
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+enum BatchPart {
+    Read(BatchOperation),
+    ChangeSet(ChangeSet),
+}
+
+/// Batch of reads and change sets.
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    boundary: String,
+    parts: Vec<BatchPart>,
+}
+
+impl BatchRequest {
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("batch_{}", uuid::Uuid::new_v4()),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Add a read.
+    pub fn get(mut self, path: &str) -> Self {
+        self.parts.push(BatchPart::Read(BatchOperation {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            body: None,
+            content_id: None,
+        }));
+        self
+    }
+
+    /// Add a change set.
+    pub fn changeset(mut self, changeset: ChangeSet) -> Self {
+        self.parts.push(BatchPart::ChangeSet(changeset));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Content-Type header of the batch request.
+    pub fn content_type(&self) -> String {
+        format!("multipart/mixed;boundary={}", self.boundary)
+    }
+
+    /// Serialize the multipart body.
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            out.push_str(&format!("--{}\r\n", self.boundary));
+            match part {
+                BatchPart::Read(op) => encode_operation(&mut out, op),
+                BatchPart::ChangeSet(changeset) => {
+                    let boundary = format!("changeset_{}", uuid::Uuid::new_v4());
+                    out.push_str(&format!("Content-Type: multipart/mixed;boundary={}\r\n\r\n", boundary));
+                    for op in &changeset.operations {
+                        out.push_str(&format!("--{}\r\n", boundary));
+                        encode_operation(&mut out, op);
+                    }
+                    out.push_str(&format!("--{}--\r\n", boundary));
+                }
+            }
+        }
+        out.push_str(&format!("--{}--\r\n", self.boundary));
+        out
+    }
+}
+
+impl Default for BatchRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode_operation(out: &mut String, op: &BatchOperation) {
+    out.push_str("Content-Type: application/http\r\nContent-Transfer-Encoding: binary\r\n");
+    if let Some(id) = &op.content_id {
+        out.push_str(&format!("Content-ID: {}\r\n", id));
+    }
+    out.push_str(&format!("\r\n{} {} HTTP/1.1\r\nAccept: application/json\r\n", op.method, op.path));
+    match &op.body {
+        Some(body) => out.push_str(&format!("Content-Type: application/json\r\n\r\n{}\r\n", body)),
+        None => out.push_str("\r\n"),
+    }
+}
+
+/// Response to one batch operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResponse {
+    pub content_id: Option<String>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Value>,
+}
+
+impl BatchResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Parse a multipart/mixed batch response into responses in request order.
+pub fn parse_response(content_type: &str, body: &str) -> Result<Vec<BatchResponse>, SapError> {
+    let boundary = boundary(content_type)
+        .ok_or_else(|| SapError::ODataError(format!("Batch response without boundary: {}", content_type)))?;
+    let body = body.replace("\r\n", "\n");
+    let mut responses = Vec::new();
+    parse_multipart(&boundary, &body, &mut responses)?;
+    Ok(responses)
+}
+
+fn boundary(content_type: &str) -> Option<String> {
+    content_type.split(';')
+        .filter_map(|p| p.trim().split_once('='))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, v)| v.trim().trim_matches('"').to_string())
+}
+
+fn parse_multipart(boundary: &str, body: &str, out: &mut Vec<BatchResponse>) -> Result<(), SapError> {
+    let delimiter = format!("--{}", boundary);
+    for part in body.split(delimiter.as_str()).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+        let (headers, content) = split_head(part.trim_start_matches('\n'));
+        let headers = parse_headers(headers);
+        let content_type = header(&headers, "Content-Type").unwrap_or_default();
+
+        if content_type.starts_with("multipart/mixed") {
+            let inner = boundary_of(content_type)?;
+            parse_multipart(&inner, content, out)?;
+            continue;
+        }
+
+        let (head, payload) = split_head(content);
+        let mut lines = head.lines();
+        let status = lines.next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| SapError::ODataError("Batch part without HTTP status line".into()))?;
+        let response_headers = parse_headers(&lines.collect::<Vec<_>>().join("\n"));
+        let payload = payload.trim();
+
+        out.push(BatchResponse {
+            content_id: header(&headers, "Content-ID")
+                .or_else(|| header(&response_headers, "Content-ID"))
+                .map(String::from),
+            status,
+            body: (!payload.is_empty()).then(|| {
+                serde_json::from_str(payload).unwrap_or_else(|_| Value::String(payload.to_string()))
+            }),
+            headers: response_headers,
+        });
+    }
+    Ok(())
+}
+
+fn boundary_of(content_type: &str) -> Result<String, SapError> {
+    boundary(content_type)
+        .ok_or_else(|| SapError::ODataError(format!("Change set without boundary: {}", content_type)))
+}
+
+/// Split at the first blank line.
+fn split_head(part: &str) -> (&str, &str) {
+    part.split_once("\n\n").unwrap_or((part, ""))
+}
+
+fn parse_headers(block: &str) -> Vec<(String, String)> {
+    block.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_and_parse() {
+        let batch = BatchRequest::new()
+            .get("SalesOrder('1')")
+            .changeset(ChangeSet::new()
+                .post("SalesOrder", json!({"SalesOrderType": "OR"}))
+                .delete("SalesOrder('2')"));
+        let body = batch.encode();
+        assert!(batch.content_type().starts_with("multipart/mixed;boundary=batch_"));
+        assert!(body.contains("GET SalesOrder('1') HTTP/1.1"));
+        assert!(body.contains("Content-ID: 2\r\n\r\nDELETE SalesOrder('2') HTTP/1.1"));
+
+        let response = "--batch_r\r\n\
+Content-Type: application/http\r\n\r\n\
+HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n\
+{\"SalesOrder\":\"1\"}\r\n\
+--batch_r\r\n\
+Content-Type: multipart/mixed; boundary=changeset_r\r\n\r\n\
+--changeset_r\r\n\
+Content-Type: application/http\r\nContent-ID: 1\r\n\r\n\
+HTTP/1.1 201 Created\r\n\r\n\
+{\"SalesOrder\":\"3\"}\r\n\
+--changeset_r\r\n\
+Content-Type: application/http\r\nContent-ID: 2\r\n\r\n\
+HTTP/1.1 204 No Content\r\n\r\n\r\n\
+--changeset_r--\r\n\
+--batch_r--\r\n";
+        let parsed = parse_response("multipart/mixed; boundary=batch_r", response).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].body, Some(json!({"SalesOrder": "1"})));
+        assert_eq!((parsed[1].content_id.as_deref(), parsed[1].status), (Some("1"), 201));
+        assert_eq!((parsed[2].status, parsed[2].body.clone()), (204, None));
+    }
+}
@@ -0,0 +1,271 @@
+//! OData V4 service metadata ($metadata / CSDL)
+//!
+//! Entity types from the service document drive key predicates
+//! (`('1000')` vs `(1000)` vs `(CompanyCode='1010',FiscalYear='2025')`) and
+//! decoding of payloads into typed structs.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::super::SapError;
+
+/// Structural property of an entity type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: String,
+    /// Primitive or qualified complex type, e.g. `Edm.String`
+    pub edm_type: String,
+    pub nullable: bool,
+    pub max_length: Option<u32>,
+}
+
+/// Navigation property to another entity type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationProperty {
+    pub name: String,
+    /// Qualified target type
+    pub target: String,
+    pub collection: bool,
+}
+
+/// Entity type with its key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityType {
+    pub name: String,
+    pub keys: Vec<String>,
+    pub properties: Vec<Property>,
+    pub navigation: Vec<NavigationProperty>,
+}
+
+impl EntityType {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Key predicate for an entity, e.g. `('1000')` or `(A='1',B=2)`.
+    ///
+    /// `key` is either the key value itself (single-key types) or an object
+    /// holding the key properties, such as a previously read entity.
+    pub fn key_predicate(&self, key: &Value) -> Result<String, SapError> {
+        let value_of = |name: &str| match key {
+            Value::Object(map) => map.get(name),
+            _ if self.keys.len() == 1 => Some(key),
+            _ => None,
+        };
+
+        let mut parts = Vec::with_capacity(self.keys.len());
+        for name in &self.keys {
+            let value = value_of(name)
+                .ok_or_else(|| SapError::ODataError(format!("Missing key property {} of {}", name, self.name)))?;
+            let edm_type = self.property(name).map_or("Edm.String", |p| p.edm_type.as_str());
+            parts.push((name, literal(edm_type, value)?));
+        }
+
+        Ok(match parts.as_slice() {
+            [(_, literal)] => format!("({})", literal),
+            _ => format!("({})", parts.iter()
+                .map(|(name, literal)| format!("{}={}", name, literal))
+                .collect::<Vec<_>>()
+                .join(",")),
+        })
+    }
+}
+
+/// Parsed service metadata.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceMetadata {
+    /// Entity types by qualified name
+    pub entity_types: HashMap<String, EntityType>,
+    /// Entity set name to qualified entity type
+    pub entity_sets: HashMap<String, String>,
+}
+
+impl ServiceMetadata {
+    /// Parse a CSDL (EDMX) document.
+    pub fn parse(xml: &str) -> Result<Self, SapError> {
+        let doc = roxmltree::Document::parse(xml)
+            .map_err(|e| SapError::ODataError(format!("Invalid $metadata: {}", e)))?;
+        let mut metadata = Self::default();
+
+        for schema in doc.descendants().filter(|n| n.has_tag_name("Schema")) {
+            let namespace = schema.attribute("Namespace").unwrap_or_default();
+            // Types may be referenced by alias instead of namespace
+            let alias = schema.attribute("Alias");
+            let qualify = |name: &str| match alias {
+                Some(alias) => name.strip_prefix(alias)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .map_or_else(|| name.to_string(), |rest| format!("{}.{}", namespace, rest)),
+                None => name.to_string(),
+            };
+
+            for node in schema.children().filter(|n| n.has_tag_name("EntityType")) {
+                let name = node.attribute("Name").unwrap_or_default().to_string();
+                let child = |tag: &'static str| node.children().filter(move |n| n.has_tag_name(tag));
+                let keys = child("Key")
+                    .flat_map(|k| k.children().filter(|n| n.has_tag_name("PropertyRef")))
+                    .filter_map(|r| r.attribute("Name").map(String::from))
+                    .collect();
+                let properties = child("Property")
+                    .map(|p| Property {
+                        name: p.attribute("Name").unwrap_or_default().to_string(),
+                        edm_type: qualify(p.attribute("Type").unwrap_or("Edm.String")),
+                        nullable: p.attribute("Nullable") != Some("false"),
+                        max_length: p.attribute("MaxLength").and_then(|m| m.parse().ok()),
+                    })
+                    .collect();
+                let navigation = child("NavigationProperty")
+                    .map(|p| {
+                        let ty = p.attribute("Type").unwrap_or_default();
+                        let inner = ty.strip_prefix("Collection(").and_then(|t| t.strip_suffix(')'));
+                        NavigationProperty {
+                            name: p.attribute("Name").unwrap_or_default().to_string(),
+                            target: qualify(inner.unwrap_or(ty)),
+                            collection: inner.is_some(),
+                        }
+                    })
+                    .collect();
+                let entity_type = EntityType { name: name.clone(), keys, properties, navigation };
+                metadata.entity_types.insert(format!("{}.{}", namespace, name), entity_type);
+            }
+
+            for set in schema.descendants().filter(|n| n.has_tag_name("EntitySet")) {
+                if let (Some(name), Some(ty)) = (set.attribute("Name"), set.attribute("EntityType")) {
+                    metadata.entity_sets.insert(name.to_string(), qualify(ty));
+                }
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Entity type of an entity set.
+    pub fn entity_type(&self, entity_set: &str) -> Result<&EntityType, SapError> {
+        self.entity_sets.get(entity_set)
+            .and_then(|ty| self.entity_types.get(ty))
+            .ok_or_else(|| SapError::ODataError(format!("Unknown entity set: {}", entity_set)))
+    }
+
+    /// Coerce an entity of `entity_set` to its declared types.
+    ///
+    /// Services asked for `IEEE754Compatible` send Int64 and Double values
+    /// as strings; those become JSON numbers so they map onto numeric
+    /// fields. Edm.Decimal stays as sent to keep amounts exact. Expanded
+    /// navigation properties are decoded recursively.
+    pub fn decode(&self, entity_set: &str, entity: Value) -> Result<Value, SapError> {
+        let qualified = self.entity_sets.get(entity_set)
+            .ok_or_else(|| SapError::ODataError(format!("Unknown entity set: {}", entity_set)))?;
+        Ok(self.decode_type(qualified, entity))
+    }
+
+    fn decode_type(&self, qualified: &str, entity: Value) -> Value {
+        let (Some(ty), Value::Object(mut map)) = (self.entity_types.get(qualified), entity) else {
+            return Value::Null;
+        };
+        for property in &ty.properties {
+            if let Some(value) = map.get_mut(&property.name) {
+                coerce(&property.edm_type, value);
+            }
+        }
+        for nav in &ty.navigation {
+            match map.remove(&nav.name) {
+                Some(Value::Array(items)) => {
+                    let items = items.into_iter().map(|item| self.decode_type(&nav.target, item)).collect();
+                    map.insert(nav.name.clone(), Value::Array(items));
+                }
+                Some(item @ Value::Object(_)) => {
+                    map.insert(nav.name.clone(), self.decode_type(&nav.target, item));
+                }
+                Some(other) => {
+                    map.insert(nav.name.clone(), other);
+                }
+                None => {}
+            }
+        }
+        Value::Object(map)
+    }
+}
+
+/// URL literal for a key value of the given type.
+fn literal(edm_type: &str, value: &Value) -> Result<String, SapError> {
+    let raw = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        other => return Err(SapError::ODataError(format!("Unsupported key value: {}", other))),
+    };
+    Ok(match edm_type {
+        "Edm.String" => format!("'{}'", raw.replace('\'', "''")),
+        // Guid, numeric, boolean and date/time literals are unquoted in V4
+        _ => raw,
+    })
+}
+
+fn coerce(edm_type: &str, value: &mut Value) {
+    let Value::String(s) = value else { return };
+    let number = match edm_type {
+        "Edm.Int64" | "Edm.Int32" | "Edm.Int16" | "Edm.Byte" | "Edm.SByte" => {
+            s.parse::<i64>().ok().map(Value::from)
+        }
+        "Edm.Double" | "Edm.Single" => s.parse::<f64>().ok().map(Value::from),
+        _ => None,
+    };
+    if let Some(number) = number {
+        *value = number;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    pub const METADATA: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<edmx:Edmx Version="4.0" xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx">
+  <edmx:DataServices>
+    <Schema Namespace="com.sap.gateway.srvd.api_salesorder.v0001" Alias="SAP__self" xmlns="http://docs.oasis-open.org/odata/ns/edm">
+      <EntityType Name="SalesOrderType">
+        <Key><PropertyRef Name="SalesOrder"/></Key>
+        <Property Name="SalesOrder" Type="Edm.String" Nullable="false" MaxLength="10"/>
+        <Property Name="TotalNetAmount" Type="Edm.Decimal" Precision="15" Scale="3"/>
+        <Property Name="ItemCount" Type="Edm.Int64"/>
+        <NavigationProperty Name="_Item" Type="Collection(SAP__self.SalesOrderItemType)"/>
+      </EntityType>
+      <EntityType Name="SalesOrderItemType">
+        <Key><PropertyRef Name="SalesOrder"/><PropertyRef Name="SalesOrderItem"/></Key>
+        <Property Name="SalesOrder" Type="Edm.String" Nullable="false"/>
+        <Property Name="SalesOrderItem" Type="Edm.Int32" Nullable="false"/>
+        <Property Name="RequestedQuantity" Type="Edm.Double"/>
+      </EntityType>
+      <EntityContainer Name="Container">
+        <EntitySet Name="SalesOrder" EntityType="SAP__self.SalesOrderType"/>
+        <EntitySet Name="SalesOrderItem" EntityType="SAP__self.SalesOrderItemType"/>
+      </EntityContainer>
+    </Schema>
+  </edmx:DataServices>
+</edmx:Edmx>"#;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keys_and_decoding() {
+        let metadata = ServiceMetadata::parse(fixtures::METADATA).unwrap();
+        let order = metadata.entity_type("SalesOrder").unwrap();
+        assert_eq!(order.key_predicate(&json!("O'1")).unwrap(), "('O''1')");
+
+        let item = metadata.entity_type("SalesOrderItem").unwrap();
+        let key = json!({"SalesOrder": "1", "SalesOrderItem": 10, "RequestedQuantity": "2"});
+        assert_eq!(item.key_predicate(&key).unwrap(), "(SalesOrder='1',SalesOrderItem=10)");
+        assert!(item.key_predicate(&json!("1")).is_err());
+
+        let decoded = metadata.decode("SalesOrder", json!({
+            "SalesOrder": "1",
+            "TotalNetAmount": "100.10",
+            "ItemCount": "2",
+            "_Item": [{"SalesOrder": "1", "SalesOrderItem": 10, "RequestedQuantity": "1.5"}]
+        })).unwrap();
+        assert_eq!(decoded["TotalNetAmount"], json!("100.10"));
+        assert_eq!(decoded["ItemCount"], json!(2));
+        assert_eq!(decoded["_Item"][0]["RequestedQuantity"], json!(1.5));
+    }
+}
@@ -0,0 +1,603 @@
+//! SAP OData Client
+//!
+//! OData v4 for S/4HANA Cloud and On-Premise:
+//! - CSRF token fetch and refresh for modifying requests
+//! - `$batch` with atomic change sets
+//! - Server-driven paging (`@odata.nextLink`)
+//! - Change tracking via delta links
+//! - Typed entities using `$metadata`
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let client = ODataClient::new(service_url, auth)?.with_sap_client("100");
+//!
+//! let orders: Vec<SalesOrder> = client.query_as("SalesOrder",
+//!     &ODataQuery::new().with_filter("SalesOrderType eq 'OR'").with_top(500))?;
+//!
+//! // First call returns everything, later calls only what changed
+//! let changes = client.track_changes("SalesOrder", &ODataQuery::new())?;
+//! ```
+
+mod batch;
+mod metadata;
+mod transport;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use super::{ODataAuth, SapError};
+
+pub use batch::{parse_response, BatchOperation, BatchRequest, BatchResponse, ChangeSet};
+pub use metadata::{EntityType, NavigationProperty, Property, ServiceMetadata};
+pub use transport::{HttpRequest, HttpResponse, ODataTransport, ReqwestTransport};
+
+/// Session state SAP binds the CSRF token to.
+#[derive(Default)]
+struct Session {
+    csrf_token: Option<String>,
+    cookies: BTreeMap<String, String>,
+}
+
+/// OData client for SAP S/4HANA.
+pub struct ODataClient {
+    base_url: String,
+    sap_client: Option<String>,
+    page_size: Option<u32>,
+    transport: Arc<dyn ODataTransport>,
+    session: Mutex<Session>,
+    metadata: Mutex<Option<Arc<ServiceMetadata>>>,
+    delta_links: Mutex<HashMap<String, String>>,
+}
+
+impl ODataClient {
+    /// Create new OData client for a service root URL.
+    pub fn new(base_url: &str, auth: ODataAuth) -> Result<Self, SapError> {
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(SapError::ODataError(format!("Invalid service URL: {}", base_url)));
+        }
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            sap_client: None,
+            page_size: None,
+            transport: Arc::new(ReqwestTransport::new(auth)),
+            session: Mutex::new(Session::default()),
+            metadata: Mutex::new(None),
+            delta_links: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Use another transport (proxies, tests).
+    pub fn with_transport(mut self, transport: Arc<dyn ODataTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// SAP client (mandant) sent as `sap-client` on every request.
+    pub fn with_sap_client(mut self, client: &str) -> Self {
+        self.sap_client = Some(client.to_string());
+        self
+    }
+
+    /// Preferred page size for server-driven paging.
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// GET entity by key.
+    pub fn get(&self, entity_set: &str, key: &str) -> Result<Value, SapError> {
+        let path = format!("{}('{}')", entity_set, key.replace('\'', "''"));
+        self.send_json(HttpRequest::new("GET", self.url(&path)))
+    }
+
+    /// POST new entity; returns the created entity.
+    pub fn post(&self, entity_set: &str, data: Value) -> Result<Value, SapError> {
+        let request = HttpRequest::new("POST", self.url(entity_set))
+            .with_header("Content-Type", "application/json")
+            .with_body(data.to_string());
+        let created = self.send_json(request)?;
+        // Services answering `return=minimal` send no body
+        Ok(if created.is_null() { data } else { created })
+    }
+
+    /// PATCH entity.
+    pub fn patch(&self, entity_set: &str, key: &str, data: Value) -> Result<(), SapError> {
+        let path = format!("{}('{}')", entity_set, key.replace('\'', "''"));
+        let request = HttpRequest::new("PATCH", self.url(&path))
+            .with_header("Content-Type", "application/json")
+            .with_body(data.to_string());
+        self.send(request)?;
+        Ok(())
+    }
+
+    /// DELETE entity.
+    pub fn delete(&self, entity_set: &str, key: &str) -> Result<(), SapError> {
+        let path = format!("{}('{}')", entity_set, key.replace('\'', "''"));
+        self.send(HttpRequest::new("DELETE", self.url(&path)))?;
+        Ok(())
+    }
+
+    /// Query with OData filters (first page).
+    pub fn query(&self, entity_set: &str, filter: Option<&str>, top: Option<u32>) -> Result<ODataResponse, SapError> {
+        let mut query = ODataQuery::new();
+        if let Some(f) = filter {
+            query = query.with_filter(f);
+        }
+        if let Some(t) = top {
+            query = query.with_top(t);
+        }
+        self.query_page(entity_set, &query)
+    }
+
+    /// First page of a query; follow `next_link` with [`Self::next_page`].
+    pub fn query_page(&self, entity_set: &str, query: &ODataQuery) -> Result<ODataResponse, SapError> {
+        let path = format!("{}{}", entity_set, query.to_query_string());
+        self.fetch_page(HttpRequest::new("GET", self.url(&path)))
+    }
+
+    /// Next page of a paged response, if any.
+    pub fn next_page(&self, response: &ODataResponse) -> Option<Result<ODataResponse, SapError>> {
+        let link = response.next_link.as_deref()?;
+        Some(self.fetch_page(HttpRequest::new("GET", self.url(link))))
+    }
+
+    /// All entities matching a query, following server-driven paging.
+    pub fn query_all(&self, entity_set: &str, query: &ODataQuery) -> Result<Vec<Value>, SapError> {
+        let mut page = self.query_page(entity_set, query)?;
+        let mut entities = std::mem::take(&mut page.value);
+        while let Some(next) = self.next_page(&page) {
+            page = next?;
+            entities.append(&mut page.value);
+        }
+        Ok(entities)
+    }
+
+    /// Entities changed since the last call for this entity set.
+    ///
+    /// The first call (or one after [`Self::reset_delta`]) returns the full
+    /// result of `query` and starts tracking; later calls follow the stored
+    /// delta link and ignore `query`.
+    pub fn track_changes(&self, entity_set: &str, query: &ODataQuery) -> Result<DeltaResult, SapError> {
+        let request = match self.delta_link(entity_set) {
+            Some(link) => HttpRequest::new("GET", self.url(&link)),
+            None => {
+                let path = format!("{}{}", entity_set, query.to_query_string());
+                HttpRequest::new("GET", self.url(&path)).with_header("Prefer", "odata.track-changes")
+            }
+        };
+
+        let mut result = DeltaResult::default();
+        let mut page = self.fetch_page(request)?;
+        loop {
+            for entity in page.value.drain(..) {
+                if entity.get("@removed").is_some() || entity.get("@odata.removed").is_some() {
+                    result.removed.push(entity);
+                } else {
+                    result.changed.push(entity);
+                }
+            }
+            match self.next_page(&page) {
+                Some(next) => page = next?,
+                None => break,
+            }
+        }
+
+        let link = page.delta_link
+            .ok_or_else(|| SapError::ODataError(format!("{} does not support change tracking", entity_set)))?;
+        self.delta_links.lock().unwrap().insert(entity_set.to_string(), link.clone());
+        result.delta_link = link;
+        Ok(result)
+    }
+
+    /// Stored delta link of an entity set.
+    pub fn delta_link(&self, entity_set: &str) -> Option<String> {
+        self.delta_links.lock().unwrap().get(entity_set).cloned()
+    }
+
+    /// Resume change tracking from a persisted delta link.
+    pub fn set_delta_link(&self, entity_set: &str, link: &str) {
+        self.delta_links.lock().unwrap().insert(entity_set.to_string(), link.to_string());
+    }
+
+    /// Stop change tracking; the next call starts from a full read.
+    pub fn reset_delta(&self, entity_set: &str) {
+        self.delta_links.lock().unwrap().remove(entity_set);
+    }
+
+    /// Execute a `$batch` request.
+    pub fn batch(&self, batch: &BatchRequest) -> Result<Vec<BatchResponse>, SapError> {
+        if batch.is_empty() {
+            return Ok(Vec::new());
+        }
+        let request = HttpRequest::new("POST", self.url("$batch"))
+            .with_header("Content-Type", batch.content_type())
+            .with_header("Accept", "multipart/mixed")
+            .with_body(batch.encode());
+        let response = self.send(request)?;
+        let content_type = response.header("Content-Type").unwrap_or_default();
+        parse_response(content_type, &response.body)
+    }
+
+    /// Get service metadata (fetched once, then cached).
+    pub fn metadata(&self) -> Result<Arc<ServiceMetadata>, SapError> {
+        if let Some(metadata) = self.metadata.lock().unwrap().as_ref() {
+            return Ok(metadata.clone());
+        }
+        let request = HttpRequest::new("GET", self.url("$metadata"))
+            .with_header("Accept", "application/xml");
+        let metadata = Arc::new(ServiceMetadata::parse(&self.send(request)?.body)?);
+        *self.metadata.lock().unwrap() = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Read an entity into a typed struct.
+    ///
+    /// `key` is the key value, or an object with all key properties for
+    /// composite keys; the predicate is formatted from `$metadata`.
+    pub fn get_as<T: DeserializeOwned>(&self, entity_set: &str, key: &Value) -> Result<T, SapError> {
+        let metadata = self.metadata()?;
+        let predicate = metadata.entity_type(entity_set)?.key_predicate(key)?;
+        let entity = self.send_json(HttpRequest::new("GET", self.url(&format!("{}{}", entity_set, predicate))))?;
+        decode_as(&metadata, entity_set, entity)
+    }
+
+    /// Run a query across all pages into typed structs.
+    pub fn query_as<T: DeserializeOwned>(&self, entity_set: &str, query: &ODataQuery) -> Result<Vec<T>, SapError> {
+        let metadata = self.metadata()?;
+        self.query_all(entity_set, query)?
+            .into_iter()
+            .map(|entity| decode_as(&metadata, entity_set, entity))
+            .collect()
+    }
+
+    /// Absolute URL for a service-relative path or a server-provided link.
+    fn url(&self, path: &str) -> String {
+        let mut url = if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+        };
+        if let Some(client) = &self.sap_client {
+            if !url.contains("sap-client=") {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                url.push_str(&format!("sap-client={}", client));
+            }
+        }
+        url
+    }
+
+    fn fetch_page(&self, mut request: HttpRequest) -> Result<ODataResponse, SapError> {
+        if let Some(size) = self.page_size {
+            let prefer = match request.header("Prefer") {
+                Some(p) => format!("{},odata.maxpagesize={}", p, size),
+                None => format!("odata.maxpagesize={}", size),
+            };
+            request.headers.retain(|(k, _)| !k.eq_ignore_ascii_case("Prefer"));
+            request = request.with_header("Prefer", prefer);
+        }
+        ODataResponse::from_json(self.send_json(request)?)
+    }
+
+    fn send_json(&self, request: HttpRequest) -> Result<Value, SapError> {
+        let response = self.send(request)?;
+        if response.body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&response.body).map_err(|e| SapError::ODataError(format!("Invalid JSON response: {}", e)))
+    }
+
+    /// Send with session cookies and, for modifying requests, a CSRF token.
+    ///
+    /// A rejected token (`403` with `X-CSRF-Token: Required`) is fetched
+    /// again and the request retried once.
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse, SapError> {
+        let modifying = !matches!(request.method.as_str(), "GET" | "HEAD");
+        let mut retried = false;
+        loop {
+            let mut attempt = request.clone();
+            if attempt.header("Accept").is_none() {
+                attempt = attempt.with_header("Accept", "application/json");
+            }
+            if modifying {
+                attempt = attempt.with_header("X-CSRF-Token", self.csrf_token()?);
+            }
+            let response = self.execute(attempt)?;
+
+            let token_rejected = response.status == 403
+                && response.header("X-CSRF-Token").is_some_and(|v| v.eq_ignore_ascii_case("required"));
+            if modifying && token_rejected && !retried {
+                self.session.lock().unwrap().csrf_token = None;
+                retried = true;
+                continue;
+            }
+            if !response.is_success() {
+                return Err(error_from(&response));
+            }
+            return Ok(response);
+        }
+    }
+
+    /// Execute with session cookies, keeping cookies the server sets.
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse, SapError> {
+        let cookies = {
+            let session = self.session.lock().unwrap();
+            session.cookies.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("; ")
+        };
+        let request = if cookies.is_empty() { request } else { request.with_header("Cookie", cookies) };
+        let response = self.transport.execute(&request)?;
+
+        let mut session = self.session.lock().unwrap();
+        for cookie in response.header_values("Set-Cookie") {
+            let pair = cookie.split(';').next().unwrap_or_default();
+            if let Some((name, value)) = pair.split_once('=') {
+                session.cookies.insert(name.trim().to_string(), value.trim().to_string());
+            }
+        }
+        Ok(response)
+    }
+
+    fn csrf_token(&self) -> Result<String, SapError> {
+        if let Some(token) = self.session.lock().unwrap().csrf_token.clone() {
+            return Ok(token);
+        }
+        // The service root is cheap and always readable
+        let request = HttpRequest::new("GET", self.url(""))
+            .with_header("X-CSRF-Token", "Fetch")
+            .with_header("Accept", "application/json");
+        let response = self.execute(request)?;
+        let token = response.header("X-CSRF-Token")
+            .filter(|t| !t.eq_ignore_ascii_case("required"))
+            .ok_or_else(|| SapError::ODataError(format!("No CSRF token issued (HTTP {})", response.status)))?
+            .to_string();
+        self.session.lock().unwrap().csrf_token = Some(token.clone());
+        Ok(token)
+    }
+}
+
+fn decode_as<T: DeserializeOwned>(metadata: &ServiceMetadata, entity_set: &str, entity: Value) -> Result<T, SapError> {
+    serde_json::from_value(metadata.decode(entity_set, entity)?)
+        .map_err(|e| SapError::ODataError(format!("Cannot map {} entity: {}", entity_set, e)))
+}
+
+/// Error from an OData error body (`{"error": {"code", "message"}}`).
+fn error_from(response: &HttpResponse) -> SapError {
+    let body: Value = serde_json::from_str(&response.body).unwrap_or_default();
+    let error = &body["error"];
+    // V2 services nest the text in message.value
+    let message = error["message"].as_str()
+        .or_else(|| error["message"]["value"].as_str())
+        .unwrap_or_else(|| response.body.trim());
+    match error["code"].as_str() {
+        Some(code) => SapError::ODataError(format!("HTTP {} {}: {}", response.status, code, message)),
+        None => SapError::ODataError(format!("HTTP {}: {}", response.status, message)),
+    }
+}
+
+/// Query options for collection reads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ODataQuery {
+    filter: Option<String>,
+    select: Vec<String>,
+    expand: Vec<String>,
+    orderby: Option<String>,
+    top: Option<u32>,
+    skip: Option<u32>,
+    count: bool,
+}
+
+impl ODataQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `$filter` expression, e.g. `CompanyCode eq '1010'`.
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    pub fn with_select(mut self, properties: &[&str]) -> Self {
+        self.select = properties.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    pub fn with_expand(mut self, navigation: &[&str]) -> Self {
+        self.expand = navigation.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// `$orderby`, e.g. `CreationDate desc`.
+    pub fn with_orderby(mut self, orderby: &str) -> Self {
+        self.orderby = Some(orderby.to_string());
+        self
+    }
+
+    pub fn with_top(mut self, top: u32) -> Self {
+        self.top = Some(top);
+        self
+    }
+
+    pub fn with_skip(mut self, skip: u32) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Request `@odata.count`.
+    pub fn with_count(mut self) -> Self {
+        self.count = true;
+        self
+    }
+
+    /// Encoded query string including the leading `?`, or empty.
+    pub fn to_query_string(&self) -> String {
+        let mut params = vec![];
+        if let Some(f) = &self.filter {
+            params.push(format!("$filter={}", encode(f)));
+        }
+        if !self.select.is_empty() {
+            params.push(format!("$select={}", encode(&self.select.join(","))));
+        }
+        if !self.expand.is_empty() {
+            params.push(format!("$expand={}", encode(&self.expand.join(","))));
+        }
+        if let Some(o) = &self.orderby {
+            params.push(format!("$orderby={}", encode(o)));
+        }
+        if let Some(t) = self.top {
+            params.push(format!("$top={}", t));
+        }
+        if let Some(s) = self.skip {
+            params.push(format!("$skip={}", s));
+        }
+        if self.count {
+            params.push("$count=true".to_string());
+        }
+
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
+/// Percent-encode a query option value.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+            | b'-' | b'.' | b'_' | b'~' | b'\'' | b'(' | b')' | b',' | b':' | b'/' | b'*' | b'$' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// OData query response.
+#[derive(Debug, Clone)]
+pub struct ODataResponse {
+    pub context: String,
+    pub count: Option<u64>,
+    pub value: Vec<serde_json::Value>,
+    /// Next page of a server-paged result
+    pub next_link: Option<String>,
+    /// Link returning changes after this result
+    pub delta_link: Option<String>,
+}
+
+impl ODataResponse {
+    fn from_json(body: Value) -> Result<Self, SapError> {
+        let Value::Object(mut map) = body else {
+            return Err(SapError::ODataError("Collection response is not a JSON object".into()));
+        };
+        let text = |map: &serde_json::Map<String, Value>, key: &str| map.get(key).and_then(Value::as_str).map(String::from);
+        let value = match map.remove("value") {
+            Some(Value::Array(items)) => items,
+            _ => return Err(SapError::ODataError("Collection response without value array".into())),
+        };
+        Ok(Self {
+            context: text(&map, "@odata.context").unwrap_or_default(),
+            count: map.get("@odata.count").and_then(|c| c.as_u64().or_else(|| c.as_str()?.parse().ok())),
+            value,
+            next_link: text(&map, "@odata.nextLink"),
+            delta_link: text(&map, "@odata.deltaLink"),
+        })
+    }
+}
+
+/// Changes reported by a delta query.
+#[derive(Debug, Clone, Default)]
+pub struct DeltaResult {
+    /// New or updated entities
+    pub changed: Vec<Value>,
+    /// Deleted entities (`@removed` entries with their key or `@id`)
+    pub removed: Vec<Value>,
+    /// Link for the next call
+    pub delta_link: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::secrets::SecretRef;
+    use serde_json::json;
+    use std::collections::VecDeque;
+
+    /// Replays canned responses and records requests.
+    #[derive(Default)]
+    struct Scripted {
+        responses: Mutex<VecDeque<HttpResponse>>,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl Scripted {
+        fn reply(self, status: u16, headers: &[(&str, &str)], body: &str) -> Self {
+            self.responses.lock().unwrap().push_back(HttpResponse {
+                status,
+                headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                body: body.to_string(),
+            });
+            self
+        }
+    }
+
+    impl ODataTransport for Scripted {
+        fn execute(&self, request: &HttpRequest) -> Result<HttpResponse, SapError> {
+            self.requests.lock().unwrap().push(request.clone());
+            self.responses.lock().unwrap().pop_front()
+                .ok_or_else(|| SapError::ODataError("no scripted response".into()))
+        }
+    }
+
+    fn client(transport: &Arc<Scripted>) -> ODataClient {
+        ODataClient::new("https://s4.example.com/sap/opu/odata4/sap/api_salesorder/srvd_a2x/sap/salesorder/0001/", ODataAuth::Basic {
+            username: "user".into(),
+            password: SecretRef::Env("SAP_ODATA_PASSWORD".into()),
+        }).unwrap().with_sap_client("100").with_transport(transport.clone())
+    }
+
+    #[test]
+    fn test_csrf_token_refetched_when_expired() {
+        let transport = Arc::new(Scripted::default()
+            .reply(200, &[("x-csrf-token", "T1"), ("set-cookie", "SAP_SESSIONID=abc; path=/")], "{}")
+            .reply(403, &[("x-csrf-token", "Required")], "")
+            .reply(200, &[("x-csrf-token", "T2")], "{}")
+            .reply(201, &[], r#"{"SalesOrder":"1"}"#));
+        let created = client(&transport).post("SalesOrder", json!({"SalesOrderType": "OR"})).unwrap();
+        assert_eq!(created["SalesOrder"], "1");
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].header("X-CSRF-Token"), Some("Fetch"));
+        assert!(requests[0].url.ends_with("/0001/?sap-client=100"));
+        assert_eq!(requests[1].header("X-CSRF-Token"), Some("T1"));
+        assert_eq!(requests[1].header("Cookie"), Some("SAP_SESSIONID=abc"));
+        assert_eq!(requests[3].header("X-CSRF-Token"), Some("T2"));
+    }
+
+    #[test]
+    fn test_paging_and_delta_tracking() {
+        let base = "https://s4.example.com/sap/opu/odata4/sap/api_salesorder/srvd_a2x/sap/salesorder/0001";
+        let transport = Arc::new(Scripted::default()
+            .reply(200, &[], r#"{"value":[{"SalesOrder":"1"}],"@odata.nextLink":"SalesOrder?$skiptoken=1"}"#)
+            .reply(200, &[], &format!(r#"{{"value":[{{"SalesOrder":"2"}}],"@odata.deltaLink":"{}/SalesOrder?!deltatoken='D1'"}}"#, base))
+            .reply(200, &[], r#"{"value":[{"SalesOrder":"2","@removed":{"reason":"deleted"}},{"SalesOrder":"3"}],"@odata.deltaLink":"SalesOrder?!deltatoken='D2'"}"#));
+        let client = client(&transport).with_page_size(1);
+
+        let initial = client.track_changes("SalesOrder", &ODataQuery::new().with_filter("SalesOrderType eq 'OR'")).unwrap();
+        assert_eq!(initial.changed.len(), 2);
+        let changes = client.track_changes("SalesOrder", &ODataQuery::new()).unwrap();
+        assert_eq!((changes.changed[0]["SalesOrder"].as_str(), changes.removed.len()), (Some("3"), 1));
+        assert_eq!(client.delta_link("SalesOrder").as_deref(), Some("SalesOrder?!deltatoken='D2'"));
+
+        let requests = transport.requests.lock().unwrap();
+        assert!(requests[0].url.ends_with("/SalesOrder?$filter=SalesOrderType%20eq%20'OR'&sap-client=100"));
+        assert_eq!(requests[0].header("Prefer"), Some("odata.track-changes,odata.maxpagesize=1"));
+        assert!(requests[1].url.ends_with("/SalesOrder?$skiptoken=1&sap-client=100"));
+        assert_eq!(requests[2].url, format!("{}/SalesOrder?!deltatoken='D1'&sap-client=100", base));
+    }
+}
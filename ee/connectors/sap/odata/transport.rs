@@ -0,0 +1,193 @@
+//! HTTP transport for OData requests
+//!
+//! The transport owns authentication (Basic, OAuth 2.0 client credentials,
+//! client certificate); the client on top only deals with OData concerns
+//! such as CSRF tokens, paging and batching. Credentials are resolved when
+//! they are needed: the certificate when connecting, the client secret per
+//! token and the Basic password per request.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+use super::super::{ODataAuth, SapError};
+use super::super::super::secrets::SecretRef;
+
+/// Renew OAuth tokens this long before they expire.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Outgoing HTTP request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    /// Create request without headers or body.
+    pub fn new(method: &str, url: impl Into<String>) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Add a header.
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// Set the body.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// First header with this name (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// HTTP response as read by the transport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// First header with this name (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// All values of a repeated header (e.g. `Set-Cookie`).
+    pub fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers.iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Executes authenticated HTTP requests.
+pub trait ODataTransport: Send + Sync {
+    fn execute(&self, request: &HttpRequest) -> Result<HttpResponse, SapError>;
+}
+
+/// Blocking reqwest transport.
+pub struct ReqwestTransport {
+    auth: ODataAuth,
+    timeout: Duration,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl ReqwestTransport {
+    /// Create transport; connections are opened on first use.
+    pub fn new(auth: ODataAuth) -> Self {
+        Self {
+            auth,
+            timeout: Duration::from_secs(60),
+            http: OnceLock::new(),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Request timeout (default 60 seconds).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn http(&self) -> Result<&reqwest::blocking::Client, SapError> {
+        self.http
+            .get_or_init(|| {
+                let mut builder = reqwest::blocking::Client::builder().timeout(self.timeout);
+                if let ODataAuth::Certificate { cert, key } = &self.auth {
+                    let (cert, key) = (cert.resolve().map_err(|e| e.to_string())?, key.resolve().map_err(|e| e.to_string())?);
+                    let pem = Zeroizing::new([cert.expose(), b"\n", key.expose()].concat());
+                    let identity = reqwest::Identity::from_pem(&pem).map_err(|e| e.to_string())?;
+                    builder = builder.identity(identity);
+                }
+                builder.build().map_err(|e| e.to_string())
+            })
+            .as_ref()
+            .map_err(|e| SapError::ODataError(e.clone()))
+    }
+
+    /// Cached client credentials token, renewed shortly before expiry.
+    fn access_token(&self, client_id: &str, client_secret: &SecretRef, token_url: &str) -> Result<String, SapError> {
+        let mut cached = self.token.lock().unwrap();
+        if let Some((token, expires)) = cached.as_ref() {
+            if Instant::now() + TOKEN_MARGIN < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let body: serde_json::Value = self.http()?
+            .post(token_url)
+            .basic_auth(client_id, Some(client_secret.resolve()?.expose_str()?))
+            .form(&[("grant_type", "client_credentials")])
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| SapError::ODataError(format!("OAuth token request failed: {}", e)))?;
+
+        let token = body["access_token"].as_str()
+            .ok_or_else(|| SapError::ODataError("OAuth response without access_token".into()))?
+            .to_string();
+        let expires_in = body["expires_in"].as_u64().unwrap_or(0);
+        *cached = Some((token.clone(), Instant::now() + Duration::from_secs(expires_in)));
+        Ok(token)
+    }
+}
+
+impl ODataTransport for ReqwestTransport {
+    fn execute(&self, request: &HttpRequest) -> Result<HttpResponse, SapError> {
+        let method = reqwest::Method::from_bytes(request.method.as_bytes())
+            .map_err(|e| SapError::ODataError(e.to_string()))?;
+        let mut builder = self.http()?.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        builder = match &self.auth {
+            ODataAuth::Basic { username, password } => builder.basic_auth(username, Some(password.resolve()?.expose_str()?)),
+            ODataAuth::OAuth2 { client_id, client_secret, token_url } => {
+                builder.bearer_auth(self.access_token(client_id, client_secret, token_url)?)
+            }
+            ODataAuth::Certificate { .. } => builder,
+        };
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        let response = builder.send().map_err(|e| SapError::ODataError(e.to_string()))?;
+        let status = response.status().as_u16();
+        if status == 401 {
+            // Token revoked early; the next call fetches a new one
+            *self.token.lock().unwrap() = None;
+        }
+        let headers = response.headers().iter()
+            .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().map_err(|e| SapError::ODataError(e.to_string()))?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
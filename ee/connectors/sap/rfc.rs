@@ -3,6 +3,7 @@
 //! Remote Function Call protocol for SAP R/3 and ECC
 
 use super::{SapConfig, SapError};
+use super::super::resilience::PooledConnection;
 
 /// RFC connection to SAP system.
 pub struct RfcConnection {
@@ -59,6 +60,13 @@ impl RfcConnection {
     }
 }
 
+impl PooledConnection for RfcConnection {
+    fn is_healthy(&self) -> bool {
+        // Production would call RFC_PING
+        self.connected
+    }
+}
+
 /// RFC execution result.
 #[derive(Debug, Clone)]
 pub struct RfcResult {
@@ -0,0 +1,325 @@
+//! Connector Secrets
+//!
+//! Credentials in connector configs are references, not values. A
+//! [`SecretRef`] names where the secret lives and is resolved only when a
+//! connection is opened or a token fetched; the resolved [`Secret`] is
+//! zeroized when dropped.
+//!
+//! References are written as strings:
+//!
+//! | Form | Source |
+//! |------|--------|
+//! | `env:SAP_PASSWORD` | environment variable |
+//! | `file:/run/secrets/sap` | file contents, trailing newline trimmed |
+//! | `vault:secret/data/sap#password` | Vault KV field (v1 or v2) |
+//! | `kms:<key id>#<base64 ciphertext>` | ciphertext decrypted with a KMS key |
+//!
+//! Vault is used when `VAULT_ADDR` and `VAULT_TOKEN` are set; KMS needs a
+//! [`KmsDecrypt`] installed with [`install_resolver`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let config = SapConfig { password: Some("vault:secret/data/sap/prd#rfc".parse()?), ..SapConfig::default() };
+//! install_resolver(SecretResolver::from_env().with_kms(Arc::new(AwsKms::new(region))));
+//! sap.connect_rfc().await?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Where a secret is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SecretRef {
+    Env(String),
+    File(String),
+    /// Path under `/v1/`, e.g. `secret/data/sap`, and the field to read
+    Vault { path: String, field: String },
+    Kms { key_id: String, ciphertext: String },
+}
+
+impl SecretRef {
+    /// Resolve with the installed resolver.
+    pub fn resolve(&self) -> Result<Secret, SecretError> {
+        resolver().resolve(self)
+    }
+}
+
+impl FromStr for SecretRef {
+    type Err = SecretError;
+
+    fn from_str(s: &str) -> Result<Self, SecretError> {
+        let invalid = || SecretError::InvalidReference(s.to_string());
+        let (scheme, rest) = s.split_once(':').ok_or_else(invalid)?;
+        let reference = match scheme {
+            "env" => SecretRef::Env(rest.to_string()),
+            "file" => SecretRef::File(rest.to_string()),
+            "vault" => {
+                let (path, field) = rest.rsplit_once('#').ok_or_else(invalid)?;
+                SecretRef::Vault { path: path.trim_matches('/').to_string(), field: field.to_string() }
+            }
+            "kms" => {
+                let (key_id, ciphertext) = rest.rsplit_once('#').ok_or_else(invalid)?;
+                SecretRef::Kms { key_id: key_id.to_string(), ciphertext: ciphertext.to_string() }
+            }
+            _ => return Err(invalid()),
+        };
+        match &reference {
+            SecretRef::Env(name) | SecretRef::File(name) if name.is_empty() => Err(invalid()),
+            _ => Ok(reference),
+        }
+    }
+}
+
+impl TryFrom<String> for SecretRef {
+    type Error = SecretError;
+
+    fn try_from(s: String) -> Result<Self, SecretError> {
+        s.parse()
+    }
+}
+
+impl From<SecretRef> for String {
+    fn from(reference: SecretRef) -> String {
+        reference.to_string()
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretRef::Env(name) => write!(f, "env:{}", name),
+            SecretRef::File(path) => write!(f, "file:{}", path),
+            SecretRef::Vault { path, field } => write!(f, "vault:{}#{}", path, field),
+            SecretRef::Kms { key_id, ciphertext } => write!(f, "kms:{}#{}", key_id, ciphertext),
+        }
+    }
+}
+
+/// A resolved secret, zeroized on drop.
+pub struct Secret(Zeroizing<Vec<u8>>);
+
+impl Secret {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn expose_str(&self) -> Result<&str, SecretError> {
+        std::str::from_utf8(&self.0).map_err(|_| SecretError::NotUtf8)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+/// Decrypts KMS ciphertexts (AWS KMS, Cloud KMS, Key Vault).
+pub trait KmsDecrypt: Send + Sync {
+    fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, SecretError>;
+}
+
+/// Vault server access.
+#[derive(Debug, Clone)]
+pub struct VaultConfig {
+    pub addr: String,
+    /// Vault token; must come from the environment or a file
+    pub token: SecretRef,
+    pub namespace: Option<String>,
+}
+
+/// Resolves secret references.
+#[derive(Clone, Default)]
+pub struct SecretResolver {
+    vault: Option<VaultConfig>,
+    kms: Option<Arc<dyn KmsDecrypt>>,
+}
+
+impl SecretResolver {
+    /// Environment and files only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Vault from `VAULT_ADDR`, `VAULT_TOKEN` and `VAULT_NAMESPACE`, if set.
+    pub fn from_env() -> Self {
+        let vault = std::env::var("VAULT_ADDR").ok()
+            .filter(|_| std::env::var_os("VAULT_TOKEN").is_some())
+            .map(|addr| VaultConfig {
+                addr,
+                token: SecretRef::Env("VAULT_TOKEN".to_string()),
+                namespace: std::env::var("VAULT_NAMESPACE").ok(),
+            });
+        Self { vault, kms: None }
+    }
+
+    pub fn with_vault(mut self, vault: VaultConfig) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    pub fn with_kms(mut self, kms: Arc<dyn KmsDecrypt>) -> Self {
+        self.kms = Some(kms);
+        self
+    }
+
+    pub fn resolve(&self, reference: &SecretRef) -> Result<Secret, SecretError> {
+        match reference {
+            SecretRef::Env(name) => std::env::var(name)
+                .map(|value| Secret::new(value.into_bytes()))
+                .map_err(|_| SecretError::NotFound(reference.to_string())),
+            SecretRef::File(path) => {
+                let bytes = Zeroizing::new(std::fs::read(path)
+                    .map_err(|e| SecretError::Io(format!("{}: {}", path, e)))?);
+                let len = bytes.iter().rposition(|b| !matches!(b, b'\n' | b'\r')).map_or(0, |i| i + 1);
+                Ok(Secret::new(bytes[..len].to_vec()))
+            }
+            SecretRef::Vault { path, field } => self.read_vault(path, field),
+            SecretRef::Kms { key_id, ciphertext } => {
+                let kms = self.kms.as_ref()
+                    .ok_or_else(|| SecretError::NotConfigured("KMS".to_string()))?;
+                let ciphertext = decode_base64(ciphertext)
+                    .ok_or_else(|| SecretError::InvalidReference(reference.to_string()))?;
+                kms.decrypt(key_id, &ciphertext).map(Secret::new)
+            }
+        }
+    }
+
+    fn read_vault(&self, path: &str, field: &str) -> Result<Secret, SecretError> {
+        let vault = self.vault.as_ref()
+            .ok_or_else(|| SecretError::NotConfigured("Vault".to_string()))?;
+        if matches!(vault.token, SecretRef::Vault { .. } | SecretRef::Kms { .. }) {
+            return Err(SecretError::NotConfigured("Vault token must come from env or file".to_string()));
+        }
+        let token = self.resolve(&vault.token)?;
+
+        let mut request = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| SecretError::Backend(e.to_string()))?
+            .get(format!("{}/v1/{}", vault.addr.trim_end_matches('/'), path))
+            .header("X-Vault-Token", token.expose_str()?);
+        if let Some(namespace) = &vault.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let response = request.send().map_err(|e| SecretError::Backend(format!("Vault: {}", e)))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(SecretError::NotFound(format!("vault:{}", path)));
+        }
+        let body = Zeroizing::new(response.error_for_status()
+            .and_then(|r| r.bytes())
+            .map_err(|e| SecretError::Backend(format!("Vault: {}", e)))?
+            .to_vec());
+        // Best effort: the parsed JSON is not zeroized
+        let json: serde_json::Value = serde_json::from_slice(&body)
+            .map_err(|e| SecretError::Backend(format!("Vault response: {}", e)))?;
+        // KV v2 nests the fields one level deeper than v1
+        let data = &json["data"];
+        let value = data["data"].get(field).or_else(|| data.get(field))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| SecretError::NotFound(format!("vault:{}#{}", path, field)))?;
+        Ok(Secret::new(value.as_bytes().to_vec()))
+    }
+}
+
+static RESOLVER: RwLock<Option<Arc<SecretResolver>>> = RwLock::new(None);
+
+/// Install the resolver used by [`SecretRef::resolve`]; without one,
+/// [`SecretResolver::from_env`] is used.
+pub fn install_resolver(resolver: SecretResolver) {
+    *RESOLVER.write().unwrap() = Some(Arc::new(resolver));
+}
+
+fn resolver() -> Arc<SecretResolver> {
+    if let Some(resolver) = RESOLVER.read().unwrap().as_ref() {
+        return resolver.clone();
+    }
+    RESOLVER.write().unwrap().get_or_insert_with(|| Arc::new(SecretResolver::from_env())).clone()
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in input.trim_end_matches('=').bytes() {
+        buffer = (buffer << 6) | ALPHABET.iter().position(|&a| a == c)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Secret resolution errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("Invalid secret reference: {0}")]
+    InvalidReference(String),
+
+    #[error("Secret not found: {0}")]
+    NotFound(String),
+
+    #[error("Secret file error: {0}")]
+    Io(String),
+
+    #[error("Secret backend error: {0}")]
+    Backend(String),
+
+    #[error("{0} not configured")]
+    NotConfigured(String),
+
+    #[error("Secret is not valid UTF-8")]
+    NotUtf8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ReverseKms;
+
+    impl KmsDecrypt for ReverseKms {
+        fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>, SecretError> {
+            assert_eq!(key_id, "arn:aws:kms:eu-central-1:111122223333:key/sap");
+            Ok(ciphertext.iter().rev().copied().collect())
+        }
+    }
+
+    #[test]
+    fn test_parse_and_resolve() {
+        for s in ["env:SAP_PASSWORD", "file:/run/secrets/sap", "vault:secret/data/sap#rfc", "kms:arn:aws:kms:eu-central-1:111122223333:key/sap#dGVyY2Vz"] {
+            assert_eq!(s.parse::<SecretRef>().unwrap().to_string(), s);
+        }
+        assert!("hunter2".parse::<SecretRef>().is_err());
+        assert!("vault:secret/data/sap".parse::<SecretRef>().is_err());
+        let config: SecretRef = serde_json::from_str(r#""env:SAP_PASSWORD""#).unwrap();
+        assert_eq!(config, SecretRef::Env("SAP_PASSWORD".into()));
+
+        let path = std::env::temp_dir().join(format!("agentkern-secret-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "s3cret\n").unwrap();
+        let resolver = SecretResolver::new().with_kms(Arc::new(ReverseKms));
+        let file = resolver.resolve(&SecretRef::File(path.display().to_string())).unwrap();
+        assert_eq!(file.expose_str().unwrap(), "s3cret");
+        assert_eq!(format!("{:?}", file), "Secret(***)");
+        std::fs::remove_file(&path).unwrap();
+
+        let kms = resolver.resolve(&"kms:arn:aws:kms:eu-central-1:111122223333:key/sap#dGVyY2Vz".parse().unwrap()).unwrap();
+        assert_eq!(kms.expose_str().unwrap(), "secret");
+        assert!(matches!(
+            resolver.resolve(&"vault:secret/data/sap#rfc".parse().unwrap()),
+            Err(SecretError::NotConfigured(_))
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+//! camt.053 / camt.054 - Bank statements and debit/credit notifications
+//!
+//! Turns end-of-day statements (camt.053) and intraday notifications
+//! (camt.054) into structured entries. Documents are validated against
+//! their schema first, so entries are only read from well-formed reports.
+
+use serde::{Deserialize, Serialize};
+
+use super::mx_schema::{self, ValidationMode};
+use super::SwiftError;
+
+/// Credit or debit to the reported account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreditDebit {
+    Credit,
+    Debit,
+}
+
+/// A statement (camt.053) or notification (camt.054).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankStatement {
+    /// `camt.053` or `camt.054`
+    pub message_type: String,
+    pub message_id: String,
+    pub created: String,
+    pub reports: Vec<AccountReport>,
+}
+
+impl BankStatement {
+    /// All entries across reports.
+    pub fn entries(&self) -> impl Iterator<Item = (&AccountReport, &StatementEntry)> {
+        self.reports.iter().flat_map(|r| r.entries.iter().map(move |e| (r, e)))
+    }
+}
+
+/// Report for one account (`Stmt` / `Ntfctn`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountReport {
+    pub id: String,
+    /// IBAN or other account identifier
+    pub account: String,
+    pub currency: Option<String>,
+    pub balances: Vec<Balance>,
+    pub entries: Vec<StatementEntry>,
+}
+
+/// Balance line (e.g. `OPBD` opening booked, `CLBD` closing booked).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Balance {
+    pub code: String,
+    pub amount: f64,
+    pub currency: String,
+    pub direction: CreditDebit,
+    pub date: String,
+}
+
+/// Booked or pending entry on the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEntry {
+    pub entry_ref: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+    pub direction: CreditDebit,
+    pub reversal: bool,
+    /// `BOOK`, `PDNG` or `INFO`
+    pub status: String,
+    pub booking_date: Option<String>,
+    pub value_date: Option<String>,
+    pub account_servicer_ref: Option<String>,
+    pub transactions: Vec<EntryTransaction>,
+}
+
+impl StatementEntry {
+    /// Whether the entry is final on the account.
+    pub fn is_booked(&self) -> bool {
+        self.status == "BOOK"
+    }
+}
+
+/// Transaction details of an entry (`NtryDtls/TxDtls`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryTransaction {
+    pub end_to_end_id: Option<String>,
+    pub uetr: Option<String>,
+    pub instruction_id: Option<String>,
+    pub transaction_id: Option<String>,
+    /// Transaction amount, when it differs from or splits the entry
+    pub amount: Option<f64>,
+    pub currency: Option<String>,
+    pub debtor: Option<String>,
+    pub creditor: Option<String>,
+    pub remittance: Vec<String>,
+}
+
+type Node<'a, 'i> = roxmltree::Node<'a, 'i>;
+
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn children<'a, 'i: 'a>(node: Node<'a, 'i>, name: &'a str) -> impl Iterator<Item = Node<'a, 'i>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// Text at a `/`-separated child path.
+fn text(node: Node<'_, '_>, path: &str) -> Option<String> {
+    path.split('/')
+        .try_fold(node, |n, step| child(n, step))
+        .and_then(|n| n.text())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Amount and currency of an amount element.
+fn amount(node: Node<'_, '_>, path: &str) -> Option<(f64, String)> {
+    let el = path.split('/').try_fold(node, |n, step| child(n, step))?;
+    Some((el.text()?.trim().parse().ok()?, el.attribute("Ccy")?.to_string()))
+}
+
+fn direction(node: Node<'_, '_>) -> Option<CreditDebit> {
+    match text(node, "CdtDbtInd")?.as_str() {
+        "CRDT" => Some(CreditDebit::Credit),
+        "DBIT" => Some(CreditDebit::Debit),
+        _ => None,
+    }
+}
+
+/// `Dt` or `DtTm` of a DateAndDateTime2Choice.
+fn date(node: Node<'_, '_>, name: &str) -> Option<String> {
+    let choice = child(node, name)?;
+    text(choice, "Dt").or_else(|| text(choice, "DtTm"))
+}
+
+/// Parse and validate a camt.053 or camt.054 document.
+pub fn parse_statement(xml: &str, mode: ValidationMode) -> Result<BankStatement, SwiftError> {
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| SwiftError::ParseError(e.to_string()))?;
+    let report = mx_schema::validate_document(&doc, mode)?;
+    let (report_tag, message_type) = match report.message_type.as_str() {
+        "camt.053" => ("Stmt", "camt.053"),
+        "camt.054" => ("Ntfctn", "camt.054"),
+        other => return Err(SwiftError::ParseError(format!("Expected camt.053 or camt.054, got {}", other))),
+    };
+    report.into_result()?;
+
+    let body = mx_schema::find_document(&doc)
+        .and_then(|d| d.children().find(|n| n.is_element()))
+        .ok_or_else(|| SwiftError::ParseError("Empty Document".into()))?;
+    let header = child(body, "GrpHdr")
+        .ok_or_else(|| SwiftError::ParseError("Missing GrpHdr".into()))?;
+
+    Ok(BankStatement {
+        message_type: message_type.to_string(),
+        message_id: text(header, "MsgId").unwrap_or_default(),
+        created: text(header, "CreDtTm").unwrap_or_default(),
+        reports: children(body, report_tag).map(parse_report).collect(),
+    })
+}
+
+fn parse_report(report: Node<'_, '_>) -> AccountReport {
+    AccountReport {
+        id: text(report, "Id").unwrap_or_default(),
+        account: text(report, "Acct/Id/IBAN")
+            .or_else(|| text(report, "Acct/Id/Othr/Id"))
+            .unwrap_or_default(),
+        currency: text(report, "Acct/Ccy"),
+        balances: children(report, "Bal").filter_map(|bal| {
+            let (amount, currency) = amount(bal, "Amt")?;
+            Some(Balance {
+                code: text(bal, "Tp/CdOrPrtry/Cd").or_else(|| text(bal, "Tp/CdOrPrtry/Prtry"))?,
+                amount,
+                currency,
+                direction: direction(bal)?,
+                date: date(bal, "Dt")?,
+            })
+        }).collect(),
+        entries: children(report, "Ntry").filter_map(parse_entry).collect(),
+    }
+}
+
+fn parse_entry(entry: Node<'_, '_>) -> Option<StatementEntry> {
+    let (amount, currency) = amount(entry, "Amt")?;
+    Some(StatementEntry {
+        entry_ref: text(entry, "NtryRef"),
+        amount,
+        currency,
+        direction: direction(entry)?,
+        reversal: text(entry, "RvslInd").is_some_and(|v| v == "true" || v == "1"),
+        status: text(entry, "Sts/Cd").or_else(|| text(entry, "Sts/Prtry"))?,
+        booking_date: date(entry, "BookgDt"),
+        value_date: date(entry, "ValDt"),
+        account_servicer_ref: text(entry, "AcctSvcrRef"),
+        transactions: children(entry, "NtryDtls")
+            .flat_map(|details| children(details, "TxDtls"))
+            .map(parse_transaction)
+            .collect(),
+    })
+}
+
+fn parse_transaction(tx: Node<'_, '_>) -> EntryTransaction {
+    let tx_amount = amount(tx, "Amt").or_else(|| amount(tx, "AmtDtls/TxAmt/Amt"));
+    // Party40Choice wraps the party in Pty; older versions name it directly
+    let party = |role: &str| text(tx, &format!("RltdPties/{}/Pty/Nm", role))
+        .or_else(|| text(tx, &format!("RltdPties/{}/Nm", role)));
+    EntryTransaction {
+        end_to_end_id: text(tx, "Refs/EndToEndId").filter(|id| id != "NOTPROVIDED"),
+        uetr: text(tx, "Refs/UETR").map(|u| u.to_ascii_lowercase()),
+        instruction_id: text(tx, "Refs/InstrId"),
+        transaction_id: text(tx, "Refs/TxId"),
+        amount: tx_amount.as_ref().map(|(a, _)| *a),
+        currency: tx_amount.map(|(_, c)| c),
+        debtor: party("Dbtr"),
+        creditor: party("Cdtr"),
+        remittance: child(tx, "RmtInf")
+            .map(|r| children(r, "Ustrd").filter_map(|u| u.text()).map(|t| t.trim().to_string()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    /// Statement with a booked debit, a batch entry and a pending entry.
+    pub const CAMT053: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>STMT-20251226</MsgId><CreDtTm>2025-12-26T18:00:00Z</CreDtTm></GrpHdr>
+    <Stmt>
+      <Id>STMT-1</Id>
+      <Acct><Id><IBAN>DE89370400440532013000</IBAN></Id><Ccy>EUR</Ccy></Acct>
+      <Bal><Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">5000.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-12-26</Dt></Dt></Bal>
+      <Bal><Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">3750.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-12-26</Dt></Dt></Bal>
+      <Ntry>
+        <NtryRef>1</NtryRef><Amt Ccy="EUR">1000.00</Amt><CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts><Cd>BOOK</Cd></Sts><BookgDt><Dt>2025-12-26</Dt></BookgDt><ValDt><Dt>2025-12-26</Dt></ValDt>
+        <AcctSvcrRef>ASR-1</AcctSvcrRef><BkTxCd><Domn><Cd>PMNT</Cd></Domn></BkTxCd>
+        <NtryDtls><TxDtls>
+          <Refs><EndToEndId>MSG001</EndToEndId><UETR>eb6305c9-1f7f-49de-aed0-16487c27b42d</UETR></Refs>
+          <RltdPties><Cdtr><Pty><Nm>Jane Smith</Nm></Pty></Cdtr></RltdPties>
+          <RmtInf><Ustrd>Invoice 123</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <NtryRef>2</NtryRef><Amt Ccy="EUR">250.00</Amt><CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts><Cd>BOOK</Cd></Sts><BookgDt><Dt>2025-12-26</Dt></BookgDt>
+        <AcctSvcrRef>ASR-2</AcctSvcrRef><BkTxCd><Domn><Cd>PMNT</Cd></Domn></BkTxCd>
+        <NtryDtls>
+          <TxDtls><Refs><EndToEndId>MSG002</EndToEndId></Refs><Amt Ccy="EUR">200.00</Amt></TxDtls>
+          <TxDtls><Refs><EndToEndId>MSG999</EndToEndId></Refs><Amt Ccy="EUR">50.00</Amt></TxDtls>
+        </NtryDtls>
+      </Ntry>
+      <Ntry>
+        <NtryRef>3</NtryRef><Amt Ccy="EUR">75.00</Amt><CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts><Cd>PDNG</Cd></Sts><AcctSvcrRef>ASR-3</AcctSvcrRef><BkTxCd/>
+        <NtryDtls><TxDtls><Refs><EndToEndId>MSG003</EndToEndId></Refs></TxDtls></NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_camt053() {
+        let statement = parse_statement(fixtures::CAMT053, ValidationMode::Strict).unwrap();
+        assert_eq!(statement.message_type, "camt.053");
+        assert_eq!(statement.message_id, "STMT-20251226");
+
+        let report = &statement.reports[0];
+        assert_eq!(report.account, "DE89370400440532013000");
+        assert_eq!(report.balances[1].code, "CLBD");
+        assert_eq!(report.entries.len(), 3);
+
+        let entry = &report.entries[0];
+        assert_eq!(entry.direction, CreditDebit::Debit);
+        assert!(entry.is_booked());
+        let tx = &entry.transactions[0];
+        assert_eq!(tx.uetr.as_deref(), Some("eb6305c9-1f7f-49de-aed0-16487c27b42d"));
+        assert_eq!(tx.creditor.as_deref(), Some("Jane Smith"));
+        assert_eq!(tx.remittance, ["Invoice 123"]);
+
+        assert_eq!(report.entries[1].transactions[1].amount, Some(50.0));
+    }
+
+    #[test]
+    fn test_rejects_other_messages() {
+        let xml = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.002.001.10">
+<FIToFIPmtStsRpt><GrpHdr><MsgId>1</MsgId><CreDtTm>2025-12-26T12:00:00Z</CreDtTm></GrpHdr></FIToFIPmtStsRpt></Document>"#;
+        assert!(matches!(parse_statement(xml, ValidationMode::Strict), Err(SwiftError::ParseError(_))));
+    }
+}
@@ -0,0 +1,297 @@
+//! gpi Tracker API client
+//!
+//! OAuth 2.0 authenticated access to the SWIFT gpi Tracker REST API (v5):
+//! - `GET  /payments/{uetr}/transactions` - payment transaction details
+//! - `GET  /payments/changed/transactions` - payments changed in a window
+//! - `PUT  /payments/{uetr}/status` - status confirmation
+//!
+//! Banks use the gpi product; corporates use g4c, which exposes the same
+//! resources under its own base path.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::super::SwiftError;
+use super::super::super::secrets::SecretRef;
+
+/// SWIFT OAuth token endpoint.
+pub const DEFAULT_TOKEN_URL: &str = "https://api.swift.com/oauth2/v1/token";
+
+/// Renew tokens this long before they expire.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Tracker API product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerProduct {
+    /// gpi for banks
+    #[default]
+    Gpi,
+    /// gpi for corporates
+    G4c,
+}
+
+impl TrackerProduct {
+    /// Production base URL.
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            Self::Gpi => "https://api.swiftnet.sipn.swift.com/swift-apitracker/v5",
+            Self::G4c => "https://api.swiftnet.sipn.swift.com/swift-apitracker-g4c/v5",
+        }
+    }
+
+    /// OAuth scope granting access to the product.
+    fn scope(&self) -> &'static str {
+        match self {
+            Self::Gpi => "swift.apitracker",
+            Self::G4c => "swift.apitracker.g4c",
+        }
+    }
+}
+
+/// gpi Tracker API settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpiApiConfig {
+    pub product: TrackerProduct,
+    /// Overrides the product base URL (e.g. the SWIFT sandbox)
+    pub base_url: Option<String>,
+    /// Overrides `DEFAULT_TOKEN_URL`
+    pub token_url: Option<String>,
+    /// OAuth consumer key
+    pub consumer_key: Option<String>,
+    /// OAuth consumer secret
+    pub consumer_secret: Option<SecretRef>,
+    /// Resource owner credentials; client credentials grant when absent
+    pub username: Option<String>,
+    pub password: Option<SecretRef>,
+    /// Shared secret for webhook signatures (HMAC-SHA256)
+    pub webhook_secret: Option<SecretRef>,
+    /// Directory for UETR timelines; in memory when absent
+    pub timeline_dir: Option<String>,
+    /// Request timeout in seconds (default 30)
+    pub timeout_secs: Option<u64>,
+}
+
+impl GpiApiConfig {
+    /// Whether API credentials are configured.
+    pub fn is_configured(&self) -> bool {
+        self.consumer_key.is_some() && self.consumer_secret.is_some()
+    }
+}
+
+/// Amount with currency as used by the Tracker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackerAmount {
+    pub currency: String,
+    pub amount: String,
+}
+
+/// Transaction status with optional reason (e.g. `ACSP` / `G000`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionStatus {
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// One event in a tracked payment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaymentEvent {
+    pub tracker_event_type: Option<String>,
+    pub message_name_identification: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub originator: Option<String>,
+    pub transaction_status: Option<TransactionStatus>,
+    pub sender_acknowledgement_receipt: Option<String>,
+    pub received_date: Option<String>,
+    pub confirmed_amount: Option<TrackerAmount>,
+}
+
+impl PaymentEvent {
+    /// Event time: acknowledgement receipt, else received date.
+    pub fn timestamp(&self) -> Option<&str> {
+        self.sender_acknowledgement_receipt.as_deref().or(self.received_date.as_deref())
+    }
+}
+
+/// Payment transaction details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerPayment {
+    pub uetr: String,
+    pub transaction_status: TransactionStatus,
+    #[serde(default)]
+    pub initiation_time: Option<String>,
+    #[serde(default)]
+    pub completion_time: Option<String>,
+    #[serde(default)]
+    pub last_update_time: Option<String>,
+    #[serde(default)]
+    pub payment_event: Vec<PaymentEvent>,
+}
+
+/// Changed payment transactions response.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ChangedPayments {
+    pub payment_transaction: Vec<TrackerPayment>,
+}
+
+/// Status confirmation submitted by an agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusConfirmation {
+    /// BIC of the confirming agent
+    pub from: String,
+    /// gpi service, `001` for customer credit transfers
+    pub business_service: String,
+    pub transaction_status: TransactionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed_amount: Option<TrackerAmount>,
+    /// Next agent when the payment was forwarded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forwarded_to_agent: Option<String>,
+    /// Time funds were made available (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub funds_available: Option<String>,
+}
+
+/// gpi Tracker operations.
+pub trait TrackerApi: Send + Sync {
+    /// Payment transaction details.
+    fn get_payment(&self, uetr: &str) -> Result<TrackerPayment, SwiftError>;
+
+    /// Payments changed within a time window.
+    fn changed_payments(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TrackerPayment>, SwiftError>;
+
+    /// Submit a status confirmation.
+    fn submit_status(&self, uetr: &str, confirmation: &StatusConfirmation) -> Result<(), SwiftError>;
+}
+
+/// HTTPS client for the gpi Tracker API.
+pub struct GpiClient {
+    config: GpiApiConfig,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl GpiClient {
+    /// Create client; connections are opened on first use.
+    pub fn new(config: &GpiApiConfig) -> Self {
+        Self {
+            config: config.clone(),
+            http: OnceLock::new(),
+            token: Mutex::new(None),
+        }
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.as_deref()
+            .unwrap_or(self.config.product.default_base_url())
+            .trim_end_matches('/')
+    }
+
+    fn http(&self) -> Result<&reqwest::blocking::Client, SwiftError> {
+        let timeout = Duration::from_secs(self.config.timeout_secs.unwrap_or(30));
+        self.http
+            .get_or_init(|| reqwest::blocking::Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| SwiftError::NetworkError(e.clone()))
+    }
+
+    /// Cached access token, renewed shortly before expiry.
+    fn access_token(&self) -> Result<String, SwiftError> {
+        let mut cached = self.token.lock().unwrap();
+        if let Some((token, expires)) = cached.as_ref() {
+            if Instant::now() + TOKEN_MARGIN < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let (Some(key), Some(secret)) = (&self.config.consumer_key, &self.config.consumer_secret) else {
+            return Err(SwiftError::NetworkError("gpi Tracker API credentials not configured".into()));
+        };
+        let (secret, password) = (secret.resolve()?, self.config.password.as_ref().map(SecretRef::resolve).transpose()?);
+        let scope = self.config.product.scope();
+        let form: Vec<(&str, &str)> = match (&self.config.username, &password) {
+            (Some(user), Some(pass)) => vec![
+                ("grant_type", "password"), ("username", user), ("password", pass.expose_str()?), ("scope", scope),
+            ],
+            _ => vec![("grant_type", "client_credentials"), ("scope", scope)],
+        };
+
+        let url = self.config.token_url.as_deref().unwrap_or(DEFAULT_TOKEN_URL);
+        let body: serde_json::Value = self.http()?
+            .post(url)
+            .basic_auth(key, Some(secret.expose_str()?))
+            .form(&form)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.json())
+            .map_err(|e| SwiftError::NetworkError(format!("OAuth token request failed: {}", e)))?;
+
+        let token = body["access_token"].as_str()
+            .ok_or_else(|| SwiftError::ParseError("OAuth response without access_token".into()))?
+            .to_string();
+        // SWIFT returns expires_in as a string
+        let expires_in = match &body["expires_in"] {
+            serde_json::Value::String(s) => s.parse().unwrap_or(0),
+            v => v.as_u64().unwrap_or(0),
+        };
+        *cached = Some((token.clone(), Instant::now() + Duration::from_secs(expires_in)));
+        Ok(token)
+    }
+
+    fn send(&self, request: reqwest::blocking::RequestBuilder) -> Result<reqwest::blocking::Response, SwiftError> {
+        let response = request
+            .bearer_auth(self.access_token()?)
+            .send()
+            .map_err(|e| SwiftError::NetworkError(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            // Token revoked early; the next call fetches a new one
+            *self.token.lock().unwrap() = None;
+        }
+        response.error_for_status()
+            .map_err(|e| SwiftError::NetworkError(e.to_string()))
+    }
+}
+
+impl TrackerApi for GpiClient {
+    fn get_payment(&self, uetr: &str) -> Result<TrackerPayment, SwiftError> {
+        let url = format!("{}/payments/{}/transactions", self.base_url(), uetr);
+        self.send(self.http()?.get(url))?
+            .json()
+            .map_err(|e| SwiftError::ParseError(e.to_string()))
+    }
+
+    fn changed_payments(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TrackerPayment>, SwiftError> {
+        let url = format!("{}/payments/changed/transactions", self.base_url());
+        let request = self.http()?.get(url).query(&[
+            ("from_date_time", from.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+            ("to_date_time", to.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        ]);
+        let changed: ChangedPayments = self.send(request)?
+            .json()
+            .map_err(|e| SwiftError::ParseError(e.to_string()))?;
+        Ok(changed.payment_transaction)
+    }
+
+    fn submit_status(&self, uetr: &str, confirmation: &StatusConfirmation) -> Result<(), SwiftError> {
+        let url = format!("{}/payments/{}/status", self.base_url(), uetr);
+        self.send(self.http()?.put(url).json(confirmation))?;
+        Ok(())
+    }
+}
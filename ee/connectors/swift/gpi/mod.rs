@@ -0,0 +1,449 @@
+//! GPI Tracker - SWIFT GPI Payment Tracking
+//!
+//! Track payments with Universal End-to-End Transaction Reference (UETR):
+//! - Status retrieval and confirmations through the gpi Tracker API (gpi or g4c)
+//! - Push updates: webhook ingestion and callbacks on every status change
+//! - UETR timelines persisted for payment-ops dashboards
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let tracker = GpiTracker::new(&config)?;
+//! tracker.subscribe(|update| {
+//!     println!("{} {:?} -> {}", update.uetr, update.previous, update.event.status);
+//! });
+//!
+//! // From the HTTP handler receiving Tracker notifications
+//! tracker.handle_webhook(&body, headers.get("X-Signature"))?;
+//! ```
+
+mod client;
+mod timeline;
+
+pub use client::{
+    GpiClient, GpiApiConfig, TrackerApi, TrackerProduct, TrackerPayment, PaymentEvent,
+    TransactionStatus, TrackerAmount, StatusConfirmation,
+};
+pub use timeline::{TimelineStore, MemoryTimelineStore, FileTimelineStore, TimelineEvent, UpdateSource};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use super::{SwiftConfig, SwiftError, GpiStatus, GpiConfirmation};
+use super::super::license::LicenseError;
+
+/// Status change delivered to subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusUpdate {
+    pub uetr: String,
+    /// Latest status before this event
+    pub previous: Option<String>,
+    pub event: TimelineEvent,
+}
+
+/// Callback invoked for every new timeline event.
+pub type StatusCallback = dyn Fn(&StatusUpdate) + Send + Sync;
+
+/// Handle returned by `GpiTracker::subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Webhook body: one payment or a changed-payments batch.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WebhookPayload {
+    Payment(Box<TrackerPayment>),
+    Batch(client::ChangedPayments),
+}
+
+/// SWIFT GPI tracker.
+pub struct GpiTracker {
+    config: SwiftConfig,
+    api: Option<Arc<dyn TrackerApi>>,
+    store: Arc<dyn TimelineStore>,
+    subscribers: RwLock<Vec<(SubscriptionId, Arc<StatusCallback>)>>,
+    next_subscription: AtomicU64,
+    /// Serializes timeline updates from polls, webhooks and confirmations
+    ingest_lock: Mutex<()>,
+}
+
+impl GpiTracker {
+    /// Create new GPI tracker (requires license).
+    pub fn new(config: &SwiftConfig) -> Result<Self, LicenseError> {
+        let api = config.gpi_api.is_configured()
+            .then(|| Arc::new(GpiClient::new(&config.gpi_api)) as Arc<dyn TrackerApi>);
+        let store: Arc<dyn TimelineStore> = match &config.gpi_api.timeline_dir {
+            Some(dir) => Arc::new(FileTimelineStore::new(dir)),
+            None => Arc::new(MemoryTimelineStore::new()),
+        };
+        Ok(Self {
+            config: config.clone(),
+            api,
+            store,
+            subscribers: RwLock::new(Vec::new()),
+            next_subscription: AtomicU64::new(1),
+            ingest_lock: Mutex::new(()),
+        })
+    }
+
+    /// Use a specific Tracker API implementation.
+    pub fn with_api(mut self, api: Arc<dyn TrackerApi>) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Use a specific timeline store.
+    pub fn with_store(mut self, store: Arc<dyn TimelineStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Generate UETR for new payment.
+    pub fn generate_uetr() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    fn api(&self) -> Result<&Arc<dyn TrackerApi>, SwiftError> {
+        self.api.as_ref()
+            .ok_or_else(|| SwiftError::NetworkError("gpi Tracker API not configured".into()))
+    }
+
+    /// Track payment by UETR.
+    ///
+    /// Refreshes from the Tracker when the API is configured, otherwise
+    /// answers from the stored timeline (e.g. fed by webhooks).
+    pub fn track(&self, uetr: &str) -> Result<GpiStatus, SwiftError> {
+        if let Some(api) = &self.api {
+            let payment = api.get_payment(uetr)?;
+            self.ingest(&[payment], UpdateSource::Tracker)?;
+        }
+
+        let timeline = self.store.timeline(uetr)?;
+        let last = timeline.last().ok_or_else(|| SwiftError::UnknownUetr(uetr.to_string()))?;
+        Ok(GpiStatus {
+            uetr: uetr.to_string(),
+            status: last.status.clone(),
+            last_update: last.timestamp.clone(),
+            confirmations: timeline.iter()
+                .filter_map(|event| Some(GpiConfirmation {
+                    confirming_agent: event.from.clone()?,
+                    status: event.status.clone(),
+                    timestamp: event.timestamp.clone(),
+                    reason_code: event.reason.clone(),
+                }))
+                .collect(),
+        })
+    }
+
+    /// Get confirmations for UETR.
+    pub fn get_confirmations(&self, uetr: &str) -> Result<Vec<GpiConfirmation>, SwiftError> {
+        let status = self.track(uetr)?;
+        Ok(status.confirmations)
+    }
+
+    /// Update payment status.
+    pub fn update_status(&self, uetr: &str, new_status: &str, reason: Option<&str>) -> Result<(), SwiftError> {
+        self.confirm(uetr, &StatusConfirmation {
+            from: self.config.own_bic.clone(),
+            business_service: "001".to_string(),
+            transaction_status: TransactionStatus {
+                status: new_status.to_string(),
+                reason: reason.map(String::from),
+            },
+            confirmed_amount: None,
+            forwarded_to_agent: None,
+            funds_available: None,
+        })
+    }
+
+    /// Submit a status confirmation and record it in the timeline.
+    pub fn confirm(&self, uetr: &str, confirmation: &StatusConfirmation) -> Result<(), SwiftError> {
+        self.api()?.submit_status(uetr, confirmation)?;
+        let event = TimelineEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            status: confirmation.transaction_status.status.clone(),
+            reason: confirmation.transaction_status.reason.clone(),
+            from: Some(confirmation.from.clone()),
+            to: confirmation.forwarded_to_agent.clone(),
+            event_type: None,
+            source: UpdateSource::Local,
+        };
+        let update = {
+            let _guard = self.ingest_lock.lock().unwrap();
+            self.append(uetr, event)?
+        };
+        self.notify(&[update]);
+        Ok(())
+    }
+
+    /// Get payments pending confirmation (latest status not final).
+    pub fn get_pending(&self) -> Result<Vec<String>, SwiftError> {
+        let mut pending = Vec::new();
+        for uetr in self.store.uetrs()? {
+            let latest = self.store.timeline(&uetr)?.pop().map(|e| e.status);
+            if !latest.is_some_and(|s| status_codes::is_final(&s)) {
+                pending.push(uetr);
+            }
+        }
+        pending.sort();
+        Ok(pending)
+    }
+
+    /// Stored timeline of a UETR.
+    pub fn timeline(&self, uetr: &str) -> Result<Vec<TimelineEvent>, SwiftError> {
+        self.store.timeline(uetr)
+    }
+
+    /// Pull payments changed since `since` from the Tracker.
+    /// Returns the number of new timeline events.
+    pub fn poll_changes(&self, since: chrono::DateTime<chrono::Utc>) -> Result<usize, SwiftError> {
+        let payments = self.api()?.changed_payments(since, chrono::Utc::now())?;
+        self.ingest(&payments, UpdateSource::Tracker)
+    }
+
+    /// Ingest a pushed status notification.
+    ///
+    /// With a `webhook_secret` configured, `signature` must be the hex
+    /// HMAC-SHA256 of the body (optionally prefixed with `sha256=`).
+    /// Returns the number of new timeline events.
+    pub fn handle_webhook(&self, body: &[u8], signature: Option<&str>) -> Result<usize, SwiftError> {
+        if let Some(secret) = &self.config.gpi_api.webhook_secret {
+            let secret = secret.resolve()?;
+            let signature = signature
+                .and_then(|s| decode_hex(s.trim().trim_start_matches("sha256=")))
+                .ok_or_else(|| SwiftError::WebhookRejected("missing or malformed signature".into()))?;
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose())
+                .map_err(|e| SwiftError::WebhookRejected(e.to_string()))?;
+            mac.update(body);
+            mac.verify_slice(&signature)
+                .map_err(|_| SwiftError::WebhookRejected("signature mismatch".into()))?;
+        }
+
+        let payments = match serde_json::from_slice(body)
+            .map_err(|e| SwiftError::ParseError(format!("Webhook payload: {}", e)))?
+        {
+            WebhookPayload::Payment(payment) => vec![*payment],
+            WebhookPayload::Batch(batch) => batch.payment_transaction,
+        };
+        self.ingest(&payments, UpdateSource::Webhook)
+    }
+
+    /// Register a callback for status changes.
+    pub fn subscribe(&self, callback: impl Fn(&StatusUpdate) + Send + Sync + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_subscription.fetch_add(1, Ordering::Relaxed));
+        self.subscribers.write().unwrap().push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Remove a callback. Returns false if it was not registered.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.write().unwrap();
+        let before = subscribers.len();
+        subscribers.retain(|(sid, _)| *sid != id);
+        subscribers.len() != before
+    }
+
+    /// Merge Tracker payments into timelines, then notify subscribers.
+    fn ingest(&self, payments: &[TrackerPayment], source: UpdateSource) -> Result<usize, SwiftError> {
+        let mut updates = Vec::new();
+        {
+            let _guard = self.ingest_lock.lock().unwrap();
+            for payment in payments {
+                let known = self.store.timeline(&payment.uetr)?;
+                let mut events: Vec<TimelineEvent> = payment.payment_event.iter()
+                    .filter_map(|e| {
+                        let status = e.transaction_status.as_ref()?;
+                        Some(TimelineEvent {
+                            timestamp: e.timestamp()?.to_string(),
+                            status: status.status.clone(),
+                            reason: status.reason.clone(),
+                            from: e.from.clone(),
+                            to: e.to.clone(),
+                            event_type: e.tracker_event_type.clone(),
+                            source,
+                        })
+                    })
+                    .filter(|e| !known.iter().any(|k| k.same_report(e)))
+                    .collect();
+
+                // Overall status without a matching event (e.g. set by the Tracker itself)
+                let latest = events.last().or(known.last()).map(|e| e.status.as_str());
+                if latest != Some(payment.transaction_status.status.as_str()) {
+                    events.push(TimelineEvent {
+                        timestamp: payment.last_update_time.clone()
+                            .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+                        status: payment.transaction_status.status.clone(),
+                        reason: payment.transaction_status.reason.clone(),
+                        from: None,
+                        to: None,
+                        event_type: None,
+                        source,
+                    });
+                }
+
+                for event in events {
+                    updates.push(self.append(&payment.uetr, event)?);
+                }
+            }
+        }
+        self.notify(&updates);
+        Ok(updates.len())
+    }
+
+    /// Append to a timeline; caller holds `ingest_lock`.
+    fn append(&self, uetr: &str, event: TimelineEvent) -> Result<StatusUpdate, SwiftError> {
+        let previous = self.store.timeline(uetr)?.pop().map(|e| e.status);
+        self.store.append(uetr, &event)?;
+        Ok(StatusUpdate { uetr: uetr.to_string(), previous, event })
+    }
+
+    fn notify(&self, updates: &[StatusUpdate]) {
+        // Snapshot so callbacks may (un)subscribe
+        let subscribers: Vec<_> = self.subscribers.read().unwrap()
+            .iter().map(|(_, cb)| cb.clone()).collect();
+        for update in updates {
+            for callback in &subscribers {
+                callback(update);
+            }
+        }
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// GPI status codes.
+pub mod status_codes {
+    pub const ACCC: &str = "ACCC"; // Accepted Settlement Completed (creditor account credited)
+    pub const ACSC: &str = "ACSC"; // Accepted Settlement Completed
+    pub const ACSP: &str = "ACSP"; // Accepted Settlement in Progress
+    pub const PDNG: &str = "PDNG"; // Pending
+    pub const RJCT: &str = "RJCT"; // Rejected
+    pub const RCVD: &str = "RCVD"; // Received
+
+    /// Whether no further status is expected.
+    pub fn is_final(status: &str) -> bool {
+        matches!(status, ACCC | ACSC | RJCT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::secrets::SecretRef;
+
+    const UETR: &str = "eb6305c9-1f7f-49de-aed0-16487c27b42d";
+
+    /// Tracker returning a fixed payment and recording confirmations.
+    #[derive(Default)]
+    struct MockTracker {
+        submitted: Mutex<Vec<StatusConfirmation>>,
+    }
+
+    impl TrackerApi for MockTracker {
+        fn get_payment(&self, uetr: &str) -> Result<TrackerPayment, SwiftError> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "uetr": uetr,
+                "transaction_status": { "status": "ACSP", "reason": "G000" },
+                "payment_event": [{
+                    "tracker_event_type": "CTSU",
+                    "from": "ABCDDEFF",
+                    "to": "IJKLGB2L",
+                    "transaction_status": { "status": "ACSP", "reason": "G000" },
+                    "sender_acknowledgement_receipt": "2025-12-26T12:00:00.000Z"
+                }]
+            })).unwrap())
+        }
+
+        fn changed_payments(
+            &self,
+            _from: chrono::DateTime<chrono::Utc>,
+            _to: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Vec<TrackerPayment>, SwiftError> {
+            Ok(vec![self.get_payment(UETR)?])
+        }
+
+        fn submit_status(&self, _uetr: &str, confirmation: &StatusConfirmation) -> Result<(), SwiftError> {
+            self.submitted.lock().unwrap().push(confirmation.clone());
+            Ok(())
+        }
+    }
+
+    fn tracker(config: SwiftConfig) -> (GpiTracker, Arc<MockTracker>) {
+        let api = Arc::new(MockTracker::default());
+        (GpiTracker::new(&config).unwrap().with_api(api.clone()), api)
+    }
+
+    #[test]
+    fn test_generate_uetr() {
+        let uetr = GpiTracker::generate_uetr();
+        assert!(!uetr.is_empty());
+        assert!(uetr.contains('-')); // UUID format
+    }
+
+    #[test]
+    fn test_track_records_timeline_once() {
+        let (tracker, _) = tracker(SwiftConfig::default());
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        tracker.subscribe(move |u| sink.lock().unwrap().push(u.event.status.clone()));
+
+        let status = tracker.track(UETR).unwrap();
+        assert_eq!(status.status, "ACSP");
+        assert_eq!(status.confirmations[0].confirming_agent, "ABCDDEFF");
+
+        // Polling the same state adds nothing
+        assert_eq!(tracker.poll_changes(chrono::Utc::now()).unwrap(), 0);
+        assert_eq!(tracker.timeline(UETR).unwrap().len(), 1);
+        assert_eq!(*updates.lock().unwrap(), vec!["ACSP"]);
+        assert_eq!(tracker.get_pending().unwrap(), vec![UETR]);
+    }
+
+    #[test]
+    fn test_confirmation_completes_payment() {
+        let config = SwiftConfig { own_bic: "IJKLGB2L".into(), ..SwiftConfig::default() };
+        let (tracker, api) = tracker(config);
+        tracker.track(UETR).unwrap();
+
+        tracker.update_status(UETR, status_codes::ACCC, Some("G000")).unwrap();
+        assert_eq!(api.submitted.lock().unwrap()[0].from, "IJKLGB2L");
+        assert_eq!(tracker.timeline(UETR).unwrap()[1].source, UpdateSource::Local);
+        assert!(tracker.get_pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_webhook_signature() {
+        let mut config = SwiftConfig::default();
+        let secret_file = std::env::temp_dir().join(format!("gpi-webhook-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&secret_file, "s3cret\n").unwrap();
+        config.gpi_api.webhook_secret = Some(SecretRef::File(secret_file.display().to_string()));
+        let tracker = GpiTracker::new(&config).unwrap();
+
+        let body = serde_json::json!({
+            "uetr": UETR,
+            "transaction_status": { "status": "RJCT", "reason": "AC04" },
+            "last_update_time": "2025-12-26T13:00:00Z"
+        }).to_string();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body.as_bytes());
+        let signature: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+
+        assert!(matches!(
+            tracker.handle_webhook(body.as_bytes(), Some("sha256=00")),
+            Err(SwiftError::WebhookRejected(_))
+        ));
+        assert_eq!(tracker.handle_webhook(body.as_bytes(), Some(&format!("sha256={}", signature))).unwrap(), 1);
+        assert_eq!(tracker.track(UETR).unwrap().status, "RJCT");
+        std::fs::remove_file(&secret_file).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+//! UETR timelines - persisted status history per payment
+//!
+//! Every status observed for a UETR (Tracker poll, webhook, own
+//! confirmation) is appended to its timeline so payment-ops dashboards can
+//! show where a payment is and how long each hop took.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+use super::super::SwiftError;
+
+/// Where a status came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateSource {
+    /// Read from the Tracker API
+    Tracker,
+    /// Pushed to our webhook
+    Webhook,
+    /// Confirmed by this institution
+    Local,
+}
+
+/// One status change of a payment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// RFC 3339 time of the event
+    pub timestamp: String,
+    pub status: String,
+    pub reason: Option<String>,
+    /// Agent reporting the status
+    pub from: Option<String>,
+    /// Next agent, when forwarded
+    pub to: Option<String>,
+    /// Tracker event type (e.g. `CTSU`, `CCTR`)
+    pub event_type: Option<String>,
+    pub source: UpdateSource,
+}
+
+impl TimelineEvent {
+    /// Whether both describe the same status report, regardless of source.
+    pub fn same_report(&self, other: &Self) -> bool {
+        self.timestamp == other.timestamp && self.status == other.status && self.from == other.from
+    }
+}
+
+/// Storage for UETR timelines.
+pub trait TimelineStore: Send + Sync {
+    /// Append an event to a timeline.
+    fn append(&self, uetr: &str, event: &TimelineEvent) -> Result<(), SwiftError>;
+
+    /// Events of a UETR in insertion order.
+    fn timeline(&self, uetr: &str) -> Result<Vec<TimelineEvent>, SwiftError>;
+
+    /// All UETRs with a timeline.
+    fn uetrs(&self) -> Result<Vec<String>, SwiftError>;
+}
+
+/// In-memory timelines (lost on restart).
+#[derive(Default)]
+pub struct MemoryTimelineStore {
+    timelines: RwLock<HashMap<String, Vec<TimelineEvent>>>,
+}
+
+impl MemoryTimelineStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TimelineStore for MemoryTimelineStore {
+    fn append(&self, uetr: &str, event: &TimelineEvent) -> Result<(), SwiftError> {
+        self.timelines.write().unwrap()
+            .entry(uetr.to_string())
+            .or_default()
+            .push(event.clone());
+        Ok(())
+    }
+
+    fn timeline(&self, uetr: &str) -> Result<Vec<TimelineEvent>, SwiftError> {
+        Ok(self.timelines.read().unwrap().get(uetr).cloned().unwrap_or_default())
+    }
+
+    fn uetrs(&self) -> Result<Vec<String>, SwiftError> {
+        Ok(self.timelines.read().unwrap().keys().cloned().collect())
+    }
+}
+
+/// One JSON-lines file per UETR in a directory.
+pub struct FileTimelineStore {
+    dir: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl FileTimelineStore {
+    /// Store timelines under `dir` (created on first write).
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn path(&self, uetr: &str) -> Result<PathBuf, SwiftError> {
+        // UETRs are UUIDs; anything else could escape the directory
+        uuid::Uuid::parse_str(uetr)
+            .map_err(|_| SwiftError::ParseError(format!("Invalid UETR: {}", uetr)))?;
+        Ok(self.dir.join(format!("{}.jsonl", uetr.to_ascii_lowercase())))
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> SwiftError {
+    SwiftError::StorageError(e.to_string())
+}
+
+impl TimelineStore for FileTimelineStore {
+    fn append(&self, uetr: &str, event: &TimelineEvent) -> Result<(), SwiftError> {
+        let path = self.path(uetr)?;
+        let mut line = serde_json::to_string(event).map_err(storage_error)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap();
+        std::fs::create_dir_all(&self.dir).map_err(storage_error)?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(storage_error)
+    }
+
+    fn timeline(&self, uetr: &str) -> Result<Vec<TimelineEvent>, SwiftError> {
+        let content = match std::fs::read_to_string(self.path(uetr)?) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(e)),
+        };
+        content.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l).map_err(storage_error))
+            .collect()
+    }
+
+    fn uetrs(&self) -> Result<Vec<String>, SwiftError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(e)),
+        };
+        Ok(entries
+            .filter_map(Result::ok)
+            .filter_map(|e| e.file_name().to_str()?.strip_suffix(".jsonl").map(String::from))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("gpi-timeline-{}", uuid::Uuid::new_v4()));
+        let store = FileTimelineStore::new(&dir);
+        let uetr = "eb6305c9-1f7f-49de-aed0-16487c27b42d";
+        let event = TimelineEvent {
+            timestamp: "2025-12-26T12:00:00Z".into(),
+            status: "ACSP".into(),
+            reason: Some("G000".into()),
+            from: Some("ABCDDEFF".into()),
+            to: None,
+            event_type: Some("CTSU".into()),
+            source: UpdateSource::Tracker,
+        };
+
+        store.append(uetr, &event).unwrap();
+        assert_eq!(store.timeline(uetr).unwrap(), vec![event.clone()]);
+        assert_eq!(store.uetrs().unwrap(), vec![uetr.to_string()]);
+        assert!(store.append("../../etc/passwd", &event).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
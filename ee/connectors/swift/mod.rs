@@ -1,19 +1,49 @@
 //! SWIFT Enterprise Connector
 //!
-//! Full SWIFT integration: MX (ISO 20022), GPI Tracking, Sanctions
+//! Full SWIFT integration: MX (ISO 20022), GPI Tracking, Sanctions,
+//! payment pre-validation
 //! Per LICENSING.md: Banking tier ($80K+ deals)
 
 mod mx_parser;
+mod mx_schema;
 mod gpi;
 mod sanctions;
+mod camt;
+mod reconciliation;
+mod prevalidation;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use super::executor::{run_blocking, CallOptions};
+use super::gating::{ConnectorAction, ConnectorGate, GateRejection};
+use super::health::{ComponentHealth, ConnectorHealth, ErrorClass, HealthSource};
 use super::license::{check_feature_license, LicenseError};
+use super::secrets::{SecretError, SecretRef};
+use super::resilience::{ConnectorError, Resilience, ResilienceConfig, Unavailable};
 
 pub use mx_parser::MxParser;
-pub use gpi::GpiTracker;
-pub use sanctions::SanctionsScreener;
+pub use mx_schema::{validate_mx, ValidationMode, ValidationReport, ValidationIssue, IssueKind};
+pub use gpi::{
+    GpiTracker, GpiApiConfig, GpiClient, TrackerApi, TrackerProduct, TrackerPayment, PaymentEvent,
+    TransactionStatus, TrackerAmount, StatusConfirmation,
+    StatusUpdate, SubscriptionId, TimelineStore, FileTimelineStore, MemoryTimelineStore, TimelineEvent,
+    UpdateSource, status_codes,
+};
+pub use sanctions::{
+    SanctionsScreener, SanctionsList, SanctionsEntry, EntryKind, ListSnapshot, ListFetcher, HttpFetcher,
+    AllowListEntry, ScreeningRecord, ScreeningDecision, RefreshHandle,
+};
+pub use camt::{
+    parse_statement, BankStatement, AccountReport, Balance, StatementEntry, EntryTransaction, CreditDebit,
+};
+pub use reconciliation::{
+    Reconciler, ReconciliationReport, ReconciledPayment, ReconciliationBreak, BreakKind,
+};
+pub use prevalidation::{
+    PaymentValidator, PaymentValidation, PaymentIssue, PaymentRule, PrevalidationConfig, CutOff,
+    BicDirectory, BicRecord, MemoryBicDirectory, check_bic_format, check_iban, minor_units,
+};
 
 /// SWIFT connector configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,12 +52,25 @@ pub struct SwiftConfig {
     pub own_bic: String,
     /// Alliance Access/Lite endpoint
     pub endpoint: String,
-    /// Certificate path
-    pub cert_path: Option<String>,
+    /// Client certificate (PEM), resolved when connecting
+    #[serde(default)]
+    pub certificate: Option<SecretRef>,
     /// Enable GPI tracking
     pub gpi_enabled: bool,
+    /// gpi Tracker API access
+    #[serde(default)]
+    pub gpi_api: GpiApiConfig,
     /// Sanctions list sources
     pub sanctions_sources: Vec<String>,
+    /// ISO 20022 schema validation mode
+    #[serde(default)]
+    pub validation_mode: ValidationMode,
+    /// Payment pre-validation (cut-off times)
+    #[serde(default)]
+    pub prevalidation: PrevalidationConfig,
+    /// Retry and circuit breaker settings for gpi calls
+    #[serde(default)]
+    pub resilience: ResilienceConfig,
 }
 
 impl Default for SwiftConfig {
@@ -35,19 +78,31 @@ impl Default for SwiftConfig {
         Self {
             own_bic: String::new(),
             endpoint: String::new(),
-            cert_path: None,
+            certificate: None,
             gpi_enabled: true,
+            gpi_api: GpiApiConfig::default(),
             sanctions_sources: vec!["OFAC".to_string(), "EU".to_string(), "UN".to_string()],
+            validation_mode: ValidationMode::Strict,
+            prevalidation: PrevalidationConfig::default(),
+            resilience: ResilienceConfig::default(),
         }
     }
 }
 
 /// SWIFT connector for ISO 20022 messaging.
+///
+/// Screening and gpi calls are async and run on the blocking pool; the
+/// `*_blocking` variants run on the current thread, for CLI tools. Parsing
+/// and validation stay synchronous.
 pub struct SwiftConnector {
     config: SwiftConfig,
     mx_parser: MxParser,
-    gpi_tracker: Option<GpiTracker>,
-    sanctions: SanctionsScreener,
+    validator: PaymentValidator,
+    gpi_tracker: Option<Arc<GpiTracker>>,
+    gpi_resilience: Arc<Resilience>,
+    sanctions: Arc<SanctionsScreener>,
+    call_options: CallOptions,
+    gate: Option<Arc<ConnectorGate>>,
 }
 
 impl SwiftConnector {
@@ -56,15 +111,19 @@ impl SwiftConnector {
         check_feature_license("swift")?;
         
         let gpi_tracker = if config.gpi_enabled {
-            Some(GpiTracker::new(&config)?)
+            Some(Arc::new(GpiTracker::new(&config)?))
         } else {
             None
         };
         
         Ok(Self {
-            sanctions: SanctionsScreener::new(&config.sanctions_sources),
-            mx_parser: MxParser::new(),
+            sanctions: Arc::new(SanctionsScreener::new(&config.sanctions_sources)),
+            mx_parser: MxParser::new().with_mode(config.validation_mode),
+            validator: PaymentValidator::new(&config.prevalidation),
             gpi_tracker,
+            gpi_resilience: Arc::new(Resilience::new("swift-gpi", &config.resilience)),
+            call_options: CallOptions::from_config(&config.resilience),
+            gate: None,
             config,
         })
     }
@@ -74,32 +133,170 @@ impl SwiftConnector {
         self.mx_parser.parse(xml)
     }
     
+    /// Validate MX (ISO 20022) message against its schema.
+    pub fn validate_mx(&self, xml: &str) -> Result<ValidationReport, SwiftError> {
+        self.mx_parser.validate(xml)
+    }
+    
+    /// Override timeout and cancellation of async calls.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.call_options = options;
+        self
+    }
+    
+    /// Check payments and gpi calls against Gate policies before they run.
+    pub fn with_gate(mut self, gate: Arc<ConnectorGate>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+    
+    /// Reject payments whose agent BICs are not in `directory`.
+    pub fn with_bic_directory(mut self, directory: Arc<dyn BicDirectory>) -> Self {
+        self.validator = self.validator.with_directory(directory);
+        self
+    }
+    
+    /// Pre-validate a payment: BICs, IBANs, currency and amount, cut-off.
+    pub fn validate_payment(&self, payment: &PaymentInstruction) -> PaymentValidation {
+        self.validator.validate(payment)
+    }
+    
     /// Create payment initiation (pacs.008).
-    pub fn create_payment(&self, payment: PaymentInstruction) -> Result<String, SwiftError> {
-        // Check sanctions first
-        self.screen_payment(&payment)?;
+    ///
+    /// Pre-validated (errors reject it, see
+    /// [`validate_payment`](Self::validate_payment) for the warnings),
+    /// gated with the amount and both parties, then sanctions-screened.
+    pub async fn create_payment(&self, payment: PaymentInstruction) -> Result<String, SwiftError> {
+        self.validator.validate(&payment).into_result()?;
+        self.authorize(Self::payment_action(&payment)).await?;
+        
+        // Then sanctions
+        self.screen_payment(&payment).await?;
         
         // Create ISO 20022 pacs.008 message
         self.mx_parser.create_pacs008(&payment)
     }
     
+    /// Blocking [`create_payment`](Self::create_payment).
+    pub fn create_payment_blocking(&self, payment: PaymentInstruction) -> Result<String, SwiftError> {
+        self.validator.validate(&payment).into_result()?;
+        self.authorize_blocking(Self::payment_action(&payment))?;
+        self.screen_payment_blocking(&payment)?;
+        self.mx_parser.create_pacs008(&payment)
+    }
+    
+    /// Parse camt.053 statement or camt.054 notification.
+    pub fn parse_statement(&self, xml: &str) -> Result<BankStatement, SwiftError> {
+        self.mx_parser.parse_statement(xml)
+    }
+    
+    /// Reconcile sent payments against statements, flagging breaks.
+    pub fn reconcile(&self, payments: &[PaymentInstruction], statements: &[BankStatement]) -> ReconciliationReport {
+        Reconciler::new().reconcile(payments, statements)
+    }
+    
+    /// Use a configured sanctions screener.
+    pub fn with_sanctions(mut self, screener: SanctionsScreener) -> Self {
+        self.sanctions = Arc::new(screener);
+        self
+    }
+    
+    /// Sanctions screener (list loading, refresh, allow-list, audit).
+    pub fn sanctions(&self) -> &Arc<SanctionsScreener> {
+        &self.sanctions
+    }
+    
+    /// Fetch the configured sanctions lists.
+    pub async fn load_sanctions(&self) -> Result<usize, SwiftError> {
+        let sanctions = self.sanctions.clone();
+        self.run(move || sanctions.load_lists()).await
+    }
+    
     /// Screen payment against sanctions lists.
-    pub fn screen_payment(&self, payment: &PaymentInstruction) -> Result<SanctionsResult, SwiftError> {
+    pub async fn screen_payment(&self, payment: &PaymentInstruction) -> Result<SanctionsResult, SwiftError> {
+        let sanctions = self.sanctions.clone();
+        let (debtor, creditor) = (payment.debtor_name.clone(), payment.creditor_name.clone());
+        // Fuzzy matching against full lists is CPU-bound
+        self.run(move || sanctions.screen(&debtor, &creditor)).await
+    }
+    
+    /// Blocking [`screen_payment`](Self::screen_payment).
+    pub fn screen_payment_blocking(&self, payment: &PaymentInstruction) -> Result<SanctionsResult, SwiftError> {
         self.sanctions.screen(&payment.debtor_name, &payment.creditor_name)
     }
     
+    async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> Result<T, SwiftError> + Send + 'static,
+    ) -> Result<T, SwiftError> {
+        run_blocking(&format!("swift-{}", self.config.own_bic), &self.call_options, work).await
+    }
+    
+    async fn authorize(&self, action: ConnectorAction) -> Result<(), SwiftError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize(action).await?),
+            None => Ok(()),
+        }
+    }
+    
+    fn authorize_blocking(&self, action: ConnectorAction) -> Result<(), SwiftError> {
+        match &self.gate {
+            Some(gate) => Ok(gate.authorize_blocking(action)?),
+            None => Ok(()),
+        }
+    }
+    
+    fn payment_action(payment: &PaymentInstruction) -> ConnectorAction {
+        let parties = [
+            Some(&payment.debtor_name), Some(&payment.debtor_account),
+            Some(&payment.creditor_name), Some(&payment.creditor_account),
+            Some(&payment.instructing_agent), payment.instructed_agent.as_ref(),
+        ];
+        ConnectorAction {
+            counterparties: parties.into_iter().flatten().cloned().collect(),
+            ..ConnectorAction::new("swift", "create_payment")
+                .with_target(payment.end_to_end_id())
+                .with_amount(payment.amount, &payment.currency)
+        }
+    }
+    
+    fn tracker(&self) -> Result<Arc<GpiTracker>, SwiftError> {
+        self.gpi_tracker.clone().ok_or(SwiftError::GpiNotEnabled)
+    }
+    
     /// Track GPI payment.
-    pub fn track_payment(&self, uetr: &str) -> Result<GpiStatus, SwiftError> {
-        let tracker = self.gpi_tracker.as_ref()
-            .ok_or(SwiftError::GpiNotEnabled)?;
-        tracker.track(uetr)
+    pub async fn track_payment(&self, uetr: &str) -> Result<GpiStatus, SwiftError> {
+        self.authorize(ConnectorAction::new("swift", "track_payment").with_target(uetr)).await?;
+        let (tracker, resilience) = (self.tracker()?, self.gpi_resilience.clone());
+        let uetr = uetr.to_string();
+        self.run(move || resilience.call(|| tracker.track(&uetr))).await
+    }
+    
+    /// Blocking [`track_payment`](Self::track_payment).
+    pub fn track_payment_blocking(&self, uetr: &str) -> Result<GpiStatus, SwiftError> {
+        self.authorize_blocking(ConnectorAction::new("swift", "track_payment").with_target(uetr))?;
+        let tracker = self.tracker()?;
+        self.gpi_resilience.call(|| tracker.track(uetr))
+    }
+    
+    /// GPI tracker (webhooks, subscriptions, timelines), if enabled.
+    pub fn gpi_tracker(&self) -> Option<&GpiTracker> {
+        self.gpi_tracker.as_deref()
     }
     
     /// Get GPI confirmations.
-    pub fn get_confirmations(&self, uetr: &str) -> Result<Vec<GpiConfirmation>, SwiftError> {
-        let tracker = self.gpi_tracker.as_ref()
-            .ok_or(SwiftError::GpiNotEnabled)?;
-        tracker.get_confirmations(uetr)
+    pub async fn get_confirmations(&self, uetr: &str) -> Result<Vec<GpiConfirmation>, SwiftError> {
+        self.authorize(ConnectorAction::new("swift", "get_confirmations").with_target(uetr)).await?;
+        let (tracker, resilience) = (self.tracker()?, self.gpi_resilience.clone());
+        let uetr = uetr.to_string();
+        self.run(move || resilience.call(|| tracker.get_confirmations(&uetr))).await
+    }
+    
+    /// Blocking [`get_confirmations`](Self::get_confirmations).
+    pub fn get_confirmations_blocking(&self, uetr: &str) -> Result<Vec<GpiConfirmation>, SwiftError> {
+        self.authorize_blocking(ConnectorAction::new("swift", "get_confirmations").with_target(uetr))?;
+        let tracker = self.tracker()?;
+        self.gpi_resilience.call(|| tracker.get_confirmations(uetr))
     }
     
     /// Health check.
@@ -112,6 +309,23 @@ impl SwiftConnector {
     }
 }
 
+impl HealthSource for SwiftConnector {
+    fn health(&self) -> ConnectorHealth {
+        // Payments are screened before they are created; without lists
+        // every screening passes
+        let sanctions = if self.sanctions.list_count() > 0 {
+            ComponentHealth::up("sanctions")
+        } else {
+            ComponentHealth::degraded("sanctions", "no sanctions lists loaded")
+        };
+        let mut health = ConnectorHealth::new(&format!("swift-{}", self.config.own_bic)).with_component(sanctions);
+        if self.gpi_tracker.is_some() {
+            health = health.with_component(ComponentHealth::from_circuit("gpi", self.gpi_resilience.state()));
+        }
+        health
+    }
+}
+
 /// Payment instruction.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentInstruction {
@@ -126,6 +340,16 @@ pub struct PaymentInstruction {
     pub amount: f64,
     pub currency: String,
     pub remittance_info: Option<String>,
+    /// gpi UETR (lowercase UUID v4)
+    #[serde(default)]
+    pub uetr: Option<String>,
+}
+
+impl PaymentInstruction {
+    /// EndToEndId sent in pacs.008 (the message ID).
+    pub fn end_to_end_id(&self) -> &str {
+        &self.message_id
+    }
 }
 
 /// Parsed MX message.
@@ -135,6 +359,9 @@ pub struct MxMessage {
     pub document_id: String,
     pub creation_date: String,
     pub content: serde_json::Value,
+    /// Schema warnings (lenient mode)
+    #[serde(default)]
+    pub warnings: Vec<ValidationIssue>,
 }
 
 /// GPI tracking status.
@@ -166,7 +393,12 @@ pub struct SanctionsResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SanctionsMatch {
     pub list: String,
+    /// List entry identifier, e.g. `OFAC-2674`
+    pub entry_id: String,
+    /// Matched list name (primary name or alias)
     pub name: String,
+    /// Name that was screened
+    pub screened_name: String,
     pub score: f64,
 }
 
@@ -187,16 +419,58 @@ pub enum SwiftError {
     #[error("Parse error: {0}")]
     ParseError(String),
     
+    #[error("Schema validation failed: {0}")]
+    Validation(ValidationReport),
+    
+    #[error("Payment pre-validation failed: {0}")]
+    PaymentRejected(PaymentValidation),
+    
     #[error("Sanctions hit: {0}")]
     SanctionsHit(String),
     
     #[error("Network error: {0}")]
     NetworkError(String),
     
+    #[error("Unknown UETR: {0}")]
+    UnknownUetr(String),
+    
+    #[error("Webhook rejected: {0}")]
+    WebhookRejected(String),
+    
+    #[error("Storage error: {0}")]
+    StorageError(String),
+    
+    #[error("{0} unavailable: {1}")]
+    Unavailable(String, Unavailable),
+    
+    #[error("Gate: {0}")]
+    Gated(#[from] GateRejection),
+    
+    #[error("Secret error: {0}")]
+    Secret(#[from] SecretError),
+    
     #[error("License error: {0}")]
     LicenseError(#[from] LicenseError),
 }
 
+impl ConnectorError for SwiftError {
+    fn is_transient(&self) -> bool {
+        matches!(self, SwiftError::NetworkError(_))
+    }
+
+    fn unavailable(service: &str, reason: Unavailable) -> Self {
+        SwiftError::Unavailable(service.to_string(), reason)
+    }
+
+    fn class(&self) -> ErrorClass {
+        match self {
+            SwiftError::Unavailable(..) => ErrorClass::Unavailable,
+            e if e.is_transient() => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -226,6 +500,7 @@ mod tests {
             amount: 1000.00,
             currency: "EUR".into(),
             remittance_info: Some("Invoice 123".into()),
+            uetr: None,
         };
         
         assert_eq!(payment.currency, "EUR");
@@ -2,27 +2,64 @@
 //!
 //! Parse and create ISO 20022 messages (pacs, pain, camt, etc.)
 
+use super::camt::{self, BankStatement};
+use super::mx_schema::{self, ValidationMode, ValidationReport};
+use super::prevalidation::minor_units;
 use super::{MxMessage, PaymentInstruction, SwiftError};
 
 /// ISO 20022 MX message parser.
-pub struct MxParser;
+pub struct MxParser {
+    mode: ValidationMode,
+}
 
 impl MxParser {
-    /// Create new parser.
+    /// Create new parser (strict validation).
     pub fn new() -> Self {
-        Self
+        Self { mode: ValidationMode::Strict }
+    }
+
+    /// Set validation mode.
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Current validation mode.
+    pub fn mode(&self) -> ValidationMode {
+        self.mode
     }
     
     /// Parse ISO 20022 XML message.
+    ///
+    /// Messages with an embedded schema (pacs.008, pacs.002, camt.053,
+    /// camt.054) are validated first and rejected with
+    /// `SwiftError::Validation` when they have errors.
     pub fn parse(&self, xml: &str) -> Result<MxMessage, SwiftError> {
-        // Production would use serde_xml or quick-xml
+        let doc = roxmltree::Document::parse(xml)
+            .map_err(|e| SwiftError::ParseError(e.to_string()))?;
         let message_type = self.detect_message_type(xml)?;
+
+        let warnings = if matches!(message_type.as_str(), "pacs.008" | "pacs.002" | "camt.053" | "camt.054") {
+            mx_schema::validate_document(&doc, self.mode)?.into_result()?.warnings
+        } else {
+            Vec::new()
+        };
+
+        // Group header of the message body, if any
+        let header = mx_schema::find_document(&doc)
+            .and_then(|d| d.children().find(|n| n.is_element()))
+            .and_then(|body| body.children().find(|n| n.has_tag_name("GrpHdr")));
+        let field = |name: &str| header
+            .and_then(|h| h.children().find(|n| n.has_tag_name(name)))
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string());
         
         Ok(MxMessage {
             message_type,
-            document_id: uuid::Uuid::new_v4().to_string(),
-            creation_date: chrono::Utc::now().to_rfc3339(),
+            document_id: field("MsgId").unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            creation_date: field("CreDtTm").unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
             content: serde_json::json!({ "raw": xml.len() }),
+            warnings,
         })
     }
     
@@ -36,67 +73,86 @@ impl MxParser {
             Ok("pain.001".to_string())
         } else if xml.contains("camt.053") || xml.contains("BkToCstmrStmt") {
             Ok("camt.053".to_string())
+        } else if xml.contains("camt.054") || xml.contains("BkToCstmrDbtCdtNtfctn") {
+            Ok("camt.054".to_string())
         } else {
             Err(SwiftError::ParseError("Unknown message type".into()))
         }
     }
     
     /// Create pacs.008 FI to FI Customer Credit Transfer.
+    ///
+    /// The generated document is validated before it is returned.
     pub fn create_pacs008(&self, payment: &PaymentInstruction) -> Result<String, SwiftError> {
+        let agent = |bic: &str| format!("<FinInstnId><BICFI>{}</BICFI></FinInstnId>", escape(bic));
+        // Without an instructed agent the creditor agent is not known to us
+        let creditor_agent = match &payment.instructed_agent {
+            Some(bic) => agent(bic),
+            None => "<FinInstnId><Othr><Id>NOTPROVIDED</Id></Othr></FinInstnId>".to_string(),
+        };
+        let instructed_agent = payment.instructed_agent.as_deref()
+            .map(|bic| format!("\n            <InstdAgt>{}</InstdAgt>", agent(bic)))
+            .unwrap_or_default();
+        let uetr = payment.uetr.as_deref()
+            .map(|uetr| format!("\n                <UETR>{}</UETR>", escape(&uetr.to_ascii_lowercase())))
+            .unwrap_or_default();
+        let remittance = payment.remittance_info.as_deref()
+            .map(|info| format!("\n            <RmtInf><Ustrd>{}</Ustrd></RmtInf>", escape(info)))
+            .unwrap_or_default();
+
         let xml = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
 <Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08">
     <FIToFICstmrCdtTrf>
         <GrpHdr>
-            <MsgId>{}</MsgId>
-            <CreDtTm>{}</CreDtTm>
+            <MsgId>{msg_id}</MsgId>
+            <CreDtTm>{created}</CreDtTm>
             <NbOfTxs>1</NbOfTxs>
             <SttlmInf>
                 <SttlmMtd>CLRG</SttlmMtd>
             </SttlmInf>
-            <InstgAgt>
-                <FinInstnId>
-                    <BICFI>{}</BICFI>
-                </FinInstnId>
-            </InstgAgt>
+            <InstgAgt>{instructing}</InstgAgt>
         </GrpHdr>
         <CdtTrfTxInf>
             <PmtId>
-                <InstrId>{}</InstrId>
-                <EndToEndId>{}</EndToEndId>
+                <InstrId>{msg_id}</InstrId>
+                <EndToEndId>{msg_id}</EndToEndId>{uetr}
             </PmtId>
-            <IntrBkSttlmAmt Ccy="{}">{:.2}</IntrBkSttlmAmt>
+            <IntrBkSttlmAmt Ccy="{currency}">{amount:.decimals$}</IntrBkSttlmAmt>
+            <ChrgBr>SHAR</ChrgBr>{instructed_agent}
             <Dbtr>
-                <Nm>{}</Nm>
+                <Nm>{debtor}</Nm>
             </Dbtr>
             <DbtrAcct>
                 <Id>
-                    <IBAN>{}</IBAN>
+                    <IBAN>{debtor_account}</IBAN>
                 </Id>
             </DbtrAcct>
+            <DbtrAgt>{instructing}</DbtrAgt>
+            <CdtrAgt>{creditor_agent}</CdtrAgt>
             <Cdtr>
-                <Nm>{}</Nm>
+                <Nm>{creditor}</Nm>
             </Cdtr>
             <CdtrAcct>
                 <Id>
-                    <IBAN>{}</IBAN>
+                    <IBAN>{creditor_account}</IBAN>
                 </Id>
-            </CdtrAcct>
+            </CdtrAcct>{remittance}
         </CdtTrfTxInf>
     </FIToFICstmrCdtTrf>
 </Document>"#,
-            payment.message_id,
-            payment.creation_date_time,
-            payment.instructing_agent,
-            payment.message_id,
-            payment.message_id,
-            payment.currency,
-            payment.amount,
-            payment.debtor_name,
-            payment.debtor_account,
-            payment.creditor_name,
-            payment.creditor_account
+            msg_id = escape(&payment.message_id),
+            created = escape(&payment.creation_date_time),
+            instructing = agent(&payment.instructing_agent),
+            currency = escape(&payment.currency),
+            amount = payment.amount,
+            decimals = minor_units(&payment.currency).unwrap_or(2) as usize,
+            debtor = escape(&payment.debtor_name),
+            debtor_account = escape(&payment.debtor_account),
+            creditor = escape(&payment.creditor_name),
+            creditor_account = escape(&payment.creditor_account),
         );
-        
+
+        mx_schema::validate_mx(&xml, self.mode)?.into_result()?;
         Ok(xml)
     }
     
@@ -106,11 +162,31 @@ impl MxParser {
         Ok(format!("<?xml version=\"1.0\"?><pain.001>{}</pain.001>", payment.message_id))
     }
     
-    /// Validate message against schema.
-    pub fn validate(&self, xml: &str, schema: &str) -> Result<bool, SwiftError> {
-        // Production would use XML schema validation
-        Ok(!xml.is_empty() && !schema.is_empty())
+    /// Parse camt.053 statement or camt.054 notification.
+    pub fn parse_statement(&self, xml: &str) -> Result<BankStatement, SwiftError> {
+        camt::parse_statement(xml, self.mode)
+    }
+    
+    /// Validate message against its embedded ISO 20022 schema.
+    pub fn validate(&self, xml: &str) -> Result<ValidationReport, SwiftError> {
+        mx_schema::validate_mx(xml, self.mode)
+    }
+}
+
+/// Escape text for XML element and attribute content.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
     }
+    out
 }
 
 impl Default for MxParser {
@@ -145,10 +221,50 @@ mod tests {
             amount: 1000.00,
             currency: "EUR".into(),
             remittance_info: None,
+            uetr: None,
         };
         
         let xml = parser.create_pacs008(&payment).unwrap();
         assert!(xml.contains("FIToFICstmrCdtTrf"));
         assert!(xml.contains("1000.00"));
     }
+
+    #[test]
+    fn test_parse_rejects_invalid_pacs008() {
+        let xml = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08">
+<FIToFICstmrCdtTrf><GrpHdr><MsgId>MSG001</MsgId></GrpHdr></FIToFICstmrCdtTrf></Document>"#;
+
+        match MxParser::new().parse(xml) {
+            Err(SwiftError::Validation(report)) => {
+                assert!(report.errors.iter().any(|e| e.path == "/Document/FIToFICstmrCdtTrf/GrpHdr/CreDtTm"));
+                assert!(report.errors.iter().any(|e| e.path == "/Document/FIToFICstmrCdtTrf/CdtTrfTxInf"));
+            }
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_created_message_lenient() {
+        let payment = PaymentInstruction {
+            message_id: "MSG002".into(),
+            creation_date_time: "2025-12-26T12:00:00Z".into(),
+            instructing_agent: "ABCDDEFF".into(),
+            instructed_agent: Some("IJKLGB2L".into()),
+            debtor_name: "Smith & Sons".into(),
+            debtor_account: "DE89370400440532013000".into(),
+            creditor_name: "Jane Smith".into(),
+            creditor_account: "GB33BUKB20201555555555".into(),
+            amount: 250.00,
+            currency: "EUR".into(),
+            remittance_info: Some("Invoice 123".into()),
+            uetr: None,
+        };
+        let parser = MxParser::new().with_mode(ValidationMode::Lenient);
+        let xml = parser.create_pacs008(&payment).unwrap();
+
+        let message = parser.parse(&xml.replace("<ChrgBr>SHAR", "<ChrgBr>XXXX")).unwrap();
+        assert_eq!(message.document_id, "MSG002");
+        assert_eq!(message.warnings.len(), 1);
+        assert!(MxParser::new().parse(&xml.replace("<ChrgBr>SHAR", "<ChrgBr>XXXX")).is_err());
+    }
 }
@@ -0,0 +1,1014 @@
+//! MX Schema - ISO 20022 structural validation
+//!
+//! Validates MX documents against the XSD content models of the core
+//! message types before they reach downstream systems:
+//!
+//! - pacs.008.001.08 FI to FI Customer Credit Transfer
+//! - pacs.002.001.10 FI to FI Payment Status Report
+//! - camt.053.001.08 Bank to Customer Statement
+//! - camt.054.001.08 Bank to Customer Debit/Credit Notification
+//!
+//! The schemas are compiled in from the published XSDs: element order,
+//! cardinality, choices, mandatory attributes and simple-type facets
+//! (lengths, code lists, patterns, dates, decimal digits). Deeply nested
+//! optional blocks (postal addresses, remittance structures, charges,
+//! card data, ...) are accepted without descending into them.
+//!
+//! Every issue carries an XPath location, e.g.
+//! `/Document/FIToFICstmrCdtTrf/CdtTrfTxInf[2]/IntrBkSttlmAmt/@Ccy`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_connectors::swift::{validate_mx, ValidationMode};
+//!
+//! let report = validate_mx(xml, ValidationMode::Strict)?;
+//! if !report.is_valid() {
+//!     for issue in &report.errors {
+//!         eprintln!("{issue}");
+//!     }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::SwiftError;
+
+/// Namespace prefix of ISO 20022 message schemas.
+pub const ISO20022_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:";
+
+/// How strictly documents are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationMode {
+    /// Every deviation from the schema is an error.
+    #[default]
+    Strict,
+    /// Unknown elements, facet violations (lengths, patterns, code lists)
+    /// and schema version mismatches are downgraded to warnings. Missing,
+    /// misplaced or mistyped elements remain errors.
+    Lenient,
+}
+
+/// Category of a validation issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Mandatory element or attribute is absent.
+    Missing,
+    /// Element occurs more often than allowed.
+    TooMany,
+    /// Element is known but out of sequence.
+    OutOfOrder,
+    /// Element is not part of the content model.
+    Unexpected,
+    /// Value is not of the declared datatype (date, decimal, ...).
+    InvalidType,
+    /// Value violates a facet (length, pattern, code list, digits).
+    Facet,
+    /// Namespace or schema version does not match.
+    Namespace,
+}
+
+impl IssueKind {
+    /// Whether lenient mode reports this kind as a warning.
+    fn is_relaxable(self) -> bool {
+        matches!(self, Self::Unexpected | Self::Facet | Self::Namespace)
+    }
+}
+
+/// A single schema violation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// XPath of the offending element or attribute
+    pub path: String,
+    /// Line in the source document (1-based)
+    pub line: u32,
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (line {}): {}", self.path, self.line, self.message)
+    }
+}
+
+/// Outcome of validating one document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Message type, e.g. `pacs.008`
+    pub message_type: String,
+    /// Full schema identifier, e.g. `pacs.008.001.08`
+    pub schema: String,
+    pub mode: ValidationMode,
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// True when the document has no errors (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Convert into `Err(SwiftError::Validation)` when there are errors.
+    pub fn into_result(self) -> Result<Self, SwiftError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(SwiftError::Validation(self))
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} against {}: {} error(s), {} warning(s)",
+            self.message_type, self.schema, self.errors.len(), self.warnings.len())?;
+        if let Some(first) = self.errors.first() {
+            write!(f, "; first: {first}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate an MX document against its embedded schema.
+///
+/// The message type is taken from the `Document` namespace. A `Document`
+/// wrapped in an envelope (e.g. next to a Business Application Header) is
+/// located automatically. Fails with `ParseError` for malformed XML or
+/// message types without an embedded schema.
+pub fn validate_mx(xml: &str, mode: ValidationMode) -> Result<ValidationReport, SwiftError> {
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| SwiftError::ParseError(e.to_string()))?;
+    validate_document(&doc, mode)
+}
+
+/// Validate an already parsed document.
+pub(crate) fn validate_document(
+    doc: &roxmltree::Document<'_>,
+    mode: ValidationMode,
+) -> Result<ValidationReport, SwiftError> {
+    let root = find_document(doc)
+        .ok_or_else(|| SwiftError::ParseError("No ISO 20022 Document element".into()))?;
+
+    let mut v = Validator { doc, mode, errors: Vec::new(), warnings: Vec::new() };
+
+    let namespace = root.tag_name().namespace().unwrap_or("");
+    let schema = match namespace.strip_prefix(ISO20022_NAMESPACE) {
+        Some(id) => match schema_for(id) {
+            Some(schema) => {
+                if schema.id != id {
+                    v.report(root, "/Document".into(), IssueKind::Namespace, format!(
+                        "schema version {} is not supported, validated against {}", id, schema.id));
+                }
+                schema
+            }
+            None => return Err(SwiftError::ParseError(format!("No schema for message {}", id))),
+        },
+        None => {
+            let body = first_element(root)
+                .ok_or_else(|| SwiftError::ParseError("Empty Document".into()))?;
+            let schema = SCHEMAS.iter()
+                .find(|s| s.root.name == body.tag_name().name())
+                .ok_or_else(|| SwiftError::ParseError(format!(
+                    "No schema for message root {}", body.tag_name().name())))?;
+            v.report(root, "/Document".into(), IssueKind::Namespace, format!(
+                "missing namespace {}{}", ISO20022_NAMESPACE, schema.id));
+            schema
+        }
+    };
+
+    v.check_children(root, "/Document", Content::Sequence(std::slice::from_ref(&schema.root)));
+
+    Ok(ValidationReport {
+        message_type: schema.message_type().to_string(),
+        schema: schema.id.to_string(),
+        mode,
+        errors: v.errors,
+        warnings: v.warnings,
+    })
+}
+
+/// The ISO 20022 `Document` element: the root itself or inside an envelope.
+pub(crate) fn find_document<'a, 'i>(doc: &'a roxmltree::Document<'i>) -> Option<roxmltree::Node<'a, 'i>> {
+    doc.root().descendants()
+        .find(|n| n.is_element() && n.tag_name().name() == "Document")
+}
+
+/// Embedded schema matching `id`; falls back to the same message in another version.
+fn schema_for(id: &str) -> Option<&'static Schema> {
+    SCHEMAS.iter().find(|s| s.id == id).or_else(|| {
+        let message = id.get(..8)?;
+        SCHEMAS.iter().find(|s| s.message_type() == message)
+    })
+}
+
+fn first_element<'a, 'i>(node: roxmltree::Node<'a, 'i>) -> Option<roxmltree::Node<'a, 'i>> {
+    node.children().find(|n| n.is_element())
+}
+
+struct Validator<'a, 'i> {
+    doc: &'a roxmltree::Document<'i>,
+    mode: ValidationMode,
+    errors: Vec<ValidationIssue>,
+    warnings: Vec<ValidationIssue>,
+}
+
+impl Validator<'_, '_> {
+    fn report(&mut self, node: roxmltree::Node<'_, '_>, path: String, kind: IssueKind, message: String) {
+        let issue = ValidationIssue {
+            path,
+            line: self.doc.text_pos_at(node.range().start).row,
+            kind,
+            message,
+        };
+        if self.mode == ValidationMode::Lenient && kind.is_relaxable() {
+            self.warnings.push(issue);
+        } else {
+            self.errors.push(issue);
+        }
+    }
+
+    fn check_element(&mut self, node: roxmltree::Node<'_, '_>, path: &str, ty: &Type) {
+        match ty.content {
+            Content::Any => {}
+            Content::Sequence(_) | Content::Choice(_) => self.check_children(node, path, ty.content),
+            Content::Simple(simple) => {
+                if let Some(child) = first_element(node) {
+                    self.report(child, format!("{}/{}", path, child.tag_name().name()),
+                        IssueKind::Unexpected, format!("{} has text content only", ty.name));
+                    return;
+                }
+                let value = node.text().unwrap_or("").trim();
+                self.check_value(node, path, ty.name, simple, value);
+            }
+            Content::Amount => {
+                let value = node.text().unwrap_or("").trim();
+                self.check_value(node, path, ty.name, Simple::Decimal { total: 18, fraction: 5 }, value);
+                match node.attribute("Ccy") {
+                    Some(ccy) => self.check_value(node, &format!("{}/@Ccy", path),
+                        "ActiveOrHistoricCurrencyCode", Simple::Currency, ccy),
+                    None => self.report(node, format!("{}/@Ccy", path),
+                        IssueKind::Missing, "missing mandatory attribute Ccy".into()),
+                }
+            }
+        }
+    }
+
+    fn check_children(&mut self, node: roxmltree::Node<'_, '_>, path: &str, content: Content) {
+        let fields = match content {
+            Content::Sequence(fields) | Content::Choice(fields) => fields,
+            _ => return,
+        };
+
+        // Unknown elements first, so they do not derail sequence matching
+        let mut children = Vec::new();
+        for child in node.children().filter(|n| n.is_element()) {
+            let name = child.tag_name().name();
+            if fields.iter().any(|f| f.name == name) {
+                children.push(child);
+            } else {
+                self.report(child, format!("{}/{}", path, name), IssueKind::Unexpected,
+                    format!("element {} is not allowed here", name));
+            }
+        }
+
+        if let Content::Choice(options) = content {
+            self.check_choice(node, path, options, &children);
+            return;
+        }
+
+        let mut i = 0;
+        for field in fields {
+            let mut count = 0;
+            while i < children.len() && children[i].tag_name().name() == field.name {
+                count += 1;
+                let child_path = field.path(path, count);
+                if field.max.is_some_and(|max| count > max) {
+                    self.report(children[i], child_path, IssueKind::TooMany, format!(
+                        "{} occurs more than {} time(s)", field.name, field.max.unwrap_or_default()));
+                } else {
+                    self.check_element(children[i], &child_path, field.ty);
+                }
+                i += 1;
+            }
+            // A mandatory element appearing later is reported as out of order instead
+            let later = children[i..].iter().any(|c| c.tag_name().name() == field.name);
+            if count < field.min && !later {
+                self.report(node, format!("{}/{}", path, field.name), IssueKind::Missing, format!(
+                    "missing mandatory element {} ({})", field.name, field.ty.name));
+            }
+        }
+
+        for child in &children[i..] {
+            let name = child.tag_name().name();
+            self.report(*child, format!("{}/{}", path, name), IssueKind::OutOfOrder,
+                format!("element {} is out of sequence", name));
+        }
+    }
+
+    fn check_choice(
+        &mut self,
+        node: roxmltree::Node<'_, '_>,
+        path: &str,
+        options: &[Field],
+        children: &[roxmltree::Node<'_, '_>],
+    ) {
+        let Some((first, rest)) = children.split_first() else {
+            let names: Vec<_> = options.iter().map(|f| f.name).collect();
+            self.report(node, format!("{}/({})", path, names.join("|")), IssueKind::Missing,
+                format!("expected one of {}", names.join(", ")));
+            return;
+        };
+        let name = first.tag_name().name();
+        if let Some(field) = options.iter().find(|f| f.name == name) {
+            self.check_element(*first, &format!("{}/{}", path, name), field.ty);
+        }
+        for extra in rest {
+            let extra_name = extra.tag_name().name();
+            self.report(*extra, format!("{}/{}", path, extra_name), IssueKind::TooMany,
+                format!("choice already satisfied by {}", name));
+        }
+    }
+
+    fn check_value(&mut self, node: roxmltree::Node<'_, '_>, path: &str, ty: &str, simple: Simple, value: &str) {
+        let (kind, problem) = match simple.check(value) {
+            Ok(()) => return,
+            Err(err) => err,
+        };
+        self.report(node, path.to_string(), kind, format!("{:?} is not a valid {}: {}", value, ty, problem));
+    }
+}
+
+// ============================================================================
+// SCHEMA MODEL
+// ============================================================================
+
+/// A message schema: identifier and its single top-level element.
+struct Schema {
+    /// e.g. `pacs.008.001.08`
+    id: &'static str,
+    root: Field,
+}
+
+impl Schema {
+    fn message_type(&self) -> &'static str {
+        &self.id[..8]
+    }
+}
+
+/// An element declaration with its cardinality.
+#[derive(Clone, Copy)]
+struct Field {
+    name: &'static str,
+    min: u32,
+    /// `None` = unbounded
+    max: Option<u32>,
+    ty: &'static Type,
+}
+
+impl Field {
+    const fn one(name: &'static str, ty: &'static Type) -> Self {
+        Self { name, min: 1, max: Some(1), ty }
+    }
+
+    const fn opt(name: &'static str, ty: &'static Type) -> Self {
+        Self { name, min: 0, max: Some(1), ty }
+    }
+
+    const fn many(name: &'static str, min: u32, ty: &'static Type) -> Self {
+        Self { name, min, max: None, ty }
+    }
+
+    const fn up_to(name: &'static str, max: u32, ty: &'static Type) -> Self {
+        Self { name, min: 0, max: Some(max), ty }
+    }
+
+    /// XPath step; repeatable elements carry a position predicate.
+    fn path(&self, parent: &str, position: u32) -> String {
+        if self.max == Some(1) {
+            format!("{}/{}", parent, self.name)
+        } else {
+            format!("{}/{}[{}]", parent, self.name, position)
+        }
+    }
+}
+
+/// A named XSD type.
+struct Type {
+    name: &'static str,
+    content: Content,
+}
+
+impl Type {
+    const fn seq(name: &'static str, fields: &'static [Field]) -> Self {
+        Self { name, content: Content::Sequence(fields) }
+    }
+
+    const fn choice(name: &'static str, fields: &'static [Field]) -> Self {
+        Self { name, content: Content::Choice(fields) }
+    }
+
+    const fn simple(name: &'static str, simple: Simple) -> Self {
+        Self { name, content: Content::Simple(simple) }
+    }
+
+    /// Complex type accepted without descending into it.
+    const fn any(name: &'static str) -> Self {
+        Self { name, content: Content::Any }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Content {
+    Sequence(&'static [Field]),
+    Choice(&'static [Field]),
+    Simple(Simple),
+    /// Decimal amount with a mandatory `Ccy` attribute
+    Amount,
+    Any,
+}
+
+/// Simple-type restrictions used by the embedded schemas.
+#[derive(Clone, Copy)]
+enum Simple {
+    Text { min: usize, max: usize },
+    /// `[0-9]{1,max}`
+    Numeric { max: usize },
+    Code(&'static [&'static str]),
+    Decimal { total: usize, fraction: usize },
+    Bool,
+    Date,
+    DateTime,
+    Bic,
+    Iban,
+    Uuid,
+    Currency,
+    Country,
+}
+
+impl Simple {
+    fn check(self, value: &str) -> Result<(), (IssueKind, String)> {
+        let facet = |msg: String| Err((IssueKind::Facet, msg));
+        let invalid = |msg: &str| Err((IssueKind::InvalidType, msg.to_string()));
+        match self {
+            Self::Text { min, max } => {
+                let len = value.chars().count();
+                if len < min || len > max {
+                    return facet(format!("length {} outside {}..={}", len, min, max));
+                }
+            }
+            Self::Numeric { max } => {
+                if value.is_empty() || value.len() > max || !value.bytes().all(|b| b.is_ascii_digit()) {
+                    return facet(format!("expected 1 to {} digits", max));
+                }
+            }
+            Self::Code(codes) => {
+                if !codes.contains(&value) {
+                    return facet(format!("expected one of {}", codes.join(", ")));
+                }
+            }
+            Self::Decimal { total, fraction } => {
+                let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+                let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+                if int.is_empty() && frac.is_empty()
+                    || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+                {
+                    return invalid("not a decimal number");
+                }
+                if value.starts_with('-') {
+                    return facet("must not be negative".into());
+                }
+                let frac = frac.trim_end_matches('0');
+                let significant = int.trim_start_matches('0').len() + frac.len();
+                if frac.len() > fraction {
+                    return facet(format!("more than {} fraction digits", fraction));
+                }
+                if significant > total {
+                    return facet(format!("more than {} total digits", total));
+                }
+            }
+            Self::Bool => {
+                if !matches!(value, "true" | "false" | "1" | "0") {
+                    return invalid("expected true or false");
+                }
+            }
+            Self::Date => {
+                if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_err() {
+                    return invalid("expected YYYY-MM-DD");
+                }
+            }
+            Self::DateTime => {
+                let local = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f");
+                if chrono::DateTime::parse_from_rfc3339(value).is_err() && local.is_err() {
+                    return invalid("expected YYYY-MM-DDThh:mm:ss with optional offset");
+                }
+            }
+            Self::Bic => {
+                // [A-Z0-9]{4}[A-Z]{2}[A-Z0-9]{2}([A-Z0-9]{3})?
+                let b = value.as_bytes();
+                let alnum = |s: &[u8]| s.iter().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+                if !matches!(b.len(), 8 | 11) || !alnum(&b[..4]) || !b[4..6].iter().all(u8::is_ascii_uppercase) || !alnum(&b[6..]) {
+                    return facet("expected an 8 or 11 character BIC".into());
+                }
+            }
+            Self::Iban => {
+                // [A-Z]{2}[0-9]{2}[a-zA-Z0-9]{1,30}
+                let b = value.as_bytes();
+                if !(5..=34).contains(&b.len())
+                    || !b[..2].iter().all(u8::is_ascii_uppercase)
+                    || !b[2..4].iter().all(u8::is_ascii_digit)
+                    || !b[4..].iter().all(u8::is_ascii_alphanumeric)
+                {
+                    return facet("expected country code, check digits and up to 30 characters".into());
+                }
+            }
+            Self::Uuid => {
+                // UUIDv4, lowercase: [a-f0-9]{8}-[a-f0-9]{4}-4[a-f0-9]{3}-[89ab][a-f0-9]{3}-[a-f0-9]{12}
+                let b = value.as_bytes();
+                let hex = |c: &u8| c.is_ascii_digit() || (b'a'..=b'f').contains(c);
+                let groups: Vec<&str> = value.split('-').collect();
+                let lens: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+                if lens != [8, 4, 4, 4, 12]
+                    || !b.iter().filter(|c| **c != b'-').all(hex)
+                    || b[14] != b'4'
+                    || !matches!(b[19], b'8' | b'9' | b'a' | b'b')
+                {
+                    return facet("expected a lowercase UUIDv4".into());
+                }
+            }
+            Self::Currency | Self::Country => {
+                let len = if matches!(self, Self::Currency) { 3 } else { 2 };
+                if value.len() != len || !value.bytes().all(|c| c.is_ascii_uppercase()) {
+                    return facet(format!("expected {} uppercase letters", len));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SHARED TYPES
+// ============================================================================
+
+static MAX35_TEXT: Type = Type::simple("Max35Text", Simple::Text { min: 1, max: 35 });
+static MAX34_TEXT: Type = Type::simple("Max34Text", Simple::Text { min: 1, max: 34 });
+static MAX70_TEXT: Type = Type::simple("Max70Text", Simple::Text { min: 1, max: 70 });
+static MAX105_TEXT: Type = Type::simple("Max105Text", Simple::Text { min: 1, max: 105 });
+static MAX140_TEXT: Type = Type::simple("Max140Text", Simple::Text { min: 1, max: 140 });
+static MAX500_TEXT: Type = Type::simple("Max500Text", Simple::Text { min: 1, max: 500 });
+static MAX5_NUMERIC: Type = Type::simple("Max5NumericText", Simple::Numeric { max: 5 });
+static MAX15_NUMERIC: Type = Type::simple("Max15NumericText", Simple::Numeric { max: 15 });
+static EXTERNAL_CODE: Type = Type::simple("External4Code", Simple::Text { min: 1, max: 4 });
+static ISO_DATE: Type = Type::simple("ISODate", Simple::Date);
+static ISO_DATE_TIME: Type = Type::simple("ISODateTime", Simple::DateTime);
+static TRUE_FALSE: Type = Type::simple("TrueFalseIndicator", Simple::Bool);
+static DECIMAL_NUMBER: Type = Type::simple("DecimalNumber", Simple::Decimal { total: 18, fraction: 17 });
+static NUMBER: Type = Type::simple("Number", Simple::Decimal { total: 18, fraction: 0 });
+static BASE_ONE_RATE: Type = Type::simple("BaseOneRate", Simple::Decimal { total: 11, fraction: 10 });
+static BICFI: Type = Type::simple("BICFIDec2014Identifier", Simple::Bic);
+static IBAN: Type = Type::simple("IBAN2007Identifier", Simple::Iban);
+static UUIDV4: Type = Type::simple("UUIDv4Identifier", Simple::Uuid);
+static CURRENCY: Type = Type::simple("ActiveOrHistoricCurrencyCode", Simple::Currency);
+static COUNTRY: Type = Type::simple("CountryCode", Simple::Country);
+static LEI: Type = Type::simple("LEIIdentifier", Simple::Text { min: 20, max: 20 });
+static AMOUNT: Type = Type { name: "ActiveOrHistoricCurrencyAndAmount", content: Content::Amount };
+static CREDIT_DEBIT: Type = Type::simple("CreditDebitCode", Simple::Code(&["CRDT", "DBIT"]));
+
+static POSTAL_ADDRESS: Type = Type::any("PostalAddress24");
+static PARTY_ID: Type = Type::any("Party38Choice");
+static CONTACT: Type = Type::any("Contact4");
+static CLEARING_MEMBER: Type = Type::any("ClearingSystemMemberIdentification2");
+static BRANCH: Type = Type::any("BranchData3");
+static SCHEME_NAME: Type = Type::any("AccountSchemeName1Choice");
+static ACCOUNT_TYPE: Type = Type::any("CashAccountType2Choice");
+static PROXY: Type = Type::any("ProxyAccountIdentification1");
+static PAYMENT_TYPE: Type = Type::any("PaymentTypeInformation28");
+static CHARGES: Type = Type::any("Charges7");
+static SUPPLEMENTARY_DATA: Type = Type::any("SupplementaryData1");
+static ORIGINAL_BUSINESS_QUERY: Type = Type::any("OriginalBusinessQuery1");
+
+static GENERIC_FI_ID: Type = Type::seq("GenericFinancialIdentification1", &[
+    Field::one("Id", &MAX35_TEXT),
+    Field::opt("SchmeNm", &Type::any("FinancialIdentificationSchemeName1Choice")),
+    Field::opt("Issr", &MAX35_TEXT),
+]);
+
+static FI_ID: Type = Type::seq("FinancialInstitutionIdentification18", &[
+    Field::opt("BICFI", &BICFI),
+    Field::opt("ClrSysMmbId", &CLEARING_MEMBER),
+    Field::opt("LEI", &LEI),
+    Field::opt("Nm", &MAX140_TEXT),
+    Field::opt("PstlAdr", &POSTAL_ADDRESS),
+    Field::opt("Othr", &GENERIC_FI_ID),
+]);
+
+static AGENT: Type = Type::seq("BranchAndFinancialInstitutionIdentification6", &[
+    Field::one("FinInstnId", &FI_ID),
+    Field::opt("BrnchId", &BRANCH),
+]);
+
+static PARTY: Type = Type::seq("PartyIdentification135", &[
+    Field::opt("Nm", &MAX140_TEXT),
+    Field::opt("PstlAdr", &POSTAL_ADDRESS),
+    Field::opt("Id", &PARTY_ID),
+    Field::opt("CtryOfRes", &COUNTRY),
+    Field::opt("CtctDtls", &CONTACT),
+]);
+
+static GENERIC_ACCOUNT_ID: Type = Type::seq("GenericAccountIdentification1", &[
+    Field::one("Id", &MAX34_TEXT),
+    Field::opt("SchmeNm", &SCHEME_NAME),
+    Field::opt("Issr", &MAX35_TEXT),
+]);
+
+static ACCOUNT_ID: Type = Type::choice("AccountIdentification4Choice", &[
+    Field::one("IBAN", &IBAN),
+    Field::one("Othr", &GENERIC_ACCOUNT_ID),
+]);
+
+static ACCOUNT: Type = Type::seq("CashAccount38", &[
+    Field::one("Id", &ACCOUNT_ID),
+    Field::opt("Tp", &ACCOUNT_TYPE),
+    Field::opt("Ccy", &CURRENCY),
+    Field::opt("Nm", &MAX70_TEXT),
+    Field::opt("Prxy", &PROXY),
+]);
+
+// ============================================================================
+// pacs.008.001.08
+// ============================================================================
+
+static SETTLEMENT_INSTRUCTION: Type = Type::seq("SettlementInstruction7", &[
+    Field::one("SttlmMtd", &Type::simple("SettlementMethod1Code", Simple::Code(&["INDA", "INGA", "COVE", "CLRG"]))),
+    Field::opt("SttlmAcct", &ACCOUNT),
+    Field::opt("ClrSys", &Type::any("ClearingSystemIdentification3Choice")),
+    Field::opt("InstgRmbrsmntAgt", &AGENT),
+    Field::opt("InstgRmbrsmntAgtAcct", &ACCOUNT),
+    Field::opt("InstdRmbrsmntAgt", &AGENT),
+    Field::opt("InstdRmbrsmntAgtAcct", &ACCOUNT),
+    Field::opt("ThrdRmbrsmntAgt", &AGENT),
+    Field::opt("ThrdRmbrsmntAgtAcct", &ACCOUNT),
+]);
+
+static PACS008_GROUP_HEADER: Type = Type::seq("GroupHeader93", &[
+    Field::one("MsgId", &MAX35_TEXT),
+    Field::one("CreDtTm", &ISO_DATE_TIME),
+    Field::opt("BtchBookg", &TRUE_FALSE),
+    Field::one("NbOfTxs", &MAX15_NUMERIC),
+    Field::opt("CtrlSum", &DECIMAL_NUMBER),
+    Field::opt("TtlIntrBkSttlmAmt", &AMOUNT),
+    Field::opt("IntrBkSttlmDt", &ISO_DATE),
+    Field::one("SttlmInf", &SETTLEMENT_INSTRUCTION),
+    Field::opt("PmtTpInf", &PAYMENT_TYPE),
+    Field::opt("InstgAgt", &AGENT),
+    Field::opt("InstdAgt", &AGENT),
+]);
+
+static PAYMENT_ID: Type = Type::seq("PaymentIdentification7", &[
+    Field::opt("InstrId", &MAX35_TEXT),
+    Field::one("EndToEndId", &MAX35_TEXT),
+    Field::opt("TxId", &MAX35_TEXT),
+    Field::opt("UETR", &UUIDV4),
+    Field::opt("ClrSysRef", &MAX35_TEXT),
+]);
+
+static REMITTANCE: Type = Type::seq("RemittanceInformation16", &[
+    Field::many("Ustrd", 0, &MAX140_TEXT),
+    Field::many("Strd", 0, &Type::any("StructuredRemittanceInformation16")),
+]);
+
+static CREDIT_TRANSFER: Type = Type::seq("CreditTransferTransaction39", &[
+    Field::one("PmtId", &PAYMENT_ID),
+    Field::opt("PmtTpInf", &PAYMENT_TYPE),
+    Field::one("IntrBkSttlmAmt", &AMOUNT),
+    Field::opt("IntrBkSttlmDt", &ISO_DATE),
+    Field::opt("SttlmPrty", &Type::simple("Priority3Code", Simple::Code(&["URGT", "HIGH", "NORM"]))),
+    Field::opt("SttlmTmIndctn", &Type::any("SettlementDateTimeIndication1")),
+    Field::opt("SttlmTmReq", &Type::any("SettlementTimeRequest2")),
+    Field::opt("AccptncDtTm", &ISO_DATE_TIME),
+    Field::opt("PoolgAdjstmntDt", &ISO_DATE),
+    Field::opt("InstdAmt", &AMOUNT),
+    Field::opt("XchgRate", &BASE_ONE_RATE),
+    Field::one("ChrgBr", &Type::simple("ChargeBearerType1Code", Simple::Code(&["DEBT", "CRED", "SHAR", "SLEV"]))),
+    Field::many("ChrgsInf", 0, &CHARGES),
+    Field::opt("PrvsInstgAgt1", &AGENT),
+    Field::opt("PrvsInstgAgt1Acct", &ACCOUNT),
+    Field::opt("PrvsInstgAgt2", &AGENT),
+    Field::opt("PrvsInstgAgt2Acct", &ACCOUNT),
+    Field::opt("PrvsInstgAgt3", &AGENT),
+    Field::opt("PrvsInstgAgt3Acct", &ACCOUNT),
+    Field::opt("InstgAgt", &AGENT),
+    Field::opt("InstdAgt", &AGENT),
+    Field::opt("IntrmyAgt1", &AGENT),
+    Field::opt("IntrmyAgt1Acct", &ACCOUNT),
+    Field::opt("IntrmyAgt2", &AGENT),
+    Field::opt("IntrmyAgt2Acct", &ACCOUNT),
+    Field::opt("IntrmyAgt3", &AGENT),
+    Field::opt("IntrmyAgt3Acct", &ACCOUNT),
+    Field::opt("UltmtDbtr", &PARTY),
+    Field::opt("InitgPty", &PARTY),
+    Field::one("Dbtr", &PARTY),
+    Field::opt("DbtrAcct", &ACCOUNT),
+    Field::one("DbtrAgt", &AGENT),
+    Field::opt("DbtrAgtAcct", &ACCOUNT),
+    Field::one("CdtrAgt", &AGENT),
+    Field::opt("CdtrAgtAcct", &ACCOUNT),
+    Field::one("Cdtr", &PARTY),
+    Field::opt("CdtrAcct", &ACCOUNT),
+    Field::opt("UltmtCdtr", &PARTY),
+    Field::many("InstrForCdtrAgt", 0, &Type::any("InstructionForCreditorAgent1")),
+    Field::many("InstrForNxtAgt", 0, &Type::any("InstructionForNextAgent1")),
+    Field::opt("Purp", &Type::any("Purpose2Choice")),
+    Field::up_to("RgltryRptg", 10, &Type::any("RegulatoryReporting3")),
+    Field::opt("Tax", &Type::any("TaxInformation8")),
+    Field::up_to("RltdRmtInf", 10, &Type::any("RemittanceLocation7")),
+    Field::opt("RmtInf", &REMITTANCE),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+static PACS008: Type = Type::seq("FIToFICustomerCreditTransferV08", &[
+    Field::one("GrpHdr", &PACS008_GROUP_HEADER),
+    Field::many("CdtTrfTxInf", 1, &CREDIT_TRANSFER),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+// ============================================================================
+// pacs.002.001.10
+// ============================================================================
+
+static PACS002_GROUP_HEADER: Type = Type::seq("GroupHeader91", &[
+    Field::one("MsgId", &MAX35_TEXT),
+    Field::one("CreDtTm", &ISO_DATE_TIME),
+    Field::opt("InstgAgt", &AGENT),
+    Field::opt("InstdAgt", &AGENT),
+    Field::opt("OrgnlBizQry", &ORIGINAL_BUSINESS_QUERY),
+]);
+
+static STATUS_REASON: Type = Type::seq("StatusReasonInformation12", &[
+    Field::opt("Orgtr", &PARTY),
+    Field::opt("Rsn", &Type::choice("StatusReason6Choice", &[
+        Field::one("Cd", &EXTERNAL_CODE),
+        Field::one("Prtry", &MAX35_TEXT),
+    ])),
+    Field::many("AddtlInf", 0, &MAX105_TEXT),
+]);
+
+static ORIGINAL_GROUP_STATUS: Type = Type::seq("OriginalGroupHeader17", &[
+    Field::one("OrgnlMsgId", &MAX35_TEXT),
+    Field::one("OrgnlMsgNmId", &MAX35_TEXT),
+    Field::opt("OrgnlCreDtTm", &ISO_DATE_TIME),
+    Field::opt("OrgnlNbOfTxs", &MAX15_NUMERIC),
+    Field::opt("OrgnlCtrlSum", &DECIMAL_NUMBER),
+    Field::opt("GrpSts", &EXTERNAL_CODE),
+    Field::many("StsRsnInf", 0, &STATUS_REASON),
+    Field::many("NbOfTxsPerSts", 0, &Type::any("NumberOfTransactionsPerStatus5")),
+]);
+
+static ORIGINAL_GROUP: Type = Type::seq("OriginalGroupInformation29", &[
+    Field::one("OrgnlMsgId", &MAX35_TEXT),
+    Field::one("OrgnlMsgNmId", &MAX35_TEXT),
+    Field::opt("OrgnlCreDtTm", &ISO_DATE_TIME),
+]);
+
+static TRANSACTION_STATUS: Type = Type::seq("PaymentTransaction110", &[
+    Field::opt("StsId", &MAX35_TEXT),
+    Field::opt("OrgnlGrpInf", &ORIGINAL_GROUP),
+    Field::opt("OrgnlInstrId", &MAX35_TEXT),
+    Field::opt("OrgnlEndToEndId", &MAX35_TEXT),
+    Field::opt("OrgnlTxId", &MAX35_TEXT),
+    Field::opt("OrgnlUETR", &UUIDV4),
+    Field::opt("TxSts", &EXTERNAL_CODE),
+    Field::many("StsRsnInf", 0, &STATUS_REASON),
+    Field::many("ChrgsInf", 0, &CHARGES),
+    Field::opt("AccptncDtTm", &ISO_DATE_TIME),
+    Field::opt("FctvIntrBkSttlmDt", &Type::any("DateAndDateTime2Choice")),
+    Field::opt("AcctSvcrRef", &MAX35_TEXT),
+    Field::opt("ClrSysRef", &MAX35_TEXT),
+    Field::opt("InstgAgt", &AGENT),
+    Field::opt("InstdAgt", &AGENT),
+    Field::opt("OrgnlTxRef", &Type::any("OriginalTransactionReference28")),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+static PACS002: Type = Type::seq("FIToFIPaymentStatusReportV10", &[
+    Field::one("GrpHdr", &PACS002_GROUP_HEADER),
+    Field::many("OrgnlGrpInfAndSts", 0, &ORIGINAL_GROUP_STATUS),
+    Field::many("TxInfAndSts", 0, &TRANSACTION_STATUS),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+// ============================================================================
+// camt.053.001.08 / camt.054.001.08
+// ============================================================================
+
+static PAGINATION: Type = Type::seq("Pagination1", &[
+    Field::one("PgNb", &MAX5_NUMERIC),
+    Field::one("LastPgInd", &TRUE_FALSE),
+]);
+
+static CAMT_GROUP_HEADER: Type = Type::seq("GroupHeader81", &[
+    Field::one("MsgId", &MAX35_TEXT),
+    Field::one("CreDtTm", &ISO_DATE_TIME),
+    Field::opt("MsgRcpt", &PARTY),
+    Field::opt("MsgPgntn", &PAGINATION),
+    Field::opt("OrgnlBizQry", &ORIGINAL_BUSINESS_QUERY),
+    Field::opt("AddtlInf", &MAX500_TEXT),
+]);
+
+static DATE_TIME_PERIOD: Type = Type::seq("DateTimePeriod1", &[
+    Field::one("FrDtTm", &ISO_DATE_TIME),
+    Field::one("ToDtTm", &ISO_DATE_TIME),
+]);
+
+static DATE_OR_DATE_TIME: Type = Type::choice("DateAndDateTime2Choice", &[
+    Field::one("Dt", &ISO_DATE),
+    Field::one("DtTm", &ISO_DATE_TIME),
+]);
+
+static REPORT_ACCOUNT: Type = Type::seq("CashAccount39", &[
+    Field::one("Id", &ACCOUNT_ID),
+    Field::opt("Tp", &ACCOUNT_TYPE),
+    Field::opt("Ccy", &CURRENCY),
+    Field::opt("Nm", &MAX70_TEXT),
+    Field::opt("Prxy", &PROXY),
+    Field::opt("Ownr", &PARTY),
+    Field::opt("Svcr", &AGENT),
+]);
+
+static COPY_DUPLICATE: Type = Type::simple("CopyDuplicate1Code", Simple::Code(&["CODU", "COPY", "DUPL"]));
+static REPORTING_SOURCE: Type = Type::any("ReportingSource1Choice");
+static SEQUENCE_RANGE: Type = Type::any("SequenceRange1Choice");
+static INTEREST_RECORD: Type = Type::any("AccountInterest4");
+static TRANSACTIONS_SUMMARY: Type = Type::any("TotalTransactions6");
+static AVAILABILITY: Type = Type::any("CashAvailability1");
+
+static BALANCE: Type = Type::seq("CashBalance8", &[
+    Field::one("Tp", &Type::seq("BalanceType13", &[
+        Field::one("CdOrPrtry", &Type::choice("BalanceType10Choice", &[
+            Field::one("Cd", &EXTERNAL_CODE),
+            Field::one("Prtry", &MAX35_TEXT),
+        ])),
+        Field::opt("SubTp", &Type::any("BalanceSubType1Choice")),
+    ])),
+    Field::many("CdtLine", 0, &Type::any("CreditLine3")),
+    Field::one("Amt", &AMOUNT),
+    Field::one("CdtDbtInd", &CREDIT_DEBIT),
+    Field::one("Dt", &DATE_OR_DATE_TIME),
+    Field::many("Avlbty", 0, &AVAILABILITY),
+]);
+
+static ENTRY: Type = Type::seq("ReportEntry10", &[
+    Field::opt("NtryRef", &MAX35_TEXT),
+    Field::one("Amt", &AMOUNT),
+    Field::one("CdtDbtInd", &CREDIT_DEBIT),
+    Field::opt("RvslInd", &TRUE_FALSE),
+    Field::one("Sts", &Type::choice("EntryStatus1Choice", &[
+        Field::one("Cd", &EXTERNAL_CODE),
+        Field::one("Prtry", &MAX35_TEXT),
+    ])),
+    Field::opt("BookgDt", &DATE_OR_DATE_TIME),
+    Field::opt("ValDt", &DATE_OR_DATE_TIME),
+    Field::opt("AcctSvcrRef", &MAX35_TEXT),
+    Field::many("Avlbty", 0, &AVAILABILITY),
+    Field::one("BkTxCd", &Type::any("BankTransactionCodeStructure4")),
+    Field::opt("ComssnWvrInd", &TRUE_FALSE),
+    Field::opt("AddtlInfInd", &Type::any("MessageIdentification2")),
+    Field::opt("AmtDtls", &Type::any("AmountAndCurrencyExchange3")),
+    Field::opt("Chrgs", &CHARGES),
+    Field::opt("TechInptChanl", &Type::any("TechnicalInputChannel1Choice")),
+    Field::opt("Intrst", &Type::any("TransactionInterest4")),
+    Field::opt("CardTx", &Type::any("CardEntry4")),
+    Field::many("NtryDtls", 0, &Type::any("EntryDetails9")),
+    Field::opt("AddtlNtryInf", &MAX500_TEXT),
+]);
+
+static STATEMENT: Type = Type::seq("AccountStatement9", &[
+    Field::one("Id", &MAX35_TEXT),
+    Field::opt("StmtPgntn", &PAGINATION),
+    Field::opt("ElctrncSeqNb", &NUMBER),
+    Field::opt("RptgSeq", &SEQUENCE_RANGE),
+    Field::opt("LglSeqNb", &NUMBER),
+    Field::opt("CreDtTm", &ISO_DATE_TIME),
+    Field::opt("FrToDt", &DATE_TIME_PERIOD),
+    Field::opt("CpyDplctInd", &COPY_DUPLICATE),
+    Field::opt("RptgSrc", &REPORTING_SOURCE),
+    Field::one("Acct", &REPORT_ACCOUNT),
+    Field::opt("RltdAcct", &ACCOUNT),
+    Field::many("Intrst", 0, &INTEREST_RECORD),
+    Field::many("Bal", 1, &BALANCE),
+    Field::opt("TxsSummry", &TRANSACTIONS_SUMMARY),
+    Field::many("Ntry", 0, &ENTRY),
+    Field::opt("AddtlStmtInf", &MAX500_TEXT),
+]);
+
+static NOTIFICATION: Type = Type::seq("AccountNotification17", &[
+    Field::one("Id", &MAX35_TEXT),
+    Field::opt("NtfctnPgntn", &PAGINATION),
+    Field::opt("ElctrncSeqNb", &NUMBER),
+    Field::opt("RptgSeq", &SEQUENCE_RANGE),
+    Field::opt("LglSeqNb", &NUMBER),
+    Field::opt("CreDtTm", &ISO_DATE_TIME),
+    Field::opt("FrToDt", &DATE_TIME_PERIOD),
+    Field::opt("CpyDplctInd", &COPY_DUPLICATE),
+    Field::opt("RptgSrc", &REPORTING_SOURCE),
+    Field::one("Acct", &REPORT_ACCOUNT),
+    Field::opt("RltdAcct", &ACCOUNT),
+    Field::many("Intrst", 0, &INTEREST_RECORD),
+    Field::opt("TxsSummry", &TRANSACTIONS_SUMMARY),
+    Field::many("Ntry", 0, &ENTRY),
+    Field::opt("AddtlNtfctnInf", &MAX500_TEXT),
+]);
+
+static CAMT053: Type = Type::seq("BankToCustomerStatementV08", &[
+    Field::one("GrpHdr", &CAMT_GROUP_HEADER),
+    Field::many("Stmt", 1, &STATEMENT),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+static CAMT054: Type = Type::seq("BankToCustomerDebitCreditNotificationV08", &[
+    Field::one("GrpHdr", &CAMT_GROUP_HEADER),
+    Field::many("Ntfctn", 1, &NOTIFICATION),
+    Field::many("SplmtryData", 0, &SUPPLEMENTARY_DATA),
+]);
+
+static SCHEMAS: [Schema; 4] = [
+    Schema { id: "pacs.008.001.08", root: Field::one("FIToFICstmrCdtTrf", &PACS008) },
+    Schema { id: "pacs.002.001.10", root: Field::one("FIToFIPmtStsRpt", &PACS002) },
+    Schema { id: "camt.053.001.08", root: Field::one("BkToCstmrStmt", &CAMT053) },
+    Schema { id: "camt.054.001.08", root: Field::one("BkToCstmrDbtCdtNtfctn", &CAMT054) },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACS008_NS: &str = "urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08";
+
+    fn pacs008(tx: &str) -> String {
+        format!(r#"<Document xmlns="{PACS008_NS}"><FIToFICstmrCdtTrf>
+<GrpHdr><MsgId>MSG1</MsgId><CreDtTm>2025-12-26T12:00:00Z</CreDtTm><NbOfTxs>1</NbOfTxs>
+<SttlmInf><SttlmMtd>CLRG</SttlmMtd></SttlmInf></GrpHdr>
+<CdtTrfTxInf>{tx}</CdtTrfTxInf>
+</FIToFICstmrCdtTrf></Document>"#)
+    }
+
+    const VALID_TX: &str = r#"<PmtId><EndToEndId>E2E1</EndToEndId></PmtId>
+<IntrBkSttlmAmt Ccy="EUR">100.50</IntrBkSttlmAmt><ChrgBr>SHAR</ChrgBr>
+<Dbtr><Nm>John Doe</Nm></Dbtr><DbtrAgt><FinInstnId><BICFI>ABCDDEFF</BICFI></FinInstnId></DbtrAgt>
+<CdtrAgt><FinInstnId><BICFI>IJKLGB2L</BICFI></FinInstnId></CdtrAgt><Cdtr><Nm>Jane Smith</Nm></Cdtr>"#;
+
+    #[test]
+    fn test_valid_pacs008() {
+        let report = validate_mx(&pacs008(VALID_TX), ValidationMode::Strict).unwrap();
+        assert_eq!(report.schema, "pacs.008.001.08");
+        assert!(report.is_valid(), "{:?}", report.errors);
+    }
+
+    #[test]
+    fn test_structural_errors_with_xpath() {
+        // Missing ChrgBr, invalid currency, unexpected element
+        let tx = VALID_TX.replace("<ChrgBr>SHAR</ChrgBr>", "<Foo/>").replace("\"EUR\"", "\"eur\"");
+        let report = validate_mx(&pacs008(&tx), ValidationMode::Strict).unwrap();
+        let at = |path: &str| report.errors.iter().find(|e| e.path == path).map(|e| e.kind);
+
+        let tx_path = "/Document/FIToFICstmrCdtTrf/CdtTrfTxInf[1]";
+        assert_eq!(at(&format!("{tx_path}/ChrgBr")), Some(IssueKind::Missing));
+        assert_eq!(at(&format!("{tx_path}/IntrBkSttlmAmt/@Ccy")), Some(IssueKind::Facet));
+        assert_eq!(at(&format!("{tx_path}/Foo")), Some(IssueKind::Unexpected));
+        assert!(report.errors.iter().all(|e| e.line >= 3));
+    }
+
+    #[test]
+    fn test_lenient_mode_downgrades_facets() {
+        let tx = VALID_TX.replace("<Foo/>", "").replace("ABCDDEFF", "bad-bic");
+        let report = validate_mx(&pacs008(&tx), ValidationMode::Lenient).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 1);
+
+        // Structural problems stay errors
+        let tx = VALID_TX.replace("<Dbtr><Nm>John Doe</Nm></Dbtr>", "");
+        let report = validate_mx(&pacs008(&tx), ValidationMode::Lenient).unwrap();
+        assert_eq!(report.errors[0].kind, IssueKind::Missing);
+    }
+
+    #[test]
+    fn test_camt053_choice_and_order() {
+        let xml = r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08"><BkToCstmrStmt>
+<GrpHdr><CreDtTm>2025-12-26T12:00:00</CreDtTm><MsgId>STMT1</MsgId></GrpHdr>
+<Stmt><Id>S1</Id><Acct><Id><IBAN>DE89370400440532013000</IBAN><Othr><Id>1</Id></Othr></Id></Acct>
+<Bal><Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp><Amt Ccy="EUR">10</Amt><CdtDbtInd>CRDT</CdtDbtInd><Dt><Dt>2025-13-01</Dt></Dt></Bal>
+</Stmt></BkToCstmrStmt></Document>"#;
+        let report = validate_mx(xml, ValidationMode::Strict).unwrap();
+        let kinds: Vec<_> = report.errors.iter().map(|e| (e.path.as_str(), e.kind)).collect();
+
+        assert!(kinds.contains(&("/Document/BkToCstmrStmt/GrpHdr/MsgId", IssueKind::OutOfOrder)));
+        assert!(kinds.contains(&("/Document/BkToCstmrStmt/Stmt[1]/Acct/Id/Othr", IssueKind::TooMany)));
+        assert!(kinds.contains(&("/Document/BkToCstmrStmt/Stmt[1]/Bal[1]/Dt/Dt", IssueKind::InvalidType)));
+        assert_eq!(report.errors.len(), 3);
+    }
+}
@@ -0,0 +1,476 @@
+//! Payment Pre-validation
+//!
+//! Checks a payment instruction before a pacs.008 is built for it, so bad
+//! data is rejected locally instead of by the network:
+//! - BIC format (ISO 9362) and, with a directory, that the BIC exists
+//! - IBAN country, length and mod-97 checksum (ISO 13616)
+//! - Currency code and amount precision (ISO 4217 minor units)
+//! - Cut-off times per currency, reported as warnings
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let config = PrevalidationConfig {
+//!     cut_offs: vec![CutOff::new("EUR", "16:00").with_warning_minutes(30)],
+//! };
+//! let validator = PaymentValidator::new(&config).with_directory(Arc::new(directory));
+//! let report = validator.validate(&payment);
+//! for issue in &report.warnings {
+//!     eprintln!("{issue}");
+//! }
+//! report.into_result()?;
+//! ```
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use super::{PaymentInstruction, SwiftError};
+
+/// ISO 3166 country codes, plus `XK` (Kosovo) as used in BICs and IBANs.
+const COUNTRIES: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ BL BM BN BO BQ \
+    BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ DE DJ DK DM DO DZ EC EE EG EH ER \
+    ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM \
+    IN IO IQ IR IS IT JE JM JO JP KE KG KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF \
+    MG MH MK ML MM MN MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK \
+    PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD \
+    TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS XK YE YT ZA ZM ZW";
+
+/// IBAN length per country (SWIFT IBAN registry).
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16), ("BG", 22),
+    ("BH", 22), ("BI", 27), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22), ("CY", 28), ("CZ", 24),
+    ("DE", 22), ("DJ", 27), ("DK", 18), ("DO", 28), ("EE", 20), ("EG", 29), ("ES", 24), ("FI", 18),
+    ("FK", 18), ("FO", 18), ("FR", 27), ("GB", 22), ("GE", 22), ("GI", 23), ("GL", 18), ("GR", 27),
+    ("GT", 28), ("HR", 21), ("HU", 28), ("IE", 22), ("IL", 23), ("IQ", 23), ("IS", 26), ("IT", 27),
+    ("JO", 30), ("KW", 30), ("KZ", 20), ("LB", 28), ("LC", 32), ("LI", 21), ("LT", 20), ("LU", 20),
+    ("LV", 21), ("LY", 25), ("MC", 27), ("MD", 24), ("ME", 22), ("MK", 19), ("MN", 20), ("MR", 27),
+    ("MT", 31), ("MU", 30), ("NI", 28), ("NL", 18), ("NO", 15), ("OM", 23), ("PK", 24), ("PL", 28),
+    ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22), ("RU", 33), ("SA", 24), ("SC", 31),
+    ("SD", 18), ("SE", 24), ("SI", 19), ("SK", 24), ("SM", 27), ("SO", 23), ("ST", 25), ("SV", 28),
+    ("TL", 23), ("TN", 24), ("TR", 26), ("UA", 29), ("VA", 22), ("VG", 24), ("XK", 20), ("YE", 30),
+];
+
+/// Active ISO 4217 currencies with two minor units.
+const CURRENCIES_2: &str = "AED AFN ALL AMD ANG AOA ARS AUD AWG AZN BAM BBD BDT BGN BMD BND BOB BRL BSD BTN BWP BYN \
+    BZD CAD CDF CHF CNY COP CRC CUP CVE CZK DKK DOP DZD EGP ERN ETB EUR FJD FKP GBP GEL GHS GIP GMD GTQ GYD HKD HNL \
+    HTG HUF IDR ILS INR IRR JMD KES KGS KHR KPW KYD KZT LAK LBP LKR LRD LSL MAD MDL MGA MKD MMK MNT MOP MRU MUR MVR \
+    MWK MXN MYR MZN NAD NGN NIO NOK NPR NZD PAB PEN PGK PHP PKR PLN QAR RON RSD RUB SAR SBD SCR SDG SEK SGD SHP SLE \
+    SOS SRD SSP STN SVC SYP SZL THB TJS TMT TOP TRY TTD TWD TZS UAH USD UYU UZS VES WST XCD YER ZAR ZMW ZWG";
+
+/// Active ISO 4217 currencies with other than two minor units.
+const CURRENCY_MINOR_UNITS: &[(&str, u32)] = &[
+    ("BIF", 0), ("CLP", 0), ("DJF", 0), ("GNF", 0), ("ISK", 0), ("JPY", 0), ("KMF", 0), ("KRW", 0),
+    ("PYG", 0), ("RWF", 0), ("UGX", 0), ("UYI", 0), ("VND", 0), ("VUV", 0), ("XAF", 0), ("XOF", 0),
+    ("XPF", 0), ("BHD", 3), ("IQD", 3), ("JOD", 3), ("KWD", 3), ("LYD", 3), ("OMR", 3), ("TND", 3),
+    ("CLF", 4), ("UYW", 4),
+];
+
+/// Most digits of an ISO 20022 `ActiveCurrencyAndAmount`.
+const MAX_AMOUNT_DIGITS: usize = 18;
+
+/// Minor units of an active ISO 4217 currency, e.g. 2 for EUR, 0 for JPY.
+pub fn minor_units(currency: &str) -> Option<u32> {
+    CURRENCY_MINOR_UNITS.iter()
+        .find(|(code, _)| *code == currency)
+        .map(|(_, units)| *units)
+        .or_else(|| CURRENCIES_2.split_whitespace().any(|code| code == currency).then_some(2))
+}
+
+/// Which check an issue comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentRule {
+    BicFormat,
+    /// BIC not in the directory
+    BicUnknown,
+    IbanFormat,
+    IbanLength,
+    IbanChecksum,
+    Currency,
+    Amount,
+    AmountPrecision,
+    CutOff,
+}
+
+/// A single pre-validation finding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentIssue {
+    /// Field of the payment instruction, e.g. `creditor_account`
+    pub field: String,
+    pub rule: PaymentRule,
+    pub message: String,
+}
+
+impl PaymentIssue {
+    fn new(field: &str, rule: PaymentRule, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), rule, message: message.into() }
+    }
+}
+
+impl fmt::Display for PaymentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Outcome of pre-validating one payment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaymentValidation {
+    /// EndToEndId of the payment
+    pub end_to_end_id: String,
+    pub errors: Vec<PaymentIssue>,
+    /// Cut-off warnings and test BICs; the payment can still be sent
+    pub warnings: Vec<PaymentIssue>,
+}
+
+impl PaymentValidation {
+    /// True when the payment has no errors (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Convert into `Err(SwiftError::PaymentRejected)` when there are errors.
+    pub fn into_result(self) -> Result<Self, SwiftError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(SwiftError::PaymentRejected(self))
+        }
+    }
+}
+
+impl fmt::Display for PaymentValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "payment {}: {} error(s), {} warning(s)",
+            self.end_to_end_id, self.errors.len(), self.warnings.len())?;
+        if let Some(first) = self.errors.first() {
+            write!(f, "; first: {first}")?;
+        }
+        Ok(())
+    }
+}
+
+/// BIC directory entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BicRecord {
+    /// BIC8 or BIC11
+    pub bic: String,
+    pub institution: String,
+    pub country: String,
+}
+
+/// BIC directory (SWIFTRef BIC Plus or a local extract).
+pub trait BicDirectory: Send + Sync {
+    /// Entry for a BIC; a BIC11 falls back to its BIC8 for the `XXX`
+    /// primary office.
+    fn lookup(&self, bic: &str) -> Option<BicRecord>;
+}
+
+/// In-memory BIC directory.
+#[derive(Default)]
+pub struct MemoryBicDirectory {
+    entries: RwLock<HashMap<String, BicRecord>>,
+}
+
+impl MemoryBicDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, record: BicRecord) {
+        self.entries.write().unwrap().insert(record.bic.to_ascii_uppercase(), record);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl BicDirectory for MemoryBicDirectory {
+    fn lookup(&self, bic: &str) -> Option<BicRecord> {
+        let entries = self.entries.read().unwrap();
+        let bic = bic.to_ascii_uppercase();
+        entries.get(&bic).or_else(|| match bic.len() {
+            11 if bic.ends_with("XXX") => entries.get(&bic[..8]),
+            8 => entries.get(&format!("{}XXX", bic)),
+            _ => None,
+        }).cloned()
+    }
+}
+
+/// Cut-off time for a currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CutOff {
+    /// ISO 4217 code, or `*` for every currency without its own cut-off
+    pub currency: String,
+    /// Latest submission time for same-day value, `HH:MM` UTC
+    pub time_utc: String,
+    /// Warn this many minutes ahead of the cut-off
+    #[serde(default)]
+    pub warning_minutes: u32,
+}
+
+impl CutOff {
+    pub fn new(currency: &str, time_utc: &str) -> Self {
+        Self { currency: currency.to_string(), time_utc: time_utc.to_string(), warning_minutes: 0 }
+    }
+
+    pub fn with_warning_minutes(mut self, minutes: u32) -> Self {
+        self.warning_minutes = minutes;
+        self
+    }
+}
+
+/// Pre-validation settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrevalidationConfig {
+    pub cut_offs: Vec<CutOff>,
+}
+
+/// Validates payment instructions before they are sent.
+#[derive(Clone)]
+pub struct PaymentValidator {
+    cut_offs: Vec<CutOff>,
+    directory: Option<Arc<dyn BicDirectory>>,
+}
+
+impl PaymentValidator {
+    pub fn new(config: &PrevalidationConfig) -> Self {
+        Self { cut_offs: config.cut_offs.clone(), directory: None }
+    }
+
+    /// Reject BICs that are not in `directory`.
+    pub fn with_directory(mut self, directory: Arc<dyn BicDirectory>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// Validate against the current time.
+    pub fn validate(&self, payment: &PaymentInstruction) -> PaymentValidation {
+        self.validate_at(payment, Utc::now())
+    }
+
+    /// Validate as if submitted at `now`.
+    pub fn validate_at(&self, payment: &PaymentInstruction, now: DateTime<Utc>) -> PaymentValidation {
+        let mut report = PaymentValidation { end_to_end_id: payment.end_to_end_id().to_string(), ..Default::default() };
+
+        self.check_bic(&mut report, "instructing_agent", &payment.instructing_agent);
+        if let Some(bic) = &payment.instructed_agent {
+            self.check_bic(&mut report, "instructed_agent", bic);
+        }
+        report.errors.extend(check_iban("debtor_account", &payment.debtor_account));
+        report.errors.extend(check_iban("creditor_account", &payment.creditor_account));
+
+        match minor_units(&payment.currency) {
+            Some(units) => report.errors.extend(check_amount(payment.amount, &payment.currency, units)),
+            None => report.errors.push(PaymentIssue::new(
+                "currency", PaymentRule::Currency,
+                format!("{} is not an active ISO 4217 currency", payment.currency),
+            )),
+        }
+
+        self.check_cut_off(&mut report, &payment.currency, now);
+        report
+    }
+
+    fn check_bic(&self, report: &mut PaymentValidation, field: &str, bic: &str) {
+        if let Err(message) = check_bic_format(bic) {
+            report.errors.push(PaymentIssue::new(field, PaymentRule::BicFormat, message));
+            return;
+        }
+        // Location code with a second character of 0 marks a test BIC
+        if bic.as_bytes()[7] == b'0' {
+            report.warnings.push(PaymentIssue::new(field, PaymentRule::BicFormat, format!("{} is a test BIC", bic)));
+        }
+        if let Some(directory) = &self.directory {
+            if directory.lookup(bic).is_none() {
+                report.errors.push(PaymentIssue::new(
+                    field, PaymentRule::BicUnknown,
+                    format!("{} is not in the BIC directory", bic),
+                ));
+            }
+        }
+    }
+
+    fn check_cut_off(&self, report: &mut PaymentValidation, currency: &str, now: DateTime<Utc>) {
+        let cut_off = self.cut_offs.iter().find(|c| c.currency == currency)
+            .or_else(|| self.cut_offs.iter().find(|c| c.currency == "*"));
+        let Some(cut_off) = cut_off else { return };
+        let Ok(time) = NaiveTime::parse_from_str(&cut_off.time_utc, "%H:%M") else {
+            report.warnings.push(PaymentIssue::new(
+                "currency", PaymentRule::CutOff,
+                format!("Cut-off {:?} for {} is not HH:MM; not checked", cut_off.time_utc, cut_off.currency),
+            ));
+            return;
+        };
+
+        let remaining = time.signed_duration_since(now.time());
+        if remaining <= chrono::Duration::zero() {
+            report.warnings.push(PaymentIssue::new(
+                "currency", PaymentRule::CutOff,
+                format!("Past the {} UTC cut-off for {}; value date moves to the next business day", cut_off.time_utc, currency),
+            ));
+        } else if remaining <= chrono::Duration::minutes(i64::from(cut_off.warning_minutes)) {
+            report.warnings.push(PaymentIssue::new(
+                "currency", PaymentRule::CutOff,
+                format!("{} min to the {} UTC cut-off for {}", remaining.num_minutes(), cut_off.time_utc, currency),
+            ));
+        }
+    }
+}
+
+/// Check a BIC against ISO 9362: 4 letters institution, ISO 3166
+/// country, 2 alphanumeric location, optional 3 alphanumeric branch.
+pub fn check_bic_format(bic: &str) -> Result<(), String> {
+    let bytes = bic.as_bytes();
+    if bytes.len() != 8 && bytes.len() != 11 {
+        return Err(format!("{} must have 8 or 11 characters", bic));
+    }
+    if !bytes.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+        return Err(format!("{} must be upper-case letters and digits", bic));
+    }
+    if !bytes[..4].iter().all(u8::is_ascii_uppercase) {
+        return Err(format!("{}: institution code must be letters", bic));
+    }
+    if !is_country(&bic[4..6]) {
+        return Err(format!("{}: {} is not a country code", bic, &bic[4..6]));
+    }
+    Ok(())
+}
+
+/// Check an IBAN: country, registered length and mod-97 checksum.
+pub fn check_iban(field: &str, iban: &str) -> Option<PaymentIssue> {
+    let issue = |rule, message: String| Some(PaymentIssue::new(field, rule, message));
+    if iban.contains(' ') {
+        return issue(PaymentRule::IbanFormat, format!("IBAN must be in electronic format: {}", iban.replace(' ', "")));
+    }
+    let bytes = iban.as_bytes();
+    if bytes.len() < 5
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..4].iter().all(u8::is_ascii_digit)
+        || !bytes[4..].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    {
+        return issue(PaymentRule::IbanFormat, format!("{} is not an IBAN", iban));
+    }
+
+    let country = &iban[..2];
+    match IBAN_LENGTHS.iter().find(|(code, _)| *code == country) {
+        None => return issue(PaymentRule::IbanFormat, format!("{} does not use IBANs", country)),
+        Some((_, length)) if *length != iban.len() => {
+            return issue(PaymentRule::IbanLength, format!("{} IBANs have {} characters, not {}", country, length, iban.len()));
+        }
+        Some(_) => {}
+    }
+
+    // Country and check digits move to the end, letters count as 10..35
+    let remainder = iban[4..].bytes().chain(iban[..4].bytes()).fold(0u32, |acc, b| {
+        if b.is_ascii_digit() {
+            (acc * 10 + u32::from(b - b'0')) % 97
+        } else {
+            (acc * 100 + u32::from(b - b'A' + 10)) % 97
+        }
+    });
+    if remainder != 1 {
+        return issue(PaymentRule::IbanChecksum, format!("{} has invalid check digits", iban));
+    }
+    None
+}
+
+fn check_amount(amount: f64, currency: &str, units: u32) -> Vec<PaymentIssue> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return vec![PaymentIssue::new("amount", PaymentRule::Amount, format!("{} must be positive", amount))];
+    }
+    let mut issues = Vec::new();
+    let scaled = amount * 10f64.powi(units as i32);
+    // Tolerate binary rounding of decimal amounts such as 0.1
+    if (scaled - scaled.round()).abs() > 1e-6 * scaled.abs().max(1.0) {
+        issues.push(PaymentIssue::new(
+            "amount", PaymentRule::AmountPrecision,
+            format!("{} allows {} decimal(s), got {}", currency, units, amount),
+        ));
+    }
+    let digits = format!("{:.*}", units as usize, amount).chars().filter(char::is_ascii_digit).count();
+    if digits > MAX_AMOUNT_DIGITS {
+        issues.push(PaymentIssue::new(
+            "amount", PaymentRule::Amount,
+            format!("{} has more than {} digits", amount, MAX_AMOUNT_DIGITS),
+        ));
+    }
+    issues
+}
+
+fn is_country(code: &str) -> bool {
+    COUNTRIES.split_whitespace().any(|c| c == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(currency: &str, amount: f64) -> PaymentInstruction {
+        PaymentInstruction {
+            message_id: "MSG-PV-1".into(),
+            creation_date_time: "2025-12-26T12:00:00Z".into(),
+            instructing_agent: "DEUTDEFF".into(),
+            instructed_agent: Some("BARCGB22XXX".into()),
+            debtor_name: "John Doe".into(),
+            debtor_account: "DE89370400440532013000".into(),
+            creditor_name: "Jane Smith".into(),
+            creditor_account: "GB33BUKB20201555555555".into(),
+            amount,
+            currency: currency.into(),
+            remittance_info: None,
+            uetr: None,
+        }
+    }
+
+    #[test]
+    fn test_prevalidation_rules() {
+        let directory = MemoryBicDirectory::new();
+        for (bic, country) in [("DEUTDEFF", "DE"), ("BARCGB22", "GB")] {
+            directory.insert(BicRecord { bic: bic.into(), institution: bic[..4].into(), country: country.into() });
+        }
+        let config = PrevalidationConfig {
+            cut_offs: vec![CutOff::new("EUR", "16:00").with_warning_minutes(30), CutOff::new("*", "18:00")],
+        };
+        let validator = PaymentValidator::new(&config).with_directory(Arc::new(directory));
+        let at = |time: &str| format!("2025-12-26T{}:00Z", time).parse::<DateTime<Utc>>().unwrap();
+
+        let report = validator.validate_at(&payment("EUR", 1000.50), at("10:00"));
+        assert!(report.is_valid() && report.warnings.is_empty(), "{report}");
+
+        // Near and past cut-off only warn
+        let warning = &validator.validate_at(&payment("EUR", 10.0), at("15:45")).warnings[0];
+        assert_eq!((warning.rule, warning.message.as_str()), (PaymentRule::CutOff, "15 min to the 16:00 UTC cut-off for EUR"));
+        let report = validator.validate_at(&payment("USD", 10.0), at("18:30"));
+        assert!(report.is_valid() && report.warnings[0].message.starts_with("Past the 18:00 UTC cut-off"));
+
+        let mut bad = payment("JPY", 1000.5);
+        bad.instructing_agent = "DEUT1EFF".into();
+        bad.instructed_agent = Some("COBADEFF".into());
+        bad.debtor_account = "DE89370400440532013001".into();
+        bad.creditor_account = "GB33BUKB2020155555555".into();
+        let report = validator.validate_at(&bad, at("10:00"));
+        let rules: Vec<_> = report.errors.iter().map(|i| (i.field.as_str(), i.rule)).collect();
+        assert_eq!(rules, [
+            ("instructing_agent", PaymentRule::BicFormat),
+            ("instructed_agent", PaymentRule::BicUnknown),
+            ("debtor_account", PaymentRule::IbanChecksum),
+            ("creditor_account", PaymentRule::IbanLength),
+            ("amount", PaymentRule::AmountPrecision),
+        ]);
+        assert!(matches!(report.into_result(), Err(SwiftError::PaymentRejected(_))));
+
+        assert_eq!(minor_units("BHD"), Some(3));
+        assert!(validator.validate_at(&payment("XYZ", 1.0), at("10:00")).errors[0].rule == PaymentRule::Currency);
+        assert!(check_iban("iban", "DE89 3704 0044 0532 0130 00").is_some());
+    }
+}
@@ -0,0 +1,350 @@
+//! Reconciliation - match statement entries to outgoing payments
+//!
+//! Outgoing `PaymentInstruction`s are matched to camt.053/054 transactions
+//! by UETR, falling back to EndToEndId. Whatever does not line up becomes a
+//! break for investigation: payments never booked, amount or currency
+//! differences, returns, duplicates and debits nobody instructed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let statement = connector.parse_statement(&camt053_xml)?;
+//! let report = Reconciler::new()
+//!     .with_account("DE89370400440532013000")
+//!     .reconcile(&sent_payments, &[statement]);
+//!
+//! for b in &report.breaks {
+//!     eprintln!("{:?}: {}", b.kind, b.detail);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::camt::{BankStatement, CreditDebit, StatementEntry};
+use super::PaymentInstruction;
+
+/// Default amount tolerance (half a cent).
+pub const DEFAULT_TOLERANCE: f64 = 0.005;
+
+/// Reason a payment or entry needs investigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakKind {
+    /// Payment does not appear on any statement
+    NotBooked,
+    /// Entry found but not yet booked
+    Pending,
+    AmountMismatch,
+    CurrencyMismatch,
+    /// Payment came back as a credit or reversal
+    Returned,
+    /// Payment debited more than once
+    Duplicate,
+    /// Debit on the account without a matching payment
+    Unmatched,
+}
+
+/// A payment matched to a booked entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciledPayment {
+    pub message_id: String,
+    pub end_to_end_id: String,
+    pub uetr: Option<String>,
+    pub entry_ref: Option<String>,
+    pub account_servicer_ref: Option<String>,
+    pub booking_date: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+}
+
+/// A discrepancy to investigate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationBreak {
+    pub kind: BreakKind,
+    /// Payment message ID, if a payment is involved
+    pub message_id: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub uetr: Option<String>,
+    /// Statement entry, if one is involved
+    pub entry_ref: Option<String>,
+    pub account_servicer_ref: Option<String>,
+    pub expected_amount: Option<f64>,
+    pub actual_amount: Option<f64>,
+    pub currency: Option<String>,
+    pub detail: String,
+}
+
+/// Outcome of a reconciliation run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    pub matched: Vec<ReconciledPayment>,
+    pub breaks: Vec<ReconciliationBreak>,
+}
+
+impl ReconciliationReport {
+    /// True when every payment matched and nothing is unexplained.
+    pub fn is_clean(&self) -> bool {
+        self.breaks.is_empty()
+    }
+
+    /// Breaks of one kind.
+    pub fn breaks_of(&self, kind: BreakKind) -> impl Iterator<Item = &ReconciliationBreak> {
+        self.breaks.iter().filter(move |b| b.kind == kind)
+    }
+}
+
+/// One transaction on a statement, flattened from its entry.
+struct Line<'a> {
+    entry: &'a StatementEntry,
+    end_to_end_id: Option<&'a str>,
+    uetr: Option<&'a str>,
+    amount: Option<f64>,
+    currency: &'a str,
+}
+
+/// Matches outgoing payments against statements.
+pub struct Reconciler {
+    tolerance: f64,
+    account: Option<String>,
+}
+
+impl Reconciler {
+    /// Create reconciler with the default tolerance.
+    pub fn new() -> Self {
+        Self {
+            tolerance: DEFAULT_TOLERANCE,
+            account: None,
+        }
+    }
+
+    /// Accept amount differences up to `tolerance`.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+
+    /// Only consider reports for this account.
+    pub fn with_account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// Reconcile payments against statements and notifications.
+    ///
+    /// An entry reported both intraday (camt.054) and at end of day
+    /// (camt.053) is counted once, preferring the booked version.
+    pub fn reconcile(&self, payments: &[PaymentInstruction], statements: &[BankStatement]) -> ReconciliationReport {
+        let lines = self.lines(statements);
+        let mut used = vec![false; lines.len()];
+        let mut report = ReconciliationReport::default();
+
+        for payment in payments {
+            let e2e = payment.end_to_end_id();
+            let uetr = payment.uetr.as_deref().map(str::to_ascii_lowercase);
+            let by_uetr: Vec<usize> = match &uetr {
+                Some(u) => (0..lines.len()).filter(|&i| lines[i].uetr == Some(u.as_str())).collect(),
+                None => Vec::new(),
+            };
+            let candidates = if by_uetr.is_empty() {
+                (0..lines.len()).filter(|&i| lines[i].end_to_end_id == Some(e2e)).collect()
+            } else {
+                by_uetr
+            };
+
+            let new_break = |kind, line: Option<&Line>, detail: String| ReconciliationBreak {
+                kind,
+                message_id: Some(payment.message_id.clone()),
+                end_to_end_id: Some(e2e.to_string()),
+                uetr: uetr.clone(),
+                entry_ref: line.and_then(|l| l.entry.entry_ref.clone()),
+                account_servicer_ref: line.and_then(|l| l.entry.account_servicer_ref.clone()),
+                expected_amount: Some(payment.amount),
+                actual_amount: line.and_then(|l| l.amount),
+                currency: Some(payment.currency.clone()),
+                detail,
+            };
+
+            let mut debits = Vec::new();
+            for &i in &candidates {
+                used[i] = true;
+                let line = &lines[i];
+                if line.entry.direction == CreditDebit::Credit || line.entry.reversal {
+                    report.breaks.push(new_break(BreakKind::Returned, Some(line), format!(
+                        "{} returned on {}", payment.message_id,
+                        line.entry.booking_date.as_deref().unwrap_or("unknown date"))));
+                } else {
+                    debits.push(line);
+                }
+            }
+
+            let Some((line, extra)) = debits.split_first() else {
+                if candidates.is_empty() {
+                    report.breaks.push(new_break(BreakKind::NotBooked, None, format!(
+                        "{} not found on any statement", payment.message_id)));
+                }
+                continue;
+            };
+            for dup in extra {
+                report.breaks.push(new_break(BreakKind::Duplicate, Some(dup), format!(
+                    "{} debited again in entry {}", payment.message_id,
+                    dup.entry.account_servicer_ref.as_deref().or(dup.entry.entry_ref.as_deref()).unwrap_or("?"))));
+            }
+
+            if line.currency != payment.currency {
+                report.breaks.push(new_break(BreakKind::CurrencyMismatch, Some(line), format!(
+                    "{} instructed in {}, booked in {}", payment.message_id, payment.currency, line.currency)));
+            } else if let Some(actual) = line.amount.filter(|a| (a - payment.amount).abs() > self.tolerance) {
+                report.breaks.push(new_break(BreakKind::AmountMismatch, Some(line), format!(
+                    "{} instructed {:.2}, booked {:.2}", payment.message_id, payment.amount, actual)));
+            } else if !line.entry.is_booked() {
+                report.breaks.push(new_break(BreakKind::Pending, Some(line), format!(
+                    "{} entry status {}", payment.message_id, line.entry.status)));
+            } else {
+                report.matched.push(ReconciledPayment {
+                    message_id: payment.message_id.clone(),
+                    end_to_end_id: e2e.to_string(),
+                    uetr: uetr.clone(),
+                    entry_ref: line.entry.entry_ref.clone(),
+                    account_servicer_ref: line.entry.account_servicer_ref.clone(),
+                    booking_date: line.entry.booking_date.clone(),
+                    amount: line.amount.unwrap_or(payment.amount),
+                    currency: line.currency.to_string(),
+                });
+            }
+        }
+
+        // Debits nobody instructed; credits are incoming payments
+        for (line, _) in lines.iter().zip(&used).filter(|(_, used)| !**used) {
+            if line.entry.direction == CreditDebit::Debit && !line.entry.reversal {
+                report.breaks.push(ReconciliationBreak {
+                    kind: BreakKind::Unmatched,
+                    message_id: None,
+                    end_to_end_id: line.end_to_end_id.map(String::from),
+                    uetr: line.uetr.map(String::from),
+                    entry_ref: line.entry.entry_ref.clone(),
+                    account_servicer_ref: line.entry.account_servicer_ref.clone(),
+                    expected_amount: None,
+                    actual_amount: line.amount,
+                    currency: Some(line.currency.to_string()),
+                    detail: match line.end_to_end_id {
+                        Some(id) => format!("Debit {} has no matching payment", id),
+                        None => "Debit without transaction references".to_string(),
+                    },
+                });
+            }
+        }
+
+        report
+    }
+
+    /// Flatten entries into transaction lines, deduplicating repeated entries.
+    fn lines<'a>(&self, statements: &'a [BankStatement]) -> Vec<Line<'a>> {
+        let mut entries: Vec<&StatementEntry> = Vec::new();
+        let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+        for (report, entry) in statements.iter().flat_map(|s| s.entries()) {
+            if self.account.as_deref().is_some_and(|a| a != report.account) {
+                continue;
+            }
+            let id = entry.account_servicer_ref.as_deref().or(entry.entry_ref.as_deref());
+            match id.and_then(|id| seen.get(&(report.account.as_str(), id))) {
+                Some(&i) => {
+                    if entry.is_booked() && !entries[i].is_booked() {
+                        entries[i] = entry;
+                    }
+                }
+                None => {
+                    if let Some(id) = id {
+                        seen.insert((report.account.as_str(), id), entries.len());
+                    }
+                    entries.push(entry);
+                }
+            }
+        }
+
+        entries.into_iter().flat_map(|entry| {
+            let single = entry.transactions.len() <= 1;
+            let lines: Vec<Line> = if entry.transactions.is_empty() {
+                vec![Line { entry, end_to_end_id: None, uetr: None, amount: Some(entry.amount), currency: &entry.currency }]
+            } else {
+                entry.transactions.iter().map(|tx| Line {
+                    entry,
+                    end_to_end_id: tx.end_to_end_id.as_deref(),
+                    uetr: tx.uetr.as_deref(),
+                    // A lone transaction without its own amount carries the entry amount
+                    amount: tx.amount.or(single.then_some(entry.amount)),
+                    currency: tx.currency.as_deref().unwrap_or(&entry.currency),
+                }).collect()
+            };
+            lines
+        }).collect()
+    }
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::camt::{fixtures, parse_statement};
+    use super::super::ValidationMode;
+
+    fn payment(message_id: &str, amount: f64, uetr: Option<&str>) -> PaymentInstruction {
+        PaymentInstruction {
+            message_id: message_id.into(),
+            creation_date_time: "2025-12-26T12:00:00Z".into(),
+            instructing_agent: "ABCDDEFF".into(),
+            instructed_agent: None,
+            debtor_name: "John Doe".into(),
+            debtor_account: "DE89370400440532013000".into(),
+            creditor_name: "Jane Smith".into(),
+            creditor_account: "GB33BUKB20201555555555".into(),
+            amount,
+            currency: "EUR".into(),
+            remittance_info: None,
+            uetr: uetr.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_flags_breaks() {
+        let statement = parse_statement(fixtures::CAMT053, ValidationMode::Strict).unwrap();
+        let payments = [
+            // Matched by UETR even though the EndToEndId differs
+            payment("MSG-A", 1000.00, Some("EB6305C9-1F7F-49DE-AED0-16487C27B42D")),
+            payment("MSG002", 210.00, None),
+            payment("MSG003", 75.00, None),
+            payment("MSG004", 10.00, None),
+        ];
+
+        let report = Reconciler::new().reconcile(&payments, &[statement]);
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.matched[0].account_servicer_ref.as_deref(), Some("ASR-1"));
+
+        let kinds: Vec<_> = report.breaks.iter()
+            .map(|b| (b.kind, b.end_to_end_id.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(kinds, [
+            (BreakKind::AmountMismatch, "MSG002"),
+            (BreakKind::Pending, "MSG003"),
+            (BreakKind::NotBooked, "MSG004"),
+            (BreakKind::Unmatched, "MSG999"),
+        ]);
+    }
+
+    #[test]
+    fn test_notification_and_statement_count_once() {
+        let statement = parse_statement(fixtures::CAMT053, ValidationMode::Strict).unwrap();
+        let payments = [payment("MSG001", 1000.00, None)];
+
+        let report = Reconciler::new()
+            .with_account("DE89370400440532013000")
+            .reconcile(&payments, &[statement.clone(), statement]);
+        assert_eq!(report.matched.len(), 1);
+        assert_eq!(report.breaks_of(BreakKind::Duplicate).count(), 0);
+    }
+}
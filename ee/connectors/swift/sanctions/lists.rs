@@ -0,0 +1,330 @@
+//! Sanctions list sources: download and parse OFAC SDN, EU and UN lists
+//!
+//! - OFAC: Specially Designated Nationals list (`SDN.XML`)
+//! - EU: Consolidated list of financial sanctions (XML 1.1)
+//! - UN: Security Council consolidated list
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::super::SwiftError;
+
+/// A published sanctions list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SanctionsList {
+    /// US Treasury OFAC SDN list
+    Ofac,
+    /// EU consolidated financial sanctions list
+    Eu,
+    /// UN Security Council consolidated list
+    Un,
+}
+
+impl SanctionsList {
+    /// Source name as used in `SwiftConfig::sanctions_sources`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ofac => "OFAC",
+            Self::Eu => "EU",
+            Self::Un => "UN",
+        }
+    }
+
+    /// Public download URL. The EU endpoint requires a token query parameter,
+    /// so it is normally overridden with `SanctionsScreener::with_source_url`.
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            Self::Ofac => "https://sanctionslistservice.ofac.treas.gov/api/PublicationPreview/exports/SDN.XML",
+            Self::Eu => "https://webgate.ec.europa.eu/fsd/fsf/public/files/xmlFullSanctionsList_1_1/content",
+            Self::Un => "https://scsanctions.un.org/resources/xml/en/consolidated.xml",
+        }
+    }
+
+    /// Parse a downloaded list document.
+    pub fn parse(&self, xml: &str) -> Result<ListSnapshot, SwiftError> {
+        let doc = roxmltree::Document::parse(xml)
+            .map_err(|e| SwiftError::ParseError(format!("{} list: {}", self.as_str(), e)))?;
+        let (published, entries) = match self {
+            Self::Ofac => parse_ofac(&doc),
+            Self::Eu => parse_eu(&doc),
+            Self::Un => parse_un(&doc),
+        };
+        if entries.is_empty() {
+            return Err(SwiftError::ParseError(format!("{} list has no entries", self.as_str())));
+        }
+        Ok(ListSnapshot {
+            list: *self,
+            published,
+            fetched_at: chrono::Utc::now(),
+            entries,
+        })
+    }
+}
+
+impl std::str::FromStr for SanctionsList {
+    type Err = SwiftError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "OFAC" | "SDN" => Ok(Self::Ofac),
+            "EU" => Ok(Self::Eu),
+            "UN" => Ok(Self::Un),
+            other => Err(SwiftError::ParseError(format!("Unknown sanctions list: {}", other))),
+        }
+    }
+}
+
+/// Kind of sanctioned subject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Individual,
+    Entity,
+    Vessel,
+    Aircraft,
+    Unknown,
+}
+
+/// A sanctioned individual or entity with all its names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanctionsEntry {
+    /// List-qualified identifier, e.g. `OFAC-36` or `UN-QDi.421`
+    pub id: String,
+    pub kind: EntryKind,
+    /// Primary name
+    pub name: String,
+    /// Aliases (a.k.a., f.k.a., alternative spellings)
+    pub aliases: Vec<String>,
+    /// Sanctions programmes or regimes
+    pub programs: Vec<String>,
+}
+
+/// One loaded version of a list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSnapshot {
+    pub list: SanctionsList,
+    /// Publication date stated by the list, if any
+    pub published: Option<String>,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+    pub entries: Vec<SanctionsEntry>,
+}
+
+/// Downloads list documents.
+pub trait ListFetcher: Send + Sync {
+    /// Fetch the document at `url`.
+    fn fetch(&self, url: &str) -> Result<String, SwiftError>;
+}
+
+/// HTTPS fetcher.
+pub struct HttpFetcher {
+    client: reqwest::blocking::Client,
+}
+
+impl HttpFetcher {
+    /// Create fetcher with a request timeout.
+    pub fn new(timeout: Duration) -> Result<Self, SwiftError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| SwiftError::NetworkError(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+impl ListFetcher for HttpFetcher {
+    fn fetch(&self, url: &str) -> Result<String, SwiftError> {
+        self.client.get(url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.text())
+            .map_err(|e| SwiftError::NetworkError(format!("{}: {}", url, e)))
+    }
+}
+
+type Node<'a, 'i> = roxmltree::Node<'a, 'i>;
+
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text(node: Node<'_, '_>, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+}
+
+fn elements<'a, 'i: 'a>(node: Node<'a, 'i>, name: &'a str) -> impl Iterator<Item = Node<'a, 'i>> + 'a {
+    node.descendants().filter(move |n| n.has_tag_name(name))
+}
+
+/// `First Last` from optional parts.
+fn join_names<I: IntoIterator<Item = Option<String>>>(parts: I) -> Option<String> {
+    let name = parts.into_iter().flatten().collect::<Vec<_>>().join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+/// OFAC `sdnList`: `sdnEntry` with `firstName`/`lastName` and an `akaList`.
+fn parse_ofac(doc: &roxmltree::Document<'_>) -> (Option<String>, Vec<SanctionsEntry>) {
+    let root = doc.root_element();
+    let published = child(root, "publshInformation").and_then(|p| child_text(p, "Publish_Date"));
+
+    let entries = root.children().filter(|n| n.has_tag_name("sdnEntry")).filter_map(|entry| {
+        let name = join_names([child_text(entry, "firstName"), child_text(entry, "lastName")])?;
+        let aliases = elements(entry, "aka")
+            .filter_map(|aka| join_names([child_text(aka, "firstName"), child_text(aka, "lastName")]))
+            .collect();
+        let kind = match child_text(entry, "sdnType").as_deref() {
+            Some("Individual") => EntryKind::Individual,
+            Some("Entity") => EntryKind::Entity,
+            Some("Vessel") => EntryKind::Vessel,
+            Some("Aircraft") => EntryKind::Aircraft,
+            _ => EntryKind::Unknown,
+        };
+        Some(SanctionsEntry {
+            id: format!("OFAC-{}", child_text(entry, "uid")?),
+            kind,
+            name,
+            aliases,
+            programs: elements(entry, "program").filter_map(|p| p.text()).map(String::from).collect(),
+        })
+    }).collect();
+
+    (published, entries)
+}
+
+/// EU `export`: `sanctionEntity` with `nameAlias@wholeName`.
+fn parse_eu(doc: &roxmltree::Document<'_>) -> (Option<String>, Vec<SanctionsEntry>) {
+    let root = doc.root_element();
+    let published = root.attribute("generationDate").map(String::from);
+
+    let entries = root.children().filter(|n| n.has_tag_name("sanctionEntity")).filter_map(|entity| {
+        let mut names = elements(entity, "nameAlias")
+            .filter_map(|a| a.attribute("wholeName"))
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from);
+        let name = names.next()?;
+        let kind = match child(entity, "subjectType").and_then(|s| s.attribute("code")) {
+            Some("person") => EntryKind::Individual,
+            Some("enterprise") => EntryKind::Entity,
+            _ => EntryKind::Unknown,
+        };
+        Some(SanctionsEntry {
+            id: format!("EU-{}", entity.attribute("logicalId")?),
+            kind,
+            name,
+            aliases: names.collect(),
+            programs: elements(entity, "regulation")
+                .filter_map(|r| r.attribute("programme"))
+                .map(String::from)
+                .collect(),
+        })
+    }).collect();
+
+    (published, entries)
+}
+
+/// UN `CONSOLIDATED_LIST`: `INDIVIDUAL` and `ENTITY` records.
+fn parse_un(doc: &roxmltree::Document<'_>) -> (Option<String>, Vec<SanctionsEntry>) {
+    let root = doc.root_element();
+    let published = root.attribute("dateGenerated").map(String::from);
+
+    let records = elements(root, "INDIVIDUAL").map(|n| (n, EntryKind::Individual, "INDIVIDUAL_ALIAS"))
+        .chain(elements(root, "ENTITY").map(|n| (n, EntryKind::Entity, "ENTITY_ALIAS")));
+    let entries = records.filter_map(|(record, kind, alias_tag)| {
+        let name = join_names(["FIRST_NAME", "SECOND_NAME", "THIRD_NAME", "FOURTH_NAME"]
+            .map(|part| child_text(record, part)))?;
+        let id = child_text(record, "REFERENCE_NUMBER").or_else(|| child_text(record, "DATAID"))?;
+        Some(SanctionsEntry {
+            id: format!("UN-{}", id),
+            kind,
+            name,
+            aliases: record.children()
+                .filter(|n| n.has_tag_name(alias_tag))
+                .filter_map(|a| child_text(a, "ALIAS_NAME"))
+                .collect(),
+            programs: child_text(record, "UN_LIST_TYPE").into_iter().collect(),
+        })
+    }).collect();
+
+    (published, entries)
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    pub const OFAC: &str = r#"<?xml version="1.0" standalone="yes"?>
+<sdnList xmlns="https://sanctionslistservice.ofac.treas.gov/api/PublicationPreview/exports/XML">
+  <publshInformation><Publish_Date>12/19/2025</Publish_Date><Record_Count>2</Record_Count></publshInformation>
+  <sdnEntry>
+    <uid>36</uid><lastName>AEROCARIBBEAN AIRLINES</lastName><sdnType>Entity</sdnType>
+    <programList><program>CUBA</program></programList>
+    <akaList><aka><uid>12</uid><type>a.k.a.</type><category>strong</category><lastName>AERO-CARIBBEAN</lastName></aka></akaList>
+  </sdnEntry>
+  <sdnEntry>
+    <uid>2674</uid><firstName>Viktor</firstName><lastName>BOUT</lastName><sdnType>Individual</sdnType>
+    <programList><program>SDGT</program></programList>
+    <akaList><aka><uid>4030</uid><firstName>Victor</firstName><lastName>BUTT</lastName></aka></akaList>
+  </sdnEntry>
+</sdnList>"#;
+
+    pub const EU: &str = r#"<export xmlns="http://eu.europa.ec/fpi/fsd/export" generationDate="2025-12-19T10:00:00.000+01:00">
+  <sanctionEntity logicalId="13" designationDate="2003-07-07">
+    <regulation programme="IRQ"/>
+    <subjectType code="person"/>
+    <nameAlias firstName="Saddam" lastName="Hussein Al-Tikriti" wholeName="Saddam Hussein Al-Tikriti"/>
+    <nameAlias wholeName="Abu Ali"/>
+  </sanctionEntity>
+</export>"#;
+
+    pub const UN: &str = r#"<CONSOLIDATED_LIST dateGenerated="2025-12-19T10:00:00.0Z">
+  <INDIVIDUALS>
+    <INDIVIDUAL>
+      <DATAID>6908555</DATAID><FIRST_NAME>RI</FIRST_NAME><SECOND_NAME>WON HO</SECOND_NAME>
+      <UN_LIST_TYPE>DPRK</UN_LIST_TYPE><REFERENCE_NUMBER>KPi.048</REFERENCE_NUMBER>
+      <INDIVIDUAL_ALIAS><QUALITY>Good</QUALITY><ALIAS_NAME>Ri Won-ho</ALIAS_NAME></INDIVIDUAL_ALIAS>
+    </INDIVIDUAL>
+  </INDIVIDUALS>
+  <ENTITIES>
+    <ENTITY>
+      <DATAID>110404</DATAID><FIRST_NAME>AL-AKHTAR TRUST INTERNATIONAL</FIRST_NAME>
+      <UN_LIST_TYPE>Al-Qaida</UN_LIST_TYPE><REFERENCE_NUMBER>QDe.121</REFERENCE_NUMBER>
+      <ENTITY_ALIAS><QUALITY>a.k.a.</QUALITY><ALIAS_NAME>Pakistan Relief Trust</ALIAS_NAME></ENTITY_ALIAS>
+    </ENTITY>
+  </ENTITIES>
+</CONSOLIDATED_LIST>"#;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ofac() {
+        let snapshot = SanctionsList::Ofac.parse(fixtures::OFAC).unwrap();
+        assert_eq!(snapshot.published.as_deref(), Some("12/19/2025"));
+        assert_eq!(snapshot.entries.len(), 2);
+
+        let bout = &snapshot.entries[1];
+        assert_eq!(bout.id, "OFAC-2674");
+        assert_eq!(bout.name, "Viktor BOUT");
+        assert_eq!(bout.aliases, ["Victor BUTT"]);
+        assert_eq!(bout.kind, EntryKind::Individual);
+    }
+
+    #[test]
+    fn test_parse_eu_and_un() {
+        let eu = SanctionsList::Eu.parse(fixtures::EU).unwrap();
+        assert_eq!(eu.entries[0].id, "EU-13");
+        assert_eq!(eu.entries[0].aliases, ["Abu Ali"]);
+
+        let un = SanctionsList::Un.parse(fixtures::UN).unwrap();
+        assert_eq!(un.entries.len(), 2);
+        assert_eq!(un.entries[0].name, "RI WON HO");
+        assert_eq!(un.entries[1].kind, EntryKind::Entity);
+        assert_eq!(un.entries[1].programs, ["Al-Qaida"]);
+
+        assert!(SanctionsList::Un.parse("<CONSOLIDATED_LIST/>").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! Fuzzy name matching for sanctions screening
+//!
+//! Names are transliterated to ASCII (Latin diacritics, Cyrillic),
+//! uppercased and stripped of punctuation and legal-form noise, then
+//! compared with Jaro-Winkler on the whole name, the token-sorted name and
+//! token by token, so word order and extra names do not hide a match.
+
+/// Tokens ignored when comparing names (legal forms, honorifics).
+const NOISE: &[&str] = &[
+    "THE", "OF", "AND", "MR", "MRS", "MS", "DR",
+    "LTD", "LLC", "INC", "CO", "CORP", "PLC", "SA", "AG", "GMBH", "JSC", "OOO", "ZAO", "PJSC", "LIMITED",
+];
+
+/// Normalize a name: transliterate, uppercase, drop punctuation and noise tokens.
+pub fn normalize(name: &str) -> String {
+    let mut ascii = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_uppercase) {
+        match transliterate(c) {
+            Some(s) => ascii.push_str(s),
+            None if c.is_ascii_alphanumeric() => ascii.push(c),
+            // Apostrophes join ("O'BRIEN" = "OBRIEN"), other punctuation separates
+            None if matches!(c, '\'' | '’' | '`') => {}
+            None => ascii.push(' '),
+        }
+    }
+    ascii.split_whitespace()
+        .filter(|t| !NOISE.contains(t))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// ASCII transliteration of an uppercase letter, if it needs one.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        // Latin diacritics
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'Æ' => "AE",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'Ğ' | 'Ģ' => "G",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'Į' | 'İ' => "I",
+        'Ķ' => "K",
+        'Ł' | 'Ľ' | 'Ļ' => "L",
+        'Ñ' | 'Ń' | 'Ň' | 'Ņ' => "N",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ő' | 'Ō' => "O",
+        'Œ' => "OE",
+        'Ř' => "R",
+        'Ś' | 'Š' | 'Ş' | 'Ș' => "S",
+        'ẞ' => "SS",
+        'Ť' | 'Ţ' | 'Ț' => "T",
+        'Þ' => "TH",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'Ý' | 'Ÿ' => "Y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        // Cyrillic (Russian, Ukrainian, Belarusian)
+        'А' => "A", 'Б' => "B", 'В' => "V", 'Г' => "G", 'Ґ' => "G", 'Д' => "D",
+        'Е' | 'Ё' | 'Э' => "E", 'Є' => "YE", 'Ж' => "ZH", 'З' => "Z", 'И' => "I",
+        'І' => "I", 'Ї' => "YI", 'Й' => "Y", 'К' => "K", 'Л' => "L", 'М' => "M",
+        'Н' => "N", 'О' => "O", 'П' => "P", 'Р' => "R", 'С' => "S", 'Т' => "T",
+        'У' | 'Ў' => "U", 'Ф' => "F", 'Х' => "KH", 'Ц' => "TS", 'Ч' => "CH",
+        'Ш' => "SH", 'Щ' => "SHCH", 'Ы' => "Y", 'Ъ' | 'Ь' => "", 'Ю' => "YU", 'Я' => "YA",
+        _ => return None,
+    })
+}
+
+/// Jaro-Winkler similarity in `0.0..=1.0`.
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// Similarity of a screened name to a list name, both already normalized.
+pub fn name_score(screened: &str, listed: &str) -> f64 {
+    if screened.is_empty() || listed.is_empty() {
+        return 0.0;
+    }
+    let whole = jaro_winkler(screened, listed);
+
+    let screened_tokens: Vec<&str> = screened.split(' ').collect();
+    let mut listed_tokens: Vec<&str> = listed.split(' ').collect();
+    // Single-word list names only match as a whole, or every "ALI" would hit
+    if listed_tokens.len() < 2 {
+        return whole;
+    }
+
+    let mut sorted_screened = screened_tokens.clone();
+    sorted_screened.sort_unstable();
+    listed_tokens.sort_unstable();
+    let sorted = jaro_winkler(&sorted_screened.join(" "), &listed_tokens.join(" "));
+
+    // Every listed token must find a counterpart in the screened name
+    let total: usize = listed_tokens.iter().map(|t| t.len()).sum();
+    let tokens = listed_tokens.iter()
+        .map(|l| {
+            let best = screened_tokens.iter().map(|s| jaro_winkler(s, l)).fold(0.0, f64::max);
+            best * l.len() as f64
+        })
+        .sum::<f64>() / total as f64;
+
+    whole.max(sorted).max(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_transliterates() {
+        assert_eq!(normalize("José Müller-Lüdenscheidt"), "JOSE MULLER LUDENSCHEIDT");
+        assert_eq!(normalize("Владимир Путин"), "VLADIMIR PUTIN");
+        assert_eq!(normalize("Acme Trading Co., Ltd."), "ACME TRADING");
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        assert!((jaro_winkler("MARTHA", "MARHTA") - 0.961).abs() < 0.001);
+        assert_eq!(jaro_winkler("SAME", "SAME"), 1.0);
+        assert_eq!(jaro_winkler("ABC", "XYZ"), 0.0);
+    }
+
+    #[test]
+    fn test_name_score() {
+        // Reordered and extra tokens
+        assert!(name_score("HASSAN ALI MOHAMMED", "MOHAMMED HASSAN") > 0.95);
+        // Misspelling
+        assert!(name_score("OSAMA BIN LADEN", "USAMA BIN LADIN") > 0.85);
+        // Unrelated
+        assert!(name_score("JANE SMITH", "BLOCKED PERSON") < 0.6);
+        // Single-token list names need a whole-name match (below the default threshold)
+        assert!(name_score("ALI HASSAN", "ALI") < super::super::DEFAULT_THRESHOLD);
+    }
+}
@@ -0,0 +1,520 @@
+//! Sanctions Screener - AML/CFT Compliance
+//!
+//! Screen payments against OFAC, EU, UN sanctions lists:
+//! - Lists are downloaded, parsed and refreshed on a schedule
+//! - Names are matched fuzzily (Jaro-Winkler + transliteration)
+//! - Confirmed false positives are suppressed through an allow-list
+//! - Every screening leaves an audit record
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let screener = Arc::new(
+//!     SanctionsScreener::new(&["OFAC".into(), "UN".into()])
+//!         .with_threshold(0.9)
+//!         .with_refresh_interval(Duration::from_secs(6 * 3600)),
+//! );
+//! screener.load_lists()?;
+//! let _refresh = screener.spawn_refresh();
+//!
+//! let result = screener.screen("Viktor Bout", "Jane Smith");
+//! ```
+
+mod lists;
+mod matching;
+
+pub use lists::{SanctionsList, SanctionsEntry, EntryKind, ListSnapshot, ListFetcher, HttpFetcher};
+use matching::{normalize, name_score};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::{SanctionsResult, SanctionsMatch, SwiftError};
+
+/// Default score at or above which a name is a hit.
+pub const DEFAULT_THRESHOLD: f64 = 0.88;
+
+/// Default interval between list refreshes.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Wait before retrying a failed refresh.
+const RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Test names screened until real lists are loaded, so integrations can
+/// exercise a hit without list access.
+const TEST_ENTRIES: &[(&str, &str)] = &[("TEST-1", "SANCTIONED ENTITY"), ("TEST-2", "BLOCKED PERSON")];
+
+/// Approved override for a confirmed false positive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowListEntry {
+    /// Name as screened (compared after normalization)
+    pub screened_name: String,
+    /// List entry the name was wrongly matched to, e.g. `OFAC-2674`
+    pub entry_id: String,
+    pub reason: String,
+    pub approved_by: String,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl AllowListEntry {
+    fn covers(&self, normalized_name: &str, entry_id: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.entry_id == entry_id
+            && normalize(&self.screened_name) == normalized_name
+            && self.expires_at.is_none_or(|t| t > now)
+    }
+}
+
+/// Version of a loaded list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListVersion {
+    pub list: String,
+    pub published: Option<String>,
+    pub entries: usize,
+}
+
+/// Outcome of one screening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreeningDecision {
+    Clear,
+    Hit,
+}
+
+/// Audit record of one screening.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningRecord {
+    pub id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub names: Vec<String>,
+    pub threshold: f64,
+    /// Lists screened against
+    pub lists: Vec<ListVersion>,
+    pub decision: ScreeningDecision,
+    pub matches: Vec<SanctionsMatch>,
+    /// Matches suppressed by the allow-list
+    pub overridden: Vec<SanctionsMatch>,
+}
+
+/// A searchable name of a list entry.
+struct IndexedName {
+    list: &'static str,
+    entry_id: String,
+    name: String,
+    normalized: String,
+}
+
+#[derive(Default)]
+struct ScreeningIndex {
+    snapshots: HashMap<SanctionsList, ListSnapshot>,
+    names: Vec<IndexedName>,
+    /// Last refresh in which every source loaded
+    refreshed_at: Option<Instant>,
+}
+
+impl ScreeningIndex {
+    fn rebuild(&mut self) {
+        self.names = self.snapshots.values()
+            .flat_map(|s| s.entries.iter().map(move |e| (s.list.as_str(), e)))
+            .flat_map(|(list, entry)| {
+                std::iter::once(&entry.name).chain(&entry.aliases).map(move |name| IndexedName {
+                    list,
+                    entry_id: entry.id.clone(),
+                    name: name.clone(),
+                    normalized: normalize(name),
+                })
+            })
+            .collect();
+    }
+
+    fn versions(&self) -> Vec<ListVersion> {
+        let mut versions: Vec<_> = self.snapshots.values().map(|s| ListVersion {
+            list: s.list.as_str().to_string(),
+            published: s.published.clone(),
+            entries: s.entries.len(),
+        }).collect();
+        versions.sort_by(|a, b| a.list.cmp(&b.list));
+        versions
+    }
+}
+
+/// Sanctions screening service.
+pub struct SanctionsScreener {
+    sources: Vec<String>,
+    urls: HashMap<SanctionsList, String>,
+    fetcher: Option<Arc<dyn ListFetcher>>,
+    threshold: f64,
+    list_thresholds: HashMap<String, f64>,
+    refresh_interval: Duration,
+    index: RwLock<ScreeningIndex>,
+    test_names: Vec<IndexedName>,
+    allow_list: RwLock<Vec<AllowListEntry>>,
+    audit: Mutex<VecDeque<ScreeningRecord>>,
+    audit_capacity: usize,
+}
+
+impl SanctionsScreener {
+    /// Create new screener with list sources.
+    pub fn new(sources: &[String]) -> Self {
+        Self {
+            sources: sources.to_vec(),
+            urls: HashMap::new(),
+            fetcher: None,
+            threshold: DEFAULT_THRESHOLD,
+            list_thresholds: HashMap::new(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            index: RwLock::new(ScreeningIndex::default()),
+            test_names: TEST_ENTRIES.iter().map(|(id, name)| IndexedName {
+                list: "TEST",
+                entry_id: id.to_string(),
+                name: name.to_string(),
+                normalized: normalize(name),
+            }).collect(),
+            allow_list: RwLock::new(Vec::new()),
+            audit: Mutex::new(VecDeque::new()),
+            audit_capacity: 10_000,
+        }
+    }
+
+    /// Set the hit threshold for all lists (0.0-1.0).
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the hit threshold for one list.
+    pub fn with_list_threshold(mut self, list: SanctionsList, threshold: f64) -> Self {
+        self.list_thresholds.insert(list.as_str().to_string(), threshold.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Override the download URL of a list.
+    pub fn with_source_url(mut self, list: SanctionsList, url: impl Into<String>) -> Self {
+        self.urls.insert(list, url.into());
+        self
+    }
+
+    /// Use a custom fetcher (proxy, mirror, air-gapped file share).
+    pub fn with_fetcher(mut self, fetcher: Arc<dyn ListFetcher>) -> Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    /// Set the interval between scheduled refreshes.
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Set how many audit records are kept in memory.
+    pub fn with_audit_capacity(mut self, capacity: usize) -> Self {
+        self.audit_capacity = capacity;
+        self
+    }
+
+    /// Download and parse all configured lists.
+    ///
+    /// Lists that fail keep their previous version; the error names every
+    /// failed source while the others are still replaced.
+    pub fn load_lists(&self) -> Result<usize, SwiftError> {
+        let fetcher = match &self.fetcher {
+            Some(fetcher) => fetcher.clone(),
+            None => Arc::new(HttpFetcher::new(Duration::from_secs(60))?),
+        };
+
+        let mut loaded = Vec::new();
+        let mut failures = Vec::new();
+        for source in &self.sources {
+            let result = source.parse::<SanctionsList>().and_then(|list| {
+                let url = self.urls.get(&list).map(String::as_str).unwrap_or(list.default_url());
+                list.parse(&fetcher.fetch(url)?)
+            });
+            match result {
+                Ok(snapshot) => loaded.push(snapshot),
+                Err(e) => failures.push(format!("{}: {}", source, e)),
+            }
+        }
+
+        let mut index = self.index.write().unwrap();
+        for snapshot in loaded {
+            index.snapshots.insert(snapshot.list, snapshot);
+        }
+        index.rebuild();
+        if failures.is_empty() {
+            index.refreshed_at = Some(Instant::now());
+            Ok(index.snapshots.values().map(|s| s.entries.len()).sum())
+        } else {
+            Err(SwiftError::NetworkError(format!("Sanctions list refresh failed: {}", failures.join("; "))))
+        }
+    }
+
+    /// Load an already parsed list, e.g. from a file on an air-gapped host.
+    pub fn load_snapshot(&self, snapshot: ListSnapshot) -> usize {
+        let mut index = self.index.write().unwrap();
+        index.snapshots.insert(snapshot.list, snapshot);
+        index.rebuild();
+        index.snapshots.values().map(|s| s.entries.len()).sum()
+    }
+
+    /// Whether the lists are older than the refresh interval.
+    pub fn refresh_due(&self) -> bool {
+        self.index.read().unwrap().refreshed_at
+            .is_none_or(|t| t.elapsed() >= self.refresh_interval)
+    }
+
+    /// Refresh lists in the background until the handle is dropped.
+    pub fn spawn_refresh(self: &Arc<Self>) -> RefreshHandle {
+        let screener = self.clone();
+        let tick = self.refresh_interval.min(RETRY_INTERVAL);
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || loop {
+            if screener.refresh_due() {
+                // On failure the previous lists stay active until the next tick
+                let _ = screener.load_lists();
+            }
+            if stopped.recv_timeout(tick) != Err(mpsc::RecvTimeoutError::Timeout) {
+                break;
+            }
+        });
+        RefreshHandle { stop: Some(stop), thread: Some(thread) }
+    }
+
+    /// Screen names against sanctions.
+    pub fn screen(&self, name1: &str, name2: &str) -> Result<SanctionsResult, SwiftError> {
+        let result = self.screen_names(&[name1, name2]);
+        if let Some(best) = result.matches.first() {
+            return Err(SwiftError::SanctionsHit(format!(
+                "{} matches found, best: {} ({} {}, score {:.2})",
+                result.matches.len(), best.name, best.list, best.entry_id, best.score
+            )));
+        }
+        Ok(result)
+    }
+
+    /// Screen any number of names and record the outcome.
+    pub fn screen_names(&self, names: &[&str]) -> SanctionsResult {
+        let now = chrono::Utc::now();
+        let index = self.index.read().unwrap();
+        let allow_list = self.allow_list.read().unwrap();
+        let candidates = if index.names.is_empty() { &self.test_names } else { &index.names };
+
+        let mut matches = Vec::new();
+        let mut overridden = Vec::new();
+        for screened in names {
+            let normalized = normalize(screened);
+            // Best score per list entry across its names
+            let mut best: HashMap<&str, (f64, &IndexedName)> = HashMap::new();
+            for candidate in candidates {
+                let score = name_score(&normalized, &candidate.normalized);
+                if score >= self.threshold_for(candidate.list) {
+                    let slot = best.entry(candidate.entry_id.as_str()).or_insert((score, candidate));
+                    if score > slot.0 {
+                        *slot = (score, candidate);
+                    }
+                }
+            }
+            for (score, candidate) in best.into_values() {
+                let hit = SanctionsMatch {
+                    list: candidate.list.to_string(),
+                    entry_id: candidate.entry_id.clone(),
+                    name: candidate.name.clone(),
+                    screened_name: screened.to_string(),
+                    score,
+                };
+                if allow_list.iter().any(|a| a.covers(&normalized, &candidate.entry_id, now)) {
+                    overridden.push(hit);
+                } else {
+                    matches.push(hit);
+                }
+            }
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        self.record(ScreeningRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: now,
+            names: names.iter().map(|n| n.to_string()).collect(),
+            threshold: self.threshold,
+            lists: index.versions(),
+            decision: if matches.is_empty() { ScreeningDecision::Clear } else { ScreeningDecision::Hit },
+            matches: matches.clone(),
+            overridden,
+        });
+
+        SanctionsResult {
+            clear: matches.is_empty(),
+            matches,
+        }
+    }
+
+    fn threshold_for(&self, list: &str) -> f64 {
+        self.list_thresholds.get(list).copied().unwrap_or(self.threshold)
+    }
+
+    fn record(&self, record: ScreeningRecord) {
+        let mut audit = self.audit.lock().unwrap();
+        if audit.len() >= self.audit_capacity {
+            audit.pop_front();
+        }
+        audit.push_back(record);
+    }
+
+    /// Add an allow-list override.
+    pub fn allow(&self, entry: AllowListEntry) {
+        self.allow_list.write().unwrap().push(entry);
+    }
+
+    /// Remove overrides for a name and list entry. Returns true if any was removed.
+    pub fn revoke(&self, screened_name: &str, entry_id: &str) -> bool {
+        let normalized = normalize(screened_name);
+        let mut allow_list = self.allow_list.write().unwrap();
+        let before = allow_list.len();
+        allow_list.retain(|a| a.entry_id != entry_id || normalize(&a.screened_name) != normalized);
+        allow_list.len() != before
+    }
+
+    /// Current allow-list.
+    pub fn allow_list(&self) -> Vec<AllowListEntry> {
+        self.allow_list.read().unwrap().clone()
+    }
+
+    /// Recent screening records, oldest first.
+    pub fn audit_records(&self) -> Vec<ScreeningRecord> {
+        self.audit.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Versions of the loaded lists.
+    pub fn list_versions(&self) -> Vec<ListVersion> {
+        self.index.read().unwrap().versions()
+    }
+
+    /// Get number of loaded list entries.
+    pub fn list_count(&self) -> usize {
+        self.index.read().unwrap().snapshots.values().map(|s| s.entries.len()).sum()
+    }
+
+    /// Get configured sources.
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+}
+
+/// Background refresh started by `SanctionsScreener::spawn_refresh`.
+pub struct RefreshHandle {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RefreshHandle {
+    /// Stop refreshing and wait for an in-flight refresh to finish.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for RefreshHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixtureFetcher;
+
+    impl ListFetcher for FixtureFetcher {
+        fn fetch(&self, url: &str) -> Result<String, SwiftError> {
+            if url.contains("ofac") {
+                Ok(lists::fixtures::OFAC.to_string())
+            } else if url.contains("un.org") {
+                Ok(lists::fixtures::UN.to_string())
+            } else {
+                Err(SwiftError::NetworkError("unreachable".into()))
+            }
+        }
+    }
+
+    fn loaded_screener() -> SanctionsScreener {
+        let screener = SanctionsScreener::new(&["OFAC".into(), "UN".into()])
+            .with_fetcher(Arc::new(FixtureFetcher));
+        assert_eq!(screener.load_lists().unwrap(), 4);
+        screener
+    }
+
+    #[test]
+    fn test_clean_names() {
+        let screener = SanctionsScreener::new(&["OFAC".into()]);
+        let result = screener.screen("John Doe", "Jane Smith").unwrap();
+        assert!(result.clear);
+    }
+
+    #[test]
+    fn test_sanctioned_name() {
+        let screener = SanctionsScreener::new(&["OFAC".into()]);
+        let result = screener.screen("SANCTIONED ENTITY", "Jane Smith");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_fuzzy_match_on_loaded_lists() {
+        let screener = loaded_screener();
+        assert!(!screener.refresh_due());
+
+        // Alias with reordered tokens and a transliterated first name
+        let result = screener.screen_names(&["Butt, Wiktor"]);
+        assert_eq!(result.matches[0].entry_id, "OFAC-2674");
+
+        let result = screener.screen_names(&["Ri Won-Ho"]);
+        assert_eq!(result.matches[0].entry_id, "UN-KPi.048");
+
+        // Test names are no longer used once real lists are loaded
+        assert!(screener.screen("SANCTIONED ENTITY", "Jane Smith").is_ok());
+    }
+
+    #[test]
+    fn test_failed_source_keeps_others() {
+        let screener = SanctionsScreener::new(&["OFAC".into(), "EU".into()])
+            .with_fetcher(Arc::new(FixtureFetcher));
+        assert!(matches!(screener.load_lists(), Err(SwiftError::NetworkError(_))));
+        assert_eq!(screener.list_count(), 2);
+        assert!(screener.refresh_due());
+    }
+
+    #[test]
+    fn test_thresholds_allow_list_and_audit() {
+        let screener = loaded_screener().with_list_threshold(SanctionsList::Ofac, 0.99);
+        assert!(screener.screen_names(&["Wiktor Butt"]).clear);
+
+        let screener = loaded_screener();
+        screener.allow(AllowListEntry {
+            screened_name: "Victor Butt".into(),
+            entry_id: "OFAC-2674".into(),
+            reason: "Different date of birth".into(),
+            approved_by: "compliance@bank.example".into(),
+            expires_at: None,
+        });
+        assert!(screener.screen("VICTOR BUTT", "Jane Smith").is_ok());
+
+        let records = screener.audit_records();
+        assert_eq!(records[0].decision, ScreeningDecision::Clear);
+        assert_eq!(records[0].overridden[0].entry_id, "OFAC-2674");
+        assert_eq!(records[0].lists.len(), 2);
+
+        assert!(screener.revoke("victor butt", "OFAC-2674"));
+        assert!(screener.screen("VICTOR BUTT", "Jane Smith").is_err());
+    }
+}
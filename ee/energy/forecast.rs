@@ -0,0 +1,418 @@
+//! Grid Carbon Forecasting
+//!
+//! 24–48h carbon intensity forecasts per region:
+//! - Provider-fed: hourly forecasts fetched from a grid data provider
+//!   (ElectricityMaps) and cached until stale
+//! - Model-based fallback: an hour-of-day profile learned from observed
+//!   intensity, for regions or outages the providers don't cover
+//! - `next_green_window(region, duration)` finds the lowest-carbon stretch
+//!   of the forecast
+//! - Implements arbiter's `CarbonForecastSource`, so `CarbonScheduler` can
+//!   defer batch work and `MitosisController` can prefer greener regions
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let forecaster = Arc::new(GridForecaster::new()?
+//!     .with_provider(Arc::new(ElectricityMapsProvider::new(&api_key))));
+//! forecaster.refresh("eu-west-1").await?;
+//! forecaster.observe("ap-south-1", grid.get_region_data("ap-south-1")?.current_intensity);
+//!
+//! let window = forecaster.next_green_window("eu-west-1", Duration::from_secs(2 * 3600));
+//! let scheduler = CarbonScheduler::new().with_forecast(forecaster.clone());
+//! let controller = MitosisController::new(policy)?.with_carbon_forecast(forecaster);
+//! ```
+
+use super::grid::GridError;
+use agentkern_arbiter::{CarbonForecastSource, GreenWindow};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Weight of the newest observation in an hour's profile.
+const LEARNING_RATE: f64 = 0.3;
+
+/// Intensity forecast for one hour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastSlot {
+    pub starts_at: DateTime<Utc>,
+    pub grams_per_kwh: f64,
+}
+
+/// Where a forecast came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastOrigin {
+    Provider(String),
+    Model,
+}
+
+/// Hourly intensity forecast for a region.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntensityForecast {
+    pub region: String,
+    pub origin: ForecastOrigin,
+    pub generated_at: DateTime<Utc>,
+    pub slots: Vec<ForecastSlot>,
+}
+
+/// Source of provider-fed forecasts.
+#[async_trait]
+pub trait ForecastProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Hourly forecast for the next `hours` hours.
+    async fn fetch(&self, region: &str, hours: u32) -> Result<Vec<ForecastSlot>, GridError>;
+}
+
+/// ElectricityMaps carbon intensity forecasts.
+pub struct ElectricityMapsProvider {
+    api_key: String,
+    endpoint: String,
+    /// Cloud region to grid zone
+    zones: HashMap<String, String>,
+    http: reqwest::Client,
+}
+
+impl ElectricityMapsProvider {
+    pub fn new(api_key: &str) -> Self {
+        let zones = [
+            ("us-east-1", "US-MIDA-PJM"),
+            ("us-west-2", "US-NW-BPAT"),
+            ("eu-west-1", "IE"),
+            ("eu-north-1", "SE-SE3"),
+            ("eu-central-1", "DE"),
+            ("ap-south-1", "IN-WE"),
+            ("ap-southeast-1", "SG"),
+        ];
+        Self {
+            api_key: api_key.to_string(),
+            endpoint: "https://api.electricitymap.org".into(),
+            zones: zones.iter().map(|(r, z)| (r.to_string(), z.to_string())).collect(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Map a cloud region to a grid zone.
+    pub fn with_zone(mut self, region: &str, zone: &str) -> Self {
+        self.zones.insert(region.to_string(), zone.to_string());
+        self
+    }
+
+    fn parse(body: &serde_json::Value) -> Result<Vec<ForecastSlot>, GridError> {
+        let points = body["forecast"].as_array()
+            .ok_or_else(|| GridError::ApiError("forecast missing from response".into()))?;
+        points.iter()
+            .map(|point| {
+                let starts_at = point["datetime"].as_str()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .ok_or_else(|| GridError::ApiError("bad forecast datetime".into()))?;
+                let grams_per_kwh = point["carbonIntensity"].as_f64()
+                    .ok_or_else(|| GridError::ApiError("bad forecast intensity".into()))?;
+                Ok(ForecastSlot { starts_at: starts_at.with_timezone(&Utc), grams_per_kwh })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ForecastProvider for ElectricityMapsProvider {
+    fn name(&self) -> &str {
+        "ElectricityMaps"
+    }
+
+    async fn fetch(&self, region: &str, hours: u32) -> Result<Vec<ForecastSlot>, GridError> {
+        let zone = self.zones.get(region).map(String::as_str).unwrap_or(region);
+        let response = self.http
+            .get(format!("{}/v3/carbon-intensity/forecast", self.endpoint))
+            .query(&[("zone", zone)])
+            .header("auth-token", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| GridError::ApiError(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(GridError::RegionNotSupported(region.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(GridError::ApiError(format!("ElectricityMaps returned {}", response.status())));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| GridError::ApiError(e.to_string()))?;
+        let mut slots = Self::parse(&body)?;
+        slots.truncate(hours as usize);
+        Ok(slots)
+    }
+}
+
+/// Hour-of-day (UTC) intensity profile per region, learned from observations.
+#[derive(Debug, Default)]
+struct DiurnalModel {
+    profiles: HashMap<String, [Option<f64>; 24]>,
+}
+
+impl DiurnalModel {
+    fn observe(&mut self, region: &str, at: DateTime<Utc>, grams_per_kwh: f64) {
+        let profile = self.profiles.entry(region.to_string()).or_insert([None; 24]);
+        let slot = &mut profile[at.hour() as usize];
+        *slot = Some(match *slot {
+            Some(current) => current + (grams_per_kwh - current) * LEARNING_RATE,
+            None => grams_per_kwh,
+        });
+    }
+
+    /// Hours never observed take the average of those that were.
+    fn forecast(&self, region: &str, from: DateTime<Utc>, hours: u32) -> Option<Vec<ForecastSlot>> {
+        let profile = self.profiles.get(region)?;
+        let observed: Vec<f64> = profile.iter().flatten().copied().collect();
+        let mean = observed.iter().sum::<f64>() / observed.len() as f64;
+        Some((0..hours)
+            .map(|h| {
+                let starts_at = from + ChronoDuration::hours(h as i64);
+                ForecastSlot { starts_at, grams_per_kwh: profile[starts_at.hour() as usize].unwrap_or(mean) }
+            })
+            .collect())
+    }
+}
+
+/// Carbon intensity forecaster, provider-fed with a learned fallback.
+pub struct GridForecaster {
+    providers: Vec<Arc<dyn ForecastProvider>>,
+    model: RwLock<DiurnalModel>,
+    cache: RwLock<HashMap<String, IntensityForecast>>,
+    horizon_hours: u32,
+    /// Provider forecasts older than this fall back to the model
+    max_age: ChronoDuration,
+}
+
+impl Default for GridForecaster {
+    fn default() -> Self {
+        Self {
+            providers: vec![],
+            model: RwLock::new(DiurnalModel::default()),
+            cache: RwLock::new(HashMap::new()),
+            horizon_hours: 48,
+            max_age: ChronoDuration::hours(6),
+        }
+    }
+}
+
+impl std::fmt::Debug for GridForecaster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GridForecaster")
+            .field("providers", &self.providers.iter().map(|p| p.name()).collect::<Vec<_>>())
+            .field("horizon_hours", &self.horizon_hours)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GridForecaster {
+    /// Create new forecaster.
+    pub fn new() -> Result<Self, GridError> {
+        crate::connectors::license::check_feature_license("grid_api")?;
+        Ok(Self::default())
+    }
+
+    /// Add a forecast provider; earlier providers are tried first.
+    pub fn with_provider(mut self, provider: Arc<dyn ForecastProvider>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+
+    /// Forecast horizon, 24 to 48 hours.
+    pub fn with_horizon_hours(mut self, hours: u32) -> Self {
+        self.horizon_hours = hours.clamp(24, 48);
+        self
+    }
+
+    /// Record a measured intensity for the model.
+    pub fn observe(&self, region: &str, grams_per_kwh: f64) {
+        self.observe_at(region, Utc::now(), grams_per_kwh);
+    }
+
+    pub fn observe_at(&self, region: &str, at: DateTime<Utc>, grams_per_kwh: f64) {
+        self.model.write().unwrap().observe(region, at, grams_per_kwh);
+    }
+
+    /// Fetch a fresh provider forecast for `region`, falling back to the model.
+    pub async fn refresh(&self, region: &str) -> Result<IntensityForecast, GridError> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.fetch(region, self.horizon_hours).await {
+                Ok(slots) if !slots.is_empty() => {
+                    let forecast = IntensityForecast {
+                        region: region.to_string(),
+                        origin: ForecastOrigin::Provider(provider.name().to_string()),
+                        generated_at: Utc::now(),
+                        slots,
+                    };
+                    self.cache.write().unwrap().insert(region.to_string(), forecast.clone());
+                    return Ok(forecast);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(provider = provider.name(), region, error = %e, "Forecast provider failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+        self.forecast(region)
+            .ok_or_else(|| last_error.unwrap_or_else(|| GridError::RegionNotSupported(region.to_string())))
+    }
+
+    /// Current forecast: a fresh provider forecast, else the model's.
+    pub fn forecast(&self, region: &str) -> Option<IntensityForecast> {
+        self.forecast_at(region, Utc::now())
+    }
+
+    fn forecast_at(&self, region: &str, now: DateTime<Utc>) -> Option<IntensityForecast> {
+        let this_hour = now.duration_trunc(ChronoDuration::hours(1)).unwrap_or(now);
+        if let Some(cached) = self.cache.read().unwrap().get(region) {
+            if now - cached.generated_at < self.max_age {
+                let slots: Vec<_> = cached.slots.iter().filter(|s| s.starts_at >= this_hour).cloned().collect();
+                if !slots.is_empty() {
+                    return Some(IntensityForecast { slots, ..cached.clone() });
+                }
+            }
+        }
+        let slots = self.model.read().unwrap().forecast(region, this_hour, self.horizon_hours)?;
+        Some(IntensityForecast {
+            region: region.to_string(),
+            origin: ForecastOrigin::Model,
+            generated_at: now,
+            slots,
+        })
+    }
+
+    /// Lowest-carbon window of `duration` within the forecast horizon.
+    pub fn next_green_window(&self, region: &str, duration: Duration) -> Option<GreenWindow> {
+        let now = Utc::now();
+        self.window_at(region, duration, now, now + ChronoDuration::hours(self.horizon_hours as i64))
+    }
+
+    fn window_at(&self, region: &str, duration: Duration, now: DateTime<Utc>, finish_by: DateTime<Utc>) -> Option<GreenWindow> {
+        let forecast = self.forecast_at(region, now)?;
+        let length = ChronoDuration::from_std(duration).ok()?;
+        let hours = (duration.as_secs().div_ceil(3600) as usize).max(1);
+        forecast.slots.windows(hours)
+            .filter_map(|slots| {
+                let starts_at = slots[0].starts_at.max(now);
+                let ends_at = starts_at + length;
+                (ends_at <= finish_by).then(|| {
+                    let avg = slots.iter().map(|s| s.grams_per_kwh).sum::<f64>() / slots.len() as f64;
+                    (starts_at, ends_at, avg)
+                })
+            })
+            // Earliest wins a tie
+            .min_by(|a, b| a.2.total_cmp(&b.2).then(a.0.cmp(&b.0)))
+            .map(|(starts_at, ends_at, avg)| GreenWindow {
+                region: region.to_string(),
+                starts_at: starts_at.timestamp() as u64,
+                ends_at: ends_at.timestamp() as u64,
+                avg_grams_per_kwh: avg,
+            })
+    }
+}
+
+impl CarbonForecastSource for GridForecaster {
+    fn current_intensity(&self, region: &str) -> Option<f64> {
+        self.forecast(region)?.slots.first().map(|s| s.grams_per_kwh)
+    }
+
+    fn green_window(&self, region: &str, duration: Duration, finish_by: u64) -> Option<GreenWindow> {
+        let finish_by = DateTime::from_timestamp(finish_by as i64, 0)?;
+        self.window_at(region, duration, Utc::now(), finish_by)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_arbiter::CarbonScheduler;
+
+    struct Fixed(Vec<f64>);
+
+    #[async_trait]
+    impl ForecastProvider for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn fetch(&self, region: &str, hours: u32) -> Result<Vec<ForecastSlot>, GridError> {
+            if region != "eu-central-1" {
+                return Err(GridError::RegionNotSupported(region.into()));
+            }
+            let this_hour = Utc::now().duration_trunc(ChronoDuration::hours(1)).unwrap();
+            Ok(self.0.iter().take(hours as usize).enumerate()
+                .map(|(h, &grams_per_kwh)| ForecastSlot { starts_at: this_hour + ChronoDuration::hours(h as i64), grams_per_kwh })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_forecast_drives_scheduler() {
+        // Dirty now, a two-hour solar dip five hours out
+        let mut hours = vec![400.0; 48];
+        hours[5] = 90.0;
+        hours[6] = 110.0;
+        let forecaster = Arc::new(GridForecaster::default().with_provider(Arc::new(Fixed(hours))));
+
+        let forecast = forecaster.refresh("eu-central-1").await.unwrap();
+        assert_eq!(forecast.origin, ForecastOrigin::Provider("fixed".into()));
+        assert!(forecaster.refresh("ap-south-1").await.is_err());
+
+        let window = forecaster.next_green_window("eu-central-1", Duration::from_secs(2 * 3600)).unwrap();
+        assert_eq!(window.avg_grams_per_kwh, 100.0);
+        assert_eq!(window.ends_at - window.starts_at, 7200);
+
+        let scheduler = CarbonScheduler::new().with_forecast(forecaster);
+        let placement = scheduler.plan_batch(&["eu-central-1", "us-east-1"], Duration::from_secs(7200), Duration::from_secs(86400)).unwrap();
+        assert_eq!(placement.region, "eu-central-1");
+        assert!(placement.deferred_secs > 4 * 3600);
+    }
+
+    #[test]
+    fn test_model_learns_daily_profile() {
+        let forecaster = GridForecaster::default();
+        let start = DateTime::parse_from_rfc3339("2026-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        for day in 0..3 {
+            for hour in 0..24 {
+                // Solar dip around 11:00-14:00 UTC
+                let grams = if (11..14).contains(&hour) { 120.0 } else { 320.0 };
+                forecaster.observe_at("eu-central-1", start + ChronoDuration::hours(day * 24 + hour), grams);
+            }
+        }
+
+        let now = start + ChronoDuration::days(3) + ChronoDuration::minutes(30);
+        let forecast = forecaster.forecast_at("eu-central-1", now).unwrap();
+        assert_eq!(forecast.origin, ForecastOrigin::Model);
+        assert_eq!(forecast.slots.len(), 48);
+
+        let window = forecaster.window_at("eu-central-1", Duration::from_secs(3 * 3600), now, now + ChronoDuration::hours(24)).unwrap();
+        let starts_at = DateTime::from_timestamp(window.starts_at as i64, 0).unwrap();
+        assert_eq!(starts_at.hour(), 11);
+        assert!((window.avg_grams_per_kwh - 120.0).abs() < 1e-9);
+        assert!(forecaster.forecast_at("us-east-1", now).is_none());
+    }
+
+    #[test]
+    fn test_parse_electricity_maps_forecast() {
+        let body = serde_json::json!({
+            "zone": "DE",
+            "forecast": [
+                { "carbonIntensity": 310, "datetime": "2026-06-01T10:00:00.000Z" },
+                { "carbonIntensity": 180, "datetime": "2026-06-01T11:00:00.000Z" }
+            ]
+        });
+        let slots = ElectricityMapsProvider::parse(&body).unwrap();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[1].grams_per_kwh, 180.0);
+        assert_eq!(slots[1].starts_at.hour(), 11);
+    }
+}
@@ -0,0 +1,394 @@
+//! Energy Metering
+//!
+//! Measured energy per agent and per cell, for carbon budgets and billing:
+//! - Executors report CPU-seconds and GPU-seconds per agent
+//! - Where Intel RAPL is readable, measured package energy for each interval
+//!   is split across agents by CPU share; elsewhere a power model converts
+//!   CPU-seconds to energy. GPU energy always comes from the power model.
+//! - Each flush feeds the treasury carbon ledger (at live grid intensity
+//!   when a forecast source is set) and the cost tracker
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let meter = EnergyMeter::new("cell-eu-1")
+//!     .with_rapl(RaplReader::detect())
+//!     .with_grid(forecaster.clone(), "eu-west-1")
+//!     .with_carbon_ledger(ledger.clone())
+//!     .with_cost_tracker(tracker.clone(), 0.18);
+//!
+//! meter.record_usage("agent-7", ComputeUsage { cpu_seconds: 1.8, gpu_seconds: 0.0 });
+//! for reading in meter.flush() {
+//!     println!("{} used {:.6} kWh ({:?})", reading.agent_id, reading.energy_kwh, reading.method);
+//! }
+//! ```
+
+use agentkern_arbiter::{CarbonForecastSource, CostCategory, CostTracker};
+use agentkern_treasury::carbon::{CarbonFootprint, CarbonLedger, CarbonRegion, ComputeType};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Joules in a kWh.
+const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+/// Compute time an agent used since it last reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComputeUsage {
+    pub cpu_seconds: f64,
+    pub gpu_seconds: f64,
+}
+
+/// Heuristic power draw, for hosts without energy counters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerModel {
+    /// Per fully busy core
+    pub cpu_watts_per_core: f64,
+    /// Per fully busy GPU
+    pub gpu_watts: f64,
+    /// Data centre overhead (power usage effectiveness)
+    pub pue: f64,
+}
+
+impl Default for PowerModel {
+    fn default() -> Self {
+        Self {
+            cpu_watts_per_core: 12.0,
+            gpu_watts: 350.0,
+            pue: 1.2,
+        }
+    }
+}
+
+/// How a reading was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteringMethod {
+    /// CPU energy from RAPL counters
+    Rapl,
+    /// Power model only
+    Estimated,
+}
+
+/// Intel RAPL package energy counters under `/sys/class/powercap`.
+#[derive(Debug)]
+pub struct RaplReader {
+    /// Counter file, wrap-around range and last value, per package
+    domains: Vec<(PathBuf, u64, Option<u64>)>,
+}
+
+impl RaplReader {
+    /// Package domains on this host, if their counters are readable.
+    pub fn detect() -> Option<Self> {
+        Self::from_root(Path::new("/sys/class/powercap"))
+    }
+
+    pub fn from_root(root: &Path) -> Option<Self> {
+        let mut domains = Vec::new();
+        for entry in std::fs::read_dir(root).ok()?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Packages only: "intel-rapl:0", not subdomains like "intel-rapl:0:1"
+            if !name.starts_with("intel-rapl:") || name.matches(':').count() != 1 {
+                continue;
+            }
+            let dir = entry.path();
+            let max = read_u64(&dir.join("max_energy_range_uj")).unwrap_or(u64::MAX);
+            let counter = dir.join("energy_uj");
+            read_u64(&counter)?;
+            domains.push((counter, max, None));
+        }
+        (!domains.is_empty()).then_some(Self { domains })
+    }
+
+    /// Joules used by all packages since the previous read.
+    fn read_joules(&mut self) -> Option<f64> {
+        let mut micro_joules = 0u64;
+        for (counter, max, last) in &mut self.domains {
+            let now = read_u64(counter)?;
+            if let Some(previous) = *last {
+                micro_joules += if now >= previous { now - previous } else { *max - previous + now };
+            }
+            *last = Some(now);
+        }
+        Some(micro_joules as f64 / 1_000_000.0)
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Energy attributed to one agent for one interval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnergyReading {
+    pub agent_id: String,
+    pub cell_id: String,
+    pub usage: ComputeUsage,
+    pub energy_kwh: f64,
+    pub co2_grams: f64,
+    pub method: MeteringMethod,
+    /// The carbon ledger reported the agent over its budget
+    pub over_budget: bool,
+}
+
+/// Running totals for an agent or the whole cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyTotals {
+    pub cpu_seconds: f64,
+    pub gpu_seconds: f64,
+    pub energy_kwh: f64,
+    pub co2_grams: f64,
+}
+
+impl EnergyTotals {
+    fn add(&mut self, reading: &EnergyReading) {
+        self.cpu_seconds += reading.usage.cpu_seconds;
+        self.gpu_seconds += reading.usage.gpu_seconds;
+        self.energy_kwh += reading.energy_kwh;
+        self.co2_grams += reading.co2_grams;
+    }
+}
+
+#[derive(Default)]
+struct MeterState {
+    pending: HashMap<String, ComputeUsage>,
+    agents: HashMap<String, EnergyTotals>,
+    cell: EnergyTotals,
+    /// Measured energy with no agent to attribute it to (kWh)
+    idle_kwh: f64,
+    last_flush: Option<Instant>,
+}
+
+/// Meters energy for the agents running in one cell.
+pub struct EnergyMeter {
+    cell_id: String,
+    model: PowerModel,
+    rapl: Option<Mutex<RaplReader>>,
+    region: CarbonRegion,
+    grid: Option<(Arc<dyn CarbonForecastSource>, String)>,
+    ledger: Option<Arc<CarbonLedger>>,
+    cost: Option<(Arc<CostTracker>, f64)>,
+    state: Mutex<MeterState>,
+}
+
+impl EnergyMeter {
+    pub fn new(cell_id: &str) -> Self {
+        Self {
+            cell_id: cell_id.to_string(),
+            model: PowerModel::default(),
+            rapl: None,
+            region: CarbonRegion::default(),
+            grid: None,
+            ledger: None,
+            cost: None,
+            state: Mutex::new(MeterState::default()),
+        }
+    }
+
+    pub fn with_power_model(mut self, model: PowerModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Use RAPL counters, e.g. from [`RaplReader::detect`].
+    pub fn with_rapl(mut self, reader: Option<RaplReader>) -> Self {
+        self.rapl = reader.map(|mut reader| {
+            // Start the interval now
+            reader.read_joules();
+            Mutex::new(reader)
+        });
+        self
+    }
+
+    /// Static grid intensity, used when no live source is set.
+    pub fn with_region(mut self, region: CarbonRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Live grid intensity for the cell's cloud region.
+    pub fn with_grid(mut self, source: Arc<dyn CarbonForecastSource>, region: &str) -> Self {
+        self.grid = Some((source, region.to_string()));
+        self
+    }
+
+    pub fn with_carbon_ledger(mut self, ledger: Arc<CarbonLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Bill energy to agents at `usd_per_kwh`.
+    pub fn with_cost_tracker(mut self, tracker: Arc<CostTracker>, usd_per_kwh: f64) -> Self {
+        self.cost = Some((tracker, usd_per_kwh));
+        self
+    }
+
+    /// Add compute time an agent used.
+    pub fn record_usage(&self, agent_id: &str, usage: ComputeUsage) {
+        let mut state = self.state.lock().unwrap();
+        let pending = state.pending.entry(agent_id.to_string()).or_default();
+        pending.cpu_seconds += usage.cpu_seconds;
+        pending.gpu_seconds += usage.gpu_seconds;
+    }
+
+    fn carbon_region(&self) -> CarbonRegion {
+        self.grid.as_ref()
+            .and_then(|(source, region)| source.current_intensity(region))
+            .map(|grams| CarbonRegion::Custom(grams.round() as u32))
+            .unwrap_or(self.region)
+    }
+
+    /// Turn usage reported since the last flush into readings, and record
+    /// them in the carbon ledger and cost tracker.
+    pub fn flush(&self) -> Vec<EnergyReading> {
+        let mut state = self.state.lock().unwrap();
+        let pending = std::mem::take(&mut state.pending);
+        let interval = state.last_flush.replace(Instant::now()).map(|at| at.elapsed());
+        let measured_joules = self.rapl.as_ref().and_then(|rapl| rapl.lock().unwrap().read_joules());
+        let total_cpu: f64 = pending.values().map(|u| u.cpu_seconds).sum();
+
+        if pending.is_empty() || total_cpu == 0.0 {
+            if let Some(joules) = measured_joules {
+                state.idle_kwh += joules * self.model.pue / JOULES_PER_KWH;
+            }
+        }
+
+        let region = self.carbon_region();
+        let mut readings = Vec::new();
+        for (agent_id, usage) in pending {
+            let (cpu_joules, method) = match measured_joules {
+                Some(joules) if total_cpu > 0.0 => (joules * usage.cpu_seconds / total_cpu, MeteringMethod::Rapl),
+                _ => (usage.cpu_seconds * self.model.cpu_watts_per_core, MeteringMethod::Estimated),
+            };
+            let cpu_kwh = cpu_joules * self.model.pue / JOULES_PER_KWH;
+            let gpu_kwh = usage.gpu_seconds * self.model.gpu_watts * self.model.pue / JOULES_PER_KWH;
+
+            let mut reading = EnergyReading {
+                agent_id,
+                cell_id: self.cell_id.clone(),
+                usage,
+                energy_kwh: cpu_kwh + gpu_kwh,
+                co2_grams: (cpu_kwh + gpu_kwh) * region.intensity() as f64,
+                method,
+                over_budget: false,
+            };
+            let duration_ms = interval.map_or(0, |d| d.as_millis() as u64);
+            reading.over_budget = self.record_carbon(&reading.agent_id, ComputeType::Cpu, cpu_kwh, duration_ms, region)
+                | self.record_carbon(&reading.agent_id, ComputeType::Gpu, gpu_kwh, duration_ms, region);
+            self.record_cost(&reading);
+
+            state.agents.entry(reading.agent_id.clone()).or_default().add(&reading);
+            state.cell.add(&reading);
+            readings.push(reading);
+        }
+        readings
+    }
+
+    /// Returns whether the agent is now over its carbon budget.
+    fn record_carbon(&self, agent_id: &str, compute_type: ComputeType, energy_kwh: f64, duration_ms: u64, region: CarbonRegion) -> bool {
+        let (Some(ledger), Some(energy_kwh)) = (&self.ledger, Decimal::from_f64(energy_kwh)) else {
+            return false;
+        };
+        if energy_kwh.is_zero() {
+            return false;
+        }
+        let footprint = CarbonFootprint::from_energy(agent_id.to_string(), "metered", compute_type, energy_kwh, duration_ms, region);
+        match ledger.record_consumed(footprint) {
+            Ok(()) => false,
+            Err(e) => {
+                tracing::warn!(agent_id, cell_id = %self.cell_id, error = %e, "Agent over carbon budget");
+                true
+            }
+        }
+    }
+
+    fn record_cost(&self, reading: &EnergyReading) {
+        let Some((tracker, usd_per_kwh)) = &self.cost else {
+            return;
+        };
+        tracker.record(tracker.event(&reading.agent_id, CostCategory::Compute)
+            .resource(&self.cell_id)
+            .amount(reading.energy_kwh * usd_per_kwh)
+            .quantity(reading.energy_kwh, "kWh")
+            .meta("co2_grams", reading.co2_grams.into())
+            .meta("method", serde_json::json!(reading.method))
+            .build());
+    }
+
+    pub fn agent_totals(&self, agent_id: &str) -> EnergyTotals {
+        self.state.lock().unwrap().agents.get(agent_id).copied().unwrap_or_default()
+    }
+
+    /// Totals for everything metered in this cell, idle energy included.
+    pub fn cell_totals(&self) -> EnergyTotals {
+        let state = self.state.lock().unwrap();
+        EnergyTotals { energy_kwh: state.cell.energy_kwh + state.idle_kwh, ..state.cell }
+    }
+
+    pub fn cell_id(&self) -> &str {
+        &self.cell_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_treasury::carbon::CarbonBudget;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_heuristic_metering_feeds_ledger_and_costs() {
+        let ledger = Arc::new(CarbonLedger::new());
+        let tracker = Arc::new(CostTracker::new());
+        ledger.set_budget(CarbonBudget::new("agent-2".to_string()).with_daily_limit(dec!(0.5)).block_on_exceed());
+        let meter = EnergyMeter::new("cell-1")
+            .with_power_model(PowerModel { cpu_watts_per_core: 10.0, gpu_watts: 300.0, pue: 1.0 })
+            .with_region(CarbonRegion::Custom(400))
+            .with_carbon_ledger(ledger.clone())
+            .with_cost_tracker(tracker.clone(), 0.2);
+
+        meter.record_usage("agent-1", ComputeUsage { cpu_seconds: 360.0, gpu_seconds: 0.0 });
+        meter.record_usage("agent-2", ComputeUsage { cpu_seconds: 0.0, gpu_seconds: 60.0 });
+        let mut readings = meter.flush();
+        readings.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+
+        // 360 s at 10 W = 1 Wh; 60 s at 300 W = 5 Wh
+        assert!((readings[0].energy_kwh - 0.001).abs() < 1e-12);
+        assert!((readings[1].energy_kwh - 0.005).abs() < 1e-12);
+        assert_eq!(readings[0].method, MeteringMethod::Estimated);
+        assert!(!readings[0].over_budget);
+        assert!(readings[1].over_budget);
+
+        assert_eq!(ledger.get_daily_usage(&"agent-2".to_string()).total_co2_grams, dec!(2));
+        assert!((tracker.get_agent_total("agent-1") - 0.0002).abs() < 1e-12);
+        assert!((meter.cell_totals().energy_kwh - 0.006).abs() < 1e-12);
+        assert!(meter.flush().is_empty());
+    }
+
+    #[test]
+    fn test_rapl_energy_split_by_cpu_share() {
+        let root = std::env::temp_dir().join(format!("rapl-{}", uuid::Uuid::new_v4()));
+        let package = root.join("intel-rapl:0");
+        std::fs::create_dir_all(root.join("intel-rapl:0:0")).unwrap();
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(package.join("max_energy_range_uj"), "2000000000").unwrap();
+        std::fs::write(package.join("energy_uj"), "1500000000").unwrap();
+
+        let meter = EnergyMeter::new("cell-1")
+            .with_power_model(PowerModel { pue: 1.0, ..PowerModel::default() })
+            .with_rapl(RaplReader::from_root(&root));
+        meter.record_usage("agent-1", ComputeUsage { cpu_seconds: 3.0, gpu_seconds: 0.0 });
+        meter.record_usage("agent-2", ComputeUsage { cpu_seconds: 1.0, gpu_seconds: 0.0 });
+        // Counter wrapped: 1000 J used in the interval
+        std::fs::write(package.join("energy_uj"), "500000000").unwrap();
+        meter.flush();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!((meter.agent_totals("agent-1").energy_kwh - 750.0 / JOULES_PER_KWH).abs() < 1e-12);
+        assert!((meter.agent_totals("agent-2").energy_kwh - 250.0 / JOULES_PER_KWH).abs() < 1e-12);
+    }
+}
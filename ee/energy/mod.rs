@@ -8,9 +8,13 @@
 pub mod grid;
 pub mod intersect;
 pub mod demo;
+pub mod forecast;
+pub mod metering;
 
 // Re-exports
 pub use grid::{GridApi, CarbonIntensityFeed, RegionData};
 pub use intersect::{IntersectClient, IntersectConfig};
 pub use demo::{DemoGridApi, GridFactory};
+pub use forecast::{GridForecaster, ForecastProvider, ElectricityMapsProvider, IntensityForecast, ForecastSlot, ForecastOrigin};
 
+pub use metering::{EnergyMeter, EnergyReading, EnergyTotals, ComputeUsage, PowerModel, MeteringMethod, RaplReader};
@@ -0,0 +1,443 @@
+//! Adaptive Card Templates
+//!
+//! Cards for escalation, drift and cost alerts:
+//! - Styled by severity: the header container and title use the Adaptive
+//!   Card `accent`, `warning` or `attention` colors
+//! - Deep links to the escalation, drift alert or cost record in the dashboard
+//! - Localized titles, labels and buttons. The language is fixed per
+//!   template set or detected from the alert text with synapse's
+//!   [`Language::detect`]; Arabic cards render right-to-left
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let templates = CardTemplates::new()
+//!     .with_dashboard_url("https://ops.example.com")
+//!     .with_language(Language::Japanese);
+//! let card = templates.drift(&drift_alert);
+//! ```
+
+use super::teams::{AdaptiveCard, CardAction, CardElement, Fact, TeamsAlert};
+use agentkern_arbiter::cost::{AlertLevel, CostAlert};
+use agentkern_synapse::drift::{AlertSeverity, DriftAlert};
+use agentkern_synapse::Language;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_DASHBOARD_URL: &str = "https://dashboard.agentkern.com";
+
+/// Card severity, which sets its styling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CardSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl CardSeverity {
+    /// Severity of an escalation level such as `High`.
+    pub fn from_level(level: &str) -> Self {
+        match level.to_ascii_lowercase().as_str() {
+            "high" | "critical" => Self::Critical,
+            "medium" | "warning" => Self::Warning,
+            _ => Self::Info,
+        }
+    }
+
+    /// Container style.
+    pub fn style(&self) -> &'static str {
+        match self {
+            Self::Info => "accent",
+            Self::Warning => "warning",
+            Self::Critical => "attention",
+        }
+    }
+
+    /// Text color.
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Info => "Accent",
+            Self::Warning => "Warning",
+            Self::Critical => "Attention",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Info => "ℹ️",
+            Self::Warning => "⚠️",
+            Self::Critical => "🚨",
+        }
+    }
+}
+
+impl From<AlertSeverity> for CardSeverity {
+    fn from(severity: AlertSeverity) -> Self {
+        match severity {
+            AlertSeverity::Info => Self::Info,
+            AlertSeverity::Warning => Self::Warning,
+            AlertSeverity::Critical => Self::Critical,
+        }
+    }
+}
+
+impl From<AlertLevel> for CardSeverity {
+    fn from(level: AlertLevel) -> Self {
+        match level {
+            AlertLevel::Warning => Self::Warning,
+            AlertLevel::Critical | AlertLevel::Emergency => Self::Critical,
+        }
+    }
+}
+
+/// Localized card text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardText {
+    EscalationTitle,
+    DriftTitle,
+    CostTitle,
+    Agent,
+    Task,
+    Level,
+    Intent,
+    DriftScore,
+    Progress,
+    Reason,
+    Spend,
+    Threshold,
+    AgentPaused,
+    Yes,
+    No,
+    Approve,
+    Reject,
+    ViewDetails,
+}
+
+impl CardText {
+    /// Text in `language`; English where there is no translation.
+    pub fn localize(&self, language: Language) -> &'static str {
+        use CardText::*;
+        match (language, self) {
+            (Language::Spanish, EscalationTitle) => "Escalamiento",
+            (Language::Spanish, DriftTitle) => "Desviación de intención",
+            (Language::Spanish, CostTitle) => "Alerta de costos",
+            (Language::Spanish, Agent) => "Agente",
+            (Language::Spanish, Task) => "Tarea",
+            (Language::Spanish, Level) => "Nivel",
+            (Language::Spanish, Intent) => "Intención",
+            (Language::Spanish, DriftScore) => "Puntuación de desviación",
+            (Language::Spanish, Progress) => "Progreso",
+            (Language::Spanish, Reason) => "Motivo",
+            (Language::Spanish, Spend) => "Gasto",
+            (Language::Spanish, Threshold) => "Umbral",
+            (Language::Spanish, AgentPaused) => "Agente pausado",
+            (Language::Spanish, Yes) => "Sí",
+            (Language::Spanish, No) => "No",
+            (Language::Spanish, Approve) => "Aprobar",
+            (Language::Spanish, Reject) => "Rechazar",
+            (Language::Spanish, ViewDetails) => "Ver detalles",
+
+            (Language::French, EscalationTitle) => "Escalade",
+            (Language::French, DriftTitle) => "Dérive d'intention",
+            (Language::French, CostTitle) => "Alerte de coûts",
+            (Language::French, Agent) => "Agent",
+            (Language::French, Task) => "Tâche",
+            (Language::French, Level) => "Niveau",
+            (Language::French, Intent) => "Intention",
+            (Language::French, DriftScore) => "Score de dérive",
+            (Language::French, Progress) => "Progression",
+            (Language::French, Reason) => "Raison",
+            (Language::French, Spend) => "Dépenses",
+            (Language::French, Threshold) => "Seuil",
+            (Language::French, AgentPaused) => "Agent suspendu",
+            (Language::French, Yes) => "Oui",
+            (Language::French, No) => "Non",
+            (Language::French, Approve) => "Approuver",
+            (Language::French, Reject) => "Rejeter",
+            (Language::French, ViewDetails) => "Voir les détails",
+
+            (Language::German, EscalationTitle) => "Eskalation",
+            (Language::German, DriftTitle) => "Intent-Abweichung",
+            (Language::German, CostTitle) => "Kostenwarnung",
+            (Language::German, Agent) => "Agent",
+            (Language::German, Task) => "Aufgabe",
+            (Language::German, Level) => "Stufe",
+            (Language::German, Intent) => "Absicht",
+            (Language::German, DriftScore) => "Abweichungswert",
+            (Language::German, Progress) => "Fortschritt",
+            (Language::German, Reason) => "Grund",
+            (Language::German, Spend) => "Ausgaben",
+            (Language::German, Threshold) => "Schwellenwert",
+            (Language::German, AgentPaused) => "Agent pausiert",
+            (Language::German, Yes) => "Ja",
+            (Language::German, No) => "Nein",
+            (Language::German, Approve) => "Genehmigen",
+            (Language::German, Reject) => "Ablehnen",
+            (Language::German, ViewDetails) => "Details anzeigen",
+
+            (Language::Portuguese, EscalationTitle) => "Escalonamento",
+            (Language::Portuguese, DriftTitle) => "Desvio de intenção",
+            (Language::Portuguese, CostTitle) => "Alerta de custos",
+            (Language::Portuguese, Agent) => "Agente",
+            (Language::Portuguese, Task) => "Tarefa",
+            (Language::Portuguese, Level) => "Nível",
+            (Language::Portuguese, Intent) => "Intenção",
+            (Language::Portuguese, DriftScore) => "Pontuação de desvio",
+            (Language::Portuguese, Progress) => "Progresso",
+            (Language::Portuguese, Reason) => "Motivo",
+            (Language::Portuguese, Spend) => "Gasto",
+            (Language::Portuguese, Threshold) => "Limite",
+            (Language::Portuguese, AgentPaused) => "Agente pausado",
+            (Language::Portuguese, Yes) => "Sim",
+            (Language::Portuguese, No) => "Não",
+            (Language::Portuguese, Approve) => "Aprovar",
+            (Language::Portuguese, Reject) => "Rejeitar",
+            (Language::Portuguese, ViewDetails) => "Ver detalhes",
+
+            (Language::Japanese, EscalationTitle) => "エスカレーション",
+            (Language::Japanese, DriftTitle) => "意図のドリフト",
+            (Language::Japanese, CostTitle) => "コストアラート",
+            (Language::Japanese, Agent) => "エージェント",
+            (Language::Japanese, Task) => "タスク",
+            (Language::Japanese, Level) => "レベル",
+            (Language::Japanese, Intent) => "意図",
+            (Language::Japanese, DriftScore) => "ドリフトスコア",
+            (Language::Japanese, Progress) => "進捗",
+            (Language::Japanese, Reason) => "理由",
+            (Language::Japanese, Spend) => "支出",
+            (Language::Japanese, Threshold) => "しきい値",
+            (Language::Japanese, AgentPaused) => "エージェント一時停止",
+            (Language::Japanese, Yes) => "はい",
+            (Language::Japanese, No) => "いいえ",
+            (Language::Japanese, Approve) => "承認",
+            (Language::Japanese, Reject) => "却下",
+            (Language::Japanese, ViewDetails) => "詳細を表示",
+
+            (Language::Arabic, EscalationTitle) => "تصعيد",
+            (Language::Arabic, DriftTitle) => "انحراف عن الهدف",
+            (Language::Arabic, CostTitle) => "تنبيه التكلفة",
+            (Language::Arabic, Agent) => "الوكيل",
+            (Language::Arabic, Task) => "المهمة",
+            (Language::Arabic, Level) => "المستوى",
+            (Language::Arabic, Intent) => "الهدف",
+            (Language::Arabic, DriftScore) => "درجة الانحراف",
+            (Language::Arabic, Progress) => "التقدم",
+            (Language::Arabic, Reason) => "السبب",
+            (Language::Arabic, Spend) => "الإنفاق",
+            (Language::Arabic, Threshold) => "الحد",
+            (Language::Arabic, AgentPaused) => "تم إيقاف الوكيل",
+            (Language::Arabic, Yes) => "نعم",
+            (Language::Arabic, No) => "لا",
+            (Language::Arabic, Approve) => "موافقة",
+            (Language::Arabic, Reject) => "رفض",
+            (Language::Arabic, ViewDetails) => "عرض التفاصيل",
+
+            (_, EscalationTitle) => "Escalation",
+            (_, DriftTitle) => "Intent Drift",
+            (_, CostTitle) => "Cost Alert",
+            (_, Agent) => "Agent",
+            (_, Task) => "Task",
+            (_, Level) => "Level",
+            (_, Intent) => "Intent",
+            (_, DriftScore) => "Drift score",
+            (_, Progress) => "Progress",
+            (_, Reason) => "Reason",
+            (_, Spend) => "Spend",
+            (_, Threshold) => "Threshold",
+            (_, AgentPaused) => "Agent paused",
+            (_, Yes) => "Yes",
+            (_, No) => "No",
+            (_, Approve) => "Approve",
+            (_, Reject) => "Reject",
+            (_, ViewDetails) => "View Details",
+        }
+    }
+}
+
+/// Builds localized alert cards.
+#[derive(Debug, Clone)]
+pub struct CardTemplates {
+    dashboard_url: String,
+    /// `None` detects the language of each alert
+    language: Option<Language>,
+}
+
+impl CardTemplates {
+    pub fn new() -> Self {
+        Self {
+            dashboard_url: DEFAULT_DASHBOARD_URL.to_string(),
+            language: None,
+        }
+    }
+
+    /// Dashboard that deep links point to.
+    pub fn with_dashboard_url(mut self, url: &str) -> Self {
+        self.dashboard_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Render all cards in one language.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    fn language_for(&self, text: &str) -> Language {
+        self.language.unwrap_or_else(|| Language::detect(text))
+    }
+
+    /// Escalation awaiting approval.
+    pub fn escalation(&self, alert: &TeamsAlert) -> AdaptiveCard {
+        let language = self.language_for(&alert.description);
+        let t = |text: CardText| text.localize(language).to_string();
+        let severity = CardSeverity::from_level(&alert.level);
+        card(
+            language,
+            vec![
+                header(severity, &t(CardText::EscalationTitle)),
+                CardElement::FactSet {
+                    facts: vec![
+                        Fact::new(t(CardText::Agent), &alert.agent_id),
+                        Fact::new(t(CardText::Task), &alert.task_id),
+                        Fact::new(t(CardText::Level), &alert.level),
+                    ],
+                },
+                CardElement::text(&alert.description),
+            ],
+            vec![
+                CardAction::ActionSubmit {
+                    title: t(CardText::Approve),
+                    data: serde_json::json!({"action": "approve", "id": alert.request_id}),
+                },
+                CardAction::ActionSubmit {
+                    title: t(CardText::Reject),
+                    data: serde_json::json!({"action": "reject", "id": alert.request_id}),
+                },
+                CardAction::ActionOpenUrl {
+                    title: t(CardText::ViewDetails),
+                    url: format!("{}/escalations/{}", self.dashboard_url, alert.request_id),
+                },
+            ],
+        )
+    }
+
+    /// Agent drifting from its declared intent.
+    pub fn drift(&self, alert: &DriftAlert) -> AdaptiveCard {
+        let language = self.language_for(&alert.original_intent);
+        let t = |text: CardText| text.localize(language).to_string();
+        let mut facts = vec![
+            Fact::new(t(CardText::Agent), &alert.agent_id),
+            Fact::new(t(CardText::Intent), &alert.original_intent),
+            Fact::new(t(CardText::DriftScore), format!("{}/100", alert.drift_result.score)),
+            Fact::new(t(CardText::Progress), format!("{}/{}", alert.current_step, alert.expected_steps)),
+        ];
+        if let Some(reason) = &alert.drift_result.reason {
+            facts.push(Fact::new(t(CardText::Reason), reason));
+        }
+        card(
+            language,
+            vec![
+                header(alert.severity.into(), &t(CardText::DriftTitle)),
+                CardElement::FactSet { facts },
+            ],
+            vec![CardAction::ActionOpenUrl {
+                title: t(CardText::ViewDetails),
+                url: format!("{}/agents/{}/drift/{}", self.dashboard_url, alert.agent_id, alert.id),
+            }],
+        )
+    }
+
+    /// Spend over a cost threshold.
+    pub fn cost(&self, alert: &CostAlert) -> AdaptiveCard {
+        let language = self.language.unwrap_or(Language::English);
+        let t = |text: CardText| text.localize(language).to_string();
+        let paused = if alert.agent_paused { CardText::Yes } else { CardText::No };
+        card(
+            language,
+            vec![
+                header(alert.level.into(), &t(CardText::CostTitle)),
+                CardElement::FactSet {
+                    facts: vec![
+                        Fact::new(t(CardText::Agent), &alert.agent_id),
+                        Fact::new(t(CardText::Spend), format!("${:.2}", alert.current_usd)),
+                        Fact::new(t(CardText::Threshold), format!("${:.2}", alert.threshold_usd)),
+                        Fact::new(t(CardText::AgentPaused), t(paused)),
+                    ],
+                },
+            ],
+            vec![CardAction::ActionOpenUrl {
+                title: t(CardText::ViewDetails),
+                url: format!("{}/costs/{}?alert={}", self.dashboard_url, alert.agent_id, alert.id),
+            }],
+        )
+    }
+}
+
+impl Default for CardTemplates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn header(severity: CardSeverity, title: &str) -> CardElement {
+    CardElement::Container {
+        style: Some(severity.style().into()),
+        bleed: true,
+        items: vec![CardElement::TextBlock {
+            text: format!("{} {}", severity.icon(), title),
+            size: "Large".into(),
+            weight: "Bolder".into(),
+            color: Some(severity.color().into()),
+            wrap: true,
+        }],
+    }
+}
+
+fn card(language: Language, body: Vec<CardElement>, actions: Vec<CardAction>) -> AdaptiveCard {
+    AdaptiveCard {
+        rtl: (language == Language::Arabic).then_some(true),
+        ..AdaptiveCard::new(body, actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_synapse::drift::DriftResult;
+
+    #[test]
+    fn test_localized_drift_and_cost_cards() {
+        let templates = CardTemplates::new().with_dashboard_url("https://ops.example.com/");
+        let drift = DriftAlert {
+            id: "d-1".into(),
+            agent_id: "agent-1".into(),
+            path_id: "p-1".into(),
+            original_intent: "請求書を処理する".into(),
+            drift_result: DriftResult { drifted: true, score: 85, reason: None },
+            severity: AlertSeverity::Critical,
+            timestamp: chrono::Utc::now(),
+            current_step: 7,
+            expected_steps: 4,
+        };
+        let json = serde_json::to_value(templates.drift(&drift)).unwrap();
+        assert_eq!(json["body"][0]["type"], "Container");
+        assert_eq!(json["body"][0]["style"], "attention");
+        assert_eq!(json["body"][0]["items"][0]["text"], "🚨 意図のドリフト");
+        assert_eq!(json["actions"][0]["type"], "Action.OpenUrl");
+        assert_eq!(json["actions"][0]["url"], "https://ops.example.com/agents/agent-1/drift/d-1");
+
+        let cost = CostAlert {
+            id: "c-1".into(),
+            threshold_id: "t-1".into(),
+            agent_id: "agent-1".into(),
+            current_usd: 120.5,
+            threshold_usd: 100.0,
+            level: AlertLevel::Warning,
+            timestamp: 0,
+            agent_paused: false,
+        };
+        let json = serde_json::to_value(templates.with_language(Language::Arabic).cost(&cost)).unwrap();
+        assert_eq!(json["rtl"], true);
+        assert_eq!(json["body"][0]["style"], "warning");
+        assert_eq!(json["body"][1]["facts"][1], serde_json::json!({"title": "الإنفاق", "value": "$120.50"}));
+    }
+}
@@ -5,9 +5,21 @@
 
 pub mod slack;
 pub mod teams;
+pub mod cards;
 pub mod pagerduty;
+pub mod runbook;
+pub mod socket_mode;
 
 // Re-exports
 pub use slack::{SlackIntegration, SlackConfig};
-pub use teams::{TeamsIntegration, TeamsConfig};
-pub use pagerduty::{PagerDutyIntegration, PagerDutyConfig};
+pub use teams::{TeamsIntegration, TeamsConfig, TeamsAlert, AdaptiveCard};
+pub use cards::{CardTemplates, CardSeverity, CardText};
+pub use pagerduty::{
+    PagerDutyIntegration, PagerDutyConfig, PagerDutyEvent, ChangeEvent, IncidentStatus,
+    EventsTransport, HttpEventsTransport,
+};
+pub use runbook::{
+    RunbookAction, RunbookKind, RunbookDispatcher, RunbookExecutor, RunbookOutcome, RunbookDecision,
+    RunbookError, HumanActor,
+};
+pub use socket_mode::{SocketModeClient, SocketConnector, SocketConnection, SocketEvent, RunbookRequest};
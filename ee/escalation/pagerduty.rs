@@ -1,8 +1,32 @@
 //! PagerDuty Integration
 //!
-//! Native PagerDuty integration with Events API v2
+//! Native PagerDuty integration with Events API v2:
+//! - Trigger, acknowledge and resolve alerts by dedup key
+//! - Change events for deploys and config changes
+//! - Alerts raised for an escalation resolve when the escalation clears
+//! - Events over the routing key's rate limit, or sent while PagerDuty is
+//!   unreachable, wait in a local queue and go out in order on `flush`
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let pagerduty = PagerDutyIntegration::new(PagerDutyConfig::new(routing_key, "PSVC123"))?;
+//! pagerduty.escalate(&escalation.request_id.to_string(), &event)?;
+//!
+//! // Reviewer approved or denied the call
+//! pagerduty.escalation_cleared(&escalation.request_id.to_string())?;
+//!
+//! // Periodically, to drain events queued during an outage
+//! pagerduty.flush()?;
+//! ```
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const DEFAULT_EVENTS_URL: &str = "https://events.pagerduty.com";
 
 /// PagerDuty configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +37,41 @@ pub struct PagerDutyConfig {
     pub service_id: String,
     /// Default severity
     pub default_severity: PagerDutySeverity,
+    /// Events API base URL
+    #[serde(default = "default_events_url")]
+    pub events_url: String,
+    /// Events sent per minute before queueing locally
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit_per_minute: u32,
+    /// Events held while PagerDuty is unreachable
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_events_url() -> String {
+    DEFAULT_EVENTS_URL.to_string()
+}
+
+fn default_rate_limit() -> u32 {
+    // Events API v2 allows 120 events per minute per routing key
+    120
+}
+
+fn default_queue_capacity() -> usize {
+    1000
+}
+
+impl PagerDutyConfig {
+    pub fn new(routing_key: impl Into<String>, service_id: impl Into<String>) -> Self {
+        Self {
+            routing_key: routing_key.into(),
+            service_id: service_id.into(),
+            default_severity: PagerDutySeverity::Error,
+            events_url: default_events_url(),
+            rate_limit_per_minute: default_rate_limit(),
+            queue_capacity: default_queue_capacity(),
+        }
+    }
 }
 
 /// PagerDuty severity levels.
@@ -35,34 +94,187 @@ impl PagerDutySeverity {
     }
 }
 
+/// Alert state as last sent for a dedup key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentStatus {
+    Triggered,
+    Acknowledged,
+    Resolved,
+}
+
+/// Sends Events API requests.
+pub trait EventsTransport: Send + Sync {
+    /// POST `body` to `path` (`/v2/enqueue` or `/v2/change/enqueue`).
+    fn post(&self, path: &str, body: &serde_json::Value) -> Result<PagerDutyResponse, PagerDutyError>;
+}
+
+/// HTTPS transport for the Events API.
+pub struct HttpEventsTransport {
+    base_url: String,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+}
+
+impl HttpEventsTransport {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: OnceLock::new(),
+        }
+    }
+
+    fn http(&self) -> Result<&reqwest::blocking::Client, PagerDutyError> {
+        self.http
+            .get_or_init(|| reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| PagerDutyError::Unavailable(e.clone()))
+    }
+}
+
+impl EventsTransport for HttpEventsTransport {
+    fn post(&self, path: &str, body: &serde_json::Value) -> Result<PagerDutyResponse, PagerDutyError> {
+        let response = self.http()?
+            .post(format!("{}{}", self.base_url, path))
+            .json(body)
+            .send()
+            .map_err(|e| PagerDutyError::Unavailable(e.to_string()))?;
+
+        let status = response.status();
+        let text = response.text().unwrap_or_default();
+        match status.as_u16() {
+            200..=299 => serde_json::from_str(&text)
+                .map_err(|e| PagerDutyError::ApiError(format!("unexpected response: {}", e))),
+            400 if text.contains("routing_key") => Err(PagerDutyError::InvalidRoutingKey),
+            400 => Err(PagerDutyError::InvalidEvent(text)),
+            429 => Err(PagerDutyError::RateLimited),
+            500..=599 => Err(PagerDutyError::Unavailable(format!("HTTP {}", status))),
+            _ => Err(PagerDutyError::ApiError(format!("HTTP {}: {}", status, text))),
+        }
+    }
+}
+
+/// Event waiting to be sent.
+#[derive(Debug, Clone)]
+struct QueuedEvent {
+    path: &'static str,
+    body: serde_json::Value,
+}
+
+#[derive(Default)]
+struct DeliveryState {
+    queue: VecDeque<QueuedEvent>,
+    tokens: f64,
+    refilled: Option<Instant>,
+    incidents: HashMap<String, IncidentStatus>,
+    /// Escalation ID to the dedup key of its alert
+    escalations: HashMap<String, String>,
+}
+
 /// PagerDuty integration.
 pub struct PagerDutyIntegration {
     config: PagerDutyConfig,
+    transport: Arc<dyn EventsTransport>,
+    state: Mutex<DeliveryState>,
 }
 
 impl PagerDutyIntegration {
     /// Create new PagerDuty integration.
     pub fn new(config: PagerDutyConfig) -> Result<Self, PagerDutyError> {
         crate::connectors::license::check_feature_license("pagerduty")?;
-        Ok(Self { config })
+        if config.routing_key.trim().is_empty() {
+            return Err(PagerDutyError::InvalidRoutingKey);
+        }
+        let transport = Arc::new(HttpEventsTransport::new(&config.events_url));
+        Ok(Self { config, transport, state: Mutex::new(DeliveryState::default()) })
+    }
+
+    /// Replace the HTTPS transport.
+    pub fn with_transport(mut self, transport: Arc<dyn EventsTransport>) -> Self {
+        self.transport = transport;
+        self
     }
     
     /// Trigger incident.
     pub fn trigger(&self, event: &PagerDutyEvent) -> Result<PagerDutyResponse, PagerDutyError> {
         let payload = self.build_trigger_payload(event);
-        self.send_event(&payload)
+        self.send_event(&event.dedup_key, IncidentStatus::Triggered, payload)
     }
     
     /// Acknowledge incident.
     pub fn acknowledge(&self, dedup_key: &str) -> Result<PagerDutyResponse, PagerDutyError> {
         let payload = self.build_ack_payload(dedup_key);
-        self.send_event(&payload)
+        self.send_event(dedup_key, IncidentStatus::Acknowledged, payload)
     }
     
     /// Resolve incident.
     pub fn resolve(&self, dedup_key: &str) -> Result<PagerDutyResponse, PagerDutyError> {
         let payload = self.build_resolve_payload(dedup_key);
-        self.send_event(&payload)
+        self.send_event(dedup_key, IncidentStatus::Resolved, payload)
+    }
+
+    /// Send a change event; change events never open incidents.
+    pub fn change(&self, event: &ChangeEvent) -> Result<PagerDutyResponse, PagerDutyError> {
+        let payload = self.build_change_payload(event);
+        self.dispatch(QueuedEvent { path: "/v2/change/enqueue", body: payload }, String::new())
+    }
+
+    /// Trigger an alert for an escalation, resolved by [`Self::escalation_cleared`].
+    pub fn escalate(&self, escalation_id: &str, event: &PagerDutyEvent) -> Result<PagerDutyResponse, PagerDutyError> {
+        let response = self.trigger(event)?;
+        self.state.lock().unwrap().escalations.insert(escalation_id.to_string(), event.dedup_key.clone());
+        Ok(response)
+    }
+
+    /// Resolve the alert of a cleared escalation, if it is still open.
+    pub fn escalation_cleared(&self, escalation_id: &str) -> Result<Option<PagerDutyResponse>, PagerDutyError> {
+        let dedup_key = {
+            let mut state = self.state.lock().unwrap();
+            match state.escalations.remove(escalation_id) {
+                Some(key) if state.incidents.get(&key) != Some(&IncidentStatus::Resolved) => key,
+                _ => return Ok(None),
+            }
+        };
+        self.resolve(&dedup_key).map(Some)
+    }
+
+    /// Last state sent for a dedup key.
+    pub fn incident_status(&self, dedup_key: &str) -> Option<IncidentStatus> {
+        self.state.lock().unwrap().incidents.get(dedup_key).copied()
+    }
+
+    /// Events waiting in the local queue.
+    pub fn queued(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+
+    /// Send queued events in order, stopping at the first that still fails.
+    ///
+    /// Returns how many were delivered.
+    pub fn flush(&self) -> Result<usize, PagerDutyError> {
+        let mut sent = 0;
+        loop {
+            let next = {
+                let mut state = self.state.lock().unwrap();
+                if state.queue.is_empty() || !self.take_token(&mut state) {
+                    return Ok(sent);
+                }
+                state.queue[0].clone()
+            };
+            match self.transport.post(next.path, &next.body) {
+                Ok(_) => {}
+                Err(e) if e.is_transient() => return Ok(sent),
+                // PagerDuty will never accept it; drop it rather than block the queue
+                Err(e) => {
+                    self.state.lock().unwrap().queue.pop_front();
+                    return Err(e);
+                }
+            }
+            self.state.lock().unwrap().queue.pop_front();
+            sent += 1;
+        }
     }
     
     fn build_trigger_payload(&self, event: &PagerDutyEvent) -> serde_json::Value {
@@ -100,18 +312,87 @@ impl PagerDutyIntegration {
             "dedup_key": dedup_key
         })
     }
+
+    fn build_change_payload(&self, event: &ChangeEvent) -> serde_json::Value {
+        serde_json::json!({
+            "routing_key": self.config.routing_key,
+            "payload": {
+                "summary": event.summary,
+                "source": event.source,
+                "timestamp": event.timestamp.unwrap_or_else(Utc::now).to_rfc3339(),
+                "custom_details": event.custom_details
+            },
+            "links": event.links.iter().map(|(text, url)| {
+                serde_json::json!({"text": text, "href": url})
+            }).collect::<Vec<_>>()
+        })
+    }
     
-    fn send_event(&self, payload: &serde_json::Value) -> Result<PagerDutyResponse, PagerDutyError> {
-        // Would POST to https://events.pagerduty.com/v2/enqueue
+    fn send_event(
+        &self,
+        dedup_key: &str,
+        status: IncidentStatus,
+        payload: serde_json::Value,
+    ) -> Result<PagerDutyResponse, PagerDutyError> {
+        let response = self.dispatch(QueuedEvent { path: "/v2/enqueue", body: payload }, dedup_key.to_string())?;
+        self.state.lock().unwrap().incidents.insert(dedup_key.to_string(), status);
+        Ok(response)
+    }
+
+    /// Send now, or queue behind earlier events when over the rate limit
+    /// or PagerDuty is unavailable.
+    fn dispatch(&self, event: QueuedEvent, dedup_key: String) -> Result<PagerDutyResponse, PagerDutyError> {
+        // Keep order: nothing jumps events already waiting. A queued event
+        // PagerDuty rejected is dropped by `flush` and not this event's error.
+        let _ = self.flush();
+        {
+            let mut state = self.state.lock().unwrap();
+            if !state.queue.is_empty() || !self.take_token(&mut state) {
+                return self.enqueue(&mut state, event, dedup_key);
+            }
+        }
+        match self.transport.post(event.path, &event.body) {
+            Ok(response) => Ok(response),
+            Err(e) if e.is_transient() => {
+                let mut state = self.state.lock().unwrap();
+                self.enqueue(&mut state, event, dedup_key)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn enqueue(
+        &self,
+        state: &mut DeliveryState,
+        event: QueuedEvent,
+        dedup_key: String,
+    ) -> Result<PagerDutyResponse, PagerDutyError> {
+        if state.queue.len() >= self.config.queue_capacity {
+            return Err(PagerDutyError::QueueFull);
+        }
+        state.queue.push_back(event);
         Ok(PagerDutyResponse {
-            status: "success".into(),
-            message: "Event processed".into(),
-            dedup_key: payload.get("dedup_key")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
+            status: "queued".into(),
+            message: "Event queued locally".into(),
+            dedup_key,
         })
     }
+
+    fn take_token(&self, state: &mut DeliveryState) -> bool {
+        let capacity = self.config.rate_limit_per_minute.max(1) as f64;
+        let now = Instant::now();
+        state.tokens = match state.refilled {
+            Some(at) => (state.tokens + now.duration_since(at).as_secs_f64() * capacity / 60.0).min(capacity),
+            None => capacity,
+        };
+        state.refilled = Some(now);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 /// PagerDuty event.
@@ -137,11 +418,24 @@ pub struct PagerDutyEvent {
     pub links: Vec<(String, String)>,
 }
 
+/// PagerDuty change event, e.g. a deploy or policy change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub summary: String,
+    pub source: Option<String>,
+    /// When the change happened; defaults to now
+    pub timestamp: Option<DateTime<Utc>>,
+    pub custom_details: serde_json::Value,
+    pub links: Vec<(String, String)>,
+}
+
 /// PagerDuty response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PagerDutyResponse {
     pub status: String,
     pub message: String,
+    /// Absent for change events
+    #[serde(default)]
     pub dedup_key: String,
 }
 
@@ -156,11 +450,27 @@ pub enum PagerDutyError {
     
     #[error("Rate limited")]
     RateLimited,
+
+    #[error("Invalid event: {0}")]
+    InvalidEvent(String),
+
+    #[error("PagerDuty unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Local event queue full")]
+    QueueFull,
     
     #[error("License error: {0}")]
     LicenseError(#[from] crate::connectors::license::LicenseError),
 }
 
+impl PagerDutyError {
+    /// Worth retrying later from the local queue.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::RateLimited | Self::Unavailable(_))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,4 +497,69 @@ mod tests {
         
         assert_eq!(event.severity, PagerDutySeverity::Critical);
     }
+
+    #[derive(Default)]
+    struct FakeTransport {
+        down: std::sync::atomic::AtomicBool,
+        sent: Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    impl EventsTransport for FakeTransport {
+        fn post(&self, path: &str, body: &serde_json::Value) -> Result<PagerDutyResponse, PagerDutyError> {
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(PagerDutyError::Unavailable("connection refused".into()));
+            }
+            self.sent.lock().unwrap().push((path.to_string(), body.clone()));
+            Ok(PagerDutyResponse {
+                status: "success".into(),
+                message: "Event processed".into(),
+                dedup_key: body["dedup_key"].as_str().unwrap_or("").to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_escalation_lifecycle_with_outage_queue() {
+        let transport = Arc::new(FakeTransport::default());
+        // Built directly: license checks read the process environment
+        let pagerduty = PagerDutyIntegration {
+            config: PagerDutyConfig::new("R0UT1NGKEY", "PSVC1"),
+            transport: transport.clone(),
+            state: Mutex::new(DeliveryState::default()),
+        };
+        let event = PagerDutyEvent {
+            dedup_key: "agentkern-esc-1".into(),
+            summary: "Agent wire transfer held for review".into(),
+            severity: PagerDutySeverity::Critical,
+            source: "AgentKern".into(),
+            component: None,
+            group: None,
+            class: None,
+            custom_details: serde_json::json!({}),
+            links: vec![],
+        };
+
+        pagerduty.escalate("esc-1", &event).unwrap();
+        assert_eq!(pagerduty.incident_status("agentkern-esc-1"), Some(IncidentStatus::Triggered));
+
+        // Outage: the resolve and a change event wait locally
+        transport.down.store(true, std::sync::atomic::Ordering::SeqCst);
+        let resolved = pagerduty.escalation_cleared("esc-1").unwrap().unwrap();
+        assert_eq!(resolved.status, "queued");
+        pagerduty.change(&ChangeEvent {
+            summary: "Policy bundle v42 deployed".into(),
+            source: None,
+            timestamp: None,
+            custom_details: serde_json::json!({}),
+            links: vec![],
+        }).unwrap();
+        assert_eq!(pagerduty.queued(), 2);
+        assert!(pagerduty.escalation_cleared("esc-1").unwrap().is_none());
+
+        transport.down.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(pagerduty.flush().unwrap(), 2);
+        let sent = transport.sent.lock().unwrap();
+        assert_eq!(sent[1].1["event_action"], "resolve");
+        assert_eq!(sent[2].0, "/v2/change/enqueue");
+    }
 }
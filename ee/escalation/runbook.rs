@@ -0,0 +1,367 @@
+//! Runbook Actions
+//!
+//! Remediations on-call engineers may run from chat. Only the actions in
+//! [`RunbookAction`] exist, and a dispatcher runs only those it was built
+//! with. Every request is:
+//! - Verified by the Gate engine as `"runbook.{action}"`, with the target
+//!   and the human actor in the policy context
+//! - Refused when denied, or held when its risk calls for review
+//! - Recorded in the audit ledger under the human actor
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Policy rule: action == 'runbook.trigger_failover' && context.actor_id != 'U024BE7LH' => Deny
+//! let dispatcher = RunbookDispatcher::new(engine, ledger, Arc::new(OpsExecutor::new()))
+//!     .with_action(RunbookKind::PauseAgent)
+//!     .with_action(RunbookKind::TriggerFailover);
+//!
+//! let action = RunbookAction::parse("pause-agent treasury-agent")?;
+//! let outcome = dispatcher.dispatch(&action, &actor).await?;
+//! ```
+
+use agentkern_arbiter::{AuditLedger, AuditOutcome, AuditRecord};
+use agentkern_gate::engine::VerificationRequestBuilder;
+use agentkern_gate::{GateEngine, VerificationRequest};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Kind of runbook action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunbookKind {
+    PauseAgent,
+    OpenCircuit,
+    TriggerFailover,
+}
+
+impl RunbookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PauseAgent => "pause_agent",
+            Self::OpenCircuit => "open_circuit",
+            Self::TriggerFailover => "trigger_failover",
+        }
+    }
+
+    /// Command word, e.g. `pause-agent`.
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::PauseAgent => "pause-agent",
+            Self::OpenCircuit => "open-circuit",
+            Self::TriggerFailover => "failover",
+        }
+    }
+}
+
+/// A whitelisted remediation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunbookAction {
+    /// Stop an agent from taking further actions
+    PauseAgent { agent_id: String },
+    /// Force a circuit breaker open
+    OpenCircuit { circuit: String },
+    /// Move a service to another region
+    TriggerFailover { service: String, region: String },
+}
+
+impl RunbookAction {
+    /// Parse command text: `pause-agent <agent>`, `open-circuit <circuit>`
+    /// or `failover <service> <region>`.
+    pub fn parse(text: &str) -> Result<Self, RunbookError> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["pause-agent", agent_id] => Ok(Self::PauseAgent { agent_id: agent_id.to_string() }),
+            ["open-circuit", circuit] => Ok(Self::OpenCircuit { circuit: circuit.to_string() }),
+            ["failover", service, region] => Ok(Self::TriggerFailover {
+                service: service.to_string(),
+                region: region.to_string(),
+            }),
+            _ => Err(RunbookError::UnknownCommand(text.trim().to_string())),
+        }
+    }
+
+    pub fn kind(&self) -> RunbookKind {
+        match self {
+            Self::PauseAgent { .. } => RunbookKind::PauseAgent,
+            Self::OpenCircuit { .. } => RunbookKind::OpenCircuit,
+            Self::TriggerFailover { .. } => RunbookKind::TriggerFailover,
+        }
+    }
+
+    /// Action name policies match on, e.g. `runbook.pause_agent`.
+    pub fn name(&self) -> String {
+        format!("runbook.{}", self.kind().as_str())
+    }
+
+    /// Agent, circuit or service acted on.
+    pub fn target(&self) -> &str {
+        match self {
+            Self::PauseAgent { agent_id } => agent_id,
+            Self::OpenCircuit { circuit } => circuit,
+            Self::TriggerFailover { service, .. } => service,
+        }
+    }
+
+    /// Command text [`parse`](Self::parse) accepts.
+    pub fn to_command(&self) -> String {
+        match self {
+            Self::TriggerFailover { service, region } => format!("failover {} {}", service, region),
+            _ => format!("{} {}", self.kind().command(), self.target()),
+        }
+    }
+
+    /// Verification request; the actor is available as `context.actor_id`.
+    pub fn to_request(&self, agent_id: &str, actor: &HumanActor) -> VerificationRequest {
+        let mut builder = VerificationRequestBuilder::new(agent_id, self.name())
+            .context("target", self.target())
+            .context("actor_id", actor.user_id.as_str())
+            .context("actor_name", actor.user_name.as_str())
+            .context("source", actor.source.as_str());
+        if let Self::TriggerFailover { region, .. } = self {
+            builder = builder.context("region", region.as_str());
+        }
+        builder.build()
+    }
+}
+
+/// Person who requested an action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HumanActor {
+    /// Chat user ID, e.g. Slack `U024BE7LH`
+    pub user_id: String,
+    pub user_name: String,
+    /// Workspace or tenant
+    pub team_id: Option<String>,
+    /// Channel the request came from
+    pub channel_id: Option<String>,
+    /// `slack`, `teams`, ...
+    pub source: String,
+}
+
+impl HumanActor {
+    pub fn slack(user_id: &str, user_name: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            user_name: user_name.to_string(),
+            team_id: None,
+            channel_id: None,
+            source: "slack".into(),
+        }
+    }
+
+    /// Audit ledger identity, e.g. `slack:U024BE7LH`.
+    pub fn audit_id(&self) -> String {
+        format!("{}:{}", self.source, self.user_id)
+    }
+}
+
+/// Carries out verified actions against the platform.
+#[async_trait]
+pub trait RunbookExecutor: Send + Sync {
+    /// Run the action; the message is shown to the actor.
+    async fn execute(&self, action: &RunbookAction, actor: &HumanActor) -> Result<String, String>;
+}
+
+/// What happened to a requested action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunbookDecision {
+    Executed,
+    Denied,
+    /// Risk too high to run from chat
+    Review,
+    /// Allowed, but the executor failed
+    Failed,
+}
+
+/// Result of a dispatched action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunbookOutcome {
+    pub request_id: Uuid,
+    pub action: RunbookAction,
+    pub decision: RunbookDecision,
+    pub risk_score: u8,
+    pub message: String,
+}
+
+impl RunbookOutcome {
+    /// Reply text for the actor.
+    pub fn to_text(&self) -> String {
+        let icon = match self.decision {
+            RunbookDecision::Executed => ":white_check_mark:",
+            RunbookDecision::Denied => ":no_entry:",
+            RunbookDecision::Review => ":hourglass:",
+            RunbookDecision::Failed => ":x:",
+        };
+        format!("{} `{}`: {}", icon, self.action.to_command(), self.message)
+    }
+}
+
+/// Verifies, runs and audits runbook actions.
+pub struct RunbookDispatcher {
+    engine: Arc<GateEngine>,
+    ledger: Arc<AuditLedger>,
+    executor: Arc<dyn RunbookExecutor>,
+    allowed: HashSet<RunbookKind>,
+    agent_id: String,
+    review_threshold: u8,
+}
+
+impl RunbookDispatcher {
+    /// Dispatcher with no actions enabled.
+    pub fn new(engine: Arc<GateEngine>, ledger: Arc<AuditLedger>, executor: Arc<dyn RunbookExecutor>) -> Self {
+        Self {
+            engine,
+            ledger,
+            executor,
+            allowed: HashSet::new(),
+            agent_id: "runbook".into(),
+            review_threshold: 60,
+        }
+    }
+
+    /// Enable an action.
+    pub fn with_action(mut self, kind: RunbookKind) -> Self {
+        self.allowed.insert(kind);
+        self
+    }
+
+    /// Agent ID requests are verified as (default `runbook`).
+    pub fn with_agent_id(mut self, agent_id: &str) -> Self {
+        self.agent_id = agent_id.to_string();
+        self
+    }
+
+    /// Risk score from which actions are held for review (default 60).
+    pub fn with_review_threshold(mut self, threshold: u8) -> Self {
+        self.review_threshold = threshold;
+        self
+    }
+
+    pub fn is_allowed(&self, kind: RunbookKind) -> bool {
+        self.allowed.contains(&kind)
+    }
+
+    /// Verify and, if allowed, run an action on behalf of `actor`.
+    pub async fn dispatch(&self, action: &RunbookAction, actor: &HumanActor) -> Result<RunbookOutcome, RunbookError> {
+        if !self.is_allowed(action.kind()) {
+            return Err(RunbookError::NotAllowed(action.kind().as_str().to_string()));
+        }
+
+        let result = self.engine.verify(action.to_request(&self.agent_id, actor)).await;
+        let (decision, message) = if !result.allowed {
+            (RunbookDecision::Denied, result.reasoning.clone())
+        } else if result.final_risk_score >= self.review_threshold {
+            (RunbookDecision::Review, format!("held for review (risk {})", result.final_risk_score))
+        } else {
+            match self.executor.execute(action, actor).await {
+                Ok(message) => (RunbookDecision::Executed, message),
+                Err(message) => (RunbookDecision::Failed, message),
+            }
+        };
+
+        let outcome = match decision {
+            RunbookDecision::Executed | RunbookDecision::Failed => AuditOutcome::Allowed,
+            RunbookDecision::Denied => AuditOutcome::Denied,
+            RunbookDecision::Review => AuditOutcome::Review,
+        };
+        let policy_id = match (result.blocking_policies.is_empty(), result.evaluated_policies.is_empty()) {
+            (false, _) => result.blocking_policies.join(","),
+            (true, false) => result.evaluated_policies.join(","),
+            (true, true) => "none".to_string(),
+        };
+        self.ledger.record(
+            AuditRecord::new(actor.audit_id(), action.name(), policy_id, result.final_risk_score, outcome)
+                .with_reasoning(message.clone())
+                .with_metadata(serde_json::json!({
+                    "request_id": result.request_id,
+                    "action": action,
+                    "decision": decision,
+                    "actor": actor,
+                })),
+        ).await;
+
+        Ok(RunbookOutcome {
+            request_id: result.request_id,
+            action: action.clone(),
+            decision,
+            risk_score: result.final_risk_score,
+            message,
+        })
+    }
+}
+
+/// Runbook errors.
+#[derive(Debug, thiserror::Error)]
+pub enum RunbookError {
+    #[error("Unknown runbook command: {0}")]
+    UnknownCommand(String),
+
+    #[error("Runbook action not enabled: {0}")]
+    NotAllowed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_gate::{Policy, PolicyAction, PolicyRule};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingExecutor(Mutex<Vec<RunbookAction>>);
+
+    #[async_trait]
+    impl RunbookExecutor for RecordingExecutor {
+        async fn execute(&self, action: &RunbookAction, _actor: &HumanActor) -> Result<String, String> {
+            self.0.lock().unwrap().push(action.clone());
+            Ok(format!("{} done", action.target()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_verifies_and_audits_actor() {
+        let engine = Arc::new(GateEngine::new().with_neural_threshold(100));
+        engine.register_policy(Policy {
+            id: "failover-owners".into(),
+            name: "failover-owners".into(),
+            description: String::new(),
+            priority: 100,
+            enabled: true,
+            jurisdictions: vec![],
+            rules: vec![PolicyRule {
+                id: "failover-owners-rule".into(),
+                condition: "action == 'runbook.trigger_failover' && context.actor_id != 'U0OWNER'".into(),
+                action: PolicyAction::Deny,
+                message: None,
+                risk_score: None,
+            }],
+        }).await;
+        let ledger = Arc::new(AuditLedger::new());
+        let executor = Arc::new(RecordingExecutor::default());
+        let dispatcher = RunbookDispatcher::new(engine, ledger.clone(), executor.clone())
+            .with_action(RunbookKind::PauseAgent)
+            .with_action(RunbookKind::TriggerFailover);
+        let actor = HumanActor::slack("U0ONCALL", "alice");
+
+        let pause = RunbookAction::parse("pause-agent treasury-agent").unwrap();
+        assert_eq!(dispatcher.dispatch(&pause, &actor).await.unwrap().decision, RunbookDecision::Executed);
+
+        let failover = RunbookAction::parse("failover gate eu-west-1").unwrap();
+        assert_eq!(dispatcher.dispatch(&failover, &actor).await.unwrap().decision, RunbookDecision::Denied);
+
+        let circuit = RunbookAction::parse("open-circuit sap").unwrap();
+        assert!(matches!(dispatcher.dispatch(&circuit, &actor).await, Err(RunbookError::NotAllowed(_))));
+        assert!(RunbookAction::parse("rm -rf /").is_err());
+
+        assert_eq!(*executor.0.lock().unwrap(), vec![pause]);
+        let records = ledger.query_by_agent("slack:U0ONCALL").await;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].outcome, AuditOutcome::Denied);
+        assert_eq!(records[1].metadata["actor"]["user_name"], "alice");
+    }
+}
@@ -1,7 +1,10 @@
 //! Slack Integration
 //!
-//! Native Slack integration with Block Kit and modal workflows
+//! Native Slack integration with Block Kit and modal workflows.
+//! Runbook commands and buttons arrive over Socket Mode, see
+//! [`super::socket_mode`].
 
+use super::runbook::RunbookAction;
 use serde::{Deserialize, Serialize};
 
 /// Slack configuration.
@@ -50,7 +53,7 @@ impl SlackIntegration {
     fn build_escalation_blocks(&self, escalation: &EscalationAlert) -> Vec<SlackBlock> {
         vec![
             SlackBlock::Header {
-                text: format!(":warning: {} Escalation", escalation.level),
+                text: format!(":warning: {:?} Escalation", escalation.level),
             },
             SlackBlock::Section {
                 text: escalation.description.clone(),
@@ -66,16 +69,26 @@ impl SlackIntegration {
                         text: "Approve".into(),
                         action_id: format!("approve_{}", escalation.request_id),
                         style: Some("primary".into()),
+                        value: None,
                     },
                     SlackElement::Button {
                         text: "Reject".into(),
                         action_id: format!("reject_{}", escalation.request_id),
                         style: Some("danger".into()),
+                        value: None,
                     },
                     SlackElement::Button {
                         text: "View Details".into(),
                         action_id: format!("details_{}", escalation.request_id),
                         style: None,
+                        value: None,
+                    },
+                    // Handled by the socket mode runbook dispatcher
+                    SlackElement::Button {
+                        text: "Pause Agent".into(),
+                        action_id: super::socket_mode::RUNBOOK_ACTION_ID.into(),
+                        style: Some("danger".into()),
+                        value: Some(RunbookAction::PauseAgent { agent_id: escalation.agent_id.clone() }.to_command()),
                     },
                 ],
             },
@@ -181,7 +194,7 @@ pub enum SlackBlock {
 /// Slack Block Kit element.
 #[derive(Debug, Clone, Serialize)]
 pub enum SlackElement {
-    Button { text: String, action_id: String, style: Option<String> },
+    Button { text: String, action_id: String, style: Option<String>, value: Option<String> },
 }
 
 /// Slack view (modal).
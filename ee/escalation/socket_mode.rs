@@ -0,0 +1,418 @@
+//! Slack Socket Mode
+//!
+//! Receives slash commands and button clicks over a Socket Mode WebSocket,
+//! so no public request URL is needed. Runbook requests:
+//! - `/agentkern pause-agent <agent>`, `/agentkern open-circuit <circuit>`,
+//!   `/agentkern failover <service> <region>`
+//! - Buttons with `action_id` `runbook` and the same command as `value`
+//!
+//! Each envelope is acknowledged right away; the request then goes through
+//! the [`RunbookDispatcher`] and the outcome is posted to the channel via
+//! the request's `response_url`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let client = Arc::new(SocketModeClient::new(&slack_config, Arc::new(dispatcher))?);
+//! tokio::spawn(client.run());
+//! ```
+
+use super::runbook::{HumanActor, RunbookAction, RunbookDispatcher, RunbookKind};
+use super::slack::{SlackConfig, SlackError};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONNECTIONS_OPEN_URL: &str = "https://slack.com/api/apps.connections.open";
+
+/// Action ID of runbook buttons.
+pub const RUNBOOK_ACTION_ID: &str = "runbook";
+
+/// Opens Socket Mode WebSocket connections.
+#[async_trait]
+pub trait SocketConnector: Send + Sync {
+    async fn connect(&self, url: &str) -> Result<Box<dyn SocketConnection>, SlackError>;
+}
+
+/// An open Socket Mode connection.
+#[async_trait]
+pub trait SocketConnection: Send {
+    /// Next text frame; `None` once the connection is closed.
+    async fn recv(&mut self) -> Option<Result<String, SlackError>>;
+
+    async fn send(&mut self, text: String) -> Result<(), SlackError>;
+}
+
+/// WebSocket connector using `tokio-tungstenite`.
+pub struct TungsteniteConnector;
+
+struct TungsteniteConnection(
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+);
+
+#[async_trait]
+impl SocketConnector for TungsteniteConnector {
+    async fn connect(&self, url: &str) -> Result<Box<dyn SocketConnection>, SlackError> {
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| SlackError::ApiError(format!("socket connect failed: {}", e)))?;
+        Ok(Box::new(TungsteniteConnection(stream)))
+    }
+}
+
+#[async_trait]
+impl SocketConnection for TungsteniteConnection {
+    async fn recv(&mut self) -> Option<Result<String, SlackError>> {
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Pings are answered by tungstenite itself
+        loop {
+            match self.0.next().await? {
+                Ok(Message::Text(text)) => return Some(Ok(text)),
+                Ok(Message::Close(_)) => return None,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(SlackError::ApiError(e.to_string()))),
+            }
+        }
+    }
+
+    async fn send(&mut self, text: String) -> Result<(), SlackError> {
+        use futures_util::SinkExt;
+
+        self.0
+            .send(tokio_tungstenite::tungstenite::Message::Text(text))
+            .await
+            .map_err(|e| SlackError::ApiError(e.to_string()))
+    }
+}
+
+/// Socket Mode frame.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    kind: String,
+    envelope_id: Option<String>,
+    #[serde(default)]
+    payload: serde_json::Value,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlashCommandPayload {
+    command: String,
+    #[serde(default)]
+    text: String,
+    user_id: String,
+    #[serde(default)]
+    user_name: String,
+    team_id: Option<String>,
+    channel_id: Option<String>,
+    response_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockActionsPayload {
+    #[serde(rename = "type")]
+    kind: String,
+    user: BlockActionsUser,
+    channel: Option<BlockActionsChannel>,
+    #[serde(default)]
+    actions: Vec<BlockAction>,
+    response_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockActionsUser {
+    id: String,
+    #[serde(default)]
+    username: String,
+    team_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockActionsChannel {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockAction {
+    action_id: String,
+    value: Option<String>,
+}
+
+/// Runbook command received over Socket Mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunbookRequest {
+    /// Command text, e.g. `pause-agent treasury-agent`
+    pub text: String,
+    pub actor: HumanActor,
+    /// Where to post the outcome
+    pub response_url: Option<String>,
+}
+
+/// Parsed Socket Mode frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketEvent {
+    Hello,
+    /// Slack is about to close the connection; reconnect
+    Disconnect { reason: Option<String> },
+    Runbook { envelope_id: String, request: RunbookRequest },
+    /// Anything else; acknowledged and ignored
+    Other { envelope_id: Option<String> },
+}
+
+/// Socket Mode client for runbook commands.
+pub struct SocketModeClient {
+    app_token: String,
+    dispatcher: Arc<RunbookDispatcher>,
+    connector: Arc<dyn SocketConnector>,
+    command: String,
+    http: reqwest::Client,
+}
+
+impl SocketModeClient {
+    /// Requires the app-level token (`xapp-...`) in the config.
+    pub fn new(config: &SlackConfig, dispatcher: Arc<RunbookDispatcher>) -> Result<Self, SlackError> {
+        crate::connectors::license::check_feature_license("slack")?;
+        let app_token = config.app_token.clone()
+            .filter(|token| token.starts_with("xapp-"))
+            .ok_or(SlackError::InvalidToken)?;
+        Ok(Self {
+            app_token,
+            dispatcher,
+            connector: Arc::new(TungsteniteConnector),
+            command: "/agentkern".into(),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Replace the WebSocket connector.
+    pub fn with_connector(mut self, connector: Arc<dyn SocketConnector>) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// Slash command to answer (default `/agentkern`).
+    pub fn with_command(mut self, command: &str) -> Self {
+        self.command = command.to_string();
+        self
+    }
+
+    /// Connect and serve until the app token is rejected, reconnecting
+    /// with backoff whenever the connection drops.
+    pub async fn run(self: Arc<Self>) -> Result<(), SlackError> {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.serve_connection().await {
+                Err(SlackError::InvalidToken) => return Err(SlackError::InvalidToken),
+                Err(e) => {
+                    tracing::warn!("Slack socket mode connection failed: {}", e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(Duration::from_secs(30));
+                }
+                Ok(()) => backoff = Duration::from_secs(1),
+            }
+        }
+    }
+
+    /// Serve one connection until Slack closes or refreshes it.
+    async fn serve_connection(self: &Arc<Self>) -> Result<(), SlackError> {
+        let url = self.open_connection().await?;
+        let mut connection = self.connector.connect(&url).await?;
+        while let Some(frame) = connection.recv().await {
+            match self.parse_envelope(&frame?)? {
+                SocketEvent::Hello => {}
+                SocketEvent::Disconnect { .. } => return Ok(()),
+                SocketEvent::Other { envelope_id } => {
+                    if let Some(id) = envelope_id {
+                        connection.send(ack(&id)).await?;
+                    }
+                }
+                SocketEvent::Runbook { envelope_id, request } => {
+                    connection.send(ack(&envelope_id)).await?;
+                    let client = self.clone();
+                    tokio::spawn(async move {
+                        let reply = client.handle_request(&request).await;
+                        if let Err(e) = client.respond(&request, &reply).await {
+                            tracing::warn!("Posting runbook reply failed: {}", e);
+                        }
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn open_connection(&self) -> Result<String, SlackError> {
+        let body: serde_json::Value = self.http
+            .post(CONNECTIONS_OPEN_URL)
+            .bearer_auth(&self.app_token)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| SlackError::ApiError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| SlackError::ApiError(e.to_string()))?;
+        match (body["ok"].as_bool(), body["url"].as_str(), body["error"].as_str()) {
+            (Some(true), Some(url), _) => Ok(url.to_string()),
+            (_, _, Some("invalid_auth" | "not_authed" | "token_revoked")) => Err(SlackError::InvalidToken),
+            (_, _, error) => Err(SlackError::ApiError(error.unwrap_or("no socket URL").to_string())),
+        }
+    }
+
+    /// Parse a Socket Mode frame.
+    pub fn parse_envelope(&self, frame: &str) -> Result<SocketEvent, SlackError> {
+        let envelope: Envelope = serde_json::from_str(frame)
+            .map_err(|e| SlackError::ApiError(format!("bad socket frame: {}", e)))?;
+        let other = SocketEvent::Other { envelope_id: envelope.envelope_id.clone() };
+        let request = match envelope.kind.as_str() {
+            "hello" => return Ok(SocketEvent::Hello),
+            "disconnect" => return Ok(SocketEvent::Disconnect { reason: envelope.reason }),
+            "slash_commands" => {
+                let Ok(command) = serde_json::from_value::<SlashCommandPayload>(envelope.payload) else {
+                    return Ok(other);
+                };
+                if command.command != self.command {
+                    return Ok(other);
+                }
+                RunbookRequest {
+                    text: command.text,
+                    actor: HumanActor {
+                        team_id: command.team_id,
+                        channel_id: command.channel_id,
+                        ..HumanActor::slack(&command.user_id, &command.user_name)
+                    },
+                    response_url: command.response_url,
+                }
+            }
+            "interactive" => {
+                let Ok(payload) = serde_json::from_value::<BlockActionsPayload>(envelope.payload) else {
+                    return Ok(other);
+                };
+                let value = payload.actions.iter()
+                    .find(|action| action.action_id == RUNBOOK_ACTION_ID)
+                    .and_then(|action| action.value.clone());
+                match value {
+                    Some(text) if payload.kind == "block_actions" => RunbookRequest {
+                        text,
+                        actor: HumanActor {
+                            team_id: payload.user.team_id,
+                            channel_id: payload.channel.map(|c| c.id),
+                            ..HumanActor::slack(&payload.user.id, &payload.user.username)
+                        },
+                        response_url: payload.response_url,
+                    },
+                    _ => return Ok(other),
+                }
+            }
+            _ => return Ok(other),
+        };
+        match envelope.envelope_id {
+            Some(envelope_id) => Ok(SocketEvent::Runbook { envelope_id, request }),
+            None => Ok(other),
+        }
+    }
+
+    /// Verify and run a request; returns the reply text.
+    pub async fn handle_request(&self, request: &RunbookRequest) -> String {
+        if request.text.trim().is_empty() || request.text.trim() == "help" {
+            return self.usage();
+        }
+        let action = match RunbookAction::parse(&request.text) {
+            Ok(action) => action,
+            Err(e) => return format!("{}\n{}", e, self.usage()),
+        };
+        match self.dispatcher.dispatch(&action, &request.actor).await {
+            Ok(outcome) => format!("<@{}> {}", request.actor.user_id, outcome.to_text()),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn usage(&self) -> String {
+        let commands: Vec<_> = [
+            (RunbookKind::PauseAgent, "pause-agent <agent>"),
+            (RunbookKind::OpenCircuit, "open-circuit <circuit>"),
+            (RunbookKind::TriggerFailover, "failover <service> <region>"),
+        ]
+        .into_iter()
+        .filter(|(kind, _)| self.dispatcher.is_allowed(*kind))
+        .map(|(_, usage)| format!("`{} {}`", self.command, usage))
+        .collect();
+        format!("Available runbook actions: {}", commands.join(", "))
+    }
+
+    async fn respond(&self, request: &RunbookRequest, text: &str) -> Result<(), SlackError> {
+        let Some(url) = &request.response_url else {
+            return Ok(());
+        };
+        self.http
+            .post(url)
+            .json(&serde_json::json!({
+                "response_type": "in_channel",
+                "replace_original": false,
+                "text": text,
+            }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| SlackError::ApiError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+fn ack(envelope_id: &str) -> String {
+    serde_json::json!({ "envelope_id": envelope_id }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escalation::runbook::RunbookExecutor;
+    use agentkern_arbiter::AuditLedger;
+    use agentkern_gate::GateEngine;
+
+    struct NoopExecutor;
+
+    #[async_trait]
+    impl RunbookExecutor for NoopExecutor {
+        async fn execute(&self, action: &RunbookAction, _actor: &HumanActor) -> Result<String, String> {
+            Ok(format!("{} paused", action.target()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slash_command_and_button_envelopes() {
+        let dispatcher = RunbookDispatcher::new(
+            Arc::new(GateEngine::new().with_neural_threshold(100)),
+            Arc::new(AuditLedger::new()),
+            Arc::new(NoopExecutor),
+        ).with_action(RunbookKind::PauseAgent);
+        let client = SocketModeClient {
+            app_token: "xapp-1".into(),
+            dispatcher: Arc::new(dispatcher),
+            connector: Arc::new(TungsteniteConnector),
+            command: "/agentkern".into(),
+            http: reqwest::Client::new(),
+        };
+
+        let slash = r#"{"envelope_id":"e1","type":"slash_commands","accepts_response_payload":true,
+            "payload":{"command":"/agentkern","text":"pause-agent treasury-agent","user_id":"U1","user_name":"alice","channel_id":"C1"}}"#;
+        let SocketEvent::Runbook { envelope_id, request } = client.parse_envelope(slash).unwrap() else {
+            panic!("slash command should be a runbook request");
+        };
+        assert_eq!((envelope_id.as_str(), request.actor.channel_id.as_deref()), ("e1", Some("C1")));
+        assert!(client.handle_request(&request).await.contains("treasury-agent paused"));
+
+        let button = r#"{"envelope_id":"e2","type":"interactive","payload":{"type":"block_actions",
+            "user":{"id":"U2","username":"bob"},"actions":[{"action_id":"runbook","value":"failover gate eu-west-1"}]}}"#;
+        let SocketEvent::Runbook { request, .. } = client.parse_envelope(button).unwrap() else {
+            panic!("runbook button should be a runbook request");
+        };
+        assert!(client.handle_request(&request).await.contains("not enabled"));
+
+        assert_eq!(client.parse_envelope(r#"{"type":"hello"}"#).unwrap(), SocketEvent::Hello);
+    }
+}
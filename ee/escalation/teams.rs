@@ -1,7 +1,11 @@
 //! Microsoft Teams Integration
 //!
-//! Native Teams integration with Adaptive Cards
+//! Native Teams integration with Adaptive Cards. Escalation, drift and cost
+//! alerts are rendered from localized [`CardTemplates`].
 
+use super::cards::CardTemplates;
+use agentkern_arbiter::cost::CostAlert;
+use agentkern_synapse::drift::DriftAlert;
 use serde::{Deserialize, Serialize};
 
 /// Teams configuration.
@@ -18,13 +22,20 @@ pub struct TeamsConfig {
 /// Microsoft Teams integration.
 pub struct TeamsIntegration {
     config: TeamsConfig,
+    templates: CardTemplates,
 }
 
 impl TeamsIntegration {
     /// Create new Teams integration.
     pub fn new(config: TeamsConfig) -> Result<Self, TeamsError> {
         crate::connectors::license::check_feature_license("teams")?;
-        Ok(Self { config })
+        Ok(Self { config, templates: CardTemplates::new() })
+    }
+
+    /// Card templates, e.g. with a fixed language or dashboard URL.
+    pub fn with_templates(mut self, templates: CardTemplates) -> Self {
+        self.templates = templates;
+        self
     }
     
     /// Send escalation via Adaptive Card.
@@ -32,6 +43,16 @@ impl TeamsIntegration {
         let card = self.build_adaptive_card(alert);
         self.post_card(&card)
     }
+
+    /// Send an intent drift alert.
+    pub fn send_drift(&self, alert: &DriftAlert) -> Result<(), TeamsError> {
+        self.post_card(&self.templates.drift(alert))
+    }
+
+    /// Send a cost threshold alert.
+    pub fn send_cost(&self, alert: &CostAlert) -> Result<(), TeamsError> {
+        self.post_card(&self.templates.cost(alert))
+    }
     
     /// Send simple message.
     pub fn send_message(&self, text: &str) -> Result<(), TeamsError> {
@@ -42,43 +63,7 @@ impl TeamsIntegration {
     }
     
     fn build_adaptive_card(&self, alert: &TeamsAlert) -> AdaptiveCard {
-        AdaptiveCard {
-            card_type: "AdaptiveCard".into(),
-            version: "1.4".into(),
-            body: vec![
-                CardElement::TextBlock {
-                    text: format!("⚠️ {} Escalation", alert.level),
-                    size: "Large".into(),
-                    weight: "Bolder".into(),
-                },
-                CardElement::FactSet {
-                    facts: vec![
-                        ("Agent", alert.agent_id.clone()),
-                        ("Task", alert.task_id.clone()),
-                        ("Level", alert.level.clone()),
-                    ],
-                },
-                CardElement::TextBlock {
-                    text: alert.description.clone(),
-                    size: "Default".into(),
-                    weight: "Default".into(),
-                },
-            ],
-            actions: vec![
-                CardAction::ActionSubmit {
-                    title: "Approve".into(),
-                    data: serde_json::json!({"action": "approve", "id": alert.request_id}),
-                },
-                CardAction::ActionSubmit {
-                    title: "Reject".into(),
-                    data: serde_json::json!({"action": "reject", "id": alert.request_id}),
-                },
-                CardAction::ActionOpenUrl {
-                    title: "View Details".into(),
-                    url: format!("https://dashboard.agentkern.com/escalations/{}", alert.request_id),
-                },
-            ],
-        }
+        self.templates.escalation(alert)
     }
     
     fn post_card(&self, card: &AdaptiveCard) -> Result<(), TeamsError> {
@@ -93,7 +78,12 @@ impl TeamsIntegration {
     }
     
     fn post_webhook(&self, payload: &serde_json::Value) -> Result<(), TeamsError> {
-        // Would use reqwest to POST to webhook_url
+        reqwest::blocking::Client::new()
+            .post(&self.config.webhook_url)
+            .json(payload)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| TeamsError::WebhookError(e.to_string()))?;
         Ok(())
     }
 }
@@ -113,22 +103,84 @@ pub struct TeamsAlert {
 pub struct AdaptiveCard {
     #[serde(rename = "type")]
     pub card_type: String,
+    #[serde(rename = "$schema")]
+    pub schema: String,
     pub version: String,
+    /// Right-to-left layout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtl: Option<bool>,
     pub body: Vec<CardElement>,
     pub actions: Vec<CardAction>,
 }
 
+impl AdaptiveCard {
+    pub fn new(body: Vec<CardElement>, actions: Vec<CardAction>) -> Self {
+        Self {
+            card_type: "AdaptiveCard".into(),
+            schema: "http://adaptivecards.io/schemas/adaptive-card.json".into(),
+            version: "1.5".into(),
+            rtl: None,
+            body,
+            actions,
+        }
+    }
+}
+
 /// Card element.
 #[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
 pub enum CardElement {
-    TextBlock { text: String, size: String, weight: String },
-    FactSet { facts: Vec<(&'static str, String)> },
+    #[serde(rename_all = "camelCase")]
+    TextBlock {
+        text: String,
+        size: String,
+        weight: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<String>,
+        wrap: bool,
+    },
+    FactSet { facts: Vec<Fact> },
+    Container {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        style: Option<String>,
+        bleed: bool,
+        items: Vec<CardElement>,
+    },
+}
+
+impl CardElement {
+    /// Wrapping body text.
+    pub fn text(text: &str) -> Self {
+        Self::TextBlock {
+            text: text.to_string(),
+            size: "Default".into(),
+            weight: "Default".into(),
+            color: None,
+            wrap: true,
+        }
+    }
+}
+
+/// Fact set entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fact {
+    pub title: String,
+    pub value: String,
+}
+
+impl Fact {
+    pub fn new(title: impl Into<String>, value: impl Into<String>) -> Self {
+        Self { title: title.into(), value: value.into() }
+    }
 }
 
 /// Card action.
 #[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
 pub enum CardAction {
+    #[serde(rename = "Action.Submit")]
     ActionSubmit { title: String, data: serde_json::Value },
+    #[serde(rename = "Action.OpenUrl")]
     ActionOpenUrl { title: String, url: String },
 }
 
@@ -0,0 +1,516 @@
+//! Zero Trust Conditional Access
+//!
+//! Turns a trust score plus request context (device posture, region,
+//! time of day) into an access decision:
+//! - `Allow`: proceed, possibly with a shortened session
+//! - `StepUp`: proceed once the agent re-authenticates with a listed method
+//! - `Deny`: refuse
+//!
+//! The trust recommendation sets the baseline (`Block` denies, `Challenge`
+//! steps up, `Restrict` steps up and shortens the session); rules can only
+//! make the decision stricter. Decisions feed Gate policies through the request
+//! context (`context.access_decision`, `context.trust_score`) and cap SSO
+//! session lifetimes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let engine = ConditionalAccessEngine::new()
+//!     .with_rule(AccessRule::new("payments-from-managed-devices", AccessEffect::Deny)
+//!         .for_resource("swift.*")
+//!         .when(AccessCondition::DeviceUnmanaged))
+//!     .with_rule(AccessRule::new("after-hours-mfa", AccessEffect::step_up(&["mfa"]))
+//!         .when(AccessCondition::OutsideHours { from_hour: 7, to_hour: 19, utc_offset_minutes: 60 }));
+//!
+//! let decision = engine.evaluate(&AccessContext::new("agent-1", "swift.create_payment", factors)
+//!     .with_device(posture)
+//!     .with_region("DE"));
+//!
+//! let mut request = VerificationRequestBuilder::new("agent-1", "swift.create_payment").build();
+//! decision.apply_to(&mut request);
+//! let result = gate.verify(request).await;
+//! ```
+
+use super::bridge::AccessDecision;
+use super::trust::{TrustFactors, TrustRecommendation, TrustScoreProvider};
+use agentkern_gate::VerificationRequest;
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Request being authorized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessContext {
+    pub agent_id: String,
+    /// Resource or action, e.g. `swift.create_payment`
+    pub resource: String,
+    pub factors: TrustFactors,
+    /// Unknown when the agent runs off a reporting device
+    pub device: Option<DevicePosture>,
+    /// ISO 3166 country of the request
+    pub region: Option<String>,
+    pub at: DateTime<Utc>,
+    /// Methods already satisfied in this session, e.g. `mfa`, `hardware_key`
+    pub authentication_methods: Vec<String>,
+}
+
+impl AccessContext {
+    pub fn new(agent_id: &str, resource: &str, factors: TrustFactors) -> Self {
+        Self {
+            agent_id: agent_id.to_string(),
+            resource: resource.to_string(),
+            factors,
+            device: None,
+            region: None,
+            at: Utc::now(),
+            authentication_methods: Vec::new(),
+        }
+    }
+
+    pub fn with_device(mut self, device: DevicePosture) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_uppercase());
+        self
+    }
+
+    /// Evaluate as of `at` instead of now.
+    pub fn at(mut self, at: DateTime<Utc>) -> Self {
+        self.at = at;
+        self
+    }
+
+    pub fn with_authentication(mut self, method: &str) -> Self {
+        self.authentication_methods.push(method.to_string());
+        self
+    }
+}
+
+/// Device the agent runs on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DevicePosture {
+    /// Enrolled in device management
+    pub managed: bool,
+    /// Meets the MDM compliance policy
+    pub compliant: bool,
+    pub disk_encrypted: bool,
+    /// Days since the last OS security patch
+    pub patch_age_days: Option<u32>,
+}
+
+/// Trust factor, for per-factor conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustFactor {
+    Identity,
+    Behavior,
+    Compliance,
+    Reliability,
+    Security,
+}
+
+impl TrustFactor {
+    fn of(self, factors: &TrustFactors) -> f64 {
+        match self {
+            TrustFactor::Identity => factors.identity,
+            TrustFactor::Behavior => factors.behavior,
+            TrustFactor::Compliance => factors.compliance,
+            TrustFactor::Reliability => factors.reliability,
+            TrustFactor::Security => factors.security,
+        }
+    }
+}
+
+/// Condition under which a rule applies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccessCondition {
+    /// Overall trust score below the threshold
+    TrustBelow { threshold: f64 },
+    /// A single factor below the threshold
+    FactorBelow { factor: TrustFactor, threshold: f64 },
+    /// No device posture reported
+    DeviceUnknown,
+    /// Device unknown or not managed
+    DeviceUnmanaged,
+    /// Device unknown, non-compliant or unencrypted
+    DeviceNonCompliant,
+    /// Device unpatched for more than `days` (or patch state unknown)
+    PatchOlderThan { days: u32 },
+    /// Region unknown or not in the list
+    RegionNotIn { regions: Vec<String> },
+    /// Region in the list
+    RegionIn { regions: Vec<String> },
+    /// Local time outside `[from_hour, to_hour)`; wraps past midnight when
+    /// `from_hour > to_hour`
+    OutsideHours { from_hour: u32, to_hour: u32, utc_offset_minutes: i32 },
+    /// Any of the conditions
+    Any { conditions: Vec<AccessCondition> },
+}
+
+impl AccessCondition {
+    fn matches(&self, ctx: &AccessContext, score: f64) -> bool {
+        let device = ctx.device.as_ref();
+        match self {
+            AccessCondition::TrustBelow { threshold } => score < *threshold,
+            AccessCondition::FactorBelow { factor, threshold } => factor.of(&ctx.factors) < *threshold,
+            AccessCondition::DeviceUnknown => device.is_none(),
+            AccessCondition::DeviceUnmanaged => !device.is_some_and(|d| d.managed),
+            AccessCondition::DeviceNonCompliant => !device.is_some_and(|d| d.compliant && d.disk_encrypted),
+            AccessCondition::PatchOlderThan { days } => {
+                device.and_then(|d| d.patch_age_days).is_none_or(|age| age > *days)
+            }
+            AccessCondition::RegionNotIn { regions } => {
+                !ctx.region.as_ref().is_some_and(|r| regions.iter().any(|x| x.eq_ignore_ascii_case(r)))
+            }
+            AccessCondition::RegionIn { regions } => {
+                ctx.region.as_ref().is_some_and(|r| regions.iter().any(|x| x.eq_ignore_ascii_case(r)))
+            }
+            AccessCondition::OutsideHours { from_hour, to_hour, utc_offset_minutes } => {
+                let local = ctx.at + chrono::Duration::minutes(*utc_offset_minutes as i64);
+                let hour = local.hour();
+                let inside = if from_hour <= to_hour {
+                    (*from_hour..*to_hour).contains(&hour)
+                } else {
+                    hour >= *from_hour || hour < *to_hour
+                };
+                !inside
+            }
+            AccessCondition::Any { conditions } => conditions.iter().any(|c| c.matches(ctx, score)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            AccessCondition::TrustBelow { threshold } => format!("trust score below {}", threshold),
+            AccessCondition::FactorBelow { factor, threshold } => format!("{:?} trust below {}", factor, threshold).to_lowercase(),
+            AccessCondition::DeviceUnknown => "device posture unknown".into(),
+            AccessCondition::DeviceUnmanaged => "device not managed".into(),
+            AccessCondition::DeviceNonCompliant => "device not compliant".into(),
+            AccessCondition::PatchOlderThan { days } => format!("device unpatched for over {} days", days),
+            AccessCondition::RegionNotIn { regions } => format!("region not in {}", regions.join(", ")),
+            AccessCondition::RegionIn { regions } => format!("region in {}", regions.join(", ")),
+            AccessCondition::OutsideHours { from_hour, to_hour, .. } => format!("outside {:02}:00-{:02}:00", from_hour, to_hour),
+            AccessCondition::Any { conditions } => {
+                conditions.iter().map(|c| c.describe()).collect::<Vec<_>>().join(" or ")
+            }
+        }
+    }
+}
+
+/// What a matching rule does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccessEffect {
+    /// Require one of the authentication methods
+    StepUp { methods: Vec<String> },
+    /// Cap the session lifetime
+    LimitSession { max_secs: u64 },
+    Deny,
+}
+
+impl AccessEffect {
+    pub fn step_up(methods: &[&str]) -> Self {
+        AccessEffect::StepUp { methods: methods.iter().map(|m| m.to_string()).collect() }
+    }
+}
+
+/// Conditional access rule: applies when ALL conditions match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessRule {
+    pub name: String,
+    /// Resource patterns (`*` suffix for prefixes); empty means all
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub conditions: Vec<AccessCondition>,
+    pub effect: AccessEffect,
+}
+
+impl AccessRule {
+    pub fn new(name: &str, effect: AccessEffect) -> Self {
+        Self { name: name.to_string(), resources: Vec::new(), conditions: Vec::new(), effect }
+    }
+
+    pub fn for_resource(mut self, pattern: &str) -> Self {
+        self.resources.push(pattern.to_string());
+        self
+    }
+
+    pub fn when(mut self, condition: AccessCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    fn applies_to(&self, resource: &str) -> bool {
+        self.resources.is_empty() || self.resources.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => resource.starts_with(prefix),
+            None => p == resource,
+        })
+    }
+}
+
+/// Access outcome, ordered from least to most restrictive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessOutcome {
+    Allow,
+    StepUp,
+    Deny,
+}
+
+impl AccessOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessOutcome::Allow => "allow",
+            AccessOutcome::StepUp => "step_up",
+            AccessOutcome::Deny => "deny",
+        }
+    }
+}
+
+/// Conditional access decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalAccessDecision {
+    pub outcome: AccessOutcome,
+    pub trust_score: f64,
+    /// Rules (and `trust_score`) that shaped the decision
+    pub matched_rules: Vec<String>,
+    pub reasons: Vec<String>,
+    /// Acceptable methods when stepping up
+    pub step_up_methods: Vec<String>,
+    /// Session lifetime cap
+    pub max_session_secs: Option<u64>,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl ConditionalAccessDecision {
+    pub fn is_allowed(&self) -> bool {
+        self.outcome == AccessOutcome::Allow
+    }
+
+    /// Expose the decision to Gate policies, e.g.
+    /// `context.access_decision == 'deny' => Deny`.
+    pub fn apply_to(&self, request: &mut VerificationRequest) {
+        let data = &mut request.context.data;
+        data.insert("access_decision".into(), self.outcome.as_str().into());
+        data.insert("trust_score".into(), self.trust_score.into());
+        data.insert("access_rules".into(), self.matched_rules.clone().into());
+        if !self.step_up_methods.is_empty() {
+            data.insert("step_up_methods".into(), self.step_up_methods.clone().into());
+        }
+    }
+
+    /// SSO session expiry (unix seconds) after applying the session cap.
+    pub fn session_expires_at(&self, created_at: u64, expires_at: u64) -> u64 {
+        match self.max_session_secs {
+            Some(max) => expires_at.min(created_at.saturating_add(max)),
+            None => expires_at,
+        }
+    }
+}
+
+impl From<&ConditionalAccessDecision> for AccessDecision {
+    fn from(decision: &ConditionalAccessDecision) -> Self {
+        let reason = match decision.outcome {
+            AccessOutcome::Allow => "allowed".to_string(),
+            AccessOutcome::StepUp => format!("step-up required: {}", decision.step_up_methods.join(" or ")),
+            AccessOutcome::Deny => format!("denied: {}", decision.reasons.join("; ")),
+        };
+        Self {
+            allowed: decision.is_allowed(),
+            reason,
+            conditions_met: if decision.is_allowed() { decision.matched_rules.clone() } else { Vec::new() },
+            conditions_failed: if decision.is_allowed() { Vec::new() } else { decision.matched_rules.clone() },
+        }
+    }
+}
+
+/// Conditional access engine.
+pub struct ConditionalAccessEngine {
+    scorer: TrustScoreProvider,
+    rules: Vec<AccessRule>,
+    /// Methods required when the trust score recommends a challenge
+    challenge_methods: Vec<String>,
+    /// Session cap when the trust score recommends restricting
+    restricted_session_secs: u64,
+}
+
+impl ConditionalAccessEngine {
+    pub fn new() -> Self {
+        Self {
+            scorer: TrustScoreProvider::new(),
+            rules: Vec::new(),
+            challenge_methods: vec!["mfa".to_string()],
+            restricted_session_secs: 900,
+        }
+    }
+
+    pub fn with_scorer(mut self, scorer: TrustScoreProvider) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    pub fn with_rule(mut self, rule: AccessRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_challenge_methods(mut self, methods: &[&str]) -> Self {
+        self.challenge_methods = methods.iter().map(|m| m.to_string()).collect();
+        self
+    }
+
+    pub fn with_restricted_session(mut self, max_secs: u64) -> Self {
+        self.restricted_session_secs = max_secs;
+        self
+    }
+
+    pub fn rules(&self) -> &[AccessRule] {
+        &self.rules
+    }
+
+    /// Evaluate a request.
+    pub fn evaluate(&self, ctx: &AccessContext) -> ConditionalAccessDecision {
+        let score = self.scorer.calculate(ctx.factors.clone());
+        let mut decision = ConditionalAccessDecision {
+            outcome: AccessOutcome::Allow,
+            trust_score: score.overall,
+            matched_rules: Vec::new(),
+            reasons: Vec::new(),
+            step_up_methods: Vec::new(),
+            max_session_secs: None,
+            evaluated_at: ctx.at,
+        };
+
+        // Lower scores get everything higher ones do, and more
+        let step_up = AccessEffect::StepUp { methods: self.challenge_methods.clone() };
+        let baseline = match score.recommendation {
+            TrustRecommendation::Allow => vec![],
+            TrustRecommendation::Challenge => vec![step_up],
+            TrustRecommendation::Restrict => {
+                vec![step_up, AccessEffect::LimitSession { max_secs: self.restricted_session_secs }]
+            }
+            TrustRecommendation::Block => vec![AccessEffect::Deny],
+        };
+        for effect in &baseline {
+            let reason = format!("trust score {:.2} ({:?})", score.overall, score.recommendation).to_lowercase();
+            self.apply(&mut decision, ctx, "trust_score", reason, effect);
+        }
+
+        for rule in self.rules.iter().filter(|r| r.applies_to(&ctx.resource)) {
+            if rule.conditions.iter().all(|c| c.matches(ctx, score.overall)) {
+                let reason = if rule.conditions.is_empty() {
+                    rule.name.clone()
+                } else {
+                    rule.conditions.iter().map(|c| c.describe()).collect::<Vec<_>>().join(" and ")
+                };
+                self.apply(&mut decision, ctx, &rule.name, reason, &rule.effect);
+            }
+        }
+
+        if decision.outcome != AccessOutcome::StepUp {
+            decision.step_up_methods.clear();
+        }
+        decision
+    }
+
+    fn apply(&self, decision: &mut ConditionalAccessDecision, ctx: &AccessContext, name: &str, reason: String, effect: &AccessEffect) {
+        let outcome = match effect {
+            AccessEffect::Deny => AccessOutcome::Deny,
+            AccessEffect::LimitSession { max_secs } => {
+                decision.max_session_secs = Some(decision.max_session_secs.map_or(*max_secs, |m| m.min(*max_secs)));
+                AccessOutcome::Allow
+            }
+            AccessEffect::StepUp { methods } => {
+                if methods.iter().any(|m| ctx.authentication_methods.contains(m)) {
+                    // Already satisfied in this session
+                    return;
+                }
+                // Prefer methods that satisfy every pending step-up
+                let common: Vec<_> = decision.step_up_methods.iter().filter(|m| methods.contains(m)).cloned().collect();
+                if decision.step_up_methods.is_empty() || common.is_empty() {
+                    decision.step_up_methods.extend(methods.iter().cloned());
+                } else {
+                    decision.step_up_methods = common;
+                }
+                AccessOutcome::StepUp
+            }
+        };
+        decision.outcome = decision.outcome.max(outcome);
+        // The trust baseline may apply several effects under one name
+        if decision.matched_rules.last().map(String::as_str) != Some(name) {
+            decision.matched_rules.push(name.to_string());
+            decision.reasons.push(reason);
+        }
+    }
+}
+
+impl Default for ConditionalAccessEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn factors(level: f64) -> TrustFactors {
+        TrustFactors { identity: level, behavior: level, compliance: level, reliability: level, security: level }
+    }
+
+    #[test]
+    fn test_conditional_access() {
+        let engine = ConditionalAccessEngine::new()
+            .with_rule(AccessRule::new("payments-need-managed-device", AccessEffect::Deny)
+                .for_resource("swift.*")
+                .when(AccessCondition::DeviceUnmanaged))
+            .with_rule(AccessRule::new("eu-only", AccessEffect::Deny)
+                .when(AccessCondition::RegionNotIn { regions: vec!["DE".into(), "FR".into()] }))
+            .with_rule(AccessRule::new("after-hours", AccessEffect::step_up(&["hardware_key", "mfa"]))
+                .when(AccessCondition::OutsideHours { from_hour: 7, to_hour: 19, utc_offset_minutes: 60 }));
+
+        let office = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let night = Utc.with_ymd_and_hms(2026, 3, 2, 22, 0, 0).unwrap();
+        let device = DevicePosture { managed: true, compliant: true, disk_encrypted: true, patch_age_days: Some(3) };
+        let base = AccessContext::new("agent-1", "swift.create_payment", factors(0.9)).with_device(device).with_region("de");
+
+        let decision = engine.evaluate(&base.clone().at(office));
+        assert_eq!(decision.outcome, AccessOutcome::Allow);
+        assert!(decision.matched_rules.is_empty());
+
+        // After hours: step up, unless already done
+        let decision = engine.evaluate(&base.clone().at(night));
+        assert_eq!(decision.outcome, AccessOutcome::StepUp);
+        assert_eq!(decision.step_up_methods, vec!["hardware_key", "mfa"]);
+        assert!(!AccessDecision::from(&decision).allowed);
+        assert!(engine.evaluate(&base.clone().at(night).with_authentication("mfa")).is_allowed());
+
+        // Unmanaged device only matters for swift.*
+        let unmanaged = AccessContext { device: None, ..base.clone().at(office) };
+        assert_eq!(engine.evaluate(&unmanaged).outcome, AccessOutcome::Deny);
+        assert!(engine.evaluate(&AccessContext { resource: "sap.read_table".into(), ..unmanaged }).is_allowed());
+
+        // Trust baseline: challenge steps up, restrict also caps the session
+        let challenged = engine.evaluate(&AccessContext { factors: factors(0.7), ..base.clone().at(office) });
+        assert_eq!(challenged.outcome, AccessOutcome::StepUp);
+        assert_eq!(challenged.matched_rules, vec!["trust_score"]);
+        let restricted = AccessContext { factors: factors(0.5), ..base.clone().at(office) };
+        assert_eq!(engine.evaluate(&restricted).outcome, AccessOutcome::StepUp);
+        let restricted = engine.evaluate(&restricted.with_authentication("mfa"));
+        assert!(restricted.is_allowed());
+        assert_eq!(restricted.session_expires_at(1_000, 100_000), 1_900);
+
+        // Deny wins, and reaches Gate policies through the context
+        let denied = engine.evaluate(&base.at(night).with_region("US"));
+        assert_eq!(denied.outcome, AccessOutcome::Deny);
+        assert!(denied.step_up_methods.is_empty());
+        let mut request = agentkern_gate::engine::VerificationRequestBuilder::new("agent-1", "swift.create_payment").build();
+        denied.apply_to(&mut request);
+        assert_eq!(request.context.data["access_decision"], "deny");
+    }
+}
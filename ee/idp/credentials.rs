@@ -0,0 +1,279 @@
+//! Agent Registration Credentials
+//!
+//! W3C Verifiable Credentials attesting an agent's registration, issued by
+//! the organization DID and proved with a detached JWS (JsonWebSignature2020).
+//! Issued credentials are tracked per subject in a [`RevocationRegistry`]
+//! so they can be revoked when the agent's directory identity goes away.
+
+use super::bridge::{AgentRegistration, AgentType};
+use super::did::{canonical_json, verify_detached, DidError, DidIdentity, DidResolver};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// Credential type issued for agent registrations.
+pub const AGENT_REGISTRATION_CREDENTIAL: &str = "AgentRegistrationCredential";
+
+/// Verifiable credential.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub credential_type: Vec<String>,
+    /// Issuer DID
+    pub issuer: String,
+    pub valid_from: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>,
+    pub credential_subject: AgentSubject,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<CredentialProof>,
+}
+
+/// Registered agent, identified by its DID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSubject {
+    pub id: String,
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub owner: String,
+    pub agent_type: AgentType,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl From<&AgentRegistration> for AgentSubject {
+    fn from(registration: &AgentRegistration) -> Self {
+        Self {
+            id: registration.did.clone(),
+            display_name: registration.display_name.clone(),
+            description: registration.description.clone(),
+            owner: registration.owner.clone(),
+            agent_type: registration.agent_type,
+            tags: registration.tags.clone(),
+        }
+    }
+}
+
+/// Credential proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProof {
+    /// `JsonWebSignature2020`
+    #[serde(rename = "type")]
+    pub proof_type: String,
+    pub created: DateTime<Utc>,
+    /// Signing key (`did#fragment`)
+    pub verification_method: String,
+    pub proof_purpose: String,
+    /// Detached JWS over the credential without its proof
+    pub jws: String,
+}
+
+impl VerifiableCredential {
+    fn signing_payload(&self) -> Result<String, DidError> {
+        let mut unsigned = self.clone();
+        unsigned.proof = None;
+        let value = serde_json::to_value(&unsigned).map_err(|e| DidError::Crypto(e.to_string()))?;
+        Ok(canonical_json(&value))
+    }
+
+    /// Subject DID.
+    pub fn subject(&self) -> &str {
+        &self.credential_subject.id
+    }
+}
+
+/// Issues registration credentials under an issuer DID.
+pub struct CredentialIssuer {
+    identity: DidIdentity,
+    validity: Option<Duration>,
+    registry: Option<Arc<RevocationRegistry>>,
+}
+
+impl CredentialIssuer {
+    pub fn new(identity: DidIdentity) -> Self {
+        Self { identity, validity: None, registry: None }
+    }
+
+    /// Track issued credentials for revocation.
+    pub fn with_registry(mut self, registry: Arc<RevocationRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Expire credentials after `validity`.
+    pub fn with_validity(mut self, validity: Duration) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
+    pub fn did(&self) -> &str {
+        self.identity.did()
+    }
+
+    /// Issue a credential for a registered agent.
+    pub fn issue(&self, registration: &AgentRegistration) -> Result<VerifiableCredential, DidError> {
+        if !registration.did.starts_with("did:") {
+            return Err(DidError::InvalidDid(registration.did.clone()));
+        }
+        let now = Utc::now();
+        let mut credential = VerifiableCredential {
+            context: vec!["https://www.w3.org/ns/credentials/v2".to_string()],
+            id: format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+            credential_type: vec!["VerifiableCredential".to_string(), AGENT_REGISTRATION_CREDENTIAL.to_string()],
+            issuer: self.identity.did().to_string(),
+            valid_from: now,
+            valid_until: self.validity.map(|v| now + v),
+            credential_subject: registration.into(),
+            proof: None,
+        };
+        let jws = self.identity.sign(credential.signing_payload()?.as_bytes())?;
+        credential.proof = Some(CredentialProof {
+            proof_type: "JsonWebSignature2020".to_string(),
+            created: now,
+            verification_method: self.identity.key_id().to_string(),
+            proof_purpose: "assertionMethod".to_string(),
+            jws,
+        });
+        if let Some(registry) = &self.registry {
+            registry.record(&credential);
+        }
+        Ok(credential)
+    }
+}
+
+/// Issued and revoked credentials by subject DID.
+#[derive(Debug, Default)]
+pub struct RevocationRegistry {
+    issued: RwLock<HashMap<String, Vec<String>>>,
+    revoked: RwLock<HashSet<String>>,
+}
+
+impl RevocationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, credential: &VerifiableCredential) {
+        self.issued.write().unwrap()
+            .entry(credential.subject().to_string())
+            .or_default()
+            .push(credential.id.clone());
+    }
+
+    /// Revoke every credential issued to `subject`; returns the newly
+    /// revoked credential IDs.
+    pub fn revoke_subject(&self, subject: &str) -> Vec<String> {
+        let issued = self.issued.read().unwrap();
+        let mut revoked = self.revoked.write().unwrap();
+        issued.get(subject).into_iter().flatten()
+            .filter(|id| revoked.insert(id.to_string()))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_revoked(&self, credential_id: &str) -> bool {
+        self.revoked.read().unwrap().contains(credential_id)
+    }
+
+    /// Fail if the credential has been revoked.
+    pub fn check(&self, credential: &VerifiableCredential) -> Result<(), CredentialError> {
+        if self.is_revoked(&credential.id) {
+            return Err(CredentialError::Revoked(credential.id.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Verify a credential's proof and validity window; returns the issuer DID.
+pub async fn verify_credential(
+    resolver: &dyn DidResolver,
+    credential: &VerifiableCredential,
+) -> Result<String, CredentialError> {
+    let proof = credential.proof.as_ref().ok_or(CredentialError::MissingProof)?;
+    let now = Utc::now();
+    if now < credential.valid_from {
+        return Err(CredentialError::NotYetValid(credential.valid_from));
+    }
+    if let Some(until) = credential.valid_until.filter(|until| now >= *until) {
+        return Err(CredentialError::Expired(until));
+    }
+
+    let signer = verify_detached(resolver, credential.signing_payload()?.as_bytes(), &proof.jws).await?;
+    if signer != credential.issuer || !proof.verification_method.starts_with(&format!("{}#", signer)) {
+        return Err(CredentialError::IssuerMismatch { issuer: credential.issuer.clone(), signer });
+    }
+    let document = resolver.resolve(&signer).await?;
+    if !document.assertion_method.contains(&proof.verification_method) {
+        return Err(CredentialError::IssuerMismatch { issuer: credential.issuer.clone(), signer });
+    }
+    Ok(signer)
+}
+
+/// Credential verification error.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    #[error("Credential has no proof")]
+    MissingProof,
+
+    #[error("Credential not valid before {0}")]
+    NotYetValid(DateTime<Utc>),
+
+    #[error("Credential expired at {0}")]
+    Expired(DateTime<Utc>),
+
+    #[error("Credential revoked: {0}")]
+    Revoked(String),
+
+    #[error("Credential issued by {issuer} but signed by {signer}")]
+    IssuerMismatch { issuer: String, signer: String },
+
+    #[error(transparent)]
+    Did(#[from] DidError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::did::{DidIssuer, StandardResolver};
+
+    #[tokio::test]
+    async fn test_registration_credential() {
+        let dids = Arc::new(DidIssuer::new());
+        let resolver = StandardResolver::new().with_hosted(dids.clone());
+        let issuer = CredentialIssuer::new(dids.issue_did_web("example.com", &["agents"]).unwrap())
+            .with_validity(Duration::days(30));
+        let agent = dids.issue_did_key().unwrap();
+
+        let registration = AgentRegistration {
+            did: agent.did().to_string(),
+            display_name: "Billing Bot".into(),
+            description: None,
+            owner: "ops@example.com".into(),
+            agent_type: AgentType::Custom,
+            tags: vec!["finance".into()],
+        };
+        let credential = issuer.issue(&registration).unwrap();
+        assert_eq!(credential.subject(), agent.did());
+
+        // Round-trips through JSON
+        let json = serde_json::to_string(&credential).unwrap();
+        let parsed: VerifiableCredential = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_credential(&resolver, &parsed).await.unwrap(), "did:web:example.com:agents");
+
+        let mut tampered = parsed.clone();
+        tampered.credential_subject.owner = "mallory@example.com".into();
+        assert!(matches!(verify_credential(&resolver, &tampered).await, Err(CredentialError::Did(DidError::InvalidSignature))));
+
+        let mut reissued = parsed;
+        reissued.issuer = agent.did().to_string();
+        assert!(verify_credential(&resolver, &reissued).await.is_err());
+    }
+}
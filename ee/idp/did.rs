@@ -0,0 +1,440 @@
+//! Agent DIDs
+//!
+//! Mints the AgentKern DIDs that external identities are federated with:
+//! - `did:key` (self-certifying, resolved locally)
+//! - `did:web` (document hosted at `https://<domain>/<path>/did.json`)
+//!
+//! Keys come from gate's crypto-agility provider; the DID verification key
+//! is the Ed25519 (classical) key. Signatures are detached JWS (EdDSA) whose
+//! `kid` is the verification method, so any holder of the DID can verify
+//! them: agent cards published through Nexus, verifiable credentials.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let issuer = Arc::new(DidIssuer::new());
+//! let agent = issuer.issue_did_web("agents.example.com", &["billing-bot"])?;
+//!
+//! let signature = sign_agent_card(&agent, &card_json)?;
+//! let resolver = StandardResolver::new().with_hosted(issuer.clone());
+//! let signer = verify_agent_card(&resolver, &card_json, &signature).await?;
+//! assert_eq!(signer, agent.did());
+//! ```
+
+use agentkern_gate::crypto_agility::{Algorithm, CryptoMode, CryptoProvider, KeyPair, Signature};
+use async_trait::async_trait;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Multicodec prefix of an Ed25519 public key.
+const ED25519_PUB: [u8; 2] = [0xed, 0x01];
+
+/// DID core and Ed25519 2020 suite contexts.
+const DID_CONTEXTS: [&str; 2] = [
+    "https://www.w3.org/ns/did/v1",
+    "https://w3id.org/security/suites/ed25519-2020/v1",
+];
+
+/// DID document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    pub verification_method: Vec<VerificationMethod>,
+    /// Verification method IDs usable for authentication
+    pub authentication: Vec<String>,
+    /// Verification method IDs usable for signing credentials
+    pub assertion_method: Vec<String>,
+}
+
+impl DidDocument {
+    fn new(did: &str, method: VerificationMethod) -> Self {
+        Self {
+            context: DID_CONTEXTS.iter().map(|c| c.to_string()).collect(),
+            id: did.to_string(),
+            authentication: vec![method.id.clone()],
+            assertion_method: vec![method.id.clone()],
+            verification_method: vec![method],
+        }
+    }
+
+    /// Verification method by full ID (`did#fragment`).
+    pub fn method(&self, id: &str) -> Option<&VerificationMethod> {
+        self.verification_method.iter().find(|m| m.id == id)
+    }
+}
+
+/// Public key of a DID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    /// `Ed25519VerificationKey2020`
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub controller: String,
+    /// Multicodec Ed25519 key, base58btc multibase (`z...`)
+    pub public_key_multibase: String,
+}
+
+impl VerificationMethod {
+    fn ed25519(did: &str, fragment: &str, multibase: &str) -> Self {
+        Self {
+            id: format!("{}#{}", did, fragment),
+            method_type: "Ed25519VerificationKey2020".to_string(),
+            controller: did.to_string(),
+            public_key_multibase: multibase.to_string(),
+        }
+    }
+
+    /// Raw 32-byte public key.
+    pub fn public_key(&self) -> Result<Vec<u8>, DidError> {
+        decode_multikey(&self.public_key_multibase)
+    }
+}
+
+/// A DID with its signing key.
+pub struct DidIdentity {
+    did: String,
+    /// Verification method ID, used as JWS `kid`
+    key_id: String,
+    keypair: KeyPair,
+    document: DidDocument,
+}
+
+impl DidIdentity {
+    pub fn did(&self) -> &str {
+        &self.did
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn document(&self) -> &DidDocument {
+        &self.document
+    }
+
+    /// Detached JWS (`header..signature`) over `payload`.
+    pub fn sign(&self, payload: &[u8]) -> Result<String, DidError> {
+        let header = serde_json::json!({ "alg": "EdDSA", "kid": self.key_id });
+        let header = URL_SAFE_NO_PAD.encode(header.to_string());
+        let signing_input = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(payload));
+        let signature = crypto().sign(signing_input.as_bytes(), &self.keypair)?;
+        let raw = signature.classical_component
+            .ok_or_else(|| DidError::Crypto("no Ed25519 signature".into()))
+            .and_then(|b64| STANDARD.decode(b64).map_err(|e| DidError::Crypto(e.to_string())))?;
+        Ok(format!("{}..{}", header, URL_SAFE_NO_PAD.encode(raw)))
+    }
+
+    /// Detached JWS over the canonical form of `value`.
+    pub fn sign_json(&self, value: &serde_json::Value) -> Result<String, DidError> {
+        self.sign(canonical_json(value).as_bytes())
+    }
+}
+
+impl fmt::Debug for DidIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DidIdentity").field("did", &self.did).field("key_id", &self.key_id).finish_non_exhaustive()
+    }
+}
+
+/// Mints DIDs and hosts the documents of `did:web` ones.
+#[derive(Default)]
+pub struct DidIssuer {
+    hosted: RwLock<HashMap<String, DidDocument>>,
+}
+
+impl DidIssuer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mint a `did:key`.
+    pub fn issue_did_key(&self) -> Result<DidIdentity, DidError> {
+        let keypair = crypto().generate_keypair()?;
+        let multibase = encode_multikey(&STANDARD.decode(&keypair.public_key).map_err(|_| DidError::InvalidKey)?);
+        let did = format!("did:key:{}", multibase);
+        let method = VerificationMethod::ed25519(&did, &multibase, &multibase);
+        Ok(DidIdentity { key_id: method.id.clone(), document: DidDocument::new(&did, method), did, keypair })
+    }
+
+    /// Mint a `did:web` under `domain`, e.g. `did:web:agents.example.com:billing-bot`
+    /// for path `["billing-bot"]`. The document is hosted by this issuer;
+    /// serve it with [`document`](Self::document).
+    pub fn issue_did_web(&self, domain: &str, path: &[&str]) -> Result<DidIdentity, DidError> {
+        if domain.is_empty() || path.iter().any(|p| p.is_empty() || p.contains(':')) {
+            return Err(DidError::InvalidDid(format!("did:web:{}:{}", domain, path.join(":"))));
+        }
+        let keypair = crypto().generate_keypair()?;
+        let multibase = encode_multikey(&STANDARD.decode(&keypair.public_key).map_err(|_| DidError::InvalidKey)?);
+        // Ports are percent-encoded in did:web
+        let mut did = format!("did:web:{}", domain.replace(':', "%3A"));
+        for segment in path {
+            did.push(':');
+            did.push_str(segment);
+        }
+        let method = VerificationMethod::ed25519(&did, "key-1", &multibase);
+        let document = DidDocument::new(&did, method);
+        self.hosted.write().unwrap().insert(did.clone(), document.clone());
+        Ok(DidIdentity { key_id: document.verification_method[0].id.clone(), document, did, keypair })
+    }
+
+    /// Hosted `did:web` document.
+    pub fn document(&self, did: &str) -> Option<DidDocument> {
+        self.hosted.read().unwrap().get(did).cloned()
+    }
+
+    /// Hosted document for an HTTP path (`/billing-bot/did.json`, or
+    /// `/.well-known/did.json` for a bare domain).
+    pub fn document_at(&self, domain: &str, http_path: &str) -> Option<DidDocument> {
+        let path = http_path.trim_start_matches('/').trim_end_matches("did.json").trim_end_matches('/');
+        let path = if path == ".well-known" { "" } else { path };
+        let mut did = format!("did:web:{}", domain.replace(':', "%3A"));
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            did.push(':');
+            did.push_str(segment);
+        }
+        self.document(&did)
+    }
+}
+
+/// Resolves DIDs to their documents.
+#[async_trait]
+pub trait DidResolver: Send + Sync {
+    async fn resolve(&self, did: &str) -> Result<DidDocument, DidError>;
+}
+
+/// Resolves `did:key` locally and `did:web` from hosted documents or over
+/// HTTPS.
+#[derive(Default)]
+pub struct StandardResolver {
+    hosted: Option<Arc<DidIssuer>>,
+    http: reqwest::Client,
+}
+
+impl StandardResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve DIDs minted by `issuer` without fetching them.
+    pub fn with_hosted(mut self, issuer: Arc<DidIssuer>) -> Self {
+        self.hosted = Some(issuer);
+        self
+    }
+}
+
+#[async_trait]
+impl DidResolver for StandardResolver {
+    async fn resolve(&self, did: &str) -> Result<DidDocument, DidError> {
+        if let Some(multibase) = did.strip_prefix("did:key:") {
+            decode_multikey(multibase)?;
+            return Ok(DidDocument::new(did, VerificationMethod::ed25519(did, multibase, multibase)));
+        }
+        let Some(rest) = did.strip_prefix("did:web:") else {
+            return Err(DidError::UnsupportedMethod(did.to_string()));
+        };
+        if let Some(document) = self.hosted.as_ref().and_then(|issuer| issuer.document(did)) {
+            return Ok(document);
+        }
+
+        let mut segments = rest.split(':');
+        let domain = segments.next().unwrap_or_default().replace("%3A", ":");
+        let path: Vec<_> = segments.collect();
+        let url = if path.is_empty() {
+            format!("https://{}/.well-known/did.json", domain)
+        } else {
+            format!("https://{}/{}/did.json", domain, path.join("/"))
+        };
+        let response = self.http.get(&url).send().await
+            .map_err(|e| DidError::Resolution(format!("{}: {}", url, e)))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(DidError::NotFound(did.to_string()));
+        }
+        let document: DidDocument = response.error_for_status()
+            .map_err(|e| DidError::Resolution(format!("{}: {}", url, e)))?
+            .json().await
+            .map_err(|e| DidError::Resolution(format!("{}: {}", url, e)))?;
+        if document.id != did {
+            return Err(DidError::Resolution(format!("{} returned the document of {}", url, document.id)));
+        }
+        Ok(document)
+    }
+}
+
+/// Verify a detached JWS over `payload`; returns the signer's DID.
+pub async fn verify_detached(resolver: &dyn DidResolver, payload: &[u8], jws: &str) -> Result<String, DidError> {
+    let (header_b64, signature_b64) = jws.split_once("..").ok_or(DidError::InvalidSignature)?;
+    let header: serde_json::Value = URL_SAFE_NO_PAD.decode(header_b64).ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or(DidError::InvalidSignature)?;
+    if header["alg"] != "EdDSA" {
+        return Err(DidError::InvalidSignature);
+    }
+    let kid = header["kid"].as_str().ok_or(DidError::InvalidSignature)?;
+    let did = kid.split('#').next().unwrap_or_default();
+
+    let document = resolver.resolve(did).await?;
+    let method = document.method(kid).ok_or_else(|| DidError::NotFound(kid.to_string()))?;
+    let raw = URL_SAFE_NO_PAD.decode(signature_b64).map_err(|_| DidError::InvalidSignature)?;
+    let signature = Signature {
+        algorithm: Algorithm::Ed25519,
+        value: STANDARD.encode(&raw),
+        key_id: kid.to_string(),
+        classical_component: Some(STANDARD.encode(&raw)),
+        pq_component: None,
+    };
+    let signing_input = format!("{}.{}", header_b64, URL_SAFE_NO_PAD.encode(payload));
+    crypto().verify(signing_input.as_bytes(), &signature, &STANDARD.encode(method.public_key()?))
+        .map_err(|_| DidError::InvalidSignature)?;
+    Ok(did.to_string())
+}
+
+/// Agent card signature (A2A `AgentCardSignature`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardSignature {
+    /// Base64url JWS protected header
+    pub protected: String,
+    /// Base64url signature
+    pub signature: String,
+}
+
+/// Sign an agent card as published by Nexus; an existing `signatures`
+/// member is not covered.
+pub fn sign_agent_card(identity: &DidIdentity, card: &serde_json::Value) -> Result<CardSignature, DidError> {
+    let jws = identity.sign_json(&unsigned_card(card))?;
+    let (protected, signature) = jws.split_once("..").expect("detached JWS");
+    Ok(CardSignature { protected: protected.to_string(), signature: signature.to_string() })
+}
+
+/// Verify an agent card signature; returns the signer's DID.
+pub async fn verify_agent_card(
+    resolver: &dyn DidResolver,
+    card: &serde_json::Value,
+    signature: &CardSignature,
+) -> Result<String, DidError> {
+    let payload = canonical_json(&unsigned_card(card));
+    verify_detached(resolver, payload.as_bytes(), &format!("{}..{}", signature.protected, signature.signature)).await
+}
+
+fn unsigned_card(card: &serde_json::Value) -> serde_json::Value {
+    let mut card = card.clone();
+    if let Some(object) = card.as_object_mut() {
+        object.remove("signatures");
+    }
+    card
+}
+
+/// JSON with object keys sorted and no whitespace, so signer and verifier
+/// hash the same bytes.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut keys: Vec<_> = object.keys().collect();
+            keys.sort();
+            let members: Vec<_> = keys.iter()
+                .map(|k| format!("{}:{}", serde_json::Value::String(k.to_string()), canonical_json(&object[k.as_str()])))
+                .collect();
+            format!("{{{}}}", members.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+fn crypto() -> CryptoProvider {
+    let mut provider = CryptoProvider::new(CryptoMode::Classical);
+    provider.set_signing_algorithm(Algorithm::Ed25519);
+    provider
+}
+
+fn encode_multikey(public_key: &[u8]) -> String {
+    let mut bytes = ED25519_PUB.to_vec();
+    bytes.extend_from_slice(public_key);
+    format!("z{}", bs58::encode(bytes).into_string())
+}
+
+fn decode_multikey(multibase: &str) -> Result<Vec<u8>, DidError> {
+    let bytes = multibase.strip_prefix('z')
+        .and_then(|b58| bs58::decode(b58).into_vec().ok())
+        .ok_or(DidError::InvalidKey)?;
+    match bytes.strip_prefix(&ED25519_PUB) {
+        Some(key) if key.len() == 32 => Ok(key.to_vec()),
+        _ => Err(DidError::InvalidKey),
+    }
+}
+
+/// DID error.
+#[derive(Debug, thiserror::Error)]
+pub enum DidError {
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+
+    #[error("Unsupported DID method: {0}")]
+    UnsupportedMethod(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid key")]
+    InvalidKey,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Resolution failed: {0}")]
+    Resolution(String),
+
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+}
+
+impl From<agentkern_gate::crypto_agility::CryptoError> for DidError {
+    fn from(e: agentkern_gate::crypto_agility::CryptoError) -> Self {
+        DidError::Crypto(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_did_issuance_and_card_signing() {
+        let issuer = Arc::new(DidIssuer::new());
+        let resolver = StandardResolver::new().with_hosted(issuer.clone());
+
+        let key = issuer.issue_did_key().unwrap();
+        assert!(key.did().starts_with("did:key:z6Mk"));
+        assert_eq!(resolver.resolve(key.did()).await.unwrap(), *key.document());
+
+        let web = issuer.issue_did_web("agents.example.com:8443", &["billing-bot"]).unwrap();
+        assert_eq!(web.did(), "did:web:agents.example.com%3A8443:billing-bot");
+        assert_eq!(web.key_id(), "did:web:agents.example.com%3A8443:billing-bot#key-1");
+        assert!(issuer.document_at("agents.example.com:8443", "/billing-bot/did.json").is_some());
+
+        let card = serde_json::json!({"name": "Billing Bot", "url": "https://agents.example.com/billing", "skills": [{"id": "invoice"}]});
+        let signature = sign_agent_card(&web, &card).unwrap();
+        assert_eq!(verify_agent_card(&resolver, &card, &signature).await.unwrap(), web.did());
+
+        // Key order and attached signatures do not matter, content does
+        let reordered = serde_json::json!({"skills": [{"id": "invoice"}], "url": "https://agents.example.com/billing", "name": "Billing Bot", "signatures": [signature]});
+        assert!(verify_agent_card(&resolver, &reordered, &signature).await.is_ok());
+        let tampered = serde_json::json!({"name": "Billing Bot", "url": "https://evil.example.com", "skills": [{"id": "invoice"}]});
+        assert!(matches!(verify_agent_card(&resolver, &tampered, &signature).await, Err(DidError::InvalidSignature)));
+
+        // Signed by did:key, claiming to be the did:web key
+        let jws = key.sign(b"payload").unwrap();
+        let forged = format!("{}..{}", URL_SAFE_NO_PAD.encode(format!(r#"{{"alg":"EdDSA","kid":"{}"}}"#, web.key_id())), jws.split_once("..").unwrap().1);
+        assert!(verify_detached(&resolver, b"payload", &forged).await.is_err());
+    }
+}
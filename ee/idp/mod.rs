@@ -7,14 +7,25 @@
 //!
 //! This module federates external IDP agent IDs with AgentKern DIDs
 //! Trust score provider for Zero Trust Conditional Access
+//! Conditional access decisions from trust score, device, region and time
+//! did:key/did:web issuance and agent registration credentials
+//! Entra/Okta directory sync with credential revocation
 //!
 //! Graceful Degradation: Works with credentials, demo mode without
 
 pub mod bridge;
 pub mod trust;
+pub mod access;
 pub mod demo;
+pub mod did;
+pub mod credentials;
+pub mod sync;
 
 pub use bridge::{IdentityBridge, IdentityConfig, AgentRegistration};
 pub use trust::{TrustScoreProvider, TrustScore, TrustFactors};
+pub use access::{ConditionalAccessEngine, ConditionalAccessDecision, AccessContext, AccessRule, AccessCondition, AccessEffect, AccessOutcome, DevicePosture};
 pub use demo::{DemoIdentity, IdentityFactory};
+pub use did::{DidIssuer, DidIdentity, DidDocument, DidResolver, StandardResolver, DidError, CardSignature, sign_agent_card, verify_agent_card, verify_detached};
+pub use credentials::{CredentialIssuer, VerifiableCredential, CredentialError, RevocationRegistry, verify_credential};
+pub use sync::{DirectorySync, DirectorySource, DirectoryAgent, EntraDirectory, OktaDirectory, AgentIdentityStore, AgentIdentityRecord, MemoryIdentityStore, SyncReport};
 
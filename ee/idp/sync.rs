@@ -0,0 +1,477 @@
+//! Directory Sync
+//!
+//! Keeps the agent identity store in line with the enterprise directories:
+//! - Entra: service principals (`accountEnabled`)
+//! - Okta: applications (`ACTIVE` / `INACTIVE`)
+//!
+//! Directory objects are linked to AgentKern DIDs through a tag (Entra,
+//! `agentkern:did=<did>`) or app profile attribute (Okta, `agentkern_did`).
+//! When a linked identity is disabled, or disappears from a complete
+//! listing, its AgentKern credentials are revoked. A failed listing never
+//! counts as a deletion, and a re-enabled identity needs new credentials.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let revocations = Arc::new(RevocationRegistry::new());
+//! let issuer = CredentialIssuer::new(org_did).with_registry(revocations.clone());
+//!
+//! let sync = Arc::new(DirectorySync::new(Arc::new(MemoryIdentityStore::new()), revocations)
+//!     .with_source(Arc::new(EntraDirectory::new(entra_config, entra_secret)))
+//!     .with_source(Arc::new(OktaDirectory::new("https://acme.okta.com", okta_token))));
+//!
+//! let task = sync.clone().spawn(Duration::from_secs(300));
+//! ```
+
+use super::bridge::{IdentityConfig, IdentityError, LifecycleStatus};
+use super::credentials::RevocationRegistry;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Entra service principal tag linking it to a DID.
+pub const DID_TAG_PREFIX: &str = "agentkern:did=";
+
+/// Okta app profile attribute holding the DID.
+pub const OKTA_DID_ATTRIBUTE: &str = "agentkern_did";
+
+/// Agent identity as listed by a directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryAgent {
+    /// Directory object ID
+    pub external_id: String,
+    pub app_id: Option<String>,
+    pub display_name: String,
+    pub enabled: bool,
+    /// Linked AgentKern DID
+    pub did: Option<String>,
+}
+
+/// Directory of agent identities.
+#[async_trait]
+pub trait DirectorySource: Send + Sync {
+    /// `entra` or `okta`
+    fn provider(&self) -> &str;
+
+    /// Complete listing; anything missing is treated as deleted.
+    async fn list_agents(&self) -> Result<Vec<DirectoryAgent>, IdentityError>;
+}
+
+/// Agent identity known to AgentKern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentIdentityRecord {
+    pub did: String,
+    pub provider: String,
+    pub external_id: String,
+    pub display_name: String,
+    pub status: LifecycleStatus,
+    pub last_synced: DateTime<Utc>,
+}
+
+/// Agent identity store.
+pub trait AgentIdentityStore: Send + Sync {
+    fn get(&self, did: &str) -> Option<AgentIdentityRecord>;
+    fn by_provider(&self, provider: &str) -> Vec<AgentIdentityRecord>;
+    fn upsert(&self, record: AgentIdentityRecord);
+}
+
+/// In-memory identity store.
+#[derive(Debug, Default)]
+pub struct MemoryIdentityStore {
+    records: RwLock<HashMap<String, AgentIdentityRecord>>,
+}
+
+impl MemoryIdentityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AgentIdentityStore for MemoryIdentityStore {
+    fn get(&self, did: &str) -> Option<AgentIdentityRecord> {
+        self.records.read().unwrap().get(did).cloned()
+    }
+
+    fn by_provider(&self, provider: &str) -> Vec<AgentIdentityRecord> {
+        self.records.read().unwrap().values().filter(|r| r.provider == provider).cloned().collect()
+    }
+
+    fn upsert(&self, record: AgentIdentityRecord) {
+        self.records.write().unwrap().insert(record.did.clone(), record);
+    }
+}
+
+/// Outcome of syncing one directory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub provider: String,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub reactivated: Vec<String>,
+    pub disabled: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Revoked credential IDs
+    pub revoked_credentials: Vec<String>,
+    /// Directory objects without a linked DID
+    pub unlinked: usize,
+    pub error: Option<String>,
+}
+
+/// Scheduled directory sync.
+pub struct DirectorySync {
+    sources: Vec<Arc<dyn DirectorySource>>,
+    store: Arc<dyn AgentIdentityStore>,
+    revocations: Arc<RevocationRegistry>,
+}
+
+impl DirectorySync {
+    pub fn new(store: Arc<dyn AgentIdentityStore>, revocations: Arc<RevocationRegistry>) -> Self {
+        Self { sources: Vec::new(), store, revocations }
+    }
+
+    pub fn with_source(mut self, source: Arc<dyn DirectorySource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Sync every directory once.
+    pub async fn sync_once(&self) -> Vec<SyncReport> {
+        let mut reports = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let mut report = SyncReport { provider: source.provider().to_string(), ..Default::default() };
+            match source.list_agents().await {
+                Ok(agents) => self.reconcile(source.provider(), agents, &mut report),
+                Err(e) => report.error = Some(e.to_string()),
+            }
+            reports.push(report);
+        }
+        reports
+    }
+
+    /// Sync every `interval` until the task is aborted.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                self.sync_once().await;
+            }
+        })
+    }
+
+    fn reconcile(&self, provider: &str, agents: Vec<DirectoryAgent>, report: &mut SyncReport) {
+        let now = Utc::now();
+        let mut listed = HashSet::new();
+
+        for agent in agents {
+            let Some(did) = agent.did.clone() else {
+                report.unlinked += 1;
+                continue;
+            };
+            let status = if agent.enabled { LifecycleStatus::Active } else { LifecycleStatus::Disabled };
+            let previous = self.store.get(&did);
+            match &previous {
+                None => report.added.push(did.clone()),
+                Some(p) if status == LifecycleStatus::Active && p.status != LifecycleStatus::Active => {
+                    report.reactivated.push(did.clone())
+                }
+                Some(p) if p.display_name != agent.display_name || p.external_id != agent.external_id => {
+                    report.updated.push(did.clone())
+                }
+                _ => {}
+            }
+            if status != LifecycleStatus::Active && previous.as_ref().is_none_or(|p| p.status == LifecycleStatus::Active) {
+                report.disabled.push(did.clone());
+                report.revoked_credentials.extend(self.revocations.revoke_subject(&did));
+            }
+            self.store.upsert(AgentIdentityRecord {
+                did: did.clone(),
+                provider: provider.to_string(),
+                external_id: agent.external_id,
+                display_name: agent.display_name,
+                status,
+                last_synced: now,
+            });
+            listed.insert(did);
+        }
+
+        for mut record in self.store.by_provider(provider) {
+            if listed.contains(&record.did) || record.status == LifecycleStatus::Retired {
+                continue;
+            }
+            report.deleted.push(record.did.clone());
+            report.revoked_credentials.extend(self.revocations.revoke_subject(&record.did));
+            record.status = LifecycleStatus::Retired;
+            record.last_synced = now;
+            self.store.upsert(record);
+        }
+    }
+}
+
+/// Entra ID service principals via Microsoft Graph.
+pub struct EntraDirectory {
+    config: IdentityConfig,
+    client_secret: String,
+    login_endpoint: String,
+    http: reqwest::Client,
+}
+
+impl EntraDirectory {
+    /// `client_secret` is the resolved value of `config.client_secret_ref`.
+    pub fn new(config: IdentityConfig, client_secret: String) -> Self {
+        Self {
+            config,
+            client_secret,
+            login_endpoint: "https://login.microsoftonline.com".into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Override the token endpoint host (sovereign clouds).
+    pub fn with_login_endpoint(mut self, endpoint: &str) -> Self {
+        self.login_endpoint = endpoint.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn token(&self) -> Result<String, IdentityError> {
+        #[derive(Deserialize)]
+        struct Token {
+            access_token: String,
+        }
+        let url = format!("{}/{}/oauth2/v2.0/token", self.login_endpoint, self.config.tenant_id);
+        let response = self.http.post(&url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("scope", "https://graph.microsoft.com/.default"),
+            ])
+            .send().await
+            .map_err(|e| IdentityError::ApiError(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(IdentityError::AuthenticationFailed);
+        }
+        let token: Token = response.json().await.map_err(|e| IdentityError::ApiError(e.to_string()))?;
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl DirectorySource for EntraDirectory {
+    fn provider(&self) -> &str {
+        "entra"
+    }
+
+    async fn list_agents(&self) -> Result<Vec<DirectoryAgent>, IdentityError> {
+        #[derive(Deserialize)]
+        struct Page {
+            value: Vec<ServicePrincipal>,
+            #[serde(rename = "@odata.nextLink")]
+            next_link: Option<String>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ServicePrincipal {
+            id: String,
+            app_id: Option<String>,
+            display_name: Option<String>,
+            account_enabled: Option<bool>,
+            #[serde(default)]
+            tags: Vec<String>,
+        }
+
+        let token = self.token().await?;
+        let mut url = Some(format!(
+            "{}/servicePrincipals?$select=id,appId,displayName,accountEnabled,tags&$top=999",
+            self.config.graph_endpoint.trim_end_matches('/')
+        ));
+        let mut agents = Vec::new();
+        while let Some(next) = url.take() {
+            let response = self.http.get(&next).bearer_auth(&token).send().await
+                .map_err(|e| IdentityError::ApiError(e.to_string()))?;
+            let page: Page = check_response(response).await?.json().await
+                .map_err(|e| IdentityError::ApiError(e.to_string()))?;
+            agents.extend(page.value.into_iter().map(|sp| DirectoryAgent {
+                did: sp.tags.iter().find_map(|t| t.strip_prefix(DID_TAG_PREFIX)).map(str::to_string),
+                display_name: sp.display_name.unwrap_or_else(|| sp.id.clone()),
+                enabled: sp.account_enabled.unwrap_or(true),
+                app_id: sp.app_id,
+                external_id: sp.id,
+            }));
+            url = page.next_link;
+        }
+        Ok(agents)
+    }
+}
+
+/// Okta applications via the Apps API.
+pub struct OktaDirectory {
+    org_url: String,
+    api_token: String,
+    http: reqwest::Client,
+}
+
+impl OktaDirectory {
+    pub fn new(org_url: &str, api_token: String) -> Self {
+        Self {
+            org_url: org_url.trim_end_matches('/').to_string(),
+            api_token,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl DirectorySource for OktaDirectory {
+    fn provider(&self) -> &str {
+        "okta"
+    }
+
+    async fn list_agents(&self) -> Result<Vec<DirectoryAgent>, IdentityError> {
+        #[derive(Deserialize)]
+        struct App {
+            id: String,
+            label: Option<String>,
+            status: String,
+            #[serde(default)]
+            profile: Option<serde_json::Value>,
+        }
+
+        let mut url = Some(format!("{}/api/v1/apps?limit=200", self.org_url));
+        let mut agents = Vec::new();
+        while let Some(next) = url.take() {
+            let response = self.http.get(&next)
+                .header("Authorization", format!("SSWS {}", self.api_token))
+                .header("Accept", "application/json")
+                .send().await
+                .map_err(|e| IdentityError::ApiError(e.to_string()))?;
+            let response = check_response(response).await?;
+            url = next_link(response.headers());
+            let apps: Vec<App> = response.json().await.map_err(|e| IdentityError::ApiError(e.to_string()))?;
+            agents.extend(apps.into_iter().map(|app| DirectoryAgent {
+                did: app.profile.as_ref()
+                    .and_then(|p| p.get(OKTA_DID_ATTRIBUTE))
+                    .and_then(|d| d.as_str())
+                    .map(str::to_string),
+                display_name: app.label.unwrap_or_else(|| app.id.clone()),
+                enabled: app.status == "ACTIVE",
+                app_id: Some(app.id.clone()),
+                external_id: app.id,
+            }));
+        }
+        Ok(agents)
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, IdentityError> {
+    match response.status() {
+        s if s.is_success() => Ok(response),
+        reqwest::StatusCode::UNAUTHORIZED => Err(IdentityError::AuthenticationFailed),
+        reqwest::StatusCode::FORBIDDEN => Err(IdentityError::PermissionDenied(response.text().await.unwrap_or_default())),
+        s => Err(IdentityError::ApiError(format!("{}: {}", s, response.text().await.unwrap_or_default()))),
+    }
+}
+
+/// `rel="next"` target of an RFC 8288 `Link` header.
+fn next_link(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers.get_all(reqwest::header::LINK).iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| link.split(';').next())
+        .map(|target| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::bridge::{AgentRegistration, AgentType};
+    use super::super::credentials::CredentialIssuer;
+    use super::super::did::DidIssuer;
+    use std::sync::Mutex;
+
+    struct FakeDirectory(Mutex<Result<Vec<DirectoryAgent>, String>>);
+
+    #[async_trait]
+    impl DirectorySource for FakeDirectory {
+        fn provider(&self) -> &str {
+            "entra"
+        }
+
+        async fn list_agents(&self) -> Result<Vec<DirectoryAgent>, IdentityError> {
+            self.0.lock().unwrap().clone().map_err(IdentityError::ApiError)
+        }
+    }
+
+    fn agent(id: &str, enabled: bool) -> DirectoryAgent {
+        DirectoryAgent {
+            external_id: format!("sp-{}", id),
+            app_id: None,
+            display_name: id.to_string(),
+            enabled,
+            did: Some(format!("did:web:example.com:{}", id)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_revokes_disabled_and_deleted() {
+        let revocations = Arc::new(RevocationRegistry::new());
+        let issuer = CredentialIssuer::new(DidIssuer::new().issue_did_key().unwrap()).with_registry(revocations.clone());
+        let mut credentials = HashMap::new();
+        for id in ["billing", "support", "ops"] {
+            let registration = AgentRegistration {
+                did: format!("did:web:example.com:{}", id),
+                display_name: id.into(),
+                description: None,
+                owner: "it@example.com".into(),
+                agent_type: AgentType::Custom,
+                tags: vec![],
+            };
+            credentials.insert(id, issuer.issue(&registration).unwrap().id);
+        }
+
+        let store = Arc::new(MemoryIdentityStore::new());
+        let directory = Arc::new(FakeDirectory(Mutex::new(Ok(vec![
+            agent("billing", true), agent("support", true), agent("ops", true),
+            DirectoryAgent { did: None, ..agent("unlinked", true) },
+        ]))));
+        let sync = DirectorySync::new(store.clone(), revocations.clone()).with_source(directory.clone());
+
+        let report = &sync.sync_once().await[0];
+        assert_eq!(report.added.len(), 3);
+        assert_eq!(report.unlinked, 1);
+        assert!(report.revoked_credentials.is_empty());
+
+        // A failed listing deletes nothing
+        *directory.0.lock().unwrap() = Err("throttled".into());
+        assert!(sync.sync_once().await[0].error.is_some());
+        assert_eq!(store.get("did:web:example.com:ops").unwrap().status, LifecycleStatus::Active);
+
+        // support disabled, ops deleted
+        *directory.0.lock().unwrap() = Ok(vec![agent("billing", true), agent("support", false)]);
+        let report = &sync.sync_once().await[0];
+        assert_eq!(report.disabled, vec!["did:web:example.com:support"]);
+        assert_eq!(report.deleted, vec!["did:web:example.com:ops"]);
+        assert_eq!(report.revoked_credentials.len(), 2);
+        assert!(revocations.is_revoked(&credentials["support"]));
+        assert!(revocations.is_revoked(&credentials["ops"]));
+        assert!(!revocations.is_revoked(&credentials["billing"]));
+        assert_eq!(store.get("did:web:example.com:ops").unwrap().status, LifecycleStatus::Retired);
+
+        // Unchanged state is not reported again
+        let report = &sync.sync_once().await[0];
+        assert!(report.disabled.is_empty() && report.deleted.is_empty());
+    }
+
+    #[test]
+    fn test_okta_next_link() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append(reqwest::header::LINK, "<https://acme.okta.com/api/v1/apps?limit=200>; rel=\"self\"".parse().unwrap());
+        headers.append(reqwest::header::LINK, "<https://acme.okta.com/api/v1/apps?after=0oa1&limit=200>; rel=\"next\"".parse().unwrap());
+        assert_eq!(next_link(&headers).as_deref(), Some("https://acme.okta.com/api/v1/apps?after=0oa1&limit=200"));
+    }
+}
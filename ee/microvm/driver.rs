@@ -32,6 +32,21 @@ pub trait MicroVmDriver: Send + Sync {
     
     /// Execute command in VM.
     async fn exec(&self, instance_id: &str, command: &[String]) -> Result<ExecResult, VmError>;
+
+    /// Snapshot a running VM's memory and device state.
+    async fn snapshot(&self, instance_id: &str) -> Result<VmSnapshot, VmError> {
+        Err(VmError::Unsupported(format!("{} cannot snapshot {}", self.name(), instance_id)))
+    }
+
+    /// Host-side Unix socket for the VM's vsock device, if it has one.
+    fn vsock_path(&self, _instance_id: &str) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    /// Start a new, running VM from a snapshot.
+    async fn restore(&self, snapshot: &VmSnapshot) -> Result<VmInstance, VmError> {
+        Err(VmError::Unsupported(format!("{} cannot restore {}", self.name(), snapshot.id)))
+    }
 }
 
 /// VM technology type.
@@ -107,6 +122,19 @@ pub struct VmInstance {
     pub started_at: Option<u64>,
 }
 
+/// Saved VM memory and device state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmSnapshot {
+    /// Snapshot ID
+    pub id: String,
+    /// Guest memory file
+    pub memory_path: String,
+    /// Device state file
+    pub state_path: String,
+    /// Created at (Unix timestamp)
+    pub created_at: u64,
+}
+
 /// VM state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VmState {
@@ -152,6 +180,9 @@ pub enum VmError {
     #[error("Timeout")]
     Timeout,
     
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+    
     #[error("License error: {0}")]
     LicenseError(#[from] crate::connectors::license::LicenseError),
 }
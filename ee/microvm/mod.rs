@@ -5,6 +5,10 @@
 
 pub mod driver;
 pub mod wasm_executor;
+pub mod pool;
+pub mod vsock;
 
-pub use driver::{MicroVmDriver, VmConfig, VmInstance, VmState};
+pub use driver::{MicroVmDriver, VmConfig, VmInstance, VmState, VmSnapshot};
 pub use wasm_executor::{WasmInVm, WasmModule, ExecutionResult};
+pub use pool::{WarmPool, PoolPolicy, PoolStats, VmLease, StartKind};
+pub use vsock::{ControlChannel, ControlError, ControlTimeouts, Invocation, ResourceUsage, PROTOCOL_VERSION};
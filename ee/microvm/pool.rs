@@ -0,0 +1,419 @@
+//! MicroVM Warm Pool
+//!
+//! Pre-booted VMs so per-request isolation does not pay a cold boot:
+//! - A golden VM is booted once and snapshotted; pool VMs are restored from
+//!   the snapshot (sub-100ms on Firecracker) and fall back to a full boot
+//!   when the driver cannot restore
+//! - Pool size follows mesh load: the per-cell request rate times the
+//!   observed execution time, plus headroom
+//! - Hygiene: a VM is destroyed after N executions, after a failed
+//!   execution, or after sitting idle too long
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let pool = WarmPool::new(FirecrackerDriver::new(), vm_config, PoolPolicy::default())?;
+//! pool.prepare_snapshot().await?;
+//! pool.resize(&mesh_metrics).await?;
+//!
+//! let result = pool.run(&["wasmtime".into(), "run".into(), "/tmp/module.wasm".into()]).await?;
+//! ```
+
+use super::driver::{ExecResult, MicroVmDriver, VmConfig, VmError, VmInstance, VmSnapshot};
+use agentkern_cloud::MeshMetrics;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Pool sizing and hygiene policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolPolicy {
+    /// Never keep fewer warm VMs
+    pub min_warm: u32,
+    /// Never keep more warm VMs
+    pub max_warm: u32,
+    /// Multiplier on expected concurrency
+    pub headroom: f64,
+    /// Recycle a VM after this many executions
+    pub max_executions_per_vm: u32,
+    /// Recycle a VM idle for longer than this (seconds)
+    pub max_idle_secs: u64,
+}
+
+impl Default for PoolPolicy {
+    fn default() -> Self {
+        Self {
+            min_warm: 2,
+            max_warm: 64,
+            headroom: 1.5,
+            max_executions_per_vm: 50,
+            max_idle_secs: 600,
+        }
+    }
+}
+
+impl PoolPolicy {
+    /// Warm VMs needed for this cell's share of mesh traffic.
+    pub fn target_size(&self, metrics: &MeshMetrics, avg_execution_ms: f64) -> u32 {
+        let cells = metrics.healthy_cells.max(1) as f64;
+        // Little's law: concurrent executions = arrival rate x time in system
+        let concurrency = metrics.total_rps as f64 / cells * avg_execution_ms / 1000.0;
+        ((concurrency * self.headroom).ceil() as u32).clamp(self.min_warm, self.max_warm)
+    }
+}
+
+/// How a leased VM was started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartKind {
+    /// Taken from the warm pool
+    Warm,
+    /// Restored from the snapshot on demand
+    Restored,
+    /// Booted on demand
+    Cold,
+}
+
+/// A VM checked out of the pool.
+#[derive(Debug)]
+pub struct VmLease {
+    pub instance: VmInstance,
+    pub start: StartKind,
+    /// Time from request to usable VM
+    pub start_latency: Duration,
+    executions: u32,
+}
+
+/// Pool counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PoolStats {
+    pub warm_hits: u64,
+    pub restored_starts: u64,
+    pub cold_starts: u64,
+    pub recycled: u64,
+    /// Average time to a usable VM (ms)
+    pub avg_start_ms: f64,
+    /// Average execution time (ms)
+    pub avg_execution_ms: f64,
+}
+
+struct WarmVm {
+    instance: VmInstance,
+    executions: u32,
+    idle_since: Instant,
+}
+
+struct PoolState {
+    idle: VecDeque<WarmVm>,
+    target: u32,
+    snapshot: Option<VmSnapshot>,
+    stats: PoolStats,
+    leases: u64,
+    executions: u64,
+}
+
+/// Warm pool of microVMs for one cell.
+pub struct WarmPool<D: MicroVmDriver> {
+    driver: D,
+    config: VmConfig,
+    policy: PoolPolicy,
+    state: Mutex<PoolState>,
+}
+
+impl<D: MicroVmDriver> WarmPool<D> {
+    /// Create an empty pool; call [`WarmPool::fill`] or [`WarmPool::resize`] to boot VMs.
+    pub fn new(driver: D, config: VmConfig, policy: PoolPolicy) -> Result<Self, VmError> {
+        crate::connectors::license::check_feature_license("microvm")?;
+        Ok(Self::unlicensed(driver, config, policy))
+    }
+
+    fn unlicensed(driver: D, config: VmConfig, policy: PoolPolicy) -> Self {
+        let target = policy.min_warm;
+        Self {
+            driver,
+            config,
+            policy,
+            state: Mutex::new(PoolState {
+                idle: VecDeque::new(),
+                target,
+                snapshot: None,
+                stats: PoolStats::default(),
+                leases: 0,
+                executions: 0,
+            }),
+        }
+    }
+
+    /// Boot a golden VM and snapshot it for fast starts.
+    pub async fn prepare_snapshot(&self) -> Result<VmSnapshot, VmError> {
+        let golden = self.boot().await?;
+        let snapshot = self.driver.snapshot(&golden.id).await;
+        let _ = self.driver.destroy(&golden.id).await;
+        let snapshot = snapshot?;
+        self.state.lock().unwrap().snapshot = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    async fn boot(&self) -> Result<VmInstance, VmError> {
+        let vm = self.driver.create(&self.config).await?;
+        if let Err(e) = self.driver.start(&vm.id).await {
+            let _ = self.driver.destroy(&vm.id).await;
+            return Err(e);
+        }
+        Ok(vm)
+    }
+
+    /// Restore from the snapshot if there is one, else boot.
+    async fn launch(&self) -> Result<(VmInstance, StartKind), VmError> {
+        let snapshot = self.state.lock().unwrap().snapshot.clone();
+        if let Some(snapshot) = snapshot {
+            match self.driver.restore(&snapshot).await {
+                Ok(vm) => return Ok((vm, StartKind::Restored)),
+                Err(e) => tracing::warn!(snapshot = %snapshot.id, error = %e, "Snapshot restore failed, booting"),
+            }
+        }
+        Ok((self.boot().await?, StartKind::Cold))
+    }
+
+    /// Boot VMs until the pool is at its target size. Returns how many were added.
+    pub async fn fill(&self) -> Result<u32, VmError> {
+        let mut added = 0;
+        loop {
+            {
+                let state = self.state.lock().unwrap();
+                if state.idle.len() as u32 >= state.target {
+                    return Ok(added);
+                }
+            }
+            let (instance, _) = self.launch().await?;
+            self.state.lock().unwrap().idle.push_back(WarmVm { instance, executions: 0, idle_since: Instant::now() });
+            added += 1;
+        }
+    }
+
+    /// Resize for current mesh load, then fill or trim to the new target.
+    pub async fn resize(&self, metrics: &MeshMetrics) -> Result<u32, VmError> {
+        let (target, surplus) = {
+            let mut state = self.state.lock().unwrap();
+            state.target = self.policy.target_size(metrics, state.stats.avg_execution_ms);
+            // Trim the longest-idle VMs first
+            let excess = state.idle.len().saturating_sub(state.target as usize);
+            let surplus: Vec<_> = state.idle.drain(..excess).collect();
+            (state.target, surplus)
+        };
+        for vm in surplus {
+            let _ = self.driver.destroy(&vm.instance.id).await;
+        }
+        self.fill().await?;
+        Ok(target)
+    }
+
+    /// Check out a VM, booting one if the pool is empty.
+    pub async fn acquire(&self) -> Result<VmLease, VmError> {
+        let requested = Instant::now();
+        let max_idle = Duration::from_secs(self.policy.max_idle_secs);
+        let (warm, stale) = {
+            let mut state = self.state.lock().unwrap();
+            // Oldest idle VMs sit at the front; reuse the most recent
+            let mut stale = Vec::new();
+            while state.idle.front().is_some_and(|vm| vm.idle_since.elapsed() > max_idle) {
+                stale.extend(state.idle.pop_front());
+            }
+            let warm = state.idle.pop_back();
+            state.stats.recycled += stale.len() as u64;
+            (warm, stale)
+        };
+        for vm in stale {
+            let _ = self.driver.destroy(&vm.instance.id).await;
+        }
+
+        let (instance, start, executions) = match warm {
+            Some(vm) => (vm.instance, StartKind::Warm, vm.executions),
+            None => {
+                let (instance, start) = self.launch().await?;
+                (instance, start, 0)
+            }
+        };
+        let start_latency = requested.elapsed();
+
+        let mut state = self.state.lock().unwrap();
+        match start {
+            StartKind::Warm => state.stats.warm_hits += 1,
+            StartKind::Restored => state.stats.restored_starts += 1,
+            StartKind::Cold => state.stats.cold_starts += 1,
+        }
+        state.leases += 1;
+        let n = state.leases as f64;
+        state.stats.avg_start_ms += (start_latency.as_secs_f64() * 1000.0 - state.stats.avg_start_ms) / n;
+
+        Ok(VmLease { instance, start, start_latency, executions })
+    }
+
+    /// Return a leased VM after one execution. It is recycled when it has
+    /// failed, reached its execution limit, or the pool is already full.
+    pub async fn release(&self, mut lease: VmLease, execution_ms: u64, healthy: bool) -> Result<(), VmError> {
+        lease.executions += 1;
+        let recycle = !healthy || lease.executions >= self.policy.max_executions_per_vm;
+        let keep = {
+            let mut state = self.state.lock().unwrap();
+            state.executions += 1;
+            let n = state.executions as f64;
+            state.stats.avg_execution_ms += (execution_ms as f64 - state.stats.avg_execution_ms) / n;
+            let keep = !recycle && (state.idle.len() as u32) < state.target;
+            if keep {
+                state.idle.push_back(WarmVm { instance: lease.instance.clone(), executions: lease.executions, idle_since: Instant::now() });
+            } else if recycle {
+                state.stats.recycled += 1;
+            }
+            keep
+        };
+        if !keep {
+            self.driver.destroy(&lease.instance.id).await?;
+        }
+        if recycle {
+            self.fill().await?;
+        }
+        Ok(())
+    }
+
+    /// Run one command in a pooled VM.
+    pub async fn run(&self, command: &[String]) -> Result<ExecResult, VmError> {
+        let lease = self.acquire().await?;
+        match self.driver.exec(&lease.instance.id, command).await {
+            Ok(result) => {
+                self.release(lease, result.duration_ms, true).await?;
+                Ok(result)
+            }
+            Err(e) => {
+                let _ = self.release(lease, 0, false).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Destroy every idle VM.
+    pub async fn drain(&self) {
+        let idle: Vec<_> = self.state.lock().unwrap().idle.drain(..).collect();
+        for vm in idle {
+            let _ = self.driver.destroy(&vm.instance.id).await;
+        }
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.state.lock().unwrap().idle.len()
+    }
+
+    pub fn target(&self) -> u32 {
+        self.state.lock().unwrap().target
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::driver::{VmState, VmType};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Default)]
+    struct FakeDriver {
+        next_id: AtomicU32,
+        live: AtomicU32,
+        restores: AtomicU32,
+        snapshots: bool,
+    }
+
+    impl FakeDriver {
+        fn instance(&self) -> VmInstance {
+            self.live.fetch_add(1, Ordering::SeqCst);
+            VmInstance {
+                id: format!("vm-{}", self.next_id.fetch_add(1, Ordering::SeqCst)),
+                state: VmState::Running,
+                ip_address: None,
+                started_at: None,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MicroVmDriver for FakeDriver {
+        fn name(&self) -> &str { "fake" }
+        fn vm_type(&self) -> VmType { VmType::Custom }
+        async fn create(&self, _config: &VmConfig) -> Result<VmInstance, VmError> { Ok(self.instance()) }
+        async fn start(&self, _instance_id: &str) -> Result<(), VmError> { Ok(()) }
+        async fn stop(&self, _instance_id: &str) -> Result<(), VmError> { Ok(()) }
+        async fn destroy(&self, _instance_id: &str) -> Result<(), VmError> {
+            self.live.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        }
+        async fn state(&self, _instance_id: &str) -> Result<VmState, VmError> { Ok(VmState::Running) }
+        async fn exec(&self, _instance_id: &str, _command: &[String]) -> Result<ExecResult, VmError> {
+            Ok(ExecResult { exit_code: 0, stdout: String::new(), stderr: String::new(), duration_ms: 40 })
+        }
+        async fn snapshot(&self, instance_id: &str) -> Result<VmSnapshot, VmError> {
+            if !self.snapshots {
+                return Err(VmError::Unsupported(instance_id.to_string()));
+            }
+            Ok(VmSnapshot { id: "golden".into(), memory_path: "mem".into(), state_path: "state".into(), created_at: 0 })
+        }
+        async fn restore(&self, _snapshot: &VmSnapshot) -> Result<VmInstance, VmError> {
+            self.restores.fetch_add(1, Ordering::SeqCst);
+            Ok(self.instance())
+        }
+    }
+
+    fn metrics(total_rps: u32) -> MeshMetrics {
+        MeshMetrics { total_cells: 4, healthy_cells: 4, avg_cpu: 50, avg_memory: 50, total_rps, timestamp: 0 }
+    }
+
+    #[test]
+    fn test_target_size_follows_mesh_load() {
+        let policy = PoolPolicy::default();
+        // 400 rps over 4 cells at 100 ms = 10 concurrent, x1.5 headroom
+        assert_eq!(policy.target_size(&metrics(400), 100.0), 15);
+        assert_eq!(policy.target_size(&metrics(0), 100.0), policy.min_warm);
+        assert_eq!(policy.target_size(&metrics(1_000_000), 100.0), policy.max_warm);
+    }
+
+    #[tokio::test]
+    async fn test_warm_pool_restores_and_recycles() {
+        let driver = FakeDriver { snapshots: true, ..Default::default() };
+        let policy = PoolPolicy { min_warm: 2, max_executions_per_vm: 2, ..Default::default() };
+        let pool = WarmPool::unlicensed(driver, VmConfig::default(), policy);
+        pool.prepare_snapshot().await.unwrap();
+        assert_eq!(pool.fill().await.unwrap(), 2);
+        assert_eq!(pool.driver.restores.load(Ordering::SeqCst), 2);
+
+        let lease = pool.acquire().await.unwrap();
+        assert_eq!(lease.start, StartKind::Warm);
+        let id = lease.instance.id.clone();
+        pool.release(lease, 40, true).await.unwrap();
+
+        // Second execution on the same VM hits the limit and it is replaced
+        let lease = pool.acquire().await.unwrap();
+        assert_eq!(lease.instance.id, id);
+        pool.release(lease, 40, true).await.unwrap();
+
+        assert_eq!(pool.idle_count(), 2);
+        assert_eq!(pool.stats().recycled, 1);
+        assert_eq!(pool.driver.live.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cold_start_without_snapshot_support() {
+        let pool = WarmPool::unlicensed(FakeDriver::default(), VmConfig::default(), PoolPolicy { min_warm: 0, ..Default::default() });
+        assert!(matches!(pool.prepare_snapshot().await, Err(VmError::Unsupported(_))));
+
+        pool.run(&["true".to_string()]).await.unwrap();
+        let stats = pool.stats();
+        assert_eq!(stats.cold_starts, 1);
+        assert_eq!(stats.avg_execution_ms, 40.0);
+        // Pool target is zero, so the VM was not kept
+        assert_eq!(pool.driver.live.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,424 @@
+//! Host-Guest Control Protocol
+//!
+//! Versioned control channel between the host and the WASM executor inside
+//! a microVM, carried over vsock:
+//! - Framing: one JSON object per line, each carrying the protocol version
+//!   `v` and a request `id`; guest frames echo the id they answer
+//! - Operations: hello (version check), load module, invoke with payload,
+//!   resource usage report, kill
+//! - During an invocation the guest streams log lines before the result
+//! - Every operation has a deadline; an invocation that overruns is killed
+//! - Guest failures come back as structured [`GuestErrorCode`]s
+//!
+//! Firecracker exposes guest vsock ports through a Unix socket on the host:
+//! the host connects, sends `CONNECT <port>` and waits for `OK <port>`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut channel = ControlChannel::connect(&vsock_path, 5000, ControlTimeouts::default()).await?;
+//! channel.load_module(&module).await?;
+//! let invocation = channel
+//!     .invoke(&module.hash, &[], serde_json::json!({"query": "..."}), Duration::from_secs(5), |stream, line| {
+//!         tracing::info!(?stream, "{}", line);
+//!     })
+//!     .await?;
+//! println!("exit {} using {} ms CPU", invocation.exit_code, invocation.usage.cpu_ms);
+//! ```
+
+use super::wasm_executor::WasmModule;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::time::Instant;
+
+/// Protocol version spoken by this host.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// One line on the wire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame<T> {
+    /// Protocol version
+    pub v: u32,
+    /// Request ID (echoed by the guest)
+    pub id: u64,
+    #[serde(flatten)]
+    pub body: T,
+}
+
+/// Host → guest request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum HostRequest {
+    /// Open the session
+    Hello { version: u32 },
+    /// Load a module into the guest runtime
+    LoadModule { hash: String, entry_point: String, module_hex: String },
+    /// Run a loaded module
+    Invoke { hash: String, args: Vec<String>, payload: serde_json::Value, timeout_ms: u64 },
+    /// Report resource usage so far
+    Usage,
+    /// Abort a running invocation
+    Kill { invocation: u64 },
+}
+
+/// Output stream of a log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// Resources used in the guest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub cpu_ms: u64,
+    pub peak_memory_bytes: u64,
+    pub fuel_consumed: u64,
+}
+
+/// Failure reported by the guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestErrorCode {
+    UnsupportedVersion,
+    InvalidRequest,
+    HashMismatch,
+    ModuleNotLoaded,
+    Trap,
+    ResourceExhausted,
+    Internal,
+}
+
+/// Guest → host message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GuestMessage {
+    Hello { version: u32, runtime: String },
+    Loaded { hash: String },
+    Log { stream: LogStream, line: String },
+    Completed { exit_code: i32, output: serde_json::Value, usage: ResourceUsage },
+    Usage { usage: ResourceUsage },
+    Killed,
+    Error { code: GuestErrorCode, message: String },
+}
+
+/// Control channel errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ControlError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Protocol version mismatch: host {host}, guest {guest}")]
+    VersionMismatch { host: u32, guest: u32 },
+
+    #[error("{op} timed out after {after:?}")]
+    Timeout { op: &'static str, after: Duration },
+
+    #[error("Guest error ({code:?}): {message}")]
+    Guest { code: GuestErrorCode, message: String },
+
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Guest closed the channel")]
+    Closed,
+}
+
+/// Deadlines for control operations.
+#[derive(Debug, Clone, Copy)]
+pub struct ControlTimeouts {
+    /// Connecting while the guest boots
+    pub connect: Duration,
+    /// Hello, load, usage
+    pub request: Duration,
+    /// Extra time past an invocation's own timeout before it is killed
+    pub result_grace: Duration,
+    /// Waiting for a kill to be acknowledged
+    pub kill: Duration,
+}
+
+impl Default for ControlTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(5),
+            request: Duration::from_secs(10),
+            result_grace: Duration::from_secs(2),
+            kill: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Result of an invocation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invocation {
+    pub exit_code: i32,
+    pub output: serde_json::Value,
+    pub usage: ResourceUsage,
+    pub duration_ms: u64,
+}
+
+/// Host end of the control channel.
+pub struct ControlChannel<S> {
+    stream: BufReader<S>,
+    timeouts: ControlTimeouts,
+    next_id: u64,
+    guest_runtime: String,
+}
+
+#[cfg(unix)]
+impl ControlChannel<tokio::net::UnixStream> {
+    /// Connect through Firecracker's vsock socket to `port` in the guest and
+    /// open the session. Retries while the guest boots.
+    pub async fn connect(uds: &std::path::Path, port: u32, timeouts: ControlTimeouts) -> Result<Self, ControlError> {
+        let deadline = Instant::now() + timeouts.connect;
+        loop {
+            if let Ok(stream) = tokio::net::UnixStream::connect(uds).await {
+                let mut stream = BufReader::new(stream);
+                stream.get_mut().write_all(format!("CONNECT {}\n", port).as_bytes()).await?;
+                let mut ack = String::new();
+                // Firecracker closes the connection if nothing listens on the port yet
+                if stream.read_line(&mut ack).await.is_ok() && ack.starts_with("OK ") {
+                    return Self::open(stream.into_inner(), timeouts).await;
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(ControlError::Timeout { op: "connect", after: timeouts.connect });
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ControlChannel<S> {
+    /// Open a session on an established stream.
+    pub async fn open(stream: S, timeouts: ControlTimeouts) -> Result<Self, ControlError> {
+        let mut channel = Self { stream: BufReader::new(stream), timeouts, next_id: 0, guest_runtime: String::new() };
+        match channel.call(HostRequest::Hello { version: PROTOCOL_VERSION }, "hello").await? {
+            GuestMessage::Hello { version, runtime } if version == PROTOCOL_VERSION => {
+                channel.guest_runtime = runtime;
+                Ok(channel)
+            }
+            GuestMessage::Hello { version, .. } => Err(ControlError::VersionMismatch { host: PROTOCOL_VERSION, guest: version }),
+            other => Err(unexpected("hello", &other)),
+        }
+    }
+
+    /// Runtime the guest reported in its hello.
+    pub fn guest_runtime(&self) -> &str {
+        &self.guest_runtime
+    }
+
+    async fn send(&mut self, body: HostRequest) -> Result<u64, ControlError> {
+        self.next_id += 1;
+        let frame = Frame { v: PROTOCOL_VERSION, id: self.next_id, body };
+        let mut line = serde_json::to_string(&frame).map_err(|e| ControlError::Protocol(e.to_string()))?;
+        line.push('\n');
+        self.stream.get_mut().write_all(line.as_bytes()).await?;
+        self.stream.get_mut().flush().await?;
+        Ok(self.next_id)
+    }
+
+    /// Next frame answering `id`. Late frames for earlier requests are dropped.
+    async fn recv(&mut self, id: u64) -> Result<GuestMessage, ControlError> {
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                return Err(ControlError::Closed);
+            }
+            let frame: Frame<GuestMessage> = serde_json::from_str(&line)
+                .map_err(|e| ControlError::Protocol(format!("malformed frame: {}", e)))?;
+            if frame.v != PROTOCOL_VERSION {
+                return Err(ControlError::VersionMismatch { host: PROTOCOL_VERSION, guest: frame.v });
+            }
+            if frame.id != id {
+                tracing::debug!(expected = id, got = frame.id, "Dropping stale guest frame");
+                continue;
+            }
+            return match frame.body {
+                GuestMessage::Error { code, message } => Err(ControlError::Guest { code, message }),
+                body => Ok(body),
+            };
+        }
+    }
+
+    async fn call(&mut self, request: HostRequest, op: &'static str) -> Result<GuestMessage, ControlError> {
+        let after = self.timeouts.request;
+        let id = self.send(request).await?;
+        tokio::time::timeout(after, self.recv(id)).await.map_err(|_| ControlError::Timeout { op, after })?
+    }
+
+    /// Load a module into the guest; the guest re-hashes it.
+    pub async fn load_module(&mut self, module: &WasmModule) -> Result<(), ControlError> {
+        let request = HostRequest::LoadModule {
+            hash: module.hash.clone(),
+            entry_point: module.entry_point.clone(),
+            module_hex: hex::encode(&module.bytes),
+        };
+        match self.call(request, "load").await? {
+            GuestMessage::Loaded { hash } if hash == module.hash => Ok(()),
+            GuestMessage::Loaded { hash } => Err(ControlError::Guest {
+                code: GuestErrorCode::HashMismatch,
+                message: format!("loaded {} instead of {}", hash, module.hash),
+            }),
+            other => Err(unexpected("load", &other)),
+        }
+    }
+
+    /// Run a loaded module, passing each log line to `on_log`. An invocation
+    /// still running past `timeout` plus grace is killed.
+    pub async fn invoke(
+        &mut self,
+        hash: &str,
+        args: &[String],
+        payload: serde_json::Value,
+        timeout: Duration,
+        mut on_log: impl FnMut(LogStream, &str),
+    ) -> Result<Invocation, ControlError> {
+        let started = Instant::now();
+        let request = HostRequest::Invoke {
+            hash: hash.to_string(),
+            args: args.to_vec(),
+            payload,
+            timeout_ms: timeout.as_millis() as u64,
+        };
+        let id = self.send(request).await?;
+        let deadline = started + timeout + self.timeouts.result_grace;
+        loop {
+            let message = match tokio::time::timeout_at(deadline, self.recv(id)).await {
+                Ok(message) => message?,
+                Err(_) => {
+                    self.kill(id).await?;
+                    return Err(ControlError::Timeout { op: "invoke", after: timeout });
+                }
+            };
+            match message {
+                GuestMessage::Log { stream, line } => on_log(stream, &line),
+                GuestMessage::Completed { exit_code, output, usage } => {
+                    return Ok(Invocation { exit_code, output, usage, duration_ms: started.elapsed().as_millis() as u64 });
+                }
+                other => return Err(unexpected("invoke", &other)),
+            }
+        }
+    }
+
+    /// Resource usage of the guest so far.
+    pub async fn usage(&mut self) -> Result<ResourceUsage, ControlError> {
+        match self.call(HostRequest::Usage, "usage").await? {
+            GuestMessage::Usage { usage } => Ok(usage),
+            other => Err(unexpected("usage", &other)),
+        }
+    }
+
+    /// Abort invocation `invocation` and wait for the guest to confirm.
+    pub async fn kill(&mut self, invocation: u64) -> Result<(), ControlError> {
+        let after = self.timeouts.kill;
+        let id = self.send(HostRequest::Kill { invocation }).await?;
+        match tokio::time::timeout(after, self.recv(id)).await {
+            Ok(Ok(GuestMessage::Killed)) => Ok(()),
+            Ok(Ok(other)) => Err(unexpected("kill", &other)),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(ControlError::Timeout { op: "kill", after }),
+        }
+    }
+}
+
+fn unexpected(op: &str, message: &GuestMessage) -> ControlError {
+    ControlError::Protocol(format!("unexpected reply to {}: {:?}", op, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::DuplexStream;
+
+    /// Scripted guest: answers each request with `reply(request)`.
+    fn guest(stream: DuplexStream, reply: fn(&Frame<HostRequest>) -> Vec<Frame<GuestMessage>>) {
+        tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(stream);
+            let mut lines = BufReader::new(read).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let request: Frame<HostRequest> = serde_json::from_str(&line).unwrap();
+                for frame in reply(&request) {
+                    let mut out = serde_json::to_string(&frame).unwrap();
+                    out.push('\n');
+                    write.write_all(out.as_bytes()).await.unwrap();
+                }
+            }
+        });
+    }
+
+    fn frame(id: u64, body: GuestMessage) -> Frame<GuestMessage> {
+        Frame { v: PROTOCOL_VERSION, id, body }
+    }
+
+    fn wasmtime_guest(request: &Frame<HostRequest>) -> Vec<Frame<GuestMessage>> {
+        let id = request.id;
+        match &request.body {
+            HostRequest::Hello { .. } => vec![frame(id, GuestMessage::Hello { version: PROTOCOL_VERSION, runtime: "wasmtime".into() })],
+            HostRequest::LoadModule { hash, .. } => vec![frame(id, GuestMessage::Loaded { hash: hash.clone() })],
+            HostRequest::Invoke { timeout_ms, .. } if *timeout_ms < 100 => {
+                // Never completes; only a log line arrives
+                vec![frame(id, GuestMessage::Log { stream: LogStream::Stderr, line: "spinning".into() })]
+            }
+            HostRequest::Invoke { payload, .. } => vec![
+                // A late reply to the previous request is skipped
+                frame(id - 1, GuestMessage::Killed),
+                frame(id, GuestMessage::Log { stream: LogStream::Stdout, line: "working".into() }),
+                frame(id, GuestMessage::Completed {
+                    exit_code: 0,
+                    output: payload.clone(),
+                    usage: ResourceUsage { cpu_ms: 12, peak_memory_bytes: 1 << 20, fuel_consumed: 5000 },
+                }),
+            ],
+            HostRequest::Usage => vec![frame(id, GuestMessage::Error { code: GuestErrorCode::Internal, message: "no cgroup".into() })],
+            HostRequest::Kill { .. } => vec![frame(id, GuestMessage::Killed)],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_invoke_and_stream_logs() {
+        let (host, guest_end) = tokio::io::duplex(4096);
+        guest(guest_end, wasmtime_guest);
+        let mut channel = ControlChannel::open(host, ControlTimeouts::default()).await.unwrap();
+        assert_eq!(channel.guest_runtime(), "wasmtime");
+
+        let module = WasmModule::new(vec![0x00, 0x61, 0x73, 0x6d], "main");
+        channel.load_module(&module).await.unwrap();
+
+        let mut logs = Vec::new();
+        let payload = serde_json::json!({"n": 3});
+        let invocation = channel
+            .invoke(&module.hash, &[], payload.clone(), Duration::from_secs(1), |stream, line| logs.push((stream, line.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(invocation.output, payload);
+        assert_eq!(invocation.usage.fuel_consumed, 5000);
+        assert_eq!(logs, vec![(LogStream::Stdout, "working".to_string())]);
+
+        let err = channel.usage().await.unwrap_err();
+        assert!(matches!(err, ControlError::Guest { code: GuestErrorCode::Internal, .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overrunning_invocation_is_killed() {
+        let (host, guest_end) = tokio::io::duplex(4096);
+        guest(guest_end, wasmtime_guest);
+        let mut channel = ControlChannel::open(host, ControlTimeouts::default()).await.unwrap();
+
+        let err = channel
+            .invoke("abc", &[], serde_json::Value::Null, Duration::from_millis(50), |_, _| {})
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ControlError::Timeout { op: "invoke", .. }));
+    }
+
+    #[tokio::test]
+    async fn test_version_mismatch() {
+        let (host, guest_end) = tokio::io::duplex(4096);
+        guest(guest_end, |request| vec![Frame { v: 2, id: request.id, body: GuestMessage::Hello { version: 2, runtime: "future".into() } }]);
+        let err = ControlChannel::open(host, ControlTimeouts::default()).await.err().unwrap();
+        assert!(matches!(err, ControlError::VersionMismatch { host: 1, guest: 2 }));
+    }
+}
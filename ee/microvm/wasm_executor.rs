@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 use super::driver::{MicroVmDriver, VmConfig, VmError};
+use super::vsock::{ControlChannel, ControlError, ControlTimeouts, LogStream};
+use std::time::Duration;
 
 /// WASM-in-VM executor.
 pub struct WasmInVm<D: MicroVmDriver> {
@@ -22,6 +24,20 @@ pub struct WasmVmConfig {
     pub verify_modules: bool,
     /// Allowed host capabilities
     pub capabilities: Vec<WasiCapability>,
+    /// Guest vsock port the executor's control server listens on
+    #[serde(default = "default_control_port")]
+    pub control_port: u32,
+    /// Per-invocation timeout (ms)
+    #[serde(default = "default_invoke_timeout_ms")]
+    pub invoke_timeout_ms: u64,
+}
+
+fn default_control_port() -> u32 {
+    5000
+}
+
+fn default_invoke_timeout_ms() -> u64 {
+    30_000
 }
 
 /// WASM runtime type.
@@ -126,30 +142,76 @@ impl<D: MicroVmDriver> WasmInVm<D> {
         self.driver.start(&vm.id).await
             .map_err(|e| WasmExecutionError::VmError(e))?;
         
-        // 4. Execute WASM
-        // In production: copy module to VM, run wasmtime
-        let exec_result = self.driver.exec(&vm.id, &[
-            "wasmtime".to_string(),
-            "run".to_string(),
-            "--invoke".to_string(),
-            module.entry_point.clone(),
-            "/tmp/module.wasm".to_string(),
-        ]).await.map_err(|e| WasmExecutionError::VmError(e))?;
+        // 4. Execute WASM: over the vsock control channel when the VM has
+        // one, else by running wasmtime directly
+        let outcome = match self.driver.vsock_path(&vm.id) {
+            Some(uds) => self.invoke_over_vsock(&uds, module, args).await,
+            None => self.exec_wasmtime(&vm.id, module).await,
+        };
         
         // 5. Destroy VM (stateless)
         let _ = self.driver.destroy(&vm.id).await;
+        let exec_result = outcome?;
         
         Ok(ExecutionResult {
             module_hash: module.hash.clone(),
             exit_code: exec_result.exit_code,
             stdout: exec_result.stdout,
             stderr: exec_result.stderr,
-            execution_time_ms: exec_result.duration_ms,
+            execution_time_ms: exec_result.execution_time_ms,
             proof_log: Some(format!("Executed {} in VM {}", module.hash, vm.id)),
         })
     }
 }
 
+/// Output of one run, before it is tied to the module and VM.
+struct RunOutput {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+    execution_time_ms: u64,
+}
+
+impl<D: MicroVmDriver> WasmInVm<D> {
+    async fn exec_wasmtime(&self, instance_id: &str, module: &WasmModule) -> Result<RunOutput, WasmExecutionError> {
+        // In production: copy module to VM, run wasmtime
+        let result = self.driver.exec(instance_id, &[
+            "wasmtime".to_string(),
+            "run".to_string(),
+            "--invoke".to_string(),
+            module.entry_point.clone(),
+            "/tmp/module.wasm".to_string(),
+        ]).await.map_err(WasmExecutionError::VmError)?;
+        Ok(RunOutput {
+            exit_code: result.exit_code,
+            stdout: result.stdout,
+            stderr: result.stderr,
+            execution_time_ms: result.duration_ms,
+        })
+    }
+
+    #[cfg(unix)]
+    async fn invoke_over_vsock(&self, uds: &std::path::Path, module: &WasmModule, args: &[String]) -> Result<RunOutput, WasmExecutionError> {
+        let mut channel = ControlChannel::connect(uds, self.config.control_port, ControlTimeouts::default()).await?;
+        channel.load_module(module).await?;
+        let (mut stdout, mut stderr) = (String::new(), String::new());
+        let timeout = Duration::from_millis(self.config.invoke_timeout_ms);
+        let invocation = channel
+            .invoke(&module.hash, args, serde_json::Value::Null, timeout, |stream, line| {
+                let out = if stream == LogStream::Stdout { &mut stdout } else { &mut stderr };
+                out.push_str(line);
+                out.push('\n');
+            })
+            .await?;
+        Ok(RunOutput { exit_code: invocation.exit_code, stdout, stderr, execution_time_ms: invocation.duration_ms })
+    }
+
+    #[cfg(not(unix))]
+    async fn invoke_over_vsock(&self, _uds: &std::path::Path, _module: &WasmModule, _args: &[String]) -> Result<RunOutput, WasmExecutionError> {
+        Err(WasmExecutionError::VmError(VmError::Unsupported("vsock requires a Unix host".to_string())))
+    }
+}
+
 /// WASM execution error.
 #[derive(Debug, thiserror::Error)]
 pub enum WasmExecutionError {
@@ -164,6 +226,18 @@ pub enum WasmExecutionError {
     
     #[error("Execution timeout")]
     Timeout,
+    
+    #[error("Control channel error: {0}")]
+    Control(ControlError),
+}
+
+impl From<ControlError> for WasmExecutionError {
+    fn from(e: ControlError) -> Self {
+        match e {
+            ControlError::Timeout { op: "invoke", .. } => WasmExecutionError::Timeout,
+            e => WasmExecutionError::Control(e),
+        }
+    }
 }
 
 // ============================================================================
@@ -113,6 +113,36 @@ pub struct InferenceRequest {
     pub stop: Vec<String>,
     /// Response format
     pub response_format: Option<ResponseFormat>,
+    /// Hard limits, enforced by the adapter
+    #[serde(default)]
+    pub budget: Option<InferenceBudget>,
+}
+
+/// Per-request limits on output, latency and spend.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InferenceBudget {
+    /// Output plus reasoning tokens
+    pub max_tokens: Option<u32>,
+    /// Wall-clock time from sending the request to the last token
+    pub max_latency_ms: Option<u64>,
+    pub max_cost_usd: Option<f64>,
+}
+
+impl InferenceBudget {
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn with_max_latency_ms(mut self, max_latency_ms: u64) -> Self {
+        self.max_latency_ms = Some(max_latency_ms);
+        self
+    }
+
+    pub fn with_max_cost_usd(mut self, max_cost_usd: f64) -> Self {
+        self.max_cost_usd = Some(max_cost_usd);
+        self
+    }
 }
 
 /// Message in conversation.
@@ -161,6 +191,28 @@ pub enum ThinkingLevel {
     Maximum,
 }
 
+impl ThinkingLevel {
+    /// Reasoning token budget for this level.
+    pub fn token_budget(self) -> u32 {
+        match self {
+            ThinkingLevel::Low => 1024,
+            ThinkingLevel::Medium => 4096,
+            ThinkingLevel::High => 16384,
+            ThinkingLevel::Maximum => 32768,
+        }
+    }
+
+    /// One step less reasoning; `None` below `Low`.
+    pub fn lower(self) -> Option<Self> {
+        match self {
+            ThinkingLevel::Low => None,
+            ThinkingLevel::Medium => Some(ThinkingLevel::Low),
+            ThinkingLevel::High => Some(ThinkingLevel::Medium),
+            ThinkingLevel::Maximum => Some(ThinkingLevel::High),
+        }
+    }
+}
+
 /// Tool definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
@@ -209,11 +261,13 @@ pub enum FinishReason {
     Length,
     ToolUse,
     ContentFilter,
+    /// Stopped by the caller mid-stream
+    Cancelled,
     Error,
 }
 
 /// Token usage.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -252,6 +306,12 @@ pub enum ModelError {
     #[error("Capability not supported: {0:?}")]
     CapabilityNotSupported(ModelCapability),
     
+    #[error("Timed out after {0} ms")]
+    Timeout(u64),
+    
+    #[error("No model available: {0}")]
+    NoModelAvailable(String),
+    
     #[error("License error: {0}")]
     LicenseError(#[from] crate::connectors::license::LicenseError),
 }
@@ -290,6 +350,7 @@ mod tests {
             tools: vec![],
             stop: vec![],
             response_format: None,
+            budget: None,
         };
         
         assert!(request.system.is_some());
@@ -0,0 +1,189 @@
+//! Inference Budgets
+//!
+//! Enforces a request's `InferenceBudget` at the adapter layer:
+//! - Before sending, the request is fitted to the budget: reasoning effort
+//!   is lowered first, then output is truncated
+//! - A model that cannot fit the budget at all refuses with
+//!   `CostLimitExceeded`, and the router switches to the next provider
+//! - While streaming, output is cut off with `FinishReason::Length` once the
+//!   latency or spend limit is reached (see [`InferenceStream::with_budget`])
+//! - Actual spend is reported back to the [`CostOptimizer`], which learns
+//!   how far its estimates are off for each model family
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let request = InferenceRequest {
+//!     budget: Some(InferenceBudget::default().with_max_cost_usd(0.02).with_max_latency_ms(8_000)),
+//!     ..request
+//! };
+//! let fitted = budget::fit(&model, &request)?;
+//! for adjustment in &fitted.adjustments {
+//!     tracing::info!(?adjustment, "Request fitted to budget");
+//! }
+//! ```
+//!
+//! [`InferenceStream::with_budget`]: super::streaming::InferenceStream::with_budget
+//! [`CostOptimizer`]: super::cost_optimizer::CostOptimizer
+
+use super::adapter::{CostEstimate, FrontierModel, InferenceRequest, ModelError, ThinkingLevel};
+use serde::{Deserialize, Serialize};
+
+/// Output is never truncated below this; past it the model refuses instead.
+const MIN_OUTPUT_TOKENS: u32 = 64;
+
+/// A change made to fit a request to its budget.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BudgetAdjustment {
+    /// `to` is `None` when reasoning was switched off
+    ThinkingLowered { from: ThinkingLevel, to: Option<ThinkingLevel> },
+    OutputTruncated { from: u32, to: u32 },
+}
+
+/// A request that fits its budget on one model.
+#[derive(Debug, Clone)]
+pub struct FittedRequest {
+    pub request: InferenceRequest,
+    pub adjustments: Vec<BudgetAdjustment>,
+    /// Estimate for the fitted request
+    pub estimate: CostEstimate,
+}
+
+/// Typical time to finish reasoning at a level.
+fn expected_latency_ms(level: ThinkingLevel) -> u64 {
+    match level {
+        ThinkingLevel::Low => 2_000,
+        ThinkingLevel::Medium => 8_000,
+        ThinkingLevel::High => 30_000,
+        ThinkingLevel::Maximum => 60_000,
+    }
+}
+
+fn lower_thinking(request: &mut InferenceRequest, adjustments: &mut Vec<BudgetAdjustment>) {
+    if let Some(from) = request.thinking_budget {
+        request.thinking_budget = from.lower();
+        adjustments.push(BudgetAdjustment::ThinkingLowered { from, to: request.thinking_budget });
+    }
+}
+
+fn truncate_output(request: &mut InferenceRequest, adjustments: &mut Vec<BudgetAdjustment>, from: u32, to: u32) {
+    request.max_tokens = Some(to);
+    // Repeated halving shows up as one truncation
+    match adjustments.last_mut() {
+        Some(BudgetAdjustment::OutputTruncated { to: last, .. }) => *last = to,
+        _ => adjustments.push(BudgetAdjustment::OutputTruncated { from, to }),
+    }
+}
+
+/// Fit `request` to its budget on `model`.
+pub fn fit(model: &dyn FrontierModel, request: &InferenceRequest) -> Result<FittedRequest, ModelError> {
+    let mut fitted = request.clone();
+    let mut adjustments = Vec::new();
+    let Some(budget) = request.budget.clone() else {
+        let estimate = model.estimate_cost(&fitted);
+        return Ok(FittedRequest { request: fitted, adjustments, estimate });
+    };
+
+    // Deep reasoning cannot finish inside a short deadline
+    if let Some(max_latency_ms) = budget.max_latency_ms {
+        while fitted.thinking_budget.is_some_and(|level| expected_latency_ms(level) > max_latency_ms) {
+            lower_thinking(&mut fitted, &mut adjustments);
+        }
+    }
+
+    // Reasoning and answer share the token cap
+    if let Some(max_tokens) = budget.max_tokens {
+        while fitted.thinking_budget.is_some_and(|level| level.token_budget() >= max_tokens) {
+            lower_thinking(&mut fitted, &mut adjustments);
+        }
+        let reasoning = fitted.thinking_budget.map_or(0, ThinkingLevel::token_budget);
+        let allowed = max_tokens - reasoning;
+        let current = fitted.max_tokens.unwrap_or_else(|| {
+            model.estimate_cost(&fitted).estimated_output_tokens.saturating_sub(reasoning)
+        });
+        if current > allowed {
+            truncate_output(&mut fitted, &mut adjustments, current, allowed);
+        }
+    }
+
+    let mut estimate = model.estimate_cost(&fitted);
+    if let Some(max_cost_usd) = budget.max_cost_usd {
+        while estimate.estimated_cost_usd > max_cost_usd {
+            if fitted.thinking_budget.is_some() {
+                lower_thinking(&mut fitted, &mut adjustments);
+            } else {
+                let current = fitted.max_tokens.unwrap_or(estimate.estimated_output_tokens);
+                if current <= MIN_OUTPUT_TOKENS {
+                    return Err(ModelError::CostLimitExceeded);
+                }
+                truncate_output(&mut fitted, &mut adjustments, current, (current / 2).max(MIN_OUTPUT_TOKENS));
+            }
+            estimate = model.estimate_cost(&fitted);
+        }
+    }
+
+    Ok(FittedRequest { request: fitted, adjustments, estimate })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::adapter::*;
+    use super::super::providers::HttpModel;
+
+    fn model() -> HttpModel {
+        HttpModel::new(ModelFamily::Claude, ModelConfig {
+            model_id: "claude-test".into(),
+            endpoint: "https://api.anthropic.com".into(),
+            api_key_ref: "test-key".into(),
+            temperature: 0.7,
+            max_tokens: 4096,
+            cost_per_input_token: 0.000003,
+            cost_per_output_token: 0.000015,
+            rate_limit_rpm: None,
+        })
+    }
+
+    fn request(thinking: Option<ThinkingLevel>, budget: InferenceBudget) -> InferenceRequest {
+        InferenceRequest {
+            system: None,
+            messages: vec![Message { role: MessageRole::User, content: MessageContent::Text("Plan the migration".into()) }],
+            temperature: None,
+            max_tokens: None,
+            thinking_budget: thinking,
+            tools: vec![],
+            stop: vec![],
+            response_format: None,
+            budget: Some(budget),
+        }
+    }
+
+    #[test]
+    fn test_lowers_reasoning_before_truncating() {
+        let model = model();
+        // Maximum reasoning costs ~$0.55; Low reasoning plus full output ~$0.08
+        let fitted = fit(&model, &request(Some(ThinkingLevel::Maximum), InferenceBudget::default().with_max_cost_usd(0.1))).unwrap();
+        assert_eq!(fitted.request.thinking_budget, Some(ThinkingLevel::Low));
+        assert_eq!(fitted.request.max_tokens, None);
+        assert_eq!(fitted.adjustments.len(), 3);
+        assert!(fitted.estimate.estimated_cost_usd <= 0.1);
+
+        let fitted = fit(&model, &request(Some(ThinkingLevel::High), InferenceBudget::default().with_max_cost_usd(0.01))).unwrap();
+        assert_eq!(fitted.request.thinking_budget, None);
+        assert_eq!(fitted.adjustments.last(), Some(&BudgetAdjustment::OutputTruncated { from: 4096, to: 512 }));
+    }
+
+    #[test]
+    fn test_token_and_latency_caps() {
+        let model = model();
+        let budget = InferenceBudget::default().with_max_tokens(6000).with_max_latency_ms(10_000);
+        let fitted = fit(&model, &request(Some(ThinkingLevel::Maximum), budget)).unwrap();
+        assert_eq!(fitted.request.thinking_budget, Some(ThinkingLevel::Medium));
+        assert_eq!(fitted.request.max_tokens, Some(6000 - 4096));
+
+        // Nothing fits a budget smaller than the prompt
+        let err = fit(&model, &request(None, InferenceBudget::default().with_max_cost_usd(0.0))).unwrap_err();
+        assert!(matches!(err, ModelError::CostLimitExceeded));
+    }
+}
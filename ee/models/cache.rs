@@ -0,0 +1,373 @@
+//! Response Caching
+//!
+//! Agents repeatedly ask near-identical questions; the cache answers them
+//! without another model call:
+//! - Exact hits are keyed by a SHA-256 over tenant, model and request
+//! - Optional semantic hits: the final prompt is embedded and compared with
+//!   recent prompts that share the same model, context and parameters
+//! - Entries expire after a TTL; tenants with sensitive workloads opt out
+//!   and are neither served from nor written to the cache
+//! - Each hit is recorded in the [`CostTracker`] at no cost, with the spend
+//!   it avoided as savings
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let cache = ResponseCache::new(CachePolicy { semantic_threshold: Some(0.92), ..CachePolicy::default() })
+//!     .with_cost_tracker(tracker.clone());
+//! cache.opt_out("clinic-42");
+//!
+//! let cached = cache.get_or_infer("acme", "agent-7", &model, &request).await?;
+//! if let Some(hit) = cached.hit {
+//!     println!("{:?} hit saved ${:.4}", hit.kind, hit.saved_usd);
+//! }
+//! ```
+
+use super::adapter::{ContentPart, FinishReason, FrontierModel, InferenceRequest, MessageContent, ModelError, ModelResponse};
+use agentkern_arbiter::{CostCategory, CostTracker};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Turns prompt text into a vector for similarity search.
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Local embedder: hashed bag of words, normalised to unit length.
+#[derive(Debug, Clone, Copy)]
+pub struct HashingEmbedder {
+    pub dimensions: usize,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self { dimensions: 512 }
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+            let mut hasher = DefaultHasher::new();
+            word.to_lowercase().hash(&mut hasher);
+            vector[(hasher.finish() % self.dimensions as u64) as usize] += 1.0;
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|y| y * y).sum::<f32>().sqrt();
+    if norm == 0.0 { 0.0 } else { dot / norm }
+}
+
+/// How long and how loosely responses are reused.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePolicy {
+    pub ttl: Duration,
+    pub max_entries: usize,
+    /// Minimum cosine similarity for a semantic hit; `None` for exact only
+    pub semantic_threshold: Option<f32>,
+    /// Recent prompts searched for semantic hits
+    pub semantic_window: usize,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(3600),
+            max_entries: 10_000,
+            semantic_threshold: None,
+            semantic_window: 256,
+        }
+    }
+}
+
+/// How a cached response was found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HitKind {
+    Exact,
+    Semantic { similarity: f32 },
+}
+
+/// A response served from the cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheHit {
+    pub kind: HitKind,
+    /// What the original call cost
+    pub saved_usd: f64,
+}
+
+/// A response, and whether it came from the cache.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub response: ModelResponse,
+    pub hit: Option<CacheHit>,
+}
+
+struct Entry {
+    tenant: String,
+    response: ModelResponse,
+    stored_at: Instant,
+}
+
+struct RecentPrompt {
+    key: String,
+    context: String,
+    embedding: Vec<f32>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    recent: VecDeque<RecentPrompt>,
+    opted_out: HashSet<String>,
+}
+
+/// Cache of model responses, shared by every agent of a deployment.
+pub struct ResponseCache {
+    policy: CachePolicy,
+    embedder: Arc<dyn Embedder>,
+    tracker: Option<Arc<CostTracker>>,
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    pub fn new(policy: CachePolicy) -> Self {
+        Self {
+            policy,
+            embedder: Arc::new(HashingEmbedder::default()),
+            tracker: None,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Record hits and their savings in `tracker`.
+    pub fn with_cost_tracker(mut self, tracker: Arc<CostTracker>) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    /// Stop caching for `tenant` and drop what it already has cached.
+    pub fn opt_out(&self, tenant: &str) {
+        let mut state = self.state.lock().unwrap();
+        let CacheState { entries, recent, opted_out } = &mut *state;
+        opted_out.insert(tenant.to_string());
+        entries.retain(|_, entry| entry.tenant != tenant);
+        recent.retain(|prompt| entries.contains_key(&prompt.key));
+    }
+
+    pub fn opt_in(&self, tenant: &str) {
+        self.state.lock().unwrap().opted_out.remove(tenant);
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash of tenant, model and request; the budget does not change the answer.
+    fn key(tenant: &str, model_id: &str, request: &InferenceRequest) -> String {
+        let request = InferenceRequest { budget: None, ..request.clone() };
+        let mut hasher = Sha256::new();
+        hasher.update(tenant.as_bytes());
+        hasher.update([0]);
+        hasher.update(model_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(&request).unwrap_or_default());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Final prompt text, and the key of everything around it.
+    fn split_prompt(tenant: &str, model_id: &str, request: &InferenceRequest) -> Option<(String, String)> {
+        let last = request.messages.last()?;
+        let prompt = match &last.content {
+            MessageContent::Text(text) => text.clone(),
+            // Images and audio are not compared by their text alone
+            MessageContent::Multimodal(parts) => parts.iter()
+                .map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?
+                .join("\n"),
+        };
+        let mut context = request.clone();
+        context.messages.pop();
+        Some((prompt, Self::key(tenant, model_id, &context)))
+    }
+
+    /// Find a live cached response for the request.
+    pub fn lookup(&self, tenant: &str, model_id: &str, request: &InferenceRequest) -> Option<(ModelResponse, CacheHit)> {
+        let mut state = self.state.lock().unwrap();
+        if state.opted_out.contains(tenant) {
+            return None;
+        }
+        let ttl = self.policy.ttl;
+        state.entries.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+
+        let key = Self::key(tenant, model_id, request);
+        if let Some(entry) = state.entries.get(&key) {
+            let hit = CacheHit { kind: HitKind::Exact, saved_usd: entry.response.cost_usd };
+            return Some((entry.response.clone(), hit));
+        }
+
+        let threshold = self.policy.semantic_threshold?;
+        let (prompt, context) = Self::split_prompt(tenant, model_id, request)?;
+        let embedding = self.embedder.embed(&prompt);
+        let (similarity, key) = state.recent.iter()
+            .filter(|recent| recent.context == context && state.entries.contains_key(&recent.key))
+            .map(|recent| (cosine(&embedding, &recent.embedding), &recent.key))
+            .filter(|(similarity, _)| *similarity >= threshold)
+            .max_by(|a, b| a.0.total_cmp(&b.0))?;
+        let entry = &state.entries[key];
+        let hit = CacheHit { kind: HitKind::Semantic { similarity }, saved_usd: entry.response.cost_usd };
+        Some((entry.response.clone(), hit))
+    }
+
+    /// Cache a response; truncated, filtered and failed ones are skipped.
+    pub fn store(&self, tenant: &str, model_id: &str, request: &InferenceRequest, response: &ModelResponse) {
+        if !matches!(response.finish_reason, FinishReason::Stop | FinishReason::ToolUse) {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.opted_out.contains(tenant) {
+            return;
+        }
+
+        let key = Self::key(tenant, model_id, request);
+        if state.entries.len() >= self.policy.max_entries && !state.entries.contains_key(&key) {
+            let oldest = state.entries.iter().min_by_key(|(_, entry)| entry.stored_at).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(key.clone(), Entry {
+            tenant: tenant.to_string(),
+            response: response.clone(),
+            stored_at: Instant::now(),
+        });
+
+        if self.policy.semantic_threshold.is_some() {
+            if let Some((prompt, context)) = Self::split_prompt(tenant, model_id, request) {
+                state.recent.push_front(RecentPrompt { key, context, embedding: self.embedder.embed(&prompt) });
+                state.recent.truncate(self.policy.semantic_window);
+            }
+        }
+    }
+
+    /// Serve the request from the cache, or call `model` and cache its answer.
+    pub async fn get_or_infer(
+        &self,
+        tenant: &str,
+        agent_id: &str,
+        model: &dyn FrontierModel,
+        request: &InferenceRequest,
+    ) -> Result<CachedResponse, ModelError> {
+        if let Some((response, hit)) = self.lookup(tenant, model.model_id(), request) {
+            if let Some(tracker) = &self.tracker {
+                tracker.record(tracker.event(agent_id, CostCategory::LlmInference)
+                    .resource(model.model_id())
+                    .saved(hit.saved_usd)
+                    .meta("cache_hit", serde_json::json!(hit.kind))
+                    .build());
+            }
+            return Ok(CachedResponse { response, hit: Some(hit) });
+        }
+        let response = model.infer(request).await?;
+        self.store(tenant, model.model_id(), request, &response);
+        Ok(CachedResponse { response, hit: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::adapter::{Message, MessageRole, ModelFamily, Usage};
+    use super::super::demo::DemoModel;
+
+    fn request(context: &str, prompt: &str) -> InferenceRequest {
+        InferenceRequest {
+            system: Some(context.into()),
+            messages: vec![Message { role: MessageRole::User, content: MessageContent::Text(prompt.into()) }],
+            temperature: None,
+            max_tokens: None,
+            thinking_budget: None,
+            tools: vec![],
+            stop: vec![],
+            response_format: None,
+            budget: None,
+        }
+    }
+
+    fn response(content: &str, cost_usd: f64) -> ModelResponse {
+        ModelResponse {
+            content: content.into(),
+            tool_calls: vec![],
+            finish_reason: FinishReason::Stop,
+            usage: Usage { input_tokens: 10, output_tokens: 5, total_tokens: 15, reasoning_tokens: None },
+            cost_usd,
+            latency_ms: 800,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exact_hit_records_savings() {
+        let tracker = Arc::new(CostTracker::new());
+        let cache = ResponseCache::new(CachePolicy::default()).with_cost_tracker(tracker.clone());
+        let model = DemoModel::new(ModelFamily::Claude);
+        let question = request("You are a travel agent", "What is the capital of France?");
+        cache.store("acme", model.model_id(), &question, &response("Paris", 0.02));
+
+        let cached = cache.get_or_infer("acme", "agent-7", &model, &question).await.unwrap();
+        assert_eq!(cached.response.content, "Paris");
+        assert_eq!(cached.hit.unwrap().kind, HitKind::Exact);
+        assert_eq!(tracker.get_agent_total("agent-7"), 0.0);
+        assert!((tracker.get_agent_savings("agent-7") - 0.02).abs() < 1e-12);
+
+        // Other tenants and expired entries miss
+        let cached = cache.get_or_infer("globex", "agent-9", &model, &question).await.unwrap();
+        assert!(cached.hit.is_none());
+        let expiring = ResponseCache::new(CachePolicy { ttl: Duration::ZERO, ..CachePolicy::default() });
+        expiring.store("acme", model.model_id(), &question, &response("Paris", 0.02));
+        assert!(expiring.lookup("acme", model.model_id(), &question).is_none());
+    }
+
+    #[test]
+    fn test_semantic_hit_and_opt_out() {
+        let cache = ResponseCache::new(CachePolicy { semantic_threshold: Some(0.8), ..CachePolicy::default() });
+        cache.store("acme", "claude", &request("You are a travel agent", "What is the capital of France?"), &response("Paris", 0.02));
+
+        let (answer, hit) = cache.lookup("acme", "claude", &request("You are a travel agent", "what's the capital of France")).unwrap();
+        assert_eq!(answer.content, "Paris");
+        assert!(matches!(hit.kind, HitKind::Semantic { similarity } if similarity < 1.0));
+        // Same question in another context, or a different question, misses
+        assert!(cache.lookup("acme", "claude", &request("You are a historian", "What is the capital of France?")).is_none());
+        assert!(cache.lookup("acme", "claude", &request("You are a travel agent", "Best time to visit Norway?")).is_none());
+
+        cache.opt_out("acme");
+        assert!(cache.is_empty());
+        cache.store("acme", "claude", &request("You are a travel agent", "Hi"), &response("Hello", 0.01));
+        assert!(cache.lookup("acme", "claude", &request("You are a travel agent", "Hi")).is_none());
+    }
+}
@@ -2,9 +2,14 @@
 //!
 //! Thinking budget controls and cost optimization
 //! Works with models that support adjustable reasoning depth
+//! Learns from actual spend reported back by the adapters
 
 use serde::{Deserialize, Serialize};
-use super::adapter::{ThinkingLevel, ModelFamily, InferenceRequest, CostEstimate};
+use std::sync::Mutex;
+use super::adapter::{ThinkingLevel, ModelFamily, InferenceRequest, InferenceBudget, CostEstimate};
+
+/// Weight of the newest observation in a calibration.
+const LEARNING_RATE: f64 = 0.2;
 
 /// Thinking budget configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,11 +41,21 @@ impl Default for ThinkingBudget {
     }
 }
 
+/// How far cost estimates for a model family have been off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpendCalibration {
+    /// Moving average of actual / estimated cost
+    pub cost_ratio: f64,
+    pub samples: u32,
+}
+
 /// Cost optimizer for model routing and budget control.
 pub struct CostOptimizer {
     budget: ThinkingBudget,
     /// Model costs by family (input, output per 1K tokens)
     model_costs: std::collections::HashMap<ModelFamily, (f64, f64)>,
+    /// Learned from reported spend
+    calibrations: Mutex<std::collections::HashMap<ModelFamily, SpendCalibration>>,
 }
 
 impl CostOptimizer {
@@ -57,7 +72,7 @@ impl CostOptimizer {
         model_costs.insert(ModelFamily::Mistral, (0.0002, 0.0006));    // Mistral Medium
         model_costs.insert(ModelFamily::Custom, (0.001, 0.001));       // Default
         
-        Self { budget, model_costs }
+        Self { budget, model_costs, calibrations: Mutex::new(std::collections::HashMap::new()) }
     }
     
     /// Determine optimal thinking level for request.
@@ -110,8 +125,51 @@ impl CostOptimizer {
         score.min(10)
     }
     
-    /// Estimate cost for request with given model.
+    /// Fill in the thinking level and budget a request leaves open.
+    pub fn prepare(&self, request: &InferenceRequest) -> InferenceRequest {
+        let mut prepared = request.clone();
+        if prepared.thinking_budget.is_none() {
+            prepared.thinking_budget = Some(self.recommend_thinking_level(request));
+        }
+        let budget = prepared.budget.get_or_insert_with(InferenceBudget::default);
+        if budget.max_cost_usd.is_none() {
+            budget.max_cost_usd = Some(self.budget.max_cost_per_request);
+        }
+        prepared
+    }
+    
+    /// Estimate cost for request with given model, corrected by reported spend.
     pub fn estimate_cost(&self, family: ModelFamily, request: &InferenceRequest) -> CostEstimate {
+        let mut estimate = self.raw_estimate(family, request);
+        if let Some(calibration) = self.calibration(family) {
+            estimate.estimated_cost_usd *= calibration.cost_ratio;
+            estimate.confidence = 0.9;
+        }
+        estimate
+    }
+    
+    /// Learn from what a request actually cost.
+    pub fn record_spend(&self, family: ModelFamily, request: &InferenceRequest, actual_cost_usd: f64) {
+        let estimated = self.raw_estimate(family, request).estimated_cost_usd;
+        if estimated <= 0.0 {
+            return;
+        }
+        let ratio = actual_cost_usd / estimated;
+        let mut calibrations = self.calibrations.lock().unwrap();
+        calibrations.entry(family)
+            .and_modify(|c| {
+                c.cost_ratio += (ratio - c.cost_ratio) * LEARNING_RATE;
+                c.samples += 1;
+            })
+            .or_insert(SpendCalibration { cost_ratio: ratio, samples: 1 });
+    }
+    
+    /// Learned correction for a family, once spend has been reported.
+    pub fn calibration(&self, family: ModelFamily) -> Option<SpendCalibration> {
+        self.calibrations.lock().unwrap().get(&family).copied()
+    }
+    
+    fn raw_estimate(&self, family: ModelFamily, request: &InferenceRequest) -> CostEstimate {
         let (input_cost, output_cost) = self.model_costs
             .get(&family)
             .copied()
@@ -217,6 +275,7 @@ mod tests {
             tools: vec![],
             stop: vec![],
             response_format: None,
+            budget: None,
         };
         
         let estimate = optimizer.estimate_cost(ModelFamily::Nova, &request);
@@ -240,6 +299,7 @@ mod tests {
             tools: vec![],
             stop: vec![],
             response_format: None,
+            budget: None,
         };
         
         // Complex request
@@ -259,6 +319,7 @@ mod tests {
             }],
             stop: vec![],
             response_format: None,
+            budget: None,
         };
         
         let simple_level = optimizer.recommend_thinking_level(&simple);
@@ -267,4 +328,36 @@ mod tests {
         assert!(matches!(simple_level, ThinkingLevel::Low | ThinkingLevel::Medium));
         assert!(matches!(complex_level, ThinkingLevel::Medium | ThinkingLevel::High | ThinkingLevel::Maximum));
     }
+
+    #[test]
+    fn test_learns_from_reported_spend() {
+        let optimizer = CostOptimizer::new(ThinkingBudget::default());
+        let request = optimizer.prepare(&InferenceRequest {
+            system: None,
+            messages: vec![Message {
+                role: MessageRole::User,
+                content: MessageContent::Text("Hello".into()),
+            }],
+            temperature: None,
+            max_tokens: Some(1000),
+            thinking_budget: Some(ThinkingLevel::Medium),
+            tools: vec![],
+            stop: vec![],
+            response_format: None,
+            budget: None,
+        });
+        assert_eq!(request.budget.as_ref().unwrap().max_cost_usd, Some(0.10));
+        
+        let before = optimizer.estimate_cost(ModelFamily::Claude, &request).estimated_cost_usd;
+        // Answers come in well under max_tokens
+        optimizer.record_spend(ModelFamily::Claude, &request, before * 0.5);
+        optimizer.record_spend(ModelFamily::Claude, &request, before * 0.25);
+        
+        let calibration = optimizer.calibration(ModelFamily::Claude).unwrap();
+        assert_eq!(calibration.samples, 2);
+        assert!((calibration.cost_ratio - 0.45).abs() < 1e-9);
+        let after = optimizer.estimate_cost(ModelFamily::Claude, &request).estimated_cost_usd;
+        assert!((after - before * 0.45).abs() < 1e-12);
+        assert!(optimizer.calibration(ModelFamily::Gpt).is_none());
+    }
 }
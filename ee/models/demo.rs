@@ -4,6 +4,7 @@
 //! Used when no real API keys are configured
 
 use super::adapter::*;
+use super::streaming::{InferenceStream, StreamDelta, StreamingModel, TokenPricing};
 use crate::core::{ConnectionMode, ConnectionStatus, GracefulService, GracefulResult};
 use async_trait::async_trait;
 
@@ -118,6 +119,26 @@ impl FrontierModel for DemoModel {
     }
 }
 
+#[async_trait]
+impl StreamingModel for DemoModel {
+    /// Streams the demo response word by word.
+    async fn stream(&self, request: &InferenceRequest) -> Result<InferenceStream, ModelError> {
+        let response = self.infer(request).await?;
+        let mut deltas: Vec<StreamDelta> = response.content
+            .split_inclusive(' ')
+            .map(|word| StreamDelta::Text { text: word.to_string() })
+            .collect();
+        deltas.push(StreamDelta::Done { finish_reason: response.finish_reason, usage: response.usage.clone() });
+
+        let pricing = TokenPricing {
+            input_usd: self.config.cost_per_input_token,
+            output_usd: self.config.cost_per_output_token,
+        };
+        let deltas = futures_util::stream::iter(deltas.into_iter().map(Ok));
+        Ok(InferenceStream::new(Box::pin(deltas), &self.config.model_id, pricing, response.usage.input_tokens))
+    }
+}
+
 /// Factory to get the best available model.
 pub struct ModelFactory;
 
@@ -171,6 +192,7 @@ mod tests {
             tools: vec![],
             stop: vec![],
             response_format: None,
+            budget: None,
         };
         
         let result = model.infer(&request).await;
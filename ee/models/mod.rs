@@ -8,8 +8,18 @@
 pub mod adapter;
 pub mod cost_optimizer;
 pub mod demo;
+pub mod streaming;
+pub mod providers;
+pub mod router;
+pub mod budget;
+pub mod cache;
 
-pub use adapter::{FrontierModel, ModelConfig, ModelResponse, InferenceRequest, ModelFamily};
-pub use cost_optimizer::{ThinkingBudget, CostOptimizer};
+pub use adapter::{FrontierModel, ModelConfig, ModelResponse, InferenceRequest, InferenceBudget, ModelFamily};
+pub use cost_optimizer::{ThinkingBudget, CostOptimizer, SpendCalibration};
 pub use demo::{DemoModel, ModelFactory};
+pub use streaming::{StreamingModel, InferenceStream, StreamDelta, DeltaStream, CancelHandle, TokenPricing};
+pub use providers::{HttpModel, Dialect};
+pub use router::{ModelRouter, Sensitivity, HealthPolicy, HealthSnapshot, RoutedResponse, FailedAttempt};
 
+pub use budget::{BudgetAdjustment, FittedRequest};
+pub use cache::{ResponseCache, CachePolicy, CachedResponse, CacheHit, HitKind, Embedder, HashingEmbedder};
@@ -0,0 +1,978 @@
+//! Provider Adapters
+//!
+//! HTTP adapters for hosted frontier models. Each speaks its provider's
+//! streaming wire format and is translated into [`StreamDelta`]s:
+//! - Anthropic Messages API (server-sent events)
+//! - OpenAI Chat Completions, also served by Mistral and most Llama hosts
+//!   (server-sent events)
+//! - Gemini `streamGenerateContent` (server-sent events)
+//! - Amazon Bedrock `ConverseStream` for Nova (AWS event stream)
+//!
+//! Non-streaming [`FrontierModel::infer`] collects the same stream, so both
+//! paths share stop-reason mapping, cost accounting and budget enforcement.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let model = HttpModel::new(ModelFamily::Claude, config);
+//! let mut stream = model.stream(&request).await?;
+//! while let Some(delta) = stream.next().await { /* ... */ }
+//! ```
+
+use super::adapter::*;
+use super::budget;
+use super::cost_optimizer::CostOptimizer;
+use super::streaming::{DeltaStream, InferenceStream, StreamDelta, StreamingModel, TokenPricing};
+use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Wire format of a provider API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dialect {
+    Anthropic,
+    /// OpenAI-compatible Chat Completions
+    OpenAi,
+    Gemini,
+    /// Amazon Bedrock Converse
+    Bedrock,
+}
+
+impl Dialect {
+    /// Native API of a model family.
+    pub fn for_family(family: ModelFamily) -> Self {
+        match family {
+            ModelFamily::Claude => Self::Anthropic,
+            ModelFamily::Gemini => Self::Gemini,
+            ModelFamily::Nova => Self::Bedrock,
+            ModelFamily::Gpt | ModelFamily::Mistral | ModelFamily::Llama | ModelFamily::Custom => Self::OpenAi,
+        }
+    }
+
+    /// Map a provider stop reason onto the unified one.
+    pub fn finish_reason(&self, raw: &str) -> FinishReason {
+        match (self, raw) {
+            (Self::Anthropic | Self::Bedrock, "end_turn" | "stop_sequence" | "pause_turn") => FinishReason::Stop,
+            (Self::Anthropic | Self::Bedrock, "max_tokens" | "model_context_window_exceeded") => FinishReason::Length,
+            (Self::Anthropic | Self::Bedrock, "tool_use") => FinishReason::ToolUse,
+            (Self::Anthropic, "refusal") => FinishReason::ContentFilter,
+            (Self::Bedrock, "guardrail_intervened" | "content_filtered") => FinishReason::ContentFilter,
+            (Self::OpenAi, "stop") => FinishReason::Stop,
+            (Self::OpenAi, "length") => FinishReason::Length,
+            (Self::OpenAi, "tool_calls" | "function_call") => FinishReason::ToolUse,
+            (Self::OpenAi, "content_filter") => FinishReason::ContentFilter,
+            (Self::Gemini, "STOP") => FinishReason::Stop,
+            (Self::Gemini, "MAX_TOKENS") => FinishReason::Length,
+            (Self::Gemini, "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY") => {
+                FinishReason::ContentFilter
+            }
+            _ => FinishReason::Error,
+        }
+    }
+}
+
+fn reasoning_effort(level: ThinkingLevel) -> &'static str {
+    match level {
+        ThinkingLevel::Low => "low",
+        ThinkingLevel::Medium => "medium",
+        ThinkingLevel::High | ThinkingLevel::Maximum => "high",
+    }
+}
+
+/// Rough prompt size, four characters to a token.
+pub(crate) fn estimate_input_tokens(request: &InferenceRequest) -> u32 {
+    let system = request.system.as_deref().map_or(0, str::len);
+    let messages: usize = request.messages.iter().map(|m| text_of(&m.content).len()).sum();
+    ((system + messages) / 4) as u32
+}
+
+fn text_of(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Multimodal(parts) => parts.iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Request system prompt plus any system-role messages.
+fn system_prompt(request: &InferenceRequest) -> Option<String> {
+    let parts: Vec<String> = request.system.iter().cloned()
+        .chain(request.messages.iter().filter(|m| m.role == MessageRole::System).map(|m| text_of(&m.content)))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// Conversation turns; tool results are passed back as user turns.
+fn turns(request: &InferenceRequest) -> impl Iterator<Item = (bool, &MessageContent)> {
+    request.messages.iter()
+        .filter(|m| m.role != MessageRole::System)
+        .map(|m| (m.role == MessageRole::Assistant, &m.content))
+}
+
+/// Adapter for a hosted model reached over HTTP.
+pub struct HttpModel {
+    family: ModelFamily,
+    config: ModelConfig,
+    dialect: Dialect,
+    http: reqwest::Client,
+    optimizer: Option<Arc<CostOptimizer>>,
+}
+
+impl HttpModel {
+    pub fn new(family: ModelFamily, config: ModelConfig) -> Self {
+        Self {
+            family,
+            config,
+            dialect: Dialect::for_family(family),
+            http: reqwest::Client::new(),
+            optimizer: None,
+        }
+    }
+
+    /// Report actual spend to `optimizer` after each completed call.
+    pub fn with_optimizer(mut self, optimizer: Arc<CostOptimizer>) -> Self {
+        self.optimizer = Some(optimizer);
+        self
+    }
+
+    /// Use another wire format, e.g. Llama behind Bedrock.
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn pricing(&self) -> TokenPricing {
+        TokenPricing {
+            input_usd: self.config.cost_per_input_token,
+            output_usd: self.config.cost_per_output_token,
+        }
+    }
+
+    /// `api_key_ref` is a key, or `env:NAME` to read one from the environment.
+    fn api_key(&self) -> Result<String, ModelError> {
+        let reference = match self.config.api_key_ref.as_str() {
+            "" => "env:AGENTKERN_MODELS_API_KEY",
+            other => other,
+        };
+        match reference.strip_prefix("env:") {
+            Some(name) => std::env::var(name).map_err(|_| ModelError::ApiError(format!("{} is not set", name))),
+            None => Ok(reference.to_string()),
+        }
+    }
+
+    fn url(&self) -> String {
+        let endpoint = self.config.endpoint.trim_end_matches('/');
+        match self.dialect {
+            Dialect::Anthropic => format!("{}/v1/messages", endpoint),
+            Dialect::OpenAi => format!("{}/chat/completions", endpoint),
+            Dialect::Gemini => format!("{}/v1beta/models/{}:streamGenerateContent?alt=sse", endpoint, self.config.model_id),
+            Dialect::Bedrock => format!("{}/model/{}/converse-stream", endpoint, self.config.model_id.replace(':', "%3A")),
+        }
+    }
+
+    /// Streaming request body in the provider's format.
+    pub fn body(&self, request: &InferenceRequest) -> Value {
+        match self.dialect {
+            Dialect::Anthropic => self.anthropic_body(request),
+            Dialect::OpenAi => self.openai_body(request),
+            Dialect::Gemini => self.gemini_body(request),
+            Dialect::Bedrock => self.bedrock_body(request),
+        }
+    }
+
+    fn max_tokens(&self, request: &InferenceRequest) -> u32 {
+        request.max_tokens.unwrap_or(self.config.max_tokens)
+    }
+
+    fn temperature(&self, request: &InferenceRequest) -> f32 {
+        request.temperature.unwrap_or(self.config.temperature)
+    }
+
+    fn anthropic_body(&self, request: &InferenceRequest) -> Value {
+        let messages: Vec<Value> = turns(request)
+            .map(|(assistant, content)| {
+                let content = match content {
+                    MessageContent::Text(text) => json!(text),
+                    MessageContent::Multimodal(parts) => Value::Array(parts.iter()
+                        .filter_map(|part| match part {
+                            ContentPart::Text { text } => Some(json!({ "type": "text", "text": text })),
+                            ContentPart::Image { url, .. } => Some(json!({ "type": "image", "source": { "type": "url", "url": url } })),
+                            _ => None,
+                        })
+                        .collect()),
+                };
+                json!({ "role": if assistant { "assistant" } else { "user" }, "content": content })
+            })
+            .collect();
+
+        let mut body = json!({
+            "model": self.config.model_id,
+            "max_tokens": self.max_tokens(request),
+            "messages": messages,
+            "stream": true,
+        });
+        if let Some(system) = system_prompt(request) {
+            body["system"] = json!(system);
+        }
+        match request.thinking_budget {
+            Some(level) => {
+                let budget = level.token_budget();
+                // The answer has to fit after the reasoning
+                body["max_tokens"] = json!(self.max_tokens(request).max(budget + 1024));
+                body["thinking"] = json!({ "type": "enabled", "budget_tokens": budget });
+            }
+            // Thinking requires the default temperature
+            None => body["temperature"] = json!(self.temperature(request)),
+        }
+        if !request.stop.is_empty() {
+            body["stop_sequences"] = json!(request.stop);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = request.tools.iter()
+                .map(|t| json!({ "name": t.name, "description": t.description, "input_schema": t.parameters }))
+                .collect();
+        }
+        body
+    }
+
+    fn openai_body(&self, request: &InferenceRequest) -> Value {
+        let mut messages: Vec<Value> = system_prompt(request)
+            .map(|system| json!({ "role": "system", "content": system }))
+            .into_iter()
+            .collect();
+        messages.extend(turns(request).map(|(assistant, content)| {
+            let content = match content {
+                MessageContent::Text(text) => json!(text),
+                MessageContent::Multimodal(parts) => Value::Array(parts.iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(json!({ "type": "text", "text": text })),
+                        ContentPart::Image { url, detail } => Some(json!({ "type": "image_url", "image_url": { "url": url, "detail": detail.as_deref().unwrap_or("auto") } })),
+                        _ => None,
+                    })
+                    .collect()),
+            };
+            json!({ "role": if assistant { "assistant" } else { "user" }, "content": content })
+        }));
+
+        let mut body = json!({
+            "model": self.config.model_id,
+            "messages": messages,
+            "max_tokens": self.max_tokens(request),
+            "temperature": self.temperature(request),
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+        if !request.stop.is_empty() {
+            body["stop"] = json!(request.stop);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = request.tools.iter()
+                .map(|t| json!({ "type": "function", "function": { "name": t.name, "description": t.description, "parameters": t.parameters } }))
+                .collect();
+        }
+        match &request.response_format {
+            Some(ResponseFormat::Json) => body["response_format"] = json!({ "type": "json_object" }),
+            Some(ResponseFormat::JsonSchema(schema)) => {
+                body["response_format"] = json!({ "type": "json_schema", "json_schema": { "name": "response", "schema": schema } })
+            }
+            Some(ResponseFormat::Text) | None => {}
+        }
+        if let (Some(level), ModelFamily::Gpt) = (request.thinking_budget, self.family) {
+            body["reasoning_effort"] = json!(reasoning_effort(level));
+        }
+        body
+    }
+
+    fn gemini_body(&self, request: &InferenceRequest) -> Value {
+        let contents: Vec<Value> = turns(request)
+            .map(|(assistant, content)| {
+                let parts: Vec<Value> = match content {
+                    MessageContent::Text(text) => vec![json!({ "text": text })],
+                    MessageContent::Multimodal(parts) => parts.iter()
+                        .map(|part| match part {
+                            ContentPart::Text { text } => json!({ "text": text }),
+                            ContentPart::Image { url, .. } | ContentPart::Audio { url } | ContentPart::Video { url } => {
+                                json!({ "fileData": { "fileUri": url } })
+                            }
+                        })
+                        .collect(),
+                };
+                json!({ "role": if assistant { "model" } else { "user" }, "parts": parts })
+            })
+            .collect();
+
+        let mut generation = json!({
+            "temperature": self.temperature(request),
+            "maxOutputTokens": self.max_tokens(request),
+        });
+        if !request.stop.is_empty() {
+            generation["stopSequences"] = json!(request.stop);
+        }
+        if let Some(level) = request.thinking_budget {
+            generation["thinkingConfig"] = json!({ "thinkingBudget": level.token_budget(), "includeThoughts": true });
+        }
+        match &request.response_format {
+            Some(ResponseFormat::Json) => generation["responseMimeType"] = json!("application/json"),
+            Some(ResponseFormat::JsonSchema(schema)) => {
+                generation["responseMimeType"] = json!("application/json");
+                generation["responseJsonSchema"] = schema.clone();
+            }
+            Some(ResponseFormat::Text) | None => {}
+        }
+
+        let mut body = json!({ "contents": contents, "generationConfig": generation });
+        if let Some(system) = system_prompt(request) {
+            body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+        }
+        if !request.tools.is_empty() {
+            let declarations: Vec<Value> = request.tools.iter()
+                .map(|t| json!({ "name": t.name, "description": t.description, "parameters": t.parameters }))
+                .collect();
+            body["tools"] = json!([{ "functionDeclarations": declarations }]);
+        }
+        body
+    }
+
+    fn bedrock_body(&self, request: &InferenceRequest) -> Value {
+        // Converse takes images as bytes, not URLs, so only text is sent
+        let messages: Vec<Value> = turns(request)
+            .map(|(assistant, content)| json!({
+                "role": if assistant { "assistant" } else { "user" },
+                "content": [{ "text": text_of(content) }],
+            }))
+            .collect();
+
+        let mut inference = json!({
+            "maxTokens": self.max_tokens(request),
+            "temperature": self.temperature(request),
+        });
+        if !request.stop.is_empty() {
+            inference["stopSequences"] = json!(request.stop);
+        }
+        let mut body = json!({ "messages": messages, "inferenceConfig": inference });
+        if let Some(system) = system_prompt(request) {
+            body["system"] = json!([{ "text": system }]);
+        }
+        if !request.tools.is_empty() {
+            let tools: Vec<Value> = request.tools.iter()
+                .map(|t| json!({ "toolSpec": { "name": t.name, "description": t.description, "inputSchema": { "json": t.parameters } } }))
+                .collect();
+            body["toolConfig"] = json!({ "tools": tools });
+        }
+        if let (Some(level), ModelFamily::Nova) = (request.thinking_budget, self.family) {
+            body["additionalModelRequestFields"] = json!({
+                "reasoningConfig": { "type": "enabled", "maxReasoningEffort": reasoning_effort(level) }
+            });
+        }
+        body
+    }
+
+    fn request(&self, request: &InferenceRequest) -> Result<reqwest::RequestBuilder, ModelError> {
+        let key = self.api_key()?;
+        let builder = self.http.post(self.url()).json(&self.body(request));
+        Ok(match self.dialect {
+            Dialect::Anthropic => builder.header("x-api-key", key).header("anthropic-version", "2023-06-01"),
+            Dialect::Gemini => builder.header("x-goog-api-key", key),
+            Dialect::OpenAi | Dialect::Bedrock => builder.bearer_auth(key),
+        })
+    }
+}
+
+/// Error for a rejected request.
+fn http_error(status: reqwest::StatusCode, body: &str, input_tokens: u32) -> ModelError {
+    let lower = body.to_lowercase();
+    match status.as_u16() {
+        // 529: Anthropic overloaded
+        429 | 529 => ModelError::RateLimited,
+        400 | 413 if lower.contains("context") && (lower.contains("too long") || lower.contains("length") || lower.contains("exceed")) => {
+            ModelError::ContextTooLong(input_tokens as usize)
+        }
+        _ => ModelError::ApiError(format!("HTTP {}: {}", status, body.chars().take(300).collect::<String>())),
+    }
+}
+
+#[async_trait]
+impl FrontierModel for HttpModel {
+    fn model_id(&self) -> &str {
+        &self.config.model_id
+    }
+
+    fn family(&self) -> ModelFamily {
+        self.family
+    }
+
+    fn max_context(&self) -> usize {
+        match self.family {
+            ModelFamily::Gemini => 1_000_000,
+            ModelFamily::Nova => 300_000,
+            ModelFamily::Claude => 200_000,
+            ModelFamily::Gpt | ModelFamily::Llama | ModelFamily::Mistral => 128_000,
+            ModelFamily::Custom => 32_000,
+        }
+    }
+
+    async fn infer(&self, request: &InferenceRequest) -> Result<ModelResponse, ModelError> {
+        self.stream(request).await?.collect().await
+    }
+
+    fn estimate_cost(&self, request: &InferenceRequest) -> CostEstimate {
+        let input_tokens = estimate_input_tokens(request);
+        let output_tokens = self.max_tokens(request) + request.thinking_budget.map_or(0, ThinkingLevel::token_budget);
+        CostEstimate {
+            input_tokens,
+            estimated_output_tokens: output_tokens,
+            estimated_cost_usd: self.pricing().cost(&Usage {
+                input_tokens,
+                output_tokens,
+                total_tokens: input_tokens + output_tokens,
+                reasoning_tokens: None,
+            }),
+            confidence: 0.6,
+        }
+    }
+
+    fn supports(&self, capability: ModelCapability) -> bool {
+        use ModelFamily::*;
+        match capability {
+            ModelCapability::TextGeneration | ModelCapability::ToolUse => true,
+            ModelCapability::VisionInput => matches!(self.family, Claude | Gpt | Gemini | Nova),
+            ModelCapability::AudioInput | ModelCapability::VideoInput => matches!(self.family, Gemini | Nova),
+            ModelCapability::ThinkingBudget => matches!(self.family, Claude | Gpt | Gemini | Nova),
+            ModelCapability::LongContext => self.max_context() > 100_000,
+            ModelCapability::ImageGeneration | ModelCapability::SpeechOutput | ModelCapability::CodeInterpreter => false,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingModel for HttpModel {
+    async fn stream(&self, request: &InferenceRequest) -> Result<InferenceStream, ModelError> {
+        let started = Instant::now();
+        let fitted = budget::fit(self, request)?;
+        if !fitted.adjustments.is_empty() {
+            tracing::debug!(model_id = %self.config.model_id, adjustments = ?fitted.adjustments, "Request fitted to budget");
+        }
+        let request = &fitted.request;
+        let input_tokens = estimate_input_tokens(request);
+        let send = self.request(request)?.send();
+        let response = match request.budget.as_ref().and_then(|b| b.max_latency_ms) {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), send).await
+                .map_err(|_| ModelError::Timeout(ms))?,
+            None => send.await,
+        }
+        .map_err(|e| ModelError::ApiError(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(http_error(status, &body, input_tokens));
+        }
+        let deltas = decode(self.dialect, response.bytes_stream());
+        let mut stream = InferenceStream::new(deltas, &self.config.model_id, self.pricing(), input_tokens);
+        if let Some(budget) = &request.budget {
+            stream = stream.with_budget(budget, started);
+        }
+        if let Some(optimizer) = &self.optimizer {
+            stream = stream.with_optimizer(optimizer.clone(), self.family, request);
+        }
+        Ok(stream)
+    }
+}
+
+/// One decoded provider event: event name (if any) and JSON data.
+type Frame = (Option<String>, String);
+
+/// Splits server-sent events.
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<Frame> {
+        self.buffer.extend(chunk.iter().filter(|b| **b != b'\r'));
+        let mut frames = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let raw: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let text = String::from_utf8_lossy(&raw);
+            let mut event = None;
+            let mut data = Vec::new();
+            for line in text.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    event = Some(value.trim().to_string());
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data.push(value.strip_prefix(' ').unwrap_or(value));
+                }
+            }
+            if !data.is_empty() {
+                frames.push((event, data.join("\n")));
+            }
+        }
+        frames
+    }
+}
+
+/// CRC-32 (IEEE), as used by AWS event stream framing.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (!(crc & 1)).wrapping_add(1));
+        }
+    }
+    !crc
+}
+
+/// Splits `application/vnd.amazon.eventstream` messages.
+#[derive(Default)]
+struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<Result<Frame, ModelError>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        while self.buffer.len() >= 12 {
+            let total = u32::from_be_bytes(self.buffer[0..4].try_into().unwrap()) as usize;
+            let headers_len = u32::from_be_bytes(self.buffer[4..8].try_into().unwrap()) as usize;
+            if total < 16 + headers_len || crc32(&self.buffer[0..8]) != u32::from_be_bytes(self.buffer[8..12].try_into().unwrap()) {
+                self.buffer.clear();
+                frames.push(Err(ModelError::ApiError("corrupt event stream prelude".into())));
+                break;
+            }
+            if self.buffer.len() < total {
+                break;
+            }
+            let message: Vec<u8> = self.buffer.drain(..total).collect();
+            if crc32(&message[..total - 4]) != u32::from_be_bytes(message[total - 4..].try_into().unwrap()) {
+                frames.push(Err(ModelError::ApiError("event stream checksum mismatch".into())));
+                continue;
+            }
+            let headers = Self::headers(&message[12..12 + headers_len]);
+            let header = |name: &str| headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+            let payload = String::from_utf8_lossy(&message[12 + headers_len..total - 4]).into_owned();
+            match header(":message-type").as_deref() {
+                Some("exception") | Some("error") => {
+                    let kind = header(":exception-type").or_else(|| header(":error-code")).unwrap_or_default();
+                    frames.push(Err(match kind.as_str() {
+                        "throttlingException" | "serviceUnavailableException" => ModelError::RateLimited,
+                        _ => ModelError::ApiError(format!("{}: {}", kind, payload)),
+                    }));
+                }
+                _ => frames.push(Ok((header(":event-type"), payload))),
+            }
+        }
+        frames
+    }
+
+    /// String-valued headers; other value types are skipped.
+    fn headers(mut raw: &[u8]) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        while let Some((&name_len, rest)) = raw.split_first() {
+            let Some(name) = rest.get(..name_len as usize) else { break };
+            let name = String::from_utf8_lossy(name).into_owned();
+            let rest = &rest[name_len as usize..];
+            let Some((&kind, rest)) = rest.split_first() else { break };
+            let fixed = match kind {
+                0 | 1 => Some(0),
+                2 => Some(1),
+                3 => Some(2),
+                4 => Some(4),
+                5 | 8 => Some(8),
+                9 => Some(16),
+                _ => None,
+            };
+            raw = match fixed {
+                Some(len) => rest.get(len..).unwrap_or_default(),
+                // 6: bytes, 7: string, both u16-length prefixed
+                None => {
+                    let Some(len) = rest.get(..2).map(|l| u16::from_be_bytes([l[0], l[1]]) as usize) else { break };
+                    let Some(value) = rest.get(2..2 + len) else { break };
+                    if kind == 7 {
+                        headers.push((name, String::from_utf8_lossy(value).into_owned()));
+                    }
+                    &rest[2 + len..]
+                }
+            };
+        }
+        headers
+    }
+}
+
+/// Turns provider events into deltas, accumulating usage and stop reason.
+struct Translator {
+    dialect: Dialect,
+    usage: Usage,
+    finish_reason: Option<FinishReason>,
+    /// Gemini sends whole function calls without indices
+    tool_calls: usize,
+}
+
+impl Translator {
+    fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            usage: Usage { input_tokens: 0, output_tokens: 0, total_tokens: 0, reasoning_tokens: None },
+            finish_reason: None,
+            tool_calls: 0,
+        }
+    }
+
+    fn frame(&mut self, (event, data): Frame) -> Result<Vec<StreamDelta>, ModelError> {
+        if data == "[DONE]" {
+            return Ok(Vec::new());
+        }
+        let data: Value = serde_json::from_str(&data).map_err(|e| ModelError::ApiError(format!("invalid stream event: {}", e)))?;
+        if let Some(error) = data.get("error").filter(|e| !e.is_null()) {
+            let kind = error["type"].as_str().or(error["status"].as_str()).unwrap_or_default();
+            return Err(match kind {
+                "overloaded_error" | "rate_limit_error" | "RESOURCE_EXHAUSTED" => ModelError::RateLimited,
+                _ => ModelError::ApiError(error["message"].as_str().unwrap_or(kind).to_string()),
+            });
+        }
+        Ok(match self.dialect {
+            Dialect::Anthropic => self.anthropic(&data),
+            Dialect::OpenAi => self.openai(&data),
+            Dialect::Gemini => self.gemini(&data),
+            Dialect::Bedrock => self.bedrock(event.as_deref().unwrap_or_default(), &data),
+        })
+    }
+
+    fn stop(&mut self, raw: Option<&str>) {
+        if let Some(raw) = raw {
+            self.finish_reason = Some(self.dialect.finish_reason(raw));
+        }
+    }
+
+    fn anthropic(&mut self, data: &Value) -> Vec<StreamDelta> {
+        let index = data["index"].as_u64().unwrap_or(0) as usize;
+        match data["type"].as_str().unwrap_or_default() {
+            "message_start" => {
+                let usage = &data["message"]["usage"];
+                self.usage.input_tokens = ["input_tokens", "cache_creation_input_tokens", "cache_read_input_tokens"]
+                    .iter()
+                    .filter_map(|field| usage[field].as_u64())
+                    .sum::<u64>() as u32;
+                Vec::new()
+            }
+            "content_block_start" if data["content_block"]["type"] == "tool_use" => vec![StreamDelta::ToolCall {
+                index,
+                id: data["content_block"]["id"].as_str().map(str::to_string),
+                name: data["content_block"]["name"].as_str().map(str::to_string),
+                arguments: String::new(),
+            }],
+            "content_block_delta" => {
+                let delta = &data["delta"];
+                match delta["type"].as_str().unwrap_or_default() {
+                    "text_delta" => vec![StreamDelta::Text { text: delta["text"].as_str().unwrap_or_default().to_string() }],
+                    "thinking_delta" => vec![StreamDelta::Reasoning { text: delta["thinking"].as_str().unwrap_or_default().to_string() }],
+                    "input_json_delta" => vec![StreamDelta::ToolCall {
+                        index,
+                        id: None,
+                        name: None,
+                        arguments: delta["partial_json"].as_str().unwrap_or_default().to_string(),
+                    }],
+                    _ => Vec::new(),
+                }
+            }
+            "message_delta" => {
+                self.stop(data["delta"]["stop_reason"].as_str());
+                if let Some(output) = data["usage"]["output_tokens"].as_u64() {
+                    self.usage.output_tokens = output as u32;
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn openai(&mut self, data: &Value) -> Vec<StreamDelta> {
+        let usage = &data["usage"];
+        if usage.is_object() {
+            // completion_tokens includes reasoning; keep them apart
+            let reasoning = usage["completion_tokens_details"]["reasoning_tokens"].as_u64().map(|r| r as u32);
+            self.usage.input_tokens = usage["prompt_tokens"].as_u64().unwrap_or(0) as u32;
+            self.usage.output_tokens = (usage["completion_tokens"].as_u64().unwrap_or(0) as u32).saturating_sub(reasoning.unwrap_or(0));
+            self.usage.reasoning_tokens = reasoning;
+        }
+        let Some(choice) = data["choices"].get(0) else {
+            return Vec::new();
+        };
+        self.stop(choice["finish_reason"].as_str());
+
+        let delta = &choice["delta"];
+        let mut deltas = Vec::new();
+        if let Some(text) = delta["reasoning_content"].as_str().or(delta["reasoning"].as_str()).filter(|t| !t.is_empty()) {
+            deltas.push(StreamDelta::Reasoning { text: text.to_string() });
+        }
+        if let Some(text) = delta["content"].as_str().filter(|t| !t.is_empty()) {
+            deltas.push(StreamDelta::Text { text: text.to_string() });
+        }
+        for call in delta["tool_calls"].as_array().into_iter().flatten() {
+            deltas.push(StreamDelta::ToolCall {
+                index: call["index"].as_u64().unwrap_or(0) as usize,
+                id: call["id"].as_str().map(str::to_string),
+                name: call["function"]["name"].as_str().map(str::to_string),
+                arguments: call["function"]["arguments"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        deltas
+    }
+
+    fn gemini(&mut self, data: &Value) -> Vec<StreamDelta> {
+        let usage = &data["usageMetadata"];
+        if usage.is_object() {
+            self.usage.input_tokens = usage["promptTokenCount"].as_u64().unwrap_or(0) as u32;
+            self.usage.output_tokens = usage["candidatesTokenCount"].as_u64().unwrap_or(0) as u32;
+            self.usage.reasoning_tokens = usage["thoughtsTokenCount"].as_u64().map(|t| t as u32);
+        }
+        let Some(candidate) = data["candidates"].get(0) else {
+            return Vec::new();
+        };
+        self.stop(candidate["finishReason"].as_str());
+
+        let mut deltas = Vec::new();
+        for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+            if let Some(call) = part.get("functionCall") {
+                deltas.push(StreamDelta::ToolCall {
+                    index: self.tool_calls,
+                    id: call["id"].as_str().map(str::to_string),
+                    name: call["name"].as_str().map(str::to_string),
+                    arguments: call["args"].to_string(),
+                });
+                self.tool_calls += 1;
+            } else if let Some(text) = part["text"].as_str() {
+                deltas.push(match part["thought"].as_bool() {
+                    Some(true) => StreamDelta::Reasoning { text: text.to_string() },
+                    _ => StreamDelta::Text { text: text.to_string() },
+                });
+            }
+        }
+        deltas
+    }
+
+    fn bedrock(&mut self, event: &str, data: &Value) -> Vec<StreamDelta> {
+        let index = data["contentBlockIndex"].as_u64().unwrap_or(0) as usize;
+        match event {
+            "contentBlockStart" => {
+                let tool = &data["start"]["toolUse"];
+                match tool.is_object() {
+                    true => vec![StreamDelta::ToolCall {
+                        index,
+                        id: tool["toolUseId"].as_str().map(str::to_string),
+                        name: tool["name"].as_str().map(str::to_string),
+                        arguments: String::new(),
+                    }],
+                    false => Vec::new(),
+                }
+            }
+            "contentBlockDelta" => {
+                let delta = &data["delta"];
+                if let Some(text) = delta["text"].as_str() {
+                    vec![StreamDelta::Text { text: text.to_string() }]
+                } else if let Some(input) = delta["toolUse"]["input"].as_str() {
+                    vec![StreamDelta::ToolCall { index, id: None, name: None, arguments: input.to_string() }]
+                } else if let Some(text) = delta["reasoningContent"]["text"].as_str() {
+                    vec![StreamDelta::Reasoning { text: text.to_string() }]
+                } else {
+                    Vec::new()
+                }
+            }
+            "messageStop" => {
+                self.stop(data["stopReason"].as_str());
+                Vec::new()
+            }
+            "metadata" => {
+                self.usage.input_tokens = data["usage"]["inputTokens"].as_u64().unwrap_or(0) as u32;
+                self.usage.output_tokens = data["usage"]["outputTokens"].as_u64().unwrap_or(0) as u32;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Final delta once the provider closes the stream.
+    fn done(&mut self) -> StreamDelta {
+        let mut finish_reason = self.finish_reason.unwrap_or(FinishReason::Error);
+        // Gemini reports STOP after a function call
+        if finish_reason == FinishReason::Stop && self.tool_calls > 0 {
+            finish_reason = FinishReason::ToolUse;
+        }
+        let mut usage = self.usage.clone();
+        usage.total_tokens = usage.input_tokens + usage.output_tokens + usage.reasoning_tokens.unwrap_or(0);
+        StreamDelta::Done { finish_reason, usage }
+    }
+}
+
+enum Decoder {
+    Sse(SseDecoder),
+    EventStream(EventStreamDecoder),
+}
+
+impl Decoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<Result<Frame, ModelError>> {
+        match self {
+            Self::Sse(decoder) => decoder.push(chunk).into_iter().map(Ok).collect(),
+            Self::EventStream(decoder) => decoder.push(chunk),
+        }
+    }
+}
+
+struct DecodeState<S> {
+    bytes: S,
+    decoder: Decoder,
+    translator: Translator,
+    pending: VecDeque<Result<StreamDelta, ModelError>>,
+    ended: bool,
+}
+
+/// Provider byte stream to deltas, ending with `Done` when the body closes.
+fn decode<S, B>(dialect: Dialect, bytes: S) -> DeltaStream
+where
+    S: Stream<Item = Result<B, reqwest::Error>> + Send + Unpin + 'static,
+    B: AsRef<[u8]>,
+{
+    let state = DecodeState {
+        bytes,
+        decoder: match dialect {
+            Dialect::Bedrock => Decoder::EventStream(EventStreamDecoder::default()),
+            _ => Decoder::Sse(SseDecoder::default()),
+        },
+        translator: Translator::new(dialect),
+        pending: VecDeque::new(),
+        ended: false,
+    };
+    Box::pin(futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((item, state));
+            }
+            if state.ended {
+                return None;
+            }
+            match state.bytes.next().await {
+                Some(Ok(chunk)) => {
+                    for frame in state.decoder.push(chunk.as_ref()) {
+                        match frame.and_then(|frame| state.translator.frame(frame)) {
+                            Ok(deltas) => state.pending.extend(deltas.into_iter().map(Ok)),
+                            Err(e) => {
+                                state.pending.push_back(Err(e));
+                                state.ended = true;
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    state.pending.push_back(Err(ModelError::ApiError(e.to_string())));
+                    state.ended = true;
+                }
+                None => {
+                    state.pending.push_back(Ok(state.translator.done()));
+                    state.ended = true;
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(dialect: Dialect, chunks: Vec<Vec<u8>>) -> Vec<StreamDelta> {
+        let bytes = futures_util::stream::iter(chunks.into_iter().map(Ok::<_, reqwest::Error>));
+        decode(dialect, bytes).map(|d| d.unwrap()).collect().await
+    }
+
+    fn done(deltas: &[StreamDelta]) -> (FinishReason, Usage) {
+        match deltas.last() {
+            Some(StreamDelta::Done { finish_reason, usage }) => (*finish_reason, usage.clone()),
+            other => panic!("no Done: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_dialects_map_to_unified_deltas() {
+        let anthropic = concat!(
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":12}}}\n\n",
+            "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\n",
+            "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"tu_1\",\"name\":\"lookup\"}}\n\n",
+            "event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":7}}\n\n",
+        );
+        // Split mid-event to exercise buffering
+        let (a, b) = anthropic.as_bytes().split_at(40);
+        let deltas = run(Dialect::Anthropic, vec![a.to_vec(), b.to_vec()]).await;
+        assert_eq!(deltas[0], StreamDelta::Text { text: "Hi".into() });
+        assert!(matches!(&deltas[1], StreamDelta::ToolCall { index: 1, name: Some(n), .. } if n == "lookup"));
+        let (reason, usage) = done(&deltas);
+        assert_eq!((reason, usage.input_tokens, usage.output_tokens), (FinishReason::ToolUse, 12, 7));
+
+        let openai = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"length\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":9,\"completion_tokens_details\":{\"reasoning_tokens\":4}}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let deltas = run(Dialect::OpenAi, vec![openai.as_bytes().to_vec()]).await;
+        let (reason, usage) = done(&deltas);
+        assert_eq!((reason, usage.output_tokens, usage.reasoning_tokens, usage.total_tokens), (FinishReason::Length, 5, Some(4), 14));
+
+        let gemini = "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"plan\",\"thought\":true},{\"functionCall\":{\"name\":\"search\",\"args\":{\"q\":\"x\"}}}]},\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"promptTokenCount\":3,\"candidatesTokenCount\":2}}\n\n";
+        let deltas = run(Dialect::Gemini, vec![gemini.as_bytes().to_vec()]).await;
+        assert_eq!(deltas[0], StreamDelta::Reasoning { text: "plan".into() });
+        assert_eq!(done(&deltas).0, FinishReason::ToolUse);
+
+        let overloaded = "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"busy\"}}\n\n";
+        let bytes = futures_util::stream::iter(vec![Ok::<_, reqwest::Error>(overloaded.as_bytes().to_vec())]);
+        let results: Vec<_> = decode(Dialect::Anthropic, bytes).collect().await;
+        assert!(matches!(results.as_slice(), [Err(ModelError::RateLimited)]));
+    }
+
+    fn event_message(event_type: &str, payload: &str) -> Vec<u8> {
+        let mut headers = Vec::new();
+        for (name, value) in [(":message-type", "event"), (":event-type", event_type)] {
+            headers.push(name.len() as u8);
+            headers.extend_from_slice(name.as_bytes());
+            headers.push(7);
+            headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            headers.extend_from_slice(value.as_bytes());
+        }
+        let total = (16 + headers.len() + payload.len()) as u32;
+        let mut message = total.to_be_bytes().to_vec();
+        message.extend_from_slice(&(headers.len() as u32).to_be_bytes());
+        message.extend_from_slice(&crc32(&message).to_be_bytes());
+        message.extend_from_slice(&headers);
+        message.extend_from_slice(payload.as_bytes());
+        message.extend_from_slice(&crc32(&message).to_be_bytes());
+        message
+    }
+
+    #[tokio::test]
+    async fn test_bedrock_event_stream() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        let mut body = event_message("contentBlockDelta", r#"{"contentBlockIndex":0,"delta":{"text":"Bonjour"}}"#);
+        body.extend(event_message("messageStop", r#"{"stopReason":"max_tokens"}"#));
+        body.extend(event_message("metadata", r#"{"usage":{"inputTokens":4,"outputTokens":2}}"#));
+        let chunks = body.chunks(7).map(<[u8]>::to_vec).collect();
+
+        let deltas = run(Dialect::Bedrock, chunks).await;
+        assert_eq!(deltas[0], StreamDelta::Text { text: "Bonjour".into() });
+        let (reason, usage) = done(&deltas);
+        assert_eq!((reason, usage.total_tokens), (FinishReason::Length, 6));
+    }
+}
@@ -0,0 +1,454 @@
+//! Model Routing
+//!
+//! Fallback routing across model adapters:
+//! - Each task sensitivity has its own ordered fallback chain, so a
+//!   regulated task never falls back to a model it may not use
+//! - Every call is timed out and recorded in a rolling health window
+//!   (error rate, latency) per model
+//! - Unhealthy models are demoted to the end of every chain for a while,
+//!   longer each time they relapse, and promoted back once it expires
+//! - A model that cannot fit the request's budget passes it to the next one
+//!   in the chain
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let router = ModelRouter::new(HealthPolicy::default())
+//!     .with_model(claude)
+//!     .with_model(gpt)
+//!     .with_model(local_llama)
+//!     .with_route(Sensitivity::Internal, &["claude-sonnet", "gpt-4o", "llama-local"])
+//!     .with_route(Sensitivity::Regulated, &["llama-local"]);
+//!
+//! let routed = router.infer(&request, Sensitivity::Internal).await?;
+//! println!("answered by {} after {} failed attempts", routed.model_id, routed.failed.len());
+//! ```
+
+use super::adapter::{InferenceRequest, ModelCapability, ModelError, ModelResponse};
+use super::streaming::{InferenceStream, StreamingModel};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How sensitive the data in a task is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sensitivity {
+    Public,
+    Internal,
+    Confidential,
+    /// Regulated data, e.g. health or payment records
+    Regulated,
+}
+
+/// When a model counts as unhealthy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthPolicy {
+    /// Per-attempt timeout
+    pub timeout: Duration,
+    /// Rolling window for error rate and latency
+    pub window: Duration,
+    /// Samples needed before rates are judged
+    pub min_samples: usize,
+    pub max_error_rate: f64,
+    /// Average latency of successful calls
+    pub max_latency: Duration,
+    /// Demote regardless of rates after this many failures in a row
+    pub max_consecutive_failures: u32,
+    /// First demotion; doubles on each relapse
+    pub demotion: Duration,
+    pub max_demotion: Duration,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            window: Duration::from_secs(300),
+            min_samples: 5,
+            max_error_rate: 0.5,
+            max_latency: Duration::from_secs(30),
+            max_consecutive_failures: 3,
+            demotion: Duration::from_secs(30),
+            max_demotion: Duration::from_secs(900),
+        }
+    }
+}
+
+/// Current health of one model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthSnapshot {
+    pub samples: usize,
+    pub error_rate: f64,
+    pub average_latency: Option<Duration>,
+    /// Remaining demotion
+    pub demoted_for: Option<Duration>,
+}
+
+#[derive(Default)]
+struct ModelHealth {
+    /// (when, succeeded, latency)
+    samples: VecDeque<(Instant, bool, Duration)>,
+    consecutive_failures: u32,
+    demoted_until: Option<Instant>,
+    /// Demotions since the model last stayed healthy
+    relapses: u32,
+}
+
+impl ModelHealth {
+    fn is_demoted(&self, now: Instant) -> bool {
+        self.demoted_until.is_some_and(|until| until > now)
+    }
+
+    fn record(&mut self, ok: bool, latency: Duration, policy: &HealthPolicy, now: Instant) {
+        while self.samples.front().is_some_and(|(at, _, _)| now.duration_since(*at) > policy.window) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, ok, latency));
+
+        if ok {
+            self.consecutive_failures = 0;
+            // Healthy again after a demotion ran out
+            if self.demoted_until.is_some_and(|until| until <= now) {
+                self.demoted_until = None;
+                self.relapses = 0;
+            }
+            if !self.breached(policy) {
+                return;
+            }
+        } else {
+            self.consecutive_failures += 1;
+            if self.consecutive_failures < policy.max_consecutive_failures && !self.breached(policy) {
+                return;
+            }
+        }
+        if !self.is_demoted(now) {
+            let backoff = policy.demotion.saturating_mul(1 << self.relapses.min(16));
+            self.demoted_until = Some(now + backoff.min(policy.max_demotion));
+            self.relapses += 1;
+            self.consecutive_failures = 0;
+            // Judge the model afresh once it is back
+            self.samples.clear();
+        }
+    }
+
+    fn breached(&self, policy: &HealthPolicy) -> bool {
+        if self.samples.len() < policy.min_samples {
+            return false;
+        }
+        let snapshot = self.snapshot(Instant::now());
+        snapshot.error_rate > policy.max_error_rate
+            || snapshot.average_latency.is_some_and(|latency| latency > policy.max_latency)
+    }
+
+    fn snapshot(&self, now: Instant) -> HealthSnapshot {
+        let failures = self.samples.iter().filter(|(_, ok, _)| !ok).count();
+        let latencies: Vec<Duration> = self.samples.iter().filter(|(_, ok, _)| *ok).map(|(_, _, l)| *l).collect();
+        HealthSnapshot {
+            samples: self.samples.len(),
+            error_rate: match self.samples.len() {
+                0 => 0.0,
+                n => failures as f64 / n as f64,
+            },
+            average_latency: (!latencies.is_empty()).then(|| latencies.iter().sum::<Duration>() / latencies.len() as u32),
+            demoted_for: self.demoted_until.filter(|until| *until > now).map(|until| until - now),
+        }
+    }
+}
+
+/// A failed attempt before the model that answered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedAttempt {
+    pub model_id: String,
+    pub error: String,
+}
+
+/// Response and the model that produced it.
+#[derive(Debug, Clone)]
+pub struct RoutedResponse {
+    pub model_id: String,
+    pub response: ModelResponse,
+    pub failed: Vec<FailedAttempt>,
+}
+
+/// Routes requests along fallback chains, skipping unhealthy models.
+pub struct ModelRouter {
+    models: HashMap<String, Arc<dyn StreamingModel>>,
+    routes: HashMap<Sensitivity, Vec<String>>,
+    policy: HealthPolicy,
+    health: Mutex<HashMap<String, ModelHealth>>,
+}
+
+impl ModelRouter {
+    pub fn new(policy: HealthPolicy) -> Self {
+        Self {
+            models: HashMap::new(),
+            routes: HashMap::new(),
+            policy,
+            health: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a model under its `model_id`.
+    pub fn with_model(mut self, model: Arc<dyn StreamingModel>) -> Self {
+        self.models.insert(model.model_id().to_string(), model);
+        self
+    }
+
+    /// Ordered fallback chain for tasks of `sensitivity`. Only these models
+    /// are ever tried for such tasks.
+    pub fn with_route(mut self, sensitivity: Sensitivity, model_ids: &[&str]) -> Self {
+        self.routes.insert(sensitivity, model_ids.iter().map(|id| id.to_string()).collect());
+        self
+    }
+
+    pub fn health(&self, model_id: &str) -> Option<HealthSnapshot> {
+        self.health.lock().unwrap().get(model_id).map(|h| h.snapshot(Instant::now()))
+    }
+
+    /// Models to try, in order: healthy ones in chain order, then demoted
+    /// ones as a last resort, soonest to recover first.
+    pub fn plan(&self, request: &InferenceRequest, sensitivity: Sensitivity) -> Result<Vec<Arc<dyn StreamingModel>>, ModelError> {
+        let chain = self.routes.get(&sensitivity)
+            .ok_or_else(|| ModelError::NoModelAvailable(format!("no route for {:?} tasks", sensitivity)))?;
+        let now = Instant::now();
+        let health = self.health.lock().unwrap();
+        let demoted_until = |id: &str| health.get(id).filter(|h| h.is_demoted(now)).and_then(|h| h.demoted_until);
+
+        let mut candidates: Vec<(Option<Instant>, usize, Arc<dyn StreamingModel>)> = chain.iter()
+            .enumerate()
+            .filter_map(|(position, id)| self.models.get(id).map(|model| (demoted_until(id), position, model.clone())))
+            .filter(|(_, _, model)| request.tools.is_empty() || model.supports(ModelCapability::ToolUse))
+            .collect();
+        // `None` sorts first: healthy models keep chain order ahead of demoted ones
+        candidates.sort_by_key(|(until, position, _)| (*until, *position));
+        if candidates.is_empty() {
+            return Err(ModelError::NoModelAvailable(format!("no usable model for {:?} tasks", sensitivity)));
+        }
+        Ok(candidates.into_iter().map(|(_, _, model)| model).collect())
+    }
+
+    fn record(&self, model_id: &str, ok: bool, latency: Duration) {
+        let mut health = self.health.lock().unwrap();
+        let entry = health.entry(model_id.to_string()).or_default();
+        let was_demoted = entry.is_demoted(Instant::now());
+        entry.record(ok, latency, &self.policy, Instant::now());
+        if !was_demoted && entry.is_demoted(Instant::now()) {
+            tracing::warn!(model_id, demoted_for = ?entry.snapshot(Instant::now()).demoted_for, "Model demoted");
+        }
+    }
+
+    /// Whether an error is the model's fault and another model may succeed.
+    fn classify(error: &ModelError) -> (bool, bool) {
+        // (counts against health, try next model)
+        match error {
+            ModelError::RateLimited | ModelError::ApiError(_) | ModelError::Timeout(_) => (true, true),
+            // A cheaper model may still fit the budget
+            ModelError::ContextTooLong(_) | ModelError::CapabilityNotSupported(_) | ModelError::CostLimitExceeded => (false, true),
+            _ => (false, false),
+        }
+    }
+
+    /// Run the request on the first model in the chain that answers.
+    pub async fn infer(&self, request: &InferenceRequest, sensitivity: Sensitivity) -> Result<RoutedResponse, ModelError> {
+        let mut failed = Vec::new();
+        let mut last_error = None;
+        for model in self.plan(request, sensitivity)? {
+            let started = Instant::now();
+            let result = match tokio::time::timeout(self.policy.timeout, model.infer(request)).await {
+                Ok(result) => result,
+                Err(_) => Err(ModelError::Timeout(self.policy.timeout.as_millis() as u64)),
+            };
+            match result {
+                Ok(response) => {
+                    self.record(model.model_id(), true, started.elapsed());
+                    return Ok(RoutedResponse { model_id: model.model_id().to_string(), response, failed });
+                }
+                Err(e) => {
+                    let (unhealthy, fall_back) = Self::classify(&e);
+                    if unhealthy {
+                        self.record(model.model_id(), false, started.elapsed());
+                    }
+                    if !fall_back {
+                        return Err(e);
+                    }
+                    tracing::info!(model_id = model.model_id(), error = %e, "Falling back to next model");
+                    failed.push(FailedAttempt { model_id: model.model_id().to_string(), error: e.to_string() });
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ModelError::NoModelAvailable("every model failed".into())))
+    }
+
+    /// Open a stream on the first model that accepts the request. Once
+    /// deltas flow, a failure ends the stream rather than switching model.
+    pub async fn stream(&self, request: &InferenceRequest, sensitivity: Sensitivity) -> Result<InferenceStream, ModelError> {
+        let mut last_error = None;
+        for model in self.plan(request, sensitivity)? {
+            let started = Instant::now();
+            let result = match tokio::time::timeout(self.policy.timeout, model.stream(request)).await {
+                Ok(result) => result,
+                Err(_) => Err(ModelError::Timeout(self.policy.timeout.as_millis() as u64)),
+            };
+            match result {
+                Ok(stream) => {
+                    self.record(model.model_id(), true, started.elapsed());
+                    return Ok(stream);
+                }
+                Err(e) => {
+                    let (unhealthy, fall_back) = Self::classify(&e);
+                    if unhealthy {
+                        self.record(model.model_id(), false, started.elapsed());
+                    }
+                    if !fall_back {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ModelError::NoModelAvailable("every model failed".into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::adapter::*;
+    use async_trait::async_trait;
+
+    /// Fails (or hangs) a set number of times, then answers.
+    struct FlakyModel {
+        id: &'static str,
+        failures: Mutex<u32>,
+        hang: bool,
+    }
+
+    impl FlakyModel {
+        fn new(id: &'static str, failures: u32, hang: bool) -> Arc<Self> {
+            Arc::new(Self { id, failures: Mutex::new(failures), hang })
+        }
+    }
+
+    #[async_trait]
+    impl FrontierModel for FlakyModel {
+        fn model_id(&self) -> &str {
+            self.id
+        }
+
+        fn family(&self) -> ModelFamily {
+            ModelFamily::Custom
+        }
+
+        fn max_context(&self) -> usize {
+            8_000
+        }
+
+        async fn infer(&self, _request: &InferenceRequest) -> Result<ModelResponse, ModelError> {
+            let failing = {
+                let mut failures = self.failures.lock().unwrap();
+                let failing = *failures > 0;
+                *failures = failures.saturating_sub(1);
+                failing
+            };
+            if failing && self.hang {
+                std::future::pending::<()>().await;
+            }
+            if failing {
+                return Err(ModelError::ApiError("HTTP 503".into()));
+            }
+            Ok(ModelResponse {
+                content: self.id.to_string(),
+                tool_calls: vec![],
+                finish_reason: FinishReason::Stop,
+                usage: Usage { input_tokens: 1, output_tokens: 1, total_tokens: 2, reasoning_tokens: None },
+                cost_usd: 0.0,
+                latency_ms: 1,
+            })
+        }
+
+        fn estimate_cost(&self, _request: &InferenceRequest) -> CostEstimate {
+            CostEstimate { input_tokens: 1, estimated_output_tokens: 1, estimated_cost_usd: 0.0, confidence: 1.0 }
+        }
+
+        fn supports(&self, _capability: ModelCapability) -> bool {
+            true
+        }
+    }
+
+    #[async_trait]
+    impl StreamingModel for FlakyModel {
+        async fn stream(&self, _request: &InferenceRequest) -> Result<InferenceStream, ModelError> {
+            Err(ModelError::CapabilityNotSupported(ModelCapability::TextGeneration))
+        }
+    }
+
+    fn request() -> InferenceRequest {
+        InferenceRequest {
+            system: None,
+            messages: vec![Message { role: MessageRole::User, content: MessageContent::Text("hi".into()) }],
+            temperature: None,
+            max_tokens: None,
+            thinking_budget: None,
+            tools: vec![],
+            stop: vec![],
+            response_format: None,
+            budget: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout_falls_back_and_demotes() {
+        let policy = HealthPolicy { timeout: Duration::from_secs(5), max_consecutive_failures: 2, ..HealthPolicy::default() };
+        let router = ModelRouter::new(policy)
+            .with_model(FlakyModel::new("primary", 2, true))
+            .with_model(FlakyModel::new("backup", 0, false))
+            .with_route(Sensitivity::Internal, &["primary", "backup"]);
+
+        let routed = router.infer(&request(), Sensitivity::Internal).await.unwrap();
+        assert_eq!(routed.model_id, "backup");
+        assert_eq!(routed.failed[0].model_id, "primary");
+        assert!(router.health("primary").unwrap().demoted_for.is_none());
+
+        // Second timeout in a row demotes the primary behind the backup
+        router.infer(&request(), Sensitivity::Internal).await.unwrap();
+        assert!(router.health("primary").unwrap().demoted_for.is_some());
+        let order: Vec<_> = router.plan(&request(), Sensitivity::Internal).unwrap().iter().map(|m| m.model_id().to_string()).collect();
+        assert_eq!(order, vec!["backup", "primary"]);
+    }
+
+    #[tokio::test]
+    async fn test_sensitivity_limits_fallbacks() {
+        let router = ModelRouter::new(HealthPolicy::default())
+            .with_model(FlakyModel::new("hosted", 0, false))
+            .with_model(FlakyModel::new("on-prem", 1, false))
+            .with_route(Sensitivity::Public, &["on-prem", "hosted"])
+            .with_route(Sensitivity::Regulated, &["on-prem"]);
+
+        assert!(matches!(router.infer(&request(), Sensitivity::Regulated).await, Err(ModelError::ApiError(_))));
+        assert_eq!(router.infer(&request(), Sensitivity::Regulated).await.unwrap().model_id, "on-prem");
+        assert!(matches!(router.infer(&request(), Sensitivity::Confidential).await, Err(ModelError::NoModelAvailable(_))));
+    }
+
+    #[test]
+    fn test_demotion_backs_off_and_recovers() {
+        let policy = HealthPolicy { max_consecutive_failures: 1, ..HealthPolicy::default() };
+        let mut health = ModelHealth::default();
+        let start = Instant::now();
+
+        health.record(false, Duration::ZERO, &policy, start);
+        assert_eq!(health.snapshot(start).demoted_for, Some(policy.demotion));
+
+        // Relapse right after recovery doubles the demotion
+        let back = start + policy.demotion;
+        health.record(false, Duration::ZERO, &policy, back);
+        assert_eq!(health.snapshot(back).demoted_for, Some(policy.demotion * 2));
+
+        // Staying healthy after recovery resets the backoff
+        let recovered = back + policy.demotion * 2;
+        health.record(true, Duration::from_millis(10), &policy, recovered);
+        assert_eq!((health.relapses, health.snapshot(recovered).demoted_for), (0, None));
+    }
+}
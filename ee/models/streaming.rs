@@ -0,0 +1,448 @@
+//! Streaming Inference
+//!
+//! Token streaming across every model adapter:
+//! - Adapters translate their provider's wire format into [`StreamDelta`]s
+//!   with a unified [`FinishReason`]
+//! - An [`InferenceStream`] can be cancelled mid-stream from another task;
+//!   the provider connection is dropped and the stream ends with
+//!   `FinishReason::Cancelled`
+//! - However the stream ends (finished, cancelled, failed or dropped), its
+//!   usage is recorded once in the [`CostTracker`]
+//! - A stream with a budget is cut off with `FinishReason::Length` once its
+//!   deadline passes or its spend reaches the limit
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut stream = model.stream(&request).await?
+//!     .with_cost_tracker(tracker.clone(), "agent-7");
+//! let cancel = stream.cancel_handle();
+//!
+//! while let Some(delta) = stream.next().await {
+//!     match delta? {
+//!         StreamDelta::Text { text } => print!("{}", text),
+//!         StreamDelta::Done { finish_reason, usage } => println!("\n{:?} {:?}", finish_reason, usage),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+
+use super::adapter::{FinishReason, FrontierModel, InferenceBudget, InferenceRequest, ModelError, ModelFamily, ModelResponse, ToolCall, Usage};
+use super::cost_optimizer::CostOptimizer;
+use agentkern_arbiter::{CostCategory, CostTracker};
+use async_trait::async_trait;
+use futures_util::task::AtomicWaker;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Incremental piece of a streamed response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamDelta {
+    Text { text: String },
+    /// Visible reasoning, for models that expose it
+    Reasoning { text: String },
+    /// Tool call fragment; `arguments` is a piece of the JSON arguments
+    ToolCall {
+        index: usize,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: String,
+    },
+    /// Always the last delta
+    Done { finish_reason: FinishReason, usage: Usage },
+}
+
+/// Provider deltas before accounting and cancellation.
+pub type DeltaStream = Pin<Box<dyn Stream<Item = Result<StreamDelta, ModelError>> + Send>>;
+
+/// Model that streams its responses.
+#[async_trait]
+pub trait StreamingModel: FrontierModel {
+    /// Start streaming a response.
+    async fn stream(&self, request: &InferenceRequest) -> Result<InferenceStream, ModelError>;
+}
+
+/// Per-token prices used to cost a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenPricing {
+    pub input_usd: f64,
+    pub output_usd: f64,
+}
+
+impl TokenPricing {
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        // Reasoning tokens are billed as output
+        let output = usage.output_tokens + usage.reasoning_tokens.unwrap_or(0);
+        usage.input_tokens as f64 * self.input_usd + output as f64 * self.output_usd
+    }
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    waker: AtomicWaker,
+}
+
+/// Cancels an [`InferenceStream`] from anywhere.
+#[derive(Clone, Default)]
+pub struct CancelHandle(Arc<CancelState>);
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.waker.wake();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+struct Metering {
+    tracker: Arc<CostTracker>,
+    agent_id: String,
+    task_id: Option<String>,
+}
+
+/// Where actual spend is reported so estimates can improve.
+struct Learning {
+    optimizer: Arc<CostOptimizer>,
+    family: ModelFamily,
+    request: InferenceRequest,
+}
+
+/// A streamed response with cancellation and usage accounting.
+pub struct InferenceStream {
+    inner: Option<DeltaStream>,
+    model_id: String,
+    pricing: TokenPricing,
+    cancel: CancelHandle,
+    metering: Option<Metering>,
+    learning: Option<Learning>,
+    deadline: Option<Pin<Box<tokio::time::Sleep>>>,
+    max_cost_usd: Option<f64>,
+    /// Prompt size guess, used when the provider never reports usage
+    estimated_input_tokens: u32,
+    /// Characters streamed so far, for the same purpose
+    streamed_chars: usize,
+    usage: Option<Usage>,
+    finish_reason: Option<FinishReason>,
+    recorded: bool,
+    started: Instant,
+}
+
+impl InferenceStream {
+    pub fn new(inner: DeltaStream, model_id: &str, pricing: TokenPricing, estimated_input_tokens: u32) -> Self {
+        Self {
+            inner: Some(inner),
+            model_id: model_id.to_string(),
+            pricing,
+            cancel: CancelHandle::default(),
+            metering: None,
+            learning: None,
+            deadline: None,
+            max_cost_usd: None,
+            estimated_input_tokens,
+            streamed_chars: 0,
+            usage: None,
+            finish_reason: None,
+            recorded: false,
+            started: Instant::now(),
+        }
+    }
+
+    /// Record usage against `agent_id` when the stream ends.
+    pub fn with_cost_tracker(mut self, tracker: Arc<CostTracker>, agent_id: &str) -> Self {
+        self.metering = Some(Metering { tracker, agent_id: agent_id.to_string(), task_id: None });
+        self
+    }
+
+    /// Attribute the recorded cost to a task.
+    pub fn with_task(mut self, task_id: &str) -> Self {
+        if let Some(metering) = &mut self.metering {
+            metering.task_id = Some(task_id.to_string());
+        }
+        self
+    }
+
+    /// Cut the stream off at the budget's latency and spend limits.
+    /// `started` is when the request was sent, so connecting counts too.
+    pub fn with_budget(mut self, budget: &InferenceBudget, started: Instant) -> Self {
+        self.started = started;
+        self.max_cost_usd = budget.max_cost_usd;
+        self.deadline = budget.max_latency_ms.map(|ms| {
+            let deadline = started + Duration::from_millis(ms);
+            Box::pin(tokio::time::sleep_until(deadline.into()))
+        });
+        self
+    }
+
+    /// Report actual spend to `optimizer` when the stream completes.
+    pub fn with_optimizer(mut self, optimizer: Arc<CostOptimizer>, family: ModelFamily, request: &InferenceRequest) -> Self {
+        self.learning = Some(Learning { optimizer, family, request: request.clone() });
+        self
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Final usage; estimated if the stream ended without a report.
+    pub fn usage(&self) -> Usage {
+        self.usage.clone().unwrap_or_else(|| self.estimated_usage())
+    }
+
+    pub fn cost_usd(&self) -> f64 {
+        self.pricing.cost(&self.usage())
+    }
+
+    fn estimated_usage(&self) -> Usage {
+        let output_tokens = self.streamed_chars.div_ceil(4) as u32;
+        Usage {
+            input_tokens: self.estimated_input_tokens,
+            output_tokens,
+            total_tokens: self.estimated_input_tokens + output_tokens,
+            reasoning_tokens: None,
+        }
+    }
+
+    /// Drop the provider stream and record usage, once.
+    fn finish(&mut self, finish_reason: FinishReason) -> Usage {
+        self.inner = None;
+        self.finish_reason.get_or_insert(finish_reason);
+        let usage = self.usage();
+        if !self.recorded {
+            self.recorded = true;
+            self.record(&usage);
+        }
+        usage
+    }
+
+    fn record(&self, usage: &Usage) {
+        // Cancelled and failed streams say little about what a request costs
+        if let (Some(learning), Some(FinishReason::Stop | FinishReason::Length | FinishReason::ToolUse)) = (&self.learning, self.finish_reason) {
+            learning.optimizer.record_spend(learning.family, &learning.request, self.pricing.cost(usage));
+        }
+        let Some(metering) = &self.metering else {
+            return;
+        };
+        let finish_reason = self.finish_reason.unwrap_or(FinishReason::Error);
+        let mut event = metering.tracker.event(&metering.agent_id, CostCategory::LlmInference)
+            .resource(&self.model_id)
+            .amount(self.pricing.cost(usage))
+            .quantity(usage.total_tokens as f64, "tokens")
+            .meta("input_tokens", usage.input_tokens.into())
+            .meta("output_tokens", usage.output_tokens.into())
+            .meta("finish_reason", serde_json::json!(finish_reason))
+            .meta("estimated", self.usage.is_none().into());
+        if let Some(task_id) = &metering.task_id {
+            event = event.task(task_id);
+        }
+        metering.tracker.record(event.build());
+    }
+
+    /// Read the whole stream into a single response.
+    pub async fn collect(mut self) -> Result<ModelResponse, ModelError> {
+        let mut content = String::new();
+        let mut calls: BTreeMap<usize, (String, String, String)> = BTreeMap::new();
+        let mut finish_reason = FinishReason::Error;
+        let mut usage = None;
+
+        while let Some(delta) = self.next().await {
+            match delta? {
+                StreamDelta::Text { text } => content.push_str(&text),
+                StreamDelta::Reasoning { .. } => {}
+                StreamDelta::ToolCall { index, id, name, arguments } => {
+                    let call = calls.entry(index).or_default();
+                    if let Some(id) = id {
+                        call.0 = id;
+                    }
+                    if let Some(name) = name {
+                        call.1 = name;
+                    }
+                    call.2.push_str(&arguments);
+                }
+                StreamDelta::Done { finish_reason: reason, usage: reported } => {
+                    finish_reason = reason;
+                    usage = Some(reported);
+                }
+            }
+        }
+
+        let usage = usage.unwrap_or_else(|| self.usage());
+        Ok(ModelResponse {
+            content,
+            tool_calls: calls.into_values()
+                .map(|(id, name, arguments)| ToolCall {
+                    id,
+                    name,
+                    arguments: serde_json::from_str(&arguments).unwrap_or(serde_json::Value::String(arguments)),
+                })
+                .collect(),
+            finish_reason,
+            cost_usd: self.pricing.cost(&usage),
+            usage,
+            latency_ms: self.started.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+impl Stream for InferenceStream {
+    type Item = Result<StreamDelta, ModelError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.inner.is_none() {
+            return Poll::Ready(None);
+        }
+
+        this.cancel.0.waker.register(cx.waker());
+        if this.cancel.is_cancelled() {
+            let usage = this.finish(FinishReason::Cancelled);
+            return Poll::Ready(Some(Ok(StreamDelta::Done { finish_reason: FinishReason::Cancelled, usage })));
+        }
+
+        let past_deadline = this.deadline.as_mut().is_some_and(|deadline| deadline.as_mut().poll(cx).is_ready());
+        let over_spend = this.max_cost_usd.is_some_and(|max| this.pricing.cost(&this.estimated_usage()) >= max);
+        if past_deadline || over_spend {
+            tracing::info!(model_id = %this.model_id, past_deadline, over_spend, "Stream cut off at budget");
+            let usage = this.finish(FinishReason::Length);
+            return Poll::Ready(Some(Ok(StreamDelta::Done { finish_reason: FinishReason::Length, usage })));
+        }
+
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match inner.poll_next_unpin(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Some(Ok(StreamDelta::Done { finish_reason, usage }))) => {
+                this.usage = Some(usage);
+                let usage = this.finish(finish_reason);
+                Poll::Ready(Some(Ok(StreamDelta::Done { finish_reason, usage })))
+            }
+            Poll::Ready(Some(Ok(delta))) => {
+                if let StreamDelta::Text { text } | StreamDelta::Reasoning { text } = &delta {
+                    this.streamed_chars += text.len();
+                }
+                Poll::Ready(Some(Ok(delta)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                this.finish(FinishReason::Error);
+                Poll::Ready(Some(Err(e)))
+            }
+            // Provider closed without a final event
+            Poll::Ready(None) => {
+                let usage = this.finish(FinishReason::Error);
+                Poll::Ready(Some(Ok(StreamDelta::Done { finish_reason: FinishReason::Error, usage })))
+            }
+        }
+    }
+}
+
+impl Drop for InferenceStream {
+    fn drop(&mut self) {
+        // Abandoned streams are billed for what was generated
+        if !self.recorded {
+            self.finish(FinishReason::Cancelled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deltas(items: Vec<StreamDelta>) -> DeltaStream {
+        Box::pin(futures_util::stream::iter(items.into_iter().map(Ok)))
+    }
+
+    fn usage(input: u32, output: u32) -> Usage {
+        Usage { input_tokens: input, output_tokens: output, total_tokens: input + output, reasoning_tokens: None }
+    }
+
+    #[tokio::test]
+    async fn test_collect_records_reported_usage_once() {
+        let tracker = Arc::new(CostTracker::new());
+        let pricing = TokenPricing { input_usd: 0.001, output_usd: 0.002 };
+        let stream = InferenceStream::new(deltas(vec![
+            StreamDelta::Text { text: "Hel".into() },
+            StreamDelta::Text { text: "lo".into() },
+            StreamDelta::ToolCall { index: 0, id: Some("call_1".into()), name: Some("lookup".into()), arguments: "{\"q\":".into() },
+            StreamDelta::ToolCall { index: 0, id: None, name: None, arguments: "1}".into() },
+            StreamDelta::Done { finish_reason: FinishReason::ToolUse, usage: usage(100, 20) },
+        ]), "claude-test", pricing, 10)
+            .with_cost_tracker(tracker.clone(), "agent-7");
+
+        let response = stream.collect().await.unwrap();
+        assert_eq!(response.content, "Hello");
+        assert_eq!(response.finish_reason, FinishReason::ToolUse);
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({ "q": 1 }));
+        assert!((tracker.get_agent_total("agent-7") - 0.14).abs() < 1e-9);
+        assert_eq!(tracker.get_agent_summary("agent-7").event_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_mid_stream_bills_partial_output() {
+        let tracker = Arc::new(CostTracker::new());
+        let pending = futures_util::stream::iter(vec![Ok(StreamDelta::Text { text: "12345678".into() })])
+            .chain(futures_util::stream::pending());
+        let mut stream = InferenceStream::new(Box::pin(pending), "gpt-test", TokenPricing { input_usd: 0.0, output_usd: 1.0 }, 50)
+            .with_cost_tracker(tracker.clone(), "agent-7");
+        let cancel = stream.cancel_handle();
+
+        assert!(matches!(stream.next().await, Some(Ok(StreamDelta::Text { .. }))));
+        tokio::spawn(async move { cancel.cancel() });
+        match stream.next().await {
+            Some(Ok(StreamDelta::Done { finish_reason, usage })) => {
+                assert_eq!(finish_reason, FinishReason::Cancelled);
+                assert_eq!((usage.input_tokens, usage.output_tokens), (50, 2));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(stream.next().await.is_none());
+        drop(stream);
+        assert_eq!(tracker.get_agent_total("agent-7"), 2.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_budget_cuts_stream_off() {
+        let budget = InferenceBudget::default().with_max_latency_ms(1_000);
+        let pending = futures_util::stream::iter(vec![Ok(StreamDelta::Text { text: "1234".into() })])
+            .chain(futures_util::stream::pending());
+        let mut stream = InferenceStream::new(Box::pin(pending), "gpt-test", TokenPricing::default(), 5)
+            .with_budget(&budget, Instant::now());
+
+        assert!(matches!(stream.next().await, Some(Ok(StreamDelta::Text { .. }))));
+        match stream.next().await {
+            Some(Ok(StreamDelta::Done { finish_reason, usage })) => {
+                assert_eq!(finish_reason, FinishReason::Length);
+                assert_eq!(usage.output_tokens, 1);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Spend limit: the second chunk would push past $2
+        let budget = InferenceBudget::default().with_max_cost_usd(2.0);
+        let response = InferenceStream::new(deltas(vec![
+            StreamDelta::Text { text: "12345678".into() },
+            StreamDelta::Text { text: "more".into() },
+            StreamDelta::Done { finish_reason: FinishReason::Stop, usage: usage(0, 3) },
+        ]), "gpt-test", TokenPricing { input_usd: 0.0, output_usd: 1.0 }, 0)
+            .with_budget(&budget, Instant::now())
+            .collect().await.unwrap();
+        assert_eq!(response.content, "12345678");
+        assert_eq!(response.finish_reason, FinishReason::Length);
+    }
+}
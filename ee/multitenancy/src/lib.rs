@@ -9,6 +9,7 @@
 //! - Resource isolation per tenant
 //! - Row-level security patterns
 //! - Per-tenant quotas
+//! - Isolated Treasury, Trust, Synapse and audit spaces per tenant
 //!
 //! # Example
 //!
@@ -24,6 +25,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+pub mod spaces;
+
+pub use spaces::{TenantSpace, TenantSpaces};
+
 mod license {
     #[derive(Debug, thiserror::Error)]
     pub enum LicenseError {
@@ -42,6 +47,21 @@ mod license {
         tracing::debug!(feature = %feature, "Enterprise multitenancy feature accessed");
         Ok(())
     }
+
+    /// Serializes tests that touch the license environment variable.
+    #[cfg(test)]
+    pub(crate) static LICENSE_ENV: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Run `f` with a license key set.
+    #[cfg(test)]
+    pub(crate) fn licensed<T>(f: impl FnOnce() -> T) -> T {
+        let _guard = LICENSE_ENV.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: environment access in tests is serialized by LICENSE_ENV
+        unsafe { std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license") };
+        let result = f();
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
+        result
+    }
 }
 
 /// Tenant identifier.
@@ -291,6 +311,26 @@ pub enum IsolationError {
     QuotaExceeded { resource: String },
     #[error("Cross-tenant access denied")]
     CrossTenantDenied,
+    #[error("Ledger error: {0}")]
+    Ledger(#[from] agentkern_treasury::balance::LedgerError),
+    #[error("Enterprise license required: {0}")]
+    Unlicensed(String),
+    #[error("Tier policy error: {0}")]
+    TierPolicy(#[from] agentkern_trust::TierConfigError),
+}
+
+impl agentkern_errors::Coded for IsolationError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::TenantNotFound => ErrorCode::NotFound,
+            Self::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            Self::CrossTenantDenied => ErrorCode::PermissionDenied,
+            Self::Ledger(e) => e.code(),
+            Self::Unlicensed(_) => ErrorCode::LicenseRequired,
+            Self::TierPolicy(_) => ErrorCode::InvalidArgument,
+        }
+    }
 }
 
 /// Row-level security filter.
@@ -364,6 +404,7 @@ impl<T> TenantScoped<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::license::{licensed, LICENSE_ENV};
 
     #[test]
     fn test_plan_tiers() {
@@ -388,22 +429,20 @@ mod tests {
 
     #[test]
     fn test_tenant_isolator_requires_license() {
-        std::env::remove_var("AGENTKERN_LICENSE_KEY");
+        let _guard = LICENSE_ENV.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: environment access in tests is serialized by LICENSE_ENV
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
         let result = TenantIsolator::new(IsolationLevel::Logical);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_tenant_isolator_with_license() {
-        std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license");
-        
-        let mut isolator = TenantIsolator::new(IsolationLevel::Schema).unwrap();
+        let mut isolator = licensed(|| TenantIsolator::new(IsolationLevel::Schema)).unwrap();
         isolator.register_tenant("org-123", PlanTier::Pro);
         
         let ctx = TenantContext::new("org-123").with_plan(PlanTier::Pro);
         assert!(isolator.can_proceed(&ctx).unwrap());
-        
-        std::env::remove_var("AGENTKERN_LICENSE_KEY");
     }
 
     #[test]
@@ -0,0 +1,342 @@
+//! Tenant Spaces
+//!
+//! Per-tenant isolation for Treasury wallets, Trust reputations, Synapse
+//! memory and the ISO 42001 audit ledger:
+//! - Each tenant gets its own ledger, trust network, state store and audit
+//!   ledger, so one tenant's data is never reachable from another's space
+//! - Every operation takes the caller's [`TenantContext`] and is refused
+//!   when it names a different tenant
+//! - Quotas from the tenant's plan are enforced before anything changes:
+//!   request rate, monthly calls, agent count and memory storage
+//! - Storage namespaces (`tenants/<id>/<component>`) keep persisted keys
+//!   and replication node IDs apart
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let spaces = TenantSpaces::new()?;
+//! let ctx = TenantContext::new("org-123").with_plan(PlanTier::Pro);
+//! let space = spaces.provision(&ctx)?;
+//!
+//! space.deposit(&ctx, "agent-1", Amount::from_float(50.0, 6))?;
+//! space.register_agent(&ctx, "agent-1")?;
+//! space.update_memory(&ctx, update).await?;
+//! ```
+
+use crate::{IsolationError, TenantContext, TenantId, TenantQuota, TenantUsage};
+use agentkern_arbiter::{AuditEvent, ComplianceLedger};
+use agentkern_synapse::{AgentState, StateStore, StateUpdate};
+use agentkern_treasury::{AgentBalance, Amount, BalanceLedger, TransferEngine, TransferRequest, TransferResult};
+use agentkern_trust::{ReputationEvent, ReputationScore, TierPolicy, TrustNetwork};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Length of the request-rate window.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+struct UsageWindow {
+    usage: TenantUsage,
+    window_started: Instant,
+}
+
+/// One tenant's isolated services.
+pub struct TenantSpace {
+    tenant_id: TenantId,
+    quota: TenantQuota,
+    usage: Mutex<UsageWindow>,
+    wallets: Arc<BalanceLedger>,
+    transfers: TransferEngine,
+    trust: Mutex<TrustNetwork>,
+    memory: StateStore,
+    /// Serialized state size per agent
+    memory_bytes: Mutex<HashMap<String, u64>>,
+    audit: Mutex<ComplianceLedger>,
+}
+
+impl TenantSpace {
+    fn new(tenant_id: TenantId, quota: TenantQuota, trust: TrustNetwork) -> Self {
+        let wallets = Arc::new(BalanceLedger::default());
+        let memory = StateStore::new().with_node_id(namespace(&tenant_id, "synapse"));
+        let audit = ComplianceLedger::new(tenant_id.clone(), env!("CARGO_PKG_VERSION").to_string());
+        Self {
+            transfers: TransferEngine::new(wallets.clone()),
+            wallets,
+            trust: Mutex::new(trust),
+            memory,
+            memory_bytes: Mutex::new(HashMap::new()),
+            audit: Mutex::new(audit),
+            usage: Mutex::new(UsageWindow { usage: TenantUsage::default(), window_started: Instant::now() }),
+            quota,
+            tenant_id,
+        }
+    }
+
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    /// Storage namespace for a component, e.g. `tenants/org-123/treasury`.
+    pub fn namespace(&self, component: &str) -> String {
+        namespace(&self.tenant_id, component)
+    }
+
+    pub fn quota(&self) -> &TenantQuota {
+        &self.quota
+    }
+
+    pub fn usage(&self) -> TenantUsage {
+        self.usage.lock().unwrap().usage.clone()
+    }
+
+    /// Check the caller belongs to this tenant and count the request.
+    fn authorize(&self, ctx: &TenantContext) -> Result<(), IsolationError> {
+        if ctx.tenant_id != self.tenant_id {
+            tracing::warn!(tenant_id = %self.tenant_id, caller = %ctx.tenant_id, "Cross-tenant access denied");
+            return Err(IsolationError::CrossTenantDenied);
+        }
+        let mut window = self.usage.lock().unwrap();
+        if window.window_started.elapsed() >= RATE_WINDOW {
+            window.usage.requests_minute = 0;
+            window.window_started = Instant::now();
+        }
+        if window.usage.requests_minute >= self.quota.requests_per_minute {
+            return Err(quota_exceeded("requests_per_minute"));
+        }
+        if window.usage.api_calls_month >= self.quota.max_api_calls_month {
+            return Err(quota_exceeded("api_calls_month"));
+        }
+        window.usage.requests_minute += 1;
+        window.usage.api_calls_month += 1;
+        Ok(())
+    }
+
+    // Treasury
+
+    pub fn deposit(&self, ctx: &TenantContext, agent_id: &str, amount: Amount) -> Result<AgentBalance, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.wallets.deposit(agent_id, amount)?)
+    }
+
+    pub fn balance(&self, ctx: &TenantContext, agent_id: &str) -> Result<AgentBalance, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.wallets.get_balance(agent_id))
+    }
+
+    /// Transfer between two wallets of this tenant.
+    pub async fn transfer(&self, ctx: &TenantContext, request: TransferRequest) -> Result<TransferResult, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.transfers.transfer(request).await)
+    }
+
+    // Trust
+
+    /// Register an agent, counting it against the agent quota.
+    pub fn register_agent(&self, ctx: &TenantContext, agent_id: &str) -> Result<ReputationScore, IsolationError> {
+        self.authorize(ctx)?;
+        let mut trust = self.trust.lock().unwrap();
+        if trust.get_reputation(agent_id).is_none() {
+            let mut window = self.usage.lock().unwrap();
+            if window.usage.active_agents >= self.quota.max_agents {
+                return Err(quota_exceeded("agents"));
+            }
+            window.usage.active_agents += 1;
+        }
+        Ok(trust.register_agent(agent_id, &self.tenant_id).reputation.clone())
+    }
+
+    pub fn record_reputation(&self, ctx: &TenantContext, agent_id: &str, event: ReputationEvent) -> Result<(), IsolationError> {
+        self.authorize(ctx)?;
+        self.trust.lock().unwrap().record_event(agent_id, event);
+        Ok(())
+    }
+
+    pub fn reputation(&self, ctx: &TenantContext, agent_id: &str) -> Result<Option<ReputationScore>, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.trust.lock().unwrap().get_reputation(agent_id).cloned())
+    }
+
+    /// Set the tenant's trust tier thresholds and promotion hold.
+    pub fn set_tier_policy(&self, ctx: &TenantContext, policy: TierPolicy) -> Result<(), IsolationError> {
+        self.authorize(ctx)?;
+        self.trust.lock().unwrap().set_tier_policy(&self.tenant_id, policy)?;
+        Ok(())
+    }
+
+    // Synapse
+
+    /// Apply a state update, refusing it if the tenant's memory would
+    /// outgrow its storage quota.
+    pub async fn update_memory(&self, ctx: &TenantContext, update: StateUpdate) -> Result<AgentState, IsolationError> {
+        self.authorize(ctx)?;
+        let mut projected = self.memory.get_state(&update.agent_id).await
+            .map(|state| state.state)
+            .unwrap_or_default();
+        projected.extend(update.updates.clone());
+        for key in update.deletes.iter().flatten() {
+            projected.remove(key);
+        }
+        let agent_bytes = serde_json::to_vec(&projected).map(|v| v.len() as u64).unwrap_or(0);
+        {
+            let mut sizes = self.memory_bytes.lock().unwrap();
+            let total = sizes.iter()
+                .filter(|(agent, _)| **agent != update.agent_id)
+                .map(|(_, bytes)| bytes)
+                .sum::<u64>() + agent_bytes;
+            if total > self.quota.max_storage_bytes {
+                return Err(quota_exceeded("storage"));
+            }
+            sizes.insert(update.agent_id.clone(), agent_bytes);
+            self.usage.lock().unwrap().usage.storage_bytes = total;
+        }
+        Ok(self.memory.update_state(update).await)
+    }
+
+    pub async fn memory(&self, ctx: &TenantContext, agent_id: &str) -> Result<Option<AgentState>, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.memory.get_state(agent_id).await)
+    }
+
+    // Audit
+
+    /// Record an audit event, tagged with the tenant and request.
+    pub fn audit(&self, ctx: &TenantContext, mut event: AuditEvent) -> Result<(), IsolationError> {
+        self.authorize(ctx)?;
+        event.context.insert("tenant_id".to_string(), self.tenant_id.clone());
+        if let Some(request_id) = &ctx.request_id {
+            event.context.insert("request_id".to_string(), request_id.clone());
+        }
+        self.audit.lock().unwrap().record(event);
+        Ok(())
+    }
+
+    pub fn audit_events(&self, ctx: &TenantContext, agent_id: &str) -> Result<Vec<AuditEvent>, IsolationError> {
+        self.authorize(ctx)?;
+        Ok(self.audit.lock().unwrap().events_by_agent(agent_id).into_iter().cloned().collect())
+    }
+}
+
+fn namespace(tenant_id: &str, component: &str) -> String {
+    format!("tenants/{}/{}", tenant_id, component)
+}
+
+fn quota_exceeded(resource: &str) -> IsolationError {
+    IsolationError::QuotaExceeded { resource: resource.to_string() }
+}
+
+/// Registry of tenant spaces.
+pub struct TenantSpaces {
+    spaces: RwLock<HashMap<TenantId, Arc<TenantSpace>>>,
+}
+
+impl TenantSpaces {
+    /// Create an empty registry (requires enterprise license).
+    pub fn new() -> Result<Self, crate::license::LicenseError> {
+        crate::license::require("MULTI_TENANCY")?;
+        Ok(Self { spaces: RwLock::new(HashMap::new()) })
+    }
+
+    /// Create the tenant's space with quotas from its plan, or return the existing one.
+    pub fn provision(&self, ctx: &TenantContext) -> Result<Arc<TenantSpace>, IsolationError> {
+        self.provision_with_quota(ctx, TenantQuota::from(ctx.plan))
+    }
+
+    pub fn provision_with_quota(&self, ctx: &TenantContext, quota: TenantQuota) -> Result<Arc<TenantSpace>, IsolationError> {
+        if let Some(space) = self.spaces.read().unwrap().get(&ctx.tenant_id) {
+            return Ok(space.clone());
+        }
+        let trust = TrustNetwork::new().map_err(|e| IsolationError::Unlicensed(e.to_string()))?;
+        let mut spaces = self.spaces.write().unwrap();
+        let space = spaces.entry(ctx.tenant_id.clone())
+            .or_insert_with(|| Arc::new(TenantSpace::new(ctx.tenant_id.clone(), quota, trust)));
+        tracing::info!(tenant_id = %ctx.tenant_id, plan = ?ctx.plan, "Tenant space provisioned");
+        Ok(space.clone())
+    }
+
+    /// The caller's own space.
+    pub fn space(&self, ctx: &TenantContext) -> Result<Arc<TenantSpace>, IsolationError> {
+        self.spaces.read().unwrap().get(&ctx.tenant_id).cloned().ok_or(IsolationError::TenantNotFound)
+    }
+
+    /// Drop a tenant's space and everything in it.
+    pub fn deprovision(&self, tenant_id: &str) -> bool {
+        self.spaces.write().unwrap().remove(tenant_id).is_some()
+    }
+
+    pub fn tenants(&self) -> Vec<TenantId> {
+        self.spaces.read().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlanTier;
+    use crate::license::licensed;
+
+    fn update(agent_id: &str, key: &str, value: serde_json::Value) -> StateUpdate {
+        StateUpdate { agent_id: agent_id.to_string(), updates: HashMap::from([(key.to_string(), value)]), deletes: None }
+    }
+
+    #[tokio::test]
+    async fn test_tenants_cannot_see_or_touch_each_other() {
+        let acme = TenantContext::new("acme").with_plan(PlanTier::Pro);
+        let globex = TenantContext::new("globex").with_plan(PlanTier::Pro);
+        let (spaces, acme_space, globex_space) = licensed(|| {
+            let spaces = TenantSpaces::new().unwrap();
+            let acme_space = spaces.provision(&acme).unwrap();
+            let globex_space = spaces.provision(&globex).unwrap();
+            (spaces, acme_space, globex_space)
+        });
+
+        // Same agent IDs in both tenants stay separate
+        acme_space.deposit(&acme, "agent-1", Amount::from_float(100.0, 6)).unwrap();
+        acme_space.register_agent(&acme, "agent-1").unwrap();
+        acme_space.record_reputation(&acme, "agent-1", ReputationEvent::ActionSuccess { action: "x".into(), impact: 100 }).unwrap();
+        acme_space.update_memory(&acme, update("agent-1", "secret", "acme-plan".into())).await.unwrap();
+
+        assert!(globex_space.balance(&globex, "agent-1").unwrap().balance.is_zero());
+        assert!(globex_space.reputation(&globex, "agent-1").unwrap().is_none());
+        assert!(globex_space.memory(&globex, "agent-1").await.unwrap().is_none());
+
+        // A context for one tenant is refused by the other's space
+        assert!(matches!(acme_space.balance(&globex, "agent-1"), Err(IsolationError::CrossTenantDenied)));
+        assert!(matches!(
+            acme_space.deposit(&globex, "agent-1", Amount::from_float(1.0, 6)),
+            Err(IsolationError::CrossTenantDenied)
+        ));
+        assert!(acme_space.memory(&globex, "agent-1").await.is_err());
+        assert_eq!(acme_space.balance(&acme, "agent-1").unwrap().balance.to_float(), 100.0);
+
+        assert_eq!(acme_space.namespace("synapse"), "tenants/acme/synapse");
+        assert!(matches!(spaces.space(&TenantContext::new("initech")), Err(IsolationError::TenantNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_quotas_enforced_per_tenant() {
+        let free = TenantContext::new("small-co");
+        let other = TenantContext::new("other-co");
+        let quota = TenantQuota { requests_per_minute: 6, max_storage_bytes: 64, ..TenantQuota::from(PlanTier::Free) };
+        let (space, other_space) = licensed(|| {
+            let spaces = TenantSpaces::new().unwrap();
+            (spaces.provision_with_quota(&free, quota).unwrap(), spaces.provision(&other).unwrap())
+        });
+
+        for i in 0..3 {
+            space.register_agent(&free, &format!("agent-{}", i)).unwrap();
+        }
+        assert!(matches!(space.register_agent(&free, "agent-3"), Err(IsolationError::QuotaExceeded { .. })));
+        // Re-registering an existing agent does not count
+        space.register_agent(&free, "agent-0").unwrap();
+
+        let big = serde_json::Value::String("x".repeat(100));
+        assert!(matches!(
+            space.update_memory(&free, update("agent-0", "notes", big)).await,
+            Err(IsolationError::QuotaExceeded { resource }) if resource == "storage"
+        ));
+        assert!(matches!(space.balance(&free, "agent-0"), Err(IsolationError::QuotaExceeded { resource }) if resource == "requests_per_minute"));
+
+        // Another tenant is unaffected
+        assert!(other_space.register_agent(&other, "agent-3").is_ok());
+    }
+}
@@ -123,7 +123,10 @@ pub struct InventoryLevel {
 #[derive(Debug, thiserror::Error)]
 pub enum RetailError {
     #[error("Rate limited")]
-    RateLimited,
+    RateLimited {
+        /// Platform's `Retry-After`, if sent
+        retry_after: Option<std::time::Duration>,
+    },
     
     #[error("Not found: {0}")]
     NotFound(String),
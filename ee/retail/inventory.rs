@@ -0,0 +1,498 @@
+//! Inventory Synchronization
+//!
+//! One ledger holds the stock every platform sells from:
+//! - Orders reserve stock when they arrive, release it when canceled and
+//!   consume it when shipped (platform-fulfilled orders are ignored)
+//! - Platforms are offered `on_hand - reserved - safety_stock`, so the last
+//!   units are never sold twice
+//! - Listings are paused when that reaches zero and resumed on restock
+//! - Reconciliation compares each platform's count with the ledger and
+//!   corrects drift
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let ledger = Arc::new(InventoryLedger::new(InventoryConfig::default().with_safety_stock(2)));
+//! ledger.set_on_hand("MUG-1", 40);
+//!
+//! let sync = Arc::new(InventorySync::new(ledger.clone())
+//!     .with_platform(shopify)
+//!     .with_platform(amazon));
+//!
+//! let mut events = ingestor.subscribe();
+//! sync.clone().spawn(Duration::from_secs(900));
+//! while let Ok(event) = events.recv().await {
+//!     if let Err(InventoryError::Insufficient { sku, .. }) = sync.handle(&event).await {
+//!         // Oversold: cancel or backorder
+//!     }
+//! }
+//! ```
+
+use super::adapter::{RetailError, RetailPlatform};
+use super::listings::{ListingStatus, ListingUpdate};
+use super::orders::{FulfillmentChannel, Order, OrderStatus};
+use super::webhooks::{OrderEvent, OrderEventKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Inventory configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryConfig {
+    /// Units held back from every platform
+    pub safety_stock: u32,
+    /// Per-SKU overrides of `safety_stock`
+    #[serde(default)]
+    pub sku_safety_stock: HashMap<String, u32>,
+    /// Pause listings with nothing to sell
+    pub pause_when_out_of_stock: bool,
+}
+
+impl Default for InventoryConfig {
+    fn default() -> Self {
+        Self {
+            safety_stock: 0,
+            sku_safety_stock: HashMap::new(),
+            pause_when_out_of_stock: true,
+        }
+    }
+}
+
+impl InventoryConfig {
+    pub fn with_safety_stock(mut self, units: u32) -> Self {
+        self.safety_stock = units;
+        self
+    }
+
+    pub fn with_sku_safety_stock(mut self, sku: &str, units: u32) -> Self {
+        self.sku_safety_stock.insert(sku.to_string(), units);
+        self
+    }
+
+    fn safety_stock(&self, sku: &str) -> u32 {
+        self.sku_safety_stock.get(sku).copied().unwrap_or(self.safety_stock)
+    }
+}
+
+/// Stock of one SKU.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockLevel {
+    pub sku: String,
+    pub on_hand: i64,
+    pub reserved: i64,
+    /// `on_hand - reserved`
+    pub available: i64,
+    /// What platforms are offered
+    pub sellable: u32,
+}
+
+#[derive(Default)]
+struct LedgerState {
+    /// sku -> (on hand, reserved)
+    stock: HashMap<String, (i64, i64)>,
+    /// order ID -> reserved (sku, quantity)
+    reservations: HashMap<String, Vec<(String, u32)>>,
+}
+
+/// Stock ledger shared by all platforms.
+pub struct InventoryLedger {
+    config: InventoryConfig,
+    state: Mutex<LedgerState>,
+}
+
+impl InventoryLedger {
+    pub fn new(config: InventoryConfig) -> Self {
+        Self { config, state: Mutex::new(LedgerState::default()) }
+    }
+
+    /// Set the physical count of a SKU.
+    pub fn set_on_hand(&self, sku: &str, quantity: i64) {
+        self.state.lock().unwrap().stock.entry(sku.to_string()).or_default().0 = quantity;
+    }
+
+    /// Add received (or remove written-off) units.
+    pub fn adjust(&self, sku: &str, delta: i64) {
+        self.state.lock().unwrap().stock.entry(sku.to_string()).or_default().0 += delta;
+    }
+
+    pub fn level(&self, sku: &str) -> Option<StockLevel> {
+        let state = self.state.lock().unwrap();
+        state.stock.get(sku).map(|&(on_hand, reserved)| self.to_level(sku, on_hand, reserved))
+    }
+
+    /// Tracked SKUs.
+    pub fn skus(&self) -> Vec<String> {
+        let mut skus: Vec<_> = self.state.lock().unwrap().stock.keys().cloned().collect();
+        skus.sort();
+        skus
+    }
+
+    fn to_level(&self, sku: &str, on_hand: i64, reserved: i64) -> StockLevel {
+        let available = on_hand - reserved;
+        let sellable = (available - self.config.safety_stock(sku) as i64).clamp(0, u32::MAX as i64) as u32;
+        StockLevel { sku: sku.to_string(), on_hand, reserved, available, sellable }
+    }
+
+    /// Reserve stock for an order, all lines or none. Reserving the same
+    /// order again is a no-op.
+    pub fn reserve(&self, order: &Order) -> Result<(), InventoryError> {
+        let mut state = self.state.lock().unwrap();
+        if state.reservations.contains_key(&order.order_id) {
+            return Ok(());
+        }
+        let lines = order_lines(order);
+        for (sku, quantity) in &lines {
+            let (on_hand, reserved) = state.stock.get(sku).copied().unwrap_or_default();
+            if on_hand - reserved < *quantity as i64 {
+                return Err(InventoryError::Insufficient {
+                    order_id: order.order_id.clone(),
+                    sku: sku.clone(),
+                    requested: *quantity,
+                    available: on_hand - reserved,
+                });
+            }
+        }
+        for (sku, quantity) in &lines {
+            state.stock.entry(sku.clone()).or_default().1 += *quantity as i64;
+        }
+        state.reservations.insert(order.order_id.clone(), lines);
+        Ok(())
+    }
+
+    /// Release an order's reservation; returns the affected SKUs.
+    pub fn release(&self, order_id: &str) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        let lines = state.reservations.remove(order_id).unwrap_or_default();
+        for (sku, quantity) in &lines {
+            if let Some(stock) = state.stock.get_mut(sku) {
+                stock.1 -= *quantity as i64;
+            }
+        }
+        lines.into_iter().map(|(sku, _)| sku).collect()
+    }
+
+    /// Ship an order: its units leave the warehouse. Orders that were never
+    /// reserved are deducted directly; shipping the same order again is a
+    /// no-op. Returns the affected SKUs.
+    pub fn fulfill(&self, order: &Order) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        // Shipped orders keep an empty reservation so redeliveries are ignored
+        let (lines, reserved) = match state.reservations.insert(order.order_id.clone(), Vec::new()) {
+            Some(lines) if lines.is_empty() => return Vec::new(),
+            Some(lines) => (lines, true),
+            None => (order.items.iter().filter(|i| !i.sku.is_empty()).map(|i| (i.sku.clone(), i.quantity_ordered)).collect(), false),
+        };
+        for (sku, quantity) in &lines {
+            let stock = state.stock.entry(sku.clone()).or_default();
+            stock.0 -= *quantity as i64;
+            if reserved {
+                stock.1 -= *quantity as i64;
+            }
+        }
+        lines.into_iter().map(|(sku, _)| sku).collect()
+    }
+
+    /// Apply an order event; returns the SKUs whose stock changed.
+    pub fn apply(&self, event: &OrderEvent) -> Result<Vec<String>, InventoryError> {
+        let order = &event.order;
+        if order.fulfillment_channel == FulfillmentChannel::Platform {
+            return Ok(Vec::new());
+        }
+        if event.kind == OrderEventKind::Canceled || order.status == OrderStatus::Canceled {
+            return Ok(self.release(&order.order_id));
+        }
+        match order.status {
+            OrderStatus::Shipped | OrderStatus::Delivered => Ok(self.fulfill(order)),
+            OrderStatus::Pending | OrderStatus::Unshipped | OrderStatus::PartiallyShipped => {
+                if self.state.lock().unwrap().reservations.contains_key(&order.order_id) {
+                    return Ok(Vec::new());
+                }
+                self.reserve(order)?;
+                Ok(order_lines(order).into_iter().map(|(sku, _)| sku).collect())
+            }
+            OrderStatus::Canceled | OrderStatus::Returned => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Unshipped quantity per SKU, merging repeated lines.
+fn order_lines(order: &Order) -> Vec<(String, u32)> {
+    let mut lines: Vec<(String, u32)> = Vec::new();
+    for item in order.items.iter().filter(|i| !i.sku.is_empty()) {
+        let remaining = item.quantity_ordered.saturating_sub(item.quantity_shipped);
+        match lines.iter_mut().find(|(sku, _)| *sku == item.sku) {
+            Some(line) => line.1 += remaining,
+            None => lines.push((item.sku.clone(), remaining)),
+        }
+    }
+    lines
+}
+
+/// A platform count that differed from the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StockDrift {
+    pub platform_id: String,
+    pub sku: String,
+    pub platform_quantity: i32,
+    pub expected: u32,
+}
+
+/// Stock pushes, pauses and corrections made by one sync pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InventoryReport {
+    /// Corrected counts
+    pub drifts: Vec<StockDrift>,
+    /// (platform, sku) listings paused
+    pub paused: Vec<(String, String)>,
+    /// (platform, sku) listings resumed
+    pub resumed: Vec<(String, String)>,
+    /// (platform, sku, error)
+    pub errors: Vec<(String, String, String)>,
+}
+
+/// Pushes ledger stock to every platform.
+pub struct InventorySync {
+    ledger: Arc<InventoryLedger>,
+    platforms: Vec<Arc<dyn RetailPlatform>>,
+    /// (platform, sku) listings paused by this sync
+    paused: Mutex<HashSet<(String, String)>>,
+}
+
+impl InventorySync {
+    pub fn new(ledger: Arc<InventoryLedger>) -> Self {
+        Self { ledger, platforms: Vec::new(), paused: Mutex::new(HashSet::new()) }
+    }
+
+    pub fn with_platform(mut self, platform: Arc<dyn RetailPlatform>) -> Self {
+        self.platforms.push(platform);
+        self
+    }
+
+    pub fn ledger(&self) -> &Arc<InventoryLedger> {
+        &self.ledger
+    }
+
+    /// Apply an order event and push the changed SKUs to every platform.
+    /// Stock is pushed even when the order could not be reserved; failed
+    /// pushes are reported and corrected by the next reconciliation.
+    pub async fn handle(&self, event: &OrderEvent) -> Result<InventoryReport, InventoryError> {
+        let (skus, result) = match self.ledger.apply(event) {
+            Ok(skus) => (skus, Ok(())),
+            Err(e) => (order_lines(&event.order).into_iter().map(|(sku, _)| sku).collect(), Err(e)),
+        };
+        let mut report = InventoryReport::default();
+        for sku in &skus {
+            for platform in &self.platforms {
+                self.push_to(platform.as_ref(), sku, &mut report).await;
+            }
+        }
+        result.map(|()| report)
+    }
+
+    /// Compare every platform's count with the ledger and correct drift.
+    pub async fn reconcile(&self) -> InventoryReport {
+        let mut report = InventoryReport::default();
+        for sku in self.ledger.skus() {
+            let Some(level) = self.ledger.level(&sku) else { continue };
+            for platform in &self.platforms {
+                match platform.get_inventory(&sku).await {
+                    Ok(current) if current.available == level.sellable as i32 => {
+                        self.sync_listing(platform.as_ref(), &sku, level.sellable, &mut report).await;
+                    }
+                    Ok(current) => {
+                        report.drifts.push(StockDrift {
+                            platform_id: platform.platform_id().to_string(),
+                            sku: sku.clone(),
+                            platform_quantity: current.available,
+                            expected: level.sellable,
+                        });
+                        self.push_to(platform.as_ref(), &sku, &mut report).await;
+                    }
+                    Err(e) => report.errors.push((platform.platform_id().to_string(), sku.clone(), e.to_string())),
+                }
+            }
+        }
+        report
+    }
+
+    /// Reconcile every `interval` until the task is aborted.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                self.reconcile().await;
+            }
+        })
+    }
+
+    async fn push_to(&self, platform: &dyn RetailPlatform, sku: &str, report: &mut InventoryReport) {
+        let sellable = self.ledger.level(sku).map_or(0, |l| l.sellable);
+        let quantity = sellable.min(i32::MAX as u32) as i32;
+        match platform.update_inventory(sku, quantity).await {
+            Ok(()) => self.sync_listing(platform, sku, sellable, report).await,
+            Err(e) => report.errors.push((platform.platform_id().to_string(), sku.to_string(), e.to_string())),
+        }
+    }
+
+    /// Pause at zero, resume on restock.
+    async fn sync_listing(&self, platform: &dyn RetailPlatform, sku: &str, sellable: u32, report: &mut InventoryReport) {
+        if !self.ledger.config.pause_when_out_of_stock {
+            return;
+        }
+        let key = (platform.platform_id().to_string(), sku.to_string());
+        let paused = self.paused.lock().unwrap().contains(&key);
+        let status = match (sellable, paused) {
+            (0, false) => ListingStatus::Inactive,
+            (1.., true) => ListingStatus::Active,
+            _ => return,
+        };
+        match platform.update_listing(&ListingUpdate::status(sku, status)).await {
+            Ok(()) if status == ListingStatus::Inactive => {
+                self.paused.lock().unwrap().insert(key.clone());
+                report.paused.push(key);
+            }
+            Ok(()) => {
+                self.paused.lock().unwrap().remove(&key);
+                report.resumed.push(key);
+            }
+            Err(e) => report.errors.push((key.0, key.1, e.to_string())),
+        }
+    }
+
+    /// Listings currently paused by this sync, as (platform, sku).
+    pub fn paused(&self) -> BTreeSet<(String, String)> {
+        self.paused.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Inventory error.
+#[derive(Debug, thiserror::Error)]
+pub enum InventoryError {
+    #[error("Insufficient stock for order {order_id}: {sku} requested {requested}, available {available}")]
+    Insufficient { order_id: String, sku: String, requested: u32, available: i64 },
+
+    #[error("Platform error: {0}")]
+    Platform(#[from] RetailError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::adapter::{InventoryLevel, OrderFilter, PlatformType};
+    use super::super::orders::{OrderItem, OrderTotal};
+    use super::super::{Fulfillment, Listing, PriceUpdate};
+    use async_trait::async_trait;
+
+    #[derive(Default)]
+    struct FakePlatform {
+        stock: Mutex<HashMap<String, i32>>,
+        statuses: Mutex<Vec<ListingStatus>>,
+    }
+
+    #[async_trait]
+    impl RetailPlatform for FakePlatform {
+        fn platform_id(&self) -> &str { "shop" }
+        fn platform_type(&self) -> PlatformType { PlatformType::Shopify }
+        async fn get_listing(&self, _sku: &str) -> Result<Listing, RetailError> { unimplemented!() }
+        async fn update_listing(&self, update: &ListingUpdate) -> Result<(), RetailError> {
+            self.statuses.lock().unwrap().extend(update.status);
+            Ok(())
+        }
+        async fn update_price(&self, _sku: &str, _price: &PriceUpdate) -> Result<(), RetailError> { unimplemented!() }
+        async fn get_orders(&self, _filter: &OrderFilter) -> Result<Vec<Order>, RetailError> { unimplemented!() }
+        async fn acknowledge_order(&self, _order_id: &str) -> Result<(), RetailError> { unimplemented!() }
+        async fn submit_fulfillment(&self, _fulfillment: &Fulfillment) -> Result<(), RetailError> { unimplemented!() }
+        async fn get_inventory(&self, sku: &str) -> Result<InventoryLevel, RetailError> {
+            let quantity = self.stock.lock().unwrap().get(sku).copied().unwrap_or_default();
+            Ok(InventoryLevel { sku: sku.into(), quantity, reserved: 0, available: quantity, last_updated: String::new() })
+        }
+        async fn update_inventory(&self, sku: &str, quantity: i32) -> Result<(), RetailError> {
+            self.stock.lock().unwrap().insert(sku.into(), quantity);
+            Ok(())
+        }
+    }
+
+    fn event(order_id: &str, quantity: u32, status: OrderStatus, kind: OrderEventKind) -> OrderEvent {
+        OrderEvent {
+            event_id: format!("{}-{:?}", order_id, status),
+            platform: PlatformType::Shopify,
+            platform_id: "shop".into(),
+            kind,
+            order: Order {
+                order_id: order_id.into(),
+                purchase_date: String::new(),
+                status,
+                items: vec![OrderItem {
+                    item_id: "1".into(),
+                    sku: "MUG-1".into(),
+                    product_id: String::new(),
+                    title: "Mug".into(),
+                    quantity_ordered: quantity,
+                    quantity_shipped: 0,
+                    item_price: 10.0,
+                    currency: "USD".into(),
+                }],
+                shipping_address: None,
+                buyer_name: None,
+                order_total: OrderTotal { amount: 10.0, currency: "USD".into() },
+                fulfillment_channel: FulfillmentChannel::Merchant,
+            },
+            received_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inventory_sync_prevents_oversell() {
+        let ledger = Arc::new(InventoryLedger::new(InventoryConfig::default().with_safety_stock(1)));
+        ledger.set_on_hand("MUG-1", 4);
+        let shop = Arc::new(FakePlatform::default());
+        shop.stock.lock().unwrap().insert("MUG-1".into(), 10);
+        let sync = InventorySync::new(ledger.clone()).with_platform(shop.clone());
+
+        // Drift corrected: 4 on hand minus 1 safety stock
+        let report = sync.reconcile().await;
+        assert_eq!(report.drifts[0].platform_quantity, 10);
+        assert_eq!(shop.stock.lock().unwrap()["MUG-1"], 3);
+
+        // Order reserves; redelivery does not double count
+        sync.handle(&event("A", 2, OrderStatus::Unshipped, OrderEventKind::Created)).await.unwrap();
+        sync.handle(&event("A", 2, OrderStatus::Unshipped, OrderEventKind::Updated)).await.unwrap();
+        assert_eq!(ledger.level("MUG-1").unwrap().reserved, 2);
+        assert_eq!(shop.stock.lock().unwrap()["MUG-1"], 1);
+
+        // Oversell rejected; last sellable unit sold pauses the listing
+        assert!(matches!(
+            sync.handle(&event("B", 3, OrderStatus::Unshipped, OrderEventKind::Created)).await,
+            Err(InventoryError::Insufficient { available: 2, .. })
+        ));
+        sync.handle(&event("C", 1, OrderStatus::Pending, OrderEventKind::Created)).await.unwrap();
+        assert_eq!(shop.stock.lock().unwrap()["MUG-1"], 0);
+        assert_eq!(*shop.statuses.lock().unwrap(), vec![ListingStatus::Inactive]);
+
+        // Shipping consumes the reservation, cancel frees stock and resumes
+        sync.handle(&event("A", 2, OrderStatus::Shipped, OrderEventKind::Updated)).await.unwrap();
+        assert_eq!(ledger.level("MUG-1").unwrap(), StockLevel { sku: "MUG-1".into(), on_hand: 2, reserved: 1, available: 1, sellable: 0 });
+        sync.handle(&event("C", 1, OrderStatus::Canceled, OrderEventKind::Canceled)).await.unwrap();
+        assert_eq!(shop.stock.lock().unwrap()["MUG-1"], 1);
+        assert_eq!(*shop.statuses.lock().unwrap(), vec![ListingStatus::Inactive, ListingStatus::Active]);
+        assert!(sync.paused().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_shipments_deduct_once() {
+        let ledger = Arc::new(InventoryLedger::new(InventoryConfig::default()));
+        ledger.set_on_hand("MUG-1", 10);
+
+        // The same webhook delivered concurrently, for an order never reserved
+        let shipped = event("A", 3, OrderStatus::Shipped, OrderEventKind::Updated);
+        let deducted: usize = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| ledger.apply(&shipped).unwrap().len())).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        assert_eq!(deducted, 1);
+        assert_eq!(ledger.level("MUG-1").unwrap().on_hand, 7);
+    }
+}
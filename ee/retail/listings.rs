@@ -71,6 +71,24 @@ pub struct ListingUpdate {
     pub bullet_points: Option<Vec<String>>,
     pub images: Option<Vec<String>>,
     pub attributes: Option<HashMap<String, serde_json::Value>>,
+    /// Activate or pause the listing
+    #[serde(default)]
+    pub status: Option<ListingStatus>,
+}
+
+impl ListingUpdate {
+    /// Update that only changes the listing status.
+    pub fn status(sku: &str, status: ListingStatus) -> Self {
+        Self {
+            sku: sku.to_string(),
+            title: None,
+            description: None,
+            bullet_points: None,
+            images: None,
+            attributes: None,
+            status: Some(status),
+        }
+    }
 }
 
 /// Price update.
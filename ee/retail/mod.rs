@@ -2,6 +2,10 @@
 //!
 //! Generic e-commerce integration (Amazon SP-API, Shopify, Walmart, etc.)
 //! Technology-focused, vendor-neutral design
+//! Rate-limit-aware scheduling of platform calls
+//! Order webhook ingestion (Shopify, SP-API notifications)
+//! Cross-platform inventory ledger with oversell protection
+//! Repricing rules with margin guards and an audit trail
 //!
 //! Graceful Degradation: Works with credentials, demo mode without
 
@@ -10,10 +14,17 @@ pub mod listings;
 pub mod orders;
 pub mod fulfillment;
 pub mod demo;
+pub mod scheduler;
+pub mod webhooks;
+pub mod inventory;
+pub mod pricing;
 
 pub use adapter::{RetailPlatform, PlatformConfig, RetailError, PlatformType};
 pub use listings::{Listing, ListingUpdate, PriceUpdate};
 pub use orders::{Order, OrderItem, OrderStatus};
 pub use fulfillment::{Fulfillment, ShipmentStatus, TrackingInfo};
 pub use demo::{DemoRetailPlatform, RetailFactory};
-
+pub use scheduler::{RequestScheduler, ScheduledPlatform, RateLimit, RequestClass, QuotaUsage};
+pub use webhooks::{OrderIngestor, OrderEvent, OrderEventKind, ShopifyWebhook, SpApiPoller, NotificationQueue, WebhookError};
+pub use inventory::{InventoryLedger, InventorySync, InventoryConfig, InventoryReport, InventoryError, StockLevel};
+pub use pricing::{RepricingEngine, RepricingPolicy, PricingRule, PricingSnapshot, PricingData, PriceDecision, PriceChangeRecord};
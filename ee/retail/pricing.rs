@@ -0,0 +1,360 @@
+//! Repricing Rules Engine
+//!
+//! Computes listing prices from a per-SKU [`RepricingPolicy`]:
+//! - Adjustments, applied in order: match a competitor with a delta,
+//!   move with sales velocity
+//! - Guards, applied last: maximum change per run, floor/ceiling, and a
+//!   minimum margin over cost that no rule can undercut
+//!
+//! [`RepricingEngine::preview`] is a dry run; [`RepricingEngine::run`]
+//! pushes the resulting [`PriceUpdate`]s and records every change in the
+//! audit trail.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let engine = Arc::new(RepricingEngine::new(RepricingPolicy::new()
+//!     .with_rule(PricingRule::CompetitorDelta { delta: -0.01, percent: false })
+//!     .with_min_margin(0.15)
+//!     .with_max_change(0.10)));
+//!
+//! for decision in engine.preview(&snapshots) {
+//!     println!("{}: {} -> {} ({})", decision.sku, decision.old_price, decision.new_price, decision.reasons.join(", "));
+//! }
+//! engine.clone().spawn(platform, pricing_data, skus, Duration::from_secs(3600));
+//! ```
+
+use super::adapter::{RetailError, RetailPlatform};
+use super::listings::PriceUpdate;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Market data for one SKU.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingSnapshot {
+    pub sku: String,
+    pub current_price: f64,
+    pub currency: String,
+    /// Landed unit cost
+    pub cost: Option<f64>,
+    /// Competitor offers for the same product
+    #[serde(default)]
+    pub competitor_prices: Vec<f64>,
+    /// Recent units sold per day
+    pub units_per_day: Option<f64>,
+}
+
+/// Source of pricing snapshots (cost, competitor and sales data).
+#[async_trait]
+pub trait PricingData: Send + Sync {
+    async fn snapshot(&self, sku: &str) -> Result<PricingSnapshot, RetailError>;
+}
+
+/// Price adjustment rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PricingRule {
+    /// Lowest competitor price plus `delta` (absolute, or a fraction when
+    /// `percent`); negative undercuts
+    CompetitorDelta { delta: f64, percent: bool },
+    /// Lower the price by `step` (fraction) when selling below the target
+    /// rate, raise it when selling above
+    Velocity { target_units_per_day: f64, step: f64 },
+}
+
+/// Pricing policy of a SKU.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepricingPolicy {
+    #[serde(default)]
+    pub rules: Vec<PricingRule>,
+    pub floor: Option<f64>,
+    pub ceiling: Option<f64>,
+    /// Minimum gross margin, `(price - cost) / price`
+    pub min_margin: Option<f64>,
+    /// Largest change per run, as a fraction of the current price
+    pub max_change: Option<f64>,
+}
+
+impl RepricingPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, rule: PricingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_bounds(mut self, floor: f64, ceiling: f64) -> Self {
+        self.floor = Some(floor);
+        self.ceiling = Some(ceiling);
+        self
+    }
+
+    pub fn with_min_margin(mut self, margin: f64) -> Self {
+        self.min_margin = Some(margin);
+        self
+    }
+
+    pub fn with_max_change(mut self, fraction: f64) -> Self {
+        self.max_change = Some(fraction);
+        self
+    }
+
+    /// Price for a snapshot, with the reason for every step.
+    pub fn evaluate(&self, snapshot: &PricingSnapshot) -> PriceDecision {
+        let old = snapshot.current_price;
+        let mut price = old;
+        let mut reasons = Vec::new();
+
+        for rule in &self.rules {
+            match rule {
+                PricingRule::CompetitorDelta { delta, percent } => {
+                    let Some(lowest) = snapshot.competitor_prices.iter().copied().filter(|p| *p > 0.0).reduce(f64::min) else {
+                        continue;
+                    };
+                    price = if *percent { lowest * (1.0 + delta) } else { lowest + delta };
+                    reasons.push(format!("competitor {:.2}{:+}{}", lowest, delta, if *percent { " (fraction)" } else { "" }));
+                }
+                PricingRule::Velocity { target_units_per_day, step } => {
+                    let Some(rate) = snapshot.units_per_day else { continue };
+                    if rate < *target_units_per_day {
+                        price *= 1.0 - step;
+                        reasons.push(format!("velocity {:.1}/day below {:.1}", rate, target_units_per_day));
+                    } else if rate > *target_units_per_day {
+                        price *= 1.0 + step;
+                        reasons.push(format!("velocity {:.1}/day above {:.1}", rate, target_units_per_day));
+                    }
+                }
+            }
+        }
+
+        if let Some(max) = self.max_change {
+            let (low, high) = (old * (1.0 - max), old * (1.0 + max));
+            if price < low || price > high {
+                price = price.clamp(low, high);
+                reasons.push(format!("limited to {:.0}% change", max * 100.0));
+            }
+        }
+        if let Some(floor) = self.floor.filter(|f| price < *f) {
+            price = floor;
+            reasons.push(format!("floor {:.2}", floor));
+        }
+        if let Some(ceiling) = self.ceiling.filter(|c| price > *c) {
+            price = ceiling;
+            reasons.push(format!("ceiling {:.2}", ceiling));
+        }
+        let mut new_price = (price * 100.0).round() / 100.0;
+        // Margin wins over every other rule, including the ceiling
+        if let (Some(cost), Some(margin)) = (snapshot.cost, self.min_margin) {
+            let minimum = cost / (1.0 - margin.min(0.99));
+            if new_price < minimum {
+                new_price = (minimum * 100.0).ceil() / 100.0;
+                reasons.push(format!("margin guard {:.0}% over cost {:.2}", margin * 100.0, cost));
+            }
+        }
+
+        PriceDecision {
+            sku: snapshot.sku.clone(),
+            currency: snapshot.currency.clone(),
+            old_price: old,
+            new_price,
+            changed: (new_price - old).abs() >= 0.005,
+            reasons,
+        }
+    }
+}
+
+/// Computed price of a SKU.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceDecision {
+    pub sku: String,
+    pub currency: String,
+    pub old_price: f64,
+    pub new_price: f64,
+    pub changed: bool,
+    pub reasons: Vec<String>,
+}
+
+impl PriceDecision {
+    pub fn to_update(&self) -> PriceUpdate {
+        PriceUpdate {
+            amount: self.new_price,
+            currency: self.currency.clone(),
+            sale_price: None,
+            sale_start: None,
+            sale_end: None,
+        }
+    }
+}
+
+/// Audit record of a price change pushed to a platform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceChangeRecord {
+    pub id: String,
+    pub platform_id: String,
+    pub decision: PriceDecision,
+    pub at: DateTime<Utc>,
+    /// Platform error, if the update failed
+    pub error: Option<String>,
+}
+
+/// Outcome of a repricing run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepricingRun {
+    pub applied: Vec<PriceDecision>,
+    pub unchanged: usize,
+    /// (sku, error)
+    pub failed: Vec<(String, String)>,
+}
+
+/// Applies repricing policies and keeps the audit trail.
+pub struct RepricingEngine {
+    default_policy: RepricingPolicy,
+    policies: HashMap<String, RepricingPolicy>,
+    audit: Mutex<Vec<PriceChangeRecord>>,
+}
+
+impl RepricingEngine {
+    pub fn new(default_policy: RepricingPolicy) -> Self {
+        Self { default_policy, policies: HashMap::new(), audit: Mutex::new(Vec::new()) }
+    }
+
+    /// Policy for one SKU instead of the default.
+    pub fn with_policy(mut self, sku: &str, policy: RepricingPolicy) -> Self {
+        self.policies.insert(sku.to_string(), policy);
+        self
+    }
+
+    pub fn policy(&self, sku: &str) -> &RepricingPolicy {
+        self.policies.get(sku).unwrap_or(&self.default_policy)
+    }
+
+    /// Dry run: what each SKU would be repriced to.
+    pub fn preview(&self, snapshots: &[PricingSnapshot]) -> Vec<PriceDecision> {
+        snapshots.iter().map(|s| self.policy(&s.sku).evaluate(s)).collect()
+    }
+
+    /// Reprice `skus` on a platform.
+    pub async fn run(&self, platform: &dyn RetailPlatform, data: &dyn PricingData, skus: &[String]) -> RepricingRun {
+        let mut run = RepricingRun::default();
+        for sku in skus {
+            let snapshot = match data.snapshot(sku).await {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    run.failed.push((sku.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let decision = self.policy(sku).evaluate(&snapshot);
+            if !decision.changed {
+                run.unchanged += 1;
+                continue;
+            }
+            let result = platform.update_price(sku, &decision.to_update()).await;
+            self.audit.lock().unwrap().push(PriceChangeRecord {
+                id: uuid::Uuid::new_v4().to_string(),
+                platform_id: platform.platform_id().to_string(),
+                decision: decision.clone(),
+                at: Utc::now(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            });
+            match result {
+                Ok(()) => run.applied.push(decision),
+                Err(e) => run.failed.push((sku.clone(), e.to_string())),
+            }
+        }
+        run
+    }
+
+    /// Reprice every `interval` until the task is aborted.
+    pub fn spawn(
+        self: Arc<Self>,
+        platform: Arc<dyn RetailPlatform>,
+        data: Arc<dyn PricingData>,
+        skus: Vec<String>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                self.run(platform.as_ref(), data.as_ref(), &skus).await;
+            }
+        })
+    }
+
+    /// Recorded price changes, optionally for one SKU.
+    pub fn audit_trail(&self, sku: Option<&str>) -> Vec<PriceChangeRecord> {
+        self.audit.lock().unwrap().iter()
+            .filter(|r| sku.is_none_or(|s| r.decision.sku == s))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::adapter::PlatformType;
+    use super::super::DemoRetailPlatform;
+
+    fn snapshot(competitors: Vec<f64>, units_per_day: Option<f64>) -> PricingSnapshot {
+        PricingSnapshot {
+            sku: "MUG-1".into(),
+            current_price: 20.0,
+            currency: "USD".into(),
+            cost: Some(15.0),
+            competitor_prices: competitors,
+            units_per_day,
+        }
+    }
+
+    struct Snapshots;
+
+    #[async_trait]
+    impl PricingData for Snapshots {
+        async fn snapshot(&self, sku: &str) -> Result<PricingSnapshot, RetailError> {
+            match sku {
+                "MUG-1" => Ok(snapshot(vec![19.50], None)),
+                _ => Err(RetailError::NotFound(sku.into())),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repricing_rules_and_guards() {
+        let policy = RepricingPolicy::new()
+            .with_rule(PricingRule::CompetitorDelta { delta: -0.01, percent: false })
+            .with_rule(PricingRule::Velocity { target_units_per_day: 5.0, step: 0.05 })
+            .with_bounds(10.0, 30.0)
+            .with_min_margin(0.2)
+            .with_max_change(0.10);
+        let engine = RepricingEngine::new(policy);
+
+        // Undercut the competitor by a cent
+        let decisions = engine.preview(&[snapshot(vec![21.0, 19.50], None)]);
+        assert_eq!(decisions[0].new_price, 19.49);
+
+        // Slow sales push lower, but margin (cost 15 at 20%) holds at 18.75
+        let decision = &engine.preview(&[snapshot(vec![17.0], Some(1.0))])[0];
+        assert_eq!(decision.new_price, 18.75);
+        assert!(decision.reasons.last().unwrap().starts_with("margin guard"));
+
+        // Fast sales with no competitors: +5%, within the 10% cap
+        assert_eq!(engine.preview(&[snapshot(vec![], Some(9.0))])[0].new_price, 21.0);
+
+        let platform = DemoRetailPlatform::new(PlatformType::Shopify);
+        let run = engine.run(&platform, &Snapshots, &["MUG-1".into(), "GONE".into()]).await;
+        assert_eq!(run.applied.len(), 1);
+        assert_eq!(run.failed[0].0, "GONE");
+        let trail = engine.audit_trail(Some("MUG-1"));
+        assert_eq!(trail.len(), 1);
+        assert_eq!((trail[0].decision.old_price, trail[0].decision.new_price), (20.0, 19.49));
+    }
+}
@@ -0,0 +1,460 @@
+//! Rate-Limit-Aware Request Scheduler
+//!
+//! Marketplaces throttle per seller account, so every call to a platform
+//! goes through one token bucket per `platform_id`:
+//! - Bursts up to the bucket capacity, then the sustained rate
+//! - Waiting requests are served by priority (orders before fulfillment,
+//!   inventory, pricing and listings), FIFO within a priority
+//! - A `RateLimited` response empties the bucket and blocks the platform
+//!   for its `Retry-After` (or an exponential backoff), then retries
+//! - Quota usage is exported as Prometheus text
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let scheduler = Arc::new(RequestScheduler::new()
+//!     .with_limit("shop-1", RateLimit::for_platform(PlatformType::Shopify)));
+//! let shop = ScheduledPlatform::new(shopify_adapter, scheduler.clone());
+//!
+//! // Throttled and retried transparently; orders jump the listing queue
+//! let orders = shop.get_orders(&OrderFilter::default()).await?;
+//! println!("{}", scheduler.prometheus());
+//! ```
+
+use super::adapter::*;
+use super::{Fulfillment, Listing, ListingUpdate, Order, PriceUpdate};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Sustained requests per second
+    pub rate_per_sec: f64,
+    /// Bucket capacity
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(rate_per_sec: f64, burst: u32) -> Self {
+        Self { rate_per_sec, burst: burst.max(1) }
+    }
+
+    /// Published default limits.
+    pub fn for_platform(platform: PlatformType) -> Self {
+        match platform {
+            // SP-API getOrders: 0.0167 rps, burst 20
+            PlatformType::AmazonMarketplace => Self::new(0.0167, 20),
+            // REST Admin API leaky bucket: 2 rps, 40 requests
+            PlatformType::Shopify => Self::new(2.0, 40),
+            PlatformType::Walmart => Self::new(5.0, 10),
+            PlatformType::Ebay => Self::new(5.0, 5),
+            PlatformType::Magento | PlatformType::WooCommerce | PlatformType::Custom => Self::new(10.0, 20),
+        }
+    }
+
+    /// Platform defaults, with `PlatformConfig::rate_limit` as the sustained rate.
+    pub fn for_config(config: &PlatformConfig) -> Self {
+        let default = Self::for_platform(config.platform);
+        match config.rate_limit {
+            Some(rps) => Self::new(rps as f64, default.burst),
+            None => default,
+        }
+    }
+}
+
+/// Kind of request, highest priority first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RequestClass {
+    Orders,
+    Fulfillment,
+    Inventory,
+    Pricing,
+    Listings,
+}
+
+impl RequestClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestClass::Orders => "orders",
+            RequestClass::Fulfillment => "fulfillment",
+            RequestClass::Inventory => "inventory",
+            RequestClass::Pricing => "pricing",
+            RequestClass::Listings => "listings",
+        }
+    }
+}
+
+/// Quota usage of one platform.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub platform_id: String,
+    pub limit: Option<RateLimit>,
+    /// Tokens left in the bucket
+    pub available: f64,
+    /// Requests waiting for a token
+    pub queued: usize,
+    /// Requests sent, by class
+    pub requests: BTreeMap<String, u64>,
+    /// Responses rejected by the platform as rate limited
+    pub throttled: u64,
+    pub retries: u64,
+    /// Total time requests spent waiting for a token
+    pub waited: Duration,
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled: Instant,
+    /// Set by a `RateLimited` response
+    blocked_until: Option<Instant>,
+    /// (class, ticket) of waiting requests
+    waiting: BTreeSet<(RequestClass, u64)>,
+    usage: QuotaUsage,
+}
+
+impl Bucket {
+    fn new(platform_id: &str, limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            refilled: Instant::now(),
+            blocked_until: None,
+            waiting: BTreeSet::new(),
+            usage: QuotaUsage { platform_id: platform_id.to_string(), limit: Some(limit), ..Default::default() },
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.rate_per_sec).min(self.limit.burst as f64);
+        self.refilled = now;
+    }
+
+    /// Take a token for `ticket`, or how long to wait before trying again.
+    fn try_take(&mut self, ticket: (RequestClass, u64), now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if let Some(until) = self.blocked_until {
+            if until > now {
+                return Err(until - now);
+            }
+            self.blocked_until = None;
+        }
+        if self.waiting.first() != Some(&ticket) {
+            // Someone more urgent goes first; re-check soon
+            return Err(Duration::from_millis(10));
+        }
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            self.waiting.remove(&ticket);
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.limit.rate_per_sec.max(f64::EPSILON)))
+        }
+    }
+}
+
+/// Per-platform token-bucket scheduler.
+pub struct RequestScheduler {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    limits: HashMap<String, RateLimit>,
+    default_limit: RateLimit,
+    next_ticket: std::sync::atomic::AtomicU64,
+    max_retries: u32,
+    /// First backoff when the platform sends no Retry-After
+    base_backoff: Duration,
+}
+
+impl RequestScheduler {
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            limits: HashMap::new(),
+            default_limit: RateLimit::for_platform(PlatformType::Custom),
+            next_ticket: std::sync::atomic::AtomicU64::new(0),
+            max_retries: 3,
+            base_backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Limit for one platform account.
+    pub fn with_limit(mut self, platform_id: &str, limit: RateLimit) -> Self {
+        self.limits.insert(platform_id.to_string(), limit);
+        self
+    }
+
+    /// Limit for platforms without their own.
+    pub fn with_default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = limit;
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_base_backoff(mut self, backoff: Duration) -> Self {
+        self.base_backoff = backoff;
+        self
+    }
+
+    /// Adjust the sustained rate from a platform response header such as
+    /// SP-API `x-amzn-RateLimit-Limit`.
+    pub fn update_rate(&self, platform_id: &str, rate_per_sec: f64) {
+        if rate_per_sec > 0.0 {
+            let mut buckets = self.buckets.lock().unwrap();
+            let bucket = self.bucket(&mut buckets, platform_id);
+            bucket.refill(Instant::now());
+            bucket.limit.rate_per_sec = rate_per_sec;
+            bucket.usage.limit = Some(bucket.limit);
+        }
+    }
+
+    /// Run `call` within the platform's quota, retrying while it is rate limited.
+    pub async fn run<T, F, Fut>(&self, platform_id: &str, class: RequestClass, mut call: F) -> Result<T, RetailError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, RetailError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.acquire(platform_id, class).await;
+            match call().await {
+                Err(RetailError::RateLimited { retry_after }) if attempt < self.max_retries => {
+                    let backoff = retry_after.unwrap_or(self.base_backoff * 2u32.pow(attempt));
+                    let mut buckets = self.buckets.lock().unwrap();
+                    let bucket = self.bucket(&mut buckets, platform_id);
+                    bucket.tokens = 0.0;
+                    bucket.blocked_until = Some(Instant::now() + backoff);
+                    bucket.usage.throttled += 1;
+                    bucket.usage.retries += 1;
+                    attempt += 1;
+                }
+                Err(e @ RetailError::RateLimited { .. }) => {
+                    self.bucket(&mut self.buckets.lock().unwrap(), platform_id).usage.throttled += 1;
+                    return Err(e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn acquire(&self, platform_id: &str, class: RequestClass) {
+        let ticket = (class, self.next_ticket.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+        let started = Instant::now();
+        // Removes the ticket if the caller gives up while queued
+        let mut guard = TicketGuard { scheduler: self, platform_id, ticket, queued: true };
+        self.bucket(&mut self.buckets.lock().unwrap(), platform_id).waiting.insert(ticket);
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = self.bucket(&mut buckets, platform_id);
+                match bucket.try_take(ticket, Instant::now()) {
+                    Ok(()) => {
+                        guard.queued = false;
+                        *bucket.usage.requests.entry(class.as_str().to_string()).or_default() += 1;
+                        bucket.usage.waited += started.elapsed();
+                        return;
+                    }
+                    Err(wait) => wait,
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn bucket<'a>(&self, buckets: &'a mut HashMap<String, Bucket>, platform_id: &str) -> &'a mut Bucket {
+        buckets.entry(platform_id.to_string()).or_insert_with(|| {
+            Bucket::new(platform_id, self.limits.get(platform_id).copied().unwrap_or(self.default_limit))
+        })
+    }
+
+    /// Quota usage of a platform.
+    pub fn usage(&self, platform_id: &str) -> QuotaUsage {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = self.bucket(&mut buckets, platform_id);
+        bucket.refill(Instant::now());
+        QuotaUsage { available: bucket.tokens, queued: bucket.waiting.len(), ..bucket.usage.clone() }
+    }
+
+    /// Quota metrics in Prometheus text format.
+    pub fn prometheus(&self) -> String {
+        let mut ids: Vec<_> = self.buckets.lock().unwrap().keys().cloned().collect();
+        ids.sort();
+        let usages: Vec<_> = ids.iter().map(|id| self.usage(id)).collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE agentkern_retail_requests_total counter");
+        for usage in &usages {
+            for (class, count) in &usage.requests {
+                let _ = writeln!(out, "agentkern_retail_requests_total{{platform=\"{}\",class=\"{}\"}} {}", escape(&usage.platform_id), class, count);
+            }
+        }
+        let _ = writeln!(out, "# TYPE agentkern_retail_throttled_total counter");
+        for usage in &usages {
+            let _ = writeln!(out, "agentkern_retail_throttled_total{{platform=\"{}\"}} {}", escape(&usage.platform_id), usage.throttled);
+        }
+        let _ = writeln!(out, "# TYPE agentkern_retail_wait_seconds_total counter");
+        for usage in &usages {
+            let _ = writeln!(out, "agentkern_retail_wait_seconds_total{{platform=\"{}\"}} {:.3}", escape(&usage.platform_id), usage.waited.as_secs_f64());
+        }
+        let _ = writeln!(out, "# TYPE agentkern_retail_quota_available gauge");
+        for usage in &usages {
+            let _ = writeln!(out, "agentkern_retail_quota_available{{platform=\"{}\"}} {:.2}", escape(&usage.platform_id), usage.available);
+        }
+        let _ = writeln!(out, "# TYPE agentkern_retail_queue_depth gauge");
+        for usage in &usages {
+            let _ = writeln!(out, "agentkern_retail_queue_depth{{platform=\"{}\"}} {}", escape(&usage.platform_id), usage.queued);
+        }
+        out
+    }
+}
+
+impl Default for RequestScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct TicketGuard<'a> {
+    scheduler: &'a RequestScheduler,
+    platform_id: &'a str,
+    ticket: (RequestClass, u64),
+    queued: bool,
+}
+
+impl Drop for TicketGuard<'_> {
+    fn drop(&mut self) {
+        if self.queued {
+            if let Some(bucket) = self.scheduler.buckets.lock().unwrap().get_mut(self.platform_id) {
+                bucket.waiting.remove(&self.ticket);
+            }
+        }
+    }
+}
+
+/// `Retry-After` header value in seconds.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<f64>().ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Platform adapter whose calls go through a [`RequestScheduler`].
+pub struct ScheduledPlatform<P> {
+    inner: P,
+    scheduler: Arc<RequestScheduler>,
+}
+
+impl<P: RetailPlatform> ScheduledPlatform<P> {
+    pub fn new(inner: P, scheduler: Arc<RequestScheduler>) -> Self {
+        Self { inner, scheduler }
+    }
+
+    pub fn scheduler(&self) -> &Arc<RequestScheduler> {
+        &self.scheduler
+    }
+}
+
+#[async_trait]
+impl<P: RetailPlatform> RetailPlatform for ScheduledPlatform<P> {
+    fn platform_id(&self) -> &str {
+        self.inner.platform_id()
+    }
+
+    fn platform_type(&self) -> PlatformType {
+        self.inner.platform_type()
+    }
+
+    async fn get_listing(&self, sku: &str) -> Result<Listing, RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Listings, || self.inner.get_listing(sku)).await
+    }
+
+    async fn update_listing(&self, update: &ListingUpdate) -> Result<(), RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Listings, || self.inner.update_listing(update)).await
+    }
+
+    async fn update_price(&self, sku: &str, price: &PriceUpdate) -> Result<(), RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Pricing, || self.inner.update_price(sku, price)).await
+    }
+
+    async fn get_orders(&self, filter: &OrderFilter) -> Result<Vec<Order>, RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Orders, || self.inner.get_orders(filter)).await
+    }
+
+    async fn acknowledge_order(&self, order_id: &str) -> Result<(), RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Orders, || self.inner.acknowledge_order(order_id)).await
+    }
+
+    async fn submit_fulfillment(&self, fulfillment: &Fulfillment) -> Result<(), RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Fulfillment, || self.inner.submit_fulfillment(fulfillment)).await
+    }
+
+    async fn get_inventory(&self, sku: &str) -> Result<InventoryLevel, RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Inventory, || self.inner.get_inventory(sku)).await
+    }
+
+    async fn update_inventory(&self, sku: &str, quantity: i32) -> Result<(), RetailError> {
+        self.scheduler.run(self.platform_id(), RequestClass::Inventory, || self.inner.update_inventory(sku, quantity)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_priority_and_retry_after() {
+        let scheduler = Arc::new(RequestScheduler::new().with_limit("shop", RateLimit::new(1.0, 2)));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Burst of 2 goes straight through
+        for _ in 0..2 {
+            scheduler.run("shop", RequestClass::Listings, || async { Ok(()) }).await.unwrap();
+        }
+
+        // Bucket empty: a listing queued first still waits for the order
+        let mut tasks = Vec::new();
+        for class in [RequestClass::Listings, RequestClass::Orders] {
+            let (scheduler, order) = (scheduler.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                scheduler.run("shop", class, || async { order.lock().unwrap().push(class); Ok(()) }).await
+            }));
+            tokio::task::yield_now().await;
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![RequestClass::Orders, RequestClass::Listings]);
+
+        // Retry-After is honored, then the call succeeds
+        let calls = AtomicU32::new(0);
+        let started = Instant::now();
+        let result = scheduler.run("shop", RequestClass::Orders, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(RetailError::RateLimited { retry_after: parse_retry_after("5") }),
+                _ => Ok(42),
+            }
+        }).await;
+        assert_eq!(result.unwrap(), 42);
+        assert!(started.elapsed() >= Duration::from_secs(5));
+
+        let usage = scheduler.usage("shop");
+        assert_eq!(usage.throttled, 1);
+        assert_eq!(usage.requests["orders"], 3);
+        assert!(scheduler.prometheus().contains("agentkern_retail_throttled_total{platform=\"shop\"} 1"));
+    }
+}
@@ -0,0 +1,543 @@
+//! Order Ingestion
+//!
+//! Receives platform order notifications and normalizes them into
+//! [`Order`] events for downstream agents:
+//! - Shopify: `orders/*` webhooks, authenticated with `X-Shopify-Hmac-Sha256`
+//! - Amazon SP-API: `ORDER_CHANGE` notifications pulled from an
+//!   SQS-compatible queue
+//!
+//! Deliveries are de-duplicated by webhook/notification ID, since both
+//! platforms deliver at least once.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let ingestor = Arc::new(OrderIngestor::new()
+//!     .with_shopify_secret("acme.myshopify.com", shopify_secret));
+//! let mut events = ingestor.subscribe();
+//!
+//! // HTTP handler
+//! let webhook = ShopifyWebhook::from_headers(request_headers)?;
+//! ingestor.ingest_shopify(&webhook, &body)?;
+//!
+//! // SP-API
+//! let poller = SpApiPoller::new(sqs_queue, ingestor.clone());
+//! poller.poll_once().await?;
+//!
+//! while let Ok(event) = events.recv().await { /* ... */ }
+//! ```
+
+use super::adapter::PlatformType;
+use super::orders::{Address, FulfillmentChannel, Order, OrderItem, OrderStatus, OrderTotal};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Delivery IDs remembered for de-duplication.
+const DEDUP_WINDOW: usize = 10_000;
+
+/// Normalized order event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderEvent {
+    /// Platform delivery ID
+    pub event_id: String,
+    pub platform: PlatformType,
+    /// Shop domain or seller ID
+    pub platform_id: String,
+    pub kind: OrderEventKind,
+    pub order: Order,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Order event kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderEventKind {
+    Created,
+    Updated,
+    Canceled,
+}
+
+/// Shopify webhook delivery headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShopifyWebhook {
+    /// `X-Shopify-Topic`, e.g. `orders/create`
+    pub topic: String,
+    /// `X-Shopify-Shop-Domain`
+    pub shop_domain: String,
+    /// `X-Shopify-Webhook-Id`
+    pub webhook_id: String,
+    /// `X-Shopify-Hmac-Sha256` (base64)
+    pub hmac: String,
+}
+
+impl ShopifyWebhook {
+    /// Read from request headers (names are case-insensitive).
+    pub fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self, WebhookError> {
+        let headers: HashMap<String, &str> = headers.into_iter().map(|(k, v)| (k.to_ascii_lowercase(), v)).collect();
+        let get = |name: &'static str| {
+            headers.get(name).map(|v| v.to_string()).ok_or(WebhookError::MissingHeader(name))
+        };
+        Ok(Self {
+            topic: get("x-shopify-topic")?,
+            shop_domain: get("x-shopify-shop-domain")?,
+            webhook_id: get("x-shopify-webhook-id")?,
+            hmac: get("x-shopify-hmac-sha256")?,
+        })
+    }
+}
+
+/// Check a Shopify webhook signature over the raw request body.
+pub fn verify_shopify_hmac(secret: &[u8], body: &[u8], signature_b64: &str) -> bool {
+    use base64::Engine;
+    let Ok(signature) = base64::engine::general_purpose::STANDARD.decode(signature_b64.trim()) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Validates, normalizes and publishes order notifications.
+pub struct OrderIngestor {
+    shopify_secrets: HashMap<String, Vec<u8>>,
+    events: broadcast::Sender<OrderEvent>,
+    seen: Mutex<(HashSet<String>, VecDeque<String>)>,
+}
+
+impl OrderIngestor {
+    pub fn new() -> Self {
+        Self {
+            shopify_secrets: HashMap::new(),
+            events: broadcast::channel(1024).0,
+            seen: Mutex::new((HashSet::new(), VecDeque::new())),
+        }
+    }
+
+    /// Webhook signing secret of a shop.
+    pub fn with_shopify_secret(mut self, shop_domain: &str, secret: impl Into<Vec<u8>>) -> Self {
+        self.shopify_secrets.insert(shop_domain.to_ascii_lowercase(), secret.into());
+        self
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderEvent> {
+        self.events.subscribe()
+    }
+
+    /// Handle a Shopify webhook. Returns `None` for duplicate deliveries.
+    pub fn ingest_shopify(&self, webhook: &ShopifyWebhook, body: &[u8]) -> Result<Option<OrderEvent>, WebhookError> {
+        let secret = self.shopify_secrets.get(&webhook.shop_domain.to_ascii_lowercase())
+            .ok_or_else(|| WebhookError::UnknownSource(webhook.shop_domain.clone()))?;
+        if !verify_shopify_hmac(secret, body, &webhook.hmac) {
+            return Err(WebhookError::InvalidSignature);
+        }
+        let kind = match webhook.topic.as_str() {
+            "orders/create" => OrderEventKind::Created,
+            "orders/cancelled" => OrderEventKind::Canceled,
+            "orders/updated" | "orders/edited" | "orders/paid" | "orders/fulfilled" | "orders/partially_fulfilled" => {
+                OrderEventKind::Updated
+            }
+            other => return Err(WebhookError::UnsupportedTopic(other.to_string())),
+        };
+        let order: ShopifyOrder = serde_json::from_slice(body).map_err(|e| WebhookError::Payload(e.to_string()))?;
+        Ok(self.publish(OrderEvent {
+            event_id: format!("shopify:{}", webhook.webhook_id),
+            platform: PlatformType::Shopify,
+            platform_id: webhook.shop_domain.clone(),
+            kind,
+            order: order.normalize()?,
+            received_at: Utc::now(),
+        }))
+    }
+
+    /// Handle an SP-API notification body. Returns `None` for duplicates and
+    /// notifications other than `ORDER_CHANGE`.
+    pub fn ingest_sp_api(&self, body: &str) -> Result<Option<OrderEvent>, WebhookError> {
+        let notification: SpApiNotification = serde_json::from_str(body).map_err(|e| WebhookError::Payload(e.to_string()))?;
+        if notification.notification_type != "ORDER_CHANGE" {
+            return Ok(None);
+        }
+        let change = notification.payload.order_change_notification
+            .ok_or_else(|| WebhookError::Payload("missing OrderChangeNotification".into()))?;
+        let kind = match change.summary.order_status.as_str() {
+            "Canceled" => OrderEventKind::Canceled,
+            "Pending" if change.order_change_type == "OrderStatusChange" => OrderEventKind::Created,
+            _ => OrderEventKind::Updated,
+        };
+        Ok(self.publish(OrderEvent {
+            event_id: format!("sp-api:{}", notification.notification_metadata.notification_id),
+            platform: PlatformType::AmazonMarketplace,
+            platform_id: change.seller_id.clone(),
+            kind,
+            order: change.normalize(),
+            received_at: Utc::now(),
+        }))
+    }
+
+    fn publish(&self, event: OrderEvent) -> Option<OrderEvent> {
+        {
+            let mut seen = self.seen.lock().unwrap();
+            let (ids, order) = &mut *seen;
+            if !ids.insert(event.event_id.clone()) {
+                return None;
+            }
+            order.push_back(event.event_id.clone());
+            if order.len() > DEDUP_WINDOW {
+                if let Some(oldest) = order.pop_front() {
+                    ids.remove(&oldest);
+                }
+            }
+        }
+        // No subscribers is fine; the event is still returned
+        let _ = self.events.send(event.clone());
+        Some(event)
+    }
+}
+
+impl Default for OrderIngestor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Deserialize)]
+struct ShopifyOrder {
+    id: u64,
+    created_at: String,
+    cancelled_at: Option<String>,
+    financial_status: Option<String>,
+    fulfillment_status: Option<String>,
+    currency: String,
+    total_price: String,
+    #[serde(default)]
+    line_items: Vec<ShopifyLineItem>,
+    shipping_address: Option<ShopifyAddress>,
+    customer: Option<ShopifyCustomer>,
+}
+
+#[derive(Deserialize)]
+struct ShopifyLineItem {
+    id: u64,
+    sku: Option<String>,
+    product_id: Option<u64>,
+    title: String,
+    quantity: u32,
+    fulfillable_quantity: Option<u32>,
+    price: String,
+}
+
+#[derive(Deserialize)]
+struct ShopifyAddress {
+    name: Option<String>,
+    address1: Option<String>,
+    address2: Option<String>,
+    city: Option<String>,
+    province_code: Option<String>,
+    zip: Option<String>,
+    country_code: Option<String>,
+    phone: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ShopifyCustomer {
+    first_name: Option<String>,
+    last_name: Option<String>,
+}
+
+impl ShopifyOrder {
+    fn normalize(self) -> Result<Order, WebhookError> {
+        let status = match (self.cancelled_at.as_deref(), self.fulfillment_status.as_deref(), self.financial_status.as_deref()) {
+            (Some(_), _, _) => OrderStatus::Canceled,
+            (_, _, Some("refunded")) => OrderStatus::Returned,
+            (_, Some("fulfilled"), _) => OrderStatus::Shipped,
+            (_, Some("partial"), _) => OrderStatus::PartiallyShipped,
+            (_, _, Some("pending" | "authorized")) => OrderStatus::Pending,
+            _ => OrderStatus::Unshipped,
+        };
+        let items = self.line_items.into_iter().map(|item| {
+            Ok(OrderItem {
+                item_id: item.id.to_string(),
+                sku: item.sku.unwrap_or_default(),
+                product_id: item.product_id.map(|id| id.to_string()).unwrap_or_default(),
+                title: item.title,
+                quantity_shipped: item.quantity.saturating_sub(item.fulfillable_quantity.unwrap_or(item.quantity)),
+                quantity_ordered: item.quantity,
+                item_price: parse_money(&item.price)?,
+                currency: self.currency.clone(),
+            })
+        }).collect::<Result<_, WebhookError>>()?;
+        Ok(Order {
+            order_id: self.id.to_string(),
+            purchase_date: self.created_at,
+            status,
+            items,
+            shipping_address: self.shipping_address.map(|a| Address {
+                name: a.name.unwrap_or_default(),
+                line1: a.address1.unwrap_or_default(),
+                line2: a.address2.filter(|l| !l.is_empty()),
+                city: a.city.unwrap_or_default(),
+                state: a.province_code,
+                postal_code: a.zip.unwrap_or_default(),
+                country_code: a.country_code.unwrap_or_default(),
+                phone: a.phone,
+            }),
+            buyer_name: self.customer.and_then(|c| {
+                let name = [c.first_name, c.last_name].into_iter().flatten().collect::<Vec<_>>().join(" ");
+                (!name.is_empty()).then_some(name)
+            }),
+            order_total: OrderTotal { amount: parse_money(&self.total_price)?, currency: self.currency },
+            fulfillment_channel: FulfillmentChannel::Merchant,
+        })
+    }
+}
+
+fn parse_money(value: &str) -> Result<f64, WebhookError> {
+    value.parse().map_err(|_| WebhookError::Payload(format!("invalid amount: {}", value)))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiNotification {
+    notification_type: String,
+    payload: SpApiPayload,
+    notification_metadata: SpApiMetadata,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiPayload {
+    order_change_notification: Option<SpApiOrderChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiMetadata {
+    notification_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiOrderChange {
+    seller_id: String,
+    amazon_order_id: String,
+    order_change_type: String,
+    summary: SpApiSummary,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiSummary {
+    order_status: String,
+    purchase_date: Option<String>,
+    fulfillment_type: Option<String>,
+    #[serde(default)]
+    order_items: Vec<SpApiOrderItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpApiOrderItem {
+    #[serde(rename = "ASIN")]
+    asin: Option<String>,
+    #[serde(rename = "SellerSKU")]
+    seller_sku: Option<String>,
+    order_item_id: Option<String>,
+    quantity: u32,
+}
+
+impl SpApiOrderChange {
+    /// Notifications carry no prices: the total is zero until the order is
+    /// fetched with `get_orders`.
+    fn normalize(self) -> Order {
+        let status = match self.summary.order_status.as_str() {
+            "Pending" | "PendingAvailability" => OrderStatus::Pending,
+            "PartiallyShipped" => OrderStatus::PartiallyShipped,
+            "Shipped" => OrderStatus::Shipped,
+            "Canceled" | "Unfulfillable" => OrderStatus::Canceled,
+            _ => OrderStatus::Unshipped,
+        };
+        let items = self.summary.order_items.into_iter().enumerate().map(|(i, item)| OrderItem {
+            item_id: item.order_item_id.unwrap_or_else(|| format!("{}-{}", self.amazon_order_id, i + 1)),
+            sku: item.seller_sku.unwrap_or_default(),
+            product_id: item.asin.unwrap_or_default(),
+            title: String::new(),
+            quantity_ordered: item.quantity,
+            quantity_shipped: if status == OrderStatus::Shipped { item.quantity } else { 0 },
+            item_price: 0.0,
+            currency: String::new(),
+        }).collect();
+        Order {
+            order_id: self.amazon_order_id,
+            purchase_date: self.summary.purchase_date.unwrap_or_default(),
+            status,
+            items,
+            shipping_address: None,
+            buyer_name: None,
+            order_total: OrderTotal { amount: 0.0, currency: String::new() },
+            fulfillment_channel: match self.summary.fulfillment_type.as_deref() {
+                Some("AFN") => FulfillmentChannel::Platform,
+                _ => FulfillmentChannel::Merchant,
+            },
+        }
+    }
+}
+
+/// Message received from an SQS-compatible queue.
+#[derive(Debug, Clone)]
+pub struct QueueMessage {
+    pub message_id: String,
+    pub receipt_handle: String,
+    pub body: String,
+}
+
+/// SQS-compatible queue (ReceiveMessage / DeleteMessage).
+#[async_trait]
+pub trait NotificationQueue: Send + Sync {
+    async fn receive(&self, max_messages: u32) -> Result<Vec<QueueMessage>, WebhookError>;
+    async fn delete(&self, receipt_handle: &str) -> Result<(), WebhookError>;
+}
+
+/// Pulls SP-API notifications from a queue into an [`OrderIngestor`].
+pub struct SpApiPoller<Q> {
+    queue: Q,
+    ingestor: std::sync::Arc<OrderIngestor>,
+    batch_size: u32,
+}
+
+impl<Q: NotificationQueue> SpApiPoller<Q> {
+    pub fn new(queue: Q, ingestor: std::sync::Arc<OrderIngestor>) -> Self {
+        Self { queue, ingestor, batch_size: 10 }
+    }
+
+    /// Messages per receive (SQS allows up to 10).
+    pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size.clamp(1, 10);
+        self
+    }
+
+    /// Receive one batch. Processed messages are deleted; malformed ones are
+    /// left for the queue's redrive policy. Returns the published events.
+    pub async fn poll_once(&self) -> Result<Vec<OrderEvent>, WebhookError> {
+        let mut events = Vec::new();
+        for message in self.queue.receive(self.batch_size).await? {
+            match self.ingestor.ingest_sp_api(&message.body) {
+                Ok(event) => {
+                    events.extend(event);
+                    self.queue.delete(&message.receipt_handle).await?;
+                }
+                Err(WebhookError::Payload(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Webhook ingestion error.
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Missing header: {0}")]
+    MissingHeader(&'static str),
+
+    #[error("Unknown webhook source: {0}")]
+    UnknownSource(String),
+
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    #[error("Unsupported topic: {0}")]
+    UnsupportedTopic(String),
+
+    #[error("Invalid payload: {0}")]
+    Payload(String),
+
+    #[error("Queue error: {0}")]
+    Queue(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use std::sync::Arc;
+
+    fn sign(secret: &[u8], body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(body);
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn test_shopify_webhook() {
+        let ingestor = OrderIngestor::new().with_shopify_secret("acme.myshopify.com", "shpss_secret");
+        let mut events = ingestor.subscribe();
+        let body = br#"{"id": 820982911946154508, "created_at": "2026-03-01T10:00:00-05:00", "cancelled_at": null,
+            "financial_status": "paid", "fulfillment_status": "partial", "currency": "USD", "total_price": "59.98",
+            "line_items": [{"id": 866550311766439020, "sku": "MUG-1", "product_id": 632910392, "title": "Mug",
+                            "quantity": 2, "fulfillable_quantity": 1, "price": "29.99"}],
+            "customer": {"first_name": "Ada", "last_name": "Lovelace"}}"#;
+        let headers = [
+            ("X-Shopify-Topic", "orders/updated"),
+            ("X-Shopify-Shop-Domain", "acme.myshopify.com"),
+            ("X-Shopify-Webhook-Id", "b54557e4-bdd9-4b37-8a5f-bf7d70bcd043"),
+        ];
+        let hmac = sign(b"shpss_secret", body);
+        let webhook = ShopifyWebhook::from_headers(headers.iter().copied().chain([("x-shopify-hmac-sha256", hmac.as_str())])).unwrap();
+
+        let event = ingestor.ingest_shopify(&webhook, body).unwrap().unwrap();
+        assert_eq!(event.kind, OrderEventKind::Updated);
+        assert_eq!(event.order.status, OrderStatus::PartiallyShipped);
+        assert_eq!(event.order.items[0].quantity_shipped, 1);
+        assert_eq!(event.order.order_total.amount, 59.98);
+        assert_eq!(event.order.buyer_name.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(events.try_recv().unwrap().event_id, event.event_id);
+
+        // Redelivery is dropped, tampering is rejected
+        assert!(ingestor.ingest_shopify(&webhook, body).unwrap().is_none());
+        let tampered = String::from_utf8_lossy(body).replace("59.98", "0.01");
+        assert!(matches!(ingestor.ingest_shopify(&webhook, tampered.as_bytes()), Err(WebhookError::InvalidSignature)));
+    }
+
+    struct MemoryQueue(Mutex<Vec<QueueMessage>>);
+
+    #[async_trait]
+    impl NotificationQueue for MemoryQueue {
+        async fn receive(&self, max_messages: u32) -> Result<Vec<QueueMessage>, WebhookError> {
+            Ok(self.0.lock().unwrap().iter().take(max_messages as usize).cloned().collect())
+        }
+
+        async fn delete(&self, receipt_handle: &str) -> Result<(), WebhookError> {
+            self.0.lock().unwrap().retain(|m| m.receipt_handle != receipt_handle);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sp_api_notifications() {
+        let body = r#"{"NotificationVersion": "1.0", "NotificationType": "ORDER_CHANGE",
+            "Payload": {"OrderChangeNotification": {"NotificationLevel": "OrderLevel", "SellerId": "A3TH9S8BH6GOGM",
+                "AmazonOrderId": "903-8868176-2219830", "OrderChangeType": "OrderStatusChange",
+                "Summary": {"MarketplaceId": "ATVPDKIKX0DER", "OrderStatus": "Canceled", "PurchaseDate": "2026-03-01T10:00:00Z",
+                    "FulfillmentType": "MFN", "OrderItems": [{"ASIN": "B0EXAMPLE", "SellerSKU": "MUG-1", "Quantity": 2}]}}},
+            "NotificationMetadata": {"ApplicationId": "amzn1.sellerapps.app.f1234566", "NotificationId": "d0e9e693-c3ad-4373-979f-ed4ec98dd746"}}"#;
+        let queue = MemoryQueue(Mutex::new(vec![
+            QueueMessage { message_id: "1".into(), receipt_handle: "r1".into(), body: body.into() },
+            QueueMessage { message_id: "2".into(), receipt_handle: "r2".into(), body: "not json".into() },
+        ]));
+        let poller = SpApiPoller::new(queue, Arc::new(OrderIngestor::new()));
+
+        let events = poller.poll_once().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, OrderEventKind::Canceled);
+        assert_eq!(events[0].order.order_id, "903-8868176-2219830");
+        assert_eq!(events[0].order.items[0].sku, "MUG-1");
+        // The malformed message stays for redrive
+        let remaining = poller.queue.0.lock().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].receipt_handle, "r2");
+    }
+}
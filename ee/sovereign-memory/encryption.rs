@@ -1,8 +1,33 @@
 //! Memory Encryption
 //!
-//! KMS integration for encrypted Memory Passport storage
+//! Envelope encryption for Memory Passport storage:
+//! - Each blob is sealed with a fresh data key (AES-256-GCM or
+//!   ChaCha20-Poly1305); the data key is wrapped by a KMS master key
+//! - Each region can have its own master key and a [`RegionKeyPolicy`]
+//!   mirroring the sovereign mesh rules of its cell, so data encrypted in
+//!   one jurisdiction is only readable where the mesh would let it sync
+//! - After a master key rotation, a [`RewrapJob`] re-wraps stored data
+//!   keys under the new version without touching the ciphertext
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let encryptor = MemoryEncryptor::new(config)?
+//!     .with_region("eu-central-1")
+//!     .with_region_key(RegionKeyPolicy::from_cell(&eu_cell), Arc::new(AwsKms::new("alias/memory-eu", "eu-central-1", creds)));
+//!
+//! let blob = encryptor.encrypt(passport_bytes)?;
+//! encryptor.rotate_key()?;
+//! let report = RewrapJob::new(&encryptor).run(stored_blobs, |id, blob| store.put(id, blob));
+//! ```
 
+use super::kms::{KeyManagementService, LocalKms, WrappedKey};
+use agentkern_sovereign_mesh::SovereignCellConfig;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Key provider type.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -38,95 +63,268 @@ impl Default for EncryptionConfig {
     }
 }
 
+/// Where data under a region's master key may be decrypted.
+///
+/// Same rules as a sovereign mesh sync: the home region is always
+/// permitted, blocked regions never are, and a non-empty allow list must
+/// name the region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionKeyPolicy {
+    /// Home region of the master key
+    pub region: String,
+    pub allowed_regions: Vec<String>,
+    pub blocked_regions: Vec<String>,
+}
+
+impl RegionKeyPolicy {
+    /// Decryptable in the home region only.
+    pub fn new(region: &str) -> Self {
+        Self {
+            region: region.to_string(),
+            allowed_regions: vec![region.to_string()],
+            blocked_regions: Vec::new(),
+        }
+    }
+
+    /// Policy matching a sovereign mesh cell's sync targets.
+    pub fn from_cell(cell: &SovereignCellConfig) -> Self {
+        Self {
+            region: cell.region.clone(),
+            allowed_regions: cell.allowed_sync_targets.clone(),
+            blocked_regions: cell.blocked_sync_targets.clone(),
+        }
+    }
+
+    /// Whether a process in `region` may unwrap data keys.
+    pub fn check(&self, region: &str) -> Result<(), EncryptionError> {
+        let denied = |reason: &str| Err(EncryptionError::RegionNotPermitted {
+            key_region: self.region.clone(),
+            region: region.to_string(),
+            reason: reason.to_string(),
+        });
+        if region == self.region {
+            return Ok(());
+        }
+        if self.blocked_regions.iter().any(|r| r == region) {
+            return denied("region is blocked");
+        }
+        if !self.allowed_regions.is_empty() && !self.allowed_regions.iter().any(|r| r == region) {
+            return denied("region is not in the allow list");
+        }
+        Ok(())
+    }
+}
+
+struct RegionKey {
+    policy: RegionKeyPolicy,
+    kms: Arc<dyn KeyManagementService>,
+}
+
 /// Memory encryptor with KMS integration.
 pub struct MemoryEncryptor {
     config: EncryptionConfig,
+    /// Key for blobs without a region
+    default_key: Option<Arc<dyn KeyManagementService>>,
+    region_keys: HashMap<String, RegionKey>,
+    /// Region this process runs in
+    region: Option<String>,
+    /// Primary master key version per key ID, as last reported by the KMS
+    versions: Mutex<HashMap<String, String>>,
+    rng: SystemRandom,
 }
 
 impl MemoryEncryptor {
-    /// Create new encryptor.
+    /// Create new encryptor. A local key file is loaded here; cloud
+    /// providers need a client from [`with_kms`](Self::with_kms).
     pub fn new(config: EncryptionConfig) -> Result<Self, EncryptionError> {
         crate::connectors::license::check_feature_license("memory_encryption")?;
-        Ok(Self { config })
+        aead_algorithm(&config.algorithm)?;
+        let default_key = match &config.key_provider {
+            KeyProvider::Local { key_path } if !key_path.is_empty() => {
+                Some(Arc::new(LocalKms::from_file(key_path)?) as Arc<dyn KeyManagementService>)
+            }
+            _ => None,
+        };
+        Ok(Self {
+            config,
+            default_key,
+            region_keys: HashMap::new(),
+            region: None,
+            versions: Mutex::new(HashMap::new()),
+            rng: SystemRandom::new(),
+        })
     }
-    
+
+    /// Master key for blobs without a region.
+    pub fn with_kms(mut self, kms: Arc<dyn KeyManagementService>) -> Self {
+        self.default_key = Some(kms);
+        self
+    }
+
+    /// Master key and policy for one region.
+    pub fn with_region_key(mut self, policy: RegionKeyPolicy, kms: Arc<dyn KeyManagementService>) -> Self {
+        self.region_keys.insert(policy.region.clone(), RegionKey { policy, kms });
+        self
+    }
+
+    /// Region this process runs in; new blobs use its key.
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
     /// Encrypt data using envelope encryption.
     pub fn encrypt(&self, plaintext: &[u8]) -> Result<EncryptedBlob, EncryptionError> {
+        let region = self.region.as_ref().filter(|r| self.region_keys.contains_key(*r)).cloned();
+        let kms = self.key_for(region.as_deref())?;
+
         // 1. Generate data encryption key (DEK)
         let dek = self.generate_dek()?;
-        
+
         // 2. Encrypt data with DEK
-        let ciphertext = self.encrypt_with_key(plaintext, &dek)?;
-        
-        // 3. Wrap DEK with KMS key (key encryption key)
-        let wrapped_dek = self.wrap_dek(&dek)?;
-        
-        Ok(EncryptedBlob {
+        let mut blob = EncryptedBlob {
             algorithm: self.config.algorithm.clone(),
-            wrapped_dek,
-            ciphertext,
-            nonce: vec![0u8; 12], // Would be random
-            tag: vec![0u8; 16],   // Would be auth tag
-        })
+            wrapped_dek: Vec::new(),
+            ciphertext: Vec::new(),
+            nonce: self.random(NONCE_LEN)?,
+            tag: Vec::new(),
+            key_id: kms.key_id().to_string(),
+            key_version: None,
+            region,
+        };
+        (blob.ciphertext, blob.tag) = self.encrypt_with_key(plaintext, &dek, &blob)?;
+
+        // 3. Wrap DEK with KMS key (key encryption key)
+        let wrapped = kms.wrap(&dek, blob.context())?;
+        self.note_version(kms.key_id(), &wrapped);
+        blob.wrapped_dek = wrapped.ciphertext;
+        blob.key_version = wrapped.key_version;
+        Ok(blob)
     }
-    
+
     /// Decrypt data.
     pub fn decrypt(&self, blob: &EncryptedBlob) -> Result<Vec<u8>, EncryptionError> {
         // 1. Unwrap DEK with KMS
-        let dek = self.unwrap_dek(&blob.wrapped_dek)?;
-        
+        let kms = self.key_for_blob(blob)?;
+        let dek = kms.unwrap(&blob.wrapped_key(), blob.context())?;
+
         // 2. Decrypt data with DEK
-        let plaintext = self.decrypt_with_key(&blob.ciphertext, &dek, &blob.nonce, &blob.tag)?;
-        
-        Ok(plaintext)
+        self.decrypt_with_key(&dek, blob)
     }
-    
-    /// Rotate the master key.
+
+    /// Rotate the master key new blobs are wrapped with.
     pub fn rotate_key(&self) -> Result<(), EncryptionError> {
-        match &self.config.key_provider {
-            KeyProvider::AwsKms { key_id } => {
-                // Would call RotateKey API
-                Ok(())
-            }
-            KeyProvider::GcpKms { .. } => Ok(()),
-            KeyProvider::AzureKeyVault { .. } => Ok(()),
-            KeyProvider::HashiCorpVault { .. } => Ok(()),
-            KeyProvider::Local { .. } => {
-                Err(EncryptionError::RotationNotSupported)
-            }
-        }
+        let region = self.region.as_deref().filter(|r| self.region_keys.contains_key(*r));
+        let kms = self.key_for(region)?;
+        let version = kms.rotate()?;
+        let mut versions = self.versions.lock().unwrap();
+        match version {
+            Some(version) => versions.insert(kms.key_id().to_string(), version),
+            None => versions.remove(kms.key_id()),
+        };
+        tracing::info!(key_id = %kms.key_id(), "Memory master key rotated");
+        Ok(())
     }
-    
-    /// Re-encrypt with new key (after rotation).
-    pub fn reencrypt(&self, blob: &EncryptedBlob, new_key_id: &str) -> Result<EncryptedBlob, EncryptionError> {
-        // Decrypt with old key, encrypt with new key
+
+    /// Wrap a blob's data key under the current master key version. The
+    /// ciphertext is unchanged.
+    pub fn rewrap(&self, blob: &EncryptedBlob) -> Result<EncryptedBlob, EncryptionError> {
+        let kms = self.key_for_blob(blob)?;
+        let wrapped = kms.rewrap(&blob.wrapped_key(), blob.context())?;
+        self.note_version(kms.key_id(), &wrapped);
+        Ok(EncryptedBlob {
+            wrapped_dek: wrapped.ciphertext,
+            key_version: wrapped.key_version,
+            ..blob.clone()
+        })
+    }
+
+    /// Re-encrypt under a fresh data key and the current master key.
+    pub fn reencrypt(&self, blob: &EncryptedBlob) -> Result<EncryptedBlob, EncryptionError> {
         let plaintext = self.decrypt(blob)?;
         self.encrypt(&plaintext)
     }
-    
+
+    /// Whether the blob's data key is wrapped by the known primary version.
+    /// Unknown versions count as stale.
+    pub fn is_current(&self, blob: &EncryptedBlob) -> bool {
+        match (&blob.key_version, self.versions.lock().unwrap().get(&blob.key_id)) {
+            (Some(version), Some(current)) => version == current,
+            _ => false,
+        }
+    }
+
+    fn key_for(&self, region: Option<&str>) -> Result<&Arc<dyn KeyManagementService>, EncryptionError> {
+        match region {
+            Some(region) => self.region_keys.get(region).map(|key| &key.kms),
+            None => self.default_key.as_ref(),
+        }
+        .ok_or(EncryptionError::KeyNotFound)
+    }
+
+    /// Master key of a blob, after checking its region's policy.
+    fn key_for_blob(&self, blob: &EncryptedBlob) -> Result<&Arc<dyn KeyManagementService>, EncryptionError> {
+        if let Some(key_region) = &blob.region {
+            let key = self.region_keys.get(key_region).ok_or(EncryptionError::KeyNotFound)?;
+            let here = self.region.as_deref().ok_or_else(|| EncryptionError::RegionNotPermitted {
+                key_region: key_region.clone(),
+                region: "unknown".into(),
+                reason: "encryptor has no region".into(),
+            })?;
+            key.policy.check(here)?;
+        }
+        let kms = self.key_for(blob.region.as_deref())?;
+        if kms.key_id() != blob.key_id {
+            return Err(EncryptionError::KeyNotFound);
+        }
+        Ok(kms)
+    }
+
+    fn note_version(&self, key_id: &str, wrapped: &WrappedKey) {
+        if let Some(version) = &wrapped.key_version {
+            self.versions.lock().unwrap().insert(key_id.to_string(), version.clone());
+        }
+    }
+
+    fn random(&self, len: usize) -> Result<Vec<u8>, EncryptionError> {
+        let mut bytes = vec![0u8; len];
+        self.rng.fill(&mut bytes).map_err(|_| EncryptionError::KmsError("no system randomness".into()))?;
+        Ok(bytes)
+    }
+
     fn generate_dek(&self) -> Result<Vec<u8>, EncryptionError> {
-        // Would use RNG
-        Ok(vec![0u8; 32])
+        self.random(32)
     }
-    
-    fn wrap_dek(&self, dek: &[u8]) -> Result<Vec<u8>, EncryptionError> {
-        // Would call KMS.encrypt
-        Ok(dek.to_vec())
+
+    fn sealing_key(algorithm: &str, key: &[u8]) -> Result<LessSafeKey, EncryptionError> {
+        let unbound = UnboundKey::new(aead_algorithm(algorithm)?, key).map_err(|_| EncryptionError::DecryptionFailed)?;
+        Ok(LessSafeKey::new(unbound))
     }
-    
-    fn unwrap_dek(&self, wrapped: &[u8]) -> Result<Vec<u8>, EncryptionError> {
-        // Would call KMS.decrypt
-        Ok(wrapped.to_vec())
+
+    fn encrypt_with_key(&self, data: &[u8], key: &[u8], blob: &EncryptedBlob) -> Result<(Vec<u8>, Vec<u8>), EncryptionError> {
+        let nonce = Nonce::try_assume_unique_for_key(&blob.nonce).map_err(|_| EncryptionError::DecryptionFailed)?;
+        let mut ciphertext = data.to_vec();
+        let tag = Self::sealing_key(&blob.algorithm, key)?
+            .seal_in_place_separate_tag(nonce, Aad::from(blob.aad()), &mut ciphertext)
+            .map_err(|_| EncryptionError::KmsError("encryption failed".into()))?;
+        Ok((ciphertext, tag.as_ref().to_vec()))
     }
-    
-    fn encrypt_with_key(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>, EncryptionError> {
-        // Would use AES-GCM
-        Ok(data.to_vec())
+
+    fn decrypt_with_key(&self, key: &[u8], blob: &EncryptedBlob) -> Result<Vec<u8>, EncryptionError> {
+        let nonce = Nonce::try_assume_unique_for_key(&blob.nonce).map_err(|_| EncryptionError::DecryptionFailed)?;
+        let mut sealed = [blob.ciphertext.as_slice(), blob.tag.as_slice()].concat();
+        let plaintext = Self::sealing_key(&blob.algorithm, key)?
+            .open_in_place(nonce, Aad::from(blob.aad()), &mut sealed)
+            .map_err(|_| EncryptionError::DecryptionFailed)?;
+        Ok(plaintext.to_vec())
     }
-    
-    fn decrypt_with_key(&self, ciphertext: &[u8], key: &[u8], nonce: &[u8], tag: &[u8]) -> Result<Vec<u8>, EncryptionError> {
-        // Would use AES-GCM
-        Ok(ciphertext.to_vec())
+}
+
+fn aead_algorithm(name: &str) -> Result<&'static ring::aead::Algorithm, EncryptionError> {
+    match name {
+        "AES-256-GCM" => Ok(&ring::aead::AES_256_GCM),
+        "ChaCha20-Poly1305" => Ok(&ring::aead::CHACHA20_POLY1305),
+        other => Err(EncryptionError::UnsupportedAlgorithm(other.to_string())),
     }
 }
 
@@ -138,6 +336,75 @@ pub struct EncryptedBlob {
     pub ciphertext: Vec<u8>,
     pub nonce: Vec<u8>,
     pub tag: Vec<u8>,
+    /// Master key that wrapped the data key
+    #[serde(default)]
+    pub key_id: String,
+    #[serde(default)]
+    pub key_version: Option<String>,
+    /// Region whose master key was used; `None` for the default key
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+impl EncryptedBlob {
+    /// Wrapping context passed to the KMS.
+    fn context(&self) -> &str {
+        self.region.as_deref().unwrap_or("global")
+    }
+
+    /// Binds the ciphertext to its algorithm, key and region.
+    fn aad(&self) -> Vec<u8> {
+        format!("agentkern-memory|{}|{}|{}", self.algorithm, self.key_id, self.context()).into_bytes()
+    }
+
+    fn wrapped_key(&self) -> WrappedKey {
+        WrappedKey { ciphertext: self.wrapped_dek.clone(), key_version: self.key_version.clone() }
+    }
+}
+
+/// Outcome of a [`RewrapJob`].
+#[derive(Debug, Clone, Default)]
+pub struct RewrapReport {
+    pub rewrapped: usize,
+    /// Already wrapped by the primary version
+    pub skipped: usize,
+    /// Blob ID and error
+    pub failed: Vec<(String, String)>,
+}
+
+/// Re-wraps stored data keys after a master key rotation.
+pub struct RewrapJob<'a> {
+    encryptor: &'a MemoryEncryptor,
+}
+
+impl<'a> RewrapJob<'a> {
+    pub fn new(encryptor: &'a MemoryEncryptor) -> Self {
+        Self { encryptor }
+    }
+
+    /// Re-wrap each blob and hand it to `save`. A failed blob is reported
+    /// and left as it was; the job carries on with the rest.
+    pub fn run<I, F>(&self, blobs: I, mut save: F) -> RewrapReport
+    where
+        I: IntoIterator<Item = (String, EncryptedBlob)>,
+        F: FnMut(&str, EncryptedBlob) -> Result<(), EncryptionError>,
+    {
+        let mut report = RewrapReport::default();
+        for (id, blob) in blobs {
+            if self.encryptor.is_current(&blob) {
+                report.skipped += 1;
+                continue;
+            }
+            match self.encryptor.rewrap(&blob).and_then(|blob| save(&id, blob)) {
+                Ok(()) => report.rewrapped += 1,
+                Err(e) => {
+                    tracing::warn!(blob = %id, error = %e, "Data key re-wrap failed");
+                    report.failed.push((id, e.to_string()));
+                }
+            }
+        }
+        report
+    }
 }
 
 /// Encryption errors.
@@ -154,6 +421,12 @@ pub enum EncryptionError {
     
     #[error("KMS error: {0}")]
     KmsError(String),
+
+    #[error("Unsupported algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Key from {key_region} may not be used in {region}: {reason}")]
+    RegionNotPermitted { key_region: String, region: String, reason: String },
     
     #[error("License error: {0}")]
     LicenseError(#[from] crate::connectors::license::LicenseError),
@@ -175,4 +448,100 @@ mod tests {
         let aws = KeyProvider::AwsKms { key_id: "alias/my-key".into() };
         assert!(matches!(aws, KeyProvider::AwsKms { .. }));
     }
+
+    /// Versioned key: each version is a local key with its own ID suffix.
+    struct RotatingKms {
+        version: Mutex<u32>,
+    }
+
+    impl RotatingKms {
+        fn key(version: &str) -> LocalKms {
+            LocalKms::new("rotating", &[version.parse::<u8>().unwrap(); 32])
+        }
+    }
+
+    impl KeyManagementService for RotatingKms {
+        fn key_id(&self) -> &str {
+            "rotating"
+        }
+
+        fn wrap(&self, dek: &[u8], context: &str) -> Result<WrappedKey, EncryptionError> {
+            let version = self.version.lock().unwrap().to_string();
+            let wrapped = Self::key(&version).wrap(dek, context)?;
+            Ok(WrappedKey { key_version: Some(version), ..wrapped })
+        }
+
+        fn unwrap(&self, wrapped: &WrappedKey, context: &str) -> Result<Vec<u8>, EncryptionError> {
+            Self::key(wrapped.key_version.as_deref().unwrap()).unwrap(wrapped, context)
+        }
+
+        fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+            let mut version = self.version.lock().unwrap();
+            *version += 1;
+            Ok(Some(version.to_string()))
+        }
+    }
+
+    fn encryptor(region: &str) -> MemoryEncryptor {
+        MemoryEncryptor {
+            config: EncryptionConfig::default(),
+            default_key: Some(Arc::new(LocalKms::new("default", &[9u8; 32]))),
+            region_keys: HashMap::new(),
+            region: Some(region.to_string()),
+            versions: Mutex::new(HashMap::new()),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    #[test]
+    fn test_envelope_round_trip_and_region_policy() {
+        let eu_key: Arc<dyn KeyManagementService> = Arc::new(LocalKms::new("eu", &[1u8; 32]));
+        let cell = SovereignCellConfig {
+            cell_id: "eu-1".into(),
+            region: "eu-central-1".into(),
+            allowed_sync_targets: vec!["eu-west-1".into()],
+            blocked_sync_targets: vec!["us-east-1".into()],
+            attestation_enabled: true,
+        };
+        let eu = encryptor("eu-central-1").with_region_key(RegionKeyPolicy::from_cell(&cell), eu_key.clone());
+
+        let blob = eu.encrypt(b"passport").unwrap();
+        assert_eq!(blob.region.as_deref(), Some("eu-central-1"));
+        assert_ne!(blob.ciphertext, b"passport");
+        assert_eq!(eu.decrypt(&blob).unwrap(), b"passport");
+
+        let mut tampered = blob.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(matches!(eu.decrypt(&tampered), Err(EncryptionError::DecryptionFailed)));
+
+        let dublin = encryptor("eu-west-1").with_region_key(RegionKeyPolicy::from_cell(&cell), eu_key.clone());
+        assert_eq!(dublin.decrypt(&blob).unwrap(), b"passport");
+        let virginia = encryptor("us-east-1").with_region_key(RegionKeyPolicy::from_cell(&cell), eu_key);
+        assert!(matches!(virginia.decrypt(&blob), Err(EncryptionError::RegionNotPermitted { .. })));
+    }
+
+    #[test]
+    fn test_rotation_rewraps_data_keys() {
+        let encryptor = encryptor("us-east-1").with_kms(Arc::new(RotatingKms { version: Mutex::new(1) }));
+        let blobs: Vec<_> = (0..3).map(|i| (format!("blob-{}", i), encryptor.encrypt(b"memory").unwrap())).collect();
+        assert!(blobs.iter().all(|(_, blob)| encryptor.is_current(blob)));
+
+        encryptor.rotate_key().unwrap();
+        let mut saved = HashMap::new();
+        let report = RewrapJob::new(&encryptor).run(blobs.clone(), |id, blob| {
+            saved.insert(id.to_string(), blob);
+            Ok(())
+        });
+        assert_eq!((report.rewrapped, report.skipped, report.failed.len()), (3, 0, 0));
+
+        for (id, original) in &blobs {
+            let rewrapped = &saved[id];
+            assert_eq!(rewrapped.key_version.as_deref(), Some("2"));
+            assert_eq!(rewrapped.ciphertext, original.ciphertext);
+            assert_eq!(encryptor.decrypt(rewrapped).unwrap(), b"memory");
+        }
+
+        let again = RewrapJob::new(&encryptor).run(saved, |_, _| Ok(()));
+        assert_eq!(again.skipped, 3);
+    }
 }
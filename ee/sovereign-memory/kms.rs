@@ -0,0 +1,420 @@
+//! Key Management Services
+//!
+//! Master keys that wrap memory passport data keys. Data keys never leave
+//! the process unwrapped; only their wrapped form is stored next to the
+//! ciphertext:
+//! - AWS KMS `Encrypt`/`Decrypt`/`ReEncrypt`, signed with SigV4
+//! - Google Cloud KMS `:encrypt`/`:decrypt`, with a service account token
+//! - Azure Key Vault `wrapkey`/`unwrapkey` (RSA-OAEP-256)
+//! - HashiCorp Vault transit `encrypt`/`decrypt`/`rewrap`
+//! - A local AES-256-GCM key file for development
+//!
+//! The wrapping context (the key's region) is bound to the wrapped key
+//! where the service supports it, so a data key cannot be replayed under
+//! another region's policy.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let kms = AwsKms::new("alias/agentkern-memory-eu", "eu-central-1", creds);
+//! let wrapped = kms.wrap(&dek, "eu-central-1")?;
+//! kms.rotate()?;
+//! let rewrapped = kms.rewrap(&wrapped, "eu-central-1")?;
+//! ```
+
+use super::encryption::EncryptionError;
+use super::migration::{AwsCredentials, AzureCredentials, GcpCredentials};
+use super::stores::{http, send, sign_v4, uri_encode, AzureTokenSource, GcpTokenSource};
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+/// A data key wrapped by a master key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub ciphertext: Vec<u8>,
+    /// Master key version that wrapped it, where the service exposes one
+    pub key_version: Option<String>,
+}
+
+/// A master key held by a key management service.
+pub trait KeyManagementService: Send + Sync {
+    /// Master key identifier recorded with each blob.
+    fn key_id(&self) -> &str;
+
+    /// Wrap a data key; `context` is authenticated but not stored.
+    fn wrap(&self, dek: &[u8], context: &str) -> Result<WrappedKey, EncryptionError>;
+
+    /// Unwrap a data key wrapped under the same `context`.
+    fn unwrap(&self, wrapped: &WrappedKey, context: &str) -> Result<Vec<u8>, EncryptionError>;
+
+    /// Make a new master key version primary; returns it when known.
+    fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+        Err(EncryptionError::RotationNotSupported)
+    }
+
+    /// Wrap a data key again under the primary version.
+    fn rewrap(&self, wrapped: &WrappedKey, context: &str) -> Result<WrappedKey, EncryptionError> {
+        let dek = self.unwrap(wrapped, context)?;
+        self.wrap(&dek, context)
+    }
+}
+
+fn kms_error(e: impl std::fmt::Display) -> EncryptionError {
+    EncryptionError::KmsError(e.to_string())
+}
+
+fn client(cell: &OnceLock<Result<reqwest::blocking::Client, String>>) -> Result<&reqwest::blocking::Client, EncryptionError> {
+    http(cell).map_err(kms_error)
+}
+
+fn post_json(request: reqwest::blocking::RequestBuilder, body: &Value) -> Result<Value, EncryptionError> {
+    send(request.json(body)).map_err(kms_error)?.json().map_err(kms_error)
+}
+
+fn decode(value: &Value, field: &str) -> Result<Vec<u8>, EncryptionError> {
+    let encoded = value[field].as_str().ok_or_else(|| kms_error(format!("response without {}", field)))?;
+    B64.decode(encoded).map_err(kms_error)
+}
+
+/// AWS KMS symmetric key.
+pub struct AwsKms {
+    key_id: String,
+    region: String,
+    creds: AwsCredentials,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+}
+
+impl AwsKms {
+    /// `key_id` may be a key ID, ARN or alias.
+    pub fn new(key_id: &str, region: &str, creds: AwsCredentials) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            region: region.to_string(),
+            creds,
+            http: OnceLock::new(),
+        }
+    }
+
+    fn call(&self, action: &str, body: Value) -> Result<Value, EncryptionError> {
+        let host = format!("kms.{}.amazonaws.com", self.region);
+        let payload = body.to_string();
+        let request = client(&self.http)?
+            .post(format!("https://{}/", host))
+            .header("x-amz-target", format!("TrentService.{}", action))
+            .header(reqwest::header::CONTENT_TYPE, "application/x-amz-json-1.1");
+        let request = sign_v4(request, &reqwest::Method::POST, &host, "/", "", payload.as_bytes(), &self.creds, &self.region, "kms");
+        send(request.body(payload)).map_err(kms_error)?.json().map_err(kms_error)
+    }
+}
+
+impl KeyManagementService for AwsKms {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn wrap(&self, dek: &[u8], context: &str) -> Result<WrappedKey, EncryptionError> {
+        let response = self.call("Encrypt", json!({
+            "KeyId": self.key_id,
+            "Plaintext": B64.encode(dek),
+            "EncryptionContext": { "region": context },
+        }))?;
+        // The ciphertext names its backing key, so no version is tracked
+        Ok(WrappedKey { ciphertext: decode(&response, "CiphertextBlob")?, key_version: None })
+    }
+
+    fn unwrap(&self, wrapped: &WrappedKey, context: &str) -> Result<Vec<u8>, EncryptionError> {
+        let response = self.call("Decrypt", json!({
+            "KeyId": self.key_id,
+            "CiphertextBlob": B64.encode(&wrapped.ciphertext),
+            "EncryptionContext": { "region": context },
+        }))?;
+        decode(&response, "Plaintext")
+    }
+
+    fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+        self.call("RotateKeyOnDemand", json!({ "KeyId": self.key_id }))?;
+        Ok(None)
+    }
+
+    fn rewrap(&self, wrapped: &WrappedKey, context: &str) -> Result<WrappedKey, EncryptionError> {
+        let response = self.call("ReEncrypt", json!({
+            "CiphertextBlob": B64.encode(&wrapped.ciphertext),
+            "SourceKeyId": self.key_id,
+            "SourceEncryptionContext": { "region": context },
+            "DestinationKeyId": self.key_id,
+            "DestinationEncryptionContext": { "region": context },
+        }))?;
+        Ok(WrappedKey { ciphertext: decode(&response, "CiphertextBlob")?, key_version: None })
+    }
+}
+
+/// Google Cloud KMS symmetric crypto key.
+pub struct GcpKms {
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*`
+    key_name: String,
+    tokens: GcpTokenSource,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+}
+
+impl GcpKms {
+    pub fn new(key_name: &str, creds: GcpCredentials) -> Self {
+        Self {
+            key_name: key_name.to_string(),
+            tokens: GcpTokenSource::new(creds, "https://www.googleapis.com/auth/cloudkms"),
+            http: OnceLock::new(),
+        }
+    }
+
+    fn call(&self, path: &str, body: Value) -> Result<Value, EncryptionError> {
+        let http = client(&self.http)?;
+        let token = self.tokens.token(http).map_err(kms_error)?;
+        post_json(http.post(format!("https://cloudkms.googleapis.com/v1/{}", path)).bearer_auth(token), &body)
+    }
+}
+
+/// Last path segment of a resource name or URL.
+fn last_segment(name: &str) -> Option<String> {
+    name.trim_end_matches('/').rsplit('/').next().filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+impl KeyManagementService for GcpKms {
+    fn key_id(&self) -> &str {
+        &self.key_name
+    }
+
+    fn wrap(&self, dek: &[u8], context: &str) -> Result<WrappedKey, EncryptionError> {
+        let response = self.call(&format!("{}:encrypt", self.key_name), json!({
+            "plaintext": B64.encode(dek),
+            "additionalAuthenticatedData": B64.encode(context),
+        }))?;
+        Ok(WrappedKey {
+            ciphertext: decode(&response, "ciphertext")?,
+            key_version: response["name"].as_str().and_then(last_segment),
+        })
+    }
+
+    fn unwrap(&self, wrapped: &WrappedKey, context: &str) -> Result<Vec<u8>, EncryptionError> {
+        let response = self.call(&format!("{}:decrypt", self.key_name), json!({
+            "ciphertext": B64.encode(&wrapped.ciphertext),
+            "additionalAuthenticatedData": B64.encode(context),
+        }))?;
+        decode(&response, "plaintext")
+    }
+
+    fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+        let created = self.call(&format!("{}/cryptoKeyVersions", self.key_name), json!({}))?;
+        let version = created["name"].as_str()
+            .and_then(last_segment)
+            .ok_or_else(|| kms_error("created key version without a name"))?;
+        self.call(&format!("{}:updatePrimaryVersion", self.key_name), json!({ "cryptoKeyVersionId": version }))?;
+        Ok(Some(version))
+    }
+}
+
+/// Azure Key Vault RSA key.
+pub struct AzureKeyVault {
+    vault_url: String,
+    key_name: String,
+    tokens: AzureTokenSource,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+}
+
+impl AzureKeyVault {
+    const API_VERSION: &'static str = "7.4";
+
+    pub fn new(vault_url: &str, key_name: &str, creds: AzureCredentials) -> Self {
+        Self {
+            vault_url: vault_url.trim_end_matches('/').to_string(),
+            key_name: key_name.to_string(),
+            tokens: AzureTokenSource::new(creds, "https://vault.azure.net/.default"),
+            http: OnceLock::new(),
+        }
+    }
+
+    fn call(&self, path: &str, body: Value) -> Result<Value, EncryptionError> {
+        let http = client(&self.http)?;
+        let token = self.tokens.token(http).map_err(kms_error)?;
+        let url = format!("{}/keys/{}/{}?api-version={}", self.vault_url, uri_encode(&self.key_name, false), path, Self::API_VERSION);
+        post_json(http.post(url).bearer_auth(token), &body)
+    }
+}
+
+impl KeyManagementService for AzureKeyVault {
+    fn key_id(&self) -> &str {
+        &self.key_name
+    }
+
+    /// Key Vault has no wrapping context; the data AAD still binds the region.
+    fn wrap(&self, dek: &[u8], _context: &str) -> Result<WrappedKey, EncryptionError> {
+        let url_safe = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let response = self.call("wrapkey", json!({ "alg": "RSA-OAEP-256", "value": url_safe.encode(dek) }))?;
+        let value = response["value"].as_str().ok_or_else(|| kms_error("response without value"))?;
+        Ok(WrappedKey {
+            ciphertext: url_safe.decode(value).map_err(kms_error)?,
+            key_version: response["kid"].as_str().and_then(last_segment),
+        })
+    }
+
+    fn unwrap(&self, wrapped: &WrappedKey, _context: &str) -> Result<Vec<u8>, EncryptionError> {
+        let url_safe = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        // Unwrap with the version that wrapped, which may no longer be current
+        let path = match &wrapped.key_version {
+            Some(version) => format!("{}/unwrapkey", version),
+            None => "unwrapkey".to_string(),
+        };
+        let response = self.call(&path, json!({ "alg": "RSA-OAEP-256", "value": url_safe.encode(&wrapped.ciphertext) }))?;
+        let value = response["value"].as_str().ok_or_else(|| kms_error("response without value"))?;
+        url_safe.decode(value).map_err(kms_error)
+    }
+
+    fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+        let response = self.call("rotate", json!({}))?;
+        Ok(response["key"]["kid"].as_str().and_then(last_segment))
+    }
+}
+
+/// HashiCorp Vault transit key.
+pub struct VaultTransit {
+    address: String,
+    mount: String,
+    key_name: String,
+    token: String,
+    http: OnceLock<Result<reqwest::blocking::Client, String>>,
+}
+
+impl VaultTransit {
+    /// `path` is `<mount>/<key>`, e.g. `transit/agentkern-memory`.
+    pub fn new(address: &str, path: &str, token: &str) -> Self {
+        let (mount, key_name) = path.trim_matches('/').rsplit_once('/').unwrap_or(("transit", path));
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            mount: mount.to_string(),
+            key_name: key_name.to_string(),
+            token: token.to_string(),
+            http: OnceLock::new(),
+        }
+    }
+
+    fn call(&self, operation: &str, body: Value) -> Result<Value, EncryptionError> {
+        let url = format!("{}/v1/{}/{}/{}", self.address, self.mount, operation, self.key_name);
+        let request = client(&self.http)?.post(url).header("X-Vault-Token", &self.token);
+        post_json(request, &body)
+    }
+
+    /// `vault:v3:...` carries its key version.
+    fn wrapped(response: &Value) -> Result<WrappedKey, EncryptionError> {
+        let ciphertext = response["data"]["ciphertext"].as_str().ok_or_else(|| kms_error("response without ciphertext"))?;
+        Ok(WrappedKey {
+            ciphertext: ciphertext.as_bytes().to_vec(),
+            key_version: ciphertext.split(':').nth(1).map(|v| v.trim_start_matches('v').to_string()),
+        })
+    }
+}
+
+impl KeyManagementService for VaultTransit {
+    fn key_id(&self) -> &str {
+        &self.key_name
+    }
+
+    /// Transit only accepts a context for derived keys, so it is not sent.
+    fn wrap(&self, dek: &[u8], _context: &str) -> Result<WrappedKey, EncryptionError> {
+        Self::wrapped(&self.call("encrypt", json!({ "plaintext": B64.encode(dek) }))?)
+    }
+
+    fn unwrap(&self, wrapped: &WrappedKey, _context: &str) -> Result<Vec<u8>, EncryptionError> {
+        let ciphertext = String::from_utf8(wrapped.ciphertext.clone()).map_err(kms_error)?;
+        let response = self.call("decrypt", json!({ "ciphertext": ciphertext }))?;
+        let plaintext = response["data"]["plaintext"].as_str().ok_or_else(|| kms_error("response without plaintext"))?;
+        B64.decode(plaintext).map_err(kms_error)
+    }
+
+    fn rotate(&self) -> Result<Option<String>, EncryptionError> {
+        let url = format!("{}/v1/{}/keys/{}/rotate", self.address, self.mount, self.key_name);
+        send(client(&self.http)?.post(url).header("X-Vault-Token", &self.token)).map_err(kms_error)?;
+        Ok(None)
+    }
+
+    fn rewrap(&self, wrapped: &WrappedKey, _context: &str) -> Result<WrappedKey, EncryptionError> {
+        let ciphertext = String::from_utf8(wrapped.ciphertext.clone()).map_err(kms_error)?;
+        Self::wrapped(&self.call("rewrap", json!({ "ciphertext": ciphertext }))?)
+    }
+}
+
+/// AES-256-GCM master key read from a local file. Development only.
+pub struct LocalKms {
+    key_id: String,
+    key: LessSafeKey,
+}
+
+impl LocalKms {
+    pub fn new(key_id: &str, key: &[u8; 32]) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            key: LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("32-byte AES-256 key")),
+        }
+    }
+
+    /// Key file holds 32 raw bytes or 64 hex characters.
+    pub fn from_file(path: &str) -> Result<Self, EncryptionError> {
+        let bytes = std::fs::read(path).map_err(|e| kms_error(format!("{}: {}", path, e)))?;
+        let key = match hex::decode(String::from_utf8_lossy(&bytes).trim()) {
+            Ok(decoded) if decoded.len() == 32 => decoded,
+            _ => bytes,
+        };
+        let key: [u8; 32] = key.try_into().map_err(|_| kms_error(format!("{}: expected a 32-byte key", path)))?;
+        Ok(Self::new(path, &key))
+    }
+}
+
+impl KeyManagementService for LocalKms {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn wrap(&self, dek: &[u8], context: &str) -> Result<WrappedKey, EncryptionError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| kms_error("no system randomness"))?;
+        let mut sealed = dek.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(context.as_bytes()), &mut sealed)
+            .map_err(|_| kms_error("wrap failed"))?;
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.extend_from_slice(&sealed);
+        Ok(WrappedKey { ciphertext, key_version: None })
+    }
+
+    fn unwrap(&self, wrapped: &WrappedKey, context: &str) -> Result<Vec<u8>, EncryptionError> {
+        if wrapped.ciphertext.len() < NONCE_LEN {
+            return Err(EncryptionError::DecryptionFailed);
+        }
+        let (nonce, sealed) = wrapped.ciphertext.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| EncryptionError::DecryptionFailed)?;
+        let mut sealed = sealed.to_vec();
+        let dek = self.key
+            .open_in_place(nonce, Aad::from(context.as_bytes()), &mut sealed)
+            .map_err(|_| EncryptionError::DecryptionFailed)?;
+        Ok(dek.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_wrap_is_bound_to_context() {
+        let kms = LocalKms::new("dev", &[7u8; 32]);
+        let wrapped = kms.wrap(&[1u8; 32], "eu-central-1").unwrap();
+        assert_eq!(kms.unwrap(&wrapped, "eu-central-1").unwrap(), vec![1u8; 32]);
+        assert!(matches!(kms.unwrap(&wrapped, "us-east-1"), Err(EncryptionError::DecryptionFailed)));
+
+        let vault = VaultTransit::wrapped(&json!({ "data": { "ciphertext": "vault:v3:abc" } })).unwrap();
+        assert_eq!(vault.key_version.as_deref(), Some("3"));
+        assert_eq!(VaultTransit::new("http://vault:8200/", "transit/memory", "t").mount, "transit");
+    }
+}
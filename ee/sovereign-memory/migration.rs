@@ -1,9 +1,13 @@
 //! Cross-Cloud Migration
 //!
-//! Migrate Memory Passports between AWS, GCP, and Azure
+//! Migrate Memory Passports between AWS, GCP, and Azure, in resumable
+//! chunks that are verified end to end (see [`super::transfer`])
 
+use super::stores::{AzureBlobStore, ObjectStore, S3Store};
+use super::transfer::{ChunkedTransfer, CheckpointStore, FileCheckpointStore, MemoryCheckpointStore};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Cloud provider target.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -51,14 +55,33 @@ pub struct MigrationConfig {
     pub encrypt_transfer: bool,
     /// Bandwidth limit (MB/s, 0 = unlimited)
     pub bandwidth_limit: u32,
+    /// Source bucket; `account/container` on Azure
+    #[serde(default)]
+    pub source_bucket: String,
+    /// Destination bucket; `account/container` on Azure
+    #[serde(default)]
+    pub destination_bucket: String,
+    /// Chunk size in MB
+    #[serde(default = "default_chunk_size_mb")]
+    pub chunk_size_mb: u32,
+    /// Directory for resume checkpoints; in memory if unset
+    #[serde(default)]
+    pub checkpoint_dir: Option<PathBuf>,
+}
+
+fn default_chunk_size_mb() -> u32 {
+    8
 }
 
 /// Cloud migrator for cross-cloud memory passport transfer.
 pub struct CloudMigrator {
     config: MigrationConfig,
-    aws_adapter: Option<AwsAdapter>,
-    gcp_adapter: Option<GcpAdapter>,
-    azure_adapter: Option<AzureAdapter>,
+    aws: Option<AwsCredentials>,
+    gcp: Option<GcpCredentials>,
+    azure: Option<AzureCredentials>,
+    source_store: Option<Arc<dyn ObjectStore>>,
+    destination_store: Option<Arc<dyn ObjectStore>>,
+    checkpoints: Arc<dyn CheckpointStore>,
 }
 
 impl CloudMigrator {
@@ -66,134 +89,108 @@ impl CloudMigrator {
     pub fn new(config: MigrationConfig) -> Result<Self, MigrationError> {
         crate::connectors::license::check_feature_license("cross_cloud")?;
         
+        let checkpoints: Arc<dyn CheckpointStore> = match &config.checkpoint_dir {
+            Some(dir) => Arc::new(FileCheckpointStore::new(dir)),
+            None => Arc::new(MemoryCheckpointStore::default()),
+        };
         Ok(Self {
             config,
-            aws_adapter: None,
-            gcp_adapter: None,
-            azure_adapter: None,
+            aws: None,
+            gcp: None,
+            azure: None,
+            source_store: None,
+            destination_store: None,
+            checkpoints,
         })
     }
     
     /// Configure AWS credentials.
     pub fn with_aws(&mut self, creds: AwsCredentials) -> &mut Self {
-        self.aws_adapter = Some(AwsAdapter::new(creds));
+        self.aws = Some(creds);
         self
     }
     
     /// Configure GCP credentials.
     pub fn with_gcp(&mut self, creds: GcpCredentials) -> &mut Self {
-        self.gcp_adapter = Some(GcpAdapter::new(creds));
+        self.gcp = Some(creds);
         self
     }
     
     /// Configure Azure credentials.
     pub fn with_azure(&mut self, creds: AzureCredentials) -> &mut Self {
-        self.azure_adapter = Some(AzureAdapter::new(creds));
+        self.azure = Some(creds);
+        self
+    }
+
+    /// Use a custom store for the source instead of one built from credentials.
+    pub fn with_source_store(&mut self, store: Arc<dyn ObjectStore>) -> &mut Self {
+        self.source_store = Some(store);
+        self
+    }
+
+    /// Use a custom store for the destination.
+    pub fn with_destination_store(&mut self, store: Arc<dyn ObjectStore>) -> &mut Self {
+        self.destination_store = Some(store);
         self
     }
     
     /// Migrate a memory passport.
+    ///
+    /// Resumes an interrupted migration of the same passport. The source is
+    /// only deleted once the destination has been read back and matches.
     pub fn migrate(&self, passport_id: &str) -> Result<MigrationResult, MigrationError> {
-        // 1. Read from source
-        let data = self.read_source(passport_id)?;
-        
-        // 2. Encrypt if needed
-        let transfer_data = if self.config.encrypt_transfer {
-            self.encrypt_for_transfer(&data)?
-        } else {
-            data.clone()
+        let source = match &self.source_store {
+            Some(store) => store.clone(),
+            None => self.store_for(&self.config.source, &self.config.source_bucket)?,
         };
-        
-        // 3. Write to destination
-        self.write_destination(passport_id, &transfer_data)?;
-        
-        // 4. Verify if needed
-        if self.config.verify {
-            self.verify_migration(passport_id, &data)?;
-        }
-        
-        // 5. Delete source if needed
+        let destination = match &self.destination_store {
+            Some(store) => store.clone(),
+            None => self.store_for(&self.config.destination, &self.config.destination_bucket)?,
+        };
+        let transfer = ChunkedTransfer::new(source, destination, self.checkpoints.clone())
+            .with_chunk_size(self.config.chunk_size_mb.max(1) as u64 * 1024 * 1024)
+            .with_bandwidth_limit(self.config.bandwidth_limit as u64 * 1024 * 1024);
+
+        // Transfers always read the destination back; deleting the source
+        // without that check is not offered
+        let report = transfer.run(passport_id)?;
         if self.config.delete_source {
-            self.delete_source(passport_id)?;
+            transfer.release_source(&report)?;
         }
         
         Ok(MigrationResult {
             passport_id: passport_id.to_string(),
             source: self.config.source.clone(),
             destination: self.config.destination.clone(),
-            bytes_transferred: data.len() as u64,
-            verified: self.config.verify,
+            bytes_transferred: report.bytes,
+            verified: true,
             source_deleted: self.config.delete_source,
+            chunks: report.chunks,
+            resumed: report.resumed,
+            digest: report.digest,
         })
     }
-    
-    fn read_source(&self, passport_id: &str) -> Result<Vec<u8>, MigrationError> {
-        match &self.config.source {
-            CloudTarget::Aws { .. } => {
-                let adapter = self.aws_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("AWS".into()))?;
-                adapter.read(passport_id)
-            }
-            CloudTarget::Gcp { .. } => {
-                let adapter = self.gcp_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("GCP".into()))?;
-                adapter.read(passport_id)
-            }
-            CloudTarget::Azure { .. } => {
-                let adapter = self.azure_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("Azure".into()))?;
-                adapter.read(passport_id)
-            }
-            CloudTarget::OnPremise { .. } => {
-                Err(MigrationError::NotSupported("OnPremise read".into()))
-            }
+
+    fn store_for(&self, target: &CloudTarget, bucket: &str) -> Result<Arc<dyn ObjectStore>, MigrationError> {
+        if bucket.is_empty() {
+            return Err(MigrationError::NotSupported(format!("{} target without a bucket", target.provider())));
         }
-    }
-    
-    fn write_destination(&self, passport_id: &str, data: &[u8]) -> Result<(), MigrationError> {
-        match &self.config.destination {
-            CloudTarget::Aws { .. } => {
-                let adapter = self.aws_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("AWS".into()))?;
-                adapter.write(passport_id, data)
-            }
-            CloudTarget::Gcp { .. } => {
-                let adapter = self.gcp_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("GCP".into()))?;
-                adapter.write(passport_id, data)
+        let missing = || MigrationError::AdapterNotConfigured(target.provider().into());
+        Ok(match target {
+            CloudTarget::Aws { region } => {
+                Arc::new(S3Store::aws(bucket, region, self.aws.clone().ok_or_else(missing)?))
             }
+            CloudTarget::Gcp { .. } => Arc::new(S3Store::gcs(bucket, self.gcp.clone().ok_or_else(missing)?)),
             CloudTarget::Azure { .. } => {
-                let adapter = self.azure_adapter.as_ref()
-                    .ok_or(MigrationError::AdapterNotConfigured("Azure".into()))?;
-                adapter.write(passport_id, data)
+                let (account, container) = bucket.split_once('/')
+                    .ok_or_else(|| MigrationError::NotSupported("Azure bucket must be account/container".into()))?;
+                Arc::new(AzureBlobStore::new(account, container, self.azure.clone().ok_or_else(missing)?))
             }
-            CloudTarget::OnPremise { .. } => {
-                Err(MigrationError::NotSupported("OnPremise write".into()))
+            // S3-compatible object storage, e.g. MinIO
+            CloudTarget::OnPremise { endpoint } => {
+                Arc::new(S3Store::compatible(endpoint, bucket, "us-east-1", self.aws.clone().ok_or_else(missing)?))
             }
-        }
-    }
-    
-    fn verify_migration(&self, passport_id: &str, original: &[u8]) -> Result<(), MigrationError> {
-        let migrated = self.read_destination(passport_id)?;
-        if migrated != original {
-            return Err(MigrationError::VerificationFailed);
-        }
-        Ok(())
-    }
-    
-    fn read_destination(&self, passport_id: &str) -> Result<Vec<u8>, MigrationError> {
-        // Same logic as read_source but for destination
-        self.read_source(passport_id) // Simplified for demo
-    }
-    
-    fn delete_source(&self, passport_id: &str) -> Result<(), MigrationError> {
-        // Would delete from source cloud
-        Ok(())
-    }
-    
-    fn encrypt_for_transfer(&self, data: &[u8]) -> Result<Vec<u8>, MigrationError> {
-        // Would use envelope encryption
-        Ok(data.to_vec())
+        })
     }
 }
 
@@ -206,6 +203,11 @@ pub struct MigrationResult {
     pub bytes_transferred: u64,
     pub verified: bool,
     pub source_deleted: bool,
+    pub chunks: usize,
+    /// Continued from a checkpoint
+    pub resumed: bool,
+    /// SHA-256 over the chunk digests
+    pub digest: String,
 }
 
 /// AWS credentials.
@@ -231,66 +233,6 @@ pub struct AzureCredentials {
     pub client_secret: String,
 }
 
-/// AWS adapter.
-struct AwsAdapter {
-    creds: AwsCredentials,
-}
-
-impl AwsAdapter {
-    fn new(creds: AwsCredentials) -> Self {
-        Self { creds }
-    }
-    
-    fn read(&self, key: &str) -> Result<Vec<u8>, MigrationError> {
-        // Would use aws-sdk-s3
-        Ok(vec![])
-    }
-    
-    fn write(&self, key: &str, data: &[u8]) -> Result<(), MigrationError> {
-        Ok(())
-    }
-}
-
-/// GCP adapter.
-struct GcpAdapter {
-    creds: GcpCredentials,
-}
-
-impl GcpAdapter {
-    fn new(creds: GcpCredentials) -> Self {
-        Self { creds }
-    }
-    
-    fn read(&self, key: &str) -> Result<Vec<u8>, MigrationError> {
-        // Would use google-cloud-storage
-        Ok(vec![])
-    }
-    
-    fn write(&self, key: &str, data: &[u8]) -> Result<(), MigrationError> {
-        Ok(())
-    }
-}
-
-/// Azure adapter.
-struct AzureAdapter {
-    creds: AzureCredentials,
-}
-
-impl AzureAdapter {
-    fn new(creds: AzureCredentials) -> Self {
-        Self { creds }
-    }
-    
-    fn read(&self, key: &str) -> Result<Vec<u8>, MigrationError> {
-        // Would use azure_storage_blobs
-        Ok(vec![])
-    }
-    
-    fn write(&self, key: &str, data: &[u8]) -> Result<(), MigrationError> {
-        Ok(())
-    }
-}
-
 /// Migration errors.
 #[derive(Debug, thiserror::Error)]
 pub enum MigrationError {
@@ -308,6 +250,15 @@ pub enum MigrationError {
     
     #[error("Write error: {0}")]
     WriteError(String),
+
+    #[error("Checksum mismatch in chunk {chunk}")]
+    ChecksumMismatch { chunk: usize },
+
+    #[error("Object store error: {0}")]
+    Store(String),
+
+    #[error("Checkpoint error: {0}")]
+    Checkpoint(String),
     
     #[error("License error: {0}")]
     LicenseError(#[from] crate::connectors::license::LicenseError),
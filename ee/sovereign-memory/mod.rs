@@ -4,8 +4,16 @@
 //! Per licensing_split.md: Enterprise tier for multi-cloud deals
 
 pub mod migration;
+pub mod stores;
+pub mod transfer;
 pub mod encryption;
+pub mod kms;
+pub mod sharding;
 
 // Re-exports
-pub use migration::{CloudMigrator, MigrationConfig, CloudTarget};
-pub use encryption::{MemoryEncryptor, EncryptionConfig, KeyProvider};
+pub use migration::{CloudMigrator, MigrationConfig, CloudTarget, MigrationError, MigrationResult};
+pub use stores::{ObjectStore, S3Store, AzureBlobStore, CompletedPart};
+pub use transfer::{ChunkedTransfer, TransferManifest, TransferReport, TransferState, CheckpointStore, FileCheckpointStore, MemoryCheckpointStore};
+pub use encryption::{MemoryEncryptor, EncryptionConfig, KeyProvider, EncryptedBlob, EncryptionError, RegionKeyPolicy, RewrapJob, RewrapReport};
+pub use kms::{KeyManagementService, WrappedKey, AwsKms, GcpKms, AzureKeyVault, VaultTransit, LocalKms};
+pub use sharding::{MemorySharder, MemoryRecord, MemoryClass, MemoryView, ResidencyRule, ShardAccessor, ShardManifest, ShardLocation, ShardAccess, ShardAction, ShardAuditSink, MemoryShardAudit, ShardError};
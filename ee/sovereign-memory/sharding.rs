@@ -0,0 +1,567 @@
+//! Memory Sharding
+//!
+//! Splits an agent's memory into shards by namespace and classification
+//! and keeps each shard in a region its residency rules allow:
+//! - The first [`ResidencyRule`] matching a shard names its permitted
+//!   regions in preference order; unmatched shards stay in the home region
+//! - A shard goes to the home cell when its region is permitted, otherwise
+//!   to the first cell in a permitted region
+//! - Reads reassemble every shard the reader's region may receive under
+//!   the sovereign mesh rules of the cell holding it; the rest are withheld
+//! - Every shard write, read, denial and deletion is audited
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let sharder = MemorySharder::new(eu_cell, eu_store)?
+//!     .with_cell(us_cell, us_store)
+//!     .with_rule(ResidencyRule::new(&["eu-central-1"]).with_class(MemoryClass::Personal))
+//!     .with_rule(ResidencyRule::new(&["us-east-1", "eu-central-1"]).with_namespace("telemetry"));
+//!
+//! sharder.write("agent-7", &records, &ShardAccessor::new("svc:memory", "eu-central-1"))?;
+//! let view = sharder.read("agent-7", &ShardAccessor::new("svc:analytics", "us-east-1"))?;
+//! ```
+
+use super::encryption::RegionKeyPolicy;
+use super::migration::MigrationError;
+use super::stores::{CompletedPart, ObjectStore};
+use agentkern_sovereign_mesh::SovereignCellConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Sensitivity of a memory record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryClass {
+    Public,
+    Internal,
+    /// Personal data under privacy law
+    Personal,
+    /// Health, financial or otherwise regulated data
+    Restricted,
+}
+
+impl MemoryClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Internal => "internal",
+            Self::Personal => "personal",
+            Self::Restricted => "restricted",
+        }
+    }
+}
+
+/// One entry of agent memory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    /// Slash-separated, e.g. `episodic/support`
+    pub namespace: String,
+    pub key: String,
+    pub class: MemoryClass,
+    pub value: serde_json::Value,
+}
+
+/// Regions that may hold matching shards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResidencyRule {
+    /// Namespace prefix; any namespace if unset
+    pub namespace: Option<String>,
+    /// Any classification if unset
+    pub class: Option<MemoryClass>,
+    /// In preference order
+    pub regions: Vec<String>,
+}
+
+impl ResidencyRule {
+    pub fn new(regions: &[&str]) -> Self {
+        Self {
+            namespace: None,
+            class: None,
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    pub fn with_namespace(mut self, prefix: &str) -> Self {
+        self.namespace = Some(prefix.trim_matches('/').to_string());
+        self
+    }
+
+    pub fn with_class(mut self, class: MemoryClass) -> Self {
+        self.class = Some(class);
+        self
+    }
+
+    /// `health` matches `health` and `health/labs`, not `healthcare`.
+    pub fn matches(&self, namespace: &str, class: MemoryClass) -> bool {
+        let namespace_matches = self.namespace.as_deref().is_none_or(|prefix| {
+            namespace == prefix || namespace.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+        });
+        namespace_matches && self.class.is_none_or(|c| c == class)
+    }
+}
+
+/// Who is touching a shard, and from where.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardAccessor {
+    pub id: String,
+    pub region: String,
+}
+
+impl ShardAccessor {
+    pub fn new(id: &str, region: &str) -> Self {
+        Self { id: id.to_string(), region: region.to_string() }
+    }
+}
+
+/// Where a shard lives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardLocation {
+    pub shard_id: String,
+    pub namespace: String,
+    pub class: MemoryClass,
+    pub cell_id: String,
+    pub region: String,
+    pub key: String,
+    /// Hex SHA-256 of the stored shard
+    pub sha256: String,
+    pub records: usize,
+}
+
+/// An agent's shards, kept in its home cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardManifest {
+    pub agent_id: String,
+    pub home_cell: String,
+    pub shards: Vec<ShardLocation>,
+    pub written_at: DateTime<Utc>,
+}
+
+/// Reassembled memory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryView {
+    pub records: Vec<MemoryRecord>,
+    /// Shards the reader's region may not receive
+    pub withheld: Vec<String>,
+}
+
+/// Kind of shard access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardAction {
+    Write,
+    Read,
+    Denied,
+    Delete,
+}
+
+/// Audit entry for one shard access.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardAccess {
+    pub timestamp: DateTime<Utc>,
+    pub agent_id: String,
+    pub shard_id: String,
+    pub cell_id: String,
+    pub accessor: String,
+    pub accessor_region: String,
+    pub action: ShardAction,
+    pub reason: Option<String>,
+}
+
+/// Receives shard access audit entries.
+pub trait ShardAuditSink: Send + Sync {
+    fn record(&self, access: ShardAccess);
+}
+
+/// In-memory shard audit trail.
+#[derive(Default)]
+pub struct MemoryShardAudit {
+    entries: Mutex<Vec<ShardAccess>>,
+}
+
+impl MemoryShardAudit {
+    pub fn entries(&self) -> Vec<ShardAccess> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    pub fn for_shard(&self, shard_id: &str) -> Vec<ShardAccess> {
+        self.entries.lock().unwrap().iter().filter(|a| a.shard_id == shard_id).cloned().collect()
+    }
+}
+
+impl ShardAuditSink for MemoryShardAudit {
+    fn record(&self, access: ShardAccess) {
+        self.entries.lock().unwrap().push(access);
+    }
+}
+
+struct Cell {
+    config: SovereignCellConfig,
+    store: Arc<dyn ObjectStore>,
+}
+
+/// Residency-aware memory sharder.
+pub struct MemorySharder {
+    home_cell: String,
+    /// In registration order, which breaks placement ties
+    cells: Vec<Cell>,
+    rules: Vec<ResidencyRule>,
+    audit: Arc<dyn ShardAuditSink>,
+}
+
+impl MemorySharder {
+    /// Sharder whose manifests live in `home`.
+    pub fn new(home: SovereignCellConfig, store: Arc<dyn ObjectStore>) -> Result<Self, ShardError> {
+        crate::connectors::license::check_feature_license("memory_sharding")?;
+        Ok(Self {
+            home_cell: home.cell_id.clone(),
+            cells: vec![Cell { config: home, store }],
+            rules: Vec::new(),
+            audit: Arc::new(MemoryShardAudit::default()),
+        })
+    }
+
+    /// Another cell shards may be placed in.
+    pub fn with_cell(mut self, config: SovereignCellConfig, store: Arc<dyn ObjectStore>) -> Self {
+        match self.cells.iter_mut().find(|cell| cell.config.cell_id == config.cell_id) {
+            Some(cell) => *cell = Cell { config, store },
+            None => self.cells.push(Cell { config, store }),
+        }
+        self
+    }
+
+    /// Residency rules are checked in the order added.
+    pub fn with_rule(mut self, rule: ResidencyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_audit(mut self, audit: Arc<dyn ShardAuditSink>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    fn home(&self) -> &Cell {
+        self.cells.iter().find(|cell| cell.config.cell_id == self.home_cell).expect("home cell is registered")
+    }
+
+    fn cell(&self, cell_id: &str) -> Result<&Cell, ShardError> {
+        self.cells.iter()
+            .find(|cell| cell.config.cell_id == cell_id)
+            .ok_or_else(|| ShardError::UnknownCell(cell_id.to_string()))
+    }
+
+    /// Cell a shard of `namespace` and `class` belongs in.
+    pub fn place(&self, namespace: &str, class: MemoryClass) -> Result<&SovereignCellConfig, ShardError> {
+        let home = self.home();
+        let regions = match self.rules.iter().find(|rule| rule.matches(namespace, class)) {
+            Some(rule) => rule.regions.clone(),
+            None => vec![home.config.region.clone()],
+        };
+        if regions.contains(&home.config.region) {
+            return Ok(&home.config);
+        }
+        regions.iter()
+            .find_map(|region| self.cells.iter().find(|cell| &cell.config.region == region))
+            .map(|cell| &cell.config)
+            .ok_or_else(|| ShardError::NoResidentCell { namespace: namespace.to_string(), class })
+    }
+
+    /// Shard and store an agent's memory, replacing what was there.
+    pub fn write(&self, agent_id: &str, records: &[MemoryRecord], accessor: &ShardAccessor) -> Result<ShardManifest, ShardError> {
+        let mut groups: BTreeMap<(&str, MemoryClass), Vec<&MemoryRecord>> = BTreeMap::new();
+        for record in records {
+            groups.entry((record.namespace.as_str(), record.class)).or_default().push(record);
+        }
+
+        // Place everything before writing anything
+        let placed = groups.into_iter()
+            .map(|((namespace, class), records)| Ok((namespace, class, self.place(namespace, class)?.cell_id.clone(), records)))
+            .collect::<Result<Vec<_>, ShardError>>()?;
+
+        let previous = self.manifest(agent_id)?;
+        let mut shards = Vec::new();
+        for (namespace, class, cell_id, records) in placed {
+            let cell = self.cell(&cell_id)?;
+            let shard_id = format!("{}/{}/{}", agent_id, namespace.trim_matches('/'), class.as_str());
+            let key = format!("memory-shards/{}.json", shard_id);
+            let body = serde_json::to_vec(&records).map_err(|e| ShardError::Serialization(e.to_string()))?;
+            put(cell.store.as_ref(), &key, &body)?;
+            self.audit(agent_id, &shard_id, &cell_id, accessor, ShardAction::Write, None);
+            shards.push(ShardLocation {
+                shard_id,
+                namespace: namespace.to_string(),
+                class,
+                region: cell.config.region.clone(),
+                cell_id,
+                key,
+                sha256: hex::encode(Sha256::digest(&body)),
+                records: records.len(),
+            });
+        }
+
+        let manifest = ShardManifest {
+            agent_id: agent_id.to_string(),
+            home_cell: self.home_cell.clone(),
+            shards,
+            written_at: Utc::now(),
+        };
+        let body = serde_json::to_vec(&manifest).map_err(|e| ShardError::Serialization(e.to_string()))?;
+        put(self.home().store.as_ref(), &Self::manifest_key(agent_id), &body)?;
+
+        // Shards that moved cell or no longer exist
+        for old in previous.into_iter().flat_map(|m| m.shards) {
+            let kept = manifest.shards.iter().any(|s| s.cell_id == old.cell_id && s.key == old.key);
+            if !kept {
+                self.cell(&old.cell_id)?.store.delete(&old.key)?;
+                self.audit(agent_id, &old.shard_id, &old.cell_id, accessor, ShardAction::Delete, None);
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Reassemble all of an agent's memory the accessor's region may receive.
+    pub fn read(&self, agent_id: &str, accessor: &ShardAccessor) -> Result<MemoryView, ShardError> {
+        self.read_where(agent_id, accessor, |_| true)
+    }
+
+    /// Reassemble one namespace and those below it.
+    pub fn read_namespace(&self, agent_id: &str, namespace: &str, accessor: &ShardAccessor) -> Result<MemoryView, ShardError> {
+        let rule = ResidencyRule::new(&[]).with_namespace(namespace);
+        self.read_where(agent_id, accessor, |shard| rule.matches(&shard.namespace, shard.class))
+    }
+
+    fn read_where(
+        &self,
+        agent_id: &str,
+        accessor: &ShardAccessor,
+        filter: impl Fn(&ShardLocation) -> bool,
+    ) -> Result<MemoryView, ShardError> {
+        let Some(manifest) = self.manifest(agent_id)? else {
+            return Ok(MemoryView::default());
+        };
+        let mut view = MemoryView::default();
+        for shard in manifest.shards.iter().filter(|shard| filter(shard)) {
+            let cell = self.cell(&shard.cell_id)?;
+            // Reading moves the shard to the accessor's region: same rule as a mesh sync
+            if let Err(e) = RegionKeyPolicy::from_cell(&cell.config).check(&accessor.region) {
+                self.audit(agent_id, &shard.shard_id, &shard.cell_id, accessor, ShardAction::Denied, Some(e.to_string()));
+                view.withheld.push(shard.shard_id.clone());
+                continue;
+            }
+            let body = get(cell.store.as_ref(), &shard.key)?.ok_or_else(|| ShardError::Missing(shard.shard_id.clone()))?;
+            if hex::encode(Sha256::digest(&body)) != shard.sha256 {
+                return Err(ShardError::Corrupted(shard.shard_id.clone()));
+            }
+            let records: Vec<MemoryRecord> = serde_json::from_slice(&body).map_err(|e| ShardError::Serialization(e.to_string()))?;
+            self.audit(agent_id, &shard.shard_id, &shard.cell_id, accessor, ShardAction::Read, None);
+            view.records.extend(records);
+        }
+        Ok(view)
+    }
+
+    /// The agent's shard manifest, if it has been written.
+    pub fn manifest(&self, agent_id: &str) -> Result<Option<ShardManifest>, ShardError> {
+        get(self.home().store.as_ref(), &Self::manifest_key(agent_id))?
+            .map(|body| serde_json::from_slice(&body).map_err(|e| ShardError::Serialization(e.to_string())))
+            .transpose()
+    }
+
+    fn manifest_key(agent_id: &str) -> String {
+        format!("memory-shards/{}/manifest.json", agent_id)
+    }
+
+    fn audit(&self, agent_id: &str, shard_id: &str, cell_id: &str, accessor: &ShardAccessor, action: ShardAction, reason: Option<String>) {
+        tracing::info!(agent_id, shard_id, cell_id, accessor = %accessor.id, region = %accessor.region, ?action, "Memory shard access");
+        self.audit.record(ShardAccess {
+            timestamp: Utc::now(),
+            agent_id: agent_id.to_string(),
+            shard_id: shard_id.to_string(),
+            cell_id: cell_id.to_string(),
+            accessor: accessor.id.clone(),
+            accessor_region: accessor.region.clone(),
+            action,
+            reason,
+        });
+    }
+}
+
+/// Store a small object as a single-part upload.
+fn put(store: &dyn ObjectStore, key: &str, data: &[u8]) -> Result<(), MigrationError> {
+    let upload_id = store.begin_upload(key)?;
+    let staged = store.upload_part(key, &upload_id, 1, data, &Sha256::digest(data))
+        .and_then(|etag| store.complete_upload(key, &upload_id, &[CompletedPart { part_number: 1, etag }]));
+    if staged.is_err() {
+        let _ = store.abort_upload(key, &upload_id);
+    }
+    staged
+}
+
+fn get(store: &dyn ObjectStore, key: &str) -> Result<Option<Vec<u8>>, MigrationError> {
+    match store.head(key)? {
+        None => Ok(None),
+        Some(0) => Ok(Some(Vec::new())),
+        Some(size) => store.read_range(key, 0, size).map(Some),
+    }
+}
+
+/// Sharding errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ShardError {
+    #[error("No permitted cell for {namespace} ({class:?})")]
+    NoResidentCell { namespace: String, class: MemoryClass },
+
+    #[error("Unknown cell: {0}")]
+    UnknownCell(String),
+
+    #[error("Shard missing: {0}")]
+    Missing(String),
+
+    #[error("Shard checksum mismatch: {0}")]
+    Corrupted(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+
+    #[error("Store error: {0}")]
+    Store(#[from] MigrationError),
+
+    #[error("License error: {0}")]
+    LicenseError(#[from] crate::connectors::license::LicenseError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        staged: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn head(&self, key: &str) -> Result<Option<u64>, MigrationError> {
+            Ok(self.objects.lock().unwrap().get(key).map(|o| o.len() as u64))
+        }
+
+        fn read_range(&self, key: &str, offset: u64, len: u64) -> Result<Vec<u8>, MigrationError> {
+            Ok(self.objects.lock().unwrap()[key][offset as usize..(offset + len) as usize].to_vec())
+        }
+
+        fn begin_upload(&self, key: &str) -> Result<String, MigrationError> {
+            Ok(key.to_string())
+        }
+
+        fn upload_part(&self, _key: &str, id: &str, _part: u32, data: &[u8], _sha: &[u8]) -> Result<String, MigrationError> {
+            self.staged.lock().unwrap().insert(id.to_string(), data.to_vec());
+            Ok("etag".into())
+        }
+
+        fn complete_upload(&self, key: &str, id: &str, _parts: &[CompletedPart]) -> Result<(), MigrationError> {
+            let object = self.staged.lock().unwrap().remove(id).unwrap();
+            self.objects.lock().unwrap().insert(key.to_string(), object);
+            Ok(())
+        }
+
+        fn abort_upload(&self, _key: &str, id: &str) -> Result<(), MigrationError> {
+            self.staged.lock().unwrap().remove(id);
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MigrationError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn cell(id: &str, region: &str, blocked: &[&str]) -> SovereignCellConfig {
+        SovereignCellConfig {
+            cell_id: id.into(),
+            region: region.into(),
+            allowed_sync_targets: Vec::new(),
+            blocked_sync_targets: blocked.iter().map(|r| r.to_string()).collect(),
+            attestation_enabled: true,
+        }
+    }
+
+    fn record(namespace: &str, key: &str, class: MemoryClass) -> MemoryRecord {
+        MemoryRecord { namespace: namespace.into(), key: key.into(), class, value: serde_json::json!(key) }
+    }
+
+    #[test]
+    fn test_residency_placement_and_audited_reads() {
+        let eu_store = Arc::new(MemoryStore::default());
+        let us_store = Arc::new(MemoryStore::default());
+        let audit = Arc::new(MemoryShardAudit::default());
+        let sharder = MemorySharder {
+            home_cell: "eu-1".into(),
+            cells: vec![Cell { config: cell("eu-1", "eu-central-1", &["us-east-1"]), store: eu_store.clone() }],
+            rules: Vec::new(),
+            audit: audit.clone(),
+        }
+        .with_cell(cell("us-1", "us-east-1", &[]), us_store.clone())
+        .with_rule(ResidencyRule::new(&["eu-central-1"]).with_class(MemoryClass::Personal))
+        .with_rule(ResidencyRule::new(&["us-east-1"]).with_namespace("telemetry"));
+
+        let writer = ShardAccessor::new("svc:memory", "eu-central-1");
+        let manifest = sharder.write("agent-7", &[
+            record("episodic", "ticket-1", MemoryClass::Personal),
+            record("telemetry/latency", "p99", MemoryClass::Internal),
+            record("episodic", "ticket-2", MemoryClass::Personal),
+            record("skills", "refunds", MemoryClass::Internal),
+        ], &writer).unwrap();
+
+        let cells: Vec<_> = manifest.shards.iter().map(|s| (s.shard_id.as_str(), s.cell_id.as_str())).collect();
+        assert_eq!(cells, vec![
+            ("agent-7/episodic/personal", "eu-1"),
+            ("agent-7/skills/internal", "eu-1"),
+            ("agent-7/telemetry/latency/internal", "us-1"),
+        ]);
+        assert!(us_store.objects.lock().unwrap().keys().all(|k| k.contains("telemetry")));
+
+        // Home region sees everything, reassembled
+        assert_eq!(sharder.read("agent-7", &writer).unwrap().records.len(), 4);
+
+        // The EU cell blocks syncs to the US, so only the US shard is readable there
+        let analytics = ShardAccessor::new("svc:analytics", "us-east-1");
+        let view = sharder.read("agent-7", &analytics).unwrap();
+        assert_eq!(view.records, vec![record("telemetry/latency", "p99", MemoryClass::Internal)]);
+        assert_eq!(view.withheld, vec!["agent-7/episodic/personal", "agent-7/skills/internal"]);
+
+        let trail = audit.for_shard("agent-7/episodic/personal");
+        let actions: Vec<_> = trail.iter().map(|a| a.action).collect();
+        assert_eq!(actions, vec![ShardAction::Write, ShardAction::Read, ShardAction::Denied]);
+        assert_eq!(trail[2].accessor, "svc:analytics");
+
+        assert_eq!(sharder.read_namespace("agent-7", "telemetry", &writer).unwrap().records.len(), 1);
+    }
+
+    #[test]
+    fn test_rewrite_removes_stale_shards_and_detects_corruption() {
+        let store = Arc::new(MemoryStore::default());
+        let sharder = MemorySharder {
+            home_cell: "eu-1".into(),
+            cells: vec![Cell { config: cell("eu-1", "eu-central-1", &[]), store: store.clone() }],
+            rules: vec![ResidencyRule::new(&["ap-south-1"]).with_class(MemoryClass::Restricted)],
+            audit: Arc::new(MemoryShardAudit::default()),
+        };
+        let writer = ShardAccessor::new("svc:memory", "eu-central-1");
+
+        assert!(matches!(
+            sharder.write("agent-7", &[record("health", "labs", MemoryClass::Restricted)], &writer),
+            Err(ShardError::NoResidentCell { .. })
+        ));
+
+        sharder.write("agent-7", &[record("episodic", "a", MemoryClass::Internal)], &writer).unwrap();
+        sharder.write("agent-7", &[record("skills", "b", MemoryClass::Internal)], &writer).unwrap();
+        assert!(store.head("memory-shards/agent-7/episodic/internal.json").unwrap().is_none());
+
+        store.objects.lock().unwrap().insert("memory-shards/agent-7/skills/internal.json".into(), b"[]".to_vec());
+        assert!(matches!(sharder.read("agent-7", &writer), Err(ShardError::Corrupted(_))));
+    }
+}
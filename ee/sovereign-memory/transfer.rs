@@ -0,0 +1,441 @@
+//! Chunked, Resumable Transfers
+//!
+//! Copies one object between [`ObjectStore`]s in chunks:
+//! - Each chunk's SHA-256 is recorded in a manifest that is checkpointed
+//!   after every staged part, so an interrupted transfer resumes with the
+//!   first missing chunk instead of starting over
+//! - Reads and uploads share a bandwidth budget
+//! - Once assembled, the destination is read back chunk by chunk and
+//!   compared with the source digests; only a verified copy lets the
+//!   source be released
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let transfer = ChunkedTransfer::new(source, destination, Arc::new(FileCheckpointStore::new("/var/lib/agentkern/migrations")))
+//!     .with_chunk_size(16 * 1024 * 1024)
+//!     .with_bandwidth_limit(50 * 1024 * 1024);
+//!
+//! // Safe to call again after a crash or network failure
+//! let report = transfer.run("passports/agent-7")?;
+//! transfer.release_source(&report)?;
+//! ```
+
+use super::migration::MigrationError;
+use super::stores::{CompletedPart, ObjectStore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Progress of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferState {
+    Uploading,
+    /// Destination assembled, not yet read back
+    Assembled,
+    /// Destination matches the source digests
+    Verified,
+}
+
+/// One chunk of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkRecord {
+    pub offset: u64,
+    pub len: u64,
+    /// Hex SHA-256 of the source bytes, once read
+    pub sha256: Option<String>,
+    /// Part ETag or block ID, once staged
+    pub etag: Option<String>,
+}
+
+/// Checkpointed state of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferManifest {
+    pub key: String,
+    pub size: u64,
+    pub chunk_size: u64,
+    pub upload_id: String,
+    pub chunks: Vec<ChunkRecord>,
+    pub state: TransferState,
+}
+
+impl TransferManifest {
+    fn new(key: &str, size: u64, chunk_size: u64, upload_id: String) -> Self {
+        let chunks = (0..size.div_ceil(chunk_size).max(1))
+            .map(|i| ChunkRecord {
+                offset: i * chunk_size,
+                len: chunk_size.min(size - i * chunk_size),
+                sha256: None,
+                etag: None,
+            })
+            .collect();
+        Self { key: key.to_string(), size, chunk_size, upload_id, chunks, state: TransferState::Uploading }
+    }
+
+    /// SHA-256 over the chunk digests, identifying the transferred content.
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for chunk in &self.chunks {
+            hasher.update(chunk.sha256.as_deref().unwrap_or_default());
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Persists manifests between attempts.
+pub trait CheckpointStore: Send + Sync {
+    fn load(&self, key: &str) -> Result<Option<TransferManifest>, MigrationError>;
+    fn save(&self, manifest: &TransferManifest) -> Result<(), MigrationError>;
+    fn remove(&self, key: &str) -> Result<(), MigrationError>;
+}
+
+/// Manifests as JSON files in a directory.
+pub struct FileCheckpointStore {
+    dir: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", hex::encode(Sha256::digest(key.as_bytes()))))
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<TransferManifest>, MigrationError> {
+        match std::fs::read(self.path(key)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(|e| MigrationError::Checkpoint(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(MigrationError::Checkpoint(e.to_string())),
+        }
+    }
+
+    fn save(&self, manifest: &TransferManifest) -> Result<(), MigrationError> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| MigrationError::Checkpoint(e.to_string()))?;
+        let path = self.path(&manifest.key);
+        let tmp = path.with_extension("tmp");
+        let bytes = serde_json::to_vec(manifest).map_err(|e| MigrationError::Checkpoint(e.to_string()))?;
+        // Write-then-rename so a crash never leaves a torn manifest
+        std::fs::write(&tmp, bytes)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .map_err(|e| MigrationError::Checkpoint(e.to_string()))
+    }
+
+    fn remove(&self, key: &str) -> Result<(), MigrationError> {
+        match std::fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(MigrationError::Checkpoint(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Manifests kept in memory; progress survives retries, not restarts.
+#[derive(Default)]
+pub struct MemoryCheckpointStore {
+    manifests: Mutex<HashMap<String, TransferManifest>>,
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<TransferManifest>, MigrationError> {
+        Ok(self.manifests.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, manifest: &TransferManifest) -> Result<(), MigrationError> {
+        self.manifests.lock().unwrap().insert(manifest.key.clone(), manifest.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), MigrationError> {
+        self.manifests.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// Bytes-per-second budget.
+struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+    sent: u64,
+}
+
+impl Throttle {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self { bytes_per_sec, started: Instant::now(), sent: 0 }
+    }
+
+    /// Account for `bytes`, sleeping while ahead of the budget.
+    fn consume(&mut self, bytes: u64) {
+        if self.bytes_per_sec == 0 {
+            return;
+        }
+        self.sent += bytes;
+        let due = Duration::from_secs_f64(self.sent as f64 / self.bytes_per_sec as f64);
+        if let Some(ahead) = due.checked_sub(self.started.elapsed()) {
+            std::thread::sleep(ahead);
+        }
+    }
+}
+
+/// Outcome of [`ChunkedTransfer::run`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferReport {
+    pub key: String,
+    pub bytes: u64,
+    pub chunks: usize,
+    /// Chunks staged by this run; fewer than `chunks` when resumed
+    pub chunks_sent: usize,
+    pub resumed: bool,
+    /// See [`TransferManifest::digest`]
+    pub digest: String,
+    pub state: TransferState,
+}
+
+/// Copies an object between stores in verified, resumable chunks.
+pub struct ChunkedTransfer {
+    source: Arc<dyn ObjectStore>,
+    destination: Arc<dyn ObjectStore>,
+    checkpoints: Arc<dyn CheckpointStore>,
+    chunk_size: u64,
+    bandwidth_limit: u64,
+}
+
+impl ChunkedTransfer {
+    pub fn new(
+        source: Arc<dyn ObjectStore>,
+        destination: Arc<dyn ObjectStore>,
+        checkpoints: Arc<dyn CheckpointStore>,
+    ) -> Self {
+        Self {
+            source,
+            destination,
+            checkpoints,
+            chunk_size: 8 * 1024 * 1024,
+            bandwidth_limit: 0,
+        }
+    }
+
+    /// Chunk size; raised to the destination's minimum part size.
+    pub fn with_chunk_size(mut self, bytes: u64) -> Self {
+        self.chunk_size = bytes.max(1);
+        self
+    }
+
+    /// Bytes per second moved, read and written combined (0 = unlimited).
+    pub fn with_bandwidth_limit(mut self, bytes_per_sec: u64) -> Self {
+        self.bandwidth_limit = bytes_per_sec;
+        self
+    }
+
+    /// Transfer and verify `key`, resuming a checkpointed attempt.
+    pub fn run(&self, key: &str) -> Result<TransferReport, MigrationError> {
+        let size = self.source.head(key)?
+            .ok_or_else(|| MigrationError::ReadError(format!("{} not found at source", key)))?;
+
+        let (mut manifest, resumed) = match self.checkpoints.load(key)? {
+            Some(manifest) if manifest.size == size => (manifest, true),
+            stale => {
+                if let Some(stale) = stale {
+                    // Source changed since the checkpoint; its parts are useless
+                    let _ = self.destination.abort_upload(key, &stale.upload_id);
+                }
+                let chunk_size = self.chunk_size.max(self.destination.min_part_size());
+                let manifest = TransferManifest::new(key, size, chunk_size, self.destination.begin_upload(key)?);
+                self.checkpoints.save(&manifest)?;
+                (manifest, false)
+            }
+        };
+
+        let mut throttle = Throttle::new(self.bandwidth_limit);
+        let mut chunks_sent = 0;
+        if manifest.state == TransferState::Uploading {
+            for index in 0..manifest.chunks.len() {
+                if manifest.chunks[index].etag.is_some() {
+                    continue;
+                }
+                let chunk = &manifest.chunks[index];
+                let data = self.source.read_range(key, chunk.offset, chunk.len)?;
+                if data.len() as u64 != chunk.len {
+                    return Err(MigrationError::ReadError(format!("short read of chunk {}", index)));
+                }
+                let digest = Sha256::digest(&data);
+                throttle.consume(2 * chunk.len);
+                let etag = self.destination.upload_part(key, &manifest.upload_id, index as u32 + 1, &data, &digest)?;
+
+                let chunk = &mut manifest.chunks[index];
+                chunk.sha256 = Some(hex::encode(digest));
+                chunk.etag = Some(etag);
+                self.checkpoints.save(&manifest)?;
+                chunks_sent += 1;
+            }
+
+            let parts: Vec<_> = manifest.chunks.iter().enumerate()
+                .map(|(i, chunk)| CompletedPart {
+                    part_number: i as u32 + 1,
+                    etag: chunk.etag.clone().unwrap_or_default(),
+                })
+                .collect();
+            self.destination.complete_upload(key, &manifest.upload_id, &parts)?;
+            manifest.state = TransferState::Assembled;
+            self.checkpoints.save(&manifest)?;
+        }
+
+        if manifest.state == TransferState::Assembled {
+            self.verify(&manifest, &mut throttle)?;
+            manifest.state = TransferState::Verified;
+            self.checkpoints.save(&manifest)?;
+        }
+
+        Ok(TransferReport {
+            key: key.to_string(),
+            bytes: size,
+            chunks: manifest.chunks.len(),
+            chunks_sent,
+            resumed,
+            digest: manifest.digest(),
+            state: manifest.state,
+        })
+    }
+
+    /// Read the destination back and compare every chunk with the source digest.
+    fn verify(&self, manifest: &TransferManifest, throttle: &mut Throttle) -> Result<(), MigrationError> {
+        if self.destination.head(&manifest.key)? != Some(manifest.size) {
+            return Err(MigrationError::VerificationFailed);
+        }
+        for (index, chunk) in manifest.chunks.iter().enumerate() {
+            let data = self.destination.read_range(&manifest.key, chunk.offset, chunk.len)?;
+            throttle.consume(chunk.len);
+            if chunk.sha256.as_deref() != Some(hex::encode(Sha256::digest(&data)).as_str()) {
+                return Err(MigrationError::ChecksumMismatch { chunk: index });
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the source copy of a verified transfer and drop its checkpoint.
+    pub fn release_source(&self, report: &TransferReport) -> Result<(), MigrationError> {
+        self.checkpoints.load(&report.key)?
+            .filter(|m| m.state == TransferState::Verified && m.digest() == report.digest)
+            .ok_or(MigrationError::VerificationFailed)?;
+        self.source.delete(&report.key)?;
+        self.checkpoints.remove(&report.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Store keeping objects and staged parts in memory.
+    #[derive(Default)]
+    struct MemoryStore {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        parts: Mutex<HashMap<u32, Vec<u8>>>,
+        /// Fail this part number once
+        fail_part: AtomicU32,
+        uploads: AtomicU32,
+    }
+
+    impl ObjectStore for MemoryStore {
+        fn head(&self, key: &str) -> Result<Option<u64>, MigrationError> {
+            Ok(self.objects.lock().unwrap().get(key).map(|o| o.len() as u64))
+        }
+
+        fn read_range(&self, key: &str, offset: u64, len: u64) -> Result<Vec<u8>, MigrationError> {
+            let objects = self.objects.lock().unwrap();
+            Ok(objects[key][offset as usize..(offset + len) as usize].to_vec())
+        }
+
+        fn begin_upload(&self, _key: &str) -> Result<String, MigrationError> {
+            Ok("upload-1".into())
+        }
+
+        fn upload_part(&self, _key: &str, _id: &str, part: u32, data: &[u8], _sha: &[u8]) -> Result<String, MigrationError> {
+            if self.fail_part.compare_exchange(part, 0, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                return Err(MigrationError::WriteError("connection reset".into()));
+            }
+            self.uploads.fetch_add(1, Ordering::SeqCst);
+            self.parts.lock().unwrap().insert(part, data.to_vec());
+            Ok(format!("etag-{}", part))
+        }
+
+        fn complete_upload(&self, key: &str, _id: &str, parts: &[CompletedPart]) -> Result<(), MigrationError> {
+            let staged = self.parts.lock().unwrap();
+            let object = parts.iter().flat_map(|p| staged[&p.part_number].clone()).collect();
+            self.objects.lock().unwrap().insert(key.to_string(), object);
+            Ok(())
+        }
+
+        fn abort_upload(&self, _key: &str, _id: &str) -> Result<(), MigrationError> {
+            self.parts.lock().unwrap().clear();
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MigrationError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        fn min_part_size(&self) -> u64 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_resume_verify_and_release() {
+        let passport: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let source = Arc::new(MemoryStore::default());
+        source.objects.lock().unwrap().insert("passports/a".into(), passport.clone());
+        let destination = Arc::new(MemoryStore::default());
+        destination.fail_part.store(3, Ordering::SeqCst);
+        let transfer = ChunkedTransfer::new(source.clone(), destination.clone(), Arc::new(MemoryCheckpointStore::default()))
+            .with_chunk_size(4096);
+
+        // Interrupted at the third chunk
+        assert!(matches!(transfer.run("passports/a"), Err(MigrationError::WriteError(_))));
+        assert!(transfer.release_source(&TransferReport {
+            key: "passports/a".into(), bytes: 0, chunks: 0, chunks_sent: 0, resumed: false,
+            digest: String::new(), state: TransferState::Uploading,
+        }).is_err());
+
+        let report = transfer.run("passports/a").unwrap();
+        assert!(report.resumed);
+        assert_eq!((report.chunks, report.chunks_sent), (3, 1));
+        assert_eq!(destination.uploads.load(Ordering::SeqCst), 3);
+        assert_eq!(report.state, TransferState::Verified);
+        assert_eq!(destination.objects.lock().unwrap()["passports/a"], passport);
+
+        transfer.release_source(&report).unwrap();
+        assert!(source.head("passports/a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_corrupted_destination_keeps_source() {
+        let source = Arc::new(MemoryStore::default());
+        source.objects.lock().unwrap().insert("k".into(), vec![7; 100]);
+        let destination = Arc::new(MemoryStore::default());
+        let checkpoints = Arc::new(MemoryCheckpointStore::default());
+        let transfer = ChunkedTransfer::new(source.clone(), destination.clone(), checkpoints.clone()).with_chunk_size(40);
+
+        let mut manifest = TransferManifest::new("k", 100, 40, "upload-1".into());
+        for (i, chunk) in manifest.chunks.iter_mut().enumerate() {
+            chunk.sha256 = Some(hex::encode(Sha256::digest(vec![7; chunk.len as usize])));
+            chunk.etag = Some(format!("etag-{}", i + 1));
+        }
+        manifest.state = TransferState::Assembled;
+        checkpoints.save(&manifest).unwrap();
+        let mut corrupted = vec![7; 100];
+        corrupted[50] = 0;
+        destination.objects.lock().unwrap().insert("k".into(), corrupted);
+
+        assert!(matches!(transfer.run("k"), Err(MigrationError::ChecksumMismatch { chunk: 1 })));
+        assert!(source.head("k").unwrap().is_some());
+    }
+}
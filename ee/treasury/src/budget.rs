@@ -0,0 +1,365 @@
+//! Spending Limits
+//!
+//! Per-agent budgets checked by [`Treasury::pay`] and
+//! [`Treasury::open_channel`] (which counts the channel's capacity as paid to
+//! the other party) before any funds move:
+//! - Daily and monthly caps per currency (UTC calendar day and month)
+//! - Caps on what an agent pays a single counterparty
+//! - Velocity: at most `n` payments in a sliding window
+//!
+//! A payment over a cap fails with [`TreasuryError::BudgetExceeded`], one
+//! over the velocity limit with [`TreasuryError::VelocityExceeded`]. A human
+//! approves an exception by granting a single-use [`BudgetOverride`] for one
+//! payment up to a given amount, which [`Treasury::pay_with_override`]
+//! spends. Overridden payments still count toward the agent's totals.
+//!
+//! [`crate::SharedTreasury`] enforces budgets set with
+//! [`crate::SharedTreasury::with_budget`] on its payments and channels the
+//! same way.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let usd = |amount| Money::parse(amount, Currency::Usd);
+//! let mut treasury = Treasury::new("org-1")?.with_budget(
+//!     "agent-A",
+//!     AgentBudget::new()
+//!         .with_daily_limit(usd("100")?)
+//!         .with_monthly_limit(usd("1000")?)
+//!         .with_counterparty_limit("vendor-X", LimitPeriod::Daily, usd("25")?)
+//!         .with_velocity(10, chrono::Duration::minutes(1)),
+//! );
+//!
+//! if let Err(TreasuryError::BudgetExceeded { .. }) = treasury.pay("agent-A", "vendor-X", usd("40")?) {
+//!     // Escalated; a human approves the exception
+//!     let grant = treasury.grant_override("agent-A", usd("40")?, "ops@example.com", chrono::Duration::hours(1));
+//!     treasury.pay_with_override("agent-A", "vendor-X", usd("40")?, &grant.token)?;
+//! }
+//! ```
+
+use crate::{Currency, Money, Treasury, TreasuryError};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Period a spending cap resets over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitPeriod {
+    /// UTC calendar day
+    Daily,
+    /// UTC calendar month
+    Monthly,
+}
+
+impl LimitPeriod {
+    /// Start of the period containing `at`.
+    pub fn start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let day = at.date_naive();
+        let first = match self {
+            Self::Daily => day,
+            Self::Monthly => day.with_day(1).expect("every month has a first day"),
+        };
+        first.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc()
+    }
+}
+
+impl fmt::Display for LimitPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+        })
+    }
+}
+
+/// A cap on spending in one currency over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    pub period: LimitPeriod,
+    /// Payments in other currencies do not count
+    pub max: Money,
+    /// Only count payments to this agent
+    pub counterparty: Option<String>,
+}
+
+impl fmt::Display for SpendingLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.counterparty {
+            Some(counterparty) => write!(f, "{} limit of {} to {}", self.period, self.max, counterparty),
+            None => write!(f, "{} limit of {}", self.period, self.max),
+        }
+    }
+}
+
+/// At most `max_payments` payments in any `window_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VelocityLimit {
+    pub max_payments: usize,
+    pub window_secs: i64,
+}
+
+/// Spending limits of one agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentBudget {
+    pub limits: Vec<SpendingLimit>,
+    pub velocity: Option<VelocityLimit>,
+}
+
+impl AgentBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap total spending per UTC day in `max`'s currency.
+    pub fn with_daily_limit(self, max: Money) -> Self {
+        self.with_limit(LimitPeriod::Daily, max, None)
+    }
+
+    /// Cap total spending per UTC month in `max`'s currency.
+    pub fn with_monthly_limit(self, max: Money) -> Self {
+        self.with_limit(LimitPeriod::Monthly, max, None)
+    }
+
+    /// Cap spending to one counterparty per period.
+    pub fn with_counterparty_limit(self, counterparty: impl Into<String>, period: LimitPeriod, max: Money) -> Self {
+        self.with_limit(period, max, Some(counterparty.into()))
+    }
+
+    /// Allow at most `max_payments` payments in any `window`.
+    pub fn with_velocity(mut self, max_payments: usize, window: Duration) -> Self {
+        self.velocity = Some(VelocityLimit { max_payments, window_secs: window.num_seconds() });
+        self
+    }
+
+    fn with_limit(mut self, period: LimitPeriod, max: Money, counterparty: Option<String>) -> Self {
+        self.limits.push(SpendingLimit { period, max, counterparty });
+        self
+    }
+}
+
+/// A human-approved exception to an agent's budget, good for one payment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetOverride {
+    pub token: String,
+    pub agent_id: String,
+    /// Largest payment the override covers
+    pub max_amount: Money,
+    pub approved_by: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct Spend {
+    to: String,
+    amount: Money,
+    at: DateTime<Utc>,
+}
+
+/// Budgets, recent spending and outstanding overrides of a [`Treasury`].
+#[derive(Debug, Default)]
+pub(crate) struct Budgets {
+    budgets: HashMap<String, AgentBudget>,
+    /// Payments per agent since the start of the month or velocity window, oldest first
+    spends: HashMap<String, VecDeque<Spend>>,
+    overrides: HashMap<String, BudgetOverride>,
+}
+
+impl Budgets {
+    pub(crate) fn set(&mut self, agent_id: &str, budget: AgentBudget) {
+        self.budgets.insert(agent_id.to_string(), budget);
+    }
+
+    /// Check a payment against the payer's limits.
+    pub(crate) fn check(&self, from: &str, to: &str, amount: Money, now: DateTime<Utc>) -> Result<(), TreasuryError> {
+        let Some(budget) = self.budgets.get(from) else {
+            return Ok(());
+        };
+        let spends = self.spends.get(from);
+        let recent = || spends.into_iter().flatten();
+
+        for limit in budget.limits.iter().filter(|l| l.max.currency() == amount.currency()) {
+            if limit.counterparty.as_ref().is_some_and(|c| c != to) {
+                continue;
+            }
+            let since = limit.period.start(now);
+            let spent: u128 = recent()
+                .filter(|s| s.at >= since && s.amount.currency() == amount.currency())
+                .filter(|s| limit.counterparty.is_none() || s.to == to)
+                .map(|s| s.amount.units())
+                .sum();
+            if spent.saturating_add(amount.units()) > limit.max.units() {
+                return Err(TreasuryError::BudgetExceeded {
+                    agent_id: from.to_string(),
+                    limit: limit.to_string(),
+                    spent: Money::from_units(spent, amount.currency()),
+                    requested: amount,
+                });
+            }
+        }
+
+        if let Some(velocity) = budget.velocity {
+            let since = now - Duration::seconds(velocity.window_secs);
+            if recent().filter(|s| s.at > since).count() >= velocity.max_payments {
+                return Err(TreasuryError::VelocityExceeded {
+                    agent_id: from.to_string(),
+                    max_payments: velocity.max_payments,
+                    window_secs: velocity.window_secs,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Count a completed payment, dropping spends no limit looks at anymore.
+    pub(crate) fn record(&mut self, from: &str, to: &str, amount: Money, now: DateTime<Utc>) {
+        let velocity = self.budgets.get(from).and_then(|b| b.velocity);
+        let window = velocity.map_or(Duration::zero(), |v| Duration::seconds(v.window_secs));
+        let keep_since = LimitPeriod::Monthly.start(now).min(now - window);
+
+        let spends = self.spends.entry(from.to_string()).or_default();
+        while spends.front().is_some_and(|s| s.at < keep_since) {
+            spends.pop_front();
+        }
+        spends.push_back(Spend { to: to.to_string(), amount, at: now });
+    }
+
+    /// Check a payment and count it in one step, so concurrent payments
+    /// can't both pass the check. Hand the spend back with
+    /// [`Budgets::release`] if the payment fails.
+    pub(crate) fn reserve(&mut self, from: &str, to: &str, amount: Money, now: DateTime<Utc>) -> Result<(), TreasuryError> {
+        self.check(from, to, amount, now)?;
+        self.record(from, to, amount, now);
+        Ok(())
+    }
+
+    /// Stop counting a reserved spend.
+    pub(crate) fn release(&mut self, from: &str, to: &str, amount: Money, at: DateTime<Utc>) {
+        let Some(spends) = self.spends.get_mut(from) else {
+            return;
+        };
+        if let Some(index) = spends.iter().rposition(|s| s.to == to && s.amount == amount && s.at == at) {
+            spends.remove(index);
+        }
+    }
+
+    /// Take out the override `token` for a payment, so it is used once.
+    /// Hand it back with [`Budgets::restore`] if the payment fails.
+    pub(crate) fn claim(&mut self, token: &str, from: &str, amount: Money) -> Result<BudgetOverride, TreasuryError> {
+        let rejected = |reason: &str| TreasuryError::OverrideRejected { reason: reason.to_string() };
+        self.overrides.retain(|_, o| o.expires_at > Utc::now());
+        let grant = self.overrides.get(token).ok_or_else(|| rejected("unknown, used or expired token"))?;
+        if grant.agent_id != from {
+            return Err(rejected("token was granted to another agent"));
+        }
+        if grant.max_amount.currency() != amount.currency() || grant.max_amount.units() < amount.units() {
+            return Err(rejected("payment exceeds the approved amount"));
+        }
+        Ok(self.overrides.remove(token).expect("override checked above"))
+    }
+
+    pub(crate) fn restore(&mut self, grant: BudgetOverride) {
+        self.overrides.insert(grant.token.clone(), grant);
+    }
+}
+
+impl Treasury {
+    /// Enforce `budget` on payments from `agent_id`.
+    pub fn with_budget(mut self, agent_id: &str, budget: AgentBudget) -> Self {
+        self.set_budget(agent_id, budget);
+        self
+    }
+
+    /// Replace an agent's budget. Spending so far still counts.
+    pub fn set_budget(&mut self, agent_id: &str, budget: AgentBudget) {
+        self.budgets.set(agent_id, budget);
+    }
+
+    pub fn budget(&self, agent_id: &str) -> Option<&AgentBudget> {
+        self.budgets.budgets.get(agent_id)
+    }
+
+    /// What `agent_id` may still spend in `currency` today under its tightest
+    /// non-counterparty cap, or `None` without such a cap.
+    pub fn remaining_budget(&self, agent_id: &str, currency: Currency) -> Option<Money> {
+        let budget = self.budget(agent_id)?;
+        let now = Utc::now();
+        let spends = self.budgets.spends.get(agent_id);
+        budget
+            .limits
+            .iter()
+            .filter(|l| l.counterparty.is_none() && l.max.currency() == currency)
+            .map(|limit| {
+                let since = limit.period.start(now);
+                let spent: u128 = spends
+                    .into_iter()
+                    .flatten()
+                    .filter(|s| s.at >= since && s.amount.currency() == currency)
+                    .map(|s| s.amount.units())
+                    .sum();
+                limit.max.units().saturating_sub(spent)
+            })
+            .min()
+            .map(|units| Money::from_units(units, currency))
+    }
+
+    /// Approve one payment by `agent_id` of up to `max_amount` past its
+    /// budget. The returned token is valid until used or `ttl` passes.
+    pub fn grant_override(&mut self, agent_id: &str, max_amount: Money, approved_by: &str, ttl: Duration) -> BudgetOverride {
+        let grant = BudgetOverride {
+            token: uuid::Uuid::new_v4().to_string(),
+            agent_id: agent_id.to_string(),
+            max_amount,
+            approved_by: approved_by.to_string(),
+            expires_at: Utc::now() + ttl,
+        };
+        tracing::info!(agent_id, max_amount = %max_amount, approved_by, "Budget override granted");
+        self.budgets.restore(grant.clone());
+        grant
+    }
+
+    /// Pay past the payer's limits with a granted override. Balance and
+    /// threshold-signature checks still apply; the token is spent only if
+    /// the payment succeeds.
+    pub fn pay_with_override(
+        &mut self,
+        from_agent: &str,
+        to_agent: &str,
+        amount: Money,
+        token: &str,
+    ) -> Result<String, TreasuryError> {
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+        let grant = self.budgets.claim(token, from_agent, amount)?;
+        match self.execute_payment(from_agent, to_agent, amount) {
+            Ok(payment_id) => {
+                tracing::warn!(
+                    from = from_agent,
+                    to = to_agent,
+                    amount = %amount,
+                    approved_by = %grant.approved_by,
+                    "Payment made under budget override"
+                );
+                Ok(payment_id)
+            }
+            Err(e) => {
+                self.budgets.restore(grant);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_start_is_utc_calendar_boundary() {
+        let at = DateTime::parse_from_rfc3339("2026-03-17T22:45:00+05:00").unwrap().with_timezone(&Utc);
+        assert_eq!(LimitPeriod::Daily.start(at).to_rfc3339(), "2026-03-17T00:00:00+00:00");
+        assert_eq!(LimitPeriod::Monthly.start(at).to_rfc3339(), "2026-03-01T00:00:00+00:00");
+    }
+}
@@ -0,0 +1,311 @@
+//! Treasury Events
+//!
+//! Lets billing systems and the arbiter's escalation module react to
+//! treasury activity as it happens instead of polling:
+//! - [`TreasuryEvents`] broadcasts a typed [`TreasuryEvent`] for every
+//!   completed payment, opened channel, released escrow and escrow
+//!   milestone, and for every operation refused for lack of funds. Events
+//!   are emitted once the operation has committed; subscribers see those
+//!   emitted after they subscribe
+//! - [`WebhookDispatcher`] forwards events to HTTP endpoints, retrying
+//!   failed deliveries with exponential backoff and signing bodies with
+//!   HMAC-SHA256 when the endpoint has a secret
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let events = TreasuryEvents::default();
+//! let mut treasury = Treasury::new("org-1")?.with_events(events.clone());
+//!
+//! let dispatcher = WebhookDispatcher::new()
+//!     .with_endpoint(WebhookEndpoint::new("https://billing.example.com/hooks/treasury").with_secret("s3cret"))
+//!     .with_endpoint(WebhookEndpoint::new("https://arbiter.internal/escalations").only(&["insufficient_balance"]));
+//! tokio::spawn(dispatcher.run(events.subscribe()));
+//! ```
+
+use crate::{Money, Treasury, TreasuryError};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before the slowest one starts missing them.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// What happened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TreasuryEventKind {
+    PaymentCompleted { payment_id: String, from_agent: String, to_agent: String, amount: Money },
+    ChannelOpened { channel_id: String, party_a: String, party_b: String, capacity: Money },
+    EscrowReleased { escrow_id: String, from_agent: String, to_agent: String, amount: Money },
+    /// One milestone's share of an escrow was paid out
+    EscrowMilestoneReleased {
+        escrow_id: String,
+        milestone: String,
+        from_agent: String,
+        to_agent: String,
+        amount: Money,
+        /// Still held for later milestones
+        remaining: Money,
+    },
+    /// An operation was refused because the agent could not cover it
+    InsufficientBalance { agent_id: String, operation: String, required: Money, available: Money },
+}
+
+impl TreasuryEventKind {
+    /// The event's `type`, e.g. `payment_completed`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PaymentCompleted { .. } => "payment_completed",
+            Self::ChannelOpened { .. } => "channel_opened",
+            Self::EscrowReleased { .. } => "escrow_released",
+            Self::EscrowMilestoneReleased { .. } => "escrow_milestone_released",
+            Self::InsufficientBalance { .. } => "insufficient_balance",
+        }
+    }
+}
+
+/// A treasury event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreasuryEvent {
+    /// Unique per event; receivers can deduplicate retried deliveries on it
+    pub id: String,
+    pub tenant_id: String,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: TreasuryEventKind,
+}
+
+/// Broadcast channel of treasury events. Clones share the channel.
+#[derive(Clone)]
+pub struct TreasuryEvents {
+    sender: broadcast::Sender<TreasuryEvent>,
+}
+
+impl Default for TreasuryEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl TreasuryEvents {
+    /// Channel buffering `capacity` events per subscriber.
+    pub fn new(capacity: usize) -> Self {
+        Self { sender: broadcast::channel(capacity).0 }
+    }
+
+    /// Receive every event emitted from now on. A receiver that falls more
+    /// than the capacity behind gets [`broadcast::error::RecvError::Lagged`]
+    /// and skips ahead.
+    pub fn subscribe(&self) -> broadcast::Receiver<TreasuryEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    pub(crate) fn emit(&self, tenant_id: &str, kind: TreasuryEventKind) {
+        let event = TreasuryEvent { id: uuid::Uuid::new_v4().to_string(), tenant_id: tenant_id.to_string(), at: Utc::now(), kind };
+        // No subscribers is fine
+        let _ = self.sender.send(event);
+    }
+
+    /// Emit [`TreasuryEventKind::InsufficientBalance`] if `result` failed
+    /// for lack of funds, and pass it through.
+    pub(crate) fn on_shortfall<T>(
+        &self,
+        tenant_id: &str,
+        agent_id: &str,
+        operation: &str,
+        result: Result<T, TreasuryError>,
+    ) -> Result<T, TreasuryError> {
+        if let Err(TreasuryError::InsufficientBalance { required, available }) = &result {
+            self.emit(
+                tenant_id,
+                TreasuryEventKind::InsufficientBalance {
+                    agent_id: agent_id.to_string(),
+                    operation: operation.to_string(),
+                    required: *required,
+                    available: *available,
+                },
+            );
+        }
+        result
+    }
+}
+
+impl Treasury {
+    /// Emit events on `events`, e.g. a channel shared with other treasuries.
+    pub fn with_events(mut self, events: TreasuryEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn events(&self) -> &TreasuryEvents {
+        &self.events
+    }
+}
+
+/// An HTTP endpoint events are POSTed to as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// Signs bodies in `X-AgentKern-Signature: sha256=<hex HMAC>`
+    pub secret: Option<String>,
+    /// Event types to send; all if empty
+    pub events: Vec<String>,
+}
+
+impl WebhookEndpoint {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), secret: None, events: Vec::new() }
+    }
+
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Only send these event types, e.g. `["payment_completed"]`.
+    pub fn only(mut self, events: &[&str]) -> Self {
+        self.events = events.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    pub fn wants(&self, event: &TreasuryEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event.kind.name())
+    }
+}
+
+/// How failed webhook deliveries are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per delivery, including the first
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubles after each one
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, initial_backoff: Duration::from_millis(500), max_backoff: Duration::from_secs(30) }
+    }
+}
+
+/// Forwards treasury events to webhook endpoints.
+pub struct WebhookDispatcher {
+    endpoints: Vec<WebhookEndpoint>,
+    retry: RetryPolicy,
+    client: reqwest::Client,
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookDispatcher {
+    pub fn new() -> Self {
+        Self { endpoints: Vec::new(), retry: RetryPolicy::default(), client: reqwest::Client::new() }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: WebhookEndpoint) -> Self {
+        self.endpoints.push(endpoint);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send an event to every endpoint that wants it. Returns the attempts
+    /// each delivery took, or why it gave up.
+    pub async fn deliver(&self, event: &TreasuryEvent) -> Vec<(String, Result<u32, TreasuryError>)> {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!(event_id = %event.id, error = %e, "Failed to serialize treasury event");
+                return Vec::new();
+            }
+        };
+        let mut results = Vec::new();
+        for endpoint in self.endpoints.iter().filter(|e| e.wants(event)) {
+            results.push((endpoint.url.clone(), self.deliver_to(endpoint, event, &body).await));
+        }
+        results
+    }
+
+    /// Deliver every event from `events` in order, until the channel closes.
+    pub async fn run(self, mut events: broadcast::Receiver<TreasuryEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    for (url, result) in self.deliver(&event).await {
+                        if let Err(e) = result {
+                            tracing::error!(event_id = %event.id, url = %url, error = %e, "Treasury webhook dropped");
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!(missed, "Treasury webhook dispatcher fell behind; events skipped");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn deliver_to(&self, endpoint: &WebhookEndpoint, event: &TreasuryEvent, body: &[u8]) -> Result<u32, TreasuryError> {
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let reason = match self.post(endpoint, event, body).await {
+                Ok(status) if status.is_success() => return Ok(attempt),
+                // The endpoint refused the event itself; retrying will not help
+                Ok(status) if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS && status != reqwest::StatusCode::REQUEST_TIMEOUT => {
+                    return Err(webhook_failed(endpoint, attempt, format!("rejected with {status}")));
+                }
+                Ok(status) => format!("responded {status}"),
+                Err(e) => e.to_string(),
+            };
+            if attempt >= self.retry.max_attempts {
+                return Err(webhook_failed(endpoint, attempt, reason));
+            }
+            tracing::debug!(url = %endpoint.url, attempt, %reason, "Treasury webhook failed; retrying");
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.retry.max_backoff);
+        }
+    }
+
+    async fn post(&self, endpoint: &WebhookEndpoint, event: &TreasuryEvent, body: &[u8]) -> Result<reqwest::StatusCode, reqwest::Error> {
+        let mut request = self
+            .client
+            .post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-AgentKern-Event", event.kind.name())
+            .header("X-AgentKern-Event-Id", &event.id)
+            .body(body.to_vec());
+        if let Some(secret) = &endpoint.secret {
+            request = request.header("X-AgentKern-Signature", format!("sha256={}", sign(secret, body)));
+        }
+        Ok(request.send().await?.status())
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn webhook_failed(endpoint: &WebhookEndpoint, attempts: u32, reason: String) -> TreasuryError {
+    TreasuryError::WebhookFailed { url: endpoint.url.clone(), attempts, reason }
+}
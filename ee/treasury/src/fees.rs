@@ -0,0 +1,235 @@
+//! Platform Fees
+//!
+//! Marketplaces built on the treasury take a cut of every transaction. A
+//! [`FeeSchedule`] names the wallet fees are credited to and the
+//! [`FeePolicy`] that prices them:
+//! - Flat: a fixed amount per transaction in the fee's currency
+//! - Percentage: a take-rate in basis points
+//! - Tiered: a take-rate that falls as the payee's volume grows
+//!
+//! Fees apply to payments made with `pay()` and to escrow releases, which
+//! is how marketplace tasks settle. The payer pays the full amount; the
+//! payee receives it less the fee, and the fee moves to the fee wallet in
+//! the same operation. Each payment records its [`FeeBreakdown`], as does
+//! each escrow release. Payments to the fee wallet itself are not charged.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let schedule = FeeSchedule::new("platform", FeePolicy::Tiered {
+//!     tiers: vec![
+//!         FeeTier { from_volume: Money::parse("0", Currency::Usd)?, bps: 500 },
+//!         FeeTier { from_volume: Money::parse("10000", Currency::Usd)?, bps: 300 },
+//!     ],
+//! })?;
+//! let mut treasury = Treasury::new("org-1")?.with_fees(schedule);
+//!
+//! let payment_id = treasury.pay("client", "worker", Money::parse("100", Currency::Usd)?)?;
+//! let fee = treasury.payments().iter().find(|p| p.id == payment_id).and_then(|p| p.fee.clone());
+//! ```
+
+use crate::{Account, Currency, Ledger, Money, Treasury, TreasuryError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Basis points in 100%.
+const BPS_SCALE: u32 = 10_000;
+
+/// A take-rate that applies once a payee's volume reaches `from_volume`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeTier {
+    /// Volume already received by the payee, in the tier's currency
+    pub from_volume: Money,
+    /// Take-rate in basis points
+    pub bps: u32,
+}
+
+/// How fees are priced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FeePolicy {
+    /// No fees
+    #[default]
+    None,
+    /// Fixed fee per transaction; transactions in other currencies pay none
+    Flat { fee: Money },
+    /// Take-rate in basis points
+    Percentage { bps: u32 },
+    /// Take-rate of the highest tier the payee's volume has reached, among
+    /// tiers in the transaction's currency; none below the lowest tier
+    Tiered { tiers: Vec<FeeTier> },
+}
+
+impl FeePolicy {
+    /// Fee on `amount` paid to a payee that has already received `volume`
+    /// base units in its currency. Never more than `amount`.
+    pub fn fee(&self, amount: Money, volume: u128) -> Money {
+        let currency = amount.currency();
+        let fee = match self {
+            Self::None => Money::zero(currency),
+            Self::Flat { fee } if fee.currency() == currency => *fee,
+            Self::Flat { .. } => Money::zero(currency),
+            Self::Percentage { bps } => take(amount, *bps),
+            Self::Tiered { tiers } => tiers
+                .iter()
+                .filter(|t| t.from_volume.currency() == currency && t.from_volume.units() <= volume)
+                .max_by_key(|t| t.from_volume.units())
+                .map_or(Money::zero(currency), |tier| take(amount, tier.bps)),
+        };
+        Money::from_units(fee.units().min(amount.units()), currency)
+    }
+
+    fn validate(&self) -> Result<(), TreasuryError> {
+        let invalid = |reason: String| Err(TreasuryError::InvalidFeePolicy { reason });
+        match self {
+            Self::Percentage { bps } if *bps > BPS_SCALE => invalid(format!("take-rate of {bps} bps exceeds 100%")),
+            Self::Tiered { tiers } if tiers.is_empty() => invalid("tiered policy has no tiers".to_string()),
+            Self::Tiered { tiers } => match tiers.iter().find(|t| t.bps > BPS_SCALE) {
+                Some(tier) => invalid(format!("take-rate of {} bps exceeds 100%", tier.bps)),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `bps` basis points of `amount`, rounded down.
+fn take(amount: Money, bps: u32) -> Money {
+    let (units, bps) = (amount.units(), u128::from(bps));
+    let scale = u128::from(BPS_SCALE);
+    Money::from_units(units / scale * bps + units % scale * bps / scale, amount.currency())
+}
+
+/// Where fees go and how they are priced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    fee_wallet: String,
+    policy: FeePolicy,
+}
+
+impl FeeSchedule {
+    /// Credit fees priced by `policy` to `fee_wallet`. Fails on take-rates
+    /// above 100% or tiered policies without tiers.
+    pub fn new(fee_wallet: impl Into<String>, policy: FeePolicy) -> Result<Self, TreasuryError> {
+        let fee_wallet = fee_wallet.into();
+        if fee_wallet.is_empty() {
+            return Err(TreasuryError::InvalidFeePolicy { reason: "fee wallet is required".to_string() });
+        }
+        policy.validate()?;
+        Ok(Self { fee_wallet, policy })
+    }
+
+    pub fn fee_wallet(&self) -> &str {
+        &self.fee_wallet
+    }
+
+    pub fn policy(&self) -> &FeePolicy {
+        &self.policy
+    }
+}
+
+/// Fee taken from one transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// What the payer paid
+    pub gross: Money,
+    pub fee: Money,
+    /// What the payee received
+    pub net: Money,
+    pub fee_wallet: String,
+}
+
+/// Fee schedule and the volume each payee has received under it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Fees {
+    schedule: Option<FeeSchedule>,
+    volumes: HashMap<(String, Currency), u128>,
+}
+
+impl Fees {
+    pub(crate) fn new(schedule: FeeSchedule) -> Self {
+        Self { schedule: Some(schedule), volumes: HashMap::new() }
+    }
+
+    pub(crate) fn schedule(&self) -> Option<&FeeSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// What paying `amount` to `payee` costs in fees, if a schedule applies.
+    pub(crate) fn quote(&self, payee: &str, amount: Money) -> Option<FeeBreakdown> {
+        let schedule = self.schedule.as_ref().filter(|s| s.fee_wallet != payee)?;
+        let fee = schedule.policy.fee(amount, self.volume(payee, amount.currency()));
+        Some(FeeBreakdown {
+            gross: amount,
+            fee,
+            net: Money::from_units(amount.units() - fee.units(), amount.currency()),
+            fee_wallet: schedule.fee_wallet.clone(),
+        })
+    }
+
+    /// Count a charged transaction toward its payee's volume.
+    pub(crate) fn record(&mut self, payee: &str, breakdown: &FeeBreakdown) {
+        let volume = self.volumes.entry((payee.to_string(), breakdown.gross.currency())).or_default();
+        *volume = volume.saturating_add(breakdown.gross.units());
+    }
+
+    pub(crate) fn volume(&self, payee: &str, currency: Currency) -> u128 {
+        self.volumes.get(&(payee.to_string(), currency)).copied().unwrap_or_default()
+    }
+}
+
+impl Treasury {
+    /// Charge fees on payments and escrow releases. Registers the fee
+    /// wallet.
+    pub fn with_fees(mut self, schedule: FeeSchedule) -> Self {
+        self.register_agent(schedule.fee_wallet());
+        self.fees = Fees::new(schedule);
+        self
+    }
+
+    pub fn fee_schedule(&self) -> Option<&FeeSchedule> {
+        self.fees.schedule()
+    }
+
+    /// Volume `agent_id` has received under the fee schedule.
+    pub fn fee_volume(&self, agent_id: &str, currency: Currency) -> Money {
+        Money::from_units(self.fees.volume(agent_id, currency), currency)
+    }
+
+    /// Fee on paying `amount` to `payee`, if a schedule applies. Checks the
+    /// fee wallet can take it, so quote before moving any funds and then
+    /// [`Self::take_fee`] cannot fail.
+    pub(crate) fn quote_fee(&self, payee: &str, amount: Money) -> Result<Option<FeeBreakdown>, TreasuryError> {
+        let Some(breakdown) = self.fees.quote(payee, amount) else {
+            return Ok(None);
+        };
+        let fee_wallet = self.wallet(&breakdown.fee_wallet).ok_or_else(|| TreasuryError::AgentNotFound {
+            agent_id: breakdown.fee_wallet.clone(),
+        })?;
+        if !fee_wallet.can_credit(breakdown.fee.currency(), breakdown.fee.units()) {
+            return Err(TreasuryError::InvalidAmount { amount: breakdown.fee.to_string() });
+        }
+        Ok(Some(breakdown))
+    }
+
+    /// Move a quoted fee from `payee`, already credited the gross amount,
+    /// to the fee wallet.
+    pub(crate) fn take_fee(&mut self, payee: &str, breakdown: &FeeBreakdown, reference: &str) {
+        self.wallet_mut(payee)
+            .and_then(|wallet| wallet.withdraw(breakdown.fee))
+            .expect("payee credited the gross amount");
+        self.wallet_mut(&breakdown.fee_wallet)
+            .and_then(|wallet| wallet.deposit(breakdown.fee))
+            .expect("fee wallet checked when quoted");
+        self.fees.record(payee, breakdown);
+        self.ledger.post_fee(payee, breakdown, reference);
+    }
+}
+
+impl Ledger {
+    /// Post a fee already moved from `payee` to the fee wallet.
+    pub(crate) fn post_fee(&mut self, payee: &str, breakdown: &FeeBreakdown, reference: &str) {
+        let (payee, fee_wallet) = (Account::Wallet(payee.to_string()), Account::Wallet(breakdown.fee_wallet.clone()));
+        self.post("platform_fee", Some(reference), &[(payee, fee_wallet, breakdown.fee)]);
+    }
+}
@@ -0,0 +1,511 @@
+//! FX Conversion
+//!
+//! Cross-currency payments: [`ExchangeRates`] asks its [`RateProvider`]s in
+//! order for a fresh rate, quotes a conversion with the configured spread,
+//! and re-checks the live rate against the quote at execution time so a
+//! payment never settles at a rate that slipped further than the
+//! [`FxPolicy`] allows.
+//!
+//! Rates are fixed-point with 18 decimals and conversions are integer, like
+//! [`Money`]. Providers:
+//! - [`StaticRates`]: a configured table, e.g. internal credit prices
+//! - [`HttpRateOracle`]: a JSON rate feed, refreshed in the background and
+//!   read from cache
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let oracle = Arc::new(HttpRateOracle::new("https://rates.example.com/latest", Currency::Usd));
+//! oracle.refresh().await?;
+//!
+//! let rates = ExchangeRates::new(FxPolicy::default())
+//!     .with_provider(oracle.clone())
+//!     .with_provider(Arc::new(StaticRates::new().with_rate(Currency::Usd, Currency::Credits, "100")?));
+//! let mut treasury = Treasury::new("org-1")?.with_exchange_rates(rates);
+//!
+//! let quote = treasury.quote_conversion(Money::parse("25", Currency::Usd)?, Currency::Eur)?;
+//! treasury.pay_with_conversion("agent-A", "agent-B", &quote)?;
+//! ```
+
+use crate::threshold::TreasuryOperation;
+use crate::{Account, Currency, Money, PaymentRequest, Treasury, TreasuryError, TreasuryEventKind};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Fractional digits of a [`Rate`].
+const RATE_DECIMALS: u32 = 18;
+const RATE_SCALE: u128 = 10_u128.pow(RATE_DECIMALS);
+const BPS: u128 = 10_000;
+
+const CURRENCIES: [Currency; 8] = [
+    Currency::Usd,
+    Currency::Eur,
+    Currency::Btc,
+    Currency::Sats,
+    Currency::Eth,
+    Currency::Usdc,
+    Currency::Usdt,
+    Currency::Credits,
+];
+
+/// Whole units of `quote` per whole unit of `base`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rate {
+    pub base: Currency,
+    pub quote: Currency,
+    /// Rate scaled by 10^18
+    scaled: u128,
+    pub as_of: DateTime<Utc>,
+    /// Provider the rate came from
+    pub source: String,
+}
+
+impl Rate {
+    /// Parse a decimal rate such as `"0.92"`.
+    pub fn parse(base: Currency, quote: Currency, rate: &str, source: impl Into<String>) -> Result<Self, TreasuryError> {
+        let invalid = || TreasuryError::InvalidAmount { amount: rate.to_string() };
+        let (whole, fraction) = rate.trim().split_once('.').unwrap_or((rate.trim(), ""));
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() && fraction.is_empty() || !digits(whole) || !digits(fraction) {
+            return Err(invalid());
+        }
+        // Digits past the 18th are truncated
+        let fraction = &fraction[..fraction.len().min(RATE_DECIMALS as usize)];
+        let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+        let fraction = format!("{:0<width$}", fraction, width = RATE_DECIMALS as usize);
+        let scaled = whole
+            .checked_mul(RATE_SCALE)
+            .and_then(|w| w.checked_add(fraction.parse().ok()?))
+            .filter(|s| *s > 0)
+            .ok_or_else(invalid)?;
+        Ok(Self { base, quote, scaled, as_of: Utc::now(), source: source.into() })
+    }
+
+    /// The rate from `quote` back to `base`.
+    pub fn inverse(&self) -> Option<Rate> {
+        let scaled = mul_div(RATE_SCALE, RATE_SCALE, self.scaled).filter(|s| *s > 0)?;
+        Some(Self { base: self.quote, quote: self.base, scaled, as_of: self.as_of, source: self.source.clone() })
+    }
+
+    /// Chain with a rate from this rate's quote currency onwards.
+    pub fn then(&self, next: &Rate) -> Option<Rate> {
+        if next.base != self.quote {
+            return None;
+        }
+        let scaled = mul_div(self.scaled, next.scaled, RATE_SCALE).filter(|s| *s > 0)?;
+        Some(Self {
+            base: self.base,
+            quote: next.quote,
+            scaled,
+            as_of: self.as_of.min(next.as_of),
+            source: self.source.clone(),
+        })
+    }
+
+    /// Convert `amount` into the quote currency, rounding down.
+    pub fn convert(&self, amount: Money) -> Result<Money, TreasuryError> {
+        amount.same_currency(Money::zero(self.base))?;
+        let overflow = || TreasuryError::InvalidAmount { amount: format!("{} at {}", amount, self) };
+        let numerator = self.scaled.checked_mul(self.quote.scale()).ok_or_else(overflow)?;
+        let denominator = RATE_SCALE * self.base.scale();
+        let units = mul_div(amount.units(), numerator, denominator).ok_or_else(overflow)?;
+        Ok(Money::from_units(units, self.quote))
+    }
+
+    /// Whether `self` is worse than `quoted` by more than `max_bps`.
+    fn slipped_from(&self, quoted: &Rate, max_bps: u32) -> bool {
+        // self < quoted * (1 - max_bps / 10_000), without overflowing
+        match (self.scaled.checked_mul(BPS), quoted.scaled.checked_mul(BPS - (max_bps as u128).min(BPS))) {
+            (Some(current), Some(floor)) => current < floor,
+            _ => mul_div(self.scaled, BPS, quoted.scaled).is_some_and(|ratio| ratio < BPS - max_bps as u128),
+        }
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fraction = format!("{:018}", self.scaled % RATE_SCALE);
+        let fraction = fraction.trim_end_matches('0');
+        let sep = if fraction.is_empty() { "" } else { "." };
+        write!(f, "1 {} = {}{}{} {}", self.base.code(), self.scaled / RATE_SCALE, sep, fraction, self.quote.code())
+    }
+}
+
+/// `a * b / d` rounded down, through a 256-bit intermediate. `None` if the
+/// result does not fit in a `u128` or `d` is zero.
+fn mul_div(a: u128, b: u128, d: u128) -> Option<u128> {
+    if d == 0 {
+        return None;
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product / d);
+    }
+
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let (lo_lo, hi_lo, lo_hi, hi_hi) = (a_lo * b_lo, a_hi * b_lo, a_lo * b_hi, a_hi * b_hi);
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (lo_lo & MASK) | (mid << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    if hi >= d {
+        return None;
+    }
+
+    // Long division of hi:lo by d, one bit at a time
+    let (mut remainder, mut quotient) = (hi, 0u128);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+/// A source of exchange rates.
+pub trait RateProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Current rate, if this provider has one.
+    fn rate(&self, base: Currency, quote: Currency) -> Option<Rate>;
+}
+
+/// Look up `base -> quote` in a table of rates, directly, inverted, or
+/// crossed through `pivot`.
+fn lookup(table: &HashMap<(Currency, Currency), Rate>, base: Currency, quote: Currency, pivot: Currency) -> Option<Rate> {
+    let direct = |b: Currency, q: Currency| {
+        table.get(&(b, q)).cloned().or_else(|| table.get(&(q, b)).and_then(Rate::inverse))
+    };
+    direct(base, quote).or_else(|| direct(base, pivot)?.then(&direct(pivot, quote)?))
+}
+
+/// A configured rate table. Rates never go stale.
+#[derive(Debug, Default)]
+pub struct StaticRates {
+    rates: RwLock<HashMap<(Currency, Currency), Rate>>,
+}
+
+impl StaticRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rate; the inverse is implied.
+    pub fn with_rate(self, base: Currency, quote: Currency, rate: &str) -> Result<Self, TreasuryError> {
+        self.set_rate(base, quote, rate)?;
+        Ok(self)
+    }
+
+    /// Replace a rate in place.
+    pub fn set_rate(&self, base: Currency, quote: Currency, rate: &str) -> Result<(), TreasuryError> {
+        let rate = Rate::parse(base, quote, rate, "static")?;
+        let mut rates = self.rates.write().unwrap_or_else(|e| e.into_inner());
+        rates.remove(&(quote, base));
+        rates.insert((base, quote), rate);
+        Ok(())
+    }
+}
+
+impl RateProvider for StaticRates {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn rate(&self, base: Currency, quote: Currency) -> Option<Rate> {
+        let rates = self.rates.read().unwrap_or_else(|e| e.into_inner());
+        let mut rate = lookup(&rates, base, quote, Currency::Usd)?;
+        rate.as_of = Utc::now();
+        Some(rate)
+    }
+}
+
+/// Rate feed response: `{"base": "USD", "rates": {"EUR": "0.92", "BTC": 0.0000165}}`.
+#[derive(Debug, Deserialize)]
+struct FeedResponse {
+    base: String,
+    rates: HashMap<String, serde_json::Value>,
+    /// Unix seconds the feed published the rates
+    #[serde(default)]
+    timestamp: Option<i64>,
+}
+
+/// Rates from a JSON feed over HTTP.
+///
+/// Call [`HttpRateOracle::refresh`] on a schedule; lookups read the last
+/// successful refresh, and stale rates are skipped by [`ExchangeRates`].
+pub struct HttpRateOracle {
+    url: String,
+    base: Currency,
+    client: reqwest::Client,
+    rates: RwLock<HashMap<(Currency, Currency), Rate>>,
+}
+
+impl HttpRateOracle {
+    /// Feed at `url` quoting rates from `base`.
+    pub fn new(url: impl Into<String>, base: Currency) -> Self {
+        Self { url: url.into(), base, client: reqwest::Client::new(), rates: RwLock::new(HashMap::new()) }
+    }
+
+    /// Fetch the feed and replace the cached rates. Returns how many were loaded.
+    pub async fn refresh(&self) -> Result<usize, TreasuryError> {
+        let source_error = |e: reqwest::Error| TreasuryError::RateSource { reason: format!("{}: {}", self.url, e) };
+        let feed: FeedResponse = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(source_error)?
+            .json()
+            .await
+            .map_err(source_error)?;
+
+        if feed.base.to_ascii_uppercase() != self.base.code() {
+            return Err(TreasuryError::RateSource {
+                reason: format!("feed base {} is not {}", feed.base, self.base.code()),
+            });
+        }
+        let as_of = feed.timestamp.and_then(|t| DateTime::from_timestamp(t, 0)).unwrap_or_else(Utc::now);
+
+        let mut rates = HashMap::new();
+        for (code, value) in feed.rates {
+            let Some(quote) = CURRENCIES.iter().copied().find(|c| c.code().eq_ignore_ascii_case(&code)) else {
+                continue;
+            };
+            let text = match &value {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => format!("{:.18}", n.as_f64().unwrap_or_default()),
+                _ => continue,
+            };
+            match Rate::parse(self.base, quote, &text, &self.url) {
+                Ok(mut rate) => {
+                    rate.as_of = as_of;
+                    rates.insert((self.base, quote), rate);
+                }
+                Err(_) => tracing::warn!(url = %self.url, currency = %code, rate = %text, "Skipping unparseable rate"),
+            }
+        }
+
+        let loaded = rates.len();
+        *self.rates.write().unwrap_or_else(|e| e.into_inner()) = rates;
+        Ok(loaded)
+    }
+}
+
+impl RateProvider for HttpRateOracle {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    fn rate(&self, base: Currency, quote: Currency) -> Option<Rate> {
+        lookup(&self.rates.read().unwrap_or_else(|e| e.into_inner()), base, quote, self.base)
+    }
+}
+
+/// Conversion limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FxPolicy {
+    /// Kept from the converted amount, in basis points
+    pub spread_bps: u32,
+    /// How far the live rate may move against a quote, in basis points
+    pub max_slippage_bps: u32,
+    /// Rates older than this are ignored
+    pub max_rate_age_secs: i64,
+}
+
+impl Default for FxPolicy {
+    fn default() -> Self {
+        Self { spread_bps: 30, max_slippage_bps: 50, max_rate_age_secs: 300 }
+    }
+}
+
+/// A priced conversion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FxQuote {
+    /// Debited from the payer
+    pub source: Money,
+    /// Credited to the payee, after the spread
+    pub target: Money,
+    /// Kept by the treasury, in the target currency
+    pub spread: Money,
+    pub rate: Rate,
+    pub quoted_at: DateTime<Utc>,
+}
+
+/// A completed cross-currency payment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FxPayment {
+    pub payment_id: String,
+    /// The conversion as executed at the live rate
+    pub executed: FxQuote,
+}
+
+/// Exchange rates from an ordered list of providers.
+#[derive(Clone, Default)]
+pub struct ExchangeRates {
+    providers: Vec<Arc<dyn RateProvider>>,
+    policy: FxPolicy,
+}
+
+impl ExchangeRates {
+    pub fn new(policy: FxPolicy) -> Self {
+        Self { providers: Vec::new(), policy }
+    }
+
+    /// Ask `provider` after the ones already added.
+    pub fn with_provider(mut self, provider: Arc<dyn RateProvider>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+
+    pub fn policy(&self) -> &FxPolicy {
+        &self.policy
+    }
+
+    /// First fresh rate from the providers.
+    pub fn rate(&self, base: Currency, quote: Currency) -> Result<Rate, TreasuryError> {
+        let oldest = Utc::now() - Duration::seconds(self.policy.max_rate_age_secs);
+        self.providers
+            .iter()
+            .filter_map(|p| p.rate(base, quote))
+            .find(|rate| rate.as_of >= oldest)
+            .ok_or(TreasuryError::RateUnavailable { from: base, to: quote })
+    }
+
+    /// Price converting `amount` into `target` at the current rate.
+    pub fn quote(&self, amount: Money, target: Currency) -> Result<FxQuote, TreasuryError> {
+        let rate = self.rate(amount.currency(), target)?;
+        self.quote_at(amount, rate)
+    }
+
+    fn quote_at(&self, amount: Money, rate: Rate) -> Result<FxQuote, TreasuryError> {
+        let gross = rate.convert(amount)?;
+        let spread = Money::from_units(
+            mul_div(gross.units(), self.policy.spread_bps as u128, BPS).unwrap_or_default(),
+            gross.currency(),
+        );
+        let target = gross.checked_sub(spread)?;
+        if target.is_zero() {
+            return Err(TreasuryError::InvalidAmount { amount: format!("{} converts to nothing", amount) });
+        }
+        Ok(FxQuote { source: amount, target, spread, rate, quoted_at: Utc::now() })
+    }
+
+    /// Re-price a quote at the live rate, failing if it slipped too far.
+    pub fn requote(&self, quoted: &FxQuote) -> Result<FxQuote, TreasuryError> {
+        let live = self.rate(quoted.rate.base, quoted.rate.quote)?;
+        if live.slipped_from(&quoted.rate, self.policy.max_slippage_bps) {
+            return Err(TreasuryError::SlippageExceeded {
+                quoted: quoted.rate.to_string(),
+                current: live.to_string(),
+                max_bps: self.policy.max_slippage_bps,
+            });
+        }
+        self.quote_at(quoted.source, live)
+    }
+}
+
+impl Treasury {
+    /// Convert cross-currency payments with these rates.
+    pub fn with_exchange_rates(mut self, rates: ExchangeRates) -> Self {
+        self.fx = rates;
+        self
+    }
+
+    pub fn exchange_rates(&self) -> &ExchangeRates {
+        &self.fx
+    }
+
+    /// Price paying `amount` to an agent that is paid in `target`.
+    pub fn quote_conversion(&self, amount: Money, target: Currency) -> Result<FxQuote, TreasuryError> {
+        self.fx.quote(amount, target)
+    }
+
+    /// Pay across currencies: debit the quote's source amount from
+    /// `from_agent` and credit the converted amount to `to_agent`, at the
+    /// live rate if it is within the slippage limit of the quote, the
+    /// payer's budget and the payee policy. Either both wallets change or neither does.
+    pub fn pay_with_conversion(
+        &mut self,
+        from_agent: &str,
+        to_agent: &str,
+        quote: &FxQuote,
+    ) -> Result<FxPayment, TreasuryError> {
+        self.budgets.check(from_agent, to_agent, quote.source, Utc::now())?;
+        self.check_payee(to_agent, quote.target)?;
+        let approval = self.approvals.approval_for(&TreasuryOperation::payment(from_agent, to_agent, quote.source))?;
+        let executed = self.fx.requote(quote)?;
+
+        // Check both sides before touching either wallet
+        let payee = self.wallet_mut(to_agent)?;
+        payee.balance(executed.target.currency()).checked_add(executed.target)?;
+        let debited = self.wallet_mut(from_agent)?.withdraw(executed.source);
+        self.events.on_shortfall(&self.tenant_id, from_agent, "payment", debited)?;
+        self.wallet_mut(to_agent)?.deposit(executed.target)?;
+
+        let payment = PaymentRequest::completed(from_agent, to_agent, executed.source)
+            .with_description(format!("FX {} -> {} at {}", executed.source, executed.target, executed.rate));
+        let payment_id = payment.id.clone();
+        self.pending_payments.push(payment);
+        self.ledger.post("fx_payment", Some(&payment_id), &[
+            (Account::Wallet(from_agent.to_string()), Account::Exchange, executed.source),
+            (Account::Exchange, Account::Wallet(to_agent.to_string()), executed.target),
+        ]);
+        self.approvals.consume(approval);
+        self.budgets.record(from_agent, to_agent, executed.source, Utc::now());
+        self.events.emit(&self.tenant_id, TreasuryEventKind::PaymentCompleted {
+            payment_id: payment_id.clone(),
+            from_agent: from_agent.to_string(),
+            to_agent: to_agent.to_string(),
+            amount: executed.source,
+        });
+
+        tracing::info!(
+            from = from_agent,
+            to = to_agent,
+            debited = %executed.source,
+            credited = %executed.target,
+            rate = %executed.rate,
+            "Cross-currency payment"
+        );
+        Ok(FxPayment { payment_id, executed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_handles_wide_products() {
+        assert_eq!(mul_div(6, 7, 4), Some(10));
+        // 10^30 * 10^20 / 10^25 overflows u128 in the middle
+        assert_eq!(mul_div(10_u128.pow(30), 10_u128.pow(20), 10_u128.pow(25)), Some(10_u128.pow(25)));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+    }
+
+    #[test]
+    fn test_rates_convert_exactly_across_decimals() {
+        let eth_usd = Rate::parse(Currency::Eth, Currency::Usd, "3000.5", "test").unwrap();
+        let thousand_eth = Money::parse("1000", Currency::Eth).unwrap();
+        assert_eq!(eth_usd.convert(thousand_eth).unwrap(), Money::parse("3000500", Currency::Usd).unwrap());
+
+        let usd_eth = eth_usd.inverse().unwrap();
+        let back = usd_eth.convert(Money::parse("3000.5", Currency::Usd).unwrap()).unwrap();
+        assert!(Money::parse("1", Currency::Eth).unwrap().units() - back.units() < 1_000);
+        assert_eq!(eth_usd.to_string(), "1 ETH = 3000.5 USD");
+
+        // Slippage is measured against the quoted rate
+        let worse = Rate::parse(Currency::Eth, Currency::Usd, "2990", "test").unwrap();
+        assert!(worse.slipped_from(&eth_usd, 30));
+        assert!(!worse.slipped_from(&eth_usd, 50));
+    }
+}
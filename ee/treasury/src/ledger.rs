@@ -0,0 +1,339 @@
+//! Double-Entry Ledger
+//!
+//! Every movement of funds in a [`Treasury`] is posted to its [`Ledger`] as
+//! balanced debit and credit lines: the account funds leave is debited and
+//! the account they arrive in is credited, for the same amount. Besides
+//! agent wallets, funds sit in transfer holds, channels and escrows, enter
+//! through deposits (the external account) and change currency through the
+//! exchange account. An account holds its credits less its debits.
+//!
+//! [`Treasury::reconcile`] proves funds conservation for auditors: every
+//! transaction must balance per currency, and every wallet, hold, channel
+//! and escrow must hold exactly what its ledger account says. Anything else
+//! is reported as drift. The ledger exports as CSV or JSON for the audit
+//! export.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let report = treasury.reconcile();
+//! if !report.is_balanced() {
+//!     tracing::error!(drift = ?report.drift, unbalanced = ?report.unbalanced, "Treasury books do not reconcile");
+//! }
+//! std::fs::write("ledger.csv", treasury.ledger().to_csv())?;
+//!
+//! // After a restart, restore the ledger alongside the wallets
+//! let treasury = Treasury::new("org-1")?.with_ledger(Ledger::from_json(&saved)?);
+//! ```
+
+use crate::{Currency, EscrowStatus, Money, Treasury};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// A ledger account.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum Account {
+    /// Outside the treasury; debited by deposits
+    External,
+    Wallet(String),
+    /// Funds held by prepared transfers
+    TransferHolds,
+    Channel(String),
+    Escrow(String),
+    /// Currency conversion; keeps the spread
+    Exchange,
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::External => write!(f, "external"),
+            Self::Wallet(agent_id) => write!(f, "wallet:{agent_id}"),
+            Self::TransferHolds => write!(f, "transfer_holds"),
+            Self::Channel(channel_id) => write!(f, "channel:{channel_id}"),
+            Self::Escrow(escrow_id) => write!(f, "escrow:{escrow_id}"),
+            Self::Exchange => write!(f, "exchange"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Debit,
+    Credit,
+}
+
+/// One line of a ledger transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerLine {
+    /// Shared by the lines of one transaction
+    pub transaction_id: String,
+    pub at: DateTime<Utc>,
+    /// What moved the funds, e.g. `deposit` or `escrow_release`
+    pub memo: String,
+    /// Payment, transfer, channel or escrow the transaction belongs to
+    pub reference: Option<String>,
+    pub account: Account,
+    pub side: Side,
+    pub amount: Money,
+}
+
+/// Debits and credits of an account in one currency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub debits: u128,
+    pub credits: u128,
+}
+
+impl AccountBalance {
+    /// Credits less debits; `None` if the account is overdrawn.
+    pub fn held(&self) -> Option<u128> {
+        self.credits.checked_sub(self.debits)
+    }
+
+    fn add(&mut self, side: Side, units: u128) {
+        match side {
+            Side::Debit => self.debits = self.debits.saturating_add(units),
+            Side::Credit => self.credits = self.credits.saturating_add(units),
+        }
+    }
+}
+
+/// Append-only double-entry ledger.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ledger {
+    lines: Vec<LedgerLine>,
+}
+
+impl Ledger {
+    /// Lines, oldest first.
+    pub fn lines(&self) -> &[LedgerLine] {
+        &self.lines
+    }
+
+    /// Balance of one account.
+    pub fn balance(&self, account: &Account, currency: Currency) -> AccountBalance {
+        let mut balance = AccountBalance::default();
+        for line in self.lines.iter().filter(|l| &l.account == account && l.amount.currency() == currency) {
+            balance.add(line.side, line.amount.units());
+        }
+        balance
+    }
+
+    /// Balance of every account, by account and currency code.
+    pub fn trial_balance(&self) -> BTreeMap<(Account, &'static str), AccountBalance> {
+        let mut balances: BTreeMap<_, AccountBalance> = BTreeMap::new();
+        for line in &self.lines {
+            balances
+                .entry((line.account.clone(), line.amount.currency().code()))
+                .or_default()
+                .add(line.side, line.amount.units());
+        }
+        balances
+    }
+
+    /// Transactions whose debits and credits differ in some currency.
+    pub fn unbalanced(&self) -> Vec<String> {
+        let mut totals: HashMap<(&str, Currency), AccountBalance> = HashMap::new();
+        for line in &self.lines {
+            totals.entry((&line.transaction_id, line.amount.currency())).or_default().add(line.side, line.amount.units());
+        }
+        let mut unbalanced: Vec<String> = totals
+            .into_iter()
+            .filter(|(_, total)| total.debits != total.credits)
+            .map(|((transaction_id, _), _)| transaction_id.to_string())
+            .collect();
+        unbalanced.sort();
+        unbalanced.dedup();
+        unbalanced
+    }
+
+    /// CSV with a header row; amounts in base units.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("transaction_id,at,memo,reference,account,side,currency,units\n");
+        for line in &self.lines {
+            let fields = [
+                csv_field(&line.transaction_id),
+                line.at.to_rfc3339(),
+                csv_field(&line.memo),
+                csv_field(line.reference.as_deref().unwrap_or_default()),
+                csv_field(&line.account.to_string()),
+                match line.side {
+                    Side::Debit => "debit".to_string(),
+                    Side::Credit => "credit".to_string(),
+                },
+                line.amount.currency().code().to_string(),
+                line.amount.units().to_string(),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Post one transaction of `(debit, credit, amount)` legs. Zero legs
+    /// are left out.
+    pub(crate) fn post(&mut self, memo: &str, reference: Option<&str>, legs: &[(Account, Account, Money)]) {
+        let transaction_id = uuid::Uuid::new_v4().to_string();
+        let at = Utc::now();
+        for (debit, credit, amount) in legs.iter().filter(|(_, _, amount)| !amount.is_zero()) {
+            for (account, side) in [(debit, Side::Debit), (credit, Side::Credit)] {
+                self.lines.push(LedgerLine {
+                    transaction_id: transaction_id.clone(),
+                    at,
+                    memo: memo.to_string(),
+                    reference: reference.map(str::to_string),
+                    account: account.clone(),
+                    side,
+                    amount: *amount,
+                });
+            }
+        }
+    }
+}
+
+impl Ledger {
+    /// Check the ledger balances and matches `holdings`.
+    pub(crate) fn reconcile(&self, tenant_id: &str, holdings: Holdings) -> Reconciliation {
+        let mut balances: HashMap<(Account, Currency), AccountBalance> = HashMap::new();
+        for line in &self.lines {
+            balances.entry((line.account.clone(), line.amount.currency())).or_default().add(line.side, line.amount.units());
+        }
+
+        let actual = holdings.0;
+        let mut keys: Vec<(Account, Currency)> = balances.keys().chain(actual.keys()).cloned().collect();
+        keys.sort_by(|(a, x), (b, y)| a.cmp(b).then(x.code().cmp(y.code())));
+        keys.dedup();
+        let drift: Vec<Drift> = keys
+            .into_iter()
+            .filter(|(account, _)| !matches!(account, Account::External | Account::Exchange))
+            .filter_map(|key| {
+                let ledger = balances.get(&key).copied().unwrap_or_default();
+                let units = actual.get(&key).copied().unwrap_or_default();
+                (ledger.held() != Some(units)).then(|| Drift { actual: Money::from_units(units, key.1), account: key.0, ledger })
+            })
+            .collect();
+
+        let report = Reconciliation {
+            tenant_id: tenant_id.to_string(),
+            generated_at: Utc::now(),
+            lines: self.lines.len(),
+            unbalanced: self.unbalanced(),
+            drift,
+        };
+        if !report.is_balanced() {
+            tracing::error!(
+                tenant = %tenant_id,
+                unbalanced = report.unbalanced.len(),
+                drift = report.drift.len(),
+                "Treasury ledger does not reconcile"
+            );
+        }
+        report
+    }
+}
+
+/// What each account holds in the treasury, per currency.
+#[derive(Debug, Default)]
+pub(crate) struct Holdings(HashMap<(Account, Currency), u128>);
+
+impl Holdings {
+    pub(crate) fn hold(&mut self, account: Account, currency: Currency, units: u128) {
+        let held = self.0.entry((account, currency)).or_default();
+        *held = held.saturating_add(units);
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// An account holding something other than its ledger balance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    pub account: Account,
+    /// What the ledger says
+    pub ledger: AccountBalance,
+    /// What the treasury holds
+    pub actual: Money,
+}
+
+/// Outcome of [`Treasury::reconcile`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reconciliation {
+    pub tenant_id: String,
+    pub generated_at: DateTime<Utc>,
+    /// Ledger lines checked
+    pub lines: usize,
+    /// Transactions whose debits and credits differ
+    pub unbalanced: Vec<String>,
+    pub drift: Vec<Drift>,
+}
+
+impl Reconciliation {
+    /// Whether the books balance and match the treasury.
+    pub fn is_balanced(&self) -> bool {
+        self.unbalanced.is_empty() && self.drift.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl Treasury {
+    /// Continue `ledger`, e.g. one exported before a restart. Call
+    /// [`Treasury::reconcile`] once wallets are restored to check they match.
+    pub fn with_ledger(mut self, ledger: Ledger) -> Self {
+        self.ledger = ledger;
+        self
+    }
+
+    /// The double-entry ledger.
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Check the ledger balances and matches every wallet, transfer hold,
+    /// channel and escrow.
+    pub fn reconcile(&self) -> Reconciliation {
+        let mut holdings = Holdings::default();
+        for (agent_id, wallet) in &self.wallets {
+            for (currency, units) in &wallet.balances {
+                holdings.hold(Account::Wallet(agent_id.clone()), *currency, *units);
+            }
+        }
+        for transfer in self.transfers.held_amounts() {
+            holdings.hold(Account::TransferHolds, transfer.currency(), transfer.units());
+        }
+        for (channel_id, channel) in self.channels.iter().filter(|(_, c)| c.is_open) {
+            holdings.hold(Account::Channel(channel_id.clone()), channel.currency, channel.capacity);
+        }
+        for (escrow_id, escrow) in self.escrows.iter().filter(|(_, e)| e.status == EscrowStatus::Locked) {
+            holdings.hold(Account::Escrow(escrow_id.clone()), escrow.amount.currency(), escrow.remaining().units());
+        }
+        self.ledger.reconcile(&self.tenant_id, holdings)
+    }
+
+    /// Post a movement between two accounts.
+    pub(crate) fn post(&mut self, memo: &str, reference: Option<&str>, debit: Account, credit: Account, amount: Money) {
+        self.ledger.post(memo, reference, &[(debit, credit, amount)]);
+    }
+}
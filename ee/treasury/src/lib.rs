@@ -7,24 +7,84 @@
 //! Features:
 //! - Agent-to-Agent micropayments
 //! - L402 Protocol integration (HTTP 402 Payment Required)
-//! - Multi-currency support (fiat, crypto, stablecoins)
-//! - Payment channels and escrow
+//! - Multi-currency support (fiat, crypto, stablecoins) with integer
+//!   [`Money`] amounts
+//! - Payment channels and escrow, with [`Milestone`]s released as work
+//!   completes
+//! - Payment and escrow statuses that only move along legal transitions,
+//!   each recorded with its actor and time
+//! - Signed off-ledger channel states with contestable closes, guarded by
+//!   a [`Watchtower`] while a party is offline, and force-closes on the
+//!   latest checkpointed state when the other party stops cooperating
+//! - Threshold signatures (t-of-n signers) for high-value escrow releases
+//!   and payments
+//! - Async [`SharedTreasury`] with per-wallet, escrow and channel locks for
+//!   concurrent callers
+//! - Two-phase transfers with idempotency keys and a journal replayed on
+//!   startup
+//! - Cross-currency payments through [`ExchangeRates`] with a spread and
+//!   slippage limits
+//! - Per-agent daily, monthly, counterparty and velocity limits, with
+//!   human-approved [`BudgetOverride`]s
+//! - Payee deny lists and named [`AddressBook`]s, with escalated approval
+//!   for payees above a threshold
+//! - Double-entry [`Ledger`] of every movement of funds, reconciled against
+//!   wallet, channel and escrow balances for audits
+//! - Platform fees ([`FeePolicy`]: flat, percentage or tiered by volume)
+//!   on payments and escrow releases, credited to a fee wallet
+//! - [`TreasuryEvents`] broadcast of payments, channels, escrow releases and
+//!   shortfalls, with signed, retried webhooks
 //! - Real-time settlement
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use agentkern_treasury::{Treasury, PaymentRequest};
+//! use agentkern_treasury::{Currency, Money, Treasury};
 //!
-//! let treasury = Treasury::new("org-123")?;
-//! treasury.pay_agent("agent-A", "agent-B", 0.001)?;
+//! let mut treasury = Treasury::new("org-123")?;
+//! treasury.pay("agent-A", "agent-B", Money::parse("0.001", Currency::Credits)?)?;
 //! ```
 
+use agentkern_fsm::{IllegalTransition, State, TransitionLog, SYSTEM_ACTOR};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use thiserror::Error;
 
+pub mod budget;
+pub mod events;
+pub mod fees;
+pub mod fx;
+pub mod ledger;
+pub mod milestones;
+pub mod money;
+pub mod payees;
+pub mod shared;
+pub mod threshold;
+pub mod transfer;
+pub mod watchtower;
+
+pub use threshold::{
+    KeyCeremony, OperationKind, SignerSet, SigningAuditRecord, SigningEvent, SigningSession, TreasuryOperation,
+};
+pub use budget::{AgentBudget, BudgetOverride, LimitPeriod, SpendingLimit, VelocityLimit};
+pub use fees::{FeeBreakdown, FeePolicy, FeeSchedule, FeeTier};
+pub use events::{RetryPolicy, TreasuryEvent, TreasuryEventKind, TreasuryEvents, WebhookDispatcher, WebhookEndpoint};
+pub use fx::{ExchangeRates, FxPayment, FxPolicy, FxQuote, HttpRateOracle, Rate, RateProvider, StaticRates};
+pub use ledger::{Account, AccountBalance, Drift, Ledger, LedgerLine, Reconciliation, Side};
+pub use milestones::Milestone;
+pub use money::Money;
+pub use payees::{AddressBook, Payee, PayeeEscalation, PayeeMode, PayeePolicy, PayeeRequest, PayeeRequestStatus};
+pub use shared::{BlockingTreasury, SharedTreasury};
+pub use transfer::{JournalEntry, PreparedTransfer, Recovery, TransferJournal, TransferRequest};
+pub use watchtower::{Contested, JusticeBlob, Watchtower};
+use budget::Budgets;
+use fees::Fees;
+use payees::Payees;
+use threshold::Approvals;
+use transfer::Transfers;
+
 mod license {
     #[derive(Debug, thiserror::Error)]
     pub enum LicenseError {
@@ -49,17 +109,59 @@ mod license {
 #[derive(Debug, Error)]
 pub enum TreasuryError {
     #[error("Insufficient balance: required {required}, available {available}")]
-    InsufficientBalance { required: f64, available: f64 },
+    InsufficientBalance { required: Money, available: Money },
     #[error("Agent not found: {agent_id}")]
     AgentNotFound { agent_id: String },
     #[error("Payment failed: {reason}")]
     PaymentFailed { reason: String },
     #[error("Invalid amount: {amount}")]
-    InvalidAmount { amount: f64 },
+    InvalidAmount { amount: String },
+    #[error("Currency mismatch: expected {expected:?}, found {found:?}")]
+    CurrencyMismatch { expected: Currency, found: Currency },
     #[error("Channel not open")]
     ChannelNotOpen,
+    #[error("Invalid channel state: {reason}")]
+    InvalidChannelState { reason: String },
+    #[error("Channel close contestable until {until}")]
+    DisputePeriodActive { until: DateTime<Utc> },
+    #[error("No close pending for channel")]
+    NoPendingClose,
     #[error("Payment expired")]
     PaymentExpired,
+    #[error("Threshold signatures required: {collected} of {required} collected")]
+    SignaturesRequired { required: usize, collected: usize },
+    #[error("Invalid signer {signer_id}: {reason}")]
+    InvalidSigner { signer_id: String, reason: String },
+    #[error("Signing session not found or expired: {session_id}")]
+    SigningSessionNotFound { session_id: String },
+    #[error("Transfer not found or already resolved: {transfer_id}")]
+    TransferNotFound { transfer_id: String },
+    #[error("Transfer journal failed: {reason}")]
+    Journal { reason: String },
+    #[error("No exchange rate from {from:?} to {to:?}")]
+    RateUnavailable { from: Currency, to: Currency },
+    #[error("Exchange rate slipped from {quoted} to {current}, more than {max_bps} bps")]
+    SlippageExceeded { quoted: String, current: String, max_bps: u32 },
+    #[error("Rate source failed: {reason}")]
+    RateSource { reason: String },
+    #[error("{limit} of {agent_id} exceeded: {spent} already spent, {requested} requested")]
+    BudgetExceeded { agent_id: String, limit: String, spent: Money, requested: Money },
+    #[error("Payment velocity of {agent_id} exceeded: at most {max_payments} payments per {window_secs}s")]
+    VelocityExceeded { agent_id: String, max_payments: usize, window_secs: i64 },
+    #[error("Budget override rejected: {reason}")]
+    OverrideRejected { reason: String },
+    #[error("Payee {payee} not allowed: {reason}")]
+    PayeeNotAllowed { payee: String, reason: String },
+    #[error("Payee request not found or already decided: {request_id}")]
+    PayeeRequestNotFound { request_id: String },
+    #[error("Webhook {url} failed after {attempts} attempts: {reason}")]
+    WebhookFailed { url: String, attempts: u32, reason: String },
+    #[error("Invalid milestone: {reason}")]
+    InvalidMilestone { reason: String },
+    #[error("Invalid fee policy: {reason}")]
+    InvalidFeePolicy { reason: String },
+    #[error(transparent)]
+    IllegalTransition(#[from] IllegalTransition),
 }
 
 /// Supported currencies.
@@ -96,16 +198,23 @@ impl Currency {
         }
     }
 
-    /// Convert to base units.
-    pub fn to_base_units(&self, amount: f64) -> u64 {
-        let multiplier = 10_u64.pow(self.decimals() as u32);
-        (amount * multiplier as f64) as u64
+    /// Base units in one whole unit, e.g. 100 cents per dollar.
+    pub fn scale(&self) -> u128 {
+        10_u128.pow(self.decimals() as u32)
     }
 
-    /// Convert from base units.
-    pub fn from_base_units(&self, units: u64) -> f64 {
-        let multiplier = 10_u64.pow(self.decimals() as u32);
-        units as f64 / multiplier as f64
+    /// Ticker code, e.g. `USD`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Usd => "USD",
+            Self::Eur => "EUR",
+            Self::Btc => "BTC",
+            Self::Sats => "SATS",
+            Self::Eth => "ETH",
+            Self::Usdc => "USDC",
+            Self::Usdt => "USDT",
+            Self::Credits => "CREDITS",
+        }
     }
 }
 
@@ -120,8 +229,8 @@ impl Default for Currency {
 pub struct AgentWallet {
     /// Agent ID
     pub agent_id: String,
-    /// Balances by currency
-    pub balances: HashMap<Currency, u64>,
+    /// Balances by currency, in base units
+    pub balances: HashMap<Currency, u128>,
     /// Pending incoming payments
     pub pending_incoming: u64,
     /// Pending outgoing payments
@@ -147,27 +256,42 @@ impl AgentWallet {
     }
 
     /// Get balance for a currency.
-    pub fn balance(&self, currency: Currency) -> f64 {
-        let units = self.balances.get(&currency).copied().unwrap_or(0);
-        currency.from_base_units(units)
+    pub fn balance(&self, currency: Currency) -> Money {
+        Money::from_units(self.balances.get(&currency).copied().unwrap_or(0), currency)
     }
 
     /// Deposit funds.
-    pub fn deposit(&mut self, currency: Currency, amount: f64) {
-        let units = currency.to_base_units(amount);
-        *self.balances.entry(currency).or_insert(0) += units;
+    pub fn deposit(&mut self, amount: Money) -> Result<(), TreasuryError> {
+        self.credit_units(amount.currency(), amount.units())
+    }
+
+    /// Credit base units directly.
+    pub fn credit_units(&mut self, currency: Currency, units: u128) -> Result<(), TreasuryError> {
+        let balance = self.balances.entry(currency).or_insert(0);
+        *balance = balance.checked_add(units).ok_or_else(|| TreasuryError::InvalidAmount {
+            amount: Money::from_units(units, currency).to_string(),
+        })?;
         self.last_activity = Utc::now();
+        Ok(())
+    }
+
+    /// Whether `units` more would fit in the balance.
+    fn can_credit(&self, currency: Currency, units: u128) -> bool {
+        self.balances.get(&currency).copied().unwrap_or(0).checked_add(units).is_some()
     }
 
     /// Withdraw funds.
-    pub fn withdraw(&mut self, currency: Currency, amount: f64) -> Result<(), TreasuryError> {
-        let units = currency.to_base_units(amount);
+    pub fn withdraw(&mut self, amount: Money) -> Result<(), TreasuryError> {
+        self.withdraw_units(amount.currency(), amount.units())
+    }
+
+    fn withdraw_units(&mut self, currency: Currency, units: u128) -> Result<(), TreasuryError> {
         let balance = self.balances.entry(currency).or_insert(0);
         
         if *balance < units {
             return Err(TreasuryError::InsufficientBalance {
-                required: amount,
-                available: currency.from_base_units(*balance),
+                required: Money::from_units(units, currency),
+                available: Money::from_units(*balance, currency),
             });
         }
         
@@ -187,9 +311,7 @@ pub struct PaymentRequest {
     /// To agent
     pub to_agent: String,
     /// Amount
-    pub amount: f64,
-    /// Currency
-    pub currency: Currency,
+    pub amount: Money,
     /// Description
     pub description: Option<String>,
     /// Expires at
@@ -200,6 +322,12 @@ pub struct PaymentRequest {
     pub invoice: Option<String>,
     /// Status
     pub status: PaymentStatus,
+    /// Status transitions
+    #[serde(default)]
+    pub history: TransitionLog<PaymentStatus>,
+    /// Platform fee taken, if a fee schedule applied
+    #[serde(default)]
+    pub fee: Option<FeeBreakdown>,
     /// Created at
     pub created_at: DateTime<Utc>,
 }
@@ -209,8 +337,7 @@ impl PaymentRequest {
     pub fn new(
         from_agent: impl Into<String>,
         to_agent: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        amount: Money,
     ) -> Self {
         let now = Utc::now();
         Self {
@@ -218,16 +345,29 @@ impl PaymentRequest {
             from_agent: from_agent.into(),
             to_agent: to_agent.into(),
             amount,
-            currency,
             description: None,
             expires_at: now + chrono::Duration::minutes(10),
             macaroon: None,
             invoice: None,
             status: PaymentStatus::Pending,
+            history: TransitionLog::default(),
+            fee: None,
             created_at: now,
         }
     }
 
+    /// A payment already made, completed by its payer.
+    pub(crate) fn completed(from_agent: &str, to_agent: &str, amount: Money) -> Self {
+        let mut payment = Self::new(from_agent, to_agent, amount);
+        payment.transition(PaymentStatus::Completed, from_agent).expect("pending payments can complete");
+        payment
+    }
+
+    /// Move to `to`, recording `actor`. Fails if the transition is illegal.
+    pub fn transition(&mut self, to: PaymentStatus, actor: &str) -> Result<(), TreasuryError> {
+        Ok(self.history.apply(&mut self.status, to, actor)?)
+    }
+
     /// Set description.
     pub fn with_description(mut self, desc: impl Into<String>) -> Self {
         self.description = Some(desc.into());
@@ -238,7 +378,7 @@ impl PaymentRequest {
     pub fn generate_invoice(&mut self) -> String {
         // Generate payment hash (simulated)
         let hash = format!("lnbc{}u1p{}", 
-            (self.amount * 100.0) as u64,
+            self.amount.units(),
             &self.id[..8]
         );
         self.invoice = Some(hash.clone());
@@ -269,6 +409,18 @@ pub enum PaymentStatus {
     Refunded,
 }
 
+impl State for PaymentStatus {
+    const MACHINE: &'static str = "payment";
+
+    fn can_transition_to(&self, to: &Self) -> bool {
+        use PaymentStatus::*;
+        matches!(
+            (self, to),
+            (Pending, Processing | Completed | Failed | Expired) | (Processing, Completed | Failed) | (Completed, Refunded)
+        )
+    }
+}
+
 /// Payment channel for high-frequency micropayments.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentChannel {
@@ -278,12 +430,12 @@ pub struct PaymentChannel {
     pub party_a: String,
     /// Party B
     pub party_b: String,
-    /// Total capacity
-    pub capacity: u64,
+    /// Total capacity, in base units
+    pub capacity: u128,
     /// Balance of party A
-    pub balance_a: u64,
+    pub balance_a: u128,
     /// Balance of party B
-    pub balance_b: u64,
+    pub balance_b: u128,
     /// Currency
     pub currency: Currency,
     /// Is open
@@ -292,6 +444,83 @@ pub struct PaymentChannel {
     pub tx_count: u64,
     /// Created at
     pub created_at: DateTime<Utc>,
+    /// Parties' keys; set for channels updated off-ledger with signed states
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<ChannelKeys>,
+    /// How long a unilateral close can be contested, in seconds
+    #[serde(default)]
+    pub dispute_period_secs: i64,
+    /// Unilateral close waiting out its dispute period
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_close: Option<PendingClose>,
+    /// Newest signed state checkpointed with the treasury
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_state: Option<SignedChannelState>,
+}
+
+/// Channel balance at one point, in base units.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelState {
+    pub channel_id: String,
+    /// Increases with every update; a higher state revokes all lower ones
+    pub sequence: u64,
+    pub balance_a: u128,
+    pub balance_b: u128,
+}
+
+impl ChannelState {
+    /// Bytes both parties sign.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "agentkern-channel-state/v1:{}:{}:{}:{}",
+            self.channel_id, self.sequence, self.balance_a, self.balance_b
+        )
+        .into_bytes()
+    }
+
+    /// Sign as one of the parties.
+    pub fn sign(&self, key: &SigningKey) -> Vec<u8> {
+        key.sign(&self.signing_bytes()).to_bytes().to_vec()
+    }
+}
+
+/// A channel state signed by both parties.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedChannelState {
+    pub state: ChannelState,
+    pub signature_a: Vec<u8>,
+    pub signature_b: Vec<u8>,
+}
+
+/// Ed25519 public keys of a channel's parties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelKeys {
+    pub party_a: [u8; 32],
+    pub party_b: [u8; 32],
+}
+
+impl ChannelKeys {
+    /// Check both parties signed the state.
+    pub fn verify(&self, signed: &SignedChannelState) -> Result<(), TreasuryError> {
+        let message = signed.state.signing_bytes();
+        for (key, signature) in [(&self.party_a, &signed.signature_a), (&self.party_b, &signed.signature_b)] {
+            let invalid = || TreasuryError::InvalidChannelState { reason: "bad signature".to_string() };
+            let key = VerifyingKey::from_bytes(key).map_err(|_| invalid())?;
+            let signature = Signature::from_slice(signature).map_err(|_| invalid())?;
+            key.verify(&message, &signature).map_err(|_| invalid())?;
+        }
+        Ok(())
+    }
+}
+
+/// A unilateral close in its dispute period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingClose {
+    /// State the channel will settle on unless contested
+    pub state: SignedChannelState,
+    /// Party that requested the close
+    pub closer: String,
+    pub contest_until: DateTime<Utc>,
 }
 
 impl PaymentChannel {
@@ -299,75 +528,92 @@ impl PaymentChannel {
     pub fn new(
         party_a: impl Into<String>,
         party_b: impl Into<String>,
-        capacity: f64,
-        currency: Currency,
+        capacity: Money,
     ) -> Self {
-        let capacity_units = currency.to_base_units(capacity);
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             party_a: party_a.into(),
             party_b: party_b.into(),
-            capacity: capacity_units,
-            balance_a: capacity_units,
+            capacity: capacity.units(),
+            balance_a: capacity.units(),
             balance_b: 0,
-            currency,
+            currency: capacity.currency(),
             is_open: true,
             tx_count: 0,
             created_at: Utc::now(),
+            keys: None,
+            dispute_period_secs: 0,
+            pending_close: None,
+            latest_state: None,
+        }
+    }
+
+    /// Update the channel off-ledger with signed states instead of
+    /// [`transfer_a_to_b`](Self::transfer_a_to_b) / [`transfer_b_to_a`](Self::transfer_b_to_a).
+    pub fn with_keys(mut self, keys: ChannelKeys, dispute_period: chrono::Duration) -> Self {
+        self.keys = Some(keys);
+        self.dispute_period_secs = dispute_period.num_seconds();
+        self
+    }
+
+    /// State both parties sign before any off-ledger update.
+    pub fn opening_state(&self) -> ChannelState {
+        ChannelState { channel_id: self.id.clone(), sequence: 0, balance_a: self.capacity, balance_b: 0 }
+    }
+
+    /// Check a state is signed by both parties and accounts for the whole capacity.
+    pub fn verify_state(&self, signed: &SignedChannelState) -> Result<(), TreasuryError> {
+        let keys = self.keys.as_ref().ok_or_else(|| TreasuryError::InvalidChannelState {
+            reason: "channel has no signing keys".to_string(),
+        })?;
+        let state = &signed.state;
+        if state.channel_id != self.id {
+            return Err(TreasuryError::InvalidChannelState { reason: "state is for another channel".to_string() });
+        }
+        if state.balance_a.checked_add(state.balance_b) != Some(self.capacity) {
+            return Err(TreasuryError::InvalidChannelState { reason: "balances do not match capacity".to_string() });
         }
+        keys.verify(signed)
     }
 
     /// Transfer from A to B.
-    pub fn transfer_a_to_b(&mut self, amount: f64) -> Result<(), TreasuryError> {
+    pub fn transfer_a_to_b(&mut self, amount: Money) -> Result<(), TreasuryError> {
         if !self.is_open {
             return Err(TreasuryError::ChannelNotOpen);
         }
-        
-        let units = self.currency.to_base_units(amount);
-        
-        if self.balance_a < units {
-            return Err(TreasuryError::InsufficientBalance {
-                required: amount,
-                available: self.currency.from_base_units(self.balance_a),
-            });
+        if self.keys.is_some() {
+            return Err(TreasuryError::InvalidChannelState { reason: "update signed channels off-ledger".to_string() });
         }
         
-        self.balance_a -= units;
-        self.balance_b += units;
+        let balance = Money::from_units(self.balance_a, self.currency);
+        self.balance_a = balance.checked_sub(amount)?.units();
+        self.balance_b += amount.units();
         self.tx_count += 1;
         
         Ok(())
     }
 
     /// Transfer from B to A.
-    pub fn transfer_b_to_a(&mut self, amount: f64) -> Result<(), TreasuryError> {
+    pub fn transfer_b_to_a(&mut self, amount: Money) -> Result<(), TreasuryError> {
         if !self.is_open {
             return Err(TreasuryError::ChannelNotOpen);
         }
-        
-        let units = self.currency.to_base_units(amount);
-        
-        if self.balance_b < units {
-            return Err(TreasuryError::InsufficientBalance {
-                required: amount,
-                available: self.currency.from_base_units(self.balance_b),
-            });
+        if self.keys.is_some() {
+            return Err(TreasuryError::InvalidChannelState { reason: "update signed channels off-ledger".to_string() });
         }
         
-        self.balance_b -= units;
-        self.balance_a += units;
+        let balance = Money::from_units(self.balance_b, self.currency);
+        self.balance_b = balance.checked_sub(amount)?.units();
+        self.balance_a += amount.units();
         self.tx_count += 1;
         
         Ok(())
     }
 
     /// Close the channel and settle.
-    pub fn close(&mut self) -> (f64, f64) {
+    pub fn close(&mut self) -> (Money, Money) {
         self.is_open = false;
-        (
-            self.currency.from_base_units(self.balance_a),
-            self.currency.from_base_units(self.balance_b),
-        )
+        (Money::from_units(self.balance_a, self.currency), Money::from_units(self.balance_b, self.currency))
     }
 }
 
@@ -381,9 +627,7 @@ pub struct Escrow {
     /// To agent
     pub to_agent: String,
     /// Amount held
-    pub amount: f64,
-    /// Currency
-    pub currency: Currency,
+    pub amount: Money,
     /// Release condition (serialized)
     pub condition: String,
     /// Status
@@ -392,6 +636,15 @@ pub struct Escrow {
     pub created_at: DateTime<Utc>,
     /// Expires at
     pub expires_at: DateTime<Utc>,
+    /// Staged payouts; empty if the escrow releases all at once
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    /// Status transitions
+    #[serde(default)]
+    pub history: TransitionLog<EscrowStatus>,
+    /// Platform fees taken from each release, oldest first
+    #[serde(default)]
+    pub fees: Vec<FeeBreakdown>,
 }
 
 /// Escrow status.
@@ -408,13 +661,21 @@ pub enum EscrowStatus {
     Expired,
 }
 
+impl State for EscrowStatus {
+    const MACHINE: &'static str = "escrow";
+
+    fn can_transition_to(&self, to: &Self) -> bool {
+        use EscrowStatus::*;
+        matches!((self, to), (Locked, Released | Refunded | Expired))
+    }
+}
+
 impl Escrow {
     /// Create a new escrow.
     pub fn new(
         from_agent: impl Into<String>,
         to_agent: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        amount: Money,
         condition: impl Into<String>,
         duration_hours: i64,
     ) -> Self {
@@ -424,36 +685,92 @@ impl Escrow {
             from_agent: from_agent.into(),
             to_agent: to_agent.into(),
             amount,
-            currency,
             condition: condition.into(),
             status: EscrowStatus::Locked,
             created_at: now,
             expires_at: now + chrono::Duration::hours(duration_hours),
+            milestones: Vec::new(),
+            history: TransitionLog::default(),
+            fees: Vec::new(),
         }
     }
 
-    /// Release funds to recipient.
-    pub fn release(&mut self) -> Result<f64, TreasuryError> {
-        if self.status != EscrowStatus::Locked {
-            return Err(TreasuryError::PaymentFailed {
-                reason: "Escrow not locked".to_string(),
-            });
-        }
-        
-        self.status = EscrowStatus::Released;
-        Ok(self.amount)
+    /// Move to `to`, recording `actor`. Fails if the transition is illegal.
+    pub fn transition(&mut self, to: EscrowStatus, actor: &str) -> Result<(), TreasuryError> {
+        Ok(self.history.apply(&mut self.status, to, actor)?)
     }
 
-    /// Refund to sender.
-    pub fn refund(&mut self) -> Result<f64, TreasuryError> {
-        if self.status != EscrowStatus::Locked {
-            return Err(TreasuryError::PaymentFailed {
-                reason: "Escrow not locked".to_string(),
-            });
+    /// Release what is still held to recipient.
+    pub fn release(&mut self) -> Result<Money, TreasuryError> {
+        self.transition(EscrowStatus::Released, SYSTEM_ACTOR)?;
+        Ok(self.remaining())
+    }
+
+    /// Refund what is still held to sender.
+    pub fn refund(&mut self) -> Result<Money, TreasuryError> {
+        self.transition(EscrowStatus::Refunded, SYSTEM_ACTOR)?;
+        Ok(self.remaining())
+    }
+
+    /// Pay milestone `milestone`'s share, or all that is still held, to the
+    /// recipient less any fee, which moves to the fee wallet. Everything
+    /// that can fail is checked before funds move. Returns the amount paid
+    /// out and the fee taken.
+    pub(crate) fn pay_out(
+        &mut self,
+        milestone: Option<&str>,
+        wallets: &mut impl WalletAccess,
+        fees: &mut Fees,
+    ) -> Result<(Money, Option<FeeBreakdown>), TreasuryError> {
+        let amount = match milestone {
+            Some(name) => self.milestone_share(name)?,
+            None => {
+                self.status.check_transition(&EscrowStatus::Released)?;
+                self.remaining()
+            }
+        };
+        let fee = fees.quote(&self.to_agent, amount);
+        if !wallets.wallet(&self.to_agent)?.can_credit(amount.currency(), amount.units()) {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
         }
-        
-        self.status = EscrowStatus::Refunded;
-        Ok(self.amount)
+        if let Some(fee) = &fee
+            && !wallets.wallet(&fee.fee_wallet)?.can_credit(fee.fee.currency(), fee.fee.units())
+        {
+            return Err(TreasuryError::InvalidAmount { amount: fee.fee.to_string() });
+        }
+
+        match milestone {
+            Some(name) => {
+                let milestone = self.milestones.iter_mut().find(|m| m.name == name).expect("milestone checked above");
+                milestone.released = Some(amount);
+                if self.milestones.iter().all(Milestone::is_released) {
+                    self.transition(EscrowStatus::Released, SYSTEM_ACTOR)?;
+                }
+            }
+            None => self.transition(EscrowStatus::Released, SYSTEM_ACTOR)?,
+        }
+        let recipient = wallets.wallet(&self.to_agent).expect("recipient checked above");
+        recipient.credit_units(amount.currency(), amount.units()).expect("recipient checked above");
+        if let Some(fee) = &fee {
+            recipient.withdraw(fee.fee).expect("recipient credited the gross amount");
+            wallets.wallet(&fee.fee_wallet).and_then(|w| w.deposit(fee.fee)).expect("fee wallet checked above");
+            fees.record(&self.to_agent, fee);
+            self.fees.push(fee.clone());
+        }
+        Ok((amount, fee))
+    }
+}
+
+/// Wallets an escrow payout moves funds between.
+pub(crate) trait WalletAccess {
+    fn wallet(&mut self, agent_id: &str) -> Result<&mut AgentWallet, TreasuryError>;
+}
+
+impl WalletAccess for HashMap<String, AgentWallet> {
+    fn wallet(&mut self, agent_id: &str) -> Result<&mut AgentWallet, TreasuryError> {
+        self.get_mut(agent_id).ok_or(TreasuryError::AgentNotFound {
+            agent_id: agent_id.to_string(),
+        })
     }
 }
 
@@ -495,6 +812,14 @@ pub struct Treasury {
     channels: HashMap<String, PaymentChannel>,
     escrows: HashMap<String, Escrow>,
     pending_payments: Vec<PaymentRequest>,
+    approvals: Approvals,
+    transfers: Transfers,
+    fx: ExchangeRates,
+    budgets: Budgets,
+    payees: Payees,
+    events: TreasuryEvents,
+    ledger: Ledger,
+    fees: Fees,
 }
 
 impl Treasury {
@@ -508,6 +833,14 @@ impl Treasury {
             channels: HashMap::new(),
             escrows: HashMap::new(),
             pending_payments: Vec::new(),
+            approvals: Approvals::default(),
+            transfers: Transfers::default(),
+            fx: ExchangeRates::default(),
+            budgets: Budgets::default(),
+            payees: Payees::default(),
+            events: TreasuryEvents::default(),
+            ledger: Ledger::default(),
+            fees: Fees::default(),
         })
     }
 
@@ -519,17 +852,48 @@ impl Treasury {
     }
 
     /// Deposit funds to an agent.
-    pub fn deposit(&mut self, agent_id: &str, currency: Currency, amount: f64) -> Result<(), TreasuryError> {
-        let wallet = self.wallets.get_mut(agent_id).ok_or(TreasuryError::AgentNotFound {
-            agent_id: agent_id.to_string(),
-        })?;
-        
-        wallet.deposit(currency, amount);
+    pub fn deposit(&mut self, agent_id: &str, amount: Money) -> Result<(), TreasuryError> {
+        self.wallet_mut(agent_id)?.deposit(amount)?;
+        self.post("deposit", None, Account::External, Account::Wallet(agent_id.to_string()), amount);
         Ok(())
     }
 
+    /// Get an agent's wallet.
+    pub fn wallet(&self, agent_id: &str) -> Option<&AgentWallet> {
+        self.wallets.get(agent_id)
+    }
+
+    /// Get a payment channel.
+    pub fn channel(&self, channel_id: &str) -> Option<&PaymentChannel> {
+        self.channels.get(channel_id)
+    }
+
+    /// Get an escrow.
+    pub fn escrow(&self, escrow_id: &str) -> Option<&Escrow> {
+        self.escrows.get(escrow_id)
+    }
+
+    /// Payment log, oldest first.
+    pub fn payments(&self) -> &[PaymentRequest] {
+        &self.pending_payments
+    }
+
+    /// Remove payments by ID, e.g. when their retention period ends.
+    /// Pending payments are kept. Returns how many were removed.
+    pub fn purge_payments(&mut self, ids: &std::collections::HashSet<String>) -> usize {
+        let before = self.pending_payments.len();
+        self.pending_payments.retain(|p| p.status == PaymentStatus::Pending || !ids.contains(&p.id));
+        before - self.pending_payments.len()
+    }
+
+    fn wallet_mut(&mut self, agent_id: &str) -> Result<&mut AgentWallet, TreasuryError> {
+        self.wallets.get_mut(agent_id).ok_or(TreasuryError::AgentNotFound {
+            agent_id: agent_id.to_string(),
+        })
+    }
+
     /// Get agent balance.
-    pub fn balance(&self, agent_id: &str, currency: Currency) -> Result<f64, TreasuryError> {
+    pub fn balance(&self, agent_id: &str, currency: Currency) -> Result<Money, TreasuryError> {
         let wallet = self.wallets.get(agent_id).ok_or(TreasuryError::AgentNotFound {
             agent_id: agent_id.to_string(),
         })?;
@@ -537,75 +901,81 @@ impl Treasury {
         Ok(wallet.balance(currency))
     }
 
-    /// Pay from one agent to another.
+    /// Pay from one agent to another, as a two-phase transfer, within the
+    /// payer's budget and the payee policy.
     pub fn pay(
         &mut self,
         from_agent: &str,
         to_agent: &str,
-        amount: f64,
-        currency: Currency,
+        amount: Money,
     ) -> Result<String, TreasuryError> {
-        if amount <= 0.0 {
-            return Err(TreasuryError::InvalidAmount { amount });
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
         }
+        self.budgets.check(from_agent, to_agent, amount, Utc::now())?;
+        self.execute_payment(from_agent, to_agent, amount)
+    }
+
+    /// Pay without checking the budget; the payment still counts toward it.
+    fn execute_payment(&mut self, from_agent: &str, to_agent: &str, amount: Money) -> Result<String, TreasuryError> {
+        self.check_payee(to_agent, amount)?;
+        let approval = self.approvals.approval_for(&TreasuryOperation::payment(from_agent, to_agent, amount))?;
+        let fee = self.quote_fee(to_agent, amount)?;
         
-        // Check sender balance
-        let from_balance = self.balance(from_agent, currency)?;
-        if from_balance < amount {
-            return Err(TreasuryError::InsufficientBalance {
-                required: amount,
-                available: from_balance,
-            });
-        }
-        
-        // Check recipient exists
-        if !self.wallets.contains_key(to_agent) {
-            return Err(TreasuryError::AgentNotFound {
-                agent_id: to_agent.to_string(),
-            });
-        }
-        
-        // Execute transfer
-        let from_wallet = self.wallets.get_mut(from_agent).unwrap();
-        from_wallet.withdraw(currency, amount)?;
-        
-        let to_wallet = self.wallets.get_mut(to_agent).unwrap();
-        to_wallet.deposit(currency, amount);
+        let request = TransferRequest::new(from_agent, to_agent, amount);
+        let transferred = self.execute_transfer(request.clone());
+        self.events.on_shortfall(&self.tenant_id, from_agent, "payment", transferred)?;
         
         // Create payment record
-        let mut request = PaymentRequest::new(from_agent, to_agent, amount, currency);
-        request.status = PaymentStatus::Completed;
-        let payment_id = request.id.clone();
-        self.pending_payments.push(request);
+        let payment_id = self.log_payment(&request);
+        if let Some(fee) = &fee {
+            self.take_fee(to_agent, fee, &payment_id);
+        }
+        self.pending_payments.last_mut().expect("payment just logged").fee = fee;
+        self.approvals.consume(approval);
+        self.budgets.record(from_agent, to_agent, amount, Utc::now());
+        self.events.emit(&self.tenant_id, TreasuryEventKind::PaymentCompleted {
+            payment_id: payment_id.clone(),
+            from_agent: from_agent.to_string(),
+            to_agent: to_agent.to_string(),
+            amount,
+        });
         
         Ok(payment_id)
     }
 
-    /// Create a payment channel.
+    /// Create a payment channel. A may pay B all of the capacity, so
+    /// funding counts as a payment from A to B against A's budget, the
+    /// payee policy and the signer set's limits.
     pub fn open_channel(
         &mut self,
         party_a: &str,
         party_b: &str,
-        capacity: f64,
-        currency: Currency,
+        capacity: Money,
     ) -> Result<String, TreasuryError> {
-        // Check party A has funds
-        let balance = self.balance(party_a, currency)?;
-        if balance < capacity {
-            return Err(TreasuryError::InsufficientBalance {
-                required: capacity,
-                available: balance,
-            });
-        }
+        // Both parties need wallets to settle into
+        self.wallet_mut(party_b)?;
+        self.check_payee(party_b, capacity)?;
+        self.budgets.check(party_a, party_b, capacity, Utc::now())?;
+        let approval = self.approvals.approval_for(&TreasuryOperation::channel_open(party_a, party_b, capacity))?;
         
         // Lock funds
-        let wallet = self.wallets.get_mut(party_a).unwrap();
-        wallet.withdraw(currency, capacity)?;
+        let locked = self.wallet_mut(party_a)?.withdraw(capacity);
+        self.events.on_shortfall(&self.tenant_id, party_a, "open_channel", locked)?;
+        self.approvals.consume(approval);
+        self.budgets.record(party_a, party_b, capacity, Utc::now());
         
         // Create channel
-        let channel = PaymentChannel::new(party_a, party_b, capacity, currency);
+        let channel = PaymentChannel::new(party_a, party_b, capacity);
         let channel_id = channel.id.clone();
         self.channels.insert(channel_id.clone(), channel);
+        self.post("channel_open", Some(&channel_id), Account::Wallet(party_a.to_string()), Account::Channel(channel_id.clone()), capacity);
+        self.events.emit(&self.tenant_id, TreasuryEventKind::ChannelOpened {
+            channel_id: channel_id.clone(),
+            party_a: party_a.to_string(),
+            party_b: party_b.to_string(),
+            capacity,
+        });
         
         Ok(channel_id)
     }
@@ -615,7 +985,7 @@ impl Treasury {
         &mut self,
         channel_id: &str,
         from_a_to_b: bool,
-        amount: f64,
+        amount: Money,
     ) -> Result<(), TreasuryError> {
         let channel = self.channels.get_mut(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
         
@@ -627,22 +997,219 @@ impl Treasury {
     }
 
     /// Close a payment channel.
-    pub fn close_channel(&mut self, channel_id: &str) -> Result<(f64, f64), TreasuryError> {
+    pub fn close_channel(&mut self, channel_id: &str) -> Result<(Money, Money), TreasuryError> {
+        let channel = self.channels.get(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        if !channel.is_open {
+            return Err(TreasuryError::ChannelNotOpen);
+        }
+        if channel.keys.is_some() {
+            return Err(TreasuryError::InvalidChannelState { reason: "close signed channels with request_close".to_string() });
+        }
+        
+        let (units_a, units_b) = (channel.balance_a, channel.balance_b);
+        self.settle_channel(channel_id, units_a, units_b)
+    }
+
+    /// Open a channel updated off-ledger with [`SignedChannelState`]s and
+    /// closed unilaterally with a dispute period.
+    pub fn open_signed_channel(
+        &mut self,
+        party_a: &str,
+        party_b: &str,
+        capacity: Money,
+        keys: ChannelKeys,
+        dispute_period: chrono::Duration,
+    ) -> Result<String, TreasuryError> {
+        let channel_id = self.open_channel(party_a, party_b, capacity)?;
+        if let Some(channel) = self.channels.remove(&channel_id) {
+            self.channels.insert(channel_id.clone(), channel.with_keys(keys, dispute_period));
+        }
+        Ok(channel_id)
+    }
+
+    /// Checkpoint a signed state of an open channel with the treasury, so
+    /// either party can later [`force_close`](Self::force_close) on it
+    /// without the other. Returns the state's sequence.
+    pub fn update_channel_state(&mut self, channel_id: &str, state: &SignedChannelState) -> Result<u64, TreasuryError> {
         let channel = self.channels.get_mut(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        if !channel.is_open {
+            return Err(TreasuryError::ChannelNotOpen);
+        }
+        if let Some(pending) = &channel.pending_close {
+            return Err(TreasuryError::DisputePeriodActive { until: pending.contest_until });
+        }
+        channel.verify_state(state)?;
+        if channel.latest_state.as_ref().is_some_and(|latest| state.state.sequence <= latest.state.sequence) {
+            return Err(TreasuryError::InvalidChannelState { reason: "state is not newer than the latest".to_string() });
+        }
+
+        channel.balance_a = state.state.balance_a;
+        channel.balance_b = state.state.balance_b;
+        channel.tx_count = state.state.sequence;
+        channel.latest_state = Some(state.clone());
+        Ok(state.state.sequence)
+    }
+
+    /// Start closing a signed channel on `state`. It settles once the
+    /// dispute period passes, unless a newer state is shown first.
+    /// Returns the end of the dispute period.
+    pub fn request_close(
+        &mut self,
+        channel_id: &str,
+        state: &SignedChannelState,
+        closer: &str,
+    ) -> Result<DateTime<Utc>, TreasuryError> {
+        let channel = self.channels.get(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        channel.verify_state(state)?;
+        if channel.latest_state.as_ref().is_some_and(|latest| state.state.sequence < latest.state.sequence) {
+            return Err(TreasuryError::InvalidChannelState { reason: "state was superseded by a checkpoint".to_string() });
+        }
+        self.begin_close(channel_id, state.clone(), closer)
+    }
+
+    /// Close a signed channel when the other party will not cooperate: the
+    /// latest checkpointed state (or, without one, the opening state) goes
+    /// through the dispute period and settles once it passes. A party
+    /// holding a newer state should checkpoint it first, as the other
+    /// party can still contest with it.
+    pub fn force_close(&mut self, channel_id: &str, closer: &str) -> Result<DateTime<Utc>, TreasuryError> {
+        let channel = self.channels.get(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        if channel.keys.is_none() {
+            return Err(TreasuryError::InvalidChannelState { reason: "channel has no signing keys".to_string() });
+        }
+        // The opening state needs no signatures: it is what the payer funded
+        let state = channel.latest_state.clone().unwrap_or_else(|| SignedChannelState {
+            state: channel.opening_state(),
+            signature_a: Vec::new(),
+            signature_b: Vec::new(),
+        });
+        tracing::warn!(channel_id = %channel_id, closer = %closer, sequence = state.state.sequence, "Channel force-close requested");
+        self.begin_close(channel_id, state, closer)
+    }
+
+    fn begin_close(&mut self, channel_id: &str, state: SignedChannelState, closer: &str) -> Result<DateTime<Utc>, TreasuryError> {
+        let channel = self.channels.get_mut(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        if !channel.is_open {
+            return Err(TreasuryError::ChannelNotOpen);
+        }
+        if let Some(pending) = &channel.pending_close {
+            return Err(TreasuryError::DisputePeriodActive { until: pending.contest_until });
+        }
+        if closer != channel.party_a && closer != channel.party_b {
+            return Err(TreasuryError::AgentNotFound { agent_id: closer.to_string() });
+        }
+
+        let contest_until = Utc::now() + chrono::Duration::seconds(channel.dispute_period_secs);
+        let sequence = state.state.sequence;
+        channel.pending_close = Some(PendingClose { state, closer: closer.to_string(), contest_until });
+        tracing::info!(channel_id = %channel_id, closer = %closer, sequence, "Channel close requested");
+        Ok(contest_until)
+    }
+
+    /// Contest a pending close with a newer state. The closer tried to
+    /// settle on a revoked state and forfeits the whole channel to the
+    /// other party.
+    pub fn contest_close(&mut self, channel_id: &str, newer: &SignedChannelState) -> Result<(Money, Money), TreasuryError> {
+        let channel = self.channels.get(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        let pending = channel.pending_close.as_ref().ok_or(TreasuryError::NoPendingClose)?;
+        if Utc::now() > pending.contest_until {
+            return Err(TreasuryError::InvalidChannelState { reason: "dispute period is over".to_string() });
+        }
+        channel.verify_state(newer)?;
+        if newer.state.sequence <= pending.state.state.sequence {
+            return Err(TreasuryError::InvalidChannelState { reason: "state is not newer than the close".to_string() });
+        }
+
+        let (units_a, units_b) = if pending.closer == channel.party_a {
+            (0, channel.capacity)
+        } else {
+            (channel.capacity, 0)
+        };
+        tracing::warn!(
+            channel_id = %channel_id,
+            closer = %pending.closer,
+            revoked = pending.state.state.sequence,
+            latest = newer.state.sequence,
+            "Revoked channel state used to close; closer penalized"
+        );
+        self.settle_channel(channel_id, units_a, units_b)
+    }
+
+    /// Settle a pending close whose dispute period has passed.
+    pub fn finalize_close(&mut self, channel_id: &str) -> Result<(Money, Money), TreasuryError> {
+        let channel = self.channels.get(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        let pending = channel.pending_close.as_ref().ok_or(TreasuryError::NoPendingClose)?;
+        if Utc::now() <= pending.contest_until {
+            return Err(TreasuryError::DisputePeriodActive { until: pending.contest_until });
+        }
+        let (units_a, units_b) = (pending.state.state.balance_a, pending.state.state.balance_b);
+        self.settle_channel(channel_id, units_a, units_b)
+    }
+
+    /// Settle every pending close whose dispute period has passed, e.g. on
+    /// a schedule so force-closes complete without the closer returning.
+    /// Returns the payout of each settled channel; channels that fail to
+    /// settle stay pending and are logged.
+    pub fn settle_expired_closes(&mut self) -> Vec<(String, (Money, Money))> {
+        let now = Utc::now();
+        let expired: Vec<String> = self
+            .pending_closes()
+            .filter(|(_, pending)| now > pending.contest_until)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        let mut settled = Vec::new();
+        for channel_id in expired {
+            match self.finalize_close(&channel_id) {
+                Ok(payout) => settled.push((channel_id, payout)),
+                Err(e) => tracing::error!(channel_id = %channel_id, error = %e, "Failed to settle expired channel close"),
+            }
+        }
+        settled
+    }
+
+    /// Open channels with a close in its dispute period.
+    pub fn pending_closes(&self) -> impl Iterator<Item = (&str, &PendingClose)> {
+        self.channels
+            .iter()
+            .filter(|(_, channel)| channel.is_open)
+            .filter_map(|(id, channel)| channel.pending_close.as_ref().map(|pending| (id.as_str(), pending)))
+    }
+
+    /// Close an open channel, paying out `units_a` / `units_b` (which must
+    /// add up to what the channel holds).
+    fn settle_channel(&mut self, channel_id: &str, units_a: u128, units_b: u128) -> Result<(Money, Money), TreasuryError> {
+        let channel = self.channels.get_mut(channel_id).ok_or(TreasuryError::ChannelNotOpen)?;
+        if !channel.is_open {
+            return Err(TreasuryError::ChannelNotOpen);
+        }
         
-        let (balance_a, balance_b) = channel.close();
         let currency = channel.currency;
-        let party_a = channel.party_a.clone();
-        let party_b = channel.party_b.clone();
-        
-        // Return funds to wallets
-        if let Some(wallet) = self.wallets.get_mut(&party_a) {
-            wallet.deposit(currency, balance_a);
-        }
-        if let Some(wallet) = self.wallets.get_mut(&party_b) {
-            wallet.deposit(currency, balance_b);
+        let fits = |agent: &str, units: u128| self.wallets.get(agent).is_some_and(|w| w.can_credit(currency, units));
+        let settles = if channel.party_a == channel.party_b {
+            units_a.checked_add(units_b).is_some_and(|units| fits(&channel.party_a, units))
+        } else {
+            fits(&channel.party_a, units_a) && fits(&channel.party_b, units_b)
+        };
+        if !settles {
+            return Err(TreasuryError::PaymentFailed {
+                reason: "Cannot settle channel balances".to_string(),
+            });
         }
         
+        // Return funds to wallets in base units
+        channel.balance_a = units_a;
+        channel.balance_b = units_b;
+        channel.pending_close = None;
+        let (balance_a, balance_b) = channel.close();
+        let (party_a, party_b) = (channel.party_a.clone(), channel.party_b.clone());
+        self.wallet_mut(&party_a)?.credit_units(currency, units_a)?;
+        self.wallet_mut(&party_b)?.credit_units(currency, units_b)?;
+        let channel = Account::Channel(channel_id.to_string());
+        self.ledger.post("channel_settle", Some(channel_id), &[
+            (channel.clone(), Account::Wallet(party_a), balance_a),
+            (channel, Account::Wallet(party_b), balance_b),
+        ]);
+        
         Ok((balance_a, balance_b))
     }
 
@@ -651,40 +1218,82 @@ impl Treasury {
         &mut self,
         from_agent: &str,
         to_agent: &str,
-        amount: f64,
-        currency: Currency,
+        amount: Money,
         condition: &str,
         duration_hours: i64,
     ) -> Result<String, TreasuryError> {
-        // Check and lock funds
-        let wallet = self.wallets.get_mut(from_agent).ok_or(TreasuryError::AgentNotFound {
-            agent_id: from_agent.to_string(),
-        })?;
+        // Recipient needs a wallet to be paid into
+        self.wallet_mut(to_agent)?;
+        self.check_payee(to_agent, amount)?;
         
-        wallet.withdraw(currency, amount)?;
+        // Check and lock funds
+        let locked = self.wallet_mut(from_agent)?.withdraw(amount);
+        self.events.on_shortfall(&self.tenant_id, from_agent, "create_escrow", locked)?;
         
         // Create escrow
-        let escrow = Escrow::new(from_agent, to_agent, amount, currency, condition, duration_hours);
+        let escrow = Escrow::new(from_agent, to_agent, amount, condition, duration_hours);
         let escrow_id = escrow.id.clone();
         self.escrows.insert(escrow_id.clone(), escrow);
+        self.post("escrow_create", Some(&escrow_id), Account::Wallet(from_agent.to_string()), Account::Escrow(escrow_id.clone()), amount);
         
         Ok(escrow_id)
     }
 
-    /// Release escrow to recipient.
+    /// Release escrow to recipient, including any milestones not yet
+    /// released. Releases at or above the signer set's limit need an
+    /// approved signing session.
     pub fn release_escrow(&mut self, escrow_id: &str) -> Result<(), TreasuryError> {
+        let amount = self.pay_out_escrow(escrow_id, None)?;
+        let escrow = &self.escrows[escrow_id];
+        self.events.emit(&self.tenant_id, TreasuryEventKind::EscrowReleased {
+            escrow_id: escrow_id.to_string(),
+            from_agent: escrow.from_agent.clone(),
+            to_agent: escrow.to_agent.clone(),
+            amount,
+        });
+        
+        Ok(())
+    }
+
+    /// Pay out an escrow, or one of its milestones, once approved, and post
+    /// the payout and its fee to the ledger.
+    pub(crate) fn pay_out_escrow(&mut self, escrow_id: &str, milestone: Option<&str>) -> Result<Money, TreasuryError> {
         let escrow = self.escrows.get_mut(escrow_id).ok_or(TreasuryError::PaymentFailed {
             reason: "Escrow not found".to_string(),
         })?;
+        let operation = match milestone {
+            Some(name) => escrow.milestone_release_operation(name)?,
+            None => escrow.release_operation()?,
+        };
+        let approval = self.approvals.approval_for(&operation)?;
+        let (amount, fee) = escrow.pay_out(milestone, &mut self.wallets, &mut self.fees)?;
+        self.approvals.consume(approval);
         
-        let amount = escrow.release()?;
-        let currency = escrow.currency;
         let to_agent = escrow.to_agent.clone();
-        
-        // Credit recipient
-        if let Some(wallet) = self.wallets.get_mut(&to_agent) {
-            wallet.deposit(currency, amount);
+        let memo = if milestone.is_some() { "escrow_milestone" } else { "escrow_release" };
+        self.post(memo, Some(escrow_id), Account::Escrow(escrow_id.to_string()), Account::Wallet(to_agent.clone()), amount);
+        if let Some(fee) = fee {
+            self.ledger.post_fee(&to_agent, &fee, escrow_id);
         }
+        Ok(amount)
+    }
+
+    /// Refund what escrow still holds to sender.
+    pub fn refund_escrow(&mut self, escrow_id: &str) -> Result<(), TreasuryError> {
+        let escrow = self.escrows.get_mut(escrow_id).ok_or(TreasuryError::PaymentFailed {
+            reason: "Escrow not found".to_string(),
+        })?;
+        
+        escrow.status.check_transition(&EscrowStatus::Refunded)?;
+        
+        let wallet = self.wallets.get_mut(&escrow.from_agent).ok_or(TreasuryError::AgentNotFound {
+            agent_id: escrow.from_agent.clone(),
+        })?;
+        let amount = escrow.remaining();
+        wallet.deposit(amount)?;
+        escrow.refund()?;
+        let from_agent = escrow.from_agent.clone();
+        self.post("escrow_refund", Some(escrow_id), Account::Escrow(escrow_id.to_string()), Account::Wallet(from_agent), amount);
         
         Ok(())
     }
@@ -830,11 +1439,10 @@ impl InsurancePolicy {
             });
         }
 
-        if amount > self.available_coverage() {
-            return Err(TreasuryError::InsufficientBalance {
-                required: amount,
-                available: self.available_coverage(),
-            });
+        let required = Money::try_from((amount, self.currency))?;
+        let available = Money::try_from((self.available_coverage(), self.currency))?;
+        if required.units() > available.units() {
+            return Err(TreasuryError::InsufficientBalance { required, available });
         }
 
         let claim = InsuranceClaim {
@@ -1072,39 +1680,51 @@ impl AgentLegalEntity {
 mod tests {
     use super::*;
 
+    fn credits(amount: &str) -> Money {
+        Money::parse(amount, Currency::Credits).unwrap()
+    }
+
     #[test]
     fn test_currency_conversion() {
-        let btc = Currency::Btc;
-        assert_eq!(btc.to_base_units(1.0), 100_000_000);
-        assert_eq!(btc.from_base_units(100_000_000), 1.0);
+        let btc = Money::parse("1", Currency::Btc).unwrap();
+        assert_eq!(btc.units(), 100_000_000);
+        assert_eq!(btc.to_f64(), 1.0);
         
-        let usd = Currency::Usd;
-        assert_eq!(usd.to_base_units(100.50), 10050);
+        let usd = Money::parse("100.50", Currency::Usd).unwrap();
+        assert_eq!(usd.units(), 10050);
     }
 
     #[test]
     fn test_agent_wallet() {
         let mut wallet = AgentWallet::new("agent-1");
         
-        wallet.deposit(Currency::Credits, 100.0);
-        assert_eq!(wallet.balance(Currency::Credits), 100.0);
+        wallet.deposit(credits("100")).unwrap();
+        assert_eq!(wallet.balance(Currency::Credits), credits("100"));
         
-        wallet.withdraw(Currency::Credits, 30.0).unwrap();
-        assert_eq!(wallet.balance(Currency::Credits), 70.0);
+        wallet.withdraw(credits("30")).unwrap();
+        assert_eq!(wallet.balance(Currency::Credits), credits("70"));
+        assert!(matches!(
+            wallet.withdraw(Money::parse("1", Currency::Usd).unwrap()),
+            Err(TreasuryError::InsufficientBalance { .. })
+        ));
     }
 
     #[test]
     fn test_payment_channel() {
-        let mut channel = PaymentChannel::new("alice", "bob", 100.0, Currency::Credits);
+        let mut channel = PaymentChannel::new("alice", "bob", credits("100"));
         
         // Alice pays Bob 30
-        channel.transfer_a_to_b(30.0).unwrap();
+        channel.transfer_a_to_b(credits("30")).unwrap();
         assert_eq!(channel.balance_a, 70_000_000);
         assert_eq!(channel.balance_b, 30_000_000);
         
         // Bob pays Alice back 10
-        channel.transfer_b_to_a(10.0).unwrap();
+        channel.transfer_b_to_a(credits("10")).unwrap();
         assert_eq!(channel.tx_count, 2);
+        
+        // Wrong currency
+        let usd = Money::parse("1", Currency::Usd).unwrap();
+        assert!(matches!(channel.transfer_a_to_b(usd), Err(TreasuryError::CurrencyMismatch { .. })));
     }
 
     #[test]
@@ -1125,13 +1745,19 @@ mod tests {
         treasury.register_agent("agent-A");
         treasury.register_agent("agent-B");
         
-        treasury.deposit("agent-A", Currency::Credits, 100.0).unwrap();
+        treasury.deposit("agent-A", credits("100")).unwrap();
         
-        let payment_id = treasury.pay("agent-A", "agent-B", 25.0, Currency::Credits).unwrap();
+        let payment_id = treasury.pay("agent-A", "agent-B", credits("25")).unwrap();
         
         assert!(!payment_id.is_empty());
-        assert_eq!(treasury.balance("agent-A", Currency::Credits).unwrap(), 75.0);
-        assert_eq!(treasury.balance("agent-B", Currency::Credits).unwrap(), 25.0);
+        assert_eq!(treasury.balance("agent-A", Currency::Credits).unwrap(), credits("75"));
+        assert_eq!(treasury.balance("agent-B", Currency::Credits).unwrap(), credits("25"));
+        
+        // Micropayments add up exactly
+        for _ in 0..10 {
+            treasury.pay("agent-A", "agent-B", credits("0.1")).unwrap();
+        }
+        assert_eq!(treasury.balance("agent-B", Currency::Credits).unwrap(), credits("26"));
         
         // SAFETY: Only used in tests, no concurrent access
         unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
@@ -1142,8 +1768,7 @@ mod tests {
         let mut escrow = Escrow::new(
             "seller",
             "buyer",
-            50.0,
-            Currency::Usdc,
+            Money::parse("50", Currency::Usdc).unwrap(),
             "delivery_confirmed",
             24
         );
@@ -1151,8 +1776,29 @@ mod tests {
         assert_eq!(escrow.status, EscrowStatus::Locked);
         
         let amount = escrow.release().unwrap();
-        assert_eq!(amount, 50.0);
+        assert_eq!(amount.to_f64(), 50.0);
+        assert_eq!(escrow.status, EscrowStatus::Released);
+        
+        // Released escrows stay released
+        assert!(matches!(escrow.refund(), Err(TreasuryError::IllegalTransition(_))));
         assert_eq!(escrow.status, EscrowStatus::Released);
+        let transitions = escrow.history.entries();
+        assert_eq!(transitions.len(), 1);
+        assert_eq!((transitions[0].from, transitions[0].to), (EscrowStatus::Locked, EscrowStatus::Released));
+    }
+
+    #[test]
+    fn test_payment_transitions() {
+        let mut payment = PaymentRequest::new("agent-A", "agent-B", credits("5"));
+        payment.transition(PaymentStatus::Processing, "agent-A").unwrap();
+        payment.transition(PaymentStatus::Completed, "agent-A").unwrap();
+        payment.transition(PaymentStatus::Refunded, "agent-B").unwrap();
+        
+        // Refunded → Completed is nonsense
+        let error = payment.transition(PaymentStatus::Completed, "agent-A").unwrap_err();
+        assert_eq!(error.to_string(), "Illegal payment transition: Refunded -> Completed");
+        assert_eq!(payment.status, PaymentStatus::Refunded);
+        assert_eq!(payment.history.last().unwrap().actor, "agent-B");
     }
 
     #[test]
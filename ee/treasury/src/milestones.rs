@@ -0,0 +1,189 @@
+//! Escrow Milestones
+//!
+//! Long tasks pay out in stages instead of all at the end. An escrow
+//! created with [`Treasury::create_milestone_escrow`] splits its amount
+//! into [`Milestone`]s, each a percentage of the total with its own release
+//! condition. [`Treasury::release_milestone`] pays one milestone's share to
+//! the recipient as soon as its work is verifiably done; the escrow keeps
+//! holding the rest. Releasing the whole escrow pays out every milestone
+//! still outstanding, and a refund returns only what is still held.
+//!
+//! Shares are rounded down to whole base units, and the last milestone
+//! released takes whatever is left, so the escrow always pays out exactly
+//! its amount.
+//!
+//! Marketplace executions report verified progress per milestone; feed
+//! those reports to [`Treasury::release_milestone`] to stream funds as the
+//! work completes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let escrow_id = treasury.create_milestone_escrow(
+//!     "client",
+//!     "worker",
+//!     Money::parse("1000", Currency::Usd)?,
+//!     vec![
+//!         Milestone::new("design", 20, "design doc approved"),
+//!         Milestone::new("build", 50, "tests pass"),
+//!         Milestone::new("deploy", 30, "deployed to production"),
+//!     ],
+//!     72,
+//! )?;
+//!
+//! let mut progress = marketplace.subscribe_progress();
+//! while let Ok(report) = progress.recv().await {
+//!     if report.verified {
+//!         treasury.release_milestone(&escrow_id, &report.milestone)?;
+//!     }
+//! }
+//! ```
+
+use crate::threshold::{OperationKind, SigningSession, TreasuryOperation};
+use crate::{Escrow, EscrowStatus, Money, Treasury, TreasuryError, TreasuryEventKind};
+use agentkern_fsm::State;
+use serde::{Deserialize, Serialize};
+
+/// A stage of an escrow, paid out on its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Milestone {
+    /// Unique within the escrow
+    pub name: String,
+    /// Share of the escrow amount, in percent
+    pub percent: u8,
+    /// Release condition (serialized)
+    pub condition: String,
+    /// Amount paid out, once released
+    pub released: Option<Money>,
+}
+
+impl Milestone {
+    pub fn new(name: impl Into<String>, percent: u8, condition: impl Into<String>) -> Self {
+        Self { name: name.into(), percent, condition: condition.into(), released: None }
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.released.is_some()
+    }
+}
+
+/// Check milestones are named uniquely and their shares add up to 100%.
+fn validate(milestones: &[Milestone]) -> Result<(), TreasuryError> {
+    let invalid = |reason: String| Err(TreasuryError::InvalidMilestone { reason });
+    if milestones.is_empty() {
+        return invalid("an escrow needs at least one milestone".to_string());
+    }
+    for (i, milestone) in milestones.iter().enumerate() {
+        if milestone.name.trim().is_empty() {
+            return invalid("milestone names must not be empty".to_string());
+        }
+        if milestone.percent == 0 {
+            return invalid(format!("{} has a zero share", milestone.name));
+        }
+        if milestones[..i].iter().any(|m| m.name == milestone.name) {
+            return invalid(format!("{} is defined twice", milestone.name));
+        }
+    }
+    let total: u32 = milestones.iter().map(|m| u32::from(m.percent)).sum();
+    if total != 100 {
+        return invalid(format!("shares add up to {total}%, not 100%"));
+    }
+    Ok(())
+}
+
+impl Escrow {
+    /// What the escrow still holds: its amount less released milestones.
+    pub fn remaining(&self) -> Money {
+        let released: u128 = self.milestones.iter().filter_map(|m| m.released).map(|m| m.units()).sum();
+        Money::from_units(self.amount.units().saturating_sub(released), self.amount.currency())
+    }
+
+    /// A milestone by name.
+    pub fn milestone(&self, name: &str) -> Option<&Milestone> {
+        self.milestones.iter().find(|m| m.name == name)
+    }
+
+    /// What releasing milestone `name` pays out. Fails unless the escrow is
+    /// locked and the milestone is outstanding.
+    pub fn milestone_share(&self, name: &str) -> Result<Money, TreasuryError> {
+        self.status.check_transition(&EscrowStatus::Released)?;
+        let milestone = self.milestone(name).ok_or_else(|| TreasuryError::InvalidMilestone {
+            reason: format!("escrow {} has no milestone {name}", self.id),
+        })?;
+        if milestone.is_released() {
+            return Err(TreasuryError::InvalidMilestone { reason: format!("{name} was already released") });
+        }
+        let outstanding = self.milestones.iter().filter(|m| !m.is_released()).count();
+        if outstanding == 1 {
+            return Ok(self.remaining());
+        }
+        let units = self.amount.units() / 100 * u128::from(milestone.percent)
+            + self.amount.units() % 100 * u128::from(milestone.percent) / 100;
+        Ok(Money::from_units(units, self.amount.currency()))
+    }
+
+    /// The operation releasing milestone `name`. It needs signatures
+    /// whenever releasing the whole escrow would.
+    pub fn milestone_release_operation(&self, name: &str) -> Result<TreasuryOperation, TreasuryError> {
+        Ok(TreasuryOperation {
+            kind: OperationKind::EscrowRelease {
+                escrow_id: self.id.clone(),
+                to_agent: self.to_agent.clone(),
+                escrow_amount: self.amount,
+                milestone: Some(name.to_string()),
+            },
+            amount: self.milestone_share(name)?,
+        })
+    }
+}
+
+impl Treasury {
+    /// Create an escrow paid out in `milestones`, whose shares must add up
+    /// to 100%.
+    pub fn create_milestone_escrow(
+        &mut self,
+        from_agent: &str,
+        to_agent: &str,
+        amount: Money,
+        milestones: Vec<Milestone>,
+        duration_hours: i64,
+    ) -> Result<String, TreasuryError> {
+        validate(&milestones)?;
+        let names: Vec<&str> = milestones.iter().map(|m| m.name.as_str()).collect();
+        let condition = format!("milestones: {}", names.join(", "));
+        let escrow_id = self.create_escrow(from_agent, to_agent, amount, &condition, duration_hours)?;
+        let escrow = self.escrows.get_mut(&escrow_id).expect("escrow just created");
+        escrow.milestones = milestones.into_iter().map(|m| Milestone { released: None, ..m }).collect();
+        Ok(escrow_id)
+    }
+
+    /// Pay milestone `name`'s share of an escrow to its recipient. The
+    /// escrow is released once its last milestone is. Milestones of escrows
+    /// at or above the signer set's limit need a signing session approving
+    /// that milestone.
+    pub fn release_milestone(&mut self, escrow_id: &str, name: &str) -> Result<Money, TreasuryError> {
+        let amount = self.pay_out_escrow(escrow_id, Some(name))?;
+        let escrow = &self.escrows[escrow_id];
+        let (from_agent, to_agent, remaining) = (escrow.from_agent.clone(), escrow.to_agent.clone(), escrow.remaining());
+        self.events.emit(&self.tenant_id, TreasuryEventKind::EscrowMilestoneReleased {
+            escrow_id: escrow_id.to_string(),
+            milestone: name.to_string(),
+            from_agent,
+            to_agent,
+            amount,
+            remaining,
+        });
+        tracing::info!(escrow_id = %escrow_id, milestone = %name, amount = %amount, remaining = %remaining, "Escrow milestone released");
+
+        Ok(amount)
+    }
+
+    /// Open a session to approve releasing one milestone of an escrow.
+    pub fn open_milestone_release(&mut self, escrow_id: &str, name: &str) -> Result<SigningSession, TreasuryError> {
+        let escrow = self.escrows.get(escrow_id).ok_or(TreasuryError::PaymentFailed {
+            reason: "Escrow not found".to_string(),
+        })?;
+        let operation = escrow.milestone_release_operation(name)?;
+        self.approvals.open(operation)
+    }
+}
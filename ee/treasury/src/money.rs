@@ -0,0 +1,161 @@
+//! Money
+//!
+//! Fixed-point amounts: a count of base units (cents, satoshis, wei...) in
+//! one [`Currency`]. All treasury arithmetic is integer, so amounts never
+//! drift the way `0.1 + 0.2` does in floats, and `u128` holds any realistic
+//! ETH amount at 18 decimals.
+//!
+//! Decimal strings convert exactly with [`Money::parse`]. Floats from
+//! legacy callers go through the explicit `TryFrom<(f64, Currency)>`, which
+//! rounds to the nearest unit and rejects negative, non-finite and
+//! out-of-range values.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let a = Money::parse("0.1", Currency::Usd)?;
+//! let b = Money::parse("0.2", Currency::Usd)?;
+//! assert_eq!(a.checked_add(b)?, Money::parse("0.3", Currency::Usd)?);
+//!
+//! // Legacy float amounts
+//! let legacy = Money::try_from((12.5, Currency::Credits))?;
+//! ```
+
+use crate::{Currency, TreasuryError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An amount of one currency, in base units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    units: u128,
+    currency: Currency,
+}
+
+impl Money {
+    pub const fn from_units(units: u128, currency: Currency) -> Self {
+        Self { units, currency }
+    }
+
+    pub const fn zero(currency: Currency) -> Self {
+        Self::from_units(0, currency)
+    }
+
+    /// Parse a decimal string such as `"12.50"` exactly. More fractional
+    /// digits than the currency has are rejected rather than rounded.
+    pub fn parse(amount: &str, currency: Currency) -> Result<Self, TreasuryError> {
+        let invalid = || TreasuryError::InvalidAmount { amount: amount.to_string() };
+        let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() && fraction.is_empty()
+            || !digits(whole)
+            || !digits(fraction)
+            || fraction.len() > currency.decimals() as usize
+        {
+            return Err(invalid());
+        }
+
+        let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+        let fraction = format!("{:0<width$}", fraction, width = currency.decimals() as usize);
+        let fraction: u128 = if fraction.is_empty() { 0 } else { fraction.parse().map_err(|_| invalid())? };
+        let units = whole.checked_mul(currency.scale()).and_then(|u| u.checked_add(fraction)).ok_or_else(invalid)?;
+        Ok(Self::from_units(units, currency))
+    }
+
+    pub const fn units(&self) -> u128 {
+        self.units
+    }
+
+    pub const fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.units == 0
+    }
+
+    /// Sum of two amounts of the same currency.
+    pub fn checked_add(self, other: Money) -> Result<Money, TreasuryError> {
+        self.same_currency(other)?;
+        let units = self.units.checked_add(other.units).ok_or_else(|| TreasuryError::InvalidAmount {
+            amount: format!("{} + {}", self, other),
+        })?;
+        Ok(Self::from_units(units, self.currency))
+    }
+
+    /// `self - other`, failing if `other` is larger.
+    pub fn checked_sub(self, other: Money) -> Result<Money, TreasuryError> {
+        self.same_currency(other)?;
+        let units = self.units.checked_sub(other.units).ok_or(TreasuryError::InsufficientBalance {
+            required: other,
+            available: self,
+        })?;
+        Ok(Self::from_units(units, self.currency))
+    }
+
+    /// Nearest float, for display and legacy callers only.
+    pub fn to_f64(&self) -> f64 {
+        self.units as f64 / self.currency.scale() as f64
+    }
+
+    pub(crate) fn same_currency(&self, other: Money) -> Result<(), TreasuryError> {
+        if self.currency != other.currency {
+            return Err(TreasuryError::CurrencyMismatch { expected: self.currency, found: other.currency });
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<(f64, Currency)> for Money {
+    type Error = TreasuryError;
+
+    /// Convert a legacy float amount, rounded to the nearest base unit.
+    fn try_from((amount, currency): (f64, Currency)) -> Result<Self, Self::Error> {
+        let units = (amount * currency.scale() as f64).round();
+        // u128::MAX as f64 rounds up to 2^128, which is itself out of range
+        if !units.is_finite() || units < 0.0 || units >= u128::MAX as f64 {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+        Ok(Self::from_units(units as u128, currency))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = self.currency.decimals() as usize;
+        let scale = self.currency.scale();
+        if decimals == 0 {
+            write!(f, "{} {}", self.units, self.currency.code())
+        } else {
+            write!(f, "{}.{:0decimals$} {}", self.units / scale, self.units % scale, self.currency.code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_arithmetic_is_exact() {
+        let usd = |s| Money::parse(s, Currency::Usd).unwrap();
+        assert_eq!(usd("0.1").checked_add(usd("0.2")).unwrap(), usd("0.30"));
+        assert_eq!(usd("12.5").units(), 1250);
+        assert_eq!(usd(".05").to_string(), "0.05 USD");
+        assert!(Money::parse("0.001", Currency::Usd).is_err());
+        assert!(Money::parse("1e3", Currency::Usd).is_err());
+
+        assert!(matches!(usd("1").checked_sub(usd("2")), Err(TreasuryError::InsufficientBalance { .. })));
+        let sats = Money::from_units(1, Currency::Sats);
+        assert!(matches!(usd("1").checked_add(sats), Err(TreasuryError::CurrencyMismatch { .. })));
+    }
+
+    #[test]
+    fn test_float_conversion_rounds_and_rejects_garbage() {
+        assert_eq!(Money::try_from((0.1 + 0.2, Currency::Usd)).unwrap().units(), 30);
+        assert_eq!(Money::try_from((19.0, Currency::Eth)).unwrap().units(), 19 * 10u128.pow(18));
+        assert!(Money::try_from((f64::NAN, Currency::Usd)).is_err());
+        assert!(Money::try_from((-1.0, Currency::Usd)).is_err());
+        assert!(Money::try_from((1e30, Currency::Eth)).is_err());
+    }
+}
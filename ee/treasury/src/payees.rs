@@ -0,0 +1,377 @@
+//! Payee Controls
+//!
+//! Limits who a tenant's agents may pay, checked by [`Treasury::pay`],
+//! cross-currency payments, channel opening and escrow creation before any
+//! funds move:
+//! - A deny list of agents that are never paid
+//! - Named [`AddressBook`]s of approved payees, each optionally capped per
+//!   payment; in [`PayeeMode::AllowListed`] only payees in a book are paid
+//!
+//! Adding a payee whose cap is above the policy's approval threshold (or
+//! that is uncapped while a threshold is set) opens a [`PayeeRequest`]
+//! instead, which is handed to the configured [`PayeeEscalation`] (normally
+//! the arbiter's approval workflow) and added once a human approves it.
+//!
+//! [`crate::SharedTreasury::with_payee_policy`] enforces a policy on the
+//! shared treasury's payments, channels and escrows. It keeps no address
+//! books, so there only the deny list and open mode let payments through.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let workflow = Arc::new(ApprovalWorkflow::new());
+//! let (escalation, triggers) = (workflow.clone(), Mutex::new(EscalationTrigger::default_trust_trigger()));
+//! let mut treasury = Treasury::new("org-1")?
+//!     .with_payee_policy(
+//!         PayeePolicy::allow_listed()
+//!             .with_approval_threshold(Money::parse("500", Currency::Usd)?)
+//!             .with_denied("agent-sanctioned"),
+//!     )
+//!     .with_payee_escalation(move |request: &PayeeRequest| {
+//!         let trigger = triggers.lock().unwrap().manual_escalate(&request.requested_by, "New payee", EscalationLevel::High);
+//!         let params = serde_json::to_value(request).map_err(|e| e.to_string())?;
+//!         Ok(escalation.request_approval(&trigger, "add_payee", params).id)
+//!     });
+//!
+//! let request = treasury.request_payee("vendors", "acme", "agent-acme", Some(usd("2000")?), "agent-A")?;
+//! // ...once the arbiter request is approved
+//! treasury.approve_payee(&request.id, "compliance@example.com")?;
+//! treasury.pay_named("agent-A", "vendors", "acme", usd("1200")?)?;
+//! ```
+
+use crate::{Currency, Money, Treasury, TreasuryError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+/// Which payees are allowed by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayeeMode {
+    /// Any payee that is not denied
+    #[default]
+    Open,
+    /// Only payees in an address book
+    AllowListed,
+}
+
+/// Payee rules of a tenant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayeePolicy {
+    pub mode: PayeeMode,
+    /// Never paid, even from an address book
+    pub denied: HashSet<String>,
+    /// New payees capped above these, or uncapped, need approval
+    pub approval_thresholds: HashMap<Currency, Money>,
+}
+
+impl PayeePolicy {
+    /// Pay anyone not denied.
+    pub fn open() -> Self {
+        Self::default()
+    }
+
+    /// Pay only payees in an address book.
+    pub fn allow_listed() -> Self {
+        Self { mode: PayeeMode::AllowListed, ..Self::default() }
+    }
+
+    pub fn with_denied(mut self, agent_id: impl Into<String>) -> Self {
+        self.denied.insert(agent_id.into());
+        self
+    }
+
+    /// Require approval for payees capped above `amount` in its currency.
+    pub fn with_approval_threshold(mut self, amount: Money) -> Self {
+        self.approval_thresholds.insert(amount.currency(), amount);
+        self
+    }
+
+    /// Whether adding a payee with this per-payment cap needs approval.
+    pub fn needs_approval(&self, max_payment: Option<Money>) -> bool {
+        match max_payment {
+            Some(max) => self.approval_thresholds.get(&max.currency()).is_some_and(|t| max.units() > t.units()),
+            None => !self.approval_thresholds.is_empty(),
+        }
+    }
+}
+
+/// An approved payee.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payee {
+    pub name: String,
+    pub agent_id: String,
+    /// Largest single payment; payments in other currencies are refused
+    pub max_payment: Option<Money>,
+    pub approved_by: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Named payees, e.g. `vendors` or `partners`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBook {
+    pub name: String,
+    payees: BTreeMap<String, Payee>,
+}
+
+impl AddressBook {
+    pub fn get(&self, name: &str) -> Option<&Payee> {
+        self.payees.get(name)
+    }
+
+    pub fn payees(&self) -> impl Iterator<Item = &Payee> {
+        self.payees.values()
+    }
+}
+
+/// State of a [`PayeeRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayeeRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A request to add a payee to an address book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayeeRequest {
+    pub id: String,
+    pub book: String,
+    pub name: String,
+    pub agent_id: String,
+    pub max_payment: Option<Money>,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    pub status: PayeeRequestStatus,
+    /// ID the escalation gave the request, e.g. an arbiter approval ID
+    pub escalation_id: Option<String>,
+    pub decided_by: Option<String>,
+}
+
+/// Where payee requests needing approval go. Returns the escalation's ID
+/// for the request.
+pub trait PayeeEscalation: Send + Sync {
+    fn escalate(&self, request: &PayeeRequest) -> Result<String, String>;
+}
+
+impl<F> PayeeEscalation for F
+where
+    F: Fn(&PayeeRequest) -> Result<String, String> + Send + Sync,
+{
+    fn escalate(&self, request: &PayeeRequest) -> Result<String, String> {
+        self(request)
+    }
+}
+
+/// Payee policy, address books and open requests of a [`Treasury`].
+#[derive(Default)]
+pub(crate) struct Payees {
+    policy: PayeePolicy,
+    books: BTreeMap<String, AddressBook>,
+    requests: HashMap<String, PayeeRequest>,
+    escalation: Option<Arc<dyn PayeeEscalation>>,
+}
+
+impl Payees {
+    pub(crate) fn set_policy(&mut self, policy: PayeePolicy) {
+        self.policy = policy;
+    }
+
+    /// Check that `payee` may be paid `amount`.
+    pub(crate) fn check(&self, payee: &str, amount: Money) -> Result<(), TreasuryError> {
+        let refused = |reason: String| TreasuryError::PayeeNotAllowed { payee: payee.to_string(), reason };
+        if self.policy.denied.contains(payee) {
+            return Err(refused("payee is denied".to_string()));
+        }
+
+        let mut entries = self.books.values().flat_map(|b| b.payees.values()).filter(|p| p.agent_id == payee).peekable();
+        if entries.peek().is_none() {
+            return match self.policy.mode {
+                PayeeMode::Open => Ok(()),
+                PayeeMode::AllowListed => Err(refused("payee is not in an address book".to_string())),
+            };
+        }
+        // Any entry covering the payment is enough
+        let covers = |max: &Option<Money>| {
+            max.is_none_or(|max| max.currency() == amount.currency() && max.units() >= amount.units())
+        };
+        if entries.any(|p| covers(&p.max_payment)) {
+            Ok(())
+        } else {
+            Err(refused(format!("{} is over the approved payment cap", amount)))
+        }
+    }
+
+    fn add(&mut self, request: &PayeeRequest, approved_by: &str) -> Payee {
+        let payee = Payee {
+            name: request.name.clone(),
+            agent_id: request.agent_id.clone(),
+            max_payment: request.max_payment,
+            approved_by: approved_by.to_string(),
+            added_at: Utc::now(),
+        };
+        let book = self
+            .books
+            .entry(request.book.clone())
+            .or_insert_with(|| AddressBook { name: request.book.clone(), payees: BTreeMap::new() });
+        book.payees.insert(payee.name.clone(), payee.clone());
+        payee
+    }
+
+    /// Take out a pending request for a decision by `decided_by`.
+    fn decide(&mut self, request_id: &str, decided_by: &str) -> Result<&mut PayeeRequest, TreasuryError> {
+        let not_found = || TreasuryError::PayeeRequestNotFound { request_id: request_id.to_string() };
+        let request = self.requests.get_mut(request_id).filter(|r| r.status == PayeeRequestStatus::Pending);
+        let request = request.ok_or_else(not_found)?;
+        if request.requested_by == decided_by {
+            return Err(TreasuryError::PayeeNotAllowed {
+                payee: request.agent_id.clone(),
+                reason: "requester cannot approve its own request".to_string(),
+            });
+        }
+        request.decided_by = Some(decided_by.to_string());
+        Ok(request)
+    }
+}
+
+impl Treasury {
+    /// Enforce `policy` on payees.
+    pub fn with_payee_policy(mut self, policy: PayeePolicy) -> Self {
+        self.payees.set_policy(policy);
+        self
+    }
+
+    /// Send payee requests needing approval to `escalation`.
+    pub fn with_payee_escalation(mut self, escalation: impl PayeeEscalation + 'static) -> Self {
+        self.payees.escalation = Some(Arc::new(escalation));
+        self
+    }
+
+    pub fn payee_policy(&self) -> &PayeePolicy {
+        &self.payees.policy
+    }
+
+    /// Never pay `agent_id` again, even if it is in an address book.
+    pub fn deny_payee(&mut self, agent_id: &str) {
+        tracing::warn!(tenant = %self.tenant_id, agent_id, "Payee denied");
+        self.payees.policy.denied.insert(agent_id.to_string());
+    }
+
+    pub fn address_book(&self, book: &str) -> Option<&AddressBook> {
+        self.payees.books.get(book)
+    }
+
+    /// Ask to add `agent_id` to `book` as `name`. The payee is added at once
+    /// unless the policy needs approval for `max_payment`, in which case the
+    /// request stays pending and is escalated.
+    pub fn request_payee(
+        &mut self,
+        book: &str,
+        name: &str,
+        agent_id: &str,
+        max_payment: Option<Money>,
+        requested_by: &str,
+    ) -> Result<PayeeRequest, TreasuryError> {
+        if self.payees.policy.denied.contains(agent_id) {
+            return Err(TreasuryError::PayeeNotAllowed {
+                payee: agent_id.to_string(),
+                reason: "payee is denied".to_string(),
+            });
+        }
+        let mut request = PayeeRequest {
+            id: uuid::Uuid::new_v4().to_string(),
+            book: book.to_string(),
+            name: name.to_string(),
+            agent_id: agent_id.to_string(),
+            max_payment,
+            requested_by: requested_by.to_string(),
+            requested_at: Utc::now(),
+            status: PayeeRequestStatus::Pending,
+            escalation_id: None,
+            decided_by: None,
+        };
+
+        if !self.payees.policy.needs_approval(max_payment) {
+            self.payees.add(&request, requested_by);
+            request.status = PayeeRequestStatus::Approved;
+            request.decided_by = Some(requested_by.to_string());
+            return Ok(request);
+        }
+
+        if let Some(escalation) = &self.payees.escalation {
+            match escalation.escalate(&request) {
+                Ok(id) => request.escalation_id = Some(id),
+                // Still pending; it can be approved directly
+                Err(reason) => tracing::warn!(request_id = %request.id, %reason, "Payee escalation failed"),
+            }
+        }
+        tracing::info!(tenant = %self.tenant_id, book, name, agent_id, requested_by, "Payee approval requested");
+        self.payees.requests.insert(request.id.clone(), request.clone());
+        Ok(request)
+    }
+
+    pub fn payee_request(&self, request_id: &str) -> Option<&PayeeRequest> {
+        self.payees.requests.get(request_id)
+    }
+
+    /// Requests awaiting a decision.
+    pub fn pending_payee_requests(&self) -> impl Iterator<Item = &PayeeRequest> {
+        self.payees.requests.values().filter(|r| r.status == PayeeRequestStatus::Pending)
+    }
+
+    /// Approve a pending request and add its payee. The requester cannot
+    /// approve its own request.
+    pub fn approve_payee(&mut self, request_id: &str, approver: &str) -> Result<Payee, TreasuryError> {
+        let request = self.payees.decide(request_id, approver)?;
+        request.status = PayeeRequestStatus::Approved;
+        let request = request.clone();
+        tracing::info!(tenant = %self.tenant_id, request_id, approver, agent_id = %request.agent_id, "Payee approved");
+        Ok(self.payees.add(&request, approver))
+    }
+
+    pub fn reject_payee(&mut self, request_id: &str, approver: &str) -> Result<(), TreasuryError> {
+        self.payees.decide(request_id, approver)?.status = PayeeRequestStatus::Rejected;
+        Ok(())
+    }
+
+    /// Remove a payee from a book.
+    pub fn remove_payee(&mut self, book: &str, name: &str) -> Option<Payee> {
+        self.payees.books.get_mut(book)?.payees.remove(name)
+    }
+
+    /// Pay the payee named `name` in `book`.
+    pub fn pay_named(&mut self, from_agent: &str, book: &str, name: &str, amount: Money) -> Result<String, TreasuryError> {
+        let agent_id = self
+            .address_book(book)
+            .and_then(|b| b.get(name))
+            .map(|p| p.agent_id.clone())
+            .ok_or_else(|| TreasuryError::PayeeNotAllowed {
+                payee: format!("{}/{}", book, name),
+                reason: "no such address book entry".to_string(),
+            })?;
+        self.pay(from_agent, &agent_id, amount)
+    }
+
+    pub(crate) fn check_payee(&self, payee: &str, amount: Money) -> Result<(), TreasuryError> {
+        self.payees.check(payee, amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_threshold_by_currency() {
+        let usd = |a| Money::parse(a, Currency::Usd).unwrap();
+        let policy = PayeePolicy::allow_listed().with_approval_threshold(usd("100"));
+        assert!(!policy.needs_approval(Some(usd("100"))));
+        assert!(policy.needs_approval(Some(usd("100.01"))));
+        assert!(policy.needs_approval(None));
+        // No threshold in EUR
+        assert!(!policy.needs_approval(Some(Money::parse("1000", Currency::Eur).unwrap())));
+        assert!(!PayeePolicy::open().needs_approval(None));
+    }
+}
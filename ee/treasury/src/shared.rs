@@ -0,0 +1,635 @@
+//! Shared Treasury
+//!
+//! Async treasury for concurrent callers such as the gateway. Instead of a
+//! `&mut Treasury` behind one global mutex, every wallet, escrow and channel
+//! has its own lock, so payments between unrelated agents run in parallel
+//! and only operations touching the same entities wait for each other.
+//!
+//! Lock ordering, which keeps concurrent operations from deadlocking:
+//! 1. Registries (ID to entity lock) are held only to look up or insert an
+//!    entry, never across an `.await` or while taking another lock.
+//! 2. An escrow or channel is locked before any wallet.
+//! 3. Several wallets are locked in ascending agent ID order.
+//! 4. Signing approvals, budgets, the payee policy, fee volumes, the ledger
+//!    and the payment log are held only briefly, never while taking another lock or
+//!    across an `.await`.
+//!
+//! Every movement is posted to a double-entry [`Ledger`] like
+//! [`Treasury`]'s, which [`SharedTreasury::reconcile`] checks against the
+//! wallets, escrows and channels.
+//!
+//! Threshold signing works as on [`Treasury`]: an approving session is
+//! claimed before funds move and handed back if the operation fails.
+//! Budgets likewise count a payment before funds move and stop counting it
+//! if the payment fails.
+//! [`BlockingTreasury`] wraps the same state in a synchronous API for tests
+//! and tools.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let treasury = SharedTreasury::new("org-1")?;
+//! treasury.register_agent("alice");
+//! treasury.register_agent("bob");
+//! treasury.deposit("alice", Money::parse("100", Currency::Usd)?).await?;
+//!
+//! // Clones share state; spawn freely
+//! let handle = treasury.clone();
+//! let amount = Money::parse("5", Currency::Usd)?;
+//! tokio::spawn(async move { handle.pay("alice", "bob", amount).await });
+//! ```
+
+use crate::budget::Budgets;
+use crate::fees::Fees;
+use crate::ledger::Holdings;
+use crate::payees::Payees;
+use crate::threshold::Approvals;
+use crate::{
+    license, Account, AgentBudget, AgentWallet, Currency, Escrow, EscrowStatus, FeeSchedule, Ledger, Money, PaymentChannel,
+    PaymentRequest, PayeePolicy, Reconciliation, SignerSet, SigningAuditRecord, SigningSession, TreasuryError,
+    TreasuryEventKind, TreasuryEvents, TreasuryOperation, WalletAccess,
+};
+use agentkern_fsm::State;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::{Mutex as EntityLock, OwnedMutexGuard};
+
+type Registry<T> = RwLock<HashMap<String, Arc<EntityLock<T>>>>;
+
+struct Inner {
+    tenant_id: String,
+    wallets: Registry<AgentWallet>,
+    escrows: Registry<Escrow>,
+    channels: Registry<PaymentChannel>,
+    payments: Mutex<Vec<PaymentRequest>>,
+    approvals: Mutex<Approvals>,
+    budgets: Mutex<Budgets>,
+    payees: RwLock<Payees>,
+    fees: Mutex<Fees>,
+    ledger: Mutex<Ledger>,
+    events: RwLock<TreasuryEvents>,
+}
+
+/// Wallets locked together, in lock order.
+struct Wallets(Vec<OwnedMutexGuard<AgentWallet>>);
+
+impl Wallets {
+    fn get(&mut self, agent_id: &str) -> &mut AgentWallet {
+        self.0.iter_mut().find(|w| w.agent_id == agent_id).expect("wallet locked")
+    }
+}
+
+impl WalletAccess for Wallets {
+    fn wallet(&mut self, agent_id: &str) -> Result<&mut AgentWallet, TreasuryError> {
+        Ok(self.get(agent_id))
+    }
+}
+
+/// Treasury with per-entity locks (requires enterprise license). Clones share state.
+#[derive(Clone)]
+pub struct SharedTreasury {
+    inner: Arc<Inner>,
+}
+
+impl SharedTreasury {
+    /// Create a new treasury (requires enterprise license).
+    pub fn new(tenant_id: impl Into<String>) -> Result<Self, license::LicenseError> {
+        license::require("TREASURY")?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                tenant_id: tenant_id.into(),
+                wallets: RwLock::default(),
+                escrows: RwLock::default(),
+                channels: RwLock::default(),
+                payments: Mutex::default(),
+                approvals: Mutex::default(),
+                budgets: Mutex::default(),
+                payees: RwLock::default(),
+                fees: Mutex::default(),
+                ledger: Mutex::default(),
+                events: RwLock::default(),
+            }),
+        })
+    }
+
+    /// Require threshold signatures for high-value operations.
+    pub fn with_signers(self, signers: SignerSet) -> Self {
+        self.inner.approvals.lock().unwrap().set_signers(signers);
+        self
+    }
+
+    /// Charge fees on payments and escrow releases. Registers the fee
+    /// wallet.
+    pub fn with_fees(self, schedule: FeeSchedule) -> Self {
+        self.register_agent(schedule.fee_wallet());
+        *self.inner.fees.lock().unwrap() = Fees::new(schedule);
+        self
+    }
+
+    /// Enforce `budget` on payments and channels funded by `agent_id`.
+    pub fn with_budget(self, agent_id: &str, budget: AgentBudget) -> Self {
+        self.inner.budgets.lock().unwrap().set(agent_id, budget);
+        self
+    }
+
+    /// Enforce `policy` on payees. Allow-listed mode pays no one, as the
+    /// shared treasury has no address books.
+    pub fn with_payee_policy(self, policy: PayeePolicy) -> Self {
+        self.inner.payees.write().unwrap().set_policy(policy);
+        self
+    }
+
+    /// Continue `ledger`, e.g. one exported before a restart.
+    pub fn with_ledger(self, ledger: Ledger) -> Self {
+        *self.inner.ledger.lock().unwrap() = ledger;
+        self
+    }
+
+    /// Emit events on `events`, e.g. a channel shared with other treasuries.
+    pub fn with_events(self, events: TreasuryEvents) -> Self {
+        *self.inner.events.write().unwrap() = events;
+        self
+    }
+
+    pub fn events(&self) -> TreasuryEvents {
+        self.inner.events.read().unwrap().clone()
+    }
+
+    pub fn tenant_id(&self) -> &str {
+        &self.inner.tenant_id
+    }
+
+    /// Register an agent wallet.
+    pub fn register_agent(&self, agent_id: &str) {
+        self.inner
+            .wallets
+            .write()
+            .unwrap()
+            .entry(agent_id.to_string())
+            .or_insert_with(|| Arc::new(EntityLock::new(AgentWallet::new(agent_id))));
+    }
+
+    /// Deposit funds to an agent.
+    pub async fn deposit(&self, agent_id: &str, amount: Money) -> Result<(), TreasuryError> {
+        let wallet = self.wallet_lock(agent_id)?;
+        let mut wallet = wallet.lock().await;
+        wallet.deposit(amount)?;
+        self.post("deposit", None, Account::External, Account::Wallet(agent_id.to_string()), amount);
+        Ok(())
+    }
+
+    /// Get agent balance.
+    pub async fn balance(&self, agent_id: &str, currency: Currency) -> Result<Money, TreasuryError> {
+        Ok(self.wallet_lock(agent_id)?.lock().await.balance(currency))
+    }
+
+    /// Snapshot of an agent's wallet.
+    pub async fn wallet(&self, agent_id: &str) -> Option<AgentWallet> {
+        let wallet = self.wallet_lock(agent_id).ok()?;
+        let wallet = wallet.lock().await;
+        Some(wallet.clone())
+    }
+
+    /// Pay from one agent to another.
+    pub async fn pay(&self, from_agent: &str, to_agent: &str, amount: Money) -> Result<String, TreasuryError> {
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+        let (currency, units) = (amount.currency(), amount.units());
+
+        self.check_payee(to_agent, amount)?;
+        let now = Utc::now();
+        self.inner.budgets.lock().unwrap().reserve(from_agent, to_agent, amount, now)?;
+        let operation = TreasuryOperation::payment(from_agent, to_agent, amount);
+        let approval = self
+            .inner
+            .approvals
+            .lock()
+            .unwrap()
+            .claim(&operation)
+            .inspect_err(|_| self.inner.budgets.lock().unwrap().release(from_agent, to_agent, amount, now))?;
+        let fee_wallet = self.inner.fees.lock().unwrap().schedule().map(|s| s.fee_wallet().to_string());
+        let mut request = PaymentRequest::completed(from_agent, to_agent, amount);
+        let moved = async {
+            let mut agents = vec![from_agent, to_agent];
+            agents.extend(fee_wallet.as_deref());
+            let mut wallets = self.lock_wallets(&agents).await?;
+            if from_agent != to_agent && !wallets.get(to_agent).can_credit(currency, units) {
+                return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+            }
+            // Quoted under the payee's lock, so its volume can't change before it is recorded
+            let fee = self.inner.fees.lock().unwrap().quote(to_agent, amount);
+            if let Some(fee) = &fee
+                && !wallets.get(&fee.fee_wallet).can_credit(currency, fee.fee.units())
+            {
+                return Err(TreasuryError::InvalidAmount { amount: fee.fee.to_string() });
+            }
+            wallets.get(from_agent).withdraw_units(currency, units)?;
+            wallets.get(to_agent).credit_units(currency, units)?;
+            if let Some(fee) = &fee {
+                wallets.get(to_agent).withdraw(fee.fee)?;
+                wallets.get(&fee.fee_wallet).deposit(fee.fee)?;
+                self.inner.fees.lock().unwrap().record(to_agent, fee);
+            }
+            let mut ledger = self.inner.ledger.lock().unwrap();
+            let (from, to) = (Account::Wallet(from_agent.to_string()), Account::Wallet(to_agent.to_string()));
+            ledger.post("payment", Some(&request.id), &[(from, to, amount)]);
+            if let Some(fee) = &fee {
+                ledger.post_fee(to_agent, fee, &request.id);
+            }
+            Ok(fee)
+        }
+        .await;
+        self.settle_approval(approval, &moved);
+        self.settle_budget(from_agent, to_agent, amount, now, &moved);
+        let events = self.events();
+        let fee = events.on_shortfall(&self.inner.tenant_id, from_agent, "payment", moved)?;
+
+        request.fee = fee;
+        let payment_id = request.id.clone();
+        self.inner.payments.lock().unwrap().push(request);
+        events.emit(&self.inner.tenant_id, TreasuryEventKind::PaymentCompleted {
+            payment_id: payment_id.clone(),
+            from_agent: from_agent.to_string(),
+            to_agent: to_agent.to_string(),
+            amount,
+        });
+        Ok(payment_id)
+    }
+
+    /// Payment log, oldest first.
+    pub fn payments(&self) -> Vec<PaymentRequest> {
+        self.inner.payments.lock().unwrap().clone()
+    }
+
+    /// Create an escrow.
+    pub async fn create_escrow(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        amount: Money,
+        condition: &str,
+        duration_hours: i64,
+    ) -> Result<String, TreasuryError> {
+        // Recipient needs a wallet to be paid into
+        self.wallet_lock(to_agent)?;
+        self.check_payee(to_agent, amount)?;
+        let locked = self.wallet_lock(from_agent)?.lock().await.withdraw(amount);
+        self.events().on_shortfall(&self.inner.tenant_id, from_agent, "create_escrow", locked)?;
+
+        let escrow = Escrow::new(from_agent, to_agent, amount, condition, duration_hours);
+        let escrow_id = escrow.id.clone();
+        self.inner.escrows.write().unwrap().insert(escrow_id.clone(), Arc::new(EntityLock::new(escrow)));
+        self.post("escrow_create", Some(&escrow_id), Account::Wallet(from_agent.to_string()), Account::Escrow(escrow_id.clone()), amount);
+        Ok(escrow_id)
+    }
+
+    /// Snapshot of an escrow.
+    pub async fn escrow(&self, escrow_id: &str) -> Option<Escrow> {
+        let escrow = self.escrow_lock(escrow_id).ok()?;
+        let escrow = escrow.lock().await;
+        Some(escrow.clone())
+    }
+
+    /// Release escrow to recipient. Releases at or above the signer set's
+    /// limit need an approved signing session.
+    pub async fn release_escrow(&self, escrow_id: &str) -> Result<(), TreasuryError> {
+        let mut escrow = self.escrow_lock(escrow_id)?.lock_owned().await;
+        let operation = escrow.release_operation()?;
+        let approval = self.inner.approvals.lock().unwrap().claim(&operation)?;
+        let fee_wallet = self.inner.fees.lock().unwrap().schedule().map(|s| s.fee_wallet().to_string());
+        let released = async {
+            let mut agents = vec![escrow.to_agent.as_str()];
+            agents.extend(fee_wallet.as_deref());
+            let mut wallets = self.lock_wallets(&agents).await?;
+            let (amount, fee) = escrow.pay_out(None, &mut wallets, &mut self.inner.fees.lock().unwrap())?;
+            let mut ledger = self.inner.ledger.lock().unwrap();
+            let (escrow_account, to) = (Account::Escrow(escrow_id.to_string()), Account::Wallet(escrow.to_agent.clone()));
+            ledger.post("escrow_release", Some(escrow_id), &[(escrow_account, to, amount)]);
+            if let Some(fee) = &fee {
+                ledger.post_fee(&escrow.to_agent, fee, escrow_id);
+            }
+            Ok(amount)
+        }
+        .await;
+        self.settle_approval(approval, &released);
+        let amount = released?;
+        self.events().emit(&self.inner.tenant_id, TreasuryEventKind::EscrowReleased {
+            escrow_id: escrow_id.to_string(),
+            from_agent: escrow.from_agent.clone(),
+            to_agent: escrow.to_agent.clone(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /// Refund escrow to sender.
+    pub async fn refund_escrow(&self, escrow_id: &str) -> Result<(), TreasuryError> {
+        let mut escrow = self.escrow_lock(escrow_id)?.lock_owned().await;
+        escrow.status.check_transition(&EscrowStatus::Refunded)?;
+        let wallet = self.wallet_lock(&escrow.from_agent)?;
+        let mut wallet = wallet.lock().await;
+        let amount = escrow.remaining();
+        wallet.deposit(amount)?;
+        escrow.refund()?;
+        self.post("escrow_refund", Some(escrow_id), Account::Escrow(escrow_id.to_string()), Account::Wallet(escrow.from_agent.clone()), amount);
+        Ok(())
+    }
+
+    /// Create a payment channel.
+    pub async fn open_channel(
+        &self,
+        party_a: &str,
+        party_b: &str,
+        capacity: Money,
+    ) -> Result<String, TreasuryError> {
+        // Both parties need wallets to settle into
+        self.wallet_lock(party_b)?;
+        let wallet = self.wallet_lock(party_a)?;
+        self.check_payee(party_b, capacity)?;
+        let now = Utc::now();
+        self.inner.budgets.lock().unwrap().reserve(party_a, party_b, capacity, now)?;
+        let operation = TreasuryOperation::channel_open(party_a, party_b, capacity);
+        let approval = self
+            .inner
+            .approvals
+            .lock()
+            .unwrap()
+            .claim(&operation)
+            .inspect_err(|_| self.inner.budgets.lock().unwrap().release(party_a, party_b, capacity, now))?;
+        let locked = wallet.lock().await.withdraw(capacity);
+        self.settle_approval(approval, &locked);
+        self.settle_budget(party_a, party_b, capacity, now, &locked);
+        self.events().on_shortfall(&self.inner.tenant_id, party_a, "open_channel", locked)?;
+
+        let channel = PaymentChannel::new(party_a, party_b, capacity);
+        let channel_id = channel.id.clone();
+        self.inner.channels.write().unwrap().insert(channel_id.clone(), Arc::new(EntityLock::new(channel)));
+        self.post("channel_open", Some(&channel_id), Account::Wallet(party_a.to_string()), Account::Channel(channel_id.clone()), capacity);
+        self.events().emit(&self.inner.tenant_id, TreasuryEventKind::ChannelOpened {
+            channel_id: channel_id.clone(),
+            party_a: party_a.to_string(),
+            party_b: party_b.to_string(),
+            capacity,
+        });
+        Ok(channel_id)
+    }
+
+    /// Transfer within a channel.
+    pub async fn channel_transfer(&self, channel_id: &str, from_a_to_b: bool, amount: Money) -> Result<(), TreasuryError> {
+        let mut channel = self.channel_lock(channel_id)?.lock_owned().await;
+        if from_a_to_b {
+            channel.transfer_a_to_b(amount)
+        } else {
+            channel.transfer_b_to_a(amount)
+        }
+    }
+
+    /// Close a payment channel. Signed channels close through [`crate::Treasury::request_close`].
+    pub async fn close_channel(&self, channel_id: &str) -> Result<(Money, Money), TreasuryError> {
+        let mut channel = self.channel_lock(channel_id)?.lock_owned().await;
+        if !channel.is_open {
+            return Err(TreasuryError::ChannelNotOpen);
+        }
+        if channel.keys.is_some() {
+            return Err(TreasuryError::InvalidChannelState { reason: "close signed channels with request_close".to_string() });
+        }
+
+        let (currency, units_a, units_b) = (channel.currency, channel.balance_a, channel.balance_b);
+        let (party_a, party_b) = (channel.party_a.clone(), channel.party_b.clone());
+        let mut wallets = self.lock_wallets(&[&party_a, &party_b]).await?;
+        let settles = if party_a == party_b {
+            units_a.checked_add(units_b).is_some_and(|units| wallets.get(&party_a).can_credit(currency, units))
+        } else {
+            wallets.get(&party_a).can_credit(currency, units_a) && wallets.get(&party_b).can_credit(currency, units_b)
+        };
+        if !settles {
+            return Err(TreasuryError::PaymentFailed { reason: "Cannot settle channel balances".to_string() });
+        }
+
+        let (balance_a, balance_b) = channel.close();
+        wallets.get(&party_a).credit_units(currency, units_a)?;
+        wallets.get(&party_b).credit_units(currency, units_b)?;
+        let channel = Account::Channel(channel_id.to_string());
+        self.inner.ledger.lock().unwrap().post("channel_settle", Some(channel_id), &[
+            (channel.clone(), Account::Wallet(party_a), balance_a),
+            (channel, Account::Wallet(party_b), balance_b),
+        ]);
+        Ok((balance_a, balance_b))
+    }
+
+    /// Snapshot of a payment channel.
+    pub async fn channel(&self, channel_id: &str) -> Option<PaymentChannel> {
+        let channel = self.channel_lock(channel_id).ok()?;
+        let channel = channel.lock().await;
+        Some(channel.clone())
+    }
+
+    /// Snapshot of the double-entry ledger.
+    pub fn ledger(&self) -> Ledger {
+        self.inner.ledger.lock().unwrap().clone()
+    }
+
+    /// Check the ledger balances and matches every wallet, channel and
+    /// escrow. Entities are read one at a time, so an operation in flight
+    /// can show up as drift; reconcile while the treasury is quiet.
+    pub async fn reconcile(&self) -> Reconciliation {
+        let mut holdings = Holdings::default();
+        let channels: Vec<_> = self.inner.channels.read().unwrap().iter().map(|(id, c)| (id.clone(), c.clone())).collect();
+        for (channel_id, channel) in channels {
+            let channel = channel.lock().await;
+            if channel.is_open {
+                holdings.hold(Account::Channel(channel_id), channel.currency, channel.capacity);
+            }
+        }
+        let escrows: Vec<_> = self.inner.escrows.read().unwrap().iter().map(|(id, e)| (id.clone(), e.clone())).collect();
+        for (escrow_id, escrow) in escrows {
+            let escrow = escrow.lock().await;
+            if escrow.status == EscrowStatus::Locked {
+                holdings.hold(Account::Escrow(escrow_id), escrow.amount.currency(), escrow.remaining().units());
+            }
+        }
+        let wallets: Vec<_> = self.inner.wallets.read().unwrap().iter().map(|(id, w)| (id.clone(), w.clone())).collect();
+        for (agent_id, wallet) in wallets {
+            for (currency, units) in &wallet.lock().await.balances {
+                holdings.hold(Account::Wallet(agent_id.clone()), *currency, *units);
+            }
+        }
+        self.inner.ledger.lock().unwrap().reconcile(&self.inner.tenant_id, holdings)
+    }
+
+    /// Open a session to approve releasing an escrow.
+    pub async fn open_escrow_release(&self, escrow_id: &str) -> Result<SigningSession, TreasuryError> {
+        let operation = self.escrow_lock(escrow_id)?.lock().await.release_operation()?;
+        self.inner.approvals.lock().unwrap().open(operation)
+    }
+
+    /// Open a session to approve a payment.
+    pub fn open_payment(&self, from_agent: &str, to_agent: &str, amount: Money) -> Result<SigningSession, TreasuryError> {
+        let operation = TreasuryOperation::payment(from_agent, to_agent, amount);
+        self.inner.approvals.lock().unwrap().open(operation)
+    }
+
+    /// Open a session to approve funding a payment channel.
+    pub fn open_channel_funding(&self, party_a: &str, party_b: &str, capacity: Money) -> Result<SigningSession, TreasuryError> {
+        let operation = TreasuryOperation::channel_open(party_a, party_b, capacity);
+        self.inner.approvals.lock().unwrap().open(operation)
+    }
+
+    /// Add a signer's signature over [`SigningSession::signing_bytes`].
+    /// Returns how many signatures the session has.
+    pub fn sign(&self, session_id: &str, signer_id: &str, signature: &[u8]) -> Result<usize, TreasuryError> {
+        self.inner.approvals.lock().unwrap().sign(session_id, signer_id, signature)
+    }
+
+    /// Get a signing session.
+    pub fn signing_session(&self, session_id: &str) -> Option<SigningSession> {
+        self.inner.approvals.lock().unwrap().session(session_id).cloned()
+    }
+
+    /// Every signer's participation, oldest first.
+    pub fn signing_audit(&self) -> Vec<SigningAuditRecord> {
+        self.inner.approvals.lock().unwrap().audit().to_vec()
+    }
+
+    /// Close a claimed approval after success, or hand it back after failure.
+    fn settle_approval<T>(&self, approval: Option<SigningSession>, result: &Result<T, TreasuryError>) {
+        let mut approvals = self.inner.approvals.lock().unwrap();
+        match result {
+            Ok(_) => approvals.executed(approval),
+            Err(_) => approvals.restore(approval),
+        }
+    }
+
+    /// Post a movement between two accounts.
+    fn post(&self, memo: &str, reference: Option<&str>, debit: Account, credit: Account, amount: Money) {
+        self.inner.ledger.lock().unwrap().post(memo, reference, &[(debit, credit, amount)]);
+    }
+
+    fn check_payee(&self, payee: &str, amount: Money) -> Result<(), TreasuryError> {
+        self.inner.payees.read().unwrap().check(payee, amount)
+    }
+
+    /// Stop counting a spend reserved at `at` if the payment failed.
+    fn settle_budget<T>(&self, from: &str, to: &str, amount: Money, at: DateTime<Utc>, result: &Result<T, TreasuryError>) {
+        if result.is_err() {
+            self.inner.budgets.lock().unwrap().release(from, to, amount, at);
+        }
+    }
+
+    /// Lock several wallets in ascending agent ID order; duplicates are locked once.
+    async fn lock_wallets(&self, agent_ids: &[&str]) -> Result<Wallets, TreasuryError> {
+        let mut agent_ids = agent_ids.to_vec();
+        agent_ids.sort_unstable();
+        agent_ids.dedup();
+        // Resolve every entry before taking the first lock
+        let entries = agent_ids.iter().map(|id| self.wallet_lock(id)).collect::<Result<Vec<_>, _>>()?;
+        let mut guards = Vec::with_capacity(entries.len());
+        for entry in entries {
+            guards.push(entry.lock_owned().await);
+        }
+        Ok(Wallets(guards))
+    }
+
+    fn wallet_lock(&self, agent_id: &str) -> Result<Arc<EntityLock<AgentWallet>>, TreasuryError> {
+        self.inner.wallets.read().unwrap().get(agent_id).cloned().ok_or(TreasuryError::AgentNotFound {
+            agent_id: agent_id.to_string(),
+        })
+    }
+
+    fn escrow_lock(&self, escrow_id: &str) -> Result<Arc<EntityLock<Escrow>>, TreasuryError> {
+        self.inner.escrows.read().unwrap().get(escrow_id).cloned().ok_or(TreasuryError::PaymentFailed {
+            reason: "Escrow not found".to_string(),
+        })
+    }
+
+    fn channel_lock(&self, channel_id: &str) -> Result<Arc<EntityLock<PaymentChannel>>, TreasuryError> {
+        self.inner.channels.read().unwrap().get(channel_id).cloned().ok_or(TreasuryError::ChannelNotOpen)
+    }
+}
+
+/// Synchronous facade over a [`SharedTreasury`], for tests and tools.
+///
+/// Each call blocks the current thread, so it must not be used from inside
+/// an async runtime.
+pub struct BlockingTreasury {
+    shared: SharedTreasury,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BlockingTreasury {
+    pub fn new(shared: SharedTreasury) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        Ok(Self { shared, runtime })
+    }
+
+    /// The async treasury behind this facade.
+    pub fn shared(&self) -> &SharedTreasury {
+        &self.shared
+    }
+
+    pub fn register_agent(&self, agent_id: &str) {
+        self.shared.register_agent(agent_id)
+    }
+
+    pub fn deposit(&self, agent_id: &str, amount: Money) -> Result<(), TreasuryError> {
+        self.runtime.block_on(self.shared.deposit(agent_id, amount))
+    }
+
+    pub fn balance(&self, agent_id: &str, currency: Currency) -> Result<Money, TreasuryError> {
+        self.runtime.block_on(self.shared.balance(agent_id, currency))
+    }
+
+    pub fn pay(&self, from_agent: &str, to_agent: &str, amount: Money) -> Result<String, TreasuryError> {
+        self.runtime.block_on(self.shared.pay(from_agent, to_agent, amount))
+    }
+
+    pub fn create_escrow(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        amount: Money,
+        condition: &str,
+        duration_hours: i64,
+    ) -> Result<String, TreasuryError> {
+        self.runtime.block_on(self.shared.create_escrow(from_agent, to_agent, amount, condition, duration_hours))
+    }
+
+    pub fn open_escrow_release(&self, escrow_id: &str) -> Result<SigningSession, TreasuryError> {
+        self.runtime.block_on(self.shared.open_escrow_release(escrow_id))
+    }
+
+    pub fn release_escrow(&self, escrow_id: &str) -> Result<(), TreasuryError> {
+        self.runtime.block_on(self.shared.release_escrow(escrow_id))
+    }
+
+    pub fn sign(&self, session_id: &str, signer_id: &str, signature: &[u8]) -> Result<usize, TreasuryError> {
+        self.shared.sign(session_id, signer_id, signature)
+    }
+
+    pub fn refund_escrow(&self, escrow_id: &str) -> Result<(), TreasuryError> {
+        self.runtime.block_on(self.shared.refund_escrow(escrow_id))
+    }
+
+    pub fn open_channel(&self, party_a: &str, party_b: &str, capacity: Money) -> Result<String, TreasuryError> {
+        self.runtime.block_on(self.shared.open_channel(party_a, party_b, capacity))
+    }
+
+    pub fn channel_transfer(&self, channel_id: &str, from_a_to_b: bool, amount: Money) -> Result<(), TreasuryError> {
+        self.runtime.block_on(self.shared.channel_transfer(channel_id, from_a_to_b, amount))
+    }
+
+    pub fn close_channel(&self, channel_id: &str) -> Result<(Money, Money), TreasuryError> {
+        self.runtime.block_on(self.shared.close_channel(channel_id))
+    }
+
+    pub fn ledger(&self) -> Ledger {
+        self.shared.ledger()
+    }
+
+    pub fn reconcile(&self) -> Reconciliation {
+        self.runtime.block_on(self.shared.reconcile())
+    }
+}
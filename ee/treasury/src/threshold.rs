@@ -0,0 +1,432 @@
+//! Threshold Signing
+//!
+//! High-value operations (escrow releases, payments and channel funding at
+//! or above a per-currency limit) need approvals from `t` of `n` registered signers
+//! instead of a single key. Signers are enrolled in a [`KeyCeremony`], each
+//! proving possession of its ed25519 key, which yields a [`SignerSet`].
+//! An operation is then opened as a [`SigningSession`]; signers sign the
+//! session's bytes, and the treasury executes the operation only once the
+//! threshold is met. Every signer's participation is kept in the signing
+//! audit trail.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut ceremony = KeyCeremony::new(2);
+//! for (id, key) in &signers {
+//!     let proof = key.sign(&ceremony.challenge(id, &key.verifying_key().to_bytes()));
+//!     ceremony.register(id, key.verifying_key().to_bytes(), &proof.to_bytes())?;
+//! }
+//! let mut treasury = Treasury::new("org-1")?
+//!     .with_signers(ceremony.finish()?.with_limit(Money::parse("10000", Currency::Usd)?));
+//!
+//! let session = treasury.open_escrow_release(&escrow_id)?;
+//! treasury.sign(&session.id, "cfo", &cfo_key.sign(&session.signing_bytes()).to_bytes())?;
+//! treasury.sign(&session.id, "treasurer", &treasurer_sig)?;
+//! treasury.release_escrow(&escrow_id)?;
+//! ```
+
+use crate::{Currency, Escrow, EscrowStatus, Money, Treasury, TreasuryError};
+use agentkern_fsm::State;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Domain separation for ceremony proofs and session signatures.
+const CEREMONY_DOMAIN: &[u8] = b"agentkern-treasury/ceremony/v1";
+const SESSION_DOMAIN: &[u8] = b"agentkern-treasury/threshold/v1";
+
+fn verify(key: &[u8; 32], message: &[u8], signature: &[u8]) -> Result<(), String> {
+    let key = VerifyingKey::from_bytes(key).map_err(|_| "invalid public key".to_string())?;
+    let signature = Signature::from_slice(signature).map_err(|_| "malformed signature".to_string())?;
+    key.verify(message, &signature).map_err(|_| "bad signature".to_string())
+}
+
+/// Enrolls signers and fixes the threshold.
+#[derive(Debug)]
+pub struct KeyCeremony {
+    id: String,
+    threshold: usize,
+    signers: BTreeMap<String, [u8; 32]>,
+}
+
+impl KeyCeremony {
+    /// Start a ceremony for a `threshold`-of-n signer set.
+    pub fn new(threshold: usize) -> Self {
+        Self { id: uuid::Uuid::new_v4().to_string(), threshold, signers: BTreeMap::new() }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Bytes a signer signs to prove it holds `key`.
+    pub fn challenge(&self, signer_id: &str, key: &[u8; 32]) -> Vec<u8> {
+        let mut bytes = CEREMONY_DOMAIN.to_vec();
+        for part in [self.id.as_bytes(), signer_id.as_bytes()] {
+            bytes.extend_from_slice(&(part.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(part);
+        }
+        bytes.extend_from_slice(key);
+        bytes
+    }
+
+    /// Enroll a signer, given its signature over [`KeyCeremony::challenge`].
+    pub fn register(&mut self, signer_id: &str, key: [u8; 32], proof: &[u8]) -> Result<(), TreasuryError> {
+        let invalid = |reason: String| TreasuryError::InvalidSigner { signer_id: signer_id.to_string(), reason };
+        if self.signers.contains_key(signer_id) {
+            return Err(invalid("already registered".to_string()));
+        }
+        if self.signers.values().any(|k| *k == key) {
+            return Err(invalid("key registered to another signer".to_string()));
+        }
+        verify(&key, &self.challenge(signer_id, &key), proof).map_err(invalid)?;
+        self.signers.insert(signer_id.to_string(), key);
+        Ok(())
+    }
+
+    /// Close enrollment. Needs at least two signers in the threshold and
+    /// enough signers to meet it.
+    pub fn finish(self) -> Result<SignerSet, TreasuryError> {
+        if self.threshold < 2 || self.threshold > self.signers.len() {
+            return Err(TreasuryError::InvalidSigner {
+                signer_id: String::new(),
+                reason: format!("threshold {} of {} signers", self.threshold, self.signers.len()),
+            });
+        }
+        tracing::info!(ceremony_id = %self.id, threshold = self.threshold, signers = self.signers.len(), "Key ceremony completed");
+        Ok(SignerSet {
+            ceremony_id: self.id,
+            threshold: self.threshold,
+            signers: self.signers,
+            limits: HashMap::new(),
+            session_ttl: chrono::Duration::hours(1),
+        })
+    }
+}
+
+/// Registered signers and when they are needed.
+#[derive(Debug, Clone)]
+pub struct SignerSet {
+    pub ceremony_id: String,
+    pub threshold: usize,
+    /// Public key per signer
+    pub signers: BTreeMap<String, [u8; 32]>,
+    /// Operations at or above these amounts (base units) need signatures
+    limits: HashMap<Currency, u128>,
+    session_ttl: chrono::Duration,
+}
+
+impl SignerSet {
+    /// Require signatures for operations of at least `limit` in its currency.
+    pub fn with_limit(mut self, limit: Money) -> Self {
+        self.limits.insert(limit.currency(), limit.units());
+        self
+    }
+
+    /// How long a session stays open for signatures (default 1 hour).
+    pub fn with_session_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+
+    /// Whether an operation of this size needs threshold signatures.
+    pub fn requires_signatures(&self, amount: Money) -> bool {
+        self.limits.get(&amount.currency()).is_some_and(|limit| amount.units() >= *limit)
+    }
+}
+
+/// What a signing session authorizes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationKind {
+    /// Release of an escrow, or of one of its milestones. Limits apply to
+    /// the escrow amount, so staging a release can't avoid signatures.
+    EscrowRelease {
+        escrow_id: String,
+        to_agent: String,
+        escrow_amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        milestone: Option<String>,
+    },
+    Payment { from_agent: String, to_agent: String },
+    /// Funding a payment channel, which lets `party_a` pay `party_b` up to
+    /// its capacity
+    ChannelOpen { party_a: String, party_b: String },
+}
+
+/// A high-value operation awaiting approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryOperation {
+    pub kind: OperationKind,
+    pub amount: Money,
+}
+
+impl TreasuryOperation {
+    pub fn payment(from_agent: &str, to_agent: &str, amount: Money) -> Self {
+        Self {
+            kind: OperationKind::Payment { from_agent: from_agent.to_string(), to_agent: to_agent.to_string() },
+            amount,
+        }
+    }
+
+    pub fn channel_open(party_a: &str, party_b: &str, capacity: Money) -> Self {
+        Self {
+            kind: OperationKind::ChannelOpen { party_a: party_a.to_string(), party_b: party_b.to_string() },
+            amount: capacity,
+        }
+    }
+
+    /// Amount compared against the signer set's limits.
+    pub fn threshold_amount(&self) -> Money {
+        match &self.kind {
+            OperationKind::EscrowRelease { escrow_amount, .. } => *escrow_amount,
+            OperationKind::Payment { .. } | OperationKind::ChannelOpen { .. } => self.amount,
+        }
+    }
+}
+
+impl Escrow {
+    /// The operation releasing what this escrow still holds. Fails unless it
+    /// is still locked.
+    pub fn release_operation(&self) -> Result<TreasuryOperation, TreasuryError> {
+        self.status.check_transition(&EscrowStatus::Released)?;
+        Ok(TreasuryOperation {
+            kind: OperationKind::EscrowRelease {
+                escrow_id: self.id.clone(),
+                to_agent: self.to_agent.clone(),
+                escrow_amount: self.amount,
+                milestone: None,
+            },
+            amount: self.remaining(),
+        })
+    }
+}
+
+/// Signatures collected for one operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningSession {
+    pub id: String,
+    pub operation: TreasuryOperation,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Signature per signer
+    pub signatures: BTreeMap<String, Vec<u8>>,
+}
+
+impl SigningSession {
+    /// Bytes each signer signs: the session ID and the operation.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = SESSION_DOMAIN.to_vec();
+        bytes.extend_from_slice(&(self.id.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(self.id.as_bytes());
+        bytes.extend(serde_json::to_vec(&self.operation).expect("operation serializes"));
+        bytes
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.expires_at
+    }
+}
+
+/// What happened in a signing session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SigningEvent {
+    Opened,
+    Signed,
+    Rejected { reason: String },
+    /// Operation executed with these signers' approvals
+    Executed { signers: Vec<String> },
+}
+
+/// One entry in the signing audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningAuditRecord {
+    pub session_id: String,
+    pub signer_id: Option<String>,
+    pub event: SigningEvent,
+    pub operation: TreasuryOperation,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Signer set, open sessions and the signing audit trail, shared by
+/// [`Treasury`] and [`crate::SharedTreasury`].
+#[derive(Debug, Default)]
+pub(crate) struct Approvals {
+    signers: Option<SignerSet>,
+    sessions: HashMap<String, SigningSession>,
+    audit: Vec<SigningAuditRecord>,
+}
+
+impl Approvals {
+    pub(crate) fn set_signers(&mut self, signers: SignerSet) {
+        self.signers = Some(signers);
+    }
+
+    pub(crate) fn open(&mut self, operation: TreasuryOperation) -> Result<SigningSession, TreasuryError> {
+        let ttl = self.signer_set()?.session_ttl;
+        let now = Utc::now();
+        let session = SigningSession {
+            id: uuid::Uuid::new_v4().to_string(),
+            operation,
+            created_at: now,
+            expires_at: now + ttl,
+            signatures: BTreeMap::new(),
+        };
+        self.record(&session, None, SigningEvent::Opened);
+        self.sessions.insert(session.id.clone(), session.clone());
+        Ok(session)
+    }
+
+    pub(crate) fn sign(&mut self, session_id: &str, signer_id: &str, signature: &[u8]) -> Result<usize, TreasuryError> {
+        let key = self.signer_set()?.signers.get(signer_id).copied();
+        let session = self
+            .sessions
+            .get(session_id)
+            .filter(|s| !s.is_expired())
+            .cloned()
+            .ok_or(TreasuryError::SigningSessionNotFound { session_id: session_id.to_string() })?;
+
+        let checked = match key {
+            None => Err("not a registered signer".to_string()),
+            Some(key) => verify(&key, &session.signing_bytes(), signature),
+        };
+        if let Err(reason) = checked {
+            tracing::warn!(session_id, signer_id, reason = %reason, "Threshold signature rejected");
+            self.record(&session, Some(signer_id), SigningEvent::Rejected { reason: reason.clone() });
+            return Err(TreasuryError::InvalidSigner { signer_id: signer_id.to_string(), reason });
+        }
+
+        self.record(&session, Some(signer_id), SigningEvent::Signed);
+        let session = self.sessions.get_mut(session_id).expect("session checked above");
+        session.signatures.insert(signer_id.to_string(), signature.to_vec());
+        Ok(session.signatures.len())
+    }
+
+    pub(crate) fn session(&self, session_id: &str) -> Option<&SigningSession> {
+        self.sessions.get(session_id)
+    }
+
+    pub(crate) fn audit(&self) -> &[SigningAuditRecord] {
+        &self.audit
+    }
+
+    fn signer_set(&self) -> Result<&SignerSet, TreasuryError> {
+        self.signers.as_ref().ok_or(TreasuryError::InvalidSigner {
+            signer_id: String::new(),
+            reason: "no signer set configured".to_string(),
+        })
+    }
+
+    /// The session approving `operation`, if it needs one. Errors when the
+    /// operation needs signatures that have not been collected.
+    pub(crate) fn approval_for(&self, operation: &TreasuryOperation) -> Result<Option<String>, TreasuryError> {
+        let Some(signers) = &self.signers else {
+            return Ok(None);
+        };
+        if !signers.requires_signatures(operation.threshold_amount()) {
+            return Ok(None);
+        }
+        let sessions = self.sessions.values().filter(|s| s.operation == *operation && !s.is_expired());
+        let best = sessions.max_by_key(|s| s.signatures.len());
+        match best {
+            Some(session) if session.signatures.len() >= signers.threshold => Ok(Some(session.id.clone())),
+            best => Err(TreasuryError::SignaturesRequired {
+                required: signers.threshold,
+                collected: best.map_or(0, |s| s.signatures.len()),
+            }),
+        }
+    }
+
+    /// Like [`Approvals::approval_for`], but takes the approving session out
+    /// so no concurrent operation can use it. Hand it back with
+    /// [`Approvals::restore`] if the operation fails, or
+    /// [`Approvals::executed`] once it has run.
+    pub(crate) fn claim(&mut self, operation: &TreasuryOperation) -> Result<Option<SigningSession>, TreasuryError> {
+        Ok(self.approval_for(operation)?.and_then(|id| self.sessions.remove(&id)))
+    }
+
+    pub(crate) fn restore(&mut self, session: Option<SigningSession>) {
+        if let Some(session) = session {
+            self.sessions.insert(session.id.clone(), session);
+        }
+    }
+
+    pub(crate) fn executed(&mut self, session: Option<SigningSession>) {
+        if let Some(session) = session {
+            let signers = session.signatures.keys().cloned().collect();
+            self.record(&session, None, SigningEvent::Executed { signers });
+        }
+    }
+
+    /// Close an approving session once its operation has executed.
+    pub(crate) fn consume(&mut self, session_id: Option<String>) {
+        let session = session_id.and_then(|id| self.sessions.remove(&id));
+        self.executed(session);
+    }
+
+    fn record(&mut self, session: &SigningSession, signer_id: Option<&str>, event: SigningEvent) {
+        self.audit.push(SigningAuditRecord {
+            session_id: session.id.clone(),
+            signer_id: signer_id.map(str::to_string),
+            event,
+            operation: session.operation.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+impl Treasury {
+    /// Require threshold signatures for high-value operations.
+    pub fn with_signers(mut self, signers: SignerSet) -> Self {
+        self.approvals.set_signers(signers);
+        self
+    }
+
+    /// Open a session to approve releasing an escrow.
+    pub fn open_escrow_release(&mut self, escrow_id: &str) -> Result<SigningSession, TreasuryError> {
+        let escrow = self.escrows.get(escrow_id).ok_or(TreasuryError::PaymentFailed {
+            reason: "Escrow not found".to_string(),
+        })?;
+        let operation = escrow.release_operation()?;
+        self.approvals.open(operation)
+    }
+
+    /// Open a session to approve a payment.
+    pub fn open_payment(
+        &mut self,
+        from_agent: &str,
+        to_agent: &str,
+        amount: Money,
+    ) -> Result<SigningSession, TreasuryError> {
+        self.approvals.open(TreasuryOperation::payment(from_agent, to_agent, amount))
+    }
+
+    /// Open a session to approve funding a payment channel.
+    pub fn open_channel_funding(
+        &mut self,
+        party_a: &str,
+        party_b: &str,
+        capacity: Money,
+    ) -> Result<SigningSession, TreasuryError> {
+        self.approvals.open(TreasuryOperation::channel_open(party_a, party_b, capacity))
+    }
+
+    /// Add a signer's signature over [`SigningSession::signing_bytes`].
+    /// Returns how many signatures the session has.
+    pub fn sign(&mut self, session_id: &str, signer_id: &str, signature: &[u8]) -> Result<usize, TreasuryError> {
+        self.approvals.sign(session_id, signer_id, signature)
+    }
+
+    /// Get a signing session.
+    pub fn signing_session(&self, session_id: &str) -> Option<&SigningSession> {
+        self.approvals.session(session_id)
+    }
+
+    /// Every signer's participation, oldest first.
+    pub fn signing_audit(&self) -> &[SigningAuditRecord] {
+        self.approvals.audit()
+    }
+}
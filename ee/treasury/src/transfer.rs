@@ -0,0 +1,379 @@
+//! Two-Phase Transfers
+//!
+//! A transfer moves funds in two phases, so a failure between debiting the
+//! sender and crediting the recipient can never lose them:
+//!
+//! 1. **Prepare**: the transfer is validated and journaled, then the amount
+//!    is taken off the sender and held by the transfer.
+//! 2. **Commit** credits the recipient; **abort** returns the hold to the
+//!    sender.
+//!
+//! Every phase is written to the [`TransferJournal`] before wallets change.
+//! Held funds are in no wallet until the transfer resolves, so after a
+//! restart (wallets restored, journal reopened) [`Treasury::recover`] aborts
+//! every transfer that was prepared but never resolved and the sender gets
+//! its funds back.
+//!
+//! Transfers are checked like payments: against the payee policy, the
+//! sender's budget and the signer set's limits.
+//!
+//! Transfers may carry an idempotency key: retrying a request with the same
+//! key returns the original transfer instead of moving funds again, also
+//! across restarts.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut treasury = Treasury::new("org-1")?.with_journal(TransferJournal::open("transfers.jsonl")?);
+//! // ...restore wallets...
+//! let recovery = treasury.recover()?;
+//!
+//! let request = TransferRequest::new("alice", "bob", Money::parse("25", Currency::Usd)?)
+//!     .with_idempotency_key("invoice-42");
+//! let transfer_id = treasury.transfer(request)?;
+//! ```
+
+use crate::{Account, Money, PaymentRequest, Treasury, TreasuryError, TreasuryOperation};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// A transfer to run through prepare and commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferRequest {
+    pub from: String,
+    pub to: String,
+    pub amount: Money,
+    /// Retries with the same key resolve to the same transfer
+    pub idempotency_key: Option<String>,
+}
+
+impl TransferRequest {
+    pub fn new(from: impl Into<String>, to: impl Into<String>, amount: Money) -> Self {
+        Self { from: from.into(), to: to.into(), amount, idempotency_key: None }
+    }
+
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+}
+
+/// A prepared transfer holding the sender's funds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreparedTransfer {
+    pub id: String,
+    pub request: TransferRequest,
+    pub prepared_at: DateTime<Utc>,
+}
+
+/// One journaled phase of a transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalEntry {
+    Prepared { transfer: PreparedTransfer },
+    Committed { transfer_id: String, at: DateTime<Utc> },
+    Aborted { transfer_id: String, reason: String, at: DateTime<Utc> },
+}
+
+impl JournalEntry {
+    pub fn transfer_id(&self) -> &str {
+        match self {
+            Self::Prepared { transfer } => &transfer.id,
+            Self::Committed { transfer_id, .. } | Self::Aborted { transfer_id, .. } => transfer_id,
+        }
+    }
+}
+
+/// Append-only transfer journal, kept in memory and optionally in a JSON
+/// Lines file that is synced after every entry.
+#[derive(Debug, Default)]
+pub struct TransferJournal {
+    path: Option<PathBuf>,
+    entries: Vec<JournalEntry>,
+}
+
+impl TransferJournal {
+    /// Journal that does not survive the process.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or create) a file-backed journal, loading its entries.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TreasuryError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).read(true).open(&path).map_err(journal_error)?;
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(journal_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line).map_err(journal_error)?);
+        }
+        Ok(Self { path: Some(path), entries })
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Prepared transfers with no commit or abort recorded.
+    pub fn incomplete(&self) -> Vec<&PreparedTransfer> {
+        let resolved: HashSet<&str> = self
+            .entries
+            .iter()
+            .filter(|e| !matches!(e, JournalEntry::Prepared { .. }))
+            .map(JournalEntry::transfer_id)
+            .collect();
+        self.entries
+            .iter()
+            .filter_map(|e| match e {
+                JournalEntry::Prepared { transfer } if !resolved.contains(transfer.id.as_str()) => Some(transfer),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn append(&mut self, entry: JournalEntry) -> Result<(), TreasuryError> {
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(journal_error)?;
+            serde_json::to_writer(&mut file, &entry).map_err(journal_error)?;
+            file.write_all(b"\n").map_err(journal_error)?;
+            file.sync_all().map_err(journal_error)?;
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+fn journal_error(e: impl std::fmt::Display) -> TreasuryError {
+    TreasuryError::Journal { reason: e.to_string() }
+}
+
+/// Outcome of [`Treasury::recover`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recovery {
+    /// Incomplete transfers aborted and refunded to their senders
+    pub aborted: Vec<String>,
+    /// Idempotency keys restored from the journal
+    pub idempotency_keys: usize,
+}
+
+/// Journal, in-flight holds and idempotency keys of a [`Treasury`].
+#[derive(Debug, Default)]
+pub(crate) struct Transfers {
+    journal: TransferJournal,
+    prepared: HashMap<String, PreparedTransfer>,
+    /// Idempotency key to transfer ID, for prepared and committed transfers
+    keys: HashMap<String, String>,
+}
+
+impl Transfers {
+    /// Amounts held by prepared transfers.
+    pub(crate) fn held_amounts(&self) -> impl Iterator<Item = Money> + '_ {
+        self.prepared.values().map(|transfer| transfer.request.amount)
+    }
+}
+
+impl Treasury {
+    /// Journal transfers to `journal`. Call [`Treasury::recover`] once
+    /// wallets are restored.
+    pub fn with_journal(mut self, journal: TransferJournal) -> Self {
+        self.transfers.journal = journal;
+        self
+    }
+
+    /// The transfer journal.
+    pub fn journal(&self) -> &TransferJournal {
+        &self.transfers.journal
+    }
+
+    /// A transfer that is prepared but not yet committed or aborted.
+    pub fn prepared_transfer(&self, transfer_id: &str) -> Option<&PreparedTransfer> {
+        self.transfers.prepared.get(transfer_id)
+    }
+
+    /// Move funds from one agent to another through prepare and commit,
+    /// within the payee policy, the sender's budget and the signer set's
+    /// limits. Returns the transfer ID; a retried idempotency key returns
+    /// the original transfer's ID without moving funds.
+    pub fn transfer(&mut self, request: TransferRequest) -> Result<String, TreasuryError> {
+        self.authorized(request, Self::execute_transfer)
+    }
+
+    /// Phase one: check the transfer as [`Treasury::transfer`] does,
+    /// journal it, and take the amount off the sender.
+    pub fn prepare_transfer(&mut self, request: TransferRequest) -> Result<String, TreasuryError> {
+        self.authorized(request, Self::hold_transfer)
+    }
+
+    /// Run `phase` once the payee, budget and threshold checks pass; the
+    /// transfer then counts toward the budget and spends its approval.
+    fn authorized(
+        &mut self,
+        request: TransferRequest,
+        phase: fn(&mut Self, TransferRequest) -> Result<String, TreasuryError>,
+    ) -> Result<String, TreasuryError> {
+        if let Some(existing) = request.idempotency_key.as_ref().and_then(|key| self.transfers.keys.get(key)) {
+            return Ok(existing.clone());
+        }
+        let TransferRequest { from, to, amount, .. } = request.clone();
+        self.check_payee(&to, amount)?;
+        self.budgets.check(&from, &to, amount, Utc::now())?;
+        let approval = self.approvals.approval_for(&TreasuryOperation::payment(&from, &to, amount))?;
+
+        let transfer_id = phase(self, request)?;
+        self.approvals.consume(approval);
+        self.budgets.record(&from, &to, amount, Utc::now());
+        Ok(transfer_id)
+    }
+
+    /// Prepare and commit without policy checks; callers run their own.
+    pub(crate) fn execute_transfer(&mut self, request: TransferRequest) -> Result<String, TreasuryError> {
+        let transfer_id = self.hold_transfer(request)?;
+        if self.transfers.prepared.contains_key(&transfer_id)
+            && let Err(e) = self.commit_transfer(&transfer_id)
+        {
+            // Commit failures leave the hold in place; hand it back
+            self.abort_transfer(&transfer_id, &e.to_string())?;
+            return Err(e);
+        }
+        Ok(transfer_id)
+    }
+
+    /// Validate, journal, and take the amount off the sender.
+    fn hold_transfer(&mut self, request: TransferRequest) -> Result<String, TreasuryError> {
+        if let Some(existing) = request.idempotency_key.as_ref().and_then(|key| self.transfers.keys.get(key)) {
+            return Ok(existing.clone());
+        }
+        let amount = request.amount;
+        if amount.is_zero() {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+        let (currency, units) = (amount.currency(), amount.units());
+
+        // Validate everything that could fail before anything is journaled
+        let available = self.wallet_mut(&request.from)?.balance(currency);
+        if available.units() < units {
+            return Err(TreasuryError::InsufficientBalance { required: amount, available });
+        }
+        if request.from != request.to && !self.wallet_mut(&request.to)?.can_credit(currency, units) {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+
+        let transfer = PreparedTransfer {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            prepared_at: Utc::now(),
+        };
+        self.transfers.journal.append(JournalEntry::Prepared { transfer: transfer.clone() })?;
+        self.wallet_mut(&transfer.request.from)?.withdraw_units(currency, units)?;
+        self.post("transfer_prepare", Some(&transfer.id), Account::Wallet(transfer.request.from.clone()), Account::TransferHolds, amount);
+
+        let transfer_id = transfer.id.clone();
+        if let Some(key) = &transfer.request.idempotency_key {
+            self.transfers.keys.insert(key.clone(), transfer_id.clone());
+        }
+        self.transfers.prepared.insert(transfer_id.clone(), transfer);
+        Ok(transfer_id)
+    }
+
+    /// Phase two: credit the recipient with the held funds.
+    pub fn commit_transfer(&mut self, transfer_id: &str) -> Result<(), TreasuryError> {
+        let transfer = self.held(transfer_id)?;
+        let TransferRequest { to, amount, .. } = &transfer.request;
+        if !self.wallet_mut(to)?.can_credit(amount.currency(), amount.units()) {
+            return Err(TreasuryError::InvalidAmount { amount: amount.to_string() });
+        }
+
+        self.transfers.journal.append(JournalEntry::Committed {
+            transfer_id: transfer_id.to_string(),
+            at: Utc::now(),
+        })?;
+        self.wallet_mut(to)?.credit_units(amount.currency(), amount.units())?;
+        self.post("transfer_commit", Some(transfer_id), Account::TransferHolds, Account::Wallet(to.clone()), *amount);
+        self.transfers.prepared.remove(transfer_id);
+        Ok(())
+    }
+
+    /// Return a prepared transfer's funds to the sender.
+    pub fn abort_transfer(&mut self, transfer_id: &str, reason: &str) -> Result<(), TreasuryError> {
+        let transfer = self.held(transfer_id)?;
+        self.transfers.journal.append(JournalEntry::Aborted {
+            transfer_id: transfer_id.to_string(),
+            reason: reason.to_string(),
+            at: Utc::now(),
+        })?;
+        self.refund(&transfer)?;
+        self.transfers.prepared.remove(transfer_id);
+        if let Some(key) = &transfer.request.idempotency_key {
+            self.transfers.keys.remove(key);
+        }
+        Ok(())
+    }
+
+    /// Replay the journal on startup: abort and refund every transfer that
+    /// was prepared but never resolved, and restore idempotency keys of
+    /// committed transfers.
+    pub fn recover(&mut self) -> Result<Recovery, TreasuryError> {
+        let incomplete: Vec<PreparedTransfer> =
+            self.transfers.journal.incomplete().into_iter().cloned().collect();
+
+        let mut recovery = Recovery::default();
+        for transfer in incomplete {
+            // Refund from the journal: after a restart the hold exists nowhere else
+            self.transfers.prepared.insert(transfer.id.clone(), transfer.clone());
+            self.abort_transfer(&transfer.id, "incomplete at recovery")?;
+            tracing::warn!(transfer_id = %transfer.id, from = %transfer.request.from, "Aborted incomplete transfer");
+            recovery.aborted.push(transfer.id);
+        }
+
+        let committed: HashSet<&str> = self
+            .transfers
+            .journal
+            .entries()
+            .iter()
+            .filter(|e| matches!(e, JournalEntry::Committed { .. }))
+            .map(JournalEntry::transfer_id)
+            .collect();
+        for entry in self.transfers.journal.entries() {
+            if let JournalEntry::Prepared { transfer } = entry
+                && committed.contains(transfer.id.as_str())
+                && let Some(key) = &transfer.request.idempotency_key
+            {
+                self.transfers.keys.insert(key.clone(), transfer.id.clone());
+            }
+        }
+        recovery.idempotency_keys = self.transfers.keys.len();
+        Ok(recovery)
+    }
+
+    fn held(&self, transfer_id: &str) -> Result<PreparedTransfer, TreasuryError> {
+        self.transfers.prepared.get(transfer_id).cloned().ok_or(TreasuryError::TransferNotFound {
+            transfer_id: transfer_id.to_string(),
+        })
+    }
+
+    fn refund(&mut self, transfer: &PreparedTransfer) -> Result<(), TreasuryError> {
+        let TransferRequest { from, amount, .. } = &transfer.request;
+        // A sender missing after a restart still gets its funds back
+        self.register_agent(from);
+        self.wallet_mut(from)?.credit_units(amount.currency(), amount.units())?;
+        self.post("transfer_abort", Some(&transfer.id), Account::TransferHolds, Account::Wallet(from.clone()), *amount);
+        Ok(())
+    }
+
+    /// Record a completed transfer in the payment log.
+    pub(crate) fn log_payment(&mut self, request: &TransferRequest) -> String {
+        let payment = PaymentRequest::completed(&request.from, &request.to, request.amount);
+        let payment_id = payment.id.clone();
+        self.pending_payments.push(payment);
+        payment_id
+    }
+}
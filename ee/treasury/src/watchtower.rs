@@ -0,0 +1,141 @@
+//! Watchtower
+//!
+//! Guards signed payment channels while a party is offline. Each time a
+//! party revokes a state by signing a newer one, it hands the tower a
+//! [`JusticeBlob`]: the newer state, encrypted under a key derived from the
+//! revoked state (signatures included) and indexed by a hint derived the
+//! same way. The tower learns nothing until the revoked state is actually
+//! used to close the channel; then it can open the blob and contest the
+//! close, and the cheating party forfeits the channel.
+
+use crate::{Money, SignedChannelState, Treasury, TreasuryError};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Newer channel state, sealed until a revoked state shows up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JusticeBlob {
+    pub channel_id: String,
+    /// Identifies the revoked state without revealing it
+    pub hint: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl JusticeBlob {
+    /// Seal `newer` so it can only be opened by someone holding `revoked`.
+    pub fn seal(revoked: &SignedChannelState, newer: &SignedChannelState) -> Result<Self, TreasuryError> {
+        if newer.state.channel_id != revoked.state.channel_id || newer.state.sequence <= revoked.state.sequence {
+            return Err(TreasuryError::InvalidChannelState { reason: "justice state must supersede the revoked one".to_string() });
+        }
+        let plaintext = serde_json::to_vec(newer).map_err(|e| TreasuryError::PaymentFailed { reason: e.to_string() })?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(revoked)
+            .encrypt(&nonce, plaintext.as_slice())
+            .expect("ChaCha20-Poly1305 encryption of an in-memory buffer");
+        Ok(Self { channel_id: revoked.state.channel_id.clone(), hint: hint(revoked), nonce: nonce.to_vec(), ciphertext })
+    }
+
+    /// Decrypt with the revoked state that was used to close.
+    fn open(&self, revoked: &SignedChannelState) -> Option<SignedChannelState> {
+        if self.nonce.len() != 12 {
+            return None;
+        }
+        let plaintext = cipher(revoked).decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice()).ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+}
+
+fn digest(domain: &[u8], state: &SignedChannelState) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(domain);
+    hasher.update(state.state.signing_bytes());
+    hasher.update(&state.signature_a);
+    hasher.update(&state.signature_b);
+    hasher.finalize().into()
+}
+
+fn hint(state: &SignedChannelState) -> String {
+    digest(b"agentkern-watchtower/hint", state)[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn cipher(state: &SignedChannelState) -> ChaCha20Poly1305 {
+    ChaCha20Poly1305::new(Key::from_slice(&digest(b"agentkern-watchtower/key", state)))
+}
+
+/// A fraudulent close the tower contested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contested {
+    pub channel_id: String,
+    /// Party that closed on a revoked state
+    pub penalized: String,
+    pub revoked_sequence: u64,
+    pub justice_sequence: u64,
+    /// Final payout to (party A, party B)
+    pub payout: (Money, Money),
+}
+
+/// Stores justice blobs for delegating parties and contests fraudulent closes.
+#[derive(Debug, Default)]
+pub struct Watchtower {
+    blobs: HashMap<String, JusticeBlob>,
+}
+
+impl Watchtower {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a blob from a delegating party.
+    pub fn delegate(&mut self, blob: JusticeBlob) {
+        self.blobs.insert(blob.hint.clone(), blob);
+    }
+
+    /// Drop every blob for a channel, e.g. once it has settled.
+    pub fn forget(&mut self, channel_id: &str) {
+        self.blobs.retain(|_, blob| blob.channel_id != channel_id);
+    }
+
+    /// Number of stored blobs.
+    pub fn len(&self) -> usize {
+        self.blobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+
+    /// Contest every pending close that uses a revoked state it holds a blob for.
+    pub fn patrol(&mut self, treasury: &mut Treasury) -> Vec<Contested> {
+        let breaches: Vec<_> = treasury
+            .pending_closes()
+            .filter_map(|(channel_id, pending)| {
+                let blob = self.blobs.get(&hint(&pending.state))?;
+                let justice = blob.open(&pending.state)?;
+                Some((channel_id.to_string(), pending.closer.clone(), pending.state.state.sequence, justice))
+            })
+            .collect();
+
+        let mut contested = Vec::new();
+        for (channel_id, penalized, revoked_sequence, justice) in breaches {
+            match treasury.contest_close(&channel_id, &justice) {
+                Ok(payout) => {
+                    tracing::warn!(channel_id = %channel_id, penalized = %penalized, "Watchtower contested a revoked close");
+                    self.forget(&channel_id);
+                    contested.push(Contested {
+                        channel_id,
+                        penalized,
+                        revoked_sequence,
+                        justice_sequence: justice.state.sequence,
+                        payout,
+                    });
+                }
+                Err(e) => tracing::error!(channel_id = %channel_id, error = %e, "Watchtower failed to contest close"),
+            }
+        }
+        contested
+    }
+}
@@ -0,0 +1,87 @@
+//! Per-agent spending limits, velocity checks and human-approved overrides.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::usd;
+
+fn treasury(budget: AgentBudget) -> Treasury {
+    let mut treasury = common::treasury(&["alice", "bob", "carol"]).with_budget("alice", budget);
+    treasury.deposit("alice", usd("1000")).unwrap();
+    treasury
+}
+
+#[test]
+fn test_limits_are_enforced_in_pay() {
+    let budget = AgentBudget::new()
+        .with_daily_limit(usd("100"))
+        .with_monthly_limit(usd("500"))
+        .with_counterparty_limit("bob", LimitPeriod::Daily, usd("30"));
+    let mut treasury = treasury(budget);
+
+    treasury.pay("alice", "bob", usd("25")).unwrap();
+    let err = treasury.pay("alice", "bob", usd("10")).unwrap_err();
+    match err {
+        TreasuryError::BudgetExceeded { limit, spent, requested, .. } => {
+            assert_eq!(limit, "daily limit of 30.00 USD to bob");
+            assert_eq!((spent, requested), (usd("25"), usd("10")));
+        }
+        other => panic!("expected BudgetExceeded, got {other:?}"),
+    }
+
+    // Other counterparties still have room under the overall daily cap
+    treasury.pay("alice", "carol", usd("70")).unwrap();
+    assert_eq!(treasury.remaining_budget("alice", Currency::Usd), Some(usd("5")));
+    let err = treasury.pay("alice", "carol", usd("10")).unwrap_err();
+    assert!(matches!(err, TreasuryError::BudgetExceeded { ref limit, .. } if limit.starts_with("daily limit of")));
+
+    // Rejected payments move nothing; other currencies and payers are unaffected
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("905"));
+    treasury.deposit("alice", Money::parse("50", Currency::Eur).unwrap()).unwrap();
+    treasury.pay("alice", "bob", Money::parse("50", Currency::Eur).unwrap()).unwrap();
+    treasury.deposit("bob", usd("500")).unwrap();
+    treasury.pay("bob", "carol", usd("400")).unwrap();
+}
+
+#[test]
+fn test_velocity_and_overrides() {
+    let budget = AgentBudget::new().with_daily_limit(usd("50")).with_velocity(3, chrono::Duration::minutes(1));
+    let mut treasury = treasury(budget);
+
+    for _ in 0..3 {
+        treasury.pay("alice", "bob", usd("1")).unwrap();
+    }
+    let err = treasury.pay("alice", "bob", usd("1")).unwrap_err();
+    assert!(matches!(err, TreasuryError::VelocityExceeded { max_payments: 3, window_secs: 60, .. }));
+
+    // A human approves one payment of up to $100 past the limits
+    let grant = treasury.grant_override("alice", usd("100"), "ops@example.com", chrono::Duration::hours(1));
+    let err = treasury.pay_with_override("bob", "carol", usd("60"), &grant.token).unwrap_err();
+    assert!(matches!(err, TreasuryError::OverrideRejected { .. }));
+    let err = treasury.pay_with_override("alice", "bob", usd("150"), &grant.token).unwrap_err();
+    assert!(matches!(err, TreasuryError::OverrideRejected { .. }));
+
+    // A payment that fails for another reason does not spend the token
+    let err = treasury.pay_with_override("alice", "nobody", usd("60"), &grant.token).unwrap_err();
+    assert!(matches!(err, TreasuryError::AgentNotFound { .. }));
+    treasury.pay_with_override("alice", "bob", usd("60"), &grant.token).unwrap();
+    assert_eq!(treasury.balance("bob", Currency::Usd).unwrap(), usd("63"));
+
+    // Single use, and the overridden payment counts toward the daily total
+    let err = treasury.pay_with_override("alice", "bob", usd("1"), &grant.token).unwrap_err();
+    assert!(matches!(err, TreasuryError::OverrideRejected { .. }));
+    assert_eq!(treasury.remaining_budget("alice", Currency::Usd), Some(usd("0")));
+}
+
+#[test]
+fn test_channel_funding_counts_toward_budget() {
+    let mut treasury = treasury(AgentBudget::new().with_daily_limit(usd("100")));
+
+    treasury.open_channel("alice", "bob", usd("80")).unwrap();
+    assert_eq!(treasury.remaining_budget("alice", Currency::Usd), Some(usd("20")));
+
+    // Neither a second channel nor a payment can go past the limit
+    assert!(matches!(treasury.open_channel("alice", "carol", usd("30")), Err(TreasuryError::BudgetExceeded { .. })));
+    assert!(matches!(treasury.pay("alice", "carol", usd("30")), Err(TreasuryError::BudgetExceeded { .. })));
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("920"));
+}
@@ -0,0 +1,30 @@
+//! Helpers shared by the integration tests. Each test binary uses a subset.
+#![allow(dead_code)]
+
+use agentkern_treasury_ee::{Currency, Money, Treasury};
+use std::sync::Once;
+
+/// Set the license key once per test binary.
+pub fn licensed() {
+    static LICENSE: Once = Once::new();
+    // SAFETY: set once, before any test in this binary reads it
+    LICENSE.call_once(|| unsafe { std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license") });
+}
+
+pub fn usd(amount: &str) -> Money {
+    Money::parse(amount, Currency::Usd).unwrap()
+}
+
+pub fn credits(amount: &str) -> Money {
+    Money::parse(amount, Currency::Credits).unwrap()
+}
+
+/// Licensed treasury with wallets for `agents`.
+pub fn treasury(agents: &[&str]) -> Treasury {
+    licensed();
+    let mut treasury = Treasury::new("org-1").unwrap();
+    for agent in agents {
+        treasury.register_agent(agent);
+    }
+    treasury
+}
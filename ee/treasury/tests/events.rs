@@ -0,0 +1,170 @@
+//! Treasury event stream and webhook delivery.
+
+mod common;
+
+use agentkern_treasury_ee::events::sign;
+use agentkern_treasury_ee::*;
+use common::{licensed, usd};
+use std::time::Duration;
+use wiremock::matchers::{header, header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn quick_retries() -> RetryPolicy {
+    RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_millis(10), max_backoff: Duration::from_millis(20) }
+}
+
+fn payment_event() -> TreasuryEvent {
+    TreasuryEvent {
+        id: "evt-1".to_string(),
+        tenant_id: "org-1".to_string(),
+        at: chrono::Utc::now(),
+        kind: TreasuryEventKind::PaymentCompleted {
+            payment_id: "pay-1".to_string(),
+            from_agent: "alice".to_string(),
+            to_agent: "bob".to_string(),
+            amount: usd("5"),
+        },
+    }
+}
+
+#[test]
+fn test_treasury_emits_typed_events() {
+    licensed();
+    let events = TreasuryEvents::default();
+    let mut received = events.subscribe();
+    let mut treasury = Treasury::new("org-1").unwrap().with_events(events);
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", usd("100")).unwrap();
+
+    let payment_id = treasury.pay("alice", "bob", usd("10")).unwrap();
+    let channel_id = treasury.open_channel("alice", "bob", usd("20")).unwrap();
+    let escrow_id = treasury.create_escrow("alice", "bob", usd("30"), "delivered", 24).unwrap();
+    treasury.release_escrow(&escrow_id).unwrap();
+    assert!(treasury.pay("alice", "bob", usd("50")).is_err());
+
+    let kinds: Vec<_> = std::iter::from_fn(|| received.try_recv().ok()).map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TreasuryEventKind::PaymentCompleted {
+                payment_id,
+                from_agent: "alice".to_string(),
+                to_agent: "bob".to_string(),
+                amount: usd("10"),
+            },
+            TreasuryEventKind::ChannelOpened {
+                channel_id,
+                party_a: "alice".to_string(),
+                party_b: "bob".to_string(),
+                capacity: usd("20"),
+            },
+            TreasuryEventKind::EscrowReleased {
+                escrow_id,
+                from_agent: "alice".to_string(),
+                to_agent: "bob".to_string(),
+                amount: usd("30"),
+            },
+            TreasuryEventKind::InsufficientBalance {
+                agent_id: "alice".to_string(),
+                operation: "payment".to_string(),
+                required: usd("50"),
+                available: usd("40"),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_shared_treasury_emits_events() {
+    licensed();
+    let treasury = SharedTreasury::new("org-1").unwrap();
+    let mut received = treasury.events().subscribe();
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", usd("10")).await.unwrap();
+
+    treasury.pay("alice", "bob", usd("4")).await.unwrap();
+    assert!(treasury.create_escrow("alice", "bob", usd("7"), "delivered", 1).await.is_err());
+
+    let event = received.recv().await.unwrap();
+    assert_eq!(event.tenant_id, "org-1");
+    assert_eq!(event.kind.name(), "payment_completed");
+    let event = received.recv().await.unwrap();
+    assert!(matches!(event.kind, TreasuryEventKind::InsufficientBalance { ref operation, .. } if operation == "create_escrow"));
+}
+
+#[tokio::test]
+async fn test_webhooks_are_signed_filtered_and_retried() {
+    let server = MockServer::start().await;
+    let event = payment_event();
+    let body = serde_json::to_vec(&event).unwrap();
+    // Fails once, then accepts
+    Mock::given(method("POST"))
+        .and(path("/billing"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/billing"))
+        .and(header("X-AgentKern-Event", "payment_completed"))
+        .and(header("X-AgentKern-Signature", format!("sha256={}", sign("s3cret", &body)).as_str()))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST")).and(path("/arbiter")).respond_with(ResponseTemplate::new(200)).expect(0).mount(&server).await;
+
+    let dispatcher = WebhookDispatcher::new()
+        .with_retry(quick_retries())
+        .with_endpoint(WebhookEndpoint::new(format!("{}/billing", server.uri())).with_secret("s3cret"))
+        .with_endpoint(WebhookEndpoint::new(format!("{}/arbiter", server.uri())).only(&["insufficient_balance"]));
+
+    let results = dispatcher.deliver(&event).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].1.as_ref().unwrap(), &2);
+}
+
+#[tokio::test]
+async fn test_webhooks_give_up_after_retries_and_on_rejection() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/down")).respond_with(ResponseTemplate::new(500)).expect(3).mount(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/rejects"))
+        .and(header_exists("X-AgentKern-Event-Id"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dispatcher = WebhookDispatcher::new()
+        .with_retry(quick_retries())
+        .with_endpoint(WebhookEndpoint::new(format!("{}/down", server.uri())))
+        .with_endpoint(WebhookEndpoint::new(format!("{}/rejects", server.uri())));
+
+    let results = dispatcher.deliver(&payment_event()).await;
+    assert!(matches!(results[0].1, Err(TreasuryError::WebhookFailed { attempts: 3, .. })));
+    assert!(matches!(results[1].1, Err(TreasuryError::WebhookFailed { attempts: 1, .. })));
+}
+
+#[tokio::test]
+async fn test_dispatcher_forwards_the_event_stream() {
+    licensed();
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/hooks")).respond_with(ResponseTemplate::new(200)).expect(1).mount(&server).await;
+
+    let events = TreasuryEvents::default();
+    let dispatcher = WebhookDispatcher::new().with_endpoint(WebhookEndpoint::new(format!("{}/hooks", server.uri())));
+    let forwarding = tokio::spawn(dispatcher.run(events.subscribe()));
+
+    let mut treasury = Treasury::new("org-1").unwrap().with_events(events);
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", usd("10")).unwrap();
+    treasury.pay("alice", "bob", usd("1")).unwrap();
+
+    // Dropping the treasury closes the channel and ends the dispatcher
+    drop(treasury);
+    forwarding.await.unwrap();
+}
@@ -0,0 +1,130 @@
+//! Platform fees on payments and escrow releases.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::{licensed, usd};
+
+fn treasury(policy: FeePolicy) -> Treasury {
+    let mut treasury = common::treasury(&["client", "worker"]).with_fees(FeeSchedule::new("platform", policy).unwrap());
+    treasury.deposit("client", usd("1000")).unwrap();
+    treasury
+}
+
+#[test]
+fn test_percentage_fee_is_credited_to_fee_wallet() {
+    let mut treasury = treasury(FeePolicy::Percentage { bps: 250 });
+    let payment_id = treasury.pay("client", "worker", usd("100")).unwrap();
+
+    assert_eq!(treasury.balance("client", Currency::Usd).unwrap(), usd("900"));
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("97.50"));
+    assert_eq!(treasury.balance("platform", Currency::Usd).unwrap(), usd("2.50"));
+
+    let payment = treasury.payments().iter().find(|p| p.id == payment_id).unwrap();
+    assert_eq!(
+        payment.fee,
+        Some(FeeBreakdown { gross: usd("100"), fee: usd("2.50"), net: usd("97.50"), fee_wallet: "platform".to_string() })
+    );
+    assert!(treasury.reconcile().is_balanced());
+}
+
+#[test]
+fn test_flat_fee_applies_only_in_its_currency() {
+    let mut treasury = treasury(FeePolicy::Flat { fee: usd("0.30") });
+    treasury.deposit("client", Money::parse("50", Currency::Eur).unwrap()).unwrap();
+
+    treasury.pay("client", "worker", usd("10")).unwrap();
+    treasury.pay("client", "worker", usd("0.10")).unwrap();
+    treasury.pay("client", "worker", Money::parse("5", Currency::Eur).unwrap()).unwrap();
+
+    // The fee never exceeds the payment
+    assert_eq!(treasury.balance("platform", Currency::Usd).unwrap(), usd("0.40"));
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("9.70"));
+    assert_eq!(treasury.balance("worker", Currency::Eur).unwrap(), Money::parse("5", Currency::Eur).unwrap());
+    assert!(treasury.reconcile().is_balanced());
+}
+
+#[test]
+fn test_tiered_fee_falls_with_volume() {
+    let mut treasury = treasury(FeePolicy::Tiered {
+        tiers: vec![
+            FeeTier { from_volume: usd("0"), bps: 1000 },
+            FeeTier { from_volume: usd("200"), bps: 500 },
+        ],
+    });
+
+    treasury.pay("client", "worker", usd("200")).unwrap();
+    treasury.pay("client", "worker", usd("100")).unwrap();
+
+    assert_eq!(treasury.balance("platform", Currency::Usd).unwrap(), usd("25"));
+    assert_eq!(treasury.fee_volume("worker", Currency::Usd), usd("300"));
+    let fees: Vec<Money> = treasury.payments().iter().filter_map(|p| p.fee.as_ref()).map(|f| f.fee).collect();
+    assert_eq!(fees, vec![usd("20"), usd("5")]);
+
+    // Payments to the fee wallet are not charged
+    let payment_id = treasury.pay("client", "platform", usd("10")).unwrap();
+    assert!(treasury.payments().iter().find(|p| p.id == payment_id).unwrap().fee.is_none());
+}
+
+#[test]
+fn test_escrow_releases_pay_fees() {
+    let mut treasury = treasury(FeePolicy::Percentage { bps: 1000 });
+    let stages = vec![Milestone::new("build", 50, "tests pass"), Milestone::new("deploy", 50, "deployed")];
+    let escrow_id = treasury.create_milestone_escrow("client", "worker", usd("400"), stages, 24).unwrap();
+
+    treasury.release_milestone(&escrow_id, "build").unwrap();
+    treasury.release_escrow(&escrow_id).unwrap();
+
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("360"));
+    assert_eq!(treasury.balance("platform", Currency::Usd).unwrap(), usd("40"));
+    let escrow = treasury.escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.fees.iter().map(|f| f.fee).collect::<Vec<_>>(), vec![usd("20"), usd("20")]);
+    assert!(treasury.reconcile().is_balanced());
+}
+
+#[test]
+fn test_shared_treasury_pays_fees() {
+    licensed();
+    let schedule = FeeSchedule::new("platform", FeePolicy::Percentage { bps: 100 }).unwrap();
+    let treasury = BlockingTreasury::new(SharedTreasury::new("org-1").unwrap().with_fees(schedule)).unwrap();
+    treasury.register_agent("client");
+    treasury.register_agent("worker");
+    treasury.deposit("client", usd("1000")).unwrap();
+
+    treasury.pay("client", "worker", usd("100")).unwrap();
+    let escrow_id = treasury.create_escrow("client", "worker", usd("200"), "done", 24).unwrap();
+    treasury.release_escrow(&escrow_id).unwrap();
+
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("297"));
+    assert_eq!(treasury.balance("platform", Currency::Usd).unwrap(), usd("3"));
+    assert_eq!(treasury.shared().payments()[0].fee.as_ref().unwrap().net, usd("99"));
+}
+
+#[test]
+fn test_invalid_policies_are_rejected() {
+    for policy in [
+        FeePolicy::Percentage { bps: 10_001 },
+        FeePolicy::Tiered { tiers: vec![] },
+        FeePolicy::Tiered { tiers: vec![FeeTier { from_volume: usd("0"), bps: 20_000 }] },
+    ] {
+        assert!(matches!(FeeSchedule::new("platform", policy), Err(TreasuryError::InvalidFeePolicy { .. })));
+    }
+    assert!(FeeSchedule::new("", FeePolicy::None).is_err());
+}
+
+#[test]
+fn test_fee_failure_moves_no_funds() {
+    let mut treasury = treasury(FeePolicy::Percentage { bps: 100 });
+    // The fee wallet can't take another unit
+    treasury.deposit("platform", Money::from_units(u128::MAX, Currency::Usd)).unwrap();
+    let escrow_id = treasury.create_escrow("client", "worker", usd("200"), "done", 24).unwrap();
+
+    assert!(matches!(treasury.pay("client", "worker", usd("100")), Err(TreasuryError::InvalidAmount { .. })));
+    assert!(matches!(treasury.release_escrow(&escrow_id), Err(TreasuryError::InvalidAmount { .. })));
+
+    assert_eq!(treasury.balance("client", Currency::Usd).unwrap(), usd("800"));
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("0"));
+    assert!(treasury.payments().is_empty());
+    assert_eq!(treasury.escrow(&escrow_id).unwrap().status, EscrowStatus::Locked);
+    assert!(treasury.reconcile().is_balanced());
+}
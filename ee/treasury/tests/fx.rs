@@ -0,0 +1,92 @@
+//! Cross-currency payments: quotes, spread, slippage limits and rate providers.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::licensed;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+fn money(amount: &str, currency: Currency) -> Money {
+    Money::parse(amount, currency).unwrap()
+}
+
+#[test]
+fn test_pay_with_conversion_applies_spread_and_slippage() {
+    licensed();
+    let table = Arc::new(StaticRates::new().with_rate(Currency::Usd, Currency::Eur, "0.9").unwrap());
+    let policy = FxPolicy { spread_bps: 100, max_slippage_bps: 50, max_rate_age_secs: 60 };
+    let mut treasury = Treasury::new("org-1").unwrap().with_exchange_rates(ExchangeRates::new(policy).with_provider(table.clone()));
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", money("100", Currency::Usd)).unwrap();
+
+    let quote = treasury.quote_conversion(money("50", Currency::Usd), Currency::Eur).unwrap();
+    assert_eq!((quote.target, quote.spread), (money("44.55", Currency::Eur), money("0.45", Currency::Eur)));
+    let paid = treasury.pay_with_conversion("alice", "bob", &quote).unwrap();
+    assert_eq!(paid.executed.target, money("44.55", Currency::Eur));
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), money("50", Currency::Usd));
+    assert_eq!(treasury.balance("bob", Currency::Eur).unwrap(), money("44.55", Currency::Eur));
+    assert!(treasury.payments().iter().any(|p| p.id == paid.payment_id));
+
+    // The rate moves 1% against the payer: over the 0.5% limit, nothing changes
+    let quote = treasury.quote_conversion(money("20", Currency::Usd), Currency::Eur).unwrap();
+    table.set_rate(Currency::Usd, Currency::Eur, "0.891").unwrap();
+    let err = treasury.pay_with_conversion("alice", "bob", &quote).unwrap_err();
+    assert!(matches!(err, TreasuryError::SlippageExceeded { max_bps: 50, .. }));
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), money("50", Currency::Usd));
+
+    // Within the limit it executes at the live rate; the inverse is implied
+    table.set_rate(Currency::Usd, Currency::Eur, "0.8982").unwrap();
+    let paid = treasury.pay_with_conversion("alice", "bob", &quote).unwrap();
+    assert_eq!(paid.executed.rate.to_string(), "1 USD = 0.8982 EUR");
+    let back = treasury.quote_conversion(money("10", Currency::Eur), Currency::Usd).unwrap();
+    assert_eq!(back.rate.base, Currency::Eur);
+
+    // Unknown pairs and insufficient funds leave both wallets alone
+    assert!(matches!(
+        treasury.quote_conversion(money("1", Currency::Usd), Currency::Btc),
+        Err(TreasuryError::RateUnavailable { .. })
+    ));
+    let big = treasury.quote_conversion(money("1000", Currency::Usd), Currency::Eur).unwrap();
+    assert!(treasury.pay_with_conversion("alice", "bob", &big).is_err());
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), money("30", Currency::Usd));
+}
+
+/// Serve one HTTP response on a local port.
+fn serve_once(body: &'static str) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 1024];
+        let _ = stream.read(&mut request);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    format!("http://{}/latest", addr)
+}
+
+#[tokio::test]
+async fn test_http_oracle_crosses_rates_through_its_base() {
+    let url = serve_once(r#"{"base":"USD","rates":{"EUR":"0.9","BTC":0.00002,"XAU":"0.0004"}}"#);
+    let oracle = Arc::new(HttpRateOracle::new(url, Currency::Usd));
+    assert_eq!(oracle.refresh().await.unwrap(), 2);
+
+    let rates = ExchangeRates::new(FxPolicy::default()).with_provider(oracle);
+    let eur_btc = rates.rate(Currency::Eur, Currency::Btc).unwrap();
+    // 1 EUR = 1/0.9 USD = 0.0000222... BTC
+    let quote = rates.quote(money("9", Currency::Eur), Currency::Btc).unwrap();
+    assert_eq!(eur_btc.quote, Currency::Btc);
+    // Rounded down at every step, never up
+    let gross = quote.spread.checked_add(quote.target).unwrap();
+    assert!((money("0.0002", Currency::Btc).units() - gross.units()) <= 1);
+
+    // A dead feed is an error, not an empty table
+    let dead = HttpRateOracle::new("http://127.0.0.1:9/latest", Currency::Usd);
+    assert!(matches!(dead.refresh().await, Err(TreasuryError::RateSource { .. })));
+}
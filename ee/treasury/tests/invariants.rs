@@ -0,0 +1,193 @@
+//! Property-based invariants for wallets, payments, channels and escrow.
+
+mod common;
+
+use agentkern_treasury_ee::{Currency, EscrowStatus, Money, Treasury};
+use common::licensed;
+use proptest::prelude::*;
+
+const AGENTS: [&str; 3] = ["agent-a", "agent-b", "agent-c"];
+const CURRENCIES: [Currency; 8] = [
+    Currency::Usd,
+    Currency::Eur,
+    Currency::Btc,
+    Currency::Sats,
+    Currency::Eth,
+    Currency::Usdc,
+    Currency::Usdt,
+    Currency::Credits,
+];
+
+#[derive(Debug, Clone)]
+enum Op {
+    Deposit { agent: usize, amount: f64 },
+    Pay { from: usize, to: usize, amount: f64 },
+    OpenChannel { a: usize, b: usize, capacity: f64 },
+    ChannelTransfer { channel: usize, a_to_b: bool, amount: f64 },
+    CloseChannel { channel: usize },
+    CreateEscrow { from: usize, to: usize, amount: f64 },
+    ReleaseEscrow { escrow: usize },
+    RefundEscrow { escrow: usize },
+}
+
+fn currency() -> impl Strategy<Value = Currency> {
+    proptest::sample::select(CURRENCIES.to_vec())
+}
+
+/// Everyday amounts, amounts past `u128` at 18 decimals, and garbage.
+fn amount() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        6 => 0.0..1_000.0f64,
+        2 => 0.0..1e21f64,
+        1 => Just(f64::NAN),
+        1 => -1_000.0..0.0f64,
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let agent = || 0..AGENTS.len();
+    prop_oneof![
+        (agent(), amount()).prop_map(|(agent, amount)| Op::Deposit { agent, amount }),
+        (agent(), agent(), amount()).prop_map(|(from, to, amount)| Op::Pay { from, to, amount }),
+        (agent(), agent(), amount()).prop_map(|(a, b, capacity)| Op::OpenChannel { a, b, capacity }),
+        (0..4usize, any::<bool>(), amount())
+            .prop_map(|(channel, a_to_b, amount)| Op::ChannelTransfer { channel, a_to_b, amount }),
+        (0..4usize).prop_map(|channel| Op::CloseChannel { channel }),
+        (agent(), agent(), amount()).prop_map(|(from, to, amount)| Op::CreateEscrow { from, to, amount }),
+        (0..4usize).prop_map(|escrow| Op::ReleaseEscrow { escrow }),
+        (0..4usize).prop_map(|escrow| Op::RefundEscrow { escrow }),
+    ]
+}
+
+/// Base units held in wallets, open channels and locked escrows.
+fn total_units(treasury: &Treasury, currency: Currency, channels: &[String], escrows: &[String]) -> u128 {
+    let wallets: u128 = AGENTS
+        .iter()
+        .filter_map(|agent| treasury.wallet(agent))
+        .map(|wallet| wallet.balances.get(&currency).copied().unwrap_or(0))
+        .sum();
+    let in_channels: u128 = channels
+        .iter()
+        .filter_map(|id| treasury.channel(id))
+        .filter(|channel| channel.is_open)
+        .map(|channel| channel.balance_a + channel.balance_b)
+        .sum();
+    let in_escrow: u128 = escrows
+        .iter()
+        .filter_map(|id| treasury.escrow(id))
+        .filter(|escrow| escrow.status == EscrowStatus::Locked)
+        .map(|escrow| escrow.amount.units())
+        .sum();
+    wallets + in_channels + in_escrow
+}
+
+proptest! {
+    #[test]
+    fn prop_funds_are_conserved(currency in currency(), ops in prop::collection::vec(op(), 1..60)) {
+        licensed();
+        let mut treasury = Treasury::new("org-prop").unwrap();
+        for agent in AGENTS {
+            treasury.register_agent(agent);
+        }
+        let mut supply: u128 = 0;
+        let mut channels: Vec<String> = Vec::new();
+        let mut escrows: Vec<String> = Vec::new();
+        // Legacy float amounts; the ones that fail conversion never reach the treasury
+        let money = |amount: f64| Money::try_from((amount, currency));
+
+        for op in ops {
+            match op {
+                Op::Deposit { agent, amount } => {
+                    if let Ok(amount) = money(amount) && treasury.deposit(AGENTS[agent], amount).is_ok() {
+                        supply += amount.units();
+                    }
+                }
+                Op::Pay { from, to, amount } => {
+                    if let Ok(amount) = money(amount) {
+                        let _ = treasury.pay(AGENTS[from], AGENTS[to], amount);
+                    }
+                }
+                Op::OpenChannel { a, b, capacity } => {
+                    if let Ok(id) = money(capacity).and_then(|capacity| treasury.open_channel(AGENTS[a], AGENTS[b], capacity)) {
+                        channels.push(id);
+                    }
+                }
+                Op::ChannelTransfer { channel, a_to_b, amount } => {
+                    if let (Some(id), Ok(amount)) = (channels.get(channel), money(amount)) {
+                        let _ = treasury.channel_transfer(id, a_to_b, amount);
+                    }
+                }
+                Op::CloseChannel { channel } => {
+                    if let Some(id) = channels.get(channel) {
+                        let _ = treasury.close_channel(id);
+                    }
+                }
+                Op::CreateEscrow { from, to, amount } => {
+                    if let Ok(id) = money(amount).and_then(|amount| treasury.create_escrow(AGENTS[from], AGENTS[to], amount, "done", 24)) {
+                        escrows.push(id);
+                    }
+                }
+                Op::ReleaseEscrow { escrow } => {
+                    if let Some(id) = escrows.get(escrow) {
+                        let _ = treasury.release_escrow(id);
+                    }
+                }
+                Op::RefundEscrow { escrow } => {
+                    if let Some(id) = escrows.get(escrow) {
+                        let _ = treasury.refund_escrow(id);
+                    }
+                }
+            }
+            prop_assert_eq!(total_units(&treasury, currency, &channels, &escrows), supply);
+            for channel in channels.iter().filter_map(|id| treasury.channel(id)) {
+                if channel.is_open {
+                    prop_assert_eq!(channel.balance_a + channel.balance_b, channel.capacity);
+                }
+            }
+            let report = treasury.reconcile();
+            prop_assert!(report.is_balanced(), "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn prop_base_units_roundtrip(currency in currency(), units in 0u128..(1 << 50)) {
+        // Two float roundings stay under half a unit below 2^50
+        let amount = Money::from_units(units, currency).to_f64();
+        prop_assert_eq!(Money::try_from((amount, currency)).unwrap().units(), units);
+    }
+
+    #[test]
+    fn prop_decimal_roundtrip(currency in currency(), units in any::<u128>()) {
+        let money = Money::from_units(units, currency);
+        let decimal = money.to_string();
+        let decimal = decimal.split(' ').next().unwrap();
+        prop_assert_eq!(Money::parse(decimal, currency).unwrap(), money);
+    }
+
+    #[test]
+    fn prop_amount_roundtrip_within_half_unit(currency in currency(), amount in 0.0..1e9f64) {
+        if let Ok(money) = Money::try_from((amount, currency)) {
+            let unit = Money::from_units(1, currency).to_f64();
+            let back = money.to_f64();
+            prop_assert!((back - amount).abs() <= unit / 2.0 + amount * f64::EPSILON * 4.0);
+        }
+    }
+
+    #[test]
+    fn prop_out_of_range_amounts_are_rejected(currency in currency(), amount in any::<f64>()) {
+        // Never panics, and never saturates silently
+        let scale = 10f64.powi(currency.decimals() as i32);
+        let limit = u128::MAX as f64 / scale;
+        if !amount.is_finite() || amount < -0.5 / scale || amount > limit {
+            prop_assert!(Money::try_from((amount, currency)).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_eth_past_u64_is_representable() {
+    let eth = |amount: f64| Money::try_from((amount, Currency::Eth));
+    assert_eq!(eth(19.0).unwrap().units(), 19 * 10u128.pow(18));
+    assert!(eth(1e20).is_ok());
+    assert!(eth(1e21).is_err());
+}
@@ -0,0 +1,146 @@
+//! Double-entry ledger, reconciliation and export.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::{licensed, usd};
+use std::sync::Arc;
+
+fn held(treasury: &Treasury, account: Account) -> Option<u128> {
+    treasury.ledger().balance(&account, Currency::Usd).held()
+}
+
+#[test]
+fn test_every_movement_is_posted_and_reconciles() {
+    licensed();
+    let mut treasury = Treasury::new("org-1").unwrap();
+    for agent in ["alice", "bob"] {
+        treasury.register_agent(agent);
+    }
+    treasury.deposit("alice", usd("100")).unwrap();
+    treasury.pay("alice", "bob", usd("10")).unwrap();
+
+    let channel_id = treasury.open_channel("alice", "bob", usd("20")).unwrap();
+    treasury.channel_transfer(&channel_id, true, usd("5")).unwrap();
+    let escrow_id = treasury.create_escrow("alice", "bob", usd("30"), "delivered", 24).unwrap();
+    let refunded = treasury.create_escrow("alice", "bob", usd("15"), "delivered", 24).unwrap();
+    treasury.refund_escrow(&refunded).unwrap();
+
+    // Funds in flight sit in their own accounts
+    assert_eq!(held(&treasury, Account::Channel(channel_id.clone())), Some(usd("20").units()));
+    assert_eq!(held(&treasury, Account::Escrow(escrow_id.clone())), Some(usd("30").units()));
+    assert!(treasury.reconcile().is_balanced());
+
+    let transfer_id = treasury.prepare_transfer(TransferRequest::new("alice", "bob", usd("7"))).unwrap();
+    assert_eq!(held(&treasury, Account::TransferHolds), Some(usd("7").units()));
+    assert!(treasury.reconcile().is_balanced());
+    treasury.abort_transfer(&transfer_id, "cancelled").unwrap();
+
+    treasury.close_channel(&channel_id).unwrap();
+    treasury.release_escrow(&escrow_id).unwrap();
+
+    let report = treasury.reconcile();
+    assert!(report.is_balanced(), "{report:?}");
+    assert_eq!(held(&treasury, Account::Wallet("alice".to_string())), Some(usd("55").units()));
+    assert_eq!(held(&treasury, Account::Wallet("bob".to_string())), Some(usd("45").units()));
+    assert_eq!(held(&treasury, Account::Channel(channel_id)), Some(0));
+    // Deposits are the only funds that came from outside
+    assert_eq!(treasury.ledger().balance(&Account::External, Currency::Usd).debits, usd("100").units());
+    assert_eq!(report.lines, treasury.ledger().lines().len());
+}
+
+#[test]
+fn test_shared_treasury_posts_and_reconciles() {
+    licensed();
+    let schedule = FeeSchedule::new("platform", FeePolicy::Percentage { bps: 100 }).unwrap();
+    let treasury = BlockingTreasury::new(SharedTreasury::new("org-1").unwrap().with_fees(schedule)).unwrap();
+    for agent in ["alice", "bob"] {
+        treasury.register_agent(agent);
+    }
+    treasury.deposit("alice", usd("1000")).unwrap();
+    treasury.pay("alice", "bob", usd("100")).unwrap();
+
+    let channel_id = treasury.open_channel("alice", "bob", usd("20")).unwrap();
+    treasury.channel_transfer(&channel_id, true, usd("5")).unwrap();
+    let escrow_id = treasury.create_escrow("alice", "bob", usd("200"), "delivered", 24).unwrap();
+    let refunded = treasury.create_escrow("alice", "bob", usd("15"), "delivered", 24).unwrap();
+    treasury.refund_escrow(&refunded).unwrap();
+    let ledger = treasury.ledger();
+    assert_eq!(ledger.balance(&Account::Channel(channel_id.clone()), Currency::Usd).held(), Some(usd("20").units()));
+    assert_eq!(ledger.balance(&Account::Escrow(escrow_id.clone()), Currency::Usd).held(), Some(usd("200").units()));
+    assert!(treasury.reconcile().is_balanced());
+
+    treasury.close_channel(&channel_id).unwrap();
+    treasury.release_escrow(&escrow_id).unwrap();
+
+    let report = treasury.reconcile();
+    assert!(report.is_balanced(), "{report:?}");
+    let ledger = treasury.ledger();
+    assert_eq!(ledger.balance(&Account::Wallet("bob".to_string()), Currency::Usd).held(), Some(usd("302").units()));
+    assert_eq!(ledger.balance(&Account::Wallet("platform".to_string()), Currency::Usd).held(), Some(usd("3").units()));
+    assert!(ledger.lines().iter().any(|line| line.memo == "payment"));
+    assert!(ledger.to_csv().contains("platform_fee"));
+}
+
+#[test]
+fn test_cross_currency_payments_balance_per_currency() {
+    licensed();
+    let table = Arc::new(StaticRates::new().with_rate(Currency::Usd, Currency::Eur, "0.9").unwrap());
+    let policy = FxPolicy { spread_bps: 100, max_slippage_bps: 50, max_rate_age_secs: 60 };
+    let mut treasury = Treasury::new("org-1").unwrap().with_exchange_rates(ExchangeRates::new(policy).with_provider(table));
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", usd("100")).unwrap();
+
+    let quote = treasury.quote_conversion(usd("50"), Currency::Eur).unwrap();
+    treasury.pay_with_conversion("alice", "bob", &quote).unwrap();
+
+    assert!(treasury.reconcile().is_balanced());
+    let eur = |account| treasury.ledger().balance(&account, Currency::Eur);
+    assert_eq!(eur(Account::Wallet("bob".to_string())).held(), Some(44_55));
+    // The exchange paid out 44.55 EUR for 50 USD
+    assert_eq!(eur(Account::Exchange).debits, 44_55);
+    assert_eq!(treasury.ledger().balance(&Account::Exchange, Currency::Usd).held(), Some(50_00));
+}
+
+#[test]
+fn test_restored_ledger_detects_drift() {
+    licensed();
+    let mut treasury = Treasury::new("org-1").unwrap();
+    treasury.register_agent("alice");
+    treasury.deposit("alice", usd("100")).unwrap();
+    let saved = treasury.ledger().to_json().unwrap();
+
+    // The ledger comes back but the wallet was restored empty
+    let mut restored = Treasury::new("org-1").unwrap().with_ledger(Ledger::from_json(&saved).unwrap());
+    restored.register_agent("alice");
+    let report = restored.reconcile();
+    assert!(!report.is_balanced());
+    assert!(report.unbalanced.is_empty());
+    assert_eq!(
+        report.drift,
+        vec![Drift {
+            account: Account::Wallet("alice".to_string()),
+            ledger: AccountBalance { debits: 0, credits: usd("100").units() },
+            actual: usd("0"),
+        }]
+    );
+
+    restored.deposit("alice", usd("100")).unwrap();
+    assert_eq!(restored.reconcile().drift[0].ledger.held(), Some(usd("200").units()));
+}
+
+#[test]
+fn test_csv_export_escapes_fields() {
+    licensed();
+    let mut treasury = Treasury::new("org-1").unwrap();
+    treasury.register_agent("acme, inc");
+    treasury.deposit("acme, inc", usd("1.50")).unwrap();
+
+    let csv = treasury.ledger().to_csv();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "transaction_id,at,memo,reference,account,side,currency,units");
+    assert_eq!(rows.len(), 3);
+    assert!(rows[1].ends_with(",deposit,,external,debit,USD,150"));
+    assert!(rows[2].ends_with(",deposit,,\"wallet:acme, inc\",credit,USD,150"));
+}
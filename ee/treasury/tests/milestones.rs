@@ -0,0 +1,103 @@
+//! Escrow milestones and partial releases.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::usd;
+
+fn treasury() -> Treasury {
+    let mut treasury = common::treasury(&["client", "worker"]);
+    treasury.deposit("client", usd("1000")).unwrap();
+    treasury
+}
+
+fn stages() -> Vec<Milestone> {
+    vec![
+        Milestone::new("design", 20, "design doc approved"),
+        Milestone::new("build", 50, "tests pass"),
+        Milestone::new("deploy", 30, "deployed to production"),
+    ]
+}
+
+#[test]
+fn test_milestones_release_in_stages() {
+    let events = TreasuryEvents::default();
+    let mut received = events.subscribe();
+    let mut treasury = treasury().with_events(events);
+    let escrow_id = treasury.create_milestone_escrow("client", "worker", usd("500"), stages(), 72).unwrap();
+
+    assert_eq!(treasury.release_milestone(&escrow_id, "build").unwrap(), usd("250"));
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("250"));
+    let escrow = treasury.escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(escrow.remaining(), usd("250"));
+    assert!(escrow.milestone("build").unwrap().is_released());
+    assert!(treasury.reconcile().is_balanced());
+
+    assert!(matches!(treasury.release_milestone(&escrow_id, "build"), Err(TreasuryError::InvalidMilestone { .. })));
+    assert!(matches!(treasury.release_milestone(&escrow_id, "launch"), Err(TreasuryError::InvalidMilestone { .. })));
+
+    treasury.release_milestone(&escrow_id, "design").unwrap();
+    treasury.release_milestone(&escrow_id, "deploy").unwrap();
+    assert_eq!(treasury.escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("500"));
+    assert!(treasury.reconcile().is_balanced());
+
+    let event = received.try_recv().unwrap();
+    assert_eq!(
+        event.kind,
+        TreasuryEventKind::EscrowMilestoneReleased {
+            escrow_id,
+            milestone: "build".to_string(),
+            from_agent: "client".to_string(),
+            to_agent: "worker".to_string(),
+            amount: usd("250"),
+            remaining: usd("250"),
+        }
+    );
+}
+
+#[test]
+fn test_last_milestone_takes_the_remainder() {
+    let mut treasury = treasury();
+    let thirds = vec![Milestone::new("a", 33, "a"), Milestone::new("b", 33, "b"), Milestone::new("c", 34, "c")];
+    let escrow_id = treasury.create_milestone_escrow("client", "worker", usd("0.10"), thirds, 1).unwrap();
+
+    assert_eq!(treasury.release_milestone(&escrow_id, "c").unwrap(), usd("0.03"));
+    assert_eq!(treasury.release_milestone(&escrow_id, "a").unwrap(), usd("0.03"));
+    assert_eq!(treasury.release_milestone(&escrow_id, "b").unwrap(), usd("0.04"));
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("0.10"));
+    assert!(treasury.reconcile().is_balanced());
+}
+
+#[test]
+fn test_refund_and_release_cover_only_what_is_held() {
+    let mut treasury = treasury();
+    let refunded = treasury.create_milestone_escrow("client", "worker", usd("100"), stages(), 24).unwrap();
+    treasury.release_milestone(&refunded, "design").unwrap();
+    treasury.refund_escrow(&refunded).unwrap();
+    assert_eq!(treasury.balance("client", Currency::Usd).unwrap(), usd("980"));
+
+    let released = treasury.create_milestone_escrow("client", "worker", usd("100"), stages(), 24).unwrap();
+    treasury.release_milestone(&released, "build").unwrap();
+    treasury.release_escrow(&released).unwrap();
+    assert_eq!(treasury.balance("worker", Currency::Usd).unwrap(), usd("120"));
+    assert!(treasury.release_milestone(&released, "deploy").is_err());
+    assert!(treasury.reconcile().is_balanced());
+}
+
+#[test]
+fn test_invalid_milestones_are_rejected() {
+    let mut treasury = treasury();
+    for milestones in [
+        vec![],
+        vec![Milestone::new("design", 40, "x"), Milestone::new("build", 50, "y")],
+        vec![Milestone::new("build", 50, "x"), Milestone::new("build", 50, "y")],
+        vec![Milestone::new("build", 100, "x"), Milestone::new("deploy", 0, "y")],
+    ] {
+        let result = treasury.create_milestone_escrow("client", "worker", usd("100"), milestones, 24);
+        assert!(matches!(result, Err(TreasuryError::InvalidMilestone { .. })));
+    }
+    // Nothing was locked
+    assert_eq!(treasury.balance("client", Currency::Usd).unwrap(), usd("1000"));
+}
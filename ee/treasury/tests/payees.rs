@@ -0,0 +1,99 @@
+//! Payee deny lists, address books and escalated payee approval.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::usd;
+use std::sync::{Arc, Mutex};
+
+fn treasury(policy: PayeePolicy) -> Treasury {
+    let mut treasury = common::treasury(&["alice", "bob", "carol", "mallory"]).with_payee_policy(policy);
+    treasury.deposit("alice", usd("1000")).unwrap();
+    treasury
+}
+
+#[test]
+fn test_allow_list_and_deny_list_are_enforced() {
+    let mut treasury = treasury(PayeePolicy::allow_listed().with_denied("mallory"));
+
+    let err = treasury.pay("alice", "bob", usd("10")).unwrap_err();
+    assert!(matches!(err, TreasuryError::PayeeNotAllowed { ref payee, .. } if payee == "bob"));
+    assert!(matches!(treasury.open_channel("alice", "bob", usd("10")), Err(TreasuryError::PayeeNotAllowed { .. })));
+
+    // Without an approval threshold, payees are added at once
+    let request = treasury.request_payee("vendors", "bobco", "bob", Some(usd("50")), "alice").unwrap();
+    assert_eq!(request.status, PayeeRequestStatus::Approved);
+    treasury.pay_named("alice", "vendors", "bobco", usd("50")).unwrap();
+    let err = treasury.pay("alice", "bob", usd("51")).unwrap_err();
+    assert!(matches!(err, TreasuryError::PayeeNotAllowed { .. }));
+    treasury.open_channel("alice", "bob", usd("20")).unwrap();
+
+    // Denied payees cannot be added, and denying one blocks it everywhere
+    let err = treasury.request_payee("vendors", "mal", "mallory", None, "alice").unwrap_err();
+    assert!(matches!(err, TreasuryError::PayeeNotAllowed { .. }));
+    treasury.deny_payee("bob");
+    assert!(matches!(treasury.pay("alice", "bob", usd("1")), Err(TreasuryError::PayeeNotAllowed { .. })));
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("930"));
+
+    // Open mode pays anyone who is not denied
+    let mut open = self::treasury(PayeePolicy::open().with_denied("mallory"));
+    open.pay("alice", "carol", usd("5")).unwrap();
+    assert!(matches!(open.pay("alice", "mallory", usd("5")), Err(TreasuryError::PayeeNotAllowed { .. })));
+}
+
+#[test]
+fn test_payees_above_threshold_are_escalated() {
+    let escalated = Arc::new(Mutex::new(Vec::new()));
+    let seen = escalated.clone();
+    let mut treasury = treasury(PayeePolicy::allow_listed().with_approval_threshold(usd("100"))).with_payee_escalation(
+        move |request: &PayeeRequest| {
+            seen.lock().unwrap().push(request.agent_id.clone());
+            Ok(format!("arbiter-{}", request.id))
+        },
+    );
+
+    let small = treasury.request_payee("vendors", "bobco", "bob", Some(usd("100")), "alice").unwrap();
+    assert_eq!(small.status, PayeeRequestStatus::Approved);
+
+    let large = treasury.request_payee("partners", "carol", "carol", Some(usd("500")), "alice").unwrap();
+    assert_eq!(large.status, PayeeRequestStatus::Pending);
+    assert_eq!(large.escalation_id, Some(format!("arbiter-{}", large.id)));
+    assert_eq!(*escalated.lock().unwrap(), vec!["carol".to_string()]);
+    assert!(matches!(treasury.pay("alice", "carol", usd("200")), Err(TreasuryError::PayeeNotAllowed { .. })));
+
+    // The requester cannot approve its own payee
+    let err = treasury.approve_payee(&large.id, "alice").unwrap_err();
+    assert!(matches!(err, TreasuryError::PayeeNotAllowed { .. }));
+    let payee = treasury.approve_payee(&large.id, "compliance@example.com").unwrap();
+    assert_eq!((payee.agent_id.as_str(), payee.approved_by.as_str()), ("carol", "compliance@example.com"));
+    treasury.pay_named("alice", "partners", "carol", usd("200")).unwrap();
+    assert_eq!(treasury.pending_payee_requests().count(), 0);
+
+    // Decided requests cannot be decided again; rejected payees are never added
+    let err = treasury.reject_payee(&large.id, "compliance@example.com").unwrap_err();
+    assert!(matches!(err, TreasuryError::PayeeRequestNotFound { .. }));
+    let uncapped = treasury.request_payee("vendors", "mal", "mallory", None, "alice").unwrap();
+    treasury.reject_payee(&uncapped.id, "compliance@example.com").unwrap();
+    assert!(treasury.address_book("vendors").unwrap().get("mal").is_none());
+    assert_eq!(treasury.payee_request(&uncapped.id).unwrap().status, PayeeRequestStatus::Rejected);
+
+    assert!(treasury.remove_payee("partners", "carol").is_some());
+    assert!(matches!(treasury.pay("alice", "carol", usd("1")), Err(TreasuryError::PayeeNotAllowed { .. })));
+}
+
+#[test]
+fn test_escrows_are_checked_against_payee_policy() {
+    let mut treasury = treasury(PayeePolicy::allow_listed().with_denied("mallory"));
+    treasury.request_payee("vendors", "bobco", "bob", Some(usd("50")), "alice").unwrap();
+
+    let refused = |result: Result<String, TreasuryError>| matches!(result, Err(TreasuryError::PayeeNotAllowed { .. }));
+    assert!(refused(treasury.create_escrow("alice", "mallory", usd("10"), "delivered", 1)));
+    assert!(refused(treasury.create_escrow("alice", "carol", usd("10"), "delivered", 1)));
+    // Over bob's cap
+    assert!(refused(treasury.create_escrow("alice", "bob", usd("60"), "delivered", 1)));
+    let escrow = treasury.create_escrow("alice", "bob", usd("50"), "delivered", 1).unwrap();
+    treasury.release_escrow(&escrow).unwrap();
+
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("950"));
+    assert_eq!(treasury.balance("bob", Currency::Usd).unwrap(), usd("50"));
+}
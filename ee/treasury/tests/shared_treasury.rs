@@ -0,0 +1,110 @@
+//! Shared treasury: concurrent payments under per-entity locks and the blocking facade.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::{credits, licensed};
+use ed25519_dalek::{Signer, SigningKey};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_opposing_payments_do_not_deadlock() {
+    licensed();
+    let treasury = SharedTreasury::new("org-1").unwrap();
+    for agent in ["alice", "bob", "carol"] {
+        treasury.register_agent(agent);
+        treasury.deposit(agent, credits("1000")).await.unwrap();
+    }
+
+    // Each pair pays in both directions at once; unordered locking would deadlock
+    let pairs = [("alice", "bob"), ("bob", "alice"), ("bob", "carol"), ("carol", "alice")];
+    let mut tasks = Vec::new();
+    for i in 0..200 {
+        let treasury = treasury.clone();
+        let (from, to) = pairs[i % pairs.len()];
+        tasks.push(tokio::spawn(async move { treasury.pay(from, to, credits("1")).await }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let mut total = Money::zero(Currency::Credits);
+    for agent in ["alice", "bob", "carol"] {
+        total = total.checked_add(treasury.balance(agent, Currency::Credits).await.unwrap()).unwrap();
+    }
+    assert_eq!(total, credits("3000"));
+    assert_eq!(treasury.balance("alice", Currency::Credits).await.unwrap(), credits("1050"));
+    assert_eq!(treasury.balance("bob", Currency::Credits).await.unwrap(), credits("950"));
+    assert_eq!(treasury.payments().len(), 200);
+}
+
+#[test]
+fn test_blocking_facade_releases_with_threshold_signatures() {
+    licensed();
+    let keys = [("cfo", SigningKey::from_bytes(&[1; 32])), ("treasurer", SigningKey::from_bytes(&[2; 32]))];
+    let mut ceremony = KeyCeremony::new(2);
+    for (id, key) in &keys {
+        let public = key.verifying_key().to_bytes();
+        let proof = key.sign(&ceremony.challenge(id, &public));
+        ceremony.register(id, public, &proof.to_bytes()).unwrap();
+    }
+    let signers = ceremony.finish().unwrap().with_limit(credits("50"));
+
+    let treasury = BlockingTreasury::new(SharedTreasury::new("org-1").unwrap().with_signers(signers)).unwrap();
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", credits("200")).unwrap();
+
+    let escrow = treasury.create_escrow("alice", "bob", credits("100"), "delivered", 1).unwrap();
+    assert!(matches!(treasury.release_escrow(&escrow), Err(TreasuryError::SignaturesRequired { .. })));
+
+    let session = treasury.open_escrow_release(&escrow).unwrap();
+    for (id, key) in &keys {
+        treasury.sign(&session.id, id, &key.sign(&session.signing_bytes()).to_bytes()).unwrap();
+    }
+    treasury.release_escrow(&escrow).unwrap();
+
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits("100"));
+    assert!(treasury.shared().signing_session(&session.id).is_none());
+    // Released once; the claimed approval is gone
+    assert!(treasury.release_escrow(&escrow).is_err());
+}
+
+#[test]
+fn test_budgets_limit_shared_payments_and_channels() {
+    licensed();
+    let budget = AgentBudget::new().with_daily_limit(credits("100"));
+    let treasury = BlockingTreasury::new(SharedTreasury::new("org-1").unwrap().with_budget("alice", budget)).unwrap();
+    treasury.register_agent("alice");
+    treasury.register_agent("bob");
+    treasury.deposit("alice", credits("80")).unwrap();
+
+    // A failed payment doesn't count toward the budget
+    assert!(matches!(treasury.pay("alice", "bob", credits("90")), Err(TreasuryError::InsufficientBalance { .. })));
+    treasury.pay("alice", "bob", credits("60")).unwrap();
+    assert!(matches!(treasury.pay("alice", "bob", credits("50")), Err(TreasuryError::BudgetExceeded { .. })));
+    assert!(matches!(treasury.open_channel("alice", "bob", credits("50")), Err(TreasuryError::BudgetExceeded { .. })));
+    treasury.open_channel("alice", "bob", credits("20")).unwrap();
+
+    assert_eq!(treasury.balance("alice", Currency::Credits).unwrap(), Money::zero(Currency::Credits));
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits("60"));
+}
+
+#[test]
+fn test_payee_policy_applies_to_shared_treasury() {
+    licensed();
+    let policy = PayeePolicy::open().with_denied("mallory");
+    let treasury = BlockingTreasury::new(SharedTreasury::new("org-1").unwrap().with_payee_policy(policy)).unwrap();
+    for agent in ["alice", "bob", "mallory"] {
+        treasury.register_agent(agent);
+    }
+    treasury.deposit("alice", credits("100")).unwrap();
+
+    let refused = |result: Result<String, TreasuryError>| matches!(result, Err(TreasuryError::PayeeNotAllowed { .. }));
+    assert!(refused(treasury.pay("alice", "mallory", credits("10"))));
+    assert!(refused(treasury.open_channel("alice", "mallory", credits("10"))));
+    assert!(refused(treasury.create_escrow("alice", "mallory", credits("10"), "delivered", 1)));
+    treasury.pay("alice", "bob", credits("10")).unwrap();
+
+    assert_eq!(treasury.balance("alice", Currency::Credits).unwrap(), credits("90"));
+    assert_eq!(treasury.balance("mallory", Currency::Credits).unwrap(), Money::zero(Currency::Credits));
+}
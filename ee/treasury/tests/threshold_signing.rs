@@ -0,0 +1,166 @@
+//! Key ceremony and threshold approval of high-value escrow releases.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::credits;
+use ed25519_dalek::{Signer, SigningKey};
+
+fn signers() -> Vec<(&'static str, SigningKey)> {
+    vec![
+        ("cfo", SigningKey::from_bytes(&[1; 32])),
+        ("treasurer", SigningKey::from_bytes(&[2; 32])),
+        ("auditor", SigningKey::from_bytes(&[3; 32])),
+    ]
+}
+
+fn ceremony(keys: &[(&str, SigningKey)]) -> SignerSet {
+    let mut ceremony = KeyCeremony::new(2);
+    for (id, key) in keys {
+        let public = key.verifying_key().to_bytes();
+        let proof = key.sign(&ceremony.challenge(id, &public));
+        ceremony.register(id, public, &proof.to_bytes()).unwrap();
+    }
+    ceremony.finish().unwrap().with_limit(credits("50"))
+}
+
+fn treasury(keys: &[(&str, SigningKey)]) -> Treasury {
+    let mut treasury = common::treasury(&["alice", "bob"]).with_signers(ceremony(keys));
+    treasury.deposit("alice", credits("200")).unwrap();
+    treasury
+}
+
+#[test]
+fn test_ceremony_requires_proof_of_possession() {
+    let keys = signers();
+    let mut ceremony = KeyCeremony::new(2);
+    let public = keys[0].1.verifying_key().to_bytes();
+
+    // Signed by someone else's key
+    let forged = keys[1].1.sign(&ceremony.challenge("cfo", &public));
+    assert!(matches!(
+        ceremony.register("cfo", public, &forged.to_bytes()),
+        Err(TreasuryError::InvalidSigner { .. })
+    ));
+
+    let proof = keys[0].1.sign(&ceremony.challenge("cfo", &public));
+    ceremony.register("cfo", public, &proof.to_bytes()).unwrap();
+    assert!(ceremony.register("cfo", public, &proof.to_bytes()).is_err());
+
+    // Threshold of 2 with a single signer
+    assert!(matches!(ceremony.finish(), Err(TreasuryError::InvalidSigner { .. })));
+}
+
+#[test]
+fn test_large_release_needs_threshold_signatures() {
+    let keys = signers();
+    let mut treasury = treasury(&keys);
+    let small = treasury.create_escrow("alice", "bob", credits("10"), "delivered", 1).unwrap();
+    let large = treasury.create_escrow("alice", "bob", credits("100"), "delivered", 1).unwrap();
+
+    // Below the limit: no signatures needed
+    treasury.release_escrow(&small).unwrap();
+
+    assert!(matches!(
+        treasury.release_escrow(&large),
+        Err(TreasuryError::SignaturesRequired { required: 2, collected: 0 })
+    ));
+
+    let session = treasury.open_escrow_release(&large).unwrap();
+    let message = session.signing_bytes();
+    let sign = |i: usize| keys[i].1.sign(&message).to_bytes();
+
+    assert_eq!(treasury.sign(&session.id, "cfo", &sign(0)).unwrap(), 1);
+    assert!(matches!(
+        treasury.release_escrow(&large),
+        Err(TreasuryError::SignaturesRequired { required: 2, collected: 1 })
+    ));
+    // Wrong key for the claimed signer
+    assert!(treasury.sign(&session.id, "auditor", &sign(1)).is_err());
+    assert_eq!(treasury.sign(&session.id, "treasurer", &sign(1)).unwrap(), 2);
+
+    treasury.release_escrow(&large).unwrap();
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits("110"));
+    assert!(treasury.signing_session(&session.id).is_none());
+
+    // Opened, cfo, auditor (rejected), treasurer, executed
+    let audit = treasury.signing_audit();
+    let participants: Vec<_> = audit.iter().map(|r| r.signer_id.as_deref()).collect();
+    assert_eq!(participants, [None, Some("cfo"), Some("auditor"), Some("treasurer"), None]);
+    assert!(matches!(audit[2].event, SigningEvent::Rejected { .. }));
+    let SigningEvent::Executed { signers } = &treasury.signing_audit()[4].event else {
+        panic!("expected execution record");
+    };
+    assert_eq!(signers, &["cfo", "treasurer"]);
+}
+
+#[test]
+fn test_milestones_of_large_escrow_need_signatures() {
+    let keys = signers();
+    let mut treasury = treasury(&keys);
+    // Every share is below the limit, the escrow is not
+    let stages = vec![Milestone::new("design", 40, "approved"), Milestone::new("build", 60, "tests pass")];
+    let escrow_id = treasury.create_milestone_escrow("alice", "bob", credits("100"), stages, 1).unwrap();
+
+    assert!(matches!(
+        treasury.release_milestone(&escrow_id, "design"),
+        Err(TreasuryError::SignaturesRequired { required: 2, collected: 0 })
+    ));
+
+    // Signatures approve one milestone, not the other
+    let session = treasury.open_milestone_release(&escrow_id, "design").unwrap();
+    let message = session.signing_bytes();
+    for (id, key) in &keys[..2] {
+        treasury.sign(&session.id, id, &key.sign(&message).to_bytes()).unwrap();
+    }
+    assert!(matches!(treasury.release_milestone(&escrow_id, "build"), Err(TreasuryError::SignaturesRequired { .. })));
+    assert_eq!(treasury.release_milestone(&escrow_id, "design").unwrap(), credits("40"));
+    assert!(matches!(treasury.release_milestone(&escrow_id, "build"), Err(TreasuryError::SignaturesRequired { .. })));
+}
+
+#[test]
+fn test_large_channels_need_signatures() {
+    let keys = signers();
+    let mut treasury = treasury(&keys);
+
+    // Channel funding can't sidestep the payment limit
+    assert!(matches!(
+        treasury.open_channel("alice", "bob", credits("100")),
+        Err(TreasuryError::SignaturesRequired { required: 2, collected: 0 })
+    ));
+    assert_eq!(treasury.balance("alice", Currency::Credits).unwrap(), credits("200"));
+
+    // A payment approval doesn't open a channel
+    let session = treasury.open_payment("alice", "bob", credits("100")).unwrap();
+    for (id, key) in &keys[..2] {
+        treasury.sign(&session.id, id, &key.sign(&session.signing_bytes()).to_bytes()).unwrap();
+    }
+    assert!(matches!(treasury.open_channel("alice", "bob", credits("100")), Err(TreasuryError::SignaturesRequired { .. })));
+
+    let session = treasury.open_channel_funding("alice", "bob", credits("100")).unwrap();
+    for (id, key) in &keys[..2] {
+        treasury.sign(&session.id, id, &key.sign(&session.signing_bytes()).to_bytes()).unwrap();
+    }
+    let channel_id = treasury.open_channel("alice", "bob", credits("100")).unwrap();
+    treasury.channel_transfer(&channel_id, true, credits("100")).unwrap();
+    assert_eq!(treasury.close_channel(&channel_id).unwrap(), (credits("0"), credits("100")));
+}
+
+#[test]
+fn test_large_transfers_need_signatures() {
+    let keys = signers();
+    let mut treasury = treasury(&keys);
+    let request = TransferRequest::new("alice", "bob", credits("60"));
+
+    assert!(matches!(treasury.transfer(request.clone()), Err(TreasuryError::SignaturesRequired { .. })));
+    assert!(matches!(treasury.prepare_transfer(request.clone()), Err(TreasuryError::SignaturesRequired { .. })));
+
+    let session = treasury.open_payment("alice", "bob", credits("60")).unwrap();
+    for (id, key) in &keys[..2] {
+        treasury.sign(&session.id, id, &key.sign(&session.signing_bytes()).to_bytes()).unwrap();
+    }
+    treasury.transfer(request.clone()).unwrap();
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits("60"));
+    // The approval is spent
+    assert!(matches!(treasury.transfer(request), Err(TreasuryError::SignaturesRequired { .. })));
+}
@@ -0,0 +1,94 @@
+//! Two-phase transfers, idempotency keys and journal recovery.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use common::usd;
+
+fn treasury(journal: TransferJournal) -> Treasury {
+    common::treasury(&["alice", "bob"]).with_journal(journal)
+}
+
+#[test]
+fn test_prepared_funds_are_held_until_resolved() {
+    let mut treasury = treasury(TransferJournal::in_memory());
+    treasury.deposit("alice", usd("100")).unwrap();
+
+    let committed = treasury.prepare_transfer(TransferRequest::new("alice", "bob", usd("30"))).unwrap();
+    let aborted = treasury.prepare_transfer(TransferRequest::new("alice", "bob", usd("20"))).unwrap();
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("50"));
+    assert_eq!(treasury.balance("bob", Currency::Usd).unwrap(), usd("0"));
+
+    treasury.commit_transfer(&committed).unwrap();
+    treasury.abort_transfer(&aborted, "cancelled").unwrap();
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("70"));
+    assert_eq!(treasury.balance("bob", Currency::Usd).unwrap(), usd("30"));
+
+    // Resolved transfers cannot be resolved again
+    assert!(matches!(treasury.commit_transfer(&aborted), Err(TreasuryError::TransferNotFound { .. })));
+    assert!(treasury.journal().incomplete().is_empty());
+    assert_eq!(treasury.journal().entries().len(), 4);
+
+    // Payments go through the same journal
+    treasury.pay("alice", "bob", usd("5")).unwrap();
+    assert_eq!(treasury.journal().entries().len(), 6);
+}
+
+#[test]
+fn test_recovery_refunds_incomplete_and_keeps_idempotency() {
+    let path = std::env::temp_dir().join(format!("transfers-{}.jsonl", uuid::Uuid::new_v4()));
+    let paid = TransferRequest::new("alice", "bob", usd("40")).with_idempotency_key("invoice-1");
+
+    {
+        let mut treasury = treasury(TransferJournal::open(&path).unwrap());
+        treasury.deposit("alice", usd("100")).unwrap();
+        let first = treasury.transfer(paid.clone()).unwrap();
+        assert_eq!(treasury.transfer(paid.clone()).unwrap(), first);
+
+        // Crash between the phases
+        treasury.prepare_transfer(TransferRequest::new("alice", "bob", usd("25"))).unwrap();
+        assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("35"));
+    }
+
+    // Restart with the wallets as they were persisted
+    let mut treasury = treasury(TransferJournal::open(&path).unwrap());
+    treasury.deposit("alice", usd("35")).unwrap();
+    treasury.deposit("bob", usd("40")).unwrap();
+    assert_eq!(treasury.journal().incomplete().len(), 1);
+
+    let recovery = treasury.recover().unwrap();
+    assert_eq!(recovery.aborted.len(), 1);
+    assert_eq!(recovery.idempotency_keys, 1);
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("60"));
+    assert!(treasury.journal().incomplete().is_empty());
+
+    // A retry after the restart does not pay twice
+    treasury.transfer(paid).unwrap();
+    assert_eq!(treasury.balance("bob", Currency::Usd).unwrap(), usd("40"));
+
+    // Nothing left to replay
+    assert!(treasury.recover().unwrap().aborted.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+
+#[test]
+fn test_transfers_are_checked_like_payments() {
+    let mut treasury = treasury(TransferJournal::in_memory())
+        .with_payee_policy(PayeePolicy::open().with_denied("mallory"))
+        .with_budget("alice", AgentBudget::new().with_daily_limit(usd("50")));
+    treasury.register_agent("mallory");
+    treasury.deposit("alice", usd("100")).unwrap();
+
+    assert!(matches!(
+        treasury.transfer(TransferRequest::new("alice", "mallory", usd("5"))),
+        Err(TreasuryError::PayeeNotAllowed { .. })
+    ));
+    treasury.prepare_transfer(TransferRequest::new("alice", "bob", usd("40"))).unwrap();
+    assert!(matches!(
+        treasury.transfer(TransferRequest::new("alice", "bob", usd("20"))),
+        Err(TreasuryError::BudgetExceeded { .. })
+    ));
+    assert_eq!(treasury.balance("alice", Currency::Usd).unwrap(), usd("60"));
+    assert_eq!(treasury.remaining_budget("alice", Currency::Usd), Some(usd("10")));
+}
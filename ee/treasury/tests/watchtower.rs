@@ -0,0 +1,139 @@
+//! Signed channel closes and the watchtower contesting revoked states.
+
+mod common;
+
+use agentkern_treasury_ee::*;
+use ed25519_dalek::SigningKey;
+
+struct Parties {
+    alice: SigningKey,
+    bob: SigningKey,
+}
+
+impl Parties {
+    fn new() -> Self {
+        Self { alice: SigningKey::from_bytes(&[1; 32]), bob: SigningKey::from_bytes(&[2; 32]) }
+    }
+
+    fn keys(&self) -> ChannelKeys {
+        ChannelKeys { party_a: self.alice.verifying_key().to_bytes(), party_b: self.bob.verifying_key().to_bytes() }
+    }
+
+    fn sign(&self, state: ChannelState) -> SignedChannelState {
+        SignedChannelState { signature_a: state.sign(&self.alice), signature_b: state.sign(&self.bob), state }
+    }
+}
+
+fn treasury() -> Treasury {
+    let mut treasury = common::treasury(&["alice", "bob"]);
+    treasury.deposit("alice", credits(100.0)).unwrap();
+    treasury
+}
+
+fn credits(amount: f64) -> Money {
+    Money::try_from((amount, Currency::Credits)).unwrap()
+}
+
+fn state(channel_id: &str, sequence: u64, alice: f64, bob: f64) -> ChannelState {
+    let units = |amount| credits(amount).units();
+    ChannelState { channel_id: channel_id.to_string(), sequence, balance_a: units(alice), balance_b: units(bob) }
+}
+
+#[test]
+fn test_watchtower_contests_revoked_close() {
+    let parties = Parties::new();
+    let mut treasury = treasury();
+    let id = treasury
+        .open_signed_channel("alice", "bob", credits(100.0), parties.keys(), chrono::Duration::hours(1))
+        .unwrap();
+    assert!(treasury.channel_transfer(&id, true, credits(10.0)).is_err());
+
+    let opening = parties.sign(treasury.channel(&id).unwrap().opening_state());
+    let first = parties.sign(state(&id, 1, 70.0, 30.0));
+    let latest = parties.sign(state(&id, 2, 40.0, 60.0));
+
+    // Bob goes offline after handing the tower justice for every state he revoked
+    let mut tower = Watchtower::new();
+    tower.delegate(JusticeBlob::seal(&opening, &latest).unwrap());
+    tower.delegate(JusticeBlob::seal(&first, &latest).unwrap());
+    assert!(JusticeBlob::seal(&latest, &first).is_err());
+
+    // A state only Alice signed is not accepted
+    let mut forged = parties.sign(state(&id, 3, 100.0, 0.0));
+    forged.signature_b = forged.state.sign(&parties.alice);
+    assert!(matches!(
+        treasury.request_close(&id, &forged, "alice"),
+        Err(TreasuryError::InvalidChannelState { .. })
+    ));
+
+    treasury.request_close(&id, &first, "alice").unwrap();
+    assert!(matches!(treasury.finalize_close(&id), Err(TreasuryError::DisputePeriodActive { .. })));
+
+    let contested = tower.patrol(&mut treasury);
+    assert_eq!(contested.len(), 1);
+    assert_eq!(contested[0].penalized, "alice");
+    assert_eq!((contested[0].revoked_sequence, contested[0].justice_sequence), (1, 2));
+    assert_eq!(contested[0].payout, (credits(0.0), credits(100.0)));
+    assert_eq!(treasury.balance("alice", Currency::Credits).unwrap(), credits(0.0));
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits(100.0));
+    assert!(!treasury.channel(&id).unwrap().is_open);
+    assert!(tower.is_empty());
+}
+
+#[test]
+fn test_honest_close_settles_after_dispute_period() {
+    let parties = Parties::new();
+    let mut treasury = treasury();
+    let id = treasury
+        .open_signed_channel("alice", "bob", credits(100.0), parties.keys(), chrono::Duration::zero())
+        .unwrap();
+
+    let first = parties.sign(state(&id, 1, 70.0, 30.0));
+    let latest = parties.sign(state(&id, 2, 40.0, 60.0));
+    let mut tower = Watchtower::new();
+    tower.delegate(JusticeBlob::seal(&first, &latest).unwrap());
+
+    treasury.request_close(&id, &latest, "bob").unwrap();
+    assert!(tower.patrol(&mut treasury).is_empty());
+    assert!(matches!(treasury.request_close(&id, &first, "alice"), Err(TreasuryError::DisputePeriodActive { .. })));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(treasury.finalize_close(&id).unwrap(), (credits(40.0), credits(60.0)));
+    assert_eq!(treasury.balance("alice", Currency::Credits).unwrap(), credits(40.0));
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits(60.0));
+    assert!(matches!(treasury.finalize_close(&id), Err(TreasuryError::NoPendingClose)));
+}
+
+#[test]
+fn test_force_close_settles_latest_checkpoint_without_counterparty() {
+    let parties = Parties::new();
+    let mut treasury = treasury();
+    treasury.deposit("alice", credits(50.0)).unwrap();
+    let id = treasury
+        .open_signed_channel("alice", "bob", credits(100.0), parties.keys(), chrono::Duration::zero())
+        .unwrap();
+
+    let first = parties.sign(state(&id, 1, 70.0, 30.0));
+    let latest = parties.sign(state(&id, 2, 40.0, 60.0));
+    assert_eq!(treasury.update_channel_state(&id, &latest).unwrap(), 2);
+    assert!(matches!(treasury.update_channel_state(&id, &first), Err(TreasuryError::InvalidChannelState { .. })));
+    assert_eq!(treasury.channel(&id).unwrap().balance_b, credits(60.0).units());
+    assert!(matches!(treasury.request_close(&id, &first, "alice"), Err(TreasuryError::InvalidChannelState { .. })));
+
+    // Alice stops responding; Bob closes on the checkpoint alone
+    treasury.force_close(&id, "bob").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let settled = treasury.settle_expired_closes();
+    assert_eq!(settled.len(), 1);
+    assert_eq!(settled[0], (id.clone(), (credits(40.0), credits(60.0))));
+    assert_eq!(treasury.balance("bob", Currency::Credits).unwrap(), credits(60.0));
+
+    // Without checkpoints a force-close returns the opening balance, and
+    // waits out the dispute period like any other close
+    let idle = treasury
+        .open_signed_channel("alice", "bob", credits(50.0), parties.keys(), chrono::Duration::hours(1))
+        .unwrap();
+    treasury.force_close(&idle, "alice").unwrap();
+    assert!(treasury.settle_expired_closes().is_empty());
+    assert_eq!(treasury.pending_closes().next().unwrap().1.state.state.sequence, 0);
+}
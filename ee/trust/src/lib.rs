@@ -10,11 +10,16 @@
 //! - Trust-based access control
 //! - Behavioral scoring
 //! - Cross-organization reputation sharing
+//! - Per-tenant tier thresholds with promotion hysteresis
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
+pub mod tiers;
+
+pub use tiers::{PendingPromotion, TierConfigError, TierPolicy, TierThresholds};
+
 mod license {
     #[derive(Debug, thiserror::Error)]
     pub enum LicenseError {
@@ -62,7 +67,7 @@ impl Default for ReputationScore {
     }
 }
 
-/// Trust tier levels.
+/// Trust tier levels, with their default score ranges.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TrustTier {
@@ -81,29 +86,14 @@ pub enum TrustTier {
 }
 
 impl TrustTier {
-    /// Get tier from numeric score.
+    /// Get tier from numeric score under the active thresholds.
     pub fn from_score(score: u16) -> Self {
-        match score {
-            0..=199 => Self::Blacklisted,
-            200..=399 => Self::Untrusted,
-            400..=599 => Self::Unknown,
-            600..=799 => Self::Trusted,
-            800..=899 => Self::Verified,
-            900..=1000 => Self::Elite,
-            _ => Self::Elite, // Cap at Elite
-        }
+        TierThresholds::active().tier_for(score)
     }
 
-    /// Get minimum score for this tier.
+    /// Get minimum score for this tier under the active thresholds.
     pub fn min_score(&self) -> u16 {
-        match self {
-            Self::Blacklisted => 0,
-            Self::Untrusted => 200,
-            Self::Unknown => 400,
-            Self::Trusted => 600,
-            Self::Verified => 800,
-            Self::Elite => 900,
-        }
+        TierThresholds::active().min_score(*self)
     }
 
     /// Check if tier allows high-risk actions.
@@ -191,6 +181,17 @@ pub struct AgentRecord {
     pub blacklisted: bool,
     /// Blacklist reason
     pub blacklist_reason: Option<String>,
+    /// Promotion waiting out the tenant's hold
+    #[serde(default)]
+    pub pending_promotion: Option<PendingPromotion>,
+}
+
+/// Contents of a [`TrustNetwork`], for backup and restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustNetworkState {
+    pub agents: Vec<AgentRecord>,
+    /// Trust relationships (agent -> agents they trust)
+    pub trust_graph: HashMap<String, Vec<String>>,
 }
 
 /// Trust network for agent-to-agent reputation.
@@ -200,6 +201,10 @@ pub struct TrustNetwork {
     agents: HashMap<String, AgentRecord>,
     /// Trust relationships (agent -> agents they trust)
     trust_graph: HashMap<String, Vec<String>>,
+    /// Tier policy of tenants without their own
+    default_policy: TierPolicy,
+    /// Tier policies by organization
+    tenant_policies: HashMap<String, TierPolicy>,
 }
 
 impl TrustNetwork {
@@ -210,9 +215,66 @@ impl TrustNetwork {
         Ok(Self {
             agents: HashMap::new(),
             trust_graph: HashMap::new(),
+            default_policy: TierPolicy::new(TierThresholds::active()),
+            tenant_policies: HashMap::new(),
         })
     }
 
+    /// Tier policy of an organization.
+    pub fn tier_policy(&self, org_id: &str) -> &TierPolicy {
+        self.tenant_policies.get(org_id).unwrap_or(&self.default_policy)
+    }
+
+    /// Use `policy` for organizations without their own.
+    pub fn set_default_tier_policy(&mut self, policy: TierPolicy) -> Result<(), TierConfigError> {
+        policy.thresholds.validate()?;
+        self.default_policy = policy;
+        self.retier_where(|_| true, Utc::now());
+        Ok(())
+    }
+
+    /// Use `policy` for an organization's agents. Agents whose score no
+    /// longer reaches their tier are demoted at once.
+    pub fn set_tier_policy(&mut self, org_id: &str, policy: TierPolicy) -> Result<(), TierConfigError> {
+        policy.thresholds.validate()?;
+        self.tenant_policies.insert(org_id.to_string(), policy);
+        self.retier_where(|r| r.org_id == org_id, Utc::now());
+        tracing::info!(org_id = %org_id, policy = ?policy, "Trust tier policy set");
+        Ok(())
+    }
+
+    /// Promote agents whose hold has passed since their last event.
+    /// Returns the agents whose tier changed.
+    pub fn refresh_tiers(&mut self, now: DateTime<Utc>) -> Vec<(String, TrustTier)> {
+        self.retier_where(|_| true, now)
+    }
+
+    fn retier_where(&mut self, filter: impl Fn(&AgentRecord) -> bool, now: DateTime<Utc>) -> Vec<(String, TrustTier)> {
+        let mut changed = Vec::new();
+        for record in self.agents.values_mut().filter(|r| filter(r)) {
+            let policy = self.tenant_policies.get(&record.org_id).unwrap_or(&self.default_policy);
+            if tiers::retier(record, policy, now) {
+                changed.push((record.agent_id.clone(), record.reputation.tier));
+            }
+        }
+        changed
+    }
+
+    /// Place an agent at the bottom of `tier` at once, bypassing the
+    /// promotion hold, e.g. when onboarding. Blacklisted agents stay
+    /// blacklisted. Returns the agent's tier.
+    pub fn assign_tier(&mut self, agent_id: &str, tier: TrustTier) -> Option<TrustTier> {
+        let record = self.agents.get_mut(agent_id)?;
+        if !record.blacklisted {
+            let policy = self.tenant_policies.get(&record.org_id).unwrap_or(&self.default_policy);
+            record.reputation.score = policy.thresholds.min_score(tier);
+            record.reputation.tier = tier;
+            record.reputation.updated_at = Utc::now();
+            record.pending_promotion = None;
+        }
+        Some(record.reputation.tier)
+    }
+
     /// Register a new agent.
     pub fn register_agent(&mut self, agent_id: &str, org_id: &str) -> &AgentRecord {
         let now = Utc::now();
@@ -229,10 +291,34 @@ impl TrustNetwork {
                 last_activity: now,
                 blacklisted: false,
                 blacklist_reason: None,
+                pending_promotion: None,
             }
         })
     }
 
+    /// Remove an agent and every trust relationship involving it.
+    pub fn remove_agent(&mut self, agent_id: &str) -> Option<AgentRecord> {
+        self.trust_graph.remove(agent_id);
+        for trusted in self.trust_graph.values_mut() {
+            trusted.retain(|a| a != agent_id);
+        }
+        self.agents.remove(agent_id)
+    }
+
+    /// Copy of the agent records and trust graph.
+    pub fn export_state(&self) -> TrustNetworkState {
+        TrustNetworkState {
+            agents: self.agents.values().cloned().collect(),
+            trust_graph: self.trust_graph.clone(),
+        }
+    }
+
+    /// Replace the agent records and trust graph.
+    pub fn import_state(&mut self, state: TrustNetworkState) {
+        self.agents = state.agents.into_iter().map(|r| (r.agent_id.clone(), r)).collect();
+        self.trust_graph = state.trust_graph;
+    }
+
     /// Get agent reputation.
     pub fn get_reputation(&self, agent_id: &str) -> Option<&ReputationScore> {
         self.agents.get(agent_id).map(|r| &r.reputation)
@@ -248,6 +334,13 @@ impl TrustNetwork {
 
     /// Record an event for an agent.
     pub fn record_event(&mut self, agent_id: &str, event: ReputationEvent) {
+        self.record_event_at(agent_id, event, Utc::now());
+    }
+
+    /// Record an event for an agent at `at`. The agent is demoted at once
+    /// if its score drops below its tier, and promoted once it has held a
+    /// higher tier's score for its tenant's promotion hold.
+    pub fn record_event_at(&mut self, agent_id: &str, event: ReputationEvent, at: DateTime<Utc>) {
         if let Some(record) = self.agents.get_mut(agent_id) {
             let impact = event.impact();
             let new_score = (record.reputation.score as i32 + impact as i32)
@@ -255,9 +348,8 @@ impl TrustNetwork {
                 .min(1000) as u16;
             
             record.reputation.score = new_score;
-            record.reputation.tier = TrustTier::from_score(new_score);
-            record.reputation.updated_at = Utc::now();
-            record.last_activity = Utc::now();
+            record.reputation.updated_at = at;
+            record.last_activity = at;
             record.total_actions += 1;
             
             // Update counters based on event type
@@ -272,12 +364,14 @@ impl TrustNetwork {
                     if record.violations >= 10 {
                         record.blacklisted = true;
                         record.blacklist_reason = Some("Too many policy violations".to_string());
-                        record.reputation.tier = TrustTier::Blacklisted;
                     }
                 }
                 _ => {}
             }
             
+            let policy = self.tenant_policies.get(&record.org_id).unwrap_or(&self.default_policy);
+            tiers::retier(record, policy, at);
+            
             // Update confidence based on activity
             let confidence = ((record.total_actions as f64).log10() * 30.0).min(100.0) as u8;
             record.reputation.confidence = confidence;
@@ -382,14 +476,14 @@ mod tests {
 
     #[test]
     fn test_trust_network_requires_license() {
-        std::env::remove_var("AGENTKERN_LICENSE_KEY");
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
         let result = TrustNetwork::new();
         assert!(result.is_err());
     }
 
     #[test]
     fn test_reputation_events() {
-        std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license");
+        unsafe { std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license") };
         
         let mut network = TrustNetwork::new().unwrap();
         network.register_agent("agent-1", "org-1");
@@ -405,12 +499,12 @@ mod tests {
         
         assert!(network.get_reputation("agent-1").unwrap().score > 500);
         
-        std::env::remove_var("AGENTKERN_LICENSE_KEY");
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
     }
 
     #[test]
     fn test_blacklisting() {
-        std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license");
+        unsafe { std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license") };
         
         let mut network = TrustNetwork::new().unwrap();
         network.register_agent("bad-agent", "org-1");
@@ -420,6 +514,39 @@ mod tests {
         assert!(!network.can_perform_high_risk("bad-agent"));
         assert_eq!(network.get_trust_tier("bad-agent"), TrustTier::Blacklisted);
         
-        std::env::remove_var("AGENTKERN_LICENSE_KEY");
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
+    }
+
+    #[test]
+    fn test_tenant_tier_policies() {
+        unsafe { std::env::set_var("AGENTKERN_LICENSE_KEY", "test-license") };
+        
+        let mut network = TrustNetwork::new().unwrap();
+        network.register_agent("bank-agent", "org-bank");
+        network.register_agent("shop-agent", "org-shop");
+        let strict = TierPolicy::new(TierThresholds { trusted: 700, ..TierThresholds::DEFAULT }).with_promotion_hold_days(7);
+        network.set_tier_policy("org-bank", strict).unwrap();
+        assert!(network.set_tier_policy("org-bank", TierPolicy::new(TierThresholds { unknown: 100, ..TierThresholds::DEFAULT })).is_err());
+        
+        let start = Utc::now();
+        let success = || ReputationEvent::ActionSuccess { action: "test".to_string(), impact: 250 };
+        network.record_event_at("bank-agent", success(), start);
+        network.record_event_at("shop-agent", success(), start);
+        assert_eq!(network.get_trust_tier("shop-agent"), TrustTier::Trusted);
+        assert_eq!(network.get_trust_tier("bank-agent"), TrustTier::Unknown);
+        
+        // Promoted once the score has been held for the tenant's hold
+        assert!(network.refresh_tiers(start + chrono::Duration::days(6)).is_empty());
+        let promoted = network.refresh_tiers(start + chrono::Duration::days(7));
+        assert_eq!(promoted, vec![("bank-agent".to_string(), TrustTier::Trusted)]);
+        
+        // Raising the bar demotes at once
+        let stricter = TierPolicy { thresholds: TierThresholds { trusted: 800, verified: 850, ..TierThresholds::DEFAULT }, ..strict };
+        network.set_tier_policy("org-bank", stricter).unwrap();
+        assert_eq!(network.get_trust_tier("bank-agent"), TrustTier::Unknown);
+        assert_eq!(network.assign_tier("bank-agent", TrustTier::Trusted), Some(TrustTier::Trusted));
+        assert_eq!(network.get_reputation("bank-agent").unwrap().score, 800);
+        
+        unsafe { std::env::remove_var("AGENTKERN_LICENSE_KEY") };
     }
 }
@@ -0,0 +1,222 @@
+//! Tier Configuration
+//!
+//! Score boundaries of each [`TrustTier`] and promotion hysteresis:
+//! - [`TierThresholds`] set the minimum score of each tier; the process-wide
+//!   active thresholds back [`TrustTier::from_score`]
+//! - [`TierPolicy`] adds a promotion hold: an agent must keep a higher
+//!   tier's score for that many days before it is promoted, while a lower
+//!   score demotes it at once, so scores hovering on a boundary do not flap
+//! - A [`TrustNetwork`](crate::TrustNetwork) has a default policy and may
+//!   override it per tenant (organization)
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let cautious = TierPolicy::new(TierThresholds { trusted: 700, ..TierThresholds::DEFAULT }).with_promotion_hold_days(7);
+//! network.set_tier_policy("org-bank", cautious)?;
+//! ```
+
+use crate::{AgentRecord, TrustTier};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// Highest reputation score.
+const MAX_SCORE: u16 = 1000;
+
+static ACTIVE: RwLock<TierThresholds> = RwLock::new(TierThresholds::DEFAULT);
+
+/// Invalid tier configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum TierConfigError {
+    #[error("Tier thresholds must rise strictly from Untrusted to Elite within 1-1000: {0:?}")]
+    InvalidThresholds(TierThresholds),
+}
+
+/// Minimum score of each tier; Blacklisted starts at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierThresholds {
+    pub untrusted: u16,
+    pub unknown: u16,
+    pub trusted: u16,
+    pub verified: u16,
+    pub elite: u16,
+}
+
+impl Default for TierThresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TierThresholds {
+    /// Built-in boundaries (600 = Trusted).
+    pub const DEFAULT: Self = Self { untrusted: 200, unknown: 400, trusted: 600, verified: 800, elite: 900 };
+
+    pub fn validate(&self) -> Result<(), TierConfigError> {
+        let bounds = [0, self.untrusted, self.unknown, self.trusted, self.verified, self.elite];
+        if bounds.windows(2).all(|w| w[0] < w[1]) && self.elite <= MAX_SCORE {
+            Ok(())
+        } else {
+            Err(TierConfigError::InvalidThresholds(*self))
+        }
+    }
+
+    /// Tier a score falls in.
+    pub fn tier_for(&self, score: u16) -> TrustTier {
+        [TrustTier::Elite, TrustTier::Verified, TrustTier::Trusted, TrustTier::Unknown, TrustTier::Untrusted]
+            .into_iter()
+            .find(|tier| score >= self.min_score(*tier))
+            .unwrap_or(TrustTier::Blacklisted)
+    }
+
+    /// Minimum score of a tier.
+    pub fn min_score(&self, tier: TrustTier) -> u16 {
+        match tier {
+            TrustTier::Blacklisted => 0,
+            TrustTier::Untrusted => self.untrusted,
+            TrustTier::Unknown => self.unknown,
+            TrustTier::Trusted => self.trusted,
+            TrustTier::Verified => self.verified,
+            TrustTier::Elite => self.elite,
+        }
+    }
+
+    /// Thresholds used by [`TrustTier::from_score`] and new trust networks.
+    pub fn active() -> Self {
+        *ACTIVE.read().unwrap()
+    }
+
+    /// Make these the process-wide active thresholds.
+    pub fn activate(self) -> Result<(), TierConfigError> {
+        self.validate()?;
+        *ACTIVE.write().unwrap() = self;
+        tracing::info!(thresholds = ?self, "Trust tier thresholds activated");
+        Ok(())
+    }
+}
+
+/// Thresholds and promotion hold of a tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierPolicy {
+    pub thresholds: TierThresholds,
+    /// Days a higher tier's score must be held before promotion; 0 promotes at once
+    pub promotion_hold_days: u32,
+}
+
+impl TierPolicy {
+    pub fn new(thresholds: TierThresholds) -> Self {
+        Self { thresholds, promotion_hold_days: 0 }
+    }
+
+    pub fn with_promotion_hold_days(mut self, days: u32) -> Self {
+        self.promotion_hold_days = days;
+        self
+    }
+}
+
+/// A promotion waiting out the hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingPromotion {
+    /// Highest tier the score has earned throughout the hold
+    pub tier: TrustTier,
+    pub since: DateTime<Utc>,
+}
+
+/// Move `record` to the tier its score earns under `policy`: at once when
+/// that is lower, once the hold has passed when it is higher. Returns
+/// whether the tier changed.
+pub(crate) fn retier(record: &mut AgentRecord, policy: &TierPolicy, now: DateTime<Utc>) -> bool {
+    let current = record.reputation.tier;
+    let earned = if record.blacklisted {
+        TrustTier::Blacklisted
+    } else {
+        policy.thresholds.tier_for(record.reputation.score)
+    };
+    if earned <= current {
+        record.reputation.tier = earned;
+        record.pending_promotion = None;
+        return earned != current;
+    }
+
+    // A dip that stays above the current tier keeps the hold running
+    let pending = match record.pending_promotion {
+        Some(pending) => PendingPromotion { tier: pending.tier.min(earned), since: pending.since },
+        None => PendingPromotion { tier: earned, since: now },
+    };
+    if now - pending.since < Duration::days(policy.promotion_hold_days.into()) {
+        record.pending_promotion = Some(pending);
+        return false;
+    }
+    record.reputation.tier = pending.tier;
+    // Climbing further starts a new hold
+    record.pending_promotion = (pending.tier < earned).then_some(PendingPromotion { tier: earned, since: now });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReputationScore;
+
+    fn record(score: u16, tier: TrustTier) -> AgentRecord {
+        let now = Utc::now();
+        AgentRecord {
+            agent_id: "agent-1".to_string(),
+            org_id: "org-1".to_string(),
+            reputation: ReputationScore { score, tier, ..ReputationScore::default() },
+            total_actions: 0,
+            successful_actions: 0,
+            violations: 0,
+            first_seen: now,
+            last_activity: now,
+            blacklisted: false,
+            blacklist_reason: None,
+            pending_promotion: None,
+        }
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let strict = TierThresholds { trusted: 700, verified: 850, ..TierThresholds::DEFAULT };
+        strict.validate().unwrap();
+        assert_eq!(strict.tier_for(650), TrustTier::Unknown);
+        assert_eq!(strict.tier_for(700), TrustTier::Trusted);
+        assert_eq!(strict.tier_for(199), TrustTier::Blacklisted);
+        assert_eq!(strict.tier_for(1000), TrustTier::Elite);
+
+        assert!(TierThresholds { trusted: 400, ..TierThresholds::DEFAULT }.validate().is_err());
+        assert!(TierThresholds { elite: 1001, ..TierThresholds::DEFAULT }.validate().is_err());
+        assert!(TierThresholds { untrusted: 0, ..TierThresholds::DEFAULT }.validate().is_err());
+    }
+
+    #[test]
+    fn test_promotion_is_held_and_demotion_is_immediate() {
+        let policy = TierPolicy::default().with_promotion_hold_days(3);
+        let start = Utc::now();
+        let mut agent = record(650, TrustTier::Unknown);
+
+        assert!(!retier(&mut agent, &policy, start));
+        assert_eq!(agent.reputation.tier, TrustTier::Unknown);
+        // Reaching Verified mid-hold neither restarts nor skips the Trusted hold
+        agent.reputation.score = 820;
+        assert!(!retier(&mut agent, &policy, start + Duration::days(2)));
+        assert!(retier(&mut agent, &policy, start + Duration::days(3)));
+        assert_eq!(agent.reputation.tier, TrustTier::Trusted);
+        assert_eq!(agent.pending_promotion.unwrap().tier, TrustTier::Verified);
+
+        // Falling back below Trusted demotes at once and resets the hold
+        agent.reputation.score = 590;
+        assert!(retier(&mut agent, &policy, start + Duration::days(4)));
+        assert_eq!(agent.reputation.tier, TrustTier::Unknown);
+        assert!(agent.pending_promotion.is_none());
+
+        // Without a hold, promotion is immediate; blacklisted agents never rise
+        agent.reputation.score = 950;
+        assert!(retier(&mut agent, &TierPolicy::default(), start));
+        assert_eq!(agent.reputation.tier, TrustTier::Elite);
+        agent.blacklisted = true;
+        retier(&mut agent, &TierPolicy::default(), start);
+        assert_eq!(agent.reputation.tier, TrustTier::Blacklisted);
+    }
+}
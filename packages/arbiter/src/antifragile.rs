@@ -339,6 +339,24 @@ impl CircuitBreaker {
         }
     }
 
+    /// Set failures that open the circuit and half-open successes that close it.
+    pub fn with_thresholds(mut self, failure_threshold: u32, success_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.success_threshold = success_threshold.max(1);
+        self
+    }
+
+    /// Set how long the circuit stays open before letting a probe through.
+    pub fn with_reset_timeout(mut self, reset_timeout: Duration) -> Self {
+        self.reset_timeout = reset_timeout;
+        self
+    }
+
+    /// Get circuit name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Check if requests should be allowed.
     pub fn is_allowed(&mut self) -> bool {
         match self.state {
@@ -574,6 +592,25 @@ mod tests {
         assert!(!cb.is_allowed());
     }
 
+    #[test]
+    fn test_circuit_breaker_custom_thresholds() {
+        let mut cb = CircuitBreaker::new("sap-rfc")
+            .with_thresholds(2, 1)
+            .with_reset_timeout(Duration::zero());
+
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        // Reset timeout elapsed: one probe, one success closes it
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(cb.is_allowed());
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
     #[tokio::test]
     async fn test_antifragile_handle_failure() {
         let engine = AntifragileEngine::new();
@@ -6,6 +6,8 @@
 //! - Traceability: Every autonomous action is logged
 //! - Risk Management: Risk scores are recorded
 //! - Human Oversight: Policy IDs and model versions are tracked
+//! - Edge evidence: store-and-forward uploads from devices are deduplicated
+//!   and clock-corrected ([`AuditLedger::ingest_edge`])
 //!
 //! # Example
 //!
@@ -22,11 +24,16 @@
 //! ));
 //! ```
 
+use agentkern_edge::audit::{AuditAck, AuditUpload};
+use agentkern_edge::PolicyAction;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
 /// Maximum records to keep in memory (older records are pruned).
@@ -141,11 +148,23 @@ impl AuditRecord {
     }
 }
 
+/// Contents of an [`AuditLedger`], for backup and restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLedgerState {
+    pub records: Vec<AuditRecord>,
+    /// Highest stored seq per edge device and boot: `(device, boot, seq)`
+    pub edge_marks: Vec<(String, u32, u64)>,
+}
+
 /// Audit ledger for storing and querying audit records.
 #[derive(Debug)]
 pub struct AuditLedger {
     records: Arc<RwLock<VecDeque<AuditRecord>>>,
     max_records: usize,
+    /// Records not yet written by [`AuditLedger::flush_to`]
+    unflushed: AtomicUsize,
+    /// Highest stored edge seq per (device, boot)
+    edge_marks: Mutex<HashMap<(String, u32), u64>>,
 }
 
 impl Default for AuditLedger {
@@ -160,6 +179,8 @@ impl AuditLedger {
         Self {
             records: Arc::new(RwLock::new(VecDeque::new())),
             max_records: DEFAULT_MAX_RECORDS,
+            unflushed: AtomicUsize::new(0),
+            edge_marks: Mutex::new(HashMap::new()),
         }
     }
 
@@ -168,6 +189,8 @@ impl AuditLedger {
         Self {
             records: Arc::new(RwLock::new(VecDeque::with_capacity(max_records))),
             max_records,
+            unflushed: AtomicUsize::new(0),
+            edge_marks: Mutex::new(HashMap::new()),
         }
     }
 
@@ -181,6 +204,128 @@ impl AuditLedger {
         }
         
         records.push_back(record);
+        self.unflushed.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Append records not yet flushed to a JSON Lines journal and fsync it.
+    /// Returns the number of records written. Records pruned before a flush
+    /// are lost, so flush at least every `max_records` entries.
+    pub async fn flush_to(&self, path: impl AsRef<Path>) -> std::io::Result<usize> {
+        let records = self.records.write().await;
+        let pending = self.unflushed.load(Ordering::Acquire).min(records.len());
+        if pending == 0 {
+            return Ok(0);
+        }
+
+        let mut journal = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        for record in records.iter().skip(records.len() - pending) {
+            serde_json::to_writer(&mut journal, record)?;
+            journal.write_all(b"\n")?;
+        }
+        journal.sync_all()?;
+
+        self.unflushed.store(0, Ordering::Release);
+        Ok(pending)
+    }
+
+    /// Store an edge device's upload. Entries already stored (by device,
+    /// boot and seq) are skipped; timestamps are shifted by the difference
+    /// between `received_at` and the device clock at send time (network
+    /// latency is not subtracted). Sequence gaps (entries dropped on the
+    /// device) are recorded as a `Logged` entry so the gap itself is audited.
+    pub async fn ingest_edge(&self, upload: &AuditUpload, received_at: DateTime<Utc>) -> EdgeIngestReport {
+        let skew_ms = received_at.timestamp_millis() - upload.sent_at as i64;
+        let mut marks = self.edge_marks.lock().await;
+        let mark = marks.entry((upload.device_id.clone(), upload.boot_id)).or_insert(0);
+
+        let mut entries: Vec<_> = upload.entries.iter().collect();
+        entries.sort_by_key(|e| e.seq);
+
+        let mut report = EdgeIngestReport {
+            accepted: 0,
+            duplicates: 0,
+            dropped_on_device: upload.dropped,
+            skew_ms,
+            ack: AuditAck { boot_id: upload.boot_id, up_to_seq: 0 },
+        };
+        let provenance = |seq: u64, device_timestamp: u64| {
+            serde_json::json!({
+                "device_id": upload.device_id,
+                "boot_id": upload.boot_id,
+                "seq": seq,
+                "device_timestamp": device_timestamp,
+                "clock_skew_ms": skew_ms,
+            })
+        };
+
+        if upload.dropped > 0 {
+            tracing::warn!("Edge device {} dropped {} audit entries while offline", upload.device_id, upload.dropped);
+        }
+
+        for entry in entries {
+            if entry.seq <= *mark {
+                report.duplicates += 1;
+                continue;
+            }
+            if entry.seq > *mark + 1 {
+                let missing = entry.seq - *mark - 1;
+                let mut gap = AuditRecord::new(&upload.device_id, "edge.audit_dropped", "edge-buffer", 0, AuditOutcome::Logged)
+                    .with_region("edge")
+                    .with_reasoning(format!("seq {}..{} ({} entries) dropped on device", *mark + 1, entry.seq - 1, missing))
+                    .with_metadata(provenance(entry.seq - 1, entry.timestamp));
+                gap.timestamp = received_at;
+                self.record(gap).await;
+            }
+            let outcome = match entry.outcome {
+                PolicyAction::Allow => AuditOutcome::Allowed,
+                PolicyAction::Deny => AuditOutcome::Denied,
+                PolicyAction::Escalate => AuditOutcome::Review,
+                PolicyAction::Queue => AuditOutcome::Logged,
+            };
+            let policy_id = entry.rule_id.as_deref().unwrap_or("edge-default");
+            let mut record = AuditRecord::new(&entry.agent_id, &entry.action, policy_id, 0, outcome)
+                .with_region("edge")
+                .with_metadata(provenance(entry.seq, entry.timestamp));
+            record.timestamp =
+                DateTime::from_timestamp_millis(entry.timestamp as i64 + skew_ms).unwrap_or(received_at);
+            self.record(record).await;
+            *mark = entry.seq;
+            report.accepted += 1;
+        }
+
+        report.ack.up_to_seq = *mark;
+        report
+    }
+
+    /// Copy of the stored records and edge sequence marks.
+    pub async fn export_state(&self) -> AuditLedgerState {
+        // Same order as `ingest_edge`: marks, then records
+        let marks = self.edge_marks.lock().await;
+        let records = self.records.read().await;
+        AuditLedgerState {
+            records: records.iter().cloned().collect(),
+            edge_marks: marks.iter().map(|((device, boot), seq)| (device.clone(), *boot, *seq)).collect(),
+        }
+    }
+
+    /// Replace the stored records and edge marks. Imported records count as
+    /// flushed.
+    pub async fn import_state(&self, state: AuditLedgerState) {
+        let mut marks = self.edge_marks.lock().await;
+        let mut records = self.records.write().await;
+        let skip = state.records.len().saturating_sub(self.max_records);
+        *records = state.records.into_iter().skip(skip).collect();
+        *marks = state.edge_marks.into_iter().map(|(device, boot, seq)| ((device, boot), seq)).collect();
+        self.unflushed.store(0, Ordering::Release);
+    }
+
+    /// Delete records by ID, e.g. when their retention period ends.
+    /// Returns how many were removed.
+    pub async fn purge(&self, ids: &HashSet<Uuid>) -> usize {
+        let mut records = self.records.write().await;
+        let before = records.len();
+        records.retain(|r| !ids.contains(&r.id));
+        before - records.len()
     }
 
     /// Get the total number of records.
@@ -251,36 +396,25 @@ impl AuditLedger {
 
     /// Get statistics for compliance reporting.
     pub async fn get_statistics(&self) -> AuditStatistics {
-        let records = self.records.read().await;
-        
-        let total = records.len();
-        let allowed = records.iter().filter(|r| r.outcome == AuditOutcome::Allowed).count();
-        let denied = records.iter().filter(|r| r.outcome == AuditOutcome::Denied).count();
-        let review = records.iter().filter(|r| r.outcome == AuditOutcome::Review).count();
-        
-        let avg_risk = if total > 0 {
-            records.iter().map(|r| r.risk_score as u32).sum::<u32>() / total as u32
-        } else {
-            0
-        };
-        
-        let avg_latency = if total > 0 {
-            records.iter().map(|r| r.latency_us).sum::<u64>() / total as u64
-        } else {
-            0
-        };
-
-        AuditStatistics {
-            total_records: total,
-            allowed_count: allowed,
-            denied_count: denied,
-            review_count: review,
-            average_risk_score: avg_risk as u8,
-            average_latency_us: avg_latency,
-        }
+        AuditStatistics::of(self.records.read().await.iter())
     }
 }
 
+/// Result of [`AuditLedger::ingest_edge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeIngestReport {
+    /// Entries stored
+    pub accepted: usize,
+    /// Entries already stored by an earlier upload
+    pub duplicates: usize,
+    /// Entries the device reported dropping
+    pub dropped_on_device: u64,
+    /// Cell clock minus device clock, in ms
+    pub skew_ms: i64,
+    /// Acknowledgement to return to the device
+    pub ack: AuditAck,
+}
+
 /// Statistics for compliance reporting.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditStatistics {
@@ -292,6 +426,37 @@ pub struct AuditStatistics {
     pub average_latency_us: u64,
 }
 
+impl AuditStatistics {
+    /// Statistics over `records`.
+    pub(crate) fn of<'a>(records: impl Iterator<Item = &'a AuditRecord>) -> Self {
+        let mut stats = Self {
+            total_records: 0,
+            allowed_count: 0,
+            denied_count: 0,
+            review_count: 0,
+            average_risk_score: 0,
+            average_latency_us: 0,
+        };
+        let (mut risk, mut latency) = (0u64, 0u64);
+        for record in records {
+            stats.total_records += 1;
+            match record.outcome {
+                AuditOutcome::Allowed => stats.allowed_count += 1,
+                AuditOutcome::Denied => stats.denied_count += 1,
+                AuditOutcome::Review => stats.review_count += 1,
+                AuditOutcome::Logged => {}
+            }
+            risk += record.risk_score as u64;
+            latency += record.latency_us;
+        }
+        if stats.total_records > 0 {
+            stats.average_risk_score = (risk / stats.total_records as u64) as u8;
+            stats.average_latency_us = latency / stats.total_records as u64;
+        }
+        stats
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -384,4 +549,63 @@ mod tests {
         assert!(json.contains("agent-1"));
         assert!(json.contains("policy"));
     }
+
+    #[tokio::test]
+    async fn test_audit_ledger_flush_to_journal() {
+        let ledger = AuditLedger::new();
+        let path = std::env::temp_dir().join(format!("audit-journal-{}.jsonl", Uuid::new_v4()));
+
+        ledger.record(AuditRecord::new("agent-1", "read", "p1", 10, AuditOutcome::Allowed)).await;
+        ledger.record(AuditRecord::new("agent-2", "delete", "p2", 90, AuditOutcome::Denied)).await;
+        assert_eq!(ledger.flush_to(&path).await.unwrap(), 2);
+        assert_eq!(ledger.flush_to(&path).await.unwrap(), 0);
+
+        ledger.record(AuditRecord::new("agent-3", "write", "p3", 40, AuditOutcome::Review)).await;
+        assert_eq!(ledger.flush_to(&path).await.unwrap(), 1);
+
+        let journal = std::fs::read_to_string(&path).unwrap();
+        let agents: Vec<String> = journal
+            .lines()
+            .map(|line| serde_json::from_str::<AuditRecord>(line).unwrap().agent_id)
+            .collect();
+        assert_eq!(agents, ["agent-1", "agent-2", "agent-3"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ingest_edge_dedupes_and_corrects_skew() {
+        use agentkern_edge::audit::AuditBuffer;
+
+        let ledger = AuditLedger::new();
+        // Device clock runs 10 minutes behind the cell
+        let cell_now = DateTime::from_timestamp_millis(1_700_000_600_000).unwrap();
+        let device_now = 1_700_000_000_000;
+
+        let mut buffer = AuditBuffer::new("robot-7", 3, 2);
+        buffer.record("picker", "sensor.read", PolicyAction::Allow, None, device_now - 3_000);
+        buffer.record("picker", "actuator.open", PolicyAction::Deny, Some("act"), device_now - 2_000);
+        buffer.record("picker", "actuator.open", PolicyAction::Escalate, Some("force"), device_now - 1_000);
+
+        let upload = buffer.upload_batch(10, device_now);
+        let report = ledger.ingest_edge(&upload, cell_now).await;
+        assert_eq!(report.accepted, 2);
+        assert_eq!(report.dropped_on_device, 1);
+        assert_eq!(report.skew_ms, 600_000);
+        assert_eq!(report.ack, AuditAck { boot_id: 3, up_to_seq: 3 });
+
+        // Ack lost: the device resends, nothing is stored twice
+        let again = ledger.ingest_edge(&upload, cell_now).await;
+        assert_eq!((again.accepted, again.duplicates), (0, 2));
+
+        buffer.acknowledge(&report.ack);
+        assert_eq!(buffer.counters().buffered, 0);
+
+        let denied = ledger.query_by_outcome(AuditOutcome::Denied).await;
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].policy_id, "act");
+        assert_eq!(denied[0].timestamp.timestamp_millis(), 1_700_000_598_000);
+        assert_eq!(denied[0].metadata["seq"], 2);
+        assert_eq!(ledger.query_by_action("edge.audit_dropped").await.len(), 1);
+        assert_eq!(ledger.count().await, 3);
+    }
 }
@@ -0,0 +1,425 @@
+//! AgentKern-Arbiter: Audit Read Replicas
+//!
+//! Compliance reports and SIEM exports scan months of records; running them
+//! against the live [`AuditLedger`] holds its lock and stalls `record`.
+//! An [`AuditReplica`] serves those reads from the persisted journal instead:
+//!
+//! - **Snapshot**: on load the replica restores its last checkpoint (records
+//!   plus the journal offset they cover), so restarts do not re-read the
+//!   whole journal
+//! - **Tail follow**: it then reads the JSON Lines journal written by
+//!   [`AuditLedger::flush_to`] from that offset, and keeps polling for new
+//!   lines. A trailing partial line (a flush in progress) is left for the
+//!   next poll
+//! - **Staleness**: a replica lags the primary by at most the time since its
+//!   last sync plus the primary's flush interval. Reports and exports carry
+//!   that bound as [`DataFreshness`], and are refused when it exceeds the
+//!   configured maximum
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_arbiter::audit_replica::AuditReplica;
+//!
+//! let replica = Arc::new(
+//!     AuditReplica::new("/var/lib/agentkern/audit.jsonl")
+//!         .with_snapshot("/var/lib/agentkern/audit-replica.json")
+//!         .with_flush_interval(Duration::from_secs(1))
+//!         .with_max_staleness(Duration::from_secs(30)),
+//! );
+//! replica.load().await?;
+//! tokio::spawn(replica.clone().follow(Duration::from_millis(500)));
+//!
+//! let report = replica.generate_report("org-1", "2.3.0", month_start, month_end).await?;
+//! let export = siem.export_replica(&replica, since, Utc::now()).await?;
+//! replica.checkpoint().await?;
+//! ```
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use agentkern_errors::{Coded, ErrorCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::audit::{AuditRecord, AuditStatistics};
+use crate::iso42001::{AuditEvent, AuditReport, ComplianceLedger, DataFreshness};
+
+/// Replica errors.
+#[derive(Debug, Error)]
+pub enum ReplicaError {
+    #[error("Audit replica I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupt audit data at byte {offset} of {path}: {reason}")]
+    Corrupt { path: String, offset: u64, reason: String },
+
+    #[error("Audit journal {path} is {len} bytes, shorter than the replicated offset {offset}")]
+    Truncated { path: String, offset: u64, len: u64 },
+
+    #[error("Audit replica has not synced with the journal yet")]
+    NotSynced,
+
+    #[error("Audit replica may be {staleness_ms}ms behind, over the {max_ms}ms bound")]
+    Stale { staleness_ms: u64, max_ms: u64 },
+}
+
+impl Coded for ReplicaError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Io(_) | Self::NotSynced | Self::Stale { .. } => ErrorCode::Unavailable,
+            Self::Corrupt { .. } => ErrorCode::Internal,
+            Self::Truncated { .. } => ErrorCode::InvalidState,
+        }
+    }
+}
+
+/// Checkpoint file contents.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReplicaState {
+    records: Vec<AuditRecord>,
+    /// Journal bytes already applied
+    journal_offset: u64,
+    /// Last time the journal was read to its end
+    synced_at: Option<DateTime<Utc>>,
+}
+
+/// Read-only copy of an audit journal for reporting and export.
+pub struct AuditReplica {
+    journal: PathBuf,
+    snapshot: Option<PathBuf>,
+    flush_interval: Duration,
+    max_staleness: Option<Duration>,
+    state: RwLock<ReplicaState>,
+    /// Held while reading the journal, so concurrent catch-ups do not apply lines twice
+    tail: Mutex<()>,
+}
+
+impl AuditReplica {
+    /// Replica of the journal at `journal`. Call [`AuditReplica::load`] before querying.
+    pub fn new(journal: impl Into<PathBuf>) -> Self {
+        Self {
+            journal: journal.into(),
+            snapshot: None,
+            flush_interval: Duration::ZERO,
+            max_staleness: None,
+            state: RwLock::new(ReplicaState::default()),
+            tail: Mutex::new(()),
+        }
+    }
+
+    /// Restore from and checkpoint to this file.
+    pub fn with_snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.snapshot = Some(path.into());
+        self
+    }
+
+    /// How often the primary calls [`AuditLedger::flush_to`](crate::audit::AuditLedger::flush_to).
+    /// Records it has not flushed yet are invisible to the replica, so this
+    /// is added to the staleness bound.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Refuse reports and exports when the data may be older than this.
+    pub fn with_max_staleness(mut self, max: Duration) -> Self {
+        self.max_staleness = Some(max);
+        self
+    }
+
+    /// Restore the snapshot, if there is one, then catch up with the journal.
+    /// Returns the number of records held.
+    pub async fn load(&self) -> Result<usize, ReplicaError> {
+        if let Some(path) = self.snapshot.clone() {
+            let restored = tokio::task::spawn_blocking(move || read_snapshot(&path))
+                .await
+                .map_err(|e| std::io::Error::other(e.to_string()))??;
+            if let Some(restored) = restored {
+                *self.state.write().await = restored;
+            }
+        }
+        self.catch_up().await?;
+        Ok(self.count().await)
+    }
+
+    /// Apply journal lines written since the last catch-up. Returns the
+    /// number of records added.
+    pub async fn catch_up(&self) -> Result<usize, ReplicaError> {
+        let _tail = self.tail.lock().await;
+        let offset = self.state.read().await.journal_offset;
+        let journal = self.journal.clone();
+        // Parse outside the state lock; queries keep running meanwhile
+        let (records, next) = tokio::task::spawn_blocking(move || read_journal(&journal, offset))
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))??;
+
+        let added = records.len();
+        let mut state = self.state.write().await;
+        state.records.extend(records);
+        state.journal_offset = next;
+        state.synced_at = Some(Utc::now());
+        Ok(added)
+    }
+
+    /// Catch up every `interval`, forever.
+    pub async fn follow(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.catch_up().await {
+                Ok(0) => {}
+                Ok(added) => tracing::debug!(added, journal = %self.journal.display(), "Audit replica caught up"),
+                Err(e) => tracing::warn!(error = %e, journal = %self.journal.display(), "Audit replica sync failed"),
+            }
+        }
+    }
+
+    /// Write the replica's records and journal offset to the snapshot file.
+    /// Returns the number of records written; 0 without a snapshot file.
+    pub async fn checkpoint(&self) -> Result<usize, ReplicaError> {
+        let Some(path) = self.snapshot.clone() else {
+            return Ok(0);
+        };
+        let (bytes, count) = {
+            let state = self.state.read().await;
+            let bytes = serde_json::to_vec(&*state).map_err(|e| ReplicaError::Corrupt {
+                path: path.display().to_string(),
+                offset: 0,
+                reason: e.to_string(),
+            })?;
+            (bytes, state.records.len())
+        };
+        tokio::task::spawn_blocking(move || {
+            // Write then rename, so a crash never leaves a half-written snapshot
+            let partial = path.with_extension("partial");
+            std::fs::write(&partial, bytes)?;
+            File::open(&partial)?.sync_all()?;
+            std::fs::rename(&partial, &path)
+        })
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))??;
+        Ok(count)
+    }
+
+    /// How current the replica is, or `None` before the first sync.
+    pub async fn freshness(&self) -> Option<DataFreshness> {
+        let state = self.state.read().await;
+        let synced_at = state.synced_at?;
+        let behind = (Utc::now() - synced_at).to_std().unwrap_or_default() + self.flush_interval;
+        Some(DataFreshness {
+            source: self.journal.display().to_string(),
+            as_of: synced_at,
+            last_record_at: state.records.iter().map(|r| r.timestamp).max(),
+            max_staleness_ms: behind.as_millis() as u64,
+        })
+    }
+
+    /// Freshness, if within the configured maximum staleness.
+    pub async fn fresh(&self) -> Result<DataFreshness, ReplicaError> {
+        let freshness = self.freshness().await.ok_or(ReplicaError::NotSynced)?;
+        match self.max_staleness {
+            Some(max) if freshness.max_staleness_ms > max.as_millis() as u64 => Err(ReplicaError::Stale {
+                staleness_ms: freshness.max_staleness_ms,
+                max_ms: max.as_millis() as u64,
+            }),
+            _ => Ok(freshness),
+        }
+    }
+
+    /// Get the number of replicated records.
+    pub async fn count(&self) -> usize {
+        self.state.read().await.records.len()
+    }
+
+    /// Query records by agent ID.
+    pub async fn query_by_agent(&self, agent_id: &str) -> Vec<AuditRecord> {
+        let state = self.state.read().await;
+        state.records.iter().filter(|r| r.agent_id == agent_id).cloned().collect()
+    }
+
+    /// Query records within a time range.
+    pub async fn query_by_time_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<AuditRecord> {
+        let state = self.state.read().await;
+        state.records.iter().filter(|r| r.timestamp >= start && r.timestamp <= end).cloned().collect()
+    }
+
+    /// Query high-risk records (risk_score >= threshold).
+    pub async fn query_high_risk(&self, threshold: u8) -> Vec<AuditRecord> {
+        let state = self.state.read().await;
+        state.records.iter().filter(|r| r.risk_score >= threshold).cloned().collect()
+    }
+
+    /// Get statistics for compliance reporting.
+    pub async fn get_statistics(&self) -> AuditStatistics {
+        AuditStatistics::of(self.state.read().await.records.iter())
+    }
+
+    /// ISO 42001 report over the replicated records in the period, with the
+    /// replica's freshness attached.
+    pub async fn generate_report(
+        &self,
+        organization_id: &str,
+        system_version: &str,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Result<AuditReport, ReplicaError> {
+        let freshness = self.fresh().await?;
+        let events: Vec<AuditEvent> = {
+            let state = self.state.read().await;
+            state
+                .records
+                .iter()
+                .filter(|r| r.timestamp >= period_start && r.timestamp <= period_end)
+                .map(AuditEvent::from)
+                .collect()
+        };
+        let events: Vec<&AuditEvent> = events.iter().collect();
+        let mut report =
+            ComplianceLedger::build_report(organization_id, system_version, &events, period_start, period_end);
+        report.freshness = Some(freshness);
+        Ok(report)
+    }
+}
+
+/// Read a checkpoint; `None` if it has not been written yet.
+fn read_snapshot(path: &Path) -> Result<Option<ReplicaState>, ReplicaError> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_slice(&bytes).map(Some).map_err(|e| ReplicaError::Corrupt {
+        path: path.display().to_string(),
+        offset: 0,
+        reason: e.to_string(),
+    })
+}
+
+/// Parse complete journal lines from `offset`. Returns the records and the
+/// offset after the last complete line.
+fn read_journal(path: &Path, offset: u64) -> Result<(Vec<AuditRecord>, u64), ReplicaError> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        // The primary has not flushed anything yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && offset == 0 => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e.into()),
+    };
+    let len = file.metadata()?.len();
+    if len < offset {
+        return Err(ReplicaError::Truncated { path: path.display().to_string(), offset, len });
+    }
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut reader = BufReader::new(file);
+    let mut records = Vec::new();
+    let mut line = Vec::new();
+    let mut next = offset;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        if !line.trim_ascii().is_empty() {
+            let record = serde_json::from_slice(&line).map_err(|e| ReplicaError::Corrupt {
+                path: path.display().to_string(),
+                offset: next,
+                reason: e.to_string(),
+            })?;
+            records.push(record);
+        }
+        next += read as u64;
+    }
+    Ok((records, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditLedger, AuditOutcome};
+    use std::io::Write;
+    use uuid::Uuid;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("audit-replica-{}-{}", name, Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_tail_follow() {
+        let journal = temp_path("journal.jsonl");
+        let snapshot = temp_path("snapshot.json");
+        let ledger = AuditLedger::new();
+        ledger.record(AuditRecord::new("agent-1", "read", "p1", 10, AuditOutcome::Allowed)).await;
+        ledger.record(AuditRecord::new("agent-2", "delete", "p2", 90, AuditOutcome::Denied)).await;
+        ledger.flush_to(&journal).await.unwrap();
+
+        let replica = AuditReplica::new(&journal).with_snapshot(&snapshot);
+        assert_eq!(replica.load().await.unwrap(), 2);
+        assert_eq!(replica.checkpoint().await.unwrap(), 2);
+
+        // A flush in progress: the partial line waits for the next poll
+        ledger.record(AuditRecord::new("agent-1", "write", "p1", 40, AuditOutcome::Review)).await;
+        ledger.flush_to(&journal).await.unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(&journal).unwrap();
+        let pending = serde_json::to_string(&AuditRecord::new("agent-3", "read", "p3", 5, AuditOutcome::Logged)).unwrap();
+        file.write_all(&pending.as_bytes()[..20]).unwrap();
+        assert_eq!(replica.catch_up().await.unwrap(), 1);
+        file.write_all(&pending.as_bytes()[20..]).unwrap();
+        file.write_all(b"\n").unwrap();
+        assert_eq!(replica.catch_up().await.unwrap(), 1);
+        assert_eq!(replica.query_by_agent("agent-1").await.len(), 2);
+        assert_eq!(replica.get_statistics().await.denied_count, 1);
+
+        // A restarted replica resumes from the checkpoint and reads only the tail
+        let restarted = AuditReplica::new(&journal).with_snapshot(&snapshot);
+        assert_eq!(restarted.load().await.unwrap(), 4);
+        assert_eq!(restarted.query_high_risk(90).await[0].agent_id, "agent-2");
+
+        // A journal replaced under the replica is not silently re-read
+        std::fs::write(&journal, b"").unwrap();
+        assert!(matches!(restarted.catch_up().await, Err(ReplicaError::Truncated { .. })));
+
+        std::fs::remove_file(&journal).unwrap();
+        std::fs::remove_file(&snapshot).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reports_carry_staleness_bound() {
+        let journal = temp_path("journal.jsonl");
+        let ledger = AuditLedger::new();
+        ledger.record(AuditRecord::new("agent-1", "transfer", "p1", 80, AuditOutcome::Allowed)).await;
+        ledger.record(AuditRecord::new("agent-1", "transfer", "p1", 30, AuditOutcome::Review)).await;
+        ledger.flush_to(&journal).await.unwrap();
+
+        let start = Utc::now() - chrono::Duration::hours(1);
+        let end = Utc::now() + chrono::Duration::hours(1);
+        let replica = AuditReplica::new(&journal)
+            .with_flush_interval(Duration::from_secs(2))
+            .with_max_staleness(Duration::from_secs(10));
+        assert!(matches!(replica.generate_report("org-1", "1.0.0", start, end).await, Err(ReplicaError::NotSynced)));
+
+        replica.load().await.unwrap();
+        let report = replica.generate_report("org-1", "1.0.0", start, end).await.unwrap();
+        assert_eq!((report.total_ai_decisions, report.high_risk_actions), (2, 1));
+        assert_eq!(report.human_oversight_percentage, 50.0);
+        let freshness = report.freshness.unwrap();
+        assert!(freshness.max_staleness_ms >= 2_000);
+        assert_eq!(freshness.source, journal.display().to_string());
+
+        // A primary that flushes less often than the bound allows never qualifies
+        let lagging = AuditReplica::new(&journal)
+            .with_flush_interval(Duration::from_secs(60))
+            .with_max_staleness(Duration::from_secs(10));
+        lagging.load().await.unwrap();
+        let err = lagging.generate_report("org-1", "1.0.0", start, end).await.unwrap_err();
+        assert!(matches!(err, ReplicaError::Stale { max_ms: 10_000, .. }));
+        assert_eq!(err.code(), ErrorCode::Unavailable);
+        std::fs::remove_file(&journal).unwrap();
+    }
+}
@@ -6,12 +6,13 @@ use axum::{
     routing::{get, post, delete},
     extract::{State, Path, Query},
     Json,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
 use serde::{Deserialize, Serialize};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use agentkern_telemetry::TraceContext;
 use agentkern_arbiter::{
     Coordinator,
     CoordinationRequest,
@@ -93,6 +94,7 @@ async fn health() -> Json<HealthResponse> {
 
 async fn coordinate(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<CoordinateRequest>,
 ) -> Json<CoordinationResult> {
     let operation = match req.operation.as_deref() {
@@ -110,6 +112,9 @@ async fn coordinate(
     if let Some(d) = req.expected_duration_ms {
         request = request.with_duration_ms(d);
     }
+    if let Some(trace) = TraceContext::from_headers(|name| headers.get(name).and_then(|v| v.to_str().ok())) {
+        request = request.with_trace(trace);
+    }
 
     Json(state.coordinator.request(request).await)
 }
@@ -7,6 +7,7 @@
 //! - Carbon intensity tracking
 //! - Emissions per transaction
 //! - Sustainable scheduling
+//! - Forecast-driven deferral of batch work via a [`CarbonForecastSource`]
 //!
 //! # Example
 //!
@@ -15,10 +16,16 @@
 //!
 //! let scheduler = CarbonScheduler::new();
 //! let best_region = scheduler.select_greenest_region(&["us-east-1", "eu-west-1"]);
+//!
+//! // With grid forecasts, batch work waits for a greener window
+//! let scheduler = CarbonScheduler::new().with_forecast(Arc::new(grid_forecaster));
+//! let placement = scheduler.plan_batch(&["us-east-1", "eu-west-1"], Duration::from_secs(7200), Duration::from_secs(86400));
 //! ```
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Carbon intensity level (gCO2eq/kWh).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -90,11 +97,52 @@ pub enum EmissionScope {
     Scope3,
 }
 
+/// Period in which a workload can run at low carbon intensity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GreenWindow {
+    pub region: String,
+    /// Unix seconds
+    pub starts_at: u64,
+    /// Unix seconds
+    pub ends_at: u64,
+    /// Forecast average over the window (gCO2eq/kWh)
+    pub avg_grams_per_kwh: f64,
+}
+
+/// Live and forecast grid intensity, e.g. from a grid data provider.
+pub trait CarbonForecastSource: Send + Sync + std::fmt::Debug {
+    /// Current gCO2eq/kWh for a region, if known.
+    fn current_intensity(&self, region: &str) -> Option<f64>;
+
+    /// Lowest-carbon window of `duration` that ends by `finish_by` (Unix seconds).
+    fn green_window(&self, region: &str, duration: Duration, finish_by: u64) -> Option<GreenWindow>;
+}
+
+/// Where and when to run a deferrable batch job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchPlacement {
+    pub region: String,
+    /// Unix seconds
+    pub starts_at: u64,
+    /// How long the job waits for its window
+    pub deferred_secs: u64,
+    pub expected_grams_per_kwh: f64,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 /// Carbon scheduler for sustainable execution.
 #[derive(Debug)]
 pub struct CarbonScheduler {
     /// Region data
     regions: HashMap<String, CarbonRegion>,
+    /// Live data and forecasts, overriding the static region table
+    forecast: Option<Arc<dyn CarbonForecastSource>>,
     /// Total emissions tracked
     total_emissions_grams: f64,
     /// Transaction count
@@ -172,11 +220,25 @@ impl CarbonScheduler {
         
         Self {
             regions,
+            forecast: None,
             total_emissions_grams: 0.0,
             transaction_count: 0,
         }
     }
 
+    /// Use live intensity and forecasts from `source`.
+    pub fn with_forecast(mut self, source: Arc<dyn CarbonForecastSource>) -> Self {
+        self.forecast = Some(source);
+        self
+    }
+
+    /// Current gCO2eq/kWh: live if available, else the region table.
+    pub fn current_intensity(&self, region_id: &str) -> Option<f64> {
+        self.forecast.as_ref()
+            .and_then(|f| f.current_intensity(region_id))
+            .or_else(|| self.regions.get(region_id).map(|r| r.current_grams_per_kwh as f64))
+    }
+
     /// Get all green regions.
     pub fn green_regions(&self) -> Vec<&CarbonRegion> {
         self.regions.values().filter(|r| r.is_green).collect()
@@ -186,13 +248,42 @@ impl CarbonScheduler {
     pub fn select_greenest<'a>(&self, candidates: &[&'a str]) -> Option<&'a str> {
         candidates
             .iter()
-            .filter_map(|&id| {
-                self.regions.get(id).map(|r| (id, r.current_grams_per_kwh))
-            })
-            .min_by_key(|(_, grams)| *grams)
+            .filter_map(|&id| self.current_intensity(id).map(|grams| (id, grams)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
             .map(|(id, _)| id)
     }
 
+    /// Place a deferrable job of `duration` that must finish within `deadline`:
+    /// now in the greenest region, or later if a forecast window is greener.
+    pub fn plan_batch(&self, candidates: &[&str], duration: Duration, deadline: Duration) -> Option<BatchPlacement> {
+        let now = unix_now();
+        let region = self.select_greenest(candidates)?;
+        let mut placement = BatchPlacement {
+            region: region.to_string(),
+            starts_at: now,
+            deferred_secs: 0,
+            expected_grams_per_kwh: self.current_intensity(region)?,
+        };
+
+        let Some(forecast) = &self.forecast else {
+            return Some(placement);
+        };
+        let finish_by = now + deadline.as_secs();
+        for &candidate in candidates {
+            if let Some(window) = forecast.green_window(candidate, duration, finish_by) {
+                if window.avg_grams_per_kwh < placement.expected_grams_per_kwh {
+                    placement = BatchPlacement {
+                        region: window.region,
+                        starts_at: window.starts_at.max(now),
+                        deferred_secs: window.starts_at.saturating_sub(now),
+                        expected_grams_per_kwh: window.avg_grams_per_kwh,
+                    };
+                }
+            }
+        }
+        Some(placement)
+    }
+
     /// Calculate emissions for a workload.
     pub fn calculate_emissions(
         &self,
@@ -299,6 +390,43 @@ mod tests {
         assert_eq!(greenest, Some("eu-north-1"));
     }
 
+    #[derive(Debug)]
+    struct SolarForecast;
+
+    impl CarbonForecastSource for SolarForecast {
+        fn current_intensity(&self, region: &str) -> Option<f64> {
+            (region == "eu-central-1").then_some(300.0)
+        }
+
+        fn green_window(&self, region: &str, duration: Duration, finish_by: u64) -> Option<GreenWindow> {
+            // Midday solar peak three hours from now
+            let starts_at = unix_now() + 3 * 3600;
+            let ends_at = starts_at + duration.as_secs();
+            (region == "eu-central-1" && ends_at <= finish_by).then(|| GreenWindow {
+                region: region.to_string(),
+                starts_at,
+                ends_at,
+                avg_grams_per_kwh: 60.0,
+            })
+        }
+    }
+
+    #[test]
+    fn test_plan_batch_defers_to_green_window() {
+        let scheduler = CarbonScheduler::new().with_forecast(Arc::new(SolarForecast));
+        let candidates = ["us-east-1", "eu-central-1"];
+
+        let placement = scheduler.plan_batch(&candidates, Duration::from_secs(3600), Duration::from_secs(86400)).unwrap();
+        assert_eq!(placement.region, "eu-central-1");
+        assert!(placement.deferred_secs > 3 * 3600 - 5);
+        assert_eq!(placement.expected_grams_per_kwh, 60.0);
+
+        // Too tight a deadline: run now in the greenest region
+        let placement = scheduler.plan_batch(&candidates, Duration::from_secs(3600), Duration::from_secs(7200)).unwrap();
+        assert_eq!((placement.region.as_str(), placement.deferred_secs), ("eu-central-1", 0));
+        assert_eq!(placement.expected_grams_per_kwh, 300.0);
+    }
+
     #[test]
     fn test_calculate_emissions() {
         let scheduler = CarbonScheduler::new();
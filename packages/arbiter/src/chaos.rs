@@ -4,9 +4,31 @@
 //!
 //! Provides fault injection and chaos engineering capabilities for testing
 //! system resilience under adverse conditions.
-
+//!
+//! Besides latency and errors around operations, the monkey can corrupt the
+//! wire frames fed to protocol adapters ([`FrameFault`]): bit flips,
+//! truncation, protocol downgrades and duplicate delivery. [`ChaosMonkey::exercise_frames`]
+//! drives a gateway with such frames and reports whether it stayed up and
+//! recovered ([`RobustnessReport`]).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Staging only: frame faults are never injected in production
+//! let monkey = ChaosMonkey::for_environment(ChaosConfig::adapter_faults(), "staging");
+//! let report = monkey
+//!     .exercise_frames(&frames, move |frame| {
+//!         let nexus = nexus.clone();
+//!         async move { nexus.receive(&frame).await.map(|_| ()).map_err(|e| e.to_string()) }
+//!     })
+//!     .await;
+//! report.assert_robust(&RobustnessCriteria::default());
+//! ```
+
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use rand::Rng;
 
 /// Chaos configuration.
@@ -20,6 +42,10 @@ pub struct ChaosConfig {
     pub error_probability: u8,
     /// Error types to inject
     pub error_types: Vec<ChaosError>,
+    /// Probability of corrupting an adapter frame (0-100%)
+    pub frame_fault_probability: u8,
+    /// Frame faults to inject
+    pub frame_faults: Vec<FrameFault>,
     /// Enable/disable chaos
     pub enabled: bool,
 }
@@ -31,6 +57,8 @@ impl Default for ChaosConfig {
             latency_range_ms: (100, 500),
             error_probability: 0,
             error_types: vec![ChaosError::Timeout, ChaosError::NetworkError],
+            frame_fault_probability: 0,
+            frame_faults: FrameFault::ALL.to_vec(),
             enabled: false,
         }
     }
@@ -59,6 +87,7 @@ impl ChaosConfig {
                 ChaosError::ServiceUnavailable,
             ],
             enabled: true,
+            ..Default::default()
         }
     }
 
@@ -75,7 +104,18 @@ impl ChaosConfig {
                 ChaosError::InternalError,
                 ChaosError::DataCorruption,
             ],
+            frame_fault_probability: 30,
             enabled: true,
+            ..Default::default()
+        }
+    }
+
+    /// Create a config that only corrupts adapter frames.
+    pub fn adapter_faults() -> Self {
+        Self {
+            frame_fault_probability: 50,
+            enabled: true,
+            ..Default::default()
         }
     }
 }
@@ -127,6 +167,69 @@ impl ChaosError {
     }
 }
 
+/// Fault injected into a protocol adapter frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameFault {
+    /// Flip one to eight random bits
+    BitFlip,
+    /// Cut the frame short
+    Truncate,
+    /// Rewrite the protocol version marker to an older version
+    ProtocolDowngrade,
+    /// Deliver the frame twice
+    DuplicateDelivery,
+}
+
+/// Version markers rewritten by [`FrameFault::ProtocolDowngrade`].
+const DOWNGRADES: &[(&str, &str)] = &[
+    ("\"jsonrpc\":\"2.0\"", "\"jsonrpc\":\"1.0\""),
+    ("\"nlipVersion\":\"1.0\"", "\"nlipVersion\":\"0.9\""),
+];
+
+impl FrameFault {
+    pub const ALL: [FrameFault; 4] = [
+        FrameFault::BitFlip,
+        FrameFault::Truncate,
+        FrameFault::ProtocolDowngrade,
+        FrameFault::DuplicateDelivery,
+    ];
+
+    /// Apply the fault, returning the frames to deliver in order.
+    pub fn apply(&self, frame: &[u8], rng: &mut impl Rng) -> Vec<Vec<u8>> {
+        match self {
+            Self::BitFlip => {
+                let mut corrupted = frame.to_vec();
+                let bits = corrupted.len() * 8;
+                if bits > 0 {
+                    let flips = rng.gen_range(1..=8.min(bits));
+                    for bit in rand::seq::index::sample(rng, bits, flips) {
+                        corrupted[bit / 8] ^= 1 << (bit % 8);
+                    }
+                }
+                vec![corrupted]
+            }
+            Self::Truncate => vec![frame[..rng.gen_range(0..frame.len().max(1))].to_vec()],
+            Self::ProtocolDowngrade => {
+                let mut text = String::from_utf8_lossy(frame).into_owned();
+                for (current, older) in DOWNGRADES {
+                    text = text.replace(current, older);
+                }
+                vec![text.into_bytes()]
+            }
+            Self::DuplicateDelivery => vec![frame.to_vec(), frame.to_vec()],
+        }
+    }
+}
+
+/// Frames produced by [`ChaosMonkey::inject_frame`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInjection {
+    /// Fault injected, if any
+    pub fault: Option<FrameFault>,
+    /// Frames to deliver in order
+    pub frames: Vec<Vec<u8>>,
+}
+
 /// Chaos injection result.
 #[derive(Debug, Clone)]
 pub enum ChaosResult<T> {
@@ -163,6 +266,8 @@ pub struct ChaosMonkey {
     latency_injections: AtomicU32,
     /// Error injections
     error_injections: AtomicU32,
+    /// Frame fault injections
+    frame_injections: AtomicU32,
     /// Is paused
     paused: AtomicBool,
 }
@@ -175,10 +280,22 @@ impl ChaosMonkey {
             total_ops: AtomicU32::new(0),
             latency_injections: AtomicU32::new(0),
             error_injections: AtomicU32::new(0),
+            frame_injections: AtomicU32::new(0),
             paused: AtomicBool::new(false),
         }
     }
 
+    /// Create a chaos monkey for a deployment environment. Chaos is
+    /// disabled in production whatever the config says.
+    pub fn for_environment(config: ChaosConfig, environment: &str) -> Self {
+        let mut monkey = Self::new(config);
+        if matches!(environment.to_ascii_lowercase().as_str(), "production" | "prod") {
+            tracing::warn!(environment, "Chaos injection disabled in production");
+            monkey.disable();
+        }
+        monkey
+    }
+
     /// Create disabled chaos monkey.
     pub fn disabled() -> Self {
         Self::new(ChaosConfig::default())
@@ -276,12 +393,80 @@ impl ChaosMonkey {
         ChaosResult::Ok(operation().await)
     }
 
+    /// Maybe corrupt an adapter frame.
+    pub fn inject_frame(&self, frame: &[u8]) -> FrameInjection {
+        self.total_ops.fetch_add(1, Ordering::Relaxed);
+
+        if !self.config.enabled || self.paused.load(Ordering::Relaxed) || self.config.frame_faults.is_empty() {
+            return FrameInjection { fault: None, frames: vec![frame.to_vec()] };
+        }
+
+        let mut rng = rand::thread_rng();
+        if rng.gen_range(0..100) >= self.config.frame_fault_probability {
+            return FrameInjection { fault: None, frames: vec![frame.to_vec()] };
+        }
+
+        self.frame_injections.fetch_add(1, Ordering::Relaxed);
+        let fault = self.config.frame_faults[rng.gen_range(0..self.config.frame_faults.len())];
+        FrameInjection { fault: Some(fault), frames: fault.apply(frame, &mut rng) }
+    }
+
+    /// Drive a gateway with `frames`, corrupting some of them.
+    ///
+    /// After every faulted delivery the clean frame is sent again to check
+    /// the gateway recovered. Handlers run on their own task so a panic is
+    /// counted rather than aborting the run.
+    pub async fn exercise_frames<F, Fut>(&self, frames: &[Vec<u8>], handler: F) -> RobustnessReport
+    where
+        F: Fn(Vec<u8>) -> Fut,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let mut report = RobustnessReport::default();
+
+        for frame in frames {
+            let injection = self.inject_frame(frame);
+            let Some(fault) = injection.fault else {
+                report.clean_frames += 1;
+                match deliver(handler(frame.clone())).await {
+                    Delivered::Ok => {}
+                    Delivered::Rejected(reason) => report.clean_failures.push(reason),
+                    Delivered::Panicked => report.panics += 1,
+                }
+                continue;
+            };
+
+            let outcome = report.faults.entry(format!("{:?}", fault)).or_default();
+            for corrupted in injection.frames {
+                outcome.delivered += 1;
+                match deliver(handler(corrupted)).await {
+                    Delivered::Ok => outcome.accepted += 1,
+                    Delivered::Rejected(_) => outcome.rejected += 1,
+                    Delivered::Panicked => {
+                        outcome.panics += 1;
+                        report.panics += 1;
+                    }
+                }
+            }
+
+            // Recovery probe
+            report.recovery_probes += 1;
+            let started = Instant::now();
+            if deliver(handler(frame.clone())).await == Delivered::Ok {
+                report.recovered += 1;
+                report.max_recovery_ms = report.max_recovery_ms.max(started.elapsed().as_millis() as u64);
+            }
+        }
+
+        report
+    }
+
     /// Get statistics.
     pub fn stats(&self) -> ChaosStats {
         ChaosStats {
             total_ops: self.total_ops.load(Ordering::Relaxed),
             latency_injections: self.latency_injections.load(Ordering::Relaxed),
             error_injections: self.error_injections.load(Ordering::Relaxed),
+            frame_injections: self.frame_injections.load(Ordering::Relaxed),
         }
     }
 
@@ -290,6 +475,131 @@ impl ChaosMonkey {
         self.total_ops.store(0, Ordering::Relaxed);
         self.latency_injections.store(0, Ordering::Relaxed);
         self.error_injections.store(0, Ordering::Relaxed);
+        self.frame_injections.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Delivered {
+    Ok,
+    Rejected(String),
+    Panicked,
+}
+
+async fn deliver<Fut>(delivery: Fut) -> Delivered
+where
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    match tokio::spawn(delivery).await {
+        Ok(Ok(())) => Delivered::Ok,
+        Ok(Err(reason)) => Delivered::Rejected(reason),
+        Err(_) => Delivered::Panicked,
+    }
+}
+
+/// How a gateway handled one kind of frame fault.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultOutcome {
+    /// Frames delivered (duplicates count twice)
+    pub delivered: u32,
+    /// Rejected with an error
+    pub rejected: u32,
+    /// Accepted despite the fault
+    pub accepted: u32,
+    pub panics: u32,
+}
+
+/// Gateway behaviour under frame faults.
+#[derive(Debug, Clone, Default)]
+pub struct RobustnessReport {
+    /// Frames delivered without a fault
+    pub clean_frames: u32,
+    /// Errors returned for clean frames
+    pub clean_failures: Vec<String>,
+    /// Outcomes per fault kind
+    pub faults: std::collections::BTreeMap<String, FaultOutcome>,
+    pub panics: u32,
+    /// Clean frames re-sent after a fault
+    pub recovery_probes: u32,
+    /// Probes the gateway accepted
+    pub recovered: u32,
+    /// Slowest successful probe
+    pub max_recovery_ms: u64,
+}
+
+/// Pass/fail thresholds for a [`RobustnessReport`].
+#[derive(Debug, Clone)]
+pub struct RobustnessCriteria {
+    pub max_panics: u32,
+    /// Share of clean frames that must be accepted (0.0-1.0)
+    pub min_clean_success: f64,
+    /// Share of recovery probes that must be accepted (0.0-1.0)
+    pub min_recovery_rate: f64,
+    pub max_recovery_ms: u64,
+}
+
+impl Default for RobustnessCriteria {
+    fn default() -> Self {
+        Self {
+            max_panics: 0,
+            min_clean_success: 1.0,
+            min_recovery_rate: 1.0,
+            max_recovery_ms: 1000,
+        }
+    }
+}
+
+impl RobustnessReport {
+    /// Share of recovery probes accepted; 1.0 when nothing was faulted.
+    pub fn recovery_rate(&self) -> f64 {
+        if self.recovery_probes == 0 {
+            return 1.0;
+        }
+        self.recovered as f64 / self.recovery_probes as f64
+    }
+
+    /// Share of clean frames accepted; 1.0 when none were sent.
+    pub fn clean_success(&self) -> f64 {
+        if self.clean_frames == 0 {
+            return 1.0;
+        }
+        1.0 - self.clean_failures.len() as f64 / self.clean_frames as f64
+    }
+
+    /// Criteria the run failed, empty if it passed.
+    pub fn violations(&self, criteria: &RobustnessCriteria) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.panics > criteria.max_panics {
+            violations.push(format!("{} panics (max {})", self.panics, criteria.max_panics));
+        }
+        if self.clean_success() < criteria.min_clean_success {
+            violations.push(format!(
+                "clean frame success {:.2} below {:.2}: {:?}",
+                self.clean_success(),
+                criteria.min_clean_success,
+                self.clean_failures
+            ));
+        }
+        if self.recovery_rate() < criteria.min_recovery_rate {
+            violations.push(format!(
+                "recovery rate {:.2} below {:.2}",
+                self.recovery_rate(),
+                criteria.min_recovery_rate
+            ));
+        }
+        if self.max_recovery_ms > criteria.max_recovery_ms {
+            violations.push(format!(
+                "recovery took {}ms (max {}ms)",
+                self.max_recovery_ms, criteria.max_recovery_ms
+            ));
+        }
+        violations
+    }
+
+    /// Panic with every violation, for use in tests.
+    pub fn assert_robust(&self, criteria: &RobustnessCriteria) {
+        let violations = self.violations(criteria);
+        assert!(violations.is_empty(), "gateway not robust under frame faults: {}", violations.join("; "));
     }
 }
 
@@ -299,6 +609,7 @@ pub struct ChaosStats {
     pub total_ops: u32,
     pub latency_injections: u32,
     pub error_injections: u32,
+    pub frame_injections: u32,
 }
 
 impl ChaosStats {
@@ -307,7 +618,7 @@ impl ChaosStats {
         if self.total_ops == 0 {
             return 0.0;
         }
-        (self.latency_injections + self.error_injections) as f64 / self.total_ops as f64 * 100.0
+        (self.latency_injections + self.error_injections + self.frame_injections) as f64 / self.total_ops as f64 * 100.0
     }
 }
 
@@ -402,4 +713,53 @@ mod tests {
         assert!(err.had_chaos());
         assert!(err.into_result().is_err());
     }
+
+    #[test]
+    fn test_frame_faults() {
+        let frame = br#"{"jsonrpc":"2.0","id":"1","method":"tasks/send"}"#;
+        let mut rng = rand::thread_rng();
+
+        let flipped = FrameFault::BitFlip.apply(frame, &mut rng);
+        assert_ne!(flipped[0], frame.to_vec());
+        assert_eq!(flipped[0].len(), frame.len());
+        assert!(FrameFault::Truncate.apply(frame, &mut rng)[0].len() < frame.len());
+        assert_eq!(FrameFault::DuplicateDelivery.apply(frame, &mut rng).len(), 2);
+        let downgraded = FrameFault::ProtocolDowngrade.apply(frame, &mut rng);
+        assert!(String::from_utf8_lossy(&downgraded[0]).contains("\"jsonrpc\":\"1.0\""));
+
+        // Never in production
+        let prod = ChaosMonkey::for_environment(ChaosConfig::adapter_faults(), "Production");
+        assert_eq!(prod.inject_frame(frame).fault, None);
+    }
+
+    #[tokio::test]
+    async fn test_exercise_frames_reports_panics_and_recovery() {
+        let monkey = ChaosMonkey::new(ChaosConfig {
+            frame_fault_probability: 100,
+            frame_faults: vec![FrameFault::Truncate],
+            enabled: true,
+            ..Default::default()
+        });
+        let frames = vec![br#"{"id":"1"}"#.to_vec(); 5];
+
+        // Panics on anything that is not valid JSON
+        let report = monkey
+            .exercise_frames(&frames, |frame| async move {
+                let _: serde_json::Value = serde_json::from_slice(&frame).unwrap();
+                Ok(())
+            })
+            .await;
+        assert_eq!(report.panics, 5);
+        assert_eq!(report.recovery_rate(), 1.0);
+        assert_eq!(report.violations(&RobustnessCriteria::default()).len(), 1);
+
+        let report = monkey
+            .exercise_frames(&frames, |frame| async move {
+                serde_json::from_slice::<serde_json::Value>(&frame).map(|_| ()).map_err(|e| e.to_string())
+            })
+            .await;
+        assert_eq!(report.faults["Truncate"].rejected, 5);
+        report.assert_robust(&RobustnessCriteria::default());
+        assert_eq!(monkey.stats().frame_injections, 10);
+    }
 }
@@ -4,6 +4,9 @@
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::Instrument;
+
+use agentkern_telemetry::start_span;
 
 use crate::locks::{LockManager, LockError};
 use crate::queue::PriorityQueue;
@@ -16,6 +19,8 @@ pub struct Coordinator {
     lock_manager: LockManager,
     queue: Arc<RwLock<PriorityQueue>>,
     avg_lock_duration_ms: u64,
+    /// Waiters allowed per resource; `None` = unbounded
+    max_waiters: Option<usize>,
 }
 
 impl Default for Coordinator {
@@ -30,36 +35,59 @@ impl Coordinator {
             lock_manager: LockManager::new(),
             queue: Arc::new(RwLock::new(PriorityQueue::new())),
             avg_lock_duration_ms: 5000, // 5 seconds default
+            max_waiters: None,
         }
     }
 
+    /// Bound each resource's wait queue; further requests are denied with
+    /// the estimated wait as a retry hint.
+    pub fn with_max_waiters(mut self, max_waiters: usize) -> Self {
+        self.max_waiters = Some(max_waiters);
+        self
+    }
+
     /// Request coordination for a resource.
+    ///
+    /// A traced request is coordinated in an `arbiter.coordinate` child span.
     pub async fn request(&self, request: CoordinationRequest) -> CoordinationResult {
-        // Try to acquire lock
-        match self.lock_manager.acquire(
-            &request.agent_id,
-            &request.resource,
-            request.priority,
-            request.operation,
-            Some(request.expected_duration_ms),
-        ).await {
-            Ok(lock) => {
-                // Lock acquired, remove from queue if present
-                let mut queue = self.queue.write().await;
-                queue.dequeue(&request.agent_id, &request.resource);
-                CoordinationResult::granted(lock)
-            }
-            Err(LockError::ResourceLocked { .. }) => {
-                // Add to queue
-                let mut queue = self.queue.write().await;
-                let position = queue.enqueue(request.clone()) as u32;
-                let wait_ms = queue.estimate_wait_ms(position as usize, self.avg_lock_duration_ms);
-                CoordinationResult::queued(position, wait_ms)
-            }
-            Err(e) => {
-                CoordinationResult::denied(e.to_string())
+        let (trace, span) = start_span("arbiter.coordinate", request.trace.as_ref(), &[]);
+        let mut result = async {
+            // Try to acquire lock
+            match self.lock_manager.acquire(
+                &request.agent_id,
+                &request.resource,
+                request.priority,
+                request.operation,
+                Some(request.expected_duration_ms),
+            ).await {
+                Ok(lock) => {
+                    // Lock acquired, remove from queue if present
+                    let mut queue = self.queue.write().await;
+                    queue.dequeue(&request.agent_id, &request.resource);
+                    CoordinationResult::granted(lock)
+                }
+                Err(LockError::ResourceLocked { .. }) => {
+                    // Add to queue
+                    let mut queue = self.queue.write().await;
+                    let waiting = queue.queue_length(&request.resource);
+                    let requeue = queue.get_position(&request.agent_id, &request.resource).is_some();
+                    if self.max_waiters.is_some_and(|max| waiting >= max) && !requeue {
+                        let wait_ms = queue.estimate_wait_ms(waiting + 1, self.avg_lock_duration_ms);
+                        return CoordinationResult::busy(wait_ms);
+                    }
+                    let position = queue.enqueue(request.clone()) as u32;
+                    let wait_ms = queue.estimate_wait_ms(position as usize, self.avg_lock_duration_ms);
+                    CoordinationResult::queued(position, wait_ms)
+                }
+                Err(e) => {
+                    CoordinationResult::denied(e.to_string())
+                }
             }
         }
+        .instrument(span)
+        .await;
+        result.trace = trace;
+        result
     }
 
     /// Acquire a lock directly (bypass queue).
@@ -127,6 +155,17 @@ mod tests {
         assert!(result.lock.is_some());
     }
 
+    #[tokio::test]
+    async fn test_coordinator_request_traced() {
+        let coord = Coordinator::new();
+        let root = agentkern_telemetry::TraceContext::new_root();
+
+        let result = coord.request(CoordinationRequest::new("agent-1", "resource-1").with_trace(root.clone())).await;
+        let trace = result.trace.unwrap();
+        assert_eq!(trace.trace_id, root.trace_id);
+        assert_eq!(trace.parent_span_id, Some(root.span_id));
+    }
+
     #[tokio::test]
     async fn test_coordinator_request_queued() {
         let coord = Coordinator::new();
@@ -143,6 +182,20 @@ mod tests {
         assert_eq!(result2.queue_position, Some(1));
     }
 
+    #[tokio::test]
+    async fn test_coordinator_bounded_queue() {
+        let coord = Coordinator::new().with_max_waiters(1);
+
+        assert!(coord.request(CoordinationRequest::new("agent-1", "resource-1")).await.granted);
+        let queued = coord.request(CoordinationRequest::new("agent-2", "resource-1")).await;
+        assert_eq!(queued.queue_position, Some(1));
+
+        let busy = coord.request(CoordinationRequest::new("agent-3", "resource-1")).await;
+        assert!(!busy.granted);
+        assert_eq!(busy.queue_position, None);
+        assert_eq!(busy.estimated_wait_ms, Some(5000));
+    }
+
     #[tokio::test]
     async fn test_coordinator_release_grants_next() {
         let coord = Coordinator::new();
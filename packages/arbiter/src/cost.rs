@@ -7,6 +7,7 @@
 //! - Per-agent cost tracking
 //! - Real-time budget alerts
 //! - Cost breakdown by resource type
+//! - Savings tracking (e.g. cached model responses)
 //! - Billing export for enterprise
 
 use serde::{Deserialize, Serialize};
@@ -50,6 +51,9 @@ pub struct CostEvent {
     pub category: CostCategory,
     /// Amount in USD
     pub amount_usd: f64,
+    /// Spend avoided, e.g. by a cache hit (USD)
+    #[serde(default)]
+    pub saved_usd: f64,
     /// Resource description
     pub resource: String,
     /// Usage quantity
@@ -69,6 +73,9 @@ pub struct AgentCostSummary {
     pub agent_id: String,
     /// Total cost in USD
     pub total_usd: f64,
+    /// Total spend avoided in USD
+    #[serde(default)]
+    pub saved_usd: f64,
     /// Cost by category
     pub by_category: HashMap<String, f64>,
     /// Number of events
@@ -167,6 +174,7 @@ impl CostTracker {
             category,
             resource: String::new(),
             amount_usd: 0.0,
+            saved_usd: 0.0,
             quantity: 0.0,
             unit: String::new(),
             task_id: None,
@@ -183,6 +191,15 @@ impl CostTracker {
             .sum()
     }
     
+    /// Get total spend avoided for an agent.
+    pub fn get_agent_savings(&self, agent_id: &str) -> f64 {
+        self.events.read()
+            .iter()
+            .filter(|e| e.agent_id == agent_id)
+            .map(|e| e.saved_usd)
+            .sum()
+    }
+    
     /// Get agent cost summary.
     pub fn get_agent_summary(&self, agent_id: &str) -> AgentCostSummary {
         let events = self.events.read();
@@ -199,6 +216,7 @@ impl CostTracker {
         AgentCostSummary {
             agent_id: agent_id.to_string(),
             total_usd: agent_events.iter().map(|e| e.amount_usd).sum(),
+            saved_usd: agent_events.iter().map(|e| e.saved_usd).sum(),
             by_category,
             event_count: agent_events.len() as u64,
             first_event: agent_events.first().map(|e| e.timestamp),
@@ -230,6 +248,7 @@ impl CostTracker {
         
         GlobalCostSummary {
             total_usd: events.iter().map(|e| e.amount_usd).sum(),
+            saved_usd: events.iter().map(|e| e.saved_usd).sum(),
             by_agent,
             by_category,
             event_count: events.len() as u64,
@@ -319,6 +338,7 @@ pub struct CostEventBuilder {
     category: CostCategory,
     resource: String,
     amount_usd: f64,
+    saved_usd: f64,
     quantity: f64,
     unit: String,
     task_id: Option<String>,
@@ -336,6 +356,12 @@ impl CostEventBuilder {
         self
     }
     
+    /// Spend this event avoided.
+    pub fn saved(mut self, usd: f64) -> Self {
+        self.saved_usd = usd;
+        self
+    }
+    
     pub fn quantity(mut self, qty: f64, unit: impl Into<String>) -> Self {
         self.quantity = qty;
         self.unit = unit.into();
@@ -359,6 +385,7 @@ impl CostEventBuilder {
             timestamp: chrono::Utc::now().timestamp_millis() as u64,
             category: self.category,
             amount_usd: self.amount_usd,
+            saved_usd: self.saved_usd,
             resource: self.resource,
             quantity: self.quantity,
             unit: self.unit,
@@ -372,6 +399,8 @@ impl CostEventBuilder {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalCostSummary {
     pub total_usd: f64,
+    #[serde(default)]
+    pub saved_usd: f64,
     pub by_agent: HashMap<String, f64>,
     pub by_category: HashMap<String, f64>,
     pub event_count: u64,
@@ -473,6 +502,19 @@ mod tests {
         assert_eq!(summary.by_agent.len(), 2);
     }
 
+    #[test]
+    fn test_savings_tracked_separately() {
+        let tracker = CostTracker::new();
+        
+        tracker.record(tracker.event("agent-1", CostCategory::LlmInference).amount(0.01).build());
+        tracker.record(tracker.event("agent-1", CostCategory::LlmInference).saved(0.01).build());
+        
+        let summary = tracker.get_agent_summary("agent-1");
+        assert!((summary.total_usd - 0.01).abs() < 0.001);
+        assert!((summary.saved_usd - 0.01).abs() < 0.001);
+        assert!((tracker.get_global_summary().saved_usd - tracker.get_agent_savings("agent-1")).abs() < 1e-12);
+    }
+
     #[test]
     fn test_export_csv() {
         let tracker = CostTracker::new();
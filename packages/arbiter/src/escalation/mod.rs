@@ -4,10 +4,12 @@
 //! Per Strategic Roadmap: Human-in-the-Loop Hub
 //!
 //! Provides escalation triggers when agents hit trust thresholds,
-//! webhook notifications, and human approval workflows.
+//! webhook notifications, declarative routing rules, and human approval
+//! workflows.
 
 pub mod triggers;
 pub mod webhook;
+pub mod routing;
 pub mod approval;
 
 // Re-exports
@@ -18,6 +20,10 @@ pub use triggers::{
 pub use webhook::{
     WebhookNotifier, WebhookConfig, WebhookPayload, WebhookResult,
 };
+pub use routing::{
+    EscalationRouter, RoutingRules, RoutingRule, RuleMatch, Destination, RoutingDecision, RoutingError,
+    BusinessHours, OnCallSchedule,
+};
 pub use approval::{
     ApprovalWorkflow, ApprovalRequest, ApprovalDecision, ApprovalStatus,
 };
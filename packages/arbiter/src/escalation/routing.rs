@@ -0,0 +1,537 @@
+//! Escalation Routing - Declarative rules for where escalations go
+//!
+//! Rules are read from YAML and evaluated in order; the first match wins
+//! unless the rule sets `continue: true`. A rule matches on escalation
+//! level, trigger type, tenant (the `tenant_id` context key), business
+//! hours and whether an on-call schedule has someone on shift, and routes
+//! to webhooks, email addresses or the current on-call person.
+//!
+//! ```yaml
+//! business_hours:
+//!   days: [mon, tue, wed, thu, fri]
+//!   start: "09:00"
+//!   end: "17:00"
+//!   utc_offset_minutes: -300
+//! schedules:
+//!   primary:
+//!     rotation: [alice@example.com, bob@example.com]
+//!     shift_hours: 168
+//!     starts_at: 2026-01-05T14:00:00Z
+//! rules:
+//!   - name: critical pages on-call
+//!     when: { min_level: Critical }
+//!     route: [!webhook pagerduty, !on_call primary]
+//!     continue: true
+//!   - name: acme in office hours
+//!     when: { tenants: [acme], business_hours: true }
+//!     route: [!webhook acme-slack]
+//!   - name: everything else
+//!     route: [!email [ops@example.com]]
+//! ```
+//!
+//! [`EscalationRouter`] reloads the file when it changes; a file that fails
+//! to parse or validate is rejected and the current rules stay in force.
+//! [`RoutingRules::simulate`] explains, rule by rule, where a hypothetical
+//! alert would land.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let router = Arc::new(EscalationRouter::load("/etc/agentkern/escalation.yaml")?);
+//! tokio::spawn(router.clone().watch(Duration::from_secs(5)));
+//!
+//! for delivery in router.dispatch(&notifier, &trigger) {
+//!     tracing::info!(destination = %delivery.destination, ok = delivery.result.is_ok(), "Escalation routed");
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveTime, Utc, Weekday};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::triggers::{EscalationLevel, TriggerResult, TriggerType};
+use super::webhook::{WebhookNotifier, WebhookResult};
+
+/// Context key naming the tenant of an escalation.
+pub const TENANT_KEY: &str = "tenant_id";
+
+/// Routing rule errors.
+#[derive(Debug, Error)]
+pub enum RoutingError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse {path}: {reason}")]
+    Parse { path: PathBuf, reason: String },
+    #[error("Invalid routing rules: {0}")]
+    Invalid(String),
+}
+
+impl agentkern_errors::Coded for RoutingError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::Io { .. } => ErrorCode::Unavailable,
+            Self::Parse { .. } | Self::Invalid(_) => ErrorCode::InvalidArgument,
+        }
+    }
+}
+
+/// Weekly office hours at a fixed UTC offset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusinessHours {
+    #[serde(default = "BusinessHours::weekdays")]
+    pub days: Vec<Weekday>,
+    /// Local opening time, `HH:MM`
+    pub start: NaiveTime,
+    /// Local closing time, `HH:MM` (exclusive)
+    pub end: NaiveTime,
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+impl BusinessHours {
+    fn weekdays() -> Vec<Weekday> {
+        vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]
+    }
+
+    /// Is `at` inside office hours?
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let Some(offset) = FixedOffset::east_opt(self.utc_offset_minutes * 60) else {
+            return false;
+        };
+        let local = at.with_timezone(&offset);
+        let time = local.time();
+        self.days.contains(&local.weekday()) && time >= self.start && time < self.end
+    }
+}
+
+/// A rotation handing over every `shift_hours`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnCallSchedule {
+    /// Contacts in rotation order
+    pub rotation: Vec<String>,
+    pub shift_hours: u32,
+    /// Start of the first shift
+    pub starts_at: DateTime<Utc>,
+}
+
+impl OnCallSchedule {
+    /// Who is on call at `at`, if the rotation has started.
+    pub fn on_call(&self, at: DateTime<Utc>) -> Option<&str> {
+        if self.rotation.is_empty() || self.shift_hours == 0 || at < self.starts_at {
+            return None;
+        }
+        let shifts = (at - self.starts_at).num_hours() / self.shift_hours as i64;
+        Some(&self.rotation[shifts as usize % self.rotation.len()])
+    }
+}
+
+/// Conditions of a rule; all set fields must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleMatch {
+    #[serde(default)]
+    pub min_level: Option<EscalationLevel>,
+    #[serde(default)]
+    pub max_level: Option<EscalationLevel>,
+    /// Any of these trigger types (empty: any)
+    #[serde(default)]
+    pub triggers: Vec<TriggerType>,
+    /// Any of these tenants (empty: any)
+    #[serde(default)]
+    pub tenants: Vec<String>,
+    /// Inside (`true`) or outside (`false`) business hours
+    #[serde(default)]
+    pub business_hours: Option<bool>,
+    /// Schedule that must have someone on call
+    #[serde(default)]
+    pub on_call: Option<String>,
+}
+
+/// Where a routed escalation is sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    /// A webhook registered with the [`WebhookNotifier`], by ID
+    Webhook(String),
+    Email(Vec<String>),
+    /// Whoever is on call in this schedule
+    OnCall(String),
+}
+
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Webhook(id) => write!(f, "webhook:{}", id),
+            Self::Email(to) => write!(f, "email:{}", to.join(",")),
+            Self::OnCall(contact) => write!(f, "on-call:{}", contact),
+        }
+    }
+}
+
+/// One routing rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutingRule {
+    pub name: String,
+    #[serde(default)]
+    pub when: RuleMatch,
+    pub route: Vec<Destination>,
+    /// Keep evaluating later rules after this one matches
+    #[serde(default, rename = "continue")]
+    pub continue_matching: bool,
+}
+
+/// A parsed routing rule file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutingRules {
+    #[serde(default)]
+    pub business_hours: Option<BusinessHours>,
+    #[serde(default)]
+    pub schedules: BTreeMap<String, OnCallSchedule>,
+    #[serde(default)]
+    pub rules: Vec<RoutingRule>,
+}
+
+/// How one rule fared against an alert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub matched: bool,
+    /// First failed condition, when not matched
+    pub reason: Option<String>,
+}
+
+/// Where an alert lands, and why.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    /// Resolved destinations, without duplicates; on-call entries name the
+    /// contact on shift
+    pub destinations: Vec<Destination>,
+    /// Rules evaluated, in order
+    pub trace: Vec<RuleTrace>,
+}
+
+impl RoutingRules {
+    /// Parse and validate rules from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, RoutingError> {
+        let rules: Self = serde_yaml::from_str(yaml).map_err(|e| RoutingError::Invalid(e.to_string()))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Read rules from a YAML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RoutingError> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)
+            .map_err(|source| RoutingError::Io { path: path.to_path_buf(), source })?;
+        Self::from_yaml(&yaml).map_err(|e| match e {
+            RoutingError::Invalid(reason) => RoutingError::Parse { path: path.to_path_buf(), reason },
+            e => e,
+        })
+    }
+
+    fn validate(&self) -> Result<(), RoutingError> {
+        let mut names = HashSet::new();
+        for rule in &self.rules {
+            if !names.insert(rule.name.as_str()) {
+                return Err(RoutingError::Invalid(format!("duplicate rule '{}'", rule.name)));
+            }
+            if rule.route.is_empty() {
+                return Err(RoutingError::Invalid(format!("rule '{}' routes nowhere", rule.name)));
+            }
+            if rule.when.business_hours.is_some() && self.business_hours.is_none() {
+                return Err(RoutingError::Invalid(format!(
+                    "rule '{}' matches on business hours, but none are defined",
+                    rule.name
+                )));
+            }
+            let schedules = rule.when.on_call.iter().chain(rule.route.iter().filter_map(|d| match d {
+                Destination::OnCall(schedule) => Some(schedule),
+                _ => None,
+            }));
+            for schedule in schedules {
+                if !self.schedules.contains_key(schedule) {
+                    return Err(RoutingError::Invalid(format!(
+                        "rule '{}' names unknown schedule '{}'",
+                        rule.name, schedule
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Route an escalation raised at `at`.
+    pub fn route(&self, trigger: &TriggerResult, at: DateTime<Utc>) -> RoutingDecision {
+        let mut decision = RoutingDecision::default();
+        for rule in &self.rules {
+            let reason = self.mismatch(&rule.when, trigger, at);
+            decision.trace.push(RuleTrace { rule: rule.name.clone(), matched: reason.is_none(), reason: reason.clone() });
+            if reason.is_some() {
+                continue;
+            }
+
+            for destination in &rule.route {
+                let resolved = match destination {
+                    Destination::OnCall(schedule) => {
+                        match self.schedules.get(schedule).and_then(|s| s.on_call(at)) {
+                            Some(contact) => Destination::OnCall(contact.to_string()),
+                            None => continue,
+                        }
+                    }
+                    other => other.clone(),
+                };
+                if !decision.destinations.contains(&resolved) {
+                    decision.destinations.push(resolved);
+                }
+            }
+            if !rule.continue_matching {
+                break;
+            }
+        }
+        decision
+    }
+
+    /// Like [`RoutingRules::route`], for an alert that has not happened.
+    pub fn simulate(
+        &self,
+        level: EscalationLevel,
+        trigger_type: TriggerType,
+        tenant: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> RoutingDecision {
+        let mut context = std::collections::HashMap::new();
+        if let Some(tenant) = tenant {
+            context.insert(TENANT_KEY.to_string(), serde_json::Value::String(tenant.to_string()));
+        }
+        let trigger = TriggerResult {
+            triggered: true,
+            level,
+            trigger_type,
+            agent_id: "simulated".to_string(),
+            reason: "simulation".to_string(),
+            context,
+            timestamp: at.timestamp_millis().max(0) as u64,
+        };
+        self.route(&trigger, at)
+    }
+
+    /// The first condition of `when` that the alert fails, if any.
+    fn mismatch(&self, when: &RuleMatch, trigger: &TriggerResult, at: DateTime<Utc>) -> Option<String> {
+        if let Some(min) = when.min_level.filter(|min| trigger.level < *min) {
+            return Some(format!("level {:?} below {:?}", trigger.level, min));
+        }
+        if let Some(max) = when.max_level.filter(|max| trigger.level > *max) {
+            return Some(format!("level {:?} above {:?}", trigger.level, max));
+        }
+        if !when.triggers.is_empty() && !when.triggers.contains(&trigger.trigger_type) {
+            return Some(format!("trigger {:?} not listed", trigger.trigger_type));
+        }
+        if !when.tenants.is_empty() {
+            let tenant = trigger.context.get(TENANT_KEY).and_then(|v| v.as_str());
+            if !tenant.is_some_and(|t| when.tenants.iter().any(|listed| listed == t)) {
+                return Some(format!("tenant {} not listed", tenant.unwrap_or("(none)")));
+            }
+        }
+        if let Some(wanted) = when.business_hours {
+            let inside = self.business_hours.as_ref().is_some_and(|hours| hours.contains(at));
+            if inside != wanted {
+                return Some(if inside { "inside business hours" } else { "outside business hours" }.to_string());
+            }
+        }
+        match &when.on_call {
+            Some(schedule) if self.schedules.get(schedule).and_then(|s| s.on_call(at)).is_none() => {
+                Some(format!("nobody on call in '{}'", schedule))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// One delivery made by [`EscalationRouter::dispatch`].
+#[derive(Debug)]
+pub struct Delivery {
+    pub destination: Destination,
+    pub result: WebhookResult<()>,
+}
+
+/// Routing rules backed by a file, reloaded when it changes.
+pub struct EscalationRouter {
+    path: PathBuf,
+    rules: RwLock<Arc<RoutingRules>>,
+    modified: Mutex<Option<SystemTime>>,
+}
+
+impl EscalationRouter {
+    /// Load rules from `path`; fails if the initial file is invalid.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, RoutingError> {
+        let path = path.into();
+        let rules = RoutingRules::from_file(&path)?;
+        let modified = modified_at(&path);
+        Ok(Self { path, rules: RwLock::new(Arc::new(rules)), modified: Mutex::new(modified) })
+    }
+
+    /// Rules currently in force.
+    pub fn rules(&self) -> Arc<RoutingRules> {
+        self.rules.read().clone()
+    }
+
+    /// Re-read the file if it changed since the last load. Returns whether
+    /// new rules were applied; invalid files leave the current rules.
+    pub fn reload(&self) -> Result<bool, RoutingError> {
+        let modified = modified_at(&self.path);
+        let mut last = self.modified.lock();
+        if modified == *last {
+            return Ok(false);
+        }
+        // Remember the attempt, so a broken file is not re-parsed every poll
+        *last = modified;
+        let rules = RoutingRules::from_file(&self.path)?;
+        *self.rules.write() = Arc::new(rules);
+        Ok(true)
+    }
+
+    /// Poll the file for changes every `interval`.
+    pub async fn watch(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.reload() {
+                Ok(true) => tracing::info!(path = %self.path.display(), "Escalation routing rules reloaded"),
+                Ok(false) => {}
+                Err(e) => tracing::warn!(error = %e, "Escalation routing rules rejected, keeping current rules"),
+            }
+        }
+    }
+
+    /// Route `trigger` now and deliver it to each destination.
+    ///
+    /// Webhooks go through `notifier`; email and on-call destinations are
+    /// logged for the mail and paging integrations to pick up.
+    pub fn dispatch(&self, notifier: &WebhookNotifier, trigger: &TriggerResult) -> Vec<Delivery> {
+        let decision = self.rules().route(trigger, Utc::now());
+        decision
+            .destinations
+            .into_iter()
+            .map(|destination| {
+                let result = match &destination {
+                    Destination::Webhook(id) => notifier.notify_webhook(id, trigger),
+                    other => {
+                        tracing::info!(destination = %other, agent_id = %trigger.agent_id, level = ?trigger.level, "Escalation queued");
+                        Ok(())
+                    }
+                };
+                Delivery { destination, result }
+            })
+            .collect()
+    }
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+business_hours:
+  days: [mon, tue, wed, thu, fri]
+  start: "09:00"
+  end: "17:00"
+schedules:
+  primary:
+    rotation: [alice, bob]
+    shift_hours: 24
+    starts_at: 2026-01-05T00:00:00Z
+rules:
+  - name: critical
+    when: { min_level: Critical }
+    route: [!webhook pagerduty, !on_call primary]
+    continue: true
+  - name: acme office hours
+    when: { tenants: [acme], business_hours: true }
+    route: [!webhook acme-slack]
+  - name: fallback
+    route: [!email [ops@example.com]]
+"#;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_rules_route_by_level_tenant_and_hours() {
+        let rules = RoutingRules::from_yaml(RULES).unwrap();
+        // Tuesday 2026-01-06, second shift
+        let office = at("2026-01-06T10:00:00Z");
+        let night = at("2026-01-06T22:00:00Z");
+
+        let decision = rules.simulate(EscalationLevel::Critical, TriggerType::BudgetExceeded, Some("acme"), office);
+        assert_eq!(
+            decision.destinations,
+            [
+                Destination::Webhook("pagerduty".into()),
+                Destination::OnCall("bob".into()),
+                Destination::Webhook("acme-slack".into()),
+            ]
+        );
+
+        let decision = rules.simulate(EscalationLevel::Medium, TriggerType::TrustScore, Some("acme"), night);
+        assert_eq!(decision.destinations, [Destination::Email(vec!["ops@example.com".into()])]);
+        assert_eq!(decision.trace[0].reason.as_deref(), Some("level Medium below Critical"));
+        assert_eq!(decision.trace[1].reason.as_deref(), Some("outside business hours"));
+        assert!(decision.trace[2].matched);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let unknown_schedule = "rules:\n  - name: page\n    route: [!on_call secondary]\n";
+        assert!(matches!(RoutingRules::from_yaml(unknown_schedule), Err(RoutingError::Invalid(_))));
+
+        let no_hours = "rules:\n  - name: day\n    when: { business_hours: true }\n    route: [!webhook slack]\n";
+        assert!(matches!(RoutingRules::from_yaml(no_hours), Err(RoutingError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_router_hot_reload_keeps_rules_on_error() {
+        let path = std::env::temp_dir().join(format!("escalation-{}.yaml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, RULES).unwrap();
+        let router = EscalationRouter::load(&path).unwrap();
+        assert!(!router.reload().unwrap());
+
+        let write = |yaml: &str| {
+            std::fs::write(&path, yaml).unwrap();
+            // Make the change visible even on coarse-grained mtimes
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        };
+
+        write("rules: [{ name: broken }]\n");
+        assert!(router.reload().is_err());
+        assert_eq!(router.rules().rules.len(), 3);
+
+        write("rules:\n  - name: all\n    route: [!webhook slack]\n");
+        assert!(router.reload().unwrap());
+        assert_eq!(router.rules().rules.len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+}
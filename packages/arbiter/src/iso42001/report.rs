@@ -29,6 +29,22 @@ pub struct AuditReport {
     pub high_risk_actions: usize,
     pub compliance_score: u8,
     pub findings: Vec<ComplianceFinding>,
+    /// Set when the report was generated from a replica rather than the live ledger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<DataFreshness>,
+}
+
+/// How current the data behind a report is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataFreshness {
+    /// Where the data was read from, e.g. the replicated journal path
+    pub source: String,
+    /// Every record persisted before this instant is included
+    pub as_of: DateTime<Utc>,
+    /// Newest record included
+    pub last_record_at: Option<DateTime<Utc>>,
+    /// Upper bound on how far the data trails the primary ledger, in ms
+    pub max_staleness_ms: u64,
 }
 
 impl AuditReport {
@@ -57,6 +73,14 @@ impl AuditReport {
             self.period_end.format("%Y-%m-%d")
         ));
         md.push_str(&format!("**Generated**: {}\n\n", self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
+        if let Some(freshness) = &self.freshness {
+            md.push_str(&format!(
+                "**Data As Of**: {} (replica `{}`, at most {:.1}s behind)\n\n",
+                freshness.as_of.format("%Y-%m-%d %H:%M:%S UTC"),
+                freshness.source,
+                freshness.max_staleness_ms as f64 / 1000.0
+            ));
+        }
         
         md.push_str("---\n\n");
         md.push_str("## Executive Summary\n\n");
@@ -156,6 +180,7 @@ mod tests {
             high_risk_actions: 10,
             compliance_score: 92,
             findings: vec![],
+            freshness: None,
         }
     }
 
@@ -200,4 +225,20 @@ mod tests {
         let md = report.export(ReportFormat::Markdown);
         assert!(md.contains("Test finding"));
     }
+
+    #[test]
+    fn test_freshness_in_report() {
+        let mut report = create_test_report();
+        assert!(!report.export(ReportFormat::Json).contains("freshness"));
+
+        report.freshness = Some(DataFreshness {
+            source: "/var/lib/agentkern/audit.jsonl".to_string(),
+            as_of: Utc::now(),
+            last_record_at: None,
+            max_staleness_ms: 2_500,
+        });
+        let md = report.export(ReportFormat::Markdown);
+        assert!(md.contains("**Data As Of**"));
+        assert!(md.contains("at most 2.5s behind"));
+    }
 }
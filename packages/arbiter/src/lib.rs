@@ -39,6 +39,7 @@
 pub mod locks;
 pub mod queue;
 pub mod coordinator;
+pub mod pipeline;
 pub mod types;
 
 // Hyper-Stack modules (per ARCHITECTURE.md)
@@ -47,7 +48,9 @@ pub mod thread_per_core;   // Thread-per-Core for minimal latency
 
 // ISO 42001 Compliance (per GLOBAL_GAPS.md §3)
 pub mod audit;             // Audit Ledger for compliance traceability
+pub mod audit_replica;     // Journal-following read replicas for reports and export
 pub mod iso42001;          // ISO 42001 AIMS automated reporting
+pub mod siem;              // Splunk HEC, Elastic bulk and syslog CEF export
 
 // EXECUTION_MANDATE.md modules
 pub mod killswitch;        // Kill Switch for agent termination (Section 6)
@@ -60,6 +63,8 @@ pub mod loop_prevention;   // Runaway Loop Prevention ($47k incident)
 
 // Phase 2: Human-in-the-Loop Escalation
 pub mod escalation;        // Escalation triggers, webhooks, approval workflow
+#[cfg(feature = "gate")]
+pub mod quorum;            // Trust-weighted multi-engine verification
 
 // Phase 3: Security Hardening & Compliance
 pub mod eu_ai_act;         // EU AI Act (Aug 2025) compliance export
@@ -73,22 +78,31 @@ pub mod cost;              // Cost attribution dashboard
 pub use locks::LockManager;
 pub use queue::PriorityQueue;
 pub use coordinator::Coordinator;
+pub use pipeline::{ActionVerifier, CoordinationPipeline, PipelineConfig, PipelineError, PipelineMetrics};
 pub use types::{BusinessLock, CoordinationRequest, CoordinationResult, LockType};
 pub use raft::{RaftLockManager, RaftConfig, RaftState};
 pub use thread_per_core::{ThreadPerCoreRuntime, ThreadPerCoreConfig};
-pub use audit::{AuditLedger, AuditRecord, AuditOutcome, AuditStatistics};
+pub use audit::{AuditLedger, AuditLedgerState, AuditRecord, AuditOutcome, AuditStatistics, EdgeIngestReport};
+pub use audit_replica::{AuditReplica, ReplicaError};
+pub use siem::{ExportReport, FieldMapping, SiemError, SiemEvent, SiemExporter, SiemFormat, SiemTransport, SyslogUdp};
 pub use killswitch::{KillSwitch, KillReason, KillRecord, TerminationType};
-pub use carbon::{CarbonScheduler, CarbonIntensity, CarbonRegion};
+pub use carbon::{CarbonScheduler, CarbonIntensity, CarbonRegion, CarbonForecastSource, GreenWindow, BatchPlacement};
 pub use antifragile::{
     AntifragileEngine, Failure, FailureClass, RecoveryStrategy, CircuitBreaker, CircuitState,
     FailureSeverity, FailureCategory, AdaptationRate, RecoveryStrategyType,
 };
-pub use chaos::{ChaosMonkey, ChaosConfig, ChaosError, ChaosResult, ChaosStats};
+pub use chaos::{
+    ChaosMonkey, ChaosConfig, ChaosError, ChaosResult, ChaosStats, FrameFault, FrameInjection, RobustnessCriteria,
+    RobustnessReport,
+};
 pub use loop_prevention::{LoopPreventer, LoopPreventionConfig, TrackedMessage, LoopPreventionError};
 pub use escalation::{
     EscalationTrigger, TriggerType, TriggerConfig, TriggerResult, EscalationLevel,
-    WebhookNotifier, WebhookConfig, ApprovalWorkflow, ApprovalRequest, ApprovalStatus,
+    WebhookNotifier, WebhookConfig, WebhookPayload, ApprovalWorkflow, ApprovalRequest, ApprovalStatus,
+    EscalationRouter, RoutingRules, RoutingDecision,
 };
+#[cfg(feature = "gate")]
+pub use quorum::{QuorumDecision, QuorumResult, QuorumVerifier, Vote, VoterTier};
 pub use eu_ai_act::{
     EuAiActExporter, TechnicalDocumentation, ComplianceReport, RiskLevel, OverallStatus,
 };
@@ -97,5 +111,5 @@ pub use cost::{
 };
 pub use iso42001::{
     ComplianceLedger, AuditEvent, HumanOversight, AuditOutcome as Iso42001Outcome,
-    AuditReport, ReportFormat, ReportGenerator,
+    AuditReport, DataFreshness, ReportFormat, ReportGenerator,
 };
@@ -39,6 +39,20 @@ impl LockManager {
         self
     }
 
+    /// Copy of the held locks.
+    pub async fn export_state(&self) -> Vec<BusinessLock> {
+        self.locks.read().await.values().cloned().collect()
+    }
+
+    /// Replace the held locks. Expired locks are dropped.
+    pub async fn import_state(&self, locks: Vec<BusinessLock>) {
+        *self.locks.write().await = locks
+            .into_iter()
+            .filter(|lock| !lock.is_expired())
+            .map(|lock| (lock.resource.clone(), lock))
+            .collect();
+    }
+
     /// Try to acquire a lock on a resource.
     pub async fn acquire(
         &self,
@@ -124,6 +138,12 @@ impl LockManager {
         locks.get(resource).filter(|l| !l.is_expired()).cloned()
     }
 
+    /// Release every lock (shutdown). Returns the locks that were still live.
+    pub async fn release_all(&self) -> Vec<BusinessLock> {
+        let mut locks = self.locks.write().await;
+        locks.drain().map(|(_, lock)| lock).filter(|lock| !lock.is_expired()).collect()
+    }
+
     /// Clean up expired locks.
     pub async fn cleanup_expired(&self) -> usize {
         let mut locks = self.locks.write().await;
@@ -156,6 +176,26 @@ pub enum LockError {
     },
 }
 
+impl agentkern_errors::Coded for LockError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::ResourceLocked { .. } => ErrorCode::LockContention,
+            Self::NotOwner { .. } => ErrorCode::PermissionDenied,
+            Self::NotFound { .. } => ErrorCode::NotFound,
+        }
+    }
+
+    fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::ResourceLocked { remaining_seconds, .. } => {
+                Some(std::time::Duration::from_secs((*remaining_seconds).max(0) as u64))
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +224,10 @@ mod tests {
         // Second agent tries to acquire (same priority)
         let result = manager.acquire("agent-2", "resource-1", 0, LockType::Write, None).await;
         assert!(result.is_err());
+
+        let error = agentkern_errors::AgentKernError::from(result.unwrap_err());
+        assert_eq!(error.code, agentkern_errors::ErrorCode::LockContention);
+        assert!(error.retryable);
     }
 
     #[tokio::test]
@@ -198,6 +242,16 @@ mod tests {
         assert_eq!(lock.locked_by, "agent-2");
     }
 
+    #[tokio::test]
+    async fn test_release_all() {
+        let manager = LockManager::new();
+        manager.acquire("agent-1", "resource-1", 0, LockType::Write, None).await.unwrap();
+        manager.acquire("agent-2", "resource-2", 0, LockType::Write, None).await.unwrap();
+
+        assert_eq!(manager.release_all().await.len(), 2);
+        assert!(manager.get_status("resource-1").await.is_none());
+    }
+
     #[tokio::test]
     async fn test_wrong_owner_release() {
         let manager = LockManager::new();
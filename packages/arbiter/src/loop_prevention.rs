@@ -325,6 +325,18 @@ pub enum LoopPreventionError {
     CircuitOpen,
 }
 
+impl agentkern_errors::Coded for LoopPreventionError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::HopLimitExceeded { .. } | Self::LoopDetected { .. } => ErrorCode::LoopDetected,
+            Self::CostCeilingExceeded { .. } => ErrorCode::BudgetExceeded,
+            Self::PairRateLimitExceeded { .. } => ErrorCode::RateLimited,
+            Self::CircuitOpen => ErrorCode::Unavailable,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,417 @@
+//! AgentKern-Arbiter: Admission Pipeline
+//!
+//! Bounded, priority-ordered hand-off between callers, Gate verification and
+//! the [`Coordinator`]. Requests wait in a queue of fixed capacity and are
+//! served highest priority first by a fixed number of workers, each of which
+//! verifies the request and then coordinates it.
+//!
+//! When the queue is full a new request either sheds the lowest-priority
+//! waiting request (if it outranks it) or is rejected. Both outcomes carry a
+//! retry-after estimated from the current depth and service time. Queue
+//! depth, wait and service latency are exported as metrics so SLOs can be
+//! enforced.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let pipeline = CoordinationPipeline::spawn(
+//!     Arc::new(Coordinator::new()),
+//!     Arc::new(GateEngine::new()), // `gate` feature
+//!     PipelineConfig::default().with_capacity(512).with_workers(8),
+//! );
+//! match pipeline.submit(CoordinationRequest::new("agent-1", "db:orders")).await {
+//!     Ok(result) => assert!(result.granted),
+//!     Err(e) => sleep(e.retry_after().unwrap_or_default()).await,
+//! }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use agentkern_errors::{Coded, ErrorCode};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
+
+use crate::coordinator::Coordinator;
+use crate::types::{CoordinationRequest, CoordinationResult};
+
+/// Shortest retry-after handed to callers.
+const MIN_RETRY_AFTER: Duration = Duration::from_millis(10);
+
+/// Weight of the newest sample in the latency averages.
+const EWMA_WEIGHT: f64 = 0.2;
+
+/// Checks a coordination request before locks are taken (normally the Gate).
+pub trait ActionVerifier: Send + Sync + 'static {
+    /// `Err` carries the reason the request was denied.
+    fn verify(&self, request: &CoordinationRequest) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+#[cfg(feature = "gate")]
+impl ActionVerifier for agentkern_gate::GateEngine {
+    /// Verifies the `coordinate` action with the resource and lock type as context.
+    async fn verify(&self, request: &CoordinationRequest) -> Result<(), String> {
+        let mut builder = agentkern_gate::engine::VerificationRequestBuilder::new(&request.agent_id, "coordinate")
+            .context("resource", request.resource.as_str())
+            .context("operation", format!("{:?}", request.operation).to_lowercase())
+            .context("priority", request.priority);
+        if let Some(trace) = &request.trace {
+            builder = builder.trace(trace.clone());
+        }
+        let result = agentkern_gate::GateEngine::verify(self, builder.build()).await;
+        if result.allowed {
+            Ok(())
+        } else {
+            Err(result.reasoning)
+        }
+    }
+}
+
+/// Pipeline sizing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    /// Requests that may wait at once
+    pub capacity: usize,
+    /// Requests verified and coordinated concurrently
+    pub workers: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self { capacity: 1024, workers: 4 }
+    }
+}
+
+impl PipelineConfig {
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+}
+
+/// Snapshot of pipeline health.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineMetrics {
+    /// Requests waiting now
+    pub depth: usize,
+    pub capacity: usize,
+    /// Requests accepted into the queue
+    pub admitted: u64,
+    /// Requests served by a worker
+    pub completed: u64,
+    /// Requests denied by the verifier
+    pub denied: u64,
+    /// Waiting requests evicted by higher-priority arrivals
+    pub shed: u64,
+    /// Requests refused because the queue was full
+    pub rejected: u64,
+    /// Moving average of time spent queued (microseconds)
+    pub avg_wait_us: u64,
+    /// Moving average of verify + coordinate time (microseconds)
+    pub avg_service_us: u64,
+}
+
+impl PipelineMetrics {
+    /// Metrics in Prometheus text format.
+    pub fn prometheus(&self) -> String {
+        format!(
+            r#"# HELP agentkern_arbiter_queue_depth Requests waiting for coordination
+# TYPE agentkern_arbiter_queue_depth gauge
+agentkern_arbiter_queue_depth {}
+agentkern_arbiter_queue_capacity {}
+
+# HELP agentkern_arbiter_requests_total Coordination requests by outcome
+# TYPE agentkern_arbiter_requests_total counter
+agentkern_arbiter_requests_total{{outcome="admitted"}} {}
+agentkern_arbiter_requests_total{{outcome="completed"}} {}
+agentkern_arbiter_requests_total{{outcome="denied"}} {}
+agentkern_arbiter_requests_total{{outcome="shed"}} {}
+agentkern_arbiter_requests_total{{outcome="rejected"}} {}
+
+# HELP agentkern_arbiter_latency_us Average latency in microseconds
+# TYPE agentkern_arbiter_latency_us gauge
+agentkern_arbiter_latency_us{{stage="wait"}} {}
+agentkern_arbiter_latency_us{{stage="service"}} {}
+"#,
+            self.depth,
+            self.capacity,
+            self.admitted,
+            self.completed,
+            self.denied,
+            self.shed,
+            self.rejected,
+            self.avg_wait_us,
+            self.avg_service_us,
+        )
+    }
+}
+
+/// Why a request did not reach the coordinator.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PipelineError {
+    #[error("Pipeline overloaded, retry after {retry_after:?}")]
+    Overloaded { retry_after: Duration },
+
+    #[error("Shed for higher-priority work, retry after {retry_after:?}")]
+    Shed { retry_after: Duration },
+
+    #[error("Pipeline closed")]
+    Closed,
+}
+
+impl Coded for PipelineError {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::Unavailable
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Overloaded { retry_after } | Self::Shed { retry_after } => Some(*retry_after),
+            Self::Closed => None,
+        }
+    }
+}
+
+type Reply = oneshot::Sender<Result<CoordinationResult, PipelineError>>;
+
+struct Waiting {
+    request: CoordinationRequest,
+    enqueued_at: Instant,
+    reply: Reply,
+}
+
+/// Highest priority first, then arrival order.
+type QueueKey = (Reverse<i32>, u64);
+
+struct Inner<V> {
+    coordinator: Arc<Coordinator>,
+    verifier: Arc<V>,
+    config: PipelineConfig,
+    queue: Mutex<BTreeMap<QueueKey, Waiting>>,
+    next_seq: AtomicU64,
+    ready: Notify,
+    closed: AtomicBool,
+    stats: Mutex<PipelineMetrics>,
+}
+
+impl<V: ActionVerifier> Inner<V> {
+    fn retry_after(&self, depth: usize) -> Duration {
+        let avg_service_us = self.stats.lock().avg_service_us;
+        let estimate = avg_service_us.saturating_mul(depth as u64 + 1) / self.config.workers.max(1) as u64;
+        Duration::from_micros(estimate).max(MIN_RETRY_AFTER)
+    }
+
+    async fn work(self: Arc<Self>) {
+        loop {
+            // Registered before checking, so a close in between still wakes us
+            let notified = self.ready.notified();
+            let next = self.queue.lock().pop_first();
+            let Some((_, waiting)) = next else {
+                if self.closed.load(Ordering::Acquire) {
+                    return;
+                }
+                notified.await;
+                continue;
+            };
+
+            let wait = waiting.enqueued_at.elapsed();
+            let started = Instant::now();
+            let (result, denied) = match self.verifier.verify(&waiting.request).await {
+                Ok(()) => (self.coordinator.request(waiting.request).await, false),
+                Err(reason) => (CoordinationResult::denied(reason), true),
+            };
+            let service = started.elapsed();
+
+            {
+                let mut stats = self.stats.lock();
+                stats.completed += 1;
+                stats.denied += denied as u64;
+                stats.avg_wait_us = ewma(stats.avg_wait_us, wait, stats.completed);
+                stats.avg_service_us = ewma(stats.avg_service_us, service, stats.completed);
+            }
+            let _ = waiting.reply.send(Ok(result));
+        }
+    }
+}
+
+fn ewma(average_us: u64, sample: Duration, count: u64) -> u64 {
+    let sample = sample.as_micros() as f64;
+    if count <= 1 {
+        return sample as u64;
+    }
+    (average_us as f64 * (1.0 - EWMA_WEIGHT) + sample * EWMA_WEIGHT) as u64
+}
+
+/// Bounded, backpressured path from callers through verification to the coordinator.
+pub struct CoordinationPipeline<V: ActionVerifier> {
+    inner: Arc<Inner<V>>,
+}
+
+impl<V: ActionVerifier> CoordinationPipeline<V> {
+    /// Start the pipeline's workers on the current Tokio runtime.
+    pub fn spawn(coordinator: Arc<Coordinator>, verifier: Arc<V>, config: PipelineConfig) -> Self {
+        let workers = config.workers.max(1);
+        let inner = Arc::new(Inner {
+            coordinator,
+            verifier,
+            stats: Mutex::new(PipelineMetrics { capacity: config.capacity, ..Default::default() }),
+            config,
+            queue: Mutex::new(BTreeMap::new()),
+            next_seq: AtomicU64::new(0),
+            ready: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+        for _ in 0..workers {
+            tokio::spawn(Arc::clone(&inner).work());
+        }
+        Self { inner }
+    }
+
+    /// Queue a request and wait for it to be verified and coordinated.
+    ///
+    /// Fails fast with a retry-after when the queue is full and the request
+    /// does not outrank anything waiting; a waiting request that gets shed
+    /// fails the same way.
+    pub async fn submit(&self, request: CoordinationRequest) -> Result<CoordinationResult, PipelineError> {
+        let inner = &self.inner;
+        if inner.closed.load(Ordering::Acquire) {
+            return Err(PipelineError::Closed);
+        }
+
+        let (reply, response) = oneshot::channel();
+        {
+            let mut queue = inner.queue.lock();
+            if queue.len() >= inner.config.capacity {
+                let retry_after = inner.retry_after(queue.len());
+                let lowest = queue.last_key_value().map(|(_, w)| w.request.priority);
+                if lowest.is_some_and(|lowest| lowest < request.priority) {
+                    if let Some((_, shed)) = queue.pop_last() {
+                        let _ = shed.reply.send(Err(PipelineError::Shed { retry_after }));
+                    }
+                    inner.stats.lock().shed += 1;
+                } else {
+                    inner.stats.lock().rejected += 1;
+                    return Err(PipelineError::Overloaded { retry_after });
+                }
+            }
+            let seq = inner.next_seq.fetch_add(1, Ordering::Relaxed);
+            queue.insert(
+                (Reverse(request.priority), seq),
+                Waiting { request, enqueued_at: Instant::now(), reply },
+            );
+            inner.stats.lock().admitted += 1;
+        }
+        inner.ready.notify_one();
+
+        response.await.unwrap_or(Err(PipelineError::Closed))
+    }
+
+    /// Current depth, outcome counters and latencies.
+    pub fn metrics(&self) -> PipelineMetrics {
+        let depth = self.inner.queue.lock().len();
+        PipelineMetrics { depth, ..self.inner.stats.lock().clone() }
+    }
+}
+
+impl<V: ActionVerifier> Drop for CoordinationPipeline<V> {
+    /// Stop workers once the queue drains; waiting requests are still served.
+    fn drop(&mut self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.ready.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Semaphore;
+
+    /// Holds every request until a permit is released.
+    struct Gated(Semaphore);
+
+    impl ActionVerifier for Gated {
+        async fn verify(&self, request: &CoordinationRequest) -> Result<(), String> {
+            self.0.acquire().await.unwrap().forget();
+            if request.agent_id == "blocked" {
+                Err("denied by policy".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn request(agent: &str, priority: i32) -> CoordinationRequest {
+        CoordinationRequest::new(agent, format!("resource-{agent}")).with_priority(priority)
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_sheds_lowest_priority() {
+        let verifier = Arc::new(Gated(Semaphore::new(0)));
+        let config = PipelineConfig::default().with_capacity(2).with_workers(1);
+        let pipeline = Arc::new(CoordinationPipeline::spawn(Arc::new(Coordinator::new()), Arc::clone(&verifier), config));
+
+        // The worker takes the first request and blocks in verify
+        let busy = tokio::spawn({
+            let pipeline = Arc::clone(&pipeline);
+            async move { pipeline.submit(request("busy", 0)).await }
+        });
+        while pipeline.metrics().admitted < 1 || pipeline.metrics().depth > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let low = tokio::spawn({
+            let pipeline = Arc::clone(&pipeline);
+            async move { pipeline.submit(request("low", 1)).await }
+        });
+        let mid = tokio::spawn({
+            let pipeline = Arc::clone(&pipeline);
+            async move { pipeline.submit(request("mid", 5)).await }
+        });
+        while pipeline.metrics().depth < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // Full: an equal-priority arrival is rejected, a higher one sheds `low`
+        let rejected = pipeline.submit(request("late", 1)).await.unwrap_err();
+        assert!(matches!(rejected, PipelineError::Overloaded { .. }));
+        assert!(rejected.retry_after().unwrap() >= MIN_RETRY_AFTER);
+
+        let high = tokio::spawn({
+            let pipeline = Arc::clone(&pipeline);
+            async move { pipeline.submit(request("high", 9)).await }
+        });
+        assert!(matches!(low.await.unwrap(), Err(PipelineError::Shed { .. })));
+
+        verifier.0.add_permits(3);
+        assert!(busy.await.unwrap().unwrap().granted);
+        assert!(high.await.unwrap().unwrap().granted);
+        assert!(mid.await.unwrap().unwrap().granted);
+
+        let metrics = pipeline.metrics();
+        assert_eq!((metrics.admitted, metrics.completed, metrics.shed, metrics.rejected), (4, 3, 1, 1));
+        assert!(metrics.prometheus().contains("agentkern_arbiter_requests_total{outcome=\"shed\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_denied_requests_skip_coordination() {
+        let coordinator = Arc::new(Coordinator::new());
+        let verifier = Arc::new(Gated(Semaphore::new(10)));
+        let pipeline = CoordinationPipeline::spawn(Arc::clone(&coordinator), verifier, PipelineConfig::default());
+
+        let result = pipeline.submit(request("blocked", 0)).await.unwrap();
+        assert!(!result.granted);
+        assert_eq!(result.reason.as_deref(), Some("denied by policy"));
+        assert!(coordinator.get_lock_status("resource-blocked").await.is_none());
+        assert_eq!(pipeline.metrics().denied, 1);
+    }
+}
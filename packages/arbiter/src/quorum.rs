@@ -0,0 +1,372 @@
+//! AgentKern-Arbiter: Quorum Verification
+//!
+//! High-risk actions can be verified by several independent Gate engines
+//! (different policy bundles or neural models) instead of one. Each engine
+//! votes, votes are weighted by the voter's trust tier, and the weighted
+//! share of allow votes decides the outcome. When the weighted minority is
+//! larger than the disagreement threshold, the decision goes to a human:
+//! an approval request is opened and the action is held.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_arbiter::quorum::{QuorumVerifier, VoterTier};
+//!
+//! let quorum = QuorumVerifier::new()
+//!     .with_voter("baseline", baseline_engine, VoterTier::Verified)
+//!     .with_voter("strict", strict_engine, VoterTier::Trusted)
+//!     .with_voter("neural-v2", neural_engine, VoterTier::Unknown)
+//!     .with_escalation(approvals.clone());
+//!
+//! let result = quorum.verify(request).await;
+//! if let QuorumDecision::Escalate { approval_id } = &result.decision {
+//!     // hold the action until a human decides
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use agentkern_gate::{GateEngine, VerificationRequest};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+use crate::escalation::{ApprovalWorkflow, EscalationLevel, TriggerResult, TriggerType, WebhookNotifier};
+
+/// Trigger type of quorum escalations.
+pub const QUORUM_TRIGGER: &str = "quorum_disagreement";
+
+/// How far a voter's verdict is trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoterTier {
+    /// Observed only; its vote carries no weight
+    Untrusted,
+    /// New or unproven bundle/model
+    Unknown,
+    /// Established
+    Trusted,
+    /// Established and audited
+    Verified,
+    /// Reference evaluator
+    Elite,
+}
+
+impl VoterTier {
+    /// Default vote weight of the tier.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Self::Untrusted => 0.0,
+            Self::Unknown => 0.5,
+            Self::Trusted => 1.0,
+            Self::Verified => 1.5,
+            Self::Elite => 2.0,
+        }
+    }
+}
+
+/// One evaluator in the quorum.
+struct Voter {
+    name: String,
+    tier: VoterTier,
+    engine: Arc<GateEngine>,
+}
+
+/// A voter's verdict.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vote {
+    pub voter: String,
+    pub tier: VoterTier,
+    pub weight: f64,
+    pub allowed: bool,
+    pub risk_score: u8,
+    pub reasoning: String,
+}
+
+/// What the quorum decided.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum QuorumDecision {
+    Allow,
+    Deny,
+    /// Voters disagreed too much; held for human approval
+    Escalate {
+        /// Approval request, if an approval workflow is configured
+        approval_id: Option<String>,
+    },
+}
+
+/// Outcome of a quorum verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumResult {
+    pub decision: QuorumDecision,
+    /// Weighted share of allow votes, 0.0..=1.0
+    pub allow_share: f64,
+    /// Weighted share of the minority side, 0.0..=0.5
+    pub disagreement: f64,
+    /// Weighted mean of the voters' final risk scores
+    pub risk_score: u8,
+    pub votes: Vec<Vote>,
+}
+
+impl QuorumResult {
+    /// Whether the action may proceed without a human.
+    pub fn allowed(&self) -> bool {
+        self.decision == QuorumDecision::Allow
+    }
+}
+
+/// Verifies a request with several weighted Gate engines.
+pub struct QuorumVerifier {
+    voters: Vec<Voter>,
+    weights: HashMap<VoterTier, f64>,
+    /// Weighted allow share needed to allow
+    approval_share: f64,
+    /// Minority share above which the decision is escalated
+    disagreement_threshold: f64,
+    escalation_level: EscalationLevel,
+    approvals: Option<Arc<ApprovalWorkflow>>,
+    webhooks: Option<Arc<WebhookNotifier>>,
+}
+
+impl QuorumVerifier {
+    /// An empty quorum: two-thirds to allow, escalate above 20% dissent.
+    pub fn new() -> Self {
+        Self {
+            voters: Vec::new(),
+            weights: HashMap::new(),
+            approval_share: 2.0 / 3.0,
+            disagreement_threshold: 0.2,
+            escalation_level: EscalationLevel::High,
+            approvals: None,
+            webhooks: None,
+        }
+    }
+
+    /// Add a voter.
+    pub fn with_voter(mut self, name: impl Into<String>, engine: Arc<GateEngine>, tier: VoterTier) -> Self {
+        self.voters.push(Voter { name: name.into(), tier, engine });
+        self
+    }
+
+    /// Override the vote weight of a tier.
+    pub fn with_tier_weight(mut self, tier: VoterTier, weight: f64) -> Self {
+        self.weights.insert(tier, weight.max(0.0));
+        self
+    }
+
+    /// Set the weighted allow share needed to allow (clamped to 0.0..=1.0).
+    pub fn with_approval_share(mut self, share: f64) -> Self {
+        self.approval_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the minority share above which decisions are escalated.
+    pub fn with_disagreement_threshold(mut self, threshold: f64) -> Self {
+        self.disagreement_threshold = threshold.clamp(0.0, 0.5);
+        self
+    }
+
+    /// Open approval requests for escalated decisions.
+    pub fn with_escalation(mut self, approvals: Arc<ApprovalWorkflow>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    /// Also notify webhooks of escalated decisions.
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookNotifier>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Set the level of escalated approval requests (default High).
+    pub fn with_escalation_level(mut self, level: EscalationLevel) -> Self {
+        self.escalation_level = level;
+        self
+    }
+
+    /// Number of voters.
+    pub fn len(&self) -> usize {
+        self.voters.len()
+    }
+
+    /// Whether the quorum has no voters.
+    pub fn is_empty(&self) -> bool {
+        self.voters.is_empty()
+    }
+
+    fn weight(&self, tier: VoterTier) -> f64 {
+        self.weights.get(&tier).copied().unwrap_or_else(|| tier.weight())
+    }
+
+    /// Have every voter verify the request and combine the votes.
+    ///
+    /// Fails closed: with no weighted votes the request is denied.
+    pub async fn verify(&self, request: VerificationRequest) -> QuorumResult {
+        let mut pending = JoinSet::new();
+        for (index, voter) in self.voters.iter().enumerate() {
+            let engine = Arc::clone(&voter.engine);
+            let request = request.clone();
+            pending.spawn(async move { (index, engine.verify(request).await) });
+        }
+
+        let mut votes: Vec<Option<Vote>> = vec![None; self.voters.len()];
+        while let Some(joined) = pending.join_next().await {
+            let Ok((index, result)) = joined else {
+                continue;
+            };
+            let voter = &self.voters[index];
+            votes[index] = Some(Vote {
+                voter: voter.name.clone(),
+                tier: voter.tier,
+                weight: self.weight(voter.tier),
+                allowed: result.allowed,
+                risk_score: result.final_risk_score,
+                reasoning: result.reasoning,
+            });
+        }
+        let votes: Vec<Vote> = votes.into_iter().flatten().collect();
+
+        let total: f64 = votes.iter().map(|v| v.weight).sum();
+        let allow: f64 = votes.iter().filter(|v| v.allowed).map(|v| v.weight).sum();
+        let (allow_share, disagreement, risk_score) = if total > 0.0 {
+            let share = allow / total;
+            let risk = votes.iter().map(|v| v.weight * v.risk_score as f64).sum::<f64>() / total;
+            (share, share.min(1.0 - share), risk.round() as u8)
+        } else {
+            (0.0, 0.0, 100)
+        };
+
+        let decision = if total <= 0.0 {
+            QuorumDecision::Deny
+        } else if disagreement > self.disagreement_threshold {
+            QuorumDecision::Escalate { approval_id: None }
+        } else if allow_share >= self.approval_share {
+            QuorumDecision::Allow
+        } else {
+            QuorumDecision::Deny
+        };
+
+        let mut result = QuorumResult { decision, allow_share, disagreement, risk_score, votes };
+        if matches!(result.decision, QuorumDecision::Escalate { .. }) {
+            let approval_id = self.escalate(&request, &result);
+            result.decision = QuorumDecision::Escalate { approval_id };
+        }
+        result
+    }
+
+    /// Raise a quorum disagreement for human review.
+    fn escalate(&self, request: &VerificationRequest, result: &QuorumResult) -> Option<String> {
+        tracing::warn!(
+            agent_id = %request.agent_id,
+            action = %request.action,
+            disagreement = result.disagreement,
+            "Quorum disagreement escalated"
+        );
+        let trigger = TriggerResult {
+            triggered: true,
+            level: self.escalation_level,
+            trigger_type: TriggerType::Custom(QUORUM_TRIGGER.to_string()),
+            agent_id: request.agent_id.clone(),
+            reason: format!(
+                "Quorum disagreement {:.0}% on '{}' exceeds {:.0}%",
+                result.disagreement * 100.0,
+                request.action,
+                self.disagreement_threshold * 100.0
+            ),
+            context: HashMap::from([
+                ("request_id".to_string(), serde_json::json!(request.request_id)),
+                ("allow_share".to_string(), serde_json::json!(result.allow_share)),
+                ("votes".to_string(), serde_json::to_value(&result.votes).unwrap_or_default()),
+            ]),
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        };
+
+        if let Some(webhooks) = &self.webhooks {
+            for failed in webhooks.notify(&trigger).into_iter().filter_map(Result::err) {
+                tracing::error!(error = %failed, "Quorum escalation webhook failed");
+            }
+        }
+        let approvals = self.approvals.as_ref()?;
+        let params = serde_json::to_value(&request.context).unwrap_or_default();
+        Some(approvals.request_approval(&trigger, &request.action, params).id)
+    }
+}
+
+impl Default for QuorumVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escalation::ApprovalStatus;
+    use agentkern_gate::engine::VerificationRequestBuilder;
+    use agentkern_gate::policy::{Policy, PolicyAction, PolicyRule};
+
+    async fn denying(action: &str) -> Arc<GateEngine> {
+        let engine = GateEngine::new();
+        engine
+            .register_policy(Policy {
+                id: "deny".to_string(),
+                name: "Deny".to_string(),
+                description: String::new(),
+                priority: 0,
+                enabled: true,
+                jurisdictions: vec![],
+                rules: vec![PolicyRule {
+                    id: "r".to_string(),
+                    condition: format!("action == '{action}'"),
+                    action: PolicyAction::Deny,
+                    message: None,
+                    risk_score: None,
+                }],
+            })
+            .await;
+        Arc::new(engine)
+    }
+
+    #[tokio::test]
+    async fn test_weighted_votes() {
+        let quorum = QuorumVerifier::new()
+            .with_voter("a", Arc::new(GateEngine::new()), VoterTier::Elite)
+            .with_voter("b", Arc::new(GateEngine::new()), VoterTier::Verified)
+            .with_voter("c", denying("wire_funds").await, VoterTier::Untrusted);
+
+        // The dissenting voter carries no weight
+        let result = quorum.verify(VerificationRequestBuilder::new("agent-1", "wire_funds").build()).await;
+        assert_eq!(result.decision, QuorumDecision::Allow);
+        assert_eq!(result.votes.len(), 3);
+        assert_eq!(result.disagreement, 0.0);
+
+        // No voters: fail closed
+        let empty = QuorumVerifier::new().verify(VerificationRequestBuilder::new("agent-1", "read").build()).await;
+        assert_eq!(empty.decision, QuorumDecision::Deny);
+    }
+
+    #[tokio::test]
+    async fn test_disagreement_escalates() {
+        let approvals = Arc::new(ApprovalWorkflow::new());
+        let quorum = QuorumVerifier::new()
+            .with_voter("baseline", Arc::new(GateEngine::new()), VoterTier::Trusted)
+            .with_voter("strict", denying("wire_funds").await, VoterTier::Trusted)
+            .with_escalation(approvals.clone());
+
+        let result = quorum.verify(VerificationRequestBuilder::new("agent-1", "wire_funds").build()).await;
+        assert!(!result.allowed());
+        assert_eq!(result.disagreement, 0.5);
+        let QuorumDecision::Escalate { approval_id: Some(id) } = result.decision else {
+            panic!("expected escalation");
+        };
+        let request = approvals.get_request(&id).unwrap();
+        assert_eq!(request.status, ApprovalStatus::Pending);
+        assert_eq!(request.action, "wire_funds");
+
+        // Unanimous: no escalation
+        let result = quorum.verify(VerificationRequestBuilder::new("agent-1", "read").build()).await;
+        assert_eq!(result.decision, QuorumDecision::Allow);
+        assert_eq!(approvals.pending_requests().len(), 1);
+    }
+}
@@ -4,7 +4,9 @@
 //! - **Arbiter (Traffic)**: Raft Consensus for "Atomic Business Locks"
 //! - Used ONLY for strong consistency operations (e.g., spending money)
 //!
-//! This module implements Raft-based distributed locking.
+//! This module implements Raft-based distributed locking: log replication
+//! through [`AppendEntries`] with majority commit. Leader election is out of
+//! scope; a node is made leader with [`RaftLockManager::become_leader`].
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,7 +17,7 @@ use parking_lot::RwLock;
 pub type NodeId = u64;
 
 /// Raft log entry for lock operations.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum LockCommand {
     Acquire {
         resource: String,
@@ -34,13 +36,33 @@ pub enum LockCommand {
 }
 
 /// Raft log entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LogEntry {
     pub term: u64,
     pub index: u64,
     pub command: LockCommand,
 }
 
+/// AppendEntries request from the leader (empty `entries` is a heartbeat).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppendEntries {
+    pub term: u64,
+    pub leader_id: NodeId,
+    pub prev_log_index: u64,
+    pub prev_log_term: u64,
+    pub entries: Vec<LogEntry>,
+    pub leader_commit: u64,
+}
+
+/// Follower's reply to [`AppendEntries`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AppendResponse {
+    pub term: u64,
+    pub success: bool,
+    /// Highest index known to match the leader's log
+    pub match_index: u64,
+}
+
 /// Raft node state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RaftState {
@@ -90,19 +112,24 @@ impl LockStateMachine {
 
     /// Apply a command to the state machine.
     pub fn apply(&mut self, command: &LockCommand) -> Result<bool, &'static str> {
+        self.apply_at(command, chrono::Utc::now())
+    }
+
+    /// Apply a command as of `now` (deterministic replay and simulation).
+    pub fn apply_at(&mut self, command: &LockCommand, now: chrono::DateTime<chrono::Utc>) -> Result<bool, &'static str> {
         match command {
             LockCommand::Acquire { resource, agent_id, priority, ttl_ms } => {
                 // Check if lock exists and is still valid
                 if let Some(existing) = self.locks.get(resource) {
-                    if existing.expires_at > chrono::Utc::now() {
+                    if existing.expires_at > now {
                         // Lock exists - check priority for preemption
                         if *priority > existing.priority {
                             // Preempt lower priority lock
                             self.locks.insert(resource.clone(), LockEntry {
                                 agent_id: agent_id.clone(),
                                 priority: *priority,
-                                acquired_at: chrono::Utc::now(),
-                                expires_at: chrono::Utc::now() + chrono::Duration::milliseconds(*ttl_ms as i64),
+                                acquired_at: now,
+                                expires_at: now + chrono::Duration::milliseconds(*ttl_ms as i64),
                             });
                             return Ok(true);
                         }
@@ -114,8 +141,8 @@ impl LockStateMachine {
                 self.locks.insert(resource.clone(), LockEntry {
                     agent_id: agent_id.clone(),
                     priority: *priority,
-                    acquired_at: chrono::Utc::now(),
-                    expires_at: chrono::Utc::now() + chrono::Duration::milliseconds(*ttl_ms as i64),
+                    acquired_at: now,
+                    expires_at: now + chrono::Duration::milliseconds(*ttl_ms as i64),
                 });
                 Ok(true)
             }
@@ -132,7 +159,7 @@ impl LockStateMachine {
             LockCommand::Heartbeat { resource, agent_id } => {
                 if let Some(existing) = self.locks.get_mut(resource) {
                     if existing.agent_id == *agent_id {
-                        existing.expires_at = chrono::Utc::now() + chrono::Duration::seconds(30);
+                        existing.expires_at = now + chrono::Duration::seconds(30);
                         return Ok(true);
                     }
                 }
@@ -143,7 +170,12 @@ impl LockStateMachine {
 
     /// Get lock status for a resource.
     pub fn get_lock(&self, resource: &str) -> Option<&LockEntry> {
-        self.locks.get(resource).filter(|e| e.expires_at > chrono::Utc::now())
+        self.get_lock_at(resource, chrono::Utc::now())
+    }
+
+    /// Get lock status for a resource as of `now`.
+    pub fn get_lock_at(&self, resource: &str, now: chrono::DateTime<chrono::Utc>) -> Option<&LockEntry> {
+        self.locks.get(resource).filter(|e| e.expires_at > now)
     }
 
     /// Clean up expired locks.
@@ -163,6 +195,10 @@ pub struct RaftLockManager {
     state_machine: Arc<RwLock<LockStateMachine>>,
     commit_index: u64,
     last_applied: u64,
+    /// Leader: next entry to send to each peer
+    next_index: HashMap<NodeId, u64>,
+    /// Leader: highest entry known replicated on each peer
+    match_index: HashMap<NodeId, u64>,
 }
 
 impl RaftLockManager {
@@ -177,6 +213,37 @@ impl RaftLockManager {
             state_machine: Arc::new(RwLock::new(LockStateMachine::new())),
             commit_index: 0,
             last_applied: 0,
+            next_index: HashMap::new(),
+            match_index: HashMap::new(),
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.config.node_id
+    }
+
+    pub fn current_term(&self) -> u64 {
+        self.current_term
+    }
+
+    pub fn commit_index(&self) -> u64 {
+        self.commit_index
+    }
+
+    /// The replicated log.
+    pub fn log(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    fn last_log_index(&self) -> u64 {
+        self.log.len() as u64
+    }
+
+    /// Term of the entry at `index` (0 before the first entry).
+    fn term_at(&self, index: u64) -> Option<u64> {
+        match index {
+            0 => Some(0),
+            i => self.log.get((i - 1) as usize).map(|e| e.term),
         }
     }
 
@@ -230,10 +297,98 @@ impl RaftLockManager {
         }
     }
 
-    /// Become leader (for single-node or after election).
+    /// Become leader in a new term (for single-node or after election).
     pub fn become_leader(&mut self) {
+        self.current_term += 1;
+        self.voted_for = Some(self.config.node_id);
         self.state = RaftState::Leader;
-        tracing::info!(node_id = self.config.node_id, "Became Raft leader");
+        let next = self.last_log_index() + 1;
+        self.next_index = self.config.peers.iter().map(|&p| (p, next)).collect();
+        self.match_index = self.config.peers.iter().map(|&p| (p, 0)).collect();
+        tracing::info!(node_id = self.config.node_id, term = self.current_term, "Became Raft leader");
+    }
+
+    /// Leader: AppendEntries for `peer`, carrying every entry it may lack.
+    pub fn append_request(&self, peer: NodeId) -> Option<AppendEntries> {
+        if !self.is_leader() {
+            return None;
+        }
+        let next = *self.next_index.get(&peer)?;
+        let prev_log_index = next - 1;
+        Some(AppendEntries {
+            term: self.current_term,
+            leader_id: self.config.node_id,
+            prev_log_index,
+            prev_log_term: self.term_at(prev_log_index).unwrap_or(0),
+            entries: self.log[prev_log_index as usize..].to_vec(),
+            leader_commit: self.commit_index,
+        })
+    }
+
+    /// Follower: accept entries that extend a matching log prefix.
+    pub fn handle_append_entries(&mut self, request: AppendEntries) -> AppendResponse {
+        if request.term < self.current_term {
+            return AppendResponse { term: self.current_term, success: false, match_index: 0 };
+        }
+        if request.term > self.current_term || self.state != RaftState::Follower {
+            self.current_term = request.term;
+            self.voted_for = None;
+            self.state = RaftState::Follower;
+        }
+        if self.term_at(request.prev_log_index) != Some(request.prev_log_term) {
+            return AppendResponse { term: self.current_term, success: false, match_index: 0 };
+        }
+
+        // Truncate only on conflict, so a stale or reordered request never drops entries
+        let match_index = request.prev_log_index + request.entries.len() as u64;
+        for entry in request.entries {
+            match self.term_at(entry.index) {
+                Some(term) if term == entry.term => continue,
+                Some(_) => self.log.truncate((entry.index - 1) as usize),
+                None => {}
+            }
+            self.log.push(entry);
+        }
+        if request.leader_commit > self.commit_index {
+            self.commit_index = self.commit_index.max(request.leader_commit.min(match_index));
+            self.apply_committed();
+        }
+        AppendResponse { term: self.current_term, success: true, match_index }
+    }
+
+    /// Leader: record a follower's reply and commit entries stored on a majority.
+    pub fn handle_append_response(&mut self, peer: NodeId, response: AppendResponse) {
+        if response.term > self.current_term {
+            self.current_term = response.term;
+            self.state = RaftState::Follower;
+            return;
+        }
+        if !self.is_leader() || response.term < self.current_term {
+            return;
+        }
+        if !response.success {
+            // Step back and retry with an earlier prefix
+            if let Some(next) = self.next_index.get_mut(&peer) {
+                *next = (*next - 1).max(1);
+            }
+            return;
+        }
+        let matched = self.match_index.entry(peer).or_insert(0);
+        *matched = (*matched).max(response.match_index);
+        let matched = *matched;
+        self.next_index.insert(peer, matched + 1);
+
+        // Highest index replicated on a majority, from this leader's term
+        let cluster = self.config.peers.len() + 1;
+        let majority = cluster / 2 + 1;
+        for index in (self.commit_index + 1..=self.last_log_index()).rev() {
+            let replicas = 1 + self.match_index.values().filter(|&&m| m >= index).count();
+            if replicas >= majority && self.term_at(index) == Some(self.current_term) {
+                self.commit_index = index;
+                self.apply_committed();
+                break;
+            }
+        }
     }
 
     /// Acquire a lock through Raft consensus.
@@ -338,4 +493,29 @@ mod tests {
         let lock = sm.read().get_lock("db:accounts").cloned();
         assert!(lock.is_some());
     }
+
+    #[test]
+    fn test_log_replication_majority_commit() {
+        let config = |node_id, peers| RaftConfig { node_id, peers, ..RaftConfig::default() };
+        let mut leader = RaftLockManager::new(config(1, vec![2, 3]));
+        let mut follower = RaftLockManager::new(config(2, vec![1, 3]));
+        leader.become_leader();
+
+        let index = leader.acquire_lock("db:accounts", "agent-1", 5, 30000).unwrap();
+        assert_eq!(leader.commit_index(), 0);
+
+        // One follower plus the leader is a majority of three
+        let request = leader.append_request(2).unwrap();
+        let response = follower.handle_append_entries(request);
+        assert!(response.success);
+        leader.handle_append_response(2, response);
+        assert_eq!(leader.commit_index(), index);
+
+        // The next heartbeat carries the commit index to the follower
+        let heartbeat = leader.append_request(2).unwrap();
+        assert!(heartbeat.entries.is_empty());
+        follower.handle_append_entries(heartbeat);
+        assert_eq!(follower.commit_index(), index);
+        assert!(follower.state_machine().read().get_lock("db:accounts").is_some());
+    }
 }
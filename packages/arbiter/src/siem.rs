@@ -0,0 +1,631 @@
+//! AgentKern-Arbiter: SIEM Export
+//!
+//! Ships audit records, compliance events and kill-switch events to a SIEM:
+//! Splunk HTTP Event Collector, the Elasticsearch bulk API, or RFC 5424
+//! syslog carrying CEF. Events are normalized to [`SiemEvent`], renamed
+//! and filtered by a [`FieldMapping`], encoded in batches and handed to a
+//! [`SiemTransport`]. Batches that fail with a retryable error are retried
+//! with exponential backoff. Bulk exports of audit records should read an
+//! [`AuditReplica`] ([`SiemExporter::export_replica`]) rather than the live
+//! ledger.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_arbiter::siem::{SiemEvent, SiemExporter, SiemFormat};
+//!
+//! let exporter = SiemExporter::new(SiemFormat::splunk_hec("agentkern"), hec_transport)
+//!     .with_batch_size(200)
+//!     .with_min_severity(5);
+//!
+//! let mut events: Vec<SiemEvent> = ledger.query_by_time_range(since, now).await.iter().map(SiemEvent::from).collect();
+//! events.extend(killswitch.get_history().await.iter().map(SiemEvent::from));
+//! let report = exporter.export(&events).await;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::Duration;
+
+use agentkern_errors::{Coded, ErrorCode};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::audit::{AuditOutcome, AuditRecord};
+use crate::audit_replica::{AuditReplica, ReplicaError};
+use crate::iso42001::{AuditEvent, AuditOutcome as ComplianceOutcome, DataFreshness};
+use crate::killswitch::{KillReason, KillRecord, TerminationType};
+
+/// CEF device vendor and product.
+const CEF_VENDOR: &str = "AgentKern";
+const CEF_PRODUCT: &str = "Arbiter";
+
+/// CEF extension keys for the standard event fields.
+const CEF_KEYS: [(&str, &str); 6] = [
+    ("id", "externalId"),
+    ("timestamp", "rt"),
+    ("agent_id", "suser"),
+    ("action", "act"),
+    ("outcome", "outcome"),
+    ("message", "msg"),
+];
+
+/// SIEM export errors.
+#[derive(Debug, Error)]
+pub enum SiemError {
+    #[error("SIEM endpoint unreachable: {0}")]
+    Unreachable(String),
+
+    #[error("SIEM endpoint rejected batch: HTTP {status}: {body}")]
+    Rejected { status: u16, body: String },
+}
+
+impl SiemError {
+    /// Whether sending the batch again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Unreachable(_) => true,
+            Self::Rejected { status, .. } => *status == 429 || *status >= 500,
+        }
+    }
+}
+
+impl Coded for SiemError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Unreachable(_) => ErrorCode::Unavailable,
+            Self::Rejected { status: 429, .. } => ErrorCode::RateLimited,
+            Self::Rejected { status, .. } if *status >= 500 => ErrorCode::Unavailable,
+            Self::Rejected { .. } => ErrorCode::InvalidArgument,
+        }
+    }
+}
+
+/// An event in SIEM-neutral form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiemEvent {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Event class: `audit`, `compliance` or `killswitch`
+    pub kind: String,
+    pub agent_id: String,
+    pub action: String,
+    pub outcome: String,
+    /// CEF scale: 0 (informational) to 10 (very high)
+    pub severity: u8,
+    pub message: String,
+    /// Source-specific fields
+    pub fields: Map<String, Value>,
+}
+
+impl From<&AuditRecord> for SiemEvent {
+    fn from(record: &AuditRecord) -> Self {
+        let risk = record.risk_score.min(100) / 10;
+        let (outcome, severity) = match record.outcome {
+            AuditOutcome::Allowed => ("allowed", risk),
+            AuditOutcome::Denied => ("denied", risk.max(7)),
+            AuditOutcome::Review => ("review", risk.max(5)),
+            AuditOutcome::Logged => ("logged", risk),
+        };
+        let mut fields = Map::new();
+        fields.insert("policy_id".into(), record.policy_id.clone().into());
+        fields.insert("policy_version".into(), record.policy_version.clone().into());
+        if let Some(model) = &record.model_version {
+            fields.insert("model_version".into(), model.clone().into());
+        }
+        fields.insert("risk_score".into(), record.risk_score.into());
+        fields.insert("region".into(), record.region.clone().into());
+        fields.insert("latency_us".into(), record.latency_us.into());
+        Self {
+            id: record.id.to_string(),
+            timestamp: record.timestamp,
+            kind: "audit".to_string(),
+            agent_id: record.agent_id.clone(),
+            action: record.action.clone(),
+            outcome: outcome.to_string(),
+            severity,
+            message: record.reasoning.clone(),
+            fields,
+        }
+    }
+}
+
+impl From<&AuditEvent> for SiemEvent {
+    fn from(event: &AuditEvent) -> Self {
+        let risk = event.risk_score.min(100) / 10;
+        let (outcome, severity) = match event.outcome {
+            ComplianceOutcome::Allowed => ("allowed", risk),
+            ComplianceOutcome::Denied => ("denied", risk.max(7)),
+            ComplianceOutcome::Escalated => ("escalated", risk.max(5)),
+            ComplianceOutcome::AuditOnly => ("logged", risk),
+        };
+        let mut fields: Map<String, Value> =
+            event.context.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect();
+        if let Some(policy) = &event.policy_id {
+            fields.insert("policy_id".into(), policy.clone().into());
+        }
+        if let Some(model) = &event.model_version {
+            fields.insert("model_version".into(), model.clone().into());
+        }
+        fields.insert("risk_score".into(), event.risk_score.into());
+        fields.insert("human_oversight".into(), format!("{:?}", event.human_oversight).to_lowercase().into());
+        Self {
+            id: event.id.clone(),
+            timestamp: event.timestamp,
+            kind: "compliance".to_string(),
+            agent_id: event.agent_id.clone(),
+            action: event.action.clone(),
+            outcome: outcome.to_string(),
+            severity,
+            message: String::new(),
+            fields,
+        }
+    }
+}
+
+impl From<&KillRecord> for SiemEvent {
+    fn from(record: &KillRecord) -> Self {
+        let severity = match (&record.reason, record.termination_type) {
+            (KillReason::EmergencyShutdown, _) => 10,
+            (_, TerminationType::Forced | TerminationType::HardwareKill) => 9,
+            _ => 8,
+        };
+        let reason = match &record.reason {
+            KillReason::Custom(reason) => reason.clone(),
+            reason => format!("{reason:?}"),
+        };
+        let mut fields = Map::new();
+        fields.insert("target_type".into(), format!("{:?}", record.target_type).to_lowercase().into());
+        fields.insert("termination_type".into(), format!("{:?}", record.termination_type).to_lowercase().into());
+        if let Some(operator) = &record.initiated_by {
+            fields.insert("initiated_by".into(), operator.clone().into());
+        }
+        if let Some(error) = &record.error {
+            fields.insert("error".into(), error.clone().into());
+        }
+        Self {
+            id: record.id.to_string(),
+            timestamp: record.timestamp,
+            kind: "killswitch".to_string(),
+            agent_id: record.target_id.clone(),
+            action: "terminate".to_string(),
+            outcome: if record.success { "terminated" } else { "failed" }.to_string(),
+            severity,
+            message: reason,
+            fields,
+        }
+    }
+}
+
+/// Renames, drops and adds fields before encoding.
+///
+/// Names refer to the flattened event: the [`SiemEvent`] fields other than
+/// `fields`, plus the keys of `fields`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldMapping {
+    /// Output name per field, e.g. `agent_id` -> `user`
+    #[serde(default)]
+    pub rename: HashMap<String, String>,
+    /// Fields left out of the output
+    #[serde(default)]
+    pub exclude: HashSet<String>,
+    /// Fields added to every event, e.g. `environment`
+    #[serde(default)]
+    pub constants: Map<String, Value>,
+}
+
+impl FieldMapping {
+    /// Rename a field.
+    pub fn with_rename(mut self, field: impl Into<String>, to: impl Into<String>) -> Self {
+        self.rename.insert(field.into(), to.into());
+        self
+    }
+
+    /// Drop a field.
+    pub fn with_exclude(mut self, field: impl Into<String>) -> Self {
+        self.exclude.insert(field.into());
+        self
+    }
+
+    /// Add a field to every event.
+    pub fn with_constant(mut self, field: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.constants.insert(field.into(), value.into());
+        self
+    }
+
+    /// The event as a flat object, keyed by source name, mapped.
+    fn apply(&self, event: &SiemEvent, defaults: &[(&str, &str)]) -> Map<String, Value> {
+        let standard = [
+            ("id", Value::from(event.id.clone())),
+            ("timestamp", Value::from(event.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true))),
+            ("kind", Value::from(event.kind.clone())),
+            ("agent_id", Value::from(event.agent_id.clone())),
+            ("action", Value::from(event.action.clone())),
+            ("outcome", Value::from(event.outcome.clone())),
+            ("severity", Value::from(event.severity)),
+            ("message", Value::from(event.message.clone())),
+        ];
+        let mut out = Map::new();
+        let fields = standard.into_iter().map(|(k, v)| (k.to_string(), v)).chain(event.fields.clone());
+        for (field, value) in fields {
+            if self.exclude.contains(&field) {
+                continue;
+            }
+            let name = match self.rename.get(&field) {
+                Some(name) => name.clone(),
+                None => defaults.iter().find(|(from, _)| *from == field).map_or(field, |(_, to)| to.to_string()),
+            };
+            out.insert(name, value);
+        }
+        out.extend(self.constants.clone());
+        out
+    }
+}
+
+/// Wire format of the SIEM endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SiemFormat {
+    /// Splunk HTTP Event Collector (`/services/collector/event`)
+    SplunkHec {
+        index: Option<String>,
+        source: String,
+        sourcetype: String,
+        host: String,
+    },
+    /// Elasticsearch `_bulk` API
+    ElasticBulk { index: String },
+    /// RFC 5424 syslog with a CEF message, one event per line
+    SyslogCef {
+        hostname: String,
+        app_name: String,
+        /// Syslog facility (16-23 are local0-local7)
+        facility: u8,
+    },
+}
+
+impl SiemFormat {
+    /// Splunk HEC with AgentKern source defaults.
+    pub fn splunk_hec(index: impl Into<String>) -> Self {
+        Self::SplunkHec {
+            index: Some(index.into()),
+            source: "agentkern-arbiter".to_string(),
+            sourcetype: "agentkern:audit".to_string(),
+            host: hostname(),
+        }
+    }
+
+    /// Elasticsearch bulk indexing into `index`.
+    pub fn elastic(index: impl Into<String>) -> Self {
+        Self::ElasticBulk { index: index.into() }
+    }
+
+    /// Syslog CEF on facility local4.
+    pub fn syslog_cef() -> Self {
+        Self::SyslogCef { hostname: hostname(), app_name: "agentkern".to_string(), facility: 20 }
+    }
+
+    /// Content type of an encoded batch.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::SplunkHec { .. } => "application/json",
+            Self::ElasticBulk { .. } => "application/x-ndjson",
+            Self::SyslogCef { .. } => "text/plain",
+        }
+    }
+
+    /// Encode a batch of events.
+    pub fn encode(&self, events: &[SiemEvent], mapping: &FieldMapping) -> Vec<u8> {
+        let mut out = String::new();
+        for event in events {
+            match self {
+                Self::SplunkHec { index, source, sourcetype, host } => {
+                    let mut envelope = serde_json::json!({
+                        "time": event.timestamp.timestamp_millis() as f64 / 1000.0,
+                        "host": host,
+                        "source": source,
+                        "sourcetype": sourcetype,
+                        "event": mapping.apply(event, &[]),
+                    });
+                    if let Some(index) = index {
+                        envelope["index"] = index.clone().into();
+                    }
+                    out.push_str(&envelope.to_string());
+                }
+                Self::ElasticBulk { index } => {
+                    out.push_str(&serde_json::json!({ "index": { "_index": index, "_id": event.id } }).to_string());
+                    out.push('\n');
+                    out.push_str(&Value::Object(mapping.apply(event, &[("timestamp", "@timestamp")])).to_string());
+                }
+                Self::SyslogCef { hostname, app_name, facility } => {
+                    let priority = *facility as u16 * 8 + syslog_severity(event.severity) as u16;
+                    out.push_str(&format!(
+                        "<{priority}>1 {} {hostname} {app_name} - {} - {}",
+                        event.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                        event.kind,
+                        cef(event, mapping)
+                    ));
+                }
+            }
+            out.push('\n');
+        }
+        out.into_bytes()
+    }
+}
+
+/// The event as a CEF message.
+fn cef(event: &SiemEvent, mapping: &FieldMapping) -> String {
+    let header = |s: &str| s.replace('\\', "\\\\").replace('|', "\\|");
+    let extension = |s: &str| {
+        s.replace('\\', "\\\\").replace('=', "\\=").replace('\r', "\\r").replace('\n', "\\n")
+    };
+    let mut fields = mapping.apply(event, &CEF_KEYS);
+    if let Some(rt) = fields.get_mut("rt") {
+        *rt = event.timestamp.timestamp_millis().into();
+    }
+    let extensions: Vec<String> = fields
+        .iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "kind" | "severity"))
+        .map(|(k, v)| {
+            let value = match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            format!("{}={}", k, extension(&value))
+        })
+        .collect();
+    format!(
+        "CEF:0|{CEF_VENDOR}|{CEF_PRODUCT}|{}|{}|{}|{}|{}",
+        env!("CARGO_PKG_VERSION"),
+        header(&event.kind),
+        header(&event.action),
+        event.severity.min(10),
+        extensions.join(" ")
+    )
+}
+
+/// Map CEF severity (0-10) to a syslog severity (0-7).
+fn syslog_severity(severity: u8) -> u8 {
+    match severity {
+        9.. => 2,    // critical
+        7..=8 => 3,  // error
+        4..=6 => 4,  // warning
+        1..=3 => 5,  // notice
+        0 => 6,      // informational
+    }
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME").unwrap_or_else(|_| "agentkern".to_string())
+}
+
+/// Delivers encoded batches (HTTP client, syslog socket, ...).
+pub trait SiemTransport: Send + Sync + 'static {
+    fn send(&self, body: Vec<u8>, content_type: &'static str) -> impl Future<Output = Result<(), SiemError>> + Send;
+}
+
+/// Syslog over UDP, one datagram per event.
+pub struct SyslogUdp {
+    socket: tokio::net::UdpSocket,
+}
+
+impl SyslogUdp {
+    /// Connect to a syslog collector, e.g. `siem.internal:514`.
+    pub async fn connect(addr: &str) -> std::io::Result<Self> {
+        let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(addr).await?;
+        Ok(Self { socket })
+    }
+}
+
+impl SiemTransport for SyslogUdp {
+    async fn send(&self, body: Vec<u8>, _content_type: &'static str) -> Result<(), SiemError> {
+        for line in body.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+            self.socket.send(line).await.map_err(|e| SiemError::Unreachable(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of [`SiemExporter::export`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportReport {
+    /// Events delivered
+    pub exported: usize,
+    /// Events below the minimum severity
+    pub filtered: usize,
+    /// Events in batches that could not be delivered
+    pub failed: usize,
+    /// Batches sent, including failed ones
+    pub batches: usize,
+    /// Retries across all batches
+    pub retries: usize,
+    /// Error of the last failed batch
+    pub last_error: Option<String>,
+    /// Staleness of the source, when exported from a replica
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<DataFreshness>,
+}
+
+/// Batches, encodes and delivers events to a SIEM.
+pub struct SiemExporter<T> {
+    format: SiemFormat,
+    transport: T,
+    mapping: FieldMapping,
+    batch_size: usize,
+    max_attempts: u32,
+    backoff: Duration,
+    min_severity: u8,
+}
+
+impl<T: SiemTransport> SiemExporter<T> {
+    /// Batches of 500, three attempts starting at 200ms backoff.
+    pub fn new(format: SiemFormat, transport: T) -> Self {
+        Self {
+            format,
+            transport,
+            mapping: FieldMapping::default(),
+            batch_size: 500,
+            max_attempts: 3,
+            backoff: Duration::from_millis(200),
+            min_severity: 0,
+        }
+    }
+
+    /// Set the field mapping.
+    pub fn with_mapping(mut self, mapping: FieldMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Set the maximum events per batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set attempts per batch and the initial backoff (doubled per retry).
+    pub fn with_retries(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only export events of at least this severity (e.g. 7 for denials
+    /// and kills).
+    pub fn with_min_severity(mut self, severity: u8) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Export events. Failed batches are reported, not returned as errors,
+    /// so later batches still go out.
+    pub async fn export(&self, events: &[SiemEvent]) -> ExportReport {
+        let selected: Vec<SiemEvent> = events.iter().filter(|e| e.severity >= self.min_severity).cloned().collect();
+        let mut report = ExportReport { filtered: events.len() - selected.len(), ..Default::default() };
+
+        for batch in selected.chunks(self.batch_size) {
+            let body = self.format.encode(batch, &self.mapping);
+            report.batches += 1;
+            let mut backoff = self.backoff;
+            let mut attempt = 1;
+            loop {
+                match self.transport.send(body.clone(), self.format.content_type()).await {
+                    Ok(()) => {
+                        report.exported += batch.len();
+                        break;
+                    }
+                    Err(e) if e.is_retryable() && attempt < self.max_attempts => {
+                        tracing::debug!(attempt, error = %e, "SIEM batch failed, retrying");
+                        report.retries += 1;
+                        attempt += 1;
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                    Err(e) => {
+                        tracing::error!(events = batch.len(), error = %e, "SIEM batch dropped");
+                        report.failed += batch.len();
+                        report.last_error = Some(e.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    /// Export the audit records in a time range from a replica, refusing a
+    /// replica that is staler than its bound.
+    pub async fn export_replica(
+        &self,
+        replica: &AuditReplica,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ExportReport, ReplicaError> {
+        let freshness = replica.fresh().await?;
+        let events: Vec<SiemEvent> = replica.query_by_time_range(start, end).await.iter().map(SiemEvent::from).collect();
+        let mut report = self.export(&events).await;
+        report.freshness = Some(freshness);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Fails the first `failures` sends, then records bodies.
+    struct Recorder {
+        failures: Mutex<Vec<SiemError>>,
+        bodies: Mutex<Vec<String>>,
+    }
+
+    impl SiemTransport for Recorder {
+        async fn send(&self, body: Vec<u8>, _content_type: &'static str) -> Result<(), SiemError> {
+            if let Some(e) = self.failures.lock().unwrap().pop() {
+                return Err(e);
+            }
+            self.bodies.lock().unwrap().push(String::from_utf8(body).unwrap());
+            Ok(())
+        }
+    }
+
+    fn denial() -> SiemEvent {
+        let record = AuditRecord::new("agent-1", "transfer_funds", "spending-limits", 80, AuditOutcome::Denied)
+            .with_reasoning("limit=100 exceeded");
+        SiemEvent::from(&record)
+    }
+
+    #[test]
+    fn test_encodings() {
+        let mapping = FieldMapping::default().with_rename("region", "dc").with_exclude("latency_us").with_constant("env", "prod");
+
+        let hec = String::from_utf8(SiemFormat::splunk_hec("main").encode(&[denial()], &mapping)).unwrap();
+        let hec: Value = serde_json::from_str(hec.trim()).unwrap();
+        assert_eq!(hec["index"], "main");
+        assert_eq!(hec["event"]["outcome"], "denied");
+        assert_eq!(hec["event"]["dc"], "global");
+        assert_eq!(hec["event"]["env"], "prod");
+        assert!(hec["event"].get("latency_us").is_none());
+
+        let bulk = String::from_utf8(SiemFormat::elastic("audit").encode(&[denial()], &mapping)).unwrap();
+        let lines: Vec<Value> = bulk.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines[0]["index"]["_index"], "audit");
+        assert!(lines[1]["@timestamp"].is_string());
+
+        let syslog = String::from_utf8(SiemFormat::syslog_cef().encode(&[denial()], &mapping)).unwrap();
+        assert!(syslog.starts_with("<163>1 "), "{syslog}");
+        assert!(syslog.contains("|audit|transfer_funds|8|"));
+        assert!(syslog.contains("suser=agent-1"));
+        assert!(syslog.contains("msg=limit\\=100 exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_batches_and_retries() {
+        let transport = Recorder {
+            failures: Mutex::new(vec![
+                SiemError::Rejected { status: 400, body: "bad".into() },
+                SiemError::Unreachable("refused".into()),
+            ]),
+            bodies: Mutex::new(Vec::new()),
+        };
+        let exporter = SiemExporter::new(SiemFormat::elastic("audit"), transport)
+            .with_batch_size(2)
+            .with_retries(3, Duration::ZERO)
+            .with_min_severity(7);
+
+        let allowed = SiemEvent::from(&AuditRecord::new("agent-1", "read", "p", 10, AuditOutcome::Allowed));
+        let events = [denial(), denial(), allowed, denial()];
+        let report = exporter.export(&events).await;
+
+        // First batch retried once, then rejected outright; second delivered
+        assert_eq!(report.filtered, 1);
+        assert_eq!((report.batches, report.retries), (2, 1));
+        assert_eq!((report.exported, report.failed), (1, 2));
+        assert!(report.last_error.unwrap().contains("400"));
+        assert_eq!(exporter.transport.bodies.lock().unwrap().len(), 1);
+    }
+}
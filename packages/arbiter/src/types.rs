@@ -1,5 +1,6 @@
 //! AgentKern-Arbiter: Core Types
 
+use agentkern_telemetry::TraceContext;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -62,6 +63,9 @@ pub struct CoordinationRequest {
     pub priority: i32,
     /// Request timestamp
     pub requested_at: DateTime<Utc>,
+    /// Caller's trace context; coordination runs in a child span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
 }
 
 impl CoordinationRequest {
@@ -73,9 +77,16 @@ impl CoordinationRequest {
             expected_duration_ms: 30000, // 30 seconds default
             priority: 0,
             requested_at: Utc::now(),
+            trace: None,
         }
     }
 
+    /// Propagate the caller's trace context.
+    pub fn with_trace(mut self, trace: TraceContext) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
     pub fn with_operation(mut self, op: LockType) -> Self {
         self.operation = op;
         self
@@ -105,6 +116,9 @@ pub struct CoordinationResult {
     pub estimated_wait_ms: Option<u64>,
     /// Reason if denied
     pub reason: Option<String>,
+    /// Context of the coordination span, when the request was traced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
 }
 
 impl CoordinationResult {
@@ -115,6 +129,7 @@ impl CoordinationResult {
             queue_position: None,
             estimated_wait_ms: None,
             reason: None,
+            trace: None,
         }
     }
 
@@ -125,6 +140,19 @@ impl CoordinationResult {
             queue_position: Some(position),
             estimated_wait_ms: Some(estimated_wait_ms),
             reason: Some("Resource is locked, request queued".to_string()),
+            trace: None,
+        }
+    }
+
+    /// Not queued because the resource's wait queue is full; retry after the estimate.
+    pub fn busy(estimated_wait_ms: u64) -> Self {
+        Self {
+            granted: false,
+            lock: None,
+            queue_position: None,
+            estimated_wait_ms: Some(estimated_wait_ms),
+            reason: Some("Resource wait queue is full, retry later".to_string()),
+            trace: None,
         }
     }
 
@@ -135,6 +163,7 @@ impl CoordinationResult {
             queue_position: None,
             estimated_wait_ms: None,
             reason: Some(reason.into()),
+            trace: None,
         }
     }
 }
@@ -0,0 +1,217 @@
+//! Store-and-Forward Audit Buffer
+//!
+//! Evidence for decisions made on the device, kept until the cell has it:
+//! - Bounded ring buffer: when full, the oldest entry is dropped and counted
+//! - Uploads in sequence order; the cell acknowledges a high-water mark and
+//!   acknowledged entries are released
+//! - Entries are keyed by `(device_id, boot_id, seq)` so retransmissions after
+//!   a lost acknowledgement are deduplicated by the cell
+//! - Every upload carries the device clock at send time; the cell corrects
+//!   entry timestamps by the difference to its own clock
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut buffer = AuditBuffer::new("robot-7", boot_count, 512);
+//! buffer.record("robot-7", "actuator.open", PolicyAction::Deny, Some("act"), now_ms);
+//!
+//! // On reconnect
+//! let upload = buffer.upload_batch(64, now_ms);
+//! let ack = cell_ledger.ingest_edge(&upload, Utc::now()).await.ack;
+//! buffer.acknowledge(&ack);
+//! ```
+
+use crate::policy::PolicyAction;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "embedded")]
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+#[cfg(not(feature = "embedded"))]
+use std::collections::VecDeque;
+
+/// One decision, as recorded on the device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Sequence number within this boot (starts at 1)
+    pub seq: u64,
+    /// Agent that requested the action
+    pub agent_id: String,
+    /// Action evaluated
+    pub action: String,
+    /// Outcome
+    pub outcome: PolicyAction,
+    /// Rule that decided, if any
+    pub rule_id: Option<String>,
+    /// Device clock (Unix ms)
+    pub timestamp: u64,
+}
+
+/// Batch sent to the cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditUpload {
+    /// Device ID
+    pub device_id: String,
+    /// Boot counter; sequence numbers restart on every boot
+    pub boot_id: u32,
+    /// Device clock when the batch was built (Unix ms)
+    pub sent_at: u64,
+    /// Entries dropped on the device since the last acknowledgement
+    pub dropped: u64,
+    /// Entries, in sequence order
+    pub entries: Vec<AuditEntry>,
+}
+
+/// Cell acknowledgement: everything up to `up_to_seq` is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditAck {
+    /// Boot the acknowledgement refers to
+    pub boot_id: u32,
+    /// Highest sequence number stored
+    pub up_to_seq: u64,
+}
+
+/// Buffer counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuditCounters {
+    /// Entries recorded since boot
+    pub recorded: u64,
+    /// Entries dropped because the buffer was full
+    pub dropped: u64,
+    /// Entries acknowledged by the cell
+    pub uploaded: u64,
+    /// Entries currently held
+    pub buffered: usize,
+}
+
+/// Bounded on-device audit store.
+#[derive(Debug)]
+pub struct AuditBuffer {
+    device_id: String,
+    boot_id: u32,
+    capacity: usize,
+    entries: VecDeque<AuditEntry>,
+    next_seq: u64,
+    counters: AuditCounters,
+    /// Dropped since the last acknowledgement (reported in uploads)
+    unreported_drops: u64,
+}
+
+impl AuditBuffer {
+    /// Create a buffer holding at most `capacity` entries (minimum 1).
+    pub fn new(device_id: impl Into<String>, boot_id: u32, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            device_id: device_id.into(),
+            boot_id,
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            next_seq: 1,
+            counters: AuditCounters::default(),
+            unreported_drops: 0,
+        }
+    }
+
+    /// Record a decision. Drops the oldest entry if full. Returns the seq.
+    pub fn record(
+        &mut self,
+        agent_id: &str,
+        action: &str,
+        outcome: PolicyAction,
+        rule_id: Option<&str>,
+        timestamp: u64,
+    ) -> u64 {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.counters.dropped += 1;
+            self.unreported_drops += 1;
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.entries.push_back(AuditEntry {
+            seq,
+            agent_id: agent_id.into(),
+            action: action.into(),
+            outcome,
+            rule_id: rule_id.map(Into::into),
+            timestamp,
+        });
+        self.counters.recorded += 1;
+        seq
+    }
+
+    /// Counters.
+    pub fn counters(&self) -> AuditCounters {
+        AuditCounters {
+            buffered: self.entries.len(),
+            ..self.counters
+        }
+    }
+
+    /// Oldest `max` entries, to send. Entries stay buffered until acknowledged.
+    pub fn upload_batch(&self, max: usize, now: u64) -> AuditUpload {
+        AuditUpload {
+            device_id: self.device_id.clone(),
+            boot_id: self.boot_id,
+            sent_at: now,
+            dropped: self.unreported_drops,
+            entries: self.entries.iter().take(max).cloned().collect(),
+        }
+    }
+
+    /// Release acknowledged entries. Acks for another boot are ignored.
+    pub fn acknowledge(&mut self, ack: &AuditAck) {
+        if ack.boot_id != self.boot_id {
+            return;
+        }
+        let before = self.entries.len();
+        while self.entries.front().is_some_and(|e| e.seq <= ack.up_to_seq) {
+            self.entries.pop_front();
+        }
+        let released = before - self.entries.len();
+        if released > 0 {
+            self.counters.uploaded += released as u64;
+            self.unreported_drops = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut buffer = AuditBuffer::new("robot-7", 1, 3);
+        for t in 0..5 {
+            buffer.record("robot-7", "sensor.read", PolicyAction::Allow, None, t);
+        }
+        let counters = buffer.counters();
+        assert_eq!(counters.recorded, 5);
+        assert_eq!(counters.dropped, 2);
+        assert_eq!(counters.buffered, 3);
+
+        let upload = buffer.upload_batch(10, 100);
+        assert_eq!(upload.dropped, 2);
+        assert_eq!(upload.entries.iter().map(|e| e.seq).collect::<Vec<_>>(), [3, 4, 5]);
+    }
+
+    #[test]
+    fn test_acknowledge_releases_entries() {
+        let mut buffer = AuditBuffer::new("robot-7", 2, 8);
+        for t in 0..4 {
+            buffer.record("robot-7", "actuator.open", PolicyAction::Deny, Some("act"), t);
+        }
+        let upload = buffer.upload_batch(2, 50);
+        assert_eq!(upload.entries.len(), 2);
+
+        // Stale boot: ignored
+        buffer.acknowledge(&AuditAck { boot_id: 1, up_to_seq: 4 });
+        assert_eq!(buffer.counters().buffered, 4);
+
+        buffer.acknowledge(&AuditAck { boot_id: 2, up_to_seq: 2 });
+        let counters = buffer.counters();
+        assert_eq!(counters.uploaded, 2);
+        assert_eq!(counters.buffered, 2);
+        assert_eq!(buffer.upload_batch(10, 60).entries[0].seq, 3);
+    }
+}
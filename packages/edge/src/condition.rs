@@ -0,0 +1,191 @@
+//! Edge Condition Evaluator
+//!
+//! Allocation-free subset of gate's policy DSL for devices:
+//!
+//! ```text
+//! expression   := comparison (('&&' | '||') comparison)*   (not mixed)
+//! comparison   := value (('==' | '!=' | '>' | '<' | '>=' | '<=') value)?
+//! value        := 'action' | 'agent_id' | 'context.' key | string | integer | boolean
+//! ```
+//!
+//! Context values are borrowed ([`Value`]) and keys are flat (no nested
+//! paths). Integers only; anything that does not parse evaluates as
+//! non-matching, as in gate.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_edge::condition::{EvalContext, Value};
+//!
+//! let ctx = EvalContext::new("actuator.open", "robot-7", &[("force", Value::Int(120))]);
+//! assert!(evaluate("action == 'actuator.open' && context.force > 100", &ctx));
+//! ```
+
+use crate::policy::PolicyAction;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "embedded")]
+use alloc::string::String;
+
+const OPERATORS: [&str; 6] = ["==", "!=", ">=", "<=", ">", "<"];
+
+/// A borrowed context value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<'a> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Str(&'a str),
+}
+
+/// Evaluation context; nothing is copied.
+#[derive(Debug, Clone, Copy)]
+pub struct EvalContext<'a> {
+    pub action: &'a str,
+    pub agent_id: &'a str,
+    pub fields: &'a [(&'a str, Value<'a>)],
+}
+
+impl<'a> EvalContext<'a> {
+    /// Build a context.
+    pub fn new(action: &'a str, agent_id: &'a str, fields: &'a [(&'a str, Value<'a>)]) -> Self {
+        Self { action, agent_id, fields }
+    }
+}
+
+/// Evaluate a condition. Does not allocate.
+pub fn evaluate(condition: &str, ctx: &EvalContext) -> bool {
+    let condition = condition.trim();
+    if condition.is_empty() || (condition.contains("&&") && condition.contains("||")) {
+        return false;
+    }
+    if condition.contains("&&") {
+        return condition.split("&&").all(|part| evaluate_single(part, ctx));
+    }
+    condition.split("||").any(|part| evaluate_single(part, ctx))
+}
+
+fn evaluate_single(expr: &str, ctx: &EvalContext) -> bool {
+    let expr = expr.trim();
+    for op in OPERATORS {
+        if let Some(idx) = expr.find(op) {
+            let left = resolve(&expr[..idx], ctx);
+            let right = resolve(&expr[idx + op.len()..], ctx);
+            return match op {
+                "==" => left == right,
+                "!=" => left != right,
+                _ => match (left, right) {
+                    (Value::Int(a), Value::Int(b)) => compare(op, a.cmp(&b)),
+                    (Value::Str(a), Value::Str(b)) => compare(op, a.cmp(b)),
+                    _ => false,
+                },
+            };
+        }
+    }
+    match resolve(expr, ctx) {
+        Value::Null => false,
+        Value::Bool(b) => b,
+        Value::Int(n) => n != 0,
+        Value::Str(s) => !s.is_empty(),
+    }
+}
+
+fn compare(op: &str, ordering: core::cmp::Ordering) -> bool {
+    use core::cmp::Ordering::*;
+    matches!(
+        (op, ordering),
+        (">", Greater) | ("<", Less) | (">=", Greater | Equal) | ("<=", Less | Equal)
+    )
+}
+
+fn resolve<'a>(token: &'a str, ctx: &EvalContext<'a>) -> Value<'a> {
+    let token = token.trim();
+    match token {
+        "action" => return Value::Str(ctx.action),
+        "agent_id" => return Value::Str(ctx.agent_id),
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        "null" => return Value::Null,
+        _ => {}
+    }
+    if let Some(key) = token.strip_prefix("context.") {
+        return ctx
+            .fields
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+            .unwrap_or(Value::Null);
+    }
+    let quoted = token.len() >= 2
+        && ((token.starts_with('\'') && token.ends_with('\'')) || (token.starts_with('"') && token.ends_with('"')));
+    if quoted {
+        return Value::Str(&token[1..token.len() - 1]);
+    }
+    token.parse().map(Value::Int).unwrap_or(Value::Null)
+}
+
+/// Rule guarded by a condition expression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionRule {
+    /// Rule ID
+    pub id: String,
+    /// Condition (see module docs)
+    pub condition: String,
+    /// Action to take when the condition holds
+    pub action: PolicyAction,
+    /// Priority (lower = higher priority)
+    pub priority: u32,
+}
+
+/// Evaluate rules: the matching rule with the lowest priority wins
+/// (earliest on ties). `None` if no rule matches.
+pub fn evaluate_rules(rules: &[ConditionRule], ctx: &EvalContext) -> Option<PolicyAction> {
+    rules
+        .iter()
+        .filter(|rule| evaluate(&rule.condition, ctx))
+        .min_by_key(|rule| rule.priority)
+        .map(|rule| rule.action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[(&str, Value)] = &[("force", Value::Int(120)), ("zone", Value::Str("cold"))];
+
+    #[test]
+    fn test_conditions() {
+        let ctx = EvalContext::new("actuator.open", "robot-7", FIELDS);
+        assert!(evaluate("action == 'actuator.open'", &ctx));
+        assert!(evaluate("context.force > 100 && context.zone == \"cold\"", &ctx));
+        assert!(evaluate("context.force < 10 || agent_id == 'robot-7'", &ctx));
+        assert!(evaluate("context.zone", &ctx));
+        assert!(!evaluate("context.missing", &ctx));
+        assert!(!evaluate("context.force >= 121", &ctx));
+        // Mixed operators are rejected, as in gate's checker
+        assert!(!evaluate("context.force > 1 && true || false", &ctx));
+    }
+
+    #[test]
+    fn test_rule_priority() {
+        let rules = vec![
+            ConditionRule {
+                id: "default".into(),
+                condition: "true".into(),
+                action: PolicyAction::Allow,
+                priority: 100,
+            },
+            ConditionRule {
+                id: "force".into(),
+                condition: "context.force > 100".into(),
+                action: PolicyAction::Escalate,
+                priority: 1,
+            },
+        ];
+        let ctx = EvalContext::new("actuator.open", "robot-7", FIELDS);
+        assert_eq!(evaluate_rules(&rules, &ctx), Some(PolicyAction::Escalate));
+        let calm = EvalContext::new("actuator.open", "robot-7", &[("force", Value::Int(5))]);
+        assert_eq!(evaluate_rules(&rules, &calm), Some(PolicyAction::Allow));
+        assert_eq!(evaluate_rules(&rules[1..], &calm), None);
+    }
+}
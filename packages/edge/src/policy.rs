@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "embedded")]
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 
 /// Edge policy.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +58,17 @@ pub enum PolicyAction {
     Escalate,
 }
 
+/// Evaluate `action` against `rules`: the matching rule with the lowest
+/// priority wins (earliest on ties); no match allows.
+pub fn evaluate(rules: &[PolicyRule], action: &str) -> PolicyAction {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(action))
+        .min_by_key(|rule| rule.priority)
+        .map(|rule| rule.action)
+        .unwrap_or(PolicyAction::Allow)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,526 @@
+//! Offline Policy Sync
+//!
+//! Sync protocol between an edge device and its cell:
+//! - Signed bundles: policy rules plus state, signed with ed25519 by the
+//!   exporting side; imports verify the signer, signature and audience
+//!   before touching local state (tamper detection)
+//! - Policy authority: any trusted peer may upload decisions, but policy
+//!   rules are only taken from bundles signed by a policy authority key, so
+//!   a compromised device can't push rules to its cell
+//! - Delta sync: decisions made offline live in a [`DecisionLog`], a
+//!   grow-only CRDT keyed by `(origin, seq)`. Each side sends only what the
+//!   peer's version vector has not seen; merging is idempotent and
+//!   order-independent
+//! - Conflict resolution: policy rules are last-writer-wins by policy
+//!   version, so an older bundle never rolls policy back. Offline decisions
+//!   that the newer policy would decide differently are reported as
+//!   [`Conflict`]s
+//! - Replay protection: bundles from a signer key must have increasing
+//!   `issued_at`
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! // Device, on reconnect
+//! let outgoing = device.export_delta(&cell_clock, now)?;
+//! cell.import(&outgoing)?;
+//!
+//! // Cell publishes new policy
+//! cell.set_policies(rules);
+//! let report = device.import(&cell.export_delta(device.clock(), now)?)?;
+//! for conflict in &report.conflicts { /* flag for review */ }
+//! ```
+
+use crate::policy::{evaluate, PolicyAction, PolicyRule};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "embedded")]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+#[cfg(not(feature = "embedded"))]
+use std::collections::BTreeMap;
+
+/// Per-replica high-water marks (replica ID → highest seq seen).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionVector(BTreeMap<String, u64>);
+
+impl VersionVector {
+    /// Highest seq seen from `replica` (0 = none).
+    pub fn get(&self, replica: &str) -> u64 {
+        self.0.get(replica).copied().unwrap_or(0)
+    }
+
+    /// Record that `seq` from `replica` has been seen.
+    pub fn observe(&mut self, replica: &str, seq: u64) {
+        if seq > self.get(replica) {
+            self.0.insert(replica.into(), seq);
+        }
+    }
+
+    /// Has this vector seen everything `other` has?
+    pub fn dominates(&self, other: &VersionVector) -> bool {
+        other.0.iter().all(|(replica, seq)| self.get(replica) >= *seq)
+    }
+}
+
+/// A policy decision made on a device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Replica that made the decision
+    pub origin: String,
+    /// Sequence number at the origin (starts at 1)
+    pub seq: u64,
+    /// Action evaluated
+    pub action: String,
+    /// Outcome
+    pub outcome: PolicyAction,
+    /// Timestamp (Unix ms, device clock)
+    pub timestamp: u64,
+    /// Policy bundle version in force
+    pub policy_version: u64,
+}
+
+/// Grow-only log of decisions from every replica.
+#[derive(Debug, Clone)]
+pub struct DecisionLog {
+    replica: String,
+    next_seq: u64,
+    entries: BTreeMap<(String, u64), Decision>,
+    clock: VersionVector,
+}
+
+impl DecisionLog {
+    /// Create a log for `replica`.
+    pub fn new(replica: impl Into<String>) -> Self {
+        Self {
+            replica: replica.into(),
+            next_seq: 1,
+            entries: BTreeMap::new(),
+            clock: VersionVector::default(),
+        }
+    }
+
+    /// Record a local decision.
+    pub fn record(&mut self, action: &str, outcome: PolicyAction, timestamp: u64, policy_version: u64) -> &Decision {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.clock.observe(&self.replica, seq);
+        let key = (self.replica.clone(), seq);
+        self.entries.entry(key).or_insert(Decision {
+            origin: self.replica.clone(),
+            seq,
+            action: action.into(),
+            outcome,
+            timestamp,
+            policy_version,
+        })
+    }
+
+    /// What this log has seen.
+    pub fn clock(&self) -> &VersionVector {
+        &self.clock
+    }
+
+    /// Decisions `peer` has not seen.
+    pub fn delta_since(&self, peer: &VersionVector) -> Vec<Decision> {
+        self.entries
+            .values()
+            .filter(|d| d.seq > peer.get(&d.origin))
+            .cloned()
+            .collect()
+    }
+
+    /// Merge remote decisions. Returns the newly added ones.
+    pub fn merge(&mut self, decisions: Vec<Decision>) -> Vec<Decision> {
+        let mut added = Vec::new();
+        for decision in decisions {
+            let key = (decision.origin.clone(), decision.seq);
+            if self.entries.contains_key(&key) {
+                continue;
+            }
+            self.clock.observe(&decision.origin, decision.seq);
+            if decision.origin == self.replica && decision.seq >= self.next_seq {
+                // Our own history restored from a peer (e.g. after a reset)
+                self.next_seq = decision.seq + 1;
+            }
+            self.entries.insert(key, decision.clone());
+            added.push(decision);
+        }
+        added
+    }
+
+    /// All decisions, ordered by origin then seq.
+    pub fn decisions(&self) -> impl Iterator<Item = &Decision> {
+        self.entries.values()
+    }
+
+    /// Number of decisions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// No decisions?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Contents of a sync bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateBundle {
+    /// Exporting replica
+    pub issuer: String,
+    /// Intended recipient (`None` = any replica trusting the issuer)
+    pub audience: Option<String>,
+    /// Policy version of `policies`
+    pub policy_version: u64,
+    /// Policy rules at `policy_version`
+    pub policies: Vec<PolicyRule>,
+    /// Decisions the recipient has not seen
+    pub decisions: Vec<Decision>,
+    /// Issuer's version vector after this bundle
+    pub clock: VersionVector,
+    /// Export time (Unix ms, issuer clock)
+    pub issued_at: u64,
+}
+
+/// A bundle plus the issuer's signature over its exact bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedBundle {
+    /// JSON-encoded [`StateBundle`]
+    pub payload: String,
+    /// Issuer's ed25519 public key
+    pub signer: [u8; 32],
+    /// ed25519 signature over `payload`
+    pub signature: Vec<u8>,
+}
+
+impl SignedBundle {
+    /// Serialize and sign a bundle.
+    pub fn sign(bundle: &StateBundle, key: &SigningKey) -> Result<Self, SyncError> {
+        let payload = serde_json::to_string(bundle).map_err(|_| SyncError::Malformed)?;
+        let signature = key.sign(payload.as_bytes());
+        Ok(Self {
+            payload,
+            signer: key.verifying_key().to_bytes(),
+            signature: signature.to_bytes().to_vec(),
+        })
+    }
+
+    /// Check the signer is trusted and the signature covers the payload.
+    pub fn verify(&self, trusted: &[VerifyingKey]) -> Result<StateBundle, SyncError> {
+        let key = trusted
+            .iter()
+            .find(|k| k.as_bytes() == &self.signer)
+            .ok_or(SyncError::UntrustedSigner)?;
+        let signature = Signature::from_slice(&self.signature).map_err(|_| SyncError::BadSignature)?;
+        key.verify(self.payload.as_bytes(), &signature)
+            .map_err(|_| SyncError::BadSignature)?;
+        serde_json::from_str(&self.payload).map_err(|_| SyncError::Malformed)
+    }
+}
+
+/// Offline decision the current policy would decide differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The decision as made
+    pub decision: Decision,
+    /// What the current policy says
+    pub current: PolicyAction,
+}
+
+/// Result of importing a bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Decisions newly merged
+    pub merged: usize,
+    /// Policies replaced by a newer version
+    pub policies_updated: bool,
+    /// Merged or local decisions that disagree with the current policy
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Sync errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncError {
+    /// Signer is not a trusted key
+    UntrustedSigner,
+    /// Signature does not match the payload
+    BadSignature,
+    /// Payload could not be encoded or decoded
+    Malformed,
+    /// Bundle addressed to another replica
+    WrongAudience,
+    /// Bundle is not newer than the last one accepted from its signer
+    Replay { issuer: String, issued_at: u64 },
+}
+
+impl core::fmt::Display for SyncError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UntrustedSigner => write!(f, "Bundle signer is not trusted"),
+            Self::BadSignature => write!(f, "Bundle signature invalid (tampered?)"),
+            Self::Malformed => write!(f, "Malformed bundle"),
+            Self::WrongAudience => write!(f, "Bundle addressed to another replica"),
+            Self::Replay { issuer, issued_at } => {
+                write!(f, "Replayed bundle from {} (issued at {})", issuer, issued_at)
+            }
+        }
+    }
+}
+
+/// One replica's side of the sync protocol (device or cell).
+pub struct EdgeSync {
+    replica: String,
+    key: SigningKey,
+    trusted: Vec<VerifyingKey>,
+    authorities: Vec<VerifyingKey>,
+    policies: Vec<PolicyRule>,
+    policy_version: u64,
+    log: DecisionLog,
+    last_issued: BTreeMap<[u8; 32], u64>,
+}
+
+impl EdgeSync {
+    /// Create a replica that signs with `key` and accepts decisions from
+    /// `trusted`. Policy rules are only accepted from policy authorities.
+    pub fn new(replica: impl Into<String>, key: SigningKey, trusted: Vec<VerifyingKey>) -> Self {
+        let replica = replica.into();
+        Self {
+            log: DecisionLog::new(replica.clone()),
+            replica,
+            key,
+            trusted,
+            authorities: Vec::new(),
+            policies: Vec::new(),
+            policy_version: 0,
+            last_issued: BTreeMap::new(),
+        }
+    }
+
+    /// Accept policy rules (and decisions) from bundles signed by
+    /// `authorities`, e.g. the cell's key on a device.
+    pub fn with_policy_authorities(mut self, authorities: Vec<VerifyingKey>) -> Self {
+        self.authorities = authorities;
+        self
+    }
+
+    /// Replica ID.
+    pub fn replica(&self) -> &str {
+        &self.replica
+    }
+
+    /// Rules in force.
+    pub fn policies(&self) -> &[PolicyRule] {
+        &self.policies
+    }
+
+    /// Version of the rules in force.
+    pub fn policy_version(&self) -> u64 {
+        self.policy_version
+    }
+
+    /// Decision log.
+    pub fn log(&self) -> &DecisionLog {
+        &self.log
+    }
+
+    /// What this replica has seen (send to the peer to request a delta).
+    pub fn clock(&self) -> &VersionVector {
+        self.log.clock()
+    }
+
+    /// Publish new rules locally (cell side); bumps the policy version.
+    pub fn set_policies(&mut self, rules: Vec<PolicyRule>) {
+        self.policies = rules;
+        self.policy_version += 1;
+    }
+
+    /// Evaluate an action and record the decision.
+    pub fn decide(&mut self, action: &str, timestamp: u64) -> PolicyAction {
+        let outcome = evaluate(&self.policies, action);
+        self.log.record(action, outcome, timestamp, self.policy_version);
+        outcome
+    }
+
+    /// Export a signed bundle with everything `peer` has not seen.
+    pub fn export_delta(&self, peer: &VersionVector, now: u64) -> Result<SignedBundle, SyncError> {
+        self.export(None, peer, now)
+    }
+
+    /// Export a signed bundle for one recipient.
+    pub fn export_for(&self, audience: &str, peer: &VersionVector, now: u64) -> Result<SignedBundle, SyncError> {
+        self.export(Some(audience.into()), peer, now)
+    }
+
+    fn export(&self, audience: Option<String>, peer: &VersionVector, now: u64) -> Result<SignedBundle, SyncError> {
+        let bundle = StateBundle {
+            issuer: self.replica.clone(),
+            audience,
+            policy_version: self.policy_version,
+            policies: self.policies.clone(),
+            decisions: self.log.delta_since(peer),
+            clock: self.log.clock().clone(),
+            issued_at: now,
+        };
+        SignedBundle::sign(&bundle, &self.key)
+    }
+
+    /// Verify and apply a peer's bundle. Nothing changes if verification
+    /// fails. Policy rules in bundles not signed by a policy authority are
+    /// ignored.
+    pub fn import(&mut self, signed: &SignedBundle) -> Result<SyncReport, SyncError> {
+        let keys: Vec<VerifyingKey> = self.trusted.iter().chain(&self.authorities).copied().collect();
+        let bundle = signed.verify(&keys)?;
+        if bundle.audience.as_deref().is_some_and(|a| a != self.replica) {
+            return Err(SyncError::WrongAudience);
+        }
+        // Keyed by the verified signer; `issuer` is only self-declared
+        if self.last_issued.get(&signed.signer).is_some_and(|last| bundle.issued_at <= *last) {
+            return Err(SyncError::Replay { issuer: bundle.issuer, issued_at: bundle.issued_at });
+        }
+        self.last_issued.insert(signed.signer, bundle.issued_at);
+        let authoritative = self.authorities.iter().any(|k| k.as_bytes() == &signed.signer);
+
+        let mut report = SyncReport {
+            merged: self.log.merge(bundle.decisions).len(),
+            ..SyncReport::default()
+        };
+        if authoritative && bundle.policy_version > self.policy_version {
+            self.policies = bundle.policies;
+            self.policy_version = bundle.policy_version;
+            report.policies_updated = true;
+        }
+        report.conflicts = self
+            .log
+            .decisions()
+            .filter(|d| d.policy_version < self.policy_version)
+            .filter_map(|d| {
+                let current = evaluate(&self.policies, &d.action);
+                (current != d.outcome).then(|| Conflict { decision: d.clone(), current })
+            })
+            .collect();
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, pattern: &str, action: PolicyAction) -> PolicyRule {
+        PolicyRule { id: id.into(), pattern: pattern.into(), action, priority: 1 }
+    }
+
+    fn pair() -> (EdgeSync, EdgeSync) {
+        let cell_key = SigningKey::from_bytes(&[1; 32]);
+        let device_key = SigningKey::from_bytes(&[2; 32]);
+        let cell = EdgeSync::new("cell", cell_key.clone(), vec![device_key.verifying_key()]);
+        let device = EdgeSync::new("robot-7", device_key, Vec::new()).with_policy_authorities(vec![cell_key.verifying_key()]);
+        (cell, device)
+    }
+
+    #[test]
+    fn test_decision_log_merge_is_idempotent_and_commutative() {
+        let mut a = DecisionLog::new("a");
+        let mut b = DecisionLog::new("b");
+        a.record("move", PolicyAction::Allow, 1, 1);
+        a.record("lift", PolicyAction::Deny, 2, 1);
+        b.record("scan", PolicyAction::Allow, 3, 1);
+
+        let from_a = a.delta_since(b.clock());
+        let from_b = b.delta_since(a.clock());
+        assert_eq!(from_a.len(), 2);
+        assert_eq!(a.merge(from_b.clone()).len(), 1);
+        assert_eq!(b.merge(from_a.clone()).len(), 2);
+        assert!(a.merge(from_b).is_empty());
+        assert!(b.merge(from_a).is_empty());
+
+        assert_eq!(a.clock(), b.clock());
+        assert!(a.decisions().eq(b.decisions()));
+        assert!(a.delta_since(b.clock()).is_empty());
+    }
+
+    #[test]
+    fn test_policy_sync_and_offline_conflicts() {
+        let (mut cell, mut device) = pair();
+        cell.set_policies(vec![rule("all", "*", PolicyAction::Allow)]);
+        let report = device.import(&cell.export_for("robot-7", device.clock(), 100).unwrap()).unwrap();
+        assert!(report.policies_updated);
+        assert_eq!(device.policy_version(), 1);
+
+        // Offline: device keeps deciding under v1
+        assert_eq!(device.decide("actuator.open", 200), PolicyAction::Allow);
+        assert_eq!(device.decide("sensor.read", 201), PolicyAction::Allow);
+
+        // Cell tightens policy meanwhile
+        cell.set_policies(vec![
+            rule("act", "actuator.*", PolicyAction::Deny),
+            rule("all", "*", PolicyAction::Allow),
+        ]);
+
+        // Reconnect: device uploads its delta, then pulls policy
+        let up = cell.import(&device.export_delta(cell.clock(), 300).unwrap()).unwrap();
+        assert_eq!(up.merged, 2);
+        assert_eq!(up.conflicts.len(), 1);
+
+        let down = device.import(&cell.export_delta(device.clock(), 301).unwrap()).unwrap();
+        assert!(down.policies_updated);
+        assert_eq!(down.merged, 0);
+        assert_eq!(down.conflicts.len(), 1);
+        assert_eq!(down.conflicts[0].decision.action, "actuator.open");
+        assert_eq!(down.conflicts[0].current, PolicyAction::Deny);
+        assert_eq!(device.decide("actuator.open", 302), PolicyAction::Deny);
+    }
+
+    #[test]
+    fn test_tampered_untrusted_and_replayed_bundles_rejected() {
+        let (mut cell, mut device) = pair();
+        cell.set_policies(vec![rule("all", "*", PolicyAction::Deny)]);
+        let v1 = cell.export_delta(&VersionVector::default(), 1).unwrap();
+
+        let mut tampered = v1.clone();
+        tampered.payload = tampered.payload.replace("Deny", "Allow");
+        assert_eq!(device.import(&tampered).unwrap_err(), SyncError::BadSignature);
+
+        let rogue = EdgeSync::new("rogue", SigningKey::from_bytes(&[9; 32]), Vec::new());
+        let forged = rogue.export_delta(&VersionVector::default(), 1).unwrap();
+        assert_eq!(device.import(&forged).unwrap_err(), SyncError::UntrustedSigner);
+
+        let elsewhere = cell.export_for("robot-8", &VersionVector::default(), 1).unwrap();
+        assert_eq!(device.import(&elsewhere).unwrap_err(), SyncError::WrongAudience);
+        assert_eq!(device.policy_version(), 0);
+
+        cell.set_policies(vec![rule("all", "*", PolicyAction::Allow)]);
+        device.import(&cell.export_delta(device.clock(), 2).unwrap()).unwrap();
+        assert_eq!(
+            device.import(&v1).unwrap_err(),
+            SyncError::Replay { issuer: "cell".into(), issued_at: 1 }
+        );
+        assert_eq!(device.policies()[0].action, PolicyAction::Allow);
+    }
+
+    #[test]
+    fn test_devices_cannot_push_policy() {
+        let (mut cell, mut device) = pair();
+        cell.set_policies(vec![rule("all", "*", PolicyAction::Deny)]);
+
+        // A compromised device allows everything and claims the last version
+        let device_key = SigningKey::from_bytes(&[2; 32]);
+        device.decide("actuator.open", 1);
+        let bundle = StateBundle {
+            issuer: "cell".into(),
+            audience: None,
+            policy_version: u64::MAX,
+            policies: vec![rule("all", "*", PolicyAction::Allow)],
+            decisions: device.log().delta_since(cell.clock()),
+            clock: device.clock().clone(),
+            issued_at: 10,
+        };
+        let report = cell.import(&SignedBundle::sign(&bundle, &device_key).unwrap()).unwrap();
+        assert_eq!(report.merged, 1);
+        assert!(!report.policies_updated);
+        assert_eq!(cell.policy_version(), 1);
+        assert_eq!(cell.decide("actuator.open", 11), PolicyAction::Deny);
+    }
+}
@@ -0,0 +1,271 @@
+//! Error Code Catalog
+//!
+//! Stable codes shared by every module. A code's string form, category,
+//! HTTP status and gRPC code never change once published; new failure
+//! modes get new codes.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Broad class of an error, for routing and dashboards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    /// Identity, license and permission failures
+    Auth,
+    /// Blocked by a policy, limit or guard
+    Policy,
+    /// Treasury and payment failures
+    Funds,
+    /// Malformed or conflicting requests
+    Request,
+    /// Transient infrastructure failures
+    Infra,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::Policy => "policy",
+            Self::Funds => "funds",
+            Self::Request => "request",
+            Self::Infra => "infra",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Stable, machine-readable error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum ErrorCode {
+    // Auth
+    Unauthenticated,
+    PermissionDenied,
+    LicenseRequired,
+
+    // Policy
+    PolicyDenied,
+    PolicyInvalid,
+    RateLimited,
+    QuotaExceeded,
+    BudgetExceeded,
+    LoopDetected,
+
+    // Funds
+    InsufficientFunds,
+    InvalidAmount,
+    CurrencyMismatch,
+    AccountNotFound,
+    SpendingLimitExceeded,
+    TransferFailed,
+
+    // Request
+    InvalidArgument,
+    NotFound,
+    AlreadyExists,
+    InvalidState,
+    Unsupported,
+
+    // Infra
+    Unavailable,
+    Timeout,
+    LockContention,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Every code in the catalog.
+    pub const ALL: [ErrorCode; 24] = [
+        Self::Unauthenticated,
+        Self::PermissionDenied,
+        Self::LicenseRequired,
+        Self::PolicyDenied,
+        Self::PolicyInvalid,
+        Self::RateLimited,
+        Self::QuotaExceeded,
+        Self::BudgetExceeded,
+        Self::LoopDetected,
+        Self::InsufficientFunds,
+        Self::InvalidAmount,
+        Self::CurrencyMismatch,
+        Self::AccountNotFound,
+        Self::SpendingLimitExceeded,
+        Self::TransferFailed,
+        Self::InvalidArgument,
+        Self::NotFound,
+        Self::AlreadyExists,
+        Self::InvalidState,
+        Self::Unsupported,
+        Self::Unavailable,
+        Self::Timeout,
+        Self::LockContention,
+        Self::Internal,
+    ];
+
+    /// Wire form, e.g. `FUNDS_INSUFFICIENT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated => "AUTH_UNAUTHENTICATED",
+            Self::PermissionDenied => "AUTH_PERMISSION_DENIED",
+            Self::LicenseRequired => "AUTH_LICENSE_REQUIRED",
+            Self::PolicyDenied => "POLICY_DENIED",
+            Self::PolicyInvalid => "POLICY_INVALID",
+            Self::RateLimited => "POLICY_RATE_LIMITED",
+            Self::QuotaExceeded => "POLICY_QUOTA_EXCEEDED",
+            Self::BudgetExceeded => "POLICY_BUDGET_EXCEEDED",
+            Self::LoopDetected => "POLICY_LOOP_DETECTED",
+            Self::InsufficientFunds => "FUNDS_INSUFFICIENT",
+            Self::InvalidAmount => "FUNDS_INVALID_AMOUNT",
+            Self::CurrencyMismatch => "FUNDS_CURRENCY_MISMATCH",
+            Self::AccountNotFound => "FUNDS_ACCOUNT_NOT_FOUND",
+            Self::SpendingLimitExceeded => "FUNDS_LIMIT_EXCEEDED",
+            Self::TransferFailed => "FUNDS_TRANSFER_FAILED",
+            Self::InvalidArgument => "REQUEST_INVALID_ARGUMENT",
+            Self::NotFound => "REQUEST_NOT_FOUND",
+            Self::AlreadyExists => "REQUEST_ALREADY_EXISTS",
+            Self::InvalidState => "REQUEST_INVALID_STATE",
+            Self::Unsupported => "REQUEST_UNSUPPORTED",
+            Self::Unavailable => "INFRA_UNAVAILABLE",
+            Self::Timeout => "INFRA_TIMEOUT",
+            Self::LockContention => "INFRA_LOCK_CONTENTION",
+            Self::Internal => "INFRA_INTERNAL",
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Unauthenticated | Self::PermissionDenied | Self::LicenseRequired => ErrorCategory::Auth,
+            Self::PolicyDenied
+            | Self::PolicyInvalid
+            | Self::RateLimited
+            | Self::QuotaExceeded
+            | Self::BudgetExceeded
+            | Self::LoopDetected => ErrorCategory::Policy,
+            Self::InsufficientFunds
+            | Self::InvalidAmount
+            | Self::CurrencyMismatch
+            | Self::AccountNotFound
+            | Self::SpendingLimitExceeded
+            | Self::TransferFailed => ErrorCategory::Funds,
+            Self::InvalidArgument | Self::NotFound | Self::AlreadyExists | Self::InvalidState | Self::Unsupported => {
+                ErrorCategory::Request
+            }
+            Self::Unavailable | Self::Timeout | Self::LockContention | Self::Internal => ErrorCategory::Infra,
+        }
+    }
+
+    /// Whether repeating the same request later can succeed.
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited | Self::QuotaExceeded | Self::Unavailable | Self::Timeout | Self::LockContention
+        )
+    }
+
+    /// HTTP status code.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::Unauthenticated => 401,
+            Self::PermissionDenied | Self::LicenseRequired | Self::PolicyDenied => 403,
+            Self::PolicyInvalid => 422,
+            Self::RateLimited | Self::QuotaExceeded | Self::BudgetExceeded => 429,
+            Self::LoopDetected => 508,
+            Self::InsufficientFunds | Self::SpendingLimitExceeded => 402,
+            Self::InvalidAmount | Self::CurrencyMismatch | Self::InvalidArgument => 400,
+            Self::AccountNotFound | Self::NotFound => 404,
+            Self::TransferFailed | Self::AlreadyExists | Self::InvalidState | Self::LockContention => 409,
+            Self::Unsupported => 501,
+            Self::Unavailable => 503,
+            Self::Timeout => 504,
+            Self::Internal => 500,
+        }
+    }
+
+    /// Canonical gRPC status code (`tonic::Code` as `i32`).
+    pub fn grpc_code(&self) -> i32 {
+        match self {
+            Self::InvalidArgument | Self::InvalidAmount | Self::CurrencyMismatch | Self::PolicyInvalid => 3,
+            Self::Timeout => 4,
+            Self::NotFound | Self::AccountNotFound => 5,
+            Self::AlreadyExists => 6,
+            Self::PermissionDenied | Self::LicenseRequired | Self::PolicyDenied => 7,
+            Self::RateLimited | Self::QuotaExceeded | Self::BudgetExceeded => 8,
+            Self::InsufficientFunds | Self::SpendingLimitExceeded | Self::InvalidState | Self::LoopDetected => 9,
+            Self::TransferFailed | Self::LockContention => 10,
+            Self::Unsupported => 12,
+            Self::Internal => 13,
+            Self::Unavailable => 14,
+            Self::Unauthenticated => 16,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<ErrorCode> for &'static str {
+    fn from(code: ErrorCode) -> Self {
+        code.as_str()
+    }
+}
+
+/// Unknown error code string.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown error code: {0}")]
+pub struct UnknownCode(pub String);
+
+impl FromStr for ErrorCode {
+    type Err = UnknownCode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|code| code.as_str() == s)
+            .ok_or_else(|| UnknownCode(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for ErrorCode {
+    type Error = UnknownCode;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_are_unique_and_prefixed_by_category() {
+        let strings: HashSet<_> = ErrorCode::ALL.iter().map(|c| c.as_str()).collect();
+        assert_eq!(strings.len(), ErrorCode::ALL.len());
+
+        for code in ErrorCode::ALL {
+            let prefix = code.category().as_str().to_uppercase();
+            assert!(code.as_str().starts_with(&prefix), "{} not in {}", code, prefix);
+            assert_eq!(code.as_str().parse::<ErrorCode>().unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn test_code_serde_uses_wire_form() {
+        let json = serde_json::to_string(&ErrorCode::InsufficientFunds).unwrap();
+        assert_eq!(json, "\"FUNDS_INSUFFICIENT\"");
+        assert_eq!(serde_json::from_str::<ErrorCode>(&json).unwrap(), ErrorCode::InsufficientFunds);
+        assert!(serde_json::from_str::<ErrorCode>("\"NOPE\"").is_err());
+    }
+}
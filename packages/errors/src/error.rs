@@ -0,0 +1,136 @@
+//! Structured Errors
+//!
+//! [`AgentKernError`] is the form every module error takes at an API
+//! boundary (NAPI, gRPC, HTTP). Module errors implement [`Coded`] to map
+//! themselves onto the catalog.
+
+use crate::code::{ErrorCategory, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Error as reported to callers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{code}: {message}")]
+pub struct AgentKernError {
+    pub code: ErrorCode,
+    pub category: ErrorCategory,
+    /// Human-readable details
+    pub message: String,
+    /// Whether the same request may succeed later
+    pub retryable: bool,
+    /// Suggested wait before retrying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+    /// Extra machine-readable context (resource, limit, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+impl AgentKernError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            category: code.category(),
+            message: message.into(),
+            retryable: code.retryable(),
+            retry_after_ms: None,
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Suggest when to retry; marks the error retryable.
+    pub fn with_retry_after(mut self, after: Duration) -> Self {
+        self.retryable = true;
+        self.retry_after_ms = Some(after.as_millis() as u64);
+        self
+    }
+
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.details.insert(key.into(), value.to_string());
+        self
+    }
+
+    pub fn http_status(&self) -> u16 {
+        self.code.http_status()
+    }
+
+    pub fn grpc_code(&self) -> i32 {
+        self.code.grpc_code()
+    }
+}
+
+/// A module error with a catalog code.
+pub trait Coded: std::error::Error {
+    fn code(&self) -> ErrorCode;
+
+    /// Suggested wait before retrying, when the error knows it.
+    fn retry_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Structured form for API boundaries.
+    fn to_agentkern_error(&self) -> AgentKernError {
+        let error = AgentKernError::new(self.code(), self.to_string());
+        match self.retry_after() {
+            Some(after) => error.with_retry_after(after),
+            None => error,
+        }
+    }
+}
+
+impl<E: Coded> From<E> for AgentKernError {
+    fn from(error: E) -> Self {
+        error.to_agentkern_error()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    enum LockError {
+        #[error("Resource is locked for {0}s")]
+        Locked(u64),
+        #[error("No such lock")]
+        Missing,
+    }
+
+    impl Coded for LockError {
+        fn code(&self) -> ErrorCode {
+            match self {
+                Self::Locked(_) => ErrorCode::LockContention,
+                Self::Missing => ErrorCode::NotFound,
+            }
+        }
+
+        fn retry_after(&self) -> Option<Duration> {
+            match self {
+                Self::Locked(secs) => Some(Duration::from_secs(*secs)),
+                Self::Missing => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_module_error_conversion() {
+        let error: AgentKernError = LockError::Locked(5).into();
+        assert_eq!(error.code, ErrorCode::LockContention);
+        assert_eq!(error.category, ErrorCategory::Infra);
+        assert!(error.retryable);
+        assert_eq!(error.retry_after_ms, Some(5000));
+        assert_eq!(error.to_string(), "INFRA_LOCK_CONTENTION: Resource is locked for 5s");
+
+        let missing = LockError::Missing.to_agentkern_error().with_detail("resource", "db");
+        assert!(!missing.retryable);
+        assert_eq!(missing.http_status(), 404);
+
+        let json = serde_json::to_value(&missing).unwrap();
+        assert_eq!(json["code"], "REQUEST_NOT_FOUND");
+        assert_eq!(json["category"], "request");
+        assert_eq!(json["details"]["resource"], "db");
+        assert!(json.get("retry_after_ms").is_none());
+        assert_eq!(serde_json::from_value::<AgentKernError>(json).unwrap(), missing);
+    }
+}
@@ -0,0 +1,27 @@
+//! AgentKern-Errors: Shared Error Taxonomy
+//!
+//! One catalog of stable, machine-readable error codes for every module.
+//! Each code belongs to a category (auth, policy, funds, request, infra),
+//! carries a retryability hint, and maps to an HTTP status and gRPC code,
+//! so the NAPI, gRPC and HTTP layers report the same failure the same way.
+//!
+//! Module errors keep their own `thiserror` enums and implement [`Coded`];
+//! at an API boundary they convert into [`AgentKernError`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_errors::{AgentKernError, Coded, ErrorCode};
+//!
+//! let error: AgentKernError = LedgerError::InsufficientFunds.into();
+//! assert_eq!(error.code, ErrorCode::InsufficientFunds);
+//! assert_eq!(error.to_string(), "FUNDS_INSUFFICIENT: Insufficient funds");
+//! assert_eq!(error.http_status(), 402);
+//! ```
+
+pub mod code;
+pub mod error;
+
+// Re-exports
+pub use code::{ErrorCategory, ErrorCode, UnknownCode};
+pub use error::{AgentKernError, Coded};
@@ -0,0 +1,182 @@
+//! AgentKern-FSM: Typed State Machines
+//!
+//! Payment, escrow, auction and settlement statuses only move along legal
+//! transitions. A status enum implements [`State`] to declare them; every
+//! change goes through a [`TransitionLog`], which refuses illegal ones with
+//! a structured [`IllegalTransition`] and records legal ones with the actor
+//! and time, so an entity carries its own audit trail.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_fsm::{State, TransitionLog};
+//!
+//! impl State for EscrowStatus {
+//!     const MACHINE: &'static str = "escrow";
+//!
+//!     fn can_transition_to(&self, to: &Self) -> bool {
+//!         matches!((self, to), (Self::Locked, Self::Released | Self::Refunded | Self::Expired))
+//!     }
+//! }
+//!
+//! let mut status = EscrowStatus::Locked;
+//! let mut history = TransitionLog::default();
+//! history.apply(&mut status, EscrowStatus::Refunded, "arbiter")?;
+//! assert!(history.apply(&mut status, EscrowStatus::Released, "worker-1").is_err());
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Actor recorded for transitions a module makes on its own, e.g. expiry.
+pub const SYSTEM_ACTOR: &str = "system";
+
+/// A status with a fixed set of legal transitions.
+pub trait State: Copy + PartialEq + fmt::Debug {
+    /// Name of the machine in errors, e.g. `escrow`
+    const MACHINE: &'static str;
+
+    /// Whether moving from `self` to `to` is legal. Staying put is not a
+    /// transition and is never legal.
+    fn can_transition_to(&self, to: &Self) -> bool;
+
+    /// Check a transition.
+    fn check_transition(&self, to: &Self) -> Result<(), IllegalTransition> {
+        if self.can_transition_to(to) {
+            Ok(())
+        } else {
+            Err(IllegalTransition { machine: Self::MACHINE, from: format!("{self:?}"), to: format!("{to:?}") })
+        }
+    }
+}
+
+/// A transition the machine does not allow.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Illegal {machine} transition: {from} -> {to}")]
+pub struct IllegalTransition {
+    pub machine: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+impl agentkern_errors::Coded for IllegalTransition {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        agentkern_errors::ErrorCode::InvalidState
+    }
+}
+
+/// A recorded transition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition<S> {
+    pub from: S,
+    pub to: S,
+    /// Agent, user or module that made it
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Transitions of one entity, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TransitionLog<S> {
+    entries: Vec<Transition<S>>,
+}
+
+impl<S> Default for TransitionLog<S> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<S: State> TransitionLog<S> {
+    /// Move `state` to `to` and record it, or leave it untouched if the
+    /// transition is illegal.
+    pub fn apply(&mut self, state: &mut S, to: S, actor: &str) -> Result<(), IllegalTransition> {
+        state.check_transition(&to)?;
+        self.entries.push(Transition { from: *state, to, actor: actor.to_string(), at: Utc::now() });
+        *state = to;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Transition<S>] {
+        &self.entries
+    }
+
+    pub fn last(&self) -> Option<&Transition<S>> {
+        self.entries.last()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_errors::{Coded, ErrorCode};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Door {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    impl State for Door {
+        const MACHINE: &'static str = "door";
+
+        fn can_transition_to(&self, to: &Self) -> bool {
+            matches!(
+                (self, to),
+                (Self::Open, Self::Closed) | (Self::Closed, Self::Open | Self::Locked) | (Self::Locked, Self::Closed)
+            )
+        }
+    }
+
+    #[test]
+    fn test_legal_transitions_are_recorded() {
+        let mut door = Door::Open;
+        let mut log = TransitionLog::default();
+        log.apply(&mut door, Door::Closed, "alice").unwrap();
+        log.apply(&mut door, Door::Locked, SYSTEM_ACTOR).unwrap();
+
+        assert_eq!(door, Door::Locked);
+        assert_eq!(log.len(), 2);
+        let last = log.last().unwrap();
+        assert_eq!((last.from, last.to, last.actor.as_str()), (Door::Closed, Door::Locked, "system"));
+        assert!(log.entries()[0].at <= last.at);
+    }
+
+    #[test]
+    fn test_illegal_transitions_are_refused() {
+        let mut door = Door::Locked;
+        let mut log = TransitionLog::default();
+
+        let error = log.apply(&mut door, Door::Open, "alice").unwrap_err();
+        assert_eq!(error.to_string(), "Illegal door transition: Locked -> Open");
+        assert_eq!(error.code(), ErrorCode::InvalidState);
+        assert!(log.apply(&mut door, Door::Locked, "alice").is_err());
+        assert_eq!(door, Door::Locked);
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_log_serializes_as_a_list() {
+        let mut door = Door::Open;
+        let mut log = TransitionLog::default();
+        log.apply(&mut door, Door::Closed, "alice").unwrap();
+
+        let json = serde_json::to_value(&log).unwrap();
+        assert_eq!(json[0]["from"], "open");
+        assert_eq!(json[0]["actor"], "alice");
+        let restored: TransitionLog<Door> = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, log);
+    }
+}
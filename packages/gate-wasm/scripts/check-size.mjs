@@ -0,0 +1,26 @@
+// Fails when the release bundle exceeds its size budget.
+//
+//   npm run build && npm run size
+
+import { readFileSync } from 'node:fs';
+import { gzipSync } from 'node:zlib';
+
+// Budget for the default (JSON-only) build
+const BUDGET = {
+  raw: 400 * 1024,
+  gzip: 150 * 1024,
+};
+
+const path = new URL('../pkg/agentkern_gate_bg.wasm', import.meta.url);
+const wasm = readFileSync(path);
+const size = { raw: wasm.length, gzip: gzipSync(wasm, { level: 9 }).length };
+
+let failed = false;
+for (const [kind, limit] of Object.entries(BUDGET)) {
+  const ok = size[kind] <= limit;
+  failed ||= !ok;
+  console.log(
+    `${kind.padEnd(5)} ${(size[kind] / 1024).toFixed(1).padStart(7)} KiB / ${limit / 1024} KiB ${ok ? 'ok' : 'OVER BUDGET'}`,
+  );
+}
+process.exit(failed ? 1 : 0);
@@ -0,0 +1,133 @@
+//! JavaScript API (wasm-bindgen).
+//!
+//! Results are plain objects with camelCase keys; errors are thrown as
+//! `Error`s.
+
+use crate::evaluator::PolicyEvaluator;
+use crate::prompt_guard::{PromptAnalysis, PromptGuard};
+use crate::types::DataRegion;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Prompt analysis, as returned to JavaScript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptCheck {
+    /// none | low | medium | high | critical
+    pub threat_level: String,
+    /// Attack types (`InstructionOverride`, `RoleHijacking`, ...)
+    pub attacks: Vec<String>,
+    /// Patterns matched
+    pub matched_patterns: Vec<String>,
+    /// Confidence (0-100)
+    pub confidence: u8,
+    /// allow | allowwithlog | review | block | blockandalert
+    pub action: String,
+    pub should_block: bool,
+    pub requires_review: bool,
+}
+
+impl From<PromptAnalysis> for PromptCheck {
+    fn from(analysis: PromptAnalysis) -> Self {
+        Self {
+            threat_level: format!("{:?}", analysis.threat_level).to_lowercase(),
+            attacks: analysis.attacks.iter().map(|a| format!("{:?}", a)).collect(),
+            matched_patterns: analysis.matched_patterns,
+            confidence: analysis.confidence,
+            action: format!("{:?}", analysis.action).to_lowercase(),
+            should_block: analysis.threat_level.should_block(),
+            requires_review: analysis.threat_level.requires_review(),
+        }
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn from_js<T: for<'de> Deserialize<'de>>(value: JsValue, what: &str) -> Result<T, JsError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsError::new(&format!("Invalid {}: {}", what, e)))
+}
+
+/// Prompt injection guard (same patterns as the server).
+#[wasm_bindgen(js_name = PromptGuard)]
+pub struct JsPromptGuard(PromptGuard);
+
+#[wasm_bindgen(js_class = PromptGuard)]
+impl JsPromptGuard {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(PromptGuard::new())
+    }
+
+    /// Full analysis (`PromptCheck` object).
+    pub fn analyze(&self, prompt: &str) -> Result<JsValue, JsError> {
+        to_js(&PromptCheck::from(self.0.analyze(prompt)))
+    }
+
+    /// Would the server block this prompt?
+    #[wasm_bindgen(js_name = shouldBlock)]
+    pub fn should_block(&self, prompt: &str) -> bool {
+        self.0.should_block(prompt)
+    }
+}
+
+impl Default for JsPromptGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Symbolic policy evaluator.
+#[wasm_bindgen(js_name = PolicyEvaluator)]
+pub struct JsPolicyEvaluator(PolicyEvaluator);
+
+#[wasm_bindgen(js_class = PolicyEvaluator)]
+impl JsPolicyEvaluator {
+    /// Load an array of policy objects (same shape as the server's JSON).
+    #[wasm_bindgen(constructor)]
+    pub fn new(policies: JsValue) -> Result<JsPolicyEvaluator, JsError> {
+        Ok(Self(PolicyEvaluator::new(from_js(policies, "policies")?)?))
+    }
+
+    /// Load a JSON policy or array of policies.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<JsPolicyEvaluator, JsError> {
+        Ok(Self(PolicyEvaluator::from_json(json)?))
+    }
+
+    /// Load YAML policy files (`---`-separated). Built with the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    #[wasm_bindgen(js_name = fromYaml)]
+    pub fn from_yaml(yaml: &str) -> Result<JsPolicyEvaluator, JsError> {
+        Ok(Self(PolicyEvaluator::from_yaml(yaml)?))
+    }
+
+    /// Evaluate only policies for `region` (`us`, `eu`, `global`, ...).
+    #[wasm_bindgen(js_name = setJurisdiction)]
+    pub fn set_jurisdiction(&mut self, region: &str) -> Result<(), JsError> {
+        let region: DataRegion = serde_json::from_value(serde_json::Value::String(region.to_string()))
+            .map_err(|_| JsError::new(&format!("Unknown jurisdiction '{}'", region)))?;
+        self.0.set_jurisdiction(region);
+        Ok(())
+    }
+
+    /// Loaded policy IDs, highest priority first.
+    #[wasm_bindgen(getter, js_name = policyIds)]
+    pub fn policy_ids(&self) -> Vec<String> {
+        self.0.policy_ids()
+    }
+
+    /// Evaluate an action (`Decision` object). `context` is a plain object.
+    pub fn evaluate(&self, agent_id: &str, action: &str, context: JsValue) -> Result<JsValue, JsError> {
+        let context: HashMap<String, serde_json::Value> = if context.is_undefined() || context.is_null() {
+            HashMap::new()
+        } else {
+            from_js(context, "context")?
+        };
+        to_js(&self.0.evaluate(agent_id, action, context))
+    }
+}
@@ -0,0 +1,295 @@
+//! Symbolic Policy Evaluator
+//!
+//! The deterministic half of `GateEngine::verify`, for client-side
+//! pre-checks. Same semantics as the engine's symbolic path:
+//! - Enabled policies for the jurisdiction, highest priority first
+//! - `deny` blocks (risk 100), `review` raises risk to at least 60, a rule's
+//!   `risk_score` raises it to that value
+//! - Allowed when nothing blocks and risk is below 80
+//!
+//! The neural path, carbon veto and compliance bundles stay on the server:
+//! an allowed pre-check is not a substitute for server verification.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let evaluator = PolicyEvaluator::from_json(policies_json)?;
+//! let decision = evaluator.evaluate("agent-1", "transfer_funds", context);
+//! if !decision.allowed {
+//!     show_blocked(&decision.reasoning);
+//! }
+//! ```
+
+use crate::dsl::{self, EvalContext};
+use crate::policy::{LintLevel, Policy, PolicyAction};
+use crate::types::DataRegion;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Risk at or above which an action is blocked (as in `GateEngine`).
+const BLOCK_RISK: u8 = 80;
+
+/// Policy loading errors.
+#[derive(Debug, Error)]
+pub enum EvaluatorError {
+    #[error("Invalid policy JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "yaml")]
+    #[error("Invalid policy YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Policy rejected: {0}")]
+    Lint(String),
+}
+
+/// Outcome of evaluating one action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Decision {
+    /// Would the server's symbolic path allow the action?
+    pub allowed: bool,
+    /// Risk score (0-100)
+    pub risk_score: u8,
+    /// Policies evaluated, in evaluation order
+    pub evaluated_policies: Vec<String>,
+    /// Policies with a matching `deny` rule
+    pub blocking_policies: Vec<String>,
+    /// Matching rules as `policy/rule`
+    pub matched_rules: Vec<String>,
+    /// A matching `review` rule will send this action to a human
+    pub review_required: bool,
+    /// Human-readable reasoning (same wording as the server)
+    pub reasoning: String,
+}
+
+/// Evaluates actions against a fixed policy set.
+#[derive(Debug, Clone, Default)]
+pub struct PolicyEvaluator {
+    /// Highest priority first
+    policies: Vec<Policy>,
+    jurisdiction: DataRegion,
+}
+
+impl PolicyEvaluator {
+    /// Load policies. Policies with lint errors are rejected.
+    pub fn new(mut policies: Vec<Policy>) -> Result<Self, EvaluatorError> {
+        let errors: Vec<String> = policies
+            .iter()
+            .flat_map(Policy::lint)
+            .filter(|issue| issue.level == LintLevel::Error)
+            .map(|issue| issue.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return Err(EvaluatorError::Lint(errors.join("; ")));
+        }
+        policies.sort_by_key(|p| std::cmp::Reverse(p.priority));
+        Ok(Self {
+            policies,
+            jurisdiction: DataRegion::Global,
+        })
+    }
+
+    /// Load a JSON policy or array of policies.
+    pub fn from_json(json: &str) -> Result<Self, EvaluatorError> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            One(Box<Policy>),
+            Many(Vec<Policy>),
+        }
+
+        match serde_json::from_str(json)? {
+            OneOrMany::One(policy) => Self::new(vec![*policy]),
+            OneOrMany::Many(policies) => Self::new(policies),
+        }
+    }
+
+    /// Load YAML policies (`---`-separated documents, as in policy files).
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, EvaluatorError> {
+        let policies = serde_yaml::Deserializer::from_str(yaml)
+            .map(Policy::deserialize)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(policies)
+    }
+
+    /// Evaluate only policies that apply in `jurisdiction`.
+    pub fn with_jurisdiction(mut self, jurisdiction: DataRegion) -> Self {
+        self.jurisdiction = jurisdiction;
+        self
+    }
+
+    /// Change the jurisdiction.
+    pub fn set_jurisdiction(&mut self, jurisdiction: DataRegion) {
+        self.jurisdiction = jurisdiction;
+    }
+
+    /// Loaded policy IDs, highest priority first.
+    pub fn policy_ids(&self) -> Vec<String> {
+        self.policies.iter().map(|p| p.id.clone()).collect()
+    }
+
+    /// Evaluate an action.
+    pub fn evaluate(&self, agent_id: &str, action: &str, context: HashMap<String, serde_json::Value>) -> Decision {
+        let ctx = EvalContext {
+            action: action.to_string(),
+            agent_id: agent_id.to_string(),
+            context,
+        };
+        let mut decision = Decision {
+            allowed: true,
+            risk_score: 0,
+            evaluated_policies: Vec::new(),
+            blocking_policies: Vec::new(),
+            matched_rules: Vec::new(),
+            review_required: false,
+            reasoning: String::new(),
+        };
+
+        let policies = self
+            .policies
+            .iter()
+            .filter(|p| p.enabled && p.applies_to_jurisdiction(self.jurisdiction));
+        for policy in policies {
+            decision.evaluated_policies.push(policy.id.clone());
+            for rule in policy.rules.iter().filter(|rule| dsl::evaluate(&rule.condition, &ctx)) {
+                decision.matched_rules.push(format!("{}/{}", policy.id, rule.id));
+                if let Some(risk) = rule.risk_score {
+                    decision.risk_score = decision.risk_score.max(risk);
+                }
+                match rule.action {
+                    PolicyAction::Deny => {
+                        if !decision.blocking_policies.contains(&policy.id) {
+                            decision.blocking_policies.push(policy.id.clone());
+                        }
+                        decision.risk_score = 100;
+                    }
+                    PolicyAction::Review => {
+                        decision.review_required = true;
+                        decision.risk_score = decision.risk_score.max(60);
+                    }
+                    PolicyAction::Audit | PolicyAction::Allow => {}
+                }
+            }
+        }
+
+        decision.allowed = decision.blocking_policies.is_empty() && decision.risk_score < BLOCK_RISK;
+        decision.reasoning = if !decision.blocking_policies.is_empty() {
+            format!("Blocked by policies: {}", decision.blocking_policies.join(", "))
+        } else if decision.risk_score >= BLOCK_RISK {
+            "Action blocked due to high risk score".to_string()
+        } else {
+            "All policies passed".to_string()
+        };
+        decision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const POLICIES: &str = r#"[
+        {
+            "id": "spending-limits",
+            "name": "Spending Limits",
+            "priority": 100,
+            "jurisdictions": ["us"],
+            "rules": [
+                {
+                    "id": "max-transaction",
+                    "condition": "action == 'transfer_funds' && context.amount > 10000",
+                    "action": "deny",
+                    "message": "Transaction exceeds maximum allowed amount"
+                },
+                {
+                    "id": "require-approval",
+                    "condition": "action == 'transfer_funds' && context.amount > 1000",
+                    "action": "review",
+                    "message": "Transaction requires human approval"
+                }
+            ]
+        },
+        {
+            "id": "audit",
+            "name": "Audit",
+            "rules": [{"id": "audit-transfers", "condition": "action == 'transfer_funds'", "action": "audit"}]
+        }
+    ]"#;
+
+    fn context(amount: u64) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("amount".to_string(), json!(amount))])
+    }
+
+    #[test]
+    fn test_matches_engine_semantics() {
+        let evaluator = PolicyEvaluator::from_json(POLICIES).unwrap().with_jurisdiction(DataRegion::Us);
+        assert_eq!(evaluator.policy_ids(), ["spending-limits", "audit"]);
+
+        let denied = evaluator.evaluate("agent-1", "transfer_funds", context(50_000));
+        assert!(!denied.allowed);
+        assert_eq!(denied.blocking_policies, ["spending-limits"]);
+        assert_eq!(denied.risk_score, 100);
+        assert_eq!(denied.reasoning, "Blocked by policies: spending-limits");
+
+        let review = evaluator.evaluate("agent-1", "transfer_funds", context(5_000));
+        assert!(review.allowed);
+        assert!(review.review_required);
+        assert_eq!(review.risk_score, 60);
+        assert_eq!(review.matched_rules, ["spending-limits/require-approval", "audit/audit-transfers"]);
+
+        // Out of jurisdiction: only the global policy applies
+        let eu = evaluator.clone().with_jurisdiction(DataRegion::Eu);
+        let decision = eu.evaluate("agent-1", "transfer_funds", context(50_000));
+        assert!(decision.allowed);
+        assert_eq!(decision.evaluated_policies, ["audit"]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_policies() {
+        let json = r#"{"id": "bad", "name": "Bad", "rules": [{"id": "r", "condition": "context.amount >", "action": "audit"}]}"#;
+        assert!(matches!(PolicyEvaluator::from_json(json), Err(EvaluatorError::Lint(_))));
+        assert!(matches!(PolicyEvaluator::from_json("{"), Err(EvaluatorError::Json(_))));
+
+        let many = r#"[{"id": "a", "name": "A", "rules": [{"id": "r", "condition": "true", "action": "allow"}]}]"#;
+        assert_eq!(PolicyEvaluator::from_json(many).unwrap().policy_ids(), ["a"]);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_loads_yaml_documents() {
+        let yaml = r#"
+id: spending-limits
+name: Spending Limits
+priority: 100
+jurisdictions: [us]
+rules:
+  - id: max-transaction
+    condition: "action == 'transfer_funds' && context.amount > 10000"
+    action: deny
+    message: "Transaction exceeds maximum allowed amount"
+  - id: require-approval
+    condition: "action == 'transfer_funds' && context.amount > 1000"
+    action: review
+    message: "Transaction requires human approval"
+---
+id: audit
+name: Audit
+rules:
+  - id: audit-transfers
+    condition: "action == 'transfer_funds'"
+    action: audit
+"#;
+        let from_yaml = PolicyEvaluator::from_yaml(yaml).unwrap().with_jurisdiction(DataRegion::Us);
+        let from_json = PolicyEvaluator::from_json(POLICIES).unwrap().with_jurisdiction(DataRegion::Us);
+        assert_eq!(from_yaml.policy_ids(), from_json.policy_ids());
+        for amount in [500, 5_000, 50_000] {
+            assert_eq!(
+                from_yaml.evaluate("agent-1", "transfer_funds", context(amount)),
+                from_json.evaluate("agent-1", "transfer_funds", context(amount))
+            );
+        }
+        assert!(matches!(PolicyEvaluator::from_yaml("rules: ["), Err(EvaluatorError::Yaml(_))));
+    }
+}
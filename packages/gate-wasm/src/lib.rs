@@ -0,0 +1,43 @@
+//! AgentKern-Gate for the Browser
+//!
+//! `wasm32-unknown-unknown` build of the parts of AgentKern-Gate that run
+//! without a server, so web-based agent sandboxes can pre-screen prompts and
+//! actions before they reach the gateway:
+//! - [`prompt_guard`]: prompt injection detection
+//! - [`evaluator`]: the symbolic policy path (DSL conditions, priorities,
+//!   jurisdictions)
+//!
+//! The guard, DSL, policy and type modules are compiled from
+//! `agentkern-gate`'s sources, so browser and server agree on every pattern
+//! and condition.
+//!
+//! # Example (JavaScript)
+//!
+//! ```js
+//! import init, { PromptGuard, PolicyEvaluator } from '@agentkern/gate-wasm';
+//!
+//! await init();
+//! const guard = new PromptGuard();
+//! if (guard.shouldBlock(prompt)) return;
+//!
+//! const evaluator = PolicyEvaluator.fromJson(policiesJson);
+//! const decision = evaluator.evaluate('agent-1', 'transfer_funds', { amount: 5000 });
+//! ```
+
+#[path = "../../gate/src/dsl.rs"]
+pub mod dsl;
+#[path = "../../gate/src/policy.rs"]
+pub mod policy;
+#[path = "../../gate/src/prompt_guard.rs"]
+pub mod prompt_guard;
+#[path = "../../gate/src/types.rs"]
+pub mod types;
+
+pub mod evaluator;
+mod bindings;
+
+pub use bindings::PromptCheck;
+pub use evaluator::{Decision, EvaluatorError, PolicyEvaluator};
+pub use policy::{Policy, PolicyAction, PolicyRule};
+pub use prompt_guard::{PromptAnalysis, PromptGuard, ThreatLevel};
+pub use types::DataRegion;
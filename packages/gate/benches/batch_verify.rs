@@ -0,0 +1,86 @@
+//! Batch Verification Benchmarks
+//!
+//! Compares `verify()` called per request against `verify_batch()`.
+//!
+//! Run with: cargo bench --bench batch_verify
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use agentkern_gate::{GateEngine, Policy, PolicyAction, PolicyRule, VerificationRequest};
+use agentkern_gate::engine::VerificationRequestBuilder;
+
+fn create_engine(rt: &tokio::runtime::Runtime) -> GateEngine {
+    let engine = GateEngine::new();
+
+    rt.block_on(async {
+        for i in 0..20 {
+            engine.register_policy(Policy {
+                id: format!("bench-policy-{}", i),
+                name: format!("Bench Policy {}", i),
+                description: String::new(),
+                priority: i,
+                enabled: true,
+                jurisdictions: vec![],
+                rules: vec![PolicyRule {
+                    id: format!("rule-{}", i),
+                    condition: format!("action == 'blocked_{}' && context.amount > 1000", i),
+                    action: PolicyAction::Deny,
+                    message: None,
+                    risk_score: Some(90),
+                }],
+            }).await;
+        }
+    });
+
+    engine
+}
+
+fn create_requests(n: usize) -> Vec<VerificationRequest> {
+    (0..n)
+        .map(|i| {
+            // Every 10th request crosses the neural threshold
+            let action = if i % 10 == 0 { format!("blocked_{}", i % 20) } else { "read_data".to_string() };
+            VerificationRequestBuilder::new("bench-agent", action)
+                .context("amount", 5000)
+                .build()
+        })
+        .collect()
+}
+
+fn bench_batch_vs_single(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let engine = create_engine(&rt);
+    let mut group = c.benchmark_group("verification_throughput");
+
+    for size in [10usize, 100, 1000] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("per_call", size), &size, |b, &size| {
+            b.iter_batched(
+                || create_requests(size),
+                |requests| {
+                    rt.block_on(async {
+                        let mut results = Vec::with_capacity(requests.len());
+                        for request in requests {
+                            results.push(engine.verify(request).await);
+                        }
+                        black_box(results)
+                    })
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(BenchmarkId::new("verify_batch", size), &size, |b, &size| {
+            b.iter_batched(
+                || create_requests(size),
+                |requests| rt.block_on(async { black_box(engine.verify_batch(requests).await) }),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_vs_single);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+//! Ingestion Benchmarks
+//!
+//! Compares the zero-copy ingest path against a copying baseline, and the
+//! io_uring listener against standard Tokio when the `io_uring` feature is on.
+//!
+//! Run with: cargo bench --bench ingest --features io_uring
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use agentkern_gate::GateEngine;
+use agentkern_gate::ingest::{parse_request, serve_tokio, spawn_ingest, BufferPool, IngestBackend, IngestConfig};
+
+const BODY: &str = r#"{"agent_id":"bench-agent","action":"transfer_funds","context":{"amount":250,"currency":"USD"}}"#;
+
+fn http_request() -> Vec<u8> {
+    format!(
+        "POST /verify HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        BODY.len(),
+        BODY
+    )
+    .into_bytes()
+}
+
+/// Baseline: copy the request into owned strings before deserializing.
+fn parse_copying(raw: &[u8]) -> serde_json::Value {
+    let text = String::from_utf8(raw.to_vec()).unwrap();
+    let (_, body) = text.split_once("\r\n\r\n").unwrap();
+    let body = body.to_owned();
+    serde_json::from_str(&body).unwrap()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let raw = http_request();
+    let pool = BufferPool::new(16 * 1024, 4);
+    let mut group = c.benchmark_group("ingest_parse");
+
+    group.bench_function("pooled_zero_copy", |b| {
+        b.iter(|| {
+            let mut buf = pool.acquire();
+            buf.extend_from_slice(&raw);
+            let parsed = parse_request(black_box(&buf)).unwrap();
+            pool.release(buf);
+            parsed
+        })
+    });
+
+    group.bench_function("copying", |b| {
+        b.iter(|| parse_copying(black_box(&raw)))
+    });
+
+    group.finish();
+}
+
+fn roundtrip(addr: SocketAddr, request: &[u8]) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request).unwrap();
+    let mut response = Vec::with_capacity(1024);
+    stream.read_to_end(&mut response).unwrap();
+    assert!(response.starts_with(b"HTTP/1.1 200"));
+}
+
+fn wait_for(addr: SocketAddr) {
+    for _ in 0..100 {
+        if TcpStream::connect(addr).is_ok() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("listener on {} did not start", addr);
+}
+
+fn bench_listeners(c: &mut Criterion) {
+    let request = http_request();
+    let mut group = c.benchmark_group("ingest_listener");
+
+    // Standard Tokio listener
+    let tokio_addr: SocketAddr = "127.0.0.1:39021".parse().unwrap();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async move {
+            let listener = tokio::net::TcpListener::bind(tokio_addr).await.unwrap();
+            let pool = Arc::new(BufferPool::new(16 * 1024, 1024));
+            let _ = serve_tokio(listener, Arc::new(GateEngine::new()), pool).await;
+        });
+    });
+    wait_for(tokio_addr);
+    group.bench_function("tokio", |b| b.iter(|| roundtrip(tokio_addr, &request)));
+
+    // Best available backend (io_uring when compiled in and supported)
+    let config = IngestConfig {
+        addr: "127.0.0.1:39022".parse().unwrap(),
+        ..IngestConfig::default()
+    };
+    let addr = config.addr;
+    let (backend, _handle) = spawn_ingest(config, Arc::new(GateEngine::new())).unwrap();
+    if backend == IngestBackend::IoUring {
+        wait_for(addr);
+        group.bench_function("io_uring", |b| b.iter(|| roundtrip(addr, &request)));
+    } else {
+        eprintln!("io_uring unavailable on this host; skipping io_uring listener bench");
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_listeners);
+criterion_main!(benches);
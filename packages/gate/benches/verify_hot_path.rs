@@ -0,0 +1,106 @@
+//! Verification Hot Path Benchmarks
+//!
+//! `GateEngine::verify` with each optional stage switched on in turn:
+//! symbolic only, prompt guard in front, WASM policies alongside, and
+//! neural scoring forced for every request.
+//!
+//! Run with: cargo bench --bench verify_hot_path [--features wasm]
+//!
+//! For regression tracking, save a baseline on `main` and compare a branch
+//! against it (see `scripts/bench-compare.mjs`):
+//!
+//!   cargo bench --bench verify_hot_path -- --save-baseline main
+//!   cargo bench --bench verify_hot_path -- --baseline main
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use agentkern_gate::engine::VerificationRequestBuilder;
+use agentkern_gate::prompt_guard::PromptGuard;
+use agentkern_gate::{GateEngine, Policy, PolicyAction, PolicyRule, VerificationRequest};
+
+const PROMPT: &str = "Summarise yesterday's invoices for the finance team and flag anything over budget.";
+
+fn register_policies(rt: &tokio::runtime::Runtime, engine: &GateEngine) {
+    rt.block_on(async {
+        for i in 0..20 {
+            engine.register_policy(Policy {
+                id: format!("bench-policy-{}", i),
+                name: format!("Bench Policy {}", i),
+                description: String::new(),
+                priority: i,
+                enabled: true,
+                jurisdictions: vec![],
+                rules: vec![PolicyRule {
+                    id: format!("rule-{}", i),
+                    condition: format!("action == 'blocked_{}' && context.amount > 1000", i),
+                    action: PolicyAction::Deny,
+                    message: None,
+                    risk_score: Some(90),
+                }],
+            }).await;
+        }
+    });
+}
+
+fn request() -> VerificationRequest {
+    VerificationRequestBuilder::new("bench-agent", "read_data")
+        .context("amount", 250)
+        .context("prompt", PROMPT)
+        .build()
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let symbolic = GateEngine::new();
+    register_policies(&rt, &symbolic);
+    // Threshold 0 sends every request down the neural path
+    let neural = GateEngine::new().with_neural_threshold(0);
+    register_policies(&rt, &neural);
+    let guard = PromptGuard::new();
+
+    let mut group = c.benchmark_group("verify_hot_path");
+
+    group.bench_function("symbolic_only", |b| {
+        b.iter(|| rt.block_on(symbolic.verify(black_box(request()))))
+    });
+
+    group.bench_function("with_prompt_guard", |b| {
+        b.iter(|| {
+            let analysis = guard.analyze(black_box(PROMPT));
+            black_box(analysis);
+            rt.block_on(symbolic.verify(black_box(request())))
+        })
+    });
+
+    #[cfg(feature = "wasm")]
+    {
+        use agentkern_gate::wasm::WasmPolicyEngine;
+
+        let mut wasm = WasmPolicyEngine::new().unwrap();
+        wasm.load_policy_wat(
+            "risk",
+            r#"(module
+                (import "env" "set_risk_score" (func $risk (param i32)))
+                (func (export "evaluate") (call $risk (i32.const 10))))"#,
+        ).unwrap();
+        let context = serde_json::json!({ "amount": 250 });
+
+        group.bench_function("with_wasm_policies", |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let wasm_result = wasm.evaluate("risk", "read_data", &context).await.unwrap();
+                    black_box(wasm_result);
+                    symbolic.verify(black_box(request())).await
+                })
+            })
+        });
+    }
+
+    group.bench_function("with_neural_scoring", |b| {
+        b.iter(|| rt.block_on(neural.verify(black_box(request()))))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
@@ -6,11 +6,28 @@
 //! - Innovation: Hot-Swap WASM components at runtime without dropping connections
 //!
 //! This implements the Bio-Mimicry pattern for zero-downtime evolution.
+//!
+//! # Hot-Swap Protocol
+//!
+//! Each policy is served by an [`EvaluatorSlot`]. Replacing its evaluator is a
+//! drain-and-replace sequence driven by [`EvaluatorSlot::advance`]:
+//!
+//! 1. **Warming** - the candidate runs warm-up probes; no live traffic.
+//! 2. **Shifting** - live traffic moves to the candidate in steps. A candidate
+//!    error is retried on the stable version, so no request is dropped.
+//! 3. **Draining** - the candidate is active; the old version finishes its
+//!    in-flight requests.
+//! 4. **Complete** - the old version is released.
+//!
+//! If the candidate's error rate exceeds [`SwapConfig::max_error_rate`] at any
+//! point, the swap is rolled back and the stable version keeps serving.
 
 #[cfg(feature = "actors")]
 use actix::prelude::*;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+#[cfg(not(feature = "actors"))]
 use parking_lot::RwLock;
 
 /// Message to evaluate a policy.
@@ -25,19 +42,20 @@ pub struct EvaluatePolicy {
 
 /// Result of policy evaluation.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "actors", derive(MessageResponse))]
 pub struct PolicyResult {
     pub allowed: bool,
     pub risk_score: u8,
     pub latency_us: u64,
 }
 
-/// Message to hot-swap a WASM policy.
+/// Message to hot-swap a policy evaluator.
 #[cfg(feature = "actors")]
 #[derive(Message)]
 #[rtype(result = "Result<(), String>")]
 pub struct HotSwapPolicy {
     pub policy_name: String,
-    pub wasm_bytes: Vec<u8>,
+    pub evaluator: Arc<dyn PolicyEvaluator>,
 }
 
 /// Message to get supervisor status.
@@ -48,10 +66,341 @@ pub struct GetStatus;
 
 /// Supervisor status response.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "actors", derive(MessageResponse))]
 pub struct SupervisorStatus {
     pub active_policies: usize,
     pub total_evaluations: u64,
     pub uptime_secs: u64,
+    /// Progress of in-flight and most recent swaps, per policy
+    pub swaps: Vec<SwapProgress>,
+}
+
+// ============================================================================
+// Hot-swap protocol
+// ============================================================================
+
+/// Policy logic that can be hot-swapped.
+pub trait PolicyEvaluator: Send + Sync {
+    /// Evaluate an action. An `Err` counts towards the version's error rate.
+    fn evaluate(&self, action: &str, context: &serde_json::Value) -> Result<PolicyResult, String>;
+}
+
+/// Evaluator that allows everything (used until real logic is loaded).
+#[derive(Debug, Default)]
+pub struct PassthroughEvaluator;
+
+impl PolicyEvaluator for PassthroughEvaluator {
+    fn evaluate(&self, _action: &str, _context: &serde_json::Value) -> Result<PolicyResult, String> {
+        Ok(PolicyResult {
+            allowed: true,
+            risk_score: 0,
+            latency_us: 50,
+        })
+    }
+}
+
+/// Hot-swap tuning.
+#[derive(Debug, Clone)]
+pub struct SwapConfig {
+    /// Warm-up probes run against the candidate before it takes traffic
+    pub warmup_evaluations: u32,
+    /// Percentage of traffic shifted per `advance()` step
+    pub shift_step_pct: u8,
+    /// Candidate error rate (0.0-1.0) that triggers rollback
+    pub max_error_rate: f64,
+    /// Minimum candidate evaluations before the error rate is judged
+    pub min_samples: u64,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        Self {
+            warmup_evaluations: 10,
+            shift_step_pct: 25,
+            max_error_rate: 0.05,
+            min_samples: 20,
+        }
+    }
+}
+
+/// Phase of a hot-swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapPhase {
+    /// Candidate is running warm-up probes
+    Warming,
+    /// Traffic is moving to the candidate
+    Shifting,
+    /// Candidate is active; old version is finishing in-flight requests
+    Draining,
+    /// Old version released
+    Complete,
+    /// Candidate rejected; old version kept
+    RolledBack,
+}
+
+/// Swap progress reported in [`SupervisorStatus`].
+#[derive(Debug, Clone)]
+pub struct SwapProgress {
+    pub policy_name: String,
+    pub from_version: u64,
+    pub to_version: u64,
+    pub phase: SwapPhase,
+    /// Share of live traffic served by the candidate
+    pub traffic_pct: u8,
+    /// Requests still running on the old version
+    pub old_in_flight: u64,
+    /// Candidate error rate observed so far
+    pub candidate_error_rate: f64,
+}
+
+/// One loaded version of a policy evaluator.
+struct EvaluatorVersion {
+    version: u64,
+    evaluator: Arc<dyn PolicyEvaluator>,
+    in_flight: AtomicU64,
+    evaluations: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl EvaluatorVersion {
+    fn new(version: u64, evaluator: Arc<dyn PolicyEvaluator>) -> Arc<Self> {
+        Arc::new(Self {
+            version,
+            evaluator,
+            in_flight: AtomicU64::new(0),
+            evaluations: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+        })
+    }
+
+    fn run(&self, action: &str, context: &serde_json::Value) -> Result<PolicyResult, String> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let result = self.evaluator.evaluate(action, context);
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        if result.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    fn error_rate(&self) -> f64 {
+        let evaluations = self.evaluations.load(Ordering::Relaxed);
+        if evaluations == 0 {
+            return 0.0;
+        }
+        self.errors.load(Ordering::Relaxed) as f64 / evaluations as f64
+    }
+}
+
+/// A swap in progress.
+struct Swap {
+    candidate: Arc<EvaluatorVersion>,
+    phase: SwapPhase,
+    traffic_pct: u8,
+    /// Old version, set once the candidate becomes active
+    draining: Option<Arc<EvaluatorVersion>>,
+}
+
+/// Per-policy slot that routes traffic between evaluator versions.
+pub struct EvaluatorSlot {
+    name: String,
+    active: Arc<EvaluatorVersion>,
+    swap: Option<Swap>,
+    last_swap: Option<SwapProgress>,
+    next_version: u64,
+    routed: AtomicU64,
+    config: SwapConfig,
+}
+
+impl EvaluatorSlot {
+    /// Create a slot serving `evaluator` as version 1.
+    pub fn new(name: impl Into<String>, evaluator: Arc<dyn PolicyEvaluator>, config: SwapConfig) -> Self {
+        Self {
+            name: name.into(),
+            active: EvaluatorVersion::new(1, evaluator),
+            swap: None,
+            last_swap: None,
+            next_version: 2,
+            routed: AtomicU64::new(0),
+            config,
+        }
+    }
+
+    /// Version currently serving stable traffic.
+    pub fn active_version(&self) -> u64 {
+        self.active.version
+    }
+
+    /// Is a swap in progress?
+    pub fn is_swapping(&self) -> bool {
+        self.swap.is_some()
+    }
+
+    /// Begin replacing the evaluator. Fails if a swap is already running.
+    pub fn begin_swap(&mut self, evaluator: Arc<dyn PolicyEvaluator>) -> Result<u64, String> {
+        if self.swap.is_some() {
+            return Err(format!("Swap already in progress for policy '{}'", self.name));
+        }
+
+        let version = self.next_version;
+        self.next_version += 1;
+        self.swap = Some(Swap {
+            candidate: EvaluatorVersion::new(version, evaluator),
+            phase: SwapPhase::Warming,
+            traffic_pct: 0,
+            draining: None,
+        });
+        tracing::info!(policy = %self.name, from = self.active.version, to = version, "Hot-swap started");
+        Ok(version)
+    }
+
+    /// Evaluate on the version chosen by the current traffic split.
+    ///
+    /// A failing candidate is retried on the stable version.
+    pub fn evaluate(&self, action: &str, context: &serde_json::Value) -> PolicyResult {
+        let stable = self.stable();
+        let chosen = match &self.swap {
+            Some(swap) if swap.phase == SwapPhase::Shifting => {
+                let n = self.routed.fetch_add(1, Ordering::Relaxed) % 100;
+                if n < swap.traffic_pct as u64 {
+                    Arc::clone(&swap.candidate)
+                } else {
+                    Arc::clone(&stable)
+                }
+            }
+            _ => Arc::clone(&self.active),
+        };
+
+        match chosen.run(action, context) {
+            Ok(result) => result,
+            Err(err) if !Arc::ptr_eq(&chosen, &stable) => {
+                tracing::warn!(policy = %self.name, version = chosen.version, %err, "Candidate failed, retrying on stable version");
+                stable.run(action, context).unwrap_or_else(|_| deny_result())
+            }
+            Err(err) => {
+                tracing::error!(policy = %self.name, version = chosen.version, %err, "Policy evaluation failed");
+                deny_result()
+            }
+        }
+    }
+
+    /// Drive the swap one step forward. Returns the current progress.
+    pub fn advance(&mut self) -> Option<SwapProgress> {
+        let Some(mut swap) = self.swap.take() else {
+            return self.last_swap.clone();
+        };
+
+        match swap.phase {
+            SwapPhase::Warming => {
+                for _ in 0..self.config.warmup_evaluations {
+                    let _ = swap.candidate.run("__warmup__", &serde_json::Value::Null);
+                }
+                if swap.candidate.error_rate() > self.config.max_error_rate {
+                    return self.rollback(swap);
+                }
+                swap.phase = SwapPhase::Shifting;
+                swap.traffic_pct = self.config.shift_step_pct.clamp(1, 100);
+            }
+            SwapPhase::Shifting => {
+                if self.candidate_unhealthy(&swap) {
+                    return self.rollback(swap);
+                }
+                swap.traffic_pct = swap.traffic_pct.saturating_add(self.config.shift_step_pct.max(1)).min(100);
+                if swap.traffic_pct == 100 {
+                    swap.phase = SwapPhase::Draining;
+                    let old = std::mem::replace(&mut self.active, Arc::clone(&swap.candidate));
+                    swap.draining = Some(old);
+                }
+            }
+            SwapPhase::Draining => {
+                if self.candidate_unhealthy(&swap) {
+                    return self.rollback(swap);
+                }
+                let in_flight = swap.draining.as_ref().map(|v| v.in_flight.load(Ordering::SeqCst)).unwrap_or(0);
+                if in_flight == 0 {
+                    swap.phase = SwapPhase::Complete;
+                    let progress = self.progress(&swap);
+                    tracing::info!(policy = %self.name, version = self.active.version, "Hot-swap complete");
+                    self.last_swap = Some(progress.clone());
+                    return Some(progress);
+                }
+            }
+            SwapPhase::Complete | SwapPhase::RolledBack => {}
+        }
+
+        let progress = self.progress(&swap);
+        self.last_swap = Some(progress.clone());
+        self.swap = Some(swap);
+        Some(progress)
+    }
+
+    /// Latest swap progress, if any swap has run.
+    pub fn progress_snapshot(&self) -> Option<SwapProgress> {
+        match &self.swap {
+            Some(swap) => Some(self.progress(swap)),
+            None => self.last_swap.clone(),
+        }
+    }
+
+    /// The version that is known-good during a swap.
+    fn stable(&self) -> Arc<EvaluatorVersion> {
+        match &self.swap {
+            Some(Swap { draining: Some(old), .. }) => Arc::clone(old),
+            _ => Arc::clone(&self.active),
+        }
+    }
+
+    fn candidate_unhealthy(&self, swap: &Swap) -> bool {
+        swap.candidate.evaluations.load(Ordering::Relaxed) >= self.config.min_samples
+            && swap.candidate.error_rate() > self.config.max_error_rate
+    }
+
+    fn rollback(&mut self, mut swap: Swap) -> Option<SwapProgress> {
+        if let Some(old) = swap.draining.take() {
+            self.active = old;
+        }
+        swap.phase = SwapPhase::RolledBack;
+        swap.traffic_pct = 0;
+        tracing::warn!(
+            policy = %self.name,
+            version = swap.candidate.version,
+            error_rate = swap.candidate.error_rate(),
+            "Hot-swap rolled back"
+        );
+        let progress = self.progress(&swap);
+        self.last_swap = Some(progress.clone());
+        Some(progress)
+    }
+
+    fn progress(&self, swap: &Swap) -> SwapProgress {
+        let old = swap.draining.as_ref().unwrap_or(&self.active);
+        SwapProgress {
+            policy_name: self.name.clone(),
+            from_version: old.version,
+            to_version: swap.candidate.version,
+            phase: swap.phase,
+            traffic_pct: swap.traffic_pct,
+            old_in_flight: old.in_flight.load(Ordering::SeqCst),
+            candidate_error_rate: swap.candidate.error_rate(),
+        }
+    }
+}
+
+fn deny_result() -> PolicyResult {
+    PolicyResult {
+        allowed: false,
+        risk_score: 100,
+        latency_us: 0,
+    }
+}
+
+fn passthrough_result() -> PolicyResult {
+    PolicyResult {
+        allowed: true,
+        risk_score: 0,
+        latency_us: 50,
+    }
 }
 
 /// Policy Cell - a supervised unit of policy logic.
@@ -93,7 +442,8 @@ impl PolicyCell {
 /// Gate Supervisor - manages policy cells with hot-swap capability.
 #[cfg(feature = "actors")]
 pub struct GateSupervisor {
-    cells: HashMap<String, Addr<PolicyCellActor>>,
+    slots: HashMap<String, EvaluatorSlot>,
+    swap_config: SwapConfig,
     start_time: std::time::Instant,
     total_evaluations: u64,
 }
@@ -102,11 +452,18 @@ pub struct GateSupervisor {
 impl GateSupervisor {
     pub fn new() -> Self {
         Self {
-            cells: HashMap::new(),
+            slots: HashMap::new(),
+            swap_config: SwapConfig::default(),
             start_time: std::time::Instant::now(),
             total_evaluations: 0,
         }
     }
+
+    /// Set hot-swap tuning.
+    pub fn with_swap_config(mut self, config: SwapConfig) -> Self {
+        self.swap_config = config;
+        self
+    }
 }
 
 #[cfg(feature = "actors")]
@@ -120,8 +477,13 @@ impl Default for GateSupervisor {
 impl Actor for GateSupervisor {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         tracing::info!("GateSupervisor started - Dynamic Supervision active");
+        ctx.run_interval(std::time::Duration::from_secs(1), |act, _ctx| {
+            for slot in act.slots.values_mut() {
+                slot.advance();
+            }
+        });
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
@@ -142,13 +504,10 @@ impl Handler<EvaluatePolicy> for GateSupervisor {
 
     fn handle(&mut self, msg: EvaluatePolicy, _ctx: &mut Self::Context) -> Self::Result {
         self.total_evaluations += 1;
-        
-        // For now, return default result
-        // In production: route to appropriate PolicyCellActor
-        PolicyResult {
-            allowed: true,
-            risk_score: 0,
-            latency_us: 50,
+
+        match self.slots.get(&msg.policy_name) {
+            Some(slot) => slot.evaluate(&msg.action, &msg.context),
+            None => passthrough_result(),
         }
     }
 }
@@ -157,18 +516,17 @@ impl Handler<EvaluatePolicy> for GateSupervisor {
 impl Handler<HotSwapPolicy> for GateSupervisor {
     type Result = Result<(), String>;
 
-    fn handle(&mut self, msg: HotSwapPolicy, ctx: &mut Self::Context) -> Self::Result {
-        tracing::info!(
-            policy = %msg.policy_name,
-            bytes = msg.wasm_bytes.len(),
-            "Hot-swapping policy WASM module"
-        );
-        
-        // Create or replace policy cell
-        let cell_actor = PolicyCellActor::new(msg.policy_name.clone()).start();
-        self.cells.insert(msg.policy_name, cell_actor);
-        
-        Ok(())
+    fn handle(&mut self, msg: HotSwapPolicy, _ctx: &mut Self::Context) -> Self::Result {
+        tracing::info!(policy = %msg.policy_name, "Hot-swapping policy evaluator");
+
+        match self.slots.get_mut(&msg.policy_name) {
+            Some(slot) => slot.begin_swap(msg.evaluator).map(|_| ()),
+            None => {
+                let slot = EvaluatorSlot::new(msg.policy_name.clone(), msg.evaluator, self.swap_config.clone());
+                self.slots.insert(msg.policy_name, slot);
+                Ok(())
+            }
+        }
     }
 }
 
@@ -178,9 +536,10 @@ impl Handler<GetStatus> for GateSupervisor {
 
     fn handle(&mut self, _msg: GetStatus, _ctx: &mut Self::Context) -> Self::Result {
         SupervisorStatus {
-            active_policies: self.cells.len(),
+            active_policies: self.slots.len(),
             total_evaluations: self.total_evaluations,
             uptime_secs: self.start_time.elapsed().as_secs(),
+            swaps: self.slots.values().filter_map(|s| s.progress_snapshot()).collect(),
         }
     }
 }
@@ -218,9 +577,10 @@ impl Supervised for PolicyCellActor {
 
 #[cfg(not(feature = "actors"))]
 pub struct GateSupervisor {
-    policies: Arc<RwLock<HashMap<String, ()>>>,
+    policies: Arc<RwLock<HashMap<String, EvaluatorSlot>>>,
+    swap_config: SwapConfig,
     start_time: std::time::Instant,
-    total_evaluations: std::sync::atomic::AtomicU64,
+    total_evaluations: AtomicU64,
 }
 
 #[cfg(not(feature = "actors"))]
@@ -228,25 +588,67 @@ impl GateSupervisor {
     pub fn new() -> Self {
         Self {
             policies: Arc::new(RwLock::new(HashMap::new())),
+            swap_config: SwapConfig::default(),
             start_time: std::time::Instant::now(),
-            total_evaluations: std::sync::atomic::AtomicU64::new(0),
+            total_evaluations: AtomicU64::new(0),
         }
     }
 
-    pub fn evaluate(&self, _policy: &str, _action: &str, _context: &serde_json::Value) -> PolicyResult {
-        self.total_evaluations.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        PolicyResult {
-            allowed: true,
-            risk_score: 0,
-            latency_us: 50,
+    /// Set hot-swap tuning.
+    pub fn with_swap_config(mut self, config: SwapConfig) -> Self {
+        self.swap_config = config;
+        self
+    }
+
+    /// Load an evaluator for a policy, starting a hot-swap if one is
+    /// already serving.
+    pub fn hot_swap(&self, policy: &str, evaluator: Arc<dyn PolicyEvaluator>) -> Result<(), String> {
+        let mut policies = self.policies.write();
+        match policies.get_mut(policy) {
+            Some(slot) => slot.begin_swap(evaluator).map(|_| ()),
+            None => {
+                policies.insert(
+                    policy.to_string(),
+                    EvaluatorSlot::new(policy, evaluator, self.swap_config.clone()),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Drive all in-progress swaps one step.
+    pub fn advance_swaps(&self) {
+        for slot in self.policies.write().values_mut() {
+            slot.advance();
+        }
+    }
+
+    /// Spawn a task that advances swaps on a fixed interval.
+    pub fn spawn_swap_driver(self: Arc<Self>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.advance_swaps();
+            }
+        })
+    }
+
+    pub fn evaluate(&self, policy: &str, action: &str, context: &serde_json::Value) -> PolicyResult {
+        self.total_evaluations.fetch_add(1, Ordering::Relaxed);
+        match self.policies.read().get(policy) {
+            Some(slot) => slot.evaluate(action, context),
+            None => passthrough_result(),
         }
     }
 
     pub fn status(&self) -> SupervisorStatus {
+        let policies = self.policies.read();
         SupervisorStatus {
-            active_policies: self.policies.read().len(),
-            total_evaluations: self.total_evaluations.load(std::sync::atomic::Ordering::Relaxed),
+            active_policies: policies.len(),
+            total_evaluations: self.total_evaluations.load(Ordering::Relaxed),
             uptime_secs: self.start_time.elapsed().as_secs(),
+            swaps: policies.values().filter_map(|s| s.progress_snapshot()).collect(),
         }
     }
 }
@@ -262,11 +664,31 @@ impl Default for GateSupervisor {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_supervisor_creation() {
-        let supervisor = GateSupervisor::new();
-        let status = supervisor.status();
-        assert_eq!(status.active_policies, 0);
+    /// Evaluator that fails every request.
+    struct FailingEvaluator;
+
+    impl PolicyEvaluator for FailingEvaluator {
+        fn evaluate(&self, _action: &str, _context: &serde_json::Value) -> Result<PolicyResult, String> {
+            Err("trap".to_string())
+        }
+    }
+
+    /// Evaluator that denies every request.
+    struct DenyEvaluator;
+
+    impl PolicyEvaluator for DenyEvaluator {
+        fn evaluate(&self, _action: &str, _context: &serde_json::Value) -> Result<PolicyResult, String> {
+            Ok(deny_result())
+        }
+    }
+
+    fn config() -> SwapConfig {
+        SwapConfig {
+            warmup_evaluations: 2,
+            shift_step_pct: 50,
+            max_error_rate: 0.1,
+            min_samples: 5,
+        }
     }
 
     #[test]
@@ -279,4 +701,77 @@ mod tests {
         assert!(result.allowed);
         assert_eq!(result.risk_score, 25);
     }
+
+    #[test]
+    fn test_swap_completes() {
+        let mut slot = EvaluatorSlot::new("p", Arc::new(PassthroughEvaluator), config());
+        assert_eq!(slot.begin_swap(Arc::new(DenyEvaluator)).unwrap(), 2);
+        assert!(slot.begin_swap(Arc::new(DenyEvaluator)).is_err());
+
+        // Warming: candidate takes no live traffic
+        assert!(slot.evaluate("read", &serde_json::Value::Null).allowed);
+
+        assert_eq!(slot.advance().unwrap().phase, SwapPhase::Shifting);
+        let served: Vec<bool> = (0..100).map(|_| slot.evaluate("read", &serde_json::Value::Null).allowed).collect();
+        assert_eq!(served.iter().filter(|allowed| !**allowed).count(), 50);
+
+        assert_eq!(slot.advance().unwrap().phase, SwapPhase::Draining);
+        assert_eq!(slot.active_version(), 2);
+        assert_eq!(slot.advance().unwrap().phase, SwapPhase::Complete);
+        assert!(!slot.is_swapping());
+        assert!(!slot.evaluate("read", &serde_json::Value::Null).allowed);
+    }
+
+    #[test]
+    fn test_failing_candidate_rolls_back_without_drops() {
+        let mut slot = EvaluatorSlot::new("p", Arc::new(PassthroughEvaluator), config());
+        slot.begin_swap(Arc::new(FailingEvaluator)).unwrap();
+
+        let progress = slot.advance().unwrap();
+        assert_eq!(progress.phase, SwapPhase::RolledBack);
+        assert_eq!(slot.active_version(), 1);
+        assert!(slot.evaluate("read", &serde_json::Value::Null).allowed);
+    }
+
+    #[test]
+    fn test_candidate_errors_retried_on_stable() {
+        let cfg = SwapConfig { warmup_evaluations: 0, ..config() };
+        let mut slot = EvaluatorSlot::new("p", Arc::new(PassthroughEvaluator), cfg);
+        slot.begin_swap(Arc::new(FailingEvaluator)).unwrap();
+        slot.advance();
+
+        // Every request succeeds even though half are routed to the failing candidate
+        assert!((0..20).all(|_| slot.evaluate("read", &serde_json::Value::Null).allowed));
+
+        assert_eq!(slot.advance().unwrap().phase, SwapPhase::RolledBack);
+        assert_eq!(slot.active_version(), 1);
+    }
+
+    #[cfg(not(feature = "actors"))]
+    #[test]
+    fn test_supervisor_creation() {
+        let supervisor = GateSupervisor::new();
+        let status = supervisor.status();
+        assert_eq!(status.active_policies, 0);
+        assert!(status.swaps.is_empty());
+    }
+
+    #[cfg(not(feature = "actors"))]
+    #[test]
+    fn test_supervisor_reports_swap_progress() {
+        let supervisor = GateSupervisor::new().with_swap_config(config());
+        supervisor.hot_swap("p", Arc::new(PassthroughEvaluator)).unwrap();
+        supervisor.hot_swap("p", Arc::new(DenyEvaluator)).unwrap();
+
+        supervisor.advance_swaps();
+        let status = supervisor.status();
+        assert_eq!(status.swaps.len(), 1);
+        assert_eq!(status.swaps[0].phase, SwapPhase::Shifting);
+        assert_eq!(status.swaps[0].traffic_pct, 50);
+
+        supervisor.advance_swaps();
+        supervisor.advance_swaps();
+        assert_eq!(supervisor.status().swaps[0].phase, SwapPhase::Complete);
+        assert!(!supervisor.evaluate("p", "read", &serde_json::Value::Null).allowed);
+    }
 }
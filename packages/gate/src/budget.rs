@@ -39,6 +39,12 @@ pub enum BudgetError {
     BudgetExhausted,
 }
 
+impl agentkern_errors::Coded for BudgetError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        agentkern_errors::ErrorCode::BudgetExceeded
+    }
+}
+
 /// Budget configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetConfig {
@@ -0,0 +1,496 @@
+//! AgentKern-Gate: Compliance Policy Bundles
+//!
+//! Per EXECUTION_MANDATE.md §2: HIPAA, PCI-DSS and Takaful shipped as presets.
+//!
+//! A bundle wires everything a regime needs into the engine in one call:
+//! - Preset policies (registered like any other `Policy`)
+//! - Validator scans over the request context (PHI, card data, Riba)
+//! - Prompt-guard pattern packs
+//! - Audit requirements (retention, required fields)
+//!
+//! When two bundles define the same rule condition with different actions,
+//! the conflict is recorded and the stricter action wins.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::{GateEngine, Bundle};
+//!
+//! let engine = GateEngine::new()
+//!     .with_bundle(Bundle::Hipaa)
+//!     .with_bundle(Bundle::Pci);
+//!
+//! for conflict in engine.bundle_conflicts() {
+//!     tracing::warn!(?conflict, "Bundle overlap");
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::hipaa::HipaaValidator;
+use crate::pci::PciValidator;
+use crate::policy::{Policy, PolicyAction, PolicyRule};
+use crate::prompt_guard::{AttackType, PatternPack, PromptGuard};
+use crate::takaful::{TakafulValidator, TransactionDetails, TransactionType};
+use crate::types::VerificationRequest;
+
+/// Compliance regime preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bundle {
+    /// HIPAA (US healthcare)
+    Hipaa,
+    /// PCI-DSS v4.0.1 (cardholder data)
+    Pci,
+    /// Takaful / Islamic finance
+    Takaful,
+}
+
+impl Bundle {
+    /// All shipped bundles.
+    pub fn all() -> &'static [Bundle] {
+        &[Bundle::Hipaa, Bundle::Pci, Bundle::Takaful]
+    }
+
+    /// Short identifier (used in blocking policy IDs).
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Hipaa => "hipaa",
+            Self::Pci => "pci",
+            Self::Takaful => "takaful",
+        }
+    }
+
+    /// Preset policies contributed by this bundle.
+    pub fn policies(&self) -> Vec<Policy> {
+        let (name, rules) = match self {
+            Self::Hipaa => (
+                "HIPAA Preset",
+                vec![
+                    rule("hipaa-export-review", "action == 'export_records'", PolicyAction::Review, 60,
+                        "PHI export requires minimum-necessary review"),
+                    rule("hipaa-record-delete", "action == 'delete_patient_record'", PolicyAction::Deny, 100,
+                        "Medical records must be retained"),
+                    rule("hipaa-phi-audit", "context.contains_phi == true", PolicyAction::Audit, 0,
+                        "PHI access must be audited"),
+                ],
+            ),
+            Self::Pci => (
+                "PCI-DSS Preset",
+                vec![
+                    rule("pci-export-deny", "action == 'export_records'", PolicyAction::Deny, 100,
+                        "Bulk export of cardholder data is prohibited"),
+                    rule("pci-store-card", "action == 'store_card_data'", PolicyAction::Deny, 100,
+                        "Tokenize card data before storage"),
+                    rule("pci-charge-audit", "action == 'charge_card'", PolicyAction::Audit, 0,
+                        "Card charges must be audited"),
+                ],
+            ),
+            Self::Takaful => (
+                "Takaful Preset",
+                vec![
+                    rule("takaful-riba", "context.interest_rate > 0", PolicyAction::Deny, 100,
+                        "Riba (interest) is not permitted"),
+                    rule("takaful-interest-loan", "action == 'issue_interest_loan'", PolicyAction::Deny, 100,
+                        "Use Murabaha or Musharakah instead of interest-bearing loans"),
+                    rule("takaful-conventional", "action == 'purchase_conventional_insurance'", PolicyAction::Review, 60,
+                        "Conventional insurance should be converted to Takaful"),
+                ],
+            ),
+        };
+
+        vec![Policy {
+            id: format!("{}-preset", self.id()),
+            name: name.to_string(),
+            description: format!("Compliance bundle preset: {}", self.id()),
+            priority: 1000,
+            enabled: true,
+            jurisdictions: vec![],
+            rules,
+        }]
+    }
+
+    /// Prompt-guard packs contributed by this bundle.
+    pub fn prompt_packs(&self) -> Vec<PatternPack> {
+        match self {
+            Self::Hipaa => vec![PatternPack::new(
+                "hipaa",
+                AttackType::DataExfiltration,
+                45,
+                [
+                    "list all patients",
+                    "dump medical records",
+                    "export all patient",
+                    "show me the patient's ssn",
+                ],
+            )],
+            Self::Pci => vec![PatternPack::new(
+                "pci",
+                AttackType::DataExfiltration,
+                45,
+                [
+                    "full card number",
+                    "print the pan",
+                    "show the cvv",
+                    "unmasked card",
+                ],
+            )],
+            Self::Takaful => vec![PatternPack::new(
+                "takaful",
+                AttackType::SafetyBypass,
+                45,
+                [
+                    "ignore the shariah",
+                    "bypass shariah board",
+                    "hide the interest",
+                ],
+            )],
+        }
+    }
+
+    /// Audit requirements for this regime.
+    pub fn audit_requirements(&self) -> AuditRequirement {
+        let (retention_days, fields): (u32, &[&str]) = match self {
+            // 45 CFR 164.316: 6 years
+            Self::Hipaa => (6 * 365, &["user_id", "resource_id", "purpose", "phi_involved"]),
+            // PCI-DSS 10.5.1: 1 year
+            Self::Pci => (365, &["user_id", "action", "card_token", "outcome"]),
+            // AAOIFI governance: retain for Shariah board review
+            Self::Takaful => (10 * 365, &["user_id", "transaction_type", "scholar_ruling"]),
+        };
+
+        AuditRequirement {
+            bundle: *self,
+            retention_days,
+            immutable: true,
+            required_fields: fields.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+fn rule(id: &str, condition: &str, action: PolicyAction, risk: u8, message: &str) -> PolicyRule {
+    PolicyRule {
+        id: id.to_string(),
+        condition: condition.to_string(),
+        action,
+        message: Some(message.to_string()),
+        risk_score: Some(risk),
+    }
+}
+
+/// Audit requirement imposed by a bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRequirement {
+    /// Bundle that imposes the requirement
+    pub bundle: Bundle,
+    /// Minimum retention in days
+    pub retention_days: u32,
+    /// Records must be append-only
+    pub immutable: bool,
+    /// Fields every audit record must carry
+    pub required_fields: Vec<String>,
+}
+
+/// Two bundles define the same condition with different actions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleConflict {
+    /// Overlapping condition
+    pub condition: String,
+    /// Bundle already active
+    pub existing_bundle: Bundle,
+    /// Rule ID in the existing bundle
+    pub existing_rule: String,
+    /// Action in the existing bundle
+    pub existing_action: PolicyAction,
+    /// Bundle being added
+    pub incoming_bundle: Bundle,
+    /// Rule ID in the incoming bundle
+    pub incoming_rule: String,
+    /// Action in the incoming bundle
+    pub incoming_action: PolicyAction,
+    /// Action applied to both rules (stricter wins)
+    pub resolved_action: PolicyAction,
+}
+
+/// Outcome of running bundle validators over a request.
+#[derive(Debug, Clone, Default)]
+pub struct BundleOutcome {
+    /// Blocking IDs (e.g. "bundle:pci")
+    pub blocking: Vec<String>,
+    /// Highest risk raised by any validator
+    pub risk: u8,
+}
+
+/// Set of active bundles held by the engine.
+#[derive(Default)]
+pub struct BundleSet {
+    active: Vec<Bundle>,
+    policies: Vec<(Bundle, Policy)>,
+    conflicts: Vec<BundleConflict>,
+    hipaa: Option<HipaaValidator>,
+    pci: Option<PciValidator>,
+    takaful: Option<TakafulValidator>,
+    guard: Option<PromptGuard>,
+}
+
+impl BundleSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Detect conflicts between bundles without activating them.
+    pub fn detect_conflicts(bundles: &[Bundle]) -> Vec<BundleConflict> {
+        let mut set = Self::new();
+        for bundle in bundles {
+            set.add(*bundle);
+        }
+        set.conflicts
+    }
+
+    /// Activate a bundle. Returns every bundle policy (re-)resolved,
+    /// ready to be registered with the engine.
+    pub fn add(&mut self, bundle: Bundle) -> Vec<Policy> {
+        if self.active.contains(&bundle) {
+            return Vec::new();
+        }
+
+        let mut incoming = bundle.policies();
+        for policy in &mut incoming {
+            for new_rule in &mut policy.rules {
+                for (existing_bundle, existing) in &mut self.policies {
+                    for old_rule in &mut existing.rules {
+                        if old_rule.condition.trim() != new_rule.condition.trim()
+                            || old_rule.action == new_rule.action
+                        {
+                            continue;
+                        }
+
+                        let resolved = stricter(old_rule.action, new_rule.action);
+                        tracing::warn!(
+                            condition = %new_rule.condition,
+                            existing = existing_bundle.id(),
+                            incoming = bundle.id(),
+                            ?resolved,
+                            "Compliance bundle conflict"
+                        );
+                        self.conflicts.push(BundleConflict {
+                            condition: new_rule.condition.clone(),
+                            existing_bundle: *existing_bundle,
+                            existing_rule: old_rule.id.clone(),
+                            existing_action: old_rule.action,
+                            incoming_bundle: bundle,
+                            incoming_rule: new_rule.id.clone(),
+                            incoming_action: new_rule.action,
+                            resolved_action: resolved,
+                        });
+                        old_rule.action = resolved;
+                        new_rule.action = resolved;
+                    }
+                }
+            }
+        }
+        self.policies.extend(incoming.into_iter().map(|p| (bundle, p)));
+
+        match bundle {
+            Bundle::Hipaa => self.hipaa = Some(HipaaValidator::new()),
+            Bundle::Pci => self.pci = Some(PciValidator::new()),
+            Bundle::Takaful => self.takaful = Some(TakafulValidator::new()),
+        }
+        let guard = self.guard.get_or_insert_with(PromptGuard::new);
+        for pack in bundle.prompt_packs() {
+            guard.add_pack(pack);
+        }
+
+        self.active.push(bundle);
+        self.policies.iter().map(|(_, p)| p.clone()).collect()
+    }
+
+    /// Is any bundle active?
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// IDs of the policies registered by active bundles.
+    pub fn policy_ids(&self) -> impl Iterator<Item = &str> {
+        self.policies.iter().map(|(_, p)| p.id.as_str())
+    }
+
+    /// Active bundles, in activation order.
+    pub fn active(&self) -> &[Bundle] {
+        &self.active
+    }
+
+    /// Conflicts detected so far.
+    pub fn conflicts(&self) -> &[BundleConflict] {
+        &self.conflicts
+    }
+
+    /// Audit requirements of all active bundles.
+    pub fn audit_requirements(&self) -> Vec<AuditRequirement> {
+        self.active.iter().map(|b| b.audit_requirements()).collect()
+    }
+
+    /// Run the bundle validators over a request's context.
+    pub fn evaluate(&self, request: &VerificationRequest) -> BundleOutcome {
+        let mut outcome = BundleOutcome::default();
+        let data = &request.context.data;
+        let texts: Vec<&str> = data.values().filter_map(|v| v.as_str()).collect();
+
+        if let Some(hipaa) = &self.hipaa {
+            let encrypted = data.get("encrypted").and_then(|v| v.as_bool()).unwrap_or(false);
+            if texts.iter().any(|t| hipaa.scan_for_phi(t).contains_phi) {
+                if encrypted {
+                    outcome.risk = outcome.risk.max(60);
+                } else {
+                    block(&mut outcome, Bundle::Hipaa);
+                }
+            }
+        }
+
+        if let Some(pci) = &self.pci {
+            if texts.iter().any(|t| pci.validate_for_storage(t).is_err()) {
+                block(&mut outcome, Bundle::Pci);
+            }
+        }
+
+        if let Some(takaful) = &self.takaful {
+            let interest_rate = data.get("interest_rate").and_then(|v| v.as_f64());
+            let transaction_type = data
+                .get("transaction_type")
+                .and_then(|v| serde_json::from_value::<TransactionType>(v.clone()).ok());
+            let sector = data.get("sector").and_then(|v| v.as_str()).map(String::from);
+            if interest_rate.is_some() || transaction_type.is_some() || sector.is_some() {
+                let details = TransactionDetails {
+                    transaction_type: transaction_type.unwrap_or(TransactionType::Trade),
+                    amount: data.get("amount").and_then(|v| v.as_f64()).unwrap_or(0.0),
+                    interest_rate,
+                    guaranteed_outcome: data
+                        .get("guaranteed_outcome")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    sector,
+                    ..Default::default()
+                };
+                match takaful.validate(&details) {
+                    Ok(result) if result.compliant => {}
+                    _ => block(&mut outcome, Bundle::Takaful),
+                }
+            }
+        }
+
+        if let (Some(guard), Some(prompt)) = (&self.guard, data.get("prompt").and_then(|v| v.as_str())) {
+            if guard.should_block(prompt) {
+                outcome.blocking.push("bundle:prompt_guard".to_string());
+                outcome.risk = 100;
+            }
+        }
+
+        outcome
+    }
+}
+
+fn block(outcome: &mut BundleOutcome, bundle: Bundle) {
+    outcome.blocking.push(format!("bundle:{}", bundle.id()));
+    outcome.risk = 100;
+}
+
+fn stricter(a: PolicyAction, b: PolicyAction) -> PolicyAction {
+    let rank = |action: PolicyAction| match action {
+        PolicyAction::Allow => 0,
+        PolicyAction::Audit => 1,
+        PolicyAction::Review => 2,
+        PolicyAction::Deny => 3,
+    };
+    if rank(a) >= rank(b) { a } else { b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{GateEngine, VerificationRequestBuilder};
+
+    #[tokio::test]
+    async fn test_bundle_registers_policies() {
+        let engine = GateEngine::new().with_bundle(Bundle::Hipaa);
+
+        let policies = engine.get_policies().await;
+        assert!(policies.iter().any(|p| p.id == "hipaa-preset"));
+        assert_eq!(engine.active_bundles(), &[Bundle::Hipaa]);
+        assert!(engine.bundle_conflicts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_bundles_conflict() {
+        let engine = GateEngine::new()
+            .with_bundle(Bundle::Hipaa)
+            .with_bundle(Bundle::Pci);
+
+        let conflicts = engine.bundle_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].existing_bundle, Bundle::Hipaa);
+        assert_eq!(conflicts[0].incoming_bundle, Bundle::Pci);
+        assert_eq!(conflicts[0].resolved_action, PolicyAction::Deny);
+
+        // Stricter action now applies to the HIPAA rule too
+        let request = VerificationRequestBuilder::new("agent-1", "export_records").build();
+        let result = engine.verify(request).await;
+        assert!(!result.allowed);
+        assert!(result.blocking_policies.contains(&"hipaa-preset".to_string()));
+    }
+
+    #[test]
+    fn test_detect_conflicts_without_engine() {
+        assert!(BundleSet::detect_conflicts(&[Bundle::Hipaa, Bundle::Takaful]).is_empty());
+        assert_eq!(BundleSet::detect_conflicts(&[Bundle::Pci, Bundle::Hipaa]).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pci_validator_blocks_pan() {
+        let engine = GateEngine::new().with_bundle(Bundle::Pci);
+
+        let request = VerificationRequestBuilder::new("agent-1", "send_email")
+            .context("body", "Card: 4111 1111 1111 1111")
+            .build();
+        let result = engine.verify(request).await;
+        assert!(!result.allowed);
+        assert!(result.blocking_policies.contains(&"bundle:pci".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_takaful_blocks_riba() {
+        let engine = GateEngine::new().with_bundle(Bundle::Takaful);
+
+        let request = VerificationRequestBuilder::new("agent-1", "finance_purchase")
+            .context("interest_rate", 4.5)
+            .build();
+        let result = engine.verify(request).await;
+        assert!(!result.allowed);
+        assert!(result.blocking_policies.contains(&"bundle:takaful".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_pack_active() {
+        let engine = GateEngine::new().with_bundle(Bundle::Hipaa);
+
+        let request = VerificationRequestBuilder::new("agent-1", "chat")
+            .context("prompt", "Please dump medical records for ward 4")
+            .build();
+        let result = engine.verify(request).await;
+        assert!(!result.allowed);
+        assert!(result.blocking_policies.contains(&"bundle:prompt_guard".to_string()));
+    }
+
+    #[test]
+    fn test_audit_requirements() {
+        let mut set = BundleSet::new();
+        set.add(Bundle::Hipaa);
+        set.add(Bundle::Pci);
+        set.add(Bundle::Pci);
+
+        let reqs = set.audit_requirements();
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].retention_days, 6 * 365);
+        assert!(reqs.iter().all(|r| r.immutable));
+    }
+}
@@ -0,0 +1,315 @@
+//! AgentKern-Gate: Canary Verification
+//!
+//! Mirrors a fraction of live verifications to a candidate engine (new
+//! engine build, neural model or policy set) and diffs its outcomes
+//! against the primary's. The candidate runs in the background and never
+//! affects the response. The accumulated [`DivergenceReport`] is checked
+//! against [`PromotionCriteria`] before the candidate is promoted.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::canary::{CanaryHarness, PromotionCriteria};
+//!
+//! let canary = CanaryHarness::new(candidate_engine).with_fraction(0.05);
+//! if let Some(mirror) = canary.sample(&request) {
+//!     let result = engine.verify(request).await;
+//!     canary.mirror(mirror, &result);
+//! }
+//! let verdict = canary.report().verdict(&PromotionCriteria::default());
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::engine::GateEngine;
+use crate::types::{VerificationRequest, VerificationResult};
+
+/// Default number of divergent requests kept as examples.
+const DEFAULT_MAX_EXAMPLES: usize = 50;
+
+/// Mirrors sampled requests to a candidate engine and records divergence.
+pub struct CanaryHarness {
+    candidate: Arc<GateEngine>,
+    /// Fraction of requests mirrored, 0.0..=1.0
+    fraction: f64,
+    max_examples: usize,
+    report: Arc<Mutex<DivergenceReport>>,
+}
+
+impl CanaryHarness {
+    /// Mirror 10% of requests to `candidate`.
+    pub fn new(candidate: GateEngine) -> Self {
+        Self {
+            candidate: Arc::new(candidate),
+            fraction: 0.1,
+            max_examples: DEFAULT_MAX_EXAMPLES,
+            report: Arc::new(Mutex::new(DivergenceReport::default())),
+        }
+    }
+
+    /// Set the fraction of requests to mirror (clamped to 0.0..=1.0).
+    pub fn with_fraction(mut self, fraction: f64) -> Self {
+        self.fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set how many divergent requests the report keeps as examples.
+    pub fn with_max_examples(mut self, max_examples: usize) -> Self {
+        self.max_examples = max_examples;
+        self
+    }
+
+    /// The candidate engine.
+    pub fn candidate(&self) -> &GateEngine {
+        &self.candidate
+    }
+
+    /// A copy of `request` to mirror, if it is sampled.
+    ///
+    /// Sampling is keyed on the request ID, so it is stable for a request.
+    pub fn sample(&self, request: &VerificationRequest) -> Option<VerificationRequest> {
+        let bucket = (request.request_id.as_u128() % 10_000) as f64;
+        (bucket < self.fraction * 10_000.0).then(|| request.clone())
+    }
+
+    /// Verify the mirrored request on the candidate in the background and
+    /// record how it compares to the primary `result`.
+    pub fn mirror(&self, request: VerificationRequest, primary: &VerificationResult) -> JoinHandle<()> {
+        let candidate = Arc::clone(&self.candidate);
+        let report = Arc::clone(&self.report);
+        let max_examples = self.max_examples;
+        let primary = Outcome::from(primary);
+        let (request_id, agent_id, action) = (request.request_id, request.agent_id.clone(), request.action.clone());
+
+        tokio::spawn(async move {
+            let candidate = Outcome::from(&candidate.verify(request).await);
+            let divergence = Divergence { request_id, agent_id, action, primary, candidate };
+            report.lock().unwrap().record(divergence, max_examples);
+        })
+    }
+
+    /// Snapshot of the divergence so far.
+    pub fn report(&self) -> DivergenceReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    /// Start a fresh report, e.g. after changing the candidate.
+    pub fn reset(&self) {
+        *self.report.lock().unwrap() = DivergenceReport::default();
+    }
+}
+
+/// The parts of a result that are compared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub allowed: bool,
+    pub final_risk_score: u8,
+    pub blocking_policies: Vec<String>,
+}
+
+impl From<&VerificationResult> for Outcome {
+    fn from(result: &VerificationResult) -> Self {
+        let mut blocking_policies = result.blocking_policies.clone();
+        blocking_policies.sort();
+        Self { allowed: result.allowed, final_risk_score: result.final_risk_score, blocking_policies }
+    }
+}
+
+/// A mirrored request whose outcomes differed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub request_id: Uuid,
+    pub agent_id: String,
+    pub action: String,
+    pub primary: Outcome,
+    pub candidate: Outcome,
+}
+
+/// Accumulated comparison of primary and candidate outcomes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DivergenceReport {
+    /// Requests verified by both engines
+    pub mirrored: u64,
+    /// Allowed by the primary, denied by the candidate
+    pub newly_denied: u64,
+    /// Denied by the primary, allowed by the candidate
+    pub newly_allowed: u64,
+    /// Same decision, different blocking policies
+    pub blocking_changed: u64,
+    /// Sum of absolute final risk score differences
+    pub total_risk_delta: u64,
+    /// Largest absolute final risk score difference
+    pub max_risk_delta: u8,
+    /// Most recent divergent requests
+    pub examples: Vec<Divergence>,
+}
+
+impl DivergenceReport {
+    /// Count one mirrored request, keeping it as an example if it diverged.
+    fn record(&mut self, comparison: Divergence, max_examples: usize) {
+        let Divergence { primary, candidate, .. } = &comparison;
+        self.mirrored += 1;
+        let delta = primary.final_risk_score.abs_diff(candidate.final_risk_score);
+        self.total_risk_delta += delta as u64;
+        self.max_risk_delta = self.max_risk_delta.max(delta);
+
+        match (primary.allowed, candidate.allowed) {
+            (true, false) => self.newly_denied += 1,
+            (false, true) => self.newly_allowed += 1,
+            _ if primary.blocking_policies != candidate.blocking_policies => self.blocking_changed += 1,
+            _ => return,
+        }
+        tracing::info!(
+            request_id = %comparison.request_id,
+            agent_id = %comparison.agent_id,
+            action = %comparison.action,
+            ?primary,
+            ?candidate,
+            "Canary diverged"
+        );
+        if max_examples == 0 {
+            return;
+        }
+        if self.examples.len() == max_examples {
+            self.examples.remove(0);
+        }
+        self.examples.push(comparison);
+    }
+
+    /// Requests whose allow/deny decision changed.
+    pub fn decision_divergences(&self) -> u64 {
+        self.newly_denied + self.newly_allowed
+    }
+
+    /// Fraction of mirrored requests whose decision changed.
+    pub fn decision_divergence_rate(&self) -> f64 {
+        if self.mirrored == 0 {
+            return 0.0;
+        }
+        self.decision_divergences() as f64 / self.mirrored as f64
+    }
+
+    /// Mean absolute final risk score difference.
+    pub fn mean_risk_delta(&self) -> f64 {
+        if self.mirrored == 0 {
+            return 0.0;
+        }
+        self.total_risk_delta as f64 / self.mirrored as f64
+    }
+
+    /// Check the report against promotion criteria.
+    pub fn verdict(&self, criteria: &PromotionCriteria) -> PromotionVerdict {
+        let mut reasons = Vec::new();
+        if self.mirrored < criteria.min_samples {
+            reasons.push(format!("{} mirrored requests, need {}", self.mirrored, criteria.min_samples));
+        }
+        if self.decision_divergence_rate() > criteria.max_decision_divergence_rate {
+            reasons.push(format!(
+                "decision divergence {:.4} exceeds {:.4}",
+                self.decision_divergence_rate(),
+                criteria.max_decision_divergence_rate
+            ));
+        }
+        if !criteria.allow_newly_allowed && self.newly_allowed > 0 {
+            reasons.push(format!("{} requests newly allowed", self.newly_allowed));
+        }
+        if self.mean_risk_delta() > criteria.max_mean_risk_delta {
+            reasons.push(format!(
+                "mean risk delta {:.2} exceeds {:.2}",
+                self.mean_risk_delta(),
+                criteria.max_mean_risk_delta
+            ));
+        }
+        PromotionVerdict { promote: reasons.is_empty(), reasons }
+    }
+}
+
+/// Thresholds a candidate must meet to be promoted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionCriteria {
+    /// Minimum mirrored requests before a verdict can pass
+    pub min_samples: u64,
+    /// Maximum fraction of requests whose decision may change
+    pub max_decision_divergence_rate: f64,
+    /// Maximum mean absolute risk score difference
+    pub max_mean_risk_delta: f64,
+    /// Whether the candidate may allow anything the primary denied
+    pub allow_newly_allowed: bool,
+}
+
+impl Default for PromotionCriteria {
+    fn default() -> Self {
+        Self {
+            min_samples: 1000,
+            max_decision_divergence_rate: 0.001,
+            max_mean_risk_delta: 5.0,
+            allow_newly_allowed: false,
+        }
+    }
+}
+
+/// Whether a candidate may be promoted, and why not.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromotionVerdict {
+    pub promote: bool,
+    /// Criteria the report failed
+    pub reasons: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::VerificationRequestBuilder;
+    use crate::policy::{Policy, PolicyAction, PolicyRule};
+
+    #[tokio::test]
+    async fn test_canary_reports_divergence() {
+        let candidate = GateEngine::new();
+        candidate
+            .register_policy(Policy {
+                id: "no-deletes".to_string(),
+                name: "No Deletes".to_string(),
+                description: String::new(),
+                priority: 0,
+                enabled: true,
+                jurisdictions: vec![],
+                rules: vec![PolicyRule {
+                    id: "r".to_string(),
+                    condition: "action == 'delete_all'".to_string(),
+                    action: PolicyAction::Deny,
+                    message: None,
+                    risk_score: None,
+                }],
+            })
+            .await;
+        let primary = GateEngine::new();
+        let canary = CanaryHarness::new(candidate).with_fraction(1.0).with_max_examples(2);
+
+        for action in ["read_data", "delete_all", "delete_all", "delete_all"] {
+            let request = VerificationRequestBuilder::new("agent-1", action).build();
+            let mirror = canary.sample(&request).unwrap();
+            let result = primary.verify(request).await;
+            assert!(result.allowed);
+            canary.mirror(mirror, &result).await.unwrap();
+        }
+
+        let report = canary.report();
+        assert_eq!(report.mirrored, 4);
+        assert_eq!(report.newly_denied, 3);
+        assert_eq!(report.examples.len(), 2);
+        assert!(report.max_risk_delta > 0);
+
+        let verdict = report.verdict(&PromotionCriteria { min_samples: 10, ..Default::default() });
+        assert!(!verdict.promote);
+        assert_eq!(verdict.reasons.len(), 3);
+
+        canary.reset();
+        assert_eq!(canary.report().mirrored, 0);
+        let never = CanaryHarness::new(GateEngine::new()).with_fraction(0.0);
+        assert!(never.sample(&VerificationRequestBuilder::new("agent-1", "read_data").build()).is_none());
+    }
+}
@@ -16,9 +16,36 @@
 //! - `action == 'transfer_funds'`
 //! - `context.amount > 10000`
 //! - `action == 'delete' && context.resource == 'database'`
+//!
+//! Use [`check`] to reject malformed conditions before they are loaded;
+//! [`evaluate`] treats anything it cannot parse as non-matching.
 
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
+use thiserror::Error;
+
+const OPERATORS: [&str; 6] = ["==", "!=", ">=", "<=", ">", "<"];
+
+/// Condition syntax error.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DslError {
+    #[error("Empty condition")]
+    Empty,
+    #[error("Mixing '&&' and '||' is not supported: {0}")]
+    MixedOperators(String),
+    #[error("Missing operand in '{0}'")]
+    MissingOperand(String),
+    #[error("Unknown identifier '{0}' (expected action, agent_id, context.<path> or a literal)")]
+    UnknownIdentifier(String),
+    #[error("Unterminated string literal: {0}")]
+    UnterminatedString(String),
+}
+
+impl agentkern_errors::Coded for DslError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        agentkern_errors::ErrorCode::PolicyInvalid
+    }
+}
 
 /// Context for evaluating expressions.
 #[derive(Debug, Clone)]
@@ -48,12 +75,72 @@ pub fn evaluate(condition: &str, ctx: &EvalContext) -> bool {
     evaluate_single(condition, ctx)
 }
 
+/// Check that a condition parses under the grammar.
+pub fn check(condition: &str) -> Result<(), DslError> {
+    let condition = condition.trim();
+    if condition.is_empty() {
+        return Err(DslError::Empty);
+    }
+    if condition.contains("&&") && condition.contains("||") {
+        return Err(DslError::MixedOperators(condition.to_string()));
+    }
+
+    condition
+        .split("&&")
+        .flat_map(|part| part.split("||"))
+        .try_for_each(check_single)
+}
+
+fn check_single(expr: &str) -> Result<(), DslError> {
+    let expr = expr.trim();
+    if expr.is_empty() {
+        return Err(DslError::Empty);
+    }
+
+    // Same operator precedence as `evaluate_single`
+    match OPERATORS.iter().find_map(|op| expr.find(op).map(|idx| (idx, op.len()))) {
+        Some((idx, len)) => {
+            for operand in [&expr[..idx], &expr[idx + len..]] {
+                let operand = operand.trim();
+                if operand.is_empty() {
+                    return Err(DslError::MissingOperand(expr.to_string()));
+                }
+                check_operand(operand)?;
+            }
+            Ok(())
+        }
+        None => check_operand(expr),
+    }
+}
+
+fn check_operand(token: &str) -> Result<(), DslError> {
+    if matches!(token, "action" | "agent_id") {
+        return Ok(());
+    }
+    if let Some(path) = token.strip_prefix("context.") {
+        return if path.is_empty() {
+            Err(DslError::UnknownIdentifier(token.to_string()))
+        } else {
+            Ok(())
+        };
+    }
+    if let Some(quote) = token.chars().next().filter(|c| *c == '\'' || *c == '"') {
+        return if token.len() >= 2 && token.ends_with(quote) {
+            Ok(())
+        } else {
+            Err(DslError::UnterminatedString(token.to_string()))
+        };
+    }
+    if matches!(token.to_lowercase().as_str(), "true" | "false" | "null") || token.parse::<f64>().is_ok() {
+        return Ok(());
+    }
+    Err(DslError::UnknownIdentifier(token.to_string()))
+}
+
 /// Evaluate a single comparison expression.
 fn evaluate_single(expr: &str, ctx: &EvalContext) -> bool {
     // Parse comparison operators
-    let operators = ["==", "!=", ">=", "<=", ">", "<"];
-    
-    for op in operators {
+    for op in OPERATORS {
         if let Some(idx) = expr.find(op) {
             let left = expr[..idx].trim();
             let right = expr[idx + op.len()..].trim();
@@ -202,4 +289,16 @@ mod tests {
         assert!(evaluate("action == 'send_email' || action == 'transfer_funds'", &ctx));
         assert!(!evaluate("action == 'delete' || action == 'drop'", &ctx));
     }
+
+    #[test]
+    fn test_check() {
+        assert!(check("action == 'transfer_funds' && context.amount > 10000").is_ok());
+        assert!(check("context.approved").is_ok());
+
+        assert_eq!(check("  "), Err(DslError::Empty));
+        assert!(matches!(check("action == 'a' && context.x > 1 || action == 'b'"), Err(DslError::MixedOperators(_))));
+        assert!(matches!(check("context.amount >"), Err(DslError::MissingOperand(_))));
+        assert!(matches!(check("amount > 100"), Err(DslError::UnknownIdentifier(_))));
+        assert!(matches!(check("action == 'oops"), Err(DslError::UnterminatedString(_))));
+    }
 }
@@ -12,11 +12,16 @@ use std::time::Instant;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 use chrono::Utc;
+use tracing::Instrument;
+use agentkern_telemetry::{start_span, TraceContext};
 
+use crate::bundles::{AuditRequirement, Bundle, BundleConflict, BundleSet};
 use crate::dsl::{evaluate, EvalContext};
 use crate::carbon::{CarbonCheckResult, CarbonVeto};
-use crate::neural::NeuralScorer;
+use crate::neural::{ModelConfig, NeuralScorer};
+use crate::neural_batch::NeuralBatcher;
 use crate::policy::{Policy, PolicyAction};
+use crate::policy_source::{lint_errors, PolicyDiff, PolicySource, PolicySourceError, RolloutStage};
 use crate::types::{
     DataRegion, LatencyBreakdown, VerificationContext, VerificationRequest, VerificationResult,
 };
@@ -38,6 +43,21 @@ pub struct GateEngine {
     jurisdiction: DataRegion,
     /// Carbon policy veto (optional)
     carbon_veto: Option<Arc<CarbonVeto>>,
+    /// Active compliance bundles
+    bundles: BundleSet,
+    /// Deployment environment, selecting the rollout stage of new revisions
+    environment: Option<String>,
+    /// Source commit of the enforced policies
+    commit: Arc<RwLock<Option<String>>>,
+    /// Revision being rolled out, not yet enforced for everyone
+    candidate: Arc<RwLock<Option<Candidate>>>,
+}
+
+/// A policy revision in shadow or canary.
+struct Candidate {
+    policies: HashMap<String, Policy>,
+    commit: Option<String>,
+    stage: RolloutStage,
 }
 
 impl Default for GateEngine {
@@ -55,9 +75,21 @@ impl GateEngine {
             neural_threshold: 50,
             jurisdiction: DataRegion::Global,
             carbon_veto: None,
+            bundles: BundleSet::new(),
+            environment: None,
+            commit: Arc::new(RwLock::new(None)),
+            candidate: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Set the deployment environment (e.g. `staging`, `production`).
+    ///
+    /// Selects each new revision's stage from its source's rollout plan.
+    pub fn with_environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
+
     /// Set the jurisdiction for policy filtering.
     pub fn with_jurisdiction(mut self, jurisdiction: DataRegion) -> Self {
         self.jurisdiction = jurisdiction;
@@ -71,12 +103,58 @@ impl GateEngine {
         self
     }
 
+    /// Configure the neural model (execution provider, inference threads).
+    pub fn with_neural_model(mut self, config: ModelConfig) -> Self {
+        self.neural_scorer = NeuralScorer::with_config(config).with_threshold(self.neural_threshold);
+        self
+    }
+
+    /// Score single verifications through a shared [`NeuralBatcher`].
+    ///
+    /// Call after [`with_neural_threshold`](Self::with_neural_threshold) and
+    /// [`with_neural_model`](Self::with_neural_model), which replace the scorer.
+    pub fn with_neural_batcher(mut self, batcher: Arc<NeuralBatcher>) -> Self {
+        self.neural_scorer = std::mem::take(&mut self.neural_scorer).with_batcher(batcher);
+        self
+    }
+
     /// Set the carbon veto controller.
     pub fn with_carbon_veto(mut self, veto: CarbonVeto) -> Self {
         self.carbon_veto = Some(Arc::new(veto));
         self
     }
 
+    /// Activate a compliance bundle (HIPAA, PCI, Takaful).
+    ///
+    /// Registers the bundle's preset policies, validators and prompt-guard
+    /// packs. Overlaps with already-active bundles are resolved in favour of
+    /// the stricter action and reported via [`GateEngine::bundle_conflicts`].
+    pub fn with_bundle(mut self, bundle: Bundle) -> Self {
+        let resolved = self.bundles.add(bundle);
+        let policies = Arc::get_mut(&mut self.policies)
+            .expect("policies are not shared while building")
+            .get_mut();
+        for policy in resolved {
+            policies.insert(policy.id.clone(), policy);
+        }
+        self
+    }
+
+    /// Active compliance bundles.
+    pub fn active_bundles(&self) -> &[Bundle] {
+        self.bundles.active()
+    }
+
+    /// Conflicts detected between active bundles.
+    pub fn bundle_conflicts(&self) -> &[BundleConflict] {
+        self.bundles.conflicts()
+    }
+
+    /// Audit requirements imposed by active bundles.
+    pub fn audit_requirements(&self) -> Vec<AuditRequirement> {
+        self.bundles.audit_requirements()
+    }
+
     /// Register a policy.
     pub async fn register_policy(&self, policy: Policy) {
         let mut policies = self.policies.write().await;
@@ -95,23 +173,151 @@ impl GateEngine {
         policies.values().cloned().collect()
     }
 
+    /// Atomically replace all non-bundle policies with those from `source`.
+    ///
+    /// Sources with lint errors are rejected and the current policies kept.
+    /// A revision whose rollout plan puts this engine's environment in
+    /// shadow or canary is staged as a candidate instead; the returned diff
+    /// is always against the enforced policies.
+    pub async fn reload_from(&self, source: &dyn PolicySource) -> Result<PolicyDiff, PolicySourceError> {
+        let revision = source.load_revision()?;
+        let errors = lint_errors(&revision.policies);
+        if !errors.is_empty() {
+            return Err(PolicySourceError::Invalid(errors));
+        }
+        let stage = revision.rollout.stage_for(self.environment.as_deref());
+
+        let bundle_ids: Vec<&str> = self.bundles.policy_ids().collect();
+        let mut policies = self.policies.write().await;
+        let current: Vec<Policy> = policies
+            .values()
+            .filter(|p| !bundle_ids.contains(&p.id.as_str()))
+            .cloned()
+            .collect();
+        let diff = PolicyDiff::between(&current, &revision.policies);
+
+        let mut incoming: HashMap<String, Policy> = policies
+            .iter()
+            .filter(|(id, _)| bundle_ids.contains(&id.as_str()))
+            .map(|(id, p)| (id.clone(), p.clone()))
+            .collect();
+        for policy in revision.policies {
+            incoming.insert(policy.id.clone(), policy);
+        }
+
+        let mut commit = self.commit.write().await;
+        let mut candidate = self.candidate.write().await;
+        if stage == RolloutStage::Enforce {
+            *policies = incoming;
+            *commit = revision.commit.clone();
+            *candidate = None;
+        } else {
+            *candidate = Some(Candidate { policies: incoming, commit: revision.commit.clone(), stage });
+        }
+
+        tracing::info!(
+            source = %source.describe(),
+            commit = ?revision.commit,
+            stage = ?stage,
+            added = diff.added.len(),
+            removed = diff.removed.len(),
+            changed = diff.changed.len(),
+            "Policies reloaded"
+        );
+        Ok(diff)
+    }
+
+    /// Commit and stage of the revision being rolled out, if any.
+    pub async fn rollout(&self) -> Option<(Option<String>, RolloutStage)> {
+        let candidate = self.candidate.read().await;
+        candidate.as_ref().map(|c| (c.commit.clone(), c.stage))
+    }
+
     /// Verify an action against all applicable policies.
-    pub async fn verify(&self, request: VerificationRequest) -> VerificationResult {
-        let start = Instant::now();
-        
-        // === SYMBOLIC PATH (Fast) ===
-        let symbolic_start = Instant::now();
-        let (evaluated, blocking, symbolic_risk) = self.evaluate_symbolic(&request).await;
-        let symbolic_us = symbolic_start.elapsed().as_micros() as u64;
+    ///
+    /// A traced request is verified in a `gate.verify` child span.
+    pub async fn verify(&self, mut request: VerificationRequest) -> VerificationResult {
+        let (trace, span) = start_span("gate.verify", request.trace.as_ref(), &[]);
+        request.trace = trace;
+
+        async move {
+            let start = Instant::now();
+
+            // === SYMBOLIC PATH (Fast) ===
+            let symbolic = self.evaluate_rollout(std::slice::from_ref(&request)).await.remove(0);
+
+            // === NEURAL PATH (If needed) ===
+            let neural_result = if symbolic.risk >= self.neural_threshold {
+                let neural_start = Instant::now();
+                let score = self.neural_scorer.score(&request.action, &request.context).await;
+                Some((score, neural_start.elapsed().as_micros() as u64))
+            } else {
+                None
+            };
+
+            self.finalize(request, symbolic, neural_result, start.elapsed().as_micros() as u64)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Verify a batch of actions.
+    ///
+    /// Policies are snapshotted and sorted once for the whole batch, and all
+    /// requests that cross the neural threshold are scored in a single
+    /// inference batch, or through the neural batcher under each request's
+    /// tenant. Results are returned in request order.
+    pub async fn verify_batch(&self, mut requests: Vec<VerificationRequest>) -> Vec<VerificationResult> {
+        if requests.is_empty() {
+            return Vec::new();
+        }
+        for request in &mut requests {
+            request.trace = request.trace.as_ref().map(TraceContext::child);
+        }
+
+        // === SYMBOLIC PATH (one policy snapshot for the batch) ===
+        let symbolic = self.evaluate_rollout(&requests).await;
 
-        // === NEURAL PATH (If needed) ===
-        let neural_result = if symbolic_risk >= self.neural_threshold {
+        // === NEURAL PATH (shared inference batch) ===
+        let neural_idx: Vec<usize> = symbolic
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.risk >= self.neural_threshold)
+            .map(|(i, _)| i)
+            .collect();
+        let mut neural: Vec<Option<(u8, u64)>> = vec![None; requests.len()];
+        if !neural_idx.is_empty() {
             let neural_start = Instant::now();
-            let score = self.neural_scorer.score(&request.action, &request.context).await;
-            Some((score, neural_start.elapsed().as_micros() as u64))
-        } else {
-            None
-        };
+            let items: Vec<_> = neural_idx.iter().map(|&i| (requests[i].action.as_str(), &requests[i].context)).collect();
+            let scores = self.neural_scorer.score_batch(&items).await;
+            // Attribute an equal share of the batch latency to each request
+            let share_us = neural_start.elapsed().as_micros() as u64 / neural_idx.len() as u64;
+            for (&i, score) in neural_idx.iter().zip(scores) {
+                neural[i] = Some((score, share_us));
+            }
+        }
+
+        requests
+            .into_iter()
+            .zip(symbolic)
+            .zip(neural)
+            .map(|((request, symbolic), neural_result)| {
+                let elapsed_us = symbolic.elapsed_us + neural_result.map(|(_, us)| us).unwrap_or(0);
+                self.finalize(request, symbolic, neural_result, elapsed_us)
+            })
+            .collect()
+    }
+
+    /// Apply the carbon veto and combine path scores into a result.
+    fn finalize(
+        &self,
+        request: VerificationRequest,
+        symbolic: SymbolicOutcome,
+        neural_result: Option<(u8, u64)>,
+        elapsed_us: u64,
+    ) -> VerificationResult {
+        let start = Instant::now();
+        let SymbolicOutcome { evaluated, blocking, risk: symbolic_risk, elapsed_us: symbolic_us, commit } = symbolic;
 
         // === CARBON PATH (ESG Veto) ===
         let carbon_result = if let Some(veto) = &self.carbon_veto {
@@ -134,7 +340,7 @@ impl GateEngine {
             None
         };
 
-        let total_us = start.elapsed().as_micros() as u64;
+        let total_us = elapsed_us + start.elapsed().as_micros() as u64;
 
         // Calculate final risk score
         let final_risk = if let Some((neural_risk, _)) = neural_result {
@@ -174,16 +380,64 @@ impl GateEngine {
                 symbolic_us,
                 neural_us: neural_result.map(|(_, us)| us),
             },
+            trace: request.trace,
+            policy_commit: commit,
         }
     }
 
-    /// Evaluate policies using the symbolic (deterministic) path.
-    async fn evaluate_symbolic(
-        &self,
-        request: &VerificationRequest,
-    ) -> (Vec<String>, Vec<String>, u8) {
+    /// Symbolic outcomes from one snapshot of the enforced and candidate
+    /// policies, each decided by whichever set the rollout assigns.
+    ///
+    /// In shadow (and for agents outside the canary) the candidate is still
+    /// evaluated, and decisions that would change are logged.
+    async fn evaluate_rollout(&self, requests: &[VerificationRequest]) -> Vec<SymbolicOutcome> {
         let policies = self.policies.read().await;
-        
+        let commit = self.commit.read().await;
+        let candidate = self.candidate.read().await;
+
+        let sorted = self.sorted_policies(&policies);
+        let staged = candidate.as_ref().map(|c| (c, self.sorted_policies(&c.policies)));
+        requests
+            .iter()
+            .map(|request| {
+                let mut enforced = self.evaluate_symbolic(&sorted, request);
+                enforced.commit = commit.clone();
+                let Some((candidate, staged)) = &staged else {
+                    return enforced;
+                };
+
+                let mut outcome = self.evaluate_symbolic(staged, request);
+                outcome.commit = candidate.commit.clone();
+                if candidate.stage.enforces_for(&request.agent_id) {
+                    return outcome;
+                }
+                if outcome.blocking != enforced.blocking {
+                    tracing::info!(
+                        request_id = %request.request_id,
+                        agent_id = %request.agent_id,
+                        commit = ?candidate.commit,
+                        enforced = ?enforced.blocking,
+                        candidate = ?outcome.blocking,
+                        "Candidate policies diverge"
+                    );
+                }
+                enforced
+            })
+            .collect()
+    }
+
+    /// Enabled policies for the current jurisdiction, highest priority first.
+    fn sorted_policies<'a>(&self, policies: &'a HashMap<String, Policy>) -> Vec<&'a Policy> {
+        let mut sorted: Vec<_> = policies.values()
+            .filter(|p| p.enabled && p.applies_to_jurisdiction(self.jurisdiction))
+            .collect();
+        sorted.sort_by_key(|p| std::cmp::Reverse(p.priority));
+        sorted
+    }
+
+    /// Evaluate policies using the symbolic (deterministic) path.
+    fn evaluate_symbolic(&self, policies: &[&Policy], request: &VerificationRequest) -> SymbolicOutcome {
+        let start = Instant::now();
         let mut evaluated = Vec::new();
         let mut blocking = Vec::new();
         let mut max_risk = 0u8;
@@ -195,13 +449,7 @@ impl GateEngine {
             context: request.context.data.clone(),
         };
 
-        // Sort policies by priority (higher first)
-        let mut sorted_policies: Vec<_> = policies.values()
-            .filter(|p| p.enabled && p.applies_to_jurisdiction(self.jurisdiction))
-            .collect();
-        sorted_policies.sort_by(|a, b| b.priority.cmp(&a.priority));
-
-        for policy in sorted_policies {
+        for policy in policies {
             evaluated.push(policy.id.clone());
 
             for rule in &policy.rules {
@@ -231,15 +479,38 @@ impl GateEngine {
             }
         }
 
-        (evaluated, blocking, max_risk)
+        if !self.bundles.is_empty() {
+            let outcome = self.bundles.evaluate(request);
+            blocking.extend(outcome.blocking);
+            max_risk = max_risk.max(outcome.risk);
+        }
+
+        SymbolicOutcome {
+            evaluated,
+            blocking,
+            risk: max_risk,
+            elapsed_us: start.elapsed().as_micros() as u64,
+            commit: None,
+        }
     }
 }
 
+/// Output of the symbolic path for a single request.
+struct SymbolicOutcome {
+    evaluated: Vec<String>,
+    blocking: Vec<String>,
+    risk: u8,
+    elapsed_us: u64,
+    /// Source commit of the policies that decided
+    commit: Option<String>,
+}
+
 /// Builder for creating verification requests.
 pub struct VerificationRequestBuilder {
     agent_id: String,
     action: String,
     context: HashMap<String, serde_json::Value>,
+    trace: Option<TraceContext>,
 }
 
 impl VerificationRequestBuilder {
@@ -248,9 +519,16 @@ impl VerificationRequestBuilder {
             agent_id: agent_id.into(),
             action: action.into(),
             context: HashMap::new(),
+            trace: None,
         }
     }
 
+    /// Propagate the caller's trace context.
+    pub fn trace(mut self, trace: TraceContext) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
     pub fn context(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
         self.context.insert(key.into(), value.into());
         self
@@ -263,6 +541,7 @@ impl VerificationRequestBuilder {
             action: self.action,
             context: VerificationContext { data: self.context },
             timestamp: Utc::now(),
+            trace: self.trace,
         }
     }
 }
@@ -330,6 +609,92 @@ mod tests {
         assert!(result.latency.total_us >= result.latency.symbolic_us);
     }
 
+    #[tokio::test]
+    async fn test_trace_context_propagates() {
+        let engine = GateEngine::new();
+        let root = TraceContext::new_root();
+
+        let request = VerificationRequestBuilder::new("agent-1", "read_data")
+            .trace(root.clone())
+            .build();
+        let trace = engine.verify(request).await.trace.unwrap();
+        assert_eq!(trace.trace_id, root.trace_id);
+        assert_eq!(trace.parent_span_id, Some(root.span_id));
+
+        let untraced = engine.verify(VerificationRequestBuilder::new("agent-1", "read_data").build()).await;
+        assert!(untraced.trace.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_preserves_order() {
+        let engine = GateEngine::new();
+        engine.register_policy(Policy {
+            id: "no-deletes".to_string(),
+            name: "No Deletes".to_string(),
+            description: String::new(),
+            priority: 100,
+            enabled: true,
+            jurisdictions: vec![],
+            rules: vec![PolicyRule {
+                id: "block-delete".to_string(),
+                condition: "action == 'delete_all'".to_string(),
+                action: PolicyAction::Deny,
+                message: None,
+                risk_score: Some(100),
+            }],
+        }).await;
+
+        let requests: Vec<_> = ["read_data", "delete_all", "send_email", "delete_all"]
+            .iter()
+            .map(|action| VerificationRequestBuilder::new("agent-1", *action).build())
+            .collect();
+        let ids: Vec<_> = requests.iter().map(|r| r.request_id).collect();
+
+        let results = engine.verify_batch(requests).await;
+        assert_eq!(results.len(), 4);
+        assert_eq!(results.iter().map(|r| r.request_id).collect::<Vec<_>>(), ids);
+        assert_eq!(
+            results.iter().map(|r| r.allowed).collect::<Vec<_>>(),
+            vec![true, false, true, false]
+        );
+        // Denied requests crossed the neural threshold and were batch-scored
+        assert!(results[1].neural_risk_score.is_some());
+        assert!(results[0].neural_risk_score.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_enforces_tenant_quotas() {
+        use crate::neural::NeuralGuard;
+        use crate::neural_batch::BatchConfig;
+
+        let config = BatchConfig::default().with_tenant_quota("blocked", 0);
+        let batcher = Arc::new(NeuralBatcher::spawn(Arc::new(NeuralGuard::new().unwrap()), config));
+        let engine = GateEngine::new().with_neural_threshold(0).with_neural_batcher(batcher.clone());
+
+        let requests = ["blocked", "tenant-a"]
+            .iter()
+            .map(|tenant| VerificationRequestBuilder::new("agent-1", "read_data").context("tenant_id", *tenant).build())
+            .collect();
+        let results = engine.verify_batch(requests).await;
+
+        assert!(results.iter().all(|r| r.neural_risk_score.is_some()));
+        let stats = batcher.stats();
+        assert_eq!((stats.rejected, stats.items), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_verify_batch_matches_single() {
+        let engine = GateEngine::new().with_bundle(crate::bundles::Bundle::Pci);
+        let build = || VerificationRequestBuilder::new("agent-1", "store_card_data").build();
+
+        let single = engine.verify(build()).await;
+        let batch = engine.verify_batch(vec![build()]).await;
+        assert_eq!(single.allowed, batch[0].allowed);
+        assert_eq!(single.blocking_policies, batch[0].blocking_policies);
+        assert_eq!(single.final_risk_score, batch[0].final_risk_score);
+        assert!(engine.verify_batch(vec![]).await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_carbon_veto_blocks_action() {
         use agentkern_treasury::carbon::{CarbonLedger, CarbonBudget};
@@ -358,4 +723,106 @@ mod tests {
         assert!(!result.allowed);
         assert!(result.reasoning.contains("Carbon budget exceeded"));
     }
+
+    #[tokio::test]
+    async fn test_reload_from_source() {
+        use crate::policy_source::StaticSource;
+
+        let rule = |condition: &str| PolicyRule {
+            id: "r".to_string(),
+            condition: condition.to_string(),
+            action: PolicyAction::Deny,
+            message: Some("blocked".to_string()),
+            risk_score: None,
+        };
+        let policy = |id: &str, condition: &str| Policy {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            priority: 0,
+            enabled: true,
+            jurisdictions: vec![],
+            rules: vec![rule(condition)],
+        };
+
+        let engine = GateEngine::new().with_bundle(Bundle::Pci);
+        engine.register_policy(policy("old", "action == 'a'")).await;
+
+        let diff = engine
+            .reload_from(&StaticSource::new(vec![policy("new", "action == 'b'")]))
+            .await
+            .unwrap();
+        assert_eq!(diff.added, ["new"]);
+        assert_eq!(diff.removed, ["old"]);
+
+        let ids: Vec<_> = engine.get_policies().await.into_iter().map(|p| p.id).collect();
+        assert!(ids.contains(&"new".to_string()));
+        assert!(ids.contains(&"pci-preset".to_string())); // bundles survive reloads
+
+        // Broken policies are rejected and the current set kept
+        let broken = StaticSource::new(vec![policy("broken", "amount > 5")]);
+        assert!(matches!(engine.reload_from(&broken).await, Err(PolicySourceError::Invalid(_))));
+        assert_eq!(engine.get_policies().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_staged_rollout_records_commit() {
+        use crate::policy_source::{PolicyRevision, RolloutPlan};
+
+        struct Versioned(RolloutStage);
+        impl PolicySource for Versioned {
+            fn describe(&self) -> String {
+                "versioned".to_string()
+            }
+            fn load(&self) -> Result<Vec<Policy>, PolicySourceError> {
+                Ok(vec![Policy {
+                    id: "no-deletes".to_string(),
+                    name: "No Deletes".to_string(),
+                    description: String::new(),
+                    priority: 0,
+                    enabled: true,
+                    jurisdictions: vec![],
+                    rules: vec![PolicyRule {
+                        id: "r".to_string(),
+                        condition: "action == 'delete_all'".to_string(),
+                        action: PolicyAction::Deny,
+                        message: None,
+                        risk_score: None,
+                    }],
+                }])
+            }
+            fn load_revision(&self) -> Result<PolicyRevision, PolicySourceError> {
+                let mut rollout = RolloutPlan::default();
+                rollout.environments.insert("production".to_string(), self.0);
+                Ok(PolicyRevision { policies: self.load()?, commit: Some("abc123".to_string()), rollout })
+            }
+        }
+        let delete = |agent: &str| VerificationRequestBuilder::new(agent, "delete_all").build();
+        let engine = GateEngine::new().with_environment("production");
+
+        // Shadow: evaluated, never decides
+        engine.reload_from(&Versioned(RolloutStage::Shadow)).await.unwrap();
+        let result = engine.verify(delete("agent-1")).await;
+        assert!(result.allowed);
+        assert_eq!(result.policy_commit, None);
+        assert!(engine.get_policies().await.is_empty());
+
+        // Canary: a stable subset of agents gets the new revision
+        engine.reload_from(&Versioned(RolloutStage::Canary(50))).await.unwrap();
+        let agents: Vec<String> = (0..40).map(|i| format!("agent-{i}")).collect();
+        let results = engine.verify_batch(agents.iter().map(|a| delete(a)).collect()).await;
+        for (agent, result) in agents.iter().zip(&results) {
+            let canary = RolloutStage::Canary(50).enforces_for(agent);
+            assert_eq!(result.allowed, !canary);
+            assert_eq!(result.policy_commit.is_some(), canary);
+        }
+        assert!(results.iter().any(|r| r.allowed) && results.iter().any(|r| !r.allowed));
+
+        // Enforce: the revision replaces the enforced policies
+        engine.reload_from(&Versioned(RolloutStage::Enforce)).await.unwrap();
+        let result = engine.verify(delete("agent-1")).await;
+        assert!(!result.allowed);
+        assert_eq!(result.policy_commit.as_deref(), Some("abc123"));
+        assert!(engine.rollout().await.is_none());
+    }
 }
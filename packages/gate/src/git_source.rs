@@ -0,0 +1,444 @@
+//! AgentKern-Gate: Git Policy Source
+//!
+//! Policy-as-code: policies live in a Git repository and are only loaded
+//! from commits signed (SSH, `gpg.format = ssh`) by an authorized
+//! ed25519 key. The commit SHA travels with the policies into every
+//! [`VerificationResult`](crate::types::VerificationResult), and an
+//! optional `rollout.yaml` in the same commit stages the revision per
+//! environment (shadow → canary percentage → enforce).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::git_source::{AuthorizedKeys, GitSource};
+//!
+//! let keys = AuthorizedKeys::parse(&std::fs::read_to_string("/etc/agentkern/policy_signers")?)?;
+//! let source = GitSource::new("/srv/policies", keys).with_ref("main").with_dir("gate");
+//! let engine = GateEngine::new().with_environment("production");
+//! engine.reload_from(&source).await?;
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::policy::Policy;
+use crate::policy_source::{
+    check_unique, parse_policies, PolicyRevision, PolicySource, PolicySourceError, RolloutPlan,
+};
+
+/// File holding the [`RolloutPlan`], next to the policies.
+pub const ROLLOUT_FILE: &str = "rollout.yaml";
+
+/// SSH signature namespace Git signs commits under.
+const GIT_NAMESPACE: &str = "git";
+
+/// Ed25519 keys allowed to sign policy commits.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorizedKeys {
+    keys: Vec<[u8; 32]>,
+}
+
+impl AuthorizedKeys {
+    /// Parse `authorized_keys` / `allowed_signers` style text.
+    ///
+    /// Each non-comment line must contain an `ssh-ed25519 <base64>` key;
+    /// principals and options before it are ignored.
+    pub fn parse(text: &str) -> Result<Self, PolicySourceError> {
+        let invalid = |line: &str, reason: &str| PolicySourceError::Parse {
+            path: PathBuf::from("authorized keys"),
+            reason: format!("{reason}: {line}"),
+        };
+
+        let mut keys = Self::default();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let mut fields = line.split_whitespace().skip_while(|f| *f != "ssh-ed25519");
+            let encoded = fields.nth(1).ok_or_else(|| invalid(line, "no ssh-ed25519 key"))?;
+            let blob = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|_| invalid(line, "bad base64"))?;
+            let key = parse_public_key(&mut SshReader::new(&blob)).ok_or_else(|| invalid(line, "bad key"))?;
+            keys.keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    /// Authorize a raw ed25519 public key.
+    pub fn with_key(mut self, key: [u8; 32]) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    pub fn contains(&self, key: &[u8; 32]) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+/// Policies from a signed commit in a Git repository.
+#[derive(Debug, Clone)]
+pub struct GitSource {
+    repo: PathBuf,
+    reference: String,
+    dir: Option<String>,
+    keys: AuthorizedKeys,
+}
+
+impl GitSource {
+    /// Read `HEAD` of `repo`, trusting commits signed by `keys`.
+    pub fn new(repo: impl Into<PathBuf>, keys: AuthorizedKeys) -> Self {
+        Self { repo: repo.into(), reference: "HEAD".to_string(), dir: None, keys }
+    }
+
+    /// Branch, tag or commit to load.
+    pub fn with_ref(mut self, reference: impl Into<String>) -> Self {
+        self.reference = reference.into();
+        self
+    }
+
+    /// Subdirectory of the tree holding the policies.
+    pub fn with_dir(mut self, dir: impl Into<String>) -> Self {
+        self.dir = Some(dir.into().trim_matches('/').to_string());
+        self
+    }
+
+    /// Repository this source reads.
+    pub fn repo(&self) -> &Path {
+        &self.repo
+    }
+
+    fn git(&self, args: &[&str]) -> Result<Vec<u8>, PolicySourceError> {
+        let git_error = |reason: String| PolicySourceError::Git { repo: self.repo.clone(), reason };
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(args)
+            .output()
+            .map_err(|e| git_error(e.to_string()))?;
+        if !output.status.success() {
+            return Err(git_error(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Resolve the configured ref to a commit SHA.
+    fn resolve(&self) -> Result<String, PolicySourceError> {
+        let spec = format!("{}^{{commit}}", self.reference);
+        let out = self.git(&["rev-parse", "--verify", "--quiet", &spec])?;
+        Ok(String::from_utf8_lossy(&out).trim().to_string())
+    }
+
+    /// Check the commit is signed by an authorized key.
+    fn verify_commit(&self, commit: &str) -> Result<(), PolicySourceError> {
+        let untrusted = |reason: &str| PolicySourceError::Untrusted {
+            commit: commit.to_string(),
+            reason: reason.to_string(),
+        };
+        let raw = self.git(&["cat-file", "commit", commit])?;
+        let (payload, armored) = split_signature(&raw).ok_or_else(|| untrusted("commit is not signed"))?;
+        let signer = verify_sshsig(&armored, &payload).map_err(untrusted)?;
+        if !self.keys.contains(&signer) {
+            return Err(untrusted("signing key is not authorized"));
+        }
+        Ok(())
+    }
+
+    /// Policy and rollout files at `commit`, relative to the configured dir.
+    fn files(&self, commit: &str) -> Result<Vec<String>, PolicySourceError> {
+        let mut args = vec!["ls-tree", "-r", "--name-only", commit];
+        if let Some(dir) = &self.dir {
+            args.extend(["--", dir.as_str()]);
+        }
+        let out = self.git(&args)?;
+        let mut files: Vec<String> = String::from_utf8_lossy(&out)
+            .lines()
+            .filter(|path| path.ends_with(".yaml") || path.ends_with(".yml"))
+            .map(str::to_string)
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn read(&self, commit: &str, path: &str) -> Result<String, PolicySourceError> {
+        let out = self.git(&["cat-file", "blob", &format!("{commit}:{path}")])?;
+        String::from_utf8(out).map_err(|e| PolicySourceError::Parse {
+            path: PathBuf::from(format!("{commit}:{path}")),
+            reason: e.to_string(),
+        })
+    }
+
+    fn rollout_path(&self) -> String {
+        match &self.dir {
+            Some(dir) => format!("{dir}/{ROLLOUT_FILE}"),
+            None => ROLLOUT_FILE.to_string(),
+        }
+    }
+}
+
+impl PolicySource for GitSource {
+    fn describe(&self) -> String {
+        format!("git:{}@{}", self.repo.display(), self.reference)
+    }
+
+    fn load(&self) -> Result<Vec<Policy>, PolicySourceError> {
+        self.load_revision().map(|revision| revision.policies)
+    }
+
+    fn load_revision(&self) -> Result<PolicyRevision, PolicySourceError> {
+        let commit = self.resolve()?;
+        self.verify_commit(&commit)?;
+
+        let rollout_path = self.rollout_path();
+        let mut policies = Vec::new();
+        let mut rollout = RolloutPlan::default();
+        for path in self.files(&commit)? {
+            let yaml = self.read(&commit, &path)?;
+            let parse_error = |reason: String| PolicySourceError::Parse {
+                path: PathBuf::from(format!("{commit}:{path}")),
+                reason,
+            };
+            if path == rollout_path {
+                rollout = serde_yaml::from_str(&yaml).map_err(|e| parse_error(e.to_string()))?;
+            } else {
+                policies.extend(parse_policies(&yaml).map_err(parse_error)?);
+            }
+        }
+        check_unique(&policies)?;
+        Ok(PolicyRevision { policies, commit: Some(commit), rollout })
+    }
+}
+
+/// Split a raw commit object into the signed payload and its `gpgsig` header.
+fn split_signature(raw: &[u8]) -> Option<(Vec<u8>, String)> {
+    let text = std::str::from_utf8(raw).ok()?;
+    let (headers, message) = text.split_once("\n\n")?;
+
+    let mut payload = String::with_capacity(text.len());
+    let mut signature: Option<String> = None;
+    let mut in_signature = false;
+    for line in headers.lines() {
+        if let Some(rest) = line.strip_prefix("gpgsig ") {
+            signature = Some(format!("{rest}\n"));
+            in_signature = true;
+        } else if in_signature && line.starts_with(' ') {
+            if let Some(sig) = signature.as_mut() {
+                sig.push_str(&line[1..]);
+                sig.push('\n');
+            }
+        } else {
+            in_signature = false;
+            payload.push_str(line);
+            payload.push('\n');
+        }
+    }
+    payload.push('\n');
+    payload.push_str(message);
+    signature.map(|sig| (payload.into_bytes(), sig))
+}
+
+/// Verify an armored SSH signature (`SSHSIG`) over `message`.
+///
+/// Returns the signer's ed25519 key.
+fn verify_sshsig(armored: &str, message: &[u8]) -> Result<[u8; 32], &'static str> {
+    let body: String = armored
+        .lines()
+        .filter(|l| !l.starts_with("-----"))
+        .collect();
+    if !armored.contains("BEGIN SSH SIGNATURE") {
+        return Err("signature is not an SSH signature");
+    }
+    let blob = base64::engine::general_purpose::STANDARD
+        .decode(body.trim())
+        .map_err(|_| "malformed signature")?;
+
+    let mut reader = SshReader::new(&blob);
+    if reader.take(6) != Some(b"SSHSIG".as_slice()) || reader.u32() != Some(1) {
+        return Err("malformed signature");
+    }
+    let public_key = reader.string().ok_or("malformed signature")?;
+    let namespace = reader.string().ok_or("malformed signature")?;
+    let reserved = reader.string().ok_or("malformed signature")?;
+    let hash_alg = reader.string().ok_or("malformed signature")?;
+    let signature = reader.string().ok_or("malformed signature")?;
+
+    if namespace != GIT_NAMESPACE.as_bytes() {
+        return Err("signature is not for git");
+    }
+    let key = parse_public_key(&mut SshReader::new(public_key)).ok_or("signer is not an ed25519 key")?;
+    let digest = match hash_alg {
+        b"sha512" => Sha512::digest(message).to_vec(),
+        b"sha256" => Sha256::digest(message).to_vec(),
+        _ => return Err("unsupported signature hash"),
+    };
+
+    let mut sig = SshReader::new(signature);
+    if sig.string() != Some(b"ssh-ed25519".as_slice()) {
+        return Err("signature is not ed25519");
+    }
+    let sig = sig
+        .string()
+        .and_then(|bytes| Signature::from_slice(bytes).ok())
+        .ok_or("malformed signature")?;
+
+    let mut signed = b"SSHSIG".to_vec();
+    for field in [namespace, reserved, hash_alg, digest.as_slice()] {
+        signed.extend((field.len() as u32).to_be_bytes());
+        signed.extend(field);
+    }
+    VerifyingKey::from_bytes(&key)
+        .and_then(|vk| vk.verify(&signed, &sig))
+        .map_err(|_| "signature does not verify")?;
+    Ok(key)
+}
+
+/// Parse an SSH wire-format `ssh-ed25519` public key.
+fn parse_public_key(reader: &mut SshReader<'_>) -> Option<[u8; 32]> {
+    if reader.string()? != b"ssh-ed25519" {
+        return None;
+    }
+    reader.string()?.try_into().ok()
+}
+
+/// Cursor over SSH wire-format data.
+struct SshReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SshReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.data.len() < n {
+            return None;
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes(b.try_into().expect("4 bytes")))
+    }
+
+    fn string(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::io::Write;
+    use std::process::Stdio;
+
+    const POLICY: &str = r#"
+id: no-deletes
+name: No Deletes
+rules:
+  - id: db
+    condition: "action == 'drop_table'"
+    action: deny
+"#;
+
+    fn wire(fields: &[&[u8]]) -> Vec<u8> {
+        fields.iter().flat_map(|f| (f.len() as u32).to_be_bytes().into_iter().chain(f.iter().copied())).collect()
+    }
+
+    fn public_blob(key: &SigningKey) -> Vec<u8> {
+        wire(&[b"ssh-ed25519", key.verifying_key().as_bytes()])
+    }
+
+    /// Armored SSHSIG over `message`, as `ssh-keygen -Y sign -n git` makes.
+    fn sshsig(key: &SigningKey, message: &[u8]) -> String {
+        let digest = Sha512::digest(message);
+        let mut signed = b"SSHSIG".to_vec();
+        signed.extend(wire(&[b"git", b"", b"sha512", &digest]));
+        let signature = wire(&[b"ssh-ed25519", &key.sign(&signed).to_bytes()]);
+
+        let mut blob = b"SSHSIG".to_vec();
+        blob.extend(1u32.to_be_bytes());
+        blob.extend(wire(&[&public_blob(key), b"git", b"", b"sha512", &signature]));
+        let body = base64::engine::general_purpose::STANDARD.encode(blob);
+        let lines: Vec<_> = body.as_bytes().chunks(70).map(|c| std::str::from_utf8(c).unwrap()).collect();
+        format!("-----BEGIN SSH SIGNATURE-----\n{}\n-----END SSH SIGNATURE-----", lines.join("\n"))
+    }
+
+    fn run(repo: &Path, args: &[&str], stdin: Option<&[u8]>) -> String {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(stdin.unwrap_or_default()).unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success(), "git {args:?} failed");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    }
+
+    /// Commit the working tree, signed by `key` if given, and move HEAD to it.
+    fn commit(repo: &Path, key: Option<&SigningKey>) -> String {
+        run(repo, &["add", "-A"], None);
+        let tree = run(repo, &["write-tree"], None);
+        let mut headers = format!("tree {tree}\n");
+        if let Ok(parent) = Command::new("git").arg("-C").arg(repo).args(["rev-parse", "-q", "--verify", "HEAD"]).output() {
+            let parent = String::from_utf8_lossy(&parent.stdout).trim().to_string();
+            if !parent.is_empty() {
+                headers.push_str(&format!("parent {parent}\n"));
+            }
+        }
+        headers.push_str("author Policy Admin <admin@example.com> 1700000000 +0000\n");
+        headers.push_str("committer Policy Admin <admin@example.com> 1700000000 +0000\n");
+        let message = "Update policies\n";
+
+        let object = match key {
+            Some(key) => {
+                let signature = sshsig(key, format!("{headers}\n{message}").as_bytes());
+                format!("{headers}gpgsig {}\n\n{message}", signature.replace('\n', "\n "))
+            }
+            None => format!("{headers}\n{message}"),
+        };
+        let sha = run(repo, &["hash-object", "-t", "commit", "-w", "--stdin"], Some(object.as_bytes()));
+        run(repo, &["update-ref", "HEAD", &sha], None);
+        sha
+    }
+
+    #[test]
+    fn test_git_source_requires_authorized_signature() {
+        let repo = std::env::temp_dir().join(format!("gate-git-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(repo.join("gate")).unwrap();
+        run(&repo, &["init", "-q"], None);
+        std::fs::write(repo.join("gate/deletes.yaml"), POLICY).unwrap();
+        std::fs::write(repo.join("gate/rollout.yaml"), "default: shadow\nenvironments:\n  production: !canary 10\n").unwrap();
+
+        let admin = SigningKey::from_bytes(&[7; 32]);
+        let intruder = SigningKey::from_bytes(&[9; 32]);
+        let line = format!(
+            "admin@example.com ssh-ed25519 {} admin",
+            base64::engine::general_purpose::STANDARD.encode(public_blob(&admin))
+        );
+        let source = GitSource::new(&repo, AuthorizedKeys::parse(&line).unwrap()).with_dir("gate");
+
+        let sha = commit(&repo, Some(&admin));
+        let revision = source.load_revision().unwrap();
+        assert_eq!(revision.commit.as_deref(), Some(sha.as_str()));
+        assert_eq!(revision.policies.len(), 1);
+        assert_eq!(revision.rollout.stage_for(Some("production")), crate::policy_source::RolloutStage::Canary(10));
+        assert_eq!(revision.rollout.stage_for(None), crate::policy_source::RolloutStage::Shadow);
+
+        commit(&repo, Some(&intruder));
+        assert!(matches!(source.load(), Err(PolicySourceError::Untrusted { .. })));
+        commit(&repo, None);
+        assert!(matches!(source.load(), Err(PolicySourceError::Untrusted { .. })));
+
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
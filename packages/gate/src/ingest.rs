@@ -0,0 +1,470 @@
+//! AgentKern-Gate: Zero-Copy Request Ingestion
+//!
+//! Per ARCHITECTURE.md: "The Hyper-Loop" - io_uring for zero-copy network I/O.
+//!
+//! A minimal HTTP/1.1 `POST /verify` listener that sits in front of the
+//! engine on the hot path:
+//! - Owned, pooled read buffers (the io_uring ownership model) that are
+//!   recycled for the response, so steady-state requests allocate no buffers
+//! - Request bodies deserialized straight out of the read buffer
+//! - `io_uring` feature: tokio-uring listener, with automatic fallback to
+//!   standard Tokio I/O when the kernel lacks io_uring support
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::ingest::{spawn_ingest, IngestConfig};
+//!
+//! let (backend, handle) = spawn_ingest(IngestConfig::default(), engine)?;
+//! tracing::info!(?backend, "Ingest listener started");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::engine::{GateEngine, VerificationRequestBuilder};
+use crate::types::VerificationRequest;
+
+/// Ingestion errors.
+#[derive(Debug, Error)]
+pub enum IngestError {
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Payload exceeds buffer size of {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IngestError {
+    fn status(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "400 Bad Request",
+            Self::NotFound(_) => "404 Not Found",
+            Self::PayloadTooLarge { .. } => "413 Payload Too Large",
+            Self::Io(_) => "500 Internal Server Error",
+        }
+    }
+}
+
+/// Listener configuration.
+#[derive(Debug, Clone)]
+pub struct IngestConfig {
+    /// Address to bind
+    pub addr: SocketAddr,
+    /// Size of each pooled buffer (max request size)
+    pub buffer_size: usize,
+    /// Maximum buffers kept in the pool
+    pub pool_size: usize,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(([0, 0, 0, 0], 3002)),
+            buffer_size: 16 * 1024,
+            pool_size: 1024,
+        }
+    }
+}
+
+/// Which I/O backend the listener runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestBackend {
+    /// tokio-uring (zero-copy, Linux 5.10+)
+    IoUring,
+    /// Standard Tokio (epoll/kqueue)
+    Tokio,
+}
+
+/// Pick the best backend for this host.
+pub fn select_backend() -> IngestBackend {
+    if crate::runtime::HyperRuntime::is_io_uring_available() {
+        IngestBackend::IoUring
+    } else {
+        IngestBackend::Tokio
+    }
+}
+
+// ============================================================================
+// Buffer pool
+// ============================================================================
+
+/// Pool of fixed-capacity owned buffers.
+///
+/// io_uring takes ownership of buffers for the duration of an operation, so
+/// buffers are handed out by value and returned after the response is sent.
+pub struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+    buffer_size: usize,
+    max_pooled: usize,
+    allocations: AtomicU64,
+}
+
+impl BufferPool {
+    /// Create a pool of `buffer_size` buffers, keeping at most `max_pooled`.
+    pub fn new(buffer_size: usize, max_pooled: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            buffer_size,
+            max_pooled,
+            allocations: AtomicU64::new(0),
+        }
+    }
+
+    /// Take an empty buffer with `buffer_size` capacity.
+    pub fn acquire(&self) -> Vec<u8> {
+        if let Some(buf) = self.buffers.lock().pop() {
+            return buf;
+        }
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        Vec::with_capacity(self.buffer_size)
+    }
+
+    /// Return a buffer to the pool.
+    pub fn release(&self, mut buf: Vec<u8>) {
+        if buf.capacity() != self.buffer_size {
+            // Grown past the pooled size; let it go
+            return;
+        }
+        buf.clear();
+        let mut buffers = self.buffers.lock();
+        if buffers.len() < self.max_pooled {
+            buffers.push(buf);
+        }
+    }
+
+    /// Capacity of each buffer.
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Total buffers ever allocated (reuse keeps this flat).
+    pub fn allocations(&self) -> u64 {
+        self.allocations.load(Ordering::Relaxed)
+    }
+}
+
+// ============================================================================
+// HTTP parsing
+// ============================================================================
+
+/// Request body, borrowing strings from the read buffer where possible.
+#[derive(Debug, Deserialize)]
+struct VerifyPayload<'a> {
+    #[serde(borrow)]
+    agent_id: Cow<'a, str>,
+    #[serde(borrow)]
+    action: Cow<'a, str>,
+    #[serde(default)]
+    context: HashMap<String, serde_json::Value>,
+}
+
+/// Outcome of parsing the bytes read so far.
+#[derive(Debug)]
+pub enum ParseStatus {
+    /// Need more bytes
+    Incomplete,
+    /// Full request available
+    Complete(Box<VerificationRequest>),
+}
+
+/// Parse a `POST /verify` request from a buffer.
+pub fn parse_request(buf: &[u8]) -> Result<ParseStatus, IngestError> {
+    let Some(header_end) = find(buf, b"\r\n\r\n") else {
+        return Ok(ParseStatus::Incomplete);
+    };
+    let head = std::str::from_utf8(&buf[..header_end])
+        .map_err(|_| IngestError::BadRequest("headers are not UTF-8".to_string()))?;
+
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("POST"), Some("/verify")) => {}
+        (Some(_), Some(path)) => return Err(IngestError::NotFound(path.to_string())),
+        _ => return Err(IngestError::BadRequest("malformed request line".to_string())),
+    }
+
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>())
+        .transpose()
+        .map_err(|_| IngestError::BadRequest("invalid Content-Length".to_string()))?
+        .ok_or_else(|| IngestError::BadRequest("missing Content-Length".to_string()))?;
+
+    let body_start = header_end + 4;
+    if buf.len() < body_start + content_length {
+        return Ok(ParseStatus::Incomplete);
+    }
+
+    let payload: VerifyPayload = serde_json::from_slice(&buf[body_start..body_start + content_length])
+        .map_err(|e| IngestError::BadRequest(e.to_string()))?;
+
+    let mut builder = VerificationRequestBuilder::new(payload.agent_id, payload.action);
+    for (key, value) in payload.context {
+        builder = builder.context(key, value);
+    }
+    Ok(ParseStatus::Complete(Box::new(builder.build())))
+}
+
+/// Render an HTTP response into `buf` (reusing its allocation).
+pub fn render_response<T: serde::Serialize>(buf: &mut Vec<u8>, status: &str, body: &T) {
+    buf.clear();
+    let json = serde_json::to_vec(body).unwrap_or_else(|_| b"{}".to_vec());
+    buf.extend_from_slice(b"HTTP/1.1 ");
+    buf.extend_from_slice(status.as_bytes());
+    buf.extend_from_slice(b"\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: ");
+    buf.extend_from_slice(json.len().to_string().as_bytes());
+    buf.extend_from_slice(b"\r\n\r\n");
+    buf.extend_from_slice(&json);
+}
+
+fn render_error(buf: &mut Vec<u8>, err: &IngestError) {
+    render_response(buf, err.status(), &serde_json::json!({ "error": err.to_string() }));
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Verify a parsed buffer and write the response into the same buffer.
+async fn respond(engine: &GateEngine, buf: &mut Vec<u8>, parsed: Result<ParseStatus, IngestError>) {
+    match parsed {
+        Ok(ParseStatus::Complete(request)) => {
+            let result = engine.verify(*request).await;
+            render_response(buf, "200 OK", &result);
+        }
+        Ok(ParseStatus::Incomplete) => {
+            render_error(buf, &IngestError::BadRequest("connection closed mid-request".to_string()))
+        }
+        Err(err) => render_error(buf, &err),
+    }
+}
+
+// ============================================================================
+// Standard Tokio backend
+// ============================================================================
+
+/// Serve connections on a Tokio listener.
+pub async fn serve_tokio(
+    listener: tokio::net::TcpListener,
+    engine: Arc<GateEngine>,
+    pool: Arc<BufferPool>,
+) -> Result<(), IngestError> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let engine = Arc::clone(&engine);
+        let pool = Arc::clone(&pool);
+        tokio::spawn(async move {
+            if let Err(e) = handle_tokio(stream, &engine, &pool).await {
+                tracing::debug!(error = %e, "Ingest connection error");
+            }
+        });
+    }
+}
+
+async fn handle_tokio(
+    mut stream: tokio::net::TcpStream,
+    engine: &GateEngine,
+    pool: &BufferPool,
+) -> Result<(), IngestError> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut buf = pool.acquire();
+    let parsed = loop {
+        if buf.len() == pool.buffer_size() {
+            break Err(IngestError::PayloadTooLarge { limit: pool.buffer_size() });
+        }
+        if stream.read_buf(&mut buf).await? == 0 {
+            break Ok(ParseStatus::Incomplete);
+        }
+        match parse_request(&buf) {
+            Ok(ParseStatus::Incomplete) => continue,
+            other => break other,
+        }
+    };
+
+    respond(engine, &mut buf, parsed).await;
+    stream.write_all(&buf).await?;
+    pool.release(buf);
+    Ok(())
+}
+
+// ============================================================================
+// io_uring backend
+// ============================================================================
+
+/// Serve connections on a tokio-uring listener (blocks the calling thread).
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub fn serve_uring(addr: SocketAddr, engine: Arc<GateEngine>, pool: Arc<BufferPool>) -> Result<(), IngestError> {
+    tokio_uring::start(async move {
+        let listener = tokio_uring::net::TcpListener::bind(addr)?;
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let engine = Arc::clone(&engine);
+            let pool = Arc::clone(&pool);
+            tokio_uring::spawn(async move {
+                if let Err(e) = handle_uring(stream, &engine, &pool).await {
+                    tracing::debug!(error = %e, "Ingest connection error");
+                }
+            });
+        }
+    })
+}
+
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+async fn handle_uring(
+    stream: tokio_uring::net::TcpStream,
+    engine: &GateEngine,
+    pool: &BufferPool,
+) -> Result<(), IngestError> {
+    use tokio_uring::buf::BoundedBuf;
+
+    let mut buf = pool.acquire();
+    let parsed = loop {
+        let filled = buf.len();
+        if filled == pool.buffer_size() {
+            break Err(IngestError::PayloadTooLarge { limit: pool.buffer_size() });
+        }
+        // The kernel writes directly into the pooled buffer after `filled`
+        let (res, slice) = stream.read(buf.slice(filled..)).await;
+        buf = slice.into_inner();
+        if res? == 0 {
+            break Ok(ParseStatus::Incomplete);
+        }
+        match parse_request(&buf) {
+            Ok(ParseStatus::Incomplete) => continue,
+            other => break other,
+        }
+    };
+
+    respond(engine, &mut buf, parsed).await;
+    let (res, buf) = stream.write_all(buf).await;
+    pool.release(buf);
+    res?;
+    Ok(())
+}
+
+/// Start the ingest listener on a dedicated thread using the best backend.
+///
+/// Falls back to standard Tokio when io_uring is unavailable.
+pub fn spawn_ingest(
+    config: IngestConfig,
+    engine: Arc<GateEngine>,
+) -> Result<(IngestBackend, std::thread::JoinHandle<Result<(), IngestError>>), IngestError> {
+    let pool = Arc::new(BufferPool::new(config.buffer_size, config.pool_size));
+    let backend = select_backend();
+
+    let handle = std::thread::Builder::new()
+        .name("gate-ingest".to_string())
+        .spawn(move || {
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            if backend == IngestBackend::IoUring {
+                return serve_uring(config.addr, engine, pool);
+            }
+
+            let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::bind(config.addr).await?;
+                serve_tokio(listener, engine, pool).await
+            })
+        })?;
+
+    Ok((backend, handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(body: &str) -> Vec<u8> {
+        format!(
+            "POST /verify HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_parse_complete_request() {
+        let buf = http(r#"{"agent_id":"agent-1","action":"read_data","context":{"amount":5}}"#);
+        match parse_request(&buf).unwrap() {
+            ParseStatus::Complete(req) => {
+                assert_eq!(req.agent_id, "agent-1");
+                assert_eq!(req.action, "read_data");
+                assert_eq!(req.context.data["amount"], 5);
+            }
+            ParseStatus::Incomplete => panic!("expected complete request"),
+        }
+    }
+
+    #[test]
+    fn test_parse_incomplete_and_errors() {
+        let buf = http(r#"{"agent_id":"a","action":"b"}"#);
+        assert!(matches!(parse_request(&buf[..20]).unwrap(), ParseStatus::Incomplete));
+        assert!(matches!(parse_request(&buf[..buf.len() - 3]).unwrap(), ParseStatus::Incomplete));
+
+        let wrong_path = b"GET /health HTTP/1.1\r\n\r\n";
+        assert!(matches!(parse_request(wrong_path), Err(IngestError::NotFound(_))));
+
+        let no_length = b"POST /verify HTTP/1.1\r\n\r\n{}";
+        assert!(matches!(parse_request(no_length), Err(IngestError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_buffer_pool_reuse() {
+        let pool = BufferPool::new(64, 2);
+        let a = pool.acquire();
+        let b = pool.acquire();
+        assert_eq!(pool.allocations(), 2);
+
+        pool.release(a);
+        pool.release(b);
+        let c = pool.acquire();
+        assert_eq!(pool.allocations(), 2);
+        assert!(c.is_empty());
+        assert_eq!(c.capacity(), 64);
+
+        // Grown buffers are not pooled
+        let mut big = pool.acquire();
+        big.extend_from_slice(&[0u8; 128]);
+        pool.release(big);
+        pool.release(c);
+        assert_eq!(pool.buffers.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tokio_backend_roundtrip() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let pool = Arc::new(BufferPool::new(4096, 8));
+        tokio::spawn(serve_tokio(listener, Arc::new(GateEngine::new()), Arc::clone(&pool)));
+
+        for _ in 0..3 {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream.write_all(&http(r#"{"agent_id":"agent-1","action":"read_data"}"#)).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+            assert!(response.contains("\"allowed\":true"));
+        }
+
+        // Sequential connections reuse one pooled buffer
+        assert_eq!(pool.allocations(), 1);
+    }
+}
@@ -6,7 +6,7 @@
 //! Features implemented:
 //! - `io_uring`: Native Tokio io_uring for zero-copy I/O
 //! - `wasm`: WASM Component Model for policy nano-isolation
-//! - `neural`: ONNX Runtime for neuro-symbolic guards
+//! - `neural`: ONNX Runtime for neuro-symbolic guards, micro-batched per tenant
 //! - `actors`: Actix for dynamic supervision with hot-swap
 //! - `sovereign`: Data sovereignty and geo-fencing
 //! - `crypto`: Quantum-safe cryptography
@@ -15,15 +15,20 @@
 //! - `pci`: PCI-DSS payment compliance
 
 pub mod policy;
+pub mod policy_source;     // File/static policy sources and hot reload
+pub mod git_source;        // Signed policy-as-code from Git
 pub mod dsl;
 pub mod neural;
+pub mod neural_batch;      // Quota-aware micro-batching for shared GPUs
 pub mod engine;
+pub mod canary;            // Mirror traffic to a candidate engine before promotion
 pub mod types;
 
 // Hyper-Stack modules (per ARCHITECTURE.md)
 pub mod runtime;           // Native Tokio io_uring runtime
 pub mod tee;               // Hardware Enclaves (TDX/SEV)
 pub mod observability;     // eBPF-compatible tracing
+pub mod ingest;            // Zero-copy request ingestion (io_uring)
 
 // ENGINEERING_STANDARD.md modules
 pub mod actors;            // Dynamic Supervision (Section 1)
@@ -39,6 +44,7 @@ pub mod mtls;              // Zero-Trust mTLS (Section 5)
 pub mod hipaa;             // HIPAA Healthcare Compliance (Section 2)
 pub mod pci;               // PCI-DSS Payment Compliance (Section 2)
 pub mod fhir;              // FHIR R4 Healthcare Integration (Section 2)
+pub mod bundles;           // Compliance regime presets (Section 2)
 
 // MANDATE.md Section 6: Prompt Defense
 pub mod prompt_guard;      // Prompt injection detection
@@ -55,20 +61,29 @@ pub mod wasm;              // WASM Component Model
 
 // Re-exports
 pub use engine::GateEngine;
-pub use policy::{Policy, PolicyRule, PolicyAction};
+pub use policy::{Policy, PolicyRule, PolicyAction, LintIssue, LintLevel};
+pub use policy_source::{
+    PolicySource, FileSource, StaticSource, PolicyDiff, PolicySourceError, PolicyRevision, RolloutPlan, RolloutStage,
+};
+pub use git_source::{GitSource, AuthorizedKeys};
+pub use neural_batch::{BatchConfig, BatchError, BatchStats, NeuralBatcher};
+pub use canary::{CanaryHarness, DivergenceReport, PromotionCriteria, PromotionVerdict};
 pub use types::{VerificationRequest, VerificationResult, DataRegion};
 pub use runtime::{HyperRuntime, TokioRuntime};
 pub use tee::Enclave;
 pub use carbon::{CarbonVeto, CarbonCheckResult};
 pub use observability::{ObservabilityPlane, GateMetrics};
-pub use actors::{GateSupervisor, PolicyResult, SupervisorStatus};
+pub use actors::{
+    GateSupervisor, PolicyResult, SupervisorStatus, PolicyEvaluator, SwapConfig, SwapPhase, SwapProgress,
+};
 pub use sovereign::{SovereignController, DataTransfer, TransferDecision};
 pub use budget::{AgentBudget, BudgetConfig, BudgetError};
 pub use crypto_agility::{CryptoProvider, CryptoMode, Algorithm};
-pub use takaful::{TakafulValidator, TakafulError, ComplianceResult};
+pub use takaful::{TakafulValidator, TakafulError, ComplianceResult, ScholarRuleset, RulesetRegistry};
 pub use mtls::{CertificateValidator, MtlsConfig, CertificateInfo};
 pub use hipaa::{HipaaValidator, HipaaError, PhiScanResult, HipaaRole};
 pub use pci::{PciValidator, PciError, CardToken, CardBrand};
+pub use bundles::{Bundle, BundleConflict, AuditRequirement};
 pub use explain::{ExplainabilityEngine, Explanation, ExplainContext, ExplanationMethod};
 pub use connectors::{
     LegacyConnector, ConnectorProtocol, ConnectorConfig, ConnectorHealth,
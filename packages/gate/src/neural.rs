@@ -23,7 +23,9 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use thiserror::Error;
+use crate::neural_batch::{NeuralBatcher, DEFAULT_TENANT};
 use crate::types::VerificationContext;
 
 /// Neural inference errors.
@@ -549,6 +551,7 @@ pub struct ValidationResult {
 /// Wraps NeuralGuard to provide async scoring interface.
 pub struct NeuralScorer {
     guard: Option<NeuralGuard>,
+    batcher: Option<Arc<NeuralBatcher>>,
     threshold: u8,
 }
 
@@ -557,6 +560,16 @@ impl NeuralScorer {
     pub fn new() -> Self {
         Self {
             guard: NeuralGuard::new().ok(),
+            batcher: None,
+            threshold: 50,
+        }
+    }
+
+    /// Create a scorer with a custom model configuration (provider, threads).
+    pub fn with_config(config: ModelConfig) -> Self {
+        Self {
+            guard: NeuralGuard::with_config(config).ok(),
+            batcher: None,
             threshold: 50,
         }
     }
@@ -567,9 +580,19 @@ impl NeuralScorer {
         self
     }
 
+    /// Score single actions through a shared micro-batcher.
+    ///
+    /// The tenant is read from the `tenant_id` context key.
+    pub fn with_batcher(mut self, batcher: Arc<NeuralBatcher>) -> Self {
+        self.batcher = Some(batcher);
+        self
+    }
+
     /// Score an action (async interface for engine).
-    pub async fn score(&self, action: &str, _context: &VerificationContext) -> u8 {
-        if let Some(guard) = &self.guard {
+    pub async fn score(&self, action: &str, context: &VerificationContext) -> u8 {
+        if let Some(batcher) = &self.batcher {
+            score_batched(batcher, tenant(context), action).await
+        } else if let Some(guard) = &self.guard {
             match guard.classify_intent(action) {
                 Ok(result) => result.intent.risk_score(),
                 Err(_) => 50, // Default on error
@@ -578,6 +601,60 @@ impl NeuralScorer {
             50 // Default when no guard
         }
     }
+
+    /// Score a batch of actions with their contexts in one inference pass.
+    ///
+    /// With a batcher, every action is queued for its context's tenant, so
+    /// each counts against that tenant's quota. Otherwise identical actions
+    /// are classified once. Scores are returned in input order.
+    pub async fn score_batch(&self, items: &[(&str, &VerificationContext)]) -> Vec<u8> {
+        if let Some(batcher) = &self.batcher {
+            let mut tasks = tokio::task::JoinSet::new();
+            for (i, (action, context)) in items.iter().enumerate() {
+                let (batcher, tenant, action) = (batcher.clone(), tenant(context).to_string(), action.to_string());
+                tasks.spawn(async move { (i, score_batched(&batcher, &tenant, &action).await) });
+            }
+            let mut scores = vec![50; items.len()];
+            while let Some(Ok((i, score))) = tasks.join_next().await {
+                scores[i] = score;
+            }
+            return scores;
+        }
+        let Some(guard) = &self.guard else {
+            return vec![50; items.len()];
+        };
+        let actions: Vec<&str> = items.iter().map(|(action, _)| *action).collect();
+
+        let mut unique: Vec<&str> = actions.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let scores: HashMap<&str, u8> = match guard.batch_classify(&unique) {
+            Ok(results) => unique
+                .iter()
+                .zip(results)
+                .map(|(action, result)| (*action, result.intent.risk_score()))
+                .collect(),
+            Err(_) => return vec![50; actions.len()], // Default on error
+        };
+
+        actions.iter().map(|a| scores.get(a).copied().unwrap_or(50)).collect()
+    }
+}
+
+/// Tenant named by the `tenant_id` context key.
+fn tenant(context: &VerificationContext) -> &str {
+    context.data.get("tenant_id").and_then(|v| v.as_str()).unwrap_or(DEFAULT_TENANT)
+}
+
+async fn score_batched(batcher: &NeuralBatcher, tenant: &str, action: &str) -> u8 {
+    match batcher.classify(tenant, action).await {
+        Ok(result) => result.intent.risk_score(),
+        Err(e) => {
+            tracing::warn!(tenant = %tenant, error = %e, "Batched neural scoring failed");
+            50 // Default on error
+        }
+    }
 }
 
 impl Default for NeuralScorer {
@@ -639,6 +716,20 @@ mod tests {
         assert!(result.reason.contains("Neural"));
     }
 
+    #[tokio::test]
+    async fn test_score_batch_matches_single() {
+        let scorer = NeuralScorer::new();
+        let ctx = VerificationContext::default();
+        let actions = ["transfer money", "read file", "transfer money"];
+
+        let items: Vec<_> = actions.iter().map(|action| (*action, &ctx)).collect();
+        let batch = scorer.score_batch(&items).await;
+        assert_eq!(batch.len(), 3);
+        for (action, score) in actions.iter().zip(&batch) {
+            assert_eq!(*score, scorer.score(action, &ctx).await);
+        }
+    }
+
     #[test]
     fn test_batch_classify() {
         let guard = NeuralGuard::new().unwrap();
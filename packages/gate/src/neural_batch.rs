@@ -0,0 +1,428 @@
+//! AgentKern-Gate: Neural Micro-Batching
+//!
+//! Concurrent verify calls on a shared GPU are far cheaper as one batched
+//! inference than as many single ones fighting over the device. The
+//! [`NeuralBatcher`] queues classification requests, waits up to
+//! `max_wait` (or until the batch is full), runs one batched inference and
+//! scatters the results back to the callers.
+//!
+//! Features:
+//! - Per-tenant quotas on outstanding requests, so one tenant cannot flood
+//!   the queue
+//! - Batches filled round-robin across tenants
+//! - Batch size tuned to the latency SLO from observed per-item inference cost
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::neural_batch::{BatchConfig, NeuralBatcher};
+//!
+//! let config = BatchConfig::default().with_max_batch(32).with_tenant_quota("tenant-a", 128);
+//! let batcher = Arc::new(NeuralBatcher::spawn(Arc::new(NeuralGuard::new()?), config));
+//! let engine = GateEngine::new().with_neural_batcher(batcher.clone());
+//!
+//! let result = batcher.classify("tenant-a", "transfer $10000").await?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::neural::{IntentResult, NeuralGuard};
+
+/// Tenant used when a request does not name one.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Weight of the newest batch in the per-item cost estimate.
+const COST_SMOOTHING: f64 = 0.2;
+
+/// Batching errors.
+#[derive(Debug, Clone, Error)]
+pub enum BatchError {
+    #[error("Tenant {tenant} has {limit} neural requests outstanding")]
+    QuotaExceeded { tenant: String, limit: usize },
+    #[error("Batched inference failed: {reason}")]
+    InferenceFailed { reason: String },
+    #[error("Neural batcher is shut down")]
+    Closed,
+}
+
+impl agentkern_errors::Coded for BatchError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        match self {
+            Self::QuotaExceeded { .. } => agentkern_errors::ErrorCode::QuotaExceeded,
+            Self::InferenceFailed { .. } => agentkern_errors::ErrorCode::Internal,
+            Self::Closed => agentkern_errors::ErrorCode::Unavailable,
+        }
+    }
+}
+
+/// Batching configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchConfig {
+    /// Longest the oldest queued request waits for a batch to fill
+    pub max_wait: Duration,
+    /// Most requests in one inference
+    pub max_batch: usize,
+    /// Target end-to-end latency (queueing + inference)
+    pub latency_slo: Duration,
+    /// Outstanding requests allowed per tenant
+    pub default_quota: usize,
+    /// Per-tenant overrides of `default_quota`
+    pub tenant_quotas: HashMap<String, usize>,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_wait: Duration::from_micros(500),
+            max_batch: 32,
+            latency_slo: Duration::from_millis(20),
+            default_quota: 64,
+            tenant_quotas: HashMap::new(),
+        }
+    }
+}
+
+impl BatchConfig {
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    pub fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch.max(1);
+        self
+    }
+
+    pub fn with_latency_slo(mut self, slo: Duration) -> Self {
+        self.latency_slo = slo;
+        self
+    }
+
+    pub fn with_default_quota(mut self, quota: usize) -> Self {
+        self.default_quota = quota;
+        self
+    }
+
+    pub fn with_tenant_quota(mut self, tenant: impl Into<String>, quota: usize) -> Self {
+        self.tenant_quotas.insert(tenant.into(), quota);
+        self
+    }
+
+    fn quota(&self, tenant: &str) -> usize {
+        self.tenant_quotas.get(tenant).copied().unwrap_or(self.default_quota)
+    }
+}
+
+/// Batcher counters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchStats {
+    pub batches: u64,
+    pub items: u64,
+    /// Requests refused by a tenant quota
+    pub rejected: u64,
+    /// Current batch size target
+    pub target_batch: usize,
+    /// Smoothed inference cost per item, once a batch has run
+    pub per_item_us: Option<f64>,
+}
+
+/// Picks batch sizes that keep queueing plus inference inside the SLO.
+#[derive(Debug, Clone, Default)]
+struct LatencyModel {
+    per_item_us: Option<f64>,
+}
+
+impl LatencyModel {
+    fn observe(&mut self, items: usize, elapsed: Duration) {
+        let sample = elapsed.as_micros() as f64 / items.max(1) as f64;
+        self.per_item_us = Some(match self.per_item_us {
+            Some(estimate) => estimate + COST_SMOOTHING * (sample - estimate),
+            None => sample,
+        });
+    }
+
+    fn target(&self, config: &BatchConfig) -> usize {
+        let Some(per_item) = self.per_item_us.filter(|cost| *cost > 0.0) else {
+            return config.max_batch;
+        };
+        let budget = config.latency_slo.saturating_sub(config.max_wait).as_micros() as f64;
+        ((budget / per_item) as usize).clamp(1, config.max_batch)
+    }
+}
+
+struct Shared {
+    config: BatchConfig,
+    outstanding: Mutex<HashMap<String, usize>>,
+    model: Mutex<LatencyModel>,
+    stats: Mutex<BatchStats>,
+}
+
+/// Holds one unit of a tenant's quota until the request is answered.
+struct Permit {
+    shared: Arc<Shared>,
+    tenant: String,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut outstanding = self.shared.outstanding.lock();
+        if let Some(count) = outstanding.get_mut(&self.tenant) {
+            *count -= 1;
+            if *count == 0 {
+                outstanding.remove(&self.tenant);
+            }
+        }
+    }
+}
+
+struct Pending {
+    text: String,
+    enqueued: Instant,
+    reply: oneshot::Sender<Result<IntentResult, BatchError>>,
+    permit: Permit,
+}
+
+impl Pending {
+    /// Release the quota first, so a caller woken by the reply can reuse it.
+    fn answer(self, result: Result<IntentResult, BatchError>) {
+        drop(self.permit);
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Micro-batching front end for a shared [`NeuralGuard`].
+pub struct NeuralBatcher {
+    tx: mpsc::UnboundedSender<(String, Pending)>,
+    shared: Arc<Shared>,
+}
+
+impl NeuralBatcher {
+    /// Start the scheduler on the current Tokio runtime.
+    pub fn spawn(guard: Arc<NeuralGuard>, config: BatchConfig) -> Self {
+        let shared = Arc::new(Shared {
+            stats: Mutex::new(BatchStats { target_batch: config.max_batch, ..Default::default() }),
+            config,
+            outstanding: Mutex::new(HashMap::new()),
+            model: Mutex::new(LatencyModel::default()),
+        });
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(schedule(guard, shared.clone(), rx));
+        Self { tx, shared }
+    }
+
+    /// Classify `text` in the next batch, counting against `tenant`'s quota.
+    pub async fn classify(&self, tenant: &str, text: impl Into<String>) -> Result<IntentResult, BatchError> {
+        let permit = self.admit(tenant)?;
+        let (reply, response) = oneshot::channel();
+        let pending = Pending { text: text.into(), enqueued: Instant::now(), reply, permit };
+        self.tx.send((tenant.to_string(), pending)).map_err(|_| BatchError::Closed)?;
+        response.await.map_err(|_| BatchError::Closed)?
+    }
+
+    pub fn stats(&self) -> BatchStats {
+        self.shared.stats.lock().clone()
+    }
+
+    fn admit(&self, tenant: &str) -> Result<Permit, BatchError> {
+        let limit = self.shared.config.quota(tenant);
+        let mut outstanding = self.shared.outstanding.lock();
+        let count = outstanding.entry(tenant.to_string()).or_insert(0);
+        if *count >= limit {
+            drop(outstanding);
+            self.shared.stats.lock().rejected += 1;
+            return Err(BatchError::QuotaExceeded { tenant: tenant.to_string(), limit });
+        }
+        *count += 1;
+        Ok(Permit { shared: self.shared.clone(), tenant: tenant.to_string() })
+    }
+}
+
+/// Queued requests per tenant, drained round-robin.
+#[derive(Default)]
+struct Queues {
+    tenants: BTreeMap<String, VecDeque<Pending>>,
+    /// Tenant to start the next batch from, so no tenant is always first
+    next: usize,
+    len: usize,
+}
+
+impl Queues {
+    fn push(&mut self, tenant: String, pending: Pending) {
+        self.tenants.entry(tenant).or_default().push_back(pending);
+        self.len += 1;
+    }
+
+    fn oldest(&self) -> Option<Instant> {
+        self.tenants.values().filter_map(|queue| queue.front()).map(|p| p.enqueued).min()
+    }
+
+    /// Take up to `size` requests, one per tenant per round.
+    fn take(&mut self, size: usize) -> Vec<Pending> {
+        let tenants: Vec<String> = self.tenants.keys().cloned().collect();
+        let start = self.next % tenants.len().max(1);
+        self.next = self.next.wrapping_add(1);
+
+        let mut batch = Vec::with_capacity(size.min(self.len));
+        while batch.len() < size && self.len > 0 {
+            for tenant in tenants.iter().cycle().skip(start).take(tenants.len()) {
+                if batch.len() == size {
+                    break;
+                }
+                if let Some(pending) = self.tenants.get_mut(tenant).and_then(VecDeque::pop_front) {
+                    batch.push(pending);
+                    self.len -= 1;
+                }
+            }
+        }
+        self.tenants.retain(|_, queue| !queue.is_empty());
+        batch
+    }
+}
+
+async fn schedule(
+    guard: Arc<NeuralGuard>,
+    shared: Arc<Shared>,
+    mut rx: mpsc::UnboundedReceiver<(String, Pending)>,
+) {
+    let mut queues = Queues::default();
+    let mut open = true;
+
+    while open || queues.len > 0 {
+        if queues.len == 0 {
+            match rx.recv().await {
+                Some((tenant, pending)) => queues.push(tenant, pending),
+                None => break,
+            }
+        }
+
+        let target = shared.model.lock().target(&shared.config);
+        let deadline = queues.oldest().unwrap_or_else(Instant::now) + shared.config.max_wait;
+        while open && queues.len < target {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some((tenant, pending))) => queues.push(tenant, pending),
+                Ok(None) => open = false,
+                Err(_) => break,
+            }
+        }
+
+        let batch = queues.take(target);
+        let texts: Vec<String> = batch.iter().map(|p| p.text.clone()).collect();
+        let runner = guard.clone();
+        let started = Instant::now();
+        let results = tokio::task::spawn_blocking(move || {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            runner.batch_classify(&texts)
+        })
+        .await;
+        let elapsed = started.elapsed();
+
+        let results = match results {
+            Ok(Ok(results)) => Ok(results),
+            Ok(Err(e)) => Err(BatchError::InferenceFailed { reason: e.to_string() }),
+            Err(e) => Err(BatchError::InferenceFailed { reason: e.to_string() }),
+        };
+        let size = batch.len();
+        match results {
+            Ok(results) => {
+                for (pending, result) in batch.into_iter().zip(results) {
+                    pending.answer(Ok(result));
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, size, "Batched neural inference failed");
+                for pending in batch {
+                    pending.answer(Err(e.clone()));
+                }
+            }
+        }
+
+        let mut model = shared.model.lock();
+        model.observe(size, elapsed);
+        let mut stats = shared.stats.lock();
+        stats.batches += 1;
+        stats.items += size as u64;
+        stats.target_batch = model.target(&shared.config);
+        stats.per_item_us = model.per_item_us;
+        tracing::debug!(size, elapsed_us = elapsed.as_micros() as u64, next_target = stats.target_batch, "Neural batch");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batcher(config: BatchConfig) -> NeuralBatcher {
+        NeuralBatcher::spawn(Arc::new(NeuralGuard::new().unwrap()), config)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_batches() {
+        let config = BatchConfig::default().with_max_wait(Duration::from_millis(20)).with_max_batch(8);
+        let batcher = Arc::new(batcher(config));
+        let guard = NeuralGuard::new().unwrap();
+        let texts: [&'static str; 6] = ["transfer money", "read file", "delete data", "send token", "query database", "write file"];
+
+        let handles: Vec<_> = texts
+            .iter()
+            .enumerate()
+            .map(|(i, &text)| {
+                let batcher = batcher.clone();
+                let tenant = if i % 2 == 0 { "tenant-a" } else { "tenant-b" };
+                tokio::spawn(async move { batcher.classify(tenant, text).await })
+            })
+            .collect();
+        for (text, handle) in texts.iter().zip(handles) {
+            let result = handle.await.unwrap().unwrap();
+            assert_eq!(result.intent, guard.classify_intent(text).unwrap().intent);
+        }
+
+        let stats = batcher.stats();
+        assert_eq!(stats.items, 6);
+        assert!(stats.batches < 6, "expected batching, got {} batches", stats.batches);
+        assert!(stats.per_item_us.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_tenant_quota_rejects_excess() {
+        let config = BatchConfig::default().with_max_wait(Duration::from_millis(20)).with_tenant_quota("noisy", 1);
+        let batcher = batcher(config);
+
+        let (first, second, other) = tokio::join!(
+            batcher.classify("noisy", "transfer money"),
+            batcher.classify("noisy", "read file"),
+            batcher.classify("quiet", "read file"),
+        );
+        assert!(first.is_ok() && other.is_ok());
+        assert!(matches!(second, Err(BatchError::QuotaExceeded { limit: 1, .. })));
+        assert_eq!(batcher.stats().rejected, 1);
+
+        // Quota is released once answered
+        assert!(batcher.classify("noisy", "read file").await.is_ok());
+    }
+
+    #[test]
+    fn test_batch_size_follows_slo() {
+        let config = BatchConfig::default()
+            .with_max_batch(64)
+            .with_max_wait(Duration::from_millis(1))
+            .with_latency_slo(Duration::from_millis(11));
+        let mut model = LatencyModel::default();
+        assert_eq!(model.target(&config), 64);
+
+        // 500µs per item leaves room for 20 in the 10ms budget
+        model.observe(10, Duration::from_millis(5));
+        assert_eq!(model.target(&config), 20);
+
+        // Slower than the whole budget still runs one at a time
+        model.observe(1, Duration::from_secs(1));
+        assert_eq!(model.target(&config), 1);
+    }
+}
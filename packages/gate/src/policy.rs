@@ -1,6 +1,8 @@
 //! AgentKern-Gate: Policy Definition
 //!
-//! YAML-based policy DSL for defining guardrails.
+//! YAML-based policy DSL for defining guardrails. Parsing lives in
+//! `policy_source`, so this module builds without a YAML parser (the
+//! browser build shares it).
 //!
 //! # Example Policy (YAML)
 //!
@@ -29,11 +31,11 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use crate::dsl;
 use crate::types::DataRegion;
 
 /// A AgentKern policy definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Policy {
     /// Unique policy identifier
     pub id: String,
@@ -59,7 +61,7 @@ fn default_priority() -> i32 { 0 }
 fn default_enabled() -> bool { true }
 
 /// Individual policy rule.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PolicyRule {
     /// Rule identifier
     pub id: String,
@@ -90,14 +92,43 @@ pub enum PolicyAction {
 }
 
 impl Policy {
-    /// Parse a policy from YAML string.
-    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
-        serde_yaml::from_str(yaml)
-    }
+    /// Lint the policy: DSL syntax, duplicate rules, out-of-range scores.
+    pub fn lint(&self) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |rule: Option<&str>, level, message: String| {
+            issues.push(LintIssue {
+                policy_id: self.id.clone(),
+                rule_id: rule.map(String::from),
+                level,
+                message,
+            })
+        };
+
+        if self.id.trim().is_empty() {
+            issue(None, LintLevel::Error, "policy id is empty".to_string());
+        }
+        if self.rules.is_empty() {
+            issue(None, LintLevel::Warning, "policy has no rules".to_string());
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for rule in &self.rules {
+            let id = Some(rule.id.as_str());
+            if !seen.insert(rule.id.as_str()) {
+                issue(id, LintLevel::Error, format!("duplicate rule id '{}'", rule.id));
+            }
+            if let Err(e) = dsl::check(&rule.condition) {
+                issue(id, LintLevel::Error, e.to_string());
+            }
+            if rule.risk_score.is_some_and(|r| r > 100) {
+                issue(id, LintLevel::Error, "risk_score must be 0-100".to_string());
+            }
+            if matches!(rule.action, PolicyAction::Deny | PolicyAction::Review) && rule.message.is_none() {
+                issue(id, LintLevel::Warning, "deny/review rule has no message".to_string());
+            }
+        }
 
-    /// Serialize policy to YAML string.
-    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
-        serde_yaml::to_string(self)
+        issues
     }
 
     /// Check if this policy applies to a given jurisdiction.
@@ -109,36 +140,46 @@ impl Policy {
     }
 }
 
+/// Lint finding severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Policy must not be loaded
+    Error,
+    /// Suspicious but loadable
+    Warning,
+}
+
+/// A single lint finding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintIssue {
+    /// Policy the finding belongs to
+    pub policy_id: String,
+    /// Rule, if rule-specific
+    pub rule_id: Option<String>,
+    /// Severity
+    pub level: LintLevel,
+    /// Description
+    pub message: String,
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            LintLevel::Error => "error",
+            LintLevel::Warning => "warning",
+        };
+        match &self.rule_id {
+            Some(rule) => write!(f, "{}: {}/{}: {}", level, self.policy_id, rule, self.message),
+            None => write!(f, "{}: {}: {}", level, self.policy_id, self.message),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_policy_from_yaml() {
-        let yaml = r#"
-id: test-policy
-name: Test Policy
-description: A test policy
-priority: 100
-enabled: true
-jurisdictions: [us, eu]
-rules:
-  - id: deny-high-risk
-    condition: "action == 'delete_all'"
-    action: deny
-    message: "This action is too dangerous"
-    risk_score: 100
-"#;
-
-        let policy = Policy::from_yaml(yaml).unwrap();
-        assert_eq!(policy.id, "test-policy");
-        assert_eq!(policy.name, "Test Policy");
-        assert_eq!(policy.priority, 100);
-        assert!(policy.enabled);
-        assert_eq!(policy.rules.len(), 1);
-        assert_eq!(policy.rules[0].action, PolicyAction::Deny);
-    }
-
     #[test]
     fn test_jurisdiction_matching() {
         let policy = Policy {
@@ -155,4 +196,22 @@ rules:
         assert!(policy.applies_to_jurisdiction(DataRegion::Us));
         assert!(!policy.applies_to_jurisdiction(DataRegion::Cn));
     }
+
+    #[test]
+    fn test_lint() {
+        let policy: Policy = serde_json::from_value(serde_json::json!({
+            "id": "lint-me",
+            "name": "Lint Me",
+            "rules": [
+                {"id": "bad-condition", "condition": "amount > 100", "action": "deny", "message": "too much"},
+                {"id": "bad-condition", "condition": "action == 'x'", "action": "review", "risk_score": 120}
+            ]
+        }))
+        .unwrap();
+        let issues = policy.lint();
+        let errors: Vec<_> = issues.iter().filter(|i| i.level == LintLevel::Error).collect();
+        assert_eq!(errors.len(), 3); // unknown identifier, duplicate id, risk_score
+        assert!(issues.iter().any(|i| i.level == LintLevel::Warning));
+        assert!(errors[0].to_string().starts_with("error: lint-me/bad-condition:"));
+    }
 }
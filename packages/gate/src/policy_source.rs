@@ -0,0 +1,397 @@
+//! AgentKern-Gate: Policy Sources
+//!
+//! Where policies are loaded from, for startup and hot reload.
+//!
+//! - [`FileSource`]: a YAML policy file, or a directory of them
+//! - [`StaticSource`]: an in-memory set (e.g. pushed over the API)
+//! - [`GitSource`](crate::git_source::GitSource): signed commits in a Git repository
+//!
+//! Each YAML file holds either a single policy or a list of policies.
+//! Reloads are atomic: a source with lint errors is rejected and the
+//! engine keeps its current policies. Versioned sources also carry a
+//! [`RolloutPlan`] staging the new revision per environment.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_gate::policy_source::FileSource;
+//!
+//! let diff = engine.reload_from(&FileSource::new("/etc/agentkern/policies")).await?;
+//! tracing::info!(added = ?diff.added, removed = ?diff.removed, "Policies reloaded");
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::policy::{LintIssue, LintLevel, Policy};
+
+/// Policy source errors.
+#[derive(Debug, Error)]
+pub enum PolicySourceError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse {path}: {reason}")]
+    Parse { path: PathBuf, reason: String },
+    #[error("Duplicate policy id '{0}'")]
+    Duplicate(String),
+    #[error("Policies failed lint: {}", .0.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; "))]
+    Invalid(Vec<LintIssue>),
+    #[error("Git error in {repo}: {reason}")]
+    Git { repo: PathBuf, reason: String },
+    #[error("Commit {commit} rejected: {reason}")]
+    Untrusted { commit: String, reason: String },
+}
+
+impl agentkern_errors::Coded for PolicySourceError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::Io { .. } | Self::Git { .. } => ErrorCode::Unavailable,
+            Self::Untrusted { .. } => ErrorCode::PermissionDenied,
+            Self::Parse { .. } | Self::Duplicate(_) | Self::Invalid(_) => ErrorCode::PolicyInvalid,
+        }
+    }
+}
+
+/// A place policies can be (re)loaded from.
+pub trait PolicySource: Send + Sync {
+    /// Human-readable description for logs.
+    fn describe(&self) -> String;
+
+    /// Load the full policy set.
+    fn load(&self) -> Result<Vec<Policy>, PolicySourceError>;
+
+    /// Load the policy set with its revision and rollout plan.
+    ///
+    /// Unversioned sources have no revision and enforce immediately.
+    fn load_revision(&self) -> Result<PolicyRevision, PolicySourceError> {
+        Ok(PolicyRevision { policies: self.load()?, commit: None, rollout: RolloutPlan::default() })
+    }
+}
+
+/// A policy set as of one source revision.
+#[derive(Debug, Clone)]
+pub struct PolicyRevision {
+    pub policies: Vec<Policy>,
+    /// Commit the policies were loaded from, for versioned sources
+    pub commit: Option<String>,
+    pub rollout: RolloutPlan,
+}
+
+/// How far a new policy revision is rolled out in an environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RolloutStage {
+    /// Evaluate alongside the current policies and log divergence only
+    Shadow,
+    /// Enforce for this percentage of agents
+    Canary(u8),
+    /// Replace the current policies
+    Enforce,
+}
+
+impl RolloutStage {
+    /// Does the new revision decide for `agent_id`?
+    ///
+    /// Canary buckets are stable per agent, so an agent sees one policy set.
+    pub fn enforces_for(&self, agent_id: &str) -> bool {
+        match *self {
+            Self::Shadow => false,
+            Self::Canary(percent) => {
+                use sha2::{Digest, Sha256};
+                let hash = Sha256::digest(agent_id.as_bytes());
+                let bucket = u64::from_be_bytes(hash[..8].try_into().expect("8 bytes")) % 100;
+                bucket < percent as u64
+            }
+            Self::Enforce => true,
+        }
+    }
+}
+
+/// Rollout stage per environment (`rollout.yaml` in a policy repository).
+///
+/// ```yaml
+/// default: shadow
+/// environments:
+///   staging: enforce
+///   production: !canary 10
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RolloutPlan {
+    /// Stage for environments not listed
+    #[serde(default = "RolloutPlan::default_stage")]
+    pub default: RolloutStage,
+    #[serde(default)]
+    pub environments: HashMap<String, RolloutStage>,
+}
+
+impl Default for RolloutPlan {
+    fn default() -> Self {
+        Self { default: RolloutStage::Enforce, environments: HashMap::new() }
+    }
+}
+
+impl RolloutPlan {
+    fn default_stage() -> RolloutStage {
+        RolloutStage::Enforce
+    }
+
+    /// Stage for an environment; engines without one use the default.
+    pub fn stage_for(&self, environment: Option<&str>) -> RolloutStage {
+        environment
+            .and_then(|env| self.environments.get(env))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Policies from a YAML file or a directory of `.yaml` / `.yml` files.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: PathBuf,
+}
+
+impl FileSource {
+    /// Create a source for a file or directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path this source reads.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn files(&self) -> Result<Vec<PathBuf>, PolicySourceError> {
+        let io = |source| PolicySourceError::Io { path: self.path.clone(), source };
+        if !self.path.is_dir() {
+            return Ok(vec![self.path.clone()]);
+        }
+
+        let mut files: Vec<_> = std::fs::read_dir(&self.path)
+            .map_err(io)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+impl PolicySource for FileSource {
+    fn describe(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+
+    fn load(&self) -> Result<Vec<Policy>, PolicySourceError> {
+        let mut policies = Vec::new();
+        for path in self.files()? {
+            let yaml = std::fs::read_to_string(&path)
+                .map_err(|source| PolicySourceError::Io { path: path.clone(), source })?;
+            policies.extend(parse_policies(&yaml).map_err(|reason| PolicySourceError::Parse {
+                path: path.clone(),
+                reason,
+            })?);
+        }
+        check_unique(&policies)?;
+        Ok(policies)
+    }
+}
+
+/// An in-memory policy set.
+#[derive(Debug, Clone, Default)]
+pub struct StaticSource {
+    policies: Vec<Policy>,
+}
+
+impl StaticSource {
+    /// Create a source from policies.
+    pub fn new(policies: Vec<Policy>) -> Self {
+        Self { policies }
+    }
+}
+
+impl PolicySource for StaticSource {
+    fn describe(&self) -> String {
+        format!("static:{} policies", self.policies.len())
+    }
+
+    fn load(&self) -> Result<Vec<Policy>, PolicySourceError> {
+        check_unique(&self.policies)?;
+        Ok(self.policies.clone())
+    }
+}
+
+impl Policy {
+    /// Parse a policy from YAML string.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    /// Serialize policy to YAML string.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+/// Parse a YAML document holding one policy or a list of policies.
+pub(crate) fn parse_policies(yaml: &str) -> Result<Vec<Policy>, String> {
+    match serde_yaml::from_str::<Vec<Policy>>(yaml) {
+        Ok(list) => Ok(list),
+        Err(_) => Policy::from_yaml(yaml).map(|p| vec![p]).map_err(|e| e.to_string()),
+    }
+}
+
+pub(crate) fn check_unique(policies: &[Policy]) -> Result<(), PolicySourceError> {
+    let mut seen = HashSet::new();
+    match policies.iter().find(|p| !seen.insert(p.id.as_str())) {
+        Some(dup) => Err(PolicySourceError::Duplicate(dup.id.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Lint a policy set; errors only.
+pub fn lint_errors(policies: &[Policy]) -> Vec<LintIssue> {
+    policies
+        .iter()
+        .flat_map(Policy::lint)
+        .filter(|issue| issue.level == LintLevel::Error)
+        .collect()
+}
+
+/// Difference between two policy sets, by policy id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDiff {
+    /// Only in the new set
+    pub added: Vec<String>,
+    /// Only in the old set
+    pub removed: Vec<String>,
+    /// In both, with different content
+    pub changed: Vec<String>,
+}
+
+impl PolicyDiff {
+    /// Compute the diff from `old` to `new`. IDs are sorted.
+    pub fn between(old: &[Policy], new: &[Policy]) -> Self {
+        let old: BTreeMap<_, _> = old.iter().map(|p| (p.id.as_str(), p)).collect();
+        let new: BTreeMap<_, _> = new.iter().map(|p| (p.id.as_str(), p)).collect();
+
+        let mut diff = Self::default();
+        for (id, policy) in &new {
+            match old.get(id) {
+                None => diff.added.push(id.to_string()),
+                Some(existing) if existing != policy => diff.changed.push(id.to_string()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old.keys().filter(|id| !new.contains_key(*id)).map(|id| id.to_string()).collect();
+        diff
+    }
+
+    /// No differences?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SINGLE: &str = r#"
+id: spending
+name: Spending
+rules:
+  - id: max
+    condition: "action == 'transfer_funds' && context.amount > 10000"
+    action: deny
+    message: "too large"
+"#;
+
+    const LIST: &str = r#"
+- id: audit
+  name: Audit
+  rules:
+    - id: all
+      condition: "action == 'transfer_funds'"
+      action: audit
+- id: deletes
+  name: Deletes
+  rules:
+    - id: db
+      condition: "action == 'drop_table'"
+      action: deny
+      message: "no"
+"#;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gate-policies-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_file_source_directory() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("a.yaml"), SINGLE).unwrap();
+        std::fs::write(dir.join("b.yml"), LIST).unwrap();
+        std::fs::write(dir.join("README.md"), "ignored").unwrap();
+
+        let policies = FileSource::new(&dir).load().unwrap();
+        let ids: Vec<_> = policies.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["spending", "audit", "deletes"]);
+
+        std::fs::write(dir.join("c.yaml"), SINGLE).unwrap();
+        assert!(matches!(FileSource::new(&dir).load(), Err(PolicySourceError::Duplicate(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_policy_diff() {
+        let old = parse_policies(LIST).unwrap();
+        let mut new = parse_policies(SINGLE).unwrap();
+        let mut changed = old[0].clone();
+        changed.priority = 50;
+        new.push(changed);
+
+        let diff = PolicyDiff::between(&old, &new);
+        assert_eq!(diff.added, ["spending"]);
+        assert_eq!(diff.removed, ["deletes"]);
+        assert_eq!(diff.changed, ["audit"]);
+        assert!(PolicyDiff::between(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_policy_from_yaml() {
+        let yaml = r#"
+id: test-policy
+name: Test Policy
+description: A test policy
+priority: 100
+enabled: true
+jurisdictions: [us, eu]
+rules:
+  - id: deny-high-risk
+    condition: "action == 'delete_all'"
+    action: deny
+    message: "This action is too dangerous"
+    risk_score: 100
+"#;
+
+        let policy = Policy::from_yaml(yaml).unwrap();
+        assert_eq!(policy.id, "test-policy");
+        assert_eq!(policy.name, "Test Policy");
+        assert_eq!(policy.priority, 100);
+        assert!(policy.enabled);
+        assert_eq!(policy.rules.len(), 1);
+        assert_eq!(policy.rules[0].action, crate::policy::PolicyAction::Deny);
+    }
+}
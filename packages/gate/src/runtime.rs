@@ -67,6 +67,17 @@ pub mod uring {
     ) -> std::io::Result<tokio_uring::fs::File> {
         tokio_uring::fs::File::create(path).await
     }
+
+    /// Probe whether the running kernel accepts io_uring.
+    ///
+    /// Fails on pre-5.1 kernels, seccomp-filtered containers, and hosts with
+    /// `kernel.io_uring_disabled = 2`.
+    pub fn kernel_supports_io_uring() -> bool {
+        let disabled = std::fs::read_to_string("/proc/sys/kernel/io_uring_disabled")
+            .map(|v| v.trim() == "2")
+            .unwrap_or(false);
+        !disabled && io_uring::IoUring::new(2).is_ok()
+    }
 }
 
 // ============================================================================
@@ -123,9 +134,15 @@ impl HyperRuntime {
     }
 
     /// Run a future on the best available runtime.
+    /// Falls back to Tokio when the kernel lacks io_uring.
     #[cfg(all(target_os = "linux", feature = "io_uring"))]
     pub fn run<F: Future>(future: F) -> F::Output {
-        uring::start(future)
+        if uring::kernel_supports_io_uring() {
+            uring::start(future)
+        } else {
+            let rt = TokioRuntime::new().expect("Failed to create runtime");
+            rt.block_on(future)
+        }
     }
 
     #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
@@ -138,11 +155,11 @@ impl HyperRuntime {
         &self.config
     }
 
-    /// Check if io_uring is available.
+    /// Check if io_uring is compiled in and supported by the kernel.
     pub fn is_io_uring_available() -> bool {
         #[cfg(all(target_os = "linux", feature = "io_uring"))]
         {
-            true
+            uring::kernel_supports_io_uring()
         }
         #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
         {
@@ -185,7 +202,7 @@ mod tests {
     fn test_io_uring_detection() {
         let available = HyperRuntime::is_io_uring_available();
         #[cfg(all(target_os = "linux", feature = "io_uring"))]
-        assert!(available);
+        assert_eq!(available, uring::kernel_supports_io_uring());
         #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
         assert!(!available);
     }
@@ -7,16 +7,29 @@
 //! - Interest (Riba) detection
 //! - Gharar (uncertainty) risk assessment
 //! - Takaful pool logic vs conventional insurance
+//! - Configurable scholar rulesets (AAOIFI defaults shipped), several per tenant
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use agentkern_gate::takaful::{TakafulValidator, TransactionType};
+//! use agentkern_gate::takaful::{RulesetRegistry, TakafulValidator, TransactionType};
 //!
 //! let validator = TakafulValidator::new();
 //! let result = validator.validate_transaction(TransactionType::Insurance)?;
+//!
+//! let registry = RulesetRegistry::new();
+//! registry.load_file("rulesets/takaful/dsn-mui.yaml")?;
+//! registry.assign("tenant-a", &["aaoifi", "dsn-mui"])?;
+//! for result in registry.validate_for_tenant("tenant-a", &details)? {
+//!     println!("{}: {:?}", result.ruleset, result.findings);
+//! }
 //! ```
 
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -31,6 +44,14 @@ pub enum TakafulError {
     MaysirDetected,
     #[error("Transaction not Shariah-compliant: {reason}")]
     NotShariaCompliant { reason: String },
+    #[error("Prohibited sector: {sector}")]
+    ProhibitedSector { sector: String },
+    #[error("Financial ratio {ratio} of {value:.2} exceeds limit {limit:.2}")]
+    RatioExceeded { ratio: String, value: f64, limit: f64 },
+    #[error("Invalid ruleset: {reason}")]
+    InvalidRuleset { reason: String },
+    #[error("Unknown ruleset: {id}")]
+    UnknownRuleset { id: String },
 }
 
 /// Type of financial transaction.
@@ -68,6 +89,21 @@ pub struct ComplianceResult {
     pub has_maysir: bool,
     /// Recommendations for compliance
     pub recommendations: Vec<String>,
+    /// Ruleset that produced this result
+    pub ruleset: String,
+    /// Rules that fired, in evaluation order
+    pub findings: Vec<RuleFinding>,
+}
+
+/// A single rule that fired during validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleFinding {
+    /// Rule ID (e.g. "riba", "debt_ratio")
+    pub rule: String,
+    /// Score deducted
+    pub penalty: u8,
+    /// Does this rule alone make the transaction non-compliant?
+    pub disqualifying: bool,
 }
 
 /// Risk level enumeration.
@@ -96,6 +132,23 @@ pub struct TransactionDetails {
     pub risk_sharing_pct: f64,
     /// Underlying asset present?
     pub has_underlying_asset: bool,
+    /// Business sector of the counterparty / investee
+    #[serde(default)]
+    pub sector: Option<String>,
+    /// Financial ratios of the investee, for equity screening
+    #[serde(default)]
+    pub financials: Option<CompanyFinancials>,
+}
+
+/// Financial ratios of a company being screened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompanyFinancials {
+    /// Interest-bearing debt / market capitalisation
+    pub debt_ratio: f64,
+    /// Interest-bearing securities and deposits / market capitalisation
+    pub interest_bearing_ratio: f64,
+    /// Impermissible income / total revenue
+    pub impermissible_income_ratio: f64,
 }
 
 impl Default for TransactionDetails {
@@ -108,30 +161,291 @@ impl Default for TransactionDetails {
             guaranteed_outcome: false,
             risk_sharing_pct: 0.0,
             has_underlying_asset: true,
+            sector: None,
+            financials: None,
+        }
+    }
+}
+
+// ============================================================================
+// Scholar rulesets
+// ============================================================================
+
+/// Shipped AAOIFI ruleset (Shariah Standard No. 21).
+const AAOIFI_RULESET: &str = include_str!("../rulesets/takaful/aaoifi.yaml");
+
+/// Financial-ratio screening limits (fractions, 0.0-1.0).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FinancialRatioLimits {
+    /// Max interest-bearing debt / market capitalisation
+    pub max_debt_ratio: f64,
+    /// Max interest-bearing securities and deposits / market capitalisation
+    pub max_interest_bearing_ratio: f64,
+    /// Max impermissible income / total revenue
+    pub max_impermissible_income_ratio: f64,
+}
+
+/// One Shariah board's screening interpretation, loaded from a ruleset file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScholarRuleset {
+    /// Unique ruleset ID (e.g. "aaoifi")
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Issuing Shariah board
+    #[serde(default)]
+    pub authority: String,
+    /// Ruling version
+    #[serde(default)]
+    pub version: String,
+    /// Sectors whose core business is impermissible
+    #[serde(default)]
+    pub prohibited_sectors: Vec<String>,
+    /// Financial-ratio screens
+    pub ratios: FinancialRatioLimits,
+    /// Max Murabaha profit margin (%)
+    pub max_profit_margin_pct: f64,
+    /// Min risk sharing for Takaful/Musharakah (%)
+    pub min_risk_sharing_pct: f64,
+    /// Min score for a compliant result
+    pub pass_score: u8,
+}
+
+impl Default for ScholarRuleset {
+    fn default() -> Self {
+        Self::aaoifi()
+    }
+}
+
+impl ScholarRuleset {
+    /// The shipped AAOIFI defaults.
+    pub fn aaoifi() -> Self {
+        aaoifi_ruleset().as_ref().clone()
+    }
+
+    /// Parse and validate a ruleset from YAML.
+    pub fn from_yaml(yaml: &str) -> Result<Self, TakafulError> {
+        let ruleset: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| TakafulError::InvalidRuleset { reason: e.to_string() })?;
+        ruleset.check()?;
+        Ok(ruleset)
+    }
+
+    /// Load a ruleset file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TakafulError> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path).map_err(|e| TakafulError::InvalidRuleset {
+            reason: format!("{}: {}", path.display(), e),
+        })?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Is the sector prohibited by this ruleset?
+    pub fn is_prohibited_sector(&self, sector: &str) -> bool {
+        let sector = sector.trim();
+        self.prohibited_sectors.iter().any(|s| s.eq_ignore_ascii_case(sector))
+    }
+
+    fn check(&self) -> Result<(), TakafulError> {
+        let invalid = |reason: String| Err(TakafulError::InvalidRuleset { reason });
+        if self.id.trim().is_empty() {
+            return invalid("ruleset id is empty".to_string());
+        }
+        let ratios = [
+            ("max_debt_ratio", self.ratios.max_debt_ratio),
+            ("max_interest_bearing_ratio", self.ratios.max_interest_bearing_ratio),
+            ("max_impermissible_income_ratio", self.ratios.max_impermissible_income_ratio),
+        ];
+        for (name, value) in ratios {
+            if !(0.0..=1.0).contains(&value) {
+                return invalid(format!("{} must be between 0 and 1, got {}", name, value));
+            }
+        }
+        for (name, value) in [
+            ("max_profit_margin_pct", self.max_profit_margin_pct),
+            ("min_risk_sharing_pct", self.min_risk_sharing_pct),
+        ] {
+            if !(0.0..=100.0).contains(&value) {
+                return invalid(format!("{} must be between 0 and 100, got {}", name, value));
+            }
         }
+        if self.pass_score > 100 {
+            return invalid(format!("pass_score must be at most 100, got {}", self.pass_score));
+        }
+        Ok(())
     }
 }
 
+fn aaoifi_ruleset() -> Arc<ScholarRuleset> {
+    static AAOIFI: OnceLock<Arc<ScholarRuleset>> = OnceLock::new();
+    AAOIFI
+        .get_or_init(|| {
+            Arc::new(ScholarRuleset::from_yaml(AAOIFI_RULESET).expect("shipped AAOIFI ruleset is valid"))
+        })
+        .clone()
+}
+
+/// Loaded rulesets and the rulesets each tenant screens against.
+///
+/// Tenants without an assignment use the AAOIFI defaults.
+#[derive(Debug)]
+pub struct RulesetRegistry {
+    rulesets: RwLock<HashMap<String, Arc<ScholarRuleset>>>,
+    tenants: RwLock<HashMap<String, Vec<String>>>,
+}
+
+impl Default for RulesetRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RulesetRegistry {
+    /// Create a registry holding the AAOIFI defaults.
+    pub fn new() -> Self {
+        let aaoifi = aaoifi_ruleset();
+        Self {
+            rulesets: RwLock::new(HashMap::from([(aaoifi.id.clone(), aaoifi)])),
+            tenants: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register (or replace) a ruleset.
+    pub fn register(&self, ruleset: ScholarRuleset) -> Result<(), TakafulError> {
+        ruleset.check()?;
+        self.rulesets.write().insert(ruleset.id.clone(), Arc::new(ruleset));
+        Ok(())
+    }
+
+    /// Load and register a ruleset file. Returns its ID.
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<String, TakafulError> {
+        let ruleset = ScholarRuleset::from_file(path)?;
+        let id = ruleset.id.clone();
+        self.register(ruleset)?;
+        Ok(id)
+    }
+
+    /// Load every `.yaml` / `.yml` ruleset in a directory. Returns their IDs.
+    pub fn load_dir(&self, dir: impl AsRef<Path>) -> Result<Vec<String>, TakafulError> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).map_err(|e| TakafulError::InvalidRuleset {
+            reason: format!("{}: {}", dir.display(), e),
+        })?;
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+            .collect();
+        paths.sort();
+
+        paths.iter().map(|path| self.load_file(path)).collect()
+    }
+
+    /// Look up a ruleset by ID.
+    pub fn get(&self, id: &str) -> Option<Arc<ScholarRuleset>> {
+        self.rulesets.read().get(id).cloned()
+    }
+
+    /// IDs of all registered rulesets, sorted.
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<_> = self.rulesets.read().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Set the rulesets a tenant screens against.
+    pub fn assign(&self, tenant_id: &str, ruleset_ids: &[&str]) -> Result<(), TakafulError> {
+        if ruleset_ids.is_empty() {
+            return Err(TakafulError::InvalidRuleset {
+                reason: format!("tenant {} needs at least one ruleset", tenant_id),
+            });
+        }
+        {
+            let rulesets = self.rulesets.read();
+            if let Some(missing) = ruleset_ids.iter().find(|id| !rulesets.contains_key(**id)) {
+                return Err(TakafulError::UnknownRuleset { id: missing.to_string() });
+            }
+        }
+        self.tenants.write().insert(
+            tenant_id.to_string(),
+            ruleset_ids.iter().map(|id| id.to_string()).collect(),
+        );
+        Ok(())
+    }
+
+    /// Rulesets a tenant screens against (AAOIFI if unassigned).
+    pub fn rulesets_for(&self, tenant_id: &str) -> Vec<Arc<ScholarRuleset>> {
+        let tenants = self.tenants.read();
+        let Some(ids) = tenants.get(tenant_id) else {
+            return vec![aaoifi_ruleset()];
+        };
+        let rulesets = self.rulesets.read();
+        ids.iter().filter_map(|id| rulesets.get(id).cloned()).collect()
+    }
+
+    /// Validate against every ruleset assigned to a tenant.
+    /// Returns one result per ruleset, in assignment order.
+    pub fn validate_for_tenant(
+        &self,
+        tenant_id: &str,
+        details: &TransactionDetails,
+    ) -> Result<Vec<ComplianceResult>, TakafulError> {
+        self.rulesets_for(tenant_id)
+            .into_iter()
+            .map(|ruleset| TakafulValidator::with_ruleset(ruleset).validate(details))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Validator
+// ============================================================================
+
 /// Takaful compliance validator.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TakafulValidator {
     /// Strict mode (reject any non-compliant transaction)
     strict_mode: bool,
+    /// Scholar ruleset providing thresholds
+    ruleset: Arc<ScholarRuleset>,
+}
+
+impl Default for TakafulValidator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TakafulValidator {
-    /// Create a new validator.
+    /// Create a new validator using the AAOIFI ruleset.
     pub fn new() -> Self {
-        Self { strict_mode: false }
+        Self { strict_mode: false, ruleset: aaoifi_ruleset() }
     }
 
     /// Create a validator in strict mode.
     pub fn strict() -> Self {
-        Self { strict_mode: true }
+        Self { strict_mode: true, ..Self::new() }
+    }
+
+    /// Create a validator for a specific scholar ruleset.
+    pub fn with_ruleset(ruleset: impl Into<Arc<ScholarRuleset>>) -> Self {
+        Self { strict_mode: false, ruleset: ruleset.into() }
+    }
+
+    /// Enable strict mode.
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict_mode = strict;
+        self
+    }
+
+    /// The active ruleset.
+    pub fn ruleset(&self) -> &ScholarRuleset {
+        &self.ruleset
     }
 
     /// Validate a transaction for Shariah compliance.
     pub fn validate(&self, details: &TransactionDetails) -> Result<ComplianceResult, TakafulError> {
+        let rules = &self.ruleset;
         let mut result = ComplianceResult {
             compliant: true,
             score: 100,
@@ -139,6 +453,8 @@ impl TakafulValidator {
             has_riba: false,
             has_maysir: false,
             recommendations: vec![],
+            ruleset: rules.id.clone(),
+            findings: vec![],
         };
 
         // Check for Riba (interest)
@@ -146,7 +462,7 @@ impl TakafulValidator {
             if rate > 0.0 {
                 result.has_riba = true;
                 result.compliant = false;
-                result.score = result.score.saturating_sub(50);
+                fire(&mut result, "riba", 50, true);
                 result.recommendations.push(
                     "Replace interest-based financing with Murabaha (cost-plus) or Musharakah (profit-sharing)".to_string()
                 );
@@ -160,7 +476,7 @@ impl TakafulValidator {
         // Check for Gharar (excessive uncertainty)
         if !details.has_underlying_asset {
             result.gharar_risk = RiskLevel::High;
-            result.score = result.score.saturating_sub(20);
+            fire(&mut result, "gharar", 20, false);
             result.recommendations.push(
                 "Ensure transaction has a tangible underlying asset".to_string()
             );
@@ -169,7 +485,7 @@ impl TakafulValidator {
         // Check for Maysir (gambling)
         if details.guaranteed_outcome && details.transaction_type == TransactionType::Insurance {
             result.has_maysir = true;
-            result.score = result.score.saturating_sub(30);
+            fire(&mut result, "maysir", 30, false);
             result.recommendations.push(
                 "Convert to Takaful model with mutual risk sharing".to_string()
             );
@@ -179,30 +495,65 @@ impl TakafulValidator {
             }
         }
 
+        // Sector screen
+        if let Some(sector) = details.sector.as_deref().filter(|s| rules.is_prohibited_sector(s)) {
+            fire(&mut result, "prohibited_sector", 50, true);
+            result.recommendations.push(format!(
+                "Sector '{}' is impermissible under {}",
+                sector, rules.name
+            ));
+
+            if self.strict_mode {
+                return Err(TakafulError::ProhibitedSector { sector: sector.to_string() });
+            }
+        }
+
+        // Financial-ratio screens
+        if let Some(financials) = &details.financials {
+            let screens = [
+                ("debt_ratio", financials.debt_ratio, rules.ratios.max_debt_ratio),
+                ("interest_bearing_ratio", financials.interest_bearing_ratio, rules.ratios.max_interest_bearing_ratio),
+                ("impermissible_income_ratio", financials.impermissible_income_ratio, rules.ratios.max_impermissible_income_ratio),
+            ];
+            for (ratio, value, limit) in screens {
+                if value <= limit {
+                    continue;
+                }
+                fire(&mut result, ratio, 20, true);
+                result.recommendations.push(format!(
+                    "{} of {:.1}% exceeds the {:.1}% limit of {}",
+                    ratio, value * 100.0, limit * 100.0, rules.name
+                ));
+
+                if self.strict_mode {
+                    return Err(TakafulError::RatioExceeded { ratio: ratio.to_string(), value, limit });
+                }
+            }
+        }
+
         // Check risk sharing for Islamic finance
         match details.transaction_type {
-            TransactionType::Takaful | TransactionType::Musharakah => {
-                if details.risk_sharing_pct < 50.0 {
-                    result.score = result.score.saturating_sub(10);
-                    result.recommendations.push(
-                        "Increase risk sharing ratio for better compliance".to_string()
-                    );
-                }
+            TransactionType::Takaful | TransactionType::Musharakah
+                if details.risk_sharing_pct < rules.min_risk_sharing_pct =>
+            {
+                fire(&mut result, "risk_sharing", 10, false);
+                result.recommendations.push(
+                    "Increase risk sharing ratio for better compliance".to_string()
+                );
             }
-            TransactionType::Murabaha => {
-                if details.profit_margin.unwrap_or(0.0) > 30.0 {
-                    result.score = result.score.saturating_sub(10);
-                    result.gharar_risk = RiskLevel::Medium;
-                    result.recommendations.push(
-                        "Consider reducing profit margin to align with market rates".to_string()
-                    );
-                }
+            TransactionType::Murabaha if details.profit_margin.unwrap_or(0.0) > rules.max_profit_margin_pct => {
+                fire(&mut result, "profit_margin", 10, false);
+                result.gharar_risk = RiskLevel::Medium;
+                result.recommendations.push(
+                    "Consider reducing profit margin to align with market rates".to_string()
+                );
             }
             _ => {}
         }
 
         // Update compliance status
-        result.compliant = result.score >= 70 && !result.has_riba;
+        result.compliant = result.score >= rules.pass_score
+            && !result.findings.iter().any(|f| f.disqualifying);
 
         Ok(result)
     }
@@ -217,6 +568,8 @@ impl TakafulValidator {
             guaranteed_outcome: false,
             risk_sharing_pct: 100.0, // Full mutual risk sharing
             has_underlying_asset: true,
+            sector: details.sector.clone(),
+            financials: details.financials,
         }
     }
 
@@ -233,6 +586,16 @@ impl TakafulValidator {
     }
 }
 
+/// Record a fired rule and deduct its penalty.
+fn fire(result: &mut ComplianceResult, rule: &str, penalty: u8, disqualifying: bool) {
+    result.score = result.score.saturating_sub(penalty);
+    result.findings.push(RuleFinding {
+        rule: rule.to_string(),
+        penalty,
+        disqualifying,
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,6 +625,7 @@ mod tests {
             guaranteed_outcome: false,
             risk_sharing_pct: 100.0,
             has_underlying_asset: true,
+            ..Default::default()
         };
         
         let result = validator.validate(&details).unwrap();
@@ -309,4 +673,112 @@ mod tests {
         assert!(!validator.is_compliant_type(TransactionType::Insurance));
         assert!(!validator.is_compliant_type(TransactionType::Loan));
     }
+
+    #[test]
+    fn test_shipped_aaoifi_ruleset() {
+        let ruleset = ScholarRuleset::aaoifi();
+        assert_eq!(ruleset.id, "aaoifi");
+        assert_eq!(ruleset.ratios.max_debt_ratio, 0.30);
+        assert_eq!(ruleset.ratios.max_impermissible_income_ratio, 0.05);
+        assert!(ruleset.is_prohibited_sector("Alcohol"));
+        assert_eq!(TakafulValidator::new().ruleset().id, "aaoifi");
+    }
+
+    #[test]
+    fn test_findings_name_ruleset_and_rule() {
+        let validator = TakafulValidator::new();
+        let details = TransactionDetails {
+            interest_rate: Some(4.0),
+            sector: Some("gambling".to_string()),
+            ..Default::default()
+        };
+
+        let result = validator.validate(&details).unwrap();
+        assert_eq!(result.ruleset, "aaoifi");
+        let rules: Vec<_> = result.findings.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(rules, ["riba", "prohibited_sector"]);
+        assert!(!result.compliant);
+    }
+
+    #[test]
+    fn test_configurable_ratio_thresholds() {
+        let details = TransactionDetails {
+            financials: Some(CompanyFinancials {
+                debt_ratio: 0.32,
+                interest_bearing_ratio: 0.10,
+                impermissible_income_ratio: 0.01,
+            }),
+            ..Default::default()
+        };
+
+        let aaoifi = TakafulValidator::new().validate(&details).unwrap();
+        assert!(!aaoifi.compliant);
+        assert_eq!(aaoifi.findings[0].rule, "debt_ratio");
+
+        // A board using a 33% debt threshold accepts the same company
+        let lenient = AAOIFI_RULESET
+            .replace("id: aaoifi", "id: lenient")
+            .replace("max_debt_ratio: 0.30", "max_debt_ratio: 0.33");
+        let ruleset = ScholarRuleset::from_yaml(&lenient).unwrap();
+        let result = TakafulValidator::with_ruleset(ruleset).validate(&details).unwrap();
+        assert!(result.compliant);
+        assert_eq!(result.ruleset, "lenient");
+
+        let strict = TakafulValidator::strict().validate(&details);
+        assert!(matches!(strict, Err(TakafulError::RatioExceeded { .. })));
+    }
+
+    #[test]
+    fn test_invalid_ruleset_rejected() {
+        let bad = AAOIFI_RULESET.replace("max_debt_ratio: 0.30", "max_debt_ratio: 30");
+        assert!(matches!(
+            ScholarRuleset::from_yaml(&bad),
+            Err(TakafulError::InvalidRuleset { .. })
+        ));
+    }
+
+    #[test]
+    fn test_registry_multiple_rulesets_per_tenant() {
+        let registry = RulesetRegistry::new();
+        let mut tobacco = ScholarRuleset::aaoifi();
+        tobacco.id = "tobacco-free".to_string();
+        tobacco.prohibited_sectors.push("tobacco".to_string());
+        registry.register(tobacco).unwrap();
+
+        registry.assign("tenant-a", &["aaoifi", "tobacco-free"]).unwrap();
+        assert!(matches!(
+            registry.assign("tenant-b", &["missing"]),
+            Err(TakafulError::UnknownRuleset { .. })
+        ));
+
+        let details = TransactionDetails {
+            sector: Some("tobacco".to_string()),
+            ..Default::default()
+        };
+        let results = registry.validate_for_tenant("tenant-a", &details).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].ruleset, "aaoifi");
+        assert!(results[0].compliant);
+        assert_eq!(results[1].ruleset, "tobacco-free");
+        assert!(!results[1].compliant);
+
+        // Unassigned tenants fall back to AAOIFI
+        let results = registry.validate_for_tenant("tenant-c", &details).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].ruleset, "aaoifi");
+    }
+
+    #[test]
+    fn test_registry_load_dir() {
+        let dir = std::env::temp_dir().join(format!("takaful-rulesets-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("custom.yaml"), AAOIFI_RULESET.replace("id: aaoifi", "id: custom")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let registry = RulesetRegistry::new();
+        assert_eq!(registry.load_dir(&dir).unwrap(), vec!["custom".to_string()]);
+        assert_eq!(registry.ids(), vec!["aaoifi".to_string(), "custom".to_string()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -34,6 +34,20 @@ pub enum TeeError {
     QuoteVerificationFailed,
 }
 
+impl agentkern_errors::Coded for TeeError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::NotAvailable => ErrorCode::Unavailable,
+            Self::NotSupported { .. } => ErrorCode::Unsupported,
+            Self::AttestationFailed { .. } | Self::SealingFailed { .. } | Self::UnsealingFailed { .. } => {
+                ErrorCode::Internal
+            }
+            Self::QuoteVerificationFailed => ErrorCode::Unauthenticated,
+        }
+    }
+}
+
 /// TEE platform type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TeePlatform {
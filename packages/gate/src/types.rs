@@ -2,6 +2,7 @@
 //!
 //! Domain types for the verification engine.
 
+use agentkern_telemetry::TraceContext;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -19,6 +20,9 @@ pub struct VerificationRequest {
     pub context: VerificationContext,
     /// Timestamp of the request
     pub timestamp: DateTime<Utc>,
+    /// Caller's trace context; verification runs in a child span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
 }
 
 /// Context for policy evaluation.
@@ -50,6 +54,12 @@ pub struct VerificationResult {
     pub reasoning: String,
     /// Latency breakdown
     pub latency: LatencyBreakdown,
+    /// Context of the verification span, when the request was traced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+    /// Source commit of the policies that decided, for Git-backed policies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_commit: Option<String>,
 }
 
 /// Latency breakdown for performance monitoring.
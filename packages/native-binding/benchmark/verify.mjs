@@ -0,0 +1,65 @@
+// Verification throughput: single calls vs batch, buffer batch and stream.
+//
+//   npm run build && npm run bench [-- <requests>]
+
+import { createRequire } from 'node:module';
+import { performance } from 'node:perf_hooks';
+
+const require = createRequire(import.meta.url);
+const native = require('../index.js');
+
+const N = Number(process.argv[2] ?? 10_000);
+
+const requests = Array.from({ length: N }, (_, i) => ({
+  agentId: `agent-${i % 32}`,
+  action: i % 10 === 0 ? 'transfer_funds' : 'read_record',
+  context: JSON.stringify({ amount: i % 5000, currency: 'USD' }),
+  requestId: String(i),
+}));
+
+async function single() {
+  for (const request of requests) {
+    await native.verifyAction(request);
+  }
+}
+
+async function concurrent() {
+  await Promise.all(requests.map((request) => native.verifyAction(request)));
+}
+
+async function batch() {
+  await native.verifyBatch(requests);
+}
+
+async function bufferBatch() {
+  const payload = Buffer.from(
+    JSON.stringify(requests.map((r) => ({ ...r, context: JSON.parse(r.context) }))),
+  );
+  await native.verifyBatchBuffer(payload);
+}
+
+function stream() {
+  return new Promise((resolve, reject) => {
+    let pending = N;
+    const s = native.verifyStream((err) => {
+      if (err) return reject(err);
+      if (--pending === 0) resolve();
+    });
+    for (const r of requests) {
+      s.pushBuffer(r.agentId, r.action, Buffer.from(r.context), r.requestId);
+    }
+    s.end();
+  });
+}
+
+const cases = { single, concurrent, batch, bufferBatch, stream };
+
+console.log(`${N} requests per case`);
+for (const [name, run] of Object.entries(cases)) {
+  await run(); // warm-up
+  const start = performance.now();
+  await run();
+  const ms = performance.now() - start;
+  const perSec = Math.round((N / ms) * 1000);
+  console.log(`${name.padEnd(12)} ${ms.toFixed(1).padStart(9)} ms  ${String(perSec).padStart(9)} req/s`);
+}
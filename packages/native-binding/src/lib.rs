@@ -2,10 +2,29 @@
 //!
 //! NAPI-RS bindings exposing Rust core to Node.js Gateway.
 //! This replaces the TypeScript simulation with real Rust execution.
+//!
+//! Besides single `verifyAction` calls, high-volume callers can amortize the
+//! per-call overhead with `verifyBatch`, `verifyBatchBuffer` (one JSON
+//! `Buffer` for the whole batch) or a `verifyStream` that batches requests
+//! as they arrive and answers through a callback.
+//!
+//! Verifications share one engine, started by `init()` (or on first use)
+//! and released by `shutdown()`. Treasury payments and balances, trust
+//! tiers and the kill switch are backed by process-wide instances, so state
+//! persists across calls.
 
+use agentkern_gate::engine::{GateEngine, VerificationRequestBuilder};
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as log_fmt, reload, EnvFilter, Registry};
 
 /// Verification request from Gateway.
 #[napi(object)]
@@ -14,12 +33,15 @@ pub struct VerifyRequest {
     pub agent_id: String,
     pub action: String,
     pub context: String, // JSON string
+    /// Echoed in the result (streams and batches)
+    pub request_id: Option<String>,
 }
 
 /// Verification result to Gateway.
 #[napi(object)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerifyResult {
+    pub request_id: Option<String>,
     pub allowed: bool,
     pub evaluated_policies: Vec<String>,
     pub blocking_policies: Vec<String>,
@@ -51,48 +73,189 @@ pub struct CarbonBudgetConfig {
 /// Verify an agent action using Rust Gate engine.
 #[napi]
 pub async fn verify_action(request: VerifyRequest) -> Result<VerifyResult> {
-    use std::time::Instant;
     let start = Instant::now();
-    
-    // Parse context
-    let context: std::collections::HashMap<String, serde_json::Value> = 
-        serde_json::from_str(&request.context).unwrap_or_default();
-    
-    // Build verification request for Rust core
-    let rust_request = agentkern_gate::types::VerificationRequest {
-        agent_id: request.agent_id.clone(),
-        action: request.action.clone(),
-        resource: context.get("resource").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-        parameters: context.iter()
-            .map(|(k, v)| (k.clone(), v.to_string()))
-            .collect(),
-        timestamp: chrono::Utc::now(),
-        session_id: None,
-    };
-    
-    // Create Gate engine and verify
-    let engine = agentkern_gate::engine::GateEngine::new();
-    let result = engine.verify(&rust_request).await;
-    
-    let latency_ms = start.elapsed().as_millis() as u32;
-    
-    match result {
-        Ok(verification) => Ok(VerifyResult {
-            allowed: verification.allowed,
-            evaluated_policies: verification.evaluated_policies,
-            blocking_policies: verification.blocking_policies,
-            risk_score: verification.risk_score as u32,
-            reasoning: verification.reasoning,
-            latency_ms,
-        }),
-        Err(e) => Ok(VerifyResult {
-            allowed: false,
-            evaluated_policies: vec![],
-            blocking_policies: vec!["error".to_string()],
-            risk_score: 100,
-            reasoning: Some(format!("Verification error: {}", e)),
-            latency_ms,
-        }),
+    let context = parse_context(request.context.as_bytes())?;
+    let engine = engine()?;
+    let verification = engine.verify(gate_request(&request.agent_id, &request.action, context)).await;
+    Ok(to_result(verification, request.request_id, start))
+}
+
+/// Verify an action with its context as a JSON `Buffer`.
+///
+/// The context is parsed straight out of Node's memory, skipping the
+/// string conversion a `context: string` argument costs.
+#[napi]
+pub async fn verify_action_buffer(agent_id: String, action: String, context: Buffer) -> Result<VerifyResult> {
+    let start = Instant::now();
+    let context = parse_context(&context)?;
+    let engine = engine()?;
+    let verification = engine.verify(gate_request(&agent_id, &action, context)).await;
+    Ok(to_result(verification, None, start))
+}
+
+/// Verify many actions in one call. Policies are snapshotted once for the
+/// batch; results are in request order.
+#[napi]
+pub async fn verify_batch(requests: Vec<VerifyRequest>) -> Result<Vec<VerifyResult>> {
+    let start = Instant::now();
+    let mut gate_requests = Vec::with_capacity(requests.len());
+    for request in &requests {
+        let context = parse_context(request.context.as_bytes())?;
+        gate_requests.push(gate_request(&request.agent_id, &request.action, context));
+    }
+    let engine = engine()?;
+    let results = engine.verify_batch(gate_requests).await;
+    Ok(results
+        .into_iter()
+        .zip(requests)
+        .map(|(verification, request)| to_result(verification, request.request_id, start))
+        .collect())
+}
+
+/// Verify a batch encoded as a JSON array of requests in a `Buffer`
+/// (`[{ "agentId", "action", "context": {...}, "requestId"? }]`).
+#[napi]
+pub async fn verify_batch_buffer(requests: Buffer) -> Result<Vec<VerifyResult>> {
+    let start = Instant::now();
+    let requests: Vec<BufferRequest> = serde_json::from_slice(&requests)
+        .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid batch JSON: {}", e)))?;
+    let ids: Vec<Option<String>> = requests.iter().map(|r| r.request_id.clone()).collect();
+    let gate_requests = requests
+        .into_iter()
+        .map(|r| gate_request(&r.agent_id, &r.action, r.context))
+        .collect();
+    let engine = engine()?;
+    let results = engine.verify_batch(gate_requests).await;
+    Ok(results
+        .into_iter()
+        .zip(ids)
+        .map(|(verification, id)| to_result(verification, id, start))
+        .collect())
+}
+
+/// Open a verification stream. Every pushed request is answered through
+/// `callback(err, result)`; requests that arrive together are verified as
+/// one batch. Results carry the request's `requestId` for correlation.
+#[napi]
+pub fn verify_stream(callback: ThreadsafeFunction<VerifyResult>) -> Result<VerifyStream> {
+    let engine = engine()?;
+    let (tx, mut rx) = mpsc::unbounded_channel::<StreamItem>();
+
+    spawn(async move {
+        while let Some(first) = rx.recv().await {
+            let mut batch = vec![first];
+            while batch.len() < STREAM_BATCH_MAX {
+                match rx.try_recv() {
+                    Ok(item) => batch.push(item),
+                    Err(_) => break,
+                }
+            }
+
+            let start = Instant::now();
+            let (ids, requests): (Vec<_>, Vec<_>) = batch.into_iter().map(|item| (item.id, item.request)).unzip();
+            let results = engine.verify_batch(requests).await;
+            for (verification, id) in results.into_iter().zip(ids) {
+                callback.call(Ok(to_result(verification, id, start)), ThreadsafeFunctionCallMode::NonBlocking);
+            }
+        }
+    });
+
+    Ok(VerifyStream { tx: Some(tx) })
+}
+
+/// Largest batch the stream worker verifies at once.
+const STREAM_BATCH_MAX: usize = 256;
+
+struct StreamItem {
+    id: Option<String>,
+    request: agentkern_gate::VerificationRequest,
+}
+
+/// Handle returned by [`verify_stream`].
+#[napi]
+pub struct VerifyStream {
+    tx: Option<mpsc::UnboundedSender<StreamItem>>,
+}
+
+#[napi]
+impl VerifyStream {
+    /// Queue a request (context as a JSON string).
+    #[napi]
+    pub fn push(&self, request: VerifyRequest) -> Result<()> {
+        let context = parse_context(request.context.as_bytes())?;
+        self.send(request.request_id, gate_request(&request.agent_id, &request.action, context))
+    }
+
+    /// Queue a request with its context as a JSON `Buffer`.
+    #[napi]
+    pub fn push_buffer(
+        &self,
+        agent_id: String,
+        action: String,
+        context: Buffer,
+        request_id: Option<String>,
+    ) -> Result<()> {
+        let context = parse_context(&context)?;
+        self.send(request_id, gate_request(&agent_id, &action, context))
+    }
+
+    /// Stop accepting requests. Queued requests are still answered.
+    #[napi]
+    pub fn end(&mut self) {
+        self.tx = None;
+    }
+
+    fn send(&self, id: Option<String>, request: agentkern_gate::VerificationRequest) -> Result<()> {
+        let tx = self
+            .tx
+            .as_ref()
+            .ok_or_else(|| Error::new(Status::Closing, "Stream has ended"))?;
+        tx.send(StreamItem { id, request })
+            .map_err(|_| Error::new(Status::Closing, "Stream worker has stopped"))
+    }
+}
+
+/// Request shape inside [`verify_batch_buffer`] payloads.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferRequest {
+    agent_id: String,
+    action: String,
+    #[serde(default)]
+    context: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+fn parse_context(bytes: &[u8]) -> Result<HashMap<String, serde_json::Value>> {
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        return Ok(HashMap::new());
+    }
+    serde_json::from_slice(bytes).map_err(|e| Error::new(Status::InvalidArg, format!("Invalid context JSON: {}", e)))
+}
+
+fn gate_request(
+    agent_id: &str,
+    action: &str,
+    context: HashMap<String, serde_json::Value>,
+) -> agentkern_gate::VerificationRequest {
+    context
+        .into_iter()
+        .fold(VerificationRequestBuilder::new(agent_id, action), |builder, (key, value)| {
+            builder.context(key, value)
+        })
+        .build()
+}
+
+fn to_result(verification: agentkern_gate::VerificationResult, request_id: Option<String>, start: Instant) -> VerifyResult {
+    VerifyResult {
+        request_id,
+        allowed: verification.allowed,
+        evaluated_policies: verification.evaluated_policies,
+        blocking_policies: verification.blocking_policies,
+        risk_score: verification.final_risk_score as u32,
+        reasoning: Some(verification.reasoning).filter(|r| !r.is_empty()),
+        latency_ms: start.elapsed().as_millis() as u32,
     }
 }
 
@@ -138,11 +301,573 @@ pub fn check_carbon_budget(agent_id: String, estimated_grams: f64) -> Result<boo
     }
 }
 
-/// Initialize the AgentKern native runtime.
+// ============================================================================
+// Treasury, Trust and Kill Switch
+// ============================================================================
+
+/// Error codes for the treasury, trust and kill-switch bindings, from the
+/// shared `agentkern-errors` catalog. Thrown errors have messages of the
+/// form `"<ErrorCode>: <details>"`; see `describeError` for the category
+/// and retryability of a code.
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    #[napi(value = "REQUEST_INVALID_ARGUMENT")]
+    InvalidArgument,
+    #[napi(value = "FUNDS_INVALID_AMOUNT")]
+    InvalidAmount,
+    #[napi(value = "FUNDS_INSUFFICIENT")]
+    InsufficientFunds,
+    #[napi(value = "FUNDS_ACCOUNT_NOT_FOUND")]
+    AccountNotFound,
+    #[napi(value = "FUNDS_TRANSFER_FAILED")]
+    TransferFailed,
+    #[napi(value = "AUTH_LICENSE_REQUIRED")]
+    LicenseRequired,
+}
+
+impl ErrorCode {
+    fn catalog(self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode as Catalog;
+        match self {
+            Self::InvalidArgument => Catalog::InvalidArgument,
+            Self::InvalidAmount => Catalog::InvalidAmount,
+            Self::InsufficientFunds => Catalog::InsufficientFunds,
+            Self::AccountNotFound => Catalog::AccountNotFound,
+            Self::TransferFailed => Catalog::TransferFailed,
+            Self::LicenseRequired => Catalog::LicenseRequired,
+        }
+    }
+
+    fn error(self, details: impl std::fmt::Display) -> Error {
+        napi_error(agentkern_errors::AgentKernError::new(self.catalog(), details.to_string()))
+    }
+}
+
+/// Throwable error for a catalog error.
+fn napi_error(error: agentkern_errors::AgentKernError) -> Error {
+    let status = match error.http_status() {
+        400 => Status::InvalidArg,
+        _ => Status::GenericFailure,
+    };
+    Error::new(status, error.to_string())
+}
+
+/// Catalog entry for an error code.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorInfo {
+    pub code: String,
+    /// auth | policy | funds | request | infra
+    pub category: String,
+    /// Whether the same call may succeed later
+    pub retryable: bool,
+    pub http_status: u32,
+}
+
+/// Describe an error code (e.g. `FUNDS_INSUFFICIENT`, or the prefix of a
+/// thrown error's message). Returns null for unknown codes.
+#[napi]
+pub fn describe_error(code: String) -> Option<ErrorInfo> {
+    let code = code.split(':').next().unwrap_or_default().trim();
+    let code: agentkern_errors::ErrorCode = code.parse().ok()?;
+    Some(ErrorInfo {
+        code: code.to_string(),
+        category: code.category().to_string(),
+        retryable: code.retryable(),
+        http_status: code.http_status().into(),
+    })
+}
+
+/// Process-wide treasury: one ledger shared by every call.
+struct Treasury {
+    ledger: Arc<agentkern_treasury::BalanceLedger>,
+    transfers: agentkern_treasury::TransferEngine,
+}
+
+static TREASURY: OnceLock<Treasury> = OnceLock::new();
+static KILL_SWITCH: OnceLock<agentkern_arbiter::KillSwitch> = OnceLock::new();
+
+fn treasury() -> &'static Treasury {
+    TREASURY.get_or_init(|| {
+        let ledger = Arc::new(agentkern_treasury::BalanceLedger::default());
+        Treasury {
+            transfers: agentkern_treasury::TransferEngine::new(ledger.clone()),
+            ledger,
+        }
+    })
+}
+
+fn kill_switch() -> &'static agentkern_arbiter::KillSwitch {
+    KILL_SWITCH.get_or_init(agentkern_arbiter::KillSwitch::new)
+}
+
+/// Agent balance, in currency units.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Balance {
+    pub agent_id: String,
+    pub currency: String,
+    pub balance: f64,
+    pub pending: f64,
+    pub available: f64,
+}
+
+/// Payment between two agents.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    pub from: String,
+    pub to: String,
+    /// Amount in currency units
+    pub amount: f64,
+    pub reference: Option<String>,
+    /// Retries with the same key return the original transaction
+    pub idempotency_key: Option<String>,
+}
+
+/// Completed payment.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentReceipt {
+    pub transaction_id: String,
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    pub timestamp: i64,
+}
+
+/// Get an agent's balance (zero for unknown agents).
+#[napi]
+pub fn get_balance(agent_id: String) -> Balance {
+    to_balance(treasury().ledger.get_balance(&agent_id))
+}
+
+/// Credit an agent's account.
+#[napi]
+pub fn deposit(agent_id: String, amount: f64) -> Result<Balance> {
+    let ledger = &treasury().ledger;
+    let amount = to_amount(amount, ledger.get_balance(&agent_id).currency)?;
+    ledger
+        .deposit(&agent_id, amount)
+        .map(to_balance)
+        .map_err(ledger_error)
+}
+
+/// Pay another agent (held, then committed). Fails with `InsufficientFunds`,
+/// `AccountNotFound`, `InvalidAmount` or `TransferFailed`.
+#[napi]
+pub async fn transfer(request: PaymentRequest) -> Result<PaymentReceipt> {
+    use agentkern_treasury::{TransferRequest, TransferStatus};
+
+    if request.from == request.to {
+        return Err(ErrorCode::InvalidArgument.error("cannot pay yourself"));
+    }
+    let treasury = treasury();
+    let amount = to_amount(request.amount, treasury.ledger.get_balance(&request.from).currency)?;
+
+    let mut transfer = TransferRequest::new(request.from.as_str(), request.to.as_str(), amount);
+    if let Some(reference) = request.reference {
+        transfer = transfer.with_reference(reference);
+    }
+    if let Some(key) = request.idempotency_key {
+        transfer = transfer.with_idempotency_key(key);
+    }
+
+    let result = treasury.transfers.transfer(transfer).await;
+    match result.status {
+        TransferStatus::Completed => Ok(PaymentReceipt {
+            transaction_id: result.transaction_id.to_string(),
+            from: request.from,
+            to: request.to,
+            amount: amount.to_float(),
+            timestamp: result.timestamp.timestamp_millis(),
+        }),
+        _ => Err(transfer_error(result.error.as_deref().unwrap_or("transfer not completed"))),
+    }
+}
+
+fn to_amount(value: f64, currency: agentkern_treasury::Currency) -> Result<agentkern_treasury::Amount> {
+    if !value.is_finite() || value <= 0.0 {
+        return Err(ErrorCode::InvalidAmount.error(format!("{} is not a positive amount", value)));
+    }
+    Ok(agentkern_treasury::Amount::from_float(value, currency.decimals()))
+}
+
+fn to_balance(balance: agentkern_treasury::AgentBalance) -> Balance {
+    Balance {
+        currency: format!("{:?}", balance.currency),
+        balance: balance.balance.to_float(),
+        pending: balance.pending.to_float(),
+        available: balance.available().to_float(),
+        agent_id: balance.agent_id,
+    }
+}
+
+fn ledger_error(error: agentkern_treasury::balance::LedgerError) -> Error {
+    napi_error(error.into())
+}
+
+/// The transfer engine reports failures as the ledger error's message.
+fn transfer_error(message: &str) -> Error {
+    use agentkern_treasury::balance::LedgerError;
+
+    [LedgerError::InsufficientFunds, LedgerError::AccountNotFound, LedgerError::InvalidAmount]
+        .into_iter()
+        .find(|e| e.to_string() == message)
+        .map(ledger_error)
+        .unwrap_or_else(|| ErrorCode::TransferFailed.error(message))
+}
+
+/// Agent trust standing.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustInfo {
+    pub agent_id: String,
+    /// blacklisted | untrusted | unknown | trusted | verified | elite
+    pub tier: String,
+    /// 0-1000 (500 for new agents)
+    pub score: u32,
+    pub allows_high_risk: bool,
+}
+
+/// Reputation event for an agent.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustEvent {
+    /// success | failure | violation | verification
+    pub kind: String,
+    /// Score change (negative lowers the score)
+    pub impact: i32,
+    /// Action or policy the event refers to
+    pub subject: Option<String>,
+}
+
+/// Look up an agent's trust tier. Requires the `enterprise` build and an
+/// enterprise license (`LicenseRequired` otherwise).
+#[napi]
+pub fn get_trust_tier(agent_id: String) -> Result<TrustInfo> {
+    trust::tier(agent_id)
+}
+
+/// Record a reputation event; returns the updated standing.
+#[napi]
+pub fn record_trust_event(agent_id: String, event: TrustEvent) -> Result<TrustInfo> {
+    trust::record(agent_id, event)
+}
+
+#[cfg(feature = "enterprise")]
+mod trust {
+    use super::{ErrorCode, TrustEvent, TrustInfo};
+    use agentkern_trust::{ReputationEvent, TrustNetwork};
+    use napi::Result;
+    use std::sync::{Mutex, OnceLock};
+
+    static NETWORK: OnceLock<Mutex<TrustNetwork>> = OnceLock::new();
+
+    fn with_network<T>(f: impl FnOnce(&mut TrustNetwork) -> T) -> Result<T> {
+        let network = match NETWORK.get() {
+            Some(network) => network,
+            None => {
+                let network = TrustNetwork::new().map_err(|e| ErrorCode::LicenseRequired.error(e))?;
+                NETWORK.get_or_init(|| Mutex::new(network))
+            }
+        };
+        let mut network = network.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok(f(&mut network))
+    }
+
+    fn info(network: &TrustNetwork, agent_id: String) -> TrustInfo {
+        let tier = network.get_trust_tier(&agent_id);
+        let score = network.get_reputation(&agent_id).map(|r| r.score).unwrap_or(500);
+        TrustInfo {
+            tier: serde_json::to_value(tier)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+            score: score as u32,
+            allows_high_risk: tier.allows_high_risk(),
+            agent_id,
+        }
+    }
+
+    pub fn tier(agent_id: String) -> Result<TrustInfo> {
+        with_network(|network| info(network, agent_id))
+    }
+
+    pub fn record(agent_id: String, event: TrustEvent) -> Result<TrustInfo> {
+        let impact = event.impact.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        let subject = event.subject.unwrap_or_default();
+        let event = match event.kind.as_str() {
+            "success" => ReputationEvent::ActionSuccess { action: subject, impact },
+            "failure" => ReputationEvent::ActionFailed { action: subject, impact },
+            "violation" => ReputationEvent::PolicyViolation { policy_id: subject, impact },
+            "verification" => ReputationEvent::VerificationComplete { impact },
+            other => {
+                return Err(ErrorCode::InvalidArgument.error(format!("unknown trust event kind '{}'", other)));
+            }
+        };
+        with_network(|network| {
+            network.register_agent(&agent_id, "default");
+            network.record_event(&agent_id, event);
+            info(network, agent_id)
+        })
+    }
+}
+
+#[cfg(not(feature = "enterprise"))]
+mod trust {
+    use super::{ErrorCode, TrustEvent, TrustInfo};
+    use napi::Result;
+
+    fn unavailable<T>() -> Result<T> {
+        Err(ErrorCode::LicenseRequired.error("trust requires the enterprise build"))
+    }
+
+    pub fn tier(_agent_id: String) -> Result<TrustInfo> {
+        unavailable()
+    }
+
+    pub fn record(_agent_id: String, _event: TrustEvent) -> Result<TrustInfo> {
+        unavailable()
+    }
+}
+
+/// Kill-switch event.
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillEvent {
+    pub id: String,
+    pub timestamp: i64,
+    pub target_id: String,
+    /// agent | swarm | region | global
+    pub target_type: String,
+    pub reason: String,
+    /// graceful | forced | hardware_kill
+    pub termination_type: String,
+    pub initiated_by: Option<String>,
+}
+
+/// Termination options.
+#[napi(object)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KillOptions {
+    /// graceful | forced (default) | hardware_kill
+    pub termination_type: Option<String>,
+    pub initiated_by: Option<String>,
+}
+
+/// Terminate an agent. `reason` is one of the kill-switch reasons
+/// (`policy_violation`, `budget_exceeded`, `prompt_injection`, ...) or free text.
+#[napi]
+pub async fn terminate_agent(agent_id: String, reason: String, options: Option<KillOptions>) -> Result<KillEvent> {
+    let options = options.unwrap_or_default();
+    let termination = termination_type(options.termination_type.as_deref())?;
+    let record = kill_switch()
+        .terminate_agent(&agent_id, kill_reason(reason), termination, options.initiated_by)
+        .await;
+    Ok(to_kill_event(record))
+}
+
+/// Terminate every agent in a swarm.
+#[napi]
+pub async fn terminate_swarm(swarm_id: String, reason: String, options: Option<KillOptions>) -> Result<KillEvent> {
+    let options = options.unwrap_or_default();
+    let termination = termination_type(options.termination_type.as_deref())?;
+    let record = kill_switch()
+        .terminate_swarm(&swarm_id, kill_reason(reason), termination, options.initiated_by)
+        .await;
+    Ok(to_kill_event(record))
+}
+
+/// Stop all agents until [`lift_emergency`] is called.
+#[napi]
+pub async fn emergency_shutdown(initiated_by: Option<String>) -> Result<KillEvent> {
+    Ok(to_kill_event(kill_switch().emergency_shutdown(initiated_by).await))
+}
+
+/// Lift an emergency shutdown.
+#[napi]
+pub async fn lift_emergency() -> Result<()> {
+    kill_switch().lift_emergency().await;
+    Ok(())
+}
+
+/// Is the agent allowed to run (not terminated, no emergency shutdown)?
+#[napi]
+pub async fn is_agent_alive(agent_id: String) -> Result<bool> {
+    Ok(kill_switch().is_agent_alive(&agent_id).await)
+}
+
+/// Kill-switch history, oldest first.
+#[napi]
+pub async fn kill_history() -> Result<Vec<KillEvent>> {
+    Ok(kill_switch().get_history().await.into_iter().map(to_kill_event).collect())
+}
+
+fn kill_reason(reason: String) -> agentkern_arbiter::KillReason {
+    serde_json::from_value(serde_json::Value::String(reason.clone()))
+        .unwrap_or(agentkern_arbiter::KillReason::Custom(reason))
+}
+
+fn termination_type(value: Option<&str>) -> Result<agentkern_arbiter::TerminationType> {
+    use agentkern_arbiter::TerminationType;
+
+    match value {
+        None | Some("forced") => Ok(TerminationType::Forced),
+        Some("graceful") => Ok(TerminationType::Graceful),
+        Some("hardware_kill") => Ok(TerminationType::HardwareKill),
+        Some(other) => Err(ErrorCode::InvalidArgument.error(format!("unknown termination type '{}'", other))),
+    }
+}
+
+fn to_kill_event(record: agentkern_arbiter::KillRecord) -> KillEvent {
+    use agentkern_arbiter::killswitch::TargetType;
+    use agentkern_arbiter::{KillReason, TerminationType};
+
+    KillEvent {
+        id: record.id.to_string(),
+        timestamp: record.timestamp.timestamp_millis(),
+        target_id: record.target_id,
+        target_type: match record.target_type {
+            TargetType::Agent => "agent",
+            TargetType::Swarm => "swarm",
+            TargetType::Region => "region",
+            TargetType::Global => "global",
+        }
+        .to_string(),
+        reason: match record.reason {
+            KillReason::Custom(reason) => reason,
+            reason => serde_json::to_value(reason)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+        },
+        termination_type: match record.termination_type {
+            TerminationType::Graceful => "graceful",
+            TerminationType::Forced => "forced",
+            TerminationType::HardwareKill => "hardware_kill",
+        }
+        .to_string(),
+        initiated_by: record.initiated_by,
+    }
+}
+
+// ============================================================================
+// Runtime lifecycle
+// ============================================================================
+
+/// Runtime options for [`init`].
+#[napi(object)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InitOptions {
+    /// Log filter (`"info"`, `"agentkern_gate=debug"`, ...). Defaults to
+    /// `RUST_LOG`, then `info`.
+    pub log_level: Option<String>,
+}
+
+/// Runtime status returned by [`init`].
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeInfo {
+    pub version: String,
+    pub log_level: String,
+    /// `false` if the host process had already installed a tracing
+    /// subscriber (native logs then go to that subscriber)
+    pub logging_installed: bool,
+}
+
+enum RuntimeState {
+    /// Not initialized; the first verification starts the runtime
+    Idle,
+    Running(Arc<GateEngine>),
+    /// After [`shutdown`], until the next [`init`]
+    Stopped,
+}
+
+static RUNTIME: Mutex<RuntimeState> = Mutex::new(RuntimeState::Idle);
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Initialize the native runtime. Safe to call more than once: later calls
+/// only change the log level. Restarts the runtime after [`shutdown`].
+#[napi]
+pub fn init(options: Option<InitOptions>) -> Result<RuntimeInfo> {
+    let log_level = options
+        .and_then(|o| o.log_level)
+        .or_else(|| std::env::var("RUST_LOG").ok())
+        .unwrap_or_else(|| "info".to_string());
+    let filter = EnvFilter::try_new(&log_level)
+        .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid log level '{}': {}", log_level, e)))?;
+    let logging_installed = init_logging(filter)?;
+
+    let mut runtime = lock_runtime();
+    if !matches!(*runtime, RuntimeState::Running(_)) {
+        *runtime = RuntimeState::Running(Arc::new(GateEngine::new()));
+        tracing::info!("AgentKern native runtime started");
+    }
+
+    Ok(RuntimeInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        log_level,
+        logging_installed,
+    })
+}
+
+/// Stop the runtime and release the engine. Verifications fail with
+/// `Closing` until [`init`] is called again; open streams finish the
+/// requests already queued.
+#[napi]
+pub fn shutdown() {
+    let mut runtime = lock_runtime();
+    if matches!(*runtime, RuntimeState::Running(_)) {
+        tracing::info!("AgentKern native runtime stopped");
+    }
+    *runtime = RuntimeState::Stopped;
+}
+
+/// Initialize the native runtime with default options.
+///
+/// Deprecated: use [`init`].
 #[napi]
 pub fn init_runtime() -> Result<String> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
-    
+    init(None)?;
     Ok("AgentKern Native Runtime initialized".to_string())
 }
+
+/// The shared engine, starting the runtime on first use.
+fn engine() -> Result<Arc<GateEngine>> {
+    let mut runtime = lock_runtime();
+    match &*runtime {
+        RuntimeState::Running(engine) => Ok(engine.clone()),
+        RuntimeState::Idle => {
+            let engine = Arc::new(GateEngine::new());
+            *runtime = RuntimeState::Running(engine.clone());
+            Ok(engine)
+        }
+        RuntimeState::Stopped => Err(Error::new(
+            Status::Closing,
+            "AgentKern runtime is shut down; call init() to restart it",
+        )),
+    }
+}
+
+fn lock_runtime() -> std::sync::MutexGuard<'static, RuntimeState> {
+    RUNTIME.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Install the global subscriber once; afterwards only swap its filter.
+/// Returns whether our subscriber is the active one.
+fn init_logging(filter: EnvFilter) -> Result<bool> {
+    if let Some(handle) = LOG_FILTER.get() {
+        handle
+            .reload(filter)
+            .map_err(|e| Error::new(Status::GenericFailure, format!("Failed to set log level: {}", e)))?;
+        return Ok(true);
+    }
+    let (layer, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry().with(layer).with(log_fmt::layer()).try_init().is_ok() {
+        let _ = LOG_FILTER.set(handle);
+        return Ok(true);
+    }
+    Ok(false)
+}
@@ -0,0 +1,52 @@
+//! Protocol Translation Benchmarks
+//!
+//! Detection, field mapping, and the full A2A → MCP path
+//! (parse, translate, serialize).
+//!
+//! Run with: cargo bench --bench translation
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use agentkern_nexus::protocols::{A2AAdapter, MCPAdapter, ProtocolTranslator};
+use agentkern_nexus::{AdapterRegistry, NexusMessage, Protocol, ProtocolAdapter};
+
+const A2A_REQUEST: &str = r#"{"jsonrpc":"2.0","id":"req-1","method":"tasks/send","params":{"task_id":"t-42","message":"translate this invoice","priority":3}}"#;
+
+fn bench_translation(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let translator = ProtocolTranslator::new();
+    let a2a = A2AAdapter::new();
+    let mcp = MCPAdapter::new();
+    let mut registry = AdapterRegistry::new();
+    registry.register(Box::new(A2AAdapter::new()));
+    registry.register(Box::new(MCPAdapter::new()));
+    let message = rt.block_on(a2a.parse(A2A_REQUEST.as_bytes())).unwrap();
+
+    let mut group = c.benchmark_group("nexus_translation");
+
+    group.bench_function("detect", |b| {
+        b.iter(|| registry.detect(black_box(A2A_REQUEST.as_bytes())))
+    });
+
+    group.bench_function("translate_message", |b| {
+        b.iter_batched(
+            || message.clone(),
+            |message: NexusMessage| translator.translate_message(message, Protocol::AnthropicMCP),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("a2a_to_mcp", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let parsed = a2a.parse(black_box(A2A_REQUEST.as_bytes())).await.unwrap();
+                let translated = translator.translate_message(parsed, Protocol::AnthropicMCP).unwrap();
+                mcp.serialize(&translated.message).await.unwrap()
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_translation);
+criterion_main!(benches);
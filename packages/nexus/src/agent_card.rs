@@ -9,6 +9,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::attestation::CardAttestation;
 use crate::types::{Skill, Capability, Modality};
 
 /// Agent Card - Universal agent discovery format.
@@ -64,6 +65,14 @@ pub struct AgentCard {
     /// AgentKern-specific extensions
     #[serde(default)]
     pub extensions: HashMap<String, serde_json::Value>,
+
+    /// Maximum tasks the agent accepts at once (unlimited if unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<u32>,
+
+    /// TEE quote proving the agent binary's measurement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<CardAttestation>,
 }
 
 impl Default for AgentCard {
@@ -82,6 +91,8 @@ impl Default for AgentCard {
             authentication: AuthInfo::default(),
             protocols: vec![],
             extensions: HashMap::new(),
+            max_concurrency: None,
+            attestation: None,
         }
     }
 }
@@ -115,6 +126,18 @@ impl AgentCard {
         self
     }
 
+    /// Limit how many tasks the agent accepts at once.
+    pub fn with_max_concurrency(mut self, max: u32) -> Self {
+        self.max_concurrency = Some(max);
+        self
+    }
+
+    /// Embed a TEE attestation.
+    pub fn with_attestation(mut self, attestation: CardAttestation) -> Self {
+        self.attestation = Some(attestation);
+        self
+    }
+
     /// Add protocol support.
     pub fn supports_protocol(mut self, protocol: ProtocolSupport) -> Self {
         self.protocols.push(protocol);
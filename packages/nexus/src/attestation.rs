@@ -0,0 +1,107 @@
+//! Agent Attestation
+//!
+//! Proof that an agent binary is what its card claims, for high-assurance
+//! tenants:
+//! - An [`AgentCard`](crate::AgentCard) can embed a [`CardAttestation`]: a
+//!   TEE quote and the enclave measurement it covers
+//! - The [`AgentRegistry`](crate::AgentRegistry) checks the quote with a
+//!   [`QuoteVerifier`] when the agent registers or updates its card, and
+//!   again on every periodic re-check, so stale or revoked measurements
+//!   lose their attested status
+//! - The [`TaskRouter`](crate::TaskRouter) can require attested agents for
+//!   sensitive skills
+//!
+//! Nexus does not verify quotes itself; the runtime plugs in the Gate TEE
+//! verifier.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let registry = Arc::new(AgentRegistry::new().with_quote_verifier(Arc::new(verifier)));
+//! tokio::spawn(registry.clone().recheck_attestations(Duration::from_secs(3600)));
+//!
+//! registry.register(card.with_attestation(CardAttestation::new("IntelTdx", &measurement, &quote, b"agent-1"))).await?;
+//! let router = TaskRouter::new(registry).with_attested_skill("payments");
+//! ```
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A TEE quote embedded in an agent card. Bytes are base64 encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardAttestation {
+    /// TEE platform, e.g. `IntelTdx` or `AmdSevSnp`
+    pub platform: String,
+    /// Enclave measurement the quote covers
+    pub measurement: String,
+    /// Quote/report from the platform
+    pub quote: String,
+    /// Report data bound into the quote; the agent's ID
+    #[serde(default)]
+    pub user_data: String,
+    /// Certificate chain for the quote
+    #[serde(default)]
+    pub cert_chain: Vec<String>,
+    /// When the quote was produced
+    pub issued_at: DateTime<Utc>,
+}
+
+impl CardAttestation {
+    /// Attestation produced now.
+    pub fn new(platform: impl Into<String>, measurement: &[u8], quote: &[u8], user_data: &[u8]) -> Self {
+        Self {
+            platform: platform.into(),
+            measurement: STANDARD.encode(measurement),
+            quote: STANDARD.encode(quote),
+            user_data: STANDARD.encode(user_data),
+            cert_chain: Vec::new(),
+            issued_at: Utc::now(),
+        }
+    }
+
+    pub fn measurement_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        STANDARD.decode(&self.measurement)
+    }
+
+    pub fn quote_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        STANDARD.decode(&self.quote)
+    }
+
+    pub fn user_data_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        STANDARD.decode(&self.user_data)
+    }
+}
+
+/// Checks a card's TEE quote. Returns why it is not acceptable.
+pub trait QuoteVerifier: Send + Sync {
+    fn verify(&self, agent_id: &str, attestation: &CardAttestation) -> Result<(), String>;
+}
+
+impl<F> QuoteVerifier for F
+where
+    F: Fn(&str, &CardAttestation) -> Result<(), String> + Send + Sync,
+{
+    fn verify(&self, agent_id: &str, attestation: &CardAttestation) -> Result<(), String> {
+        self(agent_id, attestation)
+    }
+}
+
+/// Attestation standing of a registered agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum AttestationStatus {
+    /// No attestation on the card, or no verifier to check it
+    Unattested,
+    Verified { measurement: String, checked_at: DateTime<Utc> },
+    /// Passed at registration but failed a later re-check
+    Failed { reason: String, checked_at: DateTime<Utc> },
+}
+
+impl AttestationStatus {
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified { .. })
+    }
+}
@@ -30,6 +30,15 @@ pub enum NexusError {
     #[error("No matching agent for task: {task_type}")]
     NoMatchingAgent { task_type: String },
 
+    #[error("All agents for task {task_type} are saturated and the queue is full")]
+    AgentsSaturated { task_type: String },
+
+    #[error("Attestation failed for agent {agent_id}: {reason}")]
+    AttestationFailed { agent_id: String, reason: String },
+
+    #[error("No attested agent for task: {task_type}")]
+    AttestationRequired { task_type: String },
+
     #[error("Task not found: {task_id}")]
     TaskNotFound { task_id: String },
 
@@ -52,6 +61,26 @@ pub enum NexusError {
     Timeout,
 }
 
+impl agentkern_errors::Coded for NexusError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::UnknownProtocol | Self::ParseError { .. } | Self::SerializeError { .. } => ErrorCode::InvalidArgument,
+            Self::ProtocolNotSupported { .. } | Self::AdapterNotRegistered { .. } | Self::NotSupported { .. } => {
+                ErrorCode::Unsupported
+            }
+            Self::AgentNotFound { .. } | Self::TaskNotFound { .. } | Self::NoMatchingAgent { .. } => ErrorCode::NotFound,
+            Self::AgentAlreadyExists { .. } => ErrorCode::AlreadyExists,
+            Self::TaskFailed { .. } => ErrorCode::Internal,
+            Self::NetworkError { .. } => ErrorCode::Unavailable,
+            Self::AuthenticationFailed { .. } | Self::AttestationFailed { .. } => ErrorCode::Unauthenticated,
+            Self::AttestationRequired { .. } => ErrorCode::PermissionDenied,
+            Self::RateLimited | Self::AgentsSaturated { .. } => ErrorCode::RateLimited,
+            Self::Timeout => ErrorCode::Timeout,
+        }
+    }
+}
+
 impl From<serde_json::Error> for NexusError {
     fn from(e: serde_json::Error) -> Self {
         Self::ParseError { message: e.to_string() }
@@ -73,4 +102,13 @@ mod tests {
         let err = NexusError::AgentNotFound { agent_id: "agent-1".into() };
         assert_eq!(err.to_string(), "Agent not found: agent-1");
     }
+
+    #[test]
+    fn test_error_code() {
+        use agentkern_errors::{Coded, ErrorCode};
+
+        assert_eq!(NexusError::RateLimited.code(), ErrorCode::RateLimited);
+        let err = NexusError::AgentNotFound { agent_id: "agent-1".into() }.to_agentkern_error();
+        assert_eq!(err.to_string(), "REQUEST_NOT_FOUND: Agent not found: agent-1");
+    }
 }
@@ -46,6 +46,7 @@
 
 pub mod types;
 pub mod agent_card;
+pub mod attestation;
 pub mod protocols;
 pub mod router;
 pub mod discovery;
@@ -56,12 +57,14 @@ pub mod marketplace;
 // Re-exports
 pub use types::*;
 pub use agent_card::AgentCard;
+pub use attestation::{AttestationStatus, CardAttestation, QuoteVerifier};
 pub use protocols::{Protocol, ProtocolAdapter, AdapterRegistry};
 pub use router::TaskRouter;
 pub use discovery::AgentDiscovery;
 pub use registry::AgentRegistry;
 pub use error::NexusError;
-pub use marketplace::{Marketplace, TaskAuction, Bid, Settlement};
+pub use marketplace::{Marketplace, TaskAuction, Bid, Settlement, ExecutionProgress};
+pub use marketplace::bidding::{BiddingAssistant, BidQuote, BidRefusal, CostModel, TaskEstimate, TokenPrices};
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
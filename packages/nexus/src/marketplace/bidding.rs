@@ -0,0 +1,254 @@
+//! Bidding Assistant
+//!
+//! Prices bids for worker agents from what the task will cost them to run:
+//! expected model tokens at the agent's model prices, time at an hourly
+//! rate, and a fixed overhead. The quote aims for a target margin, never
+//! goes below a minimum margin, and is refused outright when running the
+//! task would overrun the agent's Treasury budget.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_nexus::marketplace::bidding::{BiddingAssistant, CostModel, TaskEstimate, TokenPrices};
+//!
+//! let costs = CostModel::new(TokenPrices { input_usd: 0.000003, output_usd: 0.000015 })
+//!     .with_hourly_rate(12.0);
+//! let assistant = BiddingAssistant::new("worker-7", costs)
+//!     .with_margins(0.10, 0.30)
+//!     .with_budget(move |agent: &str| budgets.get_remaining(agent).map(|a| a.to_float()));
+//!
+//! match assistant.bid(&auction, &TaskEstimate::new(40_000, 8_000, 1800)) {
+//!     Ok(bid) => auction.submit_bid(bid)?,
+//!     Err(refusal) => tracing::info!(%refusal, "Not bidding"),
+//! }
+//! ```
+
+use super::{AuctionStatus, Bid, TaskAuction};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Per-token model prices in USD, as configured for the agent's model in
+/// ee/models (`TokenPricing`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenPrices {
+    pub input_usd: f64,
+    pub output_usd: f64,
+}
+
+/// Expected resource use of one task.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaskEstimate {
+    pub input_tokens: u64,
+    /// Output tokens, including reasoning tokens
+    pub output_tokens: u64,
+    pub duration_secs: u64,
+}
+
+impl TaskEstimate {
+    pub fn new(input_tokens: u64, output_tokens: u64, duration_secs: u64) -> Self {
+        Self { input_tokens, output_tokens, duration_secs }
+    }
+}
+
+/// What running a task costs the agent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CostModel {
+    pub prices: TokenPrices,
+    /// Cost of the agent's time, in USD per hour
+    pub hourly_rate_usd: f64,
+    /// Fixed cost per task, in USD
+    pub overhead_usd: f64,
+}
+
+impl CostModel {
+    pub fn new(prices: TokenPrices) -> Self {
+        Self { prices, hourly_rate_usd: 0.0, overhead_usd: 0.0 }
+    }
+
+    pub fn with_hourly_rate(mut self, usd: f64) -> Self {
+        self.hourly_rate_usd = usd;
+        self
+    }
+
+    pub fn with_overhead(mut self, usd: f64) -> Self {
+        self.overhead_usd = usd;
+        self
+    }
+
+    /// Expected cost of a task in USD.
+    pub fn cost(&self, estimate: &TaskEstimate) -> f64 {
+        let tokens = estimate.input_tokens as f64 * self.prices.input_usd
+            + estimate.output_tokens as f64 * self.prices.output_usd;
+        let time = estimate.duration_secs as f64 / 3600.0 * self.hourly_rate_usd;
+        tokens + time + self.overhead_usd
+    }
+}
+
+/// Remaining spend for an agent, normally its Treasury budget.
+pub trait BudgetSource: Send + Sync {
+    /// Remaining USD, or `None` when the agent has no limit.
+    fn remaining(&self, agent_id: &str) -> Option<f64>;
+}
+
+impl<F> BudgetSource for F
+where
+    F: Fn(&str) -> Option<f64> + Send + Sync,
+{
+    fn remaining(&self, agent_id: &str) -> Option<f64> {
+        self(agent_id)
+    }
+}
+
+/// A priced bid and how it was reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BidQuote {
+    pub price: f64,
+    pub cost: f64,
+    /// Markup over cost (0.25 = 25%)
+    pub margin: f64,
+    /// Price was lowered to the auction's budget
+    pub capped: bool,
+}
+
+/// Why the assistant will not bid.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BidRefusal {
+    #[error("Auction is not open for bids")]
+    AuctionClosed,
+
+    #[error("Best price {max_price:.2} is under the minimum-margin floor {floor:.2}")]
+    MarginTooLow { floor: f64, max_price: f64 },
+
+    #[error("Task cost {cost:.2} exceeds remaining budget {remaining:.2}")]
+    OverBudget { cost: f64, remaining: f64 },
+
+    #[error("Task needs {needed_secs}s but only {available_secs}s remain before the execution deadline")]
+    DeadlineTooTight { needed_secs: u64, available_secs: u64 },
+}
+
+impl agentkern_errors::Coded for BidRefusal {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::AuctionClosed => ErrorCode::InvalidState,
+            Self::MarginTooLow { .. } | Self::DeadlineTooTight { .. } => ErrorCode::InvalidArgument,
+            Self::OverBudget { .. } => ErrorCode::BudgetExceeded,
+        }
+    }
+}
+
+/// Prices and places bids for one worker agent.
+pub struct BiddingAssistant {
+    agent_id: String,
+    costs: CostModel,
+    min_margin: f64,
+    target_margin: f64,
+    budget: Option<Arc<dyn BudgetSource>>,
+}
+
+impl BiddingAssistant {
+    /// Defaults to a 10% minimum and 25% target margin.
+    pub fn new(agent_id: impl Into<String>, costs: CostModel) -> Self {
+        Self { agent_id: agent_id.into(), costs, min_margin: 0.10, target_margin: 0.25, budget: None }
+    }
+
+    /// Minimum and target markup over cost. The target is raised to the minimum if lower.
+    pub fn with_margins(mut self, min: f64, target: f64) -> Self {
+        self.min_margin = min.max(0.0);
+        self.target_margin = target.max(self.min_margin);
+        self
+    }
+
+    /// Refuse tasks whose cost would overrun this budget.
+    pub fn with_budget(mut self, budget: impl BudgetSource + 'static) -> Self {
+        self.budget = Some(Arc::new(budget));
+        self
+    }
+
+    /// Price a bid on `auction` without placing it.
+    pub fn quote(&self, auction: &TaskAuction, estimate: &TaskEstimate) -> Result<BidQuote, BidRefusal> {
+        let now = Utc::now();
+        if auction.status != AuctionStatus::Open || now > auction.bid_deadline {
+            return Err(BidRefusal::AuctionClosed);
+        }
+        let available_secs = (auction.execution_deadline - now).num_seconds().max(0) as u64;
+        if estimate.duration_secs > available_secs {
+            return Err(BidRefusal::DeadlineTooTight { needed_secs: estimate.duration_secs, available_secs });
+        }
+
+        // The agent pays for models and time before it is paid
+        let cost = self.costs.cost(estimate);
+        if let Some(remaining) = self.budget.as_ref().and_then(|b| b.remaining(&self.agent_id))
+            && cost > remaining
+        {
+            return Err(BidRefusal::OverBudget { cost, remaining });
+        }
+
+        let floor = cost * (1.0 + self.min_margin);
+        if floor > auction.max_budget {
+            return Err(BidRefusal::MarginTooLow { floor, max_price: auction.max_budget });
+        }
+        let target = cost * (1.0 + self.target_margin);
+        let price = target.min(auction.max_budget);
+        let margin = if cost > 0.0 { price / cost - 1.0 } else { 0.0 };
+        Ok(BidQuote { price, cost, margin, capped: price < target })
+    }
+
+    /// Build a bid on `auction` at the quoted price.
+    pub fn bid(&self, auction: &TaskAuction, estimate: &TaskEstimate) -> Result<Bid, BidRefusal> {
+        let quote = self.quote(auction, estimate)?;
+        Ok(Bid::new(&auction.task_id, &self.agent_id, quote.price, estimate.duration_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assistant() -> BiddingAssistant {
+        // 10k in + 2k out tokens = $0.06, plus half an hour at $10/h = $5.06
+        let costs = CostModel::new(TokenPrices { input_usd: 0.000003, output_usd: 0.000015 }).with_hourly_rate(10.0);
+        BiddingAssistant::new("worker-1", costs).with_margins(0.10, 0.50)
+    }
+
+    fn estimate() -> TaskEstimate {
+        TaskEstimate::new(10_000, 2_000, 1800)
+    }
+
+    #[test]
+    fn test_quote_targets_margin_and_respects_floor() {
+        let open = TaskAuction::new("task-1", "Summarise", 100.0, 1, 2, "client");
+        let quote = assistant().quote(&open, &estimate()).unwrap();
+        assert!((quote.cost - 5.06).abs() < 1e-9);
+        assert!((quote.price - 7.59).abs() < 1e-9);
+        assert!(!quote.capped);
+
+        // Capped by the auction budget, but still above the 10% floor
+        let tight = TaskAuction::new("task-2", "Summarise", 6.0, 1, 2, "client");
+        let capped = assistant().quote(&tight, &estimate()).unwrap();
+        assert_eq!((capped.price, capped.capped), (6.0, true));
+
+        let cheap = TaskAuction::new("task-3", "Summarise", 5.5, 1, 2, "client");
+        assert!(matches!(assistant().quote(&cheap, &estimate()), Err(BidRefusal::MarginTooLow { .. })));
+
+        // Three hours to the execution deadline is too little for four hours of work
+        let slow = TaskEstimate::new(0, 0, 4 * 3600);
+        assert!(matches!(assistant().quote(&open, &slow), Err(BidRefusal::DeadlineTooTight { .. })));
+        let mut cancelled = open.clone();
+        cancelled.cancel("client").unwrap();
+        assert!(matches!(assistant().quote(&cancelled, &estimate()), Err(BidRefusal::AuctionClosed)));
+    }
+
+    #[test]
+    fn test_refuses_bids_over_budget() {
+        let mut auction = TaskAuction::new("task-1", "Summarise", 100.0, 1, 2, "client");
+        let broke = assistant().with_budget(|_: &str| Some(5.0));
+        assert!(matches!(broke.bid(&auction, &estimate()), Err(BidRefusal::OverBudget { .. })));
+
+        let funded = assistant().with_budget(|agent: &str| (agent == "worker-1").then_some(50.0));
+        let bid = funded.bid(&auction, &estimate()).unwrap();
+        assert_eq!((bid.agent_id.as_str(), bid.estimated_time_secs), ("worker-1", 1800));
+        auction.submit_bid(bid).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+//! Settlement Milestones
+//!
+//! Long tasks settle in stages instead of all at the end. A settlement
+//! created with [`Marketplace::create_milestone_settlement`] splits the
+//! winning bid into [`Milestone`]s, each a percentage of the amount.
+//! During execution the executor (or a verifier on its behalf) reports
+//! [`ExecutionProgress`] per milestone; a verified report releases that
+//! milestone's share, and the settlement keeps holding the rest.
+//!
+//! Every report is also broadcast to [`Marketplace::subscribe_progress`]
+//! subscribers, so a treasury escrow with matching milestones can stream
+//! funds as the work is verifiably completed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let settlement_id = market.create_milestone_settlement(&auction, vec![
+//!     Milestone::new("design", 20),
+//!     Milestone::new("build", 50),
+//!     Milestone::new("deploy", 30),
+//! ])?;
+//!
+//! let released = market.report_progress(ExecutionProgress::verified(&auction.id, "design", "verifier-1"))?;
+//! assert_eq!(released, Some(auction.get_winning_bid().unwrap().amount * 0.2));
+//! ```
+
+use super::{AuctionStatus, Marketplace, MarketplaceError, Settlement, SettlementStatus, TaskAuction};
+use agentkern_fsm::{State, SYSTEM_ACTOR};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// A stage of a settlement, released on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    /// Unique within the settlement
+    pub name: String,
+    /// Share of the settlement amount, in percent
+    pub percent: u8,
+    /// Amount released, once released
+    pub released: Option<f64>,
+}
+
+impl Milestone {
+    /// Create a milestone.
+    pub fn new(name: impl Into<String>, percent: u8) -> Self {
+        Self { name: name.into(), percent, released: None }
+    }
+
+    /// Whether the milestone was paid out.
+    pub fn is_released(&self) -> bool {
+        self.released.is_some()
+    }
+}
+
+/// Progress on a milestone of an executing task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionProgress {
+    /// Auction ID
+    pub auction_id: String,
+    /// Milestone name
+    pub milestone: String,
+    /// Reporting agent: the executor, or a verifier
+    pub reported_by: String,
+    /// Whether the work was verified; only verified progress releases funds
+    pub verified: bool,
+    /// Evidence of the work, e.g. a test report or artifact digest
+    pub evidence: Option<String>,
+    /// Reported at
+    pub at: DateTime<Utc>,
+}
+
+impl ExecutionProgress {
+    /// Progress claimed by `reported_by`, not yet verified.
+    pub fn claimed(auction_id: &str, milestone: &str, reported_by: &str) -> Self {
+        Self {
+            auction_id: auction_id.to_string(),
+            milestone: milestone.to_string(),
+            reported_by: reported_by.to_string(),
+            verified: false,
+            evidence: None,
+            at: Utc::now(),
+        }
+    }
+
+    /// Progress verified by `reported_by`.
+    pub fn verified(auction_id: &str, milestone: &str, reported_by: &str) -> Self {
+        Self { verified: true, ..Self::claimed(auction_id, milestone, reported_by) }
+    }
+
+    /// Add evidence.
+    pub fn with_evidence(mut self, evidence: impl Into<String>) -> Self {
+        self.evidence = Some(evidence.into());
+        self
+    }
+}
+
+impl Settlement {
+    /// What the settlement still holds: its amount less released milestones.
+    pub fn remaining(&self) -> f64 {
+        let released: f64 = self.milestones.iter().filter_map(|m| m.released).sum();
+        (self.amount - released).max(0.0)
+    }
+
+    /// Release milestone `name`'s share. The last milestone takes whatever
+    /// is left, so rounding never strands funds.
+    fn release_milestone(&mut self, name: &str) -> Result<f64, MarketplaceError> {
+        self.status.check_transition(&SettlementStatus::Released)?;
+        let outstanding = self.milestones.iter().filter(|m| !m.is_released()).count();
+        let remaining = self.remaining();
+        let amount = self.amount;
+        let milestone = self
+            .milestones
+            .iter_mut()
+            .find(|m| m.name == name)
+            .ok_or_else(|| MarketplaceError::MilestoneNotFound(name.to_string()))?;
+        if milestone.is_released() {
+            return Err(MarketplaceError::MilestoneReleased(name.to_string()));
+        }
+
+        let share = if outstanding == 1 { remaining } else { amount * f64::from(milestone.percent) / 100.0 };
+        milestone.released = Some(share);
+        if self.milestones.iter().all(Milestone::is_released) {
+            self.transition(SettlementStatus::Released, SYSTEM_ACTOR)?;
+        }
+        Ok(share)
+    }
+}
+
+/// Check milestones are named uniquely and their shares add up to 100%.
+fn validate(milestones: &[Milestone]) -> Result<(), MarketplaceError> {
+    let invalid = |reason: String| Err(MarketplaceError::InvalidMilestones(reason));
+    if milestones.is_empty() {
+        return invalid("at least one milestone is required".to_string());
+    }
+    for (i, milestone) in milestones.iter().enumerate() {
+        if milestone.name.trim().is_empty() {
+            return invalid("milestone names must not be empty".to_string());
+        }
+        if milestone.percent == 0 {
+            return invalid(format!("{} has a zero share", milestone.name));
+        }
+        if milestones[..i].iter().any(|m| m.name == milestone.name) {
+            return invalid(format!("{} is defined twice", milestone.name));
+        }
+    }
+    let total: u32 = milestones.iter().map(|m| u32::from(m.percent)).sum();
+    if total != 100 {
+        return invalid(format!("shares add up to {total}%, not 100%"));
+    }
+    Ok(())
+}
+
+impl Marketplace {
+    /// Create a settlement for an awarded auction, released in `milestones`
+    /// whose shares add up to 100%.
+    pub fn create_milestone_settlement(
+        &mut self,
+        auction: &TaskAuction,
+        milestones: Vec<Milestone>,
+    ) -> Result<String, MarketplaceError> {
+        validate(&milestones)?;
+        let id = self.create_settlement(auction).ok_or(MarketplaceError::NotAwarded)?;
+        let settlement = self.settlements.get_mut(&id).expect("settlement just created");
+        settlement.milestones = milestones.into_iter().map(|m| Milestone { released: None, ..m }).collect();
+        Ok(id)
+    }
+
+    /// Release one milestone of a settlement. Returns the amount released.
+    pub fn release_milestone(&mut self, settlement_id: &str, milestone: &str) -> Result<f64, MarketplaceError> {
+        let settlement = self.settlements.get_mut(settlement_id).ok_or(MarketplaceError::SettlementNotFound)?;
+        let amount = settlement.release_milestone(milestone)?;
+        tracing::info!(settlement_id = %settlement_id, milestone = %milestone, amount, "Settlement milestone released");
+        Ok(amount)
+    }
+
+    /// Record progress on an executing auction and broadcast it. Verified
+    /// progress releases the matching milestone of the auction's escrowed
+    /// settlement, if it has one; returns the amount released.
+    pub fn report_progress(&mut self, progress: ExecutionProgress) -> Result<Option<f64>, MarketplaceError> {
+        let auction = self.auctions.get_mut(&progress.auction_id).ok_or(MarketplaceError::AuctionNotFound)?;
+        if auction.status != AuctionStatus::InProgress {
+            return Err(MarketplaceError::NotInProgress);
+        }
+
+        let settlement = self.settlements.values_mut().find(|s| {
+            s.auction_id == progress.auction_id
+                && s.status == SettlementStatus::Escrowed
+                && s.milestones.iter().any(|m| m.name == progress.milestone)
+        });
+        let released = match settlement {
+            Some(settlement) if progress.verified => Some(settlement.release_milestone(&progress.milestone)?),
+            _ => None,
+        };
+
+        auction.progress.push(progress.clone());
+        // No subscribers is fine
+        let _ = self.progress.send(progress);
+        Ok(released)
+    }
+
+    /// Receive every progress report from now on.
+    pub fn subscribe_progress(&self) -> broadcast::Receiver<ExecutionProgress> {
+        self.progress.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marketplace::Bid;
+
+    fn executing(market: &mut Marketplace) -> TaskAuction {
+        let mut auction = TaskAuction::new("task-1", "Build a pipeline", 1000.0, 1, 48, "client-1");
+        auction.submit_bid(Bid::new("task-1", "worker-1", 1000.0, 3600)).unwrap();
+        auction.evaluate();
+        auction.start_execution().unwrap();
+        market.create_auction(auction.clone());
+        auction
+    }
+
+    fn stages() -> Vec<Milestone> {
+        vec![Milestone::new("design", 20), Milestone::new("build", 50), Milestone::new("deploy", 30)]
+    }
+
+    #[test]
+    fn test_verified_progress_streams_funds() {
+        let mut market = Marketplace::new();
+        let auction = executing(&mut market);
+        let settlement_id = market.create_milestone_settlement(&auction, stages()).unwrap();
+        let mut reports = market.subscribe_progress();
+
+        // Claims alone release nothing
+        let claimed = market.report_progress(ExecutionProgress::claimed(&auction.id, "design", "worker-1")).unwrap();
+        assert_eq!(claimed, None);
+
+        let design = ExecutionProgress::verified(&auction.id, "design", "verifier-1").with_evidence("sha256:abc");
+        assert_eq!(market.report_progress(design).unwrap(), Some(200.0));
+        assert_eq!(market.get_settlement(&settlement_id).unwrap().remaining(), 800.0);
+        assert!(matches!(
+            market.report_progress(ExecutionProgress::verified(&auction.id, "design", "verifier-1")),
+            Err(MarketplaceError::MilestoneReleased(_))
+        ));
+
+        assert!(!reports.try_recv().unwrap().verified);
+        assert_eq!(reports.try_recv().unwrap().evidence.as_deref(), Some("sha256:abc"));
+        assert_eq!(market.get_auction(&auction.id).unwrap().progress.len(), 2);
+
+        // The rest settles at completion
+        assert_eq!(market.release_settlement(&settlement_id).unwrap(), 800.0);
+    }
+
+    #[test]
+    fn test_last_milestone_releases_settlement() {
+        let mut market = Marketplace::new();
+        let auction = executing(&mut market);
+        let settlement_id = market.create_milestone_settlement(&auction, stages()).unwrap();
+
+        let released: f64 = ["build", "deploy", "design"]
+            .iter()
+            .map(|m| market.release_milestone(&settlement_id, m).unwrap())
+            .sum();
+
+        assert_eq!(released, 1000.0);
+        let settlement = market.get_settlement(&settlement_id).unwrap();
+        assert_eq!(settlement.status, SettlementStatus::Released);
+        assert!(settlement.settled_at.is_some());
+        assert!(matches!(market.refund_settlement(&settlement_id), Err(MarketplaceError::IllegalTransition(_))));
+    }
+
+    #[test]
+    fn test_invalid_milestones_rejected() {
+        let mut market = Marketplace::new();
+        let auction = executing(&mut market);
+
+        for milestones in [
+            vec![],
+            vec![Milestone::new("design", 20), Milestone::new("build", 70)],
+            vec![Milestone::new("build", 50), Milestone::new("build", 50)],
+            vec![Milestone::new("build", 100), Milestone::new("deploy", 0)],
+        ] {
+            assert!(matches!(
+                market.create_milestone_settlement(&auction, milestones),
+                Err(MarketplaceError::InvalidMilestones(_))
+            ));
+        }
+    }
+}
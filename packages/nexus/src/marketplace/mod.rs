@@ -8,14 +8,29 @@
 //! 1. Task Announcement → Agents can bid
 //! 2. Bid Evaluation → Select winner
 //! 3. Escrow Lock → Payment secured
-//! 4. Task Execution → Agent performs work
-//! 5. Settlement → Payment released
+//! 4. Task Execution → Agent performs work, reporting progress per milestone
+//! 5. Settlement → Payment released, in stages with [`milestones`]
+//!
+//! Auction and settlement statuses only move along legal transitions, each
+//! recorded in the entity's `history` with its actor and time.
+//!
+//! Worker agents price their bids with the [`bidding`] assistant.
+
+pub mod bidding;
+pub mod milestones;
+
+pub use milestones::{ExecutionProgress, Milestone};
 
 use crate::types::{Task, TaskStatus};
 use crate::agent_card::AgentCard;
+use agentkern_fsm::{IllegalTransition, State, TransitionLog, SYSTEM_ACTOR};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+
+/// Progress reports buffered per subscriber.
+const PROGRESS_CAPACITY: usize = 256;
 
 /// Bid on a task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +134,12 @@ pub struct TaskAuction {
     pub created_by: String,
     /// Created at
     pub created_at: DateTime<Utc>,
+    /// Progress reported during execution, oldest first
+    #[serde(default)]
+    pub progress: Vec<ExecutionProgress>,
+    /// Status transitions
+    #[serde(default)]
+    pub history: TransitionLog<AuctionStatus>,
 }
 
 /// Auction status.
@@ -139,6 +160,21 @@ pub enum AuctionStatus {
     Cancelled,
 }
 
+impl State for AuctionStatus {
+    const MACHINE: &'static str = "auction";
+
+    fn can_transition_to(&self, to: &Self) -> bool {
+        use AuctionStatus::*;
+        matches!(
+            (self, to),
+            (Open, Evaluating | Cancelled)
+                | (Evaluating, Awarded | Cancelled)
+                | (Awarded, InProgress | Cancelled)
+                | (InProgress, Completed | Cancelled)
+        )
+    }
+}
+
 impl TaskAuction {
     /// Create a new auction.
     pub fn new(
@@ -163,6 +199,8 @@ impl TaskAuction {
             status: AuctionStatus::Open,
             created_by: created_by.to_string(),
             created_at: now,
+            progress: vec![],
+            history: TransitionLog::default(),
         }
     }
 
@@ -195,9 +233,24 @@ impl TaskAuction {
         Ok(())
     }
 
-    /// Evaluate bids and select winner.
+    /// Move to `to`, recording `actor`. Fails if the transition is illegal.
+    pub fn transition(&mut self, to: AuctionStatus, actor: &str) -> Result<(), MarketplaceError> {
+        Ok(self.history.apply(&mut self.status, to, actor)?)
+    }
+
+    /// Cancel the auction, unless it already completed.
+    pub fn cancel(&mut self, actor: &str) -> Result<(), MarketplaceError> {
+        self.transition(AuctionStatus::Cancelled, actor)
+    }
+
+    /// Evaluate bids and select winner. Returns `None` if the auction is
+    /// not open or has no pending bids, cancelling it in the latter case.
     pub fn evaluate(&mut self) -> Option<&Bid> {
-        self.status = AuctionStatus::Evaluating;
+        let creator = self.created_by.clone();
+        if let Err(e) = self.transition(AuctionStatus::Evaluating, &creator) {
+            tracing::warn!(auction_id = %self.id, error = %e, "Auction not evaluated");
+            return None;
+        }
 
         // Get pending bids and find best
         let mut best_score = f64::MAX;
@@ -211,14 +264,13 @@ impl TaskAuction {
             }
         }
 
-        if winner_id.is_none() {
-            self.status = AuctionStatus::Cancelled;
+        let Some(winner_id) = winner_id else {
+            self.cancel(&creator).expect("evaluating auctions can be cancelled");
             return None;
-        }
+        };
 
-        let winner_id = winner_id.unwrap();
         self.winning_bid = Some(winner_id.clone());
-        self.status = AuctionStatus::Awarded;
+        self.transition(AuctionStatus::Awarded, &creator).expect("evaluating auctions can be awarded");
 
         // Update bid statuses
         for bid in &mut self.bids {
@@ -239,25 +291,25 @@ impl TaskAuction {
         })
     }
 
-    /// Start execution.
+    /// Start execution, on behalf of the winning agent.
     pub fn start_execution(&mut self) -> Result<(), MarketplaceError> {
-        if self.status != AuctionStatus::Awarded {
-            return Err(MarketplaceError::NotAwarded);
-        }
-        self.status = AuctionStatus::InProgress;
-        Ok(())
+        let executor = self.executor();
+        self.transition(AuctionStatus::InProgress, &executor)
     }
 
-    /// Complete execution.
+    /// Complete execution, on behalf of the winning agent.
     pub fn complete(&mut self) -> Result<f64, MarketplaceError> {
-        if self.status != AuctionStatus::InProgress {
-            return Err(MarketplaceError::NotInProgress);
-        }
-        self.status = AuctionStatus::Completed;
+        let executor = self.executor();
+        self.transition(AuctionStatus::Completed, &executor)?;
         
         // Return amount to settle
         Ok(self.get_winning_bid().map(|b| b.amount).unwrap_or(0.0))
     }
+
+    /// The winning agent, or the system if there is none yet.
+    fn executor(&self) -> String {
+        self.get_winning_bid().map_or_else(|| SYSTEM_ACTOR.to_string(), |b| b.agent_id.clone())
+    }
 }
 
 /// Settlement record.
@@ -279,6 +331,12 @@ pub struct Settlement {
     pub created_at: DateTime<Utc>,
     /// Settled at
     pub settled_at: Option<DateTime<Utc>>,
+    /// Staged payouts; empty if the settlement releases all at once
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    /// Status transitions
+    #[serde(default)]
+    pub history: TransitionLog<SettlementStatus>,
 }
 
 /// Settlement status.
@@ -295,10 +353,31 @@ pub enum SettlementStatus {
     Disputed,
 }
 
+impl State for SettlementStatus {
+    const MACHINE: &'static str = "settlement";
+
+    fn can_transition_to(&self, to: &Self) -> bool {
+        use SettlementStatus::*;
+        matches!((self, to), (Escrowed, Released | Refunded | Disputed) | (Disputed, Released | Refunded))
+    }
+}
+
+impl Settlement {
+    /// Move to `to`, recording `actor`. Fails if the transition is illegal.
+    pub fn transition(&mut self, to: SettlementStatus, actor: &str) -> Result<(), MarketplaceError> {
+        self.history.apply(&mut self.status, to, actor)?;
+        if matches!(to, SettlementStatus::Released | SettlementStatus::Refunded) {
+            self.settled_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+}
+
 /// Marketplace service.
 pub struct Marketplace {
     auctions: HashMap<String, TaskAuction>,
     settlements: HashMap<String, Settlement>,
+    progress: broadcast::Sender<ExecutionProgress>,
 }
 
 impl Marketplace {
@@ -307,6 +386,7 @@ impl Marketplace {
         Self {
             auctions: HashMap::new(),
             settlements: HashMap::new(),
+            progress: broadcast::channel(PROGRESS_CAPACITY).0,
         }
     }
 
@@ -357,6 +437,8 @@ impl Marketplace {
             status: SettlementStatus::Escrowed,
             created_at: Utc::now(),
             settled_at: None,
+            milestones: vec![],
+            history: TransitionLog::default(),
         };
 
         let id = settlement.id.clone();
@@ -364,34 +446,30 @@ impl Marketplace {
         Some(id)
     }
 
-    /// Release settlement.
+    /// Get settlement.
+    pub fn get_settlement(&self, id: &str) -> Option<&Settlement> {
+        self.settlements.get(id)
+    }
+
+    /// Release what the settlement still holds, including milestones not
+    /// yet released.
     pub fn release_settlement(&mut self, id: &str) -> Result<f64, MarketplaceError> {
         let settlement = self.settlements.get_mut(id)
             .ok_or(MarketplaceError::SettlementNotFound)?;
         
-        if settlement.status != SettlementStatus::Escrowed {
-            return Err(MarketplaceError::InvalidSettlementState);
-        }
-
-        settlement.status = SettlementStatus::Released;
-        settlement.settled_at = Some(Utc::now());
+        settlement.transition(SettlementStatus::Released, SYSTEM_ACTOR)?;
         
-        Ok(settlement.amount)
+        Ok(settlement.remaining())
     }
 
-    /// Refund settlement.
+    /// Refund what the settlement still holds.
     pub fn refund_settlement(&mut self, id: &str) -> Result<f64, MarketplaceError> {
         let settlement = self.settlements.get_mut(id)
             .ok_or(MarketplaceError::SettlementNotFound)?;
         
-        if settlement.status != SettlementStatus::Escrowed {
-            return Err(MarketplaceError::InvalidSettlementState);
-        }
-
-        settlement.status = SettlementStatus::Refunded;
-        settlement.settled_at = Some(Utc::now());
+        settlement.transition(SettlementStatus::Refunded, SYSTEM_ACTOR)?;
         
-        Ok(settlement.amount)
+        Ok(settlement.remaining())
     }
 }
 
@@ -420,6 +498,34 @@ pub enum MarketplaceError {
     SettlementNotFound,
     #[error("Invalid settlement state")]
     InvalidSettlementState,
+    #[error("Auction not found")]
+    AuctionNotFound,
+    #[error("Invalid milestones: {0}")]
+    InvalidMilestones(String),
+    #[error("Milestone not found: {0}")]
+    MilestoneNotFound(String),
+    #[error("Milestone already released: {0}")]
+    MilestoneReleased(String),
+    #[error(transparent)]
+    IllegalTransition(#[from] IllegalTransition),
+}
+
+impl agentkern_errors::Coded for MarketplaceError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::BidExceedsBudget | Self::InvalidMilestones(_) => ErrorCode::InvalidArgument,
+            Self::DuplicateBid => ErrorCode::AlreadyExists,
+            Self::SettlementNotFound | Self::AuctionNotFound | Self::MilestoneNotFound(_) => ErrorCode::NotFound,
+            Self::AuctionClosed
+            | Self::BidDeadlinePassed
+            | Self::NotAwarded
+            | Self::NotInProgress
+            | Self::InvalidSettlementState
+            | Self::MilestoneReleased(_)
+            | Self::IllegalTransition(_) => ErrorCode::InvalidState,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -520,6 +626,31 @@ mod tests {
         assert_eq!(amount, 50.0);
     }
 
+    #[test]
+    fn test_auction_transitions_are_enforced() {
+        let mut auction = TaskAuction::new("task-1", "Quick task", 100.0, 1, 2, "client-1");
+        auction.submit_bid(Bid::new("task-1", "worker-1", 50.0, 1800)).unwrap();
+        auction.evaluate();
+        auction.start_execution().unwrap();
+        auction.complete().unwrap();
+
+        // Completed auctions can neither be re-evaluated nor cancelled
+        assert!(auction.evaluate().is_none());
+        assert!(matches!(auction.cancel("client-1"), Err(MarketplaceError::IllegalTransition(_))));
+        assert_eq!(auction.status, AuctionStatus::Completed);
+
+        let trail: Vec<_> = auction.history.entries().iter().map(|t| (t.to, t.actor.as_str())).collect();
+        assert_eq!(
+            trail,
+            vec![
+                (AuctionStatus::Evaluating, "client-1"),
+                (AuctionStatus::Awarded, "client-1"),
+                (AuctionStatus::InProgress, "worker-1"),
+                (AuctionStatus::Completed, "worker-1"),
+            ]
+        );
+    }
+
     #[test]
     fn test_bid_exceeds_budget() {
         let mut auction = TaskAuction::new(
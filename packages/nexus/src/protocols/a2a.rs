@@ -72,7 +72,8 @@ impl ProtocolAdapter for A2AAdapter {
             correlation_id: None,
             timestamp: chrono::Utc::now(),
             metadata: std::collections::HashMap::new(),
-        })
+        }
+        .with_params_trace())
     }
 
     async fn serialize(&self, msg: &NexusMessage) -> Result<Vec<u8>, NexusError> {
@@ -80,7 +81,7 @@ impl ProtocolAdapter for A2AAdapter {
             jsonrpc: "2.0".into(),
             id: Some(msg.id.clone()),
             method: msg.method.clone(),
-            params: Some(msg.params.clone()),
+            params: Some(msg.params_with_trace()),
         };
         
         serde_json::to_vec(&rpc)
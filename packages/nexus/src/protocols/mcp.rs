@@ -74,7 +74,8 @@ impl ProtocolAdapter for MCPAdapter {
             correlation_id: None,
             timestamp: chrono::Utc::now(),
             metadata: std::collections::HashMap::new(),
-        })
+        }
+        .with_params_trace())
     }
 
     async fn serialize(&self, msg: &NexusMessage) -> Result<Vec<u8>, NexusError> {
@@ -82,7 +83,7 @@ impl ProtocolAdapter for MCPAdapter {
             jsonrpc: "2.0".into(),
             id: Some(MCPId::String(msg.id.clone())),
             method: Some(msg.method.clone()),
-            params: Some(msg.params.clone()),
+            params: Some(msg.params_with_trace()),
             result: None,
             error: None,
         };
@@ -1,6 +1,7 @@
 //! Agent Registry
 //!
-//! Maintains a registry of known agents and their capabilities.
+//! Maintains a registry of known agents and their capabilities, and the
+//! attestation standing of agents whose cards carry a TEE quote.
 //! This is the OPEN SOURCE version with basic functionality.
 //!
 //! Enterprise features (in ee/nexus-enterprise):
@@ -11,13 +12,21 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
 use tokio::sync::RwLock;
 use crate::agent_card::AgentCard;
+use crate::attestation::{AttestationStatus, QuoteVerifier};
 use crate::error::NexusError;
 
 /// Agent registry - in-memory implementation (Open Source).
 pub struct AgentRegistry {
     agents: Arc<RwLock<HashMap<String, AgentCard>>>,
+    /// Attestation standing of agents whose card carries a verified quote
+    attestations: Arc<RwLock<HashMap<String, AttestationStatus>>>,
+    quote_verifier: Option<Arc<dyn QuoteVerifier>>,
+    /// Quotes older than this fail verification
+    max_quote_age: Option<chrono::Duration>,
 }
 
 impl AgentRegistry {
@@ -25,10 +34,26 @@ impl AgentRegistry {
     pub fn new() -> Self {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            attestations: Arc::new(RwLock::new(HashMap::new())),
+            quote_verifier: None,
+            max_quote_age: None,
         }
     }
 
-    /// Register an agent.
+    /// Verify TEE quotes on agent cards with `verifier`.
+    pub fn with_quote_verifier(mut self, verifier: Arc<dyn QuoteVerifier>) -> Self {
+        self.quote_verifier = Some(verifier);
+        self
+    }
+
+    /// Refuse quotes older than `max_age`, so agents must re-attest.
+    pub fn with_max_quote_age(mut self, max_age: chrono::Duration) -> Self {
+        self.max_quote_age = Some(max_age);
+        self
+    }
+
+    /// Register an agent. A card with an attestation is refused if its
+    /// quote does not verify.
     pub async fn register(&self, card: AgentCard) -> Result<(), NexusError> {
         let id = card.id.clone();
         let mut agents = self.agents.write().await;
@@ -36,13 +61,15 @@ impl AgentRegistry {
         if agents.contains_key(&id) {
             return Err(NexusError::AgentAlreadyExists { agent_id: id });
         }
+        let status = self.attest(&card)?;
         
-        tracing::info!(agent_id = %id, name = %card.name, "Agent registered");
+        tracing::info!(agent_id = %id, name = %card.name, attested = status.is_verified(), "Agent registered");
+        self.attestations.write().await.insert(id.clone(), status);
         agents.insert(id, card);
         Ok(())
     }
 
-    /// Update an existing agent.
+    /// Update an existing agent, verifying any new attestation.
     pub async fn update(&self, card: AgentCard) -> Result<(), NexusError> {
         let id = card.id.clone();
         let mut agents = self.agents.write().await;
@@ -50,7 +77,9 @@ impl AgentRegistry {
         if !agents.contains_key(&id) {
             return Err(NexusError::AgentNotFound { agent_id: id });
         }
+        let status = self.attest(&card)?;
         
+        self.attestations.write().await.insert(id.clone(), status);
         agents.insert(id, card);
         Ok(())
     }
@@ -59,10 +88,66 @@ impl AgentRegistry {
     pub async fn unregister(&self, agent_id: &str) -> Result<AgentCard, NexusError> {
         let mut agents = self.agents.write().await;
         
+        self.attestations.write().await.remove(agent_id);
         agents.remove(agent_id)
             .ok_or(NexusError::AgentNotFound { agent_id: agent_id.to_string() })
     }
 
+    /// Attestation standing of an agent.
+    pub async fn attestation_status(&self, agent_id: &str) -> AttestationStatus {
+        self.attestations.read().await.get(agent_id).cloned().unwrap_or(AttestationStatus::Unattested)
+    }
+
+    /// Whether an agent's quote passed its latest check.
+    pub async fn is_attested(&self, agent_id: &str) -> bool {
+        self.attestations.read().await.get(agent_id).is_some_and(AttestationStatus::is_verified)
+    }
+
+    /// Check every attested card's quote again. Agents that fail stay
+    /// registered but lose their attested status until they update their
+    /// card with a quote that verifies. Returns the agents that failed.
+    pub async fn reverify_attestations(&self) -> Vec<String> {
+        let agents = self.agents.read().await;
+        let mut attestations = self.attestations.write().await;
+        let mut failed = Vec::new();
+        for (id, status) in attestations.iter_mut().filter(|(_, s)| s.is_verified()) {
+            let Some(card) = agents.get(id) else { continue };
+            if let Err(NexusError::AttestationFailed { reason, .. }) = self.attest(card) {
+                tracing::warn!(agent_id = %id, %reason, "Agent attestation failed re-check");
+                *status = AttestationStatus::Failed { reason, checked_at: Utc::now() };
+                failed.push(id.clone());
+            }
+        }
+        failed
+    }
+
+    /// Re-check attestations every `interval`, forever.
+    pub async fn recheck_attestations(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let failed = self.reverify_attestations().await;
+            if !failed.is_empty() {
+                tracing::info!(failed = failed.len(), "Attestation re-check revoked agents");
+            }
+        }
+    }
+
+    /// Verify a card's attestation, if it has one and there is a verifier.
+    fn attest(&self, card: &AgentCard) -> Result<AttestationStatus, NexusError> {
+        let (Some(attestation), Some(verifier)) = (&card.attestation, &self.quote_verifier) else {
+            return Ok(AttestationStatus::Unattested);
+        };
+        let failed = |reason: String| NexusError::AttestationFailed { agent_id: card.id.clone(), reason };
+        if let Some(max_age) = self.max_quote_age
+            && Utc::now() - attestation.issued_at > max_age
+        {
+            return Err(failed(format!("quote issued at {} is stale", attestation.issued_at)));
+        }
+        verifier.verify(&card.id, attestation).map_err(failed)?;
+        Ok(AttestationStatus::Verified { measurement: attestation.measurement.clone(), checked_at: Utc::now() })
+    }
+
     /// Get an agent by ID.
     pub async fn get(&self, agent_id: &str) -> Option<AgentCard> {
         let agents = self.agents.read().await;
@@ -100,7 +185,9 @@ impl Default for AgentRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::attestation::CardAttestation;
     use crate::types::Skill;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
     fn test_card(id: &str) -> AgentCard {
         AgentCard::new(id, format!("Agent {}", id), "http://localhost")
@@ -157,4 +244,36 @@ mod tests {
         assert_eq!(nlp_agents.len(), 1);
         assert_eq!(nlp_agents[0].id, "agent-1");
     }
+
+    #[tokio::test]
+    async fn test_attestation_checked_at_registration_and_recheck() {
+        let revoked = Arc::new(AtomicBool::new(false));
+        let check = revoked.clone();
+        let verifier = move |agent_id: &str, attestation: &CardAttestation| {
+            let bound = attestation.user_data_bytes().map_err(|e| e.to_string())? == agent_id.as_bytes();
+            match attestation.measurement_bytes() {
+                Ok(m) if m == b"good" && bound && !check.load(Ordering::SeqCst) => Ok(()),
+                _ => Err("untrusted measurement".to_string()),
+            }
+        };
+        let registry = AgentRegistry::new().with_quote_verifier(Arc::new(verifier));
+        let attested = |id: &str, measurement: &[u8]| {
+            test_card(id).with_attestation(CardAttestation::new("IntelTdx", measurement, b"quote", id.as_bytes()))
+        };
+
+        registry.register(attested("agent-1", b"good")).await.unwrap();
+        registry.register(test_card("agent-2")).await.unwrap();
+        assert!(registry.is_attested("agent-1").await);
+        assert_eq!(registry.attestation_status("agent-2").await, AttestationStatus::Unattested);
+
+        let result = registry.register(attested("agent-3", b"tampered")).await;
+        assert!(matches!(result, Err(NexusError::AttestationFailed { .. })));
+        assert!(registry.get("agent-3").await.is_none());
+
+        // A measurement revoked after registration fails the next re-check
+        revoked.store(true, Ordering::SeqCst);
+        assert_eq!(registry.reverify_attestations().await, vec!["agent-1".to_string()]);
+        assert!(!registry.is_attested("agent-1").await);
+        assert!(matches!(registry.attestation_status("agent-1").await, AttestationStatus::Failed { .. }));
+    }
 }
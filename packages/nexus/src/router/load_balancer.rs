@@ -38,6 +38,8 @@ pub struct AgentLoad {
     pub weight: u32,
     /// Is healthy
     pub healthy: bool,
+    /// Maximum active tasks (unlimited if unset)
+    pub max_concurrency: Option<usize>,
 }
 
 /// Load balancer for distributing tasks.
@@ -72,6 +74,21 @@ impl LoadBalancer {
         });
     }
 
+    /// Register an agent, taking its concurrency limit from the card.
+    pub fn register_card(&mut self, card: &AgentCard, weight: u32) {
+        self.register_agent(&card.id, weight);
+        if let Some(max) = card.max_concurrency {
+            self.set_max_concurrency(&card.id, max as usize);
+        }
+    }
+
+    /// Limit an agent's active tasks.
+    pub fn set_max_concurrency(&mut self, agent_id: &str, max: usize) {
+        if let Some(load) = self.agent_loads.get_mut(agent_id) {
+            load.max_concurrency = Some(max);
+        }
+    }
+
     /// Unregister an agent.
     pub fn unregister_agent(&mut self, agent_id: &str) {
         self.agent_loads.remove(agent_id);
@@ -79,19 +96,23 @@ impl LoadBalancer {
     }
 
     /// Select an agent from the list.
+    ///
+    /// Saturated agents are never selected; `None` means every agent is at
+    /// its concurrency limit and the task should be requeued.
     pub fn select<'a>(&self, agents: &'a [AgentCard], client_id: Option<&str>) -> Option<&'a AgentCard> {
-        if agents.is_empty() {
-            return None;
-        }
+        let available: Vec<_> = agents.iter()
+            .filter(|a| !self.is_saturated(&a.id))
+            .collect();
 
         // Filter to healthy agents only
-        let healthy: Vec<_> = agents.iter()
+        let healthy: Vec<_> = available.iter()
             .filter(|a| self.is_healthy(&a.id))
+            .copied()
             .collect();
 
         if healthy.is_empty() {
-            // Fall back to any agent if none healthy
-            return agents.first();
+            // Fall back to any agent with capacity if none healthy
+            return available.first().copied();
         }
 
         match self.strategy {
@@ -183,6 +204,14 @@ impl LoadBalancer {
             .unwrap_or(true) // Default to healthy if unknown
     }
 
+    /// Is the agent at its concurrency limit?
+    pub fn is_saturated(&self, agent_id: &str) -> bool {
+        self.agent_loads
+            .get(agent_id)
+            .and_then(|l| l.max_concurrency.map(|max| l.active_tasks >= max))
+            .unwrap_or(false)
+    }
+
     /// Record task start.
     pub fn task_started(&mut self, agent_id: &str) {
         if let Some(load) = self.agent_loads.get_mut(agent_id) {
@@ -328,6 +357,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_saturated_agents_are_skipped() {
+        let mut lb = LoadBalancer::new(LoadBalanceStrategy::Sticky);
+        let agents = create_test_agents();
+        for agent in &agents {
+            lb.register_card(&agent.clone().with_max_concurrency(1), 1);
+        }
+        lb.create_sticky_session("client", "agent-1");
+
+        lb.task_started("agent-1");
+        assert!(lb.is_saturated("agent-1"));
+        assert_ne!(lb.select(&agents, Some("client")).unwrap().id, "agent-1");
+
+        lb.task_started("agent-2");
+        lb.task_started("agent-3");
+        assert!(lb.select(&agents, Some("client")).is_none());
+
+        lb.task_completed("agent-1", 10, true);
+        assert_eq!(lb.select(&agents, Some("client")).unwrap().id, "agent-1");
+    }
+
     #[test]
     fn test_task_tracking() {
         let mut lb = LoadBalancer::new(LoadBalanceStrategy::RoundRobin);
@@ -6,16 +6,50 @@ mod load_balancer;
 
 pub use load_balancer::{LoadBalancer, LoadBalanceStrategy, AgentLoad};
 
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
 use crate::agent_card::AgentCard;
 use crate::registry::AgentRegistry;
 use crate::types::Task;
 use crate::error::NexusError;
 
+/// Default bound on tasks waiting for a free agent.
+const DEFAULT_MAX_QUEUED: usize = 1024;
+
+/// A task handed to an agent.
+///
+/// The agent's slot stays taken until [`TaskRouter::release`].
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub task: Task,
+    pub agent: AgentCard,
+}
+
+/// In-flight assignments and tasks waiting for capacity.
+#[derive(Default)]
+struct Slots {
+    in_flight: HashMap<String, u32>,
+    queue: VecDeque<Task>,
+}
+
 /// Task router for matching tasks to agents.
+///
+/// Agents never hold more in-flight assignments than their card's
+/// `max_concurrency` (or the router default). Saturated agents are routed
+/// around; when every matching agent is saturated the task is queued and
+/// assigned as slots are released.
+///
+/// Tasks needing a sensitive skill only go to agents whose TEE attestation
+/// currently verifies.
 pub struct TaskRouter {
     registry: Arc<AgentRegistry>,
     round_robin_counter: std::sync::atomic::AtomicUsize,
+    /// Limit for agents whose card sets none
+    default_max_concurrency: Option<u32>,
+    max_queued: usize,
+    slots: Mutex<Slots>,
+    /// Skills only attested agents may take
+    attested_skills: HashSet<String>,
 }
 
 impl TaskRouter {
@@ -24,41 +58,151 @@ impl TaskRouter {
         Self {
             registry,
             round_robin_counter: std::sync::atomic::AtomicUsize::new(0),
+            default_max_concurrency: None,
+            max_queued: DEFAULT_MAX_QUEUED,
+            slots: Mutex::new(Slots::default()),
+            attested_skills: HashSet::new(),
         }
     }
 
+    /// Route tasks requiring `skill` only to attested agents.
+    pub fn with_attested_skill(mut self, skill: impl Into<String>) -> Self {
+        self.attested_skills.insert(skill.into());
+        self
+    }
+
+    /// Limit agents whose card does not set `max_concurrency`.
+    pub fn with_default_max_concurrency(mut self, max: u32) -> Self {
+        self.default_max_concurrency = Some(max);
+        self
+    }
+
+    /// Bound the queue of tasks waiting for a free agent.
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
     /// Find the best agent for a task.
+    ///
+    /// Does not take a slot; use [`TaskRouter::assign`] for that.
     pub async fn find_best_agent(&self, task: &Task) -> Result<AgentCard, NexusError> {
         let candidates = self.find_candidates(task).await?;
         
         if candidates.is_empty() {
             return Err(NexusError::NoMatchingAgent { task_type: task.task_type.clone() });
         }
-        
-        // Score candidates and pick best
-        let mut scored: Vec<(AgentCard, u8)> = candidates
+
+        let slots = self.slots.lock().unwrap();
+        self.select(candidates, task, &slots)
+            .ok_or_else(|| NexusError::AgentsSaturated { task_type: task.task_type.clone() })
+    }
+
+    /// Assign a task to the best agent with capacity and take its slot.
+    ///
+    /// Returns `None` when every matching agent is saturated and the task
+    /// was queued instead.
+    pub async fn assign(&self, task: Task) -> Result<Option<Assignment>, NexusError> {
+        let candidates = self.find_candidates(&task).await?;
+        if candidates.is_empty() {
+            return Err(NexusError::NoMatchingAgent { task_type: task.task_type.clone() });
+        }
+
+        let mut slots = self.slots.lock().unwrap();
+        match self.select(candidates, &task, &slots) {
+            Some(agent) => {
+                *slots.in_flight.entry(agent.id.clone()).or_default() += 1;
+                Ok(Some(Assignment { task, agent }))
+            }
+            None if slots.queue.len() < self.max_queued => {
+                tracing::debug!(task_id = %task.id, "All matching agents saturated; task queued");
+                slots.queue.push_back(task);
+                Ok(None)
+            }
+            None => Err(NexusError::AgentsSaturated { task_type: task.task_type.clone() }),
+        }
+    }
+
+    /// Free an agent's slot and assign queued tasks that now fit.
+    ///
+    /// Queued tasks are considered oldest first; ones that still have no
+    /// agent with capacity keep their place in the queue.
+    pub async fn release(&self, agent_id: &str) -> Vec<Assignment> {
+        let waiting = {
+            let mut slots = self.slots.lock().unwrap();
+            if let Some(count) = slots.in_flight.get_mut(agent_id) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    slots.in_flight.remove(agent_id);
+                }
+            }
+            std::mem::take(&mut slots.queue)
+        };
+
+        let mut assigned = Vec::new();
+        let mut still_waiting = VecDeque::new();
+        for task in waiting {
+            let candidates = self.find_candidates(&task).await.unwrap_or_default();
+            let mut slots = self.slots.lock().unwrap();
+            match self.select(candidates, &task, &slots) {
+                Some(agent) => {
+                    *slots.in_flight.entry(agent.id.clone()).or_default() += 1;
+                    assigned.push(Assignment { task, agent });
+                }
+                None => still_waiting.push_back(task),
+            }
+        }
+
+        // Tasks queued while we were assigning go behind the older ones
+        let mut slots = self.slots.lock().unwrap();
+        still_waiting.append(&mut slots.queue);
+        slots.queue = still_waiting;
+        assigned
+    }
+
+    /// In-flight assignments for an agent.
+    pub fn in_flight(&self, agent_id: &str) -> u32 {
+        self.slots.lock().unwrap().in_flight.get(agent_id).copied().unwrap_or(0)
+    }
+
+    /// Tasks waiting for a free agent.
+    pub fn queued(&self) -> usize {
+        self.slots.lock().unwrap().queue.len()
+    }
+
+    /// Pick among candidates with free capacity: best skill match first,
+    /// then fewest in-flight assignments, then round-robin.
+    fn select(&self, candidates: Vec<AgentCard>, task: &Task, slots: &Slots) -> Option<AgentCard> {
+        let in_flight = |card: &AgentCard| slots.in_flight.get(&card.id).copied().unwrap_or(0);
+
+        // Score candidates, skipping saturated ones
+        let mut scored: Vec<(AgentCard, u8, u32)> = candidates
             .into_iter()
+            .filter(|card| {
+                card.max_concurrency
+                    .or(self.default_max_concurrency)
+                    .is_none_or(|max| in_flight(card) < max)
+            })
             .map(|card| {
                 let score = self.score_agent(&card, task);
-                (card, score)
+                let load = in_flight(&card);
+                (card, score, load)
             })
             .collect();
         
-        // Sort by score descending
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        // Sort by score descending, then load ascending
+        scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
         
-        // If top candidates have same score, use round-robin
-        let top_score = scored[0].1;
+        // If top candidates tie, use round-robin
+        let (top_score, top_load) = scored.first().map(|(_, s, l)| (*s, *l))?;
         let top_candidates: Vec<_> = scored
             .into_iter()
-            .filter(|(_, s)| *s == top_score)
-            .map(|(c, _)| c)
+            .filter(|(_, s, l)| *s == top_score && *l == top_load)
+            .map(|(c, _, _)| c)
             .collect();
         
         let idx = self.round_robin_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        let selected = &top_candidates[idx % top_candidates.len()];
-        
-        Ok(selected.clone())
+        Some(top_candidates[idx % top_candidates.len()].clone())
     }
 
     /// Find all candidate agents for a task.
@@ -78,8 +222,20 @@ impl TaskRouter {
                 }
             }
         }
-        
-        Ok(candidates)
+
+        if candidates.is_empty() || !task.required_skills.iter().any(|s| self.attested_skills.contains(s)) {
+            return Ok(candidates);
+        }
+        let mut attested = Vec::new();
+        for agent in candidates {
+            if self.registry.is_attested(&agent.id).await {
+                attested.push(agent);
+            }
+        }
+        if attested.is_empty() {
+            return Err(NexusError::AttestationRequired { task_type: task.task_type.clone() });
+        }
+        Ok(attested)
     }
 
     /// Score an agent for a task (0-100).
@@ -98,4 +254,56 @@ mod tests {
         let lb = LoadBalancer::new(LoadBalanceStrategy::RoundRobin);
         assert!(lb.all_loads().is_empty());
     }
+
+    #[tokio::test]
+    async fn test_concurrency_limits_reroute_and_requeue() {
+        let registry = Arc::new(AgentRegistry::new());
+        registry.register(AgentCard::new("busy", "Busy", "http://busy.local").with_max_concurrency(1)).await.unwrap();
+        registry.register(AgentCard::new("spare", "Spare", "http://spare.local").with_max_concurrency(1)).await.unwrap();
+        let router = TaskRouter::new(registry).with_max_queued(1);
+        let task = || Task::new("work", serde_json::Value::Null);
+
+        let first = router.assign(task()).await.unwrap().unwrap();
+        let second = router.assign(task()).await.unwrap().unwrap();
+        assert_ne!(first.agent.id, second.agent.id); // rerouted around the saturated agent
+        assert_eq!(router.in_flight(&first.agent.id), 1);
+
+        // Both saturated: one task queues, the next is refused
+        let queued = task();
+        assert!(router.assign(queued.clone()).await.unwrap().is_none());
+        assert!(matches!(router.assign(task()).await, Err(NexusError::AgentsSaturated { .. })));
+        assert!(router.find_best_agent(&task()).await.is_err());
+
+        let reassigned = router.release(&second.agent.id).await;
+        assert_eq!(reassigned.len(), 1);
+        assert_eq!(reassigned[0].task.id, queued.id);
+        assert_eq!(reassigned[0].agent.id, second.agent.id);
+        assert_eq!(router.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_skills_need_attested_agents() {
+        let skill = || crate::types::Skill {
+            id: "payments".into(),
+            name: "Payments".into(),
+            description: "".into(),
+            tags: vec![],
+            input_schema: None,
+            output_schema: None,
+        };
+        let verifier = |_: &str, _: &crate::attestation::CardAttestation| Ok(());
+        let registry = Arc::new(AgentRegistry::new().with_quote_verifier(Arc::new(verifier)));
+        registry.register(AgentCard::new("plain", "Plain", "http://plain.local").with_skill(skill())).await.unwrap();
+        let task = Task::new("pay", serde_json::Value::Null).require_skills(vec!["payments".into()]);
+
+        let router = TaskRouter::new(registry.clone()).with_attested_skill("payments");
+        assert!(matches!(router.find_best_agent(&task).await, Err(NexusError::AttestationRequired { .. })));
+        // Routers without the requirement still use unattested agents
+        assert_eq!(TaskRouter::new(registry.clone()).find_best_agent(&task).await.unwrap().id, "plain");
+
+        let attestation = crate::attestation::CardAttestation::new("AmdSevSnp", b"m", b"q", b"enclave");
+        let card = AgentCard::new("enclave", "Enclave", "http://enclave.local").with_skill(skill()).with_attestation(attestation);
+        registry.register(card).await.unwrap();
+        assert_eq!(router.find_best_agent(&task).await.unwrap().id, "enclave");
+    }
 }
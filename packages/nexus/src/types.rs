@@ -3,6 +3,7 @@
 //! Unified message format that can represent any agent protocol message.
 //! Designed for extensibility - new protocols just need to implement translation.
 
+use agentkern_telemetry::{TraceContext, TRACEPARENT, TRACESTATE};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
@@ -123,9 +124,59 @@ impl NexusMessage {
         self
     }
 
+    /// Propagate a W3C trace context as `traceparent` / `tracestate` metadata.
+    pub fn with_trace(mut self, trace: &TraceContext) -> Self {
+        self.metadata.insert(TRACEPARENT.into(), trace.traceparent().into());
+        match &trace.tracestate {
+            Some(state) => self.metadata.insert(TRACESTATE.into(), state.clone().into()),
+            None => self.metadata.remove(TRACESTATE),
+        };
+        self
+    }
+
+    /// Trace context carried in metadata, if present and valid.
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        TraceContext::from_headers(|key| self.metadata.get(key).and_then(|v| v.as_str()))
+    }
+
+    /// Move trace context from JSON-RPC `params._meta` (the MCP convention)
+    /// into metadata.
+    pub(crate) fn with_params_trace(mut self) -> Self {
+        let meta = self.params.get("_meta").cloned().unwrap_or_default();
+        if let Some(trace) = TraceContext::from_headers(|key| meta.get(key).and_then(|v| v.as_str())) {
+            self = self.with_trace(&trace);
+        }
+        self
+    }
+
+    /// `params` with the trace context added under `_meta`, for JSON-RPC protocols.
+    pub(crate) fn params_with_trace(&self) -> serde_json::Value {
+        let Some(trace) = self.trace_context() else {
+            return self.params.clone();
+        };
+        let mut params = match &self.params {
+            serde_json::Value::Null => serde_json::json!({}),
+            serde_json::Value::Object(_) => self.params.clone(),
+            // Positional params have nowhere to carry it
+            _ => return self.params.clone(),
+        };
+        let meta = params
+            .as_object_mut()
+            .map(|p| p.entry("_meta").or_insert_with(|| serde_json::json!({})));
+        if let Some(serde_json::Value::Object(meta)) = meta {
+            meta.insert(TRACEPARENT.into(), trace.traceparent().into());
+            if let Some(state) = trace.tracestate {
+                meta.insert(TRACESTATE.into(), state.into());
+            }
+        }
+        params
+    }
+
     /// Create a response to this message.
+    ///
+    /// A traced message's response carries a child of its trace context.
     pub fn respond(&self, result: serde_json::Value) -> Self {
-        Self {
+        let response = Self {
             id: Uuid::new_v4().to_string(),
             method: format!("{}/response", self.method),
             params: result,
@@ -135,6 +186,10 @@ impl NexusMessage {
             correlation_id: Some(self.id.clone()),
             timestamp: Utc::now(),
             metadata: HashMap::new(),
+        };
+        match self.trace_context() {
+            Some(trace) => response.with_trace(&trace.child()),
+            None => response,
         }
     }
 }
@@ -324,6 +379,24 @@ mod tests {
         assert!(msg.metadata.contains_key("auth"));
     }
 
+    #[test]
+    fn test_trace_context_propagation() {
+        let trace = TraceContext::new_root().with_tracestate("vendor=1");
+        let msg = NexusMessage::new("tasks/create", serde_json::Value::Null).with_trace(&trace);
+        assert_eq!(msg.trace_context().unwrap().span_id, trace.span_id);
+
+        let response = msg.respond(serde_json::json!({"ok": true})).trace_context().unwrap();
+        assert_eq!(response.trace_id, trace.trace_id);
+        assert_ne!(response.span_id, trace.span_id);
+        assert_eq!(response.tracestate.as_deref(), Some("vendor=1"));
+
+        // JSON-RPC protocols carry it in params._meta
+        let params = msg.params_with_trace();
+        assert_eq!(params["_meta"]["traceparent"], trace.traceparent());
+        let parsed = NexusMessage::new("tasks/create", params).with_params_trace();
+        assert_eq!(parsed.trace_context().unwrap().span_id, trace.span_id);
+    }
+
     #[test]
     fn test_task_creation() {
         let task = Task::new("summarize", serde_json::json!({"text": "hello"}))
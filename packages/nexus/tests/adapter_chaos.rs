@@ -0,0 +1,65 @@
+//! Gateway robustness under adapter frame faults: bit flips, truncation,
+//! protocol downgrades and duplicate delivery.
+
+use agentkern_arbiter::{ChaosConfig, ChaosMonkey, FrameFault, RobustnessCriteria};
+use agentkern_nexus::Nexus;
+use agentkern_nexus::protocols::{A2AAdapter, MCPAdapter, NLIPAdapter};
+use std::sync::Arc;
+
+const FRAMES: &[&str] = &[
+    r#"{"jsonrpc":"2.0","id":"a2a-1","method":"tasks/send","params":{"task":"Summarise Q3 filings"}}"#,
+    r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"get_weather","arguments":{"city":"Lagos"}}}"#,
+    r#"{"nlipVersion":"1.0","header":{"messageId":"msg-1","sender":"agent-a"},"payload":{"content":[{"type":"text","value":"hello"}]}}"#,
+];
+
+async fn gateway() -> Arc<Nexus> {
+    let nexus = Nexus::new();
+    nexus.register_adapter(A2AAdapter::new()).await;
+    nexus.register_adapter(MCPAdapter::new()).await;
+    nexus.register_adapter(NLIPAdapter::new()).await;
+    Arc::new(nexus)
+}
+
+#[tokio::test]
+async fn test_gateway_survives_frame_faults() {
+    let nexus = gateway().await;
+    let frames: Vec<Vec<u8>> = FRAMES.iter().cycle().take(300).map(|f| f.as_bytes().to_vec()).collect();
+    let monkey = ChaosMonkey::for_environment(ChaosConfig::adapter_faults(), "staging");
+
+    let report = monkey
+        .exercise_frames(&frames, move |frame| {
+            let nexus = nexus.clone();
+            async move { nexus.receive(&frame).await.map(|_| ()).map_err(|e| e.to_string()) }
+        })
+        .await;
+
+    report.assert_robust(&RobustnessCriteria::default());
+    assert!(report.recovery_probes > 0);
+    assert!(monkey.stats().frame_injections > 0);
+}
+
+#[tokio::test]
+async fn test_downgraded_and_truncated_frames_are_rejected() {
+    let nexus = gateway().await;
+    let frames: Vec<Vec<u8>> = FRAMES[..2].iter().map(|f| f.as_bytes().to_vec()).collect();
+
+    for fault in [FrameFault::ProtocolDowngrade, FrameFault::Truncate] {
+        let monkey = ChaosMonkey::new(ChaosConfig {
+            frame_fault_probability: 100,
+            frame_faults: vec![fault],
+            enabled: true,
+            ..Default::default()
+        });
+        let nexus = nexus.clone();
+        let report = monkey
+            .exercise_frames(&frames, move |frame| {
+                let nexus = nexus.clone();
+                async move { nexus.receive(&frame).await.map(|_| ()).map_err(|e| e.to_string()) }
+            })
+            .await;
+
+        let outcome = &report.faults[&format!("{:?}", fault)];
+        assert_eq!(outcome.rejected, 2, "{:?} frames should be rejected", fault);
+        report.assert_robust(&RobustnessCriteria::default());
+    }
+}
@@ -0,0 +1,396 @@
+//! Paid Task Flow
+//!
+//! Auction → Escrow → Verify → Execute → Settle → Reputation → Evidence.
+//!
+//! The creator's payment is held in the treasury ledger from award until
+//! settlement. If verification, execution or settlement fails, the flow
+//! compensates: the hold is released, the marketplace settlement refunded
+//! and the auction cancelled. Every decision is recorded as an ISO 42001
+//! audit event, and with a [`PaymentNotifier`] the executor is told when
+//! its payment is escrowed, released or settled. With an [`SlaTracker`] the
+//! award is held to SLA terms and breaches cost the executor part of the
+//! payment.
+
+use crate::notify::{PaymentEvent, PaymentNotification, PaymentNotifier};
+use crate::ports::{ReputationSink, TaskExecutor, TaskOutcome, TaskVerifier, Verdict};
+use crate::sla::{SettlementSplit, SlaTracker};
+use agentkern_arbiter::{AuditEvent, ComplianceLedger, HumanOversight, Iso42001Outcome};
+use agentkern_fsm::State;
+use agentkern_nexus::marketplace::{AuctionStatus, MarketplaceError};
+use agentkern_nexus::{Bid, Marketplace, TaskAuction};
+use agentkern_treasury::balance::LedgerError;
+use agentkern_treasury::{Amount, BalanceLedger, Currency};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Action verified before the winning agent runs a task.
+pub const EXECUTE_ACTION: &str = "marketplace.execute";
+
+/// Stage of a paid task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlowStage {
+    Auction,
+    Escrow,
+    Verification,
+    Execution,
+    Settlement,
+}
+
+/// A settled paid task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaidTaskReceipt {
+    pub auction_id: String,
+    pub settlement_id: String,
+    /// Agent that ran the task
+    pub executor: String,
+    /// Amount paid to the executor
+    pub amount: f64,
+    /// Verification that allowed execution
+    pub verdict: Verdict,
+    /// Task result
+    pub output: serde_json::Value,
+    /// Returned to the creator for SLA breaches
+    #[serde(default)]
+    pub clawback: f64,
+    /// Still escrowed until the creator acknowledges quality
+    #[serde(default)]
+    pub reserved: f64,
+}
+
+/// Paid task failures. Funds are back with the creator by the time one is returned.
+#[derive(Debug, thiserror::Error)]
+pub enum FlowError {
+    #[error("No eligible bids for auction {auction_id}")]
+    NoEligibleBids { auction_id: String },
+
+    #[error("Escrow failed: {0}")]
+    Escrow(#[from] LedgerError),
+
+    #[error("Agent {agent_id} denied: {reasoning}")]
+    Denied { agent_id: String, reasoning: String },
+
+    #[error("Agent {agent_id} failed the task: {reason}")]
+    ExecutionFailed { agent_id: String, reason: String },
+
+    #[error("Agent {agent_id} missed the execution deadline")]
+    Timeout { agent_id: String },
+
+    #[error("Marketplace error: {0}")]
+    Marketplace(#[from] MarketplaceError),
+}
+
+impl FlowError {
+    /// Stage that failed.
+    pub fn stage(&self) -> FlowStage {
+        match self {
+            FlowError::NoEligibleBids { .. } => FlowStage::Auction,
+            FlowError::Escrow(_) => FlowStage::Escrow,
+            FlowError::Denied { .. } => FlowStage::Verification,
+            FlowError::ExecutionFailed { .. } | FlowError::Timeout { .. } => FlowStage::Execution,
+            FlowError::Marketplace(_) => FlowStage::Settlement,
+        }
+    }
+}
+
+impl agentkern_errors::Coded for FlowError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            FlowError::NoEligibleBids { .. } => ErrorCode::NotFound,
+            FlowError::Escrow(e) => e.code(),
+            FlowError::Denied { .. } => ErrorCode::PolicyDenied,
+            FlowError::ExecutionFailed { .. } => ErrorCode::Internal,
+            FlowError::Timeout { .. } => ErrorCode::Timeout,
+            FlowError::Marketplace(e) => e.code(),
+        }
+    }
+}
+
+/// Funds and records held for an awarded task, for settlement or compensation.
+struct Award {
+    auction_id: String,
+    task_id: String,
+    settlement_id: String,
+    creator: String,
+    winner: Bid,
+    amount: Amount,
+    currency: Currency,
+}
+
+/// Orchestrates a paid task across marketplace, treasury, verification,
+/// reputation and audit.
+pub struct PaidTaskFlow {
+    marketplace: Arc<Mutex<Marketplace>>,
+    ledger: Arc<BalanceLedger>,
+    verifier: Arc<dyn TaskVerifier>,
+    executor: Arc<dyn TaskExecutor>,
+    reputation: Option<Arc<dyn ReputationSink>>,
+    compliance: Option<Arc<Mutex<ComplianceLedger>>>,
+    notifier: Option<Arc<PaymentNotifier>>,
+    sla: Option<Arc<SlaTracker>>,
+}
+
+impl PaidTaskFlow {
+    pub fn new(
+        marketplace: Arc<Mutex<Marketplace>>,
+        ledger: Arc<BalanceLedger>,
+        verifier: Arc<dyn TaskVerifier>,
+        executor: Arc<dyn TaskExecutor>,
+    ) -> Self {
+        Self { marketplace, ledger, verifier, executor, reputation: None, compliance: None, notifier: None, sla: None }
+    }
+
+    pub fn with_reputation(mut self, reputation: Arc<dyn ReputationSink>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Record ISO 42001 evidence for every decision.
+    pub fn with_compliance(mut self, compliance: Arc<Mutex<ComplianceLedger>>) -> Self {
+        self.compliance = Some(compliance);
+        self
+    }
+
+    /// Notify the executor of escrow, release and settlement.
+    pub fn with_notifier(mut self, notifier: Arc<PaymentNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Hold awards to SLA terms and apply breach penalties at settlement.
+    pub fn with_sla(mut self, sla: Arc<SlaTracker>) -> Self {
+        self.sla = Some(sla);
+        self
+    }
+
+    /// Run `auction` to settlement with `bids`.
+    pub async fn run(&self, auction: TaskAuction, bids: Vec<Bid>) -> Result<PaidTaskReceipt, FlowError> {
+        let award = self.award(auction, bids)?;
+        if let Some(sla) = &self.sla {
+            sla.attach(&award.auction_id, &award.task_id, &award.winner.agent_id, &award.creator);
+        }
+        self.notify(PaymentEvent::EscrowHeld, &award).await;
+
+        // Verify the winner before it runs anything
+        let context = self.context(&award);
+        let verdict = self.verifier.verify(&award.winner.agent_id, EXECUTE_ACTION, &context).await;
+        let outcome = if verdict.allowed { Iso42001Outcome::Allowed } else { Iso42001Outcome::Denied };
+        self.audit(&award.winner.agent_id, EXECUTE_ACTION, &verdict, outcome, &context);
+        if !verdict.allowed {
+            self.compensate(&award, &TaskOutcome::Denied { policy_id: verdict.policy_id.clone() });
+            self.notify(PaymentEvent::EscrowReleased, &award).await;
+            return Err(FlowError::Denied { agent_id: award.winner.agent_id, reasoning: verdict.reasoning });
+        }
+
+        let output = match self.execute(&award).await {
+            Ok(output) => output,
+            Err(e) => {
+                let outcome = TaskOutcome::Failed { reason: e.to_string() };
+                self.compensate(&award, &outcome);
+                self.notify(PaymentEvent::EscrowReleased, &award).await;
+                return Err(e);
+            }
+        };
+
+        if let Some(sla) = &self.sla {
+            sla.record_completion(&award.auction_id, Utc::now());
+        }
+
+        let split = match self.settle(&award) {
+            Ok(split) => split,
+            Err(e) => {
+                if matches!(e, FlowError::Escrow(_)) {
+                    // Settlement compensated after the payment failed
+                    self.notify(PaymentEvent::EscrowReleased, &award).await;
+                }
+                return Err(e);
+            }
+        };
+        self.notify(PaymentEvent::Settlement, &award).await;
+        self.record_reputation(&award.winner.agent_id, &TaskOutcome::Completed);
+        self.audit(&award.winner.agent_id, "marketplace.settle", &verdict, Iso42001Outcome::Allowed, &context);
+
+        Ok(PaidTaskReceipt {
+            auction_id: award.auction_id,
+            settlement_id: award.settlement_id,
+            executor: award.winner.agent_id,
+            amount: award.winner.amount,
+            verdict,
+            output,
+            clawback: split.clawback.to_float(),
+            reserved: split.reserved.to_float(),
+        })
+    }
+
+    /// Auction the task among eligible bidders and escrow the winning bid.
+    fn award(&self, auction: TaskAuction, bids: Vec<Bid>) -> Result<Award, FlowError> {
+        let creator = auction.created_by.clone();
+        let task_id = auction.task_id.clone();
+        let mut market = self.marketplace.lock().unwrap();
+        let auction_id = market.create_auction(auction);
+        let auction = market.get_auction_mut(&auction_id).expect("auction just created");
+
+        for bid in bids {
+            if !self.reputation.as_ref().is_none_or(|r| r.is_eligible(&bid.agent_id)) {
+                tracing::debug!(agent_id = %bid.agent_id, auction_id = %auction_id, "Skipping bid from ineligible agent");
+                continue;
+            }
+            if let Err(e) = auction.submit_bid(bid) {
+                tracing::debug!(auction_id = %auction_id, error = %e, "Bid rejected");
+            }
+        }
+        let Some(winner) = auction.evaluate().cloned() else {
+            return Err(FlowError::NoEligibleBids { auction_id });
+        };
+
+        let currency = self.ledger.get_balance(&creator).currency;
+        let amount = Amount::from_float(winner.amount, currency.decimals());
+        if let Err(e) = self.ledger.hold(&creator, amount) {
+            auction.cancel(&creator).expect("awarded auctions can be cancelled");
+            tracing::warn!(auction_id = %auction_id, creator = %creator, error = %e, "Escrow failed, auction cancelled");
+            let verdict = Verdict { allowed: false, risk_score: 0, policy_id: None, reasoning: e.to_string() };
+            let context = HashMap::from([("auction_id".to_string(), auction_id.clone())]);
+            self.audit(&creator, "marketplace.escrow", &verdict, Iso42001Outcome::Denied, &context);
+            return Err(e.into());
+        }
+        let auction = auction.clone();
+        let settlement_id = market.create_settlement(&auction).expect("auction has a winner");
+
+        tracing::info!(auction_id = %auction_id, agent_id = %winner.agent_id, amount = winner.amount, "Task awarded and escrowed");
+        Ok(Award { auction_id, task_id, settlement_id, creator, winner, amount, currency })
+    }
+
+    /// Run the task, bounded by the auction's execution deadline.
+    async fn execute(&self, award: &Award) -> Result<serde_json::Value, FlowError> {
+        let auction = {
+            let mut market = self.marketplace.lock().unwrap();
+            let auction = market.get_auction_mut(&award.auction_id).expect("awarded auction");
+            auction.start_execution()?;
+            auction.clone()
+        };
+        if let Some(sla) = &self.sla {
+            sla.record_response(&award.auction_id, Utc::now());
+        }
+        let agent_id = &award.winner.agent_id;
+        let remaining = (auction.execution_deadline - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        match tokio::time::timeout(remaining, self.executor.execute(agent_id, &auction)).await {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(reason)) => Err(FlowError::ExecutionFailed { agent_id: agent_id.clone(), reason }),
+            Err(_) => Err(FlowError::Timeout { agent_id: agent_id.clone() }),
+        }
+    }
+
+    /// Pay the executor from escrow, less any SLA penalty, and close the auction.
+    fn settle(&self, award: &Award) -> Result<SettlementSplit, FlowError> {
+        let mut market = self.marketplace.lock().unwrap();
+        // Complete only once paid, so a failed payment can still cancel the auction
+        let status = market.get_auction(&award.auction_id).expect("awarded auction").status;
+        status.check_transition(&AuctionStatus::Completed).map_err(MarketplaceError::from)?;
+        let split = match &self.sla {
+            Some(sla) => sla.settlement(&award.auction_id, award.amount),
+            None => SettlementSplit::full(award.amount),
+        };
+        let paid = self.ledger.commit_transfer(&award.creator, &award.winner.agent_id, split.pay).and_then(|()| {
+            if split.clawback.is_zero() {
+                Ok(())
+            } else {
+                self.ledger.release(&award.creator, split.clawback)
+            }
+        });
+        if let Err(e) = paid {
+            drop(market);
+            self.compensate(award, &TaskOutcome::Failed { reason: e.to_string() });
+            return Err(e.into());
+        }
+        market.get_auction_mut(&award.auction_id).expect("awarded auction").complete()?;
+        market.release_settlement(&award.settlement_id)?;
+        tracing::info!(
+            auction_id = %award.auction_id,
+            agent_id = %award.winner.agent_id,
+            clawback = split.clawback.to_float(),
+            "Paid task settled"
+        );
+        Ok(split)
+    }
+
+    /// Undo an award: release escrow, refund the settlement, cancel the auction.
+    fn compensate(&self, award: &Award, outcome: &TaskOutcome) {
+        if let Some(sla) = &self.sla {
+            sla.abandon(&award.auction_id);
+        }
+        if let Err(e) = self.ledger.release(&award.creator, award.amount) {
+            tracing::error!(auction_id = %award.auction_id, creator = %award.creator, error = %e, "Failed to release escrow");
+        }
+        {
+            let mut market = self.marketplace.lock().unwrap();
+            if let Err(e) = market.refund_settlement(&award.settlement_id) {
+                tracing::warn!(settlement_id = %award.settlement_id, error = %e, "Failed to refund settlement");
+            }
+            if let Some(auction) = market.get_auction_mut(&award.auction_id) {
+                if let Err(e) = auction.cancel(&award.creator) {
+                    tracing::warn!(auction_id = %award.auction_id, error = %e, "Failed to cancel auction");
+                }
+            }
+        }
+        self.record_reputation(&award.winner.agent_id, outcome);
+        if let TaskOutcome::Failed { reason } = outcome {
+            let mut context = self.context(award);
+            context.insert("failure".to_string(), reason.clone());
+            let verdict = Verdict { allowed: false, risk_score: 0, policy_id: None, reasoning: reason.clone() };
+            self.audit(&award.winner.agent_id, "marketplace.refund", &verdict, Iso42001Outcome::AuditOnly, &context);
+        }
+        tracing::warn!(auction_id = %award.auction_id, ?outcome, "Paid task compensated");
+    }
+
+    async fn notify(&self, event: PaymentEvent, award: &Award) {
+        let Some(notifier) = &self.notifier else {
+            return;
+        };
+        let notification = PaymentNotification::new(
+            event,
+            &award.settlement_id,
+            &award.creator,
+            &award.winner.agent_id,
+            award.winner.amount,
+            format!("{:?}", award.currency),
+        );
+        notifier.notify(notification).await;
+    }
+
+    fn record_reputation(&self, agent_id: &str, outcome: &TaskOutcome) {
+        if let Some(reputation) = &self.reputation {
+            reputation.record(agent_id, outcome);
+        }
+    }
+
+    fn context(&self, award: &Award) -> HashMap<String, String> {
+        HashMap::from([
+            ("auction_id".to_string(), award.auction_id.clone()),
+            ("settlement_id".to_string(), award.settlement_id.clone()),
+            ("creator".to_string(), award.creator.clone()),
+            ("amount".to_string(), award.winner.amount.to_string()),
+        ])
+    }
+
+    fn audit(&self, agent_id: &str, action: &str, verdict: &Verdict, outcome: Iso42001Outcome, context: &HashMap<String, String>) {
+        let Some(compliance) = &self.compliance else {
+            return;
+        };
+        compliance.lock().unwrap().record(AuditEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            agent_id: agent_id.to_string(),
+            action: action.to_string(),
+            policy_id: verdict.policy_id.clone(),
+            model_version: None,
+            risk_score: verdict.risk_score,
+            human_oversight: HumanOversight::None,
+            outcome,
+            context: context.clone(),
+        });
+    }
+}
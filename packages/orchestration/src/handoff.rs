@@ -0,0 +1,413 @@
+//! Inter-Cell Task Handoff
+//!
+//! Moves in-progress agent tasks to another mesh cell when a cell is drained
+//! or its region fails over, instead of letting them die.
+//!
+//! A task is packaged with its [`IntentPath`], task state, the business
+//! locks it holds and its escrow hold, and carried in a [`MemoryPassport`]
+//! (so the passport's region restrictions apply to the move). The package is
+//! digested and signed into the passport's provenance; the target verifies
+//! both before accepting.
+//!
+//! Handoff is two-phase:
+//! 1. **Offer**: the target cell verifies and stages the package.
+//! 2. **Commit**: the source releases the task's locks, the target
+//!    re-acquires them, checks the escrow hold still covers the task and
+//!    resumes it.
+//!
+//! If the commit fails the source takes the locks back and resumes the task
+//! itself. Escrow holds live in the shared treasury ledger, so they are
+//! adopted by the target rather than moved. When the source cell is already
+//! gone, a [`CellHandoff::checkpoint`] taken earlier can be taken over by
+//! another cell with [`CellHandoff::take_over`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let cell = CellHandoff::new(local_cell, locks, ledger, Arc::new(resumer))
+//!     .with_transport(Arc::new(mesh_transport));
+//! cell.register(task);
+//!
+//! // Draining: move everything to the standby cell
+//! for result in cell.drain(&standby).await {
+//!     let receipt = result?;
+//!     tracing::info!(task_id = %receipt.task_id, target = %receipt.target_cell, "Task handed off");
+//! }
+//! ```
+
+use crate::ports::{CellTransport, TaskResumer};
+use agentkern_arbiter::locks::LockError;
+use agentkern_arbiter::{BusinessLock, LockManager};
+use agentkern_synapse::passport::schema::{AgentIdentity, ProvenanceSignature};
+use agentkern_synapse::passport::{ExportOptions, ImportOptions};
+use agentkern_synapse::{IntentPath, MemoryPassport, MeshCell, PassportError, PassportExporter, PassportImporter};
+use agentkern_treasury::{Amount, BalanceLedger};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Passport metadata key holding the handoff package.
+pub const HANDOFF_KEY: &str = "handoff";
+
+/// Funds held in the treasury ledger for a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowHold {
+    /// Account the funds are held on
+    pub holder: String,
+    pub amount: Amount,
+}
+
+/// An in-progress task owned by a cell.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellTask {
+    pub task_id: String,
+    pub agent_id: String,
+    pub intent: IntentPath,
+    /// Task-specific state needed to resume
+    pub state: serde_json::Value,
+    /// Resources whose locks the task holds
+    pub resources: Vec<String>,
+    pub escrow: Option<EscrowHold>,
+    /// Regions the task's data may move to; empty for no restriction
+    #[serde(default)]
+    pub residency: Vec<String>,
+}
+
+/// A task on its way between cells.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoffPackage {
+    pub task: CellTask,
+    /// Locks held at packaging time
+    pub locks: Vec<BusinessLock>,
+    pub source_cell: String,
+    /// Cell the package is for; `None` for a checkpoint any cell may take over
+    pub target_cell: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HandoffPackage {
+    /// SHA-256 over the package, hex-encoded.
+    pub fn digest(&self) -> Result<String, HandoffError> {
+        let bytes = serde_json::to_vec(self).map_err(|e| HandoffError::Verification { reason: e.to_string() })?;
+        Ok(hex::encode(Sha256::digest(bytes)))
+    }
+}
+
+/// A completed handoff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoffReceipt {
+    pub task_id: String,
+    pub source_cell: String,
+    pub target_cell: String,
+    pub digest: String,
+    /// Locks re-acquired by the target
+    pub locks_moved: usize,
+}
+
+/// Handoff failures. The task is still running somewhere when one is returned.
+#[derive(Debug, thiserror::Error)]
+pub enum HandoffError {
+    #[error("Task {task_id} not found")]
+    TaskNotFound { task_id: String },
+
+    #[error("Cell {cell_id} is not available for handoff")]
+    CellUnavailable { cell_id: String },
+
+    #[error("Passport error: {0}")]
+    Passport(#[from] PassportError),
+
+    #[error("Handoff package rejected: {reason}")]
+    Verification { reason: String },
+
+    #[error("Transport to cell {cell_id} failed: {reason}")]
+    Transport { cell_id: String, reason: String },
+
+    #[error("Lock reconciliation failed: {0}")]
+    Lock(#[from] LockError),
+
+    #[error("Escrow for task {task_id} no longer holds {amount} on {holder}")]
+    EscrowMissing { task_id: String, holder: String, amount: String },
+
+    #[error("Task {task_id} failed to resume: {reason}")]
+    Resume { task_id: String, reason: String },
+}
+
+impl agentkern_errors::Coded for HandoffError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            HandoffError::TaskNotFound { .. } => ErrorCode::NotFound,
+            HandoffError::CellUnavailable { .. } | HandoffError::Transport { .. } => ErrorCode::Unavailable,
+            HandoffError::Passport(e) => e.code(),
+            HandoffError::Verification { .. } => ErrorCode::InvalidArgument,
+            HandoffError::Lock(e) => e.code(),
+            HandoffError::EscrowMissing { .. } => ErrorCode::InvalidState,
+            HandoffError::Resume { .. } => ErrorCode::Internal,
+        }
+    }
+}
+
+/// Task handoff endpoint of one mesh cell.
+pub struct CellHandoff {
+    cell: MeshCell,
+    locks: Arc<LockManager>,
+    ledger: Arc<BalanceLedger>,
+    resumer: Arc<dyn TaskResumer>,
+    transport: Option<Arc<dyn CellTransport>>,
+    tasks: Mutex<HashMap<String, CellTask>>,
+    /// Offers verified and waiting for commit
+    staged: Mutex<HashMap<String, HandoffPackage>>,
+}
+
+impl CellHandoff {
+    pub fn new(cell: MeshCell, locks: Arc<LockManager>, ledger: Arc<BalanceLedger>, resumer: Arc<dyn TaskResumer>) -> Self {
+        Self {
+            cell,
+            locks,
+            ledger,
+            resumer,
+            transport: None,
+            tasks: Mutex::new(HashMap::new()),
+            staged: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reach other cells to hand tasks off to them.
+    pub fn with_transport(mut self, transport: Arc<dyn CellTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    pub fn cell(&self) -> &MeshCell {
+        &self.cell
+    }
+
+    /// Track a task running on this cell.
+    pub fn register(&self, task: CellTask) {
+        self.tasks.lock().unwrap().insert(task.task_id.clone(), task);
+    }
+
+    /// Stop tracking a finished task.
+    pub fn complete(&self, task_id: &str) -> Option<CellTask> {
+        self.tasks.lock().unwrap().remove(task_id)
+    }
+
+    pub fn task(&self, task_id: &str) -> Option<CellTask> {
+        self.tasks.lock().unwrap().get(task_id).cloned()
+    }
+
+    pub fn task_ids(&self) -> Vec<String> {
+        self.tasks.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Hand every task on this cell to `target`.
+    pub async fn drain(&self, target: &MeshCell) -> Vec<Result<HandoffReceipt, HandoffError>> {
+        let mut results = Vec::new();
+        for task_id in self.task_ids() {
+            results.push(self.hand_off(&task_id, target).await);
+        }
+        results
+    }
+
+    /// Move one task to `target`, or leave it here if anything fails.
+    pub async fn hand_off(&self, task_id: &str, target: &MeshCell) -> Result<HandoffReceipt, HandoffError> {
+        let transport = match &self.transport {
+            Some(transport) if target.active && target.id != self.cell.id => transport.clone(),
+            _ => return Err(HandoffError::CellUnavailable { cell_id: target.id.clone() }),
+        };
+        let package = self.package(task_id, Some(target)).await?;
+        let bytes = self.seal(&package, Some(target))?;
+        let digest = package.digest()?;
+
+        transport
+            .offer(target, &bytes)
+            .await
+            .map_err(|reason| HandoffError::Transport { cell_id: target.id.clone(), reason })?;
+
+        // The target owns the task from here unless the commit fails
+        self.tasks.lock().unwrap().remove(task_id);
+        for lock in &package.locks {
+            if let Err(e) = self.locks.release(&lock.locked_by, &lock.resource).await {
+                tracing::warn!(task_id, resource = %lock.resource, error = %e, "Lock already gone at handoff");
+            }
+        }
+
+        if let Err(reason) = transport.commit(target, task_id).await {
+            tracing::warn!(task_id, target = %target.id, reason = %reason, "Handoff commit failed, resuming locally");
+            // Undo: take the locks back and resume here
+            self.adopt(package).await?;
+            return Err(HandoffError::Transport { cell_id: target.id.clone(), reason });
+        }
+
+        tracing::info!(task_id, source = %self.cell.id, target = %target.id, "Task handed off");
+        Ok(HandoffReceipt {
+            task_id: task_id.to_string(),
+            source_cell: self.cell.id.clone(),
+            target_cell: target.id.clone(),
+            digest,
+            locks_moved: package.locks.len(),
+        })
+    }
+
+    /// Verify and stage an offered package (target side).
+    pub fn accept(&self, bytes: &[u8]) -> Result<String, HandoffError> {
+        let package = self.open(bytes)?;
+        if package.target_cell.as_deref() != Some(self.cell.id.as_str()) {
+            return Err(HandoffError::Verification {
+                reason: format!("package is for cell {:?}, not {}", package.target_cell, self.cell.id),
+            });
+        }
+        self.check_escrow(&package.task)?;
+        let task_id = package.task.task_id.clone();
+        self.staged.lock().unwrap().insert(task_id.clone(), package);
+        Ok(task_id)
+    }
+
+    /// Take ownership of a staged task and resume it (target side).
+    pub async fn commit(&self, task_id: &str) -> Result<(), HandoffError> {
+        let package = self
+            .staged
+            .lock()
+            .unwrap()
+            .remove(task_id)
+            .ok_or_else(|| HandoffError::TaskNotFound { task_id: task_id.to_string() })?;
+        self.adopt(package).await
+    }
+
+    /// Package a task without releasing anything, for takeover if this cell fails.
+    pub async fn checkpoint(&self, task_id: &str) -> Result<Vec<u8>, HandoffError> {
+        let package = self.package(task_id, None).await?;
+        self.seal(&package, None)
+    }
+
+    /// Resume a task from a checkpoint of a cell that is gone.
+    pub async fn take_over(&self, bytes: &[u8]) -> Result<String, HandoffError> {
+        let package = self.open(bytes)?;
+        self.check_escrow(&package.task)?;
+        let task_id = package.task.task_id.clone();
+        tracing::warn!(task_id = %task_id, source = %package.source_cell, target = %self.cell.id, "Taking over task from failed cell");
+        self.adopt(package).await?;
+        Ok(task_id)
+    }
+
+    /// Snapshot a task with the locks its agent holds on the task's resources.
+    async fn package(&self, task_id: &str, target: Option<&MeshCell>) -> Result<HandoffPackage, HandoffError> {
+        let task = self.task(task_id).ok_or_else(|| HandoffError::TaskNotFound { task_id: task_id.to_string() })?;
+        let mut locks = Vec::new();
+        for resource in &task.resources {
+            if let Some(lock) = self.locks.get_status(resource).await.filter(|l| l.locked_by == task.agent_id) {
+                locks.push(lock);
+            }
+        }
+        Ok(HandoffPackage {
+            task,
+            locks,
+            source_cell: self.cell.id.clone(),
+            target_cell: target.map(|cell| cell.id.clone()),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Wrap a package in a passport addressed to `target`'s region.
+    fn seal(&self, package: &HandoffPackage, target: Option<&MeshCell>) -> Result<Vec<u8>, HandoffError> {
+        let now = Utc::now().timestamp_millis() as u64;
+        let identity = AgentIdentity {
+            did: format!("did:agentkern:{}", package.task.agent_id),
+            public_key: String::new(),
+            algorithm: "Ed25519".to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let mut passport = MemoryPassport::new(identity, format!("{:?}", self.cell.region));
+        passport.sovereignty.allowed_regions = package.task.residency.clone();
+        let digest = package.digest()?;
+        passport.provenance.signatures.push(ProvenanceSignature {
+            signer: format!("did:agentkern:cell:{}", self.cell.id),
+            signature: digest.clone(),
+            timestamp: now,
+            prev_hash: digest,
+        });
+        let value = serde_json::to_value(package).map_err(|e| PassportError::SerializationError(e.to_string()))?;
+        passport.metadata.insert(HANDOFF_KEY.to_string(), value);
+
+        let options = ExportOptions {
+            target_region: target.map(|cell| format!("{:?}", cell.region)),
+            ..Default::default()
+        };
+        Ok(PassportExporter::new().export(&passport, &options)?)
+    }
+
+    /// Import a passport and verify the package inside against its provenance.
+    fn open(&self, bytes: &[u8]) -> Result<HandoffPackage, HandoffError> {
+        let imported = PassportImporter::new().import(bytes, &ImportOptions::default())?;
+        let passport = imported.passport.ok_or(PassportError::MissingField("passport".into()))?;
+        passport.validate()?;
+
+        let value = passport.metadata.get(HANDOFF_KEY).cloned().ok_or(PassportError::MissingField(HANDOFF_KEY.into()))?;
+        let package: HandoffPackage =
+            serde_json::from_value(value).map_err(|e| PassportError::SerializationError(e.to_string()))?;
+
+        let signed = passport.provenance.signatures.last().map(|s| s.prev_hash.as_str());
+        let digest = package.digest()?;
+        if signed != Some(digest.as_str()) {
+            return Err(HandoffError::Verification { reason: "package digest does not match provenance".to_string() });
+        }
+        if passport.identity.did != format!("did:agentkern:{}", package.task.agent_id) {
+            return Err(HandoffError::Verification { reason: "passport identity does not match task agent".to_string() });
+        }
+        Ok(package)
+    }
+
+    fn check_escrow(&self, task: &CellTask) -> Result<(), HandoffError> {
+        let Some(escrow) = &task.escrow else {
+            return Ok(());
+        };
+        let pending = self.ledger.get_balance(&escrow.holder).pending;
+        if pending.value < escrow.amount.value {
+            return Err(HandoffError::EscrowMissing {
+                task_id: task.task_id.clone(),
+                holder: escrow.holder.clone(),
+                amount: escrow.amount.to_float().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Re-acquire the package's locks here, then resume the task. Locks are
+    /// all taken or none.
+    async fn adopt(&self, package: HandoffPackage) -> Result<(), HandoffError> {
+        self.acquire_locks(&package.locks).await?;
+        let task = package.task;
+        self.register(task.clone());
+        if let Err(reason) = self.resumer.resume(&task).await {
+            self.tasks.lock().unwrap().remove(&task.task_id);
+            for lock in &package.locks {
+                let _ = self.locks.release(&lock.locked_by, &lock.resource).await;
+            }
+            return Err(HandoffError::Resume { task_id: task.task_id, reason });
+        }
+        Ok(())
+    }
+
+    async fn acquire_locks(&self, locks: &[BusinessLock]) -> Result<(), HandoffError> {
+        let mut acquired: Vec<&BusinessLock> = Vec::new();
+        for lock in locks {
+            let remaining_ms = (lock.expires_at - Utc::now()).num_milliseconds().max(1) as u64;
+            let result = self
+                .locks
+                .acquire(&lock.locked_by, &lock.resource, lock.priority, lock.lock_type, Some(remaining_ms))
+                .await;
+            if let Err(e) = result {
+                for held in acquired {
+                    let _ = self.locks.release(&held.locked_by, &held.resource).await;
+                }
+                return Err(e.into());
+            }
+            acquired.push(lock);
+        }
+        Ok(())
+    }
+
+}
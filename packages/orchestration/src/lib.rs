@@ -0,0 +1,76 @@
+//! AgentKern-Orchestration: End-to-End Paid Task Flows
+//!
+//! Ties the pillars together for agent-to-agent commerce:
+//! auction a task on the Nexus marketplace, escrow the payment in the
+//! Treasury, verify the winning agent before it runs, execute, settle,
+//! update reputation and record ISO 42001 evidence.
+//!
+//! Every stage has a compensating action: a failure after escrow releases
+//! the hold, refunds the marketplace settlement and cancels the auction, so
+//! no funds stay locked and the audit trail shows why.
+//!
+//! Gate verification and task execution are supplied as [`TaskVerifier`]
+//! and [`TaskExecutor`]; reputation as a [`ReputationSink`] (the enterprise
+//! trust network implements it with the `enterprise` feature).
+//!
+//! [`PaymentNotifier`] tells agents about payments, escrow and settlement
+//! over the Nexus bus in their own protocol, tracking acknowledgments and
+//! falling back to webhooks.
+//!
+//! [`SlaTracker`] holds awarded tasks to response, completion and quality
+//! terms, clawing back part of the escrow on breach and reporting
+//! attainment per agent.
+//!
+//! [`AgentView`] answers "everything about agent X" across wallet, trust,
+//! locks, verifications, escalations, drift and cost, paged and filtered
+//! per field by the caller's roles.
+//!
+//! [`Onboarder`] brings an agent online from a declarative
+//! [`AgentManifest`]: registry entry, wallet, budgets, trust tier and
+//! policies, provisioned all together or not at all.
+//!
+//! [`CellHandoff`] moves in-progress tasks off a draining or failed cell:
+//! the intent path and task state travel in a Memory Passport, the target
+//! verifies and resumes them, and the task's locks and escrow follow.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_orchestration::PaidTaskFlow;
+//!
+//! let flow = PaidTaskFlow::new(marketplace, ledger, Arc::new(GateVerifier(gate)), Arc::new(executor))
+//!     .with_reputation(trust_network)
+//!     .with_compliance(compliance_ledger);
+//!
+//! let auction = TaskAuction::new("task-42", "Summarise Q3 filings", 25.0, 1, 4, "client-1");
+//! let receipt = flow.run(auction, bids).await?;
+//! println!("Paid {} to {}", receipt.amount, receipt.executor);
+//!
+//! let onboarder = Onboarder::new(registry, ledger, budgets).with_trust(trust_network);
+//! onboarder.onboard(&AgentManifest::from_yaml(&std::fs::read_to_string("agent.yaml")?)?).await?;
+//! ```
+
+pub mod flow;
+pub mod handoff;
+pub mod notify;
+pub mod onboarding;
+pub mod ports;
+pub mod sla;
+pub mod view;
+
+#[cfg(feature = "enterprise")]
+mod trust;
+
+// Re-exports
+pub use flow::{PaidTaskFlow, PaidTaskReceipt, FlowStage, FlowError};
+pub use handoff::{CellHandoff, CellTask, EscrowHold, HandoffError, HandoffPackage, HandoffReceipt};
+pub use onboarding::{
+    AgentManifest, BootstrapTier, DeprovisionReceipt, Onboarder, OnboardingError, OnboardingReceipt, OnboardingStep,
+};
+pub use notify::{Delivery, PaymentEvent, PaymentNotification, PaymentNotifier};
+pub use sla::{SettlementSplit, SlaAttainment, SlaBreach, SlaError, SlaMetric, SlaRecord, SlaTerms, SlaTracker};
+pub use view::{AgentQuery, AgentSnapshot, AgentView, FieldAccess, Page, PageRequest, ViewError, ViewField};
+pub use ports::{
+    TaskVerifier, Verdict, TaskExecutor, ReputationSink, TaskOutcome, TrustBootstrap, PolicyStore, MessageTransport,
+    WebhookFallback, TrustLookup, TrustStanding, CellTransport, TaskResumer,
+};
@@ -0,0 +1,260 @@
+//! Payment Notifications
+//!
+//! Tells agents about money moving to or from them instead of leaving them
+//! to poll balances. Each [`PaymentNotification`] becomes a
+//! [`NexusMessage`], encoded with the first protocol on the recipient's
+//! agent card that the gateway has an adapter for, and handed to a
+//! [`MessageTransport`]. The recipient acknowledges by replying with the
+//! notification's message ID as correlation ID (e.g. via
+//! `NexusMessage::respond`).
+//!
+//! Notifications that cannot be delivered, and ones still unacknowledged
+//! after the ack timeout, go to the [`WebhookFallback`].
+
+use crate::ports::{MessageTransport, WebhookFallback};
+use agentkern_arbiter::{WebhookNotifier, WebhookPayload};
+use agentkern_nexus::{AgentCard, Nexus, NexusMessage, Protocol};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Method prefix of notification messages (`payment.settlement`, ...).
+pub const METHOD_PREFIX: &str = "payment.";
+
+/// What happened to the funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentEvent {
+    /// Direct payment received
+    Payment,
+    /// Funds held in escrow for the recipient
+    EscrowHeld,
+    /// Escrow released back to the payer; the recipient will not be paid
+    EscrowReleased,
+    /// Escrow paid out to the recipient
+    Settlement,
+}
+
+impl PaymentEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Payment => "payment",
+            Self::EscrowHeld => "escrow_held",
+            Self::EscrowReleased => "escrow_released",
+            Self::Settlement => "settlement",
+        }
+    }
+}
+
+/// A payment event for one recipient.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentNotification {
+    pub event: PaymentEvent,
+    /// Transfer, settlement or auction the event belongs to
+    pub reference: String,
+    pub payer: String,
+    /// Agent being notified
+    pub payee: String,
+    pub amount: f64,
+    pub currency: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PaymentNotification {
+    pub fn new(
+        event: PaymentEvent,
+        reference: impl Into<String>,
+        payer: impl Into<String>,
+        payee: impl Into<String>,
+        amount: f64,
+        currency: impl Into<String>,
+    ) -> Self {
+        Self {
+            event,
+            reference: reference.into(),
+            payer: payer.into(),
+            payee: payee.into(),
+            amount,
+            currency: currency.into(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// The notification as a message from payer to payee.
+    pub fn to_message(&self) -> NexusMessage {
+        let params = serde_json::to_value(self).expect("notification serializes");
+        NexusMessage::new(format!("{METHOD_PREFIX}{}", self.event.name()), params)
+            .from_agent(&self.payer)
+            .to_agent(&self.payee)
+    }
+}
+
+/// How a notification was handed off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "via", rename_all = "snake_case")]
+pub enum Delivery {
+    /// Sent over the Nexus bus; awaiting acknowledgment
+    Nexus { message_id: String, protocol: Protocol },
+    /// Sent through the webhook fallback
+    Webhook { reason: String },
+    /// Neither channel took it
+    Failed { reason: String },
+}
+
+/// A delivered notification awaiting acknowledgment.
+struct Pending {
+    notification: PaymentNotification,
+    sent_at: DateTime<Utc>,
+}
+
+/// Delivers payment notifications over Nexus, tracking acknowledgments.
+pub struct PaymentNotifier {
+    nexus: Arc<Nexus>,
+    transport: Arc<dyn MessageTransport>,
+    fallback: Option<Arc<dyn WebhookFallback>>,
+    ack_timeout: Duration,
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl PaymentNotifier {
+    pub fn new(nexus: Arc<Nexus>, transport: Arc<dyn MessageTransport>) -> Self {
+        Self {
+            nexus,
+            transport,
+            fallback: None,
+            ack_timeout: Duration::from_secs(30),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Send undeliverable and unacknowledged notifications here.
+    pub fn with_fallback(mut self, fallback: Arc<dyn WebhookFallback>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// How long a recipient has to acknowledge before the fallback is used.
+    pub fn with_ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// Deliver a notification to its payee.
+    pub async fn notify(&self, notification: PaymentNotification) -> Delivery {
+        match self.send(&notification).await {
+            Ok((message_id, protocol)) => {
+                let pending = Pending { notification, sent_at: Utc::now() };
+                self.pending.lock().unwrap().insert(message_id.clone(), pending);
+                Delivery::Nexus { message_id, protocol }
+            }
+            Err(reason) => {
+                tracing::warn!(payee = %notification.payee, reason = %reason, "Payment notification not delivered over Nexus");
+                self.fall_back(&notification, reason).await
+            }
+        }
+    }
+
+    /// Record a recipient's acknowledgment. Returns whether it matched a
+    /// pending notification.
+    pub fn acknowledge(&self, reply: &NexusMessage) -> bool {
+        let Some(message_id) = &reply.correlation_id else {
+            return false;
+        };
+        let mut pending = self.pending.lock().unwrap();
+        match pending.get(message_id) {
+            // Only the payee can acknowledge
+            Some(p) if reply.source_agent.as_deref() == Some(p.notification.payee.as_str()) => {
+                pending.remove(message_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Notifications delivered but not yet acknowledged.
+    pub fn unacknowledged(&self) -> Vec<PaymentNotification> {
+        self.pending.lock().unwrap().values().map(|p| p.notification.clone()).collect()
+    }
+
+    /// Send notifications unacknowledged past the ack timeout to the fallback.
+    pub async fn sweep(&self) -> Vec<Delivery> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(self.ack_timeout).unwrap_or(chrono::Duration::MAX);
+        let expired: Vec<_> = {
+            let mut pending = self.pending.lock().unwrap();
+            let ids: Vec<_> = pending.iter().filter(|(_, p)| p.sent_at <= cutoff).map(|(id, _)| id.clone()).collect();
+            ids.into_iter().filter_map(|id| pending.remove(&id)).collect()
+        };
+
+        let mut deliveries = Vec::with_capacity(expired.len());
+        for Pending { notification, .. } in expired {
+            deliveries.push(self.fall_back(&notification, "not acknowledged".to_string()).await);
+        }
+        deliveries
+    }
+
+    /// Encode for the payee's protocol and deliver.
+    async fn send(&self, notification: &PaymentNotification) -> Result<(String, Protocol), String> {
+        let card = self
+            .nexus
+            .registry()
+            .get(&notification.payee)
+            .await
+            .ok_or_else(|| format!("agent {} is not registered", notification.payee))?;
+        let message = notification.to_message();
+
+        for protocol in card_protocols(&card) {
+            let Ok(payload) = self.nexus.send(&message, protocol).await else {
+                continue;
+            };
+            self.transport.deliver(&card, protocol, &payload).await?;
+            tracing::debug!(payee = %notification.payee, message_id = %message.id, ?protocol, "Payment notification sent");
+            return Ok((message.id, protocol));
+        }
+        Err(format!("no adapter for any protocol of agent {}", notification.payee))
+    }
+
+    async fn fall_back(&self, notification: &PaymentNotification, reason: String) -> Delivery {
+        let Some(fallback) = &self.fallback else {
+            return Delivery::Failed { reason };
+        };
+        match fallback.notify(notification).await {
+            Ok(()) => Delivery::Webhook { reason },
+            Err(e) => {
+                tracing::error!(payee = %notification.payee, error = %e, "Payment notification webhook failed");
+                Delivery::Failed { reason: format!("{reason}; webhook: {e}") }
+            }
+        }
+    }
+}
+
+/// Protocols the agent card declares, in its order of preference.
+fn card_protocols(card: &AgentCard) -> impl Iterator<Item = Protocol> + '_ {
+    card.protocols
+        .iter()
+        .filter_map(|p| serde_json::from_value(serde_json::Value::String(p.name.to_lowercase())).ok())
+}
+
+/// Payment notifications as generic webhook events.
+#[async_trait]
+impl WebhookFallback for WebhookNotifier {
+    async fn notify(&self, notification: &PaymentNotification) -> Result<(), String> {
+        let payload = WebhookPayload {
+            event_type: format!("{METHOD_PREFIX}{}", notification.event.name()),
+            level: "info".to_string(),
+            agent_id: notification.payee.clone(),
+            message: format!(
+                "{} {} {} from {}",
+                notification.event.name(),
+                notification.amount,
+                notification.currency,
+                notification.payer
+            ),
+            timestamp: notification.timestamp.to_rfc3339(),
+            data: serde_json::to_value(notification).map_err(|e| e.to_string())?,
+        };
+        self.notify_event(&payload).into_iter().collect::<Result<Vec<_>, _>>().map(|_| ()).map_err(|e| e.to_string())
+    }
+}
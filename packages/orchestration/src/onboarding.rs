@@ -0,0 +1,365 @@
+//! Agent Onboarding
+//!
+//! Provisions an agent from one declarative [`AgentManifest`]: its Nexus
+//! registry entry (identity, skills, protocols), Treasury wallet funding and
+//! budgets, starting trust tier and Gate policies. Steps run in that order
+//! and a failure undoes the ones before it, so an agent is either fully
+//! onboarded or not at all. [`Onboarder::deprovision`] removes it again.
+
+use crate::ports::{PolicyStore, TrustBootstrap};
+use agentkern_nexus::agent_card::ProtocolSupport;
+use agentkern_nexus::{AgentCard, AgentRegistry, NexusError, Skill};
+use agentkern_treasury::balance::LedgerError;
+use agentkern_treasury::{Amount, BalanceLedger, BudgetManager, BudgetPeriod, Currency, SpendingLimit};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Who the agent is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestIdentity {
+    pub id: String,
+    pub name: String,
+    /// Endpoint other agents reach it at
+    pub url: String,
+    #[serde(default)]
+    pub description: String,
+    /// Owning organization, recorded in the trust network
+    pub org: String,
+}
+
+/// Initial wallet funding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletSpec {
+    /// Must match the ledger's currency when given
+    #[serde(default)]
+    pub currency: Option<Currency>,
+    /// Amount deposited at onboarding
+    #[serde(default)]
+    pub funding: f64,
+}
+
+/// A spending limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetSpec {
+    pub period: BudgetPeriod,
+    pub max_amount: f64,
+}
+
+/// Trust tier a new agent starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BootstrapTier {
+    Untrusted,
+    Unknown,
+    Trusted,
+    Verified,
+}
+
+/// Everything needed to bring an agent online.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentManifest {
+    pub identity: ManifestIdentity,
+    #[serde(default)]
+    pub skills: Vec<Skill>,
+    #[serde(default)]
+    pub protocols: Vec<ProtocolSupport>,
+    #[serde(default)]
+    pub wallet: Option<WalletSpec>,
+    #[serde(default)]
+    pub budgets: Vec<BudgetSpec>,
+    /// Starting trust tier (requires a [`TrustBootstrap`])
+    #[serde(default)]
+    pub trust_tier: Option<BootstrapTier>,
+    /// Gate policy documents scoped to this agent (requires a [`PolicyStore`])
+    #[serde(default)]
+    pub policies: Vec<serde_json::Value>,
+}
+
+impl AgentManifest {
+    /// Parse and validate a YAML manifest.
+    pub fn from_yaml(yaml: &str) -> Result<Self, OnboardingError> {
+        let manifest: Self =
+            serde_yaml::from_str(yaml).map_err(|e| OnboardingError::InvalidManifest(e.to_string()))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Check the manifest can be provisioned.
+    pub fn validate(&self) -> Result<(), OnboardingError> {
+        let invalid = |reason: &str| Err(OnboardingError::InvalidManifest(reason.to_string()));
+        if self.identity.id.trim().is_empty() {
+            return invalid("identity.id is empty");
+        }
+        if self.identity.org.trim().is_empty() {
+            return invalid("identity.org is empty");
+        }
+        if let Some(wallet) = &self.wallet {
+            if !wallet.funding.is_finite() || wallet.funding < 0.0 {
+                return invalid("wallet.funding must be a non-negative number");
+            }
+        }
+        if self.budgets.iter().any(|b| !b.max_amount.is_finite() || b.max_amount <= 0.0) {
+            return invalid("budget max_amount must be a positive number");
+        }
+        Ok(())
+    }
+
+    fn card(&self) -> AgentCard {
+        let identity = &self.identity;
+        let mut card = AgentCard::new(&identity.id, &identity.name, &identity.url)
+            .with_description(&identity.description);
+        for skill in &self.skills {
+            card = card.with_skill(skill.clone());
+        }
+        for protocol in &self.protocols {
+            card = card.supports_protocol(protocol.clone());
+        }
+        card
+    }
+}
+
+/// Step of onboarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    Registry,
+    Wallet,
+    Budgets,
+    Trust,
+    Policies,
+}
+
+/// An onboarded agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingReceipt {
+    pub agent_id: String,
+    /// Amount deposited into the wallet
+    pub funded: f64,
+    /// Spending limits set
+    pub budgets: usize,
+    pub trust_tier: Option<BootstrapTier>,
+    /// Policies installed
+    pub policies: usize,
+}
+
+/// A deprovisioned agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeprovisionReceipt {
+    pub agent_id: String,
+    /// Wallet balance when the account was closed, for the caller to return
+    pub closing_balance: f64,
+}
+
+/// Onboarding failures. Nothing stays provisioned when one is returned.
+#[derive(Debug, thiserror::Error)]
+pub enum OnboardingError {
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Manifest needs {step:?} provisioning, which is not configured")]
+    Unconfigured { step: OnboardingStep },
+
+    #[error("Agent {agent_id} is not onboarded")]
+    NotFound { agent_id: String },
+
+    #[error("Registry error: {0}")]
+    Registry(#[from] NexusError),
+
+    #[error("Wallet error: {0}")]
+    Wallet(#[from] LedgerError),
+
+    #[error("Trust bootstrap failed: {0}")]
+    Trust(String),
+
+    #[error("Policy install failed: {0}")]
+    Policies(String),
+}
+
+impl OnboardingError {
+    /// Step that failed, if a provisioning step did.
+    pub fn step(&self) -> Option<OnboardingStep> {
+        match self {
+            Self::Unconfigured { step } => Some(*step),
+            Self::Registry(_) => Some(OnboardingStep::Registry),
+            Self::Wallet(_) => Some(OnboardingStep::Wallet),
+            Self::Trust(_) => Some(OnboardingStep::Trust),
+            Self::Policies(_) => Some(OnboardingStep::Policies),
+            Self::InvalidManifest(_) | Self::NotFound { .. } => None,
+        }
+    }
+}
+
+impl agentkern_errors::Coded for OnboardingError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::InvalidManifest(_) => ErrorCode::InvalidArgument,
+            Self::Unconfigured { .. } => ErrorCode::Unsupported,
+            Self::NotFound { .. } => ErrorCode::NotFound,
+            Self::Registry(e) => e.code(),
+            Self::Wallet(e) => e.code(),
+            Self::Trust(_) => ErrorCode::Internal,
+            Self::Policies(_) => ErrorCode::PolicyInvalid,
+        }
+    }
+}
+
+/// What has been provisioned so far, for rollback.
+#[derive(Default)]
+struct Provisioned {
+    registered: bool,
+    funded: Option<Amount>,
+    previous_limits: Option<Vec<SpendingLimit>>,
+    trust: bool,
+}
+
+/// Provisions and deprovisions agents across the pillars.
+pub struct Onboarder {
+    registry: Arc<AgentRegistry>,
+    ledger: Arc<BalanceLedger>,
+    budgets: Arc<BudgetManager>,
+    trust: Option<Arc<dyn TrustBootstrap>>,
+    policies: Option<Arc<dyn PolicyStore>>,
+}
+
+impl Onboarder {
+    /// Create an onboarder over the registry, ledger and budgets.
+    pub fn new(registry: Arc<AgentRegistry>, ledger: Arc<BalanceLedger>, budgets: Arc<BudgetManager>) -> Self {
+        Self { registry, ledger, budgets, trust: None, policies: None }
+    }
+
+    /// Set starting trust tiers.
+    pub fn with_trust(mut self, trust: Arc<dyn TrustBootstrap>) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    /// Install manifest policies.
+    pub fn with_policies(mut self, policies: Arc<dyn PolicyStore>) -> Self {
+        self.policies = Some(policies);
+        self
+    }
+
+    /// Provision everything the manifest describes, or nothing.
+    pub async fn onboard(&self, manifest: &AgentManifest) -> Result<OnboardingReceipt, OnboardingError> {
+        manifest.validate()?;
+        if manifest.trust_tier.is_some() && self.trust.is_none() {
+            return Err(OnboardingError::Unconfigured { step: OnboardingStep::Trust });
+        }
+        if !manifest.policies.is_empty() && self.policies.is_none() {
+            return Err(OnboardingError::Unconfigured { step: OnboardingStep::Policies });
+        }
+
+        let agent_id = manifest.identity.id.as_str();
+        let currency = self.ledger.get_balance(agent_id).currency;
+        if let Some(wallet) = &manifest.wallet {
+            if wallet.currency.is_some_and(|c| c != currency) {
+                return Err(LedgerError::CurrencyMismatch.into());
+            }
+        }
+
+        let mut done = Provisioned::default();
+        let result = self.provision(manifest, currency, &mut done).await;
+        if let Err(error) = &result {
+            tracing::warn!(agent_id = %agent_id, step = ?error.step(), error = %error, "Onboarding failed, rolling back");
+            self.rollback(agent_id, done).await;
+        }
+        result
+    }
+
+    async fn provision(
+        &self,
+        manifest: &AgentManifest,
+        currency: Currency,
+        done: &mut Provisioned,
+    ) -> Result<OnboardingReceipt, OnboardingError> {
+        let agent_id = manifest.identity.id.as_str();
+
+        self.registry.register(manifest.card()).await?;
+        done.registered = true;
+
+        let funding = manifest.wallet.as_ref().map(|w| w.funding).unwrap_or(0.0);
+        if funding > 0.0 {
+            let amount = Amount::from_float(funding, currency.decimals());
+            self.ledger.deposit(agent_id, amount)?;
+            done.funded = Some(amount);
+        }
+
+        if !manifest.budgets.is_empty() {
+            done.previous_limits = Some(self.budgets.clear_limits(agent_id));
+            for budget in &manifest.budgets {
+                let max_amount = Amount::from_float(budget.max_amount, currency.decimals());
+                self.budgets.set_limit(agent_id, SpendingLimit::new(max_amount, budget.period));
+            }
+        }
+
+        if let (Some(tier), Some(trust)) = (manifest.trust_tier, &self.trust) {
+            trust.enroll(agent_id, &manifest.identity.org, tier).map_err(OnboardingError::Trust)?;
+            done.trust = true;
+        }
+
+        if let Some(policies) = self.policies.as_ref().filter(|_| !manifest.policies.is_empty()) {
+            policies.install(agent_id, &manifest.policies).await.map_err(OnboardingError::Policies)?;
+        }
+
+        tracing::info!(agent_id = %agent_id, org = %manifest.identity.org, "Agent onboarded");
+        Ok(OnboardingReceipt {
+            agent_id: agent_id.to_string(),
+            funded: funding,
+            budgets: manifest.budgets.len(),
+            trust_tier: manifest.trust_tier,
+            policies: manifest.policies.len(),
+        })
+    }
+
+    /// Undo completed steps in reverse order.
+    async fn rollback(&self, agent_id: &str, done: Provisioned) {
+        if done.trust {
+            if let Some(trust) = &self.trust {
+                trust.remove(agent_id);
+            }
+        }
+        if let Some(previous) = done.previous_limits {
+            self.budgets.clear_limits(agent_id);
+            for limit in previous {
+                self.budgets.set_limit(agent_id, limit);
+            }
+        }
+        if let Some(amount) = done.funded {
+            if let Err(e) = self.ledger.withdraw(agent_id, amount) {
+                tracing::error!(agent_id = %agent_id, error = %e, "Failed to reverse onboarding deposit");
+            }
+        }
+        if done.registered {
+            let _ = self.registry.unregister(agent_id).await;
+        }
+    }
+
+    /// Remove an onboarded agent everywhere.
+    ///
+    /// Fails without changing anything while the agent has funds held in
+    /// escrow.
+    pub async fn deprovision(&self, agent_id: &str) -> Result<DeprovisionReceipt, OnboardingError> {
+        if self.registry.get(agent_id).await.is_none() {
+            return Err(OnboardingError::NotFound { agent_id: agent_id.to_string() });
+        }
+
+        let closing_balance = match self.ledger.close_account(agent_id) {
+            Ok(balance) => balance.balance.to_float(),
+            Err(LedgerError::AccountNotFound) => 0.0,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(policies) = &self.policies {
+            policies.remove(agent_id).await;
+        }
+        if let Some(trust) = &self.trust {
+            trust.remove(agent_id);
+        }
+        self.budgets.clear_limits(agent_id);
+        let _ = self.registry.unregister(agent_id).await;
+
+        tracing::info!(agent_id = %agent_id, closing_balance, "Agent deprovisioned");
+        Ok(DeprovisionReceipt { agent_id: agent_id.to_string(), closing_balance })
+    }
+}
@@ -0,0 +1,139 @@
+//! Flow Ports
+//!
+//! The stages of a paid task that are supplied by the caller: verifying the
+//! agent (normally the Gate), running the task, and recording reputation.
+//! Onboarding likewise takes trust enrollment and policy installation, and
+//! payment notifications take a message transport and a webhook fallback.
+
+use crate::handoff::CellTask;
+use crate::notify::PaymentNotification;
+use crate::onboarding::BootstrapTier;
+use crate::sla::SlaMetric;
+use agentkern_nexus::{AgentCard, Protocol, TaskAuction};
+use agentkern_synapse::MeshCell;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of verifying an agent before it runs a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verdict {
+    /// Was the action allowed?
+    pub allowed: bool,
+    /// Risk score (0-100)
+    pub risk_score: u8,
+    /// Policy that decided, if any
+    pub policy_id: Option<String>,
+    /// Human-readable reasoning
+    pub reasoning: String,
+}
+
+impl Verdict {
+    /// Allow with a risk score.
+    pub fn allow(risk_score: u8) -> Self {
+        Self { allowed: true, risk_score, policy_id: None, reasoning: "Allowed".to_string() }
+    }
+
+    /// Deny under a policy.
+    pub fn deny(policy_id: impl Into<String>, reasoning: impl Into<String>) -> Self {
+        Self { allowed: false, risk_score: 100, policy_id: Some(policy_id.into()), reasoning: reasoning.into() }
+    }
+}
+
+/// Verifies an agent's action before execution (e.g. `GateEngine::verify`).
+#[async_trait]
+pub trait TaskVerifier: Send + Sync {
+    async fn verify(&self, agent_id: &str, action: &str, context: &HashMap<String, String>) -> Verdict;
+}
+
+/// Runs an awarded task on behalf of the winning agent.
+#[async_trait]
+pub trait TaskExecutor: Send + Sync {
+    /// Returns the task result, or why it failed.
+    async fn execute(&self, agent_id: &str, auction: &TaskAuction) -> Result<serde_json::Value, String>;
+}
+
+/// How a paid task ended for the executing agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskOutcome {
+    /// Completed and paid
+    Completed,
+    /// Execution failed or timed out
+    Failed { reason: String },
+    /// Verification blocked the agent
+    Denied { policy_id: Option<String> },
+    /// Missed an SLA term of the task
+    SlaBreached { metric: SlaMetric },
+}
+
+/// Reputation updates from paid tasks.
+pub trait ReputationSink: Send + Sync {
+    /// Whether the agent may bid at all.
+    fn is_eligible(&self, _agent_id: &str) -> bool {
+        true
+    }
+
+    fn record(&self, agent_id: &str, outcome: &TaskOutcome);
+}
+
+/// Trust standing of an agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustStanding {
+    pub tier: String,
+    /// Reputation score (0-1000)
+    pub score: u16,
+}
+
+/// Current trust of agents, for the agent view.
+pub trait TrustLookup: Send + Sync {
+    /// `None` for agents the trust network has never seen.
+    fn standing(&self, agent_id: &str) -> Option<TrustStanding>;
+}
+
+/// Starting trust for onboarded agents.
+pub trait TrustBootstrap: Send + Sync {
+    /// Enroll the agent at `tier`.
+    fn enroll(&self, agent_id: &str, org_id: &str, tier: BootstrapTier) -> Result<(), String>;
+
+    fn remove(&self, agent_id: &str);
+}
+
+/// Per-agent Gate policies from onboarding manifests.
+#[async_trait]
+pub trait PolicyStore: Send + Sync {
+    /// Install policy documents for the agent, all or none.
+    async fn install(&self, agent_id: &str, policies: &[serde_json::Value]) -> Result<(), String>;
+
+    async fn remove(&self, agent_id: &str);
+}
+
+/// Delivers protocol-encoded messages to an agent's endpoint.
+#[async_trait]
+pub trait MessageTransport: Send + Sync {
+    async fn deliver(&self, agent: &AgentCard, protocol: Protocol, payload: &[u8]) -> Result<(), String>;
+}
+
+/// Out-of-band channel for notifications agents did not receive or acknowledge.
+#[async_trait]
+pub trait WebhookFallback: Send + Sync {
+    async fn notify(&self, notification: &PaymentNotification) -> Result<(), String>;
+}
+
+/// Carries task handoffs to another mesh cell, which answers with its
+/// [`CellHandoff::accept`](crate::CellHandoff::accept) and
+/// [`CellHandoff::commit`](crate::CellHandoff::commit).
+#[async_trait]
+pub trait CellTransport: Send + Sync {
+    /// Offer a sealed handoff package; `Ok` once the target has verified it.
+    async fn offer(&self, target: &MeshCell, package: &[u8]) -> Result<(), String>;
+
+    /// Tell the target to take over the offered task.
+    async fn commit(&self, target: &MeshCell, task_id: &str) -> Result<(), String>;
+}
+
+/// Restarts a task on the cell that now owns it.
+#[async_trait]
+pub trait TaskResumer: Send + Sync {
+    async fn resume(&self, task: &CellTask) -> Result<(), String>;
+}
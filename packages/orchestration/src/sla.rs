@@ -0,0 +1,462 @@
+//! Service Level Agreements for Paid Tasks
+//!
+//! Terms attach to an auction when it is awarded: how quickly the winner
+//! must start (response time), how quickly it must deliver (completion
+//! time) and whether the creator has to acknowledge the result's quality
+//! before the last part of the payment is released.
+//!
+//! Breaches are detected live by [`SlaTracker::evaluate`] and when the flow
+//! records a late response or completion. Each breach is reported to the
+//! [`ReputationSink`] once; the first one also claws back
+//! [`SlaTerms::clawback_percent`] of the escrowed payment to the creator at
+//! settlement. With a quality acknowledgment the same share stays held until
+//! the creator accepts the result, or is returned if they reject it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let sla = Arc::new(
+//!     SlaTracker::new(ledger.clone())
+//!         .with_default_terms(SlaTerms::new(20).with_completion_time(Duration::from_secs(600)))
+//!         .with_reputation(trust_network),
+//! );
+//! let flow = PaidTaskFlow::new(marketplace, ledger, verifier, executor).with_sla(sla.clone());
+//!
+//! let receipt = flow.run(auction, bids).await?;
+//! sla.acknowledge_quality(&receipt.auction_id, true)?;
+//! println!("{:.1}% attainment", sla.attainment(&receipt.executor).rate() * 100.0);
+//! ```
+
+use crate::ports::{ReputationSink, TaskOutcome};
+use agentkern_treasury::balance::LedgerError;
+use agentkern_treasury::{Amount, BalanceLedger};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Measured part of an SLA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaMetric {
+    /// Award to start of execution
+    ResponseTime,
+    /// Award to delivered output
+    CompletionTime,
+    /// Creator rejected the result
+    Quality,
+}
+
+impl SlaMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaMetric::ResponseTime => "response_time",
+            SlaMetric::CompletionTime => "completion_time",
+            SlaMetric::Quality => "quality",
+        }
+    }
+}
+
+/// SLA terms for an awarded task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaTerms {
+    pub response_time: Option<Duration>,
+    pub completion_time: Option<Duration>,
+    /// Hold back the penalty share until the creator accepts the result
+    pub quality_ack: bool,
+    /// Share of the payment returned to the creator on breach (0-100)
+    pub clawback_percent: u8,
+}
+
+impl SlaTerms {
+    pub fn new(clawback_percent: u8) -> Self {
+        Self { response_time: None, completion_time: None, quality_ack: false, clawback_percent: clawback_percent.min(100) }
+    }
+
+    pub fn with_response_time(mut self, limit: Duration) -> Self {
+        self.response_time = Some(limit);
+        self
+    }
+
+    pub fn with_completion_time(mut self, limit: Duration) -> Self {
+        self.completion_time = Some(limit);
+        self
+    }
+
+    /// Require the creator to acknowledge quality before full payment.
+    pub fn with_quality_ack(mut self) -> Self {
+        self.quality_ack = true;
+        self
+    }
+
+    /// Penalty share of `amount`.
+    fn clawback(&self, amount: Amount) -> Amount {
+        Amount::new(amount.value * self.clawback_percent as i64 / 100, amount.decimals)
+    }
+}
+
+/// A missed SLA term.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaBreach {
+    pub auction_id: String,
+    pub agent_id: String,
+    pub metric: SlaMetric,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// SLA failures.
+#[derive(Debug, thiserror::Error)]
+pub enum SlaError {
+    #[error("No SLA for auction {auction_id}")]
+    NotFound { auction_id: String },
+
+    #[error("SLA for auction {auction_id} has no quality acknowledgment pending")]
+    NothingToAcknowledge { auction_id: String },
+
+    #[error("Penalty transfer failed: {0}")]
+    Ledger(#[from] LedgerError),
+}
+
+impl agentkern_errors::Coded for SlaError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            SlaError::NotFound { .. } => ErrorCode::NotFound,
+            SlaError::NothingToAcknowledge { .. } => ErrorCode::InvalidState,
+            SlaError::Ledger(e) => e.code(),
+        }
+    }
+}
+
+/// How an escrowed payment is divided at settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementSplit {
+    /// Paid to the executor now
+    pub pay: Amount,
+    /// Returned to the creator as a penalty
+    pub clawback: Amount,
+    /// Still held, pending quality acknowledgment
+    pub reserved: Amount,
+}
+
+impl SettlementSplit {
+    /// Everything to the executor.
+    pub fn full(amount: Amount) -> Self {
+        let zero = Amount::new(0, amount.decimals);
+        Self { pay: amount, clawback: zero, reserved: zero }
+    }
+}
+
+/// SLA state of one awarded task.
+#[derive(Debug, Clone)]
+pub struct SlaRecord {
+    pub auction_id: String,
+    pub task_id: String,
+    pub agent_id: String,
+    pub creator: String,
+    pub terms: SlaTerms,
+    pub awarded_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub breaches: Vec<SlaBreach>,
+    /// Penalty returned to the creator
+    pub clawed_back: Amount,
+    /// Held for the quality acknowledgment
+    pub reserved: Amount,
+    /// Settled, acknowledged or abandoned
+    pub closed: bool,
+}
+
+impl SlaRecord {
+    fn breached(&self, metric: SlaMetric) -> bool {
+        self.breaches.iter().any(|b| b.metric == metric)
+    }
+
+    /// Terms missed by `now` that have not been recorded yet.
+    fn due(&self, now: DateTime<Utc>) -> Vec<SlaMetric> {
+        let late = |limit: Option<Duration>, done: Option<DateTime<Utc>>| {
+            let Some(limit) = limit else {
+                return false;
+            };
+            let elapsed = (done.unwrap_or(now) - self.awarded_at).to_std().unwrap_or(Duration::ZERO);
+            elapsed > limit
+        };
+        let mut due = Vec::new();
+        if late(self.terms.response_time, self.responded_at) && !self.breached(SlaMetric::ResponseTime) {
+            due.push(SlaMetric::ResponseTime);
+        }
+        if late(self.terms.completion_time, self.completed_at) && !self.breached(SlaMetric::CompletionTime) {
+            due.push(SlaMetric::CompletionTime);
+        }
+        due
+    }
+}
+
+/// SLA attainment of one agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlaAttainment {
+    pub agent_id: String,
+    /// Tasks tracked under an SLA
+    pub tracked: u32,
+    /// Closed tasks
+    pub closed: u32,
+    /// Closed tasks without a breach
+    pub met: u32,
+    pub breaches: HashMap<SlaMetric, u32>,
+    /// Total penalty returned to creators
+    pub clawed_back: f64,
+}
+
+impl SlaAttainment {
+    /// Share of closed tasks that met their SLA (1.0 before any closed).
+    pub fn rate(&self) -> f64 {
+        if self.closed == 0 {
+            1.0
+        } else {
+            self.met as f64 / self.closed as f64
+        }
+    }
+}
+
+/// Tracks SLAs of awarded tasks and applies their penalties.
+pub struct SlaTracker {
+    ledger: Arc<BalanceLedger>,
+    default_terms: Option<SlaTerms>,
+    terms: Mutex<HashMap<String, SlaTerms>>,
+    records: Mutex<HashMap<String, SlaRecord>>,
+    reputation: Option<Arc<dyn ReputationSink>>,
+}
+
+impl SlaTracker {
+    pub fn new(ledger: Arc<BalanceLedger>) -> Self {
+        Self {
+            ledger,
+            default_terms: None,
+            terms: Mutex::new(HashMap::new()),
+            records: Mutex::new(HashMap::new()),
+            reputation: None,
+        }
+    }
+
+    /// Terms for tasks without their own.
+    pub fn with_default_terms(mut self, terms: SlaTerms) -> Self {
+        self.default_terms = Some(terms);
+        self
+    }
+
+    /// Report breaches as reputation events.
+    pub fn with_reputation(mut self, reputation: Arc<dyn ReputationSink>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Set the terms for `task_id`, applied when its auction is awarded.
+    pub fn set_terms(&self, task_id: &str, terms: SlaTerms) {
+        self.terms.lock().unwrap().insert(task_id.to_string(), terms);
+    }
+
+    /// Start tracking an awarded auction. Returns false if no terms apply.
+    pub fn attach(&self, auction_id: &str, task_id: &str, agent_id: &str, creator: &str) -> bool {
+        let Some(terms) = self.terms.lock().unwrap().get(task_id).cloned().or_else(|| self.default_terms.clone()) else {
+            return false;
+        };
+        let record = SlaRecord {
+            auction_id: auction_id.to_string(),
+            task_id: task_id.to_string(),
+            agent_id: agent_id.to_string(),
+            creator: creator.to_string(),
+            terms,
+            awarded_at: Utc::now(),
+            responded_at: None,
+            completed_at: None,
+            breaches: Vec::new(),
+            clawed_back: Amount::new(0, 0),
+            reserved: Amount::new(0, 0),
+            closed: false,
+        };
+        self.records.lock().unwrap().insert(auction_id.to_string(), record);
+        true
+    }
+
+    /// The executor started work.
+    pub fn record_response(&self, auction_id: &str, at: DateTime<Utc>) -> Vec<SlaBreach> {
+        self.update(auction_id, at, |record| {
+            record.responded_at.get_or_insert(at);
+        })
+    }
+
+    /// The executor delivered its output.
+    pub fn record_completion(&self, auction_id: &str, at: DateTime<Utc>) -> Vec<SlaBreach> {
+        self.update(auction_id, at, |record| {
+            record.responded_at.get_or_insert(at);
+            record.completed_at.get_or_insert(at);
+        })
+    }
+
+    /// Detect breaches of open SLAs as of `now`.
+    pub fn evaluate(&self, now: DateTime<Utc>) -> Vec<SlaBreach> {
+        let breaches: Vec<_> = {
+            let mut records = self.records.lock().unwrap();
+            records.values_mut().filter(|r| !r.closed).flat_map(|r| Self::breach(r, now)).collect()
+        };
+        self.report_breaches(&breaches);
+        breaches
+    }
+
+    /// Divide the escrowed `amount` of a settling auction. Untracked auctions are paid in full.
+    pub fn settlement(&self, auction_id: &str, amount: Amount) -> SettlementSplit {
+        let zero = Amount::new(0, amount.decimals);
+        let mut records = self.records.lock().unwrap();
+        let Some(record) = records.get_mut(auction_id).filter(|r| !r.closed) else {
+            return SettlementSplit::full(amount);
+        };
+        let penalty = record.terms.clawback(amount);
+        let pay = amount.sub(&penalty).unwrap_or(zero);
+        let split = if !record.breaches.is_empty() {
+            record.closed = true;
+            SettlementSplit { pay, clawback: penalty, reserved: zero }
+        } else if record.terms.quality_ack {
+            record.reserved = penalty;
+            SettlementSplit { pay, clawback: zero, reserved: penalty }
+        } else {
+            record.closed = true;
+            SettlementSplit::full(amount)
+        };
+        record.clawed_back = split.clawback;
+        split
+    }
+
+    /// Creator's verdict on the result: pays out or claws back the reserved share.
+    pub fn acknowledge_quality(&self, auction_id: &str, accepted: bool) -> Result<Option<SlaBreach>, SlaError> {
+        let mut records = self.records.lock().unwrap();
+        let record = records.get_mut(auction_id).ok_or_else(|| SlaError::NotFound { auction_id: auction_id.to_string() })?;
+        if record.closed || !record.terms.quality_ack || record.completed_at.is_none() {
+            return Err(SlaError::NothingToAcknowledge { auction_id: auction_id.to_string() });
+        }
+        let reserved = record.reserved;
+        if accepted {
+            if !reserved.is_zero() {
+                self.ledger.commit_transfer(&record.creator, &record.agent_id, reserved)?;
+            }
+            record.reserved = Amount::new(0, reserved.decimals);
+            record.closed = true;
+            return Ok(None);
+        }
+
+        if !reserved.is_zero() {
+            self.ledger.release(&record.creator, reserved)?;
+        }
+        let breach = SlaBreach {
+            auction_id: auction_id.to_string(),
+            agent_id: record.agent_id.clone(),
+            metric: SlaMetric::Quality,
+            detected_at: Utc::now(),
+        };
+        record.breaches.push(breach.clone());
+        record.clawed_back = reserved;
+        record.reserved = Amount::new(0, reserved.decimals);
+        record.closed = true;
+        drop(records);
+        self.report_breaches(std::slice::from_ref(&breach));
+        Ok(Some(breach))
+    }
+
+    /// Stop tracking a task that was compensated instead of settled.
+    pub fn abandon(&self, auction_id: &str) {
+        if let Some(record) = self.records.lock().unwrap().get_mut(auction_id) {
+            record.closed = true;
+        }
+    }
+
+    pub fn record(&self, auction_id: &str) -> Option<SlaRecord> {
+        self.records.lock().unwrap().get(auction_id).cloned()
+    }
+
+    /// SLA attainment of `agent_id`.
+    pub fn attainment(&self, agent_id: &str) -> SlaAttainment {
+        self.report().remove(agent_id).unwrap_or_else(|| SlaAttainment { agent_id: agent_id.to_string(), ..Default::default() })
+    }
+
+    /// SLA attainment of every tracked agent.
+    pub fn report(&self) -> HashMap<String, SlaAttainment> {
+        let mut report: HashMap<String, SlaAttainment> = HashMap::new();
+        for record in self.records.lock().unwrap().values() {
+            let entry = report
+                .entry(record.agent_id.clone())
+                .or_insert_with(|| SlaAttainment { agent_id: record.agent_id.clone(), ..Default::default() });
+            entry.tracked += 1;
+            if record.closed {
+                entry.closed += 1;
+                if record.breaches.is_empty() {
+                    entry.met += 1;
+                }
+            }
+            for breach in &record.breaches {
+                *entry.breaches.entry(breach.metric).or_default() += 1;
+            }
+            entry.clawed_back += record.clawed_back.to_float();
+        }
+        report
+    }
+
+    fn update(&self, auction_id: &str, at: DateTime<Utc>, apply: impl FnOnce(&mut SlaRecord)) -> Vec<SlaBreach> {
+        let breaches = {
+            let mut records = self.records.lock().unwrap();
+            let Some(record) = records.get_mut(auction_id).filter(|r| !r.closed) else {
+                return Vec::new();
+            };
+            apply(record);
+            Self::breach(record, at)
+        };
+        self.report_breaches(&breaches);
+        breaches
+    }
+
+    /// Record terms missed by `now`.
+    fn breach(record: &mut SlaRecord, now: DateTime<Utc>) -> Vec<SlaBreach> {
+        let breaches: Vec<_> = record
+            .due(now)
+            .into_iter()
+            .map(|metric| SlaBreach {
+                auction_id: record.auction_id.clone(),
+                agent_id: record.agent_id.clone(),
+                metric,
+                detected_at: now,
+            })
+            .collect();
+        record.breaches.extend(breaches.iter().cloned());
+        breaches
+    }
+
+    fn report_breaches(&self, breaches: &[SlaBreach]) {
+        for breach in breaches {
+            tracing::warn!(auction_id = %breach.auction_id, agent_id = %breach.agent_id, metric = ?breach.metric, "SLA breached");
+            if let Some(reputation) = &self.reputation {
+                reputation.record(&breach.agent_id, &TaskOutcome::SlaBreached { metric: breach.metric });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_evaluation_reports_each_breach_once() {
+        let tracker = SlaTracker::new(Arc::new(BalanceLedger::default()))
+            .with_default_terms(SlaTerms::new(10).with_response_time(Duration::from_secs(60)));
+        assert!(tracker.attach("auction-1", "task-1", "agent-1", "client-1"));
+
+        let later = Utc::now() + chrono::Duration::minutes(5);
+        let breaches = tracker.evaluate(later);
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].metric, SlaMetric::ResponseTime);
+        assert!(tracker.evaluate(later).is_empty());
+
+        let split = tracker.settlement("auction-1", Amount::new(5_000, 2));
+        assert_eq!((split.pay.value, split.clawback.value), (4_500, 500));
+        assert_eq!(tracker.attainment("agent-1").rate(), 0.0);
+    }
+}
@@ -0,0 +1,65 @@
+//! Trust Network Reputation
+//!
+//! Feeds paid-task outcomes into the enterprise trust network, keeps
+//! blacklisted agents out of auctions and enrolls onboarded agents.
+
+use crate::onboarding::BootstrapTier;
+use crate::ports::{ReputationSink, TaskOutcome, TrustBootstrap, TrustLookup, TrustStanding};
+use agentkern_trust::{ReputationEvent, TrustNetwork, TrustTier};
+use std::sync::Mutex;
+
+/// Organization recorded for agents first seen through the marketplace.
+const MARKETPLACE_ORG: &str = "marketplace";
+
+impl ReputationSink for Mutex<TrustNetwork> {
+    fn is_eligible(&self, agent_id: &str) -> bool {
+        self.lock().unwrap().get_trust_tier(agent_id) != TrustTier::Blacklisted
+    }
+
+    fn record(&self, agent_id: &str, outcome: &TaskOutcome) {
+        let event = match outcome {
+            TaskOutcome::Completed => ReputationEvent::ActionSuccess { action: "paid_task".to_string(), impact: 10 },
+            TaskOutcome::Failed { .. } => ReputationEvent::ActionFailed { action: "paid_task".to_string(), impact: -20 },
+            TaskOutcome::Denied { policy_id } => ReputationEvent::PolicyViolation {
+                policy_id: policy_id.clone().unwrap_or_else(|| "gate".to_string()),
+                impact: -50,
+            },
+            TaskOutcome::SlaBreached { metric } => {
+                ReputationEvent::ActionFailed { action: format!("sla.{}", metric.as_str()), impact: -15 }
+            }
+        };
+        let mut network = self.lock().unwrap();
+        network.register_agent(agent_id, MARKETPLACE_ORG);
+        network.record_event(agent_id, event);
+    }
+}
+
+impl TrustBootstrap for Mutex<TrustNetwork> {
+    fn enroll(&self, agent_id: &str, org_id: &str, tier: BootstrapTier) -> Result<(), String> {
+        let target = match tier {
+            BootstrapTier::Untrusted => TrustTier::Untrusted,
+            BootstrapTier::Unknown => TrustTier::Unknown,
+            BootstrapTier::Trusted => TrustTier::Trusted,
+            BootstrapTier::Verified => TrustTier::Verified,
+        };
+        let mut network = self.lock().unwrap();
+        network.register_agent(agent_id, org_id);
+        // Onboarding sets the tier outright rather than waiting out a promotion hold
+        match network.assign_tier(agent_id, target).unwrap_or(TrustTier::Unknown) {
+            tier if tier == target => Ok(()),
+            tier => Err(format!("agent {agent_id} is {tier:?}, not {target:?}")),
+        }
+    }
+
+    fn remove(&self, agent_id: &str) {
+        self.lock().unwrap().remove_agent(agent_id);
+    }
+}
+
+impl TrustLookup for Mutex<TrustNetwork> {
+    fn standing(&self, agent_id: &str) -> Option<TrustStanding> {
+        let network = self.lock().unwrap();
+        let reputation = network.get_reputation(agent_id)?;
+        Some(TrustStanding { tier: format!("{:?}", reputation.tier).to_lowercase(), score: reputation.score })
+    }
+}
@@ -0,0 +1,387 @@
+//! Agent 360 View
+//!
+//! One query for everything known about an agent: wallet balance, trust
+//! standing, locks it holds, recent verifications, open escalations, drift
+//! alerts and cost. Each subsystem is optional; sections without a source
+//! are left out of the result.
+//!
+//! List sections are ordered newest first and paged the same way: a
+//! [`PageRequest`] limit and an opaque cursor taken from a previous page's
+//! `next_cursor`. To page through one list, query that field alone.
+//!
+//! Access is per field: a [`FieldAccess`] grants fields to roles, and
+//! fields the caller asked for but may not see are listed in
+//! [`AgentSnapshot::redacted`] instead of being returned.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let view = AgentView::new()
+//!     .with_ledger(ledger)
+//!     .with_audit(audit)
+//!     .with_approvals(workflow)
+//!     .with_drift(alerter);
+//! let access = FieldAccess::new().grant("support", &[ViewField::Verifications, ViewField::Escalations]);
+//!
+//! let query = AgentQuery::default().with_page(PageRequest::new(10));
+//! let snapshot = view.query("agent-x", &query, &access.permitted(&["support"])).await?;
+//! ```
+
+use crate::ports::{TrustLookup, TrustStanding};
+use agentkern_arbiter::cost::AgentCostSummary;
+use agentkern_arbiter::{
+    ApprovalRequest, ApprovalStatus, ApprovalWorkflow, AuditLedger, AuditRecord, BusinessLock, CostTracker, LockManager,
+};
+use agentkern_synapse::drift::{DriftAlert, DriftAlerter};
+use agentkern_treasury::balance::AgentBalance;
+use agentkern_treasury::BalanceLedger;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Default page size.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Largest page a caller may ask for.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// A section of the agent view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewField {
+    Wallet,
+    Trust,
+    Locks,
+    Verifications,
+    Escalations,
+    DriftAlerts,
+    Cost,
+}
+
+impl ViewField {
+    pub const ALL: [ViewField; 7] = [
+        Self::Wallet,
+        Self::Trust,
+        Self::Locks,
+        Self::Verifications,
+        Self::Escalations,
+        Self::DriftAlerts,
+        Self::Cost,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wallet => "wallet",
+            Self::Trust => "trust",
+            Self::Locks => "locks",
+            Self::Verifications => "verifications",
+            Self::Escalations => "escalations",
+            Self::DriftAlerts => "drift_alerts",
+            Self::Cost => "cost",
+        }
+    }
+}
+
+impl std::str::FromStr for ViewField {
+    type Err = ViewError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.as_str() == s)
+            .ok_or_else(|| ViewError::UnknownField(s.to_string()))
+    }
+}
+
+/// Agent view failures.
+#[derive(Debug, thiserror::Error)]
+pub enum ViewError {
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+}
+
+impl agentkern_errors::Coded for ViewError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        agentkern_errors::ErrorCode::InvalidArgument
+    }
+}
+
+/// Which fields each role may see.
+#[derive(Debug, Clone, Default)]
+pub struct FieldAccess {
+    grants: HashMap<String, HashSet<ViewField>>,
+}
+
+impl FieldAccess {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let `role` see `fields`.
+    pub fn grant(mut self, role: impl Into<String>, fields: &[ViewField]) -> Self {
+        self.grants.entry(role.into()).or_default().extend(fields.iter().copied());
+        self
+    }
+
+    /// Fields visible to a caller holding `roles`.
+    pub fn permitted(&self, roles: &[&str]) -> HashSet<ViewField> {
+        roles.iter().filter_map(|role| self.grants.get(*role)).flatten().copied().collect()
+    }
+}
+
+/// Page size and position for list sections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub limit: usize,
+    /// `next_cursor` of the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+impl PageRequest {
+    pub fn new(limit: usize) -> Self {
+        Self { limit: limit.clamp(1, MAX_PAGE_SIZE), cursor: None }
+    }
+
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    fn offset(&self) -> Result<usize, ViewError> {
+        match &self.cursor {
+            None => Ok(0),
+            Some(cursor) => cursor.parse().map_err(|_| ViewError::InvalidCursor(cursor.clone())),
+        }
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE)
+    }
+}
+
+/// One page of a list section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Items across all pages
+    pub total: usize,
+    /// Cursor for the next page, if there is one
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Slice `items` (already ordered) according to `request`.
+    fn of(items: Vec<T>, request: &PageRequest, offset: usize) -> Self {
+        let total = items.len();
+        let limit = request.limit.clamp(1, MAX_PAGE_SIZE);
+        let items: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+        let end = offset + items.len();
+        Self { items, total, next_cursor: (end < total).then(|| end.to_string()) }
+    }
+}
+
+/// What to include in an agent view.
+#[derive(Debug, Clone, Default)]
+pub struct AgentQuery {
+    /// Sections to include; every section when `None`
+    pub fields: Option<HashSet<ViewField>>,
+    pub page: PageRequest,
+}
+
+impl AgentQuery {
+    pub fn with_fields(mut self, fields: impl IntoIterator<Item = ViewField>) -> Self {
+        self.fields = Some(fields.into_iter().collect());
+        self
+    }
+
+    pub fn with_page(mut self, page: PageRequest) -> Self {
+        self.page = page;
+        self
+    }
+
+    fn wants(&self, field: ViewField) -> bool {
+        self.fields.as_ref().is_none_or(|fields| fields.contains(&field))
+    }
+}
+
+/// Everything the caller may see about an agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSnapshot {
+    pub agent_id: String,
+    pub generated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<AgentBalance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustStanding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locks: Option<Page<BusinessLock>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifications: Option<Page<AuditRecord>>,
+    /// Pending approval requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalations: Option<Page<ApprovalRequest>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drift_alerts: Option<Page<DriftAlert>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<AgentCostSummary>,
+    /// Requested fields withheld from this caller
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redacted: Vec<ViewField>,
+}
+
+/// Aggregates agent state across subsystems.
+#[derive(Default)]
+pub struct AgentView {
+    ledger: Option<Arc<BalanceLedger>>,
+    trust: Option<Arc<dyn TrustLookup>>,
+    locks: Option<Arc<LockManager>>,
+    audit: Option<Arc<AuditLedger>>,
+    approvals: Option<Arc<ApprovalWorkflow>>,
+    drift: Option<Arc<DriftAlerter>>,
+    costs: Option<Arc<CostTracker>>,
+}
+
+impl AgentView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ledger(mut self, ledger: Arc<BalanceLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    pub fn with_trust(mut self, trust: Arc<dyn TrustLookup>) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    pub fn with_locks(mut self, locks: Arc<LockManager>) -> Self {
+        self.locks = Some(locks);
+        self
+    }
+
+    /// Verification history.
+    pub fn with_audit(mut self, audit: Arc<AuditLedger>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn with_approvals(mut self, approvals: Arc<ApprovalWorkflow>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+
+    pub fn with_drift(mut self, drift: Arc<DriftAlerter>) -> Self {
+        self.drift = Some(drift);
+        self
+    }
+
+    pub fn with_costs(mut self, costs: Arc<CostTracker>) -> Self {
+        self.costs = Some(costs);
+        self
+    }
+
+    /// View of `agent_id` limited to the `permitted` fields.
+    pub async fn query(
+        &self,
+        agent_id: &str,
+        query: &AgentQuery,
+        permitted: &HashSet<ViewField>,
+    ) -> Result<AgentSnapshot, ViewError> {
+        let offset = query.page.offset()?;
+        let redacted = ViewField::ALL.into_iter().filter(|f| query.wants(*f) && !permitted.contains(f)).collect();
+        let include = |field: ViewField| query.wants(field) && permitted.contains(&field);
+
+        let wallet = self.ledger.as_ref().filter(|_| include(ViewField::Wallet)).map(|l| l.get_balance(agent_id));
+        let trust = self.trust.as_ref().filter(|_| include(ViewField::Trust)).and_then(|t| t.standing(agent_id));
+        let locks = match self.locks.as_ref().filter(|_| include(ViewField::Locks)) {
+            Some(locks) => {
+                let mut held: Vec<_> = locks
+                    .export_state()
+                    .await
+                    .into_iter()
+                    .filter(|lock| lock.locked_by == agent_id && !lock.is_expired())
+                    .collect();
+                held.sort_by_key(|r| Reverse(r.acquired_at));
+                Some(Page::of(held, &query.page, offset))
+            }
+            None => None,
+        };
+        let verifications = match self.audit.as_ref().filter(|_| include(ViewField::Verifications)) {
+            Some(audit) => {
+                // Ledger order breaks timestamp ties
+                let mut records = audit.query_by_agent(agent_id).await;
+                records.reverse();
+                records.sort_by_key(|r| Reverse(r.timestamp));
+                Some(Page::of(records, &query.page, offset))
+            }
+            None => None,
+        };
+        let escalations = self.approvals.as_ref().filter(|_| include(ViewField::Escalations)).map(|approvals| {
+            let mut open: Vec<_> = approvals
+                .requests_by_agent(agent_id)
+                .into_iter()
+                .filter(|r| r.status == ApprovalStatus::Pending && !r.is_expired())
+                .collect();
+            open.sort_by_key(|r| Reverse(r.created_at));
+            Page::of(open, &query.page, offset)
+        });
+        let drift_alerts = self.drift.as_ref().filter(|_| include(ViewField::DriftAlerts)).map(|drift| {
+            let mut alerts = drift.get_alerts_for_agent(agent_id);
+            alerts.reverse();
+            alerts.sort_by_key(|r| Reverse(r.timestamp));
+            Page::of(alerts, &query.page, offset)
+        });
+        let cost = self.costs.as_ref().filter(|_| include(ViewField::Cost)).map(|c| c.get_agent_summary(agent_id));
+
+        Ok(AgentSnapshot {
+            agent_id: agent_id.to_string(),
+            generated_at: Utc::now(),
+            wallet,
+            trust,
+            locks,
+            verifications,
+            escalations,
+            drift_alerts,
+            cost,
+            redacted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_are_cursor_linked() {
+        let request = PageRequest::new(2);
+        let first = Page::of((0..5).collect(), &request, 0);
+        assert_eq!((first.items, first.total), (vec![0, 1], 5));
+
+        let next = request.with_cursor(first.next_cursor.unwrap());
+        let second = Page::of((0..5).collect(), &next, next.offset().unwrap());
+        assert_eq!((second.items, second.next_cursor.as_deref()), (vec![2, 3], Some("4")));
+        assert_eq!(Page::of((0..5).collect::<Vec<_>>(), &next, 4).next_cursor, None);
+
+        assert!(PageRequest::new(2).with_cursor("abc").offset().is_err());
+    }
+
+    #[test]
+    fn test_access_is_union_of_roles() {
+        let access = FieldAccess::new()
+            .grant("verify", &[ViewField::Trust, ViewField::Verifications])
+            .grant("payments", &[ViewField::Wallet]);
+        assert_eq!(access.permitted(&["payments"]), HashSet::from([ViewField::Wallet]));
+        assert_eq!(access.permitted(&["verify", "payments", "other"]).len(), 3);
+    }
+}
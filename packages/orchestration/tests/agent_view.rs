@@ -0,0 +1,91 @@
+//! Agent 360 view: joined sections, field-level access and paging.
+
+use agentkern_arbiter::{
+    ApprovalWorkflow, AuditLedger, AuditOutcome, AuditRecord, CostCategory, CostTracker, EscalationLevel,
+    LockManager, LockType, TriggerResult, TriggerType,
+};
+use agentkern_orchestration::*;
+use agentkern_treasury::{Amount, BalanceLedger};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+async fn view() -> AgentView {
+    let ledger = Arc::new(BalanceLedger::default());
+    ledger.deposit("agent-x", Amount::from_float(42.0, 6)).unwrap();
+
+    let audit = Arc::new(AuditLedger::new());
+    for action in ["read", "write", "delete"] {
+        audit.record(AuditRecord::new("agent-x", action, "default", 10, AuditOutcome::Allowed)).await;
+    }
+    audit.record(AuditRecord::new("agent-y", "read", "default", 10, AuditOutcome::Allowed)).await;
+
+    let locks = Arc::new(LockManager::new());
+    locks.acquire("agent-x", "customer:1", 0, LockType::Write, None).await.unwrap();
+    locks.acquire("agent-y", "customer:2", 0, LockType::Write, None).await.unwrap();
+
+    let approvals = Arc::new(ApprovalWorkflow::new());
+    let trigger = TriggerResult {
+        triggered: true,
+        level: EscalationLevel::High,
+        trigger_type: TriggerType::Custom("review".to_string()),
+        agent_id: "agent-x".to_string(),
+        reason: "Large transfer".to_string(),
+        context: HashMap::new(),
+        timestamp: 0,
+    };
+    approvals.request_approval(&trigger, "transfer", serde_json::json!({ "amount": 10_000 }));
+
+    let costs = Arc::new(CostTracker::new());
+    costs.record(costs.event("agent-x", CostCategory::LlmInference).amount(1.5).build());
+
+    AgentView::new()
+        .with_ledger(ledger)
+        .with_audit(audit)
+        .with_locks(locks)
+        .with_approvals(approvals)
+        .with_costs(costs)
+}
+
+#[tokio::test]
+async fn test_joins_subsystems_and_redacts_by_role() {
+    let view = view().await;
+    let access = FieldAccess::new()
+        .grant("support", &[ViewField::Locks, ViewField::Verifications, ViewField::Escalations])
+        .grant("finance", &[ViewField::Wallet, ViewField::Cost]);
+
+    let support = view.query("agent-x", &AgentQuery::default(), &access.permitted(&["support"])).await.unwrap();
+    assert_eq!(support.verifications.unwrap().total, 3);
+    assert_eq!(support.locks.unwrap().items[0].resource, "customer:1");
+    assert_eq!(support.escalations.unwrap().items[0].action, "transfer");
+    assert!(support.wallet.is_none() && support.cost.is_none());
+    assert_eq!(support.redacted, [ViewField::Wallet, ViewField::Trust, ViewField::DriftAlerts, ViewField::Cost]);
+
+    let json = serde_json::to_value(&support.redacted).unwrap();
+    assert_eq!(json[0], "wallet");
+
+    let all = access.permitted(&["support", "finance"]);
+    let query = AgentQuery::default().with_fields([ViewField::Wallet, ViewField::Cost]);
+    let finance = view.query("agent-x", &query, &all).await.unwrap();
+    assert_eq!(finance.wallet.unwrap().balance.to_float(), 42.0);
+    assert_eq!(finance.cost.unwrap().total_usd, 1.5);
+    assert!(finance.verifications.is_none() && finance.redacted.is_empty());
+}
+
+#[tokio::test]
+async fn test_pages_newest_first() {
+    let view = view().await;
+    let permitted = ViewField::ALL.into_iter().collect();
+    let query = AgentQuery::default().with_fields([ViewField::Verifications]).with_page(PageRequest::new(2));
+
+    let first = view.query("agent-x", &query, &permitted).await.unwrap().verifications.unwrap();
+    let actions: Vec<_> = first.items.iter().map(|r| r.action.as_str()).collect();
+    assert_eq!(actions, ["delete", "write"]);
+
+    let query = query.clone().with_page(PageRequest::new(2).with_cursor(first.next_cursor.unwrap()));
+    let second = view.query("agent-x", &query, &permitted).await.unwrap().verifications.unwrap();
+    assert_eq!(second.items[0].action, "read");
+    assert!(second.next_cursor.is_none());
+
+    let bad = AgentQuery::default().with_page(PageRequest::new(2).with_cursor("not-a-cursor"));
+    assert!(matches!(view.query("agent-x", &bad, &permitted).await, Err(ViewError::InvalidCursor(_))));
+}
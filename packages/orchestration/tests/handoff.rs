@@ -0,0 +1,152 @@
+//! Task handoff between mesh cells: drain, rollback and failover takeover.
+
+use agentkern_arbiter::{LockManager, LockType};
+use agentkern_orchestration::*;
+use agentkern_synapse::{DataRegion, IntentPath, MeshCell};
+use agentkern_treasury::{Amount, BalanceLedger, Currency};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Resumed(Mutex<Vec<String>>);
+
+#[async_trait]
+impl TaskResumer for Resumed {
+    async fn resume(&self, task: &CellTask) -> Result<(), String> {
+        self.0.lock().unwrap().push(task.task_id.clone());
+        Ok(())
+    }
+}
+
+/// Delivers to cells in this process.
+#[derive(Default)]
+struct InProcess {
+    cells: Mutex<HashMap<String, Arc<CellHandoff>>>,
+    fail_commit: AtomicBool,
+}
+
+impl InProcess {
+    fn cell(&self, target: &MeshCell) -> Result<Arc<CellHandoff>, String> {
+        self.cells.lock().unwrap().get(&target.id).cloned().ok_or_else(|| "unreachable".to_string())
+    }
+}
+
+#[async_trait]
+impl CellTransport for InProcess {
+    async fn offer(&self, target: &MeshCell, package: &[u8]) -> Result<(), String> {
+        self.cell(target)?.accept(package).map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    async fn commit(&self, target: &MeshCell, task_id: &str) -> Result<(), String> {
+        if self.fail_commit.load(Ordering::SeqCst) {
+            return Err("connection reset".to_string());
+        }
+        self.cell(target)?.commit(task_id).await.map_err(|e| e.to_string())
+    }
+}
+
+fn mesh_cell(id: &str, region: DataRegion) -> MeshCell {
+    MeshCell { id: id.to_string(), region, endpoint: format!("https://{}.mesh", id), active: true, last_heartbeat: 0 }
+}
+
+fn task(task_id: &str, escrow: Option<EscrowHold>) -> CellTask {
+    CellTask {
+        task_id: task_id.to_string(),
+        agent_id: "agent-1".to_string(),
+        intent: IntentPath::new("agent-1", "Reconcile Q3 invoices", 5),
+        state: serde_json::json!({ "step": 3, "cursor": "inv-0420" }),
+        resources: vec!["ledger:q3".to_string()],
+        escrow,
+        residency: vec![],
+    }
+}
+
+struct Mesh {
+    locks: Arc<LockManager>,
+    ledger: Arc<BalanceLedger>,
+    transport: Arc<InProcess>,
+    source: CellHandoff,
+    target: Arc<CellHandoff>,
+    resumed: Arc<Resumed>,
+}
+
+fn mesh() -> Mesh {
+    let locks = Arc::new(LockManager::new());
+    let ledger = Arc::new(BalanceLedger::new(Currency::USD));
+    let transport = Arc::new(InProcess::default());
+    let resumed = Arc::new(Resumed::default());
+
+    let source = CellHandoff::new(mesh_cell("us-1", DataRegion::UsEast), locks.clone(), ledger.clone(), resumed.clone())
+        .with_transport(transport.clone());
+    let target = Arc::new(CellHandoff::new(
+        mesh_cell("us-2", DataRegion::UsWest),
+        locks.clone(),
+        ledger.clone(),
+        resumed.clone(),
+    ));
+    transport.cells.lock().unwrap().insert("us-2".to_string(), target.clone());
+    Mesh { locks, ledger, transport, source, target, resumed }
+}
+
+#[tokio::test]
+async fn test_drain_moves_task_and_locks() {
+    let mesh = mesh();
+    mesh.locks.acquire("agent-1", "ledger:q3", 5, LockType::Write, Some(60_000)).await.unwrap();
+    mesh.source.register(task("task-1", None));
+
+    // Commit fails after the offer: the task and its lock stay with the source
+    mesh.transport.fail_commit.store(true, Ordering::SeqCst);
+    let target = mesh.target.cell().clone();
+    let err = mesh.source.hand_off("task-1", &target).await.unwrap_err();
+    assert!(matches!(err, HandoffError::Transport { .. }));
+    assert!(mesh.source.task("task-1").is_some());
+    assert_eq!(mesh.locks.get_status("ledger:q3").await.unwrap().locked_by, "agent-1");
+
+    mesh.transport.fail_commit.store(false, Ordering::SeqCst);
+    let results = mesh.source.drain(&target).await;
+    let receipt = results.into_iter().next().unwrap().unwrap();
+    assert_eq!(receipt.locks_moved, 1);
+    assert!(mesh.source.task_ids().is_empty());
+
+    let moved = mesh.target.task("task-1").unwrap();
+    assert_eq!(moved.state["cursor"], "inv-0420");
+    let lock = mesh.locks.get_status("ledger:q3").await.unwrap();
+    assert_eq!((lock.locked_by.as_str(), lock.priority), ("agent-1", 5));
+    assert_eq!(*mesh.resumed.0.lock().unwrap(), vec!["task-1", "task-1"]);
+
+    // Packages addressed to another cell, or out of residency, are refused
+    mesh.target.register(task("task-2", None));
+    let bytes = mesh.target.checkpoint("task-2").await.unwrap();
+    assert!(matches!(mesh.target.accept(&bytes), Err(HandoffError::Verification { .. })));
+    let mut pinned = task("task-3", None);
+    pinned.residency = vec!["UsEast".to_string()];
+    mesh.source.register(pinned);
+    assert!(matches!(mesh.source.hand_off("task-3", &target).await, Err(HandoffError::Passport(_))));
+    assert!(mesh.source.task("task-3").is_some());
+}
+
+#[tokio::test]
+async fn test_take_over_checks_escrow_and_integrity() {
+    let mesh = mesh();
+    let amount = Amount::new(2_500, 2);
+    mesh.ledger.deposit("client-1", Amount::new(10_000, 2)).unwrap();
+    mesh.ledger.hold("client-1", amount).unwrap();
+    mesh.source.register(task("task-1", Some(EscrowHold { holder: "client-1".to_string(), amount })));
+    let checkpoint = mesh.source.checkpoint("task-1").await.unwrap();
+
+    // Tampered checkpoints are rejected
+    let tampered = String::from_utf8(checkpoint.clone()).unwrap().replace("inv-0420", "inv-9999");
+    assert!(matches!(mesh.target.take_over(tampered.as_bytes()).await, Err(HandoffError::Verification { .. })));
+
+    // The source cell dies; its escrow is still held, so the target takes over
+    drop(mesh.source);
+    assert_eq!(mesh.target.take_over(&checkpoint).await.unwrap(), "task-1");
+    assert!(mesh.target.task("task-1").is_some());
+
+    // Once the escrow is released the task cannot be resumed elsewhere
+    mesh.ledger.release("client-1", amount).unwrap();
+    let err = mesh.target.take_over(&checkpoint).await.unwrap_err();
+    assert!(matches!(err, HandoffError::EscrowMissing { .. }));
+}
@@ -0,0 +1,155 @@
+//! Agent onboarding: full provisioning, rollback on failure, deprovisioning.
+
+use agentkern_errors::{Coded, ErrorCode};
+use agentkern_nexus::AgentRegistry;
+use agentkern_orchestration::*;
+use agentkern_treasury::{Amount, BalanceLedger, BudgetManager, Currency};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const MANIFEST: &str = r#"
+identity:
+  id: summarizer-1
+  name: Summarizer
+  url: https://agents.example.com/summarizer
+  org: acme
+skills:
+  - id: summarize
+    name: Summarization
+    description: Summarize documents
+    tags: [nlp]
+protocols:
+  - name: a2a
+    version: "0.3"
+wallet:
+  currency: VMC
+  funding: 50.0
+budgets:
+  - period: Daily
+    max_amount: 20.0
+trust_tier: trusted
+policies:
+  - id: summarizer-limits
+    rules: []
+"#;
+
+#[derive(Default)]
+struct Trust(Mutex<HashMap<String, BootstrapTier>>);
+
+impl TrustBootstrap for Trust {
+    fn enroll(&self, agent_id: &str, _org_id: &str, tier: BootstrapTier) -> Result<(), String> {
+        self.0.lock().unwrap().insert(agent_id.to_string(), tier);
+        Ok(())
+    }
+
+    fn remove(&self, agent_id: &str) {
+        self.0.lock().unwrap().remove(agent_id);
+    }
+}
+
+#[derive(Default)]
+struct Policies {
+    reject: bool,
+    installed: Mutex<HashMap<String, usize>>,
+}
+
+#[async_trait]
+impl PolicyStore for Policies {
+    async fn install(&self, agent_id: &str, policies: &[serde_json::Value]) -> Result<(), String> {
+        if self.reject {
+            return Err("rules must not be empty".to_string());
+        }
+        self.installed.lock().unwrap().insert(agent_id.to_string(), policies.len());
+        Ok(())
+    }
+
+    async fn remove(&self, agent_id: &str) {
+        self.installed.lock().unwrap().remove(agent_id);
+    }
+}
+
+struct Setup {
+    registry: Arc<AgentRegistry>,
+    ledger: Arc<BalanceLedger>,
+    budgets: Arc<BudgetManager>,
+    trust: Arc<Trust>,
+    policies: Arc<Policies>,
+    onboarder: Onboarder,
+}
+
+fn setup(reject_policies: bool) -> Setup {
+    let registry = Arc::new(AgentRegistry::new());
+    let ledger = Arc::new(BalanceLedger::new(Currency::VMC));
+    let budgets = Arc::new(BudgetManager::new());
+    let trust = Arc::new(Trust::default());
+    let policies = Arc::new(Policies { reject: reject_policies, ..Default::default() });
+    let onboarder = Onboarder::new(registry.clone(), ledger.clone(), budgets.clone())
+        .with_trust(trust.clone())
+        .with_policies(policies.clone());
+    Setup { registry, ledger, budgets, trust, policies, onboarder }
+}
+
+#[tokio::test]
+async fn test_onboard_and_deprovision() {
+    let s = setup(false);
+    let manifest = AgentManifest::from_yaml(MANIFEST).unwrap();
+
+    let receipt = s.onboarder.onboard(&manifest).await.unwrap();
+    assert_eq!(receipt.funded, 50.0);
+    assert_eq!(receipt.trust_tier, Some(BootstrapTier::Trusted));
+
+    let card = s.registry.get("summarizer-1").await.unwrap();
+    assert!(card.has_skill("summarize"));
+    assert_eq!(s.ledger.get_balance("summarizer-1").balance.to_float(), 50.0);
+    assert_eq!(s.budgets.get_remaining("summarizer-1").unwrap().to_float(), 20.0);
+    assert_eq!(s.trust.0.lock().unwrap().get("summarizer-1"), Some(&BootstrapTier::Trusted));
+    assert_eq!(s.policies.installed.lock().unwrap().get("summarizer-1"), Some(&1));
+
+    // A second onboarding of the same agent changes nothing
+    let err = s.onboarder.onboard(&manifest).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::AlreadyExists);
+    assert_eq!(s.ledger.get_balance("summarizer-1").balance.to_float(), 50.0);
+
+    // Escrowed funds block deprovisioning
+    let held = Amount::from_float(5.0, 6);
+    s.ledger.hold("summarizer-1", held).unwrap();
+    let err = s.onboarder.deprovision("summarizer-1").await.unwrap_err();
+    assert_eq!(err.step(), Some(OnboardingStep::Wallet));
+    assert!(s.registry.get("summarizer-1").await.is_some());
+    s.ledger.release("summarizer-1", held).unwrap();
+
+    let receipt = s.onboarder.deprovision("summarizer-1").await.unwrap();
+    assert_eq!(receipt.closing_balance, 50.0);
+    assert!(s.registry.get("summarizer-1").await.is_none());
+    assert!(s.budgets.get_remaining("summarizer-1").is_none());
+    assert!(s.trust.0.lock().unwrap().is_empty());
+    assert!(s.policies.installed.lock().unwrap().is_empty());
+
+    let err = s.onboarder.deprovision("summarizer-1").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn test_failed_step_rolls_back() {
+    let s = setup(true);
+    let manifest = AgentManifest::from_yaml(MANIFEST).unwrap();
+
+    let err = s.onboarder.onboard(&manifest).await.unwrap_err();
+    assert_eq!(err.step(), Some(OnboardingStep::Policies));
+    assert_eq!(err.code(), ErrorCode::PolicyInvalid);
+
+    assert!(s.registry.get("summarizer-1").await.is_none());
+    assert_eq!(s.ledger.get_balance("summarizer-1").balance.to_float(), 0.0);
+    assert!(s.budgets.get_remaining("summarizer-1").is_none());
+    assert!(s.trust.0.lock().unwrap().is_empty());
+
+    // Steps nobody can provision are refused before anything changes
+    let bare = Onboarder::new(s.registry.clone(), s.ledger.clone(), s.budgets.clone());
+    let err = bare.onboard(&manifest).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Unsupported);
+    assert_eq!(s.registry.count().await, 0);
+
+    let err = AgentManifest::from_yaml("identity: { id: '', name: x, url: x, org: acme }").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidArgument);
+}
@@ -0,0 +1,181 @@
+//! Paid task flow: happy path and compensation at each failing stage.
+
+use agentkern_arbiter::{ComplianceLedger, Iso42001Outcome};
+use agentkern_errors::{Coded, ErrorCode};
+use agentkern_nexus::marketplace::{AuctionStatus, SettlementStatus};
+use agentkern_nexus::{Bid, Marketplace, TaskAuction};
+use agentkern_orchestration::*;
+use agentkern_treasury::{Amount, BalanceLedger};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct Policy {
+    blocked: Option<&'static str>,
+}
+
+#[async_trait]
+impl TaskVerifier for Policy {
+    async fn verify(&self, agent_id: &str, _action: &str, context: &HashMap<String, String>) -> Verdict {
+        assert!(context.contains_key("auction_id"));
+        match self.blocked {
+            Some(blocked) if blocked == agent_id => Verdict::deny("no-untrusted-executors", "Agent is under review"),
+            _ => Verdict::allow(10),
+        }
+    }
+}
+
+enum Behaviour {
+    Succeed,
+    Fail,
+    Hang,
+}
+
+struct Worker(Behaviour);
+
+#[async_trait]
+impl TaskExecutor for Worker {
+    async fn execute(&self, agent_id: &str, auction: &TaskAuction) -> Result<serde_json::Value, String> {
+        match self.0 {
+            Behaviour::Succeed => Ok(serde_json::json!({ "task": auction.task_id, "by": agent_id })),
+            Behaviour::Fail => Err("model provider unavailable".to_string()),
+            Behaviour::Hang => {
+                tokio::time::sleep(Duration::from_secs(3600 * 24)).await;
+                unreachable!()
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Reputation {
+    banned: Vec<&'static str>,
+    outcomes: Mutex<Vec<(String, TaskOutcome)>>,
+}
+
+impl ReputationSink for Reputation {
+    fn is_eligible(&self, agent_id: &str) -> bool {
+        !self.banned.contains(&agent_id)
+    }
+
+    fn record(&self, agent_id: &str, outcome: &TaskOutcome) {
+        self.outcomes.lock().unwrap().push((agent_id.to_string(), outcome.clone()));
+    }
+}
+
+struct Harness {
+    marketplace: Arc<Mutex<Marketplace>>,
+    ledger: Arc<BalanceLedger>,
+    reputation: Arc<Reputation>,
+    compliance: Arc<Mutex<ComplianceLedger>>,
+    flow: PaidTaskFlow,
+}
+
+fn harness(verifier: Policy, worker: Worker, reputation: Reputation) -> Harness {
+    let marketplace = Arc::new(Mutex::new(Marketplace::new()));
+    let ledger = Arc::new(BalanceLedger::default());
+    ledger.deposit("client-1", Amount::from_float(100.0, 6)).unwrap();
+    let reputation = Arc::new(reputation);
+    let compliance = Arc::new(Mutex::new(ComplianceLedger::new("org-1".to_string(), "1.0".to_string())));
+    let flow = PaidTaskFlow::new(marketplace.clone(), ledger.clone(), Arc::new(verifier), Arc::new(worker))
+        .with_reputation(reputation.clone())
+        .with_compliance(compliance.clone());
+    Harness { marketplace, ledger, reputation, compliance, flow }
+}
+
+fn auction() -> TaskAuction {
+    TaskAuction::new("task-1", "Summarise filings", 50.0, 1, 1, "client-1")
+}
+
+fn bids() -> Vec<Bid> {
+    vec![
+        Bid::new("task-1", "agent-cheap", 20.0, 600).with_confidence(90),
+        Bid::new("task-1", "agent-pricey", 45.0, 600).with_confidence(90),
+    ]
+}
+
+fn balance(ledger: &BalanceLedger, agent_id: &str) -> (f64, f64) {
+    let balance = ledger.get_balance(agent_id);
+    (balance.balance.to_float(), balance.pending.to_float())
+}
+
+#[tokio::test]
+async fn test_paid_task_settles_and_records_evidence() {
+    let h = harness(Policy { blocked: None }, Worker(Behaviour::Succeed), Reputation::default());
+
+    let receipt = h.flow.run(auction(), bids()).await.unwrap();
+    assert_eq!(receipt.executor, "agent-cheap");
+    assert_eq!(receipt.output["by"], "agent-cheap");
+
+    assert_eq!(balance(&h.ledger, "client-1"), (80.0, 0.0));
+    assert_eq!(balance(&h.ledger, "agent-cheap"), (20.0, 0.0));
+
+    let market = h.marketplace.lock().unwrap();
+    assert_eq!(market.get_auction(&receipt.auction_id).unwrap().status, AuctionStatus::Completed);
+    assert_eq!(market.get_settlement(&receipt.settlement_id).unwrap().status, SettlementStatus::Released);
+
+    assert_eq!(*h.reputation.outcomes.lock().unwrap(), vec![("agent-cheap".to_string(), TaskOutcome::Completed)]);
+    let compliance = h.compliance.lock().unwrap();
+    let evidence = compliance.events_by_agent("agent-cheap");
+    assert_eq!(evidence.len(), 2);
+    assert!(evidence.iter().all(|e| e.outcome == Iso42001Outcome::Allowed));
+}
+
+#[tokio::test]
+async fn test_denied_agent_is_refunded_and_penalised() {
+    let h = harness(Policy { blocked: Some("agent-cheap") }, Worker(Behaviour::Succeed), Reputation::default());
+
+    let err = h.flow.run(auction(), bids()).await.unwrap_err();
+    assert_eq!(err.stage(), FlowStage::Verification);
+    assert_eq!(err.code(), ErrorCode::PolicyDenied);
+    assert_eq!(balance(&h.ledger, "client-1"), (100.0, 0.0));
+    assert_eq!(
+        h.reputation.outcomes.lock().unwrap()[0].1,
+        TaskOutcome::Denied { policy_id: Some("no-untrusted-executors".to_string()) }
+    );
+    let compliance = h.compliance.lock().unwrap();
+    assert_eq!(compliance.events_by_agent("agent-cheap")[0].outcome, Iso42001Outcome::Denied);
+}
+
+#[tokio::test]
+async fn test_failed_execution_is_compensated() {
+    let h = harness(Policy { blocked: None }, Worker(Behaviour::Fail), Reputation::default());
+
+    let err = h.flow.run(auction(), bids()).await.unwrap_err();
+    assert!(matches!(err, FlowError::ExecutionFailed { .. }));
+    assert_eq!(balance(&h.ledger, "client-1"), (100.0, 0.0));
+    assert_eq!(balance(&h.ledger, "agent-cheap"), (0.0, 0.0));
+
+    let market = h.marketplace.lock().unwrap();
+    let auction = market.list_open_auctions();
+    assert!(auction.is_empty());
+    let refunds = h.compliance.lock().unwrap().events_by_agent("agent-cheap").iter().filter(|e| e.action == "marketplace.refund").count();
+    assert_eq!(refunds, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_execution_deadline_is_enforced() {
+    let h = harness(Policy { blocked: None }, Worker(Behaviour::Hang), Reputation::default());
+
+    let err = h.flow.run(auction(), bids()).await.unwrap_err();
+    assert!(matches!(err, FlowError::Timeout { .. }));
+    assert_eq!(balance(&h.ledger, "client-1"), (100.0, 0.0));
+}
+
+#[tokio::test]
+async fn test_ineligible_bidders_and_escrow_failures() {
+    let reputation = Reputation { banned: vec!["agent-cheap", "agent-pricey"], ..Default::default() };
+    let h = harness(Policy { blocked: None }, Worker(Behaviour::Succeed), reputation);
+    let err = h.flow.run(auction(), bids()).await.unwrap_err();
+    assert_eq!(err.stage(), FlowStage::Auction);
+
+    // Creator cannot cover the winning bid
+    let h = harness(Policy { blocked: None }, Worker(Behaviour::Succeed), Reputation::default());
+    let broke = TaskAuction::new("task-2", "Audit contracts", 500.0, 1, 1, "client-2");
+    let err = h.flow.run(broke, vec![Bid::new("task-2", "agent-cheap", 200.0, 600)]).await.unwrap_err();
+    assert!(matches!(err, FlowError::Escrow(_)));
+    assert_eq!(err.code(), ErrorCode::AccountNotFound);
+    assert!(h.reputation.outcomes.lock().unwrap().is_empty());
+    assert_eq!(h.compliance.lock().unwrap().events_by_agent("client-2").len(), 1);
+}
@@ -0,0 +1,92 @@
+//! Payment notifications: Nexus delivery, acknowledgment and webhook fallback.
+
+use agentkern_nexus::agent_card::ProtocolSupport;
+use agentkern_nexus::protocols::A2AAdapter;
+use agentkern_nexus::{AgentCard, Nexus, NexusMessage, Protocol};
+use agentkern_orchestration::*;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Transport(Mutex<Vec<(String, Protocol)>>);
+
+#[async_trait]
+impl MessageTransport for Transport {
+    async fn deliver(&self, agent: &AgentCard, protocol: Protocol, payload: &[u8]) -> Result<(), String> {
+        assert!(!payload.is_empty());
+        self.0.lock().unwrap().push((agent.id.clone(), protocol));
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Webhooks(Mutex<Vec<PaymentNotification>>);
+
+#[async_trait]
+impl WebhookFallback for Webhooks {
+    async fn notify(&self, notification: &PaymentNotification) -> Result<(), String> {
+        self.0.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+async fn nexus() -> Arc<Nexus> {
+    let nexus = Nexus::new();
+    nexus.register_adapter(A2AAdapter::new()).await;
+    let card = AgentCard::new("bob", "Bob", "https://bob.example.com").supports_protocol(ProtocolSupport {
+        name: "a2a".into(),
+        version: "0.3".into(),
+        endpoint: None,
+    });
+    nexus.register_agent(card).await.unwrap();
+    Arc::new(nexus)
+}
+
+fn payment(payee: &str) -> PaymentNotification {
+    PaymentNotification::new(PaymentEvent::Payment, "tx-1", "alice", payee, 12.5, "VMC")
+}
+
+#[tokio::test]
+async fn test_delivered_over_nexus_and_acknowledged() {
+    let transport = Arc::new(Transport::default());
+    let webhooks = Arc::new(Webhooks::default());
+    let notifier = PaymentNotifier::new(nexus().await, transport.clone()).with_fallback(webhooks.clone());
+
+    let Delivery::Nexus { message_id, protocol } = notifier.notify(payment("bob")).await else {
+        panic!("expected Nexus delivery");
+    };
+    assert_eq!(protocol, Protocol::GoogleA2A);
+    assert_eq!(transport.0.lock().unwrap().as_slice(), [("bob".to_string(), Protocol::GoogleA2A)]);
+    assert_eq!(notifier.unacknowledged().len(), 1);
+
+    // Only the payee's reply counts
+    let mut ack = NexusMessage::new("payment.ack", serde_json::Value::Null).from_agent("mallory");
+    ack.correlation_id = Some(message_id);
+    assert!(!notifier.acknowledge(&ack));
+    ack.source_agent = Some("bob".into());
+    assert!(notifier.acknowledge(&ack));
+    assert!(notifier.unacknowledged().is_empty());
+    assert!(webhooks.0.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_falls_back_to_webhooks() {
+    let transport = Arc::new(Transport::default());
+    let webhooks = Arc::new(Webhooks::default());
+    let notifier = PaymentNotifier::new(nexus().await, transport)
+        .with_fallback(webhooks.clone())
+        .with_ack_timeout(Duration::ZERO);
+
+    // Unknown recipient: straight to the webhook
+    assert!(matches!(notifier.notify(payment("carol")).await, Delivery::Webhook { .. }));
+
+    // Delivered but never acknowledged
+    assert!(matches!(notifier.notify(payment("bob")).await, Delivery::Nexus { .. }));
+    let swept = notifier.sweep().await;
+    assert!(matches!(swept.as_slice(), [Delivery::Webhook { .. }]));
+    assert!(notifier.unacknowledged().is_empty());
+
+    let payees: Vec<_> = webhooks.0.lock().unwrap().iter().map(|n| n.payee.clone()).collect();
+    assert_eq!(payees, ["carol", "bob"]);
+}
@@ -0,0 +1,110 @@
+//! SLA penalties on paid tasks: late delivery clawback and quality acknowledgment.
+
+use agentkern_nexus::{Bid, Marketplace, TaskAuction};
+use agentkern_orchestration::*;
+use agentkern_treasury::{Amount, BalanceLedger};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct AllowAll;
+
+#[async_trait]
+impl TaskVerifier for AllowAll {
+    async fn verify(&self, _agent_id: &str, _action: &str, _context: &HashMap<String, String>) -> Verdict {
+        Verdict::allow(0)
+    }
+}
+
+/// Takes `delay` to deliver.
+struct Worker {
+    delay: Duration,
+}
+
+#[async_trait]
+impl TaskExecutor for Worker {
+    async fn execute(&self, agent_id: &str, _auction: &TaskAuction) -> Result<serde_json::Value, String> {
+        tokio::time::sleep(self.delay).await;
+        Ok(serde_json::json!({ "by": agent_id }))
+    }
+}
+
+#[derive(Default)]
+struct Reputation(Mutex<Vec<TaskOutcome>>);
+
+impl ReputationSink for Reputation {
+    fn record(&self, _agent_id: &str, outcome: &TaskOutcome) {
+        self.0.lock().unwrap().push(outcome.clone());
+    }
+}
+
+fn setup(terms: SlaTerms, delay: Duration) -> (Arc<BalanceLedger>, Arc<SlaTracker>, Arc<Reputation>, PaidTaskFlow) {
+    let ledger = Arc::new(BalanceLedger::default());
+    ledger.deposit("client-1", Amount::from_float(100.0, 6)).unwrap();
+    let reputation = Arc::new(Reputation::default());
+    let sla = Arc::new(SlaTracker::new(ledger.clone()).with_reputation(reputation.clone()));
+    sla.set_terms("task-1", terms);
+    let flow = PaidTaskFlow::new(
+        Arc::new(Mutex::new(Marketplace::new())),
+        ledger.clone(),
+        Arc::new(AllowAll),
+        Arc::new(Worker { delay }),
+    )
+    .with_sla(sla.clone());
+    (ledger, sla, reputation, flow)
+}
+
+fn auction() -> TaskAuction {
+    TaskAuction::new("task-1", "Summarise filings", 50.0, 1, 1, "client-1")
+}
+
+fn bids() -> Vec<Bid> {
+    vec![Bid::new("task-1", "agent-1", 20.0, 600)]
+}
+
+fn balance(ledger: &BalanceLedger, agent_id: &str) -> (f64, f64) {
+    let balance = ledger.get_balance(agent_id);
+    (balance.balance.to_float(), balance.pending.to_float())
+}
+
+#[tokio::test]
+async fn test_late_completion_claws_back_escrow() {
+    let terms = SlaTerms::new(25).with_completion_time(Duration::from_millis(5));
+    let (ledger, sla, reputation, flow) = setup(terms, Duration::from_millis(50));
+
+    let receipt = flow.run(auction(), bids()).await.unwrap();
+    assert_eq!((receipt.clawback, receipt.reserved), (5.0, 0.0));
+    assert_eq!(balance(&ledger, "agent-1"), (15.0, 0.0));
+    assert_eq!(balance(&ledger, "client-1"), (85.0, 0.0));
+
+    let outcomes = reputation.0.lock().unwrap().clone();
+    assert_eq!(outcomes, vec![TaskOutcome::SlaBreached { metric: SlaMetric::CompletionTime }]);
+
+    let attainment = sla.attainment("agent-1");
+    assert_eq!((attainment.tracked, attainment.met, attainment.clawed_back), (1, 0, 5.0));
+    assert_eq!(attainment.breaches[&SlaMetric::CompletionTime], 1);
+}
+
+#[tokio::test]
+async fn test_quality_ack_releases_or_returns_reserve() {
+    let terms = SlaTerms::new(10).with_completion_time(Duration::from_secs(60)).with_quality_ack();
+    let (ledger, sla, reputation, flow) = setup(terms, Duration::ZERO);
+
+    let accepted = flow.run(auction(), bids()).await.unwrap();
+    assert_eq!((accepted.clawback, accepted.reserved), (0.0, 2.0));
+    assert_eq!(balance(&ledger, "agent-1"), (18.0, 0.0));
+    assert_eq!(balance(&ledger, "client-1"), (82.0, 2.0));
+    assert!(sla.acknowledge_quality(&accepted.auction_id, true).unwrap().is_none());
+    assert_eq!(balance(&ledger, "agent-1"), (20.0, 0.0));
+    assert!(sla.acknowledge_quality(&accepted.auction_id, true).is_err());
+
+    let rejected = flow.run(auction(), bids()).await.unwrap();
+    let breach = sla.acknowledge_quality(&rejected.auction_id, false).unwrap().unwrap();
+    assert_eq!(breach.metric, SlaMetric::Quality);
+    assert_eq!(balance(&ledger, "agent-1"), (38.0, 0.0));
+    assert_eq!(balance(&ledger, "client-1"), (62.0, 0.0));
+
+    assert_eq!(*reputation.0.lock().unwrap(), vec![TaskOutcome::SlaBreached { metric: SlaMetric::Quality }]);
+    assert_eq!(sla.attainment("agent-1").rate(), 0.5);
+}
@@ -0,0 +1,55 @@
+"""AgentKern: Python bindings for the AgentKern Rust core."""
+
+from ._native import (
+    AccountNotFound,
+    AgentKernError,
+    Attestation,
+    Balance,
+    InsufficientFunds,
+    InvalidAmount,
+    LicenseRequired,
+    PaymentReceipt,
+    RuntimeShutDown,
+    TeeUnavailable,
+    TransferFailed,
+    TrustInfo,
+    VerifyResult,
+    deposit,
+    get_attestation,
+    get_balance,
+    get_trust_tier,
+    init,
+    record_trust_event,
+    shutdown,
+    transfer,
+    verify_action,
+    verify_action_sync,
+    verify_batch,
+)
+
+__all__ = [
+    "AccountNotFound",
+    "AgentKernError",
+    "Attestation",
+    "Balance",
+    "InsufficientFunds",
+    "InvalidAmount",
+    "LicenseRequired",
+    "PaymentReceipt",
+    "RuntimeShutDown",
+    "TeeUnavailable",
+    "TransferFailed",
+    "TrustInfo",
+    "VerifyResult",
+    "deposit",
+    "get_attestation",
+    "get_balance",
+    "get_trust_tier",
+    "init",
+    "record_trust_event",
+    "shutdown",
+    "transfer",
+    "verify_action",
+    "verify_action_sync",
+    "verify_batch",
+]
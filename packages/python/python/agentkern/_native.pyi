@@ -0,0 +1,66 @@
+from typing import Any, Awaitable, Iterable, Mapping, Optional, Union
+
+Context = Union[Mapping[str, Any], str, None]
+
+class AgentKernError(Exception): ...
+class RuntimeShutDown(AgentKernError): ...
+class TeeUnavailable(AgentKernError): ...
+class InvalidAmount(AgentKernError): ...
+class InsufficientFunds(AgentKernError): ...
+class AccountNotFound(AgentKernError): ...
+class TransferFailed(AgentKernError): ...
+class LicenseRequired(AgentKernError): ...
+
+class VerifyResult:
+    request_id: Optional[str]
+    allowed: bool
+    evaluated_policies: list[str]
+    blocking_policies: list[str]
+    risk_score: int
+    reasoning: Optional[str]
+    latency_ms: int
+
+class Attestation:
+    platform: str
+    quote: str
+    measurement: str
+    nonce: str
+    timestamp: int
+
+class Balance:
+    agent_id: str
+    currency: str
+    balance: float
+    pending: float
+    available: float
+
+class PaymentReceipt:
+    transaction_id: str
+    from_agent: str
+    to_agent: str
+    amount: float
+    timestamp: int
+
+class TrustInfo:
+    agent_id: str
+    tier: str
+    score: int
+    allows_high_risk: bool
+
+def init(log_level: Optional[str] = None) -> None: ...
+def shutdown() -> None: ...
+def verify_action(agent_id: str, action: str, context: Context = None) -> Awaitable[VerifyResult]: ...
+def verify_action_sync(agent_id: str, action: str, context: Context = None) -> VerifyResult: ...
+def verify_batch(requests: Union[Iterable[Mapping[str, Any]], str]) -> Awaitable[list[VerifyResult]]: ...
+def get_attestation(nonce: str) -> Attestation: ...
+def get_balance(agent_id: str) -> Balance: ...
+def deposit(agent_id: str, amount: float) -> Balance: ...
+def transfer(
+    from_agent: str,
+    to_agent: str,
+    amount: float,
+    reference: Optional[str] = None,
+    idempotency_key: Optional[str] = None,
+) -> Awaitable[PaymentReceipt]: ...
+def get_trust_tier(agent_id: str) -> TrustInfo: ...
+def record_trust_event(agent_id: str, kind: str, impact: int, subject: Optional[str] = None) -> TrustInfo: ...
@@ -0,0 +1,618 @@
+//! AgentKern Python Binding
+//!
+//! PyO3 bindings exposing the Rust core to Python, mirroring the Node.js
+//! binding (`@agentkern/native`): verification, TEE attestation, treasury
+//! payments and trust queries.
+//!
+//! Coroutines (`verify_action`, `verify_batch`, `transfer`) run on a shared
+//! Tokio runtime and can be awaited from any asyncio event loop; blocking
+//! variants release the GIL while they wait.
+//!
+//! # Example
+//!
+//! ```python
+//! import agentkern
+//!
+//! agentkern.init(log_level="info")
+//! result = await agentkern.verify_action("agent-123", "transfer_funds", {"amount": 1000})
+//! if not result.allowed:
+//!     print("Blocked by", result.blocking_policies)
+//! ```
+
+use agentkern_gate::engine::{GateEngine, VerificationRequestBuilder};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as log_fmt, reload, EnvFilter, Registry};
+
+create_exception!(agentkern, AgentKernError, PyException, "Base class for AgentKern errors.");
+create_exception!(agentkern, RuntimeShutDown, AgentKernError, "The runtime was shut down; call init() to restart it.");
+create_exception!(agentkern, TeeUnavailable, AgentKernError, "No TEE platform is available for attestation.");
+create_exception!(agentkern, InvalidAmount, AgentKernError, "Amount is not a positive, finite number.");
+create_exception!(agentkern, InsufficientFunds, AgentKernError, "Sender's available balance is too low.");
+create_exception!(agentkern, AccountNotFound, AgentKernError, "Sender has no account.");
+create_exception!(agentkern, TransferFailed, AgentKernError, "Transfer failed for another reason.");
+create_exception!(agentkern, LicenseRequired, AgentKernError, "Feature requires the enterprise build and license.");
+
+// ============================================================================
+// Runtime lifecycle
+// ============================================================================
+
+enum RuntimeState {
+    /// Not initialized; the first verification starts the runtime
+    Idle,
+    Running(Arc<GateEngine>),
+    /// After [`shutdown`], until the next [`init`]
+    Stopped,
+}
+
+static RUNTIME: Mutex<RuntimeState> = Mutex::new(RuntimeState::Idle);
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Initialize the runtime. Safe to call more than once: later calls only
+/// change the log level. Restarts the runtime after `shutdown()`.
+///
+/// `log_level` defaults to `RUST_LOG`, then `info`.
+#[pyfunction]
+#[pyo3(signature = (log_level=None))]
+fn init(log_level: Option<String>) -> PyResult<()> {
+    let log_level = log_level
+        .or_else(|| std::env::var("RUST_LOG").ok())
+        .unwrap_or_else(|| "info".to_string());
+    let filter = EnvFilter::try_new(&log_level)
+        .map_err(|e| PyValueError::new_err(format!("Invalid log level '{}': {}", log_level, e)))?;
+    init_logging(filter)?;
+
+    let mut runtime = lock_runtime();
+    if !matches!(*runtime, RuntimeState::Running(_)) {
+        *runtime = RuntimeState::Running(Arc::new(GateEngine::new()));
+        tracing::info!("AgentKern Python runtime started");
+    }
+    Ok(())
+}
+
+/// Stop the runtime and release the engine. Verifications raise
+/// `RuntimeShutDown` until `init()` is called again.
+#[pyfunction]
+fn shutdown() {
+    let mut runtime = lock_runtime();
+    if matches!(*runtime, RuntimeState::Running(_)) {
+        tracing::info!("AgentKern Python runtime stopped");
+    }
+    *runtime = RuntimeState::Stopped;
+}
+
+/// The shared engine, starting the runtime on first use.
+fn engine() -> PyResult<Arc<GateEngine>> {
+    let mut runtime = lock_runtime();
+    match &*runtime {
+        RuntimeState::Running(engine) => Ok(engine.clone()),
+        RuntimeState::Idle => {
+            let engine = Arc::new(GateEngine::new());
+            *runtime = RuntimeState::Running(engine.clone());
+            Ok(engine)
+        }
+        RuntimeState::Stopped => Err(RuntimeShutDown::new_err("AgentKern runtime is shut down; call init() to restart it")),
+    }
+}
+
+fn lock_runtime() -> std::sync::MutexGuard<'static, RuntimeState> {
+    RUNTIME.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Longest interpreter exit waits for coroutines still in flight.
+const EXIT_DRAIN: Duration = Duration::from_secs(2);
+
+/// Registered with `atexit`: wait, with the GIL released, until coroutines
+/// still on the Tokio runtime have handed back their results. A worker that
+/// still holds Python references while the interpreter finalizes crashes
+/// the process.
+#[pyfunction]
+fn drain_on_exit(py: Python<'_>) {
+    py.allow_threads(|| {
+        let runtime = pyo3_async_runtimes::tokio::get_runtime();
+        let deadline = Instant::now() + EXIT_DRAIN;
+        while runtime.metrics().num_alive_tasks() > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    })
+}
+
+/// Install the global subscriber once; afterwards only swap its filter.
+/// If the host already installed a subscriber, logs go to that one.
+fn init_logging(filter: EnvFilter) -> PyResult<()> {
+    if let Some(handle) = LOG_FILTER.get() {
+        return handle
+            .reload(filter)
+            .map_err(|e| AgentKernError::new_err(format!("Failed to set log level: {}", e)));
+    }
+    let (layer, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry().with(layer).with(log_fmt::layer()).try_init().is_ok() {
+        let _ = LOG_FILTER.set(handle);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Verification
+// ============================================================================
+
+/// Verification result.
+#[pyclass(get_all, frozen, module = "agentkern")]
+#[derive(Debug, Clone)]
+pub struct VerifyResult {
+    /// Echoed from the request (batches)
+    pub request_id: Option<String>,
+    pub allowed: bool,
+    pub evaluated_policies: Vec<String>,
+    pub blocking_policies: Vec<String>,
+    pub risk_score: u32,
+    pub reasoning: Option<String>,
+    pub latency_ms: u32,
+}
+
+#[pymethods]
+impl VerifyResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "VerifyResult(allowed={}, risk_score={}, blocking_policies={:?})",
+            if self.allowed { "True" } else { "False" },
+            self.risk_score,
+            self.blocking_policies
+        )
+    }
+}
+
+/// Verify an agent action (coroutine).
+///
+/// `context` is any JSON-serializable mapping, or a JSON string.
+#[pyfunction]
+#[pyo3(signature = (agent_id, action, context=None))]
+fn verify_action<'py>(
+    py: Python<'py>,
+    agent_id: String,
+    action: String,
+    context: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let context = parse_context(py, context)?;
+    let engine = engine()?;
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let start = Instant::now();
+        let verification = engine.verify(gate_request(&agent_id, &action, context)).await;
+        Ok(to_result(verification, None, start))
+    })
+}
+
+/// Blocking [`verify_action`] for synchronous callers. Releases the GIL.
+#[pyfunction]
+#[pyo3(signature = (agent_id, action, context=None))]
+fn verify_action_sync(
+    py: Python<'_>,
+    agent_id: String,
+    action: String,
+    context: Option<&Bound<'_, PyAny>>,
+) -> PyResult<VerifyResult> {
+    let context = parse_context(py, context)?;
+    let engine = engine()?;
+    Ok(py.allow_threads(|| {
+        let start = Instant::now();
+        let verification = pyo3_async_runtimes::tokio::get_runtime()
+            .block_on(engine.verify(gate_request(&agent_id, &action, context)));
+        to_result(verification, None, start)
+    }))
+}
+
+/// Verify many actions in one call (coroutine). Each request is a mapping
+/// with `agent_id`, `action` and optional `context` and `request_id`;
+/// results are in request order.
+#[pyfunction]
+fn verify_batch<'py>(py: Python<'py>, requests: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let requests: Vec<BatchRequest> = from_python(py, requests, "batch")?;
+    let engine = engine()?;
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let start = Instant::now();
+        let ids: Vec<Option<String>> = requests.iter().map(|r| r.request_id.clone()).collect();
+        let gate_requests = requests
+            .into_iter()
+            .map(|r| gate_request(&r.agent_id, &r.action, r.context))
+            .collect();
+        let results = engine.verify_batch(gate_requests).await;
+        Ok(results
+            .into_iter()
+            .zip(ids)
+            .map(|(verification, id)| to_result(verification, id, start))
+            .collect::<Vec<_>>())
+    })
+}
+
+/// Request shape inside [`verify_batch`].
+#[derive(Deserialize)]
+struct BatchRequest {
+    agent_id: String,
+    action: String,
+    #[serde(default)]
+    context: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    request_id: Option<String>,
+}
+
+/// Convert a Python value to a Rust type through `json.dumps`.
+fn from_python<T: for<'de> Deserialize<'de>>(py: Python<'_>, value: &Bound<'_, PyAny>, what: &str) -> PyResult<T> {
+    let json: String = if let Ok(text) = value.extract::<String>() {
+        text
+    } else {
+        py.import("json")?.call_method1("dumps", (value,))?.extract()?
+    };
+    serde_json::from_str(&json).map_err(|e| PyValueError::new_err(format!("Invalid {}: {}", what, e)))
+}
+
+fn parse_context(py: Python<'_>, context: Option<&Bound<'_, PyAny>>) -> PyResult<HashMap<String, serde_json::Value>> {
+    match context {
+        Some(context) if !context.is_none() => from_python(py, context, "context"),
+        _ => Ok(HashMap::new()),
+    }
+}
+
+fn gate_request(
+    agent_id: &str,
+    action: &str,
+    context: HashMap<String, serde_json::Value>,
+) -> agentkern_gate::VerificationRequest {
+    context
+        .into_iter()
+        .fold(VerificationRequestBuilder::new(agent_id, action), |builder, (key, value)| {
+            builder.context(key, value)
+        })
+        .build()
+}
+
+fn to_result(verification: agentkern_gate::VerificationResult, request_id: Option<String>, start: Instant) -> VerifyResult {
+    VerifyResult {
+        request_id,
+        allowed: verification.allowed,
+        evaluated_policies: verification.evaluated_policies,
+        blocking_policies: verification.blocking_policies,
+        risk_score: verification.final_risk_score as u32,
+        reasoning: Some(verification.reasoning).filter(|r| !r.is_empty()),
+        latency_ms: start.elapsed().as_millis() as u32,
+    }
+}
+
+// ============================================================================
+// Attestation
+// ============================================================================
+
+/// TEE attestation.
+#[pyclass(get_all, frozen, module = "agentkern")]
+#[derive(Debug, Clone)]
+pub struct Attestation {
+    pub platform: String,
+    /// Base64
+    pub quote: String,
+    /// Hex
+    pub measurement: String,
+    pub nonce: String,
+    /// Unix ms
+    pub timestamp: i64,
+}
+
+/// Get a TEE attestation bound to `nonce`.
+#[pyfunction]
+fn get_attestation(nonce: String) -> PyResult<Attestation> {
+    use agentkern_gate::tee::{TeePlatform, TeeRuntime};
+    use base64::Engine;
+
+    let runtime = TeeRuntime::detect().map_err(|e| TeeUnavailable::new_err(e.to_string()))?;
+    let attestation = runtime
+        .get_attestation(nonce.as_bytes())
+        .map_err(|e| TeeUnavailable::new_err(e.to_string()))?;
+    let platform = match runtime.platform() {
+        TeePlatform::IntelTdx => "intel_tdx",
+        TeePlatform::AmdSevSnp => "amd_sev_snp",
+        TeePlatform::IntelSgx => "intel_sgx",
+        TeePlatform::ArmCca => "arm_cca",
+        TeePlatform::Simulated => "simulated",
+    };
+
+    Ok(Attestation {
+        platform: platform.to_string(),
+        quote: base64::engine::general_purpose::STANDARD.encode(&attestation.quote),
+        measurement: hex::encode(&attestation.measurement),
+        nonce,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+// ============================================================================
+// Treasury
+// ============================================================================
+
+/// Process-wide treasury: one ledger shared by every call.
+struct Treasury {
+    ledger: Arc<agentkern_treasury::BalanceLedger>,
+    transfers: agentkern_treasury::TransferEngine,
+}
+
+static TREASURY: OnceLock<Treasury> = OnceLock::new();
+
+fn treasury() -> &'static Treasury {
+    TREASURY.get_or_init(|| {
+        let ledger = Arc::new(agentkern_treasury::BalanceLedger::default());
+        Treasury {
+            transfers: agentkern_treasury::TransferEngine::new(ledger.clone()),
+            ledger,
+        }
+    })
+}
+
+/// Agent balance, in currency units.
+#[pyclass(get_all, frozen, module = "agentkern")]
+#[derive(Debug, Clone)]
+pub struct Balance {
+    pub agent_id: String,
+    pub currency: String,
+    pub balance: f64,
+    pub pending: f64,
+    pub available: f64,
+}
+
+/// Completed payment.
+#[pyclass(get_all, frozen, module = "agentkern")]
+#[derive(Debug, Clone)]
+pub struct PaymentReceipt {
+    pub transaction_id: String,
+    pub from_agent: String,
+    pub to_agent: String,
+    pub amount: f64,
+    /// Unix ms
+    pub timestamp: i64,
+}
+
+/// Get an agent's balance (zero for unknown agents).
+#[pyfunction]
+fn get_balance(agent_id: &str) -> Balance {
+    to_balance(treasury().ledger.get_balance(agent_id))
+}
+
+/// Credit an agent's account.
+#[pyfunction]
+fn deposit(agent_id: &str, amount: f64) -> PyResult<Balance> {
+    let ledger = &treasury().ledger;
+    let amount = to_amount(amount, ledger.get_balance(agent_id).currency)?;
+    ledger.deposit(agent_id, amount).map(to_balance).map_err(ledger_error)
+}
+
+/// Pay another agent (coroutine). Raises `InsufficientFunds`,
+/// `AccountNotFound`, `InvalidAmount` or `TransferFailed`.
+#[pyfunction]
+#[pyo3(signature = (from_agent, to_agent, amount, reference=None, idempotency_key=None))]
+fn transfer(
+    py: Python<'_>,
+    from_agent: String,
+    to_agent: String,
+    amount: f64,
+    reference: Option<String>,
+    idempotency_key: Option<String>,
+) -> PyResult<Bound<'_, PyAny>> {
+    use agentkern_treasury::{TransferRequest, TransferStatus};
+
+    if from_agent == to_agent {
+        return Err(PyValueError::new_err("cannot pay yourself"));
+    }
+    let treasury = treasury();
+    let amount = to_amount(amount, treasury.ledger.get_balance(&from_agent).currency)?;
+
+    let mut request = TransferRequest::new(from_agent.as_str(), to_agent.as_str(), amount);
+    if let Some(reference) = reference {
+        request = request.with_reference(reference);
+    }
+    if let Some(key) = idempotency_key {
+        request = request.with_idempotency_key(key);
+    }
+
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let result = treasury.transfers.transfer(request).await;
+        match result.status {
+            TransferStatus::Completed => Ok(PaymentReceipt {
+                transaction_id: result.transaction_id.to_string(),
+                from_agent,
+                to_agent,
+                amount: amount.to_float(),
+                timestamp: result.timestamp.timestamp_millis(),
+            }),
+            _ => Err(transfer_error(result.error.as_deref().unwrap_or("transfer not completed"))),
+        }
+    })
+}
+
+fn to_amount(value: f64, currency: agentkern_treasury::Currency) -> PyResult<agentkern_treasury::Amount> {
+    if !value.is_finite() || value <= 0.0 {
+        return Err(InvalidAmount::new_err(format!("{} is not a positive amount", value)));
+    }
+    Ok(agentkern_treasury::Amount::from_float(value, currency.decimals()))
+}
+
+fn to_balance(balance: agentkern_treasury::AgentBalance) -> Balance {
+    Balance {
+        currency: format!("{:?}", balance.currency),
+        balance: balance.balance.to_float(),
+        pending: balance.pending.to_float(),
+        available: balance.available().to_float(),
+        agent_id: balance.agent_id,
+    }
+}
+
+fn ledger_error(error: agentkern_treasury::balance::LedgerError) -> PyErr {
+    use agentkern_treasury::balance::LedgerError;
+
+    match error {
+        LedgerError::InsufficientFunds => InsufficientFunds::new_err(error.to_string()),
+        LedgerError::AccountNotFound => AccountNotFound::new_err(error.to_string()),
+        LedgerError::InvalidAmount | LedgerError::CurrencyMismatch => InvalidAmount::new_err(error.to_string()),
+        LedgerError::FundsHeld => TransferFailed::new_err(error.to_string()),
+    }
+}
+
+/// The transfer engine reports failures as the ledger error's message.
+fn transfer_error(message: &str) -> PyErr {
+    use agentkern_treasury::balance::LedgerError;
+
+    [LedgerError::InsufficientFunds, LedgerError::AccountNotFound, LedgerError::InvalidAmount]
+        .into_iter()
+        .find(|e| e.to_string() == message)
+        .map(ledger_error)
+        .unwrap_or_else(|| TransferFailed::new_err(message.to_string()))
+}
+
+// ============================================================================
+// Trust
+// ============================================================================
+
+/// Agent trust standing.
+#[pyclass(get_all, frozen, module = "agentkern")]
+#[derive(Debug, Clone)]
+pub struct TrustInfo {
+    pub agent_id: String,
+    /// blacklisted | untrusted | unknown | trusted | verified | elite
+    pub tier: String,
+    /// 0-1000 (500 for new agents)
+    pub score: u32,
+    pub allows_high_risk: bool,
+}
+
+/// Look up an agent's trust tier. Raises `LicenseRequired` without the
+/// enterprise build and license.
+#[pyfunction]
+fn get_trust_tier(agent_id: String) -> PyResult<TrustInfo> {
+    trust::tier(agent_id)
+}
+
+/// Record a reputation event (`kind`: success | failure | violation |
+/// verification); returns the updated standing.
+#[pyfunction]
+#[pyo3(signature = (agent_id, kind, impact, subject=None))]
+fn record_trust_event(agent_id: String, kind: &str, impact: i32, subject: Option<String>) -> PyResult<TrustInfo> {
+    trust::record(agent_id, kind, impact, subject)
+}
+
+#[cfg(feature = "enterprise")]
+mod trust {
+    use super::{LicenseRequired, TrustInfo};
+    use agentkern_trust::{ReputationEvent, TrustNetwork};
+    use pyo3::exceptions::PyValueError;
+    use pyo3::PyResult;
+    use std::sync::{Mutex, OnceLock};
+
+    static NETWORK: OnceLock<Mutex<TrustNetwork>> = OnceLock::new();
+
+    fn with_network<T>(f: impl FnOnce(&mut TrustNetwork) -> T) -> PyResult<T> {
+        let network = match NETWORK.get() {
+            Some(network) => network,
+            None => {
+                let network = TrustNetwork::new().map_err(|e| LicenseRequired::new_err(e.to_string()))?;
+                NETWORK.get_or_init(|| Mutex::new(network))
+            }
+        };
+        let mut network = network.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok(f(&mut network))
+    }
+
+    fn info(network: &TrustNetwork, agent_id: String) -> TrustInfo {
+        let tier = network.get_trust_tier(&agent_id);
+        let score = network.get_reputation(&agent_id).map(|r| r.score).unwrap_or(500);
+        TrustInfo {
+            tier: serde_json::to_value(tier)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+            score: score as u32,
+            allows_high_risk: tier.allows_high_risk(),
+            agent_id,
+        }
+    }
+
+    pub fn tier(agent_id: String) -> PyResult<TrustInfo> {
+        with_network(|network| info(network, agent_id))
+    }
+
+    pub fn record(agent_id: String, kind: &str, impact: i32, subject: Option<String>) -> PyResult<TrustInfo> {
+        let impact = impact.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        let subject = subject.unwrap_or_default();
+        let event = match kind {
+            "success" => ReputationEvent::ActionSuccess { action: subject, impact },
+            "failure" => ReputationEvent::ActionFailed { action: subject, impact },
+            "violation" => ReputationEvent::PolicyViolation { policy_id: subject, impact },
+            "verification" => ReputationEvent::VerificationComplete { impact },
+            other => return Err(PyValueError::new_err(format!("unknown trust event kind '{}'", other))),
+        };
+        with_network(|network| {
+            network.register_agent(&agent_id, "default");
+            network.record_event(&agent_id, event);
+            info(network, agent_id)
+        })
+    }
+}
+
+#[cfg(not(feature = "enterprise"))]
+mod trust {
+    use super::{LicenseRequired, TrustInfo};
+    use pyo3::PyResult;
+
+    fn unavailable<T>() -> PyResult<T> {
+        Err(LicenseRequired::new_err("trust requires the enterprise build"))
+    }
+
+    pub fn tier(_agent_id: String) -> PyResult<TrustInfo> {
+        unavailable()
+    }
+
+    pub fn record(_agent_id: String, _kind: &str, _impact: i32, _subject: Option<String>) -> PyResult<TrustInfo> {
+        unavailable()
+    }
+}
+
+// ============================================================================
+// Module
+// ============================================================================
+
+#[pymodule]
+fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+
+    m.add_function(wrap_pyfunction!(init, m)?)?;
+    m.add_function(wrap_pyfunction!(shutdown, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_action, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_action_sync, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(get_attestation, m)?)?;
+    m.add_function(wrap_pyfunction!(get_balance, m)?)?;
+    m.add_function(wrap_pyfunction!(deposit, m)?)?;
+    m.add_function(wrap_pyfunction!(transfer, m)?)?;
+    m.add_function(wrap_pyfunction!(get_trust_tier, m)?)?;
+    m.add_function(wrap_pyfunction!(record_trust_event, m)?)?;
+
+    m.add_class::<VerifyResult>()?;
+    m.add_class::<Attestation>()?;
+    m.add_class::<Balance>()?;
+    m.add_class::<PaymentReceipt>()?;
+    m.add_class::<TrustInfo>()?;
+
+    py.import("atexit")?
+        .call_method1("register", (wrap_pyfunction!(drain_on_exit, m)?,))?;
+
+    m.add("AgentKernError", py.get_type::<AgentKernError>())?;
+    m.add("RuntimeShutDown", py.get_type::<RuntimeShutDown>())?;
+    m.add("TeeUnavailable", py.get_type::<TeeUnavailable>())?;
+    m.add("InvalidAmount", py.get_type::<InvalidAmount>())?;
+    m.add("InsufficientFunds", py.get_type::<InsufficientFunds>())?;
+    m.add("AccountNotFound", py.get_type::<AccountNotFound>())?;
+    m.add("TransferFailed", py.get_type::<TransferFailed>())?;
+    m.add("LicenseRequired", py.get_type::<LicenseRequired>())?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! Generates the gRPC service stubs (feature `grpc`).
+//!
+//! Messages are defined in Rust (`src/serve.rs`) with prost derives, so the
+//! service is described with tonic-build's manual builder and no `protoc`
+//! is needed at build time. Keep in sync with `proto/runtime.proto`.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    grpc::compile();
+}
+
+#[cfg(feature = "grpc")]
+mod grpc {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    fn unary(name: &str, route: &str, input: &str, output: &str) -> Method {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::serve::grpc::{}", input))
+            .output_type(format!("crate::serve::grpc::{}", output))
+            .codec_path("tonic::codec::ProstCodec")
+            .build()
+    }
+
+    pub fn compile() {
+        let service = Service::builder()
+            .name("Runtime")
+            .package("agentkern.runtime.v1")
+            .method(unary("verify", "Verify", "VerifyRequest", "VerifyResponse"))
+            .method(unary("attest", "Attest", "AttestRequest", "AttestResponse"))
+            .method(unary("health", "Health", "HealthRequest", "HealthResponse"))
+            .build();
+
+        Builder::new().build_client(false).compile(&[service]);
+    }
+}
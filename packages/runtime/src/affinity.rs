@@ -0,0 +1,63 @@
+//! CPU Affinity
+//!
+//! Pins the async runtime's worker threads one per core when
+//! `thread_per_core` is enabled. Only the first `workers` threads the
+//! runtime starts are pinned: Tokio launches its workers when the runtime is
+//! built, so later threads are the blocking pool and stay unpinned.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Thread-start hook pinning the first `workers` threads to distinct CPUs.
+pub(crate) fn pin_workers(workers: usize) -> impl Fn() + Send + Sync + 'static {
+    let cpus = Arc::new(allowed_cpus());
+    let started = AtomicUsize::new(0);
+    move || {
+        let index = started.fetch_add(1, Ordering::Relaxed);
+        if index >= workers || cpus.is_empty() {
+            return;
+        }
+        let cpu = cpus[index % cpus.len()];
+        match set_current_thread_affinity(cpu) {
+            Ok(()) => tracing::debug!(cpu, "Pinned worker thread"),
+            Err(e) => tracing::warn!(cpu, "Failed to pin worker thread: {}", e),
+        }
+    }
+}
+
+/// CPUs this process may run on (cpuset / affinity mask).
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> Vec<usize> {
+    // SAFETY: cpu_set_t is plain data; sched_getaffinity only writes to it
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Vec::new();
+        }
+        (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allowed_cpus() -> Vec<usize> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn set_current_thread_affinity(cpu: usize) -> io::Result<()> {
+    // SAFETY: as above; pid 0 is the calling thread
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut set);
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_current_thread_affinity(_cpu: usize) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "CPU affinity is only supported on Linux"))
+}
@@ -0,0 +1,96 @@
+//! Agent Attestation
+//!
+//! Checks the TEE quotes Nexus agent cards carry with the Gate attestation
+//! verifier, and turns a Gate attestation into the form a card embeds.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut verifier = AttestationVerifier::new();
+//! verifier.trust_measurement(release_measurement);
+//! let registry = AgentRegistry::new().with_quote_verifier(Arc::new(TeeQuoteVerifier::new(verifier)));
+//!
+//! // On the agent's host: bind the quote to the agent's ID
+//! let card = card.with_attestation(card_attestation(&tee.get_attestation(card.id.as_bytes())?));
+//! ```
+
+use agentkern_gate::tee::{Attestation, AttestationVerifier, TeePlatform};
+use agentkern_nexus::{CardAttestation, QuoteVerifier};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+
+/// Longest report data a quote carries.
+const MAX_USER_DATA: usize = 64;
+
+/// Gate's attestation verifier as a Nexus quote verifier.
+pub struct TeeQuoteVerifier {
+    verifier: AttestationVerifier,
+}
+
+impl TeeQuoteVerifier {
+    pub fn new(verifier: AttestationVerifier) -> Self {
+        Self { verifier }
+    }
+}
+
+impl QuoteVerifier for TeeQuoteVerifier {
+    fn verify(&self, agent_id: &str, card: &CardAttestation) -> Result<(), String> {
+        let attestation = gate_attestation(card)?;
+        // The quote must name the agent, so one agent cannot present another's
+        let id = agent_id.as_bytes();
+        if attestation.user_data != id[..id.len().min(MAX_USER_DATA)] {
+            return Err("quote is not bound to this agent".to_string());
+        }
+        match self.verifier.verify(&attestation) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("measurement is not trusted".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Card form of a Gate attestation.
+pub fn card_attestation(attestation: &Attestation) -> CardAttestation {
+    let mut card = CardAttestation::new(
+        format!("{:?}", attestation.platform),
+        &attestation.measurement,
+        &attestation.quote,
+        &attestation.user_data,
+    );
+    card.cert_chain = attestation.cert_chain.iter().map(|cert| STANDARD.encode(cert)).collect();
+    card.issued_at = DateTime::from_timestamp(attestation.timestamp as i64, 0).unwrap_or_else(Utc::now);
+    card
+}
+
+fn gate_attestation(card: &CardAttestation) -> Result<Attestation, String> {
+    let platform: TeePlatform = serde_json::from_value(serde_json::Value::String(card.platform.clone()))
+        .map_err(|_| format!("unknown TEE platform {}", card.platform))?;
+    let decode = |e: base64::DecodeError| format!("malformed attestation: {e}");
+    let mut attestation = Attestation::new(platform, &card.measurement_bytes().map_err(decode)?, card.user_data_bytes().map_err(decode)?);
+    attestation.quote = card.quote_bytes().map_err(decode)?;
+    attestation.cert_chain = card.cert_chain.iter().map(|cert| STANDARD.decode(cert)).collect::<Result<_, _>>().map_err(decode)?;
+    attestation.timestamp = card.issued_at.timestamp().max(0) as u64;
+    Ok(attestation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_gate::tee::TeeRuntime;
+
+    #[test]
+    fn test_card_quotes_verify_only_for_their_agent() {
+        let runtime = TeeRuntime::simulated();
+        let card = card_attestation(&runtime.get_attestation(b"agent-1").unwrap());
+        assert_eq!(card.platform, "Simulated");
+
+        let verifier = TeeQuoteVerifier::new(AttestationVerifier::new());
+        verifier.verify("agent-1", &card).unwrap();
+        assert!(verifier.verify("agent-2", &card).is_err());
+
+        let mut pinned = AttestationVerifier::new();
+        pinned.trust_measurement(vec![0u8; 8]);
+        assert_eq!(TeeQuoteVerifier::new(pinned).verify("agent-1", &card), Err("measurement is not trusted".to_string()));
+    }
+}
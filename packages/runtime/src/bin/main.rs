@@ -3,27 +3,53 @@
 //! Single binary that runs anywhere.
 //!
 //! Usage:
-//!   agentkern run     # Start with auto-detection
-//!   agentkern detect  # Show detected environment
-//!   agentkern config  # Show auto-generated config
+//!   agentkern run [FLAGS]              # Start with auto-detection (SIGHUP reloads config)
+//!   agentkern detect                   # Show detected environment
+//!   agentkern config [FLAGS]           # Show effective config (secrets redacted)
+//!   agentkern config validate [FLAGS]  # Validate config, exit 1 on error
+//!   agentkern policy <SUBCOMMAND>      # lint|test|push|list|diff policies
+//!   agentkern escalation simulate ...  # Show where an alert would be routed
+//!   agentkern doctor [FLAGS]           # Diagnose license, TEE, io_uring, WASM, connectivity, clock
 
-use agentkern_runtime::{detect_environment, auto_configure, VERSION};
+use agentkern_runtime::{detect_environment, load_config, ConfigSources, HostResources, VERSION};
 
-#[tokio::main]
-async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+fn main() {
+    // Initialize tracing (filter reloadable on SIGHUP)
+    agentkern_runtime::reload::init_logging();
     
     let args: Vec<String> = std::env::args().collect();
     let command = args.get(1).map(|s| s.as_str()).unwrap_or("run");
-    
+
+    // `run` sizes its runtime from the config; other commands need very little
+    let runtime = if command == "run" {
+        let sources = parse_sources(args.get(2..).unwrap_or_default());
+        load_config(&detect_environment(), &sources)
+            .map_err(|e| e.to_string())
+            .and_then(|config| agentkern_runtime::build_runtime(&config).map_err(|e| e.to_string()))
+    } else {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())
+    };
+    match runtime {
+        Ok(runtime) => runtime.block_on(cli(command, &args)),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn cli(command: &str, args: &[String]) {
     match command {
         "run" => {
             println!("AgentKern v{}", VERSION);
             println!("The Universal AI Agent Kernel");
             println!();
             
-            if let Err(e) = agentkern_runtime::run().await {
+            let sources = parse_sources(args.get(2..).unwrap_or_default());
+            if let Err(e) = agentkern_runtime::run_with(&sources).await {
                 eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
@@ -33,13 +59,45 @@ async fn main() {
             let env = detect_environment();
             println!("Detected Environment:");
             println!("{:#?}", env);
+            println!();
+            println!("Host Resources:");
+            println!("{:#?}", HostResources::detect());
         }
         
         "config" => {
+            let validate_only = args.get(2).map(|s| s.as_str()) == Some("validate");
+            let flags = if validate_only { &args[3..] } else { &args[2..] };
+            let sources = parse_sources(flags);
             let env = detect_environment();
-            let config = auto_configure(&env);
-            println!("Auto-Generated Configuration:");
-            println!("{:#?}", config);
+
+            match load_config(&env, &sources) {
+                Ok(_) if validate_only => println!("Configuration is valid"),
+                Ok(config) => {
+                    println!("# Effective Configuration (secrets redacted)");
+                    match toml::to_string_pretty(&config.redacted()) {
+                        Ok(toml) => print!("{}", toml),
+                        Err(_) => println!("{:#?}", config.redacted()),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Configuration error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        
+        "policy" => {
+            let code = agentkern_runtime::policy_cli::run(&args[2..]).await;
+            std::process::exit(code);
+        }
+
+        "escalation" => {
+            std::process::exit(agentkern_runtime::escalation_cli::run(&args[2..]));
+        }
+
+        "doctor" => {
+            let code = agentkern_runtime::doctor::run(&args[2..]).await;
+            std::process::exit(code);
         }
         
         "version" | "-v" | "--version" => {
@@ -58,6 +116,13 @@ async fn main() {
     }
 }
 
+fn parse_sources(flags: &[String]) -> ConfigSources {
+    ConfigSources::from_args(flags).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(2);
+    })
+}
+
 fn print_help() {
     println!("AgentKern - The Universal AI Agent Kernel");
     println!();
@@ -65,23 +130,76 @@ fn print_help() {
     println!("  agentkern <COMMAND>");
     println!();
     println!("COMMANDS:");
-    println!("  run      Start AgentKern with auto-detection");
-    println!("  detect   Show detected environment");
-    println!("  config   Show auto-generated configuration");
-    println!("  version  Show version");
-    println!("  help     Show this help");
+    println!("  run              Start AgentKern with auto-detection");
+    println!("  detect           Show detected environment");
+    println!("  config           Show effective configuration (secrets redacted)");
+    println!("  config validate  Validate configuration");
+    println!("  policy lint      Check policy files (exit 1 on errors)");
+    println!("  policy test      Run policy test cases");
+    println!("  policy push      Replace a running server's policies");
+    println!("  policy list      List a running server's policies");
+    println!("  policy diff      Compare policy files with a server (exit 1 if different)");
+    println!("  escalation simulate  Show where an alert would land under routing rules");
+    println!("  doctor           Diagnose the host and configuration (exit 0 ok, 1 warn, 2 fail)");
+    println!("  version          Show version");
+    println!("  help             Show this help");
+    println!();
+    println!("FLAGS (run, config):");
+    println!("  -c, --config <path>      Config file, TOML or YAML (default: /etc/agentkern/config.toml)");
+    println!("  --bind <addr>            Bind address");
+    println!("  --port <port>            HTTP port");
+    println!("  --grpc-port <port>       gRPC port");
+    println!("  --max-connections <n>    Max concurrent connections");
+    println!("  --database-url <url>     Database connection URL");
+    println!("  --cache-url <url>        Cache connection URL");
+    println!("  --log-level <filter>     Log filter, e.g. info or agentkern_gate=debug");
+    println!("  --policies <path>        Policy file or directory (repeatable)");
+    println!("  --rate-limit <rps>       Max verifications per second");
+    println!("  --drain-timeout <secs>   Shutdown deadline for in-flight work (default: 30)");
+    println!("  --audit-journal <path>   Append the audit ledger here on shutdown");
+    println!("  --worker-threads <n>     Async worker threads (default: usable CPUs)");
+    println!("  --inference <device>     Neural path: disabled, cpu or gpu (default: detected)");
+    println!("  --mesh-seed <host:port>  Mesh seed node (repeatable)");
+    println!();
+    println!("FLAGS (doctor, plus the above):");
+    println!("  --json                   Machine-readable report");
+    println!("  --ntp-server <host>      Clock reference (default: NTP_SERVER or pool.ntp.org)");
+    println!("  --offline                Skip the clock check");
+    println!();
+    println!("Precedence: defaults < config file < environment < flags");
+    println!();
+    println!("RELOAD (run):");
+    println!("  SIGHUP re-reads the configuration. log_level, policy_paths and rate_limit");
+    println!("  apply live; other changes are logged as requiring a restart.");
     println!();
     println!("ENVIRONMENT VARIABLES:");
+    println!("  AGENTKERN_CONFIG Config file path");
+    println!("  AGENTKERN_SERVER Server for policy push/list/diff (default: http://127.0.0.1:3000)");
     println!("  PORT             HTTP port (default: 3000)");
     println!("  GRPC_PORT        gRPC port (default: 50051)");
     println!("  BIND_ADDRESS     Bind address (default: 0.0.0.0)");
     println!("  DATABASE_URL     Database connection URL");
     println!("  CACHE_URL        Cache connection URL");
+    println!("  LOG_LEVEL        Log filter (default: info; RUST_LOG also accepted)");
+    println!("  RATE_LIMIT       Max verifications per second");
+    println!("  DRAIN_TIMEOUT    Shutdown deadline in seconds (default: 30)");
+    println!("  AUDIT_JOURNAL    Audit journal path (JSON Lines)");
+    println!("  WORKER_THREADS   Async worker threads");
+    println!("  INFERENCE_DEVICE Neural path: disabled, cpu or gpu");
+    println!("  MESH_SEEDS       Comma-separated mesh seed nodes");
+    println!();
+    println!("ENDPOINTS (run):");
+    println!("  POST /verify     Verify an agent action");
+    println!("  POST /attest     TEE attestation for a nonce");
+    println!("  GET  /health     Liveness check");
+    println!("  GET  /metrics    Prometheus metrics");
+    println!("  gRPC             agentkern.runtime.v1.Runtime on GRPC_PORT");
     println!();
     println!("AgentKern auto-detects:");
     println!("  - Container (Docker, Podman)");
     println!("  - Kubernetes");
-    println!("  - Serverless");
+    println!("  - Serverless (Lambda, Cloud Run, Cloud Functions, Azure Functions)");
     println!("  - Edge devices");
     println!("  - Bare metal servers");
+    println!("  - cgroup CPU/memory limits, NUMA nodes and GPUs");
 }
@@ -2,6 +2,17 @@
 //!
 //! Auto-detects the runtime environment without vendor-specific code.
 //! Uses standard Linux/POSIX signals and environment variables.
+//!
+//! [`HostResources`] complements [`Environment`] with what the process may
+//! actually use: cgroup CPU/memory limits, NUMA nodes and GPUs.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let env = detect_environment();
+//! let resources = HostResources::detect();
+//! println!("{:?} with {} CPUs, {} GPUs", env, resources.effective_cpus(), resources.gpus.len());
+//! ```
 
 use std::env;
 use std::path::Path;
@@ -45,10 +56,18 @@ pub enum ContainerRuntime {
     Unknown,
 }
 
-/// Serverless platform (detected generically).
+/// Serverless platform (from the variables each platform sets).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ServerlessPlatform {
-    FunctionAsService, // Generic - could be AWS Lambda, Azure Functions, etc.
+    /// AWS Lambda (`AWS_LAMBDA_FUNCTION_NAME`)
+    AwsLambda,
+    /// Google Cloud Run service or job (`K_SERVICE` / `CLOUD_RUN_JOB`)
+    CloudRun,
+    /// Google Cloud Functions (`FUNCTION_TARGET`, or gen1 `FUNCTION_NAME`)
+    CloudFunctions,
+    /// Azure Functions (`FUNCTIONS_WORKER_RUNTIME`)
+    AzureFunctions,
+    FunctionAsService, // Generic - any other platform setting a handler variable
     Unknown,
 }
 
@@ -82,10 +101,8 @@ pub fn detect_environment() -> Environment {
     }
     
     // Check for serverless
-    if is_serverless() {
-        return Environment::Serverless {
-            platform: ServerlessPlatform::FunctionAsService,
-        };
+    if let Some(platform) = detect_serverless_platform_with(|key| env::var(key).ok()) {
+        return Environment::Serverless { platform };
     }
     
     // Check for container
@@ -114,12 +131,24 @@ fn is_kubernetes() -> bool {
         || Path::new("/var/run/secrets/kubernetes.io").exists()
 }
 
-/// Check if running in serverless environment.
-fn is_serverless() -> bool {
-    // Generic serverless detection (no vendor names)
-    env::var("FUNCTION_NAME").is_ok()
-        || env::var("FUNCTION_TARGET").is_ok()
-        || env::var("_HANDLER").is_ok()
+/// Detect the serverless platform, if any.
+fn detect_serverless_platform_with(var: impl Fn(&str) -> Option<String>) -> Option<ServerlessPlatform> {
+    let set = |key: &str| var(key).is_some();
+
+    if set("AWS_LAMBDA_FUNCTION_NAME") || set("AWS_LAMBDA_RUNTIME_API") {
+        Some(ServerlessPlatform::AwsLambda)
+    } else if set("FUNCTIONS_WORKER_RUNTIME") {
+        Some(ServerlessPlatform::AzureFunctions)
+    } else if set("FUNCTION_TARGET") || (set("FUNCTION_NAME") && set("GCP_PROJECT")) {
+        // Gen2 functions also set K_SERVICE, so check before Cloud Run
+        Some(ServerlessPlatform::CloudFunctions)
+    } else if set("K_SERVICE") || set("CLOUD_RUN_JOB") {
+        Some(ServerlessPlatform::CloudRun)
+    } else if set("FUNCTION_NAME") || set("_HANDLER") {
+        Some(ServerlessPlatform::FunctionAsService)
+    } else {
+        None
+    }
 }
 
 /// Check if running in a container.
@@ -197,6 +226,205 @@ fn detect_os() -> OperatingSystem {
     return OperatingSystem::Unknown;
 }
 
+// ============================================================================
+// HOST RESOURCES
+// ============================================================================
+
+/// Resources available to this process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostResources {
+    /// CPUs the process may run on (affinity / cpuset)
+    pub cpus: usize,
+    /// CFS quota in CPUs (`cpu.max`), None = unthrottled
+    pub cpu_quota: Option<f64>,
+    /// Memory limit in bytes (cgroup or serverless configuration), None = unlimited
+    pub memory_limit: Option<u64>,
+    /// NUMA nodes (1 on UMA hosts)
+    pub numa_nodes: usize,
+    /// GPUs visible to the process
+    pub gpus: Vec<Gpu>,
+}
+
+/// A GPU.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpu {
+    pub vendor: GpuVendor,
+    /// Model name or device path
+    pub name: String,
+}
+
+/// GPU vendor (from the PCI vendor ID).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Other,
+}
+
+impl Default for HostResources {
+    fn default() -> Self {
+        Self {
+            cpus: 1,
+            cpu_quota: None,
+            memory_limit: None,
+            numa_nodes: 1,
+            gpus: Vec::new(),
+        }
+    }
+}
+
+impl HostResources {
+    /// Detect resources of the current host.
+    pub fn detect() -> Self {
+        let mut resources = Self::detect_in(Path::new("/"), |key| env::var(key).ok());
+        resources.cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        resources
+    }
+
+    /// Detect from a filesystem root (`/proc`, `/sys`, `/dev` below it).
+    fn detect_in(root: &Path, var: impl Fn(&str) -> Option<String>) -> Self {
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).ok();
+
+        let cpu_quota = read("sys/fs/cgroup/cpu.max")
+            .and_then(|max| parse_cpu_max(&max))
+            .or_else(|| {
+                let quota = read("sys/fs/cgroup/cpu/cpu.cfs_quota_us")?.trim().parse::<i64>().ok()?;
+                let period = read("sys/fs/cgroup/cpu/cpu.cfs_period_us")?.trim().parse::<i64>().ok()?;
+                (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+            });
+
+        let memory_limit = read("sys/fs/cgroup/memory.max")
+            .and_then(|max| parse_memory_max(&max))
+            .or_else(|| read("sys/fs/cgroup/memory/memory.limit_in_bytes").and_then(|max| parse_memory_max(&max)))
+            .or_else(|| {
+                // Serverless sandboxes don't always expose their cgroup
+                ["AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "FUNCTION_MEMORY_MB"]
+                    .into_iter()
+                    .find_map(&var)
+                    .and_then(|mb| mb.trim().parse::<u64>().ok())
+                    .map(|mb| mb * 1024 * 1024)
+            });
+
+        let numa_nodes = list_dir(&root.join("sys/devices/system/node"))
+            .iter()
+            .filter(|name| is_numbered(name, "node"))
+            .count()
+            .max(1);
+
+        Self {
+            cpus: 1,
+            cpu_quota,
+            memory_limit,
+            numa_nodes,
+            gpus: detect_gpus(root, &var),
+        }
+    }
+
+    /// CPUs usable without throttling: affinity capped by the CFS quota.
+    pub fn effective_cpus(&self) -> usize {
+        let cpus = self.cpus.max(1);
+        match self.cpu_quota {
+            Some(quota) => cpus.min(quota.ceil() as usize).max(1),
+            None => cpus,
+        }
+    }
+
+    /// Is a CUDA-capable GPU available?
+    pub fn has_cuda(&self) -> bool {
+        self.gpus.iter().any(|gpu| gpu.vendor == GpuVendor::Nvidia)
+    }
+}
+
+/// Parse cgroup v2 `cpu.max` (`"max 100000"` or `"<quota> <period>"`).
+fn parse_cpu_max(contents: &str) -> Option<f64> {
+    let mut parts = contents.split_whitespace();
+    let quota = parts.next()?.parse::<u64>().ok()?; // "max" = unthrottled
+    let period = parts.next().map_or(Some(100_000), |p| p.parse::<u64>().ok())?;
+    (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+}
+
+/// Parse cgroup `memory.max` / `memory.limit_in_bytes` (`"max"` or bytes).
+fn parse_memory_max(contents: &str) -> Option<u64> {
+    let bytes = contents.trim().parse::<u64>().ok()?;
+    // cgroup v1 reports "unlimited" as a page-aligned i64::MAX
+    (bytes > 0 && bytes < i64::MAX as u64 / 2).then_some(bytes)
+}
+
+/// Detect GPUs from DRM and the NVIDIA driver, honouring device masks.
+fn detect_gpus(root: &Path, var: &impl Fn(&str) -> Option<String>) -> Vec<Gpu> {
+    let mut gpus = Vec::new();
+
+    // NVIDIA driver: one directory per GPU with the model name
+    let nvidia = root.join("proc/driver/nvidia/gpus");
+    for bus in list_dir(&nvidia) {
+        let name = std::fs::read_to_string(nvidia.join(&bus).join("information"))
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find_map(|l| l.strip_prefix("Model:"))
+                    .map(|m| m.trim().to_string())
+            })
+            .unwrap_or(bus);
+        gpus.push(Gpu { vendor: GpuVendor::Nvidia, name });
+    }
+
+    // DRM display devices (skips connectors like card0-HDMI-A-1)
+    let drm = root.join("sys/class/drm");
+    for card in list_dir(&drm) {
+        if !is_numbered(&card, "card") {
+            continue;
+        }
+        let vendor = match std::fs::read_to_string(drm.join(&card).join("device/vendor")).as_deref().map(str::trim) {
+            Ok("0x10de") => GpuVendor::Nvidia,
+            Ok("0x1002") => GpuVendor::Amd,
+            Ok("0x8086") => GpuVendor::Intel,
+            _ => GpuVendor::Other,
+        };
+        // Already listed by the NVIDIA driver
+        if vendor == GpuVendor::Nvidia && gpus.iter().any(|g| g.vendor == GpuVendor::Nvidia) {
+            continue;
+        }
+        gpus.push(Gpu { vendor, name: format!("/dev/dri/{}", card) });
+    }
+
+    // Containers often get /dev/nvidia* without the driver's /proc entries
+    if gpus.is_empty() {
+        for dev in list_dir(&root.join("dev")) {
+            if is_numbered(&dev, "nvidia") {
+                gpus.push(Gpu { vendor: GpuVendor::Nvidia, name: format!("/dev/{}", dev) });
+            }
+        }
+    }
+
+    // NVIDIA devices masked out by the container runtime or the user
+    let masked = |key: &str, none: &[&str]| var(key).is_some_and(|v| none.contains(&v.trim()));
+    if masked("NVIDIA_VISIBLE_DEVICES", &["", "none", "void"]) || masked("CUDA_VISIBLE_DEVICES", &["", "-1"]) {
+        gpus.retain(|gpu| gpu.vendor != GpuVendor::Nvidia);
+    }
+
+    gpus
+}
+
+/// `<prefix><number>` (`node0`, `card1`), as opposed to `card1-HDMI-A-1` or `nvidiactl`.
+fn is_numbered(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix).is_some_and(|id| id.parse::<u32>().is_ok())
+}
+
+/// Entry names in a directory, sorted (empty if unreadable).
+fn list_dir(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,4 +442,66 @@ mod tests {
         // Should at least detect something
         assert!(!matches!(env, Environment::Browser)); // Not WASM in tests
     }
+
+    #[test]
+    fn test_serverless_platforms() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: Vec<(String, String)> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            detect_serverless_platform_with(|key| vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()))
+        };
+
+        assert_eq!(detect(&[("AWS_LAMBDA_FUNCTION_NAME", "fn"), ("_HANDLER", "index.handler")]), Some(ServerlessPlatform::AwsLambda));
+        assert_eq!(detect(&[("K_SERVICE", "api"), ("K_REVISION", "api-001")]), Some(ServerlessPlatform::CloudRun));
+        assert_eq!(detect(&[("K_SERVICE", "fn"), ("FUNCTION_TARGET", "handler")]), Some(ServerlessPlatform::CloudFunctions));
+        assert_eq!(detect(&[("FUNCTIONS_WORKER_RUNTIME", "custom")]), Some(ServerlessPlatform::AzureFunctions));
+        assert_eq!(detect(&[("_HANDLER", "main")]), Some(ServerlessPlatform::FunctionAsService));
+        assert_eq!(detect(&[]), None);
+    }
+
+    #[test]
+    fn test_cgroup_limits() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("250000 100000\n"), Some(2.5));
+        assert_eq!(parse_memory_max("max\n"), None);
+        assert_eq!(parse_memory_max("536870912\n"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_memory_max("9223372036854771712"), None); // v1 "unlimited"
+
+        let resources = HostResources { cpus: 16, cpu_quota: Some(2.5), ..Default::default() };
+        assert_eq!(resources.effective_cpus(), 3);
+    }
+
+    #[test]
+    fn test_detect_in_fake_root() {
+        let root = std::env::temp_dir().join(format!("agentkern-detect-{}", std::process::id()));
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        write("sys/fs/cgroup/cpu.max", "200000 100000\n");
+        write("sys/fs/cgroup/memory.max", "max\n");
+        write("sys/devices/system/node/node0/cpulist", "0-7");
+        write("sys/devices/system/node/node1/cpulist", "8-15");
+        write("sys/devices/system/node/possible", "0-1");
+        write("proc/driver/nvidia/gpus/0000:01:00.0/information", "Model: \t NVIDIA A100\n");
+        write("sys/class/drm/card0/device/vendor", "0x10de\n");
+        write("sys/class/drm/card1/device/vendor", "0x8086\n");
+        write("sys/class/drm/card1-HDMI-A-1/status", "connected");
+
+        let resources = HostResources::detect_in(&root, |key| (key == "AWS_LAMBDA_FUNCTION_MEMORY_SIZE").then(|| "1024".into()));
+        assert_eq!(resources.cpu_quota, Some(2.0));
+        assert_eq!(resources.memory_limit, Some(1024 * 1024 * 1024));
+        assert_eq!(resources.numa_nodes, 2);
+        assert_eq!(resources.gpus, vec![
+            Gpu { vendor: GpuVendor::Nvidia, name: "NVIDIA A100".into() },
+            Gpu { vendor: GpuVendor::Intel, name: "/dev/dri/card1".into() },
+        ]);
+        assert!(resources.has_cuda());
+
+        let masked = HostResources::detect_in(&root, |key| (key == "NVIDIA_VISIBLE_DEVICES").then(|| "void".into()));
+        assert!(!masked.has_cuda());
+        assert_eq!(masked.gpus.len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -0,0 +1,172 @@
+//! Escalation CLI
+//!
+//! `agentkern escalation simulate <rules.yaml> --level <level> [FLAGS]`
+//! shows where a hypothetical alert would land under an escalation routing
+//! rule file, and why each rule did or did not match.
+//!
+//! Exit codes:
+//! - `0`: the alert reaches at least one destination
+//! - `1`: invalid rules, or the alert would reach nobody
+//! - `2`: usage error
+
+use agentkern_arbiter::escalation::{EscalationLevel, RoutingRules, TriggerType};
+use chrono::{DateTime, Utc};
+
+use crate::policy_cli::{EXIT_FAILED, EXIT_OK, EXIT_USAGE};
+
+/// Run `agentkern escalation <args>`. Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let mut positional = Vec::new();
+    let mut level = None;
+    let mut trigger = TriggerType::Manual;
+    let mut tenant = None;
+    let mut at = Utc::now();
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| iter.next().cloned().ok_or(format!("{} requires a value", flag));
+        let parsed = match arg.as_str() {
+            "--level" => value("--level").and_then(|v| parse_level(&v)).map(|v| level = Some(v)),
+            "--trigger" => value("--trigger").map(|v| trigger = parse_trigger(&v)),
+            "--tenant" => value("--tenant").map(|v| tenant = Some(v)),
+            "--at" => value("--at").and_then(|v| {
+                DateTime::parse_from_rfc3339(&v)
+                    .map(|t| at = t.with_timezone(&Utc))
+                    .map_err(|e| format!("--at: {}", e))
+            }),
+            "--json" => {
+                json = true;
+                Ok(())
+            }
+            flag if flag.starts_with('-') => Err(format!("unknown flag {}", flag)),
+            _ => {
+                positional.push(arg.as_str());
+                Ok(())
+            }
+        };
+        if let Err(problem) = parsed {
+            return usage(&problem);
+        }
+    }
+
+    match (positional.as_slice(), level) {
+        (["simulate", path], Some(level)) => simulate(path, level, trigger, tenant.as_deref(), at, json),
+        (["simulate", _], None) => usage("--level is required"),
+        _ => usage("expected simulate <rules.yaml>"),
+    }
+}
+
+fn usage(problem: &str) -> i32 {
+    eprintln!("Error: {}", problem);
+    eprintln!();
+    eprintln!("USAGE:");
+    eprintln!("  agentkern escalation simulate <rules.yaml> --level <low|medium|high|critical>");
+    eprintln!("      [--trigger <type>] [--tenant <id>] [--at <rfc3339>] [--json]");
+    EXIT_USAGE
+}
+
+fn parse_level(level: &str) -> Result<EscalationLevel, String> {
+    match level.to_ascii_lowercase().as_str() {
+        "low" => Ok(EscalationLevel::Low),
+        "medium" => Ok(EscalationLevel::Medium),
+        "high" => Ok(EscalationLevel::High),
+        "critical" => Ok(EscalationLevel::Critical),
+        other => Err(format!("unknown level {}", other)),
+    }
+}
+
+/// Known trigger types by their snake_case name; anything else is custom.
+fn parse_trigger(trigger: &str) -> TriggerType {
+    serde_json::from_value(serde_json::Value::String(trigger.to_string()))
+        .unwrap_or_else(|_| TriggerType::Custom(trigger.to_string()))
+}
+
+fn simulate(
+    path: &str,
+    level: EscalationLevel,
+    trigger: TriggerType,
+    tenant: Option<&str>,
+    at: DateTime<Utc>,
+    json: bool,
+) -> i32 {
+    let rules = match RoutingRules::from_file(path) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    let decision = rules.simulate(level, trigger.clone(), tenant, at);
+
+    if json {
+        match serde_json::to_string_pretty(&decision) {
+            Ok(out) => println!("{}", out),
+            Err(e) => eprintln!("error: {}", e),
+        }
+    } else {
+        println!(
+            "Alert: {:?} {:?} tenant={} at {}",
+            level,
+            trigger,
+            tenant.unwrap_or("(none)"),
+            at.to_rfc3339()
+        );
+        for trace in &decision.trace {
+            match &trace.reason {
+                None => println!("  MATCH {}", trace.rule),
+                Some(reason) => println!("  skip  {}: {}", trace.rule, reason),
+            }
+        }
+        if decision.destinations.is_empty() {
+            println!("No destinations: the alert would not reach anyone");
+        } else {
+            println!("Destinations:");
+            for destination in &decision.destinations {
+                println!("  {}", destination);
+            }
+        }
+    }
+
+    if decision.destinations.is_empty() { EXIT_FAILED } else { EXIT_OK }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+rules:
+  - name: budget pages
+    when: { triggers: [budget_exceeded], min_level: High }
+    route: [!webhook pagerduty]
+"#;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_simulate_exit_codes() {
+        let dir = std::env::temp_dir().join(format!("agentkern-escalation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rules.yaml");
+        std::fs::write(&path, RULES).unwrap();
+        let path = path.to_str().unwrap();
+
+        let routed = args(&["simulate", path, "--level", "critical", "--trigger", "budget_exceeded"]);
+        assert_eq!(run(&routed), EXIT_OK);
+        let dropped = args(&["simulate", path, "--level", "low", "--trigger", "budget_exceeded", "--json"]);
+        assert_eq!(run(&dropped), EXIT_FAILED);
+
+        assert_eq!(run(&args(&["simulate", path])), EXIT_USAGE);
+        assert_eq!(run(&args(&["simulate", path, "--level", "urgent"])), EXIT_USAGE);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        assert_eq!(parse_trigger("loop_detected"), TriggerType::LoopDetected);
+        assert_eq!(parse_trigger("disk_full"), TriggerType::Custom("disk_full".into()));
+    }
+}
@@ -0,0 +1,531 @@
+//! Caller Identity
+//!
+//! Authenticates callers of the runtime's HTTP and gRPC APIs and checks what
+//! they may do. Three kinds of credential map to an [`AgentIdentity`]:
+//! - API keys (`ak_…`), stored only as SHA-256 hashes
+//! - Client certificates (mTLS), bound by SHA-256 fingerprint of the DER
+//!   certificate and forwarded by the TLS-terminating proxy in
+//!   `x-client-cert-sha256`. Fingerprints aren't secret, so the header is
+//!   only read once the registry trusts a proxy
+//!   ([`IdentityRegistry::with_trusted_proxy`], or [`TRUSTED_PROXY_ENV`]),
+//!   and that proxy must strip it from clients
+//! - Signed tokens: Ed25519 (`EdDSA`) JWTs with the agent and its scopes
+//!
+//! Every identity carries [`Scope`]s: `verify`, `payments` and `admin`
+//! (which implies the other two). Keys rotate with a grace period during
+//! which both the old and new secret work; rotating the signing key does the
+//! same for tokens.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let identity = Arc::new(IdentityRegistry::new());
+//! let key = identity.issue_api_key("agent-1", &[Scope::Verify]);
+//! let state = ServeState::new().with_identity(identity.clone());
+//! // curl -H "Authorization: Bearer $SECRET" ...
+//! let rotated = identity.rotate_api_key(&key.key_id, Duration::from_secs(3600))?;
+//! ```
+
+use agentkern_errors::{Coded, ErrorCode};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variable holding a bootstrap admin API key.
+pub const ADMIN_KEY_ENV: &str = "AGENTKERN_ADMIN_KEY";
+
+/// Environment variable that, when `true`, trusts [`CERT_HEADER`] from a
+/// TLS-terminating proxy.
+pub const TRUSTED_PROXY_ENV: &str = "AGENTKERN_TRUSTED_PROXY";
+
+/// Header carrying an API key (alternative to `Authorization: Bearer`).
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the client certificate fingerprint from the TLS proxy.
+/// Only read behind a trusted proxy.
+pub const CERT_HEADER: &str = "x-client-cert-sha256";
+
+const KEY_PREFIX: &str = "ak_";
+
+/// What a caller may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// `verify` and `attest`, reading policies
+    Verify,
+    /// Moving funds
+    Payments,
+    /// Everything, including policy changes and credential management
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verify => "verify",
+            Self::Payments => "payments",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// How a caller authenticated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    ApiKey,
+    Certificate,
+    Token,
+}
+
+/// An authenticated caller.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentIdentity {
+    pub agent_id: String,
+    pub scopes: Vec<Scope>,
+    pub credential: CredentialKind,
+}
+
+impl AgentIdentity {
+    /// Whether the identity holds `scope` (admin holds every scope).
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.iter().any(|s| *s == scope || *s == Scope::Admin)
+    }
+
+    /// Fail unless the identity holds `scope`.
+    pub fn require(&self, scope: Scope) -> Result<(), IdentityError> {
+        if self.allows(scope) {
+            Ok(())
+        } else {
+            Err(IdentityError::InsufficientScope(scope))
+        }
+    }
+
+    /// Fail unless the identity may act for `agent_id` (itself, or admin).
+    pub fn require_agent(&self, agent_id: &str) -> Result<(), IdentityError> {
+        if self.agent_id == agent_id || self.allows(Scope::Admin) {
+            Ok(())
+        } else {
+            Err(IdentityError::WrongAgent(agent_id.to_string()))
+        }
+    }
+}
+
+/// A newly issued API key; the secret is shown once and never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuedKey {
+    pub key_id: String,
+    pub secret: String,
+}
+
+/// Credentials as presented on a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Presented {
+    ApiKey(String),
+    Certificate(String),
+    Token(String),
+}
+
+impl Presented {
+    /// Read credentials from request headers (`get` looks up a lowercase name).
+    ///
+    /// `Authorization: Bearer` wins over `x-api-key`. The certificate header
+    /// is ignored; see [`Presented::from_proxy_headers`].
+    pub fn from_headers<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        if let Some(bearer) = get("authorization").and_then(|v| v.strip_prefix("Bearer ")) {
+            let bearer = bearer.trim().to_string();
+            return Some(if bearer.starts_with(KEY_PREFIX) { Self::ApiKey(bearer) } else { Self::Token(bearer) });
+        }
+        get(API_KEY_HEADER).map(|key| Self::ApiKey(key.trim().to_string()))
+    }
+
+    /// Like [`Presented::from_headers`], falling back to the certificate
+    /// header. Only for requests from a proxy that strips that header from
+    /// clients.
+    pub fn from_proxy_headers<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        let certificate = get(CERT_HEADER);
+        Self::from_headers(get).or_else(|| certificate.map(|fp| Self::Certificate(fp.trim().to_ascii_lowercase())))
+    }
+}
+
+/// SHA-256 fingerprint (lowercase hex) of a DER-encoded certificate.
+pub fn certificate_fingerprint(der: &[u8]) -> String {
+    hex(&Sha256::digest(der))
+}
+
+#[derive(Debug, Clone)]
+struct Grant {
+    agent_id: String,
+    scopes: Vec<Scope>,
+    /// Unix seconds after which the grant stops working
+    expires_at: Option<u64>,
+    revoked: bool,
+}
+
+impl Grant {
+    fn check(&self, now: u64) -> Result<(), IdentityError> {
+        if self.revoked {
+            return Err(IdentityError::Revoked);
+        }
+        match self.expires_at {
+            Some(at) if now >= at => Err(IdentityError::Expired),
+            _ => Ok(()),
+        }
+    }
+}
+
+struct SigningKeys {
+    current: SigningKey,
+    kid: u32,
+    /// Verifying keys by id, with the time each stops being accepted
+    verifying: HashMap<u32, (VerifyingKey, Option<u64>)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenHeader {
+    alg: String,
+    typ: String,
+    kid: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    scopes: Vec<Scope>,
+    iat: u64,
+    exp: u64,
+}
+
+/// Issues, rotates, revokes and checks caller credentials.
+pub struct IdentityRegistry {
+    /// API keys by SHA-256 of the secret
+    keys: RwLock<HashMap<[u8; 32], (String, Grant)>>,
+    /// Certificate bindings by fingerprint
+    certificates: RwLock<HashMap<String, Grant>>,
+    signing: RwLock<SigningKeys>,
+    /// Whether [`CERT_HEADER`] comes from a trusted proxy
+    trusted_proxy: bool,
+}
+
+impl IdentityRegistry {
+    /// Create an empty registry with a fresh token signing key.
+    pub fn new() -> Self {
+        let current = SigningKey::generate(&mut OsRng);
+        let verifying = HashMap::from([(1, (current.verifying_key(), None))]);
+        Self {
+            keys: RwLock::new(HashMap::new()),
+            certificates: RwLock::new(HashMap::new()),
+            signing: RwLock::new(SigningKeys { current, kid: 1, verifying }),
+            trusted_proxy: false,
+        }
+    }
+
+    /// Accept client certificates forwarded in [`CERT_HEADER`]. Only enable
+    /// behind a TLS-terminating proxy that strips the header from clients.
+    pub fn with_trusted_proxy(mut self) -> Self {
+        self.trusted_proxy = true;
+        self
+    }
+
+    /// Read the credentials on a request, including the certificate header
+    /// behind a trusted proxy.
+    pub fn presented<'a>(&self, get: impl Fn(&str) -> Option<&'a str>) -> Option<Presented> {
+        if self.trusted_proxy {
+            Presented::from_proxy_headers(get)
+        } else {
+            Presented::from_headers(get)
+        }
+    }
+
+    /// A registry holding the admin key from [`ADMIN_KEY_ENV`], if it is
+    /// set, trusting the proxy if [`TRUSTED_PROXY_ENV`] is `true`.
+    pub fn from_env() -> Option<Self> {
+        let secret = std::env::var(ADMIN_KEY_ENV).ok().filter(|s| !s.is_empty())?;
+        let mut registry = Self::new();
+        if std::env::var(TRUSTED_PROXY_ENV).is_ok_and(|v| v.eq_ignore_ascii_case("true")) {
+            registry = registry.with_trusted_proxy();
+        }
+        registry.register_api_key(&secret, "admin", &[Scope::Admin]);
+        Some(registry)
+    }
+
+    /// Issue a new API key for `agent_id`.
+    pub fn issue_api_key(&self, agent_id: &str, scopes: &[Scope]) -> IssuedKey {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let secret = format!("{}{}", KEY_PREFIX, hex(&bytes));
+        let key_id = self.register_api_key(&secret, agent_id, scopes);
+        IssuedKey { key_id, secret }
+    }
+
+    /// Register an externally generated API key; returns its key id.
+    pub fn register_api_key(&self, secret: &str, agent_id: &str, scopes: &[Scope]) -> String {
+        let hash: [u8; 32] = Sha256::digest(secret.as_bytes()).into();
+        let key_id = format!("key_{}", hex(&hash[..8]));
+        let grant = Grant { agent_id: agent_id.to_string(), scopes: scopes.to_vec(), expires_at: None, revoked: false };
+        write(&self.keys).insert(hash, (key_id.clone(), grant));
+        key_id
+    }
+
+    /// Issue a replacement for `key_id`; the old secret keeps working for `grace`.
+    pub fn rotate_api_key(&self, key_id: &str, grace: Duration) -> Result<IssuedKey, IdentityError> {
+        let (agent_id, scopes) = {
+            let mut keys = write(&self.keys);
+            let (_, grant) = keys
+                .values_mut()
+                .find(|(id, grant)| id == key_id && !grant.revoked)
+                .ok_or_else(|| IdentityError::UnknownKey(key_id.to_string()))?;
+            let expires_at = now() + grace.as_secs();
+            grant.expires_at = Some(grant.expires_at.map_or(expires_at, |at| at.min(expires_at)));
+            (grant.agent_id.clone(), grant.scopes.clone())
+        };
+        Ok(self.issue_api_key(&agent_id, &scopes))
+    }
+
+    /// Revoke an API key immediately.
+    pub fn revoke_api_key(&self, key_id: &str) -> Result<(), IdentityError> {
+        let mut keys = write(&self.keys);
+        let (_, grant) = keys
+            .values_mut()
+            .find(|(id, _)| id == key_id)
+            .ok_or_else(|| IdentityError::UnknownKey(key_id.to_string()))?;
+        grant.revoked = true;
+        Ok(())
+    }
+
+    /// Bind a client certificate fingerprint to `agent_id`.
+    pub fn bind_certificate(&self, fingerprint: &str, agent_id: &str, scopes: &[Scope]) {
+        let grant = Grant { agent_id: agent_id.to_string(), scopes: scopes.to_vec(), expires_at: None, revoked: false };
+        write(&self.certificates).insert(fingerprint.to_ascii_lowercase(), grant);
+    }
+
+    /// Stop accepting a client certificate.
+    pub fn revoke_certificate(&self, fingerprint: &str) -> Result<(), IdentityError> {
+        write(&self.certificates)
+            .get_mut(&fingerprint.to_ascii_lowercase())
+            .map(|grant| grant.revoked = true)
+            .ok_or_else(|| IdentityError::UnknownKey(fingerprint.to_string()))
+    }
+
+    /// Issue a signed token for `agent_id` valid for `ttl`.
+    pub fn issue_token(&self, agent_id: &str, scopes: &[Scope], ttl: Duration) -> String {
+        let iat = now();
+        let claims = Claims { sub: agent_id.to_string(), scopes: scopes.to_vec(), iat, exp: iat + ttl.as_secs() };
+        let signing = read(&self.signing);
+        let header = TokenHeader { alg: "EdDSA".to_string(), typ: "JWT".to_string(), kid: signing.kid.to_string() };
+        let payload = format!("{}.{}", encode_json(&header), encode_json(&claims));
+        let signature = signing.current.sign(payload.as_bytes());
+        format!("{}.{}", payload, URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+    }
+
+    /// Start signing with a new key; tokens signed by earlier keys verify for
+    /// at most `grace` (zero invalidates every outstanding token).
+    pub fn rotate_signing_key(&self, grace: Duration) {
+        let mut signing = write(&self.signing);
+        let retire_at = now() + grace.as_secs();
+        for (_, retire) in signing.verifying.values_mut() {
+            *retire = Some(retire.map_or(retire_at, |at| at.min(retire_at)));
+        }
+        signing.verifying.retain(|_, (_, retire)| retire.is_none_or(|at| at > now()));
+        signing.current = SigningKey::generate(&mut OsRng);
+        signing.kid += 1;
+        let (kid, verifying) = (signing.kid, signing.current.verifying_key());
+        signing.verifying.insert(kid, (verifying, None));
+    }
+
+    /// Resolve presented credentials to an identity.
+    pub fn authenticate(&self, presented: &Presented) -> Result<AgentIdentity, IdentityError> {
+        let now = now();
+        let (grant, credential) = match presented {
+            Presented::ApiKey(secret) => {
+                let hash: [u8; 32] = Sha256::digest(secret.as_bytes()).into();
+                let grant = read(&self.keys).get(&hash).map(|(_, g)| g.clone()).ok_or(IdentityError::InvalidCredentials)?;
+                (grant, CredentialKind::ApiKey)
+            }
+            Presented::Certificate(fingerprint) => {
+                let grant = read(&self.certificates).get(fingerprint).cloned().ok_or(IdentityError::InvalidCredentials)?;
+                (grant, CredentialKind::Certificate)
+            }
+            Presented::Token(token) => (self.verify_token(token, now)?, CredentialKind::Token),
+        };
+        grant.check(now)?;
+        Ok(AgentIdentity { agent_id: grant.agent_id, scopes: grant.scopes, credential })
+    }
+
+    fn verify_token(&self, token: &str, now: u64) -> Result<Grant, IdentityError> {
+        let malformed = || IdentityError::Token("malformed".to_string());
+        let (payload, signature) = token.rsplit_once('.').ok_or_else(malformed)?;
+        let (header, claims) = payload.split_once('.').ok_or_else(malformed)?;
+        let header: TokenHeader = decode_json(header).ok_or_else(malformed)?;
+        if header.alg != "EdDSA" {
+            return Err(IdentityError::Token(format!("unsupported alg {}", header.alg)));
+        }
+
+        let kid: u32 = header.kid.parse().map_err(|_| malformed())?;
+        let key = match read(&self.signing).verifying.get(&kid) {
+            Some((key, retire)) if retire.is_none_or(|at| now < at) => *key,
+            _ => return Err(IdentityError::Token("unknown signing key".to_string())),
+        };
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| malformed())?;
+        let signature = Signature::from_slice(&signature).map_err(|_| malformed())?;
+        key.verify(payload.as_bytes(), &signature).map_err(|_| IdentityError::InvalidCredentials)?;
+
+        let claims: Claims = decode_json(claims).ok_or_else(malformed)?;
+        Ok(Grant { agent_id: claims.sub, scopes: claims.scopes, expires_at: Some(claims.exp), revoked: false })
+    }
+}
+
+impl Default for IdentityRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Authentication or authorization failure.
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityError {
+    #[error("missing credentials")]
+    MissingCredentials,
+
+    #[error("invalid credentials")]
+    InvalidCredentials,
+
+    #[error("credentials expired")]
+    Expired,
+
+    #[error("credentials revoked")]
+    Revoked,
+
+    #[error("invalid token: {0}")]
+    Token(String),
+
+    #[error("requires the {} scope", .0.as_str())]
+    InsufficientScope(Scope),
+
+    #[error("not permitted to act for agent {0}")]
+    WrongAgent(String),
+
+    #[error("unknown credential: {0}")]
+    UnknownKey(String),
+}
+
+impl Coded for IdentityError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::MissingCredentials | Self::InvalidCredentials | Self::Expired | Self::Revoked | Self::Token(_) => {
+                ErrorCode::Unauthenticated
+            }
+            Self::InsufficientScope(_) | Self::WrongAgent(_) => ErrorCode::PermissionDenied,
+            Self::UnknownKey(_) => ErrorCode::NotFound,
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn encode_json(value: &impl Serialize) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(value).unwrap_or_default())
+}
+
+fn decode_json<T: for<'de> Deserialize<'de>>(part: &str) -> Option<T> {
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).ok()?).ok()
+}
+
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_rotation_and_revocation() {
+        let registry = IdentityRegistry::new();
+        let old = registry.issue_api_key("agent-1", &[Scope::Verify]);
+        let identity = registry.authenticate(&Presented::ApiKey(old.secret.clone())).unwrap();
+        assert_eq!(identity.agent_id, "agent-1");
+        assert!(identity.allows(Scope::Verify));
+        assert!(matches!(identity.require(Scope::Payments), Err(IdentityError::InsufficientScope(Scope::Payments))));
+
+        // Both secrets work during the grace period
+        let new = registry.rotate_api_key(&old.key_id, Duration::from_secs(3600)).unwrap();
+        assert!(registry.authenticate(&Presented::ApiKey(old.secret.clone())).is_ok());
+        assert!(registry.authenticate(&Presented::ApiKey(new.secret.clone())).is_ok());
+
+        // Without grace the old secret stops immediately
+        let newer = registry.rotate_api_key(&new.key_id, Duration::ZERO).unwrap();
+        assert!(matches!(registry.authenticate(&Presented::ApiKey(new.secret)), Err(IdentityError::Expired)));
+
+        registry.revoke_api_key(&newer.key_id).unwrap();
+        assert!(matches!(registry.authenticate(&Presented::ApiKey(newer.secret)), Err(IdentityError::Revoked)));
+        assert!(matches!(
+            registry.authenticate(&Presented::ApiKey("ak_unknown".to_string())),
+            Err(IdentityError::InvalidCredentials)
+        ));
+    }
+
+    #[test]
+    fn test_signed_tokens() {
+        let registry = IdentityRegistry::new();
+        let token = registry.issue_token("agent-2", &[Scope::Payments], Duration::from_secs(60));
+        let identity = registry.authenticate(&Presented::Token(token.clone())).unwrap();
+        assert_eq!(identity.credential, CredentialKind::Token);
+        assert!(identity.allows(Scope::Payments));
+
+        // Tampered claims fail the signature check
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let forged = encode_json(&Claims { sub: "agent-2".into(), scopes: vec![Scope::Admin], iat: 0, exp: u64::MAX });
+        parts[1] = &forged;
+        assert!(registry.authenticate(&Presented::Token(parts.join("."))).is_err());
+
+        let expired = registry.issue_token("agent-2", &[Scope::Payments], Duration::ZERO);
+        assert!(matches!(registry.authenticate(&Presented::Token(expired)), Err(IdentityError::Expired)));
+
+        // Old tokens verify during the signing key's grace period only
+        registry.rotate_signing_key(Duration::from_secs(60));
+        assert!(registry.authenticate(&Presented::Token(token.clone())).is_ok());
+        registry.rotate_signing_key(Duration::ZERO);
+        assert!(registry.authenticate(&Presented::Token(token)).is_err());
+    }
+
+    #[test]
+    fn test_certificates_and_headers() {
+        let registry = IdentityRegistry::new();
+        let fingerprint = certificate_fingerprint(b"client-cert-der");
+        registry.bind_certificate(&fingerprint, "agent-3", &[Scope::Admin]);
+
+        // Anyone can send the header; it only counts behind a trusted proxy
+        let headers = HashMap::from([(CERT_HEADER, fingerprint.to_uppercase())]);
+        assert_eq!(registry.presented(|name| headers.get(name).map(String::as_str)), None);
+        let registry = registry.with_trusted_proxy();
+        let presented = registry.presented(|name| headers.get(name).map(String::as_str)).unwrap();
+        let identity = registry.authenticate(&presented).unwrap();
+        assert_eq!(identity.credential, CredentialKind::Certificate);
+        assert!(identity.require_agent("someone-else").is_ok());
+
+        let bearer = HashMap::from([("authorization", "Bearer ak_abc".to_string())]);
+        assert_eq!(
+            Presented::from_headers(|name| bearer.get(name).map(String::as_str)),
+            Some(Presented::ApiKey("ak_abc".to_string()))
+        );
+        assert_eq!(Presented::from_headers(|_| None), None);
+    }
+}
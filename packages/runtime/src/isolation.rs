@@ -4,8 +4,19 @@
 //! AgentKern uses WASM Components (Nano-Light), NOT Docker (Heavy)
 //!
 //! Priority: WASM > Container > Process
+//!
+//! [`wasi::WasiExecutor`] runs agent plugins in WASI sandboxes (`wasm` feature).
+//! [`microvm::MicroVmExecutor`] runs them inside Firecracker/Kata microVMs.
+//! [`Sandbox`] picks one from [`IsolationConfig`].
+
+pub mod microvm;
+pub mod wasi;
 
+use microvm::{MicroVmConfig, MicroVmError, MicroVmExecutor};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+use wasi::{PluginOutput, PluginSpec, WasiError, WasiExecutor};
 
 /// Isolation mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,6 +34,10 @@ pub enum IsolationMode {
     /// Process isolation (minimal)
     /// For development/testing only
     Process,
+
+    /// Hardware-virtualized guest per run (Firecracker or Kata)
+    /// For untrusted code; must be selected explicitly
+    MicroVm,
 }
 
 impl Default for IsolationMode {
@@ -40,6 +55,9 @@ pub struct IsolationConfig {
     pub fallback: Option<IsolationMode>,
     /// WASM-specific config
     pub wasm: WasmConfig,
+    /// MicroVM-specific config
+    #[serde(default)]
+    pub microvm: MicroVmConfig,
 }
 
 impl Default for IsolationConfig {
@@ -48,6 +66,7 @@ impl Default for IsolationConfig {
             mode: IsolationMode::Wasm,
             fallback: Some(IsolationMode::Container),
             wasm: WasmConfig::default(),
+            microvm: MicroVmConfig::default(),
         }
     }
 }
@@ -134,6 +153,82 @@ fn container_runtime_available() -> bool {
         || std::path::Path::new("/run/podman/podman.sock").exists()
 }
 
+/// Sandbox errors.
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error(transparent)]
+    Wasi(#[from] WasiError),
+    #[error(transparent)]
+    MicroVm(#[from] MicroVmError),
+    #[error("Isolation mode {0:?} cannot run plugins")]
+    Unsupported(IsolationMode),
+}
+
+/// Plugin executor selected by [`IsolationConfig`].
+pub enum Sandbox {
+    /// In-process WASI sandbox
+    Wasi(Box<WasiExecutor>),
+    /// One microVM per run
+    MicroVm(Box<MicroVmExecutor>),
+}
+
+impl Sandbox {
+    /// Create the executor for `config.mode`, trying `config.fallback` if it
+    /// is unavailable. Reports the primary mode's error if both fail.
+    pub fn from_config(config: &IsolationConfig) -> Result<Self, SandboxError> {
+        match Self::for_mode(config.mode, config) {
+            Ok(sandbox) => Ok(sandbox),
+            Err(e) => match config.fallback {
+                Some(fallback) if fallback != config.mode => {
+                    tracing::warn!("Isolation mode {:?} unavailable ({}), trying {:?}", config.mode, e, fallback);
+                    Self::for_mode(fallback, config).map_err(|_| e)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    fn for_mode(mode: IsolationMode, config: &IsolationConfig) -> Result<Self, SandboxError> {
+        match mode {
+            IsolationMode::Wasm => Ok(Self::Wasi(Box::new(WasiExecutor::new(&config.wasm)?))),
+            IsolationMode::MicroVm => Ok(Self::MicroVm(Box::new(MicroVmExecutor::new(&config.microvm, &config.wasm)?))),
+            other => Err(SandboxError::Unsupported(other)),
+        }
+    }
+
+    /// Mode actually in use.
+    pub fn mode(&self) -> IsolationMode {
+        match self {
+            Self::Wasi(_) => IsolationMode::Wasm,
+            Self::MicroVm(_) => IsolationMode::MicroVm,
+        }
+    }
+
+    /// Load a plugin from bytes.
+    pub fn load(&mut self, name: impl Into<String>, bytes: &[u8]) -> Result<(), SandboxError> {
+        match self {
+            Self::Wasi(executor) => Ok(executor.load(name, bytes)?),
+            Self::MicroVm(executor) => Ok(executor.load(name, bytes)?),
+        }
+    }
+
+    /// Load a plugin from disk.
+    pub fn load_file(&mut self, name: impl Into<String>, path: impl AsRef<Path>) -> Result<(), SandboxError> {
+        match self {
+            Self::Wasi(executor) => Ok(executor.load_file(name, path)?),
+            Self::MicroVm(executor) => Ok(executor.load_file(name, path)?),
+        }
+    }
+
+    /// Run a loaded plugin.
+    pub async fn run(&self, name: &str, spec: &PluginSpec) -> Result<PluginOutput, SandboxError> {
+        match self {
+            Self::Wasi(executor) => Ok(executor.run(name, spec).await?),
+            Self::MicroVm(executor) => Ok(executor.run(name, spec).await?),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +244,27 @@ mod tests {
         assert_eq!(config.mode, IsolationMode::Wasm);
         assert!(config.wasm.verify_capabilities);
     }
+
+    #[test]
+    fn test_sandbox_reports_primary_error() {
+        let config = IsolationConfig {
+            mode: IsolationMode::MicroVm,
+            fallback: Some(IsolationMode::Process),
+            microvm: MicroVmConfig {
+                kernel_image: "/nonexistent/vmlinux".into(),
+                ..MicroVmConfig::default()
+            },
+            ..IsolationConfig::default()
+        };
+        assert!(matches!(
+            Sandbox::from_config(&config),
+            Err(SandboxError::MicroVm(MicroVmError::Unavailable(_)))
+        ));
+
+        let config = IsolationConfig { mode: IsolationMode::Process, fallback: None, ..config };
+        assert!(matches!(
+            Sandbox::from_config(&config),
+            Err(SandboxError::Unsupported(IsolationMode::Process))
+        ));
+    }
 }
@@ -0,0 +1,880 @@
+//! MicroVM Isolation Backend
+//!
+//! Runs untrusted agent plugins inside a hardware-virtualized guest, one VM
+//! per run, for workloads where a WASI sandbox alone is not enough:
+//! - Firecracker: the VMM is driven over its API socket; the plugin is staged
+//!   into a read-only task drive (ext4, built with `mkfs.ext4 -d`) next to a
+//!   shared read-only guest rootfs
+//! - Kata Containers: the task directory is bind-mounted into a Kata pod via
+//!   containerd (`ctr run --runtime io.containerd.kata.v2`)
+//!
+//! Inside the guest, `agentkern-guest` runs the module under wasmtime with
+//! the same [`PluginSpec`] grants and [`ResourceLimits`] as
+//! [`WasiExecutor`](super::wasi::WasiExecutor) (WASM-in-VM). Firecracker
+//! guests are driven over a vsock control channel: the host connects to the
+//! VMM's vsock socket, sends `CONNECT <guest_port>`, then exchanges one JSON
+//! line each way.
+//!
+//! Guests have no network device and a read-only task drive, so only
+//! read-only filesystem grants are supported; granted directories are copied
+//! into the task drive and preopened at the same path in the guest.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_runtime::isolation::microvm::{MicroVmConfig, MicroVmExecutor};
+//!
+//! let config = MicroVmConfig {
+//!     kernel_image: "/var/lib/agentkern/microvm/vmlinux".into(),
+//!     rootfs: "/var/lib/agentkern/microvm/rootfs.ext4".into(),
+//!     ..MicroVmConfig::default()
+//! };
+//! let mut executor = MicroVmExecutor::new(&config, &WasmConfig::default())?;
+//! executor.load_file("scraper", "/opt/agentkern/plugins/scraper.wasm")?;
+//! let output = executor.run("scraper", &PluginSpec::new().arg("--once")).await?;
+//! ```
+
+use super::wasi::{check_grants, PluginOutput, PluginSpec, ResourceLimits, WasiError};
+use super::{WasiCapability, WasmConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Guest context ID assigned to every VM (each VM has its own vsock socket).
+const GUEST_CID: u32 = 3;
+
+/// Extra time allowed past the plugin timeout for the guest to report back.
+const RESULT_GRACE: Duration = Duration::from_secs(2);
+
+/// MicroVM errors.
+#[derive(Debug, Error)]
+pub enum MicroVmError {
+    #[error("MicroVM isolation unavailable: {0}")]
+    Unavailable(String),
+    #[error("Capability denied: {0}")]
+    CapabilityDenied(String),
+    #[error("Plugin not found: {0}")]
+    PluginNotFound(String),
+    #[error("Failed to assemble task drive: {0}")]
+    Rootfs(String),
+    #[error("VMM error: {0}")]
+    Vmm(String),
+    #[error("Guest did not come up within {0:?}")]
+    Boot(Duration),
+    #[error("Control channel error: {0}")]
+    Control(String),
+    #[error("Plugin {name} exceeded its {timeout:?} timeout")]
+    Timeout { name: String, timeout: Duration },
+    #[error("Plugin {name} failed in guest: {reason}")]
+    Guest { name: String, reason: String },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Virtual machine monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vmm {
+    /// Firecracker, driven directly (needs `/dev/kvm`)
+    Firecracker,
+    /// Kata Containers through containerd
+    Kata,
+}
+
+/// MicroVM configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicroVmConfig {
+    /// Which VMM launches the guest
+    pub vmm: Vmm,
+    /// Firecracker binary (name on `PATH` or absolute path)
+    pub firecracker_bin: PathBuf,
+    /// Uncompressed guest kernel
+    pub kernel_image: PathBuf,
+    /// Guest root filesystem with `agentkern-guest` (attached read-only)
+    pub rootfs: PathBuf,
+    /// Kernel command line
+    pub boot_args: String,
+    /// Virtual CPUs per VM
+    pub vcpus: u8,
+    /// Guest memory per VM, in MiB
+    pub memory_mb: u32,
+    /// Vsock port `agentkern-guest` listens on
+    pub guest_port: u32,
+    /// Per-run scratch space (sockets, task drives, logs)
+    pub work_dir: PathBuf,
+    /// How long the guest may take to accept the control connection
+    pub boot_timeout_secs: u64,
+    /// containerd CLI used for Kata
+    pub ctr_bin: PathBuf,
+    /// containerd runtime handler for Kata
+    pub kata_runtime: String,
+    /// Guest image (with `agentkern-guest`) for Kata
+    pub kata_image: String,
+}
+
+impl Default for MicroVmConfig {
+    fn default() -> Self {
+        Self {
+            vmm: Vmm::Firecracker,
+            firecracker_bin: PathBuf::from("firecracker"),
+            kernel_image: PathBuf::from("/var/lib/agentkern/microvm/vmlinux"),
+            rootfs: PathBuf::from("/var/lib/agentkern/microvm/rootfs.ext4"),
+            boot_args: "console=ttyS0 reboot=k panic=1 pci=off".to_string(),
+            vcpus: 1,
+            memory_mb: 256,
+            guest_port: 5000,
+            work_dir: std::env::temp_dir().join("agentkern-microvm"),
+            boot_timeout_secs: 10,
+            ctr_bin: PathBuf::from("ctr"),
+            kata_runtime: "io.containerd.kata.v2".to_string(),
+            kata_image: "docker.io/agentkern/guest:latest".to_string(),
+        }
+    }
+}
+
+impl MicroVmConfig {
+    /// Boot timeout as a `Duration`.
+    pub fn boot_timeout(&self) -> Duration {
+        Duration::from_secs(self.boot_timeout_secs)
+    }
+}
+
+/// Check that the configured VMM can run here. `Err` explains what is missing.
+pub fn microvm_available(config: &MicroVmConfig) -> Result<(), MicroVmError> {
+    let missing = |what: String| Err(MicroVmError::Unavailable(what));
+    match config.vmm {
+        Vmm::Firecracker => {
+            if !cfg!(target_os = "linux") {
+                return missing("Firecracker requires Linux".to_string());
+            }
+            if !Path::new("/dev/kvm").exists() {
+                return missing("/dev/kvm not present".to_string());
+            }
+            if find_binary(&config.firecracker_bin).is_none() {
+                return missing(format!("{} not found", config.firecracker_bin.display()));
+            }
+            for (what, path) in [("kernel", &config.kernel_image), ("rootfs", &config.rootfs)] {
+                if !path.is_file() {
+                    return missing(format!("guest {} {} not found", what, path.display()));
+                }
+            }
+            if find_binary(Path::new("mkfs.ext4")).is_none() {
+                return missing("mkfs.ext4 not found (needed for task drives)".to_string());
+            }
+        }
+        Vmm::Kata => {
+            if find_binary(&config.ctr_bin).is_none() {
+                return missing(format!("{} not found", config.ctr_bin.display()));
+            }
+            if !Path::new("/run/containerd/containerd.sock").exists() {
+                return missing("containerd socket not found".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a binary name against `PATH`; paths are checked as-is.
+fn find_binary(binary: &Path) -> Option<PathBuf> {
+    if binary.components().count() > 1 {
+        return binary.is_file().then(|| binary.to_path_buf());
+    }
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(binary))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Check grants against the ceiling and what a guest can provide.
+pub fn check_vm_grants(ceiling: &[WasiCapability], spec: &PluginSpec) -> Result<(), MicroVmError> {
+    check_grants(ceiling, spec).map_err(|e| match e {
+        WasiError::CapabilityDenied(what) => MicroVmError::CapabilityDenied(what),
+        other => MicroVmError::CapabilityDenied(other.to_string()),
+    })?;
+    for capability in &spec.capabilities {
+        match capability {
+            WasiCapability::Network { .. } => {
+                return Err(MicroVmError::CapabilityDenied(
+                    "network access inside a microVM (guests have no network device)".to_string(),
+                ));
+            }
+            WasiCapability::Filesystem { path, readonly: false } => {
+                return Err(MicroVmError::CapabilityDenied(format!(
+                    "write access to {} inside a microVM (the task drive is read-only)",
+                    path
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Guest protocol
+// ============================================================================
+
+/// Limits as passed to the guest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestLimits {
+    pub memory_bytes: usize,
+    pub fuel: u64,
+    pub timeout_ms: u64,
+    pub max_output_bytes: usize,
+}
+
+impl From<&ResourceLimits> for GuestLimits {
+    fn from(limits: &ResourceLimits) -> Self {
+        Self {
+            memory_bytes: limits.memory_bytes,
+            fuel: limits.fuel,
+            timeout_ms: limits.timeout.as_millis() as u64,
+            max_output_bytes: limits.max_output_bytes,
+        }
+    }
+}
+
+/// `task.json` on the task drive: what the guest runs and how.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskManifest {
+    /// Module file, relative to the task root
+    pub module: String,
+    /// Command-line arguments
+    pub args: Vec<String>,
+    /// Environment variables
+    pub env: Vec<(String, String)>,
+    /// Guest paths to preopen read-only (staged under `fs/`)
+    pub preopens: Vec<String>,
+    /// Clock/random/environment grants
+    pub capabilities: Vec<WasiCapability>,
+    /// Resource limits enforced by the guest's wasmtime
+    pub limits: GuestLimits,
+}
+
+/// Host → guest control message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Run the task at `task_root/task.json`
+    Run { task_root: String },
+}
+
+/// Guest → host result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    /// `wasi:cli/run` returned `ok`
+    pub success: bool,
+    /// Captured stdout (lossy UTF-8)
+    #[serde(default)]
+    pub stdout: String,
+    /// Captured stderr (lossy UTF-8)
+    #[serde(default)]
+    pub stderr: String,
+    /// Fuel used
+    #[serde(default)]
+    pub fuel_consumed: u64,
+    /// Trap, limit or setup failure reported by the guest
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Where the task drive is mounted in the guest.
+const GUEST_TASK_ROOT: &str = "/task";
+
+// ============================================================================
+// Task drive assembly
+// ============================================================================
+
+/// Stage a run's files into `dir`: `module.wasm`, `task.json` and copies of
+/// read-only filesystem grants under `fs/`. Returns the staged byte count.
+pub fn stage_task(
+    dir: &Path,
+    module: &[u8],
+    spec: &PluginSpec,
+    limits: &ResourceLimits,
+) -> Result<u64, MicroVmError> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join("module.wasm"), module)?;
+    let mut staged = module.len() as u64;
+
+    let mut preopens = Vec::new();
+    for capability in &spec.capabilities {
+        if let WasiCapability::Filesystem { path, .. } = capability {
+            let source = Path::new(path);
+            let relative = source.strip_prefix("/").unwrap_or(source);
+            staged += copy_tree(source, &dir.join("fs").join(relative))
+                .map_err(|e| MicroVmError::Rootfs(format!("staging {}: {}", path, e)))?;
+            preopens.push(path.clone());
+        }
+    }
+
+    let manifest = TaskManifest {
+        module: "module.wasm".to_string(),
+        args: spec.args.clone(),
+        env: spec.env.clone(),
+        preopens,
+        capabilities: spec
+            .capabilities
+            .iter()
+            .filter(|c| !matches!(c, WasiCapability::Filesystem { .. } | WasiCapability::Network { .. }))
+            .cloned()
+            .collect(),
+        limits: GuestLimits::from(limits),
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| MicroVmError::Rootfs(e.to_string()))?;
+    staged += json.len() as u64;
+    std::fs::write(dir.join("task.json"), json)?;
+    Ok(staged)
+}
+
+/// Copy a file or directory tree. Symlinks are skipped, not followed.
+fn copy_tree(source: &Path, target: &Path) -> std::io::Result<u64> {
+    let meta = std::fs::symlink_metadata(source)?;
+    if meta.is_file() {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        return std::fs::copy(source, target);
+    }
+    let mut copied = 0;
+    std::fs::create_dir_all(target)?;
+    if meta.is_dir() {
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            if entry.file_type()?.is_symlink() {
+                continue;
+            }
+            copied += copy_tree(&entry.path(), &target.join(entry.file_name()))?;
+        }
+    }
+    Ok(copied)
+}
+
+/// Build a read-only ext4 task drive from a staged directory.
+pub async fn build_task_drive(staging: &Path, image: &Path, staged_bytes: u64) -> Result<(), MicroVmError> {
+    // ext4 metadata overhead plus headroom; mkfs.ext4 rejects tiny images.
+    let size_mb = staged_bytes * 2 / (1024 * 1024) + 16;
+    let output = tokio::process::Command::new("mkfs.ext4")
+        .args(["-q", "-F", "-L", "agentkern-task", "-d"])
+        .arg(staging)
+        .arg(image)
+        .arg(format!("{}M", size_mb))
+        .output()
+        .await
+        .map_err(|e| MicroVmError::Rootfs(format!("mkfs.ext4: {}", e)))?;
+    if !output.status.success() {
+        return Err(MicroVmError::Rootfs(format!(
+            "mkfs.ext4 failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Firecracker
+// ============================================================================
+
+#[cfg(unix)]
+mod firecracker {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    /// Issue one `PUT` against the Firecracker API socket.
+    pub async fn api_put(socket: &Path, path: &str, body: &serde_json::Value) -> Result<(), MicroVmError> {
+        let body = body.to_string();
+        let mut stream = UnixStream::connect(socket).await?;
+        let request = format!(
+            "PUT {} HTTP/1.1\r\nHost: localhost\r\nAccept: application/json\r\n\
+             Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut reader = BufReader::new(stream);
+        let mut status = String::new();
+        reader.read_line(&mut status).await?;
+        let code: u16 = status
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| MicroVmError::Vmm(format!("malformed response to PUT {}: {:?}", path, status.trim())))?;
+
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        if (200..300).contains(&code) {
+            return Ok(());
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await?;
+        Err(MicroVmError::Vmm(format!(
+            "PUT {} returned {}: {}",
+            path,
+            code,
+            String::from_utf8_lossy(&body).trim()
+        )))
+    }
+
+    /// Configure a freshly spawned Firecracker process and start the guest.
+    pub async fn configure(
+        api: &Path,
+        config: &MicroVmConfig,
+        task_drive: &Path,
+        vsock: &Path,
+    ) -> Result<(), MicroVmError> {
+        let steps = [
+            (
+                "/machine-config",
+                serde_json::json!({ "vcpu_count": config.vcpus, "mem_size_mib": config.memory_mb }),
+            ),
+            (
+                "/boot-source",
+                serde_json::json!({ "kernel_image_path": config.kernel_image, "boot_args": config.boot_args }),
+            ),
+            (
+                "/drives/rootfs",
+                serde_json::json!({
+                    "drive_id": "rootfs",
+                    "path_on_host": config.rootfs,
+                    "is_root_device": true,
+                    "is_read_only": true,
+                }),
+            ),
+            (
+                "/drives/task",
+                serde_json::json!({
+                    "drive_id": "task",
+                    "path_on_host": task_drive,
+                    "is_root_device": false,
+                    "is_read_only": true,
+                }),
+            ),
+            ("/vsock", serde_json::json!({ "guest_cid": GUEST_CID, "uds_path": vsock })),
+            ("/actions", serde_json::json!({ "action_type": "InstanceStart" })),
+        ];
+        for (path, body) in &steps {
+            api_put(api, path, body).await?;
+        }
+        Ok(())
+    }
+
+    /// Open the control channel: connect to the VMM's vsock socket and
+    /// forward to `port` in the guest. Retries until `deadline` while the
+    /// guest boots.
+    pub async fn connect_guest(vsock: &Path, port: u32, deadline: Instant) -> Result<BufReader<UnixStream>, MicroVmError> {
+        let boot = deadline.saturating_duration_since(Instant::now());
+        loop {
+            if let Ok(stream) = UnixStream::connect(vsock).await {
+                let mut stream = BufReader::new(stream);
+                stream.get_mut().write_all(format!("CONNECT {}\n", port).as_bytes()).await?;
+                let mut ack = String::new();
+                // Firecracker closes the connection if nothing listens on the port yet.
+                if stream.read_line(&mut ack).await.is_ok() && ack.starts_with("OK ") {
+                    return Ok(stream);
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(MicroVmError::Boot(boot));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Send one request and wait for the guest's response line.
+    pub async fn exchange(
+        channel: &mut BufReader<UnixStream>,
+        request: &ControlRequest,
+    ) -> Result<ControlResponse, MicroVmError> {
+        let mut line = serde_json::to_string(request).map_err(|e| MicroVmError::Control(e.to_string()))?;
+        line.push('\n');
+        channel.get_mut().write_all(line.as_bytes()).await?;
+
+        let mut response = String::new();
+        if channel.read_line(&mut response).await? == 0 {
+            return Err(MicroVmError::Control("guest closed the channel without a result".to_string()));
+        }
+        serde_json::from_str(&response).map_err(|e| MicroVmError::Control(format!("malformed result: {}", e)))
+    }
+
+    /// Wait for the API socket to appear after spawning the VMM.
+    pub async fn wait_for_socket(path: &Path, deadline: Instant) -> Result<(), MicroVmError> {
+        while !path.exists() {
+            if Instant::now() >= deadline {
+                return Err(MicroVmError::Vmm(format!("API socket {} never appeared", path.display())));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Executor
+// ============================================================================
+
+/// Runs WASM plugins inside per-run microVMs.
+pub struct MicroVmExecutor {
+    config: MicroVmConfig,
+    modules: HashMap<String, Vec<u8>>,
+    ceiling: Vec<WasiCapability>,
+    verify_capabilities: bool,
+    default_limits: ResourceLimits,
+    runs: AtomicU64,
+}
+
+impl MicroVmExecutor {
+    /// Create an executor; fails if the VMM is not usable here.
+    pub fn new(config: &MicroVmConfig, wasm: &WasmConfig) -> Result<Self, MicroVmError> {
+        microvm_available(config)?;
+        std::fs::create_dir_all(&config.work_dir)?;
+        Ok(Self {
+            config: config.clone(),
+            modules: HashMap::new(),
+            ceiling: wasm.capabilities.clone(),
+            verify_capabilities: wasm.verify_capabilities,
+            default_limits: ResourceLimits::from_config(wasm),
+            runs: AtomicU64::new(0),
+        })
+    }
+
+    /// The VMM in use.
+    pub fn vmm(&self) -> Vmm {
+        self.config.vmm
+    }
+
+    /// Register a module (compiled inside the guest at run time).
+    pub fn load(&mut self, name: impl Into<String>, bytes: &[u8]) -> Result<(), MicroVmError> {
+        self.modules.insert(name.into(), bytes.to_vec());
+        Ok(())
+    }
+
+    /// Register a module from disk.
+    pub fn load_file(&mut self, name: impl Into<String>, path: impl AsRef<Path>) -> Result<(), MicroVmError> {
+        let bytes = std::fs::read(path)?;
+        self.load(name, &bytes)
+    }
+
+    /// Remove a module. Returns whether it was loaded.
+    pub fn unload(&mut self, name: &str) -> bool {
+        self.modules.remove(name).is_some()
+    }
+
+    /// Loaded module names, sorted.
+    pub fn plugins(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.modules.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Boot a fresh VM, run the plugin in it and tear the VM down.
+    pub async fn run(&self, name: &str, spec: &PluginSpec) -> Result<PluginOutput, MicroVmError> {
+        let module = self
+            .modules
+            .get(name)
+            .ok_or_else(|| MicroVmError::PluginNotFound(name.to_string()))?;
+        if self.verify_capabilities {
+            check_vm_grants(&self.ceiling, spec)?;
+        }
+        let limits = spec.limits.clone().unwrap_or_else(|| self.default_limits.clone());
+
+        let id = format!(
+            "{}-{}-{}",
+            name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+            std::process::id(),
+            self.runs.fetch_add(1, Ordering::Relaxed)
+        );
+        let work = self.config.work_dir.join(&id);
+        let started = Instant::now();
+        let outcome = async {
+            let staged = stage_task(&work.join("task"), module, spec, &limits)?;
+            match self.config.vmm {
+                Vmm::Firecracker => self.run_firecracker(&id, &work, staged, &limits).await,
+                Vmm::Kata => self.run_kata(&id, &work, &limits).await,
+            }
+        }
+        .await;
+        if let Err(e) = std::fs::remove_dir_all(&work) {
+            tracing::warn!("Failed to clean up microVM work dir {}: {}", work.display(), e);
+        }
+
+        let response = match outcome {
+            Err(MicroVmError::Timeout { .. }) => {
+                return Err(MicroVmError::Timeout { name: name.to_string(), timeout: limits.timeout });
+            }
+            other => other?,
+        };
+        if let Some(reason) = response.error {
+            return Err(MicroVmError::Guest { name: name.to_string(), reason });
+        }
+        Ok(PluginOutput {
+            success: response.success,
+            stdout: response.stdout.into_bytes(),
+            stderr: response.stderr.into_bytes(),
+            fuel_consumed: response.fuel_consumed,
+            duration: started.elapsed(),
+        })
+    }
+
+    #[cfg(unix)]
+    async fn run_firecracker(
+        &self,
+        id: &str,
+        work: &Path,
+        staged: u64,
+        limits: &ResourceLimits,
+    ) -> Result<ControlResponse, MicroVmError> {
+        let drive = work.join("task.ext4");
+        build_task_drive(&work.join("task"), &drive, staged).await?;
+
+        let api = work.join("api.sock");
+        let vsock = work.join("vsock.sock");
+        let log = std::fs::File::create(work.join("firecracker.log"))?;
+        let mut vmm = tokio::process::Command::new(&self.config.firecracker_bin)
+            .arg("--api-sock")
+            .arg(&api)
+            .args(["--id", id])
+            .stdin(std::process::Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| MicroVmError::Vmm(format!("spawning {}: {}", self.config.firecracker_bin.display(), e)))?;
+
+        let result = async {
+            let boot_deadline = Instant::now() + self.config.boot_timeout();
+            firecracker::wait_for_socket(&api, boot_deadline).await?;
+            firecracker::configure(&api, &self.config, &drive, &vsock).await?;
+            let mut channel = firecracker::connect_guest(&vsock, self.config.guest_port, boot_deadline).await?;
+            let request = ControlRequest::Run { task_root: GUEST_TASK_ROOT.to_string() };
+            tokio::time::timeout(limits.timeout + RESULT_GRACE, firecracker::exchange(&mut channel, &request))
+                .await
+                .map_err(|_| MicroVmError::Timeout { name: id.to_string(), timeout: limits.timeout })?
+        }
+        .await;
+
+        if let Err(e) = vmm.kill().await {
+            tracing::warn!("Failed to stop Firecracker {}: {}", id, e);
+        }
+        result
+    }
+
+    #[cfg(not(unix))]
+    async fn run_firecracker(
+        &self,
+        _id: &str,
+        _work: &Path,
+        _staged: u64,
+        _limits: &ResourceLimits,
+    ) -> Result<ControlResponse, MicroVmError> {
+        Err(MicroVmError::Unavailable("Firecracker requires Linux".to_string()))
+    }
+
+    async fn run_kata(&self, id: &str, work: &Path, limits: &ResourceLimits) -> Result<ControlResponse, MicroVmError> {
+        let mount = format!(
+            "type=bind,src={},dst={},options=rbind:ro",
+            work.join("task").display(),
+            GUEST_TASK_ROOT
+        );
+        let child = tokio::process::Command::new(&self.config.ctr_bin)
+            .args(["run", "--rm", "--runtime", &self.config.kata_runtime, "--mount", &mount])
+            .args([&self.config.kata_image, id, "agentkern-guest", "--task-root", GUEST_TASK_ROOT])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| MicroVmError::Vmm(format!("spawning {}: {}", self.config.ctr_bin.display(), e)))?;
+
+        let budget = self.config.boot_timeout() + limits.timeout + RESULT_GRACE;
+        let output = match tokio::time::timeout(budget, child.wait_with_output()).await {
+            Ok(output) => output?,
+            Err(_) => {
+                let _ = tokio::process::Command::new(&self.config.ctr_bin)
+                    .args(["task", "kill", "--signal", "SIGKILL", id])
+                    .status()
+                    .await;
+                return Err(MicroVmError::Timeout { name: id.to_string(), timeout: limits.timeout });
+            }
+        };
+        if !output.status.success() && output.stdout.is_empty() {
+            return Err(MicroVmError::Vmm(format!(
+                "ctr run failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| MicroVmError::Control(format!("malformed result: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agentkern-microvm-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_vm_grants() {
+        let ceiling = vec![
+            WasiCapability::Clock,
+            WasiCapability::Filesystem { path: "/srv".to_string(), readonly: false },
+            WasiCapability::Network { hosts: vec!["*".to_string()] },
+        ];
+        let read = PluginSpec::new()
+            .grant(WasiCapability::Clock)
+            .grant(WasiCapability::Filesystem { path: "/srv/data".to_string(), readonly: true });
+        assert!(check_vm_grants(&ceiling, &read).is_ok());
+
+        let write = PluginSpec::new().grant(WasiCapability::Filesystem { path: "/srv".to_string(), readonly: false });
+        let net = PluginSpec::new().grant(WasiCapability::Network { hosts: vec!["example.com".to_string()] });
+        let random = PluginSpec::new().grant(WasiCapability::Random);
+        for spec in [write, net, random] {
+            assert!(matches!(check_vm_grants(&ceiling, &spec), Err(MicroVmError::CapabilityDenied(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stage_task_and_build_drive() {
+        let root = scratch("stage");
+        let data = root.join("data");
+        std::fs::create_dir_all(data.join("nested")).unwrap();
+        std::fs::write(data.join("nested/input.txt"), "hello").unwrap();
+
+        let spec = PluginSpec::new()
+            .grant(WasiCapability::Environment)
+            .grant(WasiCapability::Filesystem { path: data.display().to_string(), readonly: true })
+            .arg("--once")
+            .env("MODE", "batch");
+        let staging = root.join("task");
+        let staged = stage_task(&staging, b"\0asm", &spec, &ResourceLimits::default()).unwrap();
+        assert!(staged > 9);
+
+        let relative = data.strip_prefix("/").unwrap();
+        let copied = staging.join("fs").join(relative).join("nested/input.txt");
+        assert_eq!(std::fs::read_to_string(copied).unwrap(), "hello");
+
+        let manifest: TaskManifest =
+            serde_json::from_slice(&std::fs::read(staging.join("task.json")).unwrap()).unwrap();
+        assert_eq!(manifest.module, "module.wasm");
+        assert_eq!(manifest.args, ["--once"]);
+        assert_eq!(manifest.preopens, [data.display().to_string()]);
+        assert_eq!(manifest.capabilities, [WasiCapability::Environment]);
+        assert_eq!(manifest.limits.timeout_ms, 5_000);
+
+        if find_binary(Path::new("mkfs.ext4")).is_some() {
+            let image = root.join("task.ext4");
+            build_task_drive(&staging, &image, staged).await.unwrap();
+            assert!(std::fs::metadata(&image).unwrap().len() >= 16 * 1024 * 1024);
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_firecracker_api_and_control_channel() {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+        use tokio::net::UnixListener;
+
+        let root = scratch("vmm");
+        let api = root.join("api.sock");
+        let listener = UnixListener::bind(&api).unwrap();
+        let vmm = tokio::spawn(async move {
+            let mut paths = Vec::new();
+            for _ in 0..6 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut stream = BufReader::new(stream);
+                let mut head = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).await.unwrap();
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let mut body = vec![0; length];
+                stream.read_exact(&mut body).await.unwrap();
+                let path = head.split_whitespace().nth(1).unwrap().to_string();
+                let reply: &[u8] = if path == "/vsock" {
+                    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 28\r\n\r\n{\"fault_message\":\"no vsock\"}"
+                } else {
+                    b"HTTP/1.1 204 No Content\r\n\r\n"
+                };
+                stream.get_mut().write_all(reply).await.unwrap();
+                paths.push(path);
+                if reply.starts_with(b"HTTP/1.1 400") {
+                    break;
+                }
+            }
+            paths
+        });
+
+        let config = MicroVmConfig::default();
+        let err = firecracker::configure(&api, &config, &root.join("task.ext4"), &root.join("vsock.sock"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("PUT /vsock returned 400"));
+        assert_eq!(
+            vmm.await.unwrap(),
+            ["/machine-config", "/boot-source", "/drives/rootfs", "/drives/task", "/vsock"]
+        );
+
+        // Guest side of the vsock control channel
+        let vsock = root.join("vsock.sock");
+        let listener = UnixListener::bind(&vsock).unwrap();
+        let guest = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut connect = String::new();
+            stream.read_line(&mut connect).await.unwrap();
+            assert_eq!(connect, "CONNECT 5000\n");
+            stream.get_mut().write_all(b"OK 1073741824\n").await.unwrap();
+            let mut request = String::new();
+            stream.read_line(&mut request).await.unwrap();
+            assert_eq!(request, "{\"op\":\"run\",\"task_root\":\"/task\"}\n");
+            stream
+                .get_mut()
+                .write_all(b"{\"success\":true,\"stdout\":\"HELLO\",\"fuel_consumed\":42}\n")
+                .await
+                .unwrap();
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut channel = firecracker::connect_guest(&vsock, 5000, deadline).await.unwrap();
+        let request = ControlRequest::Run { task_root: GUEST_TASK_ROOT.to_string() };
+        let response = firecracker::exchange(&mut channel, &request).await.unwrap();
+        guest.await.unwrap();
+        assert!(response.success);
+        assert_eq!(response.stdout, "HELLO");
+        assert_eq!(response.fuel_consumed, 42);
+        assert_eq!(response.error, None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
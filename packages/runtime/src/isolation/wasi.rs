@@ -0,0 +1,653 @@
+//! WASI Plugin Executor
+//!
+//! Runs agent plugins as WASI preview 2 command components (`wasi:cli/run`)
+//! under wasmtime. Every run gets a fresh instance with:
+//! - Capability grants: preopened directories, a network allow-list and
+//!   environment variables, checked against the operator's ceiling
+//!   ([`WasmConfig::capabilities`])
+//! - Resource limits: linear memory, fuel and a wall-clock timeout
+//! - Captured, size-capped stdout/stderr
+//!
+//! Clocks and randomness are always available under WASI preview 2.
+//! Requires the `wasm` feature; without it [`WasiExecutor::new`] returns
+//! [`WasiError::Unavailable`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_runtime::isolation::wasi::{PluginSpec, WasiExecutor};
+//!
+//! let mut executor = WasiExecutor::new(&WasmConfig::default())?;
+//! executor.load_file("summarizer", "/opt/agentkern/plugins/summarizer.wasm")?;
+//!
+//! let spec = PluginSpec::new()
+//!     .grant(WasiCapability::Filesystem { path: "/srv/data".into(), readonly: true })
+//!     .grant(WasiCapability::Network { hosts: vec!["api.example.com:443".into()] });
+//! let output = executor.run("summarizer", &spec).await?;
+//! println!("{}", String::from_utf8_lossy(&output.stdout));
+//! ```
+
+use super::{WasiCapability, WasmConfig};
+use std::path::{Component as PathComponent, Path};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Epoch tick used for wall-clock timeouts.
+#[cfg(feature = "wasm")]
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// WASI executor errors.
+#[derive(Debug, Error)]
+pub enum WasiError {
+    #[error("WASI isolation unavailable: built without the `wasm` feature")]
+    Unavailable,
+    #[error("Unsupported WASM runtime {0:?}; only wasmtime is embedded")]
+    UnsupportedRuntime(super::WasmRuntime),
+    #[error("Capability denied: {0}")]
+    CapabilityDenied(String),
+    #[error("Plugin not found: {0}")]
+    PluginNotFound(String),
+    #[error("Failed to compile plugin {name}: {reason}")]
+    Compile { name: String, reason: String },
+    #[error("Failed to instantiate plugin {name}: {reason}")]
+    Instantiate { name: String, reason: String },
+    #[error("Plugin {0} ran out of fuel")]
+    FuelExhausted(String),
+    #[error("Plugin {name} exceeded its {timeout:?} timeout")]
+    Timeout { name: String, timeout: Duration },
+    #[error("Plugin {name} exceeded its {limit} byte memory limit")]
+    MemoryLimit { name: String, limit: usize },
+    #[error("Plugin {name} trapped: {reason}")]
+    Trap { name: String, reason: String },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Per-instance resource limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum linear memory per memory, in bytes
+    pub memory_bytes: usize,
+    /// Fuel budget (roughly, WASM instructions)
+    pub fuel: u64,
+    /// Wall-clock limit
+    pub timeout: Duration,
+    /// Cap on captured stdout and stderr, each
+    pub max_output_bytes: usize,
+}
+
+impl ResourceLimits {
+    /// Defaults, with fuel from the isolation config.
+    pub fn from_config(config: &WasmConfig) -> Self {
+        Self {
+            fuel: config.fuel_limit,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            memory_bytes: 64 * 1024 * 1024,
+            fuel: 10_000_000,
+            timeout: Duration::from_secs(5),
+            max_output_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// What one plugin run may do.
+#[derive(Debug, Clone, Default)]
+pub struct PluginSpec {
+    /// Requested capabilities (must fit the executor's ceiling)
+    pub capabilities: Vec<WasiCapability>,
+    /// Command-line arguments (`argv[1..]`)
+    pub args: Vec<String>,
+    /// Environment variables (requires [`WasiCapability::Environment`])
+    pub env: Vec<(String, String)>,
+    /// Limits; `None` uses the executor defaults
+    pub limits: Option<ResourceLimits>,
+}
+
+impl PluginSpec {
+    /// Empty spec: no filesystem, network or environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request a capability.
+    pub fn grant(mut self, capability: WasiCapability) -> Self {
+        self.capabilities.push(capability);
+        self
+    }
+
+    /// Add an argument.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Set an environment variable.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Override the resource limits.
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+}
+
+/// Result of a completed run.
+#[derive(Debug, Clone)]
+pub struct PluginOutput {
+    /// `wasi:cli/run` returned `ok`
+    pub success: bool,
+    /// Captured stdout
+    pub stdout: Vec<u8>,
+    /// Captured stderr
+    pub stderr: Vec<u8>,
+    /// Fuel used
+    pub fuel_consumed: u64,
+    /// Wall-clock time
+    pub duration: Duration,
+}
+
+/// Check requested capabilities against the operator's ceiling.
+///
+/// Filesystem grants must sit inside a granted path (and be read-only if it
+/// is); network hosts must be listed, or the ceiling must list `*`.
+pub fn check_grants(ceiling: &[WasiCapability], spec: &PluginSpec) -> Result<(), WasiError> {
+    let denied = |what: String| Err(WasiError::CapabilityDenied(what));
+
+    for capability in &spec.capabilities {
+        match capability {
+            WasiCapability::Clock | WasiCapability::Random | WasiCapability::Environment => {
+                if !ceiling.contains(capability) {
+                    return denied(format!("{:?}", capability));
+                }
+            }
+            WasiCapability::Filesystem { path, readonly } => {
+                let requested = Path::new(path);
+                if !requested.is_absolute()
+                    || requested.components().any(|c| c == PathComponent::ParentDir)
+                {
+                    return denied(format!("filesystem path {} must be absolute without '..'", path));
+                }
+                let allowed = ceiling.iter().any(|c| match c {
+                    WasiCapability::Filesystem { path: root, readonly: root_ro } => {
+                        requested.starts_with(root) && (*readonly || !root_ro)
+                    }
+                    _ => false,
+                });
+                if !allowed {
+                    let mode = if *readonly { "read" } else { "write" };
+                    return denied(format!("filesystem {} access to {}", mode, path));
+                }
+            }
+            WasiCapability::Network { hosts } => {
+                let listed: Vec<&str> = ceiling
+                    .iter()
+                    .filter_map(|c| match c {
+                        WasiCapability::Network { hosts } => Some(hosts.iter().map(String::as_str)),
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect();
+                for host in hosts {
+                    let (name, _) = split_host_port(host);
+                    if !listed.iter().any(|h| *h == "*" || h == host || *h == name) {
+                        return denied(format!("network access to {}", host));
+                    }
+                }
+            }
+        }
+    }
+
+    if !spec.env.is_empty() && !spec.capabilities.contains(&WasiCapability::Environment) {
+        return denied("environment variables without the Environment capability".to_string());
+    }
+    Ok(())
+}
+
+/// Split `host`, `host:port` or `[v6]:port`.
+fn split_host_port(entry: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = entry.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            return (host, port.strip_prefix(':').and_then(|p| p.parse().ok()));
+        }
+    }
+    match entry.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            Err(_) => (entry, None),
+        },
+        _ => (entry, None),
+    }
+}
+
+// ============================================================================
+// wasmtime executor
+// ============================================================================
+
+#[cfg(feature = "wasm")]
+mod executor {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+    use wasmtime::component::{Component, Linker, ResourceTable};
+    use wasmtime::{Config, Engine, ResourceLimiter, Store, Trap};
+    use wasmtime_wasi::bindings::Command;
+    use wasmtime_wasi::pipe::MemoryOutputPipe;
+    use wasmtime_wasi::{DirPerms, FilePerms, IoView, WasiCtx, WasiCtxBuilder, WasiView};
+
+    struct InstanceState {
+        ctx: WasiCtx,
+        table: ResourceTable,
+        limits: InstanceLimits,
+    }
+
+    impl IoView for InstanceState {
+        fn table(&mut self) -> &mut ResourceTable {
+            &mut self.table
+        }
+    }
+
+    impl WasiView for InstanceState {
+        fn ctx(&mut self) -> &mut WasiCtx {
+            &mut self.ctx
+        }
+    }
+
+    struct InstanceLimits {
+        memory_bytes: usize,
+        memory_exceeded: bool,
+    }
+
+    impl ResourceLimiter for InstanceLimits {
+        fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> anyhow::Result<bool> {
+            if desired > self.memory_bytes {
+                self.memory_exceeded = true;
+                anyhow::bail!("memory limit of {} bytes exceeded", self.memory_bytes);
+            }
+            Ok(true)
+        }
+
+        fn table_growing(&mut self, _current: usize, _desired: usize, _maximum: Option<usize>) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// Runs WASI command components in per-run sandboxes.
+    pub struct WasiExecutor {
+        engine: Engine,
+        linker: Linker<InstanceState>,
+        plugins: HashMap<String, Component>,
+        ceiling: Vec<WasiCapability>,
+        verify_capabilities: bool,
+        default_limits: ResourceLimits,
+        ticker: Arc<AtomicBool>,
+    }
+
+    impl WasiExecutor {
+        /// Create an executor from the isolation config.
+        pub fn new(config: &WasmConfig) -> Result<Self, WasiError> {
+            if config.runtime != super::super::WasmRuntime::Wasmtime {
+                return Err(WasiError::UnsupportedRuntime(config.runtime));
+            }
+
+            let mut wasm = Config::new();
+            wasm.async_support(true);
+            wasm.wasm_component_model(true);
+            wasm.consume_fuel(true);
+            wasm.epoch_interruption(true);
+            let engine = Engine::new(&wasm).map_err(|e| WasiError::Compile {
+                name: "engine".to_string(),
+                reason: e.to_string(),
+            })?;
+
+            let mut linker = Linker::new(&engine);
+            wasmtime_wasi::add_to_linker_async(&mut linker).map_err(|e| WasiError::Instantiate {
+                name: "linker".to_string(),
+                reason: e.to_string(),
+            })?;
+
+            // Drive epoch-based timeouts until the executor is dropped.
+            let ticker = Arc::new(AtomicBool::new(true));
+            let (running, ticking) = (Arc::clone(&ticker), engine.clone());
+            std::thread::Builder::new()
+                .name("wasi-epoch".to_string())
+                .spawn(move || {
+                    while running.load(Ordering::Relaxed) {
+                        std::thread::sleep(EPOCH_TICK);
+                        ticking.increment_epoch();
+                    }
+                })?;
+
+            Ok(Self {
+                engine,
+                linker,
+                plugins: HashMap::new(),
+                ceiling: config.capabilities.clone(),
+                verify_capabilities: config.verify_capabilities,
+                default_limits: ResourceLimits::from_config(config),
+                ticker,
+            })
+        }
+
+        /// Compile and register a plugin from component bytes (or WAT text).
+        pub fn load(&mut self, name: impl Into<String>, bytes: &[u8]) -> Result<(), WasiError> {
+            let name = name.into();
+            let component = Component::new(&self.engine, bytes).map_err(|e| WasiError::Compile {
+                name: name.clone(),
+                reason: e.to_string(),
+            })?;
+            tracing::info!(plugin = %name, "WASI plugin loaded");
+            self.plugins.insert(name, component);
+            Ok(())
+        }
+
+        /// Compile and register a plugin from a `.wasm` file.
+        pub fn load_file(&mut self, name: impl Into<String>, path: impl AsRef<Path>) -> Result<(), WasiError> {
+            let bytes = std::fs::read(path)?;
+            self.load(name, &bytes)
+        }
+
+        /// Unregister a plugin. Runs already in progress finish normally.
+        pub fn unload(&mut self, name: &str) -> bool {
+            self.plugins.remove(name).is_some()
+        }
+
+        /// Loaded plugin names, sorted.
+        pub fn plugins(&self) -> Vec<&str> {
+            let mut names: Vec<_> = self.plugins.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            names
+        }
+
+        /// Run a plugin's `wasi:cli/run` export in a fresh sandbox.
+        pub async fn run(&self, name: &str, spec: &PluginSpec) -> Result<PluginOutput, WasiError> {
+            let component = self
+                .plugins
+                .get(name)
+                .ok_or_else(|| WasiError::PluginNotFound(name.to_string()))?;
+            if self.verify_capabilities {
+                check_grants(&self.ceiling, spec)?;
+            }
+            let limits = spec.limits.clone().unwrap_or_else(|| self.default_limits.clone());
+
+            let stdout = MemoryOutputPipe::new(limits.max_output_bytes);
+            let stderr = MemoryOutputPipe::new(limits.max_output_bytes);
+            let ctx = build_ctx(name, spec, stdout.clone(), stderr.clone()).await?;
+
+            let mut store = Store::new(
+                &self.engine,
+                InstanceState {
+                    ctx,
+                    table: ResourceTable::new(),
+                    limits: InstanceLimits { memory_bytes: limits.memory_bytes, memory_exceeded: false },
+                },
+            );
+            store.limiter(|state| &mut state.limits);
+            store.set_fuel(limits.fuel).map_err(|e| trap(name, e))?;
+            store.set_epoch_deadline((limits.timeout.as_millis() / EPOCH_TICK.as_millis()).max(1) as u64);
+            store.epoch_deadline_trap();
+
+            let started = Instant::now();
+            let outcome = async {
+                let command = Command::instantiate_async(&mut store, component, &self.linker)
+                    .await
+                    .map_err(|e| WasiError::Instantiate { name: name.to_string(), reason: e.to_string() })?;
+                command.wasi_cli_run().call_run(&mut store).await.map_err(|e| trap(name, e))
+            }
+            .await;
+            let duration = started.elapsed();
+
+            let result = match outcome {
+                Ok(result) => result,
+                Err(_) if store.data().limits.memory_exceeded => {
+                    return Err(WasiError::MemoryLimit { name: name.to_string(), limit: limits.memory_bytes });
+                }
+                Err(WasiError::Trap { reason, .. }) if reason == Trap::OutOfFuel.to_string() => {
+                    return Err(WasiError::FuelExhausted(name.to_string()));
+                }
+                Err(WasiError::Trap { reason, .. }) if reason == Trap::Interrupt.to_string() => {
+                    return Err(WasiError::Timeout { name: name.to_string(), timeout: limits.timeout });
+                }
+                Err(e) => return Err(e),
+            };
+
+            let fuel_left = store.get_fuel().unwrap_or(0);
+            tracing::debug!(plugin = %name, ?duration, success = result.is_ok(), "WASI plugin finished");
+            Ok(PluginOutput {
+                success: result.is_ok(),
+                stdout: stdout.contents().to_vec(),
+                stderr: stderr.contents().to_vec(),
+                fuel_consumed: limits.fuel.saturating_sub(fuel_left),
+                duration,
+            })
+        }
+    }
+
+    impl Drop for WasiExecutor {
+        fn drop(&mut self) {
+            self.ticker.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Resolve allow-listed hosts to `(ip, port)` pairs; `None` port = any.
+    async fn resolve_allow_list(hosts: &[String]) -> Result<Vec<(IpAddr, Option<u16>)>, WasiError> {
+        let mut allowed = Vec::new();
+        for entry in hosts {
+            let (host, port) = split_host_port(entry);
+            if let Ok(ip) = host.parse::<IpAddr>() {
+                allowed.push((ip, port));
+                continue;
+            }
+            let resolved = tokio::net::lookup_host((host, port.unwrap_or(0))).await?;
+            allowed.extend(resolved.map(|addr| (addr.ip(), port)));
+        }
+        Ok(allowed)
+    }
+
+    fn trap(name: &str, error: anyhow::Error) -> WasiError {
+        let reason = match error.downcast_ref::<Trap>() {
+            Some(trap) => trap.to_string(),
+            None => format!("{:#}", error),
+        };
+        WasiError::Trap { name: name.to_string(), reason }
+    }
+
+    async fn build_ctx(
+        name: &str,
+        spec: &PluginSpec,
+        stdout: MemoryOutputPipe,
+        stderr: MemoryOutputPipe,
+    ) -> Result<WasiCtx, WasiError> {
+        let mut builder = WasiCtxBuilder::new();
+        builder.stdout(stdout).stderr(stderr);
+
+        let mut argv = vec![name.to_string()];
+        argv.extend(spec.args.iter().cloned());
+        builder.args(&argv);
+        for (key, value) in &spec.env {
+            builder.env(key, value);
+        }
+
+        let mut hosts = Vec::new();
+        for capability in &spec.capabilities {
+            match capability {
+                WasiCapability::Filesystem { path, readonly } => {
+                    let (dir_perms, file_perms) = if *readonly {
+                        (DirPerms::READ, FilePerms::READ)
+                    } else {
+                        (DirPerms::all(), FilePerms::all())
+                    };
+                    builder
+                        .preopened_dir(path, path, dir_perms, file_perms)
+                        .map_err(|e| WasiError::CapabilityDenied(format!("{}: {}", path, e)))?;
+                }
+                WasiCapability::Network { hosts: requested } => hosts.extend(requested.iter().cloned()),
+                _ => {}
+            }
+        }
+
+        // Sockets are denied unless allow-listed; names resolve, addresses are still checked.
+        if !hosts.is_empty() {
+            let allowed = Arc::new(resolve_allow_list(&hosts).await?);
+            builder.allow_ip_name_lookup(true);
+            builder.socket_addr_check(move |addr, _use| {
+                let allowed = Arc::clone(&allowed);
+                Box::pin(async move {
+                    allowed
+                        .iter()
+                        .any(|(ip, port)| *ip == addr.ip() && port.is_none_or(|p| p == addr.port()))
+                })
+            });
+        }
+
+        Ok(builder.build())
+    }
+}
+
+#[cfg(feature = "wasm")]
+pub use executor::WasiExecutor;
+
+/// Placeholder when built without the `wasm` feature.
+#[cfg(not(feature = "wasm"))]
+pub struct WasiExecutor;
+
+#[cfg(not(feature = "wasm"))]
+impl WasiExecutor {
+    pub fn new(_config: &WasmConfig) -> Result<Self, WasiError> {
+        Err(WasiError::Unavailable)
+    }
+
+    pub fn load(&mut self, _name: impl Into<String>, _bytes: &[u8]) -> Result<(), WasiError> {
+        Err(WasiError::Unavailable)
+    }
+
+    pub fn load_file(&mut self, _name: impl Into<String>, _path: impl AsRef<Path>) -> Result<(), WasiError> {
+        Err(WasiError::Unavailable)
+    }
+
+    pub fn unload(&mut self, _name: &str) -> bool {
+        false
+    }
+
+    pub fn plugins(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    pub async fn run(&self, _name: &str, _spec: &PluginSpec) -> Result<PluginOutput, WasiError> {
+        Err(WasiError::Unavailable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ceiling() -> Vec<WasiCapability> {
+        vec![
+            WasiCapability::Clock,
+            WasiCapability::Filesystem { path: "/srv/data".to_string(), readonly: true },
+            WasiCapability::Filesystem { path: "/tmp/agent".to_string(), readonly: false },
+            WasiCapability::Network { hosts: vec!["api.example.com".to_string(), "10.0.0.5:443".to_string()] },
+        ]
+    }
+
+    #[test]
+    fn test_check_grants() {
+        let fs = |path: &str, readonly| WasiCapability::Filesystem { path: path.to_string(), readonly };
+        let net = |host: &str| WasiCapability::Network { hosts: vec![host.to_string()] };
+        let ok = |cap| check_grants(&ceiling(), &PluginSpec::new().grant(cap)).is_ok();
+
+        assert!(ok(fs("/srv/data/reports", true)));
+        assert!(!ok(fs("/srv/data", false))); // read-only root
+        assert!(ok(fs("/tmp/agent/scratch", false)));
+        assert!(!ok(fs("/srv/data/../../etc", true)));
+        assert!(!ok(fs("/etc", true)));
+
+        assert!(ok(net("api.example.com:443")));
+        assert!(ok(net("10.0.0.5:443")));
+        assert!(!ok(net("10.0.0.5:22")));
+        assert!(!ok(net("evil.example.com")));
+
+        assert!(ok(WasiCapability::Clock));
+        assert!(!ok(WasiCapability::Environment));
+        assert!(check_grants(&ceiling(), &PluginSpec::new().env("KEY", "v")).is_err());
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(split_host_port("api.example.com:443"), ("api.example.com", Some(443)));
+        assert_eq!(split_host_port("api.example.com"), ("api.example.com", None));
+        assert_eq!(split_host_port("[::1]:8080"), ("::1", Some(8080)));
+        assert_eq!(split_host_port("fe80::1"), ("fe80::1", None));
+    }
+
+    #[cfg(feature = "wasm")]
+    mod wasm {
+        use super::*;
+
+        const HELLO: &str = r#"
+            (component
+              (core module $m (func (export "run") (result i32) i32.const 0))
+              (core instance $i (instantiate $m))
+              (func $run (result (result)) (canon lift (core func $i "run")))
+              (instance $r (export "run" (func $run)))
+              (export "wasi:cli/run@0.2.3" (instance $r))
+            )
+        "#;
+
+        const SPIN: &str = r#"
+            (component
+              (core module $m (func (export "run") (result i32) (loop $l (br $l)) i32.const 0))
+              (core instance $i (instantiate $m))
+              (func $run (result (result)) (canon lift (core func $i "run")))
+              (instance $r (export "run" (func $run)))
+              (export "wasi:cli/run@0.2.3" (instance $r))
+            )
+        "#;
+
+        #[tokio::test]
+        async fn test_plugin_lifecycle_and_limits() {
+            let mut executor = WasiExecutor::new(&WasmConfig::default()).unwrap();
+            executor.load("hello", HELLO.as_bytes()).unwrap();
+            executor.load("spin", SPIN.as_bytes()).unwrap();
+            assert_eq!(executor.plugins(), ["hello", "spin"]);
+
+            let output = executor.run("hello", &PluginSpec::new()).await.unwrap();
+            assert!(output.success);
+
+            let fuel = ResourceLimits { fuel: 10_000, ..ResourceLimits::default() };
+            let result = executor.run("spin", &PluginSpec::new().with_limits(fuel)).await;
+            assert!(matches!(result, Err(WasiError::FuelExhausted(_))));
+
+            let timeout = ResourceLimits {
+                fuel: u64::MAX,
+                timeout: Duration::from_millis(50),
+                ..ResourceLimits::default()
+            };
+            let result = executor.run("spin", &PluginSpec::new().with_limits(timeout)).await;
+            assert!(matches!(result, Err(WasiError::Timeout { .. })));
+
+            let denied = PluginSpec::new().grant(WasiCapability::Environment);
+            assert!(matches!(executor.run("hello", &denied).await, Err(WasiError::CapabilityDenied(_))));
+
+            assert!(executor.unload("spin"));
+            assert!(matches!(
+                executor.run("spin", &PluginSpec::new()).await,
+                Err(WasiError::PluginNotFound(_))
+            ));
+            assert!(executor.load("bad", b"not wasm").is_err());
+        }
+    }
+}
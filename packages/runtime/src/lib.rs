@@ -14,12 +14,27 @@ pub mod config;
 pub mod serve;
 pub mod isolation;
 pub mod fallback;
+pub mod policy_cli;
+pub mod escalation_cli;
+pub mod reload;
+pub mod shutdown;
+pub mod doctor;
+pub mod identity;
+pub mod attest;
+mod affinity;
 
-pub use detect::{Environment, detect_environment};
-pub use config::{RuntimeConfig, auto_configure};
-pub use serve::{serve, Protocol};
-pub use isolation::{IsolationMode, IsolationConfig, detect_best_isolation};
+pub use detect::{Environment, HostResources, detect_environment};
+pub use config::{RuntimeConfig, ConfigError, ConfigSources, ConfigFlags, InferenceDevice, auto_configure, load_config};
+pub use serve::{serve, serve_with_shutdown, ServeState, ServeError, Protocol};
+pub use isolation::{IsolationMode, IsolationConfig, Sandbox, SandboxError, detect_best_isolation};
+pub use isolation::microvm::{MicroVmConfig, MicroVmExecutor, MicroVmError, Vmm};
+pub use isolation::wasi::{WasiExecutor, PluginSpec, ResourceLimits, WasiError};
 pub use fallback::{ServiceMode, GracefulFallback, FallbackResult};
+pub use reload::{ConfigReloader, ReloadReport};
+pub use shutdown::{ShutdownCoordinator, ShutdownPhase, ShutdownReport};
+pub use doctor::{Doctor, DoctorReport, CheckResult, CheckStatus};
+pub use identity::{AgentIdentity, CredentialKind, IdentityError, IdentityRegistry, IssuedKey, Presented, Scope};
+pub use attest::{TeeQuoteVerifier, card_attestation};
 
 
 /// AgentKern kernel version.
@@ -27,6 +42,11 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Run AgentKern with auto-detection.
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    run_with(&ConfigSources::default()).await
+}
+
+/// Run AgentKern with an explicit config file and/or CLI flag overrides.
+pub async fn run_with(sources: &ConfigSources) -> Result<(), Box<dyn std::error::Error>> {
     // 1. Detect environment
     let env = detect_environment();
     tracing::info!("Detected environment: {:?}", env);
@@ -35,12 +55,59 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let isolation = detect_best_isolation();
     tracing::info!("Isolation mode: {:?}", isolation);
     
-    // 3. Auto-configure based on environment
-    let config = auto_configure(&env);
-    tracing::info!("Configuration: {:?}", config);
+    // 3. Layer defaults, config file, env and flags
+    let config = load_config(&env, sources)?;
+    tracing::info!("Configuration: {:?}", config.redacted());
     
-    // 4. Start serving
-    serve(&config).await?;
+    // 4. Apply live settings (logging, rate limit, policies); reload them on SIGHUP
+    let mut state = ServeState::with_engine(build_engine(&config))
+        .with_drain_timeout(std::time::Duration::from_secs(config.drain_timeout_secs));
+    if let Some(journal) = &config.audit_journal {
+        state = state.with_audit_journal(journal);
+    }
+    if let Some(registry) = identity::IdentityRegistry::from_env() {
+        tracing::info!("Caller authentication enabled ({} set)", identity::ADMIN_KEY_ENV);
+        state = state.with_identity(std::sync::Arc::new(registry));
+    }
+    let state = std::sync::Arc::new(state);
+    let reloader = std::sync::Arc::new(ConfigReloader::new(config.clone(), state.clone()));
+    reloader.initialize().await?;
+    tokio::spawn(reload::watch_sighup(reloader, env, sources.clone()));
+
+    // 5. Start serving
+    serve_with_shutdown(&config, state, serve::shutdown_signal()).await?;
     
     Ok(())
 }
+
+/// Build the async runtime sized by `worker_threads` and `thread_per_core`.
+pub fn build_runtime(config: &RuntimeConfig) -> std::io::Result<tokio::runtime::Runtime> {
+    let workers = match config.worker_threads {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n,
+    };
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all().worker_threads(workers);
+    if config.thread_per_core {
+        builder.on_thread_start(affinity::pin_workers(workers));
+    }
+    builder.build()
+}
+
+/// Gate engine with the neural path configured by `inference`.
+fn build_engine(config: &RuntimeConfig) -> agentkern_gate::GateEngine {
+    use agentkern_gate::neural::{ExecutionProvider, ModelConfig};
+
+    let engine = agentkern_gate::GateEngine::new();
+    let provider = match config.inference {
+        // Risk scores never exceed 100, so the neural path never triggers
+        InferenceDevice::Disabled => return engine.with_neural_threshold(u8::MAX),
+        InferenceDevice::Cpu => ExecutionProvider::Cpu,
+        InferenceDevice::Gpu => ExecutionProvider::Cuda,
+    };
+    engine.with_neural_model(ModelConfig {
+        provider,
+        num_threads: config.inference_threads as u32,
+        ..ModelConfig::default()
+    })
+}
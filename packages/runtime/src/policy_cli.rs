@@ -0,0 +1,394 @@
+//! Policy CLI
+//!
+//! `agentkern policy <lint|test|push|list|diff>` for operators and CI.
+//!
+//! Policies are read with the gate's [`FileSource`] (a YAML file or a
+//! directory of them) and checked with the gate DSL checker before anything
+//! is loaded or pushed. `push`, `list` and `diff` talk to a running
+//! `agentkern run` over its `/policies` endpoint.
+//!
+//! Exit codes:
+//! - `0`: success, no differences
+//! - `1`: lint errors, failing tests, differences (`diff`), or push rejected
+//! - `2`: usage error
+//! - `3`: server unreachable or returned an unexpected response
+
+use agentkern_gate::engine::VerificationRequestBuilder;
+use agentkern_gate::{FileSource, GateEngine, LintLevel, Policy, PolicyDiff, PolicySource};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Success / no differences.
+pub const EXIT_OK: i32 = 0;
+/// Lint errors, failing tests, differences, or push rejected.
+pub const EXIT_FAILED: i32 = 1;
+/// Bad arguments.
+pub const EXIT_USAGE: i32 = 2;
+/// Server unreachable or unexpected response.
+pub const EXIT_SERVER: i32 = 3;
+
+/// Server used when neither `--server` nor `AGENTKERN_SERVER` is set.
+pub const DEFAULT_SERVER: &str = "http://127.0.0.1:3000";
+
+/// A `policy test` case.
+///
+/// ```yaml
+/// - name: large transfers are denied
+///   action: transfer_funds
+///   context: { amount: 50000 }
+///   expect: deny
+///   blocking: [spending-limits]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyTestCase {
+    /// Case name
+    pub name: String,
+    /// Agent making the request
+    #[serde(default = "default_agent")]
+    pub agent_id: String,
+    /// Action under test
+    pub action: String,
+    /// Request context
+    #[serde(default)]
+    pub context: HashMap<String, serde_json::Value>,
+    /// Expected decision
+    pub expect: Expectation,
+    /// Policies expected to block (subset match)
+    #[serde(default)]
+    pub blocking: Vec<String>,
+}
+
+fn default_agent() -> String {
+    "policy-test".to_string()
+}
+
+/// Expected decision for a test case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Expectation {
+    Allow,
+    Deny,
+}
+
+/// Run `agentkern policy <args>`. Returns the process exit code.
+pub async fn run(args: &[String]) -> i32 {
+    let mut positional = Vec::new();
+    let mut server = std::env::var("AGENTKERN_SERVER").unwrap_or_else(|_| DEFAULT_SERVER.to_string());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--server" | "-s" => match iter.next() {
+                Some(url) => server = url.clone(),
+                None => return usage("--server requires a URL"),
+            },
+            flag if flag.starts_with("--server=") => server = flag["--server=".len()..].to_string(),
+            flag if flag.starts_with('-') => return usage(&format!("unknown flag {}", flag)),
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    let server = server.trim_end_matches('/');
+
+    match positional.as_slice() {
+        ["lint", paths @ ..] if !paths.is_empty() => lint(paths),
+        ["test", policies, cases] => test(policies, cases).await,
+        ["push", path] => push(path, server).await,
+        ["list"] => list(server).await,
+        ["diff", path] => diff(path, server).await,
+        _ => usage("expected lint|test|push|list|diff"),
+    }
+}
+
+fn usage(problem: &str) -> i32 {
+    eprintln!("Error: {}", problem);
+    eprintln!();
+    eprintln!("USAGE:");
+    eprintln!("  agentkern policy lint <path>...");
+    eprintln!("  agentkern policy test <policies> <cases.yaml>");
+    eprintln!("  agentkern policy push <path> [--server <url>]");
+    eprintln!("  agentkern policy list [--server <url>]");
+    eprintln!("  agentkern policy diff <path> [--server <url>]");
+    EXIT_USAGE
+}
+
+/// Load policies and print lint findings. `None` if loading failed or lint found errors.
+fn load_checked(path: &str) -> Option<Vec<Policy>> {
+    let policies = match FileSource::new(path).load() {
+        Ok(policies) => policies,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return None;
+        }
+    };
+
+    let issues: Vec<_> = policies.iter().flat_map(Policy::lint).collect();
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    let errors = issues.iter().filter(|i| i.level == LintLevel::Error).count();
+    println!(
+        "{}: {} policies, {} errors, {} warnings",
+        path,
+        policies.len(),
+        errors,
+        issues.len() - errors
+    );
+
+    (errors == 0).then_some(policies)
+}
+
+fn lint(paths: &[&str]) -> i32 {
+    let mut code = EXIT_OK;
+    for path in paths {
+        if load_checked(path).is_none() {
+            code = EXIT_FAILED;
+        }
+    }
+    code
+}
+
+async fn test(policies_path: &str, cases_path: &str) -> i32 {
+    if load_checked(policies_path).is_none() {
+        return EXIT_FAILED;
+    }
+    let engine = GateEngine::new();
+    if let Err(e) = engine.reload_from(&FileSource::new(policies_path)).await {
+        eprintln!("error: {}", e);
+        return EXIT_FAILED;
+    }
+
+    let cases: Vec<PolicyTestCase> = match std::fs::read_to_string(Path::new(cases_path))
+        .map_err(|e| e.to_string())
+        .and_then(|yaml| serde_yaml::from_str(&yaml).map_err(|e| e.to_string()))
+    {
+        Ok(cases) => cases,
+        Err(e) => {
+            eprintln!("error: {}: {}", cases_path, e);
+            return EXIT_FAILED;
+        }
+    };
+
+    let mut failed = 0;
+    for case in &cases {
+        let mut builder = VerificationRequestBuilder::new(&case.agent_id, &case.action);
+        for (key, value) in &case.context {
+            builder = builder.context(key, value.clone());
+        }
+        let result = engine.verify(builder.build()).await;
+
+        let allowed = case.expect == Expectation::Allow;
+        let missing: Vec<_> = case
+            .blocking
+            .iter()
+            .filter(|id| !result.blocking_policies.contains(id))
+            .collect();
+        if result.allowed == allowed && missing.is_empty() {
+            println!("PASS {}", case.name);
+        } else {
+            failed += 1;
+            println!(
+                "FAIL {}: expected {:?}{}, got {} (blocking: {:?})",
+                case.name,
+                case.expect,
+                if missing.is_empty() { String::new() } else { format!(" blocked by {:?}", missing) },
+                if result.allowed { "allow" } else { "deny" },
+                result.blocking_policies
+            );
+        }
+    }
+
+    println!("{} passed, {} failed", cases.len() - failed, failed);
+    if failed == 0 { EXIT_OK } else { EXIT_FAILED }
+}
+
+async fn fetch_remote(server: &str) -> Result<Vec<Policy>, i32> {
+    let url = format!("{}/policies", server);
+    let response = reqwest::get(&url).await.map_err(|e| {
+        eprintln!("error: {}: {}", url, e);
+        EXIT_SERVER
+    })?;
+    if !response.status().is_success() {
+        eprintln!("error: {} returned {}", url, response.status());
+        return Err(EXIT_SERVER);
+    }
+    response.json().await.map_err(|e| {
+        eprintln!("error: {}: {}", url, e);
+        EXIT_SERVER
+    })
+}
+
+async fn push(path: &str, server: &str) -> i32 {
+    let Some(policies) = load_checked(path) else {
+        return EXIT_FAILED;
+    };
+
+    let url = format!("{}/policies", server);
+    let response = match reqwest::Client::new().put(&url).json(&policies).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("error: {}: {}", url, e);
+            return EXIT_SERVER;
+        }
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+        eprintln!("rejected: {}", response.text().await.unwrap_or_default());
+        return EXIT_FAILED;
+    }
+    match response.json::<PolicyDiff>().await {
+        Ok(diff) if status.is_success() => {
+            print_diff(&diff);
+            println!("Pushed {} policies to {}", policies.len(), server);
+            EXIT_OK
+        }
+        Ok(_) => {
+            eprintln!("error: {} returned {}", url, status);
+            EXIT_SERVER
+        }
+        Err(e) => {
+            eprintln!("error: {} returned {}: {}", url, status, e);
+            EXIT_SERVER
+        }
+    }
+}
+
+async fn list(server: &str) -> i32 {
+    let policies = match fetch_remote(server).await {
+        Ok(policies) => policies,
+        Err(code) => return code,
+    };
+
+    println!("{:<32} {:>8} {:>8} {:>6}  NAME", "ID", "PRIORITY", "ENABLED", "RULES");
+    for policy in &policies {
+        println!(
+            "{:<32} {:>8} {:>8} {:>6}  {}",
+            policy.id,
+            policy.priority,
+            policy.enabled,
+            policy.rules.len(),
+            policy.name
+        );
+    }
+    EXIT_OK
+}
+
+async fn diff(path: &str, server: &str) -> i32 {
+    let local = match FileSource::new(path).load() {
+        Ok(policies) => policies,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    let remote = match fetch_remote(server).await {
+        Ok(policies) => policies,
+        Err(code) => return code,
+    };
+
+    // What a push of `path` would change on the server
+    let diff = PolicyDiff::between(&remote, &local);
+    if diff.is_empty() {
+        println!("No differences");
+        return EXIT_OK;
+    }
+    print_diff(&diff);
+    EXIT_FAILED
+}
+
+fn print_diff(diff: &PolicyDiff) {
+    for id in &diff.added {
+        println!("+ {}", id);
+    }
+    for id in &diff.removed {
+        println!("- {}", id);
+    }
+    for id in &diff.changed {
+        println!("~ {}", id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serve::{router, ServeState};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const POLICY: &str = r#"
+id: spending
+name: Spending
+rules:
+  - id: max
+    condition: "action == 'transfer_funds' && context.amount > 10000"
+    action: deny
+    message: "too large"
+"#;
+
+    const CASES: &str = r#"
+- name: small transfer
+  action: transfer_funds
+  context: { amount: 10 }
+  expect: allow
+- name: large transfer
+  action: transfer_funds
+  context: { amount: 50000 }
+  expect: deny
+  blocking: [spending]
+"#;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("policy-cli-{}-{:?}", std::process::id(), std::thread::current().id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_lint_and_test_exit_codes() {
+        let dir = temp_dir();
+        let good = write(&dir, "good.yaml", POLICY);
+        let bad = write(&dir, "bad.yaml", &POLICY.replace("action == 'transfer_funds'", "amount > 1"));
+        let cases = write(&dir, "cases.yaml", CASES);
+
+        assert_eq!(run(&args(&["lint", &good])).await, EXIT_OK);
+        assert_eq!(run(&args(&["lint", &good, &bad])).await, EXIT_FAILED);
+        assert_eq!(run(&args(&["test", &good, &cases])).await, EXIT_OK);
+
+        let wrong = write(&dir, "wrong.yaml", &CASES.replace("expect: allow", "expect: deny"));
+        assert_eq!(run(&args(&["test", &good, &wrong])).await, EXIT_FAILED);
+
+        assert_eq!(run(&args(&["frobnicate"])).await, EXIT_USAGE);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_push_list_diff_against_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, router(Arc::new(ServeState::new()))).await.unwrap();
+        });
+
+        let dir = temp_dir().join("server");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = write(&dir, "spending.yaml", POLICY);
+
+        assert_eq!(run(&args(&["diff", &path, "--server", &server])).await, EXIT_FAILED);
+        assert_eq!(run(&args(&["push", &path, "--server", &server])).await, EXIT_OK);
+        assert_eq!(run(&args(&["diff", &path, "--server", &server])).await, EXIT_OK);
+        assert_eq!(run(&args(&["list", "--server", &server])).await, EXIT_OK);
+
+        assert_eq!(run(&args(&["list", "--server", "http://127.0.0.1:1"])).await, EXIT_SERVER);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+//! Hot Configuration Reload
+//!
+//! On SIGHUP the runtime re-reads its configuration (same layers as startup)
+//! and applies what can change without dropping connections:
+//! - `log_level`
+//! - `policy_paths` (policy files are re-read even if the paths are unchanged)
+//! - `rate_limit`
+//!
+//! Anything else that changed (listeners, connection limits, backends) is
+//! reported as requiring a restart and keeps its running value. An invalid
+//! config file is rejected as a whole.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! reload::init_logging();
+//! let reloader = Arc::new(ConfigReloader::new(config, state));
+//! reloader.initialize().await?;
+//! tokio::spawn(reload::watch_sighup(reloader, env, sources));
+//! ```
+
+use crate::config::{load_config, ConfigSources, RuntimeConfig};
+use crate::detect::Environment;
+use crate::serve::ServeState;
+use agentkern_gate::PolicySourceError;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::Mutex;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as log_fmt, reload, EnvFilter, Registry};
+
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Install the global subscriber with a reloadable filter (`RUST_LOG`, default `info`).
+pub fn init_logging() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let (layer, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry().with(layer).with(log_fmt::layer()).try_init().is_ok() {
+        let _ = LOG_FILTER.set(handle);
+    }
+}
+
+/// Swap the active log filter. No-op if [`init_logging`] was not called.
+pub fn set_log_level(level: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(level).map_err(|e| e.to_string())?;
+    match LOG_FILTER.get() {
+        Some(handle) => handle.reload(filter).map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Outcome of a reload.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changes applied live
+    pub applied: Vec<String>,
+    /// Changed settings that only take effect after a restart
+    pub restart_required: Vec<String>,
+    /// Changes that failed to apply (previous value kept)
+    pub failed: Vec<String>,
+}
+
+impl ReloadReport {
+    /// Nothing changed?
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.restart_required.is_empty() && self.failed.is_empty()
+    }
+}
+
+impl fmt::Display for ReloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let sections = [
+            ("applied", &self.applied),
+            ("restart required", &self.restart_required),
+            ("failed", &self.failed),
+        ];
+        let parts: Vec<_> = sections
+            .iter()
+            .filter(|(_, items)| !items.is_empty())
+            .map(|(label, items)| format!("{}: {}", label, items.join(", ")))
+            .collect();
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Applies configuration changes to a running server.
+pub struct ConfigReloader {
+    current: Mutex<RuntimeConfig>,
+    state: Arc<ServeState>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader for the config the server was started with.
+    pub fn new(config: RuntimeConfig, state: Arc<ServeState>) -> Self {
+        Self {
+            current: Mutex::new(config),
+            state,
+        }
+    }
+
+    /// Apply the reloadable settings of the startup config.
+    pub async fn initialize(&self) -> Result<(), PolicySourceError> {
+        let config = self.current.lock().await;
+        if let Err(e) = set_log_level(&config.log_level) {
+            tracing::warn!("Invalid log_level '{}': {}", config.log_level, e);
+        }
+        self.state.set_rate_limit(config.rate_limit);
+        if !config.policy_paths.is_empty() {
+            let diff = self.state.load_policies(&config.policy_paths).await?;
+            tracing::info!("Loaded {} policies", diff.added.len());
+        }
+        Ok(())
+    }
+
+    /// The running configuration.
+    pub async fn current(&self) -> RuntimeConfig {
+        self.current.lock().await.clone()
+    }
+
+    /// Apply `new` where possible and report what changed.
+    pub async fn reload(&self, new: RuntimeConfig) -> ReloadReport {
+        let mut current = self.current.lock().await;
+        let mut report = ReloadReport::default();
+
+        if new.log_level != current.log_level {
+            match set_log_level(&new.log_level) {
+                Ok(()) => {
+                    report.applied.push(format!("log_level={}", new.log_level));
+                    current.log_level = new.log_level.clone();
+                }
+                Err(e) => report.failed.push(format!("log_level: {}", e)),
+            }
+        }
+
+        if new.rate_limit != current.rate_limit {
+            self.state.set_rate_limit(new.rate_limit);
+            report.applied.push(match new.rate_limit {
+                Some(rps) => format!("rate_limit={}/s", rps),
+                None => "rate_limit=unlimited".to_string(),
+            });
+            current.rate_limit = new.rate_limit;
+        }
+
+        if !new.policy_paths.is_empty() {
+            match self.state.load_policies(&new.policy_paths).await {
+                Ok(diff) => {
+                    if !diff.is_empty() || new.policy_paths != current.policy_paths {
+                        report.applied.push(format!(
+                            "policies (+{} ~{} -{})",
+                            diff.added.len(),
+                            diff.changed.len(),
+                            diff.removed.len()
+                        ));
+                    }
+                    current.policy_paths = new.policy_paths.clone();
+                }
+                Err(e) => report.failed.push(format!("policies: {}", e)),
+            }
+        } else if !current.policy_paths.is_empty() {
+            report.applied.push("policy_paths cleared (loaded policies kept)".to_string());
+            current.policy_paths.clear();
+        }
+
+        let restart_only = [
+            ("bind_address", new.bind_address != current.bind_address),
+            ("http_port", new.http_port != current.http_port),
+            ("grpc_port", new.grpc_port != current.grpc_port),
+            ("websocket_enabled", new.websocket_enabled != current.websocket_enabled),
+            ("max_connections", new.max_connections != current.max_connections),
+            ("memory_limit", new.memory_limit != current.memory_limit),
+            ("database_url", new.database_url != current.database_url),
+            ("cache_url", new.cache_url != current.cache_url),
+            ("protocols", new.protocols != current.protocols),
+            ("resource_mode", new.resource_mode != current.resource_mode),
+            ("drain_timeout_secs", new.drain_timeout_secs != current.drain_timeout_secs),
+            ("audit_journal", new.audit_journal != current.audit_journal),
+            ("worker_threads", new.worker_threads != current.worker_threads),
+            ("thread_per_core", new.thread_per_core != current.thread_per_core),
+            ("inference", new.inference != current.inference),
+            ("inference_threads", new.inference_threads != current.inference_threads),
+            ("mesh_seeds", new.mesh_seeds != current.mesh_seeds),
+        ];
+        report.restart_required = restart_only
+            .iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        report
+    }
+}
+
+/// Reload the configuration on every SIGHUP. Runs until the process exits.
+#[cfg(unix)]
+pub async fn watch_sighup(reloader: Arc<ConfigReloader>, env: Environment, sources: ConfigSources) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(signal) => signal,
+        Err(e) => {
+            tracing::error!("Failed to listen for SIGHUP, config reload disabled: {}", e);
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        tracing::info!("SIGHUP received, reloading configuration");
+        let config = match load_config(&env, &sources) {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::error!("Config reload rejected, keeping current configuration: {}", e);
+                continue;
+            }
+        };
+
+        let report = reloader.reload(config).await;
+        tracing::info!("Config reload: {}", report);
+        if !report.restart_required.is_empty() {
+            tracing::warn!(
+                "Restart required to apply: {}",
+                report.restart_required.join(", ")
+            );
+        }
+        for failure in &report.failed {
+            tracing::error!("Config reload failed to apply {}", failure);
+        }
+    }
+}
+
+/// SIGHUP does not exist off Unix; reload is unavailable.
+#[cfg(not(unix))]
+pub async fn watch_sighup(_reloader: Arc<ConfigReloader>, _env: Environment, _sources: ConfigSources) {
+    tracing::info!("Config reload on SIGHUP is not supported on this platform");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const POLICY: &str = r#"
+id: deletes
+name: Deletes
+rules:
+  - id: db
+    condition: "action == 'drop_table'"
+    action: deny
+    message: "no"
+"#;
+
+    fn policy_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("agentkern-reload-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("deletes.yaml"), POLICY).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_live_and_reports_restart() {
+        let state = Arc::new(ServeState::new());
+        let reloader = ConfigReloader::new(RuntimeConfig::default(), Arc::clone(&state));
+        reloader.initialize().await.unwrap();
+
+        let dir = policy_dir("live");
+        let new = RuntimeConfig {
+            log_level: "debug".to_string(),
+            rate_limit: Some(100),
+            policy_paths: vec![dir.clone()],
+            http_port: 8080,
+            database_url: Some("postgres://db/agentkern".to_string()),
+            ..RuntimeConfig::default()
+        };
+
+        let report = reloader.reload(new).await;
+        assert_eq!(report.applied, ["log_level=debug", "rate_limit=100/s", "policies (+1 ~0 -0)"]);
+        assert_eq!(report.restart_required, ["http_port", "database_url"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(state.engine().get_policies().await.len(), 1);
+
+        // Restart-only settings keep their running values
+        let current = reloader.current().await;
+        assert_eq!(current.http_port, 3000);
+        assert_eq!(current.rate_limit, Some(100));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_policy_reload_keeps_current() {
+        let dir = policy_dir("failed");
+        let state = Arc::new(ServeState::new());
+        let config = RuntimeConfig { policy_paths: vec![dir.clone()], ..RuntimeConfig::default() };
+        let reloader = ConfigReloader::new(config.clone(), Arc::clone(&state));
+        reloader.initialize().await.unwrap();
+
+        std::fs::write(dir.join("broken.yaml"), "id: [not a policy").unwrap();
+        let report = reloader.reload(config).await;
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.to_string().starts_with("failed: policies"));
+        assert_eq!(state.engine().get_policies().await.len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,69 +2,1219 @@
 //!
 //! Serves AgentKern on any environment.
 //! Uses standard protocols (HTTP, gRPC, WebSocket).
+//!
+//! HTTP routes (`RuntimeConfig::http_port`):
+//! - `POST /verify`  - Gate policy verification
+//! - `POST /attest`  - TEE attestation bound to a nonce
+//! - `GET  /health`  - Liveness
+//! - `GET  /metrics` - Prometheus metrics
+//! - `GET  /policies` - Loaded policies
+//! - `PUT  /policies` - Replace policies (hot reload; rejected on lint errors)
+//! - `GET  /canary`  - Candidate engine divergence report and promotion verdict
+//! - `GET  /agents/{agent_id}` - Agent 360 view (`?fields=wallet,locks&limit=20&cursor=…`)
+//! - `POST /identity/keys`, `POST /identity/keys/{key_id}/rotate`,
+//!   `DELETE /identity/keys/{key_id}`, `POST /identity/tokens` - Credentials
+//!
+//! With [`ServeState::with_identity`] every route except `/health` and
+//! `/metrics` requires credentials (see [`crate::identity`]): `verify` scope
+//! for verification, attestation and reading policies, `admin` for the rest.
+//! Non-admin callers may only verify as their own agent.
+//!
+//! The agent view needs `verify` scope and non-admin callers only see their
+//! own agent. Fields follow the caller's scopes through a [`FieldAccess`]:
+//! by default `verify` sees trust, locks, verifications, escalations and
+//! drift alerts, `payments` sees wallet and cost, `admin` sees everything.
+//!
+//! gRPC (`grpc` feature, `RuntimeConfig::grpc_port`): `agentkern.runtime.v1.Runtime`
+//! with `Verify`, `Attest` and `Health`; see `proto/runtime.proto`. Credentials
+//! go in the same headers as HTTP (`authorization`, `x-api-key`, ...) as metadata.
+//!
+//! On SIGINT/SIGTERM both listeners stop accepting, in-flight requests drain
+//! (bounded by `RuntimeConfig::drain_timeout_secs`), then the
+//! [`ShutdownCoordinator`] flushes the audit journal and runs other hooks.
+//! Verification is rate limited per second when `RuntimeConfig::rate_limit`
+//! is set (HTTP 429 / gRPC `RESOURCE_EXHAUSTED`).
+//!
+//! A W3C `traceparent` / `tracestate` header (gRPC: metadata) on `verify`
+//! is propagated into the verification; the result carries its span context.
+//!
+//! Failures are reported with `agentkern-errors` codes: HTTP responds with
+//! the code's status and an [`AgentKernError`] JSON body (plus `Retry-After`
+//! when known); gRPC uses the code's status and sets `x-agentkern-error-code`
+//! and `x-agentkern-retryable` metadata.
 
-use crate::config::{RuntimeConfig, Protocol};
+use crate::config::RuntimeConfig;
+use crate::identity::{AgentIdentity, IdentityError, IdentityRegistry, IssuedKey, Scope};
+use crate::shutdown::{ShutdownCoordinator, ShutdownPhase};
+use agentkern_arbiter::audit::{AuditLedger, AuditOutcome, AuditRecord};
+use agentkern_errors::{AgentKernError, Coded, ErrorCode};
+use agentkern_gate::canary::{CanaryHarness, DivergenceReport, PromotionCriteria, PromotionVerdict};
+use agentkern_gate::engine::VerificationRequestBuilder;
+use agentkern_telemetry::TraceContext;
+use agentkern_gate::observability::ObservabilityPlane;
+use agentkern_gate::tee::{Attestation, TeeError, TeeRuntime};
+use agentkern_gate::{
+    FileSource, GateEngine, Policy, PolicyDiff, PolicySource, PolicySourceError, StaticSource,
+    VerificationResult,
+};
+use agentkern_orchestration::{AgentQuery, AgentSnapshot, AgentView, FieldAccess, PageRequest, ViewField};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Extension, Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+pub use crate::config::Protocol;
+
+/// How long listeners may keep open connections once shutdown hooks have run.
+const LISTENER_GRACE: Duration = Duration::from_secs(1);
+
+/// Shared state behind every endpoint.
+pub struct ServeState {
+    engine: GateEngine,
+    tee: TeeRuntime,
+    observability: ObservabilityPlane,
+    limiter: RateLimiter,
+    audit: Arc<AuditLedger>,
+    shutdown: ShutdownCoordinator,
+    identity: Option<Arc<IdentityRegistry>>,
+    canary: Option<(CanaryHarness, PromotionCriteria)>,
+    agent_view: Option<Arc<AgentView>>,
+    field_access: FieldAccess,
+    started: Instant,
+}
+
+impl ServeState {
+    /// Create state with a default engine and the detected TEE
+    /// (simulated when no TEE hardware is present).
+    pub fn new() -> Self {
+        Self::with_engine(GateEngine::new())
+    }
+
+    /// Create state around a configured engine.
+    pub fn with_engine(engine: GateEngine) -> Self {
+        let tee = TeeRuntime::detect().unwrap_or_else(|_| TeeRuntime::simulated());
+        Self {
+            engine,
+            tee,
+            observability: ObservabilityPlane::new(),
+            limiter: RateLimiter::default(),
+            audit: Arc::new(AuditLedger::new()),
+            shutdown: ShutdownCoordinator::default(),
+            identity: None,
+            canary: None,
+            agent_view: None,
+            field_access: scope_field_access(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Set the deadline for draining in-flight work and running shutdown hooks.
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown = ShutdownCoordinator::new(timeout);
+        self
+    }
+
+    /// Append the audit ledger to a JSON Lines journal on shutdown.
+    pub fn with_audit_journal(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let audit = Arc::clone(&self.audit);
+        self.shutdown.on_shutdown("audit-journal", ShutdownPhase::Flush, move || async move {
+            let count = audit.flush_to(&path).await.map_err(|e| e.to_string())?;
+            tracing::info!("Flushed {} audit records to {}", count, path.display());
+            Ok(())
+        });
+        self
+    }
+
+    /// Require callers to authenticate against `registry`.
+    pub fn with_identity(mut self, registry: Arc<IdentityRegistry>) -> Self {
+        self.identity = Some(registry);
+        self
+    }
+
+    /// Mirror a sample of verifications to a candidate engine; responses
+    /// always come from the primary engine.
+    pub fn with_canary(mut self, canary: CanaryHarness, criteria: PromotionCriteria) -> Self {
+        self.canary = Some((canary, criteria));
+        self
+    }
+
+    /// Serve `GET /agents/{agent_id}` from `view`, with fields granted per scope.
+    pub fn with_agent_view(mut self, view: Arc<AgentView>) -> Self {
+        self.agent_view = Some(view);
+        self
+    }
+
+    /// Replace the scope-to-field grants of the agent view (roles are scope names).
+    pub fn with_field_access(mut self, access: FieldAccess) -> Self {
+        self.field_access = access;
+        self
+    }
+
+    /// Caller credentials, when authentication is enabled.
+    pub fn identity(&self) -> Option<&Arc<IdentityRegistry>> {
+        self.identity.as_ref()
+    }
+
+    /// Authenticate the caller from its headers and check it holds `scope`.
+    ///
+    /// Returns `None` when authentication is disabled.
+    pub fn authorize<'a>(
+        &self,
+        headers: impl Fn(&str) -> Option<&'a str>,
+        scope: Scope,
+    ) -> Result<Option<AgentIdentity>, IdentityError> {
+        let Some(registry) = &self.identity else {
+            return Ok(None);
+        };
+        let presented = registry.presented(headers).ok_or(IdentityError::MissingCredentials)?;
+        let identity = registry.authenticate(&presented)?;
+        identity.require(scope)?;
+        Ok(Some(identity))
+    }
+
+    /// The verification engine.
+    pub fn engine(&self) -> &GateEngine {
+        &self.engine
+    }
+
+    /// Audit trail of every verification.
+    pub fn audit(&self) -> &Arc<AuditLedger> {
+        &self.audit
+    }
+
+    /// Shutdown coordinator; register flush/release hooks here.
+    pub fn shutdown(&self) -> &ShutdownCoordinator {
+        &self.shutdown
+    }
+
+    /// Set the verification rate limit (requests per second, `None` = unlimited).
+    pub fn set_rate_limit(&self, limit: Option<u32>) {
+        self.limiter.set_limit(limit);
+    }
+
+    /// Replace the engine's policies with those under `paths`.
+    ///
+    /// All paths are read before anything changes; on error the current
+    /// policies stay in place.
+    pub async fn load_policies(&self, paths: &[PathBuf]) -> Result<PolicyDiff, PolicySourceError> {
+        let mut policies = Vec::new();
+        for path in paths {
+            policies.extend(FileSource::new(path).load()?);
+        }
+        self.engine.reload_from(&StaticSource::new(policies)).await
+    }
+
+    async fn verify(
+        &self,
+        agent_id: String,
+        action: String,
+        context: HashMap<String, serde_json::Value>,
+        trace: Option<TraceContext>,
+    ) -> VerificationResult {
+        let mut builder = VerificationRequestBuilder::new(agent_id.clone(), action.clone());
+        for (key, value) in context {
+            builder = builder.context(key, value);
+        }
+        if let Some(trace) = trace {
+            builder = builder.trace(trace);
+        }
+
+        let request = builder.build();
+        let mirror = self.canary.as_ref().and_then(|(canary, _)| canary.sample(&request));
+        let result = self.engine.verify(request).await;
+        if let (Some((canary, _)), Some(mirror)) = (&self.canary, mirror) {
+            canary.mirror(mirror, &result);
+        }
+        self.observability.metrics().record_request(
+            result.allowed,
+            result.latency.symbolic_us,
+            result.latency.neural_us.unwrap_or(0),
+        );
+
+        let (outcome, policies) = if result.allowed {
+            (AuditOutcome::Allowed, &result.evaluated_policies)
+        } else {
+            (AuditOutcome::Denied, &result.blocking_policies)
+        };
+        self.audit
+            .record(
+                AuditRecord::new(agent_id, action, policies.join(","), result.final_risk_score, outcome)
+                    .with_reasoning(result.reasoning.clone())
+                    .with_latency(result.latency.total_us),
+            )
+            .await;
+        result
+    }
+
+    fn attest(&self, nonce: &str) -> Result<Attestation, TeeError> {
+        self.tee.get_attestation(nonce.as_bytes())
+    }
+
+    fn metrics(&self) -> String {
+        format!(
+            "{}\n# HELP agentkern_uptime_seconds Seconds since the server started\n\
+             # TYPE agentkern_uptime_seconds gauge\nagentkern_uptime_seconds {}\n",
+            self.observability.prometheus_metrics(),
+            self.started.elapsed().as_secs()
+        )
+    }
+}
+
+/// Fixed one-second window limiter; lock-free while under the limit.
+#[derive(Default)]
+struct RateLimiter {
+    /// Requests per second; 0 = unlimited
+    limit: AtomicU32,
+    /// Requests admitted in the current window
+    count: AtomicU32,
+    window: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Change the limit and start a fresh window.
+    fn set_limit(&self, limit: Option<u32>) {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        *window = Some(Instant::now());
+        self.count.store(0, Ordering::Release);
+        self.limit.store(limit.unwrap_or(0), Ordering::Release);
+    }
+
+    fn admit(&self) -> bool {
+        let limit = self.limit.load(Ordering::Acquire);
+        if limit == 0 {
+            return true;
+        }
+        if self.count.fetch_add(1, Ordering::AcqRel) < limit {
+            return true;
+        }
+
+        // Over the limit: start a new window if the current one has expired.
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match *window {
+            Some(start) if now.duration_since(start) < Duration::from_secs(1) => false,
+            _ => {
+                *window = Some(now);
+                self.count.store(1, Ordering::Release);
+                true
+            }
+        }
+    }
+}
+
+impl Default for ServeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-/// Serve AgentKern with the given configuration.
+/// Serve AgentKern with the given configuration until SIGINT/SIGTERM.
 pub async fn serve(config: &RuntimeConfig) -> Result<(), ServeError> {
+    serve_with_shutdown(config, Arc::new(ServeState::new()), shutdown_signal()).await
+}
+
+/// Serve AgentKern until `shutdown` resolves, then drain in-flight requests.
+pub async fn serve_with_shutdown(
+    config: &RuntimeConfig,
+    state: Arc<ServeState>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), ServeError> {
     let addr = SocketAddr::new(config.bind_address, config.http_port);
-    
+
     tracing::info!("AgentKern starting on {}", addr);
     tracing::info!("Protocols: {:?}", config.protocols);
     tracing::info!("Resource mode: {:?}", config.resource_mode);
-    
-    // Log enabled protocols
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let drain_state = Arc::clone(&state);
+    let drain = async move {
+        shutdown.await;
+        tracing::info!("Shutting down gracefully...");
+        let _ = stop_tx.send(true);
+        drain_state.shutdown.shutdown().await
+    };
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| ServeError::Bind(format!("{}: {}", addr, e)))?;
+    let http = axum::serve(listener, router(Arc::clone(&state)))
+        .with_graceful_shutdown(stopped(stop_rx.clone()));
+    tracing::info!("HTTP enabled on port {}", config.http_port);
+
+    let grpc = serve_grpc(config, state, stop_rx.clone());
+
     for protocol in &config.protocols {
         match protocol {
-            Protocol::Http => tracing::info!("HTTP enabled on port {}", config.http_port),
-            Protocol::Grpc => {
-                if let Some(port) = config.grpc_port {
-                    tracing::info!("gRPC enabled on port {}", port);
-                }
-            }
+            Protocol::Http | Protocol::Grpc => {}
             Protocol::WebSocket => tracing::info!("WebSocket enabled"),
             Protocol::A2A => tracing::info!("A2A protocol enabled"),
             Protocol::Mcp => tracing::info!("MCP protocol enabled"),
         }
     }
-    
-    // In a full implementation, we would start the actual servers here
-    // For now, this is the interface contract
-    
+
     tracing::info!("AgentKern running. Press Ctrl+C to stop.");
-    
-    // Wait for shutdown signal
-    tokio::signal::ctrl_c().await.map_err(|e| ServeError::Signal(e.to_string()))?;
-    
-    tracing::info!("Shutting down gracefully...");
-    
+
+    let servers = async { tokio::join!(http, grpc) };
+    tokio::pin!(servers, drain);
+
+    // Listeners exit on their own only after shutdown starts, or on failure.
+    let (report, listeners) = tokio::select! {
+        report = &mut drain => (report, tokio::time::timeout(LISTENER_GRACE, servers).await.ok()),
+        listeners = &mut servers => {
+            if !*stop_rx.borrow() {
+                check_listeners(listeners)?;
+                return Err(ServeError::Protocol("listeners stopped unexpectedly".to_string()));
+            }
+            (drain.await, Some(listeners))
+        }
+    };
+
+    if report.is_clean() {
+        tracing::info!("Shutdown complete");
+    } else {
+        tracing::warn!(
+            "Shutdown finished with {} abandoned operations and {} failed hooks",
+            report.abandoned,
+            report.hooks.iter().filter(|h| h.error.is_some()).count()
+        );
+    }
+    match listeners {
+        Some(listeners) => check_listeners(listeners),
+        None => {
+            tracing::warn!("Closing connections still open after shutdown");
+            Ok(())
+        }
+    }
+}
+
+fn check_listeners(
+    (http, grpc): (std::io::Result<()>, Result<(), ServeError>),
+) -> Result<(), ServeError> {
+    http.map_err(|e| ServeError::Protocol(format!("HTTP: {}", e)))?;
+    grpc
+}
+
+/// Build the HTTP router.
+pub fn router(state: Arc<ServeState>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/metrics", get(metrics))
+        .route("/verify", post(verify))
+        .route("/attest", post(attest))
+        .route("/policies", get(list_policies).put(replace_policies))
+        .route("/canary", get(canary_report))
+        .route("/agents/{agent_id}", get(agent_view))
+        .route("/identity/keys", post(issue_key))
+        .route("/identity/keys/{key_id}/rotate", post(rotate_key))
+        .route("/identity/keys/{key_id}", delete(revoke_key))
+        .route("/identity/tokens", post(issue_token))
+        .layer(middleware::from_fn_with_state(Arc::clone(&state), authenticate))
+        .with_state(state)
+}
+
+/// Scope a route requires; `None` for routes open to anyone.
+fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    match path {
+        "/health" | "/metrics" => None,
+        "/verify" | "/attest" => Some(Scope::Verify),
+        "/policies" if method == Method::GET => Some(Scope::Verify),
+        _ if path.starts_with("/agents/") => Some(Scope::Verify),
+        _ => Some(Scope::Admin),
+    }
+}
+
+/// Reject unauthenticated or under-scoped callers; handlers see the
+/// [`AgentIdentity`] as a request extension.
+async fn authenticate(State(state): State<Arc<ServeState>>, mut request: Request, next: Next) -> Response {
+    let Some(scope) = required_scope(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let headers = request.headers();
+    match state.authorize(|name| headers.get(name).and_then(|v| v.to_str().ok()), scope) {
+        Ok(Some(identity)) => {
+            request.extensions_mut().insert(identity);
+            next.run(request).await
+        }
+        Ok(None) => next.run(request).await,
+        Err(e) => {
+            let challenge = e.code() == ErrorCode::Unauthenticated;
+            let mut response = ApiError(e.into()).into_response();
+            if challenge {
+                response.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            response
+        }
+    }
+}
+
+/// Resolve on SIGINT, or SIGTERM on Unix.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+async fn stopped(mut rx: watch::Receiver<bool>) {
+    let _ = rx.wait_for(|stop| *stop).await;
+}
+
+#[cfg(feature = "grpc")]
+async fn serve_grpc(
+    config: &RuntimeConfig,
+    state: Arc<ServeState>,
+    stop_rx: watch::Receiver<bool>,
+) -> Result<(), ServeError> {
+    let Some(port) = config.grpc_port.filter(|_| config.protocols.contains(&Protocol::Grpc)) else {
+        return Ok(());
+    };
+    let addr = SocketAddr::new(config.bind_address, port);
+    tracing::info!("gRPC enabled on port {}", port);
+
+    tonic::transport::Server::builder()
+        .add_service(grpc::RuntimeServer::new(grpc::GrpcService::new(state)))
+        .serve_with_shutdown(addr, stopped(stop_rx))
+        .await
+        .map_err(|e| ServeError::Protocol(format!("gRPC: {}", e)))
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn serve_grpc(
+    config: &RuntimeConfig,
+    _state: Arc<ServeState>,
+    _stop_rx: watch::Receiver<bool>,
+) -> Result<(), ServeError> {
+    if config.protocols.contains(&Protocol::Grpc) {
+        tracing::warn!("gRPC requested but the runtime was built without the `grpc` feature");
+    }
     Ok(())
 }
 
+// ============================================================================
+// HTTP handlers
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HealthResponse {
+    status: String,
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyBody {
+    agent_id: String,
+    action: String,
+    #[serde(default)]
+    context: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueBody {
+    agent_id: String,
+    scopes: Vec<Scope>,
+    /// Token lifetime; ignored for API keys
+    #[serde(default = "default_token_ttl")]
+    ttl_secs: u64,
+}
+
+fn default_token_ttl() -> u64 {
+    3600
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RotateBody {
+    /// How long the old secret keeps working
+    #[serde(default)]
+    grace_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AttestBody {
+    nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AttestResponse {
+    platform: String,
+    quote: String,
+    measurement: String,
+    nonce: String,
+    timestamp: u64,
+}
+
+async fn health() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "healthy".to_string(),
+        version: crate::VERSION.to_string(),
+    })
+}
+
+async fn metrics(State(state): State<Arc<ServeState>>) -> String {
+    state.metrics()
+}
+
+async fn verify(
+    State(state): State<Arc<ServeState>>,
+    identity: Option<Extension<AgentIdentity>>,
+    headers: HeaderMap,
+    Json(body): Json<VerifyBody>,
+) -> Result<Json<VerificationResult>, ApiError> {
+    if let Some(Extension(identity)) = identity {
+        identity.require_agent(&body.agent_id).map_err(|e| ApiError(e.into()))?;
+    }
+    let _op = state.shutdown.begin().ok_or_else(shutting_down)?;
+    if !state.limiter.admit() {
+        return Err(rate_limited().into());
+    }
+    let trace = TraceContext::from_headers(|name| headers.get(name).and_then(|v| v.to_str().ok()));
+    Ok(Json(state.verify(body.agent_id, body.action, body.context, trace).await))
+}
+
+async fn attest(
+    State(state): State<Arc<ServeState>>,
+    Json(body): Json<AttestBody>,
+) -> Result<Json<AttestResponse>, ApiError> {
+    let _op = state.shutdown.begin().ok_or_else(shutting_down)?;
+    let attestation = state.attest(&body.nonce).map_err(|e| ApiError(e.into()))?;
+
+    Ok(Json(AttestResponse {
+        platform: format!("{:?}", attestation.platform),
+        quote: hex(&attestation.quote),
+        measurement: hex(&attestation.measurement),
+        nonce: body.nonce,
+        timestamp: attestation.timestamp,
+    }))
+}
+
+async fn list_policies(State(state): State<Arc<ServeState>>) -> Json<Vec<Policy>> {
+    let mut policies = state.engine.get_policies().await;
+    policies.sort_by(|a, b| a.id.cmp(&b.id));
+    Json(policies)
+}
+
+async fn replace_policies(
+    State(state): State<Arc<ServeState>>,
+    Json(policies): Json<Vec<Policy>>,
+) -> Result<Json<PolicyDiff>, ApiError> {
+    let _op = state.shutdown.begin().ok_or_else(shutting_down)?;
+    state
+        .engine
+        .reload_from(&StaticSource::new(policies))
+        .await
+        .map(Json)
+        .map_err(|e| ApiError(e.into()))
+}
+
+#[derive(Debug, Serialize)]
+struct CanaryStatus {
+    report: DivergenceReport,
+    verdict: PromotionVerdict,
+}
+
+async fn canary_report(State(state): State<Arc<ServeState>>) -> Result<Json<CanaryStatus>, ApiError> {
+    let (canary, criteria) = state.canary.as_ref().ok_or_else(canary_disabled)?;
+    let report = canary.report();
+    let verdict = report.verdict(criteria);
+    Ok(Json(CanaryStatus { report, verdict }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AgentViewParams {
+    /// Comma-separated [`ViewField`] names; all fields when absent
+    fields: Option<String>,
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+/// Default grants of agent view fields to scopes.
+fn scope_field_access() -> FieldAccess {
+    FieldAccess::new()
+        .grant(
+            Scope::Verify.as_str(),
+            &[ViewField::Trust, ViewField::Locks, ViewField::Verifications, ViewField::Escalations, ViewField::DriftAlerts],
+        )
+        .grant(Scope::Payments.as_str(), &[ViewField::Wallet, ViewField::Cost])
+        .grant(Scope::Admin.as_str(), &ViewField::ALL)
+}
+
+async fn agent_view(
+    State(state): State<Arc<ServeState>>,
+    identity: Option<Extension<AgentIdentity>>,
+    Path(agent_id): Path<String>,
+    Query(params): Query<AgentViewParams>,
+) -> Result<Json<AgentSnapshot>, ApiError> {
+    let view = state.agent_view.as_ref().ok_or_else(agent_view_disabled)?;
+    let permitted = match identity {
+        Some(Extension(identity)) => {
+            identity.require_agent(&agent_id).map_err(|e| ApiError(e.into()))?;
+            let scopes: Vec<_> = identity.scopes.iter().map(Scope::as_str).collect();
+            state.field_access.permitted(&scopes)
+        }
+        None => ViewField::ALL.into_iter().collect(),
+    };
+
+    let mut page = PageRequest::new(params.limit.unwrap_or(agentkern_orchestration::view::DEFAULT_PAGE_SIZE));
+    if let Some(cursor) = params.cursor {
+        page = page.with_cursor(cursor);
+    }
+    let mut query = AgentQuery::default().with_page(page);
+    if let Some(fields) = params.fields {
+        let fields = fields
+            .split(',')
+            .map(|f| f.trim().parse::<ViewField>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ApiError(e.into()))?;
+        query = query.with_fields(fields);
+    }
+    view.query(&agent_id, &query, &permitted).await.map(Json).map_err(|e| ApiError(e.into()))
+}
+
+async fn issue_key(
+    State(state): State<Arc<ServeState>>,
+    Json(body): Json<IssueBody>,
+) -> Result<Json<IssuedKey>, ApiError> {
+    let registry = state.identity.as_ref().ok_or_else(identity_disabled)?;
+    Ok(Json(registry.issue_api_key(&body.agent_id, &body.scopes)))
+}
+
+async fn rotate_key(
+    State(state): State<Arc<ServeState>>,
+    Path(key_id): Path<String>,
+    body: Option<Json<RotateBody>>,
+) -> Result<Json<IssuedKey>, ApiError> {
+    let registry = state.identity.as_ref().ok_or_else(identity_disabled)?;
+    let Json(body) = body.unwrap_or_default();
+    registry
+        .rotate_api_key(&key_id, Duration::from_secs(body.grace_secs))
+        .map(Json)
+        .map_err(|e| ApiError(e.into()))
+}
+
+async fn revoke_key(
+    State(state): State<Arc<ServeState>>,
+    Path(key_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let registry = state.identity.as_ref().ok_or_else(identity_disabled)?;
+    registry.revoke_api_key(&key_id).map_err(|e| ApiError(e.into()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn issue_token(
+    State(state): State<Arc<ServeState>>,
+    Json(body): Json<IssueBody>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let registry = state.identity.as_ref().ok_or_else(identity_disabled)?;
+    let token = registry.issue_token(&body.agent_id, &body.scopes, Duration::from_secs(body.ttl_secs));
+    Ok(Json(TokenResponse { token }))
+}
+
+fn identity_disabled() -> AgentKernError {
+    AgentKernError::new(ErrorCode::Unsupported, "caller authentication is disabled")
+}
+
+fn agent_view_disabled() -> AgentKernError {
+    AgentKernError::new(ErrorCode::NotFound, "no agent view configured")
+}
+
+fn canary_disabled() -> AgentKernError {
+    AgentKernError::new(ErrorCode::NotFound, "no canary engine configured")
+}
+
+fn shutting_down() -> AgentKernError {
+    AgentKernError::new(ErrorCode::Unavailable, "shutting down")
+}
+
+fn rate_limited() -> AgentKernError {
+    AgentKernError::new(ErrorCode::RateLimited, "rate limit exceeded").with_retry_after(Duration::from_secs(1))
+}
+
+/// HTTP error response: the code's status with the error as JSON.
+#[derive(Debug)]
+struct ApiError(AgentKernError);
+
+impl From<AgentKernError> for ApiError {
+    fn from(error: AgentKernError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.0.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let retry_after = self.0.retry_after_ms.map(|ms| HeaderValue::from(ms.div_ceil(1000)));
+        let mut response = (status, Json(self.0)).into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, secs);
+        }
+        response
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ============================================================================
+// gRPC service
+// ============================================================================
+
+/// gRPC messages and service (see `proto/runtime.proto`).
+#[cfg(feature = "grpc")]
+pub mod grpc {
+    use super::ServeState;
+    use agentkern_telemetry::TraceContext;
+    use crate::identity::{AgentIdentity, IdentityError, Scope};
+    use agentkern_errors::{AgentKernError, ErrorCode};
+    use std::sync::Arc;
+    use tonic::metadata::MetadataValue;
+    use tonic::{Code, Request, Response, Status};
+
+    include!(concat!(env!("OUT_DIR"), "/agentkern.runtime.v1.Runtime.rs"));
+
+    pub use runtime_server::{Runtime, RuntimeServer};
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct VerifyRequest {
+        #[prost(string, tag = "1")]
+        pub agent_id: String,
+        #[prost(string, tag = "2")]
+        pub action: String,
+        /// JSON object of context values
+        #[prost(string, tag = "3")]
+        pub context_json: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct VerifyResponse {
+        #[prost(string, tag = "1")]
+        pub request_id: String,
+        #[prost(bool, tag = "2")]
+        pub allowed: bool,
+        #[prost(uint32, tag = "3")]
+        pub risk_score: u32,
+        #[prost(string, repeated, tag = "4")]
+        pub blocking_policies: Vec<String>,
+        #[prost(string, tag = "5")]
+        pub reasoning: String,
+        #[prost(uint64, tag = "6")]
+        pub latency_us: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AttestRequest {
+        #[prost(string, tag = "1")]
+        pub nonce: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AttestResponse {
+        #[prost(string, tag = "1")]
+        pub platform: String,
+        #[prost(bytes = "vec", tag = "2")]
+        pub quote: Vec<u8>,
+        #[prost(bytes = "vec", tag = "3")]
+        pub measurement: Vec<u8>,
+        #[prost(uint64, tag = "4")]
+        pub timestamp: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HealthRequest {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct HealthResponse {
+        #[prost(string, tag = "1")]
+        pub status: String,
+        #[prost(string, tag = "2")]
+        pub version: String,
+    }
+
+    /// gRPC status for an error, with its code and retryability as metadata.
+    pub fn status(error: AgentKernError) -> Status {
+        let mut status = Status::new(Code::from_i32(error.grpc_code()), error.message.clone());
+        let metadata = status.metadata_mut();
+        metadata.insert("x-agentkern-error-code", MetadataValue::from_static(error.code.as_str()));
+        metadata.insert(
+            "x-agentkern-retryable",
+            MetadataValue::from_static(if error.retryable { "true" } else { "false" }),
+        );
+        if let Some(ms) = error.retry_after_ms {
+            metadata.insert("x-agentkern-retry-after-ms", ms.into());
+        }
+        status
+    }
+
+    fn shutting_down() -> Status {
+        status(super::shutting_down())
+    }
+
+    /// gRPC front-end over the shared [`ServeState`].
+    pub struct GrpcService {
+        state: Arc<ServeState>,
+    }
+
+    impl GrpcService {
+        /// Create the service.
+        pub fn new(state: Arc<ServeState>) -> Self {
+            Self { state }
+        }
+
+        /// Authenticate the caller from request metadata and check `scope`.
+        fn authorize<T>(&self, request: &Request<T>, scope: Scope) -> Result<Option<AgentIdentity>, IdentityError> {
+            let metadata = request.metadata();
+            self.state.authorize(|name| metadata.get(name).and_then(|v| v.to_str().ok()), scope)
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Runtime for GrpcService {
+        async fn verify(&self, request: Request<VerifyRequest>) -> Result<Response<VerifyResponse>, Status> {
+            let identity = self.authorize(&request, Scope::Verify).map_err(|e| status(e.into()))?;
+            if let Some(identity) = identity {
+                identity
+                    .require_agent(&request.get_ref().agent_id)
+                    .map_err(|e| status(e.into()))?;
+            }
+            let _op = self.state.shutdown.begin().ok_or_else(shutting_down)?;
+            if !self.state.limiter.admit() {
+                return Err(status(super::rate_limited()));
+            }
+            let metadata = request.metadata();
+            let trace = TraceContext::from_headers(|name| metadata.get(name).and_then(|v| v.to_str().ok()));
+            let req = request.into_inner();
+            let context = if req.context_json.is_empty() {
+                Default::default()
+            } else {
+                serde_json::from_str(&req.context_json)
+                    .map_err(|e| {
+                        status(AgentKernError::new(ErrorCode::InvalidArgument, format!("context_json: {}", e)))
+                    })?
+            };
+
+            let result = self.state.verify(req.agent_id, req.action, context, trace).await;
+            Ok(Response::new(VerifyResponse {
+                request_id: result.request_id.to_string(),
+                allowed: result.allowed,
+                risk_score: result.final_risk_score.into(),
+                blocking_policies: result.blocking_policies,
+                reasoning: result.reasoning,
+                latency_us: result.latency.total_us,
+            }))
+        }
+
+        async fn attest(&self, request: Request<AttestRequest>) -> Result<Response<AttestResponse>, Status> {
+            self.authorize(&request, Scope::Verify).map_err(|e| status(e.into()))?;
+            let _op = self.state.shutdown.begin().ok_or_else(shutting_down)?;
+            let attestation = self
+                .state
+                .attest(&request.into_inner().nonce)
+                .map_err(|e| status(e.into()))?;
+
+            Ok(Response::new(AttestResponse {
+                platform: format!("{:?}", attestation.platform),
+                quote: attestation.quote,
+                measurement: attestation.measurement,
+                timestamp: attestation.timestamp,
+            }))
+        }
+
+        async fn health(&self, _request: Request<HealthRequest>) -> Result<Response<HealthResponse>, Status> {
+            Ok(Response::new(HealthResponse {
+                status: "healthy".to_string(),
+                version: crate::VERSION.to_string(),
+            }))
+        }
+    }
+}
+
 /// Server error.
 #[derive(Debug, thiserror::Error)]
 pub enum ServeError {
     #[error("Bind error: {0}")]
     Bind(String),
-    
+
     #[error("Signal error: {0}")]
     Signal(String),
-    
+
     #[error("Protocol error: {0}")]
     Protocol(String),
 }
 
-pub use crate::config::Protocol;
+impl Coded for ServeError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Bind(_) => ErrorCode::Unavailable,
+            Self::Signal(_) | Self::Protocol(_) => ErrorCode::Internal,
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::RuntimeConfig;
+    use std::net::{IpAddr, Ipv4Addr, TcpListener};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     #[test]
     fn test_serve_config() {
         let config = RuntimeConfig::default();
         assert!(config.protocols.contains(&Protocol::Http));
     }
+
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    async fn http(port: u16, request: String) -> String {
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    fn post(path: &str, body: &str) -> String {
+        format!(
+            "POST {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_http_routes_and_graceful_shutdown() {
+        let config = RuntimeConfig {
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            http_port: free_port(),
+            protocols: vec![Protocol::Http],
+            ..RuntimeConfig::default()
+        };
+        let port = config.http_port;
+        let journal = std::env::temp_dir().join(format!("agentkern-audit-{}.jsonl", port));
+        let state = Arc::new(ServeState::new().with_audit_journal(&journal));
+        let server_state = Arc::clone(&state);
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            serve_with_shutdown(&config, server_state, async {
+                let _ = stop_rx.await;
+            })
+            .await
+        });
+
+        let mut health = String::new();
+        for _ in 0..50 {
+            if tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+                health = http(port, "GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_string()).await;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(health.starts_with("HTTP/1.1 200"));
+        assert!(health.contains("healthy"));
+
+        let verify = http(port, post("/verify", r#"{"agent_id":"agent-1","action":"read_data"}"#)).await;
+        assert!(verify.starts_with("HTTP/1.1 200"));
+        assert!(verify.contains("\"allowed\":true"));
+
+        let attest = http(port, post("/attest", r#"{"nonce":"abc"}"#)).await;
+        assert!(attest.starts_with("HTTP/1.1 200"));
+        assert!(attest.contains("\"nonce\":\"abc\""));
+
+        let metrics = http(port, "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_string()).await;
+        assert!(metrics.contains("agentkern_gate_requests_total{status=\"allowed\"} 1"));
+
+        stop_tx.send(()).unwrap();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), server).await;
+        assert!(result.unwrap().unwrap().is_ok());
+
+        // New work is refused after shutdown; the verification was journaled
+        assert!(state.shutdown().begin().is_none());
+        let audit = std::fs::read_to_string(&journal).unwrap();
+        assert_eq!(audit.lines().count(), 1);
+        assert!(audit.contains("\"agent_id\":\"agent-1\""));
+        std::fs::remove_file(&journal).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_authentication() {
+        let identity = Arc::new(IdentityRegistry::new());
+        let admin = identity.issue_api_key("ops", &[Scope::Admin]);
+        let state = Arc::new(ServeState::new().with_identity(Arc::clone(&identity)));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+
+        let with_key = |request: String, key: &str| request.replacen("\r\n", &format!("\r\nAuthorization: Bearer {}\r\n", key), 1);
+        let body = r#"{"agent_id":"agent-1","action":"read_data"}"#;
+
+        let health = http(port, "GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_string()).await;
+        assert!(health.starts_with("HTTP/1.1 200"));
+        let missing = http(port, post("/verify", body)).await;
+        assert!(missing.starts_with("HTTP/1.1 401"));
+        assert!(missing.contains("AUTH_UNAUTHENTICATED"));
+
+        // Admin issues a verify-only key for agent-1
+        let issued = http(port, with_key(post("/identity/keys", r#"{"agent_id":"agent-1","scopes":["verify"]}"#), &admin.secret)).await;
+        assert!(issued.starts_with("HTTP/1.1 200"));
+        let json = issued.split("\r\n\r\n").nth(1).unwrap();
+        let key: IssuedKey = serde_json::from_str(json).unwrap();
+
+        assert!(http(port, with_key(post("/verify", body), &key.secret)).await.starts_with("HTTP/1.1 200"));
+        let other = http(port, with_key(post("/verify", r#"{"agent_id":"agent-2","action":"read_data"}"#), &key.secret)).await;
+        assert!(other.starts_with("HTTP/1.1 403"));
+        let admin_only = http(port, with_key(post("/identity/tokens", r#"{"agent_id":"agent-1","scopes":["admin"]}"#), &key.secret)).await;
+        assert!(admin_only.contains("AUTH_PERMISSION_DENIED"));
+
+        let revoke = format!("DELETE /identity/keys/{} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", key.key_id);
+        assert!(http(port, with_key(revoke, &admin.secret)).await.starts_with("HTTP/1.1 204"));
+        assert!(http(port, with_key(post("/verify", body), &key.secret)).await.starts_with("HTTP/1.1 401"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_view_fields_follow_scopes() {
+        let audit = Arc::new(AuditLedger::new());
+        audit.record(AuditRecord::new("agent-1", "read_data", "default", 5, AuditOutcome::Allowed)).await;
+        let view = Arc::new(AgentView::new().with_audit(audit).with_costs(Arc::new(agentkern_arbiter::CostTracker::new())));
+        let identity = Arc::new(IdentityRegistry::new());
+        let admin = identity.issue_api_key("ops", &[Scope::Admin]);
+        let agent = identity.issue_api_key("agent-1", &[Scope::Verify]);
+        let state = Arc::new(ServeState::new().with_identity(identity).with_agent_view(view));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+
+        let get = |path: &str, key: &str| {
+            format!("GET {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nConnection: close\r\n\r\n", path, key)
+        };
+
+        let own = http(port, get("/agents/agent-1", &agent.secret)).await;
+        assert!(own.starts_with("HTTP/1.1 200"));
+        let json: serde_json::Value = serde_json::from_str(own.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(json["verifications"]["total"], 1);
+        assert!(json.get("cost").is_none());
+        assert_eq!(json["redacted"], serde_json::json!(["wallet", "cost"]));
+
+        assert!(http(port, get("/agents/agent-2", &agent.secret)).await.starts_with("HTTP/1.1 403"));
+        let cost = http(port, get("/agents/agent-2?fields=cost", &admin.secret)).await;
+        assert!(cost.starts_with("HTTP/1.1 200") && cost.contains("\"total_usd\""));
+        assert!(http(port, get("/agents/agent-1?fields=bogus", &admin.secret)).await.starts_with("HTTP/1.1 400"));
+    }
+
+    #[test]
+    fn test_api_error_response() {
+        let response = ApiError(rate_limited()).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        let response = ApiError(shutting_down()).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::default();
+        assert!((0..100).all(|_| limiter.admit()));
+
+        limiter.set_limit(Some(2));
+        assert!(limiter.admit());
+        assert!(limiter.admit());
+        assert!(!limiter.admit());
+
+        // Expired window admits again
+        *limiter.window.lock().unwrap() = Some(Instant::now() - Duration::from_secs(2));
+        assert!(limiter.admit());
+        assert!(limiter.admit());
+        assert!(!limiter.admit());
+
+        limiter.set_limit(None);
+        assert!(limiter.admit());
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn test_grpc_service() {
+        use grpc::{Runtime, VerifyRequest};
+
+        let service = grpc::GrpcService::new(Arc::new(ServeState::new()));
+        let response = service
+            .verify(tonic::Request::new(VerifyRequest {
+                agent_id: "agent-1".to_string(),
+                action: "read_data".to_string(),
+                context_json: r#"{"amount":10}"#.to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.allowed);
+
+        let bad = service
+            .verify(tonic::Request::new(VerifyRequest {
+                agent_id: "agent-1".to_string(),
+                action: "read_data".to_string(),
+                context_json: "not json".to_string(),
+            }))
+            .await;
+        let bad = bad.unwrap_err();
+        assert_eq!(bad.code(), tonic::Code::InvalidArgument);
+        assert_eq!(bad.metadata().get("x-agentkern-error-code").unwrap(), "REQUEST_INVALID_ARGUMENT");
+        assert_eq!(bad.metadata().get("x-agentkern-retryable").unwrap(), "false");
+
+        let identity = Arc::new(IdentityRegistry::new());
+        let token = identity.issue_token("agent-1", &[Scope::Verify], Duration::from_secs(60));
+        let service = grpc::GrpcService::new(Arc::new(ServeState::new().with_identity(identity)));
+        let request = || VerifyRequest {
+            agent_id: "agent-1".to_string(),
+            action: "read_data".to_string(),
+            context_json: String::new(),
+        };
+        let denied = service.verify(tonic::Request::new(request())).await.unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::Unauthenticated);
+
+        let mut authed = tonic::Request::new(request());
+        authed.metadata_mut().insert("authorization", format!("Bearer {}", token).parse().unwrap());
+        assert!(service.verify(authed).await.unwrap().into_inner().allowed);
+    }
 }
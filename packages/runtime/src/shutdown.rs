@@ -0,0 +1,253 @@
+//! Shutdown Coordinator
+//!
+//! Ordered, bounded shutdown for everything the runtime hosts:
+//! 1. Stop admitting new work (requests get 503 / `UNAVAILABLE`)
+//! 2. Drain in-flight work, up to `RuntimeConfig::drain_timeout_secs`
+//! 3. [`ShutdownPhase::Flush`] hooks: audit ledgers, treasury journals
+//! 4. [`ShutdownPhase::Release`] hooks: held locks
+//!
+//! Hooks share the remaining deadline; a hook that overruns is reported
+//! and abandoned so the process can still exit.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let aggregator = Arc::new(MicropaymentAggregator::default());
+//! let journal = aggregator.clone();
+//! state.shutdown().on_shutdown("micropayments", ShutdownPhase::Flush, move || async move {
+//!     journal.flush_to_journal("/var/lib/agentkern/payments.jsonl").map(drop).map_err(|e| e.to_string())
+//! });
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+type HookFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+type Hook = Box<dyn FnOnce() -> HookFuture + Send>;
+
+/// When a shutdown hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShutdownPhase {
+    /// Persist buffered state (after in-flight work drains)
+    Flush,
+    /// Release external resources (after every flush)
+    Release,
+}
+
+/// Outcome of one hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookOutcome {
+    /// Hook name
+    pub name: String,
+    /// Phase it ran in
+    pub phase: ShutdownPhase,
+    /// Error, or `None` on success
+    pub error: Option<String>,
+}
+
+/// What happened during shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// All in-flight work finished before the deadline
+    pub drained: bool,
+    /// In-flight work still running when the deadline passed
+    pub abandoned: usize,
+    /// Hooks in the order they ran
+    pub hooks: Vec<HookOutcome>,
+}
+
+impl ShutdownReport {
+    /// Drained fully and every hook succeeded?
+    pub fn is_clean(&self) -> bool {
+        self.drained && self.hooks.iter().all(|h| h.error.is_none())
+    }
+}
+
+/// Tracks in-flight work and runs shutdown hooks.
+pub struct ShutdownCoordinator {
+    draining: AtomicBool,
+    in_flight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+    hooks: Mutex<Vec<(String, ShutdownPhase, Hook)>>,
+    drain_timeout: Duration,
+}
+
+impl ShutdownCoordinator {
+    /// Create a coordinator with the deadline for draining and hooks.
+    pub fn new(drain_timeout: Duration) -> Self {
+        Self {
+            draining: AtomicBool::new(false),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            idle: Arc::new(Notify::new()),
+            hooks: Mutex::new(Vec::new()),
+            drain_timeout,
+        }
+    }
+
+    /// Deadline for draining and hooks.
+    pub fn drain_timeout(&self) -> Duration {
+        self.drain_timeout
+    }
+
+    /// Shutdown started?
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Number of in-flight operations.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Start tracking an operation. `None` once shutdown has begun.
+    pub fn begin(&self) -> Option<InFlight> {
+        if self.is_draining() {
+            return None;
+        }
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        // Re-check: shutdown may have started between the check and the increment.
+        let guard = InFlight {
+            count: Arc::clone(&self.in_flight),
+            idle: Arc::clone(&self.idle),
+        };
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Register a hook. Hooks run once, by phase, in registration order.
+    pub fn on_shutdown<F, Fut>(&self, name: impl Into<String>, phase: ShutdownPhase, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let hook: Hook = Box::new(move || Box::pin(hook()));
+        self.hooks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name.into(), phase, hook));
+    }
+
+    /// Stop admitting work, drain, then run hooks. Later calls only drain.
+    pub async fn shutdown(&self) -> ShutdownReport {
+        let deadline = Instant::now() + self.drain_timeout;
+        self.draining.store(true, Ordering::Release);
+
+        let drained = self.wait_idle(deadline).await;
+        let abandoned = self.in_flight();
+        if drained {
+            tracing::info!("In-flight work drained");
+        } else {
+            tracing::warn!("Drain deadline passed with {} operations in flight", abandoned);
+        }
+
+        let mut hooks = std::mem::take(&mut *self.hooks.lock().unwrap_or_else(|e| e.into_inner()));
+        hooks.sort_by_key(|(_, phase, _)| *phase);
+
+        let mut outcomes = Vec::with_capacity(hooks.len());
+        for (name, phase, hook) in hooks {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let error = match tokio::time::timeout(remaining, hook()).await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e),
+                Err(_) => Some("deadline exceeded".to_string()),
+            };
+            match &error {
+                None => tracing::info!("Shutdown hook '{}' ({:?}) done", name, phase),
+                Some(e) => tracing::error!("Shutdown hook '{}' ({:?}) failed: {}", name, phase, e),
+            }
+            outcomes.push(HookOutcome { name, phase, error });
+        }
+
+        ShutdownReport { drained, abandoned, hooks: outcomes }
+    }
+
+    async fn wait_idle(&self, deadline: Instant) -> bool {
+        loop {
+            let notified = self.idle.notified();
+            if self.in_flight() == 0 {
+                return true;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if tokio::time::timeout(remaining, notified).await.is_err() {
+                return self.in_flight() == 0;
+            }
+        }
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+/// Marks one in-flight operation; finishing is signalled on drop.
+pub struct InFlight {
+    count: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drains_then_runs_hooks_in_phase_order() {
+        let coordinator = Arc::new(ShutdownCoordinator::new(Duration::from_secs(5)));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&order);
+        coordinator.on_shutdown("locks", ShutdownPhase::Release, move || async move {
+            log.lock().unwrap().push("locks");
+            Ok(())
+        });
+        let log = Arc::clone(&order);
+        coordinator.on_shutdown("ledger", ShutdownPhase::Flush, move || async move {
+            log.lock().unwrap().push("ledger");
+            Err("disk full".to_string())
+        });
+
+        let guard = coordinator.begin().unwrap();
+        let work = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(guard);
+        });
+
+        let report = coordinator.shutdown().await;
+        work.await.unwrap();
+        assert!(report.drained);
+        assert!(coordinator.begin().is_none());
+        assert_eq!(*order.lock().unwrap(), ["ledger", "locks"]);
+        assert_eq!(report.hooks[0].error.as_deref(), Some("disk full"));
+        assert!(!report.is_clean());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_abandons_stuck_work() {
+        let coordinator = ShutdownCoordinator::new(Duration::from_millis(50));
+        let _stuck = coordinator.begin().unwrap();
+        coordinator.on_shutdown("slow", ShutdownPhase::Flush, || async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        });
+
+        let report = coordinator.shutdown().await;
+        assert!(!report.drained);
+        assert_eq!(report.abandoned, 1);
+        assert_eq!(report.hooks[0].error.as_deref(), Some("deadline exceeded"));
+    }
+}
@@ -0,0 +1,35 @@
+//! Virtual Clock
+//!
+//! Simulated time in milliseconds. It only moves when the simulation
+//! advances it, so timeouts and TTLs are deterministic.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Wall-clock instant that virtual time 0 maps to.
+const EPOCH_SECS: i64 = 1_700_000_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct VirtualClock {
+    now_ms: u64,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Milliseconds since the start of the simulation.
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Current virtual time as a timestamp, for state machines that take `now`.
+    pub fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(EPOCH_SECS, 0).expect("valid epoch") + Duration::milliseconds(self.now_ms as i64)
+    }
+
+    /// Move forward to `ms`; time never goes backwards.
+    pub fn advance_to(&mut self, ms: u64) {
+        self.now_ms = self.now_ms.max(ms);
+    }
+}
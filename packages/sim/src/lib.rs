@@ -0,0 +1,28 @@
+//! AgentKern-Sim: Deterministic Simulation Testing
+//!
+//! Runs the coordination core's state machines (Arbiter locks and Raft
+//! replication, Treasury transfers) against a simulated network under a
+//! virtual clock. Every latency, drop, duplicate and partition is drawn from
+//! one seeded generator, so a failing schedule replays exactly from its seed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_sim::{scenarios, SimRunner};
+//!
+//! // Thousands of schedules; replay one with AGENTKERN_SIM_SEED=<seed>
+//! let summary = SimRunner::new("locks").with_seeds(0..5_000).run(scenarios::locks::run)?;
+//! println!("{} schedules, {} messages", summary.schedules, summary.messages);
+//! ```
+
+pub mod clock;
+pub mod network;
+pub mod rng;
+pub mod runner;
+pub mod scenarios;
+
+// Re-exports
+pub use clock::VirtualClock;
+pub use network::{FaultConfig, NetworkStats, SimNetwork};
+pub use rng::SimRng;
+pub use runner::{RunSummary, SimFailure, SimReport, SimRunner, Trace, Violation};
@@ -0,0 +1,138 @@
+//! Schedule Runner
+//!
+//! Runs a scenario once per seed and stops at the first invariant
+//! violation, reporting the seed so the failing schedule can be replayed
+//! with `AGENTKERN_SIM_SEED=<seed>`.
+
+use crate::network::NetworkStats;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Environment variable that restricts a run to one seed.
+pub const SEED_ENV: &str = "AGENTKERN_SIM_SEED";
+
+/// A broken invariant.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("at t={at_ms}ms: {message}")]
+pub struct Violation {
+    pub at_ms: u64,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(at_ms: u64, message: impl Into<String>) -> Self {
+        Self { at_ms, message: message.into() }
+    }
+}
+
+/// A failing seed.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{scenario} failed on seed {seed} (replay with {SEED_ENV}={seed}) {violation}")]
+pub struct SimFailure {
+    pub scenario: String,
+    pub seed: u64,
+    pub violation: Violation,
+}
+
+/// Order-sensitive digest of the events in one schedule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Trace {
+    digest: u64,
+    events: u64,
+}
+
+impl Trace {
+    pub fn record(&mut self, event: impl Hash) {
+        let mut hasher = DefaultHasher::new();
+        self.digest.hash(&mut hasher);
+        event.hash(&mut hasher);
+        self.digest = hasher.finish();
+        self.events += 1;
+    }
+
+    pub fn digest(&self) -> u64 {
+        self.digest
+    }
+
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+}
+
+/// Outcome of one schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimReport {
+    pub seed: u64,
+    /// Virtual time at the end
+    pub end_ms: u64,
+    pub trace: Trace,
+    pub network: NetworkStats,
+}
+
+/// Totals over all schedules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunSummary {
+    pub schedules: u64,
+    pub events: u64,
+    pub messages: u64,
+}
+
+/// Runs a scenario across many seeds.
+#[derive(Debug, Clone)]
+pub struct SimRunner {
+    scenario: String,
+    seeds: Range<u64>,
+}
+
+impl SimRunner {
+    /// 1000 schedules by default.
+    pub fn new(scenario: impl Into<String>) -> Self {
+        Self { scenario: scenario.into(), seeds: 0..1000 }
+    }
+
+    pub fn with_seeds(mut self, seeds: Range<u64>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    /// Run every seed (or only `AGENTKERN_SIM_SEED` if set).
+    pub fn run(&self, mut scenario: impl FnMut(u64) -> Result<SimReport, Violation>) -> Result<RunSummary, SimFailure> {
+        let seeds = match std::env::var(SEED_ENV).ok().and_then(|s| s.parse::<u64>().ok()) {
+            Some(seed) => seed..seed + 1,
+            None => self.seeds.clone(),
+        };
+        let mut summary = RunSummary::default();
+        for seed in seeds {
+            let report = scenario(seed).map_err(|violation| SimFailure {
+                scenario: self.scenario.clone(),
+                seed,
+                violation,
+            })?;
+            summary.schedules += 1;
+            summary.events += report.trace.events();
+            summary.messages += report.network.sent;
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runner_reports_failing_seed() {
+        let runner = SimRunner::new("demo").with_seeds(0..10);
+        let failure = runner
+            .run(|seed| {
+                if seed == 7 {
+                    return Err(Violation::new(12, "two lock holders"));
+                }
+                Ok(SimReport { seed, end_ms: 0, trace: Trace::default(), network: NetworkStats::default() })
+            })
+            .unwrap_err();
+        assert_eq!(failure.seed, 7);
+        assert!(failure.to_string().contains("AGENTKERN_SIM_SEED=7"));
+    }
+}
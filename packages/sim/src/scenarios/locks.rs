@@ -0,0 +1,177 @@
+//! Lock Service Scenario
+//!
+//! Clients lease locks from a single [`LockStateMachine`] over a lossy
+//! network, with timeouts, retries and partitions between server and
+//! clients. A client treats itself as the holder from the grant until it
+//! releases or until `sent_at + ttl`, the earliest the server could let the
+//! lease expire.
+//!
+//! Invariant: at most one client holds each resource at any instant.
+
+use crate::clock::VirtualClock;
+use crate::network::{FaultConfig, NodeId, SimNetwork};
+use crate::rng::SimRng;
+use crate::runner::{SimReport, Trace, Violation};
+use agentkern_arbiter::raft::{LockCommand, LockStateMachine};
+
+const SERVER: NodeId = 0;
+const RESOURCES: [&str; 2] = ["db:accounts", "db:orders"];
+const TTL_MS: u64 = 200;
+const TIMEOUT_MS: u64 = 100;
+const HORIZON_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Hash)]
+enum LockMsg {
+    Acquire { request_id: u64, resource: usize, owner: String },
+    Release { resource: usize, owner: String },
+    Granted { request_id: u64, ok: bool },
+}
+
+#[derive(Debug, Clone)]
+enum ClientState {
+    Idle { retry_at: u64 },
+    Waiting { request_id: u64, resource: usize, owner: String, sent_at: u64 },
+    Holding { resource: usize, owner: String, until: u64 },
+}
+
+struct Client {
+    id: NodeId,
+    state: ClientState,
+    leases: u64,
+}
+
+impl Client {
+    fn wake_at(&self) -> u64 {
+        match &self.state {
+            ClientState::Idle { retry_at } => *retry_at,
+            ClientState::Waiting { sent_at, .. } => sent_at + TIMEOUT_MS,
+            ClientState::Holding { until, .. } => *until,
+        }
+    }
+
+    fn holds(&self, resource: usize, now: u64) -> bool {
+        matches!(&self.state, ClientState::Holding { resource: r, until, .. } if *r == resource && *until > now)
+    }
+}
+
+/// Run one schedule.
+pub fn run(seed: u64) -> Result<SimReport, Violation> {
+    let mut rng = SimRng::new(seed);
+    let mut clock = VirtualClock::new();
+    let mut net = SimNetwork::new(FaultConfig::default());
+    let mut trace = Trace::default();
+    let mut server = LockStateMachine::new();
+    let mut next_request = 0u64;
+
+    let client_count = rng.range(2..=4);
+    let mut clients: Vec<Client> = (1..=client_count)
+        .map(|id| Client { id, state: ClientState::Idle { retry_at: rng.range(0..=20) }, leases: 0 })
+        .collect();
+    let mut chaos_at = rng.range(100..=400);
+
+    loop {
+        let next = clients
+            .iter()
+            .map(Client::wake_at)
+            .chain(net.next_at())
+            .chain([chaos_at])
+            .min()
+            .unwrap_or(HORIZON_MS);
+        if next > HORIZON_MS {
+            break;
+        }
+        clock.advance_to(next);
+        let now = clock.now_ms();
+
+        if chaos_at <= now {
+            if net.is_partitioned() {
+                net.heal();
+            } else if rng.chance(0.5) {
+                let cut = 1 + rng.index(clients.len()) as NodeId;
+                let rest = (0..=client_count).filter(|&n| n != cut).collect();
+                net.partition(vec![vec![cut], rest]);
+            }
+            trace.record(("chaos", now, net.is_partitioned()));
+            chaos_at = now + rng.range(100..=400);
+        }
+
+        while let Some(envelope) = net.deliver_due(now) {
+            trace.record((now, envelope.from, envelope.to, &envelope.message));
+            match envelope.message {
+                LockMsg::Acquire { request_id, resource, owner } => {
+                    let command = LockCommand::Acquire {
+                        resource: RESOURCES[resource].to_string(),
+                        agent_id: owner,
+                        priority: 0,
+                        ttl_ms: TTL_MS,
+                    };
+                    let ok = server.apply_at(&command, clock.now()).unwrap_or(false);
+                    net.send(&mut rng, now, SERVER, envelope.from, LockMsg::Granted { request_id, ok });
+                }
+                LockMsg::Release { resource, owner } => {
+                    let command = LockCommand::Release { resource: RESOURCES[resource].to_string(), agent_id: owner };
+                    let _ = server.apply_at(&command, clock.now());
+                }
+                LockMsg::Granted { request_id, ok } => {
+                    let client = &mut clients[envelope.to as usize - 1];
+                    if let ClientState::Waiting { request_id: waiting, resource, owner, sent_at } = client.state.clone() {
+                        if waiting == request_id {
+                            client.state = if ok {
+                                client.leases += 1;
+                                let hold = rng.range(10..=150);
+                                ClientState::Holding { resource, owner, until: (now + hold).min(sent_at + TTL_MS) }
+                            } else {
+                                ClientState::Idle { retry_at: now + rng.range(10..=60) }
+                            };
+                        }
+                    }
+                }
+            }
+        }
+
+        for client in clients.iter_mut() {
+            if client.wake_at() > now {
+                continue;
+            }
+            client.state = match &client.state {
+                ClientState::Idle { .. } => {
+                    next_request += 1;
+                    let resource = rng.index(RESOURCES.len());
+                    // A fresh owner per attempt fences off stale releases
+                    let owner = format!("agent-{}-{}", client.id, next_request);
+                    let message = LockMsg::Acquire { request_id: next_request, resource, owner: owner.clone() };
+                    net.send(&mut rng, now, client.id, SERVER, message);
+                    ClientState::Waiting { request_id: next_request, resource, owner, sent_at: now }
+                }
+                ClientState::Waiting { .. } => ClientState::Idle { retry_at: now + rng.range(10..=60) },
+                ClientState::Holding { resource, owner, .. } => {
+                    let message = LockMsg::Release { resource: *resource, owner: owner.clone() };
+                    net.send(&mut rng, now, client.id, SERVER, message);
+                    ClientState::Idle { retry_at: now + rng.range(0..=40) }
+                }
+            };
+        }
+
+        for (resource, name) in RESOURCES.iter().enumerate() {
+            let holders: Vec<NodeId> = clients.iter().filter(|c| c.holds(resource, now)).map(|c| c.id).collect();
+            if holders.len() > 1 {
+                return Err(Violation::new(now, format!("{name} held by clients {holders:?}")));
+            }
+        }
+    }
+
+    trace.record(("leases", clients.iter().map(|c| c.leases).sum::<u64>()));
+    Ok(SimReport { seed, end_ms: clock.now_ms(), trace, network: net.stats() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locks_make_progress() {
+        let report = run(1).unwrap();
+        assert!(report.network.delivered > 0);
+        assert_eq!(report.end_ms, run(1).unwrap().end_ms);
+    }
+}
@@ -0,0 +1,10 @@
+//! Simulation Scenarios
+//!
+//! Each scenario runs one schedule per seed and returns a [`Violation`]
+//! the moment an invariant breaks.
+//!
+//! [`Violation`]: crate::runner::Violation
+
+pub mod locks;
+pub mod raft;
+pub mod treasury;
@@ -0,0 +1,223 @@
+//! Raft Replication Scenario
+//!
+//! A 3-5 node [`RaftLockManager`] cluster replicates lock commands while the
+//! network drops, duplicates and reorders messages and partitions the
+//! cluster. After the fault phase the network is healed and made reliable,
+//! and the cluster must converge.
+//!
+//! Invariants:
+//! - a committed entry never changes, and every node agrees on it
+//! - an entry is only committed once a majority stores it
+//! - after healing, every node commits the leader's full log and the
+//!   lock state machines agree
+
+use crate::clock::VirtualClock;
+use crate::network::{FaultConfig, NodeId, SimNetwork};
+use crate::rng::SimRng;
+use crate::runner::{SimReport, Trace, Violation};
+use agentkern_arbiter::raft::{AppendEntries, AppendResponse, LockCommand, LogEntry, RaftConfig, RaftLockManager};
+
+const LEADER: NodeId = 1;
+const HEARTBEAT_MS: u64 = 30;
+/// Proposals and partitions stop here
+const FAULT_PHASE_MS: u64 = 1_500;
+/// Convergence deadline after healing
+const HORIZON_MS: u64 = 30_000;
+/// Long enough that no lease expires while the state machines are compared
+const TTL_MS: u64 = 3_600_000;
+
+#[derive(Debug, Clone)]
+enum RaftMsg {
+    Append(AppendEntries),
+    Response(AppendResponse),
+}
+
+/// Committed entries as first observed, by index.
+#[derive(Default)]
+struct Oracle {
+    committed: Vec<LogEntry>,
+}
+
+impl Oracle {
+    /// Check `node`'s committed prefix against earlier commits, then learn new ones.
+    fn observe(&mut self, node: &RaftLockManager, now: u64) -> Result<(), Violation> {
+        let log = node.log();
+        for index in 0..node.commit_index() as usize {
+            let Some(actual) = log.get(index) else {
+                return Err(Violation::new(
+                    now,
+                    format!("node {} committed {} without the entry", node.node_id(), index + 1),
+                ));
+            };
+            match self.committed.get(index) {
+                Some(expected) if expected != actual => {
+                    return Err(Violation::new(
+                        now,
+                        format!("node {} changed committed entry {}", node.node_id(), index + 1),
+                    ));
+                }
+                Some(_) => {}
+                None => self.committed.push(actual.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Every committed entry must be stored on a majority.
+    fn check_majority(&self, nodes: &[RaftLockManager], now: u64) -> Result<(), Violation> {
+        let majority = nodes.len() / 2 + 1;
+        if let Some(last) = self.committed.last() {
+            let replicas = nodes.iter().filter(|n| n.log().get(last.index as usize - 1) == Some(last)).count();
+            if replicas < majority {
+                return Err(Violation::new(
+                    now,
+                    format!("entry {} committed on {replicas} of {} nodes", last.index, nodes.len()),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run one schedule.
+pub fn run(seed: u64) -> Result<SimReport, Violation> {
+    let mut rng = SimRng::new(seed);
+    let mut clock = VirtualClock::new();
+    let mut net = SimNetwork::new(FaultConfig::default());
+    let mut trace = Trace::default();
+    let mut oracle = Oracle::default();
+
+    let size = rng.range(3..=5);
+    let mut nodes: Vec<RaftLockManager> = (1..=size)
+        .map(|node_id| {
+            let peers = (1..=size).filter(|&p| p != node_id).collect();
+            RaftLockManager::new(RaftConfig { node_id, peers, ..RaftConfig::default() })
+        })
+        .collect();
+    nodes[0].become_leader();
+
+    let mut heartbeat_at = 0;
+    let mut propose_at = rng.range(5..=40);
+    let mut chaos_at = rng.range(100..=400);
+    let mut healed = false;
+
+    loop {
+        let next = [Some(heartbeat_at), Some(propose_at), Some(chaos_at), net.next_at()]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(HORIZON_MS);
+        if next > HORIZON_MS {
+            break;
+        }
+        clock.advance_to(next);
+        let now = clock.now_ms();
+
+        if !healed && now >= FAULT_PHASE_MS {
+            net.heal();
+            net.set_faults(FaultConfig::reliable());
+            propose_at = u64::MAX;
+            chaos_at = u64::MAX;
+            healed = true;
+            // A current-term entry lets the leader commit entries from earlier terms
+            let barrier = LockCommand::Heartbeat { resource: "sim".to_string(), agent_id: "leader".to_string() };
+            let _ = nodes[0].propose(barrier);
+            trace.record(("healed", now));
+        }
+
+        if chaos_at <= now {
+            if net.is_partitioned() {
+                net.heal();
+            } else {
+                let (mut left, mut right) = (Vec::new(), Vec::new());
+                for node_id in 1..=size {
+                    if rng.chance(0.5) { left.push(node_id) } else { right.push(node_id) }
+                }
+                net.partition(vec![left, right]);
+            }
+            // Occasionally re-elect the same leader, leaving stale-term messages in flight
+            if rng.chance(0.1) {
+                nodes[0].become_leader();
+            }
+            trace.record(("chaos", now, net.is_partitioned(), nodes[0].current_term()));
+            chaos_at = now + rng.range(100..=400);
+        }
+
+        if propose_at <= now {
+            let resource = format!("resource-{}", rng.range(0..=3));
+            let agent_id = format!("agent-{}", rng.range(0..=5));
+            let command = LockCommand::Acquire { resource, agent_id, priority: rng.range(0..=3) as i32, ttl_ms: TTL_MS };
+            if let Ok(index) = nodes[0].propose(command) {
+                trace.record(("propose", now, index));
+            }
+            propose_at = now + rng.range(5..=40);
+        }
+
+        if heartbeat_at <= now {
+            for peer in 2..=size {
+                if let Some(request) = nodes[0].append_request(peer) {
+                    net.send(&mut rng, now, LEADER, peer, RaftMsg::Append(request));
+                }
+            }
+            heartbeat_at = now + HEARTBEAT_MS;
+        }
+
+        while let Some(envelope) = net.deliver_due(now) {
+            let to = envelope.to as usize - 1;
+            match envelope.message {
+                RaftMsg::Append(request) => {
+                    trace.record((now, envelope.from, envelope.to, request.term, request.prev_log_index, request.entries.len()));
+                    let response = nodes[to].handle_append_entries(request);
+                    net.send(&mut rng, now, envelope.to, envelope.from, RaftMsg::Response(response));
+                }
+                RaftMsg::Response(response) => {
+                    trace.record((now, envelope.from, envelope.to, response.term, response.success, response.match_index));
+                    nodes[to].handle_append_response(envelope.from, response);
+                }
+            }
+            oracle.observe(&nodes[to], now)?;
+            oracle.check_majority(&nodes, now)?;
+        }
+
+        if healed && converged(&nodes) {
+            break;
+        }
+    }
+
+    let now = clock.now_ms();
+    if !converged(&nodes) {
+        let commits: Vec<u64> = nodes.iter().map(RaftLockManager::commit_index).collect();
+        return Err(Violation::new(
+            now,
+            format!("no convergence after healing: leader log {}, commits {commits:?}", nodes[0].log().len()),
+        ));
+    }
+    for resource in (0..=3).map(|r| format!("resource-{r}")) {
+        let holder = |node: &RaftLockManager| node.state_machine().read().get_lock(&resource).map(|l| l.agent_id.clone());
+        let expected = holder(&nodes[0]);
+        if let Some(node) = nodes.iter().find(|n| holder(n) != expected) {
+            return Err(Violation::new(now, format!("node {} disagrees on {resource}", node.node_id())));
+        }
+    }
+
+    trace.record(("converged", now, nodes[0].commit_index()));
+    Ok(SimReport { seed, end_ms: now, trace, network: net.stats() })
+}
+
+/// Every node has committed the leader's whole log.
+fn converged(nodes: &[RaftLockManager]) -> bool {
+    let last = nodes[0].log().len() as u64;
+    nodes.iter().all(|n| n.commit_index() == last && n.log().len() as u64 == last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_converges() {
+        let report = run(3).unwrap();
+        assert!(report.end_ms >= FAULT_PHASE_MS);
+        assert!(report.trace.events() > 0);
+    }
+}
@@ -0,0 +1,215 @@
+//! Treasury Scenario
+//!
+//! Agents pay each other through a [`TransferEngine`] over a lossy network.
+//! Lost replies make clients retry with the same idempotency key, and
+//! duplicated requests replay it, so every payment may reach the engine
+//! several times.
+//!
+//! Invariants:
+//! - no double spend: a key succeeds with one transaction id, and final
+//!   balances match applying each successful key exactly once
+//! - the total supply is conserved and no balance goes negative
+//! - nothing is left held in pending transfers
+
+use crate::clock::VirtualClock;
+use crate::network::{FaultConfig, NodeId, SimNetwork};
+use crate::rng::SimRng;
+use crate::runner::{SimReport, Trace, Violation};
+use agentkern_treasury::{Amount, BalanceLedger, Currency, TransactionId, TransferEngine, TransferRequest, TransferStatus};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+const SERVER: NodeId = 0;
+const INITIAL_BALANCE: i64 = 1_000;
+const TIMEOUT_MS: u64 = 100;
+const MAX_ATTEMPTS: u32 = 5;
+const HORIZON_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, Hash)]
+enum PayMsg {
+    Pay { key: String, to: NodeId, amount: i64 },
+    Receipt { key: String, ok: bool },
+}
+
+struct Payment {
+    key: String,
+    to: NodeId,
+    amount: i64,
+    sent_at: u64,
+    attempts: u32,
+}
+
+struct Client {
+    id: NodeId,
+    payment: Option<Payment>,
+    next_at: u64,
+    issued: u64,
+}
+
+impl Client {
+    fn wake_at(&self) -> u64 {
+        match &self.payment {
+            Some(payment) => payment.sent_at + TIMEOUT_MS,
+            None => self.next_at,
+        }
+    }
+}
+
+fn agent(id: NodeId) -> String {
+    format!("agent-{id}")
+}
+
+/// Drive a future that never waits; the engine's async API is synchronous underneath.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Run one schedule.
+pub fn run(seed: u64) -> Result<SimReport, Violation> {
+    let mut rng = SimRng::new(seed);
+    let mut clock = VirtualClock::new();
+    let mut net = SimNetwork::new(FaultConfig::default());
+    let mut trace = Trace::default();
+
+    let ledger = Arc::new(BalanceLedger::new(Currency::VMC));
+    let engine = TransferEngine::new(Arc::clone(&ledger));
+    let decimals = Currency::VMC.decimals();
+
+    let client_count = rng.range(2..=4);
+    for id in 1..=client_count {
+        ledger
+            .deposit(&agent(id), Amount::new(INITIAL_BALANCE, decimals))
+            .map_err(|e| Violation::new(0, e.to_string()))?;
+    }
+    let supply = INITIAL_BALANCE * client_count as i64;
+    let mut clients: Vec<Client> =
+        (1..=client_count).map(|id| Client { id, payment: None, next_at: rng.range(0..=20), issued: 0 }).collect();
+    // Successful keys: (from, to, amount, transaction id)
+    let mut settled: BTreeMap<String, (NodeId, NodeId, i64, TransactionId)> = BTreeMap::new();
+
+    loop {
+        let next = clients.iter().map(Client::wake_at).chain(net.next_at()).min().unwrap_or(HORIZON_MS);
+        if next > HORIZON_MS {
+            break;
+        }
+        clock.advance_to(next);
+        let now = clock.now_ms();
+
+        while let Some(envelope) = net.deliver_due(now) {
+            trace.record((now, envelope.from, envelope.to, &envelope.message));
+            match envelope.message {
+                PayMsg::Pay { key, to, amount } => {
+                    let from = envelope.from;
+                    let request = TransferRequest::new(agent(from), agent(to), Amount::new(amount, decimals))
+                        .with_idempotency_key(key.clone());
+                    let result = block_on(engine.transfer(request));
+                    let ok = result.status == TransferStatus::Completed;
+                    if ok {
+                        let first = settled.entry(key.clone()).or_insert((from, to, amount, result.transaction_id));
+                        if first.3 != result.transaction_id {
+                            return Err(Violation::new(now, format!("{key} settled twice")));
+                        }
+                    }
+                    check_ledger(&ledger, &engine, client_count, supply, now)?;
+                    net.send(&mut rng, now, SERVER, from, PayMsg::Receipt { key, ok });
+                }
+                PayMsg::Receipt { key, .. } => {
+                    let client = &mut clients[envelope.to as usize - 1];
+                    if client.payment.as_ref().is_some_and(|p| p.key == key) {
+                        client.payment = None;
+                        client.next_at = now + rng.range(5..=50);
+                    }
+                }
+            }
+        }
+
+        for client in clients.iter_mut() {
+            if client.wake_at() > now {
+                continue;
+            }
+            let payment = match client.payment.take() {
+                Some(payment) if payment.attempts >= MAX_ATTEMPTS => {
+                    client.next_at = now + rng.range(5..=50);
+                    continue;
+                }
+                Some(payment) => Payment { sent_at: now, attempts: payment.attempts + 1, ..payment },
+                None => {
+                    client.issued += 1;
+                    let mut to = 1 + rng.index(client_count as usize - 1) as NodeId;
+                    if to >= client.id {
+                        to += 1;
+                    }
+                    let key = format!("{}-{}", agent(client.id), client.issued);
+                    Payment { key, to, amount: rng.range(1..=300) as i64, sent_at: now, attempts: 1 }
+                }
+            };
+            let message = PayMsg::Pay { key: payment.key.clone(), to: payment.to, amount: payment.amount };
+            net.send(&mut rng, now, client.id, SERVER, message);
+            client.payment = Some(payment);
+        }
+    }
+
+    // Final balances must equal applying every settled payment exactly once
+    let mut expected: BTreeMap<NodeId, i64> = (1..=client_count).map(|id| (id, INITIAL_BALANCE)).collect();
+    for (from, to, amount, _) in settled.values() {
+        *expected.entry(*from).or_default() -= amount;
+        *expected.entry(*to).or_default() += amount;
+    }
+    let now = clock.now_ms();
+    for (id, value) in expected {
+        let actual = ledger.get_balance(&agent(id)).balance.value;
+        if actual != value {
+            return Err(Violation::new(now, format!("{} has {actual}, expected {value}", agent(id))));
+        }
+    }
+
+    trace.record(("settled", settled.len()));
+    Ok(SimReport { seed, end_ms: now, trace, network: net.stats() })
+}
+
+fn check_ledger(
+    ledger: &BalanceLedger,
+    engine: &TransferEngine,
+    clients: u64,
+    supply: i64,
+    now: u64,
+) -> Result<(), Violation> {
+    let mut total = 0;
+    for id in 1..=clients {
+        let balance = ledger.get_balance(&agent(id));
+        if balance.balance.is_negative() {
+            return Err(Violation::new(now, format!("{} overdrawn: {}", agent(id), balance.balance)));
+        }
+        if !balance.pending.is_zero() {
+            return Err(Violation::new(now, format!("{} has {} held", agent(id), balance.pending)));
+        }
+        total += balance.balance.value;
+    }
+    if total != supply {
+        return Err(Violation::new(now, format!("supply changed from {supply} to {total}")));
+    }
+    if engine.pending_count() != 0 {
+        return Err(Violation::new(now, "transfer left pending"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payments_settle_once() {
+        let report = run(5).unwrap();
+        assert!(report.network.sent > 0);
+    }
+}
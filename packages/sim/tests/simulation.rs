@@ -0,0 +1,34 @@
+//! Seeded schedule sweeps over every scenario.
+
+use agentkern_sim::{scenarios, SimReport, SimRunner, Violation};
+
+fn sweep(name: &str, scenario: fn(u64) -> Result<SimReport, Violation>) {
+    let summary = SimRunner::new(name).run(scenario).unwrap_or_else(|failure| panic!("{failure}"));
+    assert!(summary.schedules > 0);
+    assert!(summary.messages > 0);
+}
+
+#[test]
+fn test_lock_schedules() {
+    sweep("locks", scenarios::locks::run);
+}
+
+#[test]
+fn test_raft_schedules() {
+    sweep("raft", scenarios::raft::run);
+}
+
+#[test]
+fn test_treasury_schedules() {
+    sweep("treasury", scenarios::treasury::run);
+}
+
+#[test]
+fn test_same_seed_same_schedule() {
+    for run in [scenarios::locks::run, scenarios::raft::run, scenarios::treasury::run] {
+        let (a, b) = (run(42).unwrap(), run(42).unwrap());
+        assert_eq!(a.trace, b.trace);
+        assert_eq!(a.network, b.network);
+        assert_ne!(a.trace, run(43).unwrap().trace);
+    }
+}
@@ -0,0 +1,69 @@
+//! Write Barrier
+//!
+//! Writers hold a [`WriteGuard`] while they mutate cell state. A snapshot
+//! waits for outstanding guards to drop and holds new writers back until
+//! capture is done, so every section reflects the same instant.
+
+use crate::error::SnapshotError;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+/// Shared between writers and the snapshot coordinator.
+#[derive(Debug, Clone, Default)]
+pub struct WriteBarrier {
+    lock: Arc<RwLock<()>>,
+}
+
+/// Held by a writer for the duration of one mutation.
+#[derive(Debug)]
+pub struct WriteGuard {
+    _guard: OwnedRwLockReadGuard<()>,
+}
+
+/// Held while writers are paused.
+#[derive(Debug)]
+pub(crate) struct Quiesced {
+    _guard: OwnedRwLockWriteGuard<()>,
+}
+
+impl WriteBarrier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enter before mutating state; waits while a snapshot is capturing.
+    pub async fn enter(&self) -> WriteGuard {
+        WriteGuard { _guard: self.lock.clone().read_owned().await }
+    }
+
+    /// Pause writers. New writers queue behind the pause, so in-flight ones
+    /// only have `timeout` to finish.
+    pub(crate) async fn quiesce(&self, timeout: Duration) -> Result<Quiesced, SnapshotError> {
+        tokio::time::timeout(timeout, self.lock.clone().write_owned())
+            .await
+            .map(|guard| Quiesced { _guard: guard })
+            .map_err(|_| SnapshotError::QuiesceTimeout(timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quiesce_waits_for_writers() {
+        let barrier = WriteBarrier::new();
+        let writer = barrier.enter().await;
+        assert!(matches!(
+            barrier.quiesce(Duration::from_millis(20)).await,
+            Err(SnapshotError::QuiesceTimeout(_))
+        ));
+
+        drop(writer);
+        let quiesced = barrier.quiesce(Duration::from_millis(20)).await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(20), barrier.enter()).await.is_err());
+        drop(quiesced);
+        barrier.enter().await;
+    }
+}
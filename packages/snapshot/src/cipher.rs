@@ -0,0 +1,91 @@
+//! Snapshot Encryption
+//!
+//! Snapshots are sealed with AES-256-GCM under a named key. Older keys can
+//! be kept for restore after rotation; new snapshots always use the current
+//! key.
+
+use crate::error::SnapshotError;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Ciphertext and what is needed to open it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Sealed {
+    pub key_id: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Seals and opens snapshot archives.
+pub struct SnapshotCipher {
+    key_id: String,
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl SnapshotCipher {
+    /// Seal new snapshots with `key`.
+    pub fn new(key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        let key_id = key_id.into();
+        let keys = HashMap::from([(key_id.clone(), Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))]);
+        Self { key_id, keys }
+    }
+
+    /// Keep a rotated-out key for opening older snapshots.
+    pub fn with_previous_key(mut self, key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        self.keys.entry(key_id.into()).or_insert_with(|| Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)));
+        self
+    }
+
+    /// ID of the key new snapshots are sealed with.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub(crate) fn seal(&self, plaintext: &[u8], aad: &[u8]) -> Sealed {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.keys[&self.key_id]
+            .encrypt(&nonce, Payload { msg: plaintext, aad })
+            .expect("AES-GCM encryption of an in-memory buffer");
+        Sealed { key_id: self.key_id.clone(), nonce: nonce.to_vec(), ciphertext }
+    }
+
+    pub(crate) fn open(&self, sealed: &Sealed, aad: &[u8]) -> Result<Vec<u8>, SnapshotError> {
+        let cipher = self
+            .keys
+            .get(&sealed.key_id)
+            .ok_or_else(|| SnapshotError::UnknownKey(sealed.key_id.clone()))?;
+        if sealed.nonce.len() != 12 {
+            return Err(SnapshotError::Integrity("malformed nonce".to_string()));
+        }
+        cipher
+            .decrypt(Nonce::from_slice(&sealed.nonce), Payload { msg: &sealed.ciphertext, aad })
+            .map_err(|_| SnapshotError::Integrity("ciphertext failed authentication".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let old = SnapshotCipher::new("k1", [1; 32]);
+        let sealed = old.seal(b"wallets", b"cell-a");
+        assert_eq!(old.open(&sealed, b"cell-a").unwrap(), b"wallets");
+        assert!(matches!(old.open(&sealed, b"cell-b"), Err(SnapshotError::Integrity(_))));
+
+        let mut tampered = sealed.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert!(matches!(old.open(&tampered, b"cell-a"), Err(SnapshotError::Integrity(_))));
+
+        let rotated = SnapshotCipher::new("k2", [2; 32]).with_previous_key("k1", [1; 32]);
+        assert_eq!(rotated.open(&sealed, b"cell-a").unwrap(), b"wallets");
+        assert_eq!(rotated.seal(b"x", b"").key_id, "k2");
+        assert!(matches!(
+            SnapshotCipher::new("k2", [2; 32]).open(&sealed, b"cell-a"),
+            Err(SnapshotError::UnknownKey(_))
+        ));
+    }
+}
@@ -0,0 +1,423 @@
+//! Snapshot Coordinator
+//!
+//! Snapshot: pause writers, capture every source, resume writers, then
+//! digest, seal and upload. Restore: download, open, verify every digest,
+//! pause writers, apply every section — rolling back to the pre-restore
+//! state if any section fails.
+//!
+//! Objects are stored as `<prefix>/<cell>/<taken-at-ms>-<id>.snap`, so
+//! point-in-time restore only needs a listing.
+
+use crate::barrier::WriteBarrier;
+use crate::cipher::{Sealed, SnapshotCipher};
+use crate::error::SnapshotError;
+use crate::source::SnapshotSource;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Default object key prefix.
+pub const DEFAULT_PREFIX: &str = "snapshots";
+
+/// Default time in-flight writers get to finish.
+pub const DEFAULT_QUIESCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const FORMAT_VERSION: u32 = 1;
+const EXTENSION: &str = ".snap";
+
+/// Digest of one captured section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionDigest {
+    pub name: String,
+    /// Hex SHA-256 of the section bytes
+    pub sha256: String,
+    pub bytes: usize,
+}
+
+/// What a snapshot contains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub id: Uuid,
+    pub cell: String,
+    pub taken_at: DateTime<Utc>,
+    /// How long writers were paused
+    pub quiesce_ms: u64,
+    pub sections: Vec<SectionDigest>,
+}
+
+/// A stored snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub id: Uuid,
+    pub taken_at: DateTime<Utc>,
+    /// Object key
+    pub location: String,
+    /// Object size in bytes
+    pub size: usize,
+}
+
+/// A completed restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub id: Uuid,
+    pub taken_at: DateTime<Utc>,
+    pub sections: Vec<String>,
+    /// How long writers were paused
+    pub quiesce_ms: u64,
+}
+
+/// Stored object: plaintext header, sealed archive.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    id: Uuid,
+    cell: String,
+    taken_at: DateTime<Utc>,
+    key_id: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Envelope {
+    /// Binds the ciphertext to its header, so objects cannot be swapped.
+    fn aad(id: &Uuid, cell: &str, taken_at: &DateTime<Utc>) -> Vec<u8> {
+        format!("{}:{}:{}", id, cell, taken_at.timestamp_millis()).into_bytes()
+    }
+}
+
+/// Sealed contents.
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    manifest: SnapshotManifest,
+    /// Base64 section bytes by name
+    sections: BTreeMap<String, String>,
+}
+
+/// Coordinates consistent snapshots and restores for one cell.
+pub struct SnapshotCoordinator {
+    cell: String,
+    store: Arc<dyn ObjectStore>,
+    cipher: SnapshotCipher,
+    barrier: WriteBarrier,
+    sources: Vec<Arc<dyn SnapshotSource>>,
+    prefix: String,
+    quiesce_timeout: Duration,
+}
+
+impl SnapshotCoordinator {
+    /// Create a coordinator for `cell`, shipping sealed snapshots to `store`.
+    pub fn new(cell: impl Into<String>, store: Arc<dyn ObjectStore>, cipher: SnapshotCipher) -> Self {
+        Self {
+            cell: cell.into(),
+            store,
+            cipher,
+            barrier: WriteBarrier::new(),
+            sources: Vec::new(),
+            prefix: DEFAULT_PREFIX.to_string(),
+            quiesce_timeout: DEFAULT_QUIESCE_TIMEOUT,
+        }
+    }
+
+    /// Add a store to capture. Panics if its name is already taken.
+    pub fn with_source(mut self, source: Arc<dyn SnapshotSource>) -> Self {
+        assert!(
+            self.sources.iter().all(|s| s.name() != source.name()),
+            "duplicate snapshot section {}",
+            source.name()
+        );
+        self.sources.push(source);
+        self
+    }
+
+    /// Share a barrier the cell's writers already use.
+    pub fn with_barrier(mut self, barrier: WriteBarrier) -> Self {
+        self.barrier = barrier;
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// How long in-flight writers get to finish before a snapshot gives up.
+    pub fn with_quiesce_timeout(mut self, timeout: Duration) -> Self {
+        self.quiesce_timeout = timeout;
+        self
+    }
+
+    /// Barrier writers must enter while mutating state.
+    pub fn barrier(&self) -> &WriteBarrier {
+        &self.barrier
+    }
+
+    /// Capture, seal and upload a consistent snapshot.
+    pub async fn snapshot(&self) -> Result<SnapshotManifest, SnapshotError> {
+        let paused = Instant::now();
+        let quiesced = self.barrier.quiesce(self.quiesce_timeout).await?;
+        let taken_at = Utc::now();
+        let captured = try_join_all(self.sources.iter().map(|s| s.capture())).await;
+        drop(quiesced);
+        let quiesce_ms = paused.elapsed().as_millis() as u64;
+        let captured = captured?;
+
+        let id = Uuid::new_v4();
+        let sections = self.sources.iter().zip(&captured).map(|(s, data)| digest(s.name(), data)).collect();
+        let manifest = SnapshotManifest { id, cell: self.cell.clone(), taken_at, quiesce_ms, sections };
+        let archive = Archive {
+            manifest: manifest.clone(),
+            sections: self
+                .sources
+                .iter()
+                .zip(&captured)
+                .map(|(s, data)| (s.name().to_string(), BASE64.encode(data)))
+                .collect(),
+        };
+
+        let sealed = self.cipher.seal(&serde_json::to_vec(&archive)?, &Envelope::aad(&id, &self.cell, &taken_at));
+        let envelope = Envelope {
+            version: FORMAT_VERSION,
+            id,
+            cell: self.cell.clone(),
+            taken_at,
+            key_id: sealed.key_id,
+            nonce: BASE64.encode(sealed.nonce),
+            ciphertext: BASE64.encode(sealed.ciphertext),
+        };
+        let location = self.location(&taken_at, &id);
+        self.store.put(&location, PutPayload::from(serde_json::to_vec(&envelope)?)).await?;
+
+        tracing::info!(cell = %self.cell, snapshot_id = %id, quiesce_ms, location = %location, "Snapshot stored");
+        Ok(manifest)
+    }
+
+    /// Stored snapshots for this cell, oldest first.
+    pub async fn list(&self) -> Result<Vec<SnapshotInfo>, SnapshotError> {
+        let prefix = Path::from(format!("{}/{}", self.prefix, self.cell));
+        let objects: Vec<_> = self.store.list(Some(&prefix)).try_collect().await?;
+        let mut snapshots: Vec<_> = objects
+            .into_iter()
+            .filter_map(|meta| {
+                let name = meta.location.filename()?.strip_suffix(EXTENSION)?;
+                let (millis, id) = name.split_once('-')?;
+                Some(SnapshotInfo {
+                    id: id.parse().ok()?,
+                    taken_at: DateTime::from_timestamp_millis(millis.parse().ok()?)?,
+                    location: meta.location.to_string(),
+                    size: meta.size,
+                })
+            })
+            .collect();
+        snapshots.sort_by_key(|s| s.taken_at);
+        Ok(snapshots)
+    }
+
+    /// Download a snapshot and check its integrity without applying it.
+    pub async fn verify(&self, id: Uuid) -> Result<SnapshotManifest, SnapshotError> {
+        let info = self.find(|s| s.id == id).await?.ok_or_else(|| SnapshotError::NotFound(id.to_string()))?;
+        Ok(self.fetch(&info).await?.0)
+    }
+
+    /// Restore the cell to a snapshot.
+    pub async fn restore(&self, id: Uuid) -> Result<RestoreReport, SnapshotError> {
+        let info = self.find(|s| s.id == id).await?.ok_or_else(|| SnapshotError::NotFound(id.to_string()))?;
+        self.apply(&info).await
+    }
+
+    /// Restore the cell to the latest snapshot taken at or before `point`.
+    pub async fn restore_at(&self, point: DateTime<Utc>) -> Result<RestoreReport, SnapshotError> {
+        let info = self
+            .list()
+            .await?
+            .into_iter()
+            .rev()
+            .find(|s| s.taken_at <= point)
+            .ok_or_else(|| SnapshotError::NotFound(format!("at or before {}", point)))?;
+        self.apply(&info).await
+    }
+
+    fn location(&self, taken_at: &DateTime<Utc>, id: &Uuid) -> Path {
+        Path::from(format!("{}/{}/{:016}-{}{}", self.prefix, self.cell, taken_at.timestamp_millis(), id, EXTENSION))
+    }
+
+    async fn find(&self, matches: impl Fn(&SnapshotInfo) -> bool) -> Result<Option<SnapshotInfo>, SnapshotError> {
+        Ok(self.list().await?.into_iter().find(matches))
+    }
+
+    /// Download, open and verify a snapshot, returning its sections in
+    /// source order.
+    async fn fetch(&self, info: &SnapshotInfo) -> Result<(SnapshotManifest, Vec<Vec<u8>>), SnapshotError> {
+        let bytes = self.store.get(&Path::from(info.location.as_str())).await?.bytes().await?;
+        let envelope: Envelope = serde_json::from_slice(&bytes)
+            .map_err(|e| SnapshotError::Integrity(format!("unreadable envelope: {}", e)))?;
+        if envelope.version != FORMAT_VERSION {
+            return Err(SnapshotError::Integrity(format!("unsupported format version {}", envelope.version)));
+        }
+        if envelope.id != info.id || envelope.cell != self.cell {
+            return Err(SnapshotError::Integrity("header does not match object key".to_string()));
+        }
+
+        let sealed = Sealed {
+            key_id: envelope.key_id,
+            nonce: decode_base64(&envelope.nonce)?,
+            ciphertext: decode_base64(&envelope.ciphertext)?,
+        };
+        let plaintext = self.cipher.open(&sealed, &Envelope::aad(&envelope.id, &envelope.cell, &envelope.taken_at))?;
+        let mut archive: Archive = serde_json::from_slice(&plaintext)?;
+        let manifest = archive.manifest;
+
+        let mut sections = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            let expected = manifest
+                .sections
+                .iter()
+                .find(|d| d.name == source.name())
+                .ok_or_else(|| SnapshotError::Mismatch(format!("no section for source {}", source.name())))?;
+            let data = archive
+                .sections
+                .remove(source.name())
+                .ok_or_else(|| SnapshotError::Integrity(format!("section {} missing from archive", source.name())))?;
+            let data = decode_base64(&data)?;
+            if digest(source.name(), &data) != *expected {
+                return Err(SnapshotError::Integrity(format!("section {} digest mismatch", source.name())));
+            }
+            sections.push(data);
+        }
+        if let Some(unknown) = manifest.sections.iter().find(|d| self.sources.iter().all(|s| s.name() != d.name)) {
+            return Err(SnapshotError::Mismatch(format!("no source for section {}", unknown.name)));
+        }
+        Ok((manifest, sections))
+    }
+
+    async fn apply(&self, info: &SnapshotInfo) -> Result<RestoreReport, SnapshotError> {
+        // Verify everything before touching live state
+        let (manifest, sections) = self.fetch(info).await?;
+
+        let paused = Instant::now();
+        let _quiesced = self.barrier.quiesce(self.quiesce_timeout).await?;
+        let previous = try_join_all(self.sources.iter().map(|s| s.capture())).await?;
+
+        for (index, (source, data)) in self.sources.iter().zip(&sections).enumerate() {
+            if let Err(e) = source.restore(data).await {
+                let mut rolled_back = true;
+                for (source, data) in self.sources.iter().zip(&previous).take(index + 1) {
+                    if let Err(e) = source.restore(data).await {
+                        tracing::error!(section = %source.name(), error = %e, "Failed to roll back section");
+                        rolled_back = false;
+                    }
+                }
+                return Err(SnapshotError::Restore { section: source.name().to_string(), reason: e.to_string(), rolled_back });
+            }
+        }
+
+        let quiesce_ms = paused.elapsed().as_millis() as u64;
+        tracing::info!(cell = %self.cell, snapshot_id = %manifest.id, taken_at = %manifest.taken_at, quiesce_ms, "Snapshot restored");
+        Ok(RestoreReport {
+            id: manifest.id,
+            taken_at: manifest.taken_at,
+            sections: manifest.sections.into_iter().map(|d| d.name).collect(),
+            quiesce_ms,
+        })
+    }
+}
+
+fn digest(name: &str, data: &[u8]) -> SectionDigest {
+    let sha256 = Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect();
+    SectionDigest { name: name.to_string(), sha256, bytes: data.len() }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, SnapshotError> {
+    BASE64.decode(data).map_err(|e| SnapshotError::Integrity(format!("invalid base64: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_arbiter::{LockManager, LockType};
+    use agentkern_treasury::{Amount, BalanceLedger};
+    use object_store::memory::InMemory;
+
+    fn coordinator(store: Arc<dyn ObjectStore>, ledger: Arc<BalanceLedger>, locks: Arc<LockManager>) -> SnapshotCoordinator {
+        SnapshotCoordinator::new("cell-eu", store, SnapshotCipher::new("k1", [7; 32]))
+            .with_source(ledger)
+            .with_source(locks)
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_point_in_time_restore() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let ledger = Arc::new(BalanceLedger::default());
+        let locks = Arc::new(LockManager::new());
+        let snapshots = coordinator(store.clone(), ledger.clone(), locks.clone());
+
+        ledger.deposit("agent-1", Amount::from_float(100.0, 6)).unwrap();
+        locks.acquire("agent-1", "invoice:42", 1, LockType::Write, Some(60_000)).await.unwrap();
+        let first = snapshots.snapshot().await.unwrap();
+        assert_eq!(first.sections.len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        ledger.deposit("agent-1", Amount::from_float(50.0, 6)).unwrap();
+        let second = snapshots.snapshot().await.unwrap();
+
+        ledger.deposit("agent-2", Amount::from_float(1.0, 6)).unwrap();
+        locks.release("agent-1", "invoice:42").await.unwrap();
+
+        let report = snapshots.restore_at(first.taken_at).await.unwrap();
+        assert_eq!(report.id, first.id);
+        assert_eq!(ledger.get_balance("agent-1").balance.to_float(), 100.0);
+        assert_eq!(ledger.get_balance("agent-2").balance.to_float(), 0.0);
+        assert_eq!(locks.get_status("invoice:42").await.unwrap().locked_by, "agent-1");
+
+        snapshots.restore(second.id).await.unwrap();
+        assert_eq!(ledger.get_balance("agent-1").balance.to_float(), 150.0);
+        assert!(matches!(
+            snapshots.restore_at(first.taken_at - chrono::Duration::seconds(1)).await,
+            Err(SnapshotError::NotFound(_))
+        ));
+
+        // Snapshots are scoped to their cell
+        let other = SnapshotCoordinator::new("cell-us", store.clone(), SnapshotCipher::new("k1", [7; 32]));
+        assert!(other.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tampered_snapshot_is_rejected() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let ledger = Arc::new(BalanceLedger::default());
+        let snapshots = coordinator(store.clone(), ledger.clone(), Arc::new(LockManager::new()));
+
+        ledger.deposit("agent-1", Amount::from_float(100.0, 6)).unwrap();
+        let manifest = snapshots.snapshot().await.unwrap();
+        let info = snapshots.list().await.unwrap().remove(0);
+        assert_eq!(snapshots.verify(manifest.id).await.unwrap().sections, manifest.sections);
+
+        let location = Path::from(info.location.as_str());
+        let mut envelope: serde_json::Value =
+            serde_json::from_slice(&store.get(&location).await.unwrap().bytes().await.unwrap()).unwrap();
+        let mut ciphertext = BASE64.decode(envelope["ciphertext"].as_str().unwrap()).unwrap();
+        ciphertext[10] ^= 1;
+        envelope["ciphertext"] = BASE64.encode(ciphertext).into();
+        store.put(&location, PutPayload::from(serde_json::to_vec(&envelope).unwrap())).await.unwrap();
+
+        ledger.deposit("agent-1", Amount::from_float(5.0, 6)).unwrap();
+        assert!(matches!(snapshots.restore(manifest.id).await, Err(SnapshotError::Integrity(_))));
+        assert_eq!(ledger.get_balance("agent-1").balance.to_float(), 105.0);
+
+        // A writer that never finishes blocks the snapshot, not the cell
+        let _writer = snapshots.barrier().enter().await;
+        let quick = coordinator(store, ledger, Arc::new(LockManager::new()))
+            .with_barrier(snapshots.barrier().clone())
+            .with_quiesce_timeout(Duration::from_millis(10));
+        assert!(matches!(quick.snapshot().await, Err(SnapshotError::QuiesceTimeout(_))));
+    }
+}
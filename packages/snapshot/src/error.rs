@@ -0,0 +1,60 @@
+//! Snapshot Errors
+
+use agentkern_errors::{Coded, ErrorCode};
+use std::time::Duration;
+
+/// Snapshot and restore failures.
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    #[error("Writers did not drain within {0:?}")]
+    QuiesceTimeout(Duration),
+
+    #[error("Section {section} failed: {reason}")]
+    Source { section: String, reason: String },
+
+    #[error("Snapshot {0} not found")]
+    NotFound(String),
+
+    #[error("Snapshot sealed with unknown key {0}")]
+    UnknownKey(String),
+
+    #[error("Integrity check failed: {0}")]
+    Integrity(String),
+
+    #[error("Snapshot does not match the registered sources: {0}")]
+    Mismatch(String),
+
+    #[error("Restore of section {section} failed ({reason}); rolled back: {rolled_back}")]
+    Restore { section: String, reason: String, rolled_back: bool },
+
+    #[error("Object store error: {0}")]
+    Store(#[from] object_store::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl SnapshotError {
+    pub(crate) fn section_failed(section: &str, reason: impl ToString) -> Self {
+        Self::Source { section: section.to_string(), reason: reason.to_string() }
+    }
+}
+
+impl Coded for SnapshotError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::QuiesceTimeout(_) | Self::Store(_) => ErrorCode::Unavailable,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::UnknownKey(_) => ErrorCode::PermissionDenied,
+            Self::Integrity(_) | Self::Mismatch(_) => ErrorCode::InvalidState,
+            Self::Source { .. } | Self::Restore { .. } | Self::Serialization(_) => ErrorCode::Internal,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::QuiesceTimeout(timeout) => Some(*timeout),
+            _ => None,
+        }
+    }
+}
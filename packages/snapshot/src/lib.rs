@@ -0,0 +1,54 @@
+//! AgentKern-Snapshot: Cell Backup and Disaster Recovery
+//!
+//! Consistent snapshots of a running cell's state — locks, wallets,
+//! reputation and trust graph, audit log — for disaster recovery:
+//!
+//! - Writers enter a [`WriteBarrier`]; a snapshot pauses new writers,
+//!   waits briefly for in-flight ones and captures every
+//!   [`SnapshotSource`] at the same instant
+//! - The archive is digested per section, sealed with AES-256-GCM and
+//!   shipped to object storage (S3, GCS, Azure or a local directory)
+//! - Restore verifies every digest before touching live state, applies all
+//!   sections under the barrier and rolls back if any section fails;
+//!   [`SnapshotCoordinator::restore_at`] picks the latest snapshot at or
+//!   before a point in time
+//! - Retention: per-class retention periods and legal holds, with
+//!   scheduled purges that issue chained [`PurgeCertificate`]s
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_snapshot::{SnapshotCipher, SnapshotCoordinator};
+//!
+//! let store = Arc::new(AmazonS3Builder::from_env().with_bucket_name("agentkern-dr").build()?);
+//! let snapshots = SnapshotCoordinator::new("cell-eu-1", store, SnapshotCipher::new("dr-2026", key))
+//!     .with_source(ledger.clone())
+//!     .with_source(lock_manager.clone())
+//!     .with_source(audit_ledger.clone())
+//!     .with_source(trust_network.clone());
+//!
+//! // Writers
+//! let _guard = snapshots.barrier().enter().await;
+//! ledger.deposit("agent-1", amount)?;
+//!
+//! // Backup and recovery
+//! let manifest = snapshots.snapshot().await?;
+//! snapshots.restore_at(incident_started_at).await?;
+//! ```
+
+pub mod barrier;
+pub mod cipher;
+pub mod coordinator;
+pub mod error;
+pub mod retention;
+pub mod source;
+
+// Re-exports
+pub use barrier::{WriteBarrier, WriteGuard};
+pub use cipher::SnapshotCipher;
+pub use coordinator::{RestoreReport, SectionDigest, SnapshotCoordinator, SnapshotInfo, SnapshotManifest};
+pub use error::SnapshotError;
+pub use retention::{
+    ClassPurge, LegalHold, PurgeCertificate, RetainedRecord, RetentionConfig, RetentionManager, RetentionTarget,
+};
+pub use source::SnapshotSource;
@@ -0,0 +1,443 @@
+//! Data Retention
+//!
+//! Purges records once their class's retention period has passed, unless
+//! a legal hold covers them. Periods and holds are configured centrally in
+//! a [`RetentionConfig`]; each store (audit log, intent histories, payment
+//! log) is a [`RetentionTarget`]. Every purge run issues a
+//! [`PurgeCertificate`] recording what was removed and what was held,
+//! digested and chained to the previous certificate so gaps or edits in
+//! the certificate log are detectable.
+//!
+//! Purged records remain in snapshots taken before the purge until those
+//! snapshots are themselves deleted.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_snapshot::retention::{RetentionConfig, RetentionManager};
+//!
+//! let config: RetentionConfig = serde_json::from_str(r#"{
+//!     "periods": { "audit": 2555, "intents": 90, "payments": 3650 },
+//!     "holds": [{ "id": "case-2291", "reason": "litigation", "agent_id": "agent-7" }]
+//! }"#)?;
+//! let retention = Arc::new(
+//!     RetentionManager::new(config)
+//!         .with_target(audit_ledger.clone())
+//!         .with_target(state_store.clone()),
+//! );
+//! let _purger = retention.clone().spawn();
+//! ```
+
+use agentkern_arbiter::AuditLedger;
+use agentkern_synapse::StateStore;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Default time between scheduled purges (one day).
+const DEFAULT_PURGE_INTERVAL_SECS: u64 = 86_400;
+
+fn default_purge_interval() -> u64 {
+    DEFAULT_PURGE_INTERVAL_SECS
+}
+
+/// Retention periods and legal holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Retention period in days per record class; unlisted classes are kept
+    #[serde(default)]
+    pub periods: HashMap<String, u32>,
+    #[serde(default)]
+    pub holds: Vec<LegalHold>,
+    /// Seconds between scheduled purges
+    #[serde(default = "default_purge_interval")]
+    pub purge_interval_secs: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self { periods: HashMap::new(), holds: Vec::new(), purge_interval_secs: DEFAULT_PURGE_INTERVAL_SECS }
+    }
+}
+
+impl RetentionConfig {
+    /// Keep records of `class` for `days`.
+    pub fn with_period(mut self, class: impl Into<String>, days: u32) -> Self {
+        self.periods.insert(class.into(), days);
+        self
+    }
+}
+
+/// Exempts matching records from purges while active.
+///
+/// A hold matches a record if every criterion it sets matches; a hold that
+/// sets none covers everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHold {
+    pub id: String,
+    pub reason: String,
+    /// Record classes covered (empty = all)
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// Agent whose records are covered
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Specific records covered (empty = all)
+    #[serde(default)]
+    pub record_ids: Vec<String>,
+    /// End of the hold (None = until released)
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl LegalHold {
+    pub fn new(id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            reason: reason.into(),
+            classes: Vec::new(),
+            agent_id: None,
+            record_ids: Vec::new(),
+            until: None,
+        }
+    }
+
+    /// Limit the hold to a record class.
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.classes.push(class.into());
+        self
+    }
+
+    /// Limit the hold to an agent's records.
+    pub fn with_agent(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    /// Limit the hold to a record.
+    pub fn with_record(mut self, record_id: impl Into<String>) -> Self {
+        self.record_ids.push(record_id.into());
+        self
+    }
+
+    /// End the hold at `until`.
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Whether the hold is active at `now` and covers the record.
+    pub fn covers(&self, class: &str, record: &RetainedRecord, now: DateTime<Utc>) -> bool {
+        self.until.is_none_or(|until| now < until)
+            && (self.classes.is_empty() || self.classes.iter().any(|c| c == class))
+            && self.agent_id.as_ref().is_none_or(|agent| record.agent_id.as_ref() == Some(agent))
+            && (self.record_ids.is_empty() || self.record_ids.contains(&record.id))
+    }
+}
+
+/// A record eligible for purging.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetainedRecord {
+    pub id: String,
+    pub agent_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A store whose records are subject to retention.
+#[async_trait]
+pub trait RetentionTarget: Send + Sync {
+    /// Record class, matched against [`RetentionConfig::periods`].
+    fn class(&self) -> &str;
+
+    /// Records created before `cutoff`.
+    async fn expired(&self, cutoff: DateTime<Utc>) -> Vec<RetainedRecord>;
+
+    /// Delete records by ID. Returns how many were deleted.
+    async fn purge(&self, ids: &[String]) -> usize;
+}
+
+/// Audit log.
+#[async_trait]
+impl RetentionTarget for AuditLedger {
+    fn class(&self) -> &str {
+        "audit"
+    }
+
+    async fn expired(&self, cutoff: DateTime<Utc>) -> Vec<RetainedRecord> {
+        self.query_by_time_range(DateTime::<Utc>::MIN_UTC, cutoff)
+            .await
+            .into_iter()
+            .filter(|r| r.timestamp < cutoff)
+            .map(|r| RetainedRecord { id: r.id.to_string(), agent_id: Some(r.agent_id), created_at: r.timestamp })
+            .collect()
+    }
+
+    async fn purge(&self, ids: &[String]) -> usize {
+        let ids: HashSet<Uuid> = ids.iter().filter_map(|id| id.parse().ok()).collect();
+        AuditLedger::purge(self, &ids).await
+    }
+}
+
+/// Intent histories, aged by last update.
+#[async_trait]
+impl RetentionTarget for StateStore {
+    fn class(&self) -> &str {
+        "intents"
+    }
+
+    async fn expired(&self, cutoff: DateTime<Utc>) -> Vec<RetainedRecord> {
+        self.intents()
+            .await
+            .into_iter()
+            .filter(|path| path.updated_at < cutoff)
+            .map(|path| RetainedRecord { id: path.id.to_string(), agent_id: Some(path.agent_id), created_at: path.updated_at })
+            .collect()
+    }
+
+    async fn purge(&self, ids: &[String]) -> usize {
+        let ids: HashSet<Uuid> = ids.iter().filter_map(|id| id.parse().ok()).collect();
+        self.purge_intents(&ids).await
+    }
+}
+
+/// Treasury payment log. Pending payments are never purged.
+#[cfg(feature = "enterprise")]
+#[async_trait]
+impl RetentionTarget for Mutex<agentkern_treasury_ee::Treasury> {
+    fn class(&self) -> &str {
+        "payments"
+    }
+
+    async fn expired(&self, cutoff: DateTime<Utc>) -> Vec<RetainedRecord> {
+        self.lock()
+            .unwrap()
+            .payments()
+            .iter()
+            .filter(|p| p.created_at < cutoff && p.status != agentkern_treasury_ee::PaymentStatus::Pending)
+            .map(|p| RetainedRecord { id: p.id.clone(), agent_id: Some(p.from_agent.clone()), created_at: p.created_at })
+            .collect()
+    }
+
+    async fn purge(&self, ids: &[String]) -> usize {
+        self.lock().unwrap().purge_payments(&ids.iter().cloned().collect())
+    }
+}
+
+/// What a purge run did to one record class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassPurge {
+    pub class: String,
+    /// Records created before this were eligible
+    pub cutoff: DateTime<Utc>,
+    pub purged: usize,
+    /// Eligible records kept under a legal hold
+    pub held: usize,
+    /// Holds that kept records
+    pub holds: Vec<String>,
+    /// SHA-256 over the sorted IDs of purged records
+    pub records_digest: String,
+}
+
+/// Evidence of a purge run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeCertificate {
+    pub id: Uuid,
+    pub issued_at: DateTime<Utc>,
+    pub classes: Vec<ClassPurge>,
+    /// Digest of the previous certificate
+    pub previous: Option<String>,
+    /// SHA-256 over the fields above
+    pub digest: String,
+}
+
+impl PurgeCertificate {
+    fn compute_digest(&self) -> String {
+        let body = serde_json::json!({
+            "id": self.id,
+            "issued_at": self.issued_at,
+            "classes": self.classes,
+            "previous": self.previous,
+        });
+        hex(&Sha256::digest(body.to_string().as_bytes()))
+    }
+
+    /// Whether the digest matches the certificate's contents.
+    pub fn verify(&self) -> bool {
+        self.digest == self.compute_digest()
+    }
+
+    /// Records purged across all classes.
+    pub fn purged(&self) -> usize {
+        self.classes.iter().map(|c| c.purged).sum()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Applies the retention config to its targets.
+pub struct RetentionManager {
+    config: Mutex<RetentionConfig>,
+    targets: Vec<Arc<dyn RetentionTarget>>,
+    certificates: Mutex<Vec<PurgeCertificate>>,
+}
+
+impl RetentionManager {
+    pub fn new(config: RetentionConfig) -> Self {
+        Self { config: Mutex::new(config), targets: Vec::new(), certificates: Mutex::new(Vec::new()) }
+    }
+
+    /// Add a store.
+    pub fn with_target(mut self, target: Arc<dyn RetentionTarget>) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// Place a legal hold, replacing any hold with the same ID.
+    pub fn place_hold(&self, hold: LegalHold) {
+        tracing::info!(hold_id = %hold.id, reason = %hold.reason, "Legal hold placed");
+        let mut config = self.config.lock().unwrap();
+        config.holds.retain(|h| h.id != hold.id);
+        config.holds.push(hold);
+    }
+
+    /// Release a legal hold. Returns whether it existed.
+    pub fn release_hold(&self, hold_id: &str) -> bool {
+        let mut config = self.config.lock().unwrap();
+        let before = config.holds.len();
+        config.holds.retain(|h| h.id != hold_id);
+        let released = config.holds.len() < before;
+        if released {
+            tracing::info!(hold_id, "Legal hold released");
+        }
+        released
+    }
+
+    /// Current configuration, including holds.
+    pub fn config(&self) -> RetentionConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Certificates issued so far, oldest first.
+    pub fn certificates(&self) -> Vec<PurgeCertificate> {
+        self.certificates.lock().unwrap().clone()
+    }
+
+    /// Purge every target's expired, unheld records and issue a certificate.
+    pub async fn purge(&self) -> PurgeCertificate {
+        let now = Utc::now();
+        let config = self.config();
+        let mut classes = Vec::new();
+
+        for target in &self.targets {
+            let class = target.class();
+            let Some(days) = config.periods.get(class) else {
+                continue;
+            };
+            let cutoff = now - chrono::Duration::days(*days as i64);
+
+            let mut purge = Vec::new();
+            let mut held = 0;
+            let mut holds = Vec::new();
+            for record in target.expired(cutoff).await {
+                let covering: Vec<_> = config.holds.iter().filter(|h| h.covers(class, &record, now)).collect();
+                if covering.is_empty() {
+                    purge.push(record.id);
+                    continue;
+                }
+                held += 1;
+                for hold in covering {
+                    if !holds.contains(&hold.id) {
+                        holds.push(hold.id.clone());
+                    }
+                }
+            }
+
+            purge.sort();
+            let mut hasher = Sha256::new();
+            for id in &purge {
+                hasher.update(id.as_bytes());
+                hasher.update(b"\n");
+            }
+            let purged = if purge.is_empty() { 0 } else { target.purge(&purge).await };
+            tracing::info!(class, purged, held, "Retention purge");
+            classes.push(ClassPurge { class: class.to_string(), cutoff, purged, held, holds, records_digest: hex(&hasher.finalize()) });
+        }
+
+        let mut certificates = self.certificates.lock().unwrap();
+        let mut certificate = PurgeCertificate {
+            id: Uuid::new_v4(),
+            issued_at: now,
+            classes,
+            previous: certificates.last().map(|c| c.digest.clone()),
+            digest: String::new(),
+        };
+        certificate.digest = certificate.compute_digest();
+        certificates.push(certificate.clone());
+        certificate
+    }
+
+    /// Purge on the configured interval until the handle is aborted.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let interval = self.config.lock().unwrap().purge_interval_secs.max(1);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+                let certificate = self.purge().await;
+                tracing::info!(certificate_id = %certificate.id, purged = certificate.purged(), "Scheduled retention purge completed");
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentkern_arbiter::{AuditOutcome, AuditRecord};
+
+    async fn ledger() -> Arc<AuditLedger> {
+        let ledger = Arc::new(AuditLedger::new());
+        for (agent, days_old) in [("agent-1", 40), ("agent-2", 40), ("agent-2", 45), ("agent-3", 1)] {
+            let mut record = AuditRecord::new(agent, "transfer_funds", "limits", 10, AuditOutcome::Allowed);
+            record.timestamp = Utc::now() - chrono::Duration::days(days_old);
+            ledger.record(record).await;
+        }
+        ledger
+    }
+
+    #[tokio::test]
+    async fn test_purge_respects_holds_and_chains_certificates() {
+        let ledger = ledger().await;
+        let intents = Arc::new(StateStore::new());
+        intents.start_intent("agent-1", "reconcile invoices", 3).await;
+
+        let config: RetentionConfig = serde_json::from_str(r#"{ "periods": { "audit": 30, "intents": 30 } }"#).unwrap();
+        let retention = RetentionManager::new(config).with_target(ledger.clone()).with_target(intents.clone());
+        retention.place_hold(LegalHold::new("case-1", "litigation").with_class("audit").with_agent("agent-2"));
+
+        let first = retention.purge().await;
+        assert!(first.verify());
+        let audit = &first.classes[0];
+        assert_eq!((audit.purged, audit.held), (1, 2));
+        assert_eq!(audit.holds, ["case-1"]);
+        assert_eq!(first.classes[1].purged, 0);
+        assert_eq!(ledger.count().await, 3);
+
+        assert!(retention.release_hold("case-1"));
+        let second = retention.purge().await;
+        assert_eq!(second.classes[0].purged, 2);
+        assert_eq!(second.previous.as_deref(), Some(first.digest.as_str()));
+        assert_eq!(ledger.count().await, 1);
+
+        let mut tampered = second.clone();
+        tampered.classes[0].purged = 0;
+        assert!(!tampered.verify());
+    }
+}
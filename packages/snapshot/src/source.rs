@@ -0,0 +1,102 @@
+//! Snapshot Sources
+//!
+//! One [`SnapshotSource`] per subsystem store. Each captures its state as
+//! a named section and can be restored from it.
+
+use crate::error::SnapshotError;
+use agentkern_arbiter::{AuditLedger, LockManager};
+use agentkern_treasury::BalanceLedger;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A subsystem store that can be captured and restored.
+#[async_trait]
+pub trait SnapshotSource: Send + Sync {
+    /// Section name, unique within a coordinator.
+    fn name(&self) -> &str;
+
+    async fn capture(&self) -> Result<Vec<u8>, SnapshotError>;
+
+    /// Replace the store's state with a captured section.
+    async fn restore(&self, data: &[u8]) -> Result<(), SnapshotError>;
+}
+
+fn encode<T: Serialize>(section: &str, state: &T) -> Result<Vec<u8>, SnapshotError> {
+    serde_json::to_vec(state).map_err(|e| SnapshotError::section_failed(section, e))
+}
+
+fn decode<T: DeserializeOwned>(section: &str, data: &[u8]) -> Result<T, SnapshotError> {
+    serde_json::from_slice(data).map_err(|e| SnapshotError::section_failed(section, e))
+}
+
+/// Business locks.
+#[async_trait]
+impl SnapshotSource for LockManager {
+    fn name(&self) -> &str {
+        "locks"
+    }
+
+    async fn capture(&self) -> Result<Vec<u8>, SnapshotError> {
+        encode(self.name(), &self.export_state().await)
+    }
+
+    async fn restore(&self, data: &[u8]) -> Result<(), SnapshotError> {
+        self.import_state(decode(self.name(), data)?).await;
+        Ok(())
+    }
+}
+
+/// Audit log.
+#[async_trait]
+impl SnapshotSource for AuditLedger {
+    fn name(&self) -> &str {
+        "audit"
+    }
+
+    async fn capture(&self) -> Result<Vec<u8>, SnapshotError> {
+        encode(self.name(), &self.export_state().await)
+    }
+
+    async fn restore(&self, data: &[u8]) -> Result<(), SnapshotError> {
+        self.import_state(decode(self.name(), data)?).await;
+        Ok(())
+    }
+}
+
+/// Agent wallets.
+#[async_trait]
+impl SnapshotSource for BalanceLedger {
+    fn name(&self) -> &str {
+        "wallets"
+    }
+
+    async fn capture(&self) -> Result<Vec<u8>, SnapshotError> {
+        encode(self.name(), &self.export_state())
+    }
+
+    async fn restore(&self, data: &[u8]) -> Result<(), SnapshotError> {
+        self.import_state(decode(self.name(), data)?);
+        Ok(())
+    }
+}
+
+/// Reputation and the trust graph.
+#[cfg(feature = "enterprise")]
+#[async_trait]
+impl SnapshotSource for std::sync::Mutex<agentkern_trust::TrustNetwork> {
+    fn name(&self) -> &str {
+        "reputation"
+    }
+
+    async fn capture(&self) -> Result<Vec<u8>, SnapshotError> {
+        let state = self.lock().unwrap().export_state();
+        encode(self.name(), &state)
+    }
+
+    async fn restore(&self, data: &[u8]) -> Result<(), SnapshotError> {
+        let state = decode(self.name(), data)?;
+        self.lock().unwrap().import_state(state);
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! Polyglot Memory Search Benchmarks
+//!
+//! `PolyglotMemory::search` over in-memory indexes of increasing size.
+//!
+//! Run with: cargo bench --bench polyglot_search
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use agentkern_synapse::polyglot::PolyglotMemory;
+
+const TOPICS: [&str; 5] = [
+    "refund the customer for a duplicate invoice",
+    "schedule a maintenance window for the billing database",
+    "reembolsar al cliente por una factura duplicada",
+    "rembourser le client pour une facture en double",
+    "summarise the quarterly carbon report",
+];
+
+fn memory(rt: &tokio::runtime::Runtime, size: usize) -> PolyglotMemory {
+    let memory = PolyglotMemory::new();
+    rt.block_on(async {
+        for i in 0..size {
+            let text = format!("{} #{}", TOPICS[i % TOPICS.len()], i);
+            memory.store(&format!("doc-{}", i), &text).await;
+        }
+    });
+    memory
+}
+
+fn bench_search(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("polyglot_search");
+
+    for size in [100usize, 1_000, 10_000] {
+        let memory = memory(&rt, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("top_10", size), &memory, |b, memory| {
+            b.iter(|| rt.block_on(memory.search(black_box("duplicate invoice refund"), 10)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
@@ -59,7 +59,7 @@ pub use adaptive::{AdaptiveExecutor, ExecutionStrategy, ExecutionMetrics};
 pub use embeddings::{EmbeddingConfig, EmbeddingProvider, PolyglotEmbedder, SynapseRegion};
 pub use crdt::{GCounter, PNCounter, LwwRegister, OrSet, LwwMap, AgentStateCrdt};
 pub use mesh::{GlobalMesh, MeshCell, DataRegion, MeshSync, GeoFence};
-pub use polyglot::{Language, PolyglotMemory};
+pub use polyglot::{Language, LanguageCode, LanguagePack, PolyglotMemory};
 
 // NOTE: Antifragile moved to agentkern-arbiter during consolidation
 // See: packages/arbiter/src/antifragile.rs
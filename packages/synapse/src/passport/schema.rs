@@ -56,6 +56,19 @@ pub enum PassportError {
     PolicyViolation(String),
 }
 
+impl agentkern_errors::Coded for PassportError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::IncompatibleVersion(_) | Self::MissingField(_) | Self::SerializationError(_) => {
+                ErrorCode::InvalidArgument
+            }
+            Self::InvalidSignature | Self::DecryptionFailed(_) => ErrorCode::Unauthenticated,
+            Self::PolicyViolation(_) => ErrorCode::PolicyDenied,
+        }
+    }
+}
+
 /// Agent identity using W3C DID format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentIdentity {
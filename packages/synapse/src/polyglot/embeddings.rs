@@ -31,12 +31,8 @@ pub struct PolyglotEmbedder {
 impl PolyglotEmbedder {
     /// Create a new embedder for a language.
     pub fn new(language: Language) -> Self {
-        let model = language.embedding_model().to_string();
-        let dimensions = match language {
-            Language::Arabic => 768, // Jais
-            Language::Chinese => 1024, // BGE
-            _ => 1024, // E5-large
-        };
+        let model = language.embedding_model().into_owned();
+        let dimensions = language.embedding_dimensions();
         
         Self {
             language,
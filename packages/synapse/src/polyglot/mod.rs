@@ -2,13 +2,18 @@
 //!
 //! Native language support for semantic memory.
 //! Per GLOBAL_GAPS.md: Arabic (Jais), Japanese, Hindi
+//!
+//! Further languages plug in at runtime as [`LanguagePack`]s.
 
 pub mod embeddings;
+pub mod packs;
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 pub use embeddings::{PolyglotEmbedder, EmbeddingResult};
+pub use packs::{DetectionRules, EmbeddingChoice, GuardPatterns, LanguageCode, LanguagePack, LanguagePackError};
 
 /// Supported languages with native embedding models.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -24,7 +29,8 @@ pub enum Language {
     Portuguese,
     Russian,
     Korean,
-    Other,
+    /// A language from an installed [`LanguagePack`]
+    Other(LanguageCode),
 }
 
 impl Language {
@@ -66,20 +72,80 @@ impl Language {
             return Language::Korean;
         }
         
+        // Installed language packs
+        if let Some(language) = LanguagePack::detect(text) {
+            return language;
+        }
+        
         // Default to English
         Language::English
     }
     
-    /// Get recommended embedding model for this language.
-    pub fn embedding_model(&self) -> &'static str {
-        match self {
+    /// Language with an ISO 639 code; [`Language::Other`] if not built in.
+    pub fn from_code(code: LanguageCode) -> Self {
+        [
+            Language::English,
+            Language::Arabic,
+            Language::Japanese,
+            Language::Hindi,
+            Language::Chinese,
+            Language::Spanish,
+            Language::French,
+            Language::German,
+            Language::Portuguese,
+            Language::Russian,
+            Language::Korean,
+        ]
+        .into_iter()
+        .find(|language| language.code() == code)
+        .unwrap_or(Language::Other(code))
+    }
+    
+    /// ISO 639 code.
+    pub fn code(&self) -> LanguageCode {
+        let code = match self {
+            Language::English => "en",
+            Language::Arabic => "ar",
+            Language::Japanese => "ja",
+            Language::Hindi => "hi",
+            Language::Chinese => "zh",
+            Language::Spanish => "es",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Portuguese => "pt",
+            Language::Russian => "ru",
+            Language::Korean => "ko",
+            Language::Other(code) => return *code,
+        };
+        LanguageCode::new(code).expect("built-in codes are valid")
+    }
+    
+    /// Get recommended embedding model for this language. An installed
+    /// pack for the language overrides the built-in choice.
+    pub fn embedding_model(&self) -> Cow<'static, str> {
+        if let Some(pack) = LanguagePack::find(self.code()) {
+            return Cow::Owned(pack.embedding.model.clone());
+        }
+        Cow::Borrowed(match self {
             Language::Arabic => "jais-embedding-v1",
             Language::Japanese => "multilingual-e5-large",
             Language::Hindi => "multilingual-e5-large",
             Language::Chinese => "bge-large-zh",
             Language::Korean => "ko-sroberta-multitask",
             Language::Russian => "multilingual-e5-large",
+            Language::Other(_) => "multilingual-e5-large",
             _ => "e5-large-v2",
+        })
+    }
+    
+    /// Dimensions of [`Language::embedding_model`].
+    pub fn embedding_dimensions(&self) -> usize {
+        if let Some(pack) = LanguagePack::find(self.code()) {
+            return pack.embedding.dimensions;
+        }
+        match self {
+            Language::Arabic => 768, // Jais
+            _ => 1024, // BGE, E5-large
         }
     }
 }
@@ -128,7 +194,7 @@ impl PolyglotMemory {
         let embedding = self.embed(text).await;
         
         let mut index = self.index.write();
-        index.push((id.to_string(), embedding.embedding, text.to_string(), language));
+        index.push((id.to_string(), embedding.vector, text.to_string(), language));
         
         tracing::debug!(id = %id, language = ?language, "Stored document in polyglot memory");
     }
@@ -157,7 +223,7 @@ impl PolyglotMemory {
         let mut scored: Vec<(f32, &String, &String, &Language)> = index
             .iter()
             .map(|(id, emb, text, lang)| {
-                let score = cosine_similarity(&query_embedding.embedding, emb);
+                let score = cosine_similarity(&query_embedding.vector, emb);
                 (score, id, text, lang)
             })
             .collect();
@@ -251,4 +317,34 @@ mod tests {
         assert_eq!(Language::Arabic.embedding_model(), "jais-embedding-v1");
         assert_eq!(Language::English.embedding_model(), "e5-large-v2");
     }
+
+    #[test]
+    fn test_language_packs_detect_other_languages() {
+        let code = |c| LanguageCode::new(c).unwrap();
+        assert_eq!(Language::detect("Bu akşam için bir şey değil, çok güzel"), Language::Other(code("tr")));
+        assert_eq!(Language::detect("Saya tidak akan pergi ke pasar dengan dia"), Language::Other(code("id")));
+        assert_eq!(Language::detect("Habari yako? Hii ni siku nzuri sana kwa kazi"), Language::Other(code("sw")));
+        assert_eq!(Language::detect("The weather is nice today"), Language::English);
+        assert_eq!(Language::Other(code("sw")).embedding_model(), "multilingual-e5-large");
+        assert_eq!(Language::from_code(code("ES")), Language::Spanish);
+    }
+
+    #[test]
+    fn test_runtime_pack_for_builtin_language() {
+        let pack = LanguagePack::from_json(
+            r#"{"code": "nl", "name": "Dutch", "detection": {"stopwords": ["het", "een", "niet", "van", "zijn"]},
+                "embedding": {"model": "robbert-v2", "dimensions": 768}}"#,
+        )
+        .unwrap();
+        pack.install();
+        let dutch = Language::detect("Het is niet een van de beste");
+        assert_eq!(dutch, Language::Other(LanguageCode::new("nl").unwrap()));
+        assert_eq!(dutch.embedding_dimensions(), 768);
+        assert_eq!(serde_json::to_string(&dutch).unwrap(), r#"{"Other":"nl"}"#);
+        assert!(LanguagePack::uninstall(dutch.code()));
+        assert_eq!(Language::detect("Het is niet een van de beste"), Language::English);
+
+        assert!(LanguagePack::from_json(r#"{"code": "x1", "name": "Bad", "detection": {}, "embedding": {"model": "m", "dimensions": 1}}"#).is_err());
+        assert!(LanguagePack::from_json(r#"{"code": "xx", "name": "Empty", "detection": {}, "embedding": {"model": "m", "dimensions": 1}}"#).is_err());
+    }
 }
@@ -0,0 +1,284 @@
+//! Language Packs
+//!
+//! Languages beyond the built-in set, loadable at runtime. A pack brings:
+//! - Detection rules: scripts, distinctive letters and common words
+//! - The embedding model to use for the language
+//! - Prompt-guard patterns: injection phrases in the language, for the
+//!   Gate prompt guard
+//!
+//! Installed packs are process-wide and consulted by [`Language::detect`],
+//! which reports their languages as [`Language::Other`] with the pack's ISO
+//! 639 code. Turkish, Indonesian and Swahili packs ship built in; more load
+//! from JSON files.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! LanguagePack::load_dir("/etc/agentkern/language-packs")?;
+//! assert_eq!(Language::detect("Habari yako, rafiki yangu? Ni siku nzuri sana."), Language::Other(LanguageCode::new("sw").unwrap()));
+//!
+//! // Localized injection phrases for the prompt guard
+//! let mut guard = PromptGuard::new();
+//! for pack in LanguagePack::installed() {
+//!     for group in &pack.prompt_guard {
+//!         guard.add_pack(PatternPack::new(format!("lang-{}-{}", pack.code, group.attack), group.attack.parse()?, group.weight, &group.patterns));
+//!     }
+//! }
+//! ```
+
+use super::Language;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Built-in packs, as shipped in `packs/`.
+const BUNDLED: [&str; 3] = [
+    include_str!("../../packs/tr.json"),
+    include_str!("../../packs/id.json"),
+    include_str!("../../packs/sw.json"),
+];
+
+static INSTALLED: LazyLock<RwLock<Vec<Arc<LanguagePack>>>> = LazyLock::new(|| {
+    let packs = BUNDLED
+        .iter()
+        .map(|json| LanguagePack::from_json(json).expect("bundled language pack is valid"))
+        .map(Arc::new)
+        .collect();
+    RwLock::new(packs)
+});
+
+/// Language pack errors.
+#[derive(Debug, thiserror::Error)]
+pub enum LanguagePackError {
+    #[error("Failed to read language pack {path}: {reason}")]
+    Io { path: String, reason: String },
+    #[error("Invalid language pack: {0}")]
+    Invalid(String),
+}
+
+impl agentkern_errors::Coded for LanguagePackError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::Io { .. } => ErrorCode::Unavailable,
+            Self::Invalid(_) => ErrorCode::InvalidArgument,
+        }
+    }
+}
+
+/// ISO 639 language code: two letters (639-1) or three (639-3), lowercase.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LanguageCode([u8; 3]);
+
+impl LanguageCode {
+    pub fn new(code: &str) -> Option<Self> {
+        let bytes = code.as_bytes();
+        if !(2..=3).contains(&bytes.len()) || !bytes.iter().all(u8::is_ascii_alphabetic) {
+            return None;
+        }
+        let mut packed = [0; 3];
+        for (slot, byte) in packed.iter_mut().zip(bytes) {
+            *slot = byte.to_ascii_lowercase();
+        }
+        Some(Self(packed))
+    }
+
+    pub fn as_str(&self) -> &str {
+        let len = if self.0[2] == 0 { 2 } else { 3 };
+        // Only ASCII letters are ever stored
+        std::str::from_utf8(&self.0[..len]).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Serialize for LanguageCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Self::new(&code).ok_or_else(|| serde::de::Error::custom(format!("invalid ISO 639 code: {code}")))
+    }
+}
+
+/// How to recognize a language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionRules {
+    /// Unicode ranges the text must mostly be written in; any script if empty
+    #[serde(default)]
+    pub scripts: Vec<(char, char)>,
+    /// Letters rare outside the language; each occurrence scores 2
+    #[serde(default)]
+    pub letters: String,
+    /// Common words; each occurrence scores 1
+    #[serde(default)]
+    pub stopwords: Vec<String>,
+    /// Score needed to detect the language
+    #[serde(default = "default_min_score")]
+    pub min_score: u32,
+}
+
+fn default_min_score() -> u32 {
+    2
+}
+
+impl DetectionRules {
+    /// Score of `text`, given its lowercase words. A pack with only scripts
+    /// matches any text mostly written in them.
+    fn score(&self, text: &str, words: &[String]) -> u32 {
+        if !self.scripts.is_empty() {
+            let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+            let in_script = letters.iter().filter(|c| self.scripts.iter().any(|(from, to)| (from..=to).contains(c))).count();
+            if letters.is_empty() || in_script * 2 <= letters.len() {
+                return 0;
+            }
+            if self.letters.is_empty() && self.stopwords.is_empty() {
+                return self.min_score;
+            }
+        }
+        let letters = text.chars().filter(|c| self.letters.contains(*c)).count() as u32;
+        let stopwords = words.iter().filter(|w| self.stopwords.contains(w)).count() as u32;
+        letters * 2 + stopwords
+    }
+}
+
+/// Embedding model for a language.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingChoice {
+    pub model: String,
+    pub dimensions: usize,
+}
+
+/// Injection phrases of one attack type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuardPatterns {
+    /// Gate attack type, snake_case (e.g. `instruction_override`)
+    pub attack: String,
+    /// Threat score added per match
+    pub weight: u32,
+    pub patterns: Vec<String>,
+}
+
+/// Detection rules, embedding model and prompt-guard patterns of a language.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguagePack {
+    pub code: LanguageCode,
+    /// English name, e.g. `Turkish`
+    pub name: String,
+    pub detection: DetectionRules,
+    pub embedding: EmbeddingChoice,
+    #[serde(default)]
+    pub prompt_guard: Vec<GuardPatterns>,
+}
+
+impl LanguagePack {
+    pub fn from_json(json: &str) -> Result<Self, LanguagePackError> {
+        let mut pack: Self = serde_json::from_str(json).map_err(|e| LanguagePackError::Invalid(e.to_string()))?;
+        pack.validate()?;
+        pack.detection.stopwords.iter_mut().for_each(|w| *w = w.to_lowercase());
+        pack.prompt_guard.iter_mut().flat_map(|g| g.patterns.iter_mut()).for_each(|p| *p = p.to_lowercase());
+        Ok(pack)
+    }
+
+    /// Load and install a pack file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Arc<Self>, LanguagePackError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| LanguagePackError::Io {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        Ok(Self::from_json(&json)?.install())
+    }
+
+    /// Load and install every `*.json` pack in a directory. Returns their codes.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<LanguageCode>, LanguagePackError> {
+        let dir = dir.as_ref();
+        let io = |e: std::io::Error| LanguagePackError::Io { path: dir.display().to_string(), reason: e.to_string() };
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(io)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .map_err(io)?;
+        paths.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+        paths.iter().map(|path| Self::load(path).map(|pack| pack.code)).collect()
+    }
+
+    /// Make this pack available process-wide, replacing any pack with the
+    /// same code.
+    pub fn install(self) -> Arc<Self> {
+        let pack = Arc::new(self);
+        let mut installed = INSTALLED.write().unwrap();
+        installed.retain(|p| p.code != pack.code);
+        installed.push(pack.clone());
+        tracing::info!(code = %pack.code, name = %pack.name, "Language pack installed");
+        pack
+    }
+
+    /// Remove an installed pack, including a built-in one.
+    pub fn uninstall(code: LanguageCode) -> bool {
+        let mut installed = INSTALLED.write().unwrap();
+        let before = installed.len();
+        installed.retain(|p| p.code != code);
+        installed.len() != before
+    }
+
+    pub fn installed() -> Vec<Arc<Self>> {
+        INSTALLED.read().unwrap().clone()
+    }
+
+    pub fn find(code: LanguageCode) -> Option<Arc<Self>> {
+        INSTALLED.read().unwrap().iter().find(|p| p.code == code).cloned()
+    }
+
+    /// Installed language `text` scores highest in, if any reaches its
+    /// minimum score. Earlier packs win ties.
+    pub(crate) fn detect(text: &str) -> Option<Language> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let installed = INSTALLED.read().unwrap();
+        let mut best: Option<(u32, LanguageCode)> = None;
+        for pack in installed.iter() {
+            let score = pack.detection.score(text, &words);
+            if score >= pack.detection.min_score && best.is_none_or(|(top, _)| score > top) {
+                best = Some((score, pack.code));
+            }
+        }
+        best.map(|(_, code)| Language::from_code(code))
+    }
+
+    fn validate(&self) -> Result<(), LanguagePackError> {
+        let invalid = |reason: &str| Err(LanguagePackError::Invalid(format!("{}: {reason}", self.code)));
+        if self.detection.scripts.is_empty() && self.detection.letters.is_empty() && self.detection.stopwords.is_empty() {
+            return invalid("detection rules are empty");
+        }
+        if self.detection.min_score == 0 {
+            return invalid("min_score must be at least 1");
+        }
+        if self.embedding.model.is_empty() || self.embedding.dimensions == 0 {
+            return invalid("embedding model and dimensions are required");
+        }
+        if self.prompt_guard.iter().any(|g| g.patterns.iter().any(|p| p.trim().is_empty())) {
+            return invalid("prompt guard patterns must not be empty");
+        }
+        Ok(())
+    }
+}
@@ -6,10 +6,11 @@
 //! - Uses CRDTs (LWW-Register) for eventual consistency
 //! - Supports distributed sync via vector clocks
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::Utc;
+use uuid::Uuid;
 
 use crate::types::{AgentState, StateQuery, StateUpdate};
 use crate::intent::IntentPath;
@@ -123,6 +124,20 @@ impl StateStore {
         intents.get(agent_id).cloned()
     }
 
+    /// Every agent's current intent path.
+    pub async fn intents(&self) -> Vec<IntentPath> {
+        self.intents.read().await.values().cloned().collect()
+    }
+
+    /// Remove intent paths by ID, e.g. when their retention period ends.
+    /// Returns how many were removed.
+    pub async fn purge_intents(&self, ids: &HashSet<Uuid>) -> usize {
+        let mut intents = self.intents.write().await;
+        let before = intents.len();
+        intents.retain(|_, path| !ids.contains(&path.id));
+        before - intents.len()
+    }
+
     /// Record a step in the intent path.
     pub async fn record_step(
         &self,
@@ -0,0 +1,199 @@
+//! W3C Trace Context
+//!
+//! [`TraceContext`] parses and formats the `traceparent` header
+//! (`00-<trace-id>-<span-id>-<flags>`) and keeps `tracestate` opaque.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Header / metadata key for the trace parent.
+pub const TRACEPARENT: &str = "traceparent";
+
+/// Header / metadata key for vendor trace state.
+pub const TRACESTATE: &str = "tracestate";
+
+const FLAG_SAMPLED: u8 = 0x01;
+
+/// Position of a span within a trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// 32 lowercase hex digits, shared by every span in the trace
+    pub trace_id: String,
+    /// 16 lowercase hex digits identifying this span
+    pub span_id: String,
+    /// Span this one was started from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+    #[serde(default)]
+    pub sampled: bool,
+    /// Vendor-specific `tracestate`, passed through unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Start a new, sampled trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: random_hex::<16>(),
+            span_id: random_hex::<8>(),
+            parent_span_id: None,
+            sampled: true,
+            tracestate: None,
+        }
+    }
+
+    /// Parse a `traceparent` header value.
+    pub fn parse(traceparent: &str) -> Result<Self, TraceContextError> {
+        let invalid = || TraceContextError::Invalid(traceparent.to_string());
+        let mut parts = traceparent.trim().split('-');
+        let (Some(version), Some(trace_id), Some(span_id), Some(flags)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        // Future versions may append fields; version 00 may not
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return Err(invalid());
+        }
+        if !is_hex(trace_id, 32) || !is_hex(span_id, 16) || !is_hex(flags, 2) {
+            return Err(invalid());
+        }
+        if is_zero(trace_id) || is_zero(span_id) {
+            return Err(invalid());
+        }
+        let flags = u8::from_str_radix(flags, 16).map_err(|_| invalid())?;
+        Ok(Self {
+            trace_id: trace_id.to_ascii_lowercase(),
+            span_id: span_id.to_ascii_lowercase(),
+            parent_span_id: None,
+            sampled: flags & FLAG_SAMPLED != 0,
+            tracestate: None,
+        })
+    }
+
+    /// Read `traceparent` and `tracestate` through a header lookup.
+    ///
+    /// Returns `None` when there is no valid `traceparent`.
+    pub fn from_headers<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        let mut context = Self::parse(get(TRACEPARENT)?).ok()?;
+        context.tracestate = get(TRACESTATE).map(str::to_string);
+        Some(context)
+    }
+
+    /// Attach vendor trace state.
+    pub fn with_tracestate(mut self, tracestate: impl Into<String>) -> Self {
+        self.tracestate = Some(tracestate.into());
+        self
+    }
+
+    /// The `traceparent` header value.
+    pub fn traceparent(&self) -> String {
+        let flags = if self.sampled { FLAG_SAMPLED } else { 0 };
+        format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, flags)
+    }
+
+    /// A new span in the same trace, parented to this one.
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: random_hex::<8>(),
+            parent_span_id: Some(self.span_id.clone()),
+            sampled: self.sampled,
+            tracestate: self.tracestate.clone(),
+        }
+    }
+
+    /// A link from another span to this one.
+    pub fn link(&self, relation: impl Into<String>) -> SpanLink {
+        SpanLink {
+            trace_id: self.trace_id.clone(),
+            span_id: self.span_id.clone(),
+            relation: relation.into(),
+        }
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.traceparent())
+    }
+}
+
+/// Reference to a related span outside the parent chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpanLink {
+    pub trace_id: String,
+    pub span_id: String,
+    /// Why the spans are related (e.g. `verified_by`, `locked_by`)
+    pub relation: String,
+}
+
+impl fmt::Display for SpanLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}-{}", self.relation, self.trace_id, self.span_id)
+    }
+}
+
+/// Trace context errors.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TraceContextError {
+    #[error("invalid traceparent: {0}")]
+    Invalid(String),
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn is_zero(s: &str) -> bool {
+    s.bytes().all(|b| b == b'0')
+}
+
+/// Random non-zero ID of `N` bytes as lowercase hex.
+fn random_hex<const N: usize>() -> String {
+    let mut bytes = [0u8; N];
+    while bytes.iter().all(|b| *b == 0) {
+        rand::thread_rng().fill_bytes(&mut bytes);
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_roundtrip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context = TraceContext::parse(header).unwrap();
+        assert!(context.sampled);
+        assert_eq!(context.traceparent(), header);
+
+        let child = context.child();
+        assert_eq!(child.trace_id, context.trace_id);
+        assert_ne!(child.span_id, context.span_id);
+        assert_eq!(child.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+
+        let root = TraceContext::new_root();
+        assert_eq!(TraceContext::parse(&root.traceparent()).unwrap().trace_id, root.trace_id);
+    }
+
+    #[test]
+    fn test_invalid_traceparent() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e473g-00f067aa0ba902b7-01",
+        ] {
+            assert!(TraceContext::parse(header).is_err(), "{header}");
+        }
+        // Later versions may carry extra fields
+        assert!(TraceContext::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra").is_ok());
+    }
+}
@@ -0,0 +1,36 @@
+//! AgentKern-Telemetry: Trace Context Propagation
+//!
+//! One W3C trace context (`traceparent` / `tracestate`) carried by every
+//! module, so a task crossing Nexus → Gate → Arbiter → Treasury shows up as
+//! a single trace. Each module starts a child span from the incoming
+//! context and records that span's context on its result; [`SpanLink`]s tie
+//! a span to related spans outside its parent chain (e.g. a payment to the
+//! verification that allowed it).
+//!
+//! Spans are emitted through `tracing` with `otel.name`, `trace_id`,
+//! `span_id`, `parent_span_id` and `links` fields, ready for an
+//! OpenTelemetry subscriber.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use agentkern_telemetry::TraceContext;
+//!
+//! let root = TraceContext::parse(headers["traceparent"])?;
+//! let verification = engine.verify(builder.trace(root.clone()).build()).await;
+//! let lock = coordinator.request(request.with_trace(root.clone())).await;
+//! let payment = transfers
+//!     .transfer(
+//!         TransferRequest::new("agent-a", "agent-b", amount)
+//!             .with_trace(lock.trace.unwrap())
+//!             .with_link(verification.trace.as_ref().unwrap(), "verified_by"),
+//!     )
+//!     .await;
+//! ```
+
+pub mod context;
+pub mod span;
+
+// Re-exports
+pub use context::{SpanLink, TraceContext, TraceContextError, TRACEPARENT, TRACESTATE};
+pub use span::start_span;
@@ -0,0 +1,46 @@
+//! Spans
+//!
+//! Emits `tracing` spans carrying the trace context, using the field names
+//! `tracing-opentelemetry` understands (`otel.name`).
+
+use crate::context::{SpanLink, TraceContext};
+
+/// Start a span named `name` as a child of `parent`, linked to `links`.
+///
+/// Returns the new span's context (to record on results and propagate
+/// further) and the span to instrument the work with. Without a parent
+/// the span is disabled and no context is produced.
+pub fn start_span(name: &'static str, parent: Option<&TraceContext>, links: &[SpanLink]) -> (Option<TraceContext>, tracing::Span) {
+    let Some(parent) = parent else {
+        return (None, tracing::Span::none());
+    };
+    let context = parent.child();
+    let links = links.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+    let span = tracing::info_span!(
+        "agentkern",
+        otel.name = name,
+        trace_id = %context.trace_id,
+        span_id = %context.span_id,
+        parent_span_id = %parent.span_id,
+        links = %links,
+    );
+    (Some(context), span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_span() {
+        let root = TraceContext::new_root();
+        let (context, _span) = start_span("test.work", Some(&root), &[root.link("caused_by")]);
+        let context = context.unwrap();
+        assert_eq!(context.trace_id, root.trace_id);
+        assert_eq!(context.parent_span_id, Some(root.span_id));
+
+        let (context, span) = start_span("test.work", None, &[]);
+        assert!(context.is_none());
+        assert!(span.is_none());
+    }
+}
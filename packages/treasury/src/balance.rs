@@ -131,10 +131,13 @@ impl BalanceLedger {
             .entry(agent_id.to_string())
             .or_insert_with(|| AgentBalance::new(agent_id, self.default_currency));
 
-        balance.balance = balance.balance.add(&amount)
+        // Compute both totals before touching the account, so overflow changes nothing
+        let new_balance = balance.balance.add(&amount)
             .ok_or(LedgerError::InvalidAmount)?;
-        balance.total_deposited = balance.total_deposited.add(&amount)
+        let new_deposited = balance.total_deposited.add(&amount)
             .ok_or(LedgerError::InvalidAmount)?;
+        balance.balance = new_balance;
+        balance.total_deposited = new_deposited;
         balance.updated_at = Utc::now();
 
         Ok(balance.clone())
@@ -142,6 +145,10 @@ impl BalanceLedger {
 
     /// Hold funds for a pending transaction.
     pub fn hold(&self, agent_id: &str, amount: Amount) -> Result<(), LedgerError> {
+        if amount.is_negative() {
+            return Err(LedgerError::InvalidAmount);
+        }
+
         let mut balances = self.balances.write();
         let balance = balances.get_mut(agent_id)
             .ok_or(LedgerError::AccountNotFound)?;
@@ -163,6 +170,11 @@ impl BalanceLedger {
         let balance = balances.get_mut(agent_id)
             .ok_or(LedgerError::AccountNotFound)?;
 
+        // Releasing more than is held would inflate the available balance
+        if amount.is_negative() || amount.value > balance.pending.value {
+            return Err(LedgerError::InvalidAmount);
+        }
+
         balance.pending = balance.pending.sub(&amount)
             .ok_or(LedgerError::InvalidAmount)?;
         balance.updated_at = Utc::now();
@@ -170,6 +182,59 @@ impl BalanceLedger {
         Ok(())
     }
 
+    /// Withdraw available funds from an agent's account.
+    pub fn withdraw(&self, agent_id: &str, amount: Amount) -> Result<AgentBalance, LedgerError> {
+        if amount.is_negative() {
+            return Err(LedgerError::InvalidAmount);
+        }
+
+        let mut balances = self.balances.write();
+        let balance = balances.get_mut(agent_id)
+            .ok_or(LedgerError::AccountNotFound)?;
+
+        if !balance.can_spend(&amount) {
+            return Err(LedgerError::InsufficientFunds);
+        }
+
+        let new_balance = balance.balance.sub(&amount)
+            .ok_or(LedgerError::InvalidAmount)?;
+        let new_withdrawn = balance.total_withdrawn.add(&amount)
+            .ok_or(LedgerError::InvalidAmount)?;
+        balance.balance = new_balance;
+        balance.total_withdrawn = new_withdrawn;
+        balance.updated_at = Utc::now();
+
+        Ok(balance.clone())
+    }
+
+    /// Close an agent's account, returning its final balance.
+    ///
+    /// Fails while any funds are held for pending transactions.
+    pub fn close_account(&self, agent_id: &str) -> Result<AgentBalance, LedgerError> {
+        let mut balances = self.balances.write();
+        let balance = balances.get(agent_id)
+            .ok_or(LedgerError::AccountNotFound)?;
+
+        if !balance.pending.is_zero() {
+            return Err(LedgerError::FundsHeld);
+        }
+
+        balances.remove(agent_id).ok_or(LedgerError::AccountNotFound)
+    }
+
+    /// Copy of every account.
+    pub fn export_state(&self) -> Vec<AgentBalance> {
+        self.balances.read().values().cloned().collect()
+    }
+
+    /// Replace every account.
+    pub fn import_state(&self, balances: Vec<AgentBalance>) {
+        *self.balances.write() = balances
+            .into_iter()
+            .map(|balance| (balance.agent_id.clone(), balance))
+            .collect();
+    }
+
     /// Commit a transfer (from hold -> subtract).
     pub fn commit_transfer(
         &self,
@@ -179,10 +244,12 @@ impl BalanceLedger {
     ) -> Result<(), LedgerError> {
         let mut balances = self.balances.write();
 
-        // Subtract from sender (and pending)
-        let from_balance = balances.get_mut(from_id)
+        // Stage both accounts and apply together, so a failure never debits without crediting
+        let mut from_balance = balances.get(from_id)
+            .cloned()
             .ok_or(LedgerError::AccountNotFound)?;
 
+        // Subtract from sender (and pending)
         from_balance.balance = from_balance.balance.sub(&amount)
             .ok_or(LedgerError::InvalidAmount)?;
         from_balance.pending = from_balance.pending.sub(&amount)
@@ -192,9 +259,13 @@ impl BalanceLedger {
         from_balance.updated_at = Utc::now();
 
         // Add to receiver
-        let to_balance = balances
-            .entry(to_id.to_string())
-            .or_insert_with(|| AgentBalance::new(to_id, self.default_currency));
+        let mut to_balance = if to_id == from_id {
+            from_balance.clone()
+        } else {
+            balances.get(to_id)
+                .cloned()
+                .unwrap_or_else(|| AgentBalance::new(to_id, self.default_currency))
+        };
 
         to_balance.balance = to_balance.balance.add(&amount)
             .ok_or(LedgerError::InvalidAmount)?;
@@ -202,6 +273,11 @@ impl BalanceLedger {
             .ok_or(LedgerError::InvalidAmount)?;
         to_balance.updated_at = Utc::now();
 
+        if to_id != from_id {
+            balances.insert(from_id.to_string(), from_balance);
+        }
+        balances.insert(to_id.to_string(), to_balance);
+
         Ok(())
     }
 }
@@ -217,6 +293,21 @@ pub enum LedgerError {
     InvalidAmount,
     #[error("Currency mismatch")]
     CurrencyMismatch,
+    #[error("Funds held for pending transactions")]
+    FundsHeld,
+}
+
+impl agentkern_errors::Coded for LedgerError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::AccountNotFound => ErrorCode::AccountNotFound,
+            Self::InsufficientFunds => ErrorCode::InsufficientFunds,
+            Self::InvalidAmount => ErrorCode::InvalidAmount,
+            Self::CurrencyMismatch => ErrorCode::CurrencyMismatch,
+            Self::FundsHeld => ErrorCode::InvalidState,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -265,4 +356,34 @@ mod tests {
         let result = ledger.hold("agent-1", amount);
         assert!(matches!(result, Err(LedgerError::InsufficientFunds)));
     }
+
+    #[test]
+    fn test_withdraw_and_close() {
+        let ledger = BalanceLedger::default();
+        ledger.deposit("agent-1", Amount::from_float(100.0, 6)).unwrap();
+
+        let balance = ledger.withdraw("agent-1", Amount::from_float(40.0, 6)).unwrap();
+        assert_eq!(balance.balance.to_float(), 60.0);
+        assert!(matches!(
+            ledger.withdraw("agent-1", Amount::from_float(61.0, 6)),
+            Err(LedgerError::InsufficientFunds)
+        ));
+
+        ledger.hold("agent-1", Amount::from_float(10.0, 6)).unwrap();
+        assert!(matches!(ledger.close_account("agent-1"), Err(LedgerError::FundsHeld)));
+
+        ledger.release("agent-1", Amount::from_float(10.0, 6)).unwrap();
+        assert_eq!(ledger.close_account("agent-1").unwrap().balance.to_float(), 60.0);
+        assert!(matches!(ledger.close_account("agent-1"), Err(LedgerError::AccountNotFound)));
+    }
+
+    #[test]
+    fn test_ledger_error_codes() {
+        use agentkern_errors::{AgentKernError, ErrorCategory, ErrorCode};
+
+        let error: AgentKernError = LedgerError::InsufficientFunds.into();
+        assert_eq!(error.code, ErrorCode::InsufficientFunds);
+        assert_eq!(error.category, ErrorCategory::Funds);
+        assert!(!error.retryable);
+    }
 }
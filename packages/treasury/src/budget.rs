@@ -133,6 +133,11 @@ impl BudgetManager {
         agent_limits.push(limit);
     }
 
+    /// Remove every spending limit for an agent, returning them.
+    pub fn clear_limits(&self, agent_id: &str) -> Vec<SpendingLimit> {
+        self.limits.write().remove(agent_id).unwrap_or_default()
+    }
+
     /// Check if agent can spend amount.
     pub fn can_spend(&self, agent_id: &str, amount: &Amount) -> Result<(), BudgetError> {
         let mut limits = self.limits.write();
@@ -195,6 +200,16 @@ pub enum BudgetError {
     InvalidAmount,
 }
 
+impl agentkern_errors::Coded for BudgetError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::LimitExceeded { .. } => ErrorCode::SpendingLimitExceeded,
+            Self::InvalidAmount => ErrorCode::InvalidAmount,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
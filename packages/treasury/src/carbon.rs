@@ -173,6 +173,18 @@ impl CarbonFootprint {
         let watts = Decimal::from(compute_type.typical_watts());
         let energy_kwh = watts * hours / dec!(1000);
         
+        Self::from_energy(agent_id, action, compute_type, energy_kwh, duration_ms, region)
+    }
+
+    /// Footprint of metered energy use.
+    pub fn from_energy(
+        agent_id: AgentId,
+        action: &str,
+        compute_type: ComputeType,
+        energy_kwh: Decimal,
+        duration_ms: u64,
+        region: CarbonRegion,
+    ) -> Self {
         // CO2 = Energy * Carbon Intensity
         let intensity = Decimal::from(region.intensity());
         let co2_grams = energy_kwh * intensity;
@@ -301,21 +313,36 @@ impl CarbonLedger {
 
     /// Record a carbon footprint.
     pub fn record(&self, footprint: CarbonFootprint) -> Result<(), CarbonError> {
-        // Check budget
-        if let Some(budget) = self.get_budget(&footprint.agent_id) {
-            let daily = self.get_daily_usage(&footprint.agent_id);
-            let new_total = daily.total_co2_grams + footprint.co2_grams;
-            
-            if new_total > budget.daily_limit_grams && budget.block_on_exceed {
-                return Err(CarbonError::BudgetExceeded {
-                    agent_id: footprint.agent_id.clone(),
-                    limit: budget.daily_limit_grams,
-                    current: daily.total_co2_grams,
-                    requested: footprint.co2_grams,
-                });
-            }
+        if let Some(exceeded) = self.check_budget(&footprint) {
+            return Err(exceeded);
         }
+        self.append(footprint);
+        Ok(())
+    }
+
+    /// Record energy that was already consumed, e.g. by metering. It is
+    /// always recorded; the error means the agent is now over a blocking budget.
+    pub fn record_consumed(&self, footprint: CarbonFootprint) -> Result<(), CarbonError> {
+        let exceeded = self.check_budget(&footprint);
+        self.append(footprint);
+        exceeded.map_or(Ok(()), Err)
+    }
 
+    /// Error if the footprint would take its agent over a blocking daily budget.
+    fn check_budget(&self, footprint: &CarbonFootprint) -> Option<CarbonError> {
+        let budget = self.get_budget(&footprint.agent_id)?;
+        let daily = self.get_daily_usage(&footprint.agent_id);
+        let new_total = daily.total_co2_grams + footprint.co2_grams;
+        
+        (new_total > budget.daily_limit_grams && budget.block_on_exceed).then(|| CarbonError::BudgetExceeded {
+            agent_id: footprint.agent_id.clone(),
+            limit: budget.daily_limit_grams,
+            current: daily.total_co2_grams,
+            requested: footprint.co2_grams,
+        })
+    }
+
+    fn append(&self, footprint: CarbonFootprint) {
         let mut footprints = self.footprints.write();
         footprints.push(footprint);
         
@@ -323,8 +350,6 @@ impl CarbonLedger {
         if footprints.len() > self.max_history {
             footprints.remove(0);
         }
-
-        Ok(())
     }
 
     /// Record compute and calculate footprint automatically.
@@ -479,6 +504,14 @@ pub enum CarbonError {
     },
 }
 
+impl agentkern_errors::Coded for CarbonError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        match self {
+            Self::BudgetExceeded { .. } => agentkern_errors::ErrorCode::BudgetExceeded,
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -560,6 +593,31 @@ mod tests {
         assert!(matches!(result, Err(CarbonError::BudgetExceeded { .. })));
     }
 
+    #[test]
+    fn test_consumed_energy_recorded_over_budget() {
+        let ledger = CarbonLedger::new();
+        ledger.set_budget(
+            CarbonBudget::new("agent-1".to_string())
+                .with_daily_limit(dec!(10))
+                .block_on_exceed()
+        );
+
+        // 0.1 kWh at 400 gCO2/kWh = 40g, over the 10g budget
+        let footprint = CarbonFootprint::from_energy(
+            "agent-1".to_string(),
+            "metered",
+            ComputeType::Cpu,
+            dec!(0.1),
+            60_000,
+            CarbonRegion::UsAverage,
+        );
+        assert_eq!(footprint.co2_grams, dec!(40));
+
+        let result = ledger.record_consumed(footprint);
+        assert!(matches!(result, Err(CarbonError::BudgetExceeded { .. })));
+        assert_eq!(ledger.get_daily_usage(&"agent-1".to_string()).total_co2_grams, dec!(40));
+    }
+
     #[test]
     fn test_fleet_usage() {
         let ledger = CarbonLedger::new();
@@ -39,6 +39,17 @@ pub enum LockError {
     AlreadyHeld,
 }
 
+impl agentkern_errors::Coded for LockError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::AcquisitionFailed(_) | Self::AlreadyHeld => ErrorCode::LockContention,
+            Self::Timeout(_) => ErrorCode::Timeout,
+            Self::RedisError(_) => ErrorCode::Unavailable,
+        }
+    }
+}
+
 /// Lock mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LockMode {
@@ -188,6 +199,16 @@ impl LockManager {
         })
     }
 
+    /// Release every local lock at shutdown so waiters are not stuck until
+    /// their timeout. Redis locks expire by TTL. Returns the number released.
+    pub fn release_all(&self) -> usize {
+        let mut locks = self.local_locks.write();
+        let count = locks.len();
+        locks.clear();
+        tracing::debug!(count, "Local locks released");
+        count
+    }
+
     /// Release a lock (called automatically by LockGuard drop).
     fn release_local(&self, resource: &str) {
         let mut locks = self.local_locks.write();
@@ -263,6 +284,16 @@ mod tests {
         drop(guard2);
     }
 
+    #[tokio::test]
+    async fn test_release_all() {
+        let manager = LockManager::new_auto();
+        let _a = manager.acquire("resource-a").await.unwrap();
+        let _b = manager.acquire("resource-b").await.unwrap();
+
+        assert_eq!(manager.release_all(), 2);
+        assert!(manager.try_acquire("resource-a").await.is_ok());
+    }
+
     #[test]
     fn test_default_config() {
         let config = LockConfig::default();
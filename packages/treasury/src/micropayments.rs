@@ -6,6 +6,8 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 use parking_lot::RwLock;
 use uuid::Uuid;
@@ -184,6 +186,26 @@ impl MicropaymentAggregator {
 
         batches
     }
+
+    /// Persist unsettled work before shutdown: batches all pending payments
+    /// and appends every unsettled batch to a JSON Lines journal (fsynced).
+    /// Returns the number of batches written; they remain ready until settled.
+    pub fn flush_to_journal(&self, path: impl AsRef<Path>) -> std::io::Result<usize> {
+        let flushed = self.flush_all();
+        let mut ready = self.ready_batches.write();
+        ready.extend(flushed);
+        if ready.is_empty() {
+            return Ok(0);
+        }
+
+        let mut journal = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        for batch in ready.iter() {
+            serde_json::to_writer(&mut journal, batch)?;
+            journal.write_all(b"\n")?;
+        }
+        journal.sync_all()?;
+        Ok(ready.len())
+    }
 }
 
 #[cfg(test)]
@@ -265,4 +287,27 @@ mod tests {
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].payments.len(), 5);
     }
+
+    #[test]
+    fn test_flush_to_journal() {
+        let aggregator = MicropaymentAggregator::default();
+        aggregator.add_payment(PendingPayment {
+            id: Uuid::new_v4(),
+            from: "sender".to_string(),
+            to: "receiver".to_string(),
+            amount: Amount::from_float(0.01, 6),
+            reference: None,
+            created_at: Utc::now(),
+        });
+
+        let path = std::env::temp_dir().join(format!("treasury-journal-{}.jsonl", Uuid::new_v4()));
+        assert_eq!(aggregator.flush_to_journal(&path).unwrap(), 1);
+        assert_eq!(aggregator.pending_count("receiver"), 0);
+        assert_eq!(aggregator.get_ready_batches().len(), 1);
+
+        let journal = std::fs::read_to_string(&path).unwrap();
+        let batch: PaymentBatch = serde_json::from_str(journal.lines().next().unwrap()).unwrap();
+        assert_eq!(batch.payments[0].to, "receiver");
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -3,11 +3,13 @@
 //! Per Market Research: 60% of multi-agent systems fail due to lack of atomic payments.
 //! This module implements 2-phase commit for safe agent-to-agent transfers.
 
+use agentkern_telemetry::{start_span, SpanLink, TraceContext};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use parking_lot::RwLock;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::balance::{BalanceLedger, LedgerError};
@@ -26,6 +28,12 @@ pub struct TransferRequest {
     pub reference: Option<String>,
     /// Idempotency key (prevent duplicate transfers)
     pub idempotency_key: Option<String>,
+    /// Caller's trace context; the transfer runs in a child span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+    /// Related spans, e.g. the verification that allowed this payment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<SpanLink>,
 }
 
 impl TransferRequest {
@@ -37,6 +45,8 @@ impl TransferRequest {
             amount,
             reference: None,
             idempotency_key: None,
+            trace: None,
+            links: Vec::new(),
         }
     }
 
@@ -51,6 +61,18 @@ impl TransferRequest {
         self.idempotency_key = Some(key.into());
         self
     }
+
+    /// Propagate the caller's trace context.
+    pub fn with_trace(mut self, trace: TraceContext) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Link the transfer span to a related span.
+    pub fn with_link(mut self, span: &TraceContext, relation: impl Into<String>) -> Self {
+        self.links.push(span.link(relation));
+        self
+    }
 }
 
 /// Transfer status.
@@ -77,6 +99,12 @@ pub struct TransferResult {
     pub timestamp: DateTime<Utc>,
     /// Error message if failed
     pub error: Option<String>,
+    /// Context of the transfer span, when the request was traced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<TraceContext>,
+    /// Links recorded on the transfer span
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<SpanLink>,
 }
 
 impl TransferResult {
@@ -86,6 +114,8 @@ impl TransferResult {
             status: TransferStatus::Completed,
             timestamp: Utc::now(),
             error: None,
+            trace: None,
+            links: Vec::new(),
         }
     }
 
@@ -95,6 +125,8 @@ impl TransferResult {
             status: TransferStatus::Failed,
             timestamp: Utc::now(),
             error: Some(error.into()),
+            trace: None,
+            links: Vec::new(),
         }
     }
 }
@@ -125,7 +157,19 @@ impl TransferEngine {
     }
 
     /// Execute an atomic transfer.
+    ///
+    /// A traced request runs in a `treasury.transfer` child span carrying
+    /// the request's links; both are recorded on the result.
     pub async fn transfer(&self, request: TransferRequest) -> TransferResult {
+        let (trace, span) = start_span("treasury.transfer", request.trace.as_ref(), &request.links);
+        let links = if trace.is_some() { request.links.clone() } else { Vec::new() };
+        let mut result = self.execute(request).instrument(span).await;
+        result.trace = trace;
+        result.links = links;
+        result
+    }
+
+    async fn execute(&self, request: TransferRequest) -> TransferResult {
         let transaction_id = Uuid::new_v4();
 
         // Check idempotency
@@ -232,6 +276,16 @@ pub enum TransferError {
     LedgerError(String),
 }
 
+impl agentkern_errors::Coded for TransferError {
+    fn code(&self) -> agentkern_errors::ErrorCode {
+        use agentkern_errors::ErrorCode;
+        match self {
+            Self::NotFound => ErrorCode::NotFound,
+            Self::LedgerError(_) => ErrorCode::TransferFailed,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +308,23 @@ mod tests {
         assert_eq!(result.status, TransferStatus::Completed);
     }
 
+    #[tokio::test]
+    async fn test_traced_transfer_records_links() {
+        let engine = setup();
+        let root = TraceContext::new_root();
+        let verification = root.child();
+
+        let request = TransferRequest::new("agent-1", "agent-2", Amount::from_float(100.0, 6))
+            .with_trace(root.clone())
+            .with_link(&verification, "verified_by");
+        let result = engine.transfer(request).await;
+
+        let trace = result.trace.unwrap();
+        assert_eq!(trace.trace_id, root.trace_id);
+        assert_eq!(trace.parent_span_id, Some(root.span_id));
+        assert_eq!(result.links, vec![verification.link("verified_by")]);
+    }
+
     #[tokio::test]
     async fn test_insufficient_funds() {
         let engine = setup();
@@ -55,24 +55,24 @@ impl Amount {
         self.value < 0
     }
 
-    /// Add two amounts (must have same decimals).
+    /// Add two amounts (must have same decimals; `None` on overflow).
     pub fn add(&self, other: &Amount) -> Option<Amount> {
         if self.decimals != other.decimals {
             return None;
         }
         Some(Amount {
-            value: self.value.saturating_add(other.value),
+            value: self.value.checked_add(other.value)?,
             decimals: self.decimals,
         })
     }
 
-    /// Subtract two amounts (must have same decimals).
+    /// Subtract two amounts (must have same decimals; `None` on overflow).
     pub fn sub(&self, other: &Amount) -> Option<Amount> {
         if self.decimals != other.decimals {
             return None;
         }
         Some(Amount {
-            value: self.value.saturating_sub(other.value),
+            value: self.value.checked_sub(other.value)?,
             decimals: self.decimals,
         })
     }
@@ -0,0 +1,86 @@
+//! Property-based invariants for the balance ledger and transfer engine.
+
+use agentkern_treasury::{Amount, BalanceLedger, Currency, TransferEngine, TransferRequest, TransferStatus};
+use proptest::prelude::*;
+use std::sync::Arc;
+
+const AGENTS: [&str; 3] = ["agent-a", "agent-b", "agent-c"];
+
+#[derive(Debug, Clone)]
+enum Op {
+    Deposit { agent: usize, value: i64 },
+    Transfer { from: usize, to: usize, value: i64, key: Option<u8> },
+    Hold { agent: usize, value: i64 },
+    Release { agent: usize, value: i64 },
+}
+
+/// Everyday values, values near `i64::MAX`, and invalid ones.
+fn value() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        6 => 0..1_000_000i64,
+        2 => (i64::MAX - 1_000_000)..=i64::MAX,
+        1 => i64::MIN..0,
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let agent = || 0..AGENTS.len();
+    prop_oneof![
+        3 => (agent(), value()).prop_map(|(agent, value)| Op::Deposit { agent, value }),
+        6 => (agent(), agent(), value(), proptest::option::of(0..4u8))
+            .prop_map(|(from, to, value, key)| Op::Transfer { from, to, value, key }),
+        1 => (agent(), value()).prop_map(|(agent, value)| Op::Hold { agent, value }),
+        1 => (agent(), value()).prop_map(|(agent, value)| Op::Release { agent, value }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_transfers_conserve_funds(ops in prop::collection::vec(op(), 1..60)) {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let ledger = Arc::new(BalanceLedger::new(Currency::VMC));
+        let engine = TransferEngine::new(Arc::clone(&ledger));
+        let decimals = Currency::VMC.decimals();
+        let mut supply: i128 = 0;
+
+        for op in ops {
+            match op {
+                Op::Deposit { agent, value } => {
+                    if ledger.deposit(AGENTS[agent], Amount::new(value, decimals)).is_ok() {
+                        supply += value as i128;
+                    }
+                }
+                Op::Transfer { from, to, value, key } => {
+                    let mut request = TransferRequest::new(AGENTS[from], AGENTS[to], Amount::new(value, decimals));
+                    if let Some(key) = key {
+                        request = request.with_idempotency_key(format!("key-{key}"));
+                    }
+                    let result = runtime.block_on(engine.transfer(request));
+                    prop_assert_ne!(result.status, TransferStatus::Pending);
+                }
+                Op::Hold { agent, value } => {
+                    let _ = ledger.hold(AGENTS[agent], Amount::new(value, decimals));
+                }
+                Op::Release { agent, value } => {
+                    let _ = ledger.release(AGENTS[agent], Amount::new(value, decimals));
+                }
+            }
+
+            let mut total: i128 = 0;
+            for agent in AGENTS {
+                let balance = ledger.get_balance(agent);
+                prop_assert!(!balance.balance.is_negative(), "{} overdrawn", agent);
+                total += balance.balance.value as i128;
+            }
+            prop_assert_eq!(total, supply);
+            prop_assert_eq!(engine.pending_count(), 0);
+        }
+    }
+
+    #[test]
+    fn prop_amount_arithmetic_never_wraps(a in any::<i64>(), b in any::<i64>()) {
+        let (x, y) = (Amount::new(a, 6), Amount::new(b, 6));
+        prop_assert_eq!(x.add(&y).map(|s| s.value), a.checked_add(b));
+        prop_assert_eq!(x.sub(&y).map(|d| d.value), a.checked_sub(b));
+    }
+}
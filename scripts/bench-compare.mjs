@@ -0,0 +1,55 @@
+// Fails when a benchmark regressed against a saved criterion baseline.
+//
+//   cargo bench -p agentkern-gate --bench verify_hot_path -- --save-baseline main
+//   (switch to the branch under test)
+//   cargo bench -p agentkern-gate --bench verify_hot_path -- --baseline main
+//   node scripts/bench-compare.mjs [threshold-percent]
+//
+// The synapse `polyglot_search` and nexus `translation` benches work the same way.
+//
+// Criterion writes `<bench>/change/estimates.json` for every benchmark run with
+// `--baseline`; this reads the relative change in the mean from each one.
+
+import { existsSync, readdirSync, readFileSync } from 'node:fs';
+import { join } from 'node:path';
+
+// Allowed slowdown before failing; shared CI runners are noisy
+const THRESHOLD = Number(process.argv[2] ?? process.env.BENCH_THRESHOLD ?? 10) / 100;
+
+const root = new URL('../target/criterion/', import.meta.url).pathname;
+
+function* changes(dir) {
+  for (const entry of readdirSync(dir, { withFileTypes: true })) {
+    if (!entry.isDirectory()) continue;
+    const path = join(dir, entry.name);
+    const estimates = join(path, 'change', 'estimates.json');
+    if (existsSync(estimates)) {
+      const meta = JSON.parse(readFileSync(join(path, 'new', 'benchmark.json'), 'utf8'));
+      const { mean } = JSON.parse(readFileSync(estimates, 'utf8'));
+      yield { id: meta.full_id, change: mean.point_estimate };
+    } else if (entry.name !== 'report') {
+      yield* changes(path);
+    }
+  }
+}
+
+if (!existsSync(root)) {
+  console.error(`No criterion output in ${root}; run cargo bench first`);
+  process.exit(1);
+}
+
+const results = [...changes(root)].sort((a, b) => a.id.localeCompare(b.id));
+if (results.length === 0) {
+  console.error('No baseline comparisons found; run cargo bench with --baseline');
+  process.exit(1);
+}
+
+let failed = false;
+for (const { id, change } of results) {
+  const ok = change <= THRESHOLD;
+  failed ||= !ok;
+  const pct = `${change >= 0 ? '+' : ''}${(change * 100).toFixed(1)}%`;
+  console.log(`${id.padEnd(50)} ${pct.padStart(8)} ${ok ? 'ok' : 'REGRESSED'}`);
+}
+console.log(`\n${results.length} benchmarks, threshold +${(THRESHOLD * 100).toFixed(0)}%`);
+process.exit(failed ? 1 : 0);